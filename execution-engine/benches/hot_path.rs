@@ -0,0 +1,281 @@
+use chrono::Utc;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use execution_engine::execution::exit_management::ExitManagementIntegration;
+use execution_engine::execution::{TradeExecutionOrchestrator, TradeSignal};
+use execution_engine::platforms::abstraction::circuit_breaker::CircuitBreaker;
+use execution_engine::platforms::abstraction::errors::PlatformError;
+use execution_engine::platforms::abstraction::models::{
+    OrderMetadata, UnifiedOrder, UnifiedOrderResponse, UnifiedOrderSide, UnifiedOrderStatus,
+    UnifiedOrderType, UnifiedPosition, UnifiedPositionSide, UnifiedTimeInForce,
+};
+use execution_engine::platforms::dxtrade::fix_framing::FixFrameDecoder;
+use execution_engine::platforms::dxtrade::fix_messages::FIXMessageBuilder;
+use execution_engine::platforms::dxtrade::{FIXMessage, MessageType};
+use execution_engine::testing::MockTradingPlatform;
+
+fn order() -> UnifiedOrder {
+    UnifiedOrder {
+        client_order_id: Uuid::new_v4().to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Buy,
+        order_type: UnifiedOrderType::Limit,
+        quantity: dec!(1.25),
+        price: Some(dec!(1.0850)),
+        stop_price: None,
+        take_profit: Some(dec!(1.1000)),
+        stop_loss: Some(dec!(1.0800)),
+        time_in_force: UnifiedTimeInForce::Gtc,
+        max_slippage: None,
+        account_id: Some("acc-1".to_string()),
+        metadata: OrderMetadata {
+            strategy_id: Some("wyckoff".to_string()),
+            signal_id: Some("sig-1".to_string()),
+            risk_parameters: HashMap::new(),
+            tags: vec!["strategy:wyckoff".to_string()],
+            expires_at: None,
+        },
+    }
+}
+
+/// NewOrderSingle as the FIX session would send it for `order`
+fn new_order_single(order: &UnifiedOrder, seq: u32) -> FIXMessage {
+    let side = match order.side {
+        UnifiedOrderSide::Buy => "1",
+        UnifiedOrderSide::Sell => "2",
+    };
+    let mut builder = FIXMessageBuilder::new("ENGINE".to_string(), "BROKER".to_string(), seq)
+        .with_field(11, order.client_order_id.clone()) // ClOrdID
+        .with_field(55, order.symbol.clone()) // Symbol
+        .with_field(54, side.to_string()) // Side
+        .with_field(38, order.quantity.to_string()) // OrderQty
+        .with_field(40, "2".to_string()) // OrdType (Limit)
+        .with_field(59, "1".to_string()); // TimeInForce (GTC)
+    if let Some(price) = order.price {
+        builder = builder.with_field(44, price.to_string()); // Price
+    }
+    builder.build(MessageType::NewOrderSingle).unwrap()
+}
+
+fn execution_report(seq: u32) -> String {
+    FIXMessageBuilder::new("BROKER".to_string(), "ENGINE".to_string(), seq)
+        .with_field(11, "client-1".to_string()) // ClOrdID
+        .with_field(17, "exec-1".to_string()) // ExecID
+        .with_field(37, "order-1".to_string()) // OrderID
+        .with_field(39, "2".to_string()) // OrdStatus (Filled)
+        .with_field(55, "EURUSD".to_string()) // Symbol
+        .with_field(54, "1".to_string()) // Side
+        .with_field(14, "1.25".to_string()) // CumQty
+        .with_field(6, "1.0850".to_string()) // AvgPx
+        .with_field(151, "0".to_string()) // LeavesQty
+        .build(MessageType::ExecutionReport)
+        .unwrap()
+        .raw_message
+}
+
+fn bench_fix_messages(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fix_messages");
+    let order = order();
+    group.bench_function("encode_new_order_single", |b| {
+        b.iter(|| new_order_single(black_box(&order), 42))
+    });
+    group.bench_function("encode_heartbeat", |b| {
+        b.iter(|| FIXMessage::create_heartbeat("ENGINE".to_string(), "BROKER".to_string(), 42))
+    });
+
+    let report = execution_report(7);
+    group.throughput(Throughput::Bytes(report.len() as u64));
+    group.bench_function("parse_execution_report", |b| {
+        b.iter(|| FIXMessage::parse(black_box(&report)).unwrap())
+    });
+
+    // A read's worth of reports split mid-message, as they arrive off the socket
+    let stream: Vec<u8> = (0..32)
+        .flat_map(|seq| execution_report(seq).into_bytes())
+        .collect();
+    group.throughput(Throughput::Bytes(stream.len() as u64));
+    group.bench_function("frame_and_parse_stream", |b| {
+        b.iter(|| {
+            let mut decoder = FixFrameDecoder::new();
+            let mut parsed = 0;
+            for chunk in stream.chunks(1500) {
+                decoder.extend(chunk);
+                while let Some(frame) = decoder.next_frame() {
+                    FIXMessage::parse(&frame.unwrap()).unwrap();
+                    parsed += 1;
+                }
+            }
+            assert_eq!(parsed, 32);
+        })
+    });
+    group.finish();
+}
+
+fn bench_unified_order_conversion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("unified_order_conversion");
+    let order = order();
+    group.bench_function("to_json", |b| {
+        b.iter(|| serde_json::to_vec(black_box(&order)).unwrap())
+    });
+    group.bench_function("to_fix", |b| {
+        b.iter(|| new_order_single(black_box(&order), 42))
+    });
+
+    let response = serde_json::to_string(&UnifiedOrderResponse {
+        platform_order_id: "order-1".to_string(),
+        client_order_id: order.client_order_id.clone(),
+        status: UnifiedOrderStatus::Filled,
+        symbol: order.symbol.clone(),
+        side: order.side.clone(),
+        order_type: order.order_type.clone(),
+        quantity: order.quantity,
+        filled_quantity: order.quantity,
+        remaining_quantity: Decimal::ZERO,
+        price: order.price,
+        average_fill_price: order.price,
+        commission: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        filled_at: Some(Utc::now()),
+        platform_specific: HashMap::new(),
+    })
+    .unwrap();
+    group.bench_function("response_from_json", |b| {
+        b.iter(|| serde_json::from_str::<UnifiedOrderResponse>(black_box(&response)).unwrap())
+    });
+    group.finish();
+}
+
+fn signal() -> TradeSignal {
+    TradeSignal {
+        id: "sig-bench".to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Buy,
+        entry_price: 1.0850,
+        stop_loss: 1.0800,
+        take_profit: 1.0950,
+        confidence: 0.8,
+        risk_reward_ratio: 2.0,
+        signal_time: SystemTime::now(),
+        metadata: HashMap::new(),
+    }
+}
+
+fn bench_plan_creation(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let mut group = c.benchmark_group("orchestrator_plan_creation");
+    for accounts in [1, 8, 32] {
+        let orchestrator = runtime.block_on(async {
+            let orchestrator = TradeExecutionOrchestrator::new();
+            for i in 0..accounts {
+                orchestrator
+                    .register_account(
+                        format!("acc-{}", i),
+                        Arc::new(MockTradingPlatform::new("bench")),
+                        100000.0,
+                    )
+                    .await
+                    .unwrap();
+            }
+            orchestrator
+        });
+        group.throughput(Throughput::Elements(accounts as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(accounts), &accounts, |b, _| {
+            b.iter(|| {
+                runtime
+                    .block_on(orchestrator.process_signal(signal()))
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_circuit_breaker(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let breaker = CircuitBreaker::new();
+
+    let mut group = c.benchmark_group("circuit_breaker");
+    group.bench_function("is_operation_allowed", |b| {
+        b.iter(|| assert!(breaker.is_operation_allowed()))
+    });
+    group.bench_function("execute_closed", |b| {
+        b.iter(|| {
+            runtime
+                .block_on(breaker.execute(|| async { Ok::<_, PlatformError>(black_box(1)) }))
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn position(entry_price: Decimal) -> UnifiedPosition {
+    UnifiedPosition {
+        position_id: Uuid::new_v4().to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity: dec!(1),
+        entry_price,
+        current_price: entry_price,
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: dec!(100),
+        commission: Decimal::ZERO,
+        stop_loss: Some(entry_price - dec!(0.0050)),
+        take_profit: Some(entry_price + dec!(0.0100)),
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: "acc-1".to_string(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+fn bench_exit_tick(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let mut group = c.benchmark_group("exit_management_tick");
+    for positions in [1, 10, 50] {
+        let platform = Arc::new(MockTradingPlatform::new("bench"));
+        for _ in 0..positions {
+            platform.add_position(position(dec!(1.0850)));
+        }
+        // Just above entry: every manager evaluates each position but none triggers
+        platform.set_quote("EURUSD", dec!(1.0853), dec!(1.0855));
+        let exits = ExitManagementIntegration::create_with_platform(platform.clone()).unwrap();
+        runtime.block_on(exits.run_position_checks());
+        assert!(platform.modifications().is_empty());
+
+        group.throughput(Throughput::Elements(positions as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(positions),
+            &positions,
+            |b, _| b.iter(|| runtime.block_on(exits.run_position_checks())),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_fix_messages,
+    bench_unified_order_conversion,
+    bench_plan_creation,
+    bench_circuit_breaker,
+    bench_exit_tick
+);
+criterion_main!(benches);
@@ -0,0 +1,263 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use execution_engine::execution::{AccountAssignment, ExecutionPlan, TradeExecutionOrchestrator};
+use execution_engine::platforms::abstraction::{
+    capabilities::PlatformCapabilities,
+    errors::PlatformError,
+    events::PlatformEvent,
+    interfaces::{DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter},
+    models::*,
+};
+use execution_engine::platforms::PlatformType;
+
+/// Fills every order after `latency`
+struct MockPlatform {
+    latency: Duration,
+}
+
+impl MockPlatform {
+    fn unsupported<T>() -> Result<T, PlatformError> {
+        Err(PlatformError::FeatureNotSupported {
+            feature: "mock".to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl ITradingPlatform for MockPlatform {
+    fn platform_type(&self) -> PlatformType {
+        PlatformType::DXTrade
+    }
+    fn platform_name(&self) -> &str {
+        "mock"
+    }
+    fn platform_version(&self) -> &str {
+        "1.0.0"
+    }
+    async fn connect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn disconnect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn is_connected(&self) -> bool {
+        true
+    }
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        Ok(1)
+    }
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        tokio::time::sleep(self.latency).await;
+        Ok(UnifiedOrderResponse {
+            platform_order_id: Uuid::new_v4().to_string(),
+            client_order_id: order.client_order_id,
+            status: UnifiedOrderStatus::Filled,
+            symbol: order.symbol,
+            side: order.side,
+            order_type: order.order_type,
+            quantity: order.quantity,
+            filled_quantity: order.quantity,
+            remaining_quantity: Decimal::ZERO,
+            price: Some(dec!(1.0850)),
+            average_fill_price: Some(dec!(1.0850)),
+            commission: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            filled_at: Some(Utc::now()),
+            platform_specific: HashMap::new(),
+        })
+    }
+    async fn modify_order(
+        &self,
+        _order_id: &str,
+        _modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn cancel_order(&self, _order_id: &str) -> Result<(), PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_order(&self, _order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_orders(
+        &self,
+        _filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn get_position(&self, _symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        Ok(None)
+    }
+    async fn close_position(
+        &self,
+        _symbol: &str,
+        _quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        Ok(UnifiedAccountInfo {
+            account_id: "acc-1".to_string(),
+            account_name: None,
+            currency: "USD".to_string(),
+            balance: dec!(100000),
+            equity: dec!(100000),
+            margin_used: Decimal::ZERO,
+            margin_available: dec!(100000),
+            buying_power: dec!(100000),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_level: None,
+            account_type: AccountType::Demo,
+            last_updated: Utc::now(),
+            platform_specific: HashMap::new(),
+        })
+    }
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        Ok(dec!(100000))
+    }
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_market_data(&self, _symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        Self::unsupported()
+    }
+    async fn subscribe_market_data(
+        &self,
+        _symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn unsubscribe_market_data(&self, _symbols: Vec<String>) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    fn capabilities(&self) -> PlatformCapabilities {
+        PlatformCapabilities::new("mock".to_string())
+    }
+    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_event_history(
+        &self,
+        _filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        Self::unsupported()
+    }
+}
+
+const ACCOUNTS: usize = 8;
+
+async fn orchestrator(latency: Duration) -> Arc<TradeExecutionOrchestrator> {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    for i in 0..ACCOUNTS {
+        orchestrator
+            .register_account(
+                format!("acc-{}", i),
+                Arc::new(MockPlatform { latency }),
+                100000.0,
+            )
+            .await
+            .unwrap();
+    }
+    orchestrator
+}
+
+fn plan(signal_id: String) -> ExecutionPlan {
+    ExecutionPlan {
+        signal_id,
+        symbol: "EURUSD".to_string(),
+        account_assignments: (0..ACCOUNTS)
+            .map(|i| AccountAssignment {
+                account_id: format!("acc-{}", i),
+                position_size: 1.0,
+                entry_timing_delay: Duration::ZERO,
+                priority: 1,
+                risk_per_unit: 0.0,
+            })
+            .collect(),
+        timing_variance: HashMap::new(),
+        size_variance: HashMap::new(),
+        rationale: "bench".to_string(),
+        exit_policy: None,
+        tags: Vec::new(),
+        entry_ladder: None,
+    }
+}
+
+/// Executes `plans` plans at once while another task keeps registering accounts, the
+/// pattern that used to serialize order submission behind the platform and account locks
+async fn execute_concurrently(orchestrator: &Arc<TradeExecutionOrchestrator>, plans: usize) {
+    let registering = {
+        let orchestrator = orchestrator.clone();
+        tokio::spawn(async move {
+            for i in 0.. {
+                let _ = orchestrator
+                    .register_account(
+                        format!("standby-{}", i % 4),
+                        Arc::new(MockPlatform {
+                            latency: Duration::ZERO,
+                        }),
+                        100000.0,
+                    )
+                    .await;
+                tokio::time::sleep(Duration::from_micros(200)).await;
+            }
+        })
+    };
+
+    let handles: Vec<_> = (0..plans)
+        .map(|n| {
+            let orchestrator = orchestrator.clone();
+            tokio::spawn(
+                async move { orchestrator.execute_plan(&plan(format!("sig-{}", n))).await },
+            )
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+    registering.abort();
+}
+
+fn bench_concurrent_execution(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let orchestrator = runtime.block_on(orchestrator(Duration::from_millis(2)));
+
+    let mut group = c.benchmark_group("orchestrator_concurrent_execution");
+    group.sample_size(20);
+    for plans in [1, 8, 32] {
+        group.throughput(Throughput::Elements((plans * ACCOUNTS) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(plans), &plans, |b, &plans| {
+            b.iter(|| runtime.block_on(execute_concurrently(&orchestrator, plans)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_concurrent_execution);
+criterion_main!(benches);
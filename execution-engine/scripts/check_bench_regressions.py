@@ -0,0 +1,55 @@
+"""
+Fail when a criterion benchmark regressed against its saved baseline.
+
+Run after `cargo bench --bench hot_path -- --baseline-lenient <name>`, which writes each
+benchmark's relative change to target/criterion/<group>/<bench>/change/estimates.json.
+
+Usage: check_bench_regressions.py [criterion_dir] [--threshold 0.10]
+"""
+
+import argparse
+import json
+import sys
+from pathlib import Path
+
+
+def load_changes(criterion_dir: Path):
+    """Yield (benchmark id, mean change, lower confidence bound of the change) per
+    compared benchmark. The lower bound is the smallest slowdown the interval allows,
+    so a benchmark regresses only when even that exceeds the threshold."""
+    for estimates in sorted(criterion_dir.glob("**/change/estimates.json")):
+        benchmark = estimates.parent.parent.relative_to(criterion_dir).as_posix()
+        mean = json.loads(estimates.read_text())["mean"]
+        yield benchmark, mean["point_estimate"], mean["confidence_interval"]["lower_bound"]
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(description=__doc__.strip().splitlines()[0])
+    parser.add_argument("criterion_dir", nargs="?", default="../target/criterion", type=Path)
+    parser.add_argument("--threshold", type=float, default=0.10,
+                        help="Largest tolerated slowdown of the mean, as a fraction")
+    args = parser.parse_args()
+
+    changes = list(load_changes(args.criterion_dir))
+    if not changes:
+        # Benchmarks new in this change have nothing to compare against yet
+        print(f"No baseline comparisons found under {args.criterion_dir}")
+        return 0
+
+    regressions = []
+    for benchmark, change, lower_bound in changes:
+        # Only count slowdowns the whole confidence interval agrees on, so CI noise passes
+        regressed = lower_bound > args.threshold
+        print(f"{'REGRESSED' if regressed else 'ok':>9}  {benchmark:<55} {change:+.1%}")
+        if regressed:
+            regressions.append(benchmark)
+
+    if regressions:
+        print(f"\n{len(regressions)} benchmark(s) slower than baseline by more than "
+              f"{args.threshold:.0%}: {', '.join(regressions)}")
+        return 1
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,544 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use futures_util::future::join_all;
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use risk_types::{AccountId, AlertLevel};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{error, info, warn};
+
+use super::sinks::{AlertSink, SinkConfig, NOTIFICATIONS_SINK};
+use crate::runtime::supervisor::{ShutdownSignal, Subsystem};
+
+lazy_static! {
+    pub static ref ALERTS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "execution_engine_alerts_total",
+        "Alerts submitted to the alert gateway by type and outcome",
+        &["alert_type", "outcome"]
+    )
+    .unwrap();
+}
+
+/// Route used for alert types that have no route of their own
+pub const DEFAULT_ROUTE: &str = "default";
+
+/// Newly raised conditions kept for reporting; the oldest are dropped beyond this
+const RAISED_HISTORY_LIMIT: usize = 10_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertingConfig {
+    /// Repeats of an active alert inside this window are suppressed. An alert that is
+    /// not raised again for a whole window is treated as resolved.
+    pub dedup_window_secs: u64,
+    /// Deliveries allowed per severity within `rate_limit_window_secs`; severities
+    /// without an entry are never rate limited
+    #[serde(default = "default_rate_limits")]
+    pub rate_limits: HashMap<AlertLevel, u32>,
+    pub rate_limit_window_secs: u64,
+    pub escalation_check_interval_secs: u64,
+    /// Named delivery integrations referenced by the routes
+    #[serde(default)]
+    pub sinks: HashMap<String, SinkConfig>,
+    /// Routes by alert type ("margin", "drawdown", "risk_reward"), with `default`
+    /// covering every type not listed
+    #[serde(default)]
+    pub routes: HashMap<String, AlertRoute>,
+}
+
+fn default_rate_limits() -> HashMap<AlertLevel, u32> {
+    HashMap::from([
+        (AlertLevel::Info, 5),
+        (AlertLevel::Warning, 10),
+        (AlertLevel::Critical, 20),
+    ])
+}
+
+impl Default for AlertingConfig {
+    fn default() -> Self {
+        Self {
+            dedup_window_secs: 300,
+            rate_limits: default_rate_limits(),
+            rate_limit_window_secs: 60,
+            escalation_check_interval_secs: 30,
+            sinks: HashMap::new(),
+            routes: HashMap::new(),
+        }
+    }
+}
+
+impl AlertingConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.dedup_window_secs == 0
+            || self.rate_limit_window_secs == 0
+            || self.escalation_check_interval_secs == 0
+        {
+            return Err("Alerting windows and intervals must be greater than zero".to_string());
+        }
+
+        for (alert_type, route) in &self.routes {
+            if let Some(sink) = route
+                .sinks
+                .iter()
+                .find(|s| *s != NOTIFICATIONS_SINK && !self.sinks.contains_key(*s))
+            {
+                return Err(format!(
+                    "Alert route {} refers to unknown sink {}",
+                    alert_type, sink
+                ));
+            }
+            if route.dedup_window_secs == Some(0) || route.escalate_after_minutes == Some(0) {
+                return Err(format!(
+                    "Alert route {} windows must be greater than zero",
+                    alert_type
+                ));
+            }
+        }
+
+        self.sinks.iter().try_for_each(|(name, sink)| {
+            sink.validate().map_err(|e| format!("Sink {}: {}", name, e))
+        })
+    }
+
+    fn route(&self, alert_type: &str) -> Option<&AlertRoute> {
+        self.routes
+            .get(alert_type)
+            .or_else(|| self.routes.get(DEFAULT_ROUTE))
+    }
+}
+
+/// Where alerts of one type go and how often they repeat
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRoute {
+    #[serde(default)]
+    pub sinks: Vec<String>,
+    /// Overrides the gateway-wide dedup window for this type
+    #[serde(default)]
+    pub dedup_window_secs: Option<u64>,
+    /// Deliver the alert again while it is still being raised this long after the
+    /// last delivery; unset never escalates
+    #[serde(default)]
+    pub escalate_after_minutes: Option<u64>,
+    /// Alerts below this severity are only logged
+    #[serde(default = "default_min_severity")]
+    pub min_severity: AlertLevel,
+}
+
+fn default_min_severity() -> AlertLevel {
+    AlertLevel::Info
+}
+
+impl Default for AlertRoute {
+    fn default() -> Self {
+        Self {
+            sinks: Vec::new(),
+            dedup_window_secs: None,
+            escalate_after_minutes: None,
+            min_severity: default_min_severity(),
+        }
+    }
+}
+
+/// A condition reported by a risk monitor. Alerts with the same type and key describe
+/// the same condition and are deduplicated against each other.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub alert_type: String,
+    pub key: String,
+    pub severity: AlertLevel,
+    pub account_id: Option<AccountId>,
+    pub message: String,
+    pub raised_at: DateTime<Utc>,
+}
+
+/// What the sinks receive for each delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertNotification {
+    pub alert: Alert,
+    pub first_raised_at: DateTime<Utc>,
+    /// Times the condition was raised, including suppressed repeats
+    pub occurrences: u32,
+    /// Zero for the first delivery, then counts the repeats sent while unresolved
+    pub escalation: u32,
+}
+
+impl AlertNotification {
+    pub fn title(&self) -> String {
+        let escalation = if self.escalation > 0 {
+            format!(" [ESCALATION {}]", self.escalation)
+        } else {
+            String::new()
+        };
+        format!(
+            "[{:?}] {} alert{}",
+            self.alert.severity, self.alert.alert_type, escalation
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertOutcome {
+    Delivered,
+    /// Repeat of an active alert inside its dedup window
+    Suppressed,
+    /// Over the severity's rate limit; retried on the next escalation check
+    RateLimited,
+    /// Below the route's minimum severity
+    Ignored,
+}
+
+impl AlertOutcome {
+    fn label(&self) -> &'static str {
+        match self {
+            AlertOutcome::Delivered => "delivered",
+            AlertOutcome::Suppressed => "suppressed",
+            AlertOutcome::RateLimited => "rate_limited",
+            AlertOutcome::Ignored => "ignored",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveAlertStatus {
+    pub alert: Alert,
+    pub first_raised_at: DateTime<Utc>,
+    pub last_delivered_at: Option<DateTime<Utc>>,
+    pub occurrences: u32,
+    pub escalations: u32,
+}
+
+struct ActiveAlert {
+    alert: Alert,
+    first_raised_at: DateTime<Utc>,
+    last_seen_at: DateTime<Utc>,
+    /// Unset while the alert has been rate limited since it was first raised
+    last_delivered_at: Option<DateTime<Utc>>,
+    delivered_severity: AlertLevel,
+    occurrences: u32,
+    escalations: u32,
+}
+
+impl ActiveAlert {
+    fn notification(&self) -> AlertNotification {
+        AlertNotification {
+            alert: self.alert.clone(),
+            first_raised_at: self.first_raised_at,
+            occurrences: self.occurrences,
+            escalation: self.escalations,
+        }
+    }
+}
+
+#[derive(Default)]
+struct GatewayState {
+    active: HashMap<(String, String), ActiveAlert>,
+    deliveries: HashMap<AlertLevel, VecDeque<DateTime<Utc>>>,
+    /// First occurrence of every condition, oldest first
+    raised: VecDeque<Alert>,
+}
+
+/// Sits between the risk alert managers and the outside world: repeats of an active
+/// alert are suppressed, deliveries are rate limited per severity, and alerts still
+/// being raised are escalated again once their route's escalation delay has passed
+pub struct AlertGateway {
+    config: AlertingConfig,
+    sinks: HashMap<String, Arc<dyn AlertSink>>,
+    state: Mutex<GatewayState>,
+}
+
+impl AlertGateway {
+    pub fn new(config: AlertingConfig) -> Self {
+        Self {
+            config,
+            sinks: HashMap::new(),
+            state: Mutex::new(GatewayState::default()),
+        }
+    }
+
+    /// Build a gateway with every sink in the config
+    pub fn from_config(config: AlertingConfig) -> Result<Self> {
+        let sinks = config
+            .sinks
+            .iter()
+            .map(|(name, sink)| Ok((name.clone(), sink.build(name)?)))
+            .collect::<Result<HashMap<_, _>>>()?;
+        Ok(Self {
+            sinks,
+            ..Self::new(config)
+        })
+    }
+
+    /// Register a sink under `name`, replacing any configured sink of that name
+    pub fn with_sink(mut self, name: &str, sink: Arc<dyn AlertSink>) -> Self {
+        self.sinks.insert(name.to_string(), sink);
+        self
+    }
+
+    /// Submit an alert raised at `alert.raised_at`, delivering it unless it is a
+    /// repeat or over its severity's rate limit. A repeat at a higher severity than
+    /// the last delivery is delivered straight away.
+    pub async fn submit(&self, alert: Alert) -> AlertOutcome {
+        let route = self
+            .config
+            .route(&alert.alert_type)
+            .cloned()
+            .unwrap_or_default();
+        let alert_type = alert.alert_type.clone();
+
+        let (outcome, notification) = if alert.severity < route.min_severity {
+            info!("Alert below route threshold: {}", alert.message);
+            (AlertOutcome::Ignored, None)
+        } else {
+            self.record(alert, &route)
+        };
+
+        ALERTS_TOTAL
+            .with_label_values(&[&alert_type, outcome.label()])
+            .inc();
+        if let Some(notification) = notification {
+            self.deliver(&route, &notification).await;
+        }
+        outcome
+    }
+
+    fn record(
+        &self,
+        alert: Alert,
+        route: &AlertRoute,
+    ) -> (AlertOutcome, Option<AlertNotification>) {
+        let now = alert.raised_at;
+        let dedup_window = self.dedup_window(route);
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        let fingerprint = (alert.alert_type.clone(), alert.key.clone());
+
+        let active = state
+            .active
+            .entry(fingerprint.clone())
+            .or_insert_with(|| ActiveAlert {
+                alert: alert.clone(),
+                first_raised_at: now,
+                last_seen_at: now,
+                last_delivered_at: None,
+                delivered_severity: alert.severity,
+                occurrences: 0,
+                escalations: 0,
+            });
+        let repeat = active.last_delivered_at.is_some_and(|delivered| {
+            alert.severity <= active.delivered_severity && now - delivered < dedup_window
+        });
+        let first_occurrence = active.occurrences == 0;
+        active.occurrences += 1;
+        active.last_seen_at = now;
+        active.alert = alert;
+        if first_occurrence {
+            let raised = active.alert.clone();
+            if state.raised.len() == RAISED_HISTORY_LIMIT {
+                state.raised.pop_front();
+            }
+            state.raised.push_back(raised);
+        }
+        if repeat {
+            return (AlertOutcome::Suppressed, None);
+        }
+
+        let severity = active.alert.severity;
+        if !self.take_delivery_slot(&mut state.deliveries, severity, now) {
+            warn!(
+                "Rate limited {:?} alert {}/{}",
+                severity, fingerprint.0, fingerprint.1
+            );
+            return (AlertOutcome::RateLimited, None);
+        }
+
+        let active = state.active.get_mut(&fingerprint).unwrap();
+        active.last_delivered_at = Some(now);
+        active.delivered_severity = severity;
+        (AlertOutcome::Delivered, Some(active.notification()))
+    }
+
+    /// Explicitly resolve an alert so that the next occurrence is delivered as new
+    pub fn resolve(&self, alert_type: &str, key: &str) -> bool {
+        let resolved = self
+            .state
+            .lock()
+            .unwrap()
+            .active
+            .remove(&(alert_type.to_string(), key.to_string()))
+            .is_some();
+        if resolved {
+            info!("Alert {}/{} resolved", alert_type, key);
+        }
+        resolved
+    }
+
+    /// Drop alerts that stopped being raised, retry rate limited ones and re-deliver
+    /// those whose escalation delay has passed. Returns the notifications sent.
+    pub async fn run_escalations(&self, now: DateTime<Utc>) -> Vec<AlertNotification> {
+        let mut due = Vec::new();
+        {
+            let mut state = self.state.lock().unwrap();
+            let GatewayState {
+                active, deliveries, ..
+            } = &mut *state;
+
+            active.retain(|(alert_type, key), active| {
+                let route = self.config.route(alert_type).cloned().unwrap_or_default();
+                let stale = now - active.last_seen_at >= self.dedup_window(&route);
+                if stale {
+                    info!(
+                        "Alert {}/{} no longer raised, treating as resolved",
+                        alert_type, key
+                    );
+                }
+                !stale
+            });
+
+            for ((alert_type, _), active) in active.iter_mut() {
+                let route = self.config.route(alert_type).cloned().unwrap_or_default();
+                let escalating = match (active.last_delivered_at, route.escalate_after_minutes) {
+                    // Rate limited so far, so this is its first delivery
+                    (None, _) => false,
+                    (Some(delivered), Some(minutes))
+                        if now - delivered >= ChronoDuration::minutes(minutes as i64) =>
+                    {
+                        true
+                    }
+                    _ => continue,
+                };
+                if !self.take_delivery_slot(deliveries, active.alert.severity, now) {
+                    continue;
+                }
+                if escalating {
+                    active.escalations += 1;
+                    ALERTS_TOTAL
+                        .with_label_values(&[alert_type, "escalated"])
+                        .inc();
+                }
+                active.last_delivered_at = Some(now);
+                active.delivered_severity = active.alert.severity;
+                due.push((route, active.notification()));
+            }
+        }
+
+        let mut sent = Vec::with_capacity(due.len());
+        for (route, notification) in due {
+            self.deliver(&route, &notification).await;
+            sent.push(notification);
+        }
+        sent
+    }
+
+    pub fn active_alerts(&self) -> Vec<ActiveAlertStatus> {
+        let mut alerts: Vec<ActiveAlertStatus> = self
+            .state
+            .lock()
+            .unwrap()
+            .active
+            .values()
+            .map(|active| ActiveAlertStatus {
+                alert: active.alert.clone(),
+                first_raised_at: active.first_raised_at,
+                last_delivered_at: active.last_delivered_at,
+                occurrences: active.occurrences,
+                escalations: active.escalations,
+            })
+            .collect();
+        alerts.sort_by_key(|a| a.first_raised_at);
+        alerts
+    }
+
+    /// Conditions first raised in `[from, to)`, oldest first. Repeats of a condition
+    /// are not listed again until it has been resolved.
+    pub fn raised_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<Alert> {
+        self.state
+            .lock()
+            .unwrap()
+            .raised
+            .iter()
+            .filter(|alert| alert.raised_at >= from && alert.raised_at < to)
+            .cloned()
+            .collect()
+    }
+
+    fn dedup_window(&self, route: &AlertRoute) -> ChronoDuration {
+        ChronoDuration::seconds(
+            route
+                .dedup_window_secs
+                .unwrap_or(self.config.dedup_window_secs) as i64,
+        )
+    }
+
+    fn take_delivery_slot(
+        &self,
+        deliveries: &mut HashMap<AlertLevel, VecDeque<DateTime<Utc>>>,
+        severity: AlertLevel,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let Some(&limit) = self.config.rate_limits.get(&severity) else {
+            return true;
+        };
+        let window = ChronoDuration::seconds(self.config.rate_limit_window_secs as i64);
+        let sent = deliveries.entry(severity).or_default();
+        while sent.front().is_some_and(|at| now - *at >= window) {
+            sent.pop_front();
+        }
+        if sent.len() >= limit as usize {
+            return false;
+        }
+        sent.push_back(now);
+        true
+    }
+
+    async fn deliver(&self, route: &AlertRoute, notification: &AlertNotification) {
+        match notification.alert.severity {
+            AlertLevel::Critical | AlertLevel::Emergency => {
+                error!("{}: {}", notification.title(), notification.alert.message)
+            }
+            _ => warn!("{}: {}", notification.title(), notification.alert.message),
+        }
+
+        let deliveries = route.sinks.iter().filter_map(|name| {
+            let sink = self.sinks.get(name);
+            if sink.is_none() {
+                warn!("Alert sink {} is not registered", name);
+            }
+            sink.map(|sink| async move { (name, sink.deliver(notification).await) })
+        });
+        for (name, result) in join_all(deliveries).await {
+            if let Err(e) = result {
+                error!("Failed to deliver alert via {}: {}", name, e);
+                ALERTS_TOTAL
+                    .with_label_values(&[&notification.alert.alert_type, "delivery_failed"])
+                    .inc();
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for AlertGateway {
+    fn name(&self) -> &str {
+        "alert-gateway"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_secs(
+            self.config.escalation_check_interval_secs,
+        ));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    self.run_escalations(Utc::now()).await;
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
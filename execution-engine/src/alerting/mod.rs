@@ -0,0 +1,8 @@
+pub mod gateway;
+pub mod sinks;
+
+pub use gateway::{
+    ActiveAlertStatus, Alert, AlertGateway, AlertNotification, AlertOutcome, AlertRoute,
+    AlertingConfig, DEFAULT_ROUTE,
+};
+pub use sinks::{AlertSink, EmailSink, SinkConfig, SlackSink, WebhookSink, NOTIFICATIONS_SINK};
@@ -0,0 +1,264 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use super::gateway::AlertNotification;
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sink name the engine registers its chat notifier under; routes may use it
+/// without a matching entry in `sinks`
+pub const NOTIFICATIONS_SINK: &str = "notifications";
+
+/// A delivery integration for alerts that made it through the gateway
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    async fn deliver(&self, notification: &AlertNotification) -> Result<()>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkConfig {
+    /// POSTs the notification as JSON
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    /// Slack incoming webhook
+    Slack {
+        webhook_url: String,
+        #[serde(default)]
+        channel: Option<String>,
+    },
+    /// Plain SMTP to a relay that accepts mail without authentication
+    Email {
+        smtp_host: String,
+        #[serde(default = "default_smtp_port")]
+        smtp_port: u16,
+        from: String,
+        to: Vec<String>,
+    },
+}
+
+fn default_smtp_port() -> u16 {
+    25
+}
+
+impl SinkConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            SinkConfig::Webhook { url, .. }
+            | SinkConfig::Slack {
+                webhook_url: url, ..
+            } => url
+                .parse::<reqwest::Url>()
+                .map(|_| ())
+                .map_err(|e| format!("invalid url {}: {}", url, e)),
+            SinkConfig::Email { to, .. } if to.is_empty() => {
+                Err("email sink needs at least one recipient".to_string())
+            }
+            SinkConfig::Email { .. } => Ok(()),
+        }
+    }
+
+    pub fn build(&self, name: &str) -> Result<Arc<dyn AlertSink>> {
+        self.validate()
+            .map_err(|e| anyhow!("Sink {}: {}", name, e))?;
+        Ok(match self.clone() {
+            SinkConfig::Webhook { url, headers } => Arc::new(WebhookSink::new(url, headers)?),
+            SinkConfig::Slack {
+                webhook_url,
+                channel,
+            } => Arc::new(SlackSink::new(webhook_url, channel)?),
+            SinkConfig::Email {
+                smtp_host,
+                smtp_port,
+                from,
+                to,
+            } => Arc::new(EmailSink::new(smtp_host, smtp_port, from, to)),
+        })
+    }
+}
+
+fn http_client() -> Result<Client> {
+    Ok(Client::builder().timeout(DELIVERY_TIMEOUT).build()?)
+}
+
+pub struct WebhookSink {
+    client: Client,
+    url: String,
+    headers: HashMap<String, String>,
+}
+
+impl WebhookSink {
+    pub fn new(url: String, headers: HashMap<String, String>) -> Result<Self> {
+        Ok(Self {
+            client: http_client()?,
+            url,
+            headers,
+        })
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookSink {
+    async fn deliver(&self, notification: &AlertNotification) -> Result<()> {
+        let mut request = self.client.post(&self.url).json(notification);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+pub struct SlackSink {
+    client: Client,
+    webhook_url: String,
+    channel: Option<String>,
+}
+
+impl SlackSink {
+    pub fn new(webhook_url: String, channel: Option<String>) -> Result<Self> {
+        Ok(Self {
+            client: http_client()?,
+            webhook_url,
+            channel,
+        })
+    }
+}
+
+#[async_trait]
+impl AlertSink for SlackSink {
+    async fn deliver(&self, notification: &AlertNotification) -> Result<()> {
+        let mut payload = json!({
+            "text": format!(
+                "*{}*\n{}\n_raised {} times since {}_",
+                notification.title(),
+                notification.alert.message,
+                notification.occurrences,
+                notification.first_raised_at.format("%Y-%m-%d %H:%M:%S UTC")
+            ),
+        });
+        if let Some(channel) = &self.channel {
+            payload["channel"] = json!(channel);
+        }
+        self.client
+            .post(&self.webhook_url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+pub struct EmailSink {
+    smtp_host: String,
+    smtp_port: u16,
+    from: String,
+    to: Vec<String>,
+}
+
+impl EmailSink {
+    pub fn new(smtp_host: String, smtp_port: u16, from: String, to: Vec<String>) -> Self {
+        Self {
+            smtp_host,
+            smtp_port,
+            from,
+            to,
+        }
+    }
+
+    fn message(&self, notification: &AlertNotification) -> String {
+        let alert = &notification.alert;
+        let mut body = format!(
+            "{}\r\n\r\nType: {}\r\nKey: {}\r\nFirst raised: {}\r\nOccurrences: {}\r\n",
+            alert.message,
+            alert.alert_type,
+            alert.key,
+            notification.first_raised_at.to_rfc3339(),
+            notification.occurrences
+        );
+        if let Some(account_id) = alert.account_id {
+            body.push_str(&format!("Account: {}\r\n", account_id));
+        }
+
+        let content = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}",
+            self.from,
+            self.to.join(", "),
+            notification.title(),
+            alert.raised_at.to_rfc2822(),
+            body
+        );
+        // Dot-stuffing so a line starting with '.' cannot end the DATA section early
+        format!("{}\r\n.\r\n", content.replace("\r\n.", "\r\n.."))
+    }
+
+    async fn send(&self, message: &str) -> Result<()> {
+        let stream = TcpStream::connect((self.smtp_host.as_str(), self.smtp_port))
+            .await
+            .with_context(|| format!("connecting to {}:{}", self.smtp_host, self.smtp_port))?;
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+
+        expect_reply(&mut reader, 220).await?;
+        let mut commands = vec![
+            ("EHLO execution-engine\r\n".to_string(), 250),
+            (format!("MAIL FROM:<{}>\r\n", self.from), 250),
+        ];
+        for recipient in &self.to {
+            commands.push((format!("RCPT TO:<{}>\r\n", recipient), 250));
+        }
+        commands.push(("DATA\r\n".to_string(), 354));
+        commands.push((message.to_string(), 250));
+        commands.push(("QUIT\r\n".to_string(), 221));
+
+        for (line, code) in commands {
+            writer.write_all(line.as_bytes()).await?;
+            expect_reply(&mut reader, code).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Read a possibly multi-line SMTP reply and check its code
+async fn expect_reply<R: AsyncBufReadExt + Unpin>(reader: &mut R, expected: u16) -> Result<()> {
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            bail!("SMTP server closed the connection");
+        }
+        let code: u16 = line
+            .get(..3)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| anyhow!("Malformed SMTP reply: {}", line.trim_end()))?;
+        if code != expected {
+            bail!("Unexpected SMTP reply: {}", line.trim_end());
+        }
+        // "250-..." continues the reply, "250 ..." ends it
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok(());
+        }
+    }
+}
+
+#[async_trait]
+impl AlertSink for EmailSink {
+    async fn deliver(&self, notification: &AlertNotification) -> Result<()> {
+        let message = self.message(notification);
+        timeout(DELIVERY_TIMEOUT, self.send(&message))
+            .await
+            .map_err(|_| anyhow!("SMTP delivery timed out"))?
+    }
+}
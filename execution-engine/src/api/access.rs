@@ -0,0 +1,179 @@
+// Enforcement of the API's access rules: who the caller is, what their role
+// lets them do with a route, and which accounts they may name
+
+use axum::extract::{Query, Request, State};
+use axum::http::{header, HeaderMap, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+use super::{AccountQuery, ApiState, ErrorResponse};
+use crate::auth::{AuthError, Authenticator, Principal, Role, API_KEY_HEADER};
+
+/// Admin routes whose account is named in the request body, checked by their
+/// handlers with [`authorize_account`]
+const BODY_SCOPED: &[&str] = &[
+    "/admin/emergency-close",
+    "/admin/feature-flags",
+    "/admin/dry-run",
+];
+
+/// Routes serving only the account named by `?account_id`, and every account
+/// when none is named
+const QUERY_SCOPED: &[&str] = &[
+    "/positions",
+    "/orders",
+    "/executions",
+    "/executions/page",
+    "/journal/trades",
+    "/admin/dry-run/orders",
+];
+
+/// Routes whose handlers leave out what the caller's accounts do not cover
+const CALLER_FILTERED: &[&str] = &[
+    "/accounts",
+    "/reconciliation/drop-copy",
+    "/reconciliation/statements",
+    "/reports/tax-lots",
+    "/analytics/execution",
+    "/analytics/personas",
+    "/risk/snapshots",
+];
+
+fn caller_filtered(path: &str) -> bool {
+    CALLER_FILTERED.contains(&path) || path.starts_with("/journal/trades/")
+}
+
+/// Role needed for `method` on `path`: reads are open to viewers, admin routes
+/// to admins and every other change to operators
+pub fn required_role(method: &Method, path: &str) -> Role {
+    if method == Method::GET || method == Method::HEAD {
+        Role::Viewer
+    } else if path.starts_with("/admin/") {
+        Role::Admin
+    } else {
+        Role::Operator
+    }
+}
+
+/// Resolve the caller from an `x-api-key` header or a bearer token
+pub fn authenticate(auth: &Authenticator, request: &Request) -> Result<Principal, AuthError> {
+    authenticate_headers(auth, request.headers())
+}
+
+/// [`authenticate`] from bare headers, e.g. of a WebSocket handshake
+pub fn authenticate_headers(
+    auth: &Authenticator,
+    headers: &HeaderMap,
+) -> Result<Principal, AuthError> {
+    if let Some(key) = headers.get(API_KEY_HEADER) {
+        let key = key.to_str().map_err(|_| AuthError::InvalidApiKey)?;
+        return auth.authenticate_api_key(key);
+    }
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(AuthError::MissingCredentials)?;
+    auth.authenticate_token(token.trim())
+}
+
+/// Check `principal` may use `method` on `path`, naming `query_account` if any
+fn authorize(
+    principal: &Principal,
+    method: &Method,
+    path: &str,
+    query_account: Option<&str>,
+) -> Result<(), AuthError> {
+    principal.require(required_role(method, path))?;
+
+    let path_account = path
+        .strip_prefix("/accounts/")
+        .and_then(|rest| rest.split('/').next())
+        .filter(|id| !id.is_empty());
+    for account_id in path_account.into_iter().chain(query_account) {
+        principal.require_account(account_id)?;
+    }
+
+    // Routes that span every account are left to principals that may see them
+    // all. A query account only confines the routes whose handlers honour it.
+    if !principal.is_scoped() || path_account.is_some() || caller_filtered(path) {
+        return Ok(());
+    }
+    if QUERY_SCOPED.contains(&path) {
+        return match query_account {
+            Some(_) => Ok(()),
+            None => Err(AuthError::AccountRequired),
+        };
+    }
+    if method == Method::POST && BODY_SCOPED.contains(&path) {
+        return Ok(());
+    }
+    Err(AuthError::AllAccountsRequired)
+}
+
+/// Check the caller of a body-scoped admin route may act on `account_id`, where
+/// `None` means every account
+pub fn authorize_account(
+    principal: Option<&Principal>,
+    account_id: Option<&str>,
+) -> Result<(), AuthError> {
+    match (principal, account_id) {
+        (Some(principal), Some(account_id)) => principal.require_account(account_id),
+        (Some(principal), None) if principal.is_scoped() => Err(AuthError::AccountRequired),
+        _ => Ok(()),
+    }
+}
+
+pub fn auth_error_response(error: AuthError) -> Response {
+    let body = Json(ErrorResponse {
+        error: error.to_string(),
+    });
+    if error.is_unauthenticated() {
+        (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            body,
+        )
+            .into_response()
+    } else {
+        (StatusCode::FORBIDDEN, body).into_response()
+    }
+}
+
+/// Middleware admitting requests the caller's credentials allow, with the
+/// caller's [`Principal`] added to the request for handlers to scope by
+pub async fn enforce(State(state): State<ApiState>, mut request: Request, next: Next) -> Response {
+    let path = request.uri().path().to_string();
+    let public = path == "/health" || path.starts_with("/health/") || path == "/status";
+    if !state.auth.is_enabled() || public {
+        return next.run(request).await;
+    }
+
+    let principal = match authenticate(&state.auth, &request) {
+        Ok(principal) => principal,
+        Err(e) => return auth_error_response(e),
+    };
+    let query_account = Query::<AccountQuery>::try_from_uri(request.uri())
+        .ok()
+        .and_then(|Query(query)| query.account_id);
+    if let Err(e) = authorize(
+        &principal,
+        request.method(),
+        &path,
+        query_account.as_deref(),
+    ) {
+        tracing::warn!(
+            "Refused {} {} to {} ({}): {}",
+            request.method(),
+            path,
+            principal.subject,
+            principal.role,
+            e
+        );
+        return auth_error_response(e);
+    }
+
+    request.extensions_mut().insert(principal);
+    next.run(request).await
+}
@@ -1,2 +1,1085 @@
 // API endpoints for the execution engine
-// This will contain HTTP endpoints for order management and monitoring
\ No newline at end of file
+// This will contain HTTP endpoints for order management and monitoring
+
+pub mod access;
+pub mod status;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{middleware, Extension, Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use self::status::StatusPage;
+use crate::auth::{Authenticator, Principal};
+use crate::dashboard::{DashboardAggregator, ExitSystems};
+use crate::execution::drop_copy::DropCopyReconciler;
+use crate::execution::exit_management::{
+    ExitAuditLogger, ExitManagementSystem, ExitPolicy, PositionAdopter,
+};
+use crate::execution::{
+    ControlAction, ExecutionHistoryQuery, TagFilter, TradeExecutionOrchestrator, TradeSignal,
+};
+use crate::journal::{TradeJournal, TradeQuery};
+use crate::ledger::{PositionLedger, StatementReconciler};
+use crate::notifications::{Notification, Notifier};
+use crate::platforms::abstraction::models::UnifiedPosition;
+use crate::platforms::abstraction::DryRunMode;
+use crate::reports::{PersonaDivergenceReport, StrategyScores, TaxLotQuery, TaxLotReport};
+use crate::risk::{RiskSnapshotQuery, RiskSnapshotter};
+use crate::runtime::{
+    Feature, FeatureFlags, HealthChecker, HealthLevel, HealthReport, ShutdownCoordinator,
+    ShutdownReport,
+};
+
+#[derive(Clone)]
+pub struct ApiState {
+    pub orchestrator: Arc<TradeExecutionOrchestrator>,
+    pub health: Arc<HealthChecker>,
+    pub shutdown: Arc<ShutdownCoordinator>,
+    pub dashboard: Arc<DashboardAggregator>,
+    pub journal: Arc<TradeJournal>,
+    pub exit_systems: ExitSystems,
+    pub exit_logger: Arc<ExitAuditLogger>,
+    pub notifier: Arc<Notifier>,
+    pub feature_flags: Arc<FeatureFlags>,
+    pub dry_run: Arc<DryRunMode>,
+    /// Credentials and roles checked on every request but the health probes
+    pub auth: Arc<Authenticator>,
+    /// Cache and rate limit of the public `/status` summary
+    pub status: Arc<StatusPage>,
+    /// Matching of broker drop-copy fills, when a drop-copy session is open
+    pub drop_copy: Option<Arc<DropCopyReconciler>>,
+    /// Position ledger the tax lot report is replayed from, when enabled
+    pub ledger: Option<Arc<PositionLedger>>,
+    /// Scores of each strategy from the journal, when scoring is enabled
+    pub signal_quality: Option<Arc<StrategyScores>>,
+    /// Adoption of positions opened outside the engine, when exit management runs
+    pub adoption: Option<Arc<PositionAdopter>>,
+    /// Intraday risk snapshots, when they are taken
+    pub risk_snapshots: Option<Arc<RiskSnapshotter>>,
+    /// Platform statements checked against the ledger, when enabled
+    pub statements: Option<Arc<StatementReconciler>>,
+}
+
+pub type HealthResponse = HealthReport;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProbeResponse {
+    pub ok: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<usize>,
+    /// Comma separated tags the entries must all carry
+    pub tags: Option<String>,
+}
+
+/// Filters and paging of `/executions`; `offset` counts back from the newest match
+#[derive(Debug, Deserialize)]
+pub struct ExecutionHistoryParams {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /// Comma separated tags the entries must all carry
+    pub tags: Option<String>,
+    pub signal_id: Option<String>,
+    pub account_id: Option<String>,
+    pub action: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl ExecutionHistoryParams {
+    fn into_query(self) -> ExecutionHistoryQuery {
+        ExecutionHistoryQuery {
+            signal_id: self.signal_id,
+            account_id: self.account_id,
+            action: self.action,
+            since: self.since.map(SystemTime::from),
+            until: self.until.map(SystemTime::from),
+            tags: TagFilter::parse(self.tags.as_deref()).tags().to_vec(),
+            limit: self.limit.unwrap_or(100),
+            offset: self.offset.unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountQuery {
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DryRunOrdersQuery {
+    pub account_id: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PositionQuery {
+    pub account_id: Option<String>,
+    /// Comma separated tags the positions must all carry
+    pub tags: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EmergencyCloseRequest {
+    pub account_id: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KillSwitchRequest {
+    pub engaged: bool,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeatureFlagRequest {
+    pub feature: Feature,
+    /// Unset to flag the feature for every account
+    pub account_id: Option<String>,
+    /// Unset to clear the flag, falling back to the global flag or to enabled
+    pub enabled: Option<bool>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DryRunSwitchRequest {
+    /// Unset to switch dry run for every account
+    pub account_id: Option<String>,
+    /// Unset to clear the account's override, falling back to the global switch
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ShutdownRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShutdownStatus {
+    pub requested: bool,
+    pub report: Option<ShutdownReport>,
+}
+
+pub fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/health/live", get(liveness))
+        .route("/health/ready", get(readiness))
+        .route("/status", get(status::status))
+        .route("/metrics", get(metrics))
+        .route("/accounts", get(list_accounts))
+        .route("/accounts/:account_id", get(get_account))
+        .route(
+            "/accounts/:account_id/diagnostics",
+            get(account_diagnostics),
+        )
+        .route("/accounts/:account_id/pause", post(pause_account))
+        .route("/accounts/:account_id/resume", post(resume_account))
+        .route("/positions", get(open_positions))
+        .route("/positions/adopted", get(adopted_positions))
+        .route("/orders", get(working_orders))
+        .route("/signals/preview", post(preview_signal))
+        .route("/executions", get(execution_history))
+        .route("/executions/page", get(execution_history_page))
+        .route("/exits", get(recent_exits))
+        .route("/reconciliation/drop-copy", get(drop_copy_report))
+        .route("/reconciliation/statements", get(statement_reconciliations))
+        .route("/reports/tax-lots", get(tax_lot_report))
+        .route("/analytics/signal-quality", get(signal_quality))
+        .route("/analytics/execution", get(execution_analytics))
+        .route("/analytics/personas", get(persona_divergence))
+        .route("/dashboard/state", get(dashboard_state))
+        .route("/dashboard/equity", get(consolidated_equity))
+        .route("/dashboard/exposure-heatmap", get(exposure_heatmap))
+        .route("/risk/snapshots", get(risk_snapshots))
+        .route("/journal/trades", get(journal_trades))
+        .route("/journal/trades/:trade_id", get(journal_trade))
+        .route(
+            "/accounts/:account_id/positions/:position_id/exit-policy",
+            get(get_exit_policy)
+                .put(set_exit_policy)
+                .delete(clear_exit_policy),
+        )
+        .route(
+            "/accounts/:account_id/positions/:position_id/exit-preview",
+            get(preview_exits),
+        )
+        .route(
+            "/accounts/:account_id/positions/:position_id/adopt",
+            post(adopt_position),
+        )
+        .route("/admin/emergency-close", post(emergency_close))
+        .route(
+            "/admin/kill-switch",
+            get(kill_switch_status).post(set_kill_switch),
+        )
+        .route(
+            "/admin/feature-flags",
+            get(feature_flags).post(set_feature_flag),
+        )
+        .route("/admin/feature-flags/history", get(feature_flag_history))
+        .route("/admin/dry-run", get(dry_run_state).post(set_dry_run))
+        .route("/admin/dry-run/orders", get(dry_run_orders))
+        .route(
+            "/admin/shutdown",
+            get(shutdown_status).post(request_shutdown),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            access::enforce,
+        ))
+        .with_state(state)
+}
+
+fn probe_status(ok: bool) -> StatusCode {
+    if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+async fn health(State(state): State<ApiState>) -> Response {
+    let report = state.health.check().await;
+    (
+        probe_status(report.status != HealthLevel::Unhealthy),
+        Json(report),
+    )
+        .into_response()
+}
+
+async fn liveness(State(state): State<ApiState>) -> Response {
+    let ok = state.health.is_live().await;
+    (probe_status(ok), Json(ProbeResponse { ok })).into_response()
+}
+
+async fn readiness(State(state): State<ApiState>) -> Response {
+    let ok = state.health.check().await.ready;
+    (probe_status(ok), Json(ProbeResponse { ok })).into_response()
+}
+
+/// Prometheus exposition of every registered metric, FIX session health
+/// included
+async fn metrics() -> Response {
+    match prometheus::TextEncoder::new().encode_to_string(&prometheus::gather()) {
+        Ok(body) => (
+            [(axum::http::header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+            body,
+        )
+            .into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn list_accounts(State(state): State<ApiState>, caller: Caller) -> Response {
+    let mut accounts = state.orchestrator.get_all_account_statuses().await;
+    if let Some(principal) = principal(&caller) {
+        accounts.retain(|account| principal.can_access(&account.account_id));
+    }
+    Json(accounts).into_response()
+}
+
+async fn get_account(State(state): State<ApiState>, Path(account_id): Path<String>) -> Response {
+    match state.orchestrator.get_account_status(&account_id).await {
+        Some(status) => Json(status).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Diagnostics of the account's platform adapter, with the latency and SLA
+/// compliance of each operation when calls to it are timed
+async fn account_diagnostics(
+    State(state): State<ApiState>,
+    Path(account_id): Path<String>,
+) -> Response {
+    let platform = state
+        .orchestrator
+        .get_platforms()
+        .await
+        .into_iter()
+        .find_map(|(id, platform)| (id == account_id).then_some(platform));
+    let Some(platform) = platform else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    match platform.get_diagnostics().await {
+        Ok(diagnostics) => Json(diagnostics).into_response(),
+        Err(e) => error_response(StatusCode::BAD_GATEWAY, e.to_string()),
+    }
+}
+
+fn error_response(status: StatusCode, error: String) -> Response {
+    (status, Json(ErrorResponse { error })).into_response()
+}
+
+/// The authenticated caller, absent when the API runs without authentication
+type Caller = Option<Extension<Principal>>;
+
+fn principal(caller: &Caller) -> Option<&Principal> {
+    caller.as_ref().map(|Extension(principal)| principal)
+}
+
+/// A control action by the caller, to be recorded in the audit trail
+fn control(caller: &Caller, action: &str) -> ControlAction {
+    ControlAction::new(principal(caller), action)
+}
+
+async fn pause_account(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Path(account_id): Path<String>,
+) -> Response {
+    let result = state.orchestrator.pause_account(&account_id).await;
+    state
+        .orchestrator
+        .record_control_action(
+            &control(&caller, "pause_account")
+                .with_account(Some(&account_id))
+                .with_outcome(&result, "paused"),
+        )
+        .await;
+    match result {
+        Ok(()) => Json(state.orchestrator.get_account_status(&account_id).await).into_response(),
+        Err(e) => error_response(StatusCode::NOT_FOUND, e),
+    }
+}
+
+async fn resume_account(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Path(account_id): Path<String>,
+) -> Response {
+    let result = state.orchestrator.resume_account(&account_id).await;
+    state
+        .orchestrator
+        .record_control_action(
+            &control(&caller, "resume_account")
+                .with_account(Some(&account_id))
+                .with_outcome(&result, "resumed"),
+        )
+        .await;
+    match result {
+        Ok(()) => Json(state.orchestrator.get_account_status(&account_id).await).into_response(),
+        Err(e) => error_response(StatusCode::NOT_FOUND, e),
+    }
+}
+
+async fn open_positions(
+    State(state): State<ApiState>,
+    Query(query): Query<PositionQuery>,
+) -> Response {
+    let filter = TagFilter::parse(query.tags.as_deref());
+    match state
+        .orchestrator
+        .get_tagged_positions(query.account_id.as_deref(), &filter)
+        .await
+    {
+        Ok(positions) => Json(positions).into_response(),
+        Err(e) => error_response(StatusCode::BAD_GATEWAY, e),
+    }
+}
+
+async fn working_orders(
+    State(state): State<ApiState>,
+    Query(query): Query<AccountQuery>,
+) -> Response {
+    match state
+        .orchestrator
+        .get_working_orders(query.account_id.as_deref())
+        .await
+    {
+        Ok(orders) => Json(orders).into_response(),
+        Err(e) => error_response(StatusCode::BAD_GATEWAY, e),
+    }
+}
+
+async fn execution_history(
+    State(state): State<ApiState>,
+    Query(params): Query<ExecutionHistoryParams>,
+) -> Response {
+    match state
+        .orchestrator
+        .search_execution_history(&params.into_query())
+        .await
+    {
+        Ok(page) => Json(page.entries).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e)),
+    }
+}
+
+/// Like `/executions`, with the total number of matches and the next page's offset
+async fn execution_history_page(
+    State(state): State<ApiState>,
+    Query(params): Query<ExecutionHistoryParams>,
+) -> Response {
+    match state
+        .orchestrator
+        .search_execution_history(&params.into_query())
+        .await
+    {
+        Ok(page) => Json(page).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e)),
+    }
+}
+
+async fn drop_copy_report(State(state): State<ApiState>, caller: Caller) -> Response {
+    let Some(drop_copy) = &state.drop_copy else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "No drop-copy session is configured".to_string(),
+        );
+    };
+    let mut report = drop_copy.report();
+    if let Some(principal) = principal(&caller) {
+        report
+            .accounts
+            .retain(|account_id, _| principal.can_access(account_id));
+        report
+            .unrecognized
+            .retain(|fill| principal.can_access(&fill.account_id));
+    }
+    Json(report).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaxLotParams {
+    pub account_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// `csv` for the accountants' export, JSON otherwise
+    pub format: Option<String>,
+}
+
+async fn signal_quality(State(state): State<ApiState>) -> Response {
+    let Some(scores) = &state.signal_quality else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "Signal quality scoring is disabled".to_string(),
+        );
+    };
+    match scores.report() {
+        Some(report) => Json(report).into_response(),
+        None => error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Signals have not been scored yet".to_string(),
+        ),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PersonaDivergenceParams {
+    /// Closed trades opened or closed since; the last 30 days when unset
+    pub from: Option<DateTime<Utc>>,
+}
+
+async fn persona_divergence(
+    State(state): State<ApiState>,
+    Query(params): Query<PersonaDivergenceParams>,
+    caller: Caller,
+) -> Response {
+    let now = Utc::now();
+    let from = params
+        .from
+        .unwrap_or_else(|| now - chrono::Duration::days(30));
+    let mut trades = state.journal.trades_between(from, now).await;
+    if let Some(principal) = principal(&caller) {
+        trades.retain(|trade| principal.can_access(&trade.entry.account_id));
+    }
+    Json(PersonaDivergenceReport::compile(
+        &trades,
+        state.orchestrator.personas(),
+        now,
+    ))
+    .into_response()
+}
+
+async fn execution_analytics(State(state): State<ApiState>, caller: Caller) -> Response {
+    let mut stats = state.orchestrator.slippage_stats();
+    if let Some(principal) = principal(&caller) {
+        stats.retain(|s| principal.can_access(&s.account_id));
+    }
+    Json(stats).into_response()
+}
+
+/// The latest statement reconciliation of each account
+async fn statement_reconciliations(State(state): State<ApiState>, caller: Caller) -> Response {
+    let Some(reconciler) = &state.statements else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "Statement reconciliation is disabled".to_string(),
+        );
+    };
+    let mut reconciliations = reconciler.latest().await;
+    if let Some(principal) = principal(&caller) {
+        reconciliations.retain(|r| principal.can_access(&r.account_id));
+    }
+    Json(reconciliations).into_response()
+}
+
+async fn tax_lot_report(
+    State(state): State<ApiState>,
+    Query(params): Query<TaxLotParams>,
+    caller: Caller,
+) -> Response {
+    let Some(ledger) = &state.ledger else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "The position ledger is disabled".to_string(),
+        );
+    };
+    let mut events = ledger.events().await;
+    if let Some(principal) = principal(&caller) {
+        events.retain(|event| principal.can_access(&event.account_id));
+    }
+    let report = TaxLotReport::compile(
+        &events,
+        TaxLotQuery {
+            account_id: params.account_id,
+            from: params.from,
+            to: params.to,
+        },
+    );
+
+    if params.format.as_deref() == Some("csv") {
+        (
+            [
+                (axum::http::header::CONTENT_TYPE, "text/csv"),
+                (
+                    axum::http::header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"tax-lots.csv\"",
+                ),
+            ],
+            report.to_csv(),
+        )
+            .into_response()
+    } else {
+        Json(report).into_response()
+    }
+}
+
+async fn recent_exits(
+    State(state): State<ApiState>,
+    Query(query): Query<HistoryQuery>,
+) -> Response {
+    let limit = query.limit.unwrap_or(100) as u32;
+    let filter = TagFilter::parse(query.tags.as_deref());
+    match state
+        .exit_logger
+        .get_recent_exits_matching(limit, &filter)
+        .await
+    {
+        Ok(exits) => Json(exits).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// The plan a signal would get and why, without placing any orders
+async fn preview_signal(
+    State(state): State<ApiState>,
+    Json(signal): Json<TradeSignal>,
+) -> Response {
+    Json(state.orchestrator.preview_signal(signal).await).into_response()
+}
+
+async fn dashboard_state(State(state): State<ApiState>) -> Response {
+    Json(state.dashboard.snapshot().await).into_response()
+}
+
+async fn consolidated_equity(State(state): State<ApiState>) -> Response {
+    match state.dashboard.consolidated_equity().await {
+        Some(equity) => Json(equity).into_response(),
+        None => error_response(
+            StatusCode::NOT_FOUND,
+            "Consolidation is not configured".to_string(),
+        ),
+    }
+}
+
+async fn exposure_heatmap(State(state): State<ApiState>) -> Response {
+    Json(state.dashboard.exposure_heatmap().await).into_response()
+}
+
+async fn risk_snapshots(
+    State(state): State<ApiState>,
+    Query(query): Query<RiskSnapshotQuery>,
+    caller: Caller,
+) -> Response {
+    let Some(snapshotter) = &state.risk_snapshots else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "Risk snapshots are disabled".to_string(),
+        );
+    };
+    match snapshotter.series(&query).await {
+        Ok(mut snapshots) => {
+            if let Some(principal) = principal(&caller) {
+                for snapshot in &mut snapshots {
+                    snapshot
+                        .accounts
+                        .retain(|a| principal.can_access(&a.account_id));
+                    snapshot
+                        .unavailable_accounts
+                        .retain(|a| principal.can_access(a));
+                    snapshot.unconverted.retain(|p| {
+                        p.split_once(':')
+                            .is_some_and(|(account_id, _)| principal.can_access(account_id))
+                    });
+                }
+            }
+            Json(snapshots).into_response()
+        }
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn journal_trades(
+    State(state): State<ApiState>,
+    Query(mut query): Query<TradeQuery>,
+) -> Response {
+    query.limit = Some(query.limit.unwrap_or(100));
+    Json(state.journal.query(&query).await).into_response()
+}
+
+async fn journal_trade(
+    State(state): State<ApiState>,
+    Path(trade_id): Path<String>,
+    caller: Caller,
+) -> Response {
+    // A trade of another account is as absent as one that does not exist
+    match state.journal.get_trade(&trade_id).await {
+        Some(trade) if principal(&caller).is_none_or(|p| p.can_access(&trade.entry.account_id)) => {
+            Json(trade).into_response()
+        }
+        _ => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Resolve the exit management system of an account and parse the position id
+async fn exit_system_for(
+    state: &ApiState,
+    account_id: &str,
+    position_id: &str,
+) -> Result<(ExitManagementSystem, Uuid), Response> {
+    let position_id = Uuid::parse_str(position_id).map_err(|_| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid position id: {}", position_id),
+        )
+    })?;
+    let system = state
+        .exit_systems
+        .read()
+        .await
+        .get(account_id)
+        .cloned()
+        .ok_or_else(|| {
+            error_response(
+                StatusCode::NOT_FOUND,
+                format!("No exit management for account {}", account_id),
+            )
+        })?;
+    Ok((system, position_id))
+}
+
+async fn get_exit_policy(
+    State(state): State<ApiState>,
+    Path((account_id, position_id)): Path<(String, String)>,
+) -> Response {
+    let (system, position_id) = match exit_system_for(&state, &account_id, &position_id).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    match system.get_position_policy(position_id) {
+        Some(policy) => Json(policy).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// What the exit managers would do to the position now, without doing it
+async fn preview_exits(
+    State(state): State<ApiState>,
+    Path((account_id, position_id)): Path<(String, String)>,
+) -> Response {
+    let (system, position_id) = match exit_system_for(&state, &account_id, &position_id).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    match system.preview_position(position_id).await {
+        Ok(Some(preview)) => Json(preview).into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => error_response(StatusCode::BAD_GATEWAY, format!("{:#}", e)),
+    }
+}
+
+async fn set_exit_policy(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Path((account_id, position_id)): Path<(String, String)>,
+    Json(policy): Json<ExitPolicy>,
+) -> Response {
+    let action = control(&caller, "set_exit_policy")
+        .with_account(Some(&account_id))
+        .with_parameter("position_id", &position_id)
+        .with_parameter("policy", serde_json::to_string(&policy).unwrap_or_default());
+    let (system, position_id) = match exit_system_for(&state, &account_id, &position_id).await {
+        Ok(found) => found,
+        Err(response) => {
+            state
+                .orchestrator
+                .record_control_action(&action.failed(response.status()))
+                .await;
+            return response;
+        }
+    };
+    system.set_position_policy(position_id, policy.clone());
+    state
+        .orchestrator
+        .record_control_action(&action.succeeded("policy set"))
+        .await;
+    Json(policy).into_response()
+}
+
+async fn clear_exit_policy(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Path((account_id, position_id)): Path<(String, String)>,
+) -> Response {
+    let action = control(&caller, "clear_exit_policy")
+        .with_account(Some(&account_id))
+        .with_parameter("position_id", &position_id);
+    let (system, position_id) = match exit_system_for(&state, &account_id, &position_id).await {
+        Ok(found) => found,
+        Err(response) => {
+            state
+                .orchestrator
+                .record_control_action(&action.failed(response.status()))
+                .await;
+            return response;
+        }
+    };
+    let cleared = system
+        .clear_position_policy(position_id)
+        .ok_or("no policy set");
+    state
+        .orchestrator
+        .record_control_action(&action.with_outcome(&cleared, "policy cleared"))
+        .await;
+    match cleared {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn adopted_positions(State(state): State<ApiState>) -> Response {
+    match &state.adoption {
+        Some(adopter) => Json(adopter.adopted()).into_response(),
+        None => error_response(
+            StatusCode::NOT_FOUND,
+            "Exit management is disabled".to_string(),
+        ),
+    }
+}
+
+/// Put an open position under exit management with the given policy, or the
+/// default adoption policy, and tag it as externally originated
+async fn adopt_position(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Path((account_id, position_id)): Path<(String, String)>,
+    policy: Option<Json<ExitPolicy>>,
+) -> Response {
+    let policy = policy.map(|Json(policy)| policy);
+    let action = control(&caller, "adopt_position")
+        .with_account(Some(&account_id))
+        .with_parameter("position_id", &position_id)
+        .with_parameter(
+            "policy",
+            policy
+                .as_ref()
+                .map(|p| serde_json::to_string(p).unwrap_or_default())
+                .unwrap_or_else(|| "default".to_string()),
+        );
+    let found = adoption_target(&state, &account_id, &position_id).await;
+    let (adopter, system, position) = match found {
+        Ok(found) => found,
+        Err(response) => {
+            state
+                .orchestrator
+                .record_control_action(&action.failed(response.status()))
+                .await;
+            return response;
+        }
+    };
+    let adopted = adopter
+        .adopt(&account_id, &position, &system, policy, false)
+        .await;
+    state
+        .orchestrator
+        .record_control_action(&action.succeeded("position adopted"))
+        .await;
+    Json(adopted).into_response()
+}
+
+/// The adopter, the account's exit management and the open position to adopt
+async fn adoption_target(
+    state: &ApiState,
+    account_id: &str,
+    position_id: &str,
+) -> Result<(Arc<PositionAdopter>, ExitManagementSystem, UnifiedPosition), Response> {
+    let adopter = state.adoption.clone().ok_or_else(|| {
+        error_response(
+            StatusCode::NOT_FOUND,
+            "Exit management is disabled".to_string(),
+        )
+    })?;
+    let system = state
+        .exit_systems
+        .read()
+        .await
+        .get(account_id)
+        .cloned()
+        .ok_or_else(|| {
+            error_response(
+                StatusCode::NOT_FOUND,
+                format!("No exit management for account {}", account_id),
+            )
+        })?;
+    let position = state
+        .orchestrator
+        .get_open_positions(Some(account_id))
+        .await
+        .map_err(|e| error_response(StatusCode::BAD_GATEWAY, e))?
+        .into_iter()
+        .find(|p| p.position_id == position_id)
+        .ok_or_else(|| {
+            error_response(
+                StatusCode::NOT_FOUND,
+                format!("No open position {} on account {}", position_id, account_id),
+            )
+        })?;
+    Ok((adopter, system, position))
+}
+
+async fn emergency_close(
+    State(state): State<ApiState>,
+    caller: Caller,
+    request: Option<Json<EmergencyCloseRequest>>,
+) -> Response {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    if let Err(e) = access::authorize_account(principal(&caller), request.account_id.as_deref()) {
+        return access::auth_error_response(e);
+    }
+    let reason = request.reason.unwrap_or_else(|| "admin API".to_string());
+
+    let result = state
+        .orchestrator
+        .emergency_close(request.account_id.as_deref(), reason.clone())
+        .await;
+    let summary = match &result {
+        Ok(results) => format!(
+            "{} of {} positions closed",
+            results.iter().filter(|r| r.success).count(),
+            results.len()
+        ),
+        Err(_) => String::new(),
+    };
+    state
+        .orchestrator
+        .record_control_action(
+            &control(&caller, "emergency_close")
+                .with_account(request.account_id.as_deref())
+                .with_parameter("reason", &reason)
+                .with_outcome(&result, &summary),
+        )
+        .await;
+
+    match result {
+        Ok(results) => {
+            state
+                .notifier
+                .notify(&Notification::emergency_close(
+                    request.account_id.as_deref(),
+                    &reason,
+                    &results,
+                ))
+                .await;
+            Json(results).into_response()
+        }
+        Err(e) => error_response(StatusCode::NOT_FOUND, e),
+    }
+}
+
+async fn kill_switch_status(State(state): State<ApiState>) -> Response {
+    Json(state.orchestrator.get_kill_switch().await).into_response()
+}
+
+async fn set_kill_switch(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Json(request): Json<KillSwitchRequest>,
+) -> Response {
+    let reason = request.reason.unwrap_or_else(|| "admin API".to_string());
+    state
+        .orchestrator
+        .record_control_action(
+            &control(&caller, "kill_switch")
+                .with_parameter("engaged", request.engaged)
+                .with_parameter("reason", &reason)
+                .succeeded(if request.engaged {
+                    "engaged"
+                } else {
+                    "released"
+                }),
+        )
+        .await;
+    if request.engaged {
+        state.orchestrator.engage_kill_switch(reason).await;
+    } else {
+        state.orchestrator.release_kill_switch(reason).await;
+    }
+    Json(state.orchestrator.get_kill_switch().await).into_response()
+}
+
+async fn feature_flags(State(state): State<ApiState>) -> Response {
+    Json(state.feature_flags.flags()).into_response()
+}
+
+async fn set_feature_flag(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Json(request): Json<FeatureFlagRequest>,
+) -> Response {
+    let reason = request.reason.unwrap_or_else(|| "admin API".to_string());
+    let account_id = request.account_id.as_deref();
+    if let Err(e) = access::authorize_account(principal(&caller), account_id) {
+        return access::auth_error_response(e);
+    }
+    let change = match request.enabled {
+        Some(enabled) => state
+            .feature_flags
+            .set(account_id, request.feature, enabled, &reason),
+        None => state
+            .feature_flags
+            .clear(account_id, request.feature, &reason),
+    };
+    let enabled = request
+        .enabled
+        .map_or_else(|| "cleared".to_string(), |enabled| enabled.to_string());
+    state
+        .orchestrator
+        .record_control_action(
+            &control(&caller, "feature_flag")
+                .with_account(account_id)
+                .with_parameter("feature", format!("{:?}", request.feature))
+                .with_parameter("enabled", &enabled)
+                .with_parameter("reason", &reason)
+                .succeeded(""),
+        )
+        .await;
+    Json(change).into_response()
+}
+
+async fn feature_flag_history(
+    State(state): State<ApiState>,
+    Query(query): Query<HistoryQuery>,
+) -> Response {
+    Json(state.feature_flags.history(query.limit.unwrap_or(100))).into_response()
+}
+
+async fn dry_run_state(State(state): State<ApiState>) -> Response {
+    Json(state.dry_run.state()).into_response()
+}
+
+async fn set_dry_run(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Json(request): Json<DryRunSwitchRequest>,
+) -> Response {
+    if let Err(e) = access::authorize_account(principal(&caller), request.account_id.as_deref()) {
+        return access::auth_error_response(e);
+    }
+    let outcome = match (&request.account_id, request.enabled) {
+        (None, None) => Err("Either account_id or enabled is required"),
+        _ => Ok(()),
+    };
+    let enabled = request
+        .enabled
+        .map_or_else(|| "cleared".to_string(), |enabled| enabled.to_string());
+    state
+        .orchestrator
+        .record_control_action(
+            &control(&caller, "dry_run")
+                .with_account(request.account_id.as_deref())
+                .with_parameter("enabled", &enabled)
+                .with_outcome(&outcome, ""),
+        )
+        .await;
+    match (request.account_id.as_deref(), request.enabled) {
+        (Some(account_id), enabled) => state.dry_run.set_account(account_id, enabled),
+        (None, Some(enabled)) => state.dry_run.set_global(enabled),
+        (None, None) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "Either account_id or enabled is required".to_string(),
+            )
+        }
+    }
+    Json(state.dry_run.state()).into_response()
+}
+
+async fn dry_run_orders(
+    State(state): State<ApiState>,
+    Query(query): Query<DryRunOrdersQuery>,
+) -> Response {
+    let limit = query.limit.unwrap_or(100);
+    Json(state.dry_run.recorded(query.account_id.as_deref(), limit)).into_response()
+}
+
+async fn shutdown_status(State(state): State<ApiState>) -> Response {
+    Json(ShutdownStatus {
+        requested: state.shutdown.is_requested(),
+        report: state.shutdown.report().await,
+    })
+    .into_response()
+}
+
+async fn request_shutdown(
+    State(state): State<ApiState>,
+    caller: Caller,
+    request: Option<Json<ShutdownRequest>>,
+) -> Response {
+    let reason = request
+        .and_then(|Json(request)| request.reason)
+        .unwrap_or_else(|| "admin API".to_string());
+    state
+        .orchestrator
+        .record_control_action(
+            &control(&caller, "shutdown")
+                .with_parameter("reason", &reason)
+                .succeeded("requested"),
+        )
+        .await;
+    state.shutdown.request(&reason);
+
+    (
+        StatusCode::ACCEPTED,
+        Json(ShutdownStatus {
+            requested: true,
+            report: state.shutdown.report().await,
+        }),
+    )
+        .into_response()
+}
@@ -0,0 +1,207 @@
+// Public status summary for external monitors: served without credentials,
+// from a short-lived cache and at a limited rate per client, so it cannot be
+// used to load the engine or its platforms
+
+use axum::extract::{ConnectInfo, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::{ApiState, ErrorResponse};
+use crate::runtime::Watchdog;
+
+/// Longest a platform is given to say whether it is connected
+const CONNECTION_CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusPageConfig {
+    /// How long a computed status is served before it is recomputed
+    pub cache_ttl_ms: u64,
+    /// Requests each client may make per minute
+    pub requests_per_minute: u32,
+}
+
+impl Default for StatusPageConfig {
+    fn default() -> Self {
+        Self {
+            cache_ttl_ms: 5000,
+            requests_per_minute: 30,
+        }
+    }
+}
+
+/// Whether orders reach the platforms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineMode {
+    Live,
+    DryRun,
+    /// Some accounts in dry run and some live
+    Mixed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartbeatStatus {
+    pub task: String,
+    pub last_beat_at: DateTime<Utc>,
+    pub stalled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineStatus {
+    pub mode: EngineMode,
+    pub kill_switch_engaged: bool,
+    pub shutting_down: bool,
+    pub platforms_connected: usize,
+    pub platforms_total: usize,
+    pub heartbeats: Vec<HeartbeatStatus>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Per-client request counts of the current minute
+struct RateWindow {
+    started: Instant,
+    counts: HashMap<Option<IpAddr>, u32>,
+}
+
+/// Cache and rate limit of the status page
+pub struct StatusPage {
+    config: StatusPageConfig,
+    watchdog: Option<Arc<Watchdog>>,
+    cached: tokio::sync::Mutex<Option<(Instant, EngineStatus)>>,
+    window: Mutex<RateWindow>,
+}
+
+impl StatusPage {
+    pub fn new(config: StatusPageConfig) -> Self {
+        Self {
+            config,
+            watchdog: None,
+            cached: tokio::sync::Mutex::new(None),
+            window: Mutex::new(RateWindow {
+                started: Instant::now(),
+                counts: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Report the heartbeats of the tasks `watchdog` watches
+    pub fn with_watchdog(mut self, watchdog: Arc<Watchdog>) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Count a request from `client`, returning the seconds until it may retry
+    /// when it is over its limit. Clients of unknown address share one limit.
+    fn admit(&self, client: Option<IpAddr>) -> Result<(), u64> {
+        let mut window = self.window.lock().unwrap();
+        let elapsed = window.started.elapsed();
+        if elapsed >= Duration::from_secs(60) {
+            window.started = Instant::now();
+            window.counts.clear();
+        }
+        let count = window.counts.entry(client).or_insert(0);
+        if *count >= self.config.requests_per_minute {
+            return Err(60u64.saturating_sub(elapsed.as_secs()).max(1));
+        }
+        *count += 1;
+        Ok(())
+    }
+
+    /// The status, computed at most once per cache period however many ask
+    pub async fn status(&self, state: &ApiState) -> EngineStatus {
+        let mut cached = self.cached.lock().await;
+        if let Some((computed_at, status)) = cached.as_ref() {
+            if computed_at.elapsed() < Duration::from_millis(self.config.cache_ttl_ms) {
+                return status.clone();
+            }
+        }
+        let status = self.compute(state).await;
+        *cached = Some((Instant::now(), status.clone()));
+        status
+    }
+
+    async fn compute(&self, state: &ApiState) -> EngineStatus {
+        let platforms = state.orchestrator.get_platforms().await;
+        let connected = join_all(platforms.iter().map(|(_, platform)| async move {
+            tokio::time::timeout(CONNECTION_CHECK_TIMEOUT, platform.is_connected())
+                .await
+                .unwrap_or(false)
+        }))
+        .await;
+
+        let dry_run = platforms
+            .iter()
+            .filter(|(account_id, _)| state.dry_run.is_active(account_id))
+            .count();
+        let mode = if platforms.is_empty() {
+            if state.dry_run.state().global {
+                EngineMode::DryRun
+            } else {
+                EngineMode::Live
+            }
+        } else if dry_run == 0 {
+            EngineMode::Live
+        } else if dry_run == platforms.len() {
+            EngineMode::DryRun
+        } else {
+            EngineMode::Mixed
+        };
+
+        let now = Utc::now();
+        let heartbeats = self
+            .watchdog
+            .iter()
+            .flat_map(|watchdog| watchdog.statuses())
+            .map(|task| HeartbeatStatus {
+                last_beat_at: now - chrono::Duration::milliseconds(task.silent_for_ms as i64),
+                task: task.name,
+                stalled: task.stalled,
+            })
+            .collect();
+
+        EngineStatus {
+            mode,
+            kill_switch_engaged: state.orchestrator.is_kill_switch_engaged(),
+            shutting_down: state.shutdown.is_requested(),
+            platforms_connected: connected.into_iter().filter(|ok| *ok).count(),
+            platforms_total: platforms.len(),
+            heartbeats,
+            generated_at: now,
+        }
+    }
+}
+
+pub async fn status(
+    State(state): State<ApiState>,
+    client: Option<ConnectInfo<SocketAddr>>,
+) -> Response {
+    let page = state.status.clone();
+    if let Err(retry_after) = page.admit(client.map(|ConnectInfo(address)| address.ip())) {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.to_string())],
+            Json(ErrorResponse {
+                error: "Too many status requests".to_string(),
+            }),
+        )
+            .into_response();
+    }
+    let max_age = page.config.cache_ttl_ms / 1000;
+    (
+        [(
+            header::CACHE_CONTROL,
+            format!("public, max-age={}", max_age),
+        )],
+        Json(page.status(&state).await),
+    )
+        .into_response()
+}
@@ -0,0 +1,294 @@
+// Authentication and role-based access for the engine's APIs. API keys and
+// HS256 JWTs both resolve to a principal carrying a role and the accounts it
+// may act on; transports enforce them (see `api::access` for HTTP).
+
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Header carrying an API key
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// What a principal may do, each role including the ones below it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Read state
+    Viewer,
+    /// Also pause and resume accounts and change exit policies
+    Operator,
+    /// Also emergency close, kill switch, feature flags, dry run and shutdown
+    Admin,
+}
+
+impl Role {
+    pub fn allows(self, required: Role) -> bool {
+        self >= required
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Role::Viewer => "viewer",
+            Role::Operator => "operator",
+            Role::Admin => "admin",
+        };
+        f.write_str(name)
+    }
+}
+
+/// An authenticated caller
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Principal {
+    pub subject: String,
+    pub role: Role,
+    /// Accounts the principal may see and act on; `None` for every account
+    #[serde(default)]
+    pub accounts: Option<Vec<String>>,
+}
+
+impl Principal {
+    /// Whether the principal is limited to some accounts
+    pub fn is_scoped(&self) -> bool {
+        self.accounts.is_some()
+    }
+
+    pub fn can_access(&self, account_id: &str) -> bool {
+        self.accounts
+            .as_ref()
+            .is_none_or(|accounts| accounts.iter().any(|id| id == account_id))
+    }
+
+    /// Check the principal holds `required`
+    pub fn require(&self, required: Role) -> Result<(), AuthError> {
+        if self.role.allows(required) {
+            Ok(())
+        } else {
+            Err(AuthError::InsufficientRole { required })
+        }
+    }
+
+    /// Check the principal may act on `account_id`
+    pub fn require_account(&self, account_id: &str) -> Result<(), AuthError> {
+        if self.can_access(account_id) {
+            Ok(())
+        } else {
+            Err(AuthError::AccountForbidden(account_id.to_string()))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum AuthError {
+    #[error("No credentials supplied")]
+    MissingCredentials,
+    #[error("Unknown API key")]
+    InvalidApiKey,
+    #[error("Invalid token: {0}")]
+    InvalidToken(String),
+    #[error("The {required} role is required")]
+    InsufficientRole { required: Role },
+    #[error("No access to account {0}")]
+    AccountForbidden(String),
+    #[error("Credentials scoped to accounts must name one of them")]
+    AccountRequired,
+    #[error("Only credentials for every account may use this route")]
+    AllAccountsRequired,
+}
+
+impl AuthError {
+    /// Whether the caller is unknown, rather than known but not allowed
+    pub fn is_unauthenticated(&self) -> bool {
+        matches!(
+            self,
+            AuthError::MissingCredentials | AuthError::InvalidApiKey | AuthError::InvalidToken(_)
+        )
+    }
+}
+
+/// An API key, stored by its hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyConfig {
+    /// Who holds the key, recorded as the principal's subject
+    pub name: String,
+    /// Hex SHA-256 of the key (see [`hash_api_key`]); the key itself is not stored
+    pub key_sha256: String,
+    pub role: Role,
+    /// Accounts the key is limited to; unset for every account
+    #[serde(default)]
+    pub accounts: Option<Vec<String>>,
+}
+
+/// Tokens signed with HS256 by an identity provider sharing `secret`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JwtConfig {
+    pub secret: String,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    /// Clock skew tolerated on expiry, in seconds
+    pub leeway_secs: u64,
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self {
+            secret: String::new(),
+            issuer: None,
+            audience: None,
+            leeway_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Off leaves the APIs open to anyone who can reach them
+    pub enabled: bool,
+    pub api_keys: Vec<ApiKeyConfig>,
+    pub jwt: Option<JwtConfig>,
+}
+
+impl AuthConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        // Anyone could sign tokens with an empty secret
+        if self.jwt.as_ref().is_some_and(|jwt| jwt.secret.is_empty()) {
+            return Err("JWT secret must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Claims of the tokens the engine accepts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub role: Role,
+    #[serde(default)]
+    pub accounts: Option<Vec<String>>,
+    pub exp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+}
+
+/// Hex SHA-256 of an API key, as configured in [`ApiKeyConfig::key_sha256`]
+pub fn hash_api_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+struct JwtKeys {
+    config: JwtConfig,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    validation: Validation,
+}
+
+/// Resolves credentials to principals
+pub struct Authenticator {
+    enabled: bool,
+    /// Principals by the hash of their key
+    keys: HashMap<String, Principal>,
+    jwt: Option<JwtKeys>,
+}
+
+impl Authenticator {
+    pub fn new(config: &AuthConfig) -> Self {
+        let keys = config
+            .api_keys
+            .iter()
+            .map(|key| {
+                (
+                    key.key_sha256.to_lowercase(),
+                    Principal {
+                        subject: key.name.clone(),
+                        role: key.role,
+                        accounts: key.accounts.clone(),
+                    },
+                )
+            })
+            .collect();
+        // An empty secret would accept tokens anyone can sign
+        let jwt = config.jwt.as_ref().filter(|jwt| !jwt.secret.is_empty());
+        let jwt = jwt.map(|jwt| {
+            let mut validation = Validation::new(Algorithm::HS256);
+            validation.leeway = jwt.leeway_secs;
+            if let Some(issuer) = &jwt.issuer {
+                validation.set_issuer(&[issuer]);
+            }
+            match &jwt.audience {
+                Some(audience) => validation.set_audience(&[audience]),
+                None => validation.validate_aud = false,
+            }
+            JwtKeys {
+                config: jwt.clone(),
+                encoding: EncodingKey::from_secret(jwt.secret.as_bytes()),
+                decoding: DecodingKey::from_secret(jwt.secret.as_bytes()),
+                validation,
+            }
+        });
+        Self {
+            enabled: config.enabled,
+            keys,
+            jwt,
+        }
+    }
+
+    /// Accepts every request
+    pub fn disabled() -> Self {
+        Self::new(&AuthConfig::default())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn authenticate_api_key(&self, key: &str) -> Result<Principal, AuthError> {
+        self.keys
+            .get(&hash_api_key(key))
+            .cloned()
+            .ok_or(AuthError::InvalidApiKey)
+    }
+
+    pub fn authenticate_token(&self, token: &str) -> Result<Principal, AuthError> {
+        let jwt = self
+            .jwt
+            .as_ref()
+            .ok_or_else(|| AuthError::InvalidToken("tokens are not accepted".to_string()))?;
+        let claims = decode::<Claims>(token, &jwt.decoding, &jwt.validation)
+            .map_err(|e| AuthError::InvalidToken(e.to_string()))?
+            .claims;
+        Ok(Principal {
+            subject: claims.sub,
+            role: claims.role,
+            accounts: claims.accounts,
+        })
+    }
+
+    /// Sign a token for `principal` valid for `ttl`, e.g. for service accounts
+    pub fn issue_token(&self, principal: &Principal, ttl: Duration) -> Result<String, AuthError> {
+        let jwt = self
+            .jwt
+            .as_ref()
+            .ok_or_else(|| AuthError::InvalidToken("no signing secret configured".to_string()))?;
+        let claims = Claims {
+            sub: principal.subject.clone(),
+            role: principal.role,
+            accounts: principal.accounts.clone(),
+            exp: (Utc::now() + ttl).timestamp(),
+            iss: jwt.config.issuer.clone(),
+            aud: jwt.config.audience.clone(),
+        };
+        encode(&Header::new(Algorithm::HS256), &claims, &jwt.encoding)
+            .map_err(|e| AuthError::InvalidToken(e.to_string()))
+    }
+}
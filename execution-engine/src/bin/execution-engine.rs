@@ -0,0 +1,595 @@
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use execution_engine::alerting::{AlertGateway, NOTIFICATIONS_SINK};
+use execution_engine::api::status::StatusPage;
+use execution_engine::api::{self, ApiState};
+use execution_engine::auth::Authenticator;
+use execution_engine::dashboard::{DashboardAggregator, ExitSystems};
+use execution_engine::execution::exit_management::news_protection::EconomicCalendarClient;
+use execution_engine::execution::exit_management::{
+    ExitAuditLogger, PositionAdopter, StopLossGuardian,
+};
+use execution_engine::execution::{
+    DropCopyConsumer, DropCopyReconciler, ExecutionHistoryRetention, NewsCalendar,
+    PendingSignalQueue, TradeExecutionOrchestrator,
+};
+use execution_engine::journal::TradeJournal;
+use execution_engine::ledger::{FileLedgerStore, PositionLedger, StatementReconciler};
+use execution_engine::market_analysis::StructureAnalyzer;
+use execution_engine::market_data::{CandleBuilder, CrossRates};
+use execution_engine::messaging::{
+    configured_publisher, ExecutionOutbox, FileOutboxStore, OutboxRelay,
+};
+use execution_engine::notifications::Notifier;
+use execution_engine::platforms::abstraction::{DryRunMode, ServerClocks};
+use execution_engine::platforms::dxtrade::{MessageType, SessionManager, SessionRole};
+use execution_engine::platforms::PlatformType;
+use execution_engine::recording::EventRecorder;
+use execution_engine::reports::{DailyReportGenerator, SignalQualityJob, StrategyScores};
+use execution_engine::risk::pnl_calculator::{
+    CurrencyConverter, KafkaProducer, MarketDataStream, PositionTracker, WebSocketPublisher,
+};
+use execution_engine::risk::{
+    ClusterLimits, DrawdownLadder, FileFundingEventStore, FileRiskSnapshotStore, FundingDetector,
+    MarginCalculator, RealTimePnLCalculator, RiskSnapshotter,
+};
+use execution_engine::runtime::shutdown::{
+    termination_signal, DrainExecutions, FlushExitAudit, PersistOrchestratorState, StopSignalIntake,
+};
+use execution_engine::runtime::subsystems::{
+    AdoptionSubsystem, ApiServerSubsystem, CandleSubsystem, CrossRateSubsystem,
+    DashboardStreamSubsystem, DeferredOrderSubsystem, DrawdownLadderSubsystem,
+    ExitManagementSubsystem, LadderSubsystem, MessagingSubsystem, NewsCalendarSubsystem,
+    OrchestratorSubsystem, PositionLedgerSubsystem, RecordingSubsystem, RiskMonitorSubsystem,
+    RiskSnapshotSubsystem, StatementSubsystem, StopGuardianSubsystem,
+};
+use execution_engine::runtime::{
+    init_logging, load_config, AccountBootstrapper, DXTradeConnector, FeatureFlags, HealthChecker,
+    PlatformHealthProbe, RestartPolicy, ShutdownCoordinator, StorageProbe, Supervisor,
+    TradeLockerConnector, Watchdog,
+};
+use execution_engine::storage::{open_storage, StorageProfile};
+use execution_engine::timeseries::{connect_writer, TimeSeriesSampler, TimeSeriesSink};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+    let log_levels = init_logging("info").map_err(|e| anyhow::anyhow!(e))?;
+
+    let config = load_config().map_err(|e| anyhow::anyhow!(e))?;
+    if let Err(e) = log_levels.apply(&config.logging.account_levels) {
+        warn!("Ignoring account log levels: {}", e);
+    }
+    info!(
+        "Starting execution engine with {} configured accounts",
+        config.accounts.len()
+    );
+
+    let notifier = Arc::new(
+        Notifier::from_config(&config.notifications).unwrap_or_else(|e| {
+            warn!("Notification channels unavailable: {}", e);
+            Notifier::new()
+        }),
+    );
+    let alert_gateway = Arc::new(
+        match AlertGateway::from_config(config.alerting.clone()) {
+            Ok(gateway) => gateway,
+            Err(e) => {
+                warn!("Alert sinks unavailable, alerts will only be logged: {}", e);
+                AlertGateway::new(config.alerting.clone())
+            }
+        }
+        .with_sink(NOTIFICATIONS_SINK, notifier.clone()),
+    );
+
+    let news_calendar = NewsCalendar::new();
+    let mut orchestrator = TradeExecutionOrchestrator::new()
+        .with_alert_gateway(alert_gateway.clone())
+        .with_exposure_clusters(ClusterLimits::new(
+            config.risk.exposure_limits.clusters.clone(),
+        ))
+        .with_rejection_remediation(config.rejection_remediation.clone())
+        .with_confidence_sizing(config.confidence_sizing.clone())
+        .with_scale_in(config.scale_in.clone())
+        .with_session_liquidity(config.session_liquidity.clone())
+        .with_slippage_guard(config.slippage_guard.clone())
+        .with_latency_entry(config.latency_entry.clone())
+        .with_pacing(config.pacing.clone())
+        .with_personas(config.personas.clone())
+        .with_drawdown_ladder(DrawdownLadder::new(config.risk.drawdown_ladder.clone()))
+        .with_recovery_mode(config.recovery_mode.clone())
+        .with_news_blackout(config.news_blackout.clone(), news_calendar.clone());
+    let recorder = config
+        .recording
+        .enabled
+        .then(|| Arc::new(EventRecorder::new(&config.recording.dir)));
+    if let Some(recorder) = &recorder {
+        orchestrator = orchestrator.with_recorder(recorder.clone());
+    }
+    if config.risk.margin_thresholds.pre_trade_gate {
+        orchestrator = orchestrator.with_margin_gate(
+            Arc::new(MarginCalculator::new()),
+            config.risk.margin_thresholds.warning_level,
+        );
+    }
+    let storage = open_storage(&config).await?;
+    let history_store = storage.execution_history();
+    if let Some(store) = &history_store {
+        orchestrator = orchestrator.with_history_store(store.clone());
+    }
+    // Without a publisher nothing would ever leave the outbox, so it is not attached
+    let outbox_publisher = if config.outbox.enabled {
+        let publisher = configured_publisher(&config.outbox).await?;
+        if publisher.is_none() {
+            warn!(
+                "Outbox enabled without a publish_url or nats_url: execution results are not recorded for publishing"
+            );
+        }
+        publisher
+    } else {
+        None
+    };
+    let outbox = if let Some(publisher) = outbox_publisher {
+        let outbox = Arc::new(
+            ExecutionOutbox::new(config.outbox.clone())
+                .with_store(Arc::new(FileOutboxStore::new(&config.outbox.path))),
+        );
+        if let Err(e) = outbox.load().await {
+            warn!("Failed to load outbox {}: {}", config.outbox.path, e);
+        }
+        orchestrator = orchestrator.with_outbox(outbox.clone());
+        Some((outbox, publisher))
+    } else {
+        None
+    };
+    let strategy_scores = Arc::new(StrategyScores::new(config.signal_quality.clone()));
+    orchestrator = orchestrator.with_strategy_scores(strategy_scores.clone());
+    let orchestrator = Arc::new(orchestrator);
+    let journal = Arc::new(
+        TradeJournal::new()
+            .with_store(storage.journal())
+            .with_tag_registry(orchestrator.tag_registry()),
+    );
+    if let Err(e) = journal.load().await {
+        warn!("Failed to load trade journal: {}", e);
+    }
+    let mut exit_logger = ExitAuditLogger::new()
+        .with_trade_journal(journal.clone())
+        .with_tag_registry(orchestrator.tag_registry());
+    if let Some(recorder) = &recorder {
+        exit_logger = exit_logger.with_recorder(recorder.clone());
+    }
+    let exit_logger = Arc::new(exit_logger);
+    let mut supervisor = Supervisor::new(config.supervisor.clone());
+    let watchdog = Arc::new(Watchdog::new(config.watchdog.clone()));
+
+    let dry_run = Arc::new(DryRunMode::new(&config.dry_run));
+    let state = dry_run.state();
+    if state.global || state.accounts.values().any(|active| *active) {
+        warn!(
+            "Dry run active (all accounts: {}, overrides: {:?}): orders are recorded, not sent",
+            state.global, state.accounts
+        );
+    }
+
+    // Startup order matters: messaging and accounts first, the API last so that
+    // it only accepts requests once everything it fronts is running
+    supervisor.add(watchdog.clone());
+    supervisor.add(alert_gateway.clone());
+    supervisor.add(Arc::new(MessagingSubsystem::new()));
+    let outbox_active = outbox.is_some();
+    if let Some((outbox, publisher)) = outbox {
+        supervisor.add(Arc::new(OutboxRelay::new(outbox, publisher)));
+    }
+    // Connectors add a FIX logout and a disconnect step for every account they connect
+    let shutdown = Arc::new(
+        ShutdownCoordinator::new(config.shutdown.clone())
+            .with_hook(Arc::new(StopSignalIntake::new(orchestrator.clone())))
+            .with_hook(Arc::new(DrainExecutions::new(
+                orchestrator.clone(),
+                Duration::from_secs(config.shutdown.drain_timeout_secs),
+            )))
+            .with_hook(Arc::new(FlushExitAudit::new(exit_logger.clone())))
+            .with_hook(Arc::new(
+                PersistOrchestratorState::new(
+                    orchestrator.clone(),
+                    config.shutdown.state_path.clone(),
+                )
+                .with_store(storage.orchestrator_state()),
+            )),
+    );
+    let server_clocks = ServerClocks::new();
+    let mut bootstrapper = AccountBootstrapper::new()
+        .with_quotas(&config.quotas)
+        .with_performance(config.platform_performance.clone())
+        .with_clock_sync(config.clock_sync.clone(), server_clocks.clone())
+        .with_market_data_hub(config.market_data_hub.clone())
+        .with_alert_gateway(alert_gateway.clone())
+        .with_dry_run(dry_run.clone());
+    if let Some(tradelocker) = config.tradelocker.clone() {
+        bootstrapper = bootstrapper.with_connector(
+            PlatformType::TradeLocker,
+            Arc::new(TradeLockerConnector::new(tradelocker).with_shutdown(shutdown.clone())),
+        );
+    }
+    if let Some(dxtrade) = config.dxtrade.clone() {
+        bootstrapper = bootstrapper.with_connector(
+            PlatformType::DXTrade,
+            Arc::new(DXTradeConnector::new(dxtrade).with_shutdown(shutdown.clone())),
+        );
+    }
+    supervisor.add_with_policy(
+        Arc::new(OrchestratorSubsystem::new(
+            orchestrator.clone(),
+            bootstrapper,
+            config.accounts.clone(),
+        )),
+        RestartPolicy::Never,
+    );
+    supervisor.add(Arc::new(LadderSubsystem::new(orchestrator.clone())));
+    if config.risk.drawdown_ladder.enabled {
+        supervisor.add(Arc::new(DrawdownLadderSubsystem::new(
+            orchestrator.clone(),
+            Duration::from_secs(config.risk.drawdown_ladder.assessment_interval_secs),
+        )));
+    }
+    supervisor.add(Arc::new(DeferredOrderSubsystem::new(orchestrator.clone())));
+    if config.news_blackout.enabled {
+        supervisor.add(Arc::new(NewsCalendarSubsystem::new(
+            EconomicCalendarClient::default(),
+            news_calendar,
+            config.news_blackout.clone(),
+        )));
+    }
+    let candles = config.candles.enabled.then(|| {
+        let candles = Arc::new(CandleBuilder::new(config.candles.clone()));
+        supervisor.add(Arc::new(CandleSubsystem::new(
+            orchestrator.clone(),
+            candles.clone(),
+        )));
+        candles
+    });
+    if let Some(recorder) = &recorder {
+        supervisor.add(Arc::new(RecordingSubsystem::new(
+            orchestrator.clone(),
+            recorder.clone(),
+            config.recording.clone(),
+        )));
+    }
+    let mut ledger = None;
+    if config.ledger.enabled {
+        let position_ledger = Arc::new(
+            PositionLedger::new().with_store(Arc::new(FileLedgerStore::new(&config.ledger.path))),
+        );
+        ledger = Some(position_ledger.clone());
+        let ledger = position_ledger;
+        if let Err(e) = ledger.load().await {
+            warn!(
+                "Failed to rebuild position ledger {}: {}",
+                config.ledger.path, e
+            );
+        }
+        supervisor.add(Arc::new(PositionLedgerSubsystem::new(
+            orchestrator.clone(),
+            ledger,
+            Duration::from_secs(config.ledger.reconcile_interval_secs),
+        )));
+    }
+    if let Some(store) = history_store {
+        supervisor.add(Arc::new(ExecutionHistoryRetention::new(
+            store,
+            &config.execution_history,
+        )));
+    }
+    if config.pending_signals.enabled {
+        supervisor.add(Arc::new(PendingSignalQueue::new(
+            orchestrator.clone(),
+            config.pending_signals.clone(),
+        )));
+    }
+
+    let mut currency_converter = CurrencyConverter::new();
+    let cross_rates = config
+        .cross_rates
+        .enabled
+        .then(|| CrossRates::new(config.cross_rates.clone()));
+    if let Some(cross_rates) = &cross_rates {
+        supervisor.add(Arc::new(CrossRateSubsystem::new(
+            orchestrator.clone(),
+            cross_rates.clone(),
+        )));
+        currency_converter = currency_converter.with_cross_rates(cross_rates.clone());
+    }
+    let trading_days = config.trading_day.clone().unwrap_or_default();
+    let pnl_calculator = Arc::new(RealTimePnLCalculator::new(
+        Arc::new(PositionTracker::new().with_trading_days(trading_days.clone())),
+        Arc::new(MarketDataStream::new()),
+        Arc::new(WebSocketPublisher::new()),
+        Arc::new(KafkaProducer),
+        Arc::new(currency_converter),
+    ));
+    supervisor.add(Arc::new(RiskMonitorSubsystem::new(pnl_calculator.clone())));
+
+    let mut dashboard = DashboardAggregator::new(orchestrator.clone())
+        .with_pnl_calculator(pnl_calculator)
+        .with_consolidation(config.consolidation.clone());
+    if let Some(candles) = &candles {
+        dashboard = dashboard.with_candles(candles.clone());
+    }
+    if let Some(cross_rates) = &cross_rates {
+        dashboard = dashboard.with_cross_rates(cross_rates.clone());
+    }
+    let mut funding = None;
+    if config.funding.enabled {
+        let detector = Arc::new(FundingDetector::new(
+            config.funding.clone(),
+            Arc::new(FileFundingEventStore::new(&config.funding.path)),
+        ));
+        if let Err(e) = detector.load().await {
+            warn!("Failed to load funding events: {}", e);
+        }
+        funding = Some(detector);
+    }
+    let mut statements = None;
+    if let (true, Some(ledger)) = (config.statements.enabled, &ledger) {
+        let mut reconciler = StatementReconciler::new(
+            orchestrator.clone(),
+            ledger.clone(),
+            config.statements.clone(),
+        )
+        .with_trading_days(trading_days.clone());
+        if let Some(funding) = &funding {
+            reconciler = reconciler.with_funding_detector(funding.clone());
+        }
+        let reconciler = Arc::new(reconciler);
+        supervisor.add(Arc::new(StatementSubsystem::new(reconciler.clone())));
+        statements = Some(reconciler);
+    }
+    let mut risk_snapshots = None;
+    if config.risk_snapshots.enabled {
+        let mut snapshotter = RiskSnapshotter::new(
+            orchestrator.clone(),
+            Arc::new(FileRiskSnapshotStore::new(&config.risk_snapshots.path)),
+            config.risk_snapshots.clone(),
+        )
+        .with_trading_days(trading_days.clone());
+        if let Some(cross_rates) = cross_rates {
+            snapshotter = snapshotter.with_cross_rates(cross_rates);
+        }
+        if let Some(funding) = &funding {
+            snapshotter = snapshotter.with_funding_detector(funding.clone());
+        }
+        let snapshotter = Arc::new(snapshotter);
+        supervisor.add(Arc::new(RiskSnapshotSubsystem::new(snapshotter.clone())));
+        risk_snapshots = Some(snapshotter);
+    }
+    let feature_flags = Arc::new(FeatureFlags::new(config.feature_flags.clone()));
+    let mut exit_systems = ExitSystems::default();
+    let mut adopter = None;
+    if config.exit_management.enabled {
+        let mut exit_management =
+            ExitManagementSubsystem::new(orchestrator.clone(), exit_logger.clone())
+                .with_shadow_variants(config.exit_management.shadow_variants.clone())
+                .with_market_context(config.exit_management.market_context.clone())
+                .with_scale_in_policy(config.exit_management.scale_in)
+                .with_feature_flags(feature_flags.clone())
+                .with_watchdog(watchdog.clone())
+                .with_storage(storage.clone())
+                .with_server_clocks(server_clocks.clone());
+        if let Some(trading_days) = &config.trading_day {
+            exit_management = exit_management.with_trading_days(trading_days.clone());
+        }
+        if let Some(recorder) = &recorder {
+            exit_management = exit_management.with_recorder(recorder.clone());
+        }
+        if let Some(candles) = &candles {
+            exit_management = exit_management
+                .with_candles(candles.clone())
+                .with_structure(Arc::new(StructureAnalyzer::new(
+                    candles.clone(),
+                    config.structure.clone(),
+                )));
+        }
+        let exit_management = Arc::new(exit_management);
+        exit_systems = exit_management.systems();
+        dashboard = dashboard.with_exit_systems(exit_systems.clone());
+        supervisor.add(exit_management);
+
+        let stop_guardian = &config.exit_management.stop_guardian;
+        if stop_guardian.enabled {
+            let guardian = StopLossGuardian::new(stop_guardian.clone())
+                .with_alert_gateway(alert_gateway.clone());
+            supervisor.add(Arc::new(StopGuardianSubsystem::new(
+                Arc::new(guardian),
+                exit_systems.clone(),
+            )));
+        }
+
+        let position_adopter = Arc::new(
+            PositionAdopter::new(
+                config.exit_management.adoption.clone(),
+                orchestrator.order_tracker(),
+            )
+            .with_journal(journal.clone())
+            .with_tag_registry(orchestrator.tag_registry()),
+        );
+        if config.exit_management.adoption.auto_adopt {
+            supervisor.add(Arc::new(AdoptionSubsystem::new(
+                orchestrator.clone(),
+                position_adopter.clone(),
+                exit_systems.clone(),
+            )));
+        }
+        adopter = Some(position_adopter);
+    }
+    let dashboard = Arc::new(dashboard);
+    let auth = Arc::new(Authenticator::new(&config.auth));
+
+    if config.timeseries.enabled {
+        match connect_writer(&config.timeseries).await {
+            Ok(writer) => {
+                let sink = Arc::new(TimeSeriesSink::new(&config.timeseries, writer));
+                supervisor.add(sink.clone());
+                supervisor.add(Arc::new(
+                    TimeSeriesSampler::new(orchestrator.clone(), sink, config.timeseries.clone())
+                        .with_dashboard(dashboard.clone()),
+                ));
+            }
+            Err(e) => warn!("Time series export disabled: {:#}", e),
+        }
+    }
+
+    if config.dashboard.stream_enabled {
+        supervisor.add(Arc::new(
+            DashboardStreamSubsystem::new(
+                config.dashboard.stream_bind_address.clone(),
+                dashboard.clone(),
+                Duration::from_millis(config.dashboard.publish_interval_ms),
+            )
+            .with_authenticator(auth.clone()),
+        ));
+    }
+
+    if config.reports.enabled {
+        let mut reports = DailyReportGenerator::new(config.reports.clone(), journal.clone())
+            .with_alert_gateway(alert_gateway)
+            .with_notifier(notifier.clone())
+            .with_trading_days(trading_days);
+        if let Some(funding) = funding {
+            reports = reports.with_funding_detector(funding);
+        }
+        supervisor.add(Arc::new(reports));
+    }
+    if config.signal_quality.enabled {
+        supervisor.add(Arc::new(SignalQualityJob::new(
+            strategy_scores.clone(),
+            journal.clone(),
+        )));
+    }
+
+    let mut health = HealthChecker::new(supervisor.statuses())
+        .with_shutdown(shutdown.clone())
+        .with_probe(Arc::new(PlatformHealthProbe::new(orchestrator.clone())))
+        .with_probe(watchdog.clone())
+        .with_probe(feature_flags.clone());
+    if outbox_active {
+        health = health.with_probe(Arc::new(StorageProbe::new("outbox", &config.outbox.path)));
+    }
+    if config.ledger.enabled {
+        health = health.with_probe(Arc::new(StorageProbe::new("ledger", &config.ledger.path)));
+    }
+    if config.risk_snapshots.enabled {
+        health = health.with_probe(Arc::new(StorageProbe::new(
+            "risk-snapshots",
+            &config.risk_snapshots.path,
+        )));
+    }
+    if config.funding.enabled {
+        health = health.with_probe(Arc::new(StorageProbe::new(
+            "funding-events",
+            &config.funding.path,
+        )));
+    }
+    match storage.profile() {
+        StorageProfile::Files => {
+            health =
+                health.with_probe(Arc::new(StorageProbe::new("journal", &config.journal.path)));
+            if let Some(dir) = &config.exit_management.state_dir {
+                health = health.with_probe(Arc::new(StorageProbe::new(
+                    "exit-state",
+                    std::path::Path::new(dir).join("state.json"),
+                )));
+            }
+        }
+        StorageProfile::Sqlite => {
+            health = health.with_probe(Arc::new(StorageProbe::new(
+                "sqlite",
+                &config.storage.sqlite_path,
+            )));
+        }
+    }
+
+    // Order entry and drop copy to DXtrade; drop-copy fills are matched
+    // against the orders the engine sent
+    let drop_copy = config.dxtrade.clone().map(|dxtrade| {
+        let account_id = dxtrade.credentials.account_id.clone();
+        let drop_copy_sessions = dxtrade
+            .sessions
+            .iter()
+            .any(|spec| spec.role == SessionRole::DropCopy);
+        let sessions = SessionManager::new(dxtrade).with_watchdog(watchdog.clone());
+        let reconciler = drop_copy_sessions.then(|| {
+            let reconciler = Arc::new(
+                DropCopyReconciler::new(orchestrator.order_tracker(), &account_id)
+                    .with_notifier(notifier.clone()),
+            );
+            let receiver = sessions.router().subscribe(
+                "drop-copy",
+                SessionRole::DropCopy,
+                &[MessageType::ExecutionReport],
+            );
+            supervisor.add(Arc::new(DropCopyConsumer::new(
+                reconciler.clone(),
+                receiver,
+            )));
+            reconciler
+        });
+        supervisor.add(Arc::new(sessions));
+        reconciler
+    });
+
+    let router = api::router(ApiState {
+        orchestrator: orchestrator.clone(),
+        health: Arc::new(health),
+        shutdown: shutdown.clone(),
+        dashboard,
+        journal,
+        exit_systems,
+        exit_logger,
+        notifier,
+        feature_flags,
+        dry_run,
+        auth,
+        status: Arc::new(
+            StatusPage::new(config.api.status.clone()).with_watchdog(watchdog.clone()),
+        ),
+        drop_copy: drop_copy.flatten(),
+        ledger,
+        signal_quality: config.signal_quality.enabled.then_some(strategy_scores),
+        adoption: adopter,
+        risk_snapshots,
+        statements,
+    });
+    supervisor.add(Arc::new(ApiServerSubsystem::new(
+        config.api.bind_address.clone(),
+        router,
+    )));
+
+    if let Err(e) = supervisor.start().await {
+        error!("Execution engine failed to start: {}", e);
+        return Err(e);
+    }
+    info!("Execution engine started");
+
+    tokio::select! {
+        signal = termination_signal() => {
+            shutdown.request(signal);
+        }
+        _ = shutdown.wait_for_request() => {}
+    }
+
+    let report = shutdown.execute(&mut supervisor).await;
+    if report.is_clean() {
+        info!("Execution engine stopped cleanly");
+    } else {
+        warn!(
+            "Execution engine stopped with incomplete shutdown steps: {:?}",
+            report.steps
+        );
+    }
+
+    Ok(())
+}
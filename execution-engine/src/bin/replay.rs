@@ -0,0 +1,35 @@
+use execution_engine::recording::replay::{self, USAGE};
+
+#[tokio::main]
+async fn main() {
+    let options = match replay::parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let report = match replay::replay_day(&options.dir, options.date, options.speed).await {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    if options.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        print!("{}", report.render());
+    }
+}
@@ -0,0 +1,17 @@
+use execution_engine::ctl::{self, USAGE};
+
+#[tokio::main]
+async fn main() {
+    let options = match ctl::parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(e) = ctl::run(options).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
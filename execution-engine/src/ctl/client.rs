@@ -0,0 +1,174 @@
+use anyhow::{anyhow, Result};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+use crate::api::{
+    EmergencyCloseRequest, ErrorResponse, HealthResponse, KillSwitchRequest, ShutdownStatus,
+};
+use crate::auth::API_KEY_HEADER;
+use crate::execution::{AccountStatus, EmergencyCloseResult, ExecutionAuditEntry, KillSwitchState};
+use crate::platforms::abstraction::{UnifiedOrderResponse, UnifiedPosition};
+
+/// Typed client for the execution engine's admin API
+pub struct EngineClient {
+    base_url: String,
+    http: Client,
+    api_key: Option<String>,
+}
+
+impl EngineClient {
+    pub fn new(base_url: &str) -> Result<Self> {
+        let http = Client::builder().timeout(Duration::from_secs(30)).build()?;
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http,
+            api_key: None,
+        })
+    }
+
+    /// Authenticate every request with `key`
+    pub fn with_api_key(mut self, key: &str) -> Self {
+        self.api_key = Some(key.to_string());
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Send a request and decode the body, surfacing the API's error message on failure
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        let request = match &self.api_key {
+            Some(key) => request.header(API_KEY_HEADER, key),
+            None => request,
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Engine at {} unreachable: {}", self.base_url, e))?;
+        let status = response.status();
+
+        // Health reports 503 with a normal body when a subsystem has failed
+        if status.is_success() || status == StatusCode::SERVICE_UNAVAILABLE {
+            if let Ok(body) = response.json::<T>().await {
+                return Ok(body);
+            }
+            return Err(anyhow!(
+                "Engine returned {} with an unexpected body",
+                status
+            ));
+        }
+
+        match response.json::<ErrorResponse>().await {
+            Ok(body) => Err(anyhow!("{} ({})", body.error, status)),
+            Err(_) => Err(anyhow!("Engine returned {}", status)),
+        }
+    }
+
+    pub async fn health(&self) -> Result<HealthResponse> {
+        self.send(self.http.get(self.url("/health"))).await
+    }
+
+    pub async fn accounts(&self) -> Result<Vec<AccountStatus>> {
+        self.send(self.http.get(self.url("/accounts"))).await
+    }
+
+    pub async fn account(&self, account_id: &str) -> Result<AccountStatus> {
+        self.send(
+            self.http
+                .get(self.url(&format!("/accounts/{}", account_id))),
+        )
+        .await
+        .map_err(|e| anyhow!("Account {}: {}", account_id, e))
+    }
+
+    pub async fn pause_account(&self, account_id: &str) -> Result<AccountStatus> {
+        self.send(
+            self.http
+                .post(self.url(&format!("/accounts/{}/pause", account_id))),
+        )
+        .await
+    }
+
+    pub async fn resume_account(&self, account_id: &str) -> Result<AccountStatus> {
+        self.send(
+            self.http
+                .post(self.url(&format!("/accounts/{}/resume", account_id))),
+        )
+        .await
+    }
+
+    pub async fn positions(&self, account_id: Option<&str>) -> Result<Vec<UnifiedPosition>> {
+        let mut request = self.http.get(self.url("/positions"));
+        if let Some(account_id) = account_id {
+            request = request.query(&[("account_id", account_id)]);
+        }
+        self.send(request).await
+    }
+
+    pub async fn orders(&self, account_id: Option<&str>) -> Result<Vec<UnifiedOrderResponse>> {
+        let mut request = self.http.get(self.url("/orders"));
+        if let Some(account_id) = account_id {
+            request = request.query(&[("account_id", account_id)]);
+        }
+        self.send(request).await
+    }
+
+    pub async fn emergency_close(
+        &self,
+        account_id: Option<&str>,
+        reason: &str,
+    ) -> Result<Vec<EmergencyCloseResult>> {
+        let body = EmergencyCloseRequest {
+            account_id: account_id.map(str::to_string),
+            reason: Some(reason.to_string()),
+        };
+        self.send(
+            self.http
+                .post(self.url("/admin/emergency-close"))
+                .json(&body),
+        )
+        .await
+    }
+
+    pub async fn kill_switch(&self) -> Result<KillSwitchState> {
+        self.send(self.http.get(self.url("/admin/kill-switch")))
+            .await
+    }
+
+    pub async fn set_kill_switch(&self, engaged: bool, reason: &str) -> Result<KillSwitchState> {
+        let body = KillSwitchRequest {
+            engaged,
+            reason: Some(reason.to_string()),
+        };
+        self.send(self.http.post(self.url("/admin/kill-switch")).json(&body))
+            .await
+    }
+
+    pub async fn executions(&self, limit: usize) -> Result<Vec<ExecutionAuditEntry>> {
+        self.send(
+            self.http
+                .get(self.url("/executions"))
+                .query(&[("limit", limit)]),
+        )
+        .await
+    }
+
+    pub async fn shutdown_status(&self) -> Result<ShutdownStatus> {
+        self.send(self.http.get(self.url("/admin/shutdown"))).await
+    }
+
+    pub async fn request_shutdown(&self, reason: &str) -> Result<ShutdownStatus> {
+        self.send(
+            self.http
+                .post(self.url("/admin/shutdown"))
+                .json(&serde_json::json!({ "reason": reason })),
+        )
+        .await
+    }
+}
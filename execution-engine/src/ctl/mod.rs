@@ -0,0 +1,525 @@
+// Operator CLI for a running execution engine, driven through its admin API
+
+pub mod client;
+
+pub use client::EngineClient;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime};
+
+use crate::execution::{AccountStatus, ExecutionAuditEntry};
+
+pub const DEFAULT_ENGINE_URL: &str = "http://localhost:8082";
+
+pub const USAGE: &str = "\
+Usage: tmt-ctl [--url URL] [--json] <command>
+
+Commands:
+  health                               Subsystem health
+  accounts                             List accounts and their status
+  account <id>                         Show one account
+  pause <id>                           Stop routing new signals to an account
+  resume <id>                          Resume a paused account
+  positions [--account ID]             Open positions
+  orders [--account ID]                Working orders
+  emergency-close [--account ID] --yes [--reason TEXT]
+                                       Close every open position
+  kill-switch [status|on|off] [--reason TEXT]
+                                       Show or flip the global kill switch
+  audit [--limit N] [--follow] [--interval SECS]
+                                       Tail execution audit entries
+  shutdown [--reason TEXT]             Request a graceful engine shutdown
+
+The engine URL defaults to $TMT_ENGINE_URL, then http://localhost:8082.
+Requests carry the API key in $TMT_API_KEY when the engine requires one.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillSwitchAction {
+    Status,
+    Engage,
+    Release,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Health,
+    Accounts,
+    Account {
+        account_id: String,
+    },
+    Pause {
+        account_id: String,
+    },
+    Resume {
+        account_id: String,
+    },
+    Positions {
+        account_id: Option<String>,
+    },
+    Orders {
+        account_id: Option<String>,
+    },
+    EmergencyClose {
+        account_id: Option<String>,
+        reason: String,
+    },
+    KillSwitch {
+        action: KillSwitchAction,
+        reason: String,
+    },
+    Audit {
+        limit: usize,
+        follow: bool,
+        interval: Duration,
+    },
+    Shutdown {
+        reason: String,
+    },
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CtlOptions {
+    pub url: String,
+    pub json: bool,
+    pub command: Command,
+}
+
+/// Parse command-line arguments, excluding the program name
+pub fn parse_args<I>(args: I) -> Result<CtlOptions, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut url = std::env::var("TMT_ENGINE_URL").unwrap_or_else(|_| DEFAULT_ENGINE_URL.into());
+    let mut json = false;
+    let mut args = args.into_iter().peekable();
+
+    while let Some(arg) = args.next_if(|arg| arg.starts_with("--")) {
+        match arg.as_str() {
+            "--url" => url = args.next().ok_or("--url requires a value")?,
+            "--json" => json = true,
+            "--help" => {
+                return Ok(CtlOptions {
+                    url,
+                    json,
+                    command: Command::Help,
+                })
+            }
+            other => return Err(format!("Unknown option {}", other)),
+        }
+    }
+
+    let name = args.next().unwrap_or_else(|| "help".to_string());
+    let mut flags = Flags::parse(args)?;
+
+    let command = match name.as_str() {
+        "health" => Command::Health,
+        "accounts" => Command::Accounts,
+        "account" => Command::Account {
+            account_id: flags.positional("account id")?,
+        },
+        "pause" => Command::Pause {
+            account_id: flags.positional("account id")?,
+        },
+        "resume" => Command::Resume {
+            account_id: flags.positional("account id")?,
+        },
+        "positions" => Command::Positions {
+            account_id: flags.value("--account"),
+        },
+        "orders" => Command::Orders {
+            account_id: flags.value("--account"),
+        },
+        "emergency-close" => {
+            if !flags.switch("--yes") {
+                return Err(
+                    "emergency-close closes every open position; pass --yes to confirm".to_string(),
+                );
+            }
+            Command::EmergencyClose {
+                account_id: flags.value("--account"),
+                reason: flags.reason(),
+            }
+        }
+        "kill-switch" => {
+            let action = match flags.optional_positional().as_deref() {
+                None | Some("status") => KillSwitchAction::Status,
+                Some("on") => KillSwitchAction::Engage,
+                Some("off") => KillSwitchAction::Release,
+                Some(other) => {
+                    return Err(format!(
+                        "Unknown kill-switch action {}, expected status, on or off",
+                        other
+                    ))
+                }
+            };
+            Command::KillSwitch {
+                action,
+                reason: flags.reason(),
+            }
+        }
+        "audit" => Command::Audit {
+            limit: flags.parsed("--limit")?.unwrap_or(50),
+            follow: flags.switch("--follow"),
+            interval: Duration::from_secs(flags.parsed("--interval")?.unwrap_or(2)),
+        },
+        "shutdown" => Command::Shutdown {
+            reason: flags.reason(),
+        },
+        "help" => Command::Help,
+        other => return Err(format!("Unknown command {}", other)),
+    };
+
+    flags.finish()?;
+    Ok(CtlOptions { url, json, command })
+}
+
+/// Arguments following the command name, consumed as the command asks for them
+struct Flags {
+    positional: Vec<String>,
+    values: Vec<(String, Option<String>)>,
+}
+
+impl Flags {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut values = Vec::new();
+        let mut args = args.peekable();
+
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") {
+                positional.push(arg);
+                continue;
+            }
+            let value = match arg.as_str() {
+                "--yes" | "--follow" => None,
+                _ => Some(
+                    args.next_if(|next| !next.starts_with("--"))
+                        .ok_or_else(|| format!("{} requires a value", arg))?,
+                ),
+            };
+            values.push((arg, value));
+        }
+
+        positional.reverse();
+        Ok(Self { positional, values })
+    }
+
+    fn positional(&mut self, what: &str) -> Result<String, String> {
+        self.positional
+            .pop()
+            .ok_or_else(|| format!("Missing {}", what))
+    }
+
+    fn optional_positional(&mut self) -> Option<String> {
+        self.positional.pop()
+    }
+
+    fn take(&mut self, name: &str) -> Option<Option<String>> {
+        let index = self.values.iter().position(|(flag, _)| flag == name)?;
+        Some(self.values.remove(index).1)
+    }
+
+    fn switch(&mut self, name: &str) -> bool {
+        self.take(name).is_some()
+    }
+
+    fn value(&mut self, name: &str) -> Option<String> {
+        self.take(name).flatten()
+    }
+
+    fn parsed<T: std::str::FromStr>(&mut self, name: &str) -> Result<Option<T>, String> {
+        self.value(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("Invalid value {} for {}", value, name))
+            })
+            .transpose()
+    }
+
+    /// Reason recorded in the engine's audit trail, defaulting to the operator name
+    fn reason(&mut self) -> String {
+        self.value("--reason").unwrap_or_else(|| {
+            let operator = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
+            format!("tmt-ctl ({})", operator)
+        })
+    }
+
+    fn finish(self) -> Result<(), String> {
+        if let Some(arg) = self.positional.last() {
+            return Err(format!("Unexpected argument {}", arg));
+        }
+        if let Some((flag, _)) = self.values.first() {
+            return Err(format!("Unknown option {}", flag));
+        }
+        Ok(())
+    }
+}
+
+pub async fn run(options: CtlOptions) -> Result<()> {
+    let mut client = EngineClient::new(&options.url)?;
+    if let Ok(key) = std::env::var("TMT_API_KEY") {
+        client = client.with_api_key(&key);
+    }
+    let json = options.json;
+
+    match options.command {
+        Command::Help => println!("{}", USAGE),
+        Command::Health => {
+            let health = client.health().await?;
+            if json {
+                return print_json(&health);
+            }
+            println!(
+                "Engine is {:?} (live: {}, ready: {})",
+                health.status, health.live, health.ready
+            );
+            println!(
+                "{:<20} {:<12} {:>8}  LAST ERROR",
+                "SUBSYSTEM", "STATE", "RESTARTS"
+            );
+            for status in health.subsystems {
+                println!(
+                    "{:<20} {:<12} {:>8}  {}",
+                    status.name,
+                    format!("{:?}", status.state),
+                    status.restarts,
+                    status.last_error.unwrap_or_default()
+                );
+            }
+            println!();
+            println!(
+                "{:<24} {:<12} {:<10} {:>8}  DETAIL",
+                "DEPENDENCY", "KIND", "LEVEL", "LATENCY"
+            );
+            for dependency in health.dependencies {
+                println!(
+                    "{:<24} {:<12} {:<10} {:>8}  {}",
+                    dependency.name,
+                    format!("{:?}", dependency.kind),
+                    format!("{:?}", dependency.level),
+                    dependency
+                        .latency_ms
+                        .map(|ms| format!("{}ms", ms))
+                        .unwrap_or_default(),
+                    dependency.detail.unwrap_or_default()
+                );
+            }
+        }
+        Command::Accounts => {
+            let accounts = client.accounts().await?;
+            if json {
+                return print_json(&accounts);
+            }
+            print_accounts(&accounts);
+        }
+        Command::Account { account_id } => {
+            let account = client.account(&account_id).await?;
+            if json {
+                return print_json(&account);
+            }
+            print_accounts(&[account]);
+        }
+        Command::Pause { account_id } => {
+            let account = client.pause_account(&account_id).await?;
+            if json {
+                return print_json(&account);
+            }
+            println!("Paused account {}", account_id);
+        }
+        Command::Resume { account_id } => {
+            let account = client.resume_account(&account_id).await?;
+            if json {
+                return print_json(&account);
+            }
+            println!("Resumed account {}", account_id);
+        }
+        Command::Positions { account_id } => {
+            let positions = client.positions(account_id.as_deref()).await?;
+            if json {
+                return print_json(&positions);
+            }
+            println!(
+                "{:<16} {:<10} {:<6} {:>12} {:>12} {:>12} {:>12}",
+                "ACCOUNT", "SYMBOL", "SIDE", "QUANTITY", "ENTRY", "CURRENT", "UNREALIZED"
+            );
+            for p in positions {
+                println!(
+                    "{:<16} {:<10} {:<6} {:>12} {:>12} {:>12} {:>12}",
+                    p.account_id,
+                    p.symbol,
+                    format!("{:?}", p.side),
+                    p.quantity,
+                    p.entry_price,
+                    p.current_price,
+                    p.unrealized_pnl
+                );
+            }
+        }
+        Command::Orders { account_id } => {
+            let orders = client.orders(account_id.as_deref()).await?;
+            if json {
+                return print_json(&orders);
+            }
+            println!(
+                "{:<20} {:<10} {:<6} {:<10} {:<16} {:>12} {:>12} {:>12}",
+                "ORDER", "SYMBOL", "SIDE", "TYPE", "STATUS", "QUANTITY", "FILLED", "PRICE"
+            );
+            for o in orders {
+                println!(
+                    "{:<20} {:<10} {:<6} {:<10} {:<16} {:>12} {:>12} {:>12}",
+                    o.platform_order_id,
+                    o.symbol,
+                    format!("{:?}", o.side),
+                    format!("{:?}", o.order_type),
+                    format!("{:?}", o.status),
+                    o.quantity,
+                    o.filled_quantity,
+                    o.price.map(|p| p.to_string()).unwrap_or_else(|| "-".into())
+                );
+            }
+        }
+        Command::EmergencyClose { account_id, reason } => {
+            let results = client
+                .emergency_close(account_id.as_deref(), &reason)
+                .await?;
+            if json {
+                return print_json(&results);
+            }
+            for r in &results {
+                match &r.error_message {
+                    None => println!("closed  {:<16} {}", r.account_id, r.symbol),
+                    Some(e) => println!("FAILED  {:<16} {} {}", r.account_id, r.symbol, e),
+                }
+            }
+            let failed = results.iter().filter(|r| !r.success).count();
+            println!(
+                "Emergency close finished: {} closed, {} failed",
+                results.len() - failed,
+                failed
+            );
+            if failed > 0 {
+                anyhow::bail!("{} positions could not be closed", failed);
+            }
+        }
+        Command::KillSwitch { action, reason } => {
+            let state = match action {
+                KillSwitchAction::Status => client.kill_switch().await?,
+                KillSwitchAction::Engage => client.set_kill_switch(true, &reason).await?,
+                KillSwitchAction::Release => client.set_kill_switch(false, &reason).await?,
+            };
+            if json {
+                return print_json(&state);
+            }
+            println!(
+                "Kill switch {}{}",
+                if state.engaged { "ENGAGED" } else { "released" },
+                state
+                    .reason
+                    .map(|reason| format!(": {}", reason))
+                    .unwrap_or_default()
+            );
+        }
+        Command::Audit {
+            limit,
+            follow,
+            interval,
+        } => tail_audit(&client, limit, follow, interval, json).await?,
+        Command::Shutdown { reason } => {
+            let status = client.request_shutdown(&reason).await?;
+            if json {
+                return print_json(&status);
+            }
+            println!("Shutdown requested: {}", reason);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn print_accounts(accounts: &[AccountStatus]) {
+    println!(
+        "{:<16} {:<12} {:<7} {:>12} {:>10} {:>9} {:>5}",
+        "ACCOUNT", "PLATFORM", "STATUS", "MARGIN", "RISK LEFT", "DAILY DD", "OPEN"
+    );
+    for a in accounts {
+        println!(
+            "{:<16} {:<12} {:<7} {:>12.2} {:>10.2} {:>9.2} {:>5}",
+            a.account_id,
+            a.platform,
+            if a.is_active { "active" } else { "paused" },
+            a.available_margin,
+            a.risk_budget_remaining,
+            a.daily_drawdown,
+            a.open_positions
+        );
+    }
+}
+
+fn format_time(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+fn print_audit_entry(entry: &ExecutionAuditEntry, json: bool) {
+    if json {
+        if let Ok(line) = serde_json::to_string(entry) {
+            println!("{}", line);
+        }
+        return;
+    }
+    println!(
+        "{}  {:<20} {:<12} {:<16} {}",
+        format_time(entry.timestamp),
+        entry.action,
+        entry.signal_id,
+        entry.account_id,
+        entry.decision_rationale
+    );
+}
+
+/// Print the latest audit entries and, when following, keep polling for new ones
+async fn tail_audit(
+    client: &EngineClient,
+    limit: usize,
+    follow: bool,
+    interval: Duration,
+    json: bool,
+) -> Result<()> {
+    let entries = client.executions(limit).await?;
+    if json && !follow {
+        return print_json(&entries);
+    }
+
+    for entry in &entries {
+        print_audit_entry(entry, json);
+    }
+    if !follow {
+        return Ok(());
+    }
+
+    let mut seen: HashSet<String> = entries.into_iter().map(|e| e.id).collect();
+    loop {
+        tokio::time::sleep(interval).await;
+        match client.executions(limit).await {
+            Ok(entries) => {
+                for entry in entries.iter().filter(|e| !seen.contains(&e.id)) {
+                    print_audit_entry(entry, json);
+                }
+                seen = entries.into_iter().map(|e| e.id).collect();
+            }
+            // Keep tailing through engine restarts
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
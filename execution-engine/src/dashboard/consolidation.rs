@@ -0,0 +1,264 @@
+// Equity and P&L of every account converted to one base currency, so accounts
+// denominated in different currencies can be added up
+//
+// Rates are sourced, in order:
+// 1. none needed when the account is in the base currency
+// 2. the live mid of the account currency against the base currency, as
+//    CCYBASE or inverted from BASECCY, asked first of the account's own platform
+//    and then of the other registered platforms
+// 3. the static rate configured for either pair, for currencies no platform
+//    quotes
+// Accounts no rate is found for are listed but left out of the totals.
+
+use chrono::{DateTime, Duration, Utc};
+use futures_util::future::join_all;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::platforms::abstraction::ITradingPlatform;
+
+type Platform = Arc<dyn ITradingPlatform + Send + Sync>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsolidationConfig {
+    /// Currency consolidated figures are reported in
+    pub base_currency: String,
+    /// Fallback rates by pair, such as `EURUSD`, as the price of one unit of the
+    /// first currency in the second; either direction may be given
+    pub rates: HashMap<String, Decimal>,
+    /// Quotes older than this are not used for conversion
+    pub max_quote_age_secs: u64,
+}
+
+impl Default for ConsolidationConfig {
+    fn default() -> Self {
+        Self {
+            base_currency: "USD".to_string(),
+            rates: HashMap::new(),
+            max_quote_age_secs: 60,
+        }
+    }
+}
+
+/// Where a conversion rate came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum RateSource {
+    /// The account is in the base currency
+    Identity,
+    /// The mid of `symbol` as quoted by `account_id`'s platform, inverted when
+    /// the pair is quoted in the account currency
+    Quote {
+        symbol: String,
+        account_id: String,
+        inverted: bool,
+    },
+    /// The rate configured for `symbol`
+    Configured { symbol: String, inverted: bool },
+}
+
+/// Units of the base currency per unit of an account currency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversionRate {
+    pub rate: Decimal,
+    pub source: RateSource,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct EquityAmounts {
+    pub balance: Decimal,
+    pub equity: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub realized_pnl: Decimal,
+}
+
+impl EquityAmounts {
+    fn converted(&self, rate: Decimal) -> Self {
+        Self {
+            balance: self.balance * rate,
+            equity: self.equity * rate,
+            unrealized_pnl: self.unrealized_pnl * rate,
+            realized_pnl: self.realized_pnl * rate,
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.balance += other.balance;
+        self.equity += other.equity;
+        self.unrealized_pnl += other.unrealized_pnl;
+        self.realized_pnl += other.realized_pnl;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountEquity {
+    pub account_id: String,
+    pub currency: String,
+    /// In the account currency
+    pub native: EquityAmounts,
+    /// None when no rate was found for the account currency
+    pub rate: Option<ConversionRate>,
+    /// In the base currency
+    pub converted: Option<EquityAmounts>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidatedEquity {
+    pub generated_at: DateTime<Utc>,
+    pub base_currency: String,
+    /// Sum of the converted accounts, in the base currency
+    pub totals: EquityAmounts,
+    pub accounts: Vec<AccountEquity>,
+    /// Accounts left out of the totals for want of a rate
+    pub unconverted_accounts: Vec<String>,
+    /// Accounts whose platform could not report them
+    pub unavailable_accounts: Vec<String>,
+}
+
+/// Converts account figures to the base currency of `config`
+pub struct EquityConsolidator {
+    config: ConsolidationConfig,
+}
+
+impl EquityConsolidator {
+    pub fn new(config: ConsolidationConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn base_currency(&self) -> &str {
+        &self.config.base_currency
+    }
+
+    /// Consolidate the accounts of `platforms`, keyed by account id
+    pub async fn consolidate(&self, platforms: &[(String, Platform)]) -> ConsolidatedEquity {
+        let infos = join_all(
+            platforms
+                .iter()
+                .map(|(_, platform)| platform.get_account_info()),
+        )
+        .await;
+
+        let mut consolidated = ConsolidatedEquity {
+            generated_at: Utc::now(),
+            base_currency: self.config.base_currency.clone(),
+            totals: EquityAmounts::default(),
+            accounts: Vec::new(),
+            unconverted_accounts: Vec::new(),
+            unavailable_accounts: Vec::new(),
+        };
+        // One rate per currency, so every account in it is converted alike
+        let mut rates: HashMap<String, Option<ConversionRate>> = HashMap::new();
+
+        for ((account_id, platform), info) in platforms.iter().zip(infos) {
+            let info = match info {
+                Ok(info) => info,
+                Err(e) => {
+                    warn!("Could not consolidate account {}: {}", account_id, e);
+                    consolidated.unavailable_accounts.push(account_id.clone());
+                    continue;
+                }
+            };
+            let currency = info.currency.to_uppercase();
+            let rate = match rates.get(&currency) {
+                Some(rate) => rate.clone(),
+                None => {
+                    let rate = self
+                        .rate(&currency, (account_id, platform), platforms)
+                        .await;
+                    rates.insert(currency.clone(), rate.clone());
+                    rate
+                }
+            };
+
+            let native = EquityAmounts {
+                balance: info.balance,
+                equity: info.equity,
+                unrealized_pnl: info.unrealized_pnl,
+                realized_pnl: info.realized_pnl,
+            };
+            let converted = rate.as_ref().map(|rate| native.converted(rate.rate));
+            match &converted {
+                Some(converted) => consolidated.totals.add(converted),
+                None => consolidated.unconverted_accounts.push(account_id.clone()),
+            }
+            consolidated.accounts.push(AccountEquity {
+                account_id: account_id.clone(),
+                currency,
+                native,
+                rate,
+                converted,
+            });
+        }
+
+        consolidated
+    }
+
+    /// The rate of `currency` to the base currency, asking `own` platform for a
+    /// quote before the others
+    pub async fn rate(
+        &self,
+        currency: &str,
+        own: (&String, &Platform),
+        platforms: &[(String, Platform)],
+    ) -> Option<ConversionRate> {
+        let base = self.config.base_currency.to_uppercase();
+        let currency = currency.to_uppercase();
+        if currency == base {
+            return Some(ConversionRate {
+                rate: Decimal::ONE,
+                source: RateSource::Identity,
+            });
+        }
+
+        let direct = format!("{}{}", currency, base);
+        let inverse = format!("{}{}", base, currency);
+        let candidates = std::iter::once(own).chain(
+            platforms
+                .iter()
+                .filter(|(account_id, _)| account_id != own.0)
+                .map(|(account_id, platform)| (account_id, platform)),
+        );
+        for (account_id, platform) in candidates {
+            for (symbol, inverted) in [(&direct, false), (&inverse, true)] {
+                if let Some(mid) = self.quote_mid(platform, symbol).await {
+                    return Some(ConversionRate {
+                        rate: if inverted { Decimal::ONE / mid } else { mid },
+                        source: RateSource::Quote {
+                            symbol: symbol.clone(),
+                            account_id: account_id.clone(),
+                            inverted,
+                        },
+                    });
+                }
+            }
+        }
+
+        for (symbol, inverted) in [(direct, false), (inverse, true)] {
+            if let Some(rate) = self.config.rates.get(&symbol).filter(|r| !r.is_zero()) {
+                return Some(ConversionRate {
+                    rate: if inverted { Decimal::ONE / rate } else { *rate },
+                    source: RateSource::Configured { symbol, inverted },
+                });
+            }
+        }
+
+        warn!("No rate converts {} to {}", currency, base);
+        None
+    }
+
+    async fn quote_mid(&self, platform: &Platform, symbol: &str) -> Option<Decimal> {
+        let quote = platform.get_market_data(symbol).await.ok()?;
+        let max_age = Duration::seconds(self.config.max_quote_age_secs as i64);
+        if Utc::now() - quote.timestamp > max_age {
+            return None;
+        }
+        if quote.bid <= Decimal::ZERO || quote.ask <= Decimal::ZERO {
+            return None;
+        }
+        Some((quote.bid + quote.ask) / Decimal::TWO)
+    }
+}
@@ -0,0 +1,176 @@
+// Net exposure of every account by symbol and by currency leg, from live positions,
+// laid out as matrices for the dashboard heatmap
+//
+// Notionals are in each account's own currency, so they can be set against its
+// equity. A position's own price converts between the two currencies of its pair;
+// other currencies are converted at the cross rates, when configured. Symbols that
+// are not currency pairs are taken to be priced in the account currency and have
+// no currency legs.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::market_data::cross_rates::{currency_pair, CrossRates};
+use crate::platforms::abstraction::models::UnifiedPositionSide;
+use crate::platforms::abstraction::{UnifiedAccountInfo, UnifiedPosition};
+
+/// One column of the heatmap
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeatmapAccount {
+    pub account_id: String,
+    pub currency: String,
+    pub equity: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExposureCell {
+    /// Net exposure in the account currency, negative when short
+    pub notional: Decimal,
+    /// The notional in percent of the account's equity; None without equity
+    pub equity_pct: Option<Decimal>,
+}
+
+/// One symbol or currency, with a cell for each account in column order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeatmapRow {
+    pub key: String,
+    pub cells: Vec<ExposureCell>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExposureHeatmap {
+    pub generated_at: DateTime<Utc>,
+    /// Columns of both matrices, by account id
+    pub accounts: Vec<HeatmapAccount>,
+    /// Symbol by account, by symbol
+    pub symbols: Vec<HeatmapRow>,
+    /// Currency leg by account, by currency
+    pub currencies: Vec<HeatmapRow>,
+    /// Positions left out for want of a rate, as `account_id:symbol`
+    pub unconverted: Vec<String>,
+    /// Accounts whose platform could not report them
+    pub unavailable_accounts: Vec<String>,
+}
+
+impl ExposureHeatmap {
+    /// The heatmap of `accounts`, by account id with their open positions,
+    /// converting at `rates` where a position's own price does not suffice
+    pub fn build(
+        accounts: Vec<(String, UnifiedAccountInfo, Vec<UnifiedPosition>)>,
+        unavailable_accounts: Vec<String>,
+        rates: Option<&CrossRates>,
+        generated_at: DateTime<Utc>,
+    ) -> Self {
+        let mut accounts = accounts;
+        accounts.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let columns = accounts.len();
+        let mut symbols: BTreeMap<String, Vec<Decimal>> = BTreeMap::new();
+        let mut currencies: BTreeMap<String, Vec<Decimal>> = BTreeMap::new();
+        let mut unconverted = Vec::new();
+        let mut add = |rows: &mut BTreeMap<String, Vec<Decimal>>, key: &str, column, amount| {
+            rows.entry(key.to_string())
+                .or_insert_with(|| vec![Decimal::ZERO; columns])[column] += amount;
+        };
+
+        for (column, (account_id, info, positions)) in accounts.iter().enumerate() {
+            let account_currency = info.currency.to_uppercase();
+            for position in positions {
+                let sign = match position.side {
+                    UnifiedPositionSide::Long => Decimal::ONE,
+                    UnifiedPositionSide::Short => -Decimal::ONE,
+                };
+                let size = sign * position.quantity;
+                let compact = position.symbol.replace(['_', '/'], "");
+                let Some((base, quote)) = currency_pair(&compact) else {
+                    add(
+                        &mut symbols,
+                        &position.symbol,
+                        column,
+                        size * position.current_price,
+                    );
+                    continue;
+                };
+
+                let convert = |amount: Decimal, currency: &str| {
+                    to_account_currency(
+                        amount,
+                        currency,
+                        &account_currency,
+                        (&base, &quote, position.current_price),
+                        rates,
+                    )
+                };
+                let base_leg = convert(size, &base);
+                let quote_leg = convert(-size * position.current_price, &quote);
+                let (Some(base_leg), Some(quote_leg)) = (base_leg, quote_leg) else {
+                    unconverted.push(format!("{}:{}", account_id, position.symbol));
+                    continue;
+                };
+                add(&mut symbols, &compact.to_uppercase(), column, -quote_leg);
+                add(&mut currencies, &base, column, base_leg);
+                add(&mut currencies, &quote, column, quote_leg);
+            }
+        }
+
+        let rows = |rows: BTreeMap<String, Vec<Decimal>>| {
+            rows.into_iter()
+                .map(|(key, notionals)| HeatmapRow {
+                    key,
+                    cells: notionals
+                        .into_iter()
+                        .zip(&accounts)
+                        .map(|(notional, (_, info, _))| ExposureCell {
+                            notional,
+                            equity_pct: (info.equity > Decimal::ZERO)
+                                .then(|| notional / info.equity * Decimal::ONE_HUNDRED),
+                        })
+                        .collect(),
+                })
+                .collect()
+        };
+        let symbols = rows(symbols);
+        let currencies = rows(currencies);
+
+        Self {
+            generated_at,
+            accounts: accounts
+                .iter()
+                .map(|(account_id, info, _)| HeatmapAccount {
+                    account_id: account_id.clone(),
+                    currency: info.currency.to_uppercase(),
+                    equity: info.equity,
+                })
+                .collect(),
+            symbols,
+            currencies,
+            unconverted,
+            unavailable_accounts,
+        }
+    }
+}
+
+/// `amount` of `currency` in `account_currency`, through the price of the pair
+/// `base`/`quote` when it links the two, else at `rates`
+pub(crate) fn to_account_currency(
+    amount: Decimal,
+    currency: &str,
+    account_currency: &str,
+    (base, quote, price): (&str, &str, Decimal),
+    rates: Option<&CrossRates>,
+) -> Option<Decimal> {
+    if currency == account_currency {
+        return Some(amount);
+    }
+    if price > Decimal::ZERO {
+        if currency == base && account_currency == quote {
+            return Some(amount * price);
+        }
+        if currency == quote && account_currency == base {
+            return Some(amount / price);
+        }
+    }
+    rates?.convert(amount, currency, account_currency).ok()
+}
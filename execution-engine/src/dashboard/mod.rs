@@ -0,0 +1,296 @@
+// Joined view of accounts, positions, risk and exit management for the UI
+
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::execution::exit_management::{ExitManagementSystem, PositionExitState};
+use crate::execution::{AccountStatus, KillSwitchState, TradeExecutionOrchestrator};
+use crate::market_data::{Candle, CandleBuilder, CrossRates};
+use crate::platforms::abstraction::{ITradingPlatform, UnifiedAccountInfo, UnifiedPosition};
+use crate::risk::RealTimePnLCalculator;
+use risk_types::PnLSnapshot;
+
+mod consolidation;
+mod heatmap;
+
+pub use consolidation::{
+    AccountEquity, ConsolidatedEquity, ConsolidationConfig, ConversionRate, EquityAmounts,
+    EquityConsolidator, RateSource,
+};
+pub(crate) use heatmap::to_account_currency;
+pub use heatmap::{ExposureCell, ExposureHeatmap, HeatmapAccount, HeatmapRow};
+
+/// Exit management systems keyed by account id
+pub type ExitSystems = Arc<RwLock<HashMap<String, ExitManagementSystem>>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardSnapshot {
+    pub generated_at: DateTime<Utc>,
+    pub kill_switch: KillSwitchState,
+    pub totals: DashboardTotals,
+    pub accounts: Vec<AccountDashboard>,
+    /// Candles still forming, by symbol then timeframe
+    #[serde(default)]
+    pub candles: Vec<Candle>,
+    /// Equity and P&L of all accounts in the base currency, when configured
+    #[serde(default)]
+    pub consolidated: Option<ConsolidatedEquity>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DashboardTotals {
+    pub accounts: usize,
+    pub active_accounts: usize,
+    pub open_positions: usize,
+    pub unrealized_pnl: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountDashboard {
+    pub status: AccountStatus,
+    pub risk: Option<AccountRisk>,
+    pub positions: Vec<PositionDashboard>,
+    /// Set when the platform could not be queried; positions are then empty
+    pub positions_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountRisk {
+    pub unrealized_pnl: Decimal,
+    pub realized_pnl_today: Decimal,
+    pub total_pnl: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionDashboard {
+    pub position: UnifiedPosition,
+    pub pnl: Option<PnLSnapshot>,
+    pub exit: Option<PositionExitState>,
+}
+
+/// Builds `DashboardSnapshot`s by querying every subsystem at the same instant
+pub struct DashboardAggregator {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    pnl_calculator: Option<Arc<RealTimePnLCalculator>>,
+    exit_systems: Option<ExitSystems>,
+    candles: Option<Arc<CandleBuilder>>,
+    consolidator: Option<EquityConsolidator>,
+    cross_rates: Option<CrossRates>,
+}
+
+impl DashboardAggregator {
+    pub fn new(orchestrator: Arc<TradeExecutionOrchestrator>) -> Self {
+        Self {
+            orchestrator,
+            pnl_calculator: None,
+            exit_systems: None,
+            candles: None,
+            consolidator: None,
+            cross_rates: None,
+        }
+    }
+
+    pub fn with_pnl_calculator(mut self, pnl_calculator: Arc<RealTimePnLCalculator>) -> Self {
+        self.pnl_calculator = Some(pnl_calculator);
+        self
+    }
+
+    pub fn with_exit_systems(mut self, exit_systems: ExitSystems) -> Self {
+        self.exit_systems = Some(exit_systems);
+        self
+    }
+
+    pub fn with_candles(mut self, candles: Arc<CandleBuilder>) -> Self {
+        self.candles = Some(candles);
+        self
+    }
+
+    /// Report equity and P&L of all accounts together in one base currency
+    pub fn with_consolidation(mut self, config: ConsolidationConfig) -> Self {
+        self.consolidator = Some(EquityConsolidator::new(config));
+        self
+    }
+
+    /// Convert exposure heatmap legs in currencies other than the account's and the
+    /// position's pair at `rates`
+    pub fn with_cross_rates(mut self, rates: CrossRates) -> Self {
+        self.cross_rates = Some(rates);
+        self
+    }
+
+    /// Net exposure of every account by symbol and currency leg, from its live
+    /// positions
+    pub async fn exposure_heatmap(&self) -> ExposureHeatmap {
+        let generated_at = Utc::now();
+        let (accounts, unavailable_accounts) = live_accounts(&self.orchestrator).await;
+        ExposureHeatmap::build(
+            accounts,
+            unavailable_accounts,
+            self.cross_rates.as_ref(),
+            generated_at,
+        )
+    }
+
+    /// Equity and P&L of all accounts in the base currency; None unless
+    /// consolidation is configured
+    pub async fn consolidated_equity(&self) -> Option<ConsolidatedEquity> {
+        let consolidator = self.consolidator.as_ref()?;
+        let platforms = self.orchestrator.get_platforms().await;
+        Some(consolidator.consolidate(&platforms).await)
+    }
+
+    pub async fn snapshot(&self) -> DashboardSnapshot {
+        let generated_at = Utc::now();
+        let statuses = self.orchestrator.get_all_account_statuses().await;
+        let platforms: HashMap<String, Arc<dyn ITradingPlatform + Send + Sync>> = self
+            .orchestrator
+            .get_platforms()
+            .await
+            .into_iter()
+            .collect();
+        let exit_systems = match &self.exit_systems {
+            Some(systems) => systems.read().await.clone(),
+            None => HashMap::new(),
+        };
+
+        // Query all accounts concurrently so their data is as close in time as possible
+        let accounts = join_all(statuses.into_iter().map(|status| {
+            let platform = platforms.get(&status.account_id).cloned();
+            let exit_system = exit_systems.get(&status.account_id).cloned();
+            self.account_dashboard(status, platform, exit_system)
+        }))
+        .await;
+        let consolidated = self.consolidated_equity().await;
+
+        let totals = DashboardTotals {
+            accounts: accounts.len(),
+            active_accounts: accounts.iter().filter(|a| a.status.is_active).count(),
+            open_positions: accounts.iter().map(|a| a.positions.len()).sum(),
+            unrealized_pnl: accounts
+                .iter()
+                .flat_map(|a| &a.positions)
+                .map(|p| p.position.unrealized_pnl)
+                .sum(),
+        };
+
+        DashboardSnapshot {
+            generated_at,
+            kill_switch: self.orchestrator.get_kill_switch().await,
+            totals,
+            accounts,
+            candles: self
+                .candles
+                .as_ref()
+                .map(|candles| candles.forming())
+                .unwrap_or_default(),
+            consolidated,
+        }
+    }
+
+    async fn account_dashboard(
+        &self,
+        status: AccountStatus,
+        platform: Option<Arc<dyn ITradingPlatform + Send + Sync>>,
+        exit_system: Option<ExitManagementSystem>,
+    ) -> AccountDashboard {
+        let (positions, positions_error) = match platform {
+            Some(platform) => match platform.get_positions().await {
+                Ok(positions) => (positions, None),
+                Err(e) => {
+                    warn!(
+                        "Dashboard could not load positions for {}: {}",
+                        status.account_id, e
+                    );
+                    (Vec::new(), Some(e.to_string()))
+                }
+            },
+            None => (Vec::new(), Some("No platform registered".to_string())),
+        };
+
+        // Risk types key accounts and positions by UUID; other ids have no risk data
+        let account_pnl = match (&self.pnl_calculator, Uuid::parse_str(&status.account_id)) {
+            (Some(calculator), Ok(account_id)) => calculator.get_account_pnl(account_id).await.ok(),
+            _ => None,
+        };
+
+        let positions = positions
+            .into_iter()
+            .map(|mut position| {
+                position.account_id = status.account_id.clone();
+                let position_id = Uuid::parse_str(&position.position_id).ok();
+                let pnl = account_pnl.as_ref().and_then(|account| {
+                    account
+                        .position_pnls
+                        .iter()
+                        .find(|p| Some(p.position_id) == position_id)
+                        .cloned()
+                });
+                let exit = exit_system
+                    .as_ref()
+                    .zip(position_id)
+                    .map(|(system, id)| system.position_exit_state(id));
+
+                PositionDashboard {
+                    position,
+                    pnl,
+                    exit,
+                }
+            })
+            .collect();
+
+        AccountDashboard {
+            risk: account_pnl.map(|pnl| AccountRisk {
+                unrealized_pnl: pnl.unrealized_pnl,
+                realized_pnl_today: pnl.realized_pnl_today,
+                total_pnl: pnl.total_pnl,
+            }),
+            status,
+            positions,
+            positions_error,
+        }
+    }
+}
+
+/// Account info and open positions of every account registered with the
+/// orchestrator, queried concurrently, and the ids of those that could not be
+/// queried, sorted
+pub(crate) async fn live_accounts(
+    orchestrator: &TradeExecutionOrchestrator,
+) -> (
+    Vec<(String, UnifiedAccountInfo, Vec<UnifiedPosition>)>,
+    Vec<String>,
+) {
+    let platforms = orchestrator.get_platforms().await;
+    let reports = join_all(platforms.iter().map(|(account_id, platform)| async move {
+        let report = match platform.get_account_info().await {
+            Ok(info) => platform
+                .get_positions()
+                .await
+                .map(|positions| (account_id.clone(), info, positions)),
+            Err(e) => Err(e),
+        };
+        (account_id, report)
+    }))
+    .await;
+
+    let mut accounts = Vec::new();
+    let mut unavailable_accounts = Vec::new();
+    for (account_id, report) in reports {
+        match report {
+            Ok(report) => accounts.push(report),
+            Err(e) => {
+                warn!("Could not load account {}: {}", account_id, e);
+                unavailable_accounts.push(account_id.clone());
+            }
+        }
+    }
+    unavailable_accounts.sort();
+    (accounts, unavailable_accounts)
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+use tokio::sync::{oneshot, Mutex};
+use tracing::error;
+
+use super::orchestrator::AccountStatus;
+use crate::runtime::channel::{bounded, BoundedReceiver, BoundedSender, OverflowPolicy};
+use crate::runtime::spawn::supervised_spawn;
+use crate::runtime::supervisor::RestartPolicy;
+
+/// Commands queued before senders wait on the actor
+const ACCOUNT_COMMAND_QUEUE_CAPACITY: usize = 4096;
+
+/// Change to or question about account state. Commands are applied in the order
+/// they were sent, so a read sees every change queued before it.
+#[derive(Debug)]
+enum AccountCommand {
+    /// Add an account, replacing any status it had
+    Register(AccountStatus),
+    SetActive {
+        account_id: String,
+        active: bool,
+        /// False when the account is unknown
+        ack: oneshot::Sender<bool>,
+    },
+    OrderPlaced {
+        account_id: String,
+        at: SystemTime,
+        /// Units the order filled, which use up `risk_per_unit` of risk budget each
+        filled_quantity: f64,
+        risk_per_unit: f64,
+        /// False when the fill adds to a position already counted
+        opens_position: bool,
+    },
+    /// Latest drawdowns measured from the account's equity, as fractions
+    Drawdowns {
+        account_id: String,
+        daily: f64,
+        total: f64,
+    },
+    Get {
+        account_id: String,
+        reply: oneshot::Sender<Option<AccountStatus>>,
+    },
+    /// Every account, by account id
+    Snapshot(oneshot::Sender<Vec<AccountStatus>>),
+}
+
+/// Handle to the task owning every account's status. Execution tasks, pauses and
+/// reads all go through its queue, so no caller contends for a lock or sees an
+/// update half applied. Cheap to clone; the task starts on first use and is
+/// restarted after a panic with the queue and the accounts it held.
+#[derive(Clone, Default)]
+pub(crate) struct AccountActor {
+    sender: Arc<OnceLock<BoundedSender<AccountCommand>>>,
+}
+
+impl AccountActor {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    fn sender(&self) -> &BoundedSender<AccountCommand> {
+        self.sender.get_or_init(|| {
+            let (tx, rx) = bounded(
+                "account-commands",
+                ACCOUNT_COMMAND_QUEUE_CAPACITY,
+                OverflowPolicy::Block,
+            );
+            let state = Arc::new(Mutex::new(ActorState {
+                commands: rx,
+                accounts: HashMap::new(),
+            }));
+            supervised_spawn("account-actor", RestartPolicy::default(), move || {
+                run(state.clone())
+            });
+            tx
+        })
+    }
+
+    async fn send(&self, command: AccountCommand) -> bool {
+        if self.sender().send(command).await.is_err() {
+            error!("Account actor gave up restarting; account command dropped");
+            return false;
+        }
+        true
+    }
+
+    pub(crate) async fn register(&self, status: AccountStatus) {
+        self.send(AccountCommand::Register(status)).await;
+    }
+
+    /// Whether the account was found
+    pub(crate) async fn set_active(&self, account_id: &str, active: bool) -> bool {
+        let (ack, rx) = oneshot::channel();
+        let command = AccountCommand::SetActive {
+            account_id: account_id.to_string(),
+            active,
+            ack,
+        };
+        self.send(command).await && rx.await.unwrap_or(false)
+    }
+
+    pub(crate) async fn order_placed(
+        &self,
+        account_id: &str,
+        filled_quantity: f64,
+        risk_per_unit: f64,
+    ) {
+        self.record_fill(account_id, filled_quantity, risk_per_unit, true)
+            .await;
+    }
+
+    /// A later fill of a position already counted by `order_placed`
+    pub(crate) async fn position_added(
+        &self,
+        account_id: &str,
+        filled_quantity: f64,
+        risk_per_unit: f64,
+    ) {
+        self.record_fill(account_id, filled_quantity, risk_per_unit, false)
+            .await;
+    }
+
+    async fn record_fill(
+        &self,
+        account_id: &str,
+        filled_quantity: f64,
+        risk_per_unit: f64,
+        opens_position: bool,
+    ) {
+        let command = AccountCommand::OrderPlaced {
+            account_id: account_id.to_string(),
+            at: SystemTime::now(),
+            filled_quantity,
+            risk_per_unit,
+            opens_position,
+        };
+        self.send(command).await;
+    }
+
+    /// Record the account's daily drawdown and keep the deepest total drawdown
+    pub(crate) async fn set_drawdowns(&self, account_id: &str, daily: f64, total: f64) {
+        let command = AccountCommand::Drawdowns {
+            account_id: account_id.to_string(),
+            daily,
+            total,
+        };
+        self.send(command).await;
+    }
+
+    pub(crate) async fn get(&self, account_id: &str) -> Option<AccountStatus> {
+        let (reply, rx) = oneshot::channel();
+        let command = AccountCommand::Get {
+            account_id: account_id.to_string(),
+            reply,
+        };
+        if !self.send(command).await {
+            return None;
+        }
+        rx.await.ok().flatten()
+    }
+
+    /// Every account as of one point in the queue, by account id
+    pub(crate) async fn snapshot(&self) -> Vec<AccountStatus> {
+        let (reply, rx) = oneshot::channel();
+        if !self.send(AccountCommand::Snapshot(reply)).await {
+            return Vec::new();
+        }
+        rx.await.unwrap_or_default()
+    }
+}
+
+/// Kept outside the task so a restarted actor picks up where the last one panicked
+struct ActorState {
+    commands: BoundedReceiver<AccountCommand>,
+    accounts: HashMap<String, AccountStatus>,
+}
+
+async fn run(state: Arc<Mutex<ActorState>>) {
+    let mut state = state.lock().await;
+    let ActorState { commands, accounts } = &mut *state;
+    while let Some(command) = commands.recv().await {
+        match command {
+            AccountCommand::Register(status) => {
+                accounts.insert(status.account_id.clone(), status);
+            }
+            AccountCommand::SetActive {
+                account_id,
+                active,
+                ack,
+            } => {
+                let found = match accounts.get_mut(&account_id) {
+                    Some(account) => {
+                        account.is_active = active;
+                        true
+                    }
+                    None => false,
+                };
+                let _ = ack.send(found);
+            }
+            AccountCommand::OrderPlaced {
+                account_id,
+                at,
+                filled_quantity,
+                risk_per_unit,
+                opens_position,
+            } => {
+                if let Some(account) = accounts.get_mut(&account_id) {
+                    account.last_trade_time = Some(at);
+                    if opens_position {
+                        account.open_positions += 1;
+                    }
+                    account.open_exposure += filled_quantity;
+                    account.risk_budget_remaining -= filled_quantity * risk_per_unit;
+                }
+            }
+            AccountCommand::Drawdowns {
+                account_id,
+                daily,
+                total,
+            } => {
+                if let Some(account) = accounts.get_mut(&account_id) {
+                    account.daily_drawdown = daily;
+                    account.max_drawdown = account.max_drawdown.max(total);
+                }
+            }
+            AccountCommand::Get { account_id, reply } => {
+                let _ = reply.send(accounts.get(&account_id).cloned());
+            }
+            AccountCommand::Snapshot(reply) => {
+                let mut statuses: Vec<AccountStatus> = accounts.values().cloned().collect();
+                statuses.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+                let _ = reply.send(statuses);
+            }
+        }
+    }
+}
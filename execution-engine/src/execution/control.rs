@@ -0,0 +1,108 @@
+// Control actions taken on the engine from outside it, such as pausing an
+// account or engaging the kill switch, recorded in the execution audit trail
+// alongside the executions they affect
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use super::orchestrator::ExecutionAuditEntry;
+use crate::auth::{Principal, Role};
+
+/// Tag carried by every control action's audit entry
+pub const CONTROL_TAG: &str = "control";
+/// Actor recorded when the API runs without authentication
+pub const ANONYMOUS_ACTOR: &str = "anonymous";
+
+/// Who did what to which account, with what parameters and outcome
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlAction {
+    pub actor: String,
+    pub role: Option<Role>,
+    /// e.g. `pause_account` or `kill_switch`
+    pub action: String,
+    /// Unset for actions on every account or on the engine itself
+    pub account_id: Option<String>,
+    pub parameters: Vec<(String, String)>,
+    /// What came of it, or why it failed
+    pub outcome: Result<String, String>,
+}
+
+impl ControlAction {
+    /// `action` by `principal`, or by an anonymous caller when there is none
+    pub fn new(principal: Option<&Principal>, action: &str) -> Self {
+        Self {
+            actor: principal
+                .map(|p| p.subject.clone())
+                .unwrap_or_else(|| ANONYMOUS_ACTOR.to_string()),
+            role: principal.map(|p| p.role),
+            action: action.to_string(),
+            account_id: None,
+            parameters: Vec::new(),
+            outcome: Ok(String::new()),
+        }
+    }
+
+    pub fn with_account(mut self, account_id: Option<&str>) -> Self {
+        self.account_id = account_id.map(str::to_string);
+        self
+    }
+
+    pub fn with_parameter(mut self, name: &str, value: impl ToString) -> Self {
+        self.parameters.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn succeeded(mut self, summary: &str) -> Self {
+        self.outcome = Ok(summary.to_string());
+        self
+    }
+
+    pub fn failed(mut self, error: impl ToString) -> Self {
+        self.outcome = Err(error.to_string());
+        self
+    }
+
+    pub fn with_outcome<T, E: ToString>(mut self, outcome: &Result<T, E>, summary: &str) -> Self {
+        self.outcome = match outcome {
+            Ok(_) => Ok(summary.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        self
+    }
+
+    /// The audit entry of the action: `CONTROL_<ACTION>`, with the actor, role,
+    /// outcome and each parameter (as `param.<name>`) in its metadata
+    pub fn to_audit_entry(&self) -> ExecutionAuditEntry {
+        let mut metadata = HashMap::from([("actor".to_string(), self.actor.clone())]);
+        if let Some(role) = self.role {
+            metadata.insert("role".to_string(), role.to_string());
+        }
+        for (name, value) in &self.parameters {
+            metadata.insert(format!("param.{}", name), value.clone());
+        }
+        let (outcome, detail) = match &self.outcome {
+            Ok(summary) => ("succeeded", summary),
+            Err(error) => ("failed", error),
+        };
+        metadata.insert("outcome".to_string(), outcome.to_string());
+
+        let mut rationale = format!("{} by {} {}", self.action, self.actor, outcome);
+        if !detail.is_empty() {
+            rationale.push_str(": ");
+            rationale.push_str(detail);
+        }
+
+        ExecutionAuditEntry {
+            id: Uuid::new_v4().to_string(),
+            timestamp: SystemTime::now(),
+            signal_id: String::new(),
+            account_id: self.account_id.clone().unwrap_or_default(),
+            action: format!("CONTROL_{}", self.action.to_uppercase()),
+            decision_rationale: rationale,
+            result: None,
+            metadata,
+            tags: vec![CONTROL_TAG.to_string()],
+        }
+    }
+}
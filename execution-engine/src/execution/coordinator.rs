@@ -244,7 +244,7 @@ impl ExecutionCoordinator {
             .get(&monitor.account_id)
             .ok_or_else(|| "Platform not found".to_string())?;
 
-        let completion_order = UnifiedOrder {
+        let mut completion_order = UnifiedOrder {
             client_order_id: Uuid::new_v4().to_string(),
             symbol: "EURUSD".to_string(),
             order_type: UnifiedOrderType::Market,
@@ -263,8 +263,14 @@ impl ExecutionCoordinator {
                 tags: vec!["partial_fill_completion".to_string()],
                 expires_at: None,
             },
+            max_slippage: None,
         };
 
+        platform
+            .capabilities()
+            .normalize_order_quantity(&mut completion_order)
+            .map_err(|e| format!("Completion order rejected before submission: {}", e))?;
+
         match timeout(
             self.partial_fill_timeout,
             platform.place_order(completion_order),
@@ -0,0 +1,330 @@
+// Drop-copy reconciliation: fills the broker copies from its side of the account,
+// matched against the orders the engine sent, so trades placed by hand on the
+// account are flagged
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, warn};
+
+use super::order_tracker::{OrderTracker, TrackedOrder};
+use crate::notifications::{Notification, NotificationTopic, Notifier};
+use crate::platforms::abstraction::models::UnifiedOrderSide;
+use crate::platforms::dxtrade::{FIXMessage, MessageType, RoutedMessage};
+use crate::runtime::channel::BoundedReceiver;
+use crate::runtime::supervisor::{ShutdownSignal, Subsystem};
+
+/// How long a fill may wait for the order that caused it to be recorded, as
+/// the copy can arrive before the order's own acknowledgement
+const DEFAULT_GRACE: Duration = Duration::from_secs(10);
+/// Execution ids remembered to drop redelivered copies
+const SEEN_EXEC_IDS: usize = 10_000;
+/// Unrecognized trades kept in the report
+const REPORTED_TRADES: usize = 500;
+
+/// One fill from a drop-copy execution report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DropCopyFill {
+    pub exec_id: String,
+    pub account_id: String,
+    pub platform_order_id: Option<String>,
+    pub client_order_id: Option<String>,
+    pub symbol: String,
+    pub side: Option<UnifiedOrderSide>,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    pub executed_at: DateTime<Utc>,
+}
+
+impl DropCopyFill {
+    /// The fill an execution report carries, if it reports one. Reports
+    /// without an Account (1) are taken to be on `default_account`.
+    pub fn from_execution_report(message: &FIXMessage, default_account: &str) -> Option<Self> {
+        if message.msg_type != MessageType::ExecutionReport {
+            return None;
+        }
+        // ExecType (150): F is a trade in FIX 4.4, 1 and 2 partial and full
+        // fills in earlier versions
+        if !matches!(
+            message.get_field(150).map(String::as_str),
+            Some("F" | "1" | "2")
+        ) {
+            return None;
+        }
+        let quantity = message.get_field_as_decimal(32)?;
+        if quantity.is_zero() {
+            return None;
+        }
+
+        Some(Self {
+            exec_id: message.get_field(17)?.clone(),
+            account_id: message
+                .get_field(1)
+                .cloned()
+                .unwrap_or_else(|| default_account.to_string()),
+            platform_order_id: message.get_field(37).cloned(),
+            client_order_id: message.get_field(11).cloned(),
+            symbol: message.get_field(55).cloned().unwrap_or_default(),
+            side: match message.get_field(54).map(String::as_str) {
+                Some("1") => Some(UnifiedOrderSide::Buy),
+                Some("2") => Some(UnifiedOrderSide::Sell),
+                _ => None,
+            },
+            quantity,
+            price: message.get_field_as_decimal(31).unwrap_or_default(),
+            executed_at: message.get_field_as_datetime(60).unwrap_or_else(Utc::now),
+        })
+    }
+}
+
+/// What a fill was matched to
+#[derive(Debug, Clone, PartialEq)]
+pub enum DropCopyMatch {
+    /// An order the engine sent
+    Ours(TrackedOrder),
+    /// No order the engine sent yet; flagged if none turns up within the grace
+    Pending,
+    /// Seen before under the same execution id
+    Duplicate,
+}
+
+/// Drop-copy counts of one account
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccountDropCopyStats {
+    pub matched: u64,
+    pub unrecognized: u64,
+    pub last_fill_at: Option<DateTime<Utc>>,
+}
+
+/// What drop-copy reconciliation has found so far
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DropCopyReport {
+    pub fills: u64,
+    pub matched: u64,
+    pub duplicates: u64,
+    /// Fills still waiting for their order to be recorded
+    pub pending: usize,
+    pub accounts: HashMap<String, AccountDropCopyStats>,
+    /// Fills of orders the engine never sent, most recent last
+    pub unrecognized: Vec<DropCopyFill>,
+}
+
+#[derive(Debug, Default)]
+struct SeenExecIds {
+    ids: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl SeenExecIds {
+    /// Whether `exec_id` is new, remembering it
+    fn insert(&mut self, exec_id: &str) -> bool {
+        if !self.ids.insert(exec_id.to_string()) {
+            return false;
+        }
+        self.order.push_back(exec_id.to_string());
+        if self.order.len() > SEEN_EXEC_IDS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// Matches drop-copy fills to the orders in the engine's order tracker
+pub struct DropCopyReconciler {
+    tracker: Arc<OrderTracker>,
+    default_account: String,
+    grace: Duration,
+    notifier: Option<Arc<Notifier>>,
+    seen: Mutex<SeenExecIds>,
+    pending: Mutex<Vec<(DropCopyFill, Instant)>>,
+    report: RwLock<DropCopyReport>,
+}
+
+impl DropCopyReconciler {
+    /// Reconcile against `tracker` the fills of the drop copy of
+    /// `default_account`
+    pub fn new(tracker: Arc<OrderTracker>, default_account: &str) -> Self {
+        Self {
+            tracker,
+            default_account: default_account.to_string(),
+            grace: DEFAULT_GRACE,
+            notifier: None,
+            seen: Mutex::new(SeenExecIds::default()),
+            pending: Mutex::new(Vec::new()),
+            report: RwLock::new(DropCopyReport::default()),
+        }
+    }
+
+    pub fn with_grace(mut self, grace: Duration) -> Self {
+        self.grace = grace;
+        self
+    }
+
+    /// Notify of every trade the engine did not place
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Reconcile one drop-copy message; messages without a fill are ignored
+    pub fn ingest(&self, message: &FIXMessage) -> Option<DropCopyMatch> {
+        let fill = DropCopyFill::from_execution_report(message, &self.default_account)?;
+        Some(self.reconcile(fill))
+    }
+
+    pub fn reconcile(&self, fill: DropCopyFill) -> DropCopyMatch {
+        if !self.seen.lock().unwrap().insert(&fill.exec_id) {
+            self.report.write().unwrap().duplicates += 1;
+            return DropCopyMatch::Duplicate;
+        }
+
+        let mut report = self.report.write().unwrap();
+        report.fills += 1;
+        match self.find(&fill) {
+            Some(order) => {
+                record_match(&mut report, &fill);
+                DropCopyMatch::Ours(order)
+            }
+            None => {
+                debug!(
+                    "Drop-copy fill {} on {} waits for its order",
+                    fill.exec_id, fill.account_id
+                );
+                let mut pending = self.pending.lock().unwrap();
+                pending.push((fill, Instant::now()));
+                report.pending = pending.len();
+                DropCopyMatch::Pending
+            }
+        }
+    }
+
+    fn find(&self, fill: &DropCopyFill) -> Option<TrackedOrder> {
+        self.tracker.find(
+            fill.platform_order_id.as_deref(),
+            fill.client_order_id.as_deref(),
+        )
+    }
+
+    /// Match the waiting fills again and flag those past the grace whose
+    /// order never turned up. Returns the fills flagged.
+    pub async fn sweep(&self) -> Vec<DropCopyFill> {
+        let flagged = {
+            let mut report = self.report.write().unwrap();
+            let mut pending = self.pending.lock().unwrap();
+            let mut flagged = Vec::new();
+            pending.retain(|(fill, received_at)| {
+                if self.find(fill).is_some() {
+                    record_match(&mut report, fill);
+                    return false;
+                }
+                if received_at.elapsed() < self.grace {
+                    return true;
+                }
+                flagged.push(fill.clone());
+                false
+            });
+            report.pending = pending.len();
+            for fill in &flagged {
+                let stats = report.accounts.entry(fill.account_id.clone()).or_default();
+                stats.unrecognized += 1;
+                stats.last_fill_at = Some(fill.executed_at);
+                report.unrecognized.push(fill.clone());
+            }
+            let excess = report.unrecognized.len().saturating_sub(REPORTED_TRADES);
+            report.unrecognized.drain(..excess);
+            flagged
+        };
+
+        for fill in &flagged {
+            warn!(
+                "Trade not placed by the engine on {}: {} {} at {} (order {:?}, exec {})",
+                fill.account_id,
+                fill.quantity,
+                fill.symbol,
+                fill.price,
+                fill.platform_order_id,
+                fill.exec_id
+            );
+            if let Some(notifier) = &self.notifier {
+                let notification = Notification::new(
+                    NotificationTopic::RiskAlert,
+                    "Manual trade detected",
+                    format!(
+                        "The broker reports a fill of {} {} at {} the engine did not place",
+                        fill.quantity, fill.symbol, fill.price
+                    ),
+                )
+                .for_account(fill.account_id.clone());
+                notifier.notify(&notification).await;
+            }
+        }
+        flagged
+    }
+
+    pub fn report(&self) -> DropCopyReport {
+        self.report.read().unwrap().clone()
+    }
+}
+
+fn record_match(report: &mut DropCopyReport, fill: &DropCopyFill) {
+    report.matched += 1;
+    let stats = report.accounts.entry(fill.account_id.clone()).or_default();
+    stats.matched += 1;
+    stats.last_fill_at = Some(fill.executed_at);
+}
+
+/// Feeds the reconciler the execution reports of the drop-copy sessions and
+/// sweeps its waiting fills each second
+pub struct DropCopyConsumer {
+    reconciler: Arc<DropCopyReconciler>,
+    receiver: tokio::sync::Mutex<BoundedReceiver<RoutedMessage>>,
+}
+
+impl DropCopyConsumer {
+    /// `receiver` is subscribed to the execution reports of drop-copy sessions
+    pub fn new(
+        reconciler: Arc<DropCopyReconciler>,
+        receiver: BoundedReceiver<RoutedMessage>,
+    ) -> Self {
+        Self {
+            reconciler,
+            receiver: tokio::sync::Mutex::new(receiver),
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for DropCopyConsumer {
+    fn name(&self) -> &str {
+        "drop-copy"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut receiver = self.receiver.lock().await;
+        let mut ticker = interval(Duration::from_secs(1));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                routed = receiver.recv() => match routed {
+                    Some(routed) => {
+                        self.reconciler.ingest(&routed.message);
+                    }
+                    None => return Ok(()),
+                },
+                _ = ticker.tick() => {
+                    self.reconciler.sweep().await;
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
@@ -0,0 +1,238 @@
+// Adoption of positions opened outside the engine, e.g. by hand in the broker's
+// platform, so exit management protects them like the engine's own
+
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+use super::platform_adapter::ticket_position_id;
+use super::policy::ExitPolicy;
+use super::types::PositionId;
+use super::ExitManagementSystem;
+use crate::execution::order_tracker::{OrderOrigin, OrderTracker};
+use crate::execution::tags::TagRegistry;
+use crate::journal::TradeJournal;
+use crate::platforms::abstraction::models::UnifiedPosition;
+
+/// Tag marking the trades and positions of adopted positions
+pub const EXTERNAL_ORIGIN_TAG: &str = "origin:external";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdoptionConfig {
+    /// Adopt positions no engine order opened as they are found; manual adoption
+    /// through the API is always available
+    pub auto_adopt: bool,
+    pub check_interval_secs: u64,
+    /// How long a position must be open before it is judged, so the order that
+    /// opened it has been recorded
+    pub min_age_secs: i64,
+    /// How long before a position opened an engine order may have been sent and
+    /// still count as the order that opened it
+    pub match_window_secs: i64,
+    /// Policy attached to adopted positions when none is given
+    pub default_policy: ExitPolicy,
+}
+
+impl Default for AdoptionConfig {
+    fn default() -> Self {
+        Self {
+            auto_adopt: false,
+            check_interval_secs: 60,
+            min_age_secs: 30,
+            match_window_secs: 300,
+            default_policy: ExitPolicy {
+                name: Some("adopted".to_string()),
+                ..ExitPolicy::default()
+            },
+        }
+    }
+}
+
+/// A position put under exit management after it was opened outside the engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdoptedPosition {
+    pub account_id: String,
+    /// Platform ticket id
+    pub position_id: String,
+    /// Id exit management knows the position by
+    pub exit_position_id: PositionId,
+    pub symbol: String,
+    pub policy: ExitPolicy,
+    /// Whether reconciliation adopted it, rather than an operator
+    pub automatic: bool,
+    /// Journal trade tagged as externally originated
+    pub trade_id: Option<String>,
+    pub adopted_at: DateTime<Utc>,
+}
+
+/// Finds open positions the engine did not open and registers them with exit
+/// management. Engine orders are only remembered while the engine runs, so
+/// positions already open when it started are left to manual adoption.
+pub struct PositionAdopter {
+    config: AdoptionConfig,
+    order_tracker: Arc<OrderTracker>,
+    journal: Option<Arc<TradeJournal>>,
+    tag_registry: Option<Arc<TagRegistry>>,
+    started_at: DateTime<Utc>,
+    adopted: DashMap<String, AdoptedPosition>,
+    /// Positions judged to have been opened by the engine, with their account
+    own: DashMap<String, String>,
+}
+
+impl PositionAdopter {
+    pub fn new(config: AdoptionConfig, order_tracker: Arc<OrderTracker>) -> Self {
+        Self {
+            config,
+            order_tracker,
+            journal: None,
+            tag_registry: None,
+            started_at: Utc::now(),
+            adopted: DashMap::new(),
+            own: DashMap::new(),
+        }
+    }
+
+    /// Tag the journal trades of adopted positions as externally originated
+    pub fn with_journal(mut self, journal: Arc<TradeJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Tag adopted positions in `tag_registry`, so position queries and exit audit
+    /// entries carry the origin too
+    pub fn with_tag_registry(mut self, tag_registry: Arc<TagRegistry>) -> Self {
+        self.tag_registry = Some(tag_registry);
+        self
+    }
+
+    pub fn config(&self) -> &AdoptionConfig {
+        &self.config
+    }
+
+    /// Whether an order the engine sent could have opened `position`
+    pub fn opened_by_engine(&self, account_id: &str, position: &UnifiedPosition) -> bool {
+        self.order_tracker.sent_between(
+            account_id,
+            &position.symbol,
+            OrderOrigin::Entry,
+            position.opened_at - Duration::seconds(self.config.match_window_secs),
+            // Allowance for the platform clock running behind ours
+            position.opened_at + Duration::seconds(5),
+        )
+    }
+
+    /// Put `position` under exit management with `policy`, or the default policy,
+    /// and tag it as externally originated
+    pub async fn adopt(
+        &self,
+        account_id: &str,
+        position: &UnifiedPosition,
+        system: &ExitManagementSystem,
+        policy: Option<ExitPolicy>,
+        automatic: bool,
+    ) -> AdoptedPosition {
+        let policy = policy.unwrap_or_else(|| self.config.default_policy.clone());
+        let exit_position_id = ticket_position_id(&position.position_id);
+        system.set_position_policy(exit_position_id, policy.clone());
+
+        let external = vec![EXTERNAL_ORIGIN_TAG.to_string()];
+        if let Some(registry) = &self.tag_registry {
+            let mut tags = registry.tags_for(&position.position_id);
+            if !tags.contains(&external[0]) {
+                tags.extend(external.clone());
+            }
+            registry.set(account_id, &position.position_id, tags);
+        }
+        let trade_id = match &self.journal {
+            Some(journal) => {
+                journal.record_open(account_id, position).await;
+                journal.add_tags(&position.position_id, &external).await
+            }
+            None => None,
+        };
+
+        info!(
+            "Adopted {} position {} on {} into exit management with policy {}",
+            if automatic {
+                "external"
+            } else {
+                "manually selected"
+            },
+            position.position_id,
+            account_id,
+            policy.name.as_deref().unwrap_or("unnamed")
+        );
+        let adopted = AdoptedPosition {
+            account_id: account_id.to_string(),
+            position_id: position.position_id.clone(),
+            exit_position_id,
+            symbol: position.symbol.clone(),
+            policy,
+            automatic,
+            trade_id,
+            adopted_at: Utc::now(),
+        };
+        self.adopted
+            .insert(position.position_id.clone(), adopted.clone());
+        adopted
+    }
+
+    /// Adopt the open positions of `account_id` that no engine order opened and
+    /// forget those that have closed. Returns the positions adopted.
+    pub async fn reconcile(
+        &self,
+        account_id: &str,
+        positions: &[UnifiedPosition],
+        system: &ExitManagementSystem,
+    ) -> Vec<AdoptedPosition> {
+        let open: HashSet<&str> = positions.iter().map(|p| p.position_id.as_str()).collect();
+        self.adopted
+            .retain(|id, adopted| adopted.account_id != account_id || open.contains(id.as_str()));
+        self.own
+            .retain(|id, account| account != account_id || open.contains(id.as_str()));
+
+        if !self.config.auto_adopt {
+            return Vec::new();
+        }
+
+        let now = Utc::now();
+        let mut adopted = Vec::new();
+        for position in positions {
+            let id = &position.position_id;
+            if self.adopted.contains_key(id)
+                || self.own.contains_key(id)
+                || position.opened_at < self.started_at
+                || now - position.opened_at < Duration::seconds(self.config.min_age_secs)
+            {
+                continue;
+            }
+            // A policy from a signal or an operator already governs it
+            if system.get_position_policy(ticket_position_id(id)).is_some()
+                || self.opened_by_engine(account_id, position)
+            {
+                debug!("Position {} on {} was opened by the engine", id, account_id);
+                self.own.insert(id.clone(), account_id.to_string());
+                continue;
+            }
+            adopted.push(self.adopt(account_id, position, system, None, true).await);
+        }
+        adopted
+    }
+
+    /// Positions adopted that are still open, by account then position
+    pub fn adopted(&self) -> Vec<AdoptedPosition> {
+        let mut adopted: Vec<AdoptedPosition> =
+            self.adopted.iter().map(|a| a.value().clone()).collect();
+        adopted
+            .sort_by(|a, b| (&a.account_id, &a.position_id).cmp(&(&b.account_id, &b.position_id)));
+        adopted
+    }
+
+    pub fn is_adopted(&self, position_id: &str) -> bool {
+        self.adopted.contains_key(position_id)
+    }
+}
@@ -1,21 +1,31 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration};
 use dashmap::DashSet;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn, Instrument};
 
 use super::exit_logger::ExitAuditLogger;
+use super::market_context::MarketContextProvider;
+use super::policy::{resolve_config, ExitPolicies};
 use super::types::*;
 use super::TradingPlatform;
+use crate::instruments::InstrumentMetadataService;
+use crate::market_analysis::StructureAnalyzer;
+use crate::platforms::abstraction::errors::ValidationError;
+use crate::runtime::logging::LogContext;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BreakEvenManager {
     trading_platform: Arc<dyn TradingPlatform>,
     exit_logger: Arc<ExitAuditLogger>,
     break_even_configs: HashMap<String, BreakEvenConfig>,
     break_even_positions: Arc<DashSet<PositionId>>,
+    exit_policies: Option<Arc<ExitPolicies>>,
+    instruments: Arc<InstrumentMetadataService>,
+    structure: Option<Arc<StructureAnalyzer>>,
+    market_context: Arc<MarketContextProvider>,
 }
 
 impl BreakEvenManager {
@@ -24,13 +34,49 @@ impl BreakEvenManager {
         exit_logger: Arc<ExitAuditLogger>,
     ) -> Self {
         Self {
+            market_context: Arc::new(MarketContextProvider::new(trading_platform.clone())),
             trading_platform,
             exit_logger,
             break_even_configs: HashMap::new(),
             break_even_positions: Arc::new(DashSet::new()),
+            exit_policies: None,
+            instruments: Arc::new(InstrumentMetadataService::new()),
+            structure: None,
         }
     }
 
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentMetadataService>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+
+    pub fn with_exit_policies(mut self, exit_policies: Arc<ExitPolicies>) -> Self {
+        self.exit_policies = Some(exit_policies);
+        self
+    }
+
+    /// Read support and resistance from `structure` for `structure_trigger`
+    pub fn with_structure(mut self, structure: Arc<StructureAnalyzer>) -> Self {
+        self.structure = Some(structure);
+        self
+    }
+
+    /// Record audit entries with market context captured by `market_context`
+    pub fn with_market_context(mut self, market_context: Arc<MarketContextProvider>) -> Self {
+        self.market_context = market_context;
+        self
+    }
+
+    /// Configuration that applies to `position`: its policy, else its symbol's
+    pub fn config_for(&self, position: &Position) -> BreakEvenConfig {
+        resolve_config(
+            self.exit_policies
+                .as_ref()
+                .and_then(|p| p.break_even(position.id)),
+            self.break_even_configs.get(&position.symbol),
+        )
+    }
+
     pub fn configure_symbol(&mut self, symbol: String, config: BreakEvenConfig) {
         self.break_even_configs.insert(symbol, config);
     }
@@ -39,80 +85,101 @@ impl BreakEvenManager {
         let open_positions = self.get_positions_without_breakeven().await?;
 
         for position in open_positions {
-            if self.is_break_even_triggered(&position).await? {
-                if let Err(e) = self.execute_break_even(&position).await {
-                    error!(
-                        "Failed to execute break-even for position {}: {}",
-                        position.id, e
-                    );
+            async {
+                if self.is_break_even_triggered(&position).await? {
+                    if let Err(e) = self.execute_break_even(&position).await {
+                        error!(
+                            "Failed to execute break-even for position {}: {}",
+                            position.id, e
+                        );
+                    }
                 }
+                Ok::<_, anyhow::Error>(())
             }
+            .instrument(LogContext::position(position.id).span())
+            .await?;
         }
 
         Ok(())
     }
 
     async fn is_break_even_triggered(&self, position: &Position) -> Result<bool> {
+        let trigger = self.break_even_trigger(position).await?;
+        if let Some(reason) = &trigger {
+            info!(
+                "Break-even triggered for position {}: {}",
+                position.id, reason
+            );
+        }
+        Ok(trigger.is_some())
+    }
+
+    /// Why break-even would trigger for the position now, if it would
+    async fn break_even_trigger(&self, position: &Position) -> Result<Option<String>> {
         let current_price = self.get_current_price(&position.symbol).await?;
         let entry_price = position.entry_price;
-        let initial_stop = position.stop_loss.unwrap_or(0.0);
+        let initial_stop = position.stop_loss.unwrap_or_default();
 
-        if initial_stop == 0.0 {
-            return Ok(false); // No stop loss set, can't calculate break-even
+        if initial_stop.is_zero() {
+            return Ok(None); // No stop loss set, can't calculate break-even
         }
 
+        let instrument = self.instruments.get(&position.symbol);
+
         // Calculate current profit in pips
-        let profit_pips = match position.position_type {
-            UnifiedPositionSide::Long => (current_price - entry_price) * 10000.0,
-            UnifiedPositionSide::Short => (entry_price - current_price) * 10000.0,
-        };
+        let profit_pips = instrument.price_to_pips(match position.position_type {
+            UnifiedPositionSide::Long => current_price - entry_price,
+            UnifiedPositionSide::Short => entry_price - current_price,
+        });
 
         // Calculate initial risk in pips
-        let risk_pips = match position.position_type {
-            UnifiedPositionSide::Long => (entry_price - initial_stop) * 10000.0,
-            UnifiedPositionSide::Short => (initial_stop - entry_price) * 10000.0,
-        };
+        let risk_pips = instrument.price_to_pips(match position.position_type {
+            UnifiedPositionSide::Long => entry_price - initial_stop,
+            UnifiedPositionSide::Short => initial_stop - entry_price,
+        });
 
-        if risk_pips <= 0.0 {
-            return Ok(false); // Invalid risk calculation
+        if risk_pips <= Decimal::ZERO {
+            return Ok(None); // Invalid risk calculation
         }
 
-        let default_config = BreakEvenConfig::default();
-        let config = self
-            .break_even_configs
-            .get(&position.symbol)
-            .unwrap_or(&default_config);
+        let config = self.config_for(position);
 
         if !config.enabled {
-            return Ok(false);
+            return Ok(None);
         }
 
         // Check if risk-reward threshold achieved
-        let break_even_threshold = risk_pips * config.trigger_ratio;
-        let triggered = profit_pips >= break_even_threshold;
-
-        if triggered {
-            info!(
-                "Break-even triggered for position {}: Profit {:.1} pips >= Threshold {:.1} pips",
-                position.id, profit_pips, break_even_threshold
-            );
+        let break_even_threshold = scale_by(risk_pips, config.trigger_ratio);
+        if profit_pips >= break_even_threshold {
+            return Ok(Some(format!(
+                "Profit {:.1} pips >= Threshold {:.1} pips",
+                profit_pips, break_even_threshold
+            )));
         }
 
-        Ok(triggered)
+        // A broken zone only counts once the stop can sit beyond the buffer
+        if !config.structure_trigger || profit_pips <= config.break_even_buffer_pips {
+            return Ok(None);
+        }
+        let Some(structure) = self
+            .structure
+            .as_ref()
+            .and_then(|s| s.analyze(&position.symbol, current_price))
+        else {
+            return Ok(None);
+        };
+        Ok(structure
+            .zones_between(entry_price, current_price)
+            .last()
+            .map(|zone| format!("price broke the {}-{} zone", zone.lower, zone.upper)))
     }
 
-    async fn execute_break_even(&self, position: &Position) -> Result<()> {
-        let default_config = BreakEvenConfig::default();
-        let config = self
-            .break_even_configs
-            .get(&position.symbol)
-            .unwrap_or(&default_config);
-
-        // Calculate break-even level with buffer
-        let buffer = config.break_even_buffer_pips / 10000.0; // Convert pips to price
-        let break_even_level = match position.position_type {
-            UnifiedPositionSide::Long => position.entry_price + buffer,
-            UnifiedPositionSide::Short => position.entry_price - buffer,
+    /// Move the stop to break-even. Returns false without touching the position when
+    /// the platform's stop distances do not allow the level yet.
+    async fn execute_break_even(&self, position: &Position) -> Result<bool> {
+        let config = self.config_for(position);
+        let Some(break_even_level) = self.break_even_level(position, &config).await? else {
+            return Ok(false);
         };
 
         let modify_request = OrderModifyRequest {
@@ -121,12 +188,22 @@ impl BreakEvenManager {
             new_take_profit: position.take_profit,
         };
 
+        // The stop moves first: if the partial close then fails, the whole position is
+        // still protected, whereas a partial without the stop would leave it at full risk
         let result = self
             .trading_platform
             .modify_order(modify_request)
             .await
             .context("Failed to modify order for break-even stop")?;
 
+        if !result.success {
+            return Err(anyhow::anyhow!(
+                "Break-even stop rejected for position {}: {}",
+                position.id,
+                result.message
+            ));
+        }
+
         // Mark position as having break-even stop
         self.break_even_positions.insert(position.id);
 
@@ -137,11 +214,135 @@ impl BreakEvenManager {
         info!(
             "Break-even stop activated for position {}: {} -> {} (+{} pip buffer)",
             position.id,
-            position.stop_loss.unwrap_or(0.0),
+            position.stop_loss.unwrap_or_default(),
             break_even_level,
             config.break_even_buffer_pips
         );
 
+        if let Some(percent) = config.partial_close_percent {
+            // Not retried on failure: the position is already protected at break-even
+            self.execute_break_even_partial(position, percent)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Break-even stop set but partial close failed for position {}",
+                        position.id
+                    )
+                })?;
+        }
+
+        Ok(true)
+    }
+
+    /// Break-even level with its buffer, or None while the platform's stop
+    /// distances do not allow it
+    async fn break_even_level(
+        &self,
+        position: &Position,
+        config: &BreakEvenConfig,
+    ) -> Result<Option<Decimal>> {
+        let instrument = self.instruments.get(&position.symbol);
+        let buffer = instrument.pips_to_price(config.break_even_buffer_pips);
+        let break_even_level = instrument.round_price(match position.position_type {
+            UnifiedPositionSide::Long => position.entry_price + buffer,
+            UnifiedPositionSide::Short => position.entry_price - buffer,
+        });
+
+        // A level inside the minimum stop distance, or a stop inside the freeze
+        // distance, would only be rejected; it is tried again as price moves on
+        let market_data = self
+            .trading_platform
+            .get_market_data(&position.symbol)
+            .await?;
+        let allowed = instrument.allowed_stop(
+            position.position_type == UnifiedPositionSide::Long,
+            market_data.closing_price(&position.position_type),
+            position.stop_loss,
+            break_even_level,
+        );
+        if allowed != Some(break_even_level) {
+            debug!(
+                "Break-even stop {} for position {} not allowed by the platform's stop distances yet",
+                break_even_level, position.id
+            );
+            return Ok(None);
+        }
+        Ok(Some(break_even_level))
+    }
+
+    /// The stop break-even would set on the position now, and what it would close
+    pub async fn preview_break_even(&self, position: &Position) -> Result<Option<StopPreview>> {
+        if self.is_break_even_active(position.id) {
+            return Ok(None);
+        }
+        let Some(reason) = self.break_even_trigger(position).await? else {
+            return Ok(None);
+        };
+        let config = self.config_for(position);
+        let Some(new_stop) = self.break_even_level(position, &config).await? else {
+            return Ok(None);
+        };
+        Ok(Some(StopPreview {
+            current_stop: position.stop_loss,
+            new_stop,
+            close_volume: config
+                .partial_close_percent
+                .filter(|percent| *percent > 0.0 && *percent < 1.0)
+                .and_then(|percent| self.partial_close_volume(position, percent).ok()),
+            reason,
+        }))
+    }
+
+    /// `percent` of the position, rounded down to a size the platform accepts
+    fn partial_close_volume(
+        &self,
+        position: &Position,
+        percent: f64,
+    ) -> Result<Decimal, ValidationError> {
+        self.trading_platform
+            .quantity_limits(&position.symbol)
+            .normalize(&position.symbol, scale_by(position.volume, percent))
+    }
+
+    async fn execute_break_even_partial(&self, position: &Position, percent: f64) -> Result<()> {
+        if !(percent > 0.0 && percent < 1.0) {
+            warn!(
+                "Ignoring break-even partial close of {} for position {}: must be between 0 and 1",
+                percent, position.id
+            );
+            return Ok(());
+        }
+
+        let close_volume = match self.partial_close_volume(position, percent) {
+            Ok(volume) => volume,
+            Err(e) => {
+                warn!(
+                    "Skipping break-even partial close for position {}: {}",
+                    position.id, e
+                );
+                return Ok(());
+            }
+        };
+
+        let close_request = PartialCloseRequest {
+            position_id: position.id,
+            volume: close_volume,
+            reason: format!("Break-even partial close of {:.0}%", percent * 100.0),
+        };
+
+        let close_result = self
+            .trading_platform
+            .close_position_partial(close_request)
+            .await?;
+
+        self.log_break_even_partial(position, close_volume, close_result.close_price)
+            .await?;
+
+        info!(
+            "Break-even partial close for position {}: {:.4} of {:.4} at {}",
+            position.id, close_volume, position.volume, close_result.close_price
+        );
+
         Ok(())
     }
 
@@ -149,7 +350,12 @@ impl BreakEvenManager {
         let positions = self.trading_platform.get_positions().await?;
 
         if let Some(position) = positions.iter().find(|p| p.id == position_id) {
-            self.execute_break_even(position).await?;
+            if !self.execute_break_even(position).await? {
+                return Err(anyhow::anyhow!(
+                    "Break-even stop for position {} is too close to the market",
+                    position_id
+                ));
+            }
         } else {
             return Err(anyhow::anyhow!("Position {} not found", position_id));
         }
@@ -161,6 +367,10 @@ impl BreakEvenManager {
         self.break_even_positions.contains(&position_id)
     }
 
+    pub fn restore_break_even(&self, position_id: PositionId) {
+        self.break_even_positions.insert(position_id);
+    }
+
     pub fn remove_break_even_tracking(&self, position_id: PositionId) {
         self.break_even_positions.remove(&position_id);
     }
@@ -178,38 +388,53 @@ impl BreakEvenManager {
         Ok(positions_without_breakeven)
     }
 
-    async fn get_current_price(&self, symbol: &str) -> Result<f64> {
+    async fn get_current_price(&self, symbol: &str) -> Result<Decimal> {
         let market_data = self.trading_platform.get_market_data(symbol).await?;
-        Ok((market_data.bid + market_data.ask) / 2.0)
+        Ok(market_data.mid())
     }
 
     async fn log_break_even_activation(
         &self,
         position: &Position,
-        break_even_level: f64,
+        break_even_level: Decimal,
     ) -> Result<()> {
-        let current_price = self.get_current_price(&position.symbol).await?;
-
-        let market_context = MarketContext {
-            current_price,
-            atr_14: 0.0015, // Simplified
-            trend_strength: 0.5,
-            volatility: 0.02,
-            spread: 0.0001,
-            timestamp: Utc::now(),
-        };
+        let market_context = self.market_context.capture(&position.symbol).await?;
 
         let modification = ExitModification {
             position_id: position.id,
             modification_type: ExitModificationType::BreakEven,
-            old_value: position.stop_loss.unwrap_or(0.0),
+            old_value: position.stop_loss.unwrap_or_default(),
             new_value: break_even_level,
             reasoning: format!(
                 "Break-even stop activated at 1:1 R:R with {} pip buffer",
-                self.break_even_configs
-                    .get(&position.symbol)
-                    .unwrap_or(&BreakEvenConfig::default())
-                    .break_even_buffer_pips
+                self.config_for(position).break_even_buffer_pips
+            ),
+            market_context,
+        };
+
+        self.exit_logger.log_exit_modification(modification).await?;
+        Ok(())
+    }
+
+    async fn log_break_even_partial(
+        &self,
+        position: &Position,
+        volume: Decimal,
+        close_price: Decimal,
+    ) -> Result<()> {
+        let market_context = self
+            .market_context
+            .capture_at(&position.symbol, close_price)
+            .await;
+
+        let modification = ExitModification {
+            position_id: position.id,
+            modification_type: ExitModificationType::BreakEven,
+            old_value: position.volume,
+            new_value: volume,
+            reasoning: format!(
+                "Break-even partial close: Volume {:.4} at {}",
+                volume, close_price
             ),
             market_context,
         };
@@ -246,40 +471,34 @@ impl BreakEvenManager {
     ) -> Result<BreakEvenValidation> {
         let current_price = self.get_current_price(&position.symbol).await?;
         let entry_price = position.entry_price;
-        let stop_loss = position.stop_loss.unwrap_or(0.0);
+        let stop_loss = position.stop_loss.unwrap_or_default();
 
-        if stop_loss == 0.0 {
+        if stop_loss.is_zero() {
             return Ok(BreakEvenValidation {
                 is_valid: false,
                 reason: "No stop loss set".to_string(),
-                current_profit_pips: 0.0,
-                required_profit_pips: 0.0,
+                current_profit_pips: Decimal::ZERO,
+                required_profit_pips: Decimal::ZERO,
                 risk_reward_ratio: 0.0,
             });
         }
 
-        let profit_pips = match position.position_type {
-            UnifiedPositionSide::Long => (current_price - entry_price) * 10000.0,
-            UnifiedPositionSide::Short => (entry_price - current_price) * 10000.0,
-        };
+        let instrument = self.instruments.get(&position.symbol);
 
-        let risk_pips = match position.position_type {
-            UnifiedPositionSide::Long => (entry_price - stop_loss) * 10000.0,
-            UnifiedPositionSide::Short => (stop_loss - entry_price) * 10000.0,
-        };
+        let profit_pips = instrument.price_to_pips(match position.position_type {
+            UnifiedPositionSide::Long => current_price - entry_price,
+            UnifiedPositionSide::Short => entry_price - current_price,
+        });
 
-        let default_config = BreakEvenConfig::default();
-        let config = self
-            .break_even_configs
-            .get(&position.symbol)
-            .unwrap_or(&default_config);
+        let risk_pips = instrument.price_to_pips(match position.position_type {
+            UnifiedPositionSide::Long => entry_price - stop_loss,
+            UnifiedPositionSide::Short => stop_loss - entry_price,
+        });
 
-        let required_profit_pips = risk_pips * config.trigger_ratio;
-        let current_rr = if risk_pips > 0.0 {
-            profit_pips / risk_pips
-        } else {
-            0.0
-        };
+        let config = self.config_for(position);
+
+        let required_profit_pips = scale_by(risk_pips, config.trigger_ratio);
+        let current_rr = ratio_of(profit_pips, risk_pips);
 
         Ok(BreakEvenValidation {
             is_valid: profit_pips >= required_profit_pips,
@@ -302,7 +521,7 @@ impl BreakEvenManager {
 pub struct BreakEvenValidation {
     pub is_valid: bool,
     pub reason: String,
-    pub current_profit_pips: f64,
-    pub required_profit_pips: f64,
+    pub current_profit_pips: Decimal,
+    pub required_profit_pips: Decimal,
     pub risk_reward_ratio: f64,
 }
@@ -0,0 +1,95 @@
+use anyhow::Result;
+use chrono::Utc;
+use dashmap::DashMap;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::exit_logger::ExitAuditLogger;
+use super::types::*;
+use super::TradingPlatform;
+
+/// Tracks maximum adverse/favorable excursion of every open position
+#[derive(Debug)]
+pub struct ExcursionTracker {
+    trading_platform: Arc<dyn TradingPlatform>,
+    exit_logger: Arc<ExitAuditLogger>,
+    excursions: Arc<DashMap<PositionId, PositionExcursion>>,
+}
+
+impl ExcursionTracker {
+    pub fn new(
+        trading_platform: Arc<dyn TradingPlatform>,
+        exit_logger: Arc<ExitAuditLogger>,
+    ) -> Self {
+        Self {
+            trading_platform,
+            exit_logger,
+            excursions: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Sample current prices for all open positions and drop positions that have closed
+    pub async fn update_excursions(&self) -> Result<()> {
+        let positions = self.trading_platform.get_positions().await?;
+        let open_ids: HashSet<PositionId> = positions.iter().map(|p| p.id).collect();
+        self.excursions.retain(|id, _| open_ids.contains(id));
+
+        let mut prices: HashMap<String, Decimal> = HashMap::new();
+        for position in positions {
+            let price = match prices.get(&position.symbol) {
+                Some(price) => *price,
+                None => match self
+                    .trading_platform
+                    .get_market_data(&position.symbol)
+                    .await
+                {
+                    Ok(data) => {
+                        let mid = data.mid();
+                        prices.insert(position.symbol.clone(), mid);
+                        mid
+                    }
+                    Err(e) => {
+                        warn!(
+                            "No price for {} to track excursions of {}: {}",
+                            position.symbol, position.id, e
+                        );
+                        continue;
+                    }
+                },
+            };
+
+            if let Some(excursion) = self.observe(&position, price) {
+                self.exit_logger.log_excursion(&excursion).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record a price for a position. Returns the excursion if its MAE or MFE widened.
+    pub fn observe(&self, position: &Position, price: Decimal) -> Option<PositionExcursion> {
+        let mut entry = self
+            .excursions
+            .entry(position.id)
+            .or_insert_with(|| PositionExcursion::new(position));
+
+        let widened = entry.observe(price);
+        if widened {
+            debug!(
+                "Excursion widened for {}: MAE {:.5}, MFE {:.5}",
+                position.id, entry.max_adverse_excursion, entry.max_favorable_excursion
+            );
+        }
+        widened.then(|| entry.clone())
+    }
+
+    pub fn get_excursion(&self, position_id: PositionId) -> Option<PositionExcursion> {
+        self.excursions.get(&position_id).map(|e| e.clone())
+    }
+
+    pub fn get_excursions(&self) -> Vec<PositionExcursion> {
+        self.excursions.iter().map(|e| e.value().clone()).collect()
+    }
+}
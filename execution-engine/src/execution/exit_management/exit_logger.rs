@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -7,7 +8,12 @@ use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use super::scale_in::ScaleIn;
 use super::types::*;
+use crate::execution::tags::{TagFilter, TagRegistry};
+use crate::journal::{TradeAddition, TradeJournal};
+use crate::platforms::abstraction::events::{EventData, PlatformEvent, PositionCloseEventData};
+use crate::recording::{EventRecorder, RecordedEvent};
 
 // Database interface trait - would be implemented by actual database client
 #[async_trait::async_trait]
@@ -25,6 +31,11 @@ pub trait AuditDatabase: Send + Sync + std::fmt::Debug {
         modification_type: ExitModificationType,
         limit: Option<u32>,
     ) -> Result<Vec<AuditEntry>>;
+
+    /// Write out any buffered entries; a no-op for unbuffered databases
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 // In-memory implementation for testing/demo
@@ -104,7 +115,7 @@ impl AuditDatabase for InMemoryAuditDatabase {
             .collect();
 
         // Sort by timestamp, newest first
-        filtered.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        filtered.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
 
         if let Some(limit) = limit {
             filtered.truncate(limit as usize);
@@ -166,7 +177,11 @@ impl ExitAnalytics {
         match modification.modification_type {
             ExitModificationType::TrailingStop => {
                 // Positive impact for trailing stops (protecting profits)
-                Ok(0.1 * (modification.new_value - modification.old_value).abs())
+                Ok(0.1
+                    * (modification.new_value - modification.old_value)
+                        .abs()
+                        .to_f64()
+                        .unwrap_or(0.0))
             }
             ExitModificationType::BreakEven => {
                 // Strong positive impact (risk elimination)
@@ -174,7 +189,7 @@ impl ExitAnalytics {
             }
             ExitModificationType::PartialProfit => {
                 // Positive impact (profit realization)
-                Ok(0.3 * modification.new_value)
+                Ok(0.3 * modification.new_value.to_f64().unwrap_or(0.0))
             }
             ExitModificationType::TimeExit => {
                 // Neutral to negative impact (forced exit)
@@ -197,6 +212,11 @@ impl ExitAnalytics {
 pub struct ExitAuditLogger {
     audit_database: Arc<dyn AuditDatabase>,
     exit_analytics: Arc<ExitAnalytics>,
+    position_closes: Arc<RwLock<HashMap<String, Vec<PositionCloseEventData>>>>,
+    shadow_results: Arc<RwLock<HashMap<(PositionId, String), ShadowExitResult>>>,
+    trade_journal: Option<Arc<TradeJournal>>,
+    tag_registry: Option<Arc<TagRegistry>>,
+    recorder: Option<Arc<EventRecorder>>,
 }
 
 impl ExitAuditLogger {
@@ -207,6 +227,11 @@ impl ExitAuditLogger {
         Self {
             audit_database,
             exit_analytics,
+            position_closes: Arc::new(RwLock::new(HashMap::new())),
+            shadow_results: Arc::new(RwLock::new(HashMap::new())),
+            trade_journal: None,
+            tag_registry: None,
+            recorder: None,
         }
     }
 
@@ -216,9 +241,36 @@ impl ExitAuditLogger {
         Self {
             audit_database,
             exit_analytics,
+            position_closes: Arc::new(RwLock::new(HashMap::new())),
+            shadow_results: Arc::new(RwLock::new(HashMap::new())),
+            trade_journal: None,
+            tag_registry: None,
+            recorder: None,
         }
     }
 
+    /// Tell the journal which exit manager acted last, so exits are attributed to it
+    pub fn with_trade_journal(mut self, trade_journal: Arc<TradeJournal>) -> Self {
+        self.trade_journal = Some(trade_journal);
+        self
+    }
+
+    /// Stamp audit entries with the tags of the position they touch
+    pub fn with_tag_registry(mut self, tag_registry: Arc<TagRegistry>) -> Self {
+        self.tag_registry = Some(tag_registry);
+        self
+    }
+
+    /// Record every audit entry in `recorder`, for diffing replays of the day
+    pub fn with_recorder(mut self, recorder: Arc<EventRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub async fn flush(&self) -> Result<()> {
+        self.audit_database.flush().await
+    }
+
     pub async fn log_exit_modification(
         &self,
         modification: ExitModification,
@@ -235,6 +287,11 @@ impl ExitAuditLogger {
             market_context: modification.market_context.clone(),
             performance_impact,
             timestamp: Utc::now(),
+            tags: self
+                .tag_registry
+                .as_ref()
+                .map(|registry| registry.tags_for(&modification.position_id.to_string()))
+                .unwrap_or_default(),
         };
 
         // Store in audit database
@@ -249,6 +306,23 @@ impl ExitAuditLogger {
             .await
             .context("Failed to update exit analytics")?;
 
+        if let Some(journal) = &self.trade_journal {
+            journal
+                .record_exit_action(
+                    &modification.position_id.to_string(),
+                    modification.modification_type.clone(),
+                )
+                .await;
+        }
+
+        if let Some(recorder) = &self.recorder {
+            recorder
+                .record(RecordedEvent::Audit {
+                    entry: audit_entry.clone(),
+                })
+                .await;
+        }
+
         info!(
             "Exit modification logged: Position {}, Type: {:?}, {} -> {}, Reason: {}",
             modification.position_id,
@@ -269,8 +343,10 @@ impl ExitAuditLogger {
         Ok(match modification.modification_type {
             ExitModificationType::TrailingStop => {
                 // Impact based on how much profit protection was increased
-                let protection_improvement =
-                    price_change.abs() / modification.market_context.current_price;
+                let protection_improvement = ratio_of(
+                    price_change.abs(),
+                    modification.market_context.current_price,
+                );
                 protection_improvement * 100.0 // Convert to basis points
             }
             ExitModificationType::BreakEven => {
@@ -278,13 +354,20 @@ impl ExitAuditLogger {
                 50.0 // 50 basis points
             }
             ExitModificationType::PartialProfit => {
-                // Impact based on profit realization relative to market volatility
-                (modification.new_value / modification.old_value - 1.0) / market_volatility * 10.0
+                // Impact based on profit realization relative to market volatility, which is
+                // unknown until there is candle history
+                if market_volatility > 0.0 {
+                    (ratio_of(modification.new_value, modification.old_value) - 1.0)
+                        / market_volatility
+                        * 10.0
+                } else {
+                    0.0
+                }
             }
             ExitModificationType::TimeExit => {
                 // Negative impact proportional to how far from entry price
                 let exit_distance = (modification.new_value - modification.old_value).abs();
-                -(exit_distance / modification.market_context.current_price * 100.0)
+                -(ratio_of(exit_distance, modification.market_context.current_price) * 100.0)
             }
             ExitModificationType::NewsProtection => {
                 // Positive impact for risk reduction, scaled by volatility expectation
@@ -306,7 +389,7 @@ impl ExitAuditLogger {
             trailing_stop_stats: TrailingStopStats {
                 total_trails: 0,
                 successful_exits: 0,
-                average_trail_distance: 0.0,
+                average_trail_distance: Decimal::ZERO,
                 profit_captured: Decimal::ZERO,
                 best_trail_profit: Decimal::ZERO,
                 worst_trail_loss: Decimal::ZERO,
@@ -344,6 +427,7 @@ impl ExitAuditLogger {
                 start: time_range.start,
                 end: time_range.end,
             },
+            shadow_results: Vec::new(),
         };
 
         // Analyze each exit type
@@ -361,6 +445,18 @@ impl ExitAuditLogger {
         // Calculate overall performance
         report.overall_performance = self.calculate_overall_performance(&audit_entries).await?;
 
+        report.shadow_results = self
+            .shadow_results
+            .read()
+            .await
+            .values()
+            .filter(|r| r.updated_at >= time_range.start && r.updated_at <= time_range.end)
+            .cloned()
+            .collect();
+        report
+            .shadow_results
+            .sort_by(|a, b| (a.position_id, &a.variant).cmp(&(b.position_id, &b.variant)));
+
         Ok(report)
     }
 
@@ -377,13 +473,13 @@ impl ExitAuditLogger {
         report.trailing_stop_stats.total_trails = trailing_entries.len() as u32;
 
         if !trailing_entries.is_empty() {
-            let total_distance: f64 = trailing_entries
+            let total_distance: Decimal = trailing_entries
                 .iter()
                 .map(|e| (e.new_value - e.old_value).abs())
                 .sum();
 
             report.trailing_stop_stats.average_trail_distance =
-                total_distance / trailing_entries.len() as f64;
+                total_distance / Decimal::from(trailing_entries.len());
 
             // Calculate profit captured (simplified)
             let total_impact: f64 = trailing_entries.iter().map(|e| e.performance_impact).sum();
@@ -434,10 +530,8 @@ impl ExitAuditLogger {
         report.partial_profit_stats.total_partials = partial_entries.len() as u32;
 
         if !partial_entries.is_empty() {
-            let total_volume: f64 = partial_entries.iter().map(|e| e.new_value).sum();
-
             report.partial_profit_stats.total_volume_closed =
-                Decimal::from_f64_retain(total_volume).unwrap_or(Decimal::ZERO);
+                partial_entries.iter().map(|e| e.new_value).sum();
 
             let average_profit = partial_entries
                 .iter()
@@ -558,7 +652,7 @@ impl ExitAuditLogger {
             })
             .collect();
 
-        timeline.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        timeline.sort_by_key(|e| e.timestamp);
         Ok(timeline)
     }
 
@@ -665,6 +759,107 @@ impl ExitAuditLogger {
         Ok(lessons)
     }
 
+    /// Carry MAE/MFE seen by exit management into the position's journal trade
+    pub async fn log_excursion(&self, excursion: &PositionExcursion) {
+        if let Some(journal) = &self.trade_journal {
+            journal
+                .record_excursion(
+                    &excursion.position_id.to_string(),
+                    excursion.max_adverse_excursion,
+                    excursion.max_favorable_excursion,
+                )
+                .await;
+        }
+    }
+
+    /// Record an addition to a position as part of its journal trade
+    pub async fn log_scale_in(&self, scale_in: &ScaleIn) {
+        if let Some(journal) = &self.trade_journal {
+            let addition = TradeAddition {
+                quantity: scale_in.entry.quantity,
+                price: scale_in.entry.price,
+                stop_loss: scale_in.stop_loss,
+                take_profit: scale_in.take_profit,
+                signal_id: scale_in.entry.signal_id.clone(),
+                added_at: scale_in.entry.filled_at,
+            };
+            if journal
+                .record_addition(&scale_in.ticket, addition)
+                .await
+                .is_none()
+            {
+                warn!(
+                    "No journal trade for position {} to record its addition on",
+                    scale_in.ticket
+                );
+            }
+        }
+    }
+
+    /// Record realized P&L from position close events and feed the trade journal
+    pub async fn handle_platform_event(&self, event: &PlatformEvent) -> Result<()> {
+        if let EventData::PositionClose(close) = &event.data {
+            self.log_position_close(close.clone()).await;
+        }
+        if let Some(journal) = &self.trade_journal {
+            journal.handle_platform_event(event).await;
+        }
+        Ok(())
+    }
+
+    pub async fn log_position_close(&self, close: PositionCloseEventData) {
+        info!(
+            "Position close logged: {} {} closed {} @ {} (remaining {}), realized P&L {}",
+            close.position_id,
+            close.symbol,
+            close.closed_quantity,
+            close.close_price,
+            close.remaining_quantity,
+            close.realized_pnl
+        );
+
+        let mut closes = self.position_closes.write().await;
+        closes
+            .entry(close.position_id.clone())
+            .or_insert_with(Vec::new)
+            .push(close);
+    }
+
+    pub async fn get_position_closes(&self, position_id: &str) -> Vec<PositionCloseEventData> {
+        let closes = self.position_closes.read().await;
+        closes.get(position_id).cloned().unwrap_or_default()
+    }
+
+    /// Total realized P&L across all closes of a position
+    pub async fn get_realized_pnl(&self, position_id: &str) -> Decimal {
+        let closes = self.position_closes.read().await;
+        closes
+            .get(position_id)
+            .map(|c| c.iter().map(|close| close.realized_pnl).sum())
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    /// Keep the latest comparison of a position under a shadow exit variant
+    pub async fn record_shadow_result(&self, result: ShadowExitResult) {
+        self.shadow_results
+            .write()
+            .await
+            .insert((result.position_id, result.variant.clone()), result);
+    }
+
+    pub async fn get_shadow_results(&self, position_id: PositionId) -> Vec<ShadowExitResult> {
+        let mut results: Vec<ShadowExitResult> = self
+            .shadow_results
+            .read()
+            .await
+            .values()
+            .filter(|r| r.position_id == position_id)
+            .cloned()
+            .collect();
+        results.sort_by(|a, b| a.variant.cmp(&b.variant));
+        results
+    }
+
     pub async fn log_emergency_close_event(&self, reason: String) -> Result<()> {
         self.audit_database
             .store_emergency_close_event(reason, Utc::now())
@@ -673,6 +868,16 @@ impl ExitAuditLogger {
     }
 
     pub async fn get_recent_exits(&self, limit: u32) -> Result<Vec<AuditEntry>> {
+        self.get_recent_exits_matching(limit, &TagFilter::default())
+            .await
+    }
+
+    /// Exits of the last week carrying all of the filter's tags, newest first
+    pub async fn get_recent_exits_matching(
+        &self,
+        limit: u32,
+        filter: &TagFilter,
+    ) -> Result<Vec<AuditEntry>> {
         let now = Utc::now();
         let one_week_ago =
             now - Duration::from_std(std::time::Duration::from_secs(7 * 24 * 3600)).unwrap();
@@ -683,7 +888,8 @@ impl ExitAuditLogger {
         };
 
         let mut entries = self.audit_database.get_entries_in_range(time_range).await?;
-        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        entries.retain(|e| filter.matches(&e.tags));
+        entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
         entries.truncate(limit as usize);
 
         Ok(entries)
@@ -716,10 +922,10 @@ pub struct ExitTimelineEvent {
     pub timestamp: DateTime<Utc>,
     pub event_type: ExitModificationType,
     pub description: String,
-    pub old_value: f64,
-    pub new_value: f64,
+    pub old_value: Decimal,
+    pub new_value: Decimal,
     pub impact: f64,
-    pub market_price: f64,
+    pub market_price: Decimal,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,220 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Timelike, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use tracing::debug;
+
+use super::time_exits::is_weekend_close_window;
+use super::types::*;
+use super::TradingPlatform;
+use crate::market_analysis::StructureAnalyzer;
+use crate::market_data::{CandleBuilder, Timeframe};
+use crate::platforms::abstraction::clock::ServerClock;
+
+/// Forex session by UTC hour, naming the most liquid one open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketSession {
+    Sydney,
+    Tokyo,
+    London,
+    LondonNewYork,
+    NewYork,
+    /// The weekend, as time exits see it
+    Closed,
+}
+
+impl MarketSession {
+    pub fn at(at: DateTime<Utc>) -> Self {
+        if is_weekend_close_window(at) {
+            return MarketSession::Closed;
+        }
+        match at.hour() {
+            12..=15 => MarketSession::LondonNewYork,
+            7..=11 => MarketSession::London,
+            16..=20 => MarketSession::NewYork,
+            0..=6 => MarketSession::Tokyo,
+            _ => MarketSession::Sydney,
+        }
+    }
+}
+
+/// The news event closest to a modification, on either side of it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsProximity {
+    pub event: NewsEvent,
+    /// Negative once the event has been released
+    pub minutes_until: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarketContextConfig {
+    /// Candles volatility, range and trend are measured on
+    pub timeframe: Timeframe,
+    pub atr_period: usize,
+    /// Closed candles the recent range and trend strength span
+    pub range_candles: usize,
+    /// News further than this from a modification is not recorded
+    pub news_window_minutes: i64,
+}
+
+impl Default for MarketContextConfig {
+    fn default() -> Self {
+        Self {
+            timeframe: Timeframe::H1,
+            atr_period: 14,
+            range_candles: 24,
+            news_window_minutes: 240,
+        }
+    }
+}
+
+/// Captures the market as it stands when an exit modification is made, for the
+/// audit trail. Measures needing candle history are zero until there is some.
+#[derive(Debug, Clone)]
+pub struct MarketContextProvider {
+    trading_platform: Arc<dyn TradingPlatform>,
+    config: MarketContextConfig,
+    candles: Option<Arc<CandleBuilder>>,
+    structure: Option<Arc<StructureAnalyzer>>,
+    /// Calendar events last fetched by news protection, shared between clones
+    news_events: Arc<RwLock<Vec<NewsEvent>>>,
+    /// Sessions and news proximity are timed on the platform server's clock
+    clock: ServerClock,
+}
+
+impl MarketContextProvider {
+    pub fn new(trading_platform: Arc<dyn TradingPlatform>) -> Self {
+        Self {
+            trading_platform,
+            config: MarketContextConfig::default(),
+            candles: None,
+            structure: None,
+            news_events: Arc::new(RwLock::new(Vec::new())),
+            clock: ServerClock::new(),
+        }
+    }
+
+    pub fn with_config(mut self, config: MarketContextConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Measure volatility, range and trend on `candles`
+    pub fn with_candles(mut self, candles: Arc<CandleBuilder>) -> Self {
+        self.candles = Some(candles);
+        self
+    }
+
+    /// Record support and resistance read by `structure`
+    pub fn with_structure(mut self, structure: Arc<StructureAnalyzer>) -> Self {
+        self.structure = Some(structure);
+        self
+    }
+
+    /// Time sessions and news proximity on `clock` instead of ours
+    pub fn with_server_clock(mut self, clock: ServerClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn config(&self) -> &MarketContextConfig {
+        &self.config
+    }
+
+    /// Replace the calendar news proximity is measured against
+    pub fn set_news_events(&self, events: Vec<NewsEvent>) {
+        *self.news_events.write().unwrap() = events;
+    }
+
+    /// Context at the symbol's current mid price
+    pub async fn capture(&self, symbol: &str) -> Result<MarketContext> {
+        let quote = self.trading_platform.get_market_data(symbol).await?;
+        Ok(self.build(symbol, quote.mid(), quote.ask - quote.bid, self.clock.now()))
+    }
+
+    /// Context at `price`, e.g. a fill. The spread is zero if the symbol's quote
+    /// cannot be read.
+    pub async fn capture_at(&self, symbol: &str, price: Decimal) -> MarketContext {
+        let spread = match self.trading_platform.get_market_data(symbol).await {
+            Ok(quote) => quote.ask - quote.bid,
+            Err(e) => {
+                debug!("No quote for {} market context: {}", symbol, e);
+                Decimal::ZERO
+            }
+        };
+        self.build(symbol, price, spread, self.clock.now())
+    }
+
+    fn build(
+        &self,
+        symbol: &str,
+        price: Decimal,
+        spread: Decimal,
+        now: DateTime<Utc>,
+    ) -> MarketContext {
+        let timeframe = self.config.timeframe;
+        let atr = self
+            .candles
+            .as_ref()
+            .and_then(|candles| candles.atr(symbol, timeframe, self.config.atr_period))
+            .unwrap_or(Decimal::ZERO);
+        let recent = self
+            .candles
+            .as_ref()
+            .map(|candles| candles.candles(symbol, timeframe, self.config.range_candles))
+            .unwrap_or_default();
+
+        let recent_range = recent
+            .iter()
+            .map(|c| c.high)
+            .max()
+            .zip(recent.iter().map(|c| c.low).min())
+            .map(|(high, low)| high - low);
+
+        // Net move over the path travelled: 1 for a straight line, near 0 for chop
+        let path: Decimal = recent
+            .windows(2)
+            .map(|pair| (pair[1].close - pair[0].close).abs())
+            .sum();
+        let trend_strength = match (recent.first(), recent.last()) {
+            (Some(first), Some(last)) if !path.is_zero() => {
+                ratio_of((last.close - first.close).abs(), path)
+            }
+            _ => 0.0,
+        };
+
+        MarketContext {
+            current_price: price,
+            atr_14: atr,
+            trend_strength,
+            volatility: ratio_of(atr, price),
+            spread,
+            timestamp: now,
+            structure: self
+                .structure
+                .as_ref()
+                .and_then(|s| s.analyze(symbol, price)),
+            session: Some(MarketSession::at(now)),
+            recent_range,
+            news: self.nearest_news(symbol, now),
+        }
+    }
+
+    fn nearest_news(&self, symbol: &str, now: DateTime<Utc>) -> Option<NewsProximity> {
+        let window = Duration::minutes(self.config.news_window_minutes);
+        self.news_events
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|event| symbol.contains(&event.currency))
+            .filter(|event| (event.time - now).abs() <= window)
+            .min_by_key(|event| (event.time - now).abs())
+            .map(|event| NewsProximity {
+                event: event.clone(),
+                minutes_until: (event.time - now).num_minutes(),
+            })
+    }
+}
@@ -1,9 +1,17 @@
+pub mod adoption;
 pub mod break_even;
+pub mod excursions;
 pub mod exit_logger;
 pub mod integration;
+pub mod market_context;
 pub mod news_protection;
 pub mod partial_profits;
 pub mod platform_adapter;
+pub mod policy;
+pub mod scale_in;
+pub mod shadow;
+pub mod state_store;
+pub mod stop_guardian;
 pub mod time_exits;
 pub mod trailing_stops;
 pub mod types;
@@ -11,19 +19,50 @@ pub mod types;
 #[cfg(test)]
 pub mod tests;
 
+pub use adoption::{AdoptedPosition, AdoptionConfig, PositionAdopter, EXTERNAL_ORIGIN_TAG};
 pub use break_even::BreakEvenManager;
+pub use excursions::ExcursionTracker;
 pub use exit_logger::ExitAuditLogger;
 pub use integration::{ExitManagementComponents, ExitManagementIntegration};
+pub use market_context::{
+    MarketContextConfig, MarketContextProvider, MarketSession, NewsProximity,
+};
 pub use news_protection::NewsEventProtection;
-pub use partial_profits::PartialProfitManager;
+pub use partial_profits::{PartialCloseVerification, PartialProfitManager};
 pub use platform_adapter::{ExitManagementPlatformAdapter, PlatformAdapterFactory};
+pub use policy::{resolve_config, ExitPolicies, ExitPolicy, PendingExitPolicy};
+pub use scale_in::{
+    PositionEntry, ScaleIn, ScaleInManager, ScaleInPolicy, ScaleInStopPolicy, ScaleInTargetPolicy,
+    ScaledPosition,
+};
+pub use shadow::{LiveExitConfigs, ShadowExitEvaluator, ShadowVariant};
+pub use state_store::{ExitStateStore, FileExitStateStore, PositionExitCheckpoint};
+pub use stop_guardian::{
+    StopCheckReport, StopGuardianConfig, StopLossGuardian, NAKED_POSITION_ALERT_TYPE,
+};
 pub use time_exits::TimeBasedExitManager;
 pub use trailing_stops::TrailingStopManager;
 pub use types::*;
 
 use async_trait::async_trait;
+use dashmap::DashMap;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::time::{interval, Duration};
+use tracing::{Instrument, Span};
+
+use crate::execution::order_tracker::OrderTracker;
+use crate::instruments::InstrumentMetadataService;
+use crate::market_analysis::StructureAnalyzer;
+use crate::market_data::CandleBuilder;
+use crate::platforms::abstraction::capabilities::QuantityLimits;
+use crate::platforms::abstraction::clock::ServerClock;
+use crate::recording::EventRecorder;
+use crate::risk::trading_day::TradingDayRollover;
+use crate::runtime::feature_flags::{AccountFeatures, Feature};
+use crate::runtime::spawn::supervised_spawn;
+use crate::runtime::supervisor::RestartPolicy;
 // Simple trading platform trait for exit management
 #[async_trait::async_trait]
 pub trait TradingPlatform: Send + Sync + std::fmt::Debug {
@@ -41,6 +80,30 @@ pub trait TradingPlatform: Send + Sync + std::fmt::Debug {
         &self,
         request: types::PartialCloseRequest,
     ) -> Result<types::ClosePositionResult>;
+
+    /// Whether the platform can trail a stop on `symbol` itself
+    fn supports_native_trailing(&self, _symbol: &str) -> bool {
+        false
+    }
+
+    /// Sizes the platform accepts on `symbol`; any size unless overridden
+    fn quantity_limits(&self, _symbol: &str) -> QuantityLimits {
+        QuantityLimits::default()
+    }
+
+    async fn place_native_trailing_stop(
+        &self,
+        request: types::NativeTrailingStopRequest,
+    ) -> Result<types::OrderModifyResult> {
+        anyhow::bail!(
+            "Platform does not trail stops natively for {}",
+            request.symbol
+        )
+    }
+
+    async fn cancel_native_trailing_stop(&self, _order_id: &str) -> Result<()> {
+        Ok(())
+    }
 }
 use anyhow::Result;
 use chrono::{DateTime, Utc};
@@ -48,12 +111,24 @@ use uuid::Uuid;
 
 #[derive(Debug, Clone)]
 pub struct ExitManagementSystem {
+    trading_platform: Arc<dyn TradingPlatform>,
     trailing_stop_manager: Arc<TrailingStopManager>,
     break_even_manager: Arc<BreakEvenManager>,
     partial_profit_manager: Arc<PartialProfitManager>,
     time_exit_manager: Arc<TimeBasedExitManager>,
     news_protection: Arc<NewsEventProtection>,
+    excursion_tracker: Arc<ExcursionTracker>,
+    scale_in_manager: Arc<ScaleInManager>,
+    exit_policies: Arc<ExitPolicies>,
     exit_logger: Arc<ExitAuditLogger>,
+    instruments: Arc<InstrumentMetadataService>,
+    market_context: Arc<MarketContextProvider>,
+    shadow_evaluator: Option<Arc<ShadowExitEvaluator>>,
+    state_store: Option<Arc<dyn ExitStateStore>>,
+    checkpoints: Arc<DashMap<PositionId, PositionExitCheckpoint>>,
+    state_restored: Arc<AtomicBool>,
+    features: Option<AccountFeatures>,
+    span: Span,
     enabled: bool,
 }
 
@@ -62,114 +137,655 @@ impl ExitManagementSystem {
         trading_platform: Arc<dyn TradingPlatform>,
         exit_logger: Arc<ExitAuditLogger>,
     ) -> Self {
-        let trailing_stop_manager = Arc::new(TrailingStopManager::new(
-            trading_platform.clone(),
-            exit_logger.clone(),
-        ));
-
-        let break_even_manager = Arc::new(BreakEvenManager::new(
+        let excursion_tracker = Arc::new(ExcursionTracker::new(
             trading_platform.clone(),
             exit_logger.clone(),
         ));
-
-        let partial_profit_manager = Arc::new(PartialProfitManager::new(
-            trading_platform.clone(),
-            exit_logger.clone(),
-        ));
-
-        let time_exit_manager = Arc::new(TimeBasedExitManager::new(
-            trading_platform.clone(),
-            exit_logger.clone(),
-        ));
-
-        let news_protection = Arc::new(NewsEventProtection::new(
+        let scale_in_manager = Arc::new(ScaleInManager::new(
             trading_platform.clone(),
             exit_logger.clone(),
         ));
+        let exit_policies = Arc::new(ExitPolicies::new());
+        let instruments = Arc::new(InstrumentMetadataService::new());
+        let market_context = Arc::new(MarketContextProvider::new(trading_platform.clone()));
+
+        let trailing_stop_manager = Arc::new(
+            TrailingStopManager::new(trading_platform.clone(), exit_logger.clone())
+                .with_excursion_tracker(excursion_tracker.clone())
+                .with_exit_policies(exit_policies.clone())
+                .with_instruments(instruments.clone())
+                .with_market_context(market_context.clone()),
+        );
+
+        let break_even_manager = Arc::new(
+            BreakEvenManager::new(trading_platform.clone(), exit_logger.clone())
+                .with_exit_policies(exit_policies.clone())
+                .with_instruments(instruments.clone())
+                .with_market_context(market_context.clone()),
+        );
+
+        let partial_profit_manager = Arc::new(
+            PartialProfitManager::new(trading_platform.clone(), exit_logger.clone())
+                .with_exit_policies(exit_policies.clone())
+                .with_market_context(market_context.clone()),
+        );
+
+        let time_exit_manager = Arc::new(
+            TimeBasedExitManager::new(trading_platform.clone(), exit_logger.clone())
+                .with_exit_policies(exit_policies.clone())
+                .with_market_context(market_context.clone()),
+        );
+
+        let news_protection = Arc::new(
+            NewsEventProtection::new(trading_platform.clone(), exit_logger.clone())
+                .with_market_context(market_context.clone()),
+        );
 
         Self {
+            trading_platform,
             trailing_stop_manager,
             break_even_manager,
             partial_profit_manager,
             time_exit_manager,
             news_protection,
+            excursion_tracker,
+            scale_in_manager,
+            exit_policies,
             exit_logger,
+            instruments,
+            market_context,
+            shadow_evaluator: None,
+            state_store: None,
+            checkpoints: Arc::new(DashMap::new()),
+            state_restored: Arc::new(AtomicBool::new(true)),
+            features: None,
+            span: Span::none(),
             enabled: true,
         }
     }
 
-    /// Create ExitManagementSystem from pre-existing components
+    /// Create ExitManagementSystem from pre-existing components. The managers should
+    /// share `exit_policies` for position policies to take effect.
     pub fn from_components(
+        trading_platform: Arc<dyn TradingPlatform>,
         trailing_stop_manager: Arc<TrailingStopManager>,
         break_even_manager: Arc<BreakEvenManager>,
         partial_profit_manager: Arc<PartialProfitManager>,
         time_exit_manager: Arc<TimeBasedExitManager>,
         news_protection: Arc<NewsEventProtection>,
+        excursion_tracker: Arc<ExcursionTracker>,
+        exit_policies: Arc<ExitPolicies>,
         exit_logger: Arc<ExitAuditLogger>,
     ) -> Self {
         Self {
+            market_context: Arc::new(MarketContextProvider::new(trading_platform.clone())),
+            scale_in_manager: Arc::new(ScaleInManager::new(
+                trading_platform.clone(),
+                exit_logger.clone(),
+            )),
+            trading_platform,
             trailing_stop_manager,
             break_even_manager,
             partial_profit_manager,
             time_exit_manager,
             news_protection,
+            excursion_tracker,
+            exit_policies,
             exit_logger,
+            instruments: Arc::new(InstrumentMetadataService::new()),
+            shadow_evaluator: None,
+            state_store: None,
+            checkpoints: Arc::new(DashMap::new()),
+            state_restored: Arc::new(AtomicBool::new(true)),
+            features: None,
+            span: Span::none(),
             enabled: true,
         }
     }
 
+    /// Checkpoint manager state after every check so a restart can resume it.
+    /// Checks do not run until `restore_state` has succeeded.
+    pub fn with_state_store(mut self, state_store: Arc<dyn ExitStateStore>) -> Self {
+        self.state_store = Some(state_store);
+        self.state_restored = Arc::new(AtomicBool::new(false));
+        self
+    }
+
+    /// Skip the managers an operator has switched off for the account in `features`
+    pub fn with_feature_flags(mut self, features: AccountFeatures) -> Self {
+        self.features = Some(features);
+        self
+    }
+
+    /// Record the calendar events news protection fetches, for replaying the day
+    pub fn with_recorder(mut self, recorder: Arc<EventRecorder>) -> Self {
+        self.news_protection = Arc::new(
+            self.news_protection
+                .as_ref()
+                .clone()
+                .with_recorder(recorder),
+        );
+        self
+    }
+
+    /// Time market sessions, news windows and the weekend close on `clock`, the
+    /// account's measure of its platform server's time. Set it before
+    /// `with_shadow_variants` for the variants to follow it too.
+    pub fn with_server_clock(mut self, clock: ServerClock) -> Self {
+        self.time_exit_manager = Arc::new(
+            self.time_exit_manager
+                .as_ref()
+                .clone()
+                .with_server_clock(clock.clone()),
+        );
+        self.news_protection = Arc::new(
+            self.news_protection
+                .as_ref()
+                .clone()
+                .with_server_clock(clock.clone()),
+        );
+        let market_context = self
+            .market_context
+            .as_ref()
+            .clone()
+            .with_server_clock(clock);
+        self.share_market_context(market_context)
+    }
+
+    /// Run checks inside `span`, e.g. the account's `LogContext`, so their logs carry it
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Time the weekend close from the account's trading day rollover. Set it before
+    /// `with_shadow_variants` for the variants to follow it too.
+    pub fn with_trading_day(mut self, trading_day: TradingDayRollover) -> Self {
+        self.time_exit_manager = Arc::new(
+            self.time_exit_manager
+                .as_ref()
+                .clone()
+                .with_trading_day(trading_day),
+        );
+        self
+    }
+
+    /// Measure trailing stop ATR and the audit market context on `candles`. Set it
+    /// before `with_shadow_variants` for the variants to use it too.
+    pub fn with_candles(mut self, candles: Arc<CandleBuilder>) -> Self {
+        self.trailing_stop_manager = Arc::new(
+            self.trailing_stop_manager
+                .as_ref()
+                .clone()
+                .with_candles(candles.clone()),
+        );
+        let market_context = self.market_context.as_ref().clone().with_candles(candles);
+        self.share_market_context(market_context)
+    }
+
+    /// Read support and resistance from `structure` for the break-even trigger,
+    /// take-profit placement and the audit market context
+    pub fn with_structure(mut self, structure: Arc<StructureAnalyzer>) -> Self {
+        self.break_even_manager = Arc::new(
+            self.break_even_manager
+                .as_ref()
+                .clone()
+                .with_structure(structure.clone()),
+        );
+        self.partial_profit_manager = Arc::new(
+            self.partial_profit_manager
+                .as_ref()
+                .clone()
+                .with_structure(structure.clone()),
+        );
+        let market_context = self
+            .market_context
+            .as_ref()
+            .clone()
+            .with_structure(structure);
+        self.share_market_context(market_context)
+    }
+
+    /// Capture the market context of audit entries per `config`
+    pub fn with_market_context(self, config: MarketContextConfig) -> Self {
+        let market_context = self.market_context.as_ref().clone().with_config(config);
+        self.share_market_context(market_context)
+    }
+
+    fn share_market_context(mut self, market_context: MarketContextProvider) -> Self {
+        let market_context = Arc::new(market_context);
+        self.trailing_stop_manager = Arc::new(
+            self.trailing_stop_manager
+                .as_ref()
+                .clone()
+                .with_market_context(market_context.clone()),
+        );
+        self.break_even_manager = Arc::new(
+            self.break_even_manager
+                .as_ref()
+                .clone()
+                .with_market_context(market_context.clone()),
+        );
+        self.partial_profit_manager = Arc::new(
+            self.partial_profit_manager
+                .as_ref()
+                .clone()
+                .with_market_context(market_context.clone()),
+        );
+        self.time_exit_manager = Arc::new(
+            self.time_exit_manager
+                .as_ref()
+                .clone()
+                .with_market_context(market_context.clone()),
+        );
+        self.news_protection = Arc::new(
+            self.news_protection
+                .as_ref()
+                .clone()
+                .with_market_context(market_context.clone()),
+        );
+        self.market_context = market_context;
+        self
+    }
+
+    /// Recompute stops and targets of positions added to per `policy`
+    pub fn with_scale_in_policy(mut self, policy: ScaleInPolicy) -> Self {
+        self.scale_in_manager =
+            Arc::new(self.scale_in_manager.as_ref().clone().with_policy(policy));
+        self
+    }
+
+    /// Attribute additions to the engine orders `tracker` records for `account_id`
+    pub fn with_order_tracker(mut self, tracker: Arc<OrderTracker>, account_id: &str) -> Self {
+        self.scale_in_manager = Arc::new(
+            self.scale_in_manager
+                .as_ref()
+                .clone()
+                .with_order_tracker(tracker, account_id),
+        );
+        self
+    }
+
+    /// Simulate `variants` next to the live managers and report how each would have done.
+    /// Variants fall back to the live configuration for the sections they leave unset.
+    pub fn with_shadow_variants(mut self, variants: Vec<ShadowVariant>) -> Self {
+        let live_configs = LiveExitConfigs {
+            trailing: self.trailing_stop_manager.clone(),
+            break_even: self.break_even_manager.clone(),
+            partial_profit: self.partial_profit_manager.clone(),
+            time_exit: self.time_exit_manager.clone(),
+        };
+        self.shadow_evaluator = (!variants.is_empty()).then(|| {
+            Arc::new(
+                ShadowExitEvaluator::new(
+                    self.trading_platform.clone(),
+                    self.exit_logger.clone(),
+                    variants,
+                )
+                .with_live_configs(live_configs)
+                .with_instruments(self.instruments.clone()),
+            )
+        });
+        self
+    }
+
+    /// Acting on open positions without their saved state would repeat exits already taken
+    async fn ensure_state_restored(&self) -> bool {
+        if self.state_restored.load(Ordering::Acquire) {
+            return true;
+        }
+        match self.restore_state().await {
+            Ok(_) => true,
+            Err(e) => {
+                tracing::error!("Exit checks paused, state restore failed: {}", e);
+                false
+            }
+        }
+    }
+
     pub async fn start_exit_monitoring(&self) -> Result<()> {
         if !self.enabled {
             return Ok(());
         }
 
-        let trailing_manager = self.trailing_stop_manager.clone();
-        let break_even_manager = self.break_even_manager.clone();
-        let partial_manager = self.partial_profit_manager.clone();
-        let time_manager = self.time_exit_manager.clone();
-        let news_manager = self.news_protection.clone();
+        let position_checks = self.clone();
+        supervised_spawn(
+            "exit-position-checks",
+            RestartPolicy::default(),
+            move || {
+                let position_checks = position_checks.clone();
+                async move {
+                    let mut interval = interval(Duration::from_millis(500)); // Check every 500ms
+
+                    loop {
+                        interval.tick().await;
+                        position_checks.run_position_checks().await;
+                    }
+                }
+            },
+        );
+
+        let schedule_checks = self.clone();
+        supervised_spawn(
+            "exit-schedule-checks",
+            RestartPolicy::default(),
+            move || {
+                let schedule_checks = schedule_checks.clone();
+                async move {
+                    let mut interval = interval(Duration::from_secs(30)); // Check every 30 seconds
+
+                    loop {
+                        interval.tick().await;
+                        schedule_checks.run_schedule_checks().await;
+                    }
+                }
+            },
+        );
 
-        tokio::spawn(async move {
-            let mut interval = interval(Duration::from_millis(500)); // Check every 500ms
+        tracing::info!("Exit management system monitoring started");
+        Ok(())
+    }
 
-            loop {
-                interval.tick().await;
+    /// Price-driven checks: scale-ins first so every manager sees the new average,
+    /// excursions next so strategies see the latest MAE/MFE, then trailing stops, break-even, partial profit targets and shadow variants
+    pub async fn run_position_checks(&self) {
+        self.position_checks().instrument(self.span.clone()).await
+    }
 
-                if let Err(e) = trailing_manager.update_trailing_stops().await {
-                    tracing::error!("Error updating trailing stops: {}", e);
-                }
+    async fn position_checks(&self) {
+        if !self.ensure_state_restored().await {
+            return;
+        }
 
-                if let Err(e) = break_even_manager.check_break_even_triggers().await {
-                    tracing::error!("Error checking break-even triggers: {}", e);
-                }
+        if let Err(e) = self.attach_pending_policies().await {
+            tracing::error!("Error attaching pending exit policies: {}", e);
+        }
 
-                if let Err(e) = partial_manager.check_profit_targets().await {
-                    tracing::error!("Error checking profit targets: {}", e);
+        match self.scale_in_manager.check_scale_ins().await {
+            Ok(scale_ins) => {
+                for scale_in in scale_ins {
+                    self.partial_profit_manager
+                        .add_volume(scale_in.position_id, scale_in.entry.quantity);
                 }
             }
-        });
+            Err(e) => tracing::error!("Error checking positions for scale-ins: {}", e),
+        }
+
+        if let Err(e) = self.excursion_tracker.update_excursions().await {
+            tracing::error!("Error updating position excursions: {}", e);
+        }
+
+        if self.feature_enabled(Feature::TrailingStops) {
+            if let Err(e) = self.trailing_stop_manager.update_trailing_stops().await {
+                tracing::error!("Error updating trailing stops: {}", e);
+            }
+        }
+
+        if self.feature_enabled(Feature::BreakEven) {
+            if let Err(e) = self.break_even_manager.check_break_even_triggers().await {
+                tracing::error!("Error checking break-even triggers: {}", e);
+            }
+        }
+
+        if self.feature_enabled(Feature::PartialProfits) {
+            if let Err(e) = self.partial_profit_manager.check_profit_targets().await {
+                tracing::error!("Error checking profit targets: {}", e);
+            }
+        }
+
+        if let Some(shadow) = &self.shadow_evaluator {
+            if let Err(e) = shadow.evaluate().await {
+                tracing::error!("Error evaluating shadow exit variants: {}", e);
+            }
+        }
+
+        self.checkpoint_state().await;
+    }
+
+    /// Clock-driven checks: time-based exits and news protection
+    pub async fn run_schedule_checks(&self) {
+        self.schedule_checks().instrument(self.span.clone()).await
+    }
 
-        tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(30)); // Check every 30 seconds
+    async fn schedule_checks(&self) {
+        if !self.ensure_state_restored().await {
+            return;
+        }
+
+        if self.feature_enabled(Feature::TimeExits) {
+            if let Err(e) = self.time_exit_manager.check_time_based_exits().await {
+                tracing::error!("Error checking time-based exits: {}", e);
+            }
+        }
+
+        if self.feature_enabled(Feature::NewsProtection) {
+            if let Err(e) = self.news_protection.monitor_upcoming_news().await {
+                tracing::error!("Error monitoring news events: {}", e);
+            }
+        }
+
+        // Stops tightened before news are restored even with news protection off
+        if let Err(e) = self.news_protection.restore_post_news_stops().await {
+            tracing::error!("Error restoring post-news stops: {}", e);
+        }
+
+        if let Err(e) = self.prune_exit_policies().await {
+            tracing::error!("Error pruning exit policies: {}", e);
+        }
 
-            loop {
-                interval.tick().await;
+        self.checkpoint_state().await;
+    }
+
+    fn checkpoint_for(&self, position_id: PositionId) -> PositionExitCheckpoint {
+        PositionExitCheckpoint {
+            position_id,
+            trailing_stop: self.trailing_stop_manager.get_active_trail(position_id),
+            break_even_active: self.break_even_manager.is_break_even_active(position_id),
+            profit_targets: self
+                .partial_profit_manager
+                .get_position_target_status(position_id),
+            time_exit_warned: self.time_exit_manager.is_position_warned(position_id),
+            exit_policy: self.exit_policies.get(position_id),
+            saved_at: Utc::now(),
+        }
+    }
+
+    /// Positions any exit manager currently holds state for
+    fn tracked_positions(&self) -> HashSet<PositionId> {
+        let mut positions: HashSet<PositionId> = self
+            .trailing_stop_manager
+            .get_active_trails()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        positions.extend(self.break_even_manager.get_break_even_positions());
+        positions.extend(
+            self.partial_profit_manager
+                .get_position_target_statuses()
+                .into_iter()
+                .map(|s| s.position_id),
+        );
+        positions.extend(self.time_exit_manager.get_warned_positions());
+        positions.extend(self.exit_policies.positions());
+        positions
+    }
 
-                if let Err(e) = time_manager.check_time_based_exits().await {
-                    tracing::error!("Error checking time-based exits: {}", e);
+    /// Save the state of positions that changed since the last checkpoint
+    pub async fn checkpoint_state(&self) {
+        let Some(store) = &self.state_store else {
+            return;
+        };
+
+        let tracked = self.tracked_positions();
+        for position_id in &tracked {
+            let checkpoint = self.checkpoint_for(*position_id);
+            let changed = self
+                .checkpoints
+                .get(position_id)
+                .map_or(true, |saved| checkpoint.differs_from(&saved));
+            if !changed {
+                continue;
+            }
+
+            match store.save(&checkpoint).await {
+                Ok(()) => {
+                    self.checkpoints.insert(*position_id, checkpoint);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to checkpoint exit state of {}: {}", position_id, e)
                 }
+            }
+        }
 
-                if let Err(e) = news_manager.monitor_upcoming_news().await {
-                    tracing::error!("Error monitoring news events: {}", e);
+        let released: Vec<PositionId> = self
+            .checkpoints
+            .iter()
+            .map(|c| *c.key())
+            .filter(|id| !tracked.contains(id))
+            .collect();
+        for position_id in released {
+            match store.remove(position_id).await {
+                Ok(()) => {
+                    self.checkpoints.remove(&position_id);
                 }
+                Err(e) => tracing::error!(
+                    "Failed to remove exit state checkpoint of {}: {}",
+                    position_id,
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Reload checkpointed state for positions that are still open and discard the rest.
+    /// Returns the number of positions restored.
+    pub async fn restore_state(&self) -> Result<usize> {
+        let Some(store) = &self.state_store else {
+            return Ok(0);
+        };
+
+        // Restoring twice would overwrite state the managers have built since
+        if self.state_restored.load(Ordering::Acquire) {
+            return Ok(0);
+        }
 
-                if let Err(e) = news_manager.restore_post_news_stops().await {
-                    tracing::error!("Error restoring post-news stops: {}", e);
+        let checkpoints = store.load_all().await?;
+        let live: std::collections::HashMap<PositionId, types::Position> = self
+            .trading_platform
+            .get_positions()
+            .await?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+
+        let mut restored = 0;
+        for mut checkpoint in checkpoints {
+            let Some(position) = live.get(&checkpoint.position_id) else {
+                tracing::info!(
+                    "Discarding exit state of {}: position closed while offline",
+                    checkpoint.position_id
+                );
+                store.remove(checkpoint.position_id).await?;
+                continue;
+            };
+
+            // The platform is the source of truth for what is left of the position
+            if let Some(targets) = checkpoint.profit_targets.as_mut() {
+                if targets.remaining_volume != position.volume {
+                    tracing::warn!(
+                        "Position {} volume is {} on the platform but {} in exit state; using the platform",
+                        position.id,
+                        position.volume,
+                        targets.remaining_volume
+                    );
+                    targets.remaining_volume = position.volume;
                 }
             }
-        });
 
-        tracing::info!("Exit management system monitoring started");
+            if let Some(trail) = checkpoint.trailing_stop.clone() {
+                self.trailing_stop_manager.restore_trail(trail);
+            }
+            if checkpoint.break_even_active {
+                self.break_even_manager.restore_break_even(position.id);
+            }
+            if let Some(targets) = checkpoint.profit_targets.clone() {
+                self.partial_profit_manager
+                    .restore_position_target_status(targets);
+            }
+            if checkpoint.time_exit_warned {
+                self.time_exit_manager.restore_warning(position.id);
+            }
+            if let Some(policy) = checkpoint.exit_policy.clone() {
+                self.exit_policies.set(position.id, policy);
+            }
+
+            self.checkpoints.insert(checkpoint.position_id, checkpoint);
+            restored += 1;
+        }
+
+        self.state_restored.store(true, Ordering::Release);
+        tracing::info!("Restored exit state for {} open positions", restored);
+        Ok(restored)
+    }
+
+    /// Attach an exit policy that overrides the symbol configuration for one position
+    pub fn set_position_policy(&self, position_id: PositionId, policy: ExitPolicy) {
+        tracing::info!(
+            "Exit policy {} attached to position {}",
+            policy.name.as_deref().unwrap_or("unnamed"),
+            position_id
+        );
+        self.exit_policies.set(position_id, policy);
+    }
+
+    /// Attach `pending.policy` to the next position opened on its symbol
+    pub fn expect_position_policy(&self, pending: PendingExitPolicy) {
+        self.exit_policies.expect(pending);
+    }
+
+    /// Hand pending policies to the positions their signals opened
+    async fn attach_pending_policies(&self) -> Result<()> {
+        if !self.exit_policies.has_pending() {
+            return Ok(());
+        }
+
+        let positions = self.trading_platform.get_positions().await?;
+        for (position_id, pending) in self.exit_policies.claim(&positions) {
+            tracing::info!(
+                "Exit policy from signal {} attached to position {}",
+                pending.signal_id,
+                position_id
+            );
+        }
+        Ok(())
+    }
+
+    /// Return a position to its symbol configuration
+    pub fn clear_position_policy(&self, position_id: PositionId) -> Option<ExitPolicy> {
+        self.exit_policies.remove(position_id)
+    }
+
+    pub fn get_position_policy(&self, position_id: PositionId) -> Option<ExitPolicy> {
+        self.exit_policies.get(position_id)
+    }
+
+    /// Drop policies of positions that are no longer open
+    async fn prune_exit_policies(&self) -> Result<()> {
+        let attached = self.exit_policies.positions();
+        if attached.is_empty() {
+            return Ok(());
+        }
+
+        let open: HashSet<PositionId> = self
+            .trading_platform
+            .get_positions()
+            .await?
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        for position_id in attached.into_iter().filter(|id| !open.contains(id)) {
+            self.exit_policies.remove(position_id);
+        }
         Ok(())
     }
 
@@ -181,6 +797,13 @@ impl ExitManagementSystem {
         self.enabled = false;
     }
 
+    /// Whether `feature` is switched on for this system's account
+    pub fn feature_enabled(&self, feature: Feature) -> bool {
+        self.features
+            .as_ref()
+            .map_or(true, |features| features.is_enabled(feature))
+    }
+
     pub fn is_enabled(&self) -> bool {
         self.enabled
     }
@@ -198,6 +821,81 @@ impl ExitManagementSystem {
         Ok(results)
     }
 
+    pub fn position_exit_state(&self, position_id: PositionId) -> PositionExitState {
+        let targets = self
+            .partial_profit_manager
+            .get_position_target_status(position_id);
+
+        PositionExitState {
+            trailing_stop: self.trailing_stop_manager.get_active_trail(position_id),
+            break_even_active: self.break_even_manager.is_break_even_active(position_id),
+            profit_targets_hit: targets
+                .as_ref()
+                .map(|t| t.targets_hit.clone())
+                .unwrap_or_default(),
+            remaining_volume: targets.map(|t| t.remaining_volume),
+            time_exit_warned: self.time_exit_manager.is_position_warned(position_id),
+            excursion: self.excursion_tracker.get_excursion(position_id),
+        }
+    }
+
+    /// What each manager would do to the position if the checks ran now, for
+    /// the features enabled on the account. Reads the platform but changes
+    /// nothing on it. None when the position is not open.
+    pub async fn preview_position(&self, position_id: PositionId) -> Result<Option<ExitPreview>> {
+        let Some(position) = self
+            .trading_platform
+            .get_positions()
+            .await?
+            .into_iter()
+            .find(|p| p.id == position_id)
+        else {
+            return Ok(None);
+        };
+        let current_price = self
+            .trading_platform
+            .get_market_data(&position.symbol)
+            .await?
+            .mid();
+
+        let mut preview = ExitPreview {
+            position_id,
+            symbol: position.symbol.clone(),
+            volume: position.volume,
+            current_price,
+            stop_loss: position.stop_loss,
+            trailing_stop: None,
+            break_even: None,
+            partial_profits: Vec::new(),
+            time_exit: None,
+            news_protected: self.news_protection.has_position_protection(position_id),
+            evaluated_at: Utc::now(),
+        };
+        if self.feature_enabled(Feature::TrailingStops) {
+            preview.trailing_stop = self.trailing_stop_manager.preview_trail(&position).await?;
+        }
+        if self.feature_enabled(Feature::BreakEven) {
+            preview.break_even = self
+                .break_even_manager
+                .preview_break_even(&position)
+                .await?;
+        }
+        if self.feature_enabled(Feature::PartialProfits) {
+            preview.partial_profits = self
+                .partial_profit_manager
+                .preview_targets(&position)
+                .await?;
+        }
+        if self.feature_enabled(Feature::TimeExits) {
+            preview.time_exit = self.time_exit_manager.preview_time_exit(&position).await?;
+        }
+        Ok(Some(preview))
+    }
+
+    pub fn get_trading_platform(&self) -> Arc<dyn TradingPlatform> {
+        self.trading_platform.clone()
+    }
+
     pub fn get_trailing_stop_manager(&self) -> Arc<TrailingStopManager> {
         self.trailing_stop_manager.clone()
     }
@@ -209,4 +907,34 @@ impl ExitManagementSystem {
     pub fn get_partial_profit_manager(&self) -> Arc<PartialProfitManager> {
         self.partial_profit_manager.clone()
     }
+
+    pub fn get_scale_in_manager(&self) -> Arc<ScaleInManager> {
+        self.scale_in_manager.clone()
+    }
+
+    pub fn get_market_context_provider(&self) -> Arc<MarketContextProvider> {
+        self.market_context.clone()
+    }
+
+    pub fn get_excursion_tracker(&self) -> Arc<ExcursionTracker> {
+        self.excursion_tracker.clone()
+    }
+
+    pub fn get_exit_policies(&self) -> Arc<ExitPolicies> {
+        self.exit_policies.clone()
+    }
+
+    pub fn get_news_protection(&self) -> Arc<NewsEventProtection> {
+        self.news_protection.clone()
+    }
+
+    /// Pip sizes and price precision used by the trailing, break-even and shadow math.
+    /// Register platform symbols here so levels round to what the platform accepts.
+    pub fn get_instruments(&self) -> Arc<InstrumentMetadataService> {
+        self.instruments.clone()
+    }
+
+    pub fn get_shadow_evaluator(&self) -> Option<Arc<ShadowExitEvaluator>> {
+        self.shadow_evaluator.clone()
+    }
 }
@@ -2,13 +2,18 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn, Instrument};
 
 use super::exit_logger::ExitAuditLogger;
+use super::market_context::MarketContextProvider;
 use super::types::*;
 use super::TradingPlatform;
+use crate::platforms::abstraction::clock::ServerClock;
+use crate::recording::{EventRecorder, RecordedEvent};
+use crate::runtime::logging::LogContext;
 
 #[derive(Debug, Clone)]
 pub struct EconomicCalendarClient {
@@ -18,6 +23,16 @@ pub struct EconomicCalendarClient {
     base_url: String,
 }
 
+impl Default for EconomicCalendarClient {
+    // In a real implementation, these would come from configuration
+    fn default() -> Self {
+        Self::new(
+            "demo_api_key".to_string(),
+            "https://api.forexfactory.com".to_string(),
+        )
+    }
+}
+
 impl EconomicCalendarClient {
     pub fn new(api_key: String, base_url: String) -> Self {
         Self { api_key, base_url }
@@ -51,13 +66,17 @@ impl EconomicCalendarClient {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct NewsEventProtection {
     trading_platform: Arc<dyn TradingPlatform>,
     exit_logger: Arc<ExitAuditLogger>,
     economic_calendar: EconomicCalendarClient,
     news_configs: HashMap<String, NewsProtectionConfig>,
     protected_positions: Arc<DashMap<PositionId, NewsProtection>>,
+    market_context: Arc<MarketContextProvider>,
+    recorder: Option<Arc<EventRecorder>>,
+    /// Calendar times are compared with the platform server's clock
+    clock: ServerClock,
 }
 
 impl NewsEventProtection {
@@ -65,18 +84,17 @@ impl NewsEventProtection {
         trading_platform: Arc<dyn TradingPlatform>,
         exit_logger: Arc<ExitAuditLogger>,
     ) -> Self {
-        // In a real implementation, these would come from configuration
-        let economic_calendar = EconomicCalendarClient::new(
-            "demo_api_key".to_string(),
-            "https://api.forexfactory.com".to_string(),
-        );
+        let economic_calendar = EconomicCalendarClient::default();
 
         Self {
+            market_context: Arc::new(MarketContextProvider::new(trading_platform.clone())),
             trading_platform,
             exit_logger,
             economic_calendar,
             news_configs: HashMap::new(),
             protected_positions: Arc::new(DashMap::new()),
+            recorder: None,
+            clock: ServerClock::new(),
         }
     }
 
@@ -84,6 +102,24 @@ impl NewsEventProtection {
         self.news_configs.insert(currency, config);
     }
 
+    /// Record audit entries with market context captured by `market_context`
+    pub fn with_market_context(mut self, market_context: Arc<MarketContextProvider>) -> Self {
+        self.market_context = market_context;
+        self
+    }
+
+    /// Record the calendar events fetched, for replaying the day
+    pub fn with_recorder(mut self, recorder: Arc<EventRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Restore stops after news on `clock` instead of ours
+    pub fn with_server_clock(mut self, clock: ServerClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     pub async fn monitor_upcoming_news(&self) -> Result<()> {
         let lookback_duration =
             Duration::from_std(std::time::Duration::from_secs(4 * 3600)).unwrap();
@@ -91,6 +127,16 @@ impl NewsEventProtection {
             .economic_calendar
             .get_upcoming_events(lookback_duration, ImpactLevel::High)
             .await?;
+        self.market_context.set_news_events(upcoming_events.clone());
+        if let Some(recorder) = &self.recorder {
+            for event in &upcoming_events {
+                recorder
+                    .record(RecordedEvent::News {
+                        event: event.clone(),
+                    })
+                    .await;
+            }
+        }
 
         for event in upcoming_events {
             if let Err(e) = self.apply_news_protection(&event).await {
@@ -104,7 +150,8 @@ impl NewsEventProtection {
         Ok(())
     }
 
-    async fn apply_news_protection(&self, event: &NewsEvent) -> Result<()> {
+    /// Protect the open positions exposed to `event`'s currency
+    pub async fn apply_news_protection(&self, event: &NewsEvent) -> Result<()> {
         let affected_positions = self.get_positions_for_currency(&event.currency).await?;
         let default_config = NewsProtectionConfig::default();
         let config = self
@@ -124,24 +171,29 @@ impl NewsEventProtection {
         );
 
         for position in affected_positions {
-            // Check if already protected for this event
-            if self.is_position_protected(&position.id, &event.id) {
-                continue;
-            }
-
-            match config.protection_strategy {
-                NewsProtectionStrategy::TightenStops => {
-                    self.tighten_stops_for_news(&position, event, config)
-                        .await?;
-                }
-                NewsProtectionStrategy::ClosePosition => {
-                    self.close_position_for_news(&position, event).await?;
+            async {
+                // Check if already protected for this event
+                if self.is_position_protected(&position.id, &event.id) {
+                    return Ok(());
                 }
-                NewsProtectionStrategy::ReduceSize => {
-                    self.reduce_position_for_news(&position, event, config)
-                        .await?;
+
+                match config.protection_strategy {
+                    NewsProtectionStrategy::TightenStops => {
+                        self.tighten_stops_for_news(&position, event, config)
+                            .await?;
+                    }
+                    NewsProtectionStrategy::ClosePosition => {
+                        self.close_position_for_news(&position, event).await?;
+                    }
+                    NewsProtectionStrategy::ReduceSize => {
+                        self.reduce_position_for_news(&position, event, config)
+                            .await?;
+                    }
                 }
+                Ok::<_, anyhow::Error>(())
             }
+            .instrument(LogContext::position(position.id).span())
+            .await?;
         }
 
         Ok(())
@@ -162,7 +214,16 @@ impl NewsEventProtection {
             UnifiedPositionSide::Short => current_stop - entry_price,
         };
 
-        let reduced_risk = normal_risk * config.stop_tighten_factor;
+        // A stop already at or past entry carries no risk left to tighten
+        if normal_risk <= Decimal::ZERO {
+            debug!(
+                "Position {} already protected at {} ahead of {}",
+                position.id, current_stop, event.description
+            );
+            return Ok(());
+        }
+
+        let reduced_risk = scale_by(normal_risk, config.stop_tighten_factor);
         let new_stop = match position.position_type {
             UnifiedPositionSide::Long => entry_price - reduced_risk,
             UnifiedPositionSide::Short => entry_price + reduced_risk,
@@ -241,8 +302,7 @@ impl NewsEventProtection {
     ) -> Result<()> {
         // Reduce position size by 50%
         let reduction_percentage = 0.5;
-        let reduce_volume =
-            position.volume * Decimal::from_f64_retain(reduction_percentage).unwrap();
+        let reduce_volume = scale_by(position.volume, reduction_percentage);
 
         let close_request = PartialCloseRequest {
             position_id: position.id,
@@ -274,7 +334,7 @@ impl NewsEventProtection {
     }
 
     pub async fn restore_post_news_stops(&self) -> Result<()> {
-        let now = Utc::now();
+        let now = self.clock.now();
         let mut to_restore = Vec::new();
 
         // Find positions ready for stop restoration
@@ -335,7 +395,7 @@ impl NewsEventProtection {
         Ok(())
     }
 
-    async fn calculate_reasonable_stop_post_news(&self, position: &Position) -> Result<f64> {
+    async fn calculate_reasonable_stop_post_news(&self, position: &Position) -> Result<Decimal> {
         // This would use technical analysis to determine a reasonable stop level
         // For now, using a simple ATR-based calculation
 
@@ -343,10 +403,10 @@ impl NewsEventProtection {
             .trading_platform
             .get_market_data(&position.symbol)
             .await?;
-        let current_price = (market_data.bid + market_data.ask) / 2.0;
+        let current_price = market_data.mid();
 
         // Use 2x ATR for stop distance (simplified)
-        let atr_distance = market_data.spread * 4.0; // Simplified ATR calculation
+        let atr_distance = market_data.spread * dec!(4); // Simplified ATR calculation
 
         let reasonable_stop = match position.position_type {
             UnifiedPositionSide::Long => current_price - atr_distance,
@@ -384,23 +444,10 @@ impl NewsEventProtection {
         &self,
         position: &Position,
         event: &NewsEvent,
-        old_stop: f64,
-        new_stop: f64,
+        old_stop: Decimal,
+        new_stop: Decimal,
     ) -> Result<()> {
-        let current_price = (self
-            .trading_platform
-            .get_market_data(&position.symbol)
-            .await?)
-            .ask;
-
-        let market_context = MarketContext {
-            current_price,
-            atr_14: 0.0015,      // Simplified
-            trend_strength: 0.3, // Reduced during news protection
-            volatility: 0.05,    // Increased volatility expected
-            spread: 0.0002,      // Wider spreads during news
-            timestamp: Utc::now(),
-        };
+        let market_context = self.market_context.capture(&position.symbol).await?;
 
         let modification = ExitModification {
             position_id: position.id,
@@ -422,16 +469,12 @@ impl NewsEventProtection {
         &self,
         position: &Position,
         event: &NewsEvent,
-        close_price: f64,
+        close_price: Decimal,
     ) -> Result<()> {
-        let market_context = MarketContext {
-            current_price: close_price,
-            atr_14: 0.0015,
-            trend_strength: 0.0, // Position closed
-            volatility: 0.05,
-            spread: 0.0002,
-            timestamp: Utc::now(),
-        };
+        let market_context = self
+            .market_context
+            .capture_at(&position.symbol, close_price)
+            .await;
 
         let modification = ExitModification {
             position_id: position.id,
@@ -454,22 +497,18 @@ impl NewsEventProtection {
         position: &Position,
         event: &NewsEvent,
         reduced_volume: Decimal,
-        close_price: f64,
+        close_price: Decimal,
     ) -> Result<()> {
-        let market_context = MarketContext {
-            current_price: close_price,
-            atr_14: 0.0015,
-            trend_strength: 0.5,
-            volatility: 0.05,
-            spread: 0.0002,
-            timestamp: Utc::now(),
-        };
+        let market_context = self
+            .market_context
+            .capture_at(&position.symbol, close_price)
+            .await;
 
         let modification = ExitModification {
             position_id: position.id,
             modification_type: ExitModificationType::NewsProtection,
-            old_value: f64::try_from(position.volume).unwrap_or(0.0),
-            new_value: f64::try_from(reduced_volume).unwrap_or(0.0),
+            old_value: position.volume,
+            new_value: reduced_volume,
             reasoning: format!(
                 "News protection: Position size reduced by {:.4} lots for {} {} event",
                 reduced_volume, event.currency, event.description
@@ -485,22 +524,9 @@ impl NewsEventProtection {
         &self,
         position: &Position,
         protection: &NewsProtection,
-        new_stop: f64,
+        new_stop: Decimal,
     ) -> Result<()> {
-        let current_price = (self
-            .trading_platform
-            .get_market_data(&position.symbol)
-            .await?)
-            .ask;
-
-        let market_context = MarketContext {
-            current_price,
-            atr_14: 0.0015,
-            trend_strength: 0.5, // Normal conditions restored
-            volatility: 0.02,    // Normal volatility
-            spread: 0.0001,      // Normal spreads
-            timestamp: Utc::now(),
-        };
+        let market_context = self.market_context.capture(&position.symbol).await?;
 
         let modification = ExitModification {
             position_id: position.id,
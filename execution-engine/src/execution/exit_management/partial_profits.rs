@@ -2,29 +2,104 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{error, info, warn};
+use tracing::{error, info, warn, Instrument};
 
 use super::exit_logger::ExitAuditLogger;
+use super::market_context::MarketContextProvider;
+use super::policy::ExitPolicies;
 use super::types::*;
 use super::TradingPlatform;
+use crate::market_analysis::StructureAnalyzer;
+use crate::runtime::logging::LogContext;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PositionTargetStatus {
     pub position_id: PositionId,
     pub targets_hit: Vec<u32>, // Which target levels have been hit
     pub remaining_volume: Decimal,
     pub total_partial_profit: Decimal,
     pub last_target_hit: Option<DateTime<Utc>>,
+    /// Entry and stop the targets' R:R is measured from, fixed when tracking
+    /// starts. Platforms that re-average a position on a partial close would
+    /// otherwise move every later target.
+    #[serde(default)]
+    pub entry_price: Option<Decimal>,
+    #[serde(default)]
+    pub initial_stop: Option<Decimal>,
+    #[serde(default)]
+    pub last_verification: Option<PartialCloseVerification>,
 }
 
-#[derive(Debug)]
+/// The position as the platform showed it after a partial close, against what
+/// the close should have left
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartialCloseVerification {
+    pub position_id: PositionId,
+    pub expected_volume: Decimal,
+    /// None when the position is no longer on the platform
+    pub platform_volume: Option<Decimal>,
+    /// Platform average before the close
+    pub expected_entry: Decimal,
+    pub platform_entry: Option<Decimal>,
+    pub expected_stop: Option<Decimal>,
+    pub platform_stop: Option<Decimal>,
+    /// Whether the stop was put back where it was before the close
+    pub stop_restored: bool,
+    pub verified_at: DateTime<Utc>,
+}
+
+impl PartialCloseVerification {
+    /// Each way the platform differs from what was expected
+    pub fn discrepancies(&self) -> Vec<String> {
+        let mut found = Vec::new();
+        let Some(volume) = self.platform_volume else {
+            if self.expected_volume > Decimal::ZERO {
+                found.push(format!(
+                    "position gone, {} expected to remain",
+                    self.expected_volume
+                ));
+            }
+            return found;
+        };
+        if volume != self.expected_volume {
+            found.push(format!(
+                "volume {} where {} was expected",
+                volume, self.expected_volume
+            ));
+        }
+        if let Some(entry) = self.platform_entry.filter(|e| *e != self.expected_entry) {
+            found.push(format!(
+                "entry re-averaged from {} to {}",
+                self.expected_entry, entry
+            ));
+        }
+        if self.platform_stop != self.expected_stop {
+            found.push(format!(
+                "stop {:?} where {:?} was expected",
+                self.platform_stop, self.expected_stop
+            ));
+        }
+        found
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies().is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct PartialProfitManager {
     trading_platform: Arc<dyn TradingPlatform>,
     exit_logger: Arc<ExitAuditLogger>,
     profit_configs: HashMap<String, ProfitTakingConfig>,
     position_targets: Arc<DashMap<PositionId, PositionTargetStatus>>,
+    exit_policies: Option<Arc<ExitPolicies>>,
+    structure: Option<Arc<StructureAnalyzer>>,
+    market_context: Arc<MarketContextProvider>,
 }
 
 impl PartialProfitManager {
@@ -33,51 +108,187 @@ impl PartialProfitManager {
         exit_logger: Arc<ExitAuditLogger>,
     ) -> Self {
         Self {
+            market_context: Arc::new(MarketContextProvider::new(trading_platform.clone())),
             trading_platform,
             exit_logger,
             profit_configs: HashMap::new(),
             position_targets: Arc::new(DashMap::new()),
+            exit_policies: None,
+            structure: None,
         }
     }
 
+    pub fn with_exit_policies(mut self, exit_policies: Arc<ExitPolicies>) -> Self {
+        self.exit_policies = Some(exit_policies);
+        self
+    }
+
+    /// Place take-profits at support and resistance read from `structure`, for
+    /// configs with `take_profit_at_structure`
+    pub fn with_structure(mut self, structure: Arc<StructureAnalyzer>) -> Self {
+        self.structure = Some(structure);
+        self
+    }
+
+    /// Record audit entries with market context captured by `market_context`
+    pub fn with_market_context(mut self, market_context: Arc<MarketContextProvider>) -> Self {
+        self.market_context = market_context;
+        self
+    }
+
+    /// Profit taking only applies to positions with a policy or a configured symbol
+    pub fn config_for(&self, position: &Position) -> Option<ProfitTakingConfig> {
+        self.exit_policies
+            .as_ref()
+            .and_then(|p| p.profit_taking(position.id))
+            .or_else(|| self.profit_configs.get(&position.symbol).cloned())
+    }
+
     pub fn configure_symbol(&mut self, symbol: String, config: ProfitTakingConfig) {
         self.profit_configs.insert(symbol, config);
     }
 
     pub async fn check_profit_targets(&self) -> Result<()> {
+        if self.structure.is_some() {
+            self.check_structure_take_profits().await?;
+        }
+
         let positions_with_targets = self.get_positions_with_remaining_targets().await?;
 
         for position in positions_with_targets {
-            let targets_hit = match self.evaluate_profit_targets(&position).await {
-                Ok(targets) => targets,
-                Err(e) => {
-                    error!(
-                        "Failed to evaluate profit targets for position {}: {}",
-                        position.id, e
-                    );
-                    continue;
+            async {
+                let targets_hit = match self.evaluate_profit_targets(&position).await {
+                    Ok(targets) => targets,
+                    Err(e) => {
+                        error!(
+                            "Failed to evaluate profit targets for position {}: {}",
+                            position.id, e
+                        );
+                        return;
+                    }
+                };
+
+                for target in targets_hit {
+                    if let Err(e) = self.execute_partial_close(&position, &target).await {
+                        error!(
+                            "Failed to execute partial close for position {}: {}",
+                            position.id, e
+                        );
+                    }
                 }
-            };
+            }
+            .instrument(LogContext::position(position.id).span())
+            .await;
+        }
 
-            for target in targets_hit {
-                if let Err(e) = self.execute_partial_close(&position, &target).await {
+        Ok(())
+    }
+
+    async fn check_structure_take_profits(&self) -> Result<()> {
+        let positions = self.trading_platform.get_positions().await?;
+
+        for position in positions {
+            let wanted = self
+                .config_for(&position)
+                .is_some_and(|config| config.enabled && config.take_profit_at_structure);
+            if !wanted {
+                continue;
+            }
+            async {
+                if let Err(e) = self.place_structure_take_profit(&position).await {
                     error!(
-                        "Failed to execute partial close for position {}: {}",
+                        "Failed to place structure take-profit for position {}: {}",
                         position.id, e
                     );
                 }
             }
+            .instrument(LogContext::position(position.id).span())
+            .await;
+        }
+
+        Ok(())
+    }
+
+    /// Move the take-profit to the near edge of the closest opposing zone, when that
+    /// is still ahead of the price and closer than the current take-profit
+    async fn place_structure_take_profit(&self, position: &Position) -> Result<()> {
+        let Some(analyzer) = &self.structure else {
+            return Ok(());
+        };
+        let current_price = self.get_current_price(&position.symbol).await?;
+        let Some(structure) = analyzer.analyze(&position.symbol, current_price) else {
+            return Ok(());
+        };
+
+        let target = match position.position_type {
+            UnifiedPositionSide::Long => structure
+                .resistance
+                .as_ref()
+                .map(|zone| zone.lower)
+                .filter(|level| position.take_profit.map_or(true, |tp| *level < tp)),
+            UnifiedPositionSide::Short => structure
+                .support
+                .as_ref()
+                .map(|zone| zone.upper)
+                .filter(|level| position.take_profit.map_or(true, |tp| *level > tp)),
+        };
+        let Some(target) = target else {
+            return Ok(());
+        };
+
+        let result = self
+            .trading_platform
+            .modify_order(OrderModifyRequest {
+                order_id: position.order_id.clone(),
+                new_stop_loss: position.stop_loss,
+                new_take_profit: Some(target),
+            })
+            .await
+            .context("Failed to modify order for structure take-profit")?;
+        if !result.success {
+            return Err(anyhow::anyhow!(
+                "Structure take-profit rejected for position {}: {}",
+                position.id,
+                result.message
+            ));
         }
 
+        info!(
+            "Take-profit for position {} moved to {} ahead of the nearest zone",
+            position.id, target
+        );
+
+        let mut market_context = self
+            .market_context
+            .capture_at(&position.symbol, current_price)
+            .await;
+        market_context.structure = Some(structure);
+
+        let modification = ExitModification {
+            position_id: position.id,
+            modification_type: ExitModificationType::PartialProfit,
+            old_value: position.take_profit.unwrap_or_default(),
+            new_value: target,
+            reasoning: format!(
+                "Take-profit moved to {} at the nearest {} zone",
+                target,
+                match position.position_type {
+                    UnifiedPositionSide::Long => "resistance",
+                    UnifiedPositionSide::Short => "support",
+                }
+            ),
+            market_context,
+        };
+        self.exit_logger.log_exit_modification(modification).await?;
         Ok(())
     }
 
     async fn evaluate_profit_targets(&self, position: &Position) -> Result<Vec<ProfitTarget>> {
         let current_price = self.get_current_price(&position.symbol).await?;
-        let entry_price = position.entry_price;
-        let initial_stop = position.stop_loss.unwrap_or(0.0);
+        let (entry_price, initial_stop) = self.risk_basis(position);
+        let initial_stop = initial_stop.unwrap_or_default();
 
-        if initial_stop == 0.0 {
+        if initial_stop.is_zero() {
             return Ok(Vec::new()); // Can't calculate R:R without stop loss
         }
 
@@ -89,11 +300,7 @@ impl PartialProfitManager {
             &position.position_type,
         );
 
-        let default_config = ProfitTakingConfig::default();
-        let config = self
-            .profit_configs
-            .get(&position.symbol)
-            .unwrap_or(&default_config);
+        let config = self.config_for(position).unwrap_or_default();
 
         if !config.enabled {
             return Ok(Vec::new());
@@ -102,9 +309,16 @@ impl PartialProfitManager {
         let mut targets_hit = Vec::new();
 
         // Get current position status
-        let position_status = self.position_targets.get(&position.id);
+        let position_status = self.position_targets.get_mut(&position.id);
         let already_hit: Vec<u32> = match position_status {
-            Some(status) => status.targets_hit.clone(),
+            Some(mut status) => {
+                // Restored from a checkpoint without one, or reset by a scale-in
+                if status.entry_price.is_none() {
+                    status.entry_price = Some(entry_price);
+                    status.initial_stop = Some(initial_stop);
+                }
+                status.targets_hit.clone()
+            }
             None => {
                 // Initialize position target tracking
                 let initial_status = PositionTargetStatus {
@@ -113,6 +327,9 @@ impl PartialProfitManager {
                     remaining_volume: position.volume,
                     total_partial_profit: Decimal::ZERO,
                     last_target_hit: None,
+                    entry_price: Some(entry_price),
+                    initial_stop: Some(initial_stop),
+                    last_verification: None,
                 };
                 self.position_targets.insert(position.id, initial_status);
                 Vec::new()
@@ -133,6 +350,51 @@ impl PartialProfitManager {
         Ok(targets_hit)
     }
 
+    /// The partial closes the position's targets would take now, each from what
+    /// the ones before it leave
+    pub async fn preview_targets(&self, position: &Position) -> Result<Vec<PartialClosePreview>> {
+        let config = self.config_for(position).unwrap_or_default();
+        let (entry_price, initial_stop) = self.risk_basis(position);
+        let Some(initial_stop) = initial_stop.filter(|stop| !stop.is_zero()) else {
+            return Ok(Vec::new());
+        };
+        if !config.enabled {
+            return Ok(Vec::new());
+        }
+
+        let current_price = self.get_current_price(&position.symbol).await?;
+        let current_rr = self.calculate_risk_reward_ratio(
+            entry_price,
+            current_price,
+            initial_stop,
+            &position.position_type,
+        );
+        let (already_hit, mut remaining_volume) = match self.position_targets.get(&position.id) {
+            Some(status) => (status.targets_hit.clone(), status.remaining_volume),
+            None => (Vec::new(), position.volume),
+        };
+        let min_volume = self.get_minimum_volume(&position.symbol).await?;
+
+        let mut previews = Vec::new();
+        for target in &config.profit_targets {
+            if current_rr < target.risk_reward_ratio || already_hit.contains(&target.level) {
+                continue;
+            }
+            let close_volume = scale_by(remaining_volume, target.close_percentage);
+            if close_volume < min_volume {
+                continue;
+            }
+            remaining_volume -= close_volume;
+            previews.push(PartialClosePreview {
+                level: target.level,
+                risk_reward_ratio: target.risk_reward_ratio,
+                current_risk_reward: current_rr,
+                close_volume,
+            });
+        }
+        Ok(previews)
+    }
+
     async fn execute_partial_close(
         &self,
         position: &Position,
@@ -145,10 +407,8 @@ impl PartialProfitManager {
         };
 
         // Calculate volume to close
-        let close_volume =
-            current_volume * Decimal::from_f64_retain(target.close_percentage).unwrap();
-        let min_volume =
-            Decimal::from_f64_retain(self.get_minimum_volume(&position.symbol).await?).unwrap();
+        let close_volume = scale_by(current_volume, target.close_percentage);
+        let min_volume = self.get_minimum_volume(&position.symbol).await?;
 
         // Validate minimum volume requirements
         if close_volume < min_volume {
@@ -178,7 +438,7 @@ impl PartialProfitManager {
             UnifiedPositionSide::Short => position.entry_price - close_result.close_price,
         };
 
-        let partial_profit = Decimal::from_f64_retain(profit_per_unit).unwrap() * close_volume;
+        let partial_profit = profit_per_unit * close_volume;
 
         // Update position tracking
         self.update_position_target_status(position.id, target, close_volume, partial_profit)
@@ -203,9 +463,117 @@ impl PartialProfitManager {
             partial_profit
         );
 
+        if let Err(e) = self
+            .verify_partial_close(position, current_volume - close_volume)
+            .await
+        {
+            warn!(
+                "Failed to verify partial close of position {}: {:#}",
+                position.id, e
+            );
+        }
+
         Ok(())
     }
 
+    /// Re-read the position after a partial close. Tracking takes the volume the
+    /// platform shows, the targets stay measured from the original entry however
+    /// the platform re-averaged, and a stop the close dropped or moved is put back.
+    async fn verify_partial_close(
+        &self,
+        position: &Position,
+        expected_volume: Decimal,
+    ) -> Result<PartialCloseVerification> {
+        let platform_position = self
+            .trading_platform
+            .get_positions()
+            .await?
+            .into_iter()
+            .find(|p| p.id == position.id);
+
+        let mut verification = PartialCloseVerification {
+            position_id: position.id,
+            expected_volume,
+            platform_volume: platform_position.as_ref().map(|p| p.volume),
+            expected_entry: position.entry_price,
+            platform_entry: platform_position.as_ref().map(|p| p.entry_price),
+            expected_stop: position.stop_loss,
+            platform_stop: platform_position.as_ref().and_then(|p| p.stop_loss),
+            stop_restored: false,
+            verified_at: Utc::now(),
+        };
+        let discrepancies = verification.discrepancies();
+
+        if let Some(platform_position) = &platform_position {
+            if verification.platform_stop != verification.expected_stop
+                && verification.expected_stop.is_some()
+            {
+                let request = OrderModifyRequest {
+                    order_id: platform_position.order_id.clone(),
+                    new_stop_loss: verification.expected_stop,
+                    new_take_profit: platform_position.take_profit.or(position.take_profit),
+                };
+                match self.trading_platform.modify_order(request).await {
+                    Ok(result) if result.success => verification.stop_restored = true,
+                    Ok(result) => error!(
+                        "Platform refused to restore the stop of position {}: {}",
+                        position.id, result.message
+                    ),
+                    Err(e) => error!(
+                        "Failed to restore the stop of position {}: {:#}",
+                        position.id, e
+                    ),
+                }
+            }
+        }
+
+        let basis = self.risk_basis(position).0;
+        if let Some(mut status) = self.position_targets.get_mut(&position.id) {
+            if let Some(volume) = verification.platform_volume {
+                status.remaining_volume = volume;
+            }
+            status.last_verification = Some(verification.clone());
+        }
+
+        if discrepancies.is_empty() {
+            return Ok(verification);
+        }
+        warn!(
+            "Position {} after partial close: {}; targets measured from entry {}",
+            position.id,
+            discrepancies.join(", "),
+            basis
+        );
+        let market_context = self.market_context.capture(&position.symbol).await?;
+        let modification = ExitModification {
+            position_id: position.id,
+            modification_type: ExitModificationType::PartialProfit,
+            old_value: expected_volume,
+            new_value: verification.platform_volume.unwrap_or_default(),
+            reasoning: format!(
+                "Partial close verification: {}{}",
+                discrepancies.join(", "),
+                if verification.stop_restored {
+                    ", stop restored"
+                } else {
+                    ""
+                }
+            ),
+            market_context,
+        };
+        self.exit_logger.log_exit_modification(modification).await?;
+        Ok(verification)
+    }
+
+    /// Entry and stop the position's R:R is measured from: those fixed when
+    /// tracking started, or the position's own until then
+    fn risk_basis(&self, position: &Position) -> (Decimal, Option<Decimal>) {
+        self.position_targets
+            .get(&position.id)
+            .and_then(|status| status.entry_price.map(|entry| (entry, status.initial_stop)))
+            .unwrap_or((position.entry_price, position.stop_loss))
+    }
+
     async fn update_position_target_status(
         &self,
         position_id: PositionId,
@@ -224,9 +592,9 @@ impl PartialProfitManager {
 
     fn calculate_risk_reward_ratio(
         &self,
-        entry_price: f64,
-        current_price: f64,
-        stop_loss: f64,
+        entry_price: Decimal,
+        current_price: Decimal,
+        stop_loss: Decimal,
         position_type: &UnifiedPositionSide,
     ) -> f64 {
         let profit = match position_type {
@@ -239,11 +607,7 @@ impl PartialProfitManager {
             UnifiedPositionSide::Short => stop_loss - entry_price,
         };
 
-        if risk > 0.0 {
-            profit / risk
-        } else {
-            0.0
-        }
+        ratio_of(profit, risk)
     }
 
     async fn get_positions_with_remaining_targets(&self) -> Result<Vec<Position>> {
@@ -253,7 +617,7 @@ impl PartialProfitManager {
         let positions_with_targets: Vec<Position> = all_positions
             .into_iter()
             .filter(|pos| {
-                if let Some(config) = self.profit_configs.get(&pos.symbol) {
+                if let Some(config) = self.config_for(pos) {
                     if !config.enabled {
                         return false;
                     }
@@ -278,15 +642,15 @@ impl PartialProfitManager {
         Ok(positions_with_targets)
     }
 
-    async fn get_current_price(&self, symbol: &str) -> Result<f64> {
+    async fn get_current_price(&self, symbol: &str) -> Result<Decimal> {
         let market_data = self.trading_platform.get_market_data(symbol).await?;
-        Ok((market_data.bid + market_data.ask) / 2.0)
+        Ok(market_data.mid())
     }
 
-    async fn get_minimum_volume(&self, symbol: &str) -> Result<f64> {
+    async fn get_minimum_volume(&self, symbol: &str) -> Result<Decimal> {
         // This would typically come from broker specifications
         // For now, using a standard minimum
-        Ok(0.01) // 0.01 lots
+        Ok(dec!(0.01)) // 0.01 lots
     }
 
     async fn log_partial_profit_taking(
@@ -294,25 +658,16 @@ impl PartialProfitManager {
         position: &Position,
         target: &ProfitTarget,
         volume: Decimal,
-        close_price: f64,
+        close_price: Decimal,
         profit: Decimal,
     ) -> Result<()> {
-        let current_price = self.get_current_price(&position.symbol).await?;
-
-        let market_context = MarketContext {
-            current_price,
-            atr_14: 0.0015, // Simplified
-            trend_strength: 0.5,
-            volatility: 0.02,
-            spread: 0.0001,
-            timestamp: Utc::now(),
-        };
+        let market_context = self.market_context.capture(&position.symbol).await?;
 
         let modification = ExitModification {
             position_id: position.id,
             modification_type: ExitModificationType::PartialProfit,
-            old_value: f64::try_from(position.volume).unwrap_or(0.0),
-            new_value: f64::try_from(volume).unwrap_or(0.0),
+            old_value: position.volume,
+            new_value: volume,
             reasoning: format!(
                 "Partial profit taking: {}% at {:.2} R:R, Volume: {:.4}, Profit: {:.2}",
                 target.close_percentage * 100.0,
@@ -334,6 +689,28 @@ impl PartialProfitManager {
         self.position_targets.get(&position_id).map(|s| s.clone())
     }
 
+    pub fn get_position_target_statuses(&self) -> Vec<PositionTargetStatus> {
+        self.position_targets
+            .iter()
+            .map(|s| s.value().clone())
+            .collect()
+    }
+
+    /// Reinstate progress from a checkpoint so hit targets are not taken again
+    pub fn restore_position_target_status(&self, status: PositionTargetStatus) {
+        self.position_targets.insert(status.position_id, status);
+    }
+
+    /// Let later targets close their share of volume added by a scale-in, and
+    /// measure them from the new average and stop
+    pub fn add_volume(&self, position_id: PositionId, volume: Decimal) {
+        if let Some(mut status) = self.position_targets.get_mut(&position_id) {
+            status.remaining_volume += volume;
+            status.entry_price = None;
+            status.initial_stop = None;
+        }
+    }
+
     pub fn remove_position_tracking(&self, position_id: PositionId) {
         self.position_targets.remove(&position_id);
     }
@@ -349,8 +726,7 @@ impl PartialProfitManager {
             total_partials += status.targets_hit.len() as u32;
             total_profit += status.total_partial_profit;
 
-            let original_volume = status.remaining_volume
-                + (status.total_partial_profit / Decimal::from_f64_retain(1.0).unwrap()); // Simplified
+            let original_volume = status.remaining_volume + status.total_partial_profit; // Simplified
             let volume_closed = original_volume - status.remaining_volume;
             total_volume_closed += volume_closed;
 
@@ -396,11 +772,7 @@ impl PartialProfitManager {
         position: &Position,
     ) -> Result<PartialProfitValidation> {
         let current_price = self.get_current_price(&position.symbol).await?;
-        let default_config = ProfitTakingConfig::default();
-        let config = self
-            .profit_configs
-            .get(&position.symbol)
-            .unwrap_or(&default_config);
+        let config = self.config_for(position).unwrap_or_default();
 
         let mut validation = PartialProfitValidation {
             is_enabled: config.enabled,
@@ -409,9 +781,10 @@ impl PartialProfitManager {
             targets_already_hit: Vec::new(),
         };
 
-        if let Some(stop_loss) = position.stop_loss {
+        let (entry_price, initial_stop) = self.risk_basis(position);
+        if let Some(stop_loss) = initial_stop {
             validation.current_risk_reward = self.calculate_risk_reward_ratio(
-                position.entry_price,
+                entry_price,
                 current_price,
                 stop_loss,
                 &position.position_type,
@@ -1,22 +1,30 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use super::types::*;
 use super::TradingPlatform;
+use crate::execution::order_tracker::{OrderOrigin, OrderTracker, TrackedOrder};
+use crate::platforms::abstraction::capabilities::QuantityLimits;
+use crate::platforms::abstraction::degradation::TRAILING_DISTANCE_PARAM;
 use crate::platforms::abstraction::events::PlatformEvent;
 use crate::platforms::abstraction::interfaces::EventFilter;
 use crate::platforms::abstraction::{
-    ITradingPlatform, OrderModification, PlatformError, UnifiedMarketData, UnifiedOrderResponse,
-    UnifiedPosition,
+    ITradingPlatform, OrderMetadata, OrderModification, PlatformError, UnifiedMarketData,
+    UnifiedOrder, UnifiedOrderResponse, UnifiedOrderSide, UnifiedOrderStatus, UnifiedOrderType,
+    UnifiedPosition, UnifiedTimeInForce,
 };
 
 /// Platform adapter that bridges the exit management system with the actual platform abstraction
 pub struct ExitManagementPlatformAdapter {
     platform: Arc<dyn ITradingPlatform + Send + Sync>,
+    /// Where the orders exits send are recorded, with the account they are on
+    order_tracker: Option<(Arc<OrderTracker>, String)>,
 }
 
 impl std::fmt::Debug for ExitManagementPlatformAdapter {
@@ -30,24 +38,48 @@ impl std::fmt::Debug for ExitManagementPlatformAdapter {
 
 impl ExitManagementPlatformAdapter {
     pub fn new(platform: Arc<dyn ITradingPlatform + Send + Sync>) -> Self {
-        Self { platform }
+        Self {
+            platform,
+            order_tracker: None,
+        }
+    }
+
+    /// Record the orders sent for exits on `account_id` in `tracker`
+    pub fn with_order_tracker(mut self, tracker: Arc<OrderTracker>, account_id: &str) -> Self {
+        self.order_tracker = Some((tracker, account_id.to_string()));
+        self
+    }
+
+    fn track(&self, response: &UnifiedOrderResponse) {
+        if let Some((tracker, account_id)) = &self.order_tracker {
+            tracker.record(TrackedOrder {
+                account_id: account_id.clone(),
+                platform_order_id: response.platform_order_id.clone(),
+                client_order_id: Some(response.client_order_id.clone()),
+                signal_id: None,
+                symbol: response.symbol.clone(),
+                origin: OrderOrigin::Exit,
+                sent_at: chrono::Utc::now(),
+            });
+        }
     }
 
-    /// Convert UnifiedPosition to our exit management Position
+    /// Convert UnifiedPosition to our exit management Position. Every ticket becomes
+    /// its own position, so the tickets of a hedged symbol are supervised separately.
     fn convert_position(&self, unified_pos: &UnifiedPosition) -> Position {
         Position {
-            id: Uuid::parse_str(&unified_pos.position_id).unwrap_or_else(|_| Uuid::new_v4()),
-            order_id: unified_pos.position_id.clone(), // Using position_id as order_id for now
+            id: ticket_position_id(&unified_pos.position_id),
+            order_id: unified_pos.position_id.clone(), // The platform ticket id
             symbol: unified_pos.symbol.clone(),
             position_type: unified_pos.side.clone(), // UnifiedPositionSide is already compatible
             volume: unified_pos.quantity,
-            entry_price: unified_pos.entry_price.to_f64().unwrap_or(0.0),
-            current_price: unified_pos.current_price.to_f64().unwrap_or(0.0),
-            stop_loss: unified_pos.stop_loss.map(|sl| sl.to_f64().unwrap_or(0.0)),
-            take_profit: unified_pos.take_profit.map(|tp| tp.to_f64().unwrap_or(0.0)),
-            unrealized_pnl: unified_pos.unrealized_pnl.to_f64().unwrap_or(0.0),
-            swap: 0.0, // Not available in UnifiedPosition
-            commission: unified_pos.commission.to_f64().unwrap_or(0.0),
+            entry_price: unified_pos.entry_price,
+            current_price: unified_pos.current_price,
+            stop_loss: unified_pos.stop_loss,
+            take_profit: unified_pos.take_profit,
+            unrealized_pnl: unified_pos.unrealized_pnl,
+            swap: Decimal::ZERO, // Not available in UnifiedPosition
+            commission: unified_pos.commission,
             open_time: unified_pos.opened_at,
             magic_number: None, // Not available in UnifiedPosition
             comment: None,      // Not available in UnifiedPosition
@@ -58,9 +90,9 @@ impl ExitManagementPlatformAdapter {
     fn convert_market_data(&self, unified_data: &UnifiedMarketData) -> MarketData {
         MarketData {
             symbol: unified_data.symbol.clone(),
-            bid: unified_data.bid.to_f64().unwrap_or(0.0),
-            ask: unified_data.ask.to_f64().unwrap_or(0.0),
-            spread: unified_data.spread.to_f64().unwrap_or(0.0),
+            bid: unified_data.bid,
+            ask: unified_data.ask,
+            spread: unified_data.spread,
             timestamp: unified_data.timestamp,
         }
     }
@@ -98,14 +130,8 @@ impl TradingPlatform for ExitManagementPlatformAdapter {
             quantity: None,   // Not modifying quantity for exit management
             price: None,      // Not modifying price for exit management
             stop_price: None, // Not using stop_price
-            take_profit: request
-                .new_take_profit
-                .map(Decimal::from_f64_retain)
-                .flatten(),
-            stop_loss: request
-                .new_stop_loss
-                .map(Decimal::from_f64_retain)
-                .flatten(),
+            take_profit: request.new_take_profit,
+            stop_loss: request.new_stop_loss,
             time_in_force: None, // Not modifying time in force
         };
 
@@ -128,7 +154,7 @@ impl TradingPlatform for ExitManagementPlatformAdapter {
     }
 
     async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
-        // Find the position to get its symbol
+        // Find the position to get its platform ticket
         let positions = self.get_positions().await?;
         let position = positions
             .iter()
@@ -137,17 +163,14 @@ impl TradingPlatform for ExitManagementPlatformAdapter {
 
         let response = self
             .platform
-            .close_position(&position.symbol, None)
+            .close_position_ticket(&position.order_id, None)
             .await
             .map_err(|e| anyhow::anyhow!("Platform error closing position: {:?}", e))?;
+        self.track(&response);
 
         Ok(ClosePositionResult {
             position_id: request.position_id,
-            close_price: response
-                .average_fill_price
-                .unwrap_or_default()
-                .to_f64()
-                .unwrap_or(0.0),
+            close_price: response.average_fill_price.unwrap_or_default(),
             realized_pnl: Some(Decimal::ZERO), // Would need to calculate this
             close_time: chrono::Utc::now(),
         })
@@ -157,7 +180,7 @@ impl TradingPlatform for ExitManagementPlatformAdapter {
         &self,
         request: PartialCloseRequest,
     ) -> Result<ClosePositionResult> {
-        // Find the position to get its symbol
+        // Find the position to get its platform ticket
         let positions = self.get_positions().await?;
         let position = positions
             .iter()
@@ -166,21 +189,97 @@ impl TradingPlatform for ExitManagementPlatformAdapter {
 
         let response = self
             .platform
-            .close_position(&position.symbol, Some(request.volume))
+            .close_position_ticket(&position.order_id, Some(request.volume))
             .await
             .map_err(|e| anyhow::anyhow!("Platform error partially closing position: {:?}", e))?;
+        self.track(&response);
 
         Ok(ClosePositionResult {
             position_id: request.position_id,
-            close_price: response
-                .average_fill_price
-                .unwrap_or_default()
-                .to_f64()
-                .unwrap_or(0.0),
+            close_price: response.average_fill_price.unwrap_or_default(),
             realized_pnl: Some(Decimal::ZERO), // Would need to calculate this
             close_time: chrono::Utc::now(),
         })
     }
+
+    fn supports_native_trailing(&self, _symbol: &str) -> bool {
+        self.platform
+            .capabilities()
+            .supports_order_type(&UnifiedOrderType::TrailingStop)
+    }
+
+    fn quantity_limits(&self, symbol: &str) -> QuantityLimits {
+        self.platform.capabilities().quantity_limits(symbol)
+    }
+
+    async fn place_native_trailing_stop(
+        &self,
+        request: NativeTrailingStopRequest,
+    ) -> Result<OrderModifyResult> {
+        let order = UnifiedOrder {
+            client_order_id: format!("trail_{}", Uuid::new_v4()),
+            symbol: request.symbol.clone(),
+            side: match request.position_type {
+                UnifiedPositionSide::Long => UnifiedOrderSide::Sell,
+                UnifiedPositionSide::Short => UnifiedOrderSide::Buy,
+            },
+            order_type: UnifiedOrderType::TrailingStop,
+            quantity: request.volume,
+            price: None,
+            stop_price: Some(request.stop_level),
+            take_profit: None,
+            stop_loss: None,
+            time_in_force: UnifiedTimeInForce::Gtc,
+            account_id: None,
+            metadata: OrderMetadata {
+                strategy_id: None,
+                signal_id: None,
+                risk_parameters: HashMap::from([(
+                    TRAILING_DISTANCE_PARAM.to_string(),
+                    serde_json::Value::String(request.trail_distance.to_string()),
+                )]),
+                tags: vec![format!("trailing_stop:{}", request.order_id)],
+                expires_at: None,
+            },
+            max_slippage: None,
+        };
+
+        let response = self
+            .platform
+            .place_order(order)
+            .await
+            .map_err(|e| anyhow::anyhow!("Platform error placing trailing stop: {:?}", e))?;
+        self.track(&response);
+
+        Ok(OrderModifyResult {
+            order_id: response.platform_order_id,
+            success: matches!(
+                response.status,
+                UnifiedOrderStatus::Pending | UnifiedOrderStatus::New
+            ),
+            message: format!("Trailing stop placed: {:?}", response.status),
+        })
+    }
+
+    async fn cancel_native_trailing_stop(&self, order_id: &str) -> Result<()> {
+        self.platform
+            .cancel_order(order_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Platform error cancelling trailing stop: {:?}", e))
+    }
+}
+
+/// Exit management id for a platform ticket. Ticket ids that are not UUIDs (MetaTrader
+/// tickets are integers) are hashed, so a ticket keeps the same id across polls.
+pub(crate) fn ticket_position_id(position_id: &str) -> PositionId {
+    Uuid::parse_str(position_id).unwrap_or_else(|_| {
+        let hash = |salt: u8| {
+            let mut hasher = DefaultHasher::new();
+            (salt, position_id).hash(&mut hasher);
+            hasher.finish()
+        };
+        Uuid::from_u64_pair(hash(0), hash(1))
+    })
 }
 
 /// Factory for creating platform adapters
@@ -197,255 +296,76 @@ impl PlatformAdapterFactory {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::platforms::abstraction::{UnifiedMarketData, UnifiedPositionSide};
+    use crate::platforms::abstraction::UnifiedPositionSide;
+    use crate::testing::MockTradingPlatform;
     use chrono::Utc;
     use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
     use std::collections::HashMap;
 
-    struct MockPlatform;
-
-    #[async_trait]
-    impl ITradingPlatform for MockPlatform {
-        fn platform_type(&self) -> crate::platforms::PlatformType {
-            crate::platforms::PlatformType::MetaTrader4
-        }
-
-        fn platform_name(&self) -> &str {
-            "MockPlatform"
-        }
-        fn platform_version(&self) -> &str {
-            "1.0.0"
-        }
-
-        async fn connect(&mut self) -> Result<(), PlatformError> {
-            Ok(())
-        }
-        async fn disconnect(&mut self) -> Result<(), PlatformError> {
-            Ok(())
-        }
-        async fn is_connected(&self) -> bool {
-            true
-        }
-        async fn ping(&self) -> Result<u64, PlatformError> {
-            Ok(10)
-        }
-
-        async fn place_order(
-            &self,
-            _order: crate::platforms::abstraction::UnifiedOrder,
-        ) -> Result<UnifiedOrderResponse, PlatformError> {
-            unimplemented!()
-        }
-
-        async fn modify_order(
-            &self,
-            order_id: &str,
-            _modifications: OrderModification,
-        ) -> Result<UnifiedOrderResponse, PlatformError> {
-            Ok(UnifiedOrderResponse {
-                platform_order_id: order_id.to_string(),
-                client_order_id: order_id.to_string(),
-                status: crate::platforms::abstraction::UnifiedOrderStatus::New,
-                symbol: "EURUSD".to_string(),
-                side: crate::platforms::abstraction::UnifiedOrderSide::Buy,
-                order_type: crate::platforms::abstraction::UnifiedOrderType::Market,
-                quantity: Decimal::from(1),
-                filled_quantity: Decimal::ZERO,
-                remaining_quantity: Decimal::from(1),
-                price: None,
-                average_fill_price: Some(Decimal::from_f64_retain(1.1000).unwrap()),
-                commission: Some(Decimal::ZERO),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                filled_at: None,
-                platform_specific: HashMap::new(),
-            })
-        }
-
-        async fn cancel_order(&self, _order_id: &str) -> Result<(), PlatformError> {
-            Ok(())
-        }
-
-        async fn get_order(&self, _order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
-            unimplemented!()
-        }
-
-        async fn get_orders(
-            &self,
-            _filter: Option<crate::platforms::abstraction::OrderFilter>,
-        ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
-            Ok(Vec::new())
-        }
-
-        async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
-            Ok(vec![UnifiedPosition {
-                position_id: "test-position-1".to_string(),
-                symbol: "EURUSD".to_string(),
-                side: UnifiedPositionSide::Long,
-                quantity: Decimal::from(1),
-                entry_price: Decimal::from_f64_retain(1.1000).unwrap(),
-                current_price: Decimal::from_f64_retain(1.1050).unwrap(),
-                unrealized_pnl: Decimal::from_f64_retain(50.0).unwrap(),
-                realized_pnl: Decimal::ZERO,
-                margin_used: Decimal::from(100),
-                commission: Decimal::from_f64_retain(2.0).unwrap(),
-                stop_loss: Some(Decimal::from_f64_retain(1.0950).unwrap()),
-                take_profit: Some(Decimal::from_f64_retain(1.1100).unwrap()),
-                opened_at: Utc::now(),
-                updated_at: Utc::now(),
-                account_id: "test-account".to_string(),
-                platform_specific: HashMap::new(),
-            }])
-        }
-
-        async fn get_position(
-            &self,
-            _symbol: &str,
-        ) -> Result<Option<UnifiedPosition>, PlatformError> {
-            Ok(None)
-        }
-
-        async fn close_position(
-            &self,
-            _symbol: &str,
-            _quantity: Option<Decimal>,
-        ) -> Result<UnifiedOrderResponse, PlatformError> {
-            Ok(UnifiedOrderResponse {
-                platform_order_id: "close-order-1".to_string(),
-                client_order_id: "close-order-1".to_string(),
-                status: crate::platforms::abstraction::UnifiedOrderStatus::Filled,
-                symbol: "EURUSD".to_string(),
-                side: crate::platforms::abstraction::UnifiedOrderSide::Sell,
-                order_type: crate::platforms::abstraction::UnifiedOrderType::Market,
-                quantity: Decimal::from(1),
-                filled_quantity: Decimal::from(1),
-                remaining_quantity: Decimal::ZERO,
-                price: None,
-                average_fill_price: Some(Decimal::from_f64_retain(1.1050).unwrap()),
-                commission: Some(Decimal::from_f64_retain(2.0).unwrap()),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                filled_at: Some(Utc::now()),
-                platform_specific: HashMap::new(),
-            })
-        }
-
-        async fn get_account_info(
-            &self,
-        ) -> Result<crate::platforms::abstraction::UnifiedAccountInfo, PlatformError> {
-            unimplemented!()
-        }
-
-        async fn get_balance(&self) -> Result<Decimal, PlatformError> {
-            Ok(Decimal::from(10000))
-        }
-
-        async fn get_margin_info(
-            &self,
-        ) -> Result<crate::platforms::abstraction::MarginInfo, PlatformError> {
-            unimplemented!()
-        }
-
-        async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
-            Ok(UnifiedMarketData {
-                symbol: symbol.to_string(),
-                bid: Decimal::from_f64_retain(1.1049).unwrap(),
-                ask: Decimal::from_f64_retain(1.1051).unwrap(),
-                spread: Decimal::from_f64_retain(0.0002).unwrap(),
-                last_price: Some(Decimal::from_f64_retain(1.1050).unwrap()),
-                volume: Some(Decimal::from(1000)),
-                high: Some(Decimal::from_f64_retain(1.1080).unwrap()),
-                low: Some(Decimal::from_f64_retain(1.1020).unwrap()),
-                timestamp: Utc::now(),
-                session: Some(crate::platforms::abstraction::TradingSession::Regular),
-                platform_specific: HashMap::new(),
-            })
-        }
-
-        async fn subscribe_market_data(
-            &self,
-            _symbols: Vec<String>,
-        ) -> Result<tokio::sync::mpsc::Receiver<UnifiedMarketData>, PlatformError> {
-            unimplemented!()
-        }
-
-        async fn unsubscribe_market_data(
-            &self,
-            _symbols: Vec<String>,
-        ) -> Result<(), PlatformError> {
-            Ok(())
-        }
-
-        fn capabilities(&self) -> crate::platforms::abstraction::PlatformCapabilities {
-            unimplemented!()
-        }
-
-        async fn subscribe_events(
-            &self,
-        ) -> Result<
-            tokio::sync::mpsc::Receiver<crate::platforms::abstraction::PlatformEvent>,
-            PlatformError,
-        > {
-            unimplemented!()
-        }
-
-        async fn get_event_history(
-            &self,
-            _filter: EventFilter,
-        ) -> Result<Vec<PlatformEvent>, PlatformError> {
-            Ok(Vec::new())
-        }
-
-        async fn health_check(
-            &self,
-        ) -> Result<crate::platforms::abstraction::HealthStatus, PlatformError> {
-            unimplemented!()
-        }
-
-        async fn get_diagnostics(
-            &self,
-        ) -> Result<crate::platforms::abstraction::DiagnosticsInfo, PlatformError> {
-            unimplemented!()
-        }
+    /// EURUSD long at 1.1000, marked at 1.1050
+    fn mock_platform() -> Arc<MockTradingPlatform> {
+        let platform = MockTradingPlatform::new("MockPlatform")
+            .with_platform_type(crate::platforms::PlatformType::MetaTrader4)
+            .with_quote("EURUSD", dec!(1.1049), dec!(1.1051));
+        platform.add_position(UnifiedPosition {
+            position_id: "test-position-1".to_string(),
+            symbol: "EURUSD".to_string(),
+            side: UnifiedPositionSide::Long,
+            quantity: Decimal::from(1),
+            entry_price: dec!(1.1000),
+            current_price: dec!(1.1050),
+            unrealized_pnl: dec!(50.0),
+            realized_pnl: Decimal::ZERO,
+            margin_used: Decimal::from(100),
+            commission: dec!(2.0),
+            stop_loss: Some(dec!(1.0950)),
+            take_profit: Some(dec!(1.1100)),
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            account_id: "test-account".to_string(),
+            platform_specific: HashMap::new(),
+        });
+        Arc::new(platform)
     }
 
     #[tokio::test]
     async fn test_platform_adapter_get_positions() {
-        let mock_platform = Arc::new(MockPlatform);
+        let mock_platform = mock_platform();
         let adapter = ExitManagementPlatformAdapter::new(mock_platform);
 
         let positions = adapter.get_positions().await.unwrap();
         assert_eq!(positions.len(), 1);
         assert_eq!(positions[0].symbol, "EURUSD");
-        assert_eq!(positions[0].entry_price, 1.1000);
-        assert_eq!(positions[0].current_price, 1.1050);
+        assert_eq!(positions[0].entry_price, dec!(1.1000));
+        assert_eq!(positions[0].current_price, dec!(1.1050));
     }
 
     #[tokio::test]
     async fn test_platform_adapter_get_market_data() {
-        let mock_platform = Arc::new(MockPlatform);
+        let mock_platform = mock_platform();
         let adapter = ExitManagementPlatformAdapter::new(mock_platform);
 
         let market_data = adapter.get_market_data("EURUSD").await.unwrap();
         assert_eq!(market_data.symbol, "EURUSD");
-        assert_eq!(market_data.bid, 1.1049);
-        assert_eq!(market_data.ask, 1.1051);
-        assert_eq!(market_data.spread, 0.0002);
+        assert_eq!(market_data.bid, dec!(1.1049));
+        assert_eq!(market_data.ask, dec!(1.1051));
+        assert_eq!(market_data.spread, dec!(0.0002));
     }
 
     #[tokio::test]
     async fn test_platform_adapter_modify_order() {
-        let mock_platform = Arc::new(MockPlatform);
+        let mock_platform = mock_platform();
         let adapter = ExitManagementPlatformAdapter::new(mock_platform);
 
         let request = OrderModifyRequest {
-            order_id: "test-order".to_string(),
-            new_stop_loss: Some(1.0950),
-            new_take_profit: Some(1.1100),
+            order_id: "test-position-1".to_string(),
+            new_stop_loss: Some(dec!(1.0950)),
+            new_take_profit: Some(dec!(1.1100)),
         };
 
         let result = adapter.modify_order(request).await.unwrap();
         assert!(result.success);
-        assert_eq!(result.order_id, "test-order");
+        assert_eq!(result.order_id, "test-position-1");
     }
 }
@@ -0,0 +1,183 @@
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::types::*;
+
+/// Exit configuration for a single position. Each set section replaces the symbol's
+/// configuration for that manager; unset sections fall back to it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExitPolicy {
+    /// Label for logs and the audit trail, e.g. "news-trade"
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub trailing: Option<TrailingConfig>,
+    #[serde(default)]
+    pub break_even: Option<BreakEvenConfig>,
+    #[serde(default)]
+    pub profit_taking: Option<ProfitTakingConfig>,
+    #[serde(default)]
+    pub time_exit: Option<TimeExitConfig>,
+}
+
+/// A policy waiting for the position its signal is about to open
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingExitPolicy {
+    pub signal_id: String,
+    pub symbol: String,
+    pub policy: ExitPolicy,
+    /// Set before the order is sent; only positions opened after this can claim the policy
+    pub created_at: DateTime<Utc>,
+    /// Size the order actually filled; a position of that size is preferred over
+    /// others opened around the same time
+    #[serde(default)]
+    pub quantity: Option<rust_decimal::Decimal>,
+}
+
+impl PendingExitPolicy {
+    /// Unclaimed policies are dropped after this long
+    pub fn ttl() -> Duration {
+        Duration::hours(1)
+    }
+
+    /// Allowance for the platform clock running behind ours
+    fn clock_tolerance() -> Duration {
+        Duration::seconds(5)
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now - self.created_at > Self::ttl()
+    }
+
+    /// Prefer positions of the filled size, then fall back to any on the symbol
+    fn pick<'a>(&self, candidates: &[&'a Position]) -> Option<&'a Position> {
+        if let Some(quantity) = self.quantity {
+            let sized: Vec<&Position> = candidates
+                .iter()
+                .copied()
+                .filter(|p| p.volume == quantity)
+                .collect();
+            if let Some(position) = self.pick_by_time(&sized) {
+                return Some(position);
+            }
+        }
+        self.pick_by_time(candidates)
+    }
+
+    /// Prefer the first position opened after the order was sent; failing that, the one
+    /// opened closest before it, within the clock tolerance
+    fn pick_by_time<'a>(&self, candidates: &[&'a Position]) -> Option<&'a Position> {
+        let on_symbol = candidates.iter().filter(|p| p.symbol == self.symbol);
+        let after = on_symbol
+            .clone()
+            .filter(|p| p.open_time >= self.created_at)
+            .min_by_key(|p| p.open_time);
+        after
+            .or_else(|| {
+                on_symbol
+                    .filter(|p| p.open_time >= self.created_at - Self::clock_tolerance())
+                    .max_by_key(|p| p.open_time)
+            })
+            .copied()
+    }
+}
+
+/// Position policy first, then the symbol's configuration, then the default
+pub fn resolve_config<T: Clone + Default>(policy: Option<T>, symbol: Option<&T>) -> T {
+    policy.or_else(|| symbol.cloned()).unwrap_or_default()
+}
+
+/// Exit policies attached to individual positions, shared by all exit managers
+#[derive(Debug, Default)]
+pub struct ExitPolicies {
+    policies: DashMap<PositionId, ExitPolicy>,
+    pending: DashMap<Symbol, VecDeque<PendingExitPolicy>>,
+}
+
+impl ExitPolicies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, position_id: PositionId, policy: ExitPolicy) {
+        self.policies.insert(position_id, policy);
+    }
+
+    pub fn remove(&self, position_id: PositionId) -> Option<ExitPolicy> {
+        self.policies.remove(&position_id).map(|(_, policy)| policy)
+    }
+
+    pub fn get(&self, position_id: PositionId) -> Option<ExitPolicy> {
+        self.policies.get(&position_id).map(|p| p.clone())
+    }
+
+    pub fn positions(&self) -> Vec<PositionId> {
+        self.policies.iter().map(|p| *p.key()).collect()
+    }
+
+    /// Hold a policy until the position its signal opens shows up
+    pub fn expect(&self, pending: PendingExitPolicy) {
+        self.pending
+            .entry(pending.symbol.clone())
+            .or_default()
+            .push_back(pending);
+    }
+
+    /// True if a policy is still waiting for its position; expired ones are dropped
+    pub fn has_pending(&self) -> bool {
+        let now = Utc::now();
+        self.pending.iter_mut().fold(false, |waiting, mut queue| {
+            queue.retain(|pending| !pending.is_expired(now));
+            waiting || !queue.is_empty()
+        })
+    }
+
+    /// Attach pending policies, oldest first, to the open positions their signals opened.
+    /// Positions that already have a policy are left alone.
+    pub fn claim(&self, positions: &[Position]) -> Vec<(PositionId, PendingExitPolicy)> {
+        let mut candidates: Vec<&Position> = positions
+            .iter()
+            .filter(|p| !self.policies.contains_key(&p.id))
+            .collect();
+        let mut claimed = Vec::new();
+        let now = Utc::now();
+
+        for mut queue in self.pending.iter_mut() {
+            queue.retain(|pending| !pending.is_expired(now));
+            let mut waiting = VecDeque::new();
+            while let Some(pending) = queue.pop_front() {
+                match pending.pick(&candidates) {
+                    Some(position) => {
+                        candidates.retain(|p| p.id != position.id);
+                        claimed.push((position.id, pending));
+                    }
+                    None => waiting.push_back(pending),
+                }
+            }
+            *queue = waiting;
+        }
+
+        for (position_id, pending) in &claimed {
+            self.policies.insert(*position_id, pending.policy.clone());
+        }
+        claimed
+    }
+
+    pub fn trailing(&self, position_id: PositionId) -> Option<TrailingConfig> {
+        self.policies.get(&position_id)?.trailing.clone()
+    }
+
+    pub fn break_even(&self, position_id: PositionId) -> Option<BreakEvenConfig> {
+        self.policies.get(&position_id)?.break_even.clone()
+    }
+
+    pub fn profit_taking(&self, position_id: PositionId) -> Option<ProfitTakingConfig> {
+        self.policies.get(&position_id)?.profit_taking.clone()
+    }
+
+    pub fn time_exit(&self, position_id: PositionId) -> Option<TimeExitConfig> {
+        self.policies.get(&position_id)?.time_exit.clone()
+    }
+}
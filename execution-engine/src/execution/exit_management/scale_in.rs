@@ -0,0 +1,336 @@
+// Positions added to after they opened. Each entry is kept, the position is
+// managed at their weighted average, and its stop and target are recomputed
+// per the configured policy when an addition fills.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use super::exit_logger::ExitAuditLogger;
+use super::types::{OrderModifyRequest, Position, PositionId, UnifiedPositionSide};
+use super::TradingPlatform;
+use crate::execution::order_tracker::{OrderOrigin, OrderTracker};
+
+/// Where the stop goes once a position is added to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScaleInStopPolicy {
+    /// Leave the stop where it is
+    #[default]
+    Keep,
+    /// Keep the first entry's distance from entry to stop, from the new average
+    PreserveDistance,
+    /// Average of each entry's stop, weighted by quantity
+    WeightedAverage,
+}
+
+/// Where the take-profit goes once a position is added to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScaleInTargetPolicy {
+    /// Leave the target where it is
+    #[default]
+    Keep,
+    /// Keep the first entry's distance from entry to target, from the new average
+    PreserveDistance,
+    /// Keep the first entry's reward to risk, from the new average and stop
+    PreserveRiskReward,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScaleInPolicy {
+    pub stop: ScaleInStopPolicy,
+    pub target: ScaleInTargetPolicy,
+}
+
+/// One fill that opened or added to a position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionEntry {
+    pub quantity: Decimal,
+    pub price: Decimal,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    /// Signal of the engine order that filled it, when known
+    pub signal_id: Option<String>,
+    pub filled_at: DateTime<Utc>,
+}
+
+/// A position with the entries it was built from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScaledPosition {
+    pub position_id: PositionId,
+    pub symbol: String,
+    pub side: UnifiedPositionSide,
+    pub entries: Vec<PositionEntry>,
+    /// Open quantity, less what partial closes took
+    pub quantity: Decimal,
+    pub average_price: Decimal,
+}
+
+impl ScaledPosition {
+    fn open(position: &Position) -> Self {
+        Self {
+            position_id: position.id,
+            symbol: position.symbol.clone(),
+            side: position.position_type.clone(),
+            entries: vec![PositionEntry {
+                quantity: position.volume,
+                price: position.entry_price,
+                stop_loss: position.stop_loss,
+                take_profit: position.take_profit,
+                signal_id: None,
+                filled_at: position.open_time,
+            }],
+            quantity: position.volume,
+            average_price: position.entry_price,
+        }
+    }
+
+    /// Fold an addition into the weighted average
+    pub fn merge(&mut self, entry: PositionEntry) {
+        let total = self.quantity + entry.quantity;
+        if total > Decimal::ZERO {
+            self.average_price =
+                (self.average_price * self.quantity + entry.price * entry.quantity) / total;
+        }
+        self.quantity = total;
+        self.entries.push(entry);
+    }
+
+    pub fn is_scaled(&self) -> bool {
+        self.entries.len() > 1
+    }
+
+    /// Stop and target under `policy`, starting from the position's current ones
+    pub fn levels(
+        &self,
+        policy: &ScaleInPolicy,
+        stop_loss: Option<Decimal>,
+        take_profit: Option<Decimal>,
+    ) -> (Option<Decimal>, Option<Decimal>) {
+        let first = &self.entries[0];
+        let stop = match policy.stop {
+            ScaleInStopPolicy::Keep => stop_loss,
+            ScaleInStopPolicy::PreserveDistance => first
+                .stop_loss
+                .map(|stop| self.average_price - (first.price - stop))
+                .or(stop_loss),
+            ScaleInStopPolicy::WeightedAverage => {
+                let (weighted, quantity) = self
+                    .entries
+                    .iter()
+                    .filter_map(|e| e.stop_loss.map(|stop| (stop * e.quantity, e.quantity)))
+                    .fold((Decimal::ZERO, Decimal::ZERO), |(w, q), (ew, eq)| {
+                        (w + ew, q + eq)
+                    });
+                if quantity > Decimal::ZERO {
+                    Some(weighted / quantity)
+                } else {
+                    stop_loss
+                }
+            }
+        };
+        let target = match policy.target {
+            ScaleInTargetPolicy::Keep => take_profit,
+            ScaleInTargetPolicy::PreserveDistance => first
+                .take_profit
+                .map(|target| self.average_price + (target - first.price))
+                .or(take_profit),
+            ScaleInTargetPolicy::PreserveRiskReward => {
+                match (first.stop_loss, first.take_profit, stop) {
+                    (Some(first_stop), Some(first_target), Some(stop))
+                        if first.price != first_stop =>
+                    {
+                        let reward_to_risk =
+                            (first_target - first.price) / (first.price - first_stop);
+                        Some(self.average_price + (self.average_price - stop) * reward_to_risk)
+                    }
+                    _ => take_profit,
+                }
+            }
+        };
+        (stop, target)
+    }
+}
+
+/// An addition found on a position, with the levels it was given
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaleIn {
+    pub position_id: PositionId,
+    /// Platform ticket id
+    pub ticket: String,
+    pub entry: PositionEntry,
+    pub average_price: Decimal,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+}
+
+/// Watches open positions for volume added after they opened. Entries are held
+/// in memory; after a restart a position starts over as one entry at its average.
+#[derive(Debug, Clone)]
+pub struct ScaleInManager {
+    trading_platform: Arc<dyn TradingPlatform>,
+    exit_logger: Arc<ExitAuditLogger>,
+    policy: ScaleInPolicy,
+    order_tracker: Option<(Arc<OrderTracker>, String)>,
+    positions: Arc<DashMap<PositionId, ScaledPosition>>,
+}
+
+impl ScaleInManager {
+    pub fn new(
+        trading_platform: Arc<dyn TradingPlatform>,
+        exit_logger: Arc<ExitAuditLogger>,
+    ) -> Self {
+        Self {
+            trading_platform,
+            exit_logger,
+            policy: ScaleInPolicy::default(),
+            order_tracker: None,
+            positions: Arc::new(DashMap::new()),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: ScaleInPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Attribute additions to the last entry order `tracker` holds for the
+    /// symbol on `account_id`
+    pub fn with_order_tracker(mut self, tracker: Arc<OrderTracker>, account_id: &str) -> Self {
+        self.order_tracker = Some((tracker, account_id.to_string()));
+        self
+    }
+
+    pub fn policy(&self) -> &ScaleInPolicy {
+        &self.policy
+    }
+
+    pub fn get_position(&self, position_id: PositionId) -> Option<ScaledPosition> {
+        self.positions.get(&position_id).map(|p| p.clone())
+    }
+
+    /// Tracked positions that have been added to
+    pub fn scaled_positions(&self) -> Vec<ScaledPosition> {
+        self.positions
+            .iter()
+            .filter(|p| p.is_scaled())
+            .map(|p| p.clone())
+            .collect()
+    }
+
+    /// Track new positions, fold volume added to tracked ones into their average
+    /// and move their levels per the policy. Returns the additions found.
+    pub async fn check_scale_ins(&self) -> Result<Vec<ScaleIn>> {
+        let positions = self.trading_platform.get_positions().await?;
+        let open: HashSet<PositionId> = positions.iter().map(|p| p.id).collect();
+        self.positions.retain(|id, _| open.contains(id));
+
+        let mut scale_ins = Vec::new();
+        for position in &positions {
+            let added = match self.positions.get_mut(&position.id) {
+                None => {
+                    self.positions
+                        .insert(position.id, ScaledPosition::open(position));
+                    continue;
+                }
+                Some(mut tracked) if position.volume < tracked.quantity => {
+                    // Partial closes leave the average where it was
+                    tracked.quantity = position.volume;
+                    continue;
+                }
+                Some(tracked) if position.volume == tracked.quantity => continue,
+                Some(tracked) => {
+                    let quantity = position.volume - tracked.quantity;
+                    // The platform reports the new average; the addition filled at
+                    // whatever price brings the old average there
+                    let price = ((position.entry_price * position.volume
+                        - tracked.average_price * tracked.quantity)
+                        / quantity)
+                        .round_dp(8);
+                    PositionEntry {
+                        quantity,
+                        price,
+                        stop_loss: position.stop_loss,
+                        take_profit: position.take_profit,
+                        signal_id: self.signal_for(position),
+                        filled_at: Utc::now(),
+                    }
+                }
+            };
+            if let Some(scale_in) = self.apply(position, added).await {
+                scale_ins.push(scale_in);
+            }
+        }
+        Ok(scale_ins)
+    }
+
+    async fn apply(&self, position: &Position, entry: PositionEntry) -> Option<ScaleIn> {
+        let (stop_loss, take_profit, average_price) = {
+            let mut tracked = self.positions.get_mut(&position.id)?;
+            tracked.merge(entry.clone());
+            // The platform's average is the one its P&L and closes go by
+            tracked.average_price = position.entry_price;
+            let (stop, target) =
+                tracked.levels(&self.policy, position.stop_loss, position.take_profit);
+            (stop, target, tracked.average_price)
+        };
+        info!(
+            "Position {} on {} added {} at {}, average now {}",
+            position.id, position.symbol, entry.quantity, entry.price, average_price
+        );
+
+        if stop_loss != position.stop_loss || take_profit != position.take_profit {
+            let request = OrderModifyRequest {
+                order_id: position.order_id.clone(),
+                new_stop_loss: stop_loss,
+                new_take_profit: take_profit,
+            };
+            match self.trading_platform.modify_order(request).await {
+                Ok(result) if result.success => info!(
+                    "Levels of scaled position {} moved to stop {:?}, target {:?}",
+                    position.id, stop_loss, take_profit
+                ),
+                Ok(result) => error!(
+                    "Platform refused new levels for scaled position {}: {}",
+                    position.id, result.message
+                ),
+                Err(e) => error!(
+                    "Failed to move levels of scaled position {}: {:#}",
+                    position.id, e
+                ),
+            }
+        }
+
+        let scale_in = ScaleIn {
+            position_id: position.id,
+            ticket: position.order_id.clone(),
+            entry,
+            average_price,
+            stop_loss,
+            take_profit,
+        };
+        self.exit_logger.log_scale_in(&scale_in).await;
+        Some(scale_in)
+    }
+
+    fn signal_for(&self, position: &Position) -> Option<String> {
+        let (tracker, account_id) = self.order_tracker.as_ref()?;
+        let now = Utc::now();
+        tracker
+            .latest_sent(
+                account_id,
+                &position.symbol,
+                OrderOrigin::Entry,
+                now - Duration::minutes(5),
+                now,
+            )
+            .and_then(|order| order.signal_id)
+    }
+}
@@ -0,0 +1,494 @@
+// Shadow evaluation: alternative exit configurations simulated against live prices
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use super::break_even::BreakEvenManager;
+use super::exit_logger::ExitAuditLogger;
+use super::partial_profits::PartialProfitManager;
+use super::policy::ExitPolicy;
+use super::time_exits::{weekend_close_window, TimeBasedExitManager};
+use super::trailing_stops::TrailingStopManager;
+use super::types::*;
+use super::TradingPlatform;
+use crate::instruments::{InstrumentMetadata, InstrumentMetadataService};
+use crate::risk::trading_day::TradingDayRollover;
+
+/// An alternative exit configuration. Sections it sets replace the configuration the live
+/// managers use for a position; unset sections follow the live configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShadowVariant {
+    pub name: String,
+    #[serde(default)]
+    pub policy: ExitPolicy,
+}
+
+/// Live configuration the variants fall back to, taken from the live managers
+#[derive(Debug, Clone)]
+pub struct LiveExitConfigs {
+    pub trailing: Arc<TrailingStopManager>,
+    pub break_even: Arc<BreakEvenManager>,
+    pub partial_profit: Arc<PartialProfitManager>,
+    pub time_exit: Arc<TimeBasedExitManager>,
+}
+
+/// Configuration of one variant for one position
+#[derive(Debug, Clone)]
+struct ShadowConfig {
+    trailing: TrailingConfig,
+    break_even: BreakEvenConfig,
+    profit_taking: Option<ProfitTakingConfig>,
+    time_exit: TimeExitConfig,
+    trading_day: Option<TradingDayRollover>,
+    instrument: InstrumentMetadata,
+}
+
+/// Simulated state of one position under one variant
+#[derive(Debug, Clone)]
+struct ShadowPosition {
+    symbol: String,
+    side: UnifiedPositionSide,
+    entry_price: Decimal,
+    initial_stop: Option<Decimal>,
+    take_profit: Option<Decimal>,
+    opened_at: DateTime<Utc>,
+    initial_volume: Decimal,
+    remaining_volume: Decimal,
+    stop: Option<Decimal>,
+    trailing_active: bool,
+    break_even_done: bool,
+    targets_hit: Vec<u32>,
+    realized_pnl: Decimal,
+    last_price: Decimal,
+    exit_reason: Option<String>,
+    closed_at: Option<DateTime<Utc>>,
+    config: ShadowConfig,
+}
+
+impl ShadowPosition {
+    fn new(position: &Position, config: ShadowConfig) -> Self {
+        let volume = position.volume;
+        Self {
+            symbol: position.symbol.clone(),
+            side: position.position_type.clone(),
+            entry_price: position.entry_price,
+            initial_stop: position.stop_loss,
+            take_profit: position.take_profit,
+            opened_at: position.open_time,
+            initial_volume: volume,
+            remaining_volume: volume,
+            stop: position.stop_loss,
+            trailing_active: false,
+            break_even_done: false,
+            targets_hit: Vec::new(),
+            realized_pnl: Decimal::ZERO,
+            last_price: position.current_price,
+            exit_reason: None,
+            closed_at: None,
+            config,
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed_at.is_some()
+    }
+
+    /// Price move in the position's favour
+    fn profit(&self, price: Decimal) -> Decimal {
+        match self.side {
+            UnifiedPositionSide::Long => price - self.entry_price,
+            UnifiedPositionSide::Short => self.entry_price - price,
+        }
+    }
+
+    fn risk(&self) -> Option<Decimal> {
+        let risk = self.profit(self.initial_stop?).abs();
+        (risk > Decimal::ZERO).then_some(risk)
+    }
+
+    /// True if `level` is a tighter stop than the current one
+    fn improves_stop(&self, level: Decimal) -> bool {
+        match (self.stop, &self.side) {
+            (None, _) => true,
+            (Some(stop), UnifiedPositionSide::Long) => level > stop,
+            (Some(stop), UnifiedPositionSide::Short) => level < stop,
+        }
+    }
+
+    fn pnl(&self) -> Decimal {
+        self.realized_pnl + self.profit(self.last_price) * self.remaining_volume
+    }
+
+    fn close(&mut self, volume: Decimal, price: Decimal, reason: &str, now: DateTime<Utc>) {
+        let volume = volume.min(self.remaining_volume);
+        self.realized_pnl += self.profit(price) * volume;
+        self.remaining_volume -= volume;
+        if self.remaining_volume <= Decimal::ZERO {
+            self.remaining_volume = Decimal::ZERO;
+            self.exit_reason = Some(reason.to_string());
+            self.closed_at = Some(now);
+        }
+    }
+
+    /// Apply one price observation the way the live managers would, without orders.
+    /// Stops fill at their level; the trend override of time exits is not simulated.
+    fn observe(&mut self, price: Decimal, atr: Decimal, now: DateTime<Utc>) {
+        if self.is_closed() {
+            return;
+        }
+        self.last_price = price;
+        let profit = self.profit(price);
+
+        if let Some(stop) = self.stop {
+            if self.profit(stop) >= profit {
+                let reason = if self.trailing_active {
+                    "trailing_stop"
+                } else if self.break_even_done {
+                    "break_even"
+                } else {
+                    "stop_loss"
+                };
+                self.close(self.remaining_volume, stop, reason, now);
+                return;
+            }
+        }
+        if let Some(take_profit) = self.take_profit {
+            if profit >= self.profit(take_profit) {
+                self.close(self.remaining_volume, take_profit, "take_profit", now);
+                return;
+            }
+        }
+
+        let time_exit = &self.config.time_exit;
+        if time_exit.enabled
+            && (now - self.opened_at > time_exit.max_hold_duration
+                || (time_exit.close_before_weekend
+                    && weekend_close_window(self.config.trading_day.as_ref(), now)))
+        {
+            self.close(self.remaining_volume, price, "time_exit", now);
+            return;
+        }
+
+        if let (Some(risk), Some(targets)) = (self.risk(), self.config.profit_taking.clone()) {
+            if targets.enabled {
+                for target in targets.profit_targets {
+                    if self.targets_hit.contains(&target.level)
+                        || profit < scale_by(risk, target.risk_reward_ratio)
+                    {
+                        continue;
+                    }
+                    self.targets_hit.push(target.level);
+                    let volume = scale_by(self.initial_volume, target.close_percentage);
+                    self.close(volume, price, "partial_profit", now);
+                    if self.is_closed() {
+                        return;
+                    }
+                }
+            }
+        }
+
+        let break_even = self.config.break_even.clone();
+        if break_even.enabled && !self.break_even_done {
+            if let Some(risk) = self.risk() {
+                if profit >= scale_by(risk, break_even.trigger_ratio) {
+                    let instrument = &self.config.instrument;
+                    let buffer = instrument.pips_to_price(break_even.break_even_buffer_pips);
+                    let level = instrument.round_price(match self.side {
+                        UnifiedPositionSide::Long => self.entry_price + buffer,
+                        UnifiedPositionSide::Short => self.entry_price - buffer,
+                    });
+                    if self.improves_stop(level) {
+                        self.stop = Some(level);
+                    }
+                    self.break_even_done = true;
+                    if let Some(percent) = break_even.partial_close_percent {
+                        let volume = scale_by(self.remaining_volume, percent);
+                        self.close(volume, price, "break_even", now);
+                        if self.is_closed() {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+
+        let trailing = &self.config.trailing;
+        if !self.trailing_active && profit >= trailing.activation_threshold {
+            self.trailing_active = true;
+        }
+        if self.trailing_active {
+            let distance = scale_by(atr, trailing.atr_multiplier)
+                .max(trailing.min_trail_distance)
+                .min(trailing.max_trail_distance);
+            let level = self.config.instrument.round_price(match self.side {
+                UnifiedPositionSide::Long => price - distance,
+                UnifiedPositionSide::Short => price + distance,
+            });
+            if self.improves_stop(level) {
+                self.stop = Some(level);
+            }
+        }
+    }
+}
+
+/// What the live position has made so far, priced the same way as the shadows
+#[derive(Debug, Clone)]
+struct LivePosition {
+    symbol: String,
+    side: UnifiedPositionSide,
+    entry_price: Decimal,
+    last_price: Decimal,
+    volume: Decimal,
+    closed_at: Option<DateTime<Utc>>,
+}
+
+impl LivePosition {
+    fn profit(&self, price: Decimal) -> Decimal {
+        match self.side {
+            UnifiedPositionSide::Long => price - self.entry_price,
+            UnifiedPositionSide::Short => self.entry_price - price,
+        }
+    }
+}
+
+/// Runs each variant on every open position alongside live exit management and
+/// reports the P&L the position would have made under it. Never places orders.
+#[derive(Debug)]
+pub struct ShadowExitEvaluator {
+    trading_platform: Arc<dyn TradingPlatform>,
+    exit_logger: Arc<ExitAuditLogger>,
+    variants: Vec<ShadowVariant>,
+    live_configs: Option<LiveExitConfigs>,
+    live: DashMap<PositionId, LivePosition>,
+    shadows: DashMap<(PositionId, String), ShadowPosition>,
+    instruments: Arc<InstrumentMetadataService>,
+}
+
+impl ShadowExitEvaluator {
+    pub fn new(
+        trading_platform: Arc<dyn TradingPlatform>,
+        exit_logger: Arc<ExitAuditLogger>,
+        variants: Vec<ShadowVariant>,
+    ) -> Self {
+        Self {
+            trading_platform,
+            exit_logger,
+            variants,
+            live_configs: None,
+            live: DashMap::new(),
+            shadows: DashMap::new(),
+            instruments: Arc::new(InstrumentMetadataService::new()),
+        }
+    }
+
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentMetadataService>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+
+    /// Fall back to the live managers' configuration instead of the defaults
+    pub fn with_live_configs(mut self, live_configs: LiveExitConfigs) -> Self {
+        self.live_configs = Some(live_configs);
+        self
+    }
+
+    /// How long a variant is followed after the live position closed
+    pub fn follow_after_close() -> Duration {
+        Duration::hours(24)
+    }
+
+    pub fn variants(&self) -> &[ShadowVariant] {
+        &self.variants
+    }
+
+    fn config_for(&self, variant: &ShadowVariant, position: &Position) -> ShadowConfig {
+        let live = self.live_configs.as_ref();
+        let policy = &variant.policy;
+        ShadowConfig {
+            trailing: policy.trailing.clone().unwrap_or_else(|| {
+                live.map(|l| l.trailing.config_for(position))
+                    .unwrap_or_default()
+            }),
+            break_even: policy.break_even.clone().unwrap_or_else(|| {
+                live.map(|l| l.break_even.config_for(position))
+                    .unwrap_or_default()
+            }),
+            profit_taking: policy
+                .profit_taking
+                .clone()
+                .or_else(|| live.and_then(|l| l.partial_profit.config_for(position))),
+            time_exit: policy.time_exit.clone().unwrap_or_else(|| {
+                live.map(|l| l.time_exit.config_for(position))
+                    .unwrap_or_default()
+            }),
+            trading_day: live.and_then(|l| l.time_exit.trading_day().copied()),
+            instrument: self.instruments.get(&position.symbol),
+        }
+    }
+
+    /// Advance every variant by one price observation and publish the comparisons
+    pub async fn evaluate(&self) -> Result<()> {
+        if self.variants.is_empty() {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let positions = self.trading_platform.get_positions().await?;
+        let open: HashSet<PositionId> = positions.iter().map(|p| p.id).collect();
+
+        for position in &positions {
+            let volume = position.volume;
+            self.live
+                .entry(position.id)
+                .and_modify(|live| live.volume = volume)
+                .or_insert_with(|| LivePosition {
+                    symbol: position.symbol.clone(),
+                    side: position.position_type.clone(),
+                    entry_price: position.entry_price,
+                    last_price: position.current_price,
+                    volume,
+                    closed_at: None,
+                });
+            for variant in &self.variants {
+                self.shadows
+                    .entry((position.id, variant.name.clone()))
+                    .or_insert_with(|| {
+                        ShadowPosition::new(position, self.config_for(variant, position))
+                    });
+            }
+        }
+        for mut live in self.live.iter_mut() {
+            if live.closed_at.is_none() && !open.contains(live.key()) {
+                live.closed_at = Some(now);
+            }
+        }
+
+        let symbols: HashSet<String> = self.live.iter().map(|l| l.symbol.clone()).collect();
+        let mut prices: HashMap<String, (Decimal, Decimal)> = HashMap::new();
+        for symbol in symbols {
+            match self.trading_platform.get_market_data(&symbol).await {
+                Ok(data) => {
+                    // Same ATR proxy as the live trailing stop manager
+                    prices.insert(symbol, (data.mid(), data.spread * Decimal::TWO));
+                }
+                Err(e) => warn!("No price for {} to evaluate exit variants: {}", symbol, e),
+            }
+        }
+
+        for mut live in self.live.iter_mut() {
+            if live.closed_at.is_none() {
+                if let Some((price, _)) = prices.get(&live.symbol) {
+                    live.last_price = *price;
+                }
+            }
+        }
+
+        for mut shadow in self.shadows.iter_mut() {
+            let position_id = shadow.key().0;
+            let live_closed_at = self.live.get(&position_id).and_then(|l| l.closed_at);
+            if shadow.is_closed() {
+                continue;
+            }
+            if let Some(closed_at) = live_closed_at {
+                if now - closed_at > Self::follow_after_close() {
+                    let (volume, price) = (shadow.remaining_volume, shadow.last_price);
+                    shadow.close(volume, price, "follow_window_ended", now);
+                    continue;
+                }
+            }
+            if let Some((price, atr)) = prices.get(&shadow.symbol) {
+                shadow.observe(*price, *atr, now);
+                if let Some(reason) = &shadow.exit_reason {
+                    debug!(
+                        "Variant {} would have closed {} ({}) with P&L {}",
+                        shadow.key().1,
+                        position_id,
+                        reason,
+                        shadow.pnl()
+                    );
+                }
+            }
+        }
+
+        self.publish(now).await;
+        self.prune();
+        Ok(())
+    }
+
+    /// P&L of the live position: closes reported by the platform plus what is still open
+    async fn live_pnl(&self, position_id: PositionId, live: &LivePosition) -> Decimal {
+        let closes = self
+            .exit_logger
+            .get_position_closes(&position_id.to_string())
+            .await;
+        let realized: Decimal = closes
+            .iter()
+            .map(|close| live.profit(close.close_price) * close.closed_quantity)
+            .sum();
+        if live.closed_at.is_some() {
+            if closes.is_empty() {
+                // The close was not reported; the last price seen is the best estimate
+                return live.profit(live.last_price) * live.volume;
+            }
+            return realized;
+        }
+        realized + live.profit(live.last_price) * live.volume
+    }
+
+    async fn publish(&self, now: DateTime<Utc>) {
+        let shadows: Vec<((PositionId, String), ShadowPosition)> = self
+            .shadows
+            .iter()
+            .map(|s| (s.key().clone(), s.value().clone()))
+            .collect();
+
+        for ((position_id, variant), shadow) in shadows {
+            let Some(live) = self.live.get(&position_id).map(|l| l.clone()) else {
+                continue;
+            };
+            let live_pnl = self.live_pnl(position_id, &live).await;
+            let shadow_pnl = shadow.pnl();
+            self.exit_logger
+                .record_shadow_result(ShadowExitResult {
+                    position_id,
+                    symbol: shadow.symbol.clone(),
+                    variant,
+                    live_pnl,
+                    shadow_pnl,
+                    pnl_difference: shadow_pnl - live_pnl,
+                    live_closed: live.closed_at.is_some(),
+                    shadow_closed: shadow.is_closed(),
+                    shadow_exit_reason: shadow.exit_reason.clone(),
+                    updated_at: now,
+                })
+                .await;
+        }
+    }
+
+    /// Forget positions once the live position and every variant are done with them
+    fn prune(&self) {
+        let finished: Vec<PositionId> = self
+            .live
+            .iter()
+            .filter(|live| live.closed_at.is_some())
+            .map(|live| *live.key())
+            .filter(|id| {
+                !self
+                    .shadows
+                    .iter()
+                    .any(|s| s.key().0 == *id && !s.is_closed())
+            })
+            .collect();
+        for position_id in finished {
+            self.live.remove(&position_id);
+            self.shadows.retain(|key, _| key.0 != position_id);
+            info!("Shadow evaluation of {} complete", position_id);
+        }
+    }
+}
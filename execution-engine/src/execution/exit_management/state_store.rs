@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+
+use super::partial_profits::PositionTargetStatus;
+use super::policy::ExitPolicy;
+use super::types::*;
+
+/// Exit manager state for one position that a restart would otherwise lose
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionExitCheckpoint {
+    pub position_id: PositionId,
+    pub trailing_stop: Option<ActiveTrail>,
+    pub break_even_active: bool,
+    pub profit_targets: Option<PositionTargetStatus>,
+    pub time_exit_warned: bool,
+    #[serde(default)]
+    pub exit_policy: Option<ExitPolicy>,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl PositionExitCheckpoint {
+    /// True if the manager state differs, ignoring when it was saved
+    pub fn differs_from(&self, other: &PositionExitCheckpoint) -> bool {
+        self.trailing_stop != other.trailing_stop
+            || self.break_even_active != other.break_even_active
+            || self.profit_targets != other.profit_targets
+            || self.time_exit_warned != other.time_exit_warned
+            || self.exit_policy != other.exit_policy
+    }
+}
+
+/// Durable exit manager state keyed by position id
+#[async_trait]
+pub trait ExitStateStore: Send + Sync + std::fmt::Debug {
+    async fn save(&self, checkpoint: &PositionExitCheckpoint) -> Result<()>;
+    async fn remove(&self, position_id: PositionId) -> Result<()>;
+    async fn load_all(&self) -> Result<Vec<PositionExitCheckpoint>>;
+}
+
+/// Keeps all checkpoints of one account in a JSON file, rewritten atomically on change
+#[derive(Debug)]
+pub struct FileExitStateStore {
+    path: PathBuf,
+    // Loaded on first use so a save never overwrites checkpoints it has not read
+    checkpoints: Mutex<Option<HashMap<PositionId, PositionExitCheckpoint>>>,
+}
+
+impl FileExitStateStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            checkpoints: Mutex::new(None),
+        }
+    }
+
+    async fn read_file(&self) -> Result<HashMap<PositionId, PositionExitCheckpoint>> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let checkpoints: Vec<PositionExitCheckpoint> = serde_json::from_str(&content)
+            .with_context(|| format!("Corrupt exit state file {}", self.path.display()))?;
+        Ok(checkpoints
+            .into_iter()
+            .map(|c| (c.position_id, c))
+            .collect())
+    }
+
+    async fn write_file(
+        &self,
+        checkpoints: &HashMap<PositionId, PositionExitCheckpoint>,
+    ) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let checkpoints: Vec<&PositionExitCheckpoint> = checkpoints.values().collect();
+        let content = serde_json::to_string_pretty(&checkpoints)?;
+
+        // Write then rename so a crash mid-write leaves the previous state intact
+        let temp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&temp_path, content).await?;
+        tokio::fs::rename(&temp_path, &self.path)
+            .await
+            .with_context(|| {
+                format!("Failed to replace exit state file {}", self.path.display())
+            })?;
+        Ok(())
+    }
+
+    async fn update<F>(&self, change: F) -> Result<()>
+    where
+        F: FnOnce(&mut HashMap<PositionId, PositionExitCheckpoint>),
+    {
+        let mut guard = self.checkpoints.lock().await;
+        if guard.is_none() {
+            *guard = Some(self.read_file().await?);
+        }
+        let checkpoints = guard.as_mut().expect("checkpoints loaded above");
+        change(checkpoints);
+        self.write_file(checkpoints).await
+    }
+}
+
+#[async_trait]
+impl ExitStateStore for FileExitStateStore {
+    async fn save(&self, checkpoint: &PositionExitCheckpoint) -> Result<()> {
+        self.update(|checkpoints| {
+            checkpoints.insert(checkpoint.position_id, checkpoint.clone());
+        })
+        .await
+    }
+
+    async fn remove(&self, position_id: PositionId) -> Result<()> {
+        self.update(|checkpoints| {
+            checkpoints.remove(&position_id);
+        })
+        .await
+    }
+
+    async fn load_all(&self) -> Result<Vec<PositionExitCheckpoint>> {
+        let mut guard = self.checkpoints.lock().await;
+        let checkpoints = self.read_file().await?;
+        let loaded = checkpoints.values().cloned().collect();
+        *guard = Some(checkpoints);
+        Ok(loaded)
+    }
+}
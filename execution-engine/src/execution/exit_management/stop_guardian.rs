@@ -0,0 +1,241 @@
+// Stop-loss integrity checks. A stop modification the platform acknowledged can
+// still be lost, so open positions are re-read and their stops compared with the
+// level exit management last set.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use dashmap::DashMap;
+use risk_types::AlertLevel;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use super::types::{OrderModifyRequest, Position, PositionId};
+use super::ExitManagementSystem;
+use crate::alerting::{Alert, AlertGateway};
+
+/// Alert type raised for positions left without a stop-loss
+pub const NAKED_POSITION_ALERT_TYPE: &str = "naked_position";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StopGuardianConfig {
+    pub enabled: bool,
+    pub check_interval_secs: u64,
+    /// How long a position may sit without a stop before a critical alert
+    pub grace_period_secs: i64,
+    /// Stops within this many pips of the expected level are left alone
+    pub tolerance_pips: Decimal,
+}
+
+impl Default for StopGuardianConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval_secs: 30,
+            grace_period_secs: 60,
+            tolerance_pips: Decimal::new(5, 1),
+        }
+    }
+}
+
+/// Outcome of one pass over an account's positions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StopCheckReport {
+    pub checked: usize,
+    /// Positions whose stop was missing or off and was sent again
+    pub reapplied: Vec<PositionId>,
+    /// Positions seen without any stop on the platform
+    pub naked: Vec<PositionId>,
+}
+
+/// Verifies that every open position carries the stop exit management expects,
+/// re-applying missing or moved stops
+pub struct StopLossGuardian {
+    config: StopGuardianConfig,
+    gateway: Option<Arc<AlertGateway>>,
+    /// Last stop seen on the platform per position, restored if it disappears
+    observed_stops: DashMap<PositionId, Decimal>,
+    naked_since: DashMap<PositionId, DateTime<Utc>>,
+}
+
+impl StopLossGuardian {
+    pub fn new(config: StopGuardianConfig) -> Self {
+        Self {
+            config,
+            gateway: None,
+            observed_stops: DashMap::new(),
+            naked_since: DashMap::new(),
+        }
+    }
+
+    /// Raise positions left naked past the grace period through `gateway`
+    pub fn with_alert_gateway(mut self, gateway: Arc<AlertGateway>) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
+    pub fn config(&self) -> &StopGuardianConfig {
+        &self.config
+    }
+
+    /// When the position was first seen without a stop, if it still has none
+    pub fn naked_since(&self, position_id: PositionId) -> Option<DateTime<Utc>> {
+        self.naked_since.get(&position_id).map(|since| *since)
+    }
+
+    /// Check the stops of `account_id`'s open positions against `system`
+    pub async fn verify(
+        &self,
+        account_id: &str,
+        system: &ExitManagementSystem,
+    ) -> Result<StopCheckReport> {
+        let positions = system.get_trading_platform().get_positions().await?;
+        let now = Utc::now();
+        let mut report = StopCheckReport {
+            checked: positions.len(),
+            ..Default::default()
+        };
+
+        let protected: Vec<_> = system.get_news_protection().get_protected_positions();
+        for position in &positions {
+            let expected = protected
+                .iter()
+                .find(|(id, _)| *id == position.id)
+                .map(|(_, protection)| protection.protected_stop)
+                .or_else(|| {
+                    system
+                        .get_trailing_stop_manager()
+                        .get_active_trail(position.id)
+                        // The platform moves native trails, so their level goes stale
+                        .filter(|trail| trail.native_order_id.is_none())
+                        .map(|trail| trail.trail_level)
+                })
+                // Without a managed level, a stop that vanished is put back where it was
+                .or_else(|| match position.stop_loss {
+                    Some(_) => None,
+                    None => self.observed_stops.get(&position.id).map(|stop| *stop),
+                });
+
+            match position.stop_loss {
+                Some(stop) => {
+                    self.observed_stops.insert(position.id, stop);
+                    if self.naked_since.remove(&position.id).is_some() {
+                        self.resolve(position.id);
+                    }
+                }
+                None => {
+                    let since = *self.naked_since.entry(position.id).or_insert(now);
+                    report.naked.push(position.id);
+                    if now - since >= ChronoDuration::seconds(self.config.grace_period_secs) {
+                        self.alert_naked(account_id, position, since, now).await;
+                    }
+                }
+            }
+
+            let Some(expected) = expected else {
+                continue;
+            };
+            let tolerance = system
+                .get_instruments()
+                .pips_to_price(&position.symbol, self.config.tolerance_pips);
+            let in_place = position
+                .stop_loss
+                .is_some_and(|stop| (stop - expected).abs() <= tolerance);
+            if !in_place && self.reapply(position, expected, system).await {
+                report.reapplied.push(position.id);
+            }
+        }
+
+        // Forget positions that have closed
+        let open: HashSet<PositionId> = positions.iter().map(|p| p.id).collect();
+        self.observed_stops.retain(|id, _| open.contains(id));
+        let closed: Vec<PositionId> = self
+            .naked_since
+            .iter()
+            .map(|entry| *entry.key())
+            .filter(|id| !open.contains(id))
+            .collect();
+        for position_id in closed {
+            self.naked_since.remove(&position_id);
+            self.resolve(position_id);
+        }
+
+        Ok(report)
+    }
+
+    async fn reapply(
+        &self,
+        position: &Position,
+        expected: Decimal,
+        system: &ExitManagementSystem,
+    ) -> bool {
+        warn!(
+            "Position {} on {} has stop {:?}, expected {}; re-applying",
+            position.id, position.symbol, position.stop_loss, expected
+        );
+        let request = OrderModifyRequest {
+            order_id: position.order_id.clone(),
+            new_stop_loss: Some(expected),
+            new_take_profit: position.take_profit,
+        };
+        match system.get_trading_platform().modify_order(request).await {
+            Ok(result) if result.success => true,
+            Ok(result) => {
+                error!(
+                    "Platform rejected stop re-application for position {}: {}",
+                    position.id, result.message
+                );
+                false
+            }
+            Err(e) => {
+                error!(
+                    "Failed to re-apply stop for position {}: {:#}",
+                    position.id, e
+                );
+                false
+            }
+        }
+    }
+
+    async fn alert_naked(
+        &self,
+        account_id: &str,
+        position: &Position,
+        since: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) {
+        error!(
+            "Position {} on {} has had no stop-loss since {}",
+            position.id, position.symbol, since
+        );
+        if let Some(gateway) = &self.gateway {
+            gateway
+                .submit(Alert {
+                    alert_type: NAKED_POSITION_ALERT_TYPE.to_string(),
+                    key: position.id.to_string(),
+                    severity: AlertLevel::Critical,
+                    account_id: Uuid::parse_str(account_id).ok(),
+                    message: format!(
+                        "{} {:?} {} on account {} has had no stop-loss for {}s",
+                        position.symbol,
+                        position.position_type,
+                        position.volume,
+                        account_id,
+                        (now - since).num_seconds()
+                    ),
+                    raised_at: now,
+                })
+                .await;
+        }
+    }
+
+    fn resolve(&self, position_id: PositionId) {
+        if let Some(gateway) = &self.gateway {
+            gateway.resolve(NAKED_POSITION_ALERT_TYPE, &position_id.to_string());
+        }
+    }
+}
@@ -2,107 +2,21 @@ pub mod test_break_even;
 pub mod test_platform_integration;
 pub mod test_trailing_stops;
 
-use super::{types::*, TradingPlatform};
+use super::{types::*, ExitManagementPlatformAdapter};
+use crate::testing::MockTradingPlatform;
 use chrono::{Duration, Utc};
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
 use uuid::Uuid;
 
-// Mock trading platform for testing
-#[derive(Debug, Clone)]
-pub struct MockTradingPlatform {
-    positions: Vec<Position>,
-    market_data: std::collections::HashMap<String, MarketData>,
-}
-
-impl MockTradingPlatform {
-    pub fn new() -> Self {
-        let mut market_data = std::collections::HashMap::new();
-
-        // Add default market data
-        market_data.insert(
-            "EURUSD".to_string(),
-            MarketData {
-                symbol: "EURUSD".to_string(),
-                bid: 1.0800,
-                ask: 1.0802,
-                spread: 0.0002,
-                timestamp: Utc::now(),
-            },
-        );
-
-        market_data.insert(
-            "GBPUSD".to_string(),
-            MarketData {
-                symbol: "GBPUSD".to_string(),
-                bid: 1.2500,
-                ask: 1.2502,
-                spread: 0.0002,
-                timestamp: Utc::now(),
-            },
-        );
-
-        Self {
-            positions: Vec::new(),
-            market_data,
-        }
-    }
-
-    pub fn add_position(&mut self, position: Position) {
-        self.positions.push(position);
-    }
-
-    pub fn update_market_data(&mut self, symbol: String, market_data: MarketData) {
-        self.market_data.insert(symbol, market_data);
-    }
-}
-
-#[async_trait::async_trait]
-impl TradingPlatform for MockTradingPlatform {
-    async fn get_positions(&self) -> anyhow::Result<Vec<Position>> {
-        Ok(self.positions.clone())
-    }
-
-    async fn get_market_data(&self, symbol: &str) -> anyhow::Result<MarketData> {
-        self.market_data
-            .get(symbol)
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!("Market data not found for symbol: {}", symbol))
-    }
-
-    async fn modify_order(
-        &self,
-        _request: OrderModifyRequest,
-    ) -> anyhow::Result<OrderModifyResult> {
-        Ok(OrderModifyResult {
-            order_id: "test_order".to_string(),
-            success: true,
-            message: "Order modified successfully".to_string(),
-        })
-    }
-
-    async fn close_position(
-        &self,
-        _request: ClosePositionRequest,
-    ) -> anyhow::Result<ClosePositionResult> {
-        Ok(ClosePositionResult {
-            position_id: Uuid::new_v4(),
-            close_price: 1.0801,
-            realized_pnl: Some(Decimal::from_f64_retain(10.0).unwrap()),
-            close_time: Utc::now(),
-        })
-    }
-
-    async fn close_position_partial(
-        &self,
-        _request: PartialCloseRequest,
-    ) -> anyhow::Result<ClosePositionResult> {
-        Ok(ClosePositionResult {
-            position_id: Uuid::new_v4(),
-            close_price: 1.0801,
-            realized_pnl: Some(Decimal::from_f64_retain(5.0).unwrap()),
-            close_time: Utc::now(),
-        })
-    }
+/// Shared mock platform behind the exit-management adapter, quoting EURUSD and GBPUSD
+pub fn mock_platform() -> Arc<ExitManagementPlatformAdapter> {
+    Arc::new(ExitManagementPlatformAdapter::new(Arc::new(
+        MockTradingPlatform::new("exit_management_tests")
+            .with_quote("EURUSD", dec!(1.0800), dec!(1.0802))
+            .with_quote("GBPUSD", dec!(1.2500), dec!(1.2502)),
+    )))
 }
 
 // Helper function to create a test position
@@ -113,13 +27,13 @@ pub fn create_test_position() -> Position {
         symbol: "EURUSD".to_string(),
         position_type: UnifiedPositionSide::Long,
         volume: Decimal::from_f64_retain(1.0).unwrap(),
-        entry_price: 1.0800,
-        current_price: 1.0820,
-        stop_loss: Some(1.0780),
-        take_profit: Some(1.0850),
-        unrealized_pnl: 20.0,
-        swap: 0.0,
-        commission: 5.0,
+        entry_price: dec!(1.0800),
+        current_price: dec!(1.0820),
+        stop_loss: Some(dec!(1.0780)),
+        take_profit: Some(dec!(1.0850)),
+        unrealized_pnl: dec!(20.0),
+        swap: dec!(0.0),
+        commission: dec!(5.0),
         open_time: Utc::now()
             - Duration::from_std(std::time::Duration::from_secs(2 * 3600)).unwrap(),
         magic_number: Some(12345),
@@ -130,9 +44,9 @@ pub fn create_test_position() -> Position {
 pub fn create_test_position_with_params(
     symbol: &str,
     position_type: UnifiedPositionSide,
-    entry_price: f64,
-    current_price: f64,
-    stop_loss: Option<f64>,
+    entry_price: Decimal,
+    current_price: Decimal,
+    stop_loss: Option<Decimal>,
     age_hours: i64,
 ) -> Position {
     Position {
@@ -144,13 +58,13 @@ pub fn create_test_position_with_params(
         entry_price,
         current_price,
         stop_loss,
-        take_profit: Some(entry_price + 0.0050), // 50 pips TP
+        take_profit: Some(entry_price + dec!(0.0050)), // 50 pips TP
         unrealized_pnl: match position_type {
-            UnifiedPositionSide::Long => (current_price - entry_price) * 10000.0, // Convert to pips
-            UnifiedPositionSide::Short => (entry_price - current_price) * 10000.0,
+            UnifiedPositionSide::Long => (current_price - entry_price) * dec!(10000), // Convert to pips
+            UnifiedPositionSide::Short => (entry_price - current_price) * dec!(10000),
         },
-        swap: 0.0,
-        commission: 5.0,
+        swap: dec!(0.0),
+        commission: dec!(5.0),
         open_time: Utc::now()
             - Duration::from_std(std::time::Duration::from_hours(age_hours as u64)).unwrap(),
         magic_number: Some(12345),
@@ -1,11 +1,12 @@
 use super::*;
 use crate::execution::exit_management::types::*;
 use crate::execution::exit_management::{BreakEvenManager, ExitAuditLogger};
+use rust_decimal_macros::dec;
 use std::sync::Arc;
 
 #[tokio::test]
 async fn test_break_even_trigger_detection() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let break_even_manager = BreakEvenManager::new(mock_platform.clone(), exit_logger);
 
@@ -13,9 +14,9 @@ async fn test_break_even_trigger_detection() {
     let position = create_test_position_with_params(
         "EURUSD",
         UnifiedPositionSide::Long,
-        1.0800,       // Entry
-        1.0820,       // Current (+20 pips)
-        Some(1.0780), // Stop (-20 pips), so 1:1 R:R
+        dec!(1.0800),       // Entry
+        dec!(1.0820),       // Current (+20 pips)
+        Some(dec!(1.0780)), // Stop (-20 pips), so 1:1 R:R
         1,
     );
 
@@ -34,7 +35,7 @@ async fn test_break_even_trigger_detection() {
 
 #[tokio::test]
 async fn test_break_even_insufficient_profit() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let break_even_manager = BreakEvenManager::new(mock_platform.clone(), exit_logger);
 
@@ -42,9 +43,9 @@ async fn test_break_even_insufficient_profit() {
     let position = create_test_position_with_params(
         "EURUSD",
         UnifiedPositionSide::Long,
-        1.0800,       // Entry
-        1.0810,       // Current (+10 pips)
-        Some(1.0780), // Stop (-20 pips), so only 0.5:1 R:R
+        dec!(1.0800),       // Entry
+        dec!(1.0810),       // Current (+10 pips)
+        Some(dec!(1.0780)), // Stop (-20 pips), so only dec!(0.5):1 R:R
         1,
     );
 
@@ -58,7 +59,7 @@ async fn test_break_even_insufficient_profit() {
 
 #[tokio::test]
 async fn test_break_even_short_position() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let break_even_manager = BreakEvenManager::new(mock_platform.clone(), exit_logger);
 
@@ -66,9 +67,9 @@ async fn test_break_even_short_position() {
     let position = create_test_position_with_params(
         "EURUSD",
         UnifiedPositionSide::Short,
-        1.0800,       // Entry
-        1.0780,       // Current (-20 pips profit for short)
-        Some(1.0820), // Stop (+20 pips risk), so 1:1 R:R
+        dec!(1.0800),       // Entry
+        dec!(1.0780),       // Current (-20 pips profit for short)
+        Some(dec!(1.0820)), // Stop (+20 pips risk), so 1:1 R:R
         1,
     );
 
@@ -82,7 +83,7 @@ async fn test_break_even_short_position() {
 
 #[tokio::test]
 async fn test_break_even_no_stop_loss() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let break_even_manager = BreakEvenManager::new(mock_platform.clone(), exit_logger);
 
@@ -90,8 +91,8 @@ async fn test_break_even_no_stop_loss() {
     let position = create_test_position_with_params(
         "EURUSD",
         UnifiedPositionSide::Long,
-        1.0800,
-        1.0820,
+        dec!(1.0800),
+        dec!(1.0820),
         None, // No stop loss
         1,
     );
@@ -106,7 +107,7 @@ async fn test_break_even_no_stop_loss() {
 
 #[tokio::test]
 async fn test_break_even_tracking() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let break_even_manager = BreakEvenManager::new(mock_platform.clone(), exit_logger);
 
@@ -122,7 +123,7 @@ async fn test_break_even_tracking() {
 
 #[tokio::test]
 async fn test_break_even_stats() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let break_even_manager = BreakEvenManager::new(mock_platform.clone(), exit_logger);
 
@@ -135,14 +136,15 @@ async fn test_break_even_stats() {
 
 #[tokio::test]
 async fn test_break_even_configuration() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let mut break_even_manager = BreakEvenManager::new(mock_platform.clone(), exit_logger);
 
     let custom_config = BreakEvenConfig {
-        trigger_ratio: 1.5,           // Require 1.5:1 R:R instead of 1:1
-        break_even_buffer_pips: 10.0, // 10 pip buffer
+        trigger_ratio: 1.5,                 // Require 1.5:1 R:R instead of 1:1
+        break_even_buffer_pips: dec!(10.0), // 10 pip buffer
         enabled: true,
+        ..BreakEvenConfig::default()
     };
 
     break_even_manager.configure_symbol("EURUSD".to_string(), custom_config);
@@ -151,9 +153,9 @@ async fn test_break_even_configuration() {
     let position = create_test_position_with_params(
         "EURUSD",
         UnifiedPositionSide::Long,
-        1.0800,       // Entry
-        1.0820,       // Current (+20 pips)
-        Some(1.0780), // Stop (-20 pips), so 1:1 R:R
+        dec!(1.0800),       // Entry
+        dec!(1.0820),       // Current (+20 pips)
+        Some(dec!(1.0780)), // Stop (-20 pips), so 1:1 R:R
         1,
     );
 
@@ -1,7 +1,6 @@
-use async_trait::async_trait;
 use chrono::Utc;
-use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -9,288 +8,16 @@ use super::*;
 use crate::execution::exit_management::{
     ExitManagementIntegration, ExitManagementSystem, PlatformAdapterFactory,
 };
-use crate::platforms::abstraction::events::PlatformEvent;
-use crate::platforms::abstraction::interfaces::EventFilter;
-use crate::platforms::abstraction::{
-    ITradingPlatform, OrderModification, PlatformError, UnifiedMarketData, UnifiedOrderResponse,
-    UnifiedOrderSide, UnifiedOrderStatus, UnifiedOrderType, UnifiedPosition, UnifiedPositionSide,
-};
-
-/// Mock platform that implements the full ITradingPlatform interface for integration testing
-struct MockIntegratedPlatform {
-    positions: std::sync::RwLock<Vec<UnifiedPosition>>,
-    market_data: std::sync::RwLock<HashMap<String, UnifiedMarketData>>,
-    order_modifications: std::sync::RwLock<Vec<(String, OrderModification)>>,
-}
-
-impl MockIntegratedPlatform {
-    fn new() -> Self {
-        let mut market_data = HashMap::new();
-
-        // Add default market data
-        market_data.insert(
-            "EURUSD".to_string(),
-            UnifiedMarketData {
-                symbol: "EURUSD".to_string(),
-                bid: Decimal::from_f64_retain(1.0799).unwrap(),
-                ask: Decimal::from_f64_retain(1.0801).unwrap(),
-                spread: Decimal::from_f64_retain(0.0002).unwrap(),
-                last_price: Some(Decimal::from_f64_retain(1.0800).unwrap()),
-                volume: Some(Decimal::from(1000)),
-                high: Some(Decimal::from_f64_retain(1.0850).unwrap()),
-                low: Some(Decimal::from_f64_retain(1.0750).unwrap()),
-                timestamp: Utc::now(),
-                session: Some(crate::platforms::abstraction::TradingSession::Regular),
-                platform_specific: HashMap::new(),
-            },
-        );
-
-        market_data.insert(
-            "GBPUSD".to_string(),
-            UnifiedMarketData {
-                symbol: "GBPUSD".to_string(),
-                bid: Decimal::from_f64_retain(1.2499).unwrap(),
-                ask: Decimal::from_f64_retain(1.2501).unwrap(),
-                spread: Decimal::from_f64_retain(0.0002).unwrap(),
-                last_price: Some(Decimal::from_f64_retain(1.2500).unwrap()),
-                volume: Some(Decimal::from(800)),
-                high: Some(Decimal::from_f64_retain(1.2550).unwrap()),
-                low: Some(Decimal::from_f64_retain(1.2450).unwrap()),
-                timestamp: Utc::now(),
-                session: Some(crate::platforms::abstraction::TradingSession::Regular),
-                platform_specific: HashMap::new(),
-            },
-        );
-
-        Self {
-            positions: std::sync::RwLock::new(Vec::new()),
-            market_data: std::sync::RwLock::new(market_data),
-            order_modifications: std::sync::RwLock::new(Vec::new()),
-        }
-    }
-
-    fn add_position(&self, position: UnifiedPosition) {
-        let mut positions = self.positions.write().unwrap();
-        positions.push(position);
-    }
-
-    fn update_position_price(&self, position_id: &str, new_price: Decimal) {
-        let mut positions = self.positions.write().unwrap();
-        for position in positions.iter_mut() {
-            if position.position_id == position_id {
-                position.current_price = new_price;
-                // Recalculate unrealized PnL
-                let price_diff = match position.side {
-                    UnifiedPositionSide::Long => new_price - position.entry_price,
-                    UnifiedPositionSide::Short => position.entry_price - new_price,
-                };
-                position.unrealized_pnl = price_diff * position.quantity;
-                break;
-            }
-        }
-    }
-
-    fn get_order_modifications(&self) -> Vec<(String, OrderModification)> {
-        self.order_modifications.read().unwrap().clone()
-    }
-}
-
-#[async_trait]
-impl ITradingPlatform for MockIntegratedPlatform {
-    fn platform_type(&self) -> crate::platforms::PlatformType {
-        crate::platforms::PlatformType::MetaTrader4
-    }
-
-    fn platform_name(&self) -> &str {
-        "MockIntegratedPlatform"
-    }
-    fn platform_version(&self) -> &str {
-        "1.0.0"
-    }
-
-    async fn connect(&mut self) -> Result<(), PlatformError> {
-        Ok(())
-    }
-    async fn disconnect(&mut self) -> Result<(), PlatformError> {
-        Ok(())
-    }
-    async fn is_connected(&self) -> bool {
-        true
-    }
-    async fn ping(&self) -> Result<u64, PlatformError> {
-        Ok(10)
-    }
-
-    async fn place_order(
-        &self,
-        _order: crate::platforms::abstraction::UnifiedOrder,
-    ) -> Result<UnifiedOrderResponse, PlatformError> {
-        unimplemented!("place_order not needed for exit management tests")
-    }
-
-    async fn modify_order(
-        &self,
-        order_id: &str,
-        modifications: OrderModification,
-    ) -> Result<UnifiedOrderResponse, PlatformError> {
-        // Record the modification
-        {
-            let mut mods = self.order_modifications.write().unwrap();
-            mods.push((order_id.to_string(), modifications.clone()));
-        }
-
-        // Simulate successful modification
-        Ok(UnifiedOrderResponse {
-            platform_order_id: order_id.to_string(),
-            client_order_id: order_id.to_string(),
-            status: UnifiedOrderStatus::New,
-            symbol: "EURUSD".to_string(),
-            side: UnifiedOrderSide::Buy,
-            order_type: UnifiedOrderType::Market,
-            quantity: Decimal::from(1),
-            filled_quantity: Decimal::ZERO,
-            remaining_quantity: Decimal::from(1),
-            price: None,
-            average_fill_price: modifications.price,
-            commission: Some(Decimal::from_f64_retain(2.0).unwrap()),
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-            filled_at: None,
-            platform_specific: HashMap::new(),
-        })
-    }
-
-    async fn cancel_order(&self, _order_id: &str) -> Result<(), PlatformError> {
-        Ok(())
-    }
-
-    async fn get_order(&self, _order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
-        unimplemented!("get_order not needed for exit management tests")
-    }
-
-    async fn get_orders(
-        &self,
-        _filter: Option<crate::platforms::abstraction::OrderFilter>,
-    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
-        Ok(Vec::new())
-    }
-
-    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
-        Ok(self.positions.read().unwrap().clone())
-    }
-
-    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
-        let positions = self.positions.read().unwrap();
-        Ok(positions.iter().find(|p| p.symbol == symbol).cloned())
-    }
-
-    async fn close_position(
-        &self,
-        symbol: &str,
-        quantity: Option<Decimal>,
-    ) -> Result<UnifiedOrderResponse, PlatformError> {
-        let positions = self.positions.read().unwrap();
-        let position = positions
-            .iter()
-            .find(|p| p.symbol == symbol)
-            .ok_or_else(|| PlatformError::PositionNotFound {
-                symbol: symbol.to_string(),
-            })?;
-
-        let close_quantity = quantity.unwrap_or(position.quantity);
-
-        Ok(UnifiedOrderResponse {
-            platform_order_id: format!("close_{}", position.position_id),
-            client_order_id: format!("close_{}", position.position_id),
-            status: UnifiedOrderStatus::Filled,
-            symbol: symbol.to_string(),
-            side: match position.side {
-                UnifiedPositionSide::Long => UnifiedOrderSide::Sell,
-                UnifiedPositionSide::Short => UnifiedOrderSide::Buy,
-            },
-            order_type: UnifiedOrderType::Market,
-            quantity: close_quantity,
-            filled_quantity: close_quantity,
-            remaining_quantity: Decimal::ZERO,
-            price: None,
-            average_fill_price: Some(position.current_price),
-            commission: Some(Decimal::from_f64_retain(2.0).unwrap()),
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-            filled_at: Some(Utc::now()),
-            platform_specific: HashMap::new(),
-        })
-    }
-
-    async fn get_account_info(
-        &self,
-    ) -> Result<crate::platforms::abstraction::UnifiedAccountInfo, PlatformError> {
-        unimplemented!("get_account_info not needed for exit management tests")
-    }
-
-    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
-        Ok(Decimal::from(10000))
-    }
-
-    async fn get_margin_info(
-        &self,
-    ) -> Result<crate::platforms::abstraction::MarginInfo, PlatformError> {
-        unimplemented!("get_margin_info not needed for exit management tests")
-    }
-
-    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
-        self.market_data
-            .read()
-            .unwrap()
-            .get(symbol)
-            .cloned()
-            .ok_or_else(|| PlatformError::MarketDataNotFound {
-                symbol: symbol.to_string(),
-            })
-    }
-
-    async fn subscribe_market_data(
-        &self,
-        _symbols: Vec<String>,
-    ) -> Result<tokio::sync::mpsc::Receiver<UnifiedMarketData>, PlatformError> {
-        unimplemented!("subscribe_market_data not needed for exit management tests")
-    }
-
-    async fn unsubscribe_market_data(&self, _symbols: Vec<String>) -> Result<(), PlatformError> {
-        Ok(())
-    }
-
-    fn capabilities(&self) -> crate::platforms::abstraction::PlatformCapabilities {
-        unimplemented!("capabilities not needed for exit management tests")
-    }
-
-    async fn subscribe_events(
-        &self,
-    ) -> Result<
-        tokio::sync::mpsc::Receiver<crate::platforms::abstraction::PlatformEvent>,
-        PlatformError,
-    > {
-        unimplemented!("subscribe_events not needed for exit management tests")
-    }
-
-    async fn get_event_history(
-        &self,
-        _filter: EventFilter,
-    ) -> Result<Vec<PlatformEvent>, PlatformError> {
-        Ok(Vec::new())
-    }
-
-    async fn health_check(
-        &self,
-    ) -> Result<crate::platforms::abstraction::HealthStatus, PlatformError> {
-        unimplemented!("health_check not needed for exit management tests")
-    }
-
-    async fn get_diagnostics(
-        &self,
-    ) -> Result<crate::platforms::abstraction::DiagnosticsInfo, PlatformError> {
-        unimplemented!("get_diagnostics not needed for exit management tests")
-    }
+use crate::platforms::abstraction::{UnifiedPosition, UnifiedPositionSide};
+use crate::platforms::PlatformType;
+use crate::testing::MockTradingPlatform;
+
+/// Shared mock platform quoting the symbols these tests trade
+fn integrated_platform() -> MockTradingPlatform {
+    MockTradingPlatform::new("MockIntegratedPlatform")
+        .with_platform_type(PlatformType::MetaTrader4)
+        .with_quote("EURUSD", dec!(1.0799), dec!(1.0801))
+        .with_quote("GBPUSD", dec!(1.2499), dec!(1.2501))
 }
 
 fn create_test_unified_position(
@@ -333,7 +60,7 @@ fn create_test_unified_position(
 
 #[tokio::test]
 async fn test_platform_integration_basic_workflow() {
-    let mock_platform = Arc::new(MockIntegratedPlatform::new());
+    let mock_platform = Arc::new(integrated_platform());
 
     // Add a test position
     let position = create_test_unified_position(
@@ -364,7 +91,7 @@ async fn test_platform_integration_basic_workflow() {
 
 #[tokio::test]
 async fn test_platform_integration_trailing_stops() {
-    let mock_platform = Arc::new(MockIntegratedPlatform::new());
+    let mock_platform = Arc::new(integrated_platform());
 
     // Add a profitable position
     let position = create_test_unified_position(
@@ -408,7 +135,7 @@ async fn test_platform_integration_trailing_stops() {
 
 #[tokio::test]
 async fn test_platform_integration_break_even() {
-    let mock_platform = Arc::new(MockIntegratedPlatform::new());
+    let mock_platform = Arc::new(integrated_platform());
 
     // Add a position at 1:1 risk-reward (break-even trigger)
     let position = create_test_unified_position(
@@ -434,7 +161,7 @@ async fn test_platform_integration_break_even() {
     );
 
     // Verify order modification was requested
-    let modifications = mock_platform.get_order_modifications();
+    let modifications = mock_platform.modifications();
     assert_eq!(
         modifications.len(),
         1,
@@ -450,7 +177,7 @@ async fn test_platform_integration_break_even() {
 
 #[tokio::test]
 async fn test_platform_integration_partial_profits() {
-    let mock_platform = Arc::new(MockIntegratedPlatform::new());
+    let mock_platform = Arc::new(integrated_platform());
 
     // Add a position at 1:1 risk-reward for partial profit taking
     let position = create_test_unified_position(
@@ -481,7 +208,7 @@ async fn test_platform_integration_partial_profits() {
 
 #[tokio::test]
 async fn test_platform_integration_full_monitoring_cycle() {
-    let mock_platform = Arc::new(MockIntegratedPlatform::new());
+    let mock_platform = Arc::new(integrated_platform());
 
     // Add multiple positions with different scenarios
     let position1 = create_test_unified_position(
@@ -530,7 +257,7 @@ async fn test_platform_integration_full_monitoring_cycle() {
 
 #[tokio::test]
 async fn test_platform_adapter_conversion() {
-    let mock_platform = Arc::new(MockIntegratedPlatform::new());
+    let mock_platform = Arc::new(integrated_platform());
 
     // Add a test position
     let unified_position = create_test_unified_position(
@@ -552,15 +279,15 @@ async fn test_platform_adapter_conversion() {
 
     let position = &positions[0];
     assert_eq!(position.symbol, "EURUSD");
-    assert_eq!(position.entry_price, 1.0800);
-    assert_eq!(position.current_price, 1.0825);
-    assert_eq!(position.stop_loss, Some(1.0780));
-    assert_eq!(position.take_profit, Some(1.0850));
+    assert_eq!(position.entry_price, dec!(1.0800));
+    assert_eq!(position.current_price, dec!(1.0825));
+    assert_eq!(position.stop_loss, Some(dec!(1.0780)));
+    assert_eq!(position.take_profit, Some(dec!(1.0850)));
 
     // Test market data conversion
     let market_data = adapter.get_market_data("EURUSD").await.unwrap();
     assert_eq!(market_data.symbol, "EURUSD");
-    assert_eq!(market_data.bid, 1.0799);
-    assert_eq!(market_data.ask, 1.0801);
-    assert_eq!(market_data.spread, 0.0002);
+    assert_eq!(market_data.bid, dec!(1.0799));
+    assert_eq!(market_data.ask, dec!(1.0801));
+    assert_eq!(market_data.spread, dec!(0.0002));
 }
@@ -1,4 +1,5 @@
 use chrono::Utc;
+use rust_decimal_macros::dec;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -8,7 +9,7 @@ use crate::execution::exit_management::{ExitAuditLogger, TrailingStopManager};
 
 #[tokio::test]
 async fn test_trailing_stop_activation() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let trailing_manager = TrailingStopManager::new(mock_platform.clone(), exit_logger);
 
@@ -16,10 +17,10 @@ async fn test_trailing_stop_activation() {
     let position = create_test_position_with_params(
         "EURUSD",
         UnifiedPositionSide::Long,
-        1.0800,       // Entry
-        1.0825,       // Current (25 pips profit)
-        Some(1.0780), // Stop loss
-        1,            // 1 hour old
+        dec!(1.0800),       // Entry
+        dec!(1.0825),       // Current (25 pips profit)
+        Some(dec!(1.0780)), // Stop loss
+        1,                  // 1 hour old
     );
 
     // Activate trailing stop
@@ -37,7 +38,7 @@ async fn test_trailing_stop_activation() {
 
 #[tokio::test]
 async fn test_trailing_stop_insufficient_profit() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let trailing_manager = TrailingStopManager::new(mock_platform.clone(), exit_logger);
 
@@ -45,10 +46,10 @@ async fn test_trailing_stop_insufficient_profit() {
     let position = create_test_position_with_params(
         "EURUSD",
         UnifiedPositionSide::Long,
-        1.0800,       // Entry
-        1.0805,       // Current (only 5 pips profit)
-        Some(1.0780), // Stop loss
-        1,            // 1 hour old
+        dec!(1.0800),       // Entry
+        dec!(1.0805),       // Current (only 5 pips profit)
+        Some(dec!(1.0780)), // Stop loss
+        1,                  // 1 hour old
     );
 
     // Attempt to activate trailing stop
@@ -62,16 +63,16 @@ async fn test_trailing_stop_insufficient_profit() {
 
 #[tokio::test]
 async fn test_trailing_stop_update() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let trailing_manager = TrailingStopManager::new(mock_platform.clone(), exit_logger);
 
     let position = create_test_position_with_params(
         "EURUSD",
         UnifiedPositionSide::Long,
-        1.0800,
-        1.0825,
-        Some(1.0780),
+        dec!(1.0800),
+        dec!(1.0825),
+        Some(dec!(1.0780)),
         1,
     );
 
@@ -87,7 +88,7 @@ async fn test_trailing_stop_update() {
 
     // Mock price improvement
     let mut improved_position = position.clone();
-    improved_position.current_price = 1.0835; // 10 more pips profit
+    improved_position.current_price = dec!(1.0835); // 10 more pips profit
 
     // Update trailing stops should improve the trail level
     let result = trailing_manager.update_trailing_stops().await;
@@ -99,16 +100,16 @@ async fn test_trailing_stop_update() {
 
 #[tokio::test]
 async fn test_trailing_stop_deactivation() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let trailing_manager = TrailingStopManager::new(mock_platform.clone(), exit_logger);
 
     let position = create_test_position_with_params(
         "EURUSD",
         UnifiedPositionSide::Long,
-        1.0800,
-        1.0825,
-        Some(1.0780),
+        dec!(1.0800),
+        dec!(1.0825),
+        Some(dec!(1.0780)),
         1,
     );
 
@@ -131,7 +132,7 @@ async fn test_trailing_stop_deactivation() {
 
 #[tokio::test]
 async fn test_trailing_stop_short_position() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let trailing_manager = TrailingStopManager::new(mock_platform.clone(), exit_logger);
 
@@ -139,9 +140,9 @@ async fn test_trailing_stop_short_position() {
     let position = create_test_position_with_params(
         "EURUSD",
         UnifiedPositionSide::Short,
-        1.0800,       // Entry
-        1.0775,       // Current (25 pips profit for short)
-        Some(1.0820), // Stop loss above entry
+        dec!(1.0800),       // Entry
+        dec!(1.0775),       // Current (25 pips profit for short)
+        Some(dec!(1.0820)), // Stop loss above entry
         1,
     );
 
@@ -160,18 +161,19 @@ async fn test_trailing_stop_short_position() {
 
 #[tokio::test]
 async fn test_trailing_configuration() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let mut trailing_manager = TrailingStopManager::new(mock_platform.clone(), exit_logger);
 
     // Configure custom trailing settings
     let custom_config = TrailingConfig {
         atr_multiplier: 3.0,
-        min_trail_distance: 0.0005,   // 5 pips
-        max_trail_distance: 0.0200,   // 200 pips
-        activation_threshold: 0.0020, // 20 pips
+        min_trail_distance: dec!(0.0005),   // 5 pips
+        max_trail_distance: dec!(0.0200),   // 200 pips
+        activation_threshold: dec!(0.0020), // 20 pips
         symbol: "EURUSD".to_string(),
         timeframe: "H1".to_string(),
+        ..TrailingConfig::default()
     };
 
     trailing_manager.configure_symbol("EURUSD".to_string(), custom_config);
@@ -179,9 +181,9 @@ async fn test_trailing_configuration() {
     let position = create_test_position_with_params(
         "EURUSD",
         UnifiedPositionSide::Long,
-        1.0800,
-        1.0825, // 25 pips profit (above 20 pip threshold)
-        Some(1.0780),
+        dec!(1.0800),
+        dec!(1.0825), // 25 pips profit (above 20 pip threshold)
+        Some(dec!(1.0780)),
         1,
     );
 
@@ -193,7 +195,7 @@ async fn test_trailing_configuration() {
 
 #[tokio::test]
 async fn test_trailing_stop_performance_stats() {
-    let mock_platform = Arc::new(MockTradingPlatform::new());
+    let mock_platform = mock_platform();
     let exit_logger = Arc::new(ExitAuditLogger::new());
     let trailing_manager = TrailingStopManager::new(mock_platform.clone(), exit_logger);
 
@@ -211,7 +213,7 @@ mod property_tests {
 
     #[tokio::test]
     async fn test_trail_level_always_moves_favorably() {
-        let mock_platform = Arc::new(MockTradingPlatform::new());
+        let mock_platform = mock_platform();
         let exit_logger = Arc::new(ExitAuditLogger::new());
         let trailing_manager = TrailingStopManager::new(mock_platform.clone(), exit_logger);
 
@@ -219,13 +221,13 @@ mod property_tests {
             let position = create_test_position_with_params(
                 "EURUSD",
                 UnifiedPositionSide::Long,
-                1.0800,
-                1.0800 + (rand::random::<f64>() * 0.01), // Random profit up to 100 pips
-                Some(1.0780),
+                dec!(1.0800),
+                dec!(1.0800) + scale_by(dec!(0.01), rand::random::<f64>()), // Random profit up to 100 pips
+                Some(dec!(1.0780)),
                 1,
             );
 
-            if position.current_price - position.entry_price >= 0.0015 {
+            if position.current_price - position.entry_price >= dec!(0.0015) {
                 // Sufficient profit
                 trailing_manager
                     .activate_trailing_stop(&position)
@@ -1,21 +1,59 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc, Weekday};
 use dashmap::DashSet;
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{error, info, warn};
+use tracing::{error, info, warn, Instrument};
 
 use super::exit_logger::ExitAuditLogger;
+use super::market_context::MarketContextProvider;
+use super::policy::{resolve_config, ExitPolicies};
 use super::types::*;
 use super::TradingPlatform;
+use crate::platforms::abstraction::clock::ServerClock;
+use crate::risk::trading_day::TradingDayRollover;
+use crate::runtime::logging::LogContext;
+
+/// Positions that must not be held over the weekend are closed from this hour (UTC) on Friday
+const WEEKEND_CUTOFF_HOUR_UTC: u32 = 20;
+/// Hour (UTC) on Sunday when the market reopens
+const WEEKEND_REOPEN_HOUR_UTC: u32 = 22;
+/// With a trading day rollover, positions are closed this long before the week's last rollover
+const WEEKEND_CUTOFF_LEAD_HOURS: i64 = 1;
+
+/// True between the Friday cutoff and the Sunday reopen
+pub fn is_weekend_close_window(now: DateTime<Utc>) -> bool {
+    match now.weekday() {
+        Weekday::Fri => now.hour() >= WEEKEND_CUTOFF_HOUR_UTC,
+        Weekday::Sat => true,
+        Weekday::Sun => now.hour() < WEEKEND_REOPEN_HOUR_UTC,
+        _ => false,
+    }
+}
+
+/// Weekend close window of an account rolling over at `trading_day`: from shortly
+/// before the Friday rollover until the Monday trading day begins. Falls back to the
+/// fixed UTC window when the account has no rollover configured.
+pub fn weekend_close_window(trading_day: Option<&TradingDayRollover>, now: DateTime<Utc>) -> bool {
+    match trading_day {
+        Some(rollover) => rollover.is_weekend(now, Duration::hours(WEEKEND_CUTOFF_LEAD_HOURS)),
+        None => is_weekend_close_window(now),
+    }
+}
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TimeBasedExitManager {
     trading_platform: Arc<dyn TradingPlatform>,
     exit_logger: Arc<ExitAuditLogger>,
     time_configs: HashMap<String, TimeExitConfig>,
     warned_positions: Arc<DashSet<PositionId>>,
+    exit_policies: Option<Arc<ExitPolicies>>,
+    trading_day: Option<TradingDayRollover>,
+    market_context: Arc<MarketContextProvider>,
+    /// The weekend close is timed on the platform server's clock
+    clock: ServerClock,
 }
 
 impl TimeBasedExitManager {
@@ -24,13 +62,54 @@ impl TimeBasedExitManager {
         exit_logger: Arc<ExitAuditLogger>,
     ) -> Self {
         Self {
+            market_context: Arc::new(MarketContextProvider::new(trading_platform.clone())),
             trading_platform,
             exit_logger,
             time_configs: HashMap::new(),
             warned_positions: Arc::new(DashSet::new()),
+            exit_policies: None,
+            trading_day: None,
+            clock: ServerClock::new(),
         }
     }
 
+    pub fn with_exit_policies(mut self, exit_policies: Arc<ExitPolicies>) -> Self {
+        self.exit_policies = Some(exit_policies);
+        self
+    }
+
+    /// Time the weekend close from the account's trading day rollover
+    pub fn with_trading_day(mut self, trading_day: TradingDayRollover) -> Self {
+        self.trading_day = Some(trading_day);
+        self
+    }
+
+    /// Record audit entries with market context captured by `market_context`
+    pub fn with_market_context(mut self, market_context: Arc<MarketContextProvider>) -> Self {
+        self.market_context = market_context;
+        self
+    }
+
+    /// Time the weekend close on `clock` instead of ours
+    pub fn with_server_clock(mut self, clock: ServerClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn trading_day(&self) -> Option<&TradingDayRollover> {
+        self.trading_day.as_ref()
+    }
+
+    /// Configuration that applies to `position`: its policy, else its symbol's
+    pub fn config_for(&self, position: &Position) -> TimeExitConfig {
+        resolve_config(
+            self.exit_policies
+                .as_ref()
+                .and_then(|p| p.time_exit(position.id)),
+            self.time_configs.get(&position.symbol),
+        )
+    }
+
     pub fn configure_symbol(&mut self, symbol: String, config: TimeExitConfig) {
         self.time_configs.insert(symbol, config);
     }
@@ -39,24 +118,28 @@ impl TimeBasedExitManager {
         let aged_positions = self.get_aged_positions().await?;
 
         for position in aged_positions {
-            match self.should_exit_on_time(&position).await {
-                Ok(should_exit) => {
-                    if should_exit {
-                        if let Err(e) = self.execute_time_based_exit(&position).await {
-                            error!(
-                                "Failed to execute time-based exit for position {}: {}",
-                                position.id, e
-                            );
+            async {
+                match self.should_exit_on_time(&position).await {
+                    Ok(should_exit) => {
+                        if should_exit {
+                            if let Err(e) = self.execute_time_based_exit(&position).await {
+                                error!(
+                                    "Failed to execute time-based exit for position {}: {}",
+                                    position.id, e
+                                );
+                            }
                         }
                     }
-                }
-                Err(e) => {
-                    error!(
-                        "Error checking time exit for position {}: {}",
-                        position.id, e
-                    );
+                    Err(e) => {
+                        error!(
+                            "Error checking time exit for position {}: {}",
+                            position.id, e
+                        );
+                    }
                 }
             }
+            .instrument(LogContext::position(position.id).span())
+            .await;
         }
 
         Ok(())
@@ -64,16 +147,21 @@ impl TimeBasedExitManager {
 
     async fn should_exit_on_time(&self, position: &Position) -> Result<bool> {
         let position_age = Utc::now() - position.open_time;
-        let default_config = TimeExitConfig::default();
-        let config = self
-            .time_configs
-            .get(&position.symbol)
-            .unwrap_or(&default_config);
+        let config = self.config_for(position);
 
         if !config.enabled {
             return Ok(false);
         }
 
+        if config.close_before_weekend && weekend_close_window(self.trading_day(), self.clock.now())
+        {
+            info!(
+                "Weekend exit triggered for position {}: not held over the weekend",
+                position.id
+            );
+            return Ok(true);
+        }
+
         // Check warning threshold first
         if position_age > config.warning_duration && !self.warned_positions.contains(&position.id) {
             self.send_time_warning(position, &config).await?;
@@ -86,7 +174,7 @@ impl TimeBasedExitManager {
         }
 
         // Check for trend strength override
-        if position.unrealized_pnl > 0.0 {
+        if position.unrealized_pnl > Decimal::ZERO {
             let market_conditions = self.analyze_market_conditions(&position.symbol).await?;
 
             if market_conditions.trend_strength > config.trend_strength_override_threshold {
@@ -108,6 +196,48 @@ impl TimeBasedExitManager {
         Ok(true)
     }
 
+    /// Where the position stands against its time exit, and whether the next
+    /// check would warn or close it. None when time exits are off for it.
+    pub async fn preview_time_exit(&self, position: &Position) -> Result<Option<TimeExitPreview>> {
+        let config = self.config_for(position);
+        if !config.enabled {
+            return Ok(None);
+        }
+        let position_age = Utc::now() - position.open_time;
+        let mut preview = TimeExitPreview {
+            age_secs: position_age.num_seconds(),
+            exits_in_secs: (config.max_hold_duration - position_age).num_seconds(),
+            would_warn: position_age > config.warning_duration
+                && !self.warned_positions.contains(&position.id),
+            would_exit: false,
+            reason: None,
+        };
+
+        if config.close_before_weekend && weekend_close_window(self.trading_day(), self.clock.now())
+        {
+            preview.would_exit = true;
+            preview.reason = Some("not held over the weekend".to_string());
+        } else if position_age > config.max_hold_duration {
+            let overridden = position.unrealized_pnl > Decimal::ZERO
+                && self
+                    .analyze_market_conditions(&position.symbol)
+                    .await?
+                    .trend_strength
+                    > config.trend_strength_override_threshold;
+            preview.would_exit = !overridden;
+            preview.reason = Some(if overridden {
+                "held past the maximum by a strong trend".to_string()
+            } else {
+                format!(
+                    "age {} hours > max {} hours",
+                    position_age.num_hours(),
+                    config.max_hold_duration.num_hours()
+                )
+            });
+        }
+        Ok(Some(preview))
+    }
+
     async fn execute_time_based_exit(&self, position: &Position) -> Result<()> {
         let close_request = ClosePositionRequest {
             position_id: position.id,
@@ -192,21 +322,8 @@ impl TimeBasedExitManager {
         let aged_positions: Vec<Position> = all_positions
             .into_iter()
             .filter(|pos| {
-                if let Some(config) = self.time_configs.get(&pos.symbol) {
-                    if !config.enabled {
-                        return false;
-                    }
-                    let position_age = now - pos.open_time;
-                    position_age > config.warning_duration
-                } else {
-                    // Use default config if no specific config exists
-                    let default_config = TimeExitConfig::default();
-                    if !default_config.enabled {
-                        return false;
-                    }
-                    let position_age = now - pos.open_time;
-                    position_age > default_config.warning_duration
-                }
+                let config = self.config_for(pos);
+                config.enabled && now - pos.open_time > config.warning_duration
             })
             .collect();
 
@@ -225,27 +342,26 @@ impl TimeBasedExitManager {
 
         // Simplified calculation - would need real technical analysis
         let price_change = market_data.ask - market_data.bid; // Simplified
-        let trend_strength = (price_change.abs() / market_data.ask).min(1.0);
+        let trend_strength = ratio_of(price_change.abs(), market_data.ask).min(1.0);
 
         Ok(MarketConditions {
             symbol: symbol.to_string(),
             trend_strength,
             volatility: 0.02,    // Simplified
             volume_profile: 1.0, // Simplified
-            support_resistance_levels: vec![market_data.bid - 0.01, market_data.ask + 0.01], // Simplified
+            support_resistance_levels: vec![
+                market_data.bid - dec!(0.01),
+                market_data.ask + dec!(0.01),
+            ], // Simplified
             analysis_time: Utc::now(),
         })
     }
 
-    async fn log_time_based_exit(&self, position: &Position, exit_price: f64) -> Result<()> {
-        let market_context = MarketContext {
-            current_price: exit_price,
-            atr_14: 0.0015,      // Simplified
-            trend_strength: 0.3, // Time exit suggests weak trend
-            volatility: 0.02,
-            spread: 0.0001,
-            timestamp: Utc::now(),
-        };
+    async fn log_time_based_exit(&self, position: &Position, exit_price: Decimal) -> Result<()> {
+        let market_context = self
+            .market_context
+            .capture_at(&position.symbol, exit_price)
+            .await;
 
         let modification = ExitModification {
             position_id: position.id,
@@ -255,11 +371,7 @@ impl TimeBasedExitManager {
             reasoning: format!(
                 "Time-based exit after {} hours (max {} hours configured)",
                 (Utc::now() - position.open_time).num_hours(),
-                self.time_configs
-                    .get(&position.symbol)
-                    .unwrap_or(&TimeExitConfig::default())
-                    .max_hold_duration
-                    .num_hours()
+                self.config_for(position).max_hold_duration.num_hours()
             ),
             market_context,
         };
@@ -269,26 +381,13 @@ impl TimeBasedExitManager {
     }
 
     async fn log_time_warning(&self, position: &Position, remaining_time: Duration) -> Result<()> {
-        let current_price = (self
-            .trading_platform
-            .get_market_data(&position.symbol)
-            .await?)
-            .ask;
-
-        let market_context = MarketContext {
-            current_price,
-            atr_14: 0.0015, // Simplified
-            trend_strength: 0.5,
-            volatility: 0.02,
-            spread: 0.0001,
-            timestamp: Utc::now(),
-        };
+        let market_context = self.market_context.capture(&position.symbol).await?;
 
         let modification = ExitModification {
             position_id: position.id,
             modification_type: ExitModificationType::TimeExit,
-            old_value: 0.0,
-            new_value: remaining_time.num_hours() as f64,
+            old_value: Decimal::ZERO,
+            new_value: Decimal::from(remaining_time.num_hours()),
             reasoning: format!(
                 "Time exit warning: {} hours remaining before automatic close",
                 remaining_time.num_hours()
@@ -304,16 +403,12 @@ impl TimeBasedExitManager {
         &self,
         position: &Position,
         reason: &str,
-        exit_price: f64,
+        exit_price: Decimal,
     ) -> Result<()> {
-        let market_context = MarketContext {
-            current_price: exit_price,
-            atr_14: 0.0015,      // Simplified
-            trend_strength: 0.0, // Forced exit
-            volatility: 0.02,
-            spread: 0.0001,
-            timestamp: Utc::now(),
-        };
+        let market_context = self
+            .market_context
+            .capture_at(&position.symbol, exit_price)
+            .await;
 
         let modification = ExitModification {
             position_id: position.id,
@@ -336,6 +431,14 @@ impl TimeBasedExitManager {
         self.warned_positions.contains(&position_id)
     }
 
+    pub fn get_warned_positions(&self) -> Vec<PositionId> {
+        self.warned_positions.iter().map(|id| *id).collect()
+    }
+
+    pub fn restore_warning(&self, position_id: PositionId) {
+        self.warned_positions.insert(position_id);
+    }
+
     pub fn get_warned_positions_count(&self) -> usize {
         self.warned_positions.len()
     }
@@ -398,11 +501,7 @@ impl TimeBasedExitManager {
         let positions = self.trading_platform.get_positions().await?;
 
         if let Some(position) = positions.iter().find(|p| p.id == position_id) {
-            let default_config = TimeExitConfig::default();
-            let config = self
-                .time_configs
-                .get(&position.symbol)
-                .unwrap_or(&default_config);
+            let config = self.config_for(position);
 
             let position_age = Utc::now() - position.open_time;
             let remaining_time = config.max_hold_duration - position_age;
@@ -416,7 +515,7 @@ impl TimeBasedExitManager {
                 remaining_hours: remaining_time.num_hours(),
                 is_warned: self.warned_positions.contains(&position_id),
                 trend_strength: market_conditions.trend_strength,
-                will_override_time_exit: position.unrealized_pnl > 0.0
+                will_override_time_exit: position.unrealized_pnl > Decimal::ZERO
                     && market_conditions.trend_strength > config.trend_strength_override_threshold,
                 exit_probability: self.calculate_exit_probability(
                     &position,
@@ -445,7 +544,7 @@ impl TimeBasedExitManager {
         let mut probability = age_factor.min(1.0);
 
         // Reduce probability if trend is strong and position is profitable
-        if position.unrealized_pnl > 0.0
+        if position.unrealized_pnl > Decimal::ZERO
             && market_conditions.trend_strength > config.trend_strength_override_threshold
         {
             probability *= 0.2; // Significantly reduce probability
@@ -1,21 +1,52 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
-use std::collections::HashMap;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tracing::{error, info, warn};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn, Instrument};
 
+use super::excursions::ExcursionTracker;
 use super::exit_logger::ExitAuditLogger;
+use super::market_context::MarketContextProvider;
+use super::policy::{resolve_config, ExitPolicies};
 use super::types::*;
 use super::TradingPlatform;
+use crate::instruments::InstrumentMetadataService;
+use crate::market_data::{CandleBuilder, Timeframe};
+use crate::runtime::logging::LogContext;
 
-#[derive(Debug)]
+/// Smallest trail improvement worth a modify request, in pips
+const MIN_TRAIL_MOVEMENT_PIPS: Decimal = dec!(5);
+
+/// Span of recent prices tick velocity is measured over, in seconds
+const VELOCITY_WINDOW_SECS: i64 = 5;
+
+/// Mid price read at a quote's time
+type PriceSample = (DateTime<Utc>, Decimal);
+
+/// Candles ATR trails are measured on when a candle builder is attached
+pub const ATR_TIMEFRAME: Timeframe = Timeframe::H1;
+
+#[derive(Debug, Clone)]
 pub struct TrailingStopManager {
     trading_platform: Arc<dyn TradingPlatform>,
     exit_logger: Arc<ExitAuditLogger>,
     trail_configs: HashMap<String, TrailingConfig>,
     active_trails: Arc<DashMap<PositionId, ActiveTrail>>,
     atr_cache: Arc<DashMap<String, ATRCalculation>>,
+    excursion_tracker: Option<Arc<ExcursionTracker>>,
+    exit_policies: Option<Arc<ExitPolicies>>,
+    instruments: Arc<InstrumentMetadataService>,
+    candles: Option<Arc<CandleBuilder>>,
+    market_context: Arc<MarketContextProvider>,
+    /// Recent mid prices per symbol, for tick velocity
+    price_samples: Arc<DashMap<String, VecDeque<PriceSample>>>,
+    /// Smoothed round-trip of stop modifications, in milliseconds
+    modify_latency_ms: Arc<AtomicU64>,
 }
 
 impl TrailingStopManager {
@@ -24,29 +55,74 @@ impl TrailingStopManager {
         exit_logger: Arc<ExitAuditLogger>,
     ) -> Self {
         Self {
+            market_context: Arc::new(MarketContextProvider::new(trading_platform.clone())),
             trading_platform,
             exit_logger,
             trail_configs: HashMap::new(),
             active_trails: Arc::new(DashMap::new()),
             atr_cache: Arc::new(DashMap::new()),
+            excursion_tracker: None,
+            exit_policies: None,
+            instruments: Arc::new(InstrumentMetadataService::new()),
+            candles: None,
+            price_samples: Arc::new(DashMap::new()),
+            modify_latency_ms: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentMetadataService>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+
+    pub fn with_exit_policies(mut self, exit_policies: Arc<ExitPolicies>) -> Self {
+        self.exit_policies = Some(exit_policies);
+        self
+    }
+
+    /// Measure ATR on candles built from the quote stream instead of the spread
+    pub fn with_candles(mut self, candles: Arc<CandleBuilder>) -> Self {
+        self.candles = Some(candles);
+        self
+    }
+
+    /// Record audit entries with market context captured by `market_context`
+    pub fn with_market_context(mut self, market_context: Arc<MarketContextProvider>) -> Self {
+        self.market_context = market_context;
+        self
+    }
+
+    /// Configuration that applies to `position`: its policy, else its symbol's
+    pub fn config_for(&self, position: &Position) -> TrailingConfig {
+        resolve_config(
+            self.exit_policies
+                .as_ref()
+                .and_then(|p| p.trailing(position.id)),
+            self.trail_configs.get(&position.symbol),
+        )
+    }
+
+    /// Let MFE retracement tighten trails (see `TrailingConfig::mfe_retracement_threshold`)
+    pub fn with_excursion_tracker(mut self, excursion_tracker: Arc<ExcursionTracker>) -> Self {
+        self.excursion_tracker = Some(excursion_tracker);
+        self
+    }
+
     pub fn configure_symbol(&mut self, symbol: String, config: TrailingConfig) {
         self.trail_configs.insert(symbol, config);
     }
 
     pub async fn activate_trailing_stop(&self, position: &Position) -> Result<()> {
-        let default_config = TrailingConfig::default();
-        let config = self
-            .trail_configs
-            .get(&position.symbol)
-            .unwrap_or(&default_config);
+        let config = self.config_for(position);
 
         // Check if position has enough profit to activate trailing
-        let current_price = self.get_current_price(&position.symbol).await?;
+        let market_data = self
+            .trading_platform
+            .get_market_data(&position.symbol)
+            .await?;
+        let current_price = market_data.mid();
         let entry_price = position.entry_price;
-        let initial_stop = position.stop_loss.unwrap_or(0.0);
+        let initial_stop = position.stop_loss.unwrap_or_default();
 
         let profit = match position.position_type {
             UnifiedPositionSide::Long => current_price - entry_price,
@@ -59,13 +135,38 @@ impl TrailingStopManager {
 
         // Calculate initial trailing stop level
         let atr = self.calculate_atr(&position.symbol, 14).await?;
-        let trail_distance = (atr * config.atr_multiplier)
+        let trail_distance = scale_by(atr, config.atr_multiplier)
             .max(config.min_trail_distance)
             .min(config.max_trail_distance);
 
-        let trail_level = match position.position_type {
-            UnifiedPositionSide::Long => current_price - trail_distance,
-            UnifiedPositionSide::Short => current_price + trail_distance,
+        // The trail starts no closer to the market than the platform allows a stop,
+        // and not until the current stop is out of the freeze distance
+        let Some(trail_level) = self.instruments.allowed_stop(
+            &position.symbol,
+            position.position_type == UnifiedPositionSide::Long,
+            market_data.closing_price(&position.position_type),
+            position.stop_loss,
+            match position.position_type {
+                UnifiedPositionSide::Long => current_price - trail_distance,
+                UnifiedPositionSide::Short => current_price + trail_distance,
+            },
+        ) else {
+            debug!(
+                "Stop of position {} is inside the freeze distance; trail not activated yet",
+                position.id
+            );
+            return Ok(());
+        };
+
+        let native_order_id = if config.native_trailing
+            && self
+                .trading_platform
+                .supports_native_trailing(&position.symbol)
+        {
+            self.place_native_trail(position, trail_level, trail_distance)
+                .await
+        } else {
+            None
         };
 
         let active_trail = ActiveTrail {
@@ -76,6 +177,7 @@ impl TrailingStopManager {
             last_updated: Utc::now(),
             update_count: 0,
             activation_price: current_price,
+            native_order_id,
         };
 
         self.active_trails.insert(position.id, active_trail);
@@ -85,70 +187,202 @@ impl TrailingStopManager {
             position.id, trail_level, current_price
         );
 
-        self.log_trail_activation(position.id, trail_level, current_price)
+        self.log_trail_activation(position, trail_level, current_price)
             .await?;
 
         Ok(())
     }
 
+    /// Place the platform's own trailing stop, returning its order. The trail is
+    /// emulated instead if the platform refuses it.
+    async fn place_native_trail(
+        &self,
+        position: &Position,
+        trail_level: Decimal,
+        trail_distance: Decimal,
+    ) -> Option<String> {
+        let request = NativeTrailingStopRequest {
+            position_id: position.id,
+            order_id: position.order_id.clone(),
+            symbol: position.symbol.clone(),
+            position_type: position.position_type.clone(),
+            volume: position.volume,
+            stop_level: trail_level,
+            trail_distance,
+        };
+        match self
+            .trading_platform
+            .place_native_trailing_stop(request)
+            .await
+        {
+            Ok(result) if result.success => Some(result.order_id),
+            Ok(result) => {
+                warn!(
+                    "Native trailing stop refused for position {}, trailing from the engine: {}",
+                    position.id, result.message
+                );
+                None
+            }
+            Err(e) => {
+                warn!(
+                    "Native trailing stop failed for position {}, trailing from the engine: {:#}",
+                    position.id, e
+                );
+                None
+            }
+        }
+    }
+
     pub async fn update_trailing_stops(&self) -> Result<()> {
         if self.active_trails.is_empty() {
             return Ok(());
         }
 
-        // Get all open positions that have active trails
-        let open_positions = self.get_open_positions_with_trails().await?;
+        let all_positions = self.trading_platform.get_positions().await?;
+        self.cancel_orphaned_native_trails(&all_positions).await;
+
+        // Only open positions that have active trails
+        let open_positions: Vec<Position> = all_positions
+            .into_iter()
+            .filter(|pos| self.active_trails.contains_key(&pos.id))
+            .collect();
 
         for position in open_positions {
-            if let Some(trail_ref) = self.active_trails.get(&position.id) {
-                let trail = trail_ref.clone();
-                drop(trail_ref); // Release the reference
-
-                match self.calculate_new_trail_level(&position, &trail).await {
-                    Ok(update) => {
-                        if self.should_update_trail(&trail, &update) {
-                            if let Err(e) = self.execute_trail_update(&position, update).await {
-                                error!(
-                                    "Failed to execute trail update for position {}: {}",
-                                    position.id, e
-                                );
+            async {
+                if let Some(trail_ref) = self.active_trails.get(&position.id) {
+                    let trail = trail_ref.clone();
+                    drop(trail_ref); // Release the reference
+                    if trail.native_order_id.is_some() {
+                        return; // The platform trails it
+                    }
+
+                    match self.calculate_new_trail_level(&position, &trail).await {
+                        Ok(update) => {
+                            if self.should_update_trail(&position, &trail, &update) {
+                                if let Err(e) = self.execute_trail_update(&position, update).await {
+                                    error!(
+                                        "Failed to execute trail update for position {}: {}",
+                                        position.id, e
+                                    );
+                                }
                             }
                         }
-                    }
-                    Err(e) => {
-                        error!(
-                            "Failed to calculate trail update for position {}: {}",
-                            position.id, e
-                        );
+                        Err(e) => {
+                            error!(
+                                "Failed to calculate trail update for position {}: {}",
+                                position.id, e
+                            );
+                        }
                     }
                 }
             }
+            .instrument(LogContext::position(position.id).span())
+            .await;
         }
 
         Ok(())
     }
 
+    /// Where the position's trail would move on the next update, if anywhere
+    pub async fn preview_trail(&self, position: &Position) -> Result<Option<StopPreview>> {
+        let Some(trail) = self.get_active_trail(position.id) else {
+            return Ok(None);
+        };
+        if trail.native_order_id.is_some() {
+            return Ok(None);
+        }
+        let update = self.calculate_new_trail_level(position, &trail).await?;
+        if !self.should_update_trail(position, &trail, &update) {
+            return Ok(None);
+        }
+        Ok(Some(StopPreview {
+            current_stop: Some(trail.trail_level),
+            new_stop: update.new_level,
+            close_volume: None,
+            reason: update.update_reason,
+        }))
+    }
+
     async fn calculate_new_trail_level(
         &self,
         position: &Position,
         current_trail: &ActiveTrail,
     ) -> Result<TrailUpdate> {
         let current_atr = self.calculate_atr(&position.symbol, 14).await?;
-        let default_config = TrailingConfig::default();
-        let config = self
-            .trail_configs
-            .get(&position.symbol)
-            .unwrap_or(&default_config);
+        let config = self.config_for(position);
 
-        let trail_distance = (current_atr * config.atr_multiplier)
+        let mut trail_distance = scale_by(current_atr, config.atr_multiplier)
             .max(config.min_trail_distance)
             .min(config.max_trail_distance);
 
-        let current_price = self.get_current_price(&position.symbol).await?;
+        // Protect what is left once too much of the best move has been given back
+        let retracement = self.mfe_retracement(position.id, &config);
+        if retracement.is_some() {
+            trail_distance = scale_by(trail_distance, config.retracement_tighten_factor);
+        }
 
-        let new_trail_level = match position.position_type {
-            UnifiedPositionSide::Long => current_price - trail_distance,
-            UnifiedPositionSide::Short => current_price + trail_distance,
+        let market_data = self
+            .trading_platform
+            .get_market_data(&position.symbol)
+            .await?;
+        let current_price = market_data.mid();
+        let instrument = self.instruments.get(&position.symbol);
+
+        // The modification lands a check interval and a platform round-trip after the
+        // price was read, so trail from where recent velocity puts the price by then,
+        // never more than half the trail distance away
+        let velocity = self.record_price(&position.symbol, market_data.timestamp, current_price);
+        let projection = match velocity.filter(|_| config.latency_compensation) {
+            Some(velocity) => {
+                let lookahead_ms =
+                    config.latency_allowance_ms + self.modify_latency_ms.load(Ordering::Relaxed);
+                let limit = trail_distance / Decimal::TWO;
+                (velocity * Decimal::from(lookahead_ms) / dec!(1000))
+                    .max(-limit)
+                    .min(limit)
+            }
+            None => Decimal::ZERO,
+        };
+        let reference_price = current_price + projection;
+
+        let target_level = match position.position_type {
+            UnifiedPositionSide::Long => reference_price - trail_distance,
+            UnifiedPositionSide::Short => reference_price + trail_distance,
+        };
+        // The platform refuses stops inside its minimum distance and any change to a
+        // stop inside its freeze distance, so trail to the nearest level it accepts
+        // and hold the trail while the stop is frozen
+        let new_trail_level = match instrument.allowed_stop(
+            position.position_type == UnifiedPositionSide::Long,
+            market_data.closing_price(&position.position_type),
+            position.stop_loss,
+            target_level,
+        ) {
+            Some(level) => {
+                if level != instrument.round_price(target_level) {
+                    debug!(
+                        "Trail for position {} held at {} by the minimum stop distance",
+                        position.id, level
+                    );
+                }
+                level
+            }
+            None => {
+                debug!(
+                    "Stop of position {} is inside the freeze distance; trail held at {}",
+                    position.id, current_trail.trail_level
+                );
+                current_trail.trail_level
+            }
+        };
+        let distance_pips = instrument.price_to_pips(trail_distance);
+        let projected = if projection.is_zero() {
+            String::new()
+        } else {
+            format!(
+                ", projected {} pips for latency",
+                instrument.price_to_pips(projection)
+            )
         };
 
         Ok(TrailUpdate {
@@ -156,18 +390,76 @@ impl TrailingStopManager {
             old_level: current_trail.trail_level,
             new_level: new_trail_level,
             atr_used: current_atr,
-            distance_pips: trail_distance * 10000.0, // Convert to pips
+            distance_pips,
             trigger_price: current_price,
-            update_reason: format!(
-                "ATR-based trail: ATR={:.5}, Multiplier={}, Distance={:.1} pips",
-                current_atr,
-                config.atr_multiplier,
-                trail_distance * 10000.0
-            ),
+            update_reason: match retracement {
+                Some(retracement) => format!(
+                    "Tightened trail: MFE retraced {:.0}%, Distance={} pips{}",
+                    retracement * 100.0,
+                    distance_pips,
+                    projected
+                ),
+                None => format!(
+                    "ATR-based trail: ATR={}, Multiplier={}, Distance={} pips{}",
+                    current_atr, config.atr_multiplier, distance_pips, projected
+                ),
+            },
         })
     }
 
-    fn should_update_trail(&self, current: &ActiveTrail, update: &TrailUpdate) -> bool {
+    /// Record `price` for `symbol` and return its velocity over the velocity window,
+    /// in price per second
+    fn record_price(&self, symbol: &str, at: DateTime<Utc>, price: Decimal) -> Option<Decimal> {
+        let mut samples = self.price_samples.entry(symbol.to_string()).or_default();
+        // A quote read again adds nothing
+        if samples.back().is_none_or(|(last, _)| at > *last) {
+            samples.push_back((at, price));
+        }
+        let (last_at, last_price) = *samples.back()?;
+        let horizon = last_at - chrono::Duration::seconds(VELOCITY_WINDOW_SECS);
+        while samples.front().is_some_and(|(time, _)| *time < horizon) {
+            samples.pop_front();
+        }
+
+        let (first_at, first_price) = *samples.front()?;
+        let elapsed_ms = (last_at - first_at).num_milliseconds();
+        (elapsed_ms > 0)
+            .then(|| (last_price - first_price) * dec!(1000) / Decimal::from(elapsed_ms))
+    }
+
+    /// Smoothed time the platform takes to apply a stop modification
+    pub fn modify_latency(&self) -> Duration {
+        Duration::from_millis(self.modify_latency_ms.load(Ordering::Relaxed))
+    }
+
+    fn record_modify_latency(&self, elapsed: Duration) {
+        let sample = elapsed.as_millis() as u64;
+        let previous = self.modify_latency_ms.load(Ordering::Relaxed);
+        let smoothed = if previous == 0 {
+            sample
+        } else {
+            (previous * 3 + sample) / 4
+        };
+        self.modify_latency_ms.store(smoothed, Ordering::Relaxed);
+    }
+
+    /// MFE retracement of a position if it exceeds the configured threshold
+    fn mfe_retracement(&self, position_id: PositionId, config: &TrailingConfig) -> Option<f64> {
+        let threshold = config.mfe_retracement_threshold?;
+        let retracement = self
+            .excursion_tracker
+            .as_ref()?
+            .get_excursion(position_id)?
+            .mfe_retracement()?;
+        (retracement >= threshold).then_some(retracement)
+    }
+
+    fn should_update_trail(
+        &self,
+        position: &Position,
+        current: &ActiveTrail,
+        update: &TrailUpdate,
+    ) -> bool {
         let improvement = match current.position_type {
             UnifiedPositionSide::Long => update.new_level > current.trail_level,
             UnifiedPositionSide::Short => update.new_level < current.trail_level,
@@ -175,7 +467,9 @@ impl TrailingStopManager {
 
         // Also check minimum movement threshold to avoid excessive updates
         let movement = (update.new_level - current.trail_level).abs();
-        let min_movement = 0.0005; // 0.5 pips minimum movement
+        let min_movement = self
+            .instruments
+            .pips_to_price(&position.symbol, MIN_TRAIL_MOVEMENT_PIPS);
 
         improvement && movement >= min_movement
     }
@@ -187,11 +481,13 @@ impl TrailingStopManager {
             new_take_profit: position.take_profit,
         };
 
+        let started = Instant::now();
         let result = self
             .trading_platform
             .modify_order(modify_request)
             .await
             .context("Failed to modify order for trailing stop update")?;
+        self.record_modify_latency(started.elapsed());
 
         // Update active trail record
         if let Some(mut trail) = self.active_trails.get_mut(&position.id) {
@@ -200,7 +496,7 @@ impl TrailingStopManager {
             trail.update_count += 1;
         }
 
-        self.log_trail_update(position.id, &update).await?;
+        self.log_trail_update(position, &update).await?;
 
         info!(
             "Trailing stop updated for position {}: {} -> {} ({})",
@@ -210,8 +506,41 @@ impl TrailingStopManager {
         Ok(())
     }
 
+    /// Cancel native trailing stops left behind by positions that have closed, so
+    /// they cannot open a new position
+    async fn cancel_orphaned_native_trails(&self, open_positions: &[Position]) {
+        let orphaned: Vec<PositionId> = self
+            .active_trails
+            .iter()
+            .filter(|trail| trail.native_order_id.is_some())
+            .map(|trail| *trail.key())
+            .filter(|id| !open_positions.iter().any(|pos| pos.id == *id))
+            .collect();
+        for position_id in orphaned {
+            if let Err(e) = self.deactivate_trailing_stop(position_id).await {
+                warn!(
+                    "Failed to deactivate trailing stop of closed position {}: {:#}",
+                    position_id, e
+                );
+            }
+        }
+    }
+
     pub async fn deactivate_trailing_stop(&self, position_id: PositionId) -> Result<()> {
         if let Some((_, trail)) = self.active_trails.remove(&position_id) {
+            if let Some(order_id) = &trail.native_order_id {
+                // Already gone if it closed the position
+                if let Err(e) = self
+                    .trading_platform
+                    .cancel_native_trailing_stop(order_id)
+                    .await
+                {
+                    debug!(
+                        "Native trailing stop {} of position {} not cancelled: {:#}",
+                        order_id, position_id, e
+                    );
+                }
+            }
             self.log_trail_deactivation(position_id, trail.trail_level)
                 .await?;
             info!("Trailing stop deactivated for position {}", position_id);
@@ -219,7 +548,7 @@ impl TrailingStopManager {
         Ok(())
     }
 
-    async fn calculate_atr(&self, symbol: &str, period: u32) -> Result<f64> {
+    async fn calculate_atr(&self, symbol: &str, period: u32) -> Result<Decimal> {
         // Check cache first
         if let Some(cached_atr) = self.atr_cache.get(symbol) {
             let cache_age = Utc::now() - cached_atr.calculation_time;
@@ -229,19 +558,24 @@ impl TrailingStopManager {
             }
         }
 
-        // Calculate new ATR (this is a simplified implementation)
-        // In a real system, you would fetch historical price data and calculate ATR properly
         let market_data = self.trading_platform.get_market_data(symbol).await?;
 
-        // Simplified ATR calculation - using current spread as proxy
-        // Real implementation should use True Range over specified period
-        let atr = market_data.spread * 2.0; // Simplified calculation
+        // True range over the candles when there is enough history, otherwise the
+        // current spread as a proxy
+        let atr = match self
+            .candles
+            .as_ref()
+            .and_then(|candles| candles.atr(symbol, ATR_TIMEFRAME, period as usize))
+        {
+            Some(atr) => atr,
+            None => market_data.spread * Decimal::TWO,
+        };
 
         let atr_calc = ATRCalculation {
             symbol: symbol.to_string(),
             period,
             current_atr: atr,
-            normalized_atr: atr / market_data.ask, // ATR as percentage of price
+            normalized_atr: ratio_of(atr, market_data.ask), // ATR as percentage of price
             calculation_time: Utc::now(),
         };
 
@@ -250,42 +584,21 @@ impl TrailingStopManager {
         Ok(atr)
     }
 
-    async fn get_current_price(&self, symbol: &str) -> Result<f64> {
-        let market_data = self.trading_platform.get_market_data(symbol).await?;
-        Ok((market_data.bid + market_data.ask) / 2.0) // Mid price
-    }
-
-    async fn get_open_positions_with_trails(&self) -> Result<Vec<Position>> {
-        let all_positions = self.trading_platform.get_positions().await?;
-
-        // Filter to only positions with active trails
-        let positions_with_trails: Vec<Position> = all_positions
-            .into_iter()
-            .filter(|pos| self.active_trails.contains_key(&pos.id))
-            .collect();
-
-        Ok(positions_with_trails)
-    }
-
     async fn log_trail_activation(
         &self,
-        position_id: PositionId,
-        trail_level: f64,
-        price: f64,
+        position: &Position,
+        trail_level: Decimal,
+        price: Decimal,
     ) -> Result<()> {
-        let market_context = MarketContext {
-            current_price: price,
-            atr_14: self.calculate_atr(&"EURUSD", 14).await.unwrap_or(0.0), // Simplified
-            trend_strength: 0.5,                                            // Simplified
-            volatility: 0.02,                                               // Simplified
-            spread: 0.0001,                                                 // Simplified
-            timestamp: Utc::now(),
-        };
+        let market_context = self
+            .market_context
+            .capture_at(&position.symbol, price)
+            .await;
 
         let modification = ExitModification {
-            position_id,
+            position_id: position.id,
             modification_type: ExitModificationType::TrailingStop,
-            old_value: 0.0,
+            old_value: Decimal::ZERO,
             new_value: trail_level,
             reasoning: "Trailing stop activated - sufficient profit reached".to_string(),
             market_context,
@@ -295,18 +608,14 @@ impl TrailingStopManager {
         Ok(())
     }
 
-    async fn log_trail_update(&self, position_id: PositionId, update: &TrailUpdate) -> Result<()> {
-        let market_context = MarketContext {
-            current_price: update.trigger_price,
-            atr_14: update.atr_used,
-            trend_strength: 0.5, // Simplified
-            volatility: 0.02,    // Simplified
-            spread: 0.0001,      // Simplified
-            timestamp: Utc::now(),
-        };
+    async fn log_trail_update(&self, position: &Position, update: &TrailUpdate) -> Result<()> {
+        let market_context = self
+            .market_context
+            .capture_at(&position.symbol, update.trigger_price)
+            .await;
 
         let modification = ExitModification {
-            position_id,
+            position_id: position.id,
             modification_type: ExitModificationType::TrailingStop,
             old_value: update.old_level,
             new_value: update.new_level,
@@ -321,22 +630,26 @@ impl TrailingStopManager {
     async fn log_trail_deactivation(
         &self,
         position_id: PositionId,
-        final_level: f64,
+        final_level: Decimal,
     ) -> Result<()> {
         let market_context = MarketContext {
-            current_price: 0.0, // Position closed
-            atr_14: 0.0,
+            current_price: Decimal::ZERO, // Position closed
+            atr_14: Decimal::ZERO,
             trend_strength: 0.0,
             volatility: 0.0,
-            spread: 0.0,
+            spread: Decimal::ZERO,
             timestamp: Utc::now(),
+            structure: None,
+            session: None,
+            recent_range: None,
+            news: None,
         };
 
         let modification = ExitModification {
             position_id,
             modification_type: ExitModificationType::TrailingStop,
             old_value: final_level,
-            new_value: 0.0,
+            new_value: Decimal::ZERO,
             reasoning: "Trailing stop deactivated - position closed".to_string(),
             market_context,
         };
@@ -352,6 +665,20 @@ impl TrailingStopManager {
             .collect()
     }
 
+    /// Resume a trail from a checkpoint without touching the order on the platform
+    pub fn restore_trail(&self, trail: ActiveTrail) {
+        self.active_trails.insert(trail.position_id, trail);
+    }
+
+    /// Forget a trail without logging, e.g. for a position that closed while offline
+    pub fn remove_trail(&self, position_id: PositionId) {
+        self.active_trails.remove(&position_id);
+    }
+
+    pub fn get_active_trail(&self, position_id: PositionId) -> Option<ActiveTrail> {
+        self.active_trails.get(&position_id).map(|t| t.clone())
+    }
+
     pub fn get_trail_count(&self) -> usize {
         self.active_trails.len()
     }
@@ -362,10 +689,10 @@ impl TrailingStopManager {
         Ok(TrailingStopStats {
             total_trails: self.active_trails.len() as u32,
             successful_exits: 0, // Would be calculated from historical data
-            average_trail_distance: 0.0, // Would be calculated from historical data
-            profit_captured: rust_decimal::Decimal::ZERO,
-            best_trail_profit: rust_decimal::Decimal::ZERO,
-            worst_trail_loss: rust_decimal::Decimal::ZERO,
+            average_trail_distance: Decimal::ZERO, // Would be calculated from historical data
+            profit_captured: Decimal::ZERO,
+            best_trail_profit: Decimal::ZERO,
+            worst_trail_loss: Decimal::ZERO,
         })
     }
 }
@@ -1,6 +1,10 @@
+use super::market_context::{MarketSession, NewsProximity};
+use crate::market_analysis::MarketStructure;
 pub use crate::platforms::abstraction::models::UnifiedPositionSide;
 use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -9,72 +13,145 @@ pub type PositionId = Uuid;
 pub type OrderId = String;
 pub type Symbol = String;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// `value` scaled by a configured ratio or fraction such as an ATR multiplier
+pub fn scale_by(value: Decimal, factor: f64) -> Decimal {
+    value * Decimal::from_f64(factor).unwrap_or_default()
+}
+
+/// Ratio of two price distances, e.g. profit over initial risk; zero if `denominator` is not positive
+pub fn ratio_of(numerator: Decimal, denominator: Decimal) -> f64 {
+    if denominator <= Decimal::ZERO {
+        return 0.0;
+    }
+    (numerator / denominator).to_f64().unwrap_or(0.0)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TrailingConfig {
     pub atr_multiplier: f64,
-    pub min_trail_distance: f64,
-    pub max_trail_distance: f64,
-    pub activation_threshold: f64,
+    pub min_trail_distance: Decimal,
+    pub max_trail_distance: Decimal,
+    pub activation_threshold: Decimal,
     pub symbol: String,
     pub timeframe: String,
+    /// Fraction of MFE given back that tightens the trail, e.g. 0.5; `None` disables it
+    #[serde(default)]
+    pub mfe_retracement_threshold: Option<f64>,
+    /// Multiplier applied to the trail distance once the retracement threshold is hit
+    #[serde(default = "default_retracement_tighten_factor")]
+    pub retracement_tighten_factor: f64,
+    /// Hand the trail to the platform's own trailing stop where it offers one
+    #[serde(default)]
+    pub native_trailing: bool,
+    /// Trail emulated stops from where recent tick velocity puts the price by the
+    /// time the modification lands, instead of where it was read
+    #[serde(default)]
+    pub latency_compensation: bool,
+    /// Delay between reading the price and modifying the stop, on top of the
+    /// measured modify round-trip, e.g. the check loop interval
+    #[serde(default = "default_latency_allowance_ms")]
+    pub latency_allowance_ms: u64,
+}
+
+fn default_retracement_tighten_factor() -> f64 {
+    0.5
+}
+
+fn default_latency_allowance_ms() -> u64 {
+    500
 }
 
 impl Default for TrailingConfig {
     fn default() -> Self {
         Self {
             atr_multiplier: 2.0,
-            min_trail_distance: 0.0010,   // 10 pips for EURUSD
-            max_trail_distance: 0.0100,   // 100 pips
-            activation_threshold: 0.0015, // 15 pips profit before trailing starts
+            min_trail_distance: dec!(0.0010),   // 10 pips for EURUSD
+            max_trail_distance: dec!(0.0100),   // 100 pips
+            activation_threshold: dec!(0.0015), // 15 pips profit before trailing starts
             symbol: "EURUSD".to_string(),
             timeframe: "H1".to_string(),
+            mfe_retracement_threshold: None,
+            retracement_tighten_factor: default_retracement_tighten_factor(),
+            native_trailing: false,
+            latency_compensation: false,
+            latency_allowance_ms: default_latency_allowance_ms(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ActiveTrail {
     pub position_id: PositionId,
-    pub trail_level: f64,
-    pub original_stop: f64,
+    pub trail_level: Decimal,
+    pub original_stop: Decimal,
     pub position_type: UnifiedPositionSide,
     pub last_updated: DateTime<Utc>,
     pub update_count: u32,
-    pub activation_price: f64,
+    pub activation_price: Decimal,
+    /// Platform order trailing the stop natively; the engine leaves such trails alone
+    #[serde(default)]
+    pub native_order_id: Option<String>,
+}
+
+/// Platform-native trailing stop protecting a position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NativeTrailingStopRequest {
+    pub position_id: PositionId,
+    pub order_id: String,
+    pub symbol: String,
+    pub position_type: UnifiedPositionSide,
+    pub volume: Decimal,
+    /// Level the stop starts at
+    pub stop_level: Decimal,
+    /// Distance the platform keeps the stop behind the market
+    pub trail_distance: Decimal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrailUpdate {
     pub position_id: PositionId,
-    pub old_level: f64,
-    pub new_level: f64,
-    pub atr_used: f64,
-    pub distance_pips: f64,
-    pub trigger_price: f64,
+    pub old_level: Decimal,
+    pub new_level: Decimal,
+    pub atr_used: Decimal,
+    pub distance_pips: Decimal,
+    pub trigger_price: Decimal,
     pub update_reason: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BreakEvenConfig {
     pub trigger_ratio: f64, // 1.0 for 1:1 R:R
-    pub break_even_buffer_pips: f64,
+    pub break_even_buffer_pips: Decimal,
     pub enabled: bool,
+    /// Fraction of the position to close once the stop is at break-even, e.g. 0.5
+    #[serde(default)]
+    pub partial_close_percent: Option<f64>,
+    /// Also trigger once price has broken a support or resistance zone between the
+    /// entry and the current price, ahead of the R:R trigger
+    #[serde(default)]
+    pub structure_trigger: bool,
 }
 
 impl Default for BreakEvenConfig {
     fn default() -> Self {
         Self {
             trigger_ratio: 1.0,
-            break_even_buffer_pips: 5.0,
+            break_even_buffer_pips: dec!(5),
             enabled: true,
+            partial_close_percent: None,
+            structure_trigger: false,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProfitTakingConfig {
     pub profit_targets: Vec<ProfitTarget>,
     pub enabled: bool,
+    /// Pull the take-profit in to just short of the nearest resistance (longs) or
+    /// support (shorts) when that is closer than the current one
+    #[serde(default)]
+    pub take_profit_at_structure: bool,
 }
 
 impl Default for ProfitTakingConfig {
@@ -93,23 +170,27 @@ impl Default for ProfitTakingConfig {
                 },
             ],
             enabled: true,
+            take_profit_at_structure: false,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProfitTarget {
     pub level: u32,
     pub risk_reward_ratio: f64,
     pub close_percentage: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TimeExitConfig {
     pub max_hold_duration: Duration,
     pub warning_duration: Duration,
     pub enabled: bool,
     pub trend_strength_override_threshold: f64,
+    /// Close the position ahead of the weekend market close regardless of its age
+    #[serde(default)]
+    pub close_before_weekend: bool,
 }
 
 impl Default for TimeExitConfig {
@@ -121,6 +202,7 @@ impl Default for TimeExitConfig {
                 .unwrap(),
             enabled: true,
             trend_strength_override_threshold: 0.8,
+            close_before_weekend: false,
         }
     }
 }
@@ -162,7 +244,7 @@ pub struct NewsEvent {
     pub time: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ImpactLevel {
     Low,
     Medium,
@@ -172,8 +254,8 @@ pub enum ImpactLevel {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewsProtection {
     pub position_id: PositionId,
-    pub original_stop: f64,
-    pub protected_stop: f64,
+    pub original_stop: Decimal,
+    pub protected_stop: Decimal,
     pub news_event: NewsEvent,
     pub protection_start: DateTime<Utc>,
     pub restoration_scheduled: Option<DateTime<Utc>>,
@@ -183,8 +265,8 @@ pub struct NewsProtection {
 pub struct ExitModification {
     pub position_id: PositionId,
     pub modification_type: ExitModificationType,
-    pub old_value: f64,
-    pub new_value: f64,
+    pub old_value: Decimal,
+    pub new_value: Decimal,
     pub reasoning: String,
     pub market_context: MarketContext,
 }
@@ -200,12 +282,24 @@ pub enum ExitModificationType {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketContext {
-    pub current_price: f64,
-    pub atr_14: f64,
+    pub current_price: Decimal,
+    pub atr_14: Decimal,
     pub trend_strength: f64,
     pub volatility: f64,
-    pub spread: f64,
+    pub spread: Decimal,
     pub timestamp: DateTime<Utc>,
+    /// Support and resistance around the price, where the exit read it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structure: Option<MarketStructure>,
+    /// Forex session open at the time
+    #[serde(default)]
+    pub session: Option<MarketSession>,
+    /// High to low of the recent candles
+    #[serde(default)]
+    pub recent_range: Option<Decimal>,
+    /// Nearest calendar event for either currency of the symbol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub news: Option<NewsProximity>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -213,7 +307,7 @@ pub struct ExitResult {
     pub position_id: PositionId,
     pub exit_type: ExitModificationType,
     pub success: bool,
-    pub exit_price: Option<f64>,
+    pub exit_price: Option<Decimal>,
     pub volume_closed: Option<Decimal>,
     pub profit_loss: Option<Decimal>,
     pub message: String,
@@ -225,12 +319,15 @@ pub struct AuditEntry {
     pub entry_id: Uuid,
     pub position_id: PositionId,
     pub modification_type: ExitModificationType,
-    pub old_value: f64,
-    pub new_value: f64,
+    pub old_value: Decimal,
+    pub new_value: Decimal,
     pub reasoning: String,
     pub market_context: MarketContext,
     pub performance_impact: f64,
     pub timestamp: DateTime<Utc>,
+    /// Tags of the order that opened the position
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -242,13 +339,33 @@ pub struct ExitPerformanceReport {
     pub news_protection_stats: NewsProtectionStats,
     pub overall_performance: f64,
     pub report_period: ReportPeriod,
+    /// Counterfactual P&L of the shadow exit variants, per position
+    #[serde(default)]
+    pub shadow_results: Vec<ShadowExitResult>,
+}
+
+/// How one position did under a shadow exit variant compared to live exit management.
+/// P&L is price movement times volume, so live and shadow figures are comparable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShadowExitResult {
+    pub position_id: PositionId,
+    pub symbol: String,
+    pub variant: String,
+    pub live_pnl: Decimal,
+    pub shadow_pnl: Decimal,
+    /// Shadow minus live; positive when the variant would have done better
+    pub pnl_difference: Decimal,
+    pub live_closed: bool,
+    pub shadow_closed: bool,
+    pub shadow_exit_reason: Option<String>,
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrailingStopStats {
     pub total_trails: u32,
     pub successful_exits: u32,
-    pub average_trail_distance: f64,
+    pub average_trail_distance: Decimal,
     pub profit_captured: Decimal,
     pub best_trail_profit: Decimal,
     pub worst_trail_loss: Decimal,
@@ -296,7 +413,7 @@ pub struct ReportPeriod {
 pub struct ATRCalculation {
     pub symbol: String,
     pub period: u32,
-    pub current_atr: f64,
+    pub current_atr: Decimal,
     pub normalized_atr: f64, // ATR as percentage of price
     pub calculation_time: DateTime<Utc>,
 }
@@ -307,7 +424,7 @@ pub struct MarketConditions {
     pub trend_strength: f64,
     pub volatility: f64,
     pub volume_profile: f64,
-    pub support_resistance_levels: Vec<f64>,
+    pub support_resistance_levels: Vec<Decimal>,
     pub analysis_time: DateTime<Utc>,
 }
 
@@ -315,8 +432,8 @@ pub struct MarketConditions {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderModifyRequest {
     pub order_id: String,
-    pub new_stop_loss: Option<f64>,
-    pub new_take_profit: Option<f64>,
+    pub new_stop_loss: Option<Decimal>,
+    pub new_take_profit: Option<Decimal>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -342,11 +459,146 @@ pub struct PartialCloseRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClosePositionResult {
     pub position_id: PositionId,
-    pub close_price: f64,
+    pub close_price: Decimal,
     pub realized_pnl: Option<Decimal>,
     pub close_time: DateTime<Utc>,
 }
 
+/// Furthest a position has moved against and in favour of its entry, in price units
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionExcursion {
+    pub position_id: PositionId,
+    pub position_type: UnifiedPositionSide,
+    pub entry_price: Decimal,
+    pub max_adverse_excursion: Decimal,
+    pub max_favorable_excursion: Decimal,
+    pub last_price: Decimal,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PositionExcursion {
+    pub fn new(position: &Position) -> Self {
+        Self {
+            position_id: position.id,
+            position_type: position.position_type.clone(),
+            entry_price: position.entry_price,
+            max_adverse_excursion: Decimal::ZERO,
+            max_favorable_excursion: Decimal::ZERO,
+            last_price: position.entry_price,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Current move in favour of the entry; negative when the position is losing
+    pub fn current_excursion(&self) -> Decimal {
+        match self.position_type {
+            UnifiedPositionSide::Long => self.last_price - self.entry_price,
+            UnifiedPositionSide::Short => self.entry_price - self.last_price,
+        }
+    }
+
+    /// Fraction of the MFE given back since the peak, or `None` before any favorable move
+    pub fn mfe_retracement(&self) -> Option<f64> {
+        if self.max_favorable_excursion <= Decimal::ZERO {
+            return None;
+        }
+        Some(ratio_of(
+            self.max_favorable_excursion - self.current_excursion(),
+            self.max_favorable_excursion,
+        ))
+    }
+
+    /// Record a price. Returns true if the MAE or MFE widened.
+    pub fn observe(&mut self, price: Decimal) -> bool {
+        self.last_price = price;
+        self.updated_at = Utc::now();
+
+        let excursion = self.current_excursion();
+        let mut widened = false;
+        if excursion > self.max_favorable_excursion {
+            self.max_favorable_excursion = excursion;
+            widened = true;
+        }
+        if -excursion > self.max_adverse_excursion {
+            self.max_adverse_excursion = -excursion;
+            widened = true;
+        }
+        widened
+    }
+}
+
+/// What exit management is currently doing with one position
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PositionExitState {
+    pub trailing_stop: Option<ActiveTrail>,
+    pub break_even_active: bool,
+    pub profit_targets_hit: Vec<u32>,
+    pub remaining_volume: Option<Decimal>,
+    pub time_exit_warned: bool,
+    pub excursion: Option<PositionExcursion>,
+}
+
+/// What the exit managers would do to one position if they ran now. Worked out
+/// from the same rules they act on, without modifying or closing anything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitPreview {
+    pub position_id: PositionId,
+    pub symbol: String,
+    pub volume: Decimal,
+    pub current_price: Decimal,
+    pub stop_loss: Option<Decimal>,
+    /// None when the trailing stop would not move
+    pub trailing_stop: Option<StopPreview>,
+    /// None when break-even would not trigger
+    pub break_even: Option<StopPreview>,
+    /// Profit targets that would close part of the position, in order
+    pub partial_profits: Vec<PartialClosePreview>,
+    /// None when no time exit applies to the position
+    pub time_exit: Option<TimeExitPreview>,
+    pub news_protected: bool,
+    pub evaluated_at: DateTime<Utc>,
+}
+
+impl ExitPreview {
+    /// Whether any manager would touch the position
+    pub fn would_act(&self) -> bool {
+        self.trailing_stop.is_some()
+            || self.break_even.is_some()
+            || !self.partial_profits.is_empty()
+            || self
+                .time_exit
+                .as_ref()
+                .is_some_and(|t| t.would_exit || t.would_warn)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StopPreview {
+    pub current_stop: Option<Decimal>,
+    pub new_stop: Decimal,
+    /// Volume closed along with the stop move, if any
+    pub close_volume: Option<Decimal>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartialClosePreview {
+    pub level: u32,
+    pub risk_reward_ratio: f64,
+    pub current_risk_reward: f64,
+    pub close_volume: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeExitPreview {
+    pub age_secs: i64,
+    /// Time left until the maximum hold, negative once it has passed
+    pub exits_in_secs: i64,
+    pub would_warn: bool,
+    pub would_exit: bool,
+    pub reason: Option<String>,
+}
+
 // Simple position struct for exit management
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
@@ -355,13 +607,13 @@ pub struct Position {
     pub symbol: String,
     pub position_type: UnifiedPositionSide,
     pub volume: Decimal,
-    pub entry_price: f64,
-    pub current_price: f64,
-    pub stop_loss: Option<f64>,
-    pub take_profit: Option<f64>,
-    pub unrealized_pnl: f64,
-    pub swap: f64,
-    pub commission: f64,
+    pub entry_price: Decimal,
+    pub current_price: Decimal,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    pub unrealized_pnl: Decimal,
+    pub swap: Decimal,
+    pub commission: Decimal,
     pub open_time: DateTime<Utc>,
     pub magic_number: Option<i32>,
     pub comment: Option<String>,
@@ -371,8 +623,22 @@ pub struct Position {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketData {
     pub symbol: String,
-    pub bid: f64,
-    pub ask: f64,
-    pub spread: f64,
+    pub bid: Decimal,
+    pub ask: Decimal,
+    pub spread: Decimal,
     pub timestamp: DateTime<Utc>,
 }
+
+impl MarketData {
+    pub fn mid(&self) -> Decimal {
+        (self.bid + self.ask) / Decimal::TWO
+    }
+
+    /// Price a position on `side` closes at, which its stop is measured from
+    pub fn closing_price(&self, side: &UnifiedPositionSide) -> Decimal {
+        match side {
+            UnifiedPositionSide::Long => self.bid,
+            UnifiedPositionSide::Short => self.ask,
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::time::{Duration, SystemTime};
+use tokio_postgres::types::ToSql;
+use tokio_postgres::{Client, NoTls};
+use tracing::error;
+
+use super::{ExecutionHistoryPage, ExecutionHistoryQuery, ExecutionHistoryStore, RetentionPolicy};
+use crate::execution::orchestrator::ExecutionAuditEntry;
+use crate::runtime::spawn::spawn_isolated;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS execution_history (
+    seq BIGSERIAL PRIMARY KEY,
+    id TEXT NOT NULL UNIQUE,
+    recorded_at TIMESTAMPTZ NOT NULL,
+    signal_id TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    entry JSONB NOT NULL
+);
+CREATE INDEX IF NOT EXISTS execution_history_signal_idx
+    ON execution_history (signal_id, recorded_at);
+CREATE INDEX IF NOT EXISTS execution_history_account_idx
+    ON execution_history (account_id, recorded_at);
+CREATE INDEX IF NOT EXISTS execution_history_recorded_at_idx
+    ON execution_history (recorded_at);
+";
+
+type Param = Box<dyn ToSql + Sync + Send>;
+
+/// Execution history in the `execution_history` table, created with its indexes
+/// on connect. Each entry is kept whole as JSONB next to the columns queries
+/// filter on.
+#[derive(Debug)]
+pub struct PostgresExecutionHistoryStore {
+    client: Client,
+}
+
+impl PostgresExecutionHistoryStore {
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let (client, connection) = tokio_postgres::connect(database_url, NoTls)
+            .await
+            .context("Failed to connect to the execution history database")?;
+        spawn_isolated("execution-history-connection", async move {
+            if let Err(e) = connection.await {
+                error!("Execution history database connection closed: {}", e);
+            }
+        });
+        client
+            .batch_execute(SCHEMA)
+            .await
+            .context("Failed to create the execution history table")?;
+        Ok(Self { client })
+    }
+}
+
+/// `WHERE` clause and its parameters for the filters of `query`
+fn filters(query: &ExecutionHistoryQuery) -> (String, Vec<Param>) {
+    let mut clauses = Vec::new();
+    let mut params: Vec<Param> = Vec::new();
+    let mut add = |clause: &str, param: Param| {
+        params.push(param);
+        clauses.push(clause.replace('?', &format!("${}", params.len())));
+    };
+
+    if let Some(signal_id) = &query.signal_id {
+        add("signal_id = ?", Box::new(signal_id.clone()));
+    }
+    if let Some(account_id) = &query.account_id {
+        add("account_id = ?", Box::new(account_id.clone()));
+    }
+    if let Some(action) = &query.action {
+        add("action = ?", Box::new(action.clone()));
+    }
+    if let Some(since) = query.since {
+        add("recorded_at >= ?", Box::new(since));
+    }
+    if let Some(until) = query.until {
+        add("recorded_at < ?", Box::new(until));
+    }
+    if !query.tags.is_empty() {
+        add("tags @> ?", Box::new(query.tags.clone()));
+    }
+
+    let clause = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", clauses.join(" AND "))
+    };
+    (clause, params)
+}
+
+fn refs(params: &[Param]) -> Vec<&(dyn ToSql + Sync)> {
+    params
+        .iter()
+        .map(|param| param.as_ref() as &(dyn ToSql + Sync))
+        .collect()
+}
+
+fn cutoff(now: SystemTime, days: u32) -> SystemTime {
+    now - Duration::from_secs(u64::from(days) * 86_400)
+}
+
+#[async_trait]
+impl ExecutionHistoryStore for PostgresExecutionHistoryStore {
+    async fn append(&self, entry: &ExecutionAuditEntry) -> Result<()> {
+        self.client
+            .execute(
+                "INSERT INTO execution_history
+                    (id, recorded_at, signal_id, account_id, action, tags, entry)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT (id) DO NOTHING",
+                &[
+                    &entry.id,
+                    &entry.timestamp,
+                    &entry.signal_id,
+                    &entry.account_id,
+                    &entry.action,
+                    &entry.tags,
+                    &serde_json::to_value(entry)?,
+                ],
+            )
+            .await
+            .with_context(|| format!("Failed to store execution history entry {}", entry.id))?;
+        Ok(())
+    }
+
+    async fn query(&self, query: &ExecutionHistoryQuery) -> Result<ExecutionHistoryPage> {
+        let (clause, mut params) = filters(query);
+        let total: i64 = self
+            .client
+            .query_one(
+                &format!("SELECT COUNT(*) FROM execution_history{}", clause),
+                &refs(&params),
+            )
+            .await?
+            .get(0);
+
+        params.push(Box::new(query.limit as i64));
+        params.push(Box::new(query.offset as i64));
+        let rows = self
+            .client
+            .query(
+                &format!(
+                    "SELECT entry FROM execution_history{} \
+                     ORDER BY recorded_at DESC, seq DESC LIMIT ${} OFFSET ${}",
+                    clause,
+                    params.len() - 1,
+                    params.len()
+                ),
+                &refs(&params),
+            )
+            .await?;
+
+        let mut entries = rows
+            .into_iter()
+            .map(|row| {
+                serde_json::from_value::<ExecutionAuditEntry>(row.get(0))
+                    .context("Unreadable execution history entry")
+            })
+            .collect::<Result<Vec<_>>>()?;
+        entries.reverse();
+        Ok(ExecutionHistoryPage::new(entries, total as usize, query))
+    }
+
+    async fn purge(&self, policy: &RetentionPolicy, now: SystemTime) -> Result<u64> {
+        let mut purged = 0;
+        for (action, days) in &policy.actions {
+            if *days == 0 {
+                continue;
+            }
+            purged += self
+                .client
+                .execute(
+                    "DELETE FROM execution_history WHERE action = $1 AND recorded_at < $2",
+                    &[action, &cutoff(now, *days)],
+                )
+                .await?;
+        }
+
+        if policy.default_days > 0 {
+            let overridden: Vec<String> = policy.actions.keys().cloned().collect();
+            purged += self
+                .client
+                .execute(
+                    "DELETE FROM execution_history
+                     WHERE action <> ALL($1) AND recorded_at < $2",
+                    &[&overridden, &cutoff(now, policy.default_days)],
+                )
+                .await?;
+        }
+        Ok(purged)
+    }
+}
@@ -0,0 +1,570 @@
+// Ladder entries: one signal split into limit orders stepped away from the
+// signal price, managed as a group with a shared stop and target
+
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::instruments::InstrumentMetadata;
+use crate::platforms::abstraction::interfaces::ITradingPlatform;
+use crate::platforms::abstraction::models::{
+    OrderMetadata, OrderModification, UnifiedMarketData, UnifiedOrder, UnifiedOrderSide,
+    UnifiedOrderStatus, UnifiedOrderType, UnifiedPositionSide, UnifiedTimeInForce,
+};
+
+/// How a ladder's total size is shared between its rungs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeDistribution {
+    #[default]
+    Equal,
+    /// Each rung one unit larger than the one before it, so the most size sits at
+    /// the best prices
+    Pyramid,
+    /// Explicit relative weights, one per rung, nearest rung first
+    Weights(Vec<f64>),
+}
+
+/// Ladder instructions from a signal's `entry_ladder` metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LadderConfig {
+    pub rungs: usize,
+    pub spacing_pips: f64,
+    #[serde(default)]
+    pub distribution: SizeDistribution,
+    /// Spread the rungs evenly either side of the signal price instead of
+    /// stepping only towards better prices
+    #[serde(default)]
+    pub centered: bool,
+}
+
+impl LadderConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rungs < 2 {
+            return Err("a ladder needs at least two rungs".to_string());
+        }
+        if !self.spacing_pips.is_finite() || self.spacing_pips <= 0.0 {
+            return Err(format!(
+                "rung spacing must be a positive number of pips, got {}",
+                self.spacing_pips
+            ));
+        }
+        if let SizeDistribution::Weights(weights) = &self.distribution {
+            if weights.len() != self.rungs {
+                return Err(format!(
+                    "{} weights given for {} rungs",
+                    weights.len(),
+                    self.rungs
+                ));
+            }
+            if weights.iter().any(|w| !w.is_finite() || *w <= 0.0) {
+                return Err("rung weights must be positive".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Share of the total size on each rung, nearest rung first, summing to one
+    pub fn weights(&self) -> Vec<f64> {
+        let raw: Vec<f64> = match &self.distribution {
+            SizeDistribution::Equal => vec![1.0; self.rungs],
+            SizeDistribution::Pyramid => (1..=self.rungs).map(|i| i as f64).collect(),
+            SizeDistribution::Weights(weights) => weights.clone(),
+        };
+        let total: f64 = raw.iter().sum();
+        raw.into_iter().map(|w| w / total).collect()
+    }
+
+    /// Rung prices for an entry at `entry`, nearest first. Buys step down and
+    /// sells step up; a centered ladder starts as far above (or below) the price
+    /// as its last rung ends below (or above) it.
+    pub fn rung_prices(
+        &self,
+        symbol: &str,
+        side: &UnifiedOrderSide,
+        entry: Decimal,
+    ) -> Vec<Decimal> {
+        let instrument = InstrumentMetadata::conventional(symbol);
+        let spacing =
+            instrument.pips_to_price(Decimal::from_f64(self.spacing_pips).unwrap_or_default());
+        let direction = match side {
+            UnifiedOrderSide::Buy => -Decimal::ONE,
+            UnifiedOrderSide::Sell => Decimal::ONE,
+        };
+        let start = if self.centered {
+            -spacing * Decimal::from(self.rungs - 1) / Decimal::TWO
+        } else {
+            Decimal::ZERO
+        };
+        (0..self.rungs)
+            .map(|i| {
+                let offset = start + spacing * Decimal::from(i);
+                instrument.round_price(entry + direction * offset)
+            })
+            .collect()
+    }
+}
+
+/// Everything an account needs to open its ladder for a signal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LadderPlan {
+    pub config: LadderConfig,
+    pub side: UnifiedOrderSide,
+    pub entry_price: f64,
+    pub stop_loss: f64,
+    pub take_profit: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RungStatus {
+    Working,
+    Filled,
+    Cancelled,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LadderRung {
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub order_id: Option<String>,
+    pub status: RungStatus,
+    pub fill_price: Option<Decimal>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LadderState {
+    /// Rungs are still working
+    Active,
+    /// The group's stop was reached and the unfilled rungs cancelled
+    Stopped,
+    /// The position closed, by its stop, its target or by hand, and the unfilled
+    /// rungs were cancelled
+    Closed,
+    /// Every rung has filled or gone
+    Complete,
+}
+
+/// One account's ladder for one signal. The stop and target keep the signal's
+/// distances from the average fill price, so they move as deeper rungs fill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LadderGroup {
+    pub signal_id: String,
+    pub account_id: String,
+    pub symbol: String,
+    pub side: UnifiedOrderSide,
+    pub rungs: Vec<LadderRung>,
+    pub stop_distance: Decimal,
+    pub target_distance: Decimal,
+    pub stop_loss: Decimal,
+    pub take_profit: Decimal,
+    pub state: LadderState,
+    pub created_at: DateTime<Utc>,
+    /// Risk budget each filled unit uses on the account
+    #[serde(default)]
+    pub risk_per_unit: f64,
+}
+
+/// Quantity a group's rungs filled since the last poll
+#[derive(Debug, Clone, PartialEq)]
+pub struct LadderFill {
+    pub signal_id: String,
+    pub account_id: String,
+    pub quantity: Decimal,
+    pub risk_per_unit: f64,
+    /// True for the group's first fill, which opens its position
+    pub opens_position: bool,
+}
+
+impl LadderGroup {
+    pub fn new(
+        signal_id: &str,
+        account_id: &str,
+        symbol: &str,
+        plan: &LadderPlan,
+        quantity: Decimal,
+    ) -> Result<Self, String> {
+        plan.config.validate()?;
+        let to_decimal = |value: f64, name: &str| {
+            Decimal::from_f64(value).ok_or_else(|| format!("invalid {}: {}", name, value))
+        };
+        let entry = to_decimal(plan.entry_price, "entry price")?;
+        let stop_loss = to_decimal(plan.stop_loss, "stop loss")?;
+        let take_profit = to_decimal(plan.take_profit, "take profit")?;
+
+        let prices = plan.config.rung_prices(symbol, &plan.side, entry);
+        let beyond_stop = |price: &Decimal| match plan.side {
+            UnifiedOrderSide::Buy => *price <= stop_loss,
+            UnifiedOrderSide::Sell => *price >= stop_loss,
+        };
+        if let Some(price) = prices.iter().find(|p| beyond_stop(p)) {
+            return Err(format!(
+                "rung at {} is at or beyond the stop loss of {}",
+                price, stop_loss
+            ));
+        }
+
+        let rungs = prices
+            .into_iter()
+            .zip(plan.config.weights())
+            .map(|(price, weight)| LadderRung {
+                price,
+                quantity: quantity * Decimal::from_f64(weight).unwrap_or_default(),
+                order_id: None,
+                status: RungStatus::Working,
+                fill_price: None,
+                error: None,
+            })
+            .collect();
+
+        Ok(Self {
+            signal_id: signal_id.to_string(),
+            account_id: account_id.to_string(),
+            symbol: symbol.to_string(),
+            side: plan.side.clone(),
+            rungs,
+            stop_distance: (entry - stop_loss).abs(),
+            target_distance: (take_profit - entry).abs(),
+            stop_loss,
+            take_profit,
+            state: LadderState::Active,
+            created_at: Utc::now(),
+            risk_per_unit: 0.0,
+        })
+    }
+
+    pub fn with_risk_per_unit(mut self, risk_per_unit: f64) -> Self {
+        self.risk_per_unit = risk_per_unit;
+        self
+    }
+
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.signal_id, self.account_id)
+    }
+
+    pub fn filled_quantity(&self) -> Decimal {
+        self.rungs
+            .iter()
+            .filter(|r| r.status == RungStatus::Filled)
+            .map(|r| r.quantity)
+            .sum()
+    }
+
+    /// Quantity-weighted price of the filled rungs, to 8 dp so uneven rung sizes
+    /// do not leave a price with more digits than any platform quotes
+    pub fn average_entry(&self) -> Option<Decimal> {
+        let filled = self.filled_quantity();
+        if filled.is_zero() {
+            return None;
+        }
+        let notional: Decimal = self
+            .rungs
+            .iter()
+            .filter(|r| r.status == RungStatus::Filled)
+            .map(|r| r.fill_price.unwrap_or(r.price) * r.quantity)
+            .sum();
+        Some((notional / filled).round_dp(8))
+    }
+
+    pub fn working_rungs(&self) -> usize {
+        self.rungs
+            .iter()
+            .filter(|r| r.status == RungStatus::Working)
+            .count()
+    }
+
+    /// Move the stop and target to the signal's distances from the average fill.
+    /// Returns true if either changed.
+    pub fn reprice(&mut self) -> bool {
+        let Some(average) = self.average_entry() else {
+            return false;
+        };
+        let instrument = InstrumentMetadata::conventional(&self.symbol);
+        let (stop_loss, take_profit) = match self.side {
+            UnifiedOrderSide::Buy => (average - self.stop_distance, average + self.target_distance),
+            UnifiedOrderSide::Sell => {
+                (average + self.stop_distance, average - self.target_distance)
+            }
+        };
+        let stop_loss = instrument.round_price(stop_loss);
+        let take_profit = instrument.round_price(take_profit);
+        let changed = stop_loss != self.stop_loss || take_profit != self.take_profit;
+        self.stop_loss = stop_loss;
+        self.take_profit = take_profit;
+        changed
+    }
+
+    /// True once the price a long (short) exits at has reached the stop
+    pub fn stop_triggered(&self, quote: &UnifiedMarketData) -> bool {
+        match self.side {
+            UnifiedOrderSide::Buy => quote.bid <= self.stop_loss,
+            UnifiedOrderSide::Sell => quote.ask >= self.stop_loss,
+        }
+    }
+}
+
+/// Ladder groups opened by the orchestrator, kept in step with their platforms
+/// by `poll`
+#[derive(Default)]
+pub struct LadderManager {
+    groups: RwLock<HashMap<String, LadderGroup>>,
+}
+
+impl LadderManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Place every rung of a new group. The group is kept if at least one rung
+    /// is working; rungs the platform refused are recorded as rejected.
+    pub async fn open(
+        &self,
+        platform: &Arc<dyn ITradingPlatform + Send + Sync>,
+        mut group: LadderGroup,
+        tags: &[String],
+    ) -> Result<LadderGroup, String> {
+        let capabilities = platform.capabilities();
+        for rung in &mut group.rungs {
+            let mut order = UnifiedOrder {
+                client_order_id: Uuid::new_v4().to_string(),
+                symbol: group.symbol.clone(),
+                side: group.side.clone(),
+                order_type: UnifiedOrderType::Limit,
+                quantity: rung.quantity,
+                price: Some(rung.price),
+                stop_price: None,
+                stop_loss: Some(group.stop_loss),
+                take_profit: Some(group.take_profit),
+                time_in_force: UnifiedTimeInForce::Gtc,
+                account_id: Some(group.account_id.clone()),
+                metadata: OrderMetadata {
+                    strategy_id: None,
+                    signal_id: Some(group.signal_id.clone()),
+                    risk_parameters: HashMap::new(),
+                    tags: tags.to_vec(),
+                    expires_at: None,
+                },
+                max_slippage: None,
+            };
+            if let Err(e) = capabilities.normalize_order_quantity(&mut order) {
+                rung.status = RungStatus::Rejected;
+                rung.error = Some(e.to_string());
+                continue;
+            }
+            rung.quantity = order.quantity;
+            match platform.place_order(order).await {
+                Ok(response) => rung.order_id = Some(response.platform_order_id),
+                Err(e) => {
+                    rung.status = RungStatus::Rejected;
+                    rung.error = Some(e.to_string());
+                }
+            }
+        }
+
+        if group.working_rungs() == 0 {
+            let errors: Vec<_> = group.rungs.iter().filter_map(|r| r.error.clone()).collect();
+            return Err(format!("No ladder rung was placed: {}", errors.join("; ")));
+        }
+        info!(
+            "Opened {}-rung ladder on {} for signal {} on account {}",
+            group.working_rungs(),
+            group.symbol,
+            group.signal_id,
+            group.account_id
+        );
+        self.groups.write().await.insert(group.key(), group.clone());
+        Ok(group)
+    }
+
+    pub async fn groups(&self) -> Vec<LadderGroup> {
+        self.groups.read().await.values().cloned().collect()
+    }
+
+    pub async fn group(&self, signal_id: &str, account_id: &str) -> Option<LadderGroup> {
+        self.groups
+            .read()
+            .await
+            .get(&format!("{}:{}", signal_id, account_id))
+            .cloned()
+    }
+
+    /// Bring every active group up to date with its platform: record fills,
+    /// move the position's stop and target after new fills, and cancel the
+    /// unfilled rungs once the stop is reached or the position has closed.
+    /// Returns what each group filled since the last poll.
+    pub async fn poll(
+        &self,
+        platforms: &HashMap<String, Arc<dyn ITradingPlatform + Send + Sync>>,
+    ) -> Vec<LadderFill> {
+        let active: Vec<LadderGroup> = self
+            .groups
+            .read()
+            .await
+            .values()
+            .filter(|g| g.state == LadderState::Active)
+            .cloned()
+            .collect();
+
+        let mut fills = Vec::new();
+        for mut group in active {
+            let Some(platform) = platforms.get(&group.account_id) else {
+                continue;
+            };
+            let filled_before = group.filled_quantity();
+            sync_group(platform, &mut group).await;
+            let filled = group.filled_quantity();
+            if filled > filled_before {
+                fills.push(LadderFill {
+                    signal_id: group.signal_id.clone(),
+                    account_id: group.account_id.clone(),
+                    quantity: filled - filled_before,
+                    risk_per_unit: group.risk_per_unit,
+                    opens_position: filled_before.is_zero(),
+                });
+            }
+            self.groups.write().await.insert(group.key(), group);
+        }
+        fills
+    }
+}
+
+async fn sync_group(platform: &Arc<dyn ITradingPlatform + Send + Sync>, group: &mut LadderGroup) {
+    let filled_before = group.filled_quantity();
+    for rung in group
+        .rungs
+        .iter_mut()
+        .filter(|r| r.status == RungStatus::Working)
+    {
+        let Some(order_id) = &rung.order_id else {
+            continue;
+        };
+        match platform.get_order(order_id).await {
+            Ok(order) => match order.status {
+                UnifiedOrderStatus::Filled => {
+                    rung.status = RungStatus::Filled;
+                    rung.fill_price = order.average_fill_price.or(order.price);
+                }
+                UnifiedOrderStatus::Canceled | UnifiedOrderStatus::Expired => {
+                    rung.status = RungStatus::Cancelled
+                }
+                UnifiedOrderStatus::Rejected => rung.status = RungStatus::Rejected,
+                _ => {}
+            },
+            Err(e) => debug!("Could not check ladder rung {}: {}", order_id, e),
+        }
+    }
+
+    let filled = group.filled_quantity();
+    if filled > filled_before && group.reprice() {
+        apply_exits(platform, group).await;
+    }
+
+    if filled > Decimal::ZERO {
+        match platform.get_position(&group.symbol).await {
+            Ok(None) => {
+                info!(
+                    "Ladder position for signal {} on {} closed, cancelling the rest",
+                    group.signal_id, group.account_id
+                );
+                cancel_working(platform, group).await;
+                group.state = LadderState::Closed;
+                return;
+            }
+            Ok(Some(_)) => {}
+            Err(e) => debug!("Could not check ladder position {}: {}", group.key(), e),
+        }
+    }
+
+    if group.working_rungs() == 0 {
+        group.state = LadderState::Complete;
+        return;
+    }
+
+    match platform.get_market_data(&group.symbol).await {
+        Ok(quote) if group.stop_triggered(&quote) => {
+            info!(
+                "Ladder stop {} reached for signal {} on {}, cancelling {} unfilled rungs",
+                group.stop_loss,
+                group.signal_id,
+                group.account_id,
+                group.working_rungs()
+            );
+            cancel_working(platform, group).await;
+            group.state = LadderState::Stopped;
+        }
+        Ok(_) => {}
+        Err(e) => debug!("No price to check ladder stop {}: {}", group.key(), e),
+    }
+}
+
+/// Put the group's stop and target on every ticket of the open position, so
+/// hedging accounts holding one ticket per rung move together
+async fn apply_exits(platform: &Arc<dyn ITradingPlatform + Send + Sync>, group: &LadderGroup) {
+    let side = match group.side {
+        UnifiedOrderSide::Buy => UnifiedPositionSide::Long,
+        UnifiedOrderSide::Sell => UnifiedPositionSide::Short,
+    };
+    let tickets: Vec<_> = match platform.get_positions().await {
+        Ok(positions) => positions
+            .into_iter()
+            .filter(|p| p.symbol == group.symbol && p.side == side)
+            .collect(),
+        Err(e) => {
+            warn!("Could not find ladder position {}: {}", group.key(), e);
+            return;
+        }
+    };
+    for ticket in tickets {
+        let modification = OrderModification {
+            quantity: None,
+            price: None,
+            stop_price: None,
+            take_profit: Some(group.take_profit),
+            stop_loss: Some(group.stop_loss),
+            time_in_force: None,
+        };
+        if let Err(e) = platform
+            .modify_order(&ticket.position_id, modification)
+            .await
+        {
+            warn!("Failed to move ladder exits for {}: {}", group.key(), e);
+        }
+    }
+    info!(
+        "Ladder {} filled {} at an average of {}, stop {} target {}",
+        group.key(),
+        group.filled_quantity(),
+        group.average_entry().unwrap_or_default(),
+        group.stop_loss,
+        group.take_profit
+    );
+}
+
+async fn cancel_working(
+    platform: &Arc<dyn ITradingPlatform + Send + Sync>,
+    group: &mut LadderGroup,
+) {
+    for rung in group
+        .rungs
+        .iter_mut()
+        .filter(|r| r.status == RungStatus::Working)
+    {
+        let Some(order_id) = &rung.order_id else {
+            continue;
+        };
+        match platform.cancel_order(order_id).await {
+            Ok(()) => rung.status = RungStatus::Cancelled,
+            Err(e) => {
+                warn!("Failed to cancel ladder rung {}: {}", order_id, e);
+                rung.error = Some(e.to_string());
+            }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+// Market entries sent as marketable limit orders while a platform is slow to
+// answer. The latency comes from the platform health checks; a converted order
+// that has not filled by its timeout is cancelled.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use crate::instruments::InstrumentMetadata;
+use crate::platforms::abstraction::interfaces::ITradingPlatform;
+use crate::platforms::abstraction::models::{
+    UnifiedOrder, UnifiedOrderResponse, UnifiedOrderSide, UnifiedOrderStatus, UnifiedOrderType,
+};
+
+lazy_static! {
+    pub static ref LATENCY_CONVERSIONS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "execution_latency_limit_entries_total",
+        "Market entries sent as limit orders because of platform latency, by outcome",
+        &["account_id", "outcome"]
+    )
+    .unwrap();
+}
+
+/// How often a converted order is polled while waiting for its fill
+const FILL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyEntryConfig {
+    /// Off, market entries are sent as market orders whatever the latency
+    pub enabled: bool,
+    /// Platform latency above which market entries are converted
+    pub max_latency_ms: u64,
+    /// How far through the quote the limit price is set, so it fills straight away
+    pub limit_offset_pips: f64,
+    /// How long a converted order may rest before it is cancelled
+    pub fill_timeout_ms: u64,
+    /// Latency samples older than this are ignored
+    pub max_sample_age_secs: u64,
+}
+
+impl Default for LatencyEntryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_latency_ms: 500,
+            limit_offset_pips: 1.0,
+            fill_timeout_ms: 3000,
+            max_sample_age_secs: 60,
+        }
+    }
+}
+
+impl LatencyEntryConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_latency_ms == 0 {
+            return Err("Latency entry max_latency_ms must be above 0".to_string());
+        }
+        if !self.limit_offset_pips.is_finite() || self.limit_offset_pips < 0.0 {
+            return Err("Latency entry limit_offset_pips must not be negative".to_string());
+        }
+        if self.fill_timeout_ms == 0 {
+            return Err("Latency entry fill_timeout_ms must be above 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// One platform latency measurement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LatencySample {
+    pub latency_ms: u64,
+    pub measured_at: DateTime<Utc>,
+}
+
+/// Latest latency measured per account. Cheap to clone; clones share their samples.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyMonitor {
+    samples: Arc<DashMap<String, LatencySample>>,
+}
+
+impl LatencyMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, account_id: &str, latency_ms: u64) {
+        self.samples.insert(
+            account_id.to_string(),
+            LatencySample {
+                latency_ms,
+                measured_at: Utc::now(),
+            },
+        );
+    }
+
+    pub fn latest(&self, account_id: &str) -> Option<LatencySample> {
+        self.samples.get(account_id).map(|s| *s)
+    }
+}
+
+/// A market entry converted to a limit order
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitConversion {
+    pub latency_ms: u64,
+    pub limit_price: Decimal,
+    pub fill_timeout: Duration,
+}
+
+/// Applies `LatencyEntryConfig` to market entries, reading latency from a shared
+/// `LatencyMonitor`
+#[derive(Debug, Clone, Default)]
+pub struct LatencyEntryPolicy {
+    config: LatencyEntryConfig,
+    monitor: LatencyMonitor,
+}
+
+impl LatencyEntryPolicy {
+    pub fn new(config: LatencyEntryConfig, monitor: LatencyMonitor) -> Self {
+        Self { config, monitor }
+    }
+
+    pub fn config(&self) -> &LatencyEntryConfig {
+        &self.config
+    }
+
+    pub fn monitor(&self) -> &LatencyMonitor {
+        &self.monitor
+    }
+
+    /// Latency of `account_id` when it is recent and above the limit
+    pub fn excess_latency(&self, account_id: &str) -> Option<u64> {
+        if !self.config.enabled {
+            return None;
+        }
+        let sample = self.monitor.latest(account_id)?;
+        let max_age = ChronoDuration::seconds(self.config.max_sample_age_secs as i64);
+        (Utc::now() - sample.measured_at <= max_age
+            && sample.latency_ms > self.config.max_latency_ms)
+            .then_some(sample.latency_ms)
+    }
+
+    /// Turn `order` into a marketable limit order when its account is slow. Without
+    /// a quote to price it from, the order is left a market order.
+    pub async fn convert(
+        &self,
+        platform: &(dyn ITradingPlatform + Send + Sync),
+        account_id: &str,
+        order: &mut UnifiedOrder,
+    ) -> Option<LimitConversion> {
+        if order.order_type != UnifiedOrderType::Market {
+            return None;
+        }
+        let latency_ms = self.excess_latency(account_id)?;
+        let quote = match platform.get_market_data(&order.symbol).await {
+            Ok(quote) => quote,
+            Err(e) => {
+                warn!(
+                    "No quote for {} to price a limit entry on {}, sending at market: {}",
+                    order.symbol, account_id, e
+                );
+                return None;
+            }
+        };
+        let instrument = InstrumentMetadata::conventional(&order.symbol);
+        let offset = instrument
+            .pips_to_price(Decimal::from_f64(self.config.limit_offset_pips).unwrap_or_default());
+        let limit_price = instrument.round_price(match order.side {
+            UnifiedOrderSide::Buy => quote.ask + offset,
+            UnifiedOrderSide::Sell => quote.bid - offset,
+        });
+        order.order_type = UnifiedOrderType::Limit;
+        order.price = Some(limit_price);
+        info!(
+            "Platform latency of {} is {} ms; entry on {} sent as a limit at {}",
+            account_id, latency_ms, order.symbol, limit_price
+        );
+        Some(LimitConversion {
+            latency_ms,
+            limit_price,
+            fill_timeout: Duration::from_millis(self.config.fill_timeout_ms),
+        })
+    }
+
+    /// Wait for a converted order to fill and cancel what is left of it at the
+    /// timeout. An order cancelled after part of it filled is reported partially filled.
+    pub async fn settle(
+        &self,
+        platform: &(dyn ITradingPlatform + Send + Sync),
+        account_id: &str,
+        placed: UnifiedOrderResponse,
+        conversion: &LimitConversion,
+    ) -> UnifiedOrderResponse {
+        let deadline = Instant::now() + conversion.fill_timeout;
+        let mut order = placed;
+        while !is_final(&order.status) {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            tokio::time::sleep(FILL_POLL_INTERVAL.min(deadline - now)).await;
+            match platform.get_order(&order.platform_order_id).await {
+                Ok(current) => order = current,
+                Err(e) => debug!(
+                    "Failed to poll limit entry {}: {}",
+                    order.platform_order_id, e
+                ),
+            }
+        }
+
+        if !is_final(&order.status) {
+            if let Err(e) = platform.cancel_order(&order.platform_order_id).await {
+                warn!(
+                    "Failed to cancel unfilled limit entry {} for {}: {}",
+                    order.platform_order_id, account_id, e
+                );
+            }
+            // It may have filled while being cancelled
+            if let Ok(current) = platform.get_order(&order.platform_order_id).await {
+                order = current;
+            }
+        }
+
+        let outcome = match order.status {
+            UnifiedOrderStatus::Filled => "filled",
+            _ if order.filled_quantity > Decimal::ZERO => {
+                order.status = UnifiedOrderStatus::PartiallyFilled;
+                "partially_filled"
+            }
+            _ => "expired",
+        };
+        LATENCY_CONVERSIONS_TOTAL
+            .with_label_values(&[account_id, outcome])
+            .inc();
+        order
+    }
+}
+
+fn is_final(status: &UnifiedOrderStatus) -> bool {
+    matches!(
+        status,
+        UnifiedOrderStatus::Filled
+            | UnifiedOrderStatus::Canceled
+            | UnifiedOrderStatus::Rejected
+            | UnifiedOrderStatus::Expired
+    )
+}
@@ -1,24 +1,63 @@
+mod account_actor;
+pub mod control;
 pub mod coordinator;
+pub mod drop_copy;
 pub mod exit_management;
+pub mod history;
+pub mod ladder;
+pub mod latency_entry;
+pub mod news_blackout;
 pub mod orchestrator;
-
-#[cfg(test)]
-pub mod mock_platform;
+pub mod order_tracker;
+pub mod pacing;
+pub mod pending_signals;
+pub mod persona;
+pub mod preview;
+pub mod recovery_mode;
+pub mod scale_in;
+pub mod session_liquidity;
+pub mod signal_extensions;
+pub mod sizing;
+pub mod slippage;
+pub mod tags;
 
 #[cfg(test)]
 mod simple_test;
 
 pub use orchestrator::{
-    AccountAssignment, AccountStatus, ExecutionAuditEntry, ExecutionPlan, ExecutionResult,
-    TradeExecutionOrchestrator, TradeSignal,
+    AccountAssignment, AccountStatus, EmergencyCloseResult, ExecutionAuditEntry, ExecutionPlan,
+    ExecutionResult, KillSwitchState, OrchestratorSnapshot, TradeExecutionOrchestrator,
+    TradeSignal,
 };
 
+pub use control::{ControlAction, CONTROL_TAG};
+pub use drop_copy::{
+    DropCopyConsumer, DropCopyFill, DropCopyMatch, DropCopyReconciler, DropCopyReport,
+};
+pub use history::{
+    connect_store as connect_history_store, ExecutionHistoryConfig, ExecutionHistoryPage,
+    ExecutionHistoryQuery, ExecutionHistoryRetention, ExecutionHistoryStore,
+    InMemoryExecutionHistoryStore, RetentionPolicy,
+};
+pub use ladder::{LadderConfig, LadderGroup, LadderManager};
+pub use latency_entry::{LatencyEntryConfig, LatencyEntryPolicy, LatencyMonitor};
+pub use news_blackout::{BlackoutWindow, BlackoutWindows, NewsBlackoutConfig, NewsCalendar};
+pub use order_tracker::{OrderOrigin, OrderTracker, TrackedOrder};
+pub use pacing::{AccountPacer, ActivityWindow, DailySchedule, PacingConfig};
+pub use pending_signals::{PendingSignalQueue, SignalDisposition};
+pub use persona::{AccountPersona, Persona, PersonaConfig};
+pub use preview::{AccountDecision, PlanPreview, SizeDerivation};
+pub use recovery_mode::{RecoveryMode, RecoveryModeConfig, RecoveryPeriod};
+pub use scale_in::ScaleInConfig;
+pub use session_liquidity::SessionLiquidityConfig;
+pub use signal_extensions::SignalExtensions;
+pub use sizing::{ConfidencePoint, ConfidenceSizingConfig};
+pub use slippage::{SlippageBreachAction, SlippageGuard, SlippageGuardConfig, SlippageStats};
+pub use tags::{TagFilter, TagRegistry, TaggedPosition};
+
 pub use coordinator::{ExecutionCoordinator, ExecutionMonitor, ExecutionSummary, PartialFill};
 
 pub use exit_management::{
     BreakEvenManager, ExitAuditLogger, ExitManagementSystem, NewsEventProtection,
     PartialProfitManager, TimeBasedExitManager, TrailingStopManager,
 };
-
-#[cfg(test)]
-pub use mock_platform::MockTradingPlatform;
@@ -0,0 +1,190 @@
+// Entry blackouts around economic releases: new signals are refused from shortly
+// before a release in one of the symbol's currencies until shortly after it, with
+// the window set per impact level and overridable per strategy
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use super::exit_management::types::{ImpactLevel, NewsEvent};
+
+/// Action of audit entries for signals refused during a blackout
+pub const NEWS_BLACKOUT_ACTION: &str = "NEWS_BLACKOUT";
+
+/// Minutes before and after a release that entries are refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlackoutWindow {
+    pub before_mins: u32,
+    pub after_mins: u32,
+}
+
+/// Window per impact level; levels without one do not block entries
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlackoutWindows {
+    pub high: Option<BlackoutWindow>,
+    pub medium: Option<BlackoutWindow>,
+    pub low: Option<BlackoutWindow>,
+}
+
+impl BlackoutWindows {
+    pub fn window(&self, impact: &ImpactLevel) -> Option<BlackoutWindow> {
+        match impact {
+            ImpactLevel::High => self.high,
+            ImpactLevel::Medium => self.medium,
+            ImpactLevel::Low => self.low,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (level, window) in [
+            ("high", self.high),
+            ("medium", self.medium),
+            ("low", self.low),
+        ] {
+            if window.is_some_and(|w| w.before_mins == 0 && w.after_mins == 0) {
+                return Err(format!(
+                    "News blackout window for {} impact events must cover some time",
+                    level
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NewsBlackoutConfig {
+    /// Off, entries are taken whatever the calendar says
+    pub enabled: bool,
+    pub windows: BlackoutWindows,
+    /// Windows replacing `windows` for signals of a strategy; a strategy without
+    /// any trades through every release
+    pub strategy_overrides: HashMap<String, BlackoutWindows>,
+    /// How often the calendar is fetched
+    pub refresh_interval_secs: u64,
+    /// How far ahead releases are fetched
+    pub horizon_hours: u32,
+}
+
+impl Default for NewsBlackoutConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            windows: BlackoutWindows {
+                high: Some(BlackoutWindow {
+                    before_mins: 30,
+                    after_mins: 15,
+                }),
+                medium: None,
+                low: None,
+            },
+            strategy_overrides: HashMap::new(),
+            refresh_interval_secs: 900,
+            horizon_hours: 24,
+        }
+    }
+}
+
+impl NewsBlackoutConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.refresh_interval_secs == 0 || self.horizon_hours == 0 {
+            return Err(
+                "News blackout refresh_interval_secs and horizon_hours must be above 0".to_string(),
+            );
+        }
+        self.windows.validate()?;
+        for (strategy, windows) in &self.strategy_overrides {
+            windows
+                .validate()
+                .map_err(|e| format!("{} of strategy {}", e, strategy))?;
+        }
+        Ok(())
+    }
+
+    /// Windows applying to signals of `strategy`
+    pub fn windows_for(&self, strategy: Option<&str>) -> &BlackoutWindows {
+        strategy
+            .and_then(|strategy| self.strategy_overrides.get(strategy))
+            .unwrap_or(&self.windows)
+    }
+}
+
+/// A release that blocks entries on a symbol, and the window it blocks
+#[derive(Debug, Clone)]
+pub struct Blackout {
+    pub event: NewsEvent,
+    pub window: BlackoutWindow,
+}
+
+impl Blackout {
+    pub fn starts_at(&self) -> DateTime<Utc> {
+        self.event.time - Duration::minutes(self.window.before_mins as i64)
+    }
+
+    pub fn ends_at(&self) -> DateTime<Utc> {
+        self.event.time + Duration::minutes(self.window.after_mins as i64)
+    }
+
+    pub fn reason(&self, symbol: &str) -> String {
+        format!(
+            "Entries on {} are blacked out from {} to {} around {} ({:?} impact, {}) at {}",
+            symbol,
+            self.starts_at().format("%H:%M"),
+            self.ends_at().format("%H:%M UTC"),
+            self.event.description,
+            self.event.impact,
+            self.event.currency,
+            self.event.time.format("%H:%M UTC")
+        )
+    }
+}
+
+/// Upcoming releases, replaced on every calendar refresh. Cheap to clone; clones
+/// share their events.
+#[derive(Debug, Clone, Default)]
+pub struct NewsCalendar {
+    events: Arc<RwLock<Vec<NewsEvent>>>,
+}
+
+impl NewsCalendar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_events(&self, events: Vec<NewsEvent>) {
+        *self.events.write().unwrap() = events;
+    }
+
+    pub fn events(&self) -> Vec<NewsEvent> {
+        self.events.read().unwrap().clone()
+    }
+
+    /// The blackout holding entries on `symbol` at `now` under `windows`, the one
+    /// ending last when several overlap. Releases count for a symbol when their
+    /// currency is one of its legs, as USD is of EURUSD and XAUUSD.
+    pub fn blackout(
+        &self,
+        symbol: &str,
+        windows: &BlackoutWindows,
+        now: DateTime<Utc>,
+    ) -> Option<Blackout> {
+        let symbol = symbol.to_uppercase();
+        self.events
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|event| symbol.contains(&event.currency.to_uppercase()))
+            .filter_map(|event| {
+                let window = windows.window(&event.impact)?;
+                let blackout = Blackout {
+                    event: event.clone(),
+                    window,
+                };
+                (blackout.starts_at() <= now && now <= blackout.ends_at()).then_some(blackout)
+            })
+            .max_by_key(|blackout| blackout.ends_at())
+    }
+}
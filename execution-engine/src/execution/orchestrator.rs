@@ -1,20 +1,68 @@
+use chrono::Utc;
+use futures_util::future::join_all;
 use rand::Rng;
 use rust_decimal::prelude::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
-use tokio::sync::RwLock;
-use tracing::{debug, error, info, warn};
+use tokio::sync::{watch, RwLock};
+use tracing::{debug, error, info, warn, Instrument};
 use uuid::Uuid;
 
+use crate::alerting::AlertGateway;
+use crate::execution::account_actor::AccountActor;
+use crate::execution::control::ControlAction;
+use crate::execution::exit_management::{ExitPolicy, MarketSession, PendingExitPolicy};
+use crate::execution::history::{
+    ExecutionHistoryPage, ExecutionHistoryQuery, ExecutionHistoryStore,
+};
+use crate::execution::ladder::{LadderGroup, LadderManager, LadderPlan};
+use crate::execution::latency_entry::{
+    LatencyEntryConfig, LatencyEntryPolicy, LatencyMonitor, LimitConversion,
+};
+use crate::execution::news_blackout::{NewsBlackoutConfig, NewsCalendar, NEWS_BLACKOUT_ACTION};
+use crate::execution::order_tracker::{OrderOrigin, OrderTracker, TrackedOrder};
+use crate::execution::pacing::{AccountPacer, PacingConfig};
+use crate::execution::persona::PersonaConfig;
+use crate::execution::preview::{PlanPreview, PlanTrace, SizeDerivation};
+use crate::execution::recovery_mode::{RecoveryMode, RecoveryModeConfig, RECOVERY_MODE_ACTION};
+use crate::execution::scale_in::{position_added_to, ScaleInConfig, SCALE_IN_ACTION};
+use crate::execution::session_liquidity::SessionLiquidityConfig;
+use crate::execution::signal_extensions::SignalExtensions;
+use crate::execution::sizing::{confidence_tag, ConfidenceSizingConfig};
+use crate::execution::slippage::{SlippageGuard, SlippageGuardConfig, SlippageStats};
+use crate::execution::tags::{TagFilter, TagRegistry, TaggedPosition};
+use crate::messaging::outbox::{ExecutionOutbox, OutboxMessage};
+use crate::platforms::abstraction::circuit_breaker::as_emergency;
+use crate::platforms::abstraction::quota::with_caller;
+use crate::platforms::abstraction::rejections::{
+    adjust_order, RejectionClassifier, RejectionReason, RejectionRemediationConfig, Remediation,
+};
 use crate::platforms::abstraction::{
+    capabilities::PlatformFeature,
+    errors::PlatformError,
     interfaces::ITradingPlatform,
-    models::{UnifiedOrder, UnifiedOrderSide, UnifiedOrderType},
+    models::{
+        TradingSession, UnifiedOrder, UnifiedOrderResponse, UnifiedOrderSide, UnifiedOrderStatus,
+        UnifiedOrderType, UnifiedPosition,
+    },
 };
+use crate::recording::{EventRecorder, RecordedEvent};
+use crate::reports::signal_quality::{strategy_of, StrategyScores};
+use crate::risk::drawdown_ladder::{DrawdownLadder, EquityMarks, RiskLevel};
+use crate::risk::margin_monitor::{AccountMarginState, MarginCalculator};
+use crate::risk::portfolio_exposure::{ClusterLimits, PortfolioExposure};
+use crate::runtime::logging::LogContext;
+use crate::runtime::spawn::spawn_isolated;
+use crate::storage::{FileOrchestratorStateStore, OrchestratorStateStore};
 // Temporarily disabled complex risk dependencies
 // use crate::risk::{DrawdownTracker, ExposureMonitor, MarginMonitor};
 
+/// Quota budget that order placement is charged to
+pub const ORCHESTRATOR_CALLER: &str = "orchestrator";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountStatus {
     pub account_id: String,
@@ -24,6 +72,9 @@ pub struct AccountStatus {
     pub daily_drawdown: f64,
     pub max_drawdown: f64,
     pub open_positions: usize,
+    /// Units filled into the account's open positions by executed orders
+    #[serde(default)]
+    pub open_exposure: f64,
     pub last_trade_time: Option<SystemTime>,
     pub is_active: bool,
     pub correlation_score: f64,
@@ -46,10 +97,21 @@ pub struct TradeSignal {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionPlan {
     pub signal_id: String,
+    #[serde(default)]
+    pub symbol: String,
     pub account_assignments: Vec<AccountAssignment>,
     pub timing_variance: HashMap<String, Duration>,
     pub size_variance: HashMap<String, f64>,
     pub rationale: String,
+    /// Exit policy from the signal's metadata, handed to exit management once the position opens
+    #[serde(default)]
+    pub exit_policy: Option<ExitPolicy>,
+    /// Tags from the signal's metadata, carried to the orders, positions and results
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Enter through a ladder of limit orders instead of a market order
+    #[serde(default)]
+    pub entry_ladder: Option<LadderPlan>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +120,9 @@ pub struct AccountAssignment {
     pub position_size: f64,
     pub entry_timing_delay: Duration,
     pub priority: u8,
+    /// Risk budget used per unit filled: the distance from entry to the signal's stop
+    #[serde(default)]
+    pub risk_per_unit: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,6 +135,43 @@ pub struct ExecutionResult {
     pub execution_time: Duration,
     pub actual_entry_price: Option<f64>,
     pub slippage: Option<f64>,
+    /// Size sent to the platform, after snapping to its lot step
+    #[serde(default)]
+    pub requested_quantity: Option<f64>,
+    /// Size that actually filled; less than requested on a partial fill
+    #[serde(default)]
+    pub filled_quantity: Option<f64>,
+    /// Why the platform last refused the order, even if a resend then went through
+    #[serde(default)]
+    pub rejection: Option<RejectionReason>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl ExecutionResult {
+    /// A result for an order that was not placed
+    pub fn failed(
+        signal_id: impl Into<String>,
+        account_id: impl Into<String>,
+        error_message: impl Into<String>,
+        execution_time: Duration,
+        tags: Vec<String>,
+    ) -> Self {
+        Self {
+            signal_id: signal_id.into(),
+            account_id: account_id.into(),
+            order_id: None,
+            success: false,
+            error_message: Some(error_message.into()),
+            execution_time,
+            actual_entry_price: None,
+            slippage: None,
+            requested_quantity: None,
+            filled_quantity: None,
+            rejection: None,
+            tags,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,10 +184,58 @@ pub struct ExecutionAuditEntry {
     pub decision_rationale: String,
     pub result: Option<ExecutionResult>,
     pub metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// An order rejected while its market was closed, held to be sent once it opens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeferredOrder {
+    /// The rejected assignment alone, sent again unchanged when released
+    pub plan: ExecutionPlan,
+    pub account_id: String,
+    pub reason: RejectionReason,
+    pub deferred_at: chrono::DateTime<Utc>,
+    pub expires_at: chrono::DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillSwitchState {
+    pub engaged: bool,
+    pub reason: Option<String>,
+    pub changed_at: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyCloseResult {
+    pub account_id: String,
+    pub symbol: String,
+    pub success: bool,
+    pub order_id: Option<String>,
+    pub error_message: Option<String>,
+}
+
+/// Point-in-time copy of orchestrator state written during shutdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrchestratorSnapshot {
+    pub saved_at: SystemTime,
+    pub accounts: Vec<AccountStatus>,
+    pub active_executions: Vec<ExecutionPlan>,
+    pub execution_history: Vec<ExecutionAuditEntry>,
+    /// Execution results not yet acknowledged by the message bus
+    #[serde(default)]
+    pub unpublished_results: Vec<OutboxMessage>,
+}
+
+impl OrchestratorSnapshot {
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
 }
 
 pub struct TradeExecutionOrchestrator {
-    accounts: Arc<RwLock<HashMap<String, AccountStatus>>>,
+    accounts: AccountActor,
     platforms: Arc<RwLock<HashMap<String, Arc<dyn ITradingPlatform + Send + Sync>>>>,
     // Temporarily disabled complex risk dependencies
     // drawdown_trackers: Arc<RwLock<HashMap<String, DrawdownTracker>>>,
@@ -93,18 +243,82 @@ pub struct TradeExecutionOrchestrator {
     // margin_monitors: Arc<RwLock<HashMap<String, MarginMonitor>>>,
     execution_history: Arc<RwLock<Vec<ExecutionAuditEntry>>>,
     active_executions: Arc<RwLock<HashMap<String, ExecutionPlan>>>,
+    pending_exit_policies: Arc<RwLock<HashMap<String, Vec<PendingExitPolicy>>>>,
+    tag_registry: Arc<TagRegistry>,
+    order_tracker: Arc<OrderTracker>,
+    ladders: Arc<LadderManager>,
+    exposure_clusters: ClusterLimits,
+    outbox: Option<Arc<ExecutionOutbox>>,
+    recorder: Option<Arc<EventRecorder>>,
+    history_store: Option<Arc<dyn ExecutionHistoryStore>>,
+    rejection_remediation: Arc<RejectionRemediationConfig>,
+    strategy_scores: Option<Arc<StrategyScores>>,
+    confidence_sizing: ConfidenceSizingConfig,
+    scale_in: ScaleInConfig,
+    /// Additions planned per account and position, while the position is open
+    scale_in_counts: Arc<RwLock<HashMap<(String, String), u32>>>,
+    slippage_guard: SlippageGuard,
+    latency_entry: LatencyEntryPolicy,
+    news_blackout: NewsBlackoutConfig,
+    news_calendar: NewsCalendar,
+    session_liquidity: SessionLiquidityConfig,
+    pacing: AccountPacer,
+    personas: Arc<PersonaConfig>,
+    drawdown_ladder: Option<Arc<DrawdownLadder>>,
+    /// Equity each account's drawdowns are measured from
+    equity_marks: Arc<RwLock<HashMap<String, EquityMarks>>>,
+    recovery_mode: RecoveryMode,
+    /// Margin calculator and the lowest margin level, in percent, a plan may
+    /// leave an account at
+    margin_gate: Option<(Arc<MarginCalculator>, rust_decimal::Decimal)>,
+    alert_gateway: Option<Arc<AlertGateway>>,
+    deferred_orders: Arc<RwLock<Vec<DeferredOrder>>>,
     correlation_matrix: Arc<RwLock<HashMap<(String, String), f64>>>,
     max_correlation_threshold: f64,
     min_timing_variance_ms: u64,
     max_timing_variance_ms: u64,
     min_size_variance_pct: f64,
     max_size_variance_pct: f64,
+    accepting_signals: Arc<AtomicBool>,
+    cancel_tx: watch::Sender<bool>,
+    kill_switch: Arc<AtomicBool>,
+    kill_switch_state: Arc<RwLock<KillSwitchState>>,
+}
+
+/// Most positions an account may hold at normal risk
+const MAX_OPEN_POSITIONS: usize = 3;
+
+/// Why `status` keeps its account out of new plans, if it does, when it may hold
+/// up to `max_positions`
+fn exclusion_reason(status: &AccountStatus, max_positions: usize) -> Option<String> {
+    if !status.is_active {
+        Some("the account is inactive".to_string())
+    } else if status.available_margin < 1000.0 {
+        Some(format!(
+            "available margin {:.2} is below 1000.00",
+            status.available_margin
+        ))
+    } else if status.risk_budget_remaining <= 0.0 {
+        Some("no risk budget remains".to_string())
+    } else if status.daily_drawdown > 0.04 {
+        Some(format!(
+            "daily drawdown of {:.2}% exceeds the 4% limit",
+            status.daily_drawdown * 100.0
+        ))
+    } else if status.open_positions >= max_positions {
+        Some(format!(
+            "{} positions are open, the most allowed",
+            status.open_positions
+        ))
+    } else {
+        None
+    }
 }
 
 impl TradeExecutionOrchestrator {
     pub fn new() -> Self {
         Self {
-            accounts: Arc::new(RwLock::new(HashMap::new())),
+            accounts: AccountActor::new(),
             platforms: Arc::new(RwLock::new(HashMap::new())),
             // Temporarily disabled
             // drawdown_trackers: Arc::new(RwLock::new(HashMap::new())),
@@ -112,24 +326,209 @@ impl TradeExecutionOrchestrator {
             // margin_monitors: Arc::new(RwLock::new(HashMap::new())),
             execution_history: Arc::new(RwLock::new(Vec::new())),
             active_executions: Arc::new(RwLock::new(HashMap::new())),
+            pending_exit_policies: Arc::new(RwLock::new(HashMap::new())),
+            tag_registry: Arc::new(TagRegistry::new()),
+            order_tracker: Arc::new(OrderTracker::new()),
+            ladders: Arc::new(LadderManager::new()),
+            exposure_clusters: ClusterLimits::default(),
+            outbox: None,
+            recorder: None,
+            history_store: None,
+            rejection_remediation: Arc::new(RejectionRemediationConfig::default()),
+            strategy_scores: None,
+            confidence_sizing: ConfidenceSizingConfig::default(),
+            scale_in: ScaleInConfig::default(),
+            scale_in_counts: Arc::new(RwLock::new(HashMap::new())),
+            slippage_guard: SlippageGuard::default(),
+            latency_entry: LatencyEntryPolicy::default(),
+            news_blackout: NewsBlackoutConfig::default(),
+            news_calendar: NewsCalendar::new(),
+            session_liquidity: SessionLiquidityConfig::default(),
+            pacing: AccountPacer::default(),
+            personas: Arc::new(PersonaConfig::default()),
+            drawdown_ladder: None,
+            equity_marks: Arc::new(RwLock::new(HashMap::new())),
+            recovery_mode: RecoveryMode::default(),
+            margin_gate: None,
+            alert_gateway: None,
+            deferred_orders: Arc::new(RwLock::new(Vec::new())),
             correlation_matrix: Arc::new(RwLock::new(HashMap::new())),
             max_correlation_threshold: 0.7,
             min_timing_variance_ms: 1000,
             max_timing_variance_ms: 30000,
             min_size_variance_pct: 0.05,
             max_size_variance_pct: 0.15,
+            accepting_signals: Arc::new(AtomicBool::new(true)),
+            cancel_tx: watch::channel(false).0,
+            kill_switch: Arc::new(AtomicBool::new(false)),
+            kill_switch_state: Arc::new(RwLock::new(KillSwitchState {
+                engaged: false,
+                reason: None,
+                changed_at: None,
+            })),
         }
     }
 
+    /// Cap new trades so the combined exposure of each cluster across all
+    /// accounts stays within its limit
+    pub fn with_exposure_clusters(mut self, exposure_clusters: ClusterLimits) -> Self {
+        self.exposure_clusters = exposure_clusters;
+        self
+    }
+
+    /// Record every execution result in `outbox` for the relay to publish
+    pub fn with_outbox(mut self, outbox: Arc<ExecutionOutbox>) -> Self {
+        self.outbox = Some(outbox);
+        self
+    }
+
+    /// Record every incoming signal in `recorder`, for replaying the day
+    pub fn with_recorder(mut self, recorder: Arc<EventRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Keep every audit entry in `store`, beyond the recent entries held in memory
+    pub fn with_history_store(mut self, store: Arc<dyn ExecutionHistoryStore>) -> Self {
+        self.history_store = Some(store);
+        self
+    }
+
+    /// Resize, re-stop or defer rejected orders per `config` instead of failing them
+    pub fn with_rejection_remediation(mut self, config: RejectionRemediationConfig) -> Self {
+        self.rejection_remediation = Arc::new(config);
+        self
+    }
+
+    /// Scale position sizes by the allocation multiplier of each signal's strategy
+    pub fn with_strategy_scores(mut self, scores: Arc<StrategyScores>) -> Self {
+        self.strategy_scores = Some(scores);
+        self
+    }
+
+    /// Scale the risk of each signal by its confidence per `config`
+    pub fn with_confidence_sizing(mut self, config: ConfidenceSizingConfig) -> Self {
+        self.confidence_sizing = config;
+        self
+    }
+
+    /// Draw each account's entry delay from `min_ms`..=`max_ms` instead of 1-30s
+    pub fn with_timing_variance(mut self, min_ms: u64, max_ms: u64) -> Self {
+        self.min_timing_variance_ms = min_ms;
+        self.max_timing_variance_ms = max_ms.max(min_ms);
+        self
+    }
+
+    /// Size and limit signals that add to a position already open on their side
+    pub fn with_scale_in(mut self, config: ScaleInConfig) -> Self {
+        self.scale_in = config;
+        self
+    }
+
+    /// Measure market orders against the quote they are sent at per `config`
+    pub fn with_slippage_guard(mut self, config: SlippageGuardConfig) -> Self {
+        let guard =
+            SlippageGuard::new(config).with_session_liquidity(self.session_liquidity.clone());
+        self.slippage_guard = match self.alert_gateway.clone() {
+            Some(gateway) => guard.with_alert_gateway(gateway),
+            None => guard,
+        };
+        self
+    }
+
+    /// Send market entries as limit orders per `config` while their platform is
+    /// slow; latency already measured is kept
+    pub fn with_latency_entry(mut self, config: LatencyEntryConfig) -> Self {
+        self.latency_entry = LatencyEntryPolicy::new(config, self.latency_entry.monitor().clone());
+        self
+    }
+
+    /// Refuse signals around the releases in `calendar` per `config`
+    pub fn with_news_blackout(
+        mut self,
+        config: NewsBlackoutConfig,
+        calendar: NewsCalendar,
+    ) -> Self {
+        self.news_blackout = config;
+        self.news_calendar = calendar;
+        self
+    }
+
+    /// Spread entry delays and allow slippage by the liquidity of the session
+    /// a signal arrives in, per `config`
+    pub fn with_session_liquidity(mut self, config: SessionLiquidityConfig) -> Self {
+        self.slippage_guard = self
+            .slippage_guard
+            .clone()
+            .with_session_liquidity(config.clone());
+        self.session_liquidity = config;
+        self
+    }
+
+    /// Keep each account to its trading windows, spacing and daily trades per
+    /// `config`
+    pub fn with_pacing(mut self, config: PacingConfig) -> Self {
+        self.pacing = AccountPacer::new(config);
+        self
+    }
+
+    /// Size, time and exit each account's trades after its persona in `config`
+    pub fn with_personas(mut self, config: PersonaConfig) -> Self {
+        self.personas = Arc::new(config);
+        self
+    }
+
+    pub fn personas(&self) -> &PersonaConfig {
+        &self.personas
+    }
+
+    /// Scale risk, cap positions and tighten exits of accounts down `ladder`
+    pub fn with_drawdown_ladder(mut self, ladder: Arc<DrawdownLadder>) -> Self {
+        self.drawdown_ladder = Some(ladder);
+        self
+    }
+
+    /// Hold accounts to the constraints of `config` for some days after a large loss
+    pub fn with_recovery_mode(mut self, config: RecoveryModeConfig) -> Self {
+        self.recovery_mode = RecoveryMode::new(config);
+        self
+    }
+
+    pub fn recovery_mode(&self) -> &RecoveryMode {
+        &self.recovery_mode
+    }
+
+    /// Drop accounts from plans that would take their margin level below
+    /// `min_margin_level`, judging each plan's sizes together as one basket
+    pub fn with_margin_gate(
+        mut self,
+        calculator: Arc<MarginCalculator>,
+        min_margin_level: rust_decimal::Decimal,
+    ) -> Self {
+        self.margin_gate = Some((calculator, min_margin_level));
+        self
+    }
+
+    /// The account's rung on the drawdown ladder while it is down it
+    fn risk_level(&self, account_id: &str) -> Option<RiskLevel> {
+        self.drawdown_ladder
+            .as_ref()
+            .and_then(|ladder| ladder.level(account_id))
+    }
+
+    /// Raise slippage breaches to `gateway`
+    pub fn with_alert_gateway(mut self, gateway: Arc<AlertGateway>) -> Self {
+        self.slippage_guard = self.slippage_guard.with_alert_gateway(gateway.clone());
+        self.alert_gateway = Some(gateway);
+        self
+    }
+
     pub async fn register_account(
         &self,
         account_id: String,
         platform: Arc<dyn ITradingPlatform + Send + Sync>,
         initial_balance: f64,
     ) -> Result<(), String> {
-        let mut accounts = self.accounts.write().await;
-        let mut platforms = self.platforms.write().await;
-
         let account_info = platform
             .get_account_info()
             .await
@@ -143,13 +542,18 @@ impl TradeExecutionOrchestrator {
             daily_drawdown: 0.0,
             max_drawdown: 0.0,
             open_positions: 0,
+            open_exposure: 0.0,
             last_trade_time: None,
             is_active: true,
             correlation_score: 0.0,
         };
 
-        accounts.insert(account_id.clone(), status);
-        platforms.insert(account_id.clone(), platform);
+        // The platform is in place before the account can be planned for
+        self.platforms
+            .write()
+            .await
+            .insert(account_id.clone(), platform);
+        self.accounts.register(status).await;
 
         info!(
             "Registered account {} with initial balance {}",
@@ -159,20 +563,58 @@ impl TradeExecutionOrchestrator {
     }
 
     pub async fn process_signal(&self, signal: TradeSignal) -> Result<ExecutionPlan, String> {
-        info!("Processing signal {} for {}", signal.id, signal.symbol);
+        if let Some(recorder) = &self.recorder {
+            recorder
+                .record(RecordedEvent::Signal {
+                    signal: signal.clone(),
+                })
+                .await;
+        }
+        let span = LogContext::signal(&signal.id).span();
+        self.plan_signal(signal).instrument(span).await
+    }
 
-        let accounts = self.accounts.read().await;
-        let eligible_accounts = self.select_eligible_accounts(&accounts, &signal).await?;
+    /// The plan `signal` would get and the reasoning for each account, without
+    /// placing orders, recording the signal or writing audit entries
+    pub async fn preview_signal(&self, signal: TradeSignal) -> PlanPreview {
+        let mut trace = PlanTrace::default();
+        let mut plan = self.build_plan(&signal, &mut trace, false).await;
+        if let Some(reason) = self.intake_rejection() {
+            plan = Err(reason.to_string());
+        }
+        trace.into_preview(signal.id, plan)
+    }
 
-        if eligible_accounts.is_empty() {
-            return Err("No eligible accounts for signal execution".to_string());
+    /// Why no signal is taken at the moment, if so
+    fn intake_rejection(&self) -> Option<&'static str> {
+        if !self.is_accepting_signals() {
+            Some("Orchestrator is shutting down and not accepting signals")
+        } else if self.is_kill_switch_engaged() {
+            Some("Kill switch engaged, signal rejected")
+        } else {
+            None
         }
+    }
 
-        let mut plan = self
-            .create_execution_plan(signal.clone(), eligible_accounts)
+    async fn plan_signal(&self, signal: TradeSignal) -> Result<ExecutionPlan, String> {
+        if let Some(reason) = self.intake_rejection() {
+            return Err(reason.to_string());
+        }
+
+        info!("Processing signal {} for {}", signal.id, signal.symbol);
+
+        let plan = self
+            .build_plan(&signal, &mut PlanTrace::default(), true)
             .await?;
 
-        plan = self.apply_anti_correlation(&plan).await?;
+        // Each account's trade counts from when its delayed entry goes out
+        let now = Utc::now();
+        for assignment in &plan.account_assignments {
+            let delay = chrono::Duration::from_std(assignment.entry_timing_delay)
+                .unwrap_or_else(|_| chrono::Duration::zero());
+            self.pacing
+                .record_trade(&assignment.account_id, now + delay);
+        }
 
         let mut active = self.active_executions.write().await;
         active.insert(signal.id.clone(), plan.clone());
@@ -185,82 +627,176 @@ impl TradeExecutionOrchestrator {
                 plan.account_assignments.len()
             ),
             None,
+            plan.tags.clone(),
         )
         .await;
 
         Ok(plan)
     }
 
-    async fn select_eligible_accounts(
+    /// Eligibility, sizing and the adjustments to the whole plan, traced into
+    /// `trace`; audit entries are only written when `audit` is set
+    async fn build_plan(
         &self,
-        accounts: &HashMap<String, AccountStatus>,
-        _signal: &TradeSignal,
-    ) -> Result<Vec<String>, String> {
-        let mut eligible = Vec::new();
-
-        for (account_id, status) in accounts.iter() {
-            if !status.is_active {
-                debug!("Account {} is inactive", account_id);
-                continue;
-            }
+        signal: &TradeSignal,
+        trace: &mut PlanTrace,
+        audit: bool,
+    ) -> Result<ExecutionPlan, String> {
+        // One snapshot for the whole plan, so every account is judged and sized as of
+        // the same moment
+        let accounts = self.accounts.snapshot().await;
+        let eligible_accounts = self.select_eligible_accounts(&accounts, trace);
 
-            if status.available_margin < 1000.0 {
-                debug!("Account {} has insufficient margin", account_id);
-                continue;
-            }
+        if eligible_accounts.is_empty() {
+            return Err("No eligible accounts for signal execution".to_string());
+        }
 
-            if status.risk_budget_remaining <= 0.0 {
-                debug!("Account {} has no risk budget remaining", account_id);
-                continue;
-            }
+        let mut plan = self
+            .create_execution_plan(signal.clone(), &accounts, eligible_accounts, trace)
+            .await?;
 
-            if status.daily_drawdown > 0.04 {
-                debug!("Account {} exceeds daily drawdown limit", account_id);
-                continue;
-            }
+        plan = self.apply_news_blackout(plan, signal, audit).await?;
+        plan = self
+            .apply_recovery_mode(plan, signal, &accounts, trace, audit)
+            .await?;
+        plan = self.apply_scale_in(plan, signal, trace, audit).await?;
+        plan = self.apply_strategy_allocation(plan, trace, audit).await;
+        plan = self.apply_exposure_caps(plan, signal, trace, audit).await?;
+        plan = self.apply_margin_gate(plan, signal, trace, audit).await?;
+        self.apply_anti_correlation(&plan, trace).await
+    }
 
-            if status.open_positions >= 3 {
-                debug!("Account {} has maximum positions open", account_id);
-                continue;
+    fn select_eligible_accounts(
+        &self,
+        accounts: &[AccountStatus],
+        trace: &mut PlanTrace,
+    ) -> Vec<String> {
+        let mut eligible = Vec::new();
+        let now = Utc::now();
+
+        for status in accounts {
+            let max_positions = self
+                .risk_level(&status.account_id)
+                .and_then(|level| level.max_positions)
+                .map_or(MAX_OPEN_POSITIONS, |most| most.min(MAX_OPEN_POSITIONS));
+            let reason = exclusion_reason(status, max_positions)
+                .or_else(|| self.pacing.check(&status.account_id, now));
+            match reason {
+                Some(reason) => {
+                    debug!("Account {} excluded: {}", status.account_id, reason);
+                    trace.exclusions.push((status.account_id.clone(), reason));
+                }
+                None => eligible.push(status.account_id.clone()),
             }
-
-            eligible.push(account_id.clone());
         }
 
-        Ok(eligible)
+        eligible
     }
 
     async fn create_execution_plan(
         &self,
         signal: TradeSignal,
+        accounts: &[AccountStatus],
         eligible_accounts: Vec<String>,
+        trace: &mut PlanTrace,
     ) -> Result<ExecutionPlan, String> {
-        let mut rng = rand::thread_rng();
+        let mut extensions = SignalExtensions::parse(&signal.metadata)
+            .map_err(|e| format!("Invalid signal metadata: {}", e))?;
+        let confidence_multiplier = self.confidence_sizing.multiplier(signal.confidence);
+        if self.confidence_sizing.enabled {
+            extensions.tags.push(confidence_tag(signal.confidence));
+        }
+
+        let now = Utc::now();
+        let (min_delay_ms, max_delay_ms) = self.session_liquidity.delay_range(
+            self.min_timing_variance_ms,
+            self.max_timing_variance_ms,
+            now,
+        );
+        if self.session_liquidity.enabled {
+            let session = MarketSession::at(now);
+            trace.adjustments.push(format!(
+                "Entry delays drawn from {}-{} ms in the {:?} session, at {:.2} of peak liquidity",
+                min_delay_ms,
+                max_delay_ms,
+                session,
+                self.session_liquidity.liquidity(session)
+            ));
+        }
+
         let mut assignments = Vec::new();
 
         for (priority, account_id) in eligible_accounts.iter().enumerate() {
-            let base_delay_ms =
-                rng.gen_range(self.min_timing_variance_ms..=self.max_timing_variance_ms);
+            let persona = self.personas.persona_of(account_id);
+            // The thread-local rng must not be held across the await below, or
+            // planning could not run on a spawned task
+            let (base_delay_ms, size_multiplier) = {
+                let mut rng = rand::thread_rng();
+                match &persona {
+                    Some(persona) => {
+                        let (min_ms, max_ms) = persona.delay_range(min_delay_ms, max_delay_ms);
+                        let (smallest, largest) = persona.size_range(self.max_size_variance_pct);
+                        (
+                            rng.gen_range(min_ms..=max_ms),
+                            rng.gen_range(smallest..=largest),
+                        )
+                    }
+                    None => {
+                        let variance_pct =
+                            rng.gen_range(self.min_size_variance_pct..=self.max_size_variance_pct);
+                        let sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+                        (
+                            rng.gen_range(min_delay_ms..=max_delay_ms),
+                            1.0 + (variance_pct * sign),
+                        )
+                    }
+                }
+            };
             let delay = Duration::from_millis(base_delay_ms);
+            if let Some(persona) = &persona {
+                trace
+                    .account_adjustments
+                    .entry(account_id.clone())
+                    .or_default()
+                    .push(format!(
+                        "sized and timed as persona {} at aggressiveness {:.2}",
+                        persona.persona, persona.aggressiveness
+                    ));
+            }
 
-            let variance_pct =
-                rng.gen_range(self.min_size_variance_pct..=self.max_size_variance_pct);
-            let sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
-            let size_multiplier = 1.0 + (variance_pct * sign);
-
-            let accounts = self.accounts.read().await;
             let account = accounts
-                .get(account_id)
+                .iter()
+                .find(|a| &a.account_id == account_id)
                 .ok_or_else(|| format!("Account {} not found", account_id))?;
 
-            let base_size = self.calculate_position_size(account, &signal);
-            let adjusted_size = (base_size * size_multiplier * 100.0).round() / 100.0;
+            let rung_multiplier = match self.risk_level(account_id) {
+                Some(level) => {
+                    trace
+                        .account_adjustments
+                        .entry(account_id.clone())
+                        .or_default()
+                        .push(format!(
+                            "risk per trade at {}x on drawdown rung {}",
+                            level.risk_multiplier, level.rung
+                        ));
+                    level.risk_multiplier.to_f64().unwrap_or(1.0)
+                }
+                None => 1.0,
+            };
+
+            let mut sizing =
+                self.size_derivation(account, &signal, confidence_multiplier, rung_multiplier);
+            let adjusted_size = (sizing.base_size * size_multiplier * 100.0).round() / 100.0;
+            sizing.variance_multiplier = size_multiplier;
+            sizing.planned_size = adjusted_size;
+            trace.sizing.insert(account_id.clone(), sizing);
 
             assignments.push(AccountAssignment {
                 account_id: account_id.clone(),
                 position_size: adjusted_size,
                 entry_timing_delay: delay,
                 priority: priority as u8,
+                risk_per_unit: (signal.entry_price - signal.stop_loss).abs(),
             });
         }
 
@@ -272,33 +808,494 @@ impl TradeExecutionOrchestrator {
             size_variance.insert(assignment.account_id.clone(), assignment.position_size);
         }
 
+        let entry_ladder = extensions.entry_ladder.clone().map(|config| LadderPlan {
+            config,
+            side: signal.side.clone(),
+            entry_price: signal.entry_price,
+            stop_loss: signal.stop_loss,
+            take_profit: signal.take_profit,
+        });
+
         Ok(ExecutionPlan {
+            exit_policy: extensions.exit_policy(&signal.id),
+            tags: extensions.tags,
+            entry_ladder,
             signal_id: signal.id,
+            symbol: signal.symbol,
             account_assignments: assignments,
             timing_variance,
             size_variance,
-            rationale: format!(
-                "Distributed signal across {} accounts with variance",
-                eligible_accounts.len()
-            ),
+            rationale: if self.confidence_sizing.enabled {
+                format!(
+                    "Distributed signal across {} accounts with variance, risk sized at {:.2}x for confidence {:.2}",
+                    eligible_accounts.len(),
+                    confidence_multiplier,
+                    signal.confidence
+                )
+            } else {
+                format!(
+                    "Distributed signal across {} accounts with variance",
+                    eligible_accounts.len()
+                )
+            },
         })
     }
 
-    fn calculate_position_size(&self, account: &AccountStatus, signal: &TradeSignal) -> f64 {
+    /// Size risking 1% of available margin times `risk_multiplier` and
+    /// `rung_multiplier`, within the account's remaining risk budget
+    fn size_derivation(
+        &self,
+        account: &AccountStatus,
+        signal: &TradeSignal,
+        risk_multiplier: f64,
+        rung_multiplier: f64,
+    ) -> SizeDerivation {
         let risk_per_trade = account
             .risk_budget_remaining
-            .min(account.available_margin * 0.01);
+            .min(account.available_margin * 0.01 * risk_multiplier * rung_multiplier);
 
         let stop_distance = (signal.entry_price - signal.stop_loss).abs();
         let position_size = risk_per_trade / stop_distance;
 
         let volatility_adjustment = 1.0 - (account.daily_drawdown / 0.05).min(0.5);
         let adjusted_size = position_size * volatility_adjustment;
+        let base_size = (adjusted_size * 100.0).round() / 100.0;
+
+        SizeDerivation {
+            risk_budget_remaining: account.risk_budget_remaining,
+            available_margin: account.available_margin,
+            confidence_multiplier: risk_multiplier,
+            rung_multiplier,
+            risk_per_trade,
+            stop_distance,
+            drawdown_adjustment: volatility_adjustment,
+            base_size,
+            variance_multiplier: 1.0,
+            planned_size: base_size,
+        }
+    }
+
+    /// Size the plan up or down by how well its strategy has traded
+    async fn apply_strategy_allocation(
+        &self,
+        mut plan: ExecutionPlan,
+        trace: &mut PlanTrace,
+        audit: bool,
+    ) -> ExecutionPlan {
+        let Some(scores) = &self.strategy_scores else {
+            return plan;
+        };
+        let multiplier = scores.allocation_multiplier(&plan.tags);
+        if multiplier == 1.0 {
+            return plan;
+        }
+        for assignment in &mut plan.account_assignments {
+            assignment.position_size =
+                (assignment.position_size * multiplier * 100.0).round() / 100.0;
+        }
+        let note = format!(
+            "Sized at {:.2}x for the score of {}",
+            multiplier,
+            strategy_of(&plan.tags)
+        );
+        trace.adjustments.push(note.clone());
+        if audit {
+            self.log_audit_entry(
+                plan.signal_id.clone(),
+                "STRATEGY_ALLOCATION".to_string(),
+                note,
+                None,
+                plan.tags.clone(),
+            )
+            .await;
+        }
+        plan
+    }
+
+    /// Size down the assignments that add to a position open on the signal's side,
+    /// and drop those the scale-in rules refuse
+    async fn apply_scale_in(
+        &self,
+        mut plan: ExecutionPlan,
+        signal: &TradeSignal,
+        trace: &mut PlanTrace,
+        audit: bool,
+    ) -> Result<ExecutionPlan, String> {
+        if !self.scale_in.enabled {
+            return Ok(plan);
+        }
+        let mut open_positions = HashMap::new();
+        for (account_id, platform) in self.get_platforms().await {
+            if !plan
+                .account_assignments
+                .iter()
+                .any(|a| a.account_id == account_id)
+            {
+                continue;
+            }
+            match platform.get_positions().await {
+                Ok(positions) => {
+                    open_positions.insert(account_id, positions);
+                }
+                Err(e) => warn!(
+                    "Positions of {} unavailable, planned as a fresh entry: {}",
+                    account_id, e
+                ),
+            }
+        }
+
+        let mut counts = self.scale_in_counts.write().await;
+        let mut kept = Vec::new();
+        let mut notes = Vec::new();
+        for mut assignment in std::mem::take(&mut plan.account_assignments) {
+            let account_id = assignment.account_id.clone();
+            let Some(positions) = open_positions.get(&account_id) else {
+                kept.push(assignment);
+                continue;
+            };
+            counts.retain(|(account, position_id), _| {
+                *account != account_id || positions.iter().any(|p| p.position_id == *position_id)
+            });
+            let Some(position) = position_added_to(positions, &signal.symbol, &signal.side) else {
+                kept.push(assignment);
+                continue;
+            };
+
+            let key = (account_id.clone(), position.position_id.clone());
+            let adds = counts.get(&key).copied().unwrap_or(0);
+            let note = match self.scale_in.refusal(position, adds) {
+                Some(reason) => {
+                    let note = format!("scale-in refused: {}", reason);
+                    trace
+                        .account_adjustments
+                        .entry(account_id.clone())
+                        .or_default()
+                        .push(note.clone());
+                    note
+                }
+                None => {
+                    assignment.position_size =
+                        (assignment.position_size * self.scale_in.size_multiplier * 100.0).round()
+                            / 100.0;
+                    let note = format!(
+                        "adds to position {} (addition {} of {}), sized at {:.2}x",
+                        position.position_id,
+                        adds + 1,
+                        self.scale_in.max_adds,
+                        self.scale_in.size_multiplier
+                    );
+                    trace
+                        .account_adjustments
+                        .entry(account_id.clone())
+                        .or_default()
+                        .push(note.clone());
+                    if audit {
+                        counts.insert(key, adds + 1);
+                    }
+                    kept.push(assignment);
+                    note
+                }
+            };
+            notes.push(format!("{}: {}", account_id, note));
+        }
+        drop(counts);
+        plan.account_assignments = kept;
+
+        if audit && !notes.is_empty() {
+            self.log_audit_entry(
+                plan.signal_id.clone(),
+                SCALE_IN_ACTION.to_string(),
+                notes.join("; "),
+                None,
+                plan.tags.clone(),
+            )
+            .await;
+        }
+        if plan.account_assignments.is_empty() {
+            return Err("No account may scale in to its open position".to_string());
+        }
+        Ok(plan)
+    }
+
+    /// Drop the assignments of accounts in recovery mode the signal does not meet
+    /// its constraints for, putting accounts in recovery when a trigger is hit
+    async fn apply_recovery_mode(
+        &self,
+        mut plan: ExecutionPlan,
+        signal: &TradeSignal,
+        accounts: &[AccountStatus],
+        trace: &mut PlanTrace,
+        audit: bool,
+    ) -> Result<ExecutionPlan, String> {
+        if !self.recovery_mode.config().enabled {
+            return Ok(plan);
+        }
+        let now = Utc::now();
+        let mut kept = Vec::new();
+        let mut notes = Vec::new();
+        for assignment in std::mem::take(&mut plan.account_assignments) {
+            let account_id = assignment.account_id.clone();
+            let Some(status) = accounts.iter().find(|a| a.account_id == account_id) else {
+                kept.push(assignment);
+                continue;
+            };
+            let period = match self.recovery_mode.period(&account_id, now) {
+                Some(period) => period,
+                None => {
+                    let rung = self.risk_level(&account_id).map(|level| level.rung);
+                    let Some(reason) = self.recovery_mode.trigger(status.daily_drawdown, rung)
+                    else {
+                        kept.push(assignment);
+                        continue;
+                    };
+                    // A preview shows the constraints without starting the period
+                    if audit {
+                        warn!("Account {} enters recovery mode: {}", account_id, reason);
+                        self.recovery_mode.enter(&account_id, reason, now)
+                    } else {
+                        self.recovery_mode.starting(&account_id, reason, now)
+                    }
+                }
+            };
+
+            let refusal = self.recovery_mode.refusal(
+                signal.confidence,
+                signal.risk_reward_ratio,
+                status.open_positions,
+            );
+            let note = match refusal {
+                Some(refusal) => format!(
+                    "recovery mode until {} ({}) refused the signal: {}",
+                    period.ends_at.format("%Y-%m-%d %H:%M UTC"),
+                    period.reason,
+                    refusal
+                ),
+                None => {
+                    kept.push(assignment);
+                    format!(
+                        "recovery mode until {} ({}) let the signal through",
+                        period.ends_at.format("%Y-%m-%d %H:%M UTC"),
+                        period.reason
+                    )
+                }
+            };
+            trace
+                .account_adjustments
+                .entry(account_id.clone())
+                .or_default()
+                .push(note.clone());
+            notes.push(format!("{}: {}", account_id, note));
+        }
+        plan.account_assignments = kept;
+
+        if audit && !notes.is_empty() {
+            self.log_audit_entry(
+                plan.signal_id.clone(),
+                RECOVERY_MODE_ACTION.to_string(),
+                notes.join("; "),
+                None,
+                plan.tags.clone(),
+            )
+            .await;
+        }
+        if plan.account_assignments.is_empty() {
+            return Err("Recovery mode refused the signal for every account".to_string());
+        }
+        Ok(plan)
+    }
+
+    /// Reject the plan while a release in one of its symbol's currencies is near,
+    /// under the windows of the signal's strategy
+    async fn apply_news_blackout(
+        &self,
+        plan: ExecutionPlan,
+        signal: &TradeSignal,
+        audit: bool,
+    ) -> Result<ExecutionPlan, String> {
+        if !self.news_blackout.enabled {
+            return Ok(plan);
+        }
+        let strategy = strategy_tag(&plan.tags);
+        let windows = self.news_blackout.windows_for(strategy.as_deref());
+        let Some(blackout) = self
+            .news_calendar
+            .blackout(&signal.symbol, windows, Utc::now())
+        else {
+            return Ok(plan);
+        };
+
+        let reason = blackout.reason(&signal.symbol);
+        if audit {
+            self.log_audit_entry(
+                signal.id.clone(),
+                NEWS_BLACKOUT_ACTION.to_string(),
+                reason.clone(),
+                None,
+                plan.tags.clone(),
+            )
+            .await;
+        }
+        Err(reason)
+    }
+
+    /// Scale the plan down to the room left under the cluster caps, or reject
+    /// it when a cluster has none
+    async fn apply_exposure_caps(
+        &self,
+        mut plan: ExecutionPlan,
+        signal: &TradeSignal,
+        trace: &mut PlanTrace,
+        audit: bool,
+    ) -> Result<ExecutionPlan, String> {
+        if self.exposure_clusters.is_empty() {
+            return Ok(plan);
+        }
+        let portfolio = PortfolioExposure::collect(&self.get_platforms().await).await;
+        let total_size: f64 = plan
+            .account_assignments
+            .iter()
+            .map(|a| a.position_size)
+            .sum();
+        let notional = rust_decimal::Decimal::from_f64_retain(total_size * signal.entry_price)
+            .unwrap_or_default();
+        let allowance =
+            self.exposure_clusters
+                .check_trade(&portfolio, &signal.symbol, &signal.side, notional);
+        let Some(cluster) = allowance.limiting_cluster else {
+            return Ok(plan);
+        };
+
+        let fraction = allowance.fraction.to_f64().unwrap_or(0.0);
+        if fraction <= 0.0 {
+            let reason = format!("Exposure cap for cluster {} reached", cluster);
+            if audit {
+                self.log_audit_entry(
+                    signal.id.clone(),
+                    "EXPOSURE_CAP_REJECTED".to_string(),
+                    reason.clone(),
+                    None,
+                    plan.tags.clone(),
+                )
+                .await;
+            }
+            return Err(reason);
+        }
+        for assignment in &mut plan.account_assignments {
+            assignment.position_size *= fraction;
+        }
+        let note = format!(
+            "Scaled to {:.1}% of the planned size to stay within cluster {}",
+            fraction * 100.0,
+            cluster
+        );
+        trace.adjustments.push(note.clone());
+        if audit {
+            self.log_audit_entry(
+                signal.id.clone(),
+                "EXPOSURE_CAPPED".to_string(),
+                note,
+                None,
+                plan.tags.clone(),
+            )
+            .await;
+        }
+        Ok(plan)
+    }
+
+    /// Drop the accounts whose margin level the whole plan, netted against their
+    /// open positions, would take below the gate's minimum
+    async fn apply_margin_gate(
+        &self,
+        mut plan: ExecutionPlan,
+        signal: &TradeSignal,
+        trace: &mut PlanTrace,
+        audit: bool,
+    ) -> Result<ExecutionPlan, String> {
+        let Some((calculator, min_margin_level)) = &self.margin_gate else {
+            return Ok(plan);
+        };
+        let platforms = self.platforms.read().await.clone();
+        let states = join_all(plan.account_assignments.iter().map(|assignment| {
+            let platform = platforms.get(&assignment.account_id).cloned();
+            async move {
+                let platform = platform?;
+                let info = platform.get_account_info().await.ok()?;
+                let positions = platform.get_positions().await.ok()?;
+                Some(AccountMarginState::new(
+                    &assignment.account_id,
+                    &info,
+                    &positions,
+                    platform.supports_feature(PlatformFeature::HedgedPositions),
+                ))
+            }
+        }))
+        .await;
+        let states: Vec<AccountMarginState> = states.into_iter().flatten().collect();
+
+        let entry_price =
+            rust_decimal::Decimal::from_f64_retain(signal.entry_price).unwrap_or_default();
+        let impact = calculator.calculate_basket_margin_impact(
+            &plan,
+            &signal.side,
+            entry_price,
+            &states,
+            *min_margin_level,
+        );
+        let level = |level: Option<rust_decimal::Decimal>| match level {
+            Some(level) => format!("{:.1}%", level),
+            None => "unused".to_string(),
+        };
+        let mut refused = Vec::new();
+        for account in &impact.accounts {
+            let note = format!(
+                "margin level {} -> {} for {:.2} more margin",
+                level(account.current_margin_level),
+                level(account.projected_margin_level),
+                account.additional_margin_required
+            );
+            let note = if account.impact_acceptable {
+                note
+            } else {
+                refused.push(account.account_id.clone());
+                format!("{}, below the {:.1}% required", note, min_margin_level)
+            };
+            trace
+                .account_adjustments
+                .entry(account.account_id.clone())
+                .or_default()
+                .push(note);
+        }
+        if refused.is_empty() {
+            return Ok(plan);
+        }
 
-        (adjusted_size * 100.0).round() / 100.0
+        plan.account_assignments
+            .retain(|a| !refused.contains(&a.account_id));
+        if audit {
+            self.log_audit_entry(
+                signal.id.clone(),
+                "MARGIN_GATE_REFUSED".to_string(),
+                format!(
+                    "Margin level would fall below {:.1}% on {}",
+                    min_margin_level,
+                    refused.join(", ")
+                ),
+                None,
+                plan.tags.clone(),
+            )
+            .await;
+        }
+        if plan.account_assignments.is_empty() {
+            return Err("Margin gate refused the plan for every account".to_string());
+        }
+        Ok(plan)
     }
 
-    async fn apply_anti_correlation(&self, plan: &ExecutionPlan) -> Result<ExecutionPlan, String> {
+    async fn apply_anti_correlation(
+        &self,
+        plan: &ExecutionPlan,
+        trace: &mut PlanTrace,
+    ) -> Result<ExecutionPlan, String> {
         let correlation_matrix = self.correlation_matrix.read().await;
         let mut modified_plan = plan.clone();
 
@@ -323,6 +1320,16 @@ impl TradeExecutionOrchestrator {
                         );
                         modified_plan.account_assignments[j].entry_timing_delay += additional_delay;
                         modified_plan.account_assignments[j].position_size *= 0.9;
+                        trace
+                            .account_adjustments
+                            .entry(modified_plan.account_assignments[j].account_id.clone())
+                            .or_default()
+                            .push(format!(
+                                "delayed {:?} and sized down 10% for correlation {:.2} with {}",
+                                additional_delay,
+                                correlation,
+                                modified_plan.account_assignments[i].account_id
+                            ));
 
                         info!(
                             "Applied anti-correlation adjustment between {} and {} (correlation: {:.2})",
@@ -337,108 +1344,111 @@ impl TradeExecutionOrchestrator {
     }
 
     pub async fn execute_plan(&self, plan: &ExecutionPlan) -> Vec<ExecutionResult> {
+        let span = LogContext::signal(&plan.signal_id).span();
+        // Orders count against the orchestrator's quota budget whoever asked for them
+        with_caller(ORCHESTRATOR_CALLER, self.execute_assignments(plan))
+            .instrument(span)
+            .await
+    }
+
+    /// Each account's order runs in its own task, logging in that account's context
+    async fn execute_assignments(&self, plan: &ExecutionPlan) -> Vec<ExecutionResult> {
         let mut results = Vec::new();
         let mut handles = Vec::new();
+        // Tells this run's results apart from those of executing the signal again
+        let execution_id = Uuid::new_v4().to_string();
 
-        for assignment in &plan.account_assignments {
-            let assignment = assignment.clone();
-            let platforms = self.platforms.clone();
-            let _execution_history = self.execution_history.clone();
-            let accounts = self.accounts.clone();
-            let signal_id = plan.signal_id.clone();
-
-            let handle = tokio::spawn(async move {
-                tokio::time::sleep(assignment.entry_timing_delay).await;
-
-                let start_time = Instant::now();
-                let platforms = platforms.read().await;
-
-                if let Some(platform) = platforms.get(&assignment.account_id) {
-                    let order = UnifiedOrder {
-                        client_order_id: Uuid::new_v4().to_string(),
-                        symbol: "EURUSD".to_string(),
-                        order_type: UnifiedOrderType::Market,
-                        side: UnifiedOrderSide::Buy,
-                        quantity: rust_decimal::Decimal::from_f64_retain(assignment.position_size)
-                            .unwrap(),
-                        price: None,
-                        stop_price: None,
-                        stop_loss: Some(rust_decimal::Decimal::from_f64_retain(1.0800).unwrap()),
-                        take_profit: Some(rust_decimal::Decimal::from_f64_retain(1.1000).unwrap()),
-                        time_in_force:
-                            crate::platforms::abstraction::models::UnifiedTimeInForce::Gtc,
-                        account_id: Some(assignment.account_id.clone()),
-                        metadata: crate::platforms::abstraction::models::OrderMetadata {
-                            strategy_id: Some(signal_id.clone()),
-                            signal_id: Some(signal_id.clone()),
-                            risk_parameters: HashMap::new(),
-                            tags: vec![],
-                            expires_at: None,
-                        },
-                    };
-
-                    match platform.place_order(order).await {
-                        Ok(placed_order) => {
-                            let mut accounts = accounts.write().await;
-                            if let Some(account) = accounts.get_mut(&assignment.account_id) {
-                                account.last_trade_time = Some(SystemTime::now());
-                                account.open_positions += 1;
-                            }
-
-                            ExecutionResult {
-                                signal_id: signal_id.clone(),
-                                account_id: assignment.account_id.clone(),
-                                order_id: Some(placed_order.platform_order_id),
-                                success: true,
-                                error_message: None,
-                                execution_time: start_time.elapsed(),
-                                actual_entry_price: placed_order
-                                    .price
-                                    .map(|p| p.to_f64().unwrap_or(0.0)),
-                                slippage: None,
-                            }
-                        }
-                        Err(e) => {
-                            error!(
-                                "Failed to execute order for account {}: {}",
-                                assignment.account_id, e
-                            );
-                            ExecutionResult {
-                                signal_id: signal_id.clone(),
-                                account_id: assignment.account_id.clone(),
-                                order_id: None,
-                                success: false,
-                                error_message: Some(e.to_string()),
-                                execution_time: start_time.elapsed(),
-                                actual_entry_price: None,
-                                slippage: None,
-                            }
-                        }
-                    }
-                } else {
-                    ExecutionResult {
-                        signal_id: signal_id.clone(),
-                        account_id: assignment.account_id.clone(),
-                        order_id: None,
-                        success: false,
-                        error_message: Some("Platform not found".to_string()),
-                        execution_time: start_time.elapsed(),
-                        actual_entry_price: None,
-                        slippage: None,
-                    }
-                }
-            });
+        self.active_executions
+            .write()
+            .await
+            .entry(plan.signal_id.clone())
+            .or_insert_with(|| plan.clone());
+
+        // Platform handles are resolved up front so no execution task holds the platforms
+        // lock while its order is in flight
+        let platforms: HashMap<String, Arc<dyn ITradingPlatform + Send + Sync>> = {
+            let platforms = self.platforms.read().await;
+            plan.account_assignments
+                .iter()
+                .filter_map(|a| {
+                    platforms
+                        .get(&a.account_id)
+                        .map(|platform| (a.account_id.clone(), platform.clone()))
+                })
+                .collect()
+        };
 
-            handles.push(handle);
+        for assignment in &plan.account_assignments {
+            let platform = platforms.get(&assignment.account_id).cloned();
+            let exit_policy = match self.personas.persona_of(&assignment.account_id) {
+                Some(persona) => Some(persona.exit_policy(plan.exit_policy.clone())),
+                None => plan.exit_policy.clone(),
+            };
+            let exit_policy = match self.risk_level(&assignment.account_id) {
+                Some(level) => level.tighten(exit_policy),
+                None => exit_policy,
+            };
+
+            let task_name = format!("execution:{}:{}", plan.signal_id, assignment.account_id);
+            let failure = (assignment.account_id.clone(), plan.tags.clone());
+            let mut log_context = LogContext::account(&assignment.account_id);
+            if let Some(platform) = &platform {
+                log_context = log_context.with_platform(platform.platform_type());
+            }
+            let span = log_context.span();
+            let _entered = span.enter();
+            let run = AssignmentRun {
+                signal_id: plan.signal_id.clone(),
+                symbol: plan.symbol.clone(),
+                assignment: assignment.clone(),
+                platform,
+                exit_policy,
+                tags: plan.tags.clone(),
+                entry_ladder: plan.entry_ladder.clone(),
+                accounts: self.accounts.clone(),
+                ladders: self.ladders.clone(),
+                pending_exit_policies: self.pending_exit_policies.clone(),
+                tag_registry: self.tag_registry.clone(),
+                order_tracker: self.order_tracker.clone(),
+                kill_switch: self.kill_switch.clone(),
+                remediation: self.rejection_remediation.clone(),
+                deferred_orders: self.deferred_orders.clone(),
+                slippage_guard: self.slippage_guard.clone(),
+                latency_entry: self.latency_entry.clone(),
+            };
+            let handle = spawn_isolated(task_name, run.execute(self.cancel_tx.subscribe()));
+
+            handles.push((handle, failure));
         }
 
-        for handle in handles {
-            if let Ok(result) = handle.await {
-                self.log_execution_result(&result).await;
-                results.push(result);
+        for (handle, (account_id, tags)) in handles {
+            // A panicked task may or may not have placed its order, so it is reported
+            // as failed rather than dropped
+            let result = match handle.await {
+                Ok(Ok(result)) => result,
+                Ok(Err(panic)) => ExecutionResult::failed(
+                    &plan.signal_id,
+                    account_id,
+                    panic,
+                    Duration::ZERO,
+                    tags,
+                ),
+                Err(_) => continue,
+            };
+            self.log_execution_result(&result).await;
+            if let Some(outbox) = &self.outbox {
+                if let Err(e) = outbox.enqueue(&execution_id, &result).await {
+                    error!(
+                        "Failed to record execution result for {} on {} in the outbox: {}",
+                        result.signal_id, result.account_id, e
+                    );
+                }
             }
+            results.push(result);
         }
 
+        self.active_executions.write().await.remove(&plan.signal_id);
+
         results
     }
 
@@ -472,10 +1482,12 @@ impl TradeExecutionOrchestrator {
             position_size: assignment.position_size * 0.95,
             entry_timing_delay: Duration::from_millis(500),
             priority: 99,
+            risk_per_unit: assignment.risk_per_unit,
         };
 
         let retry_plan = ExecutionPlan {
             signal_id: plan.signal_id.clone(),
+            symbol: plan.symbol.clone(),
             account_assignments: vec![new_assignment],
             timing_variance: HashMap::new(),
             size_variance: HashMap::new(),
@@ -483,6 +1495,9 @@ impl TradeExecutionOrchestrator {
                 "Retry execution on alternative account {}",
                 selected_account
             ),
+            exit_policy: plan.exit_policy.clone(),
+            tags: plan.tags.clone(),
+            entry_ladder: plan.entry_ladder.clone(),
         };
 
         let retry_results = self.execute_plan(&retry_plan).await;
@@ -498,7 +1513,7 @@ impl TradeExecutionOrchestrator {
         failed_account: &str,
         plan: &ExecutionPlan,
     ) -> Result<Vec<String>, String> {
-        let accounts = self.accounts.read().await;
+        let accounts = self.accounts.snapshot().await;
         let mut alternatives = Vec::new();
 
         let used_accounts: Vec<String> = plan
@@ -507,17 +1522,17 @@ impl TradeExecutionOrchestrator {
             .map(|a| a.account_id.clone())
             .collect();
 
-        for (account_id, status) in accounts.iter() {
-            if account_id == failed_account {
+        for status in &accounts {
+            if status.account_id == failed_account {
                 continue;
             }
 
-            if used_accounts.contains(account_id) {
+            if used_accounts.contains(&status.account_id) {
                 continue;
             }
 
             if status.is_active && status.available_margin > 1000.0 {
-                alternatives.push(account_id.clone());
+                alternatives.push(status.account_id.clone());
             }
         }
 
@@ -530,6 +1545,7 @@ impl TradeExecutionOrchestrator {
         action: String,
         rationale: String,
         result: Option<ExecutionResult>,
+        tags: Vec<String>,
     ) {
         let entry = ExecutionAuditEntry {
             id: Uuid::new_v4().to_string(),
@@ -541,9 +1557,44 @@ impl TradeExecutionOrchestrator {
                 .unwrap_or_default(),
             action,
             decision_rationale: rationale,
+            tags,
             result,
             metadata: HashMap::new(),
         };
+        self.append_audit_entry(entry).await;
+    }
+
+    /// Record who took a control action and what came of it in the audit trail
+    pub async fn record_control_action(&self, action: &ControlAction) {
+        match &action.outcome {
+            Ok(_) => info!(
+                "Control action {} by {} on {}",
+                action.action,
+                action.actor,
+                action.account_id.as_deref().unwrap_or("all accounts")
+            ),
+            Err(e) => warn!(
+                "Control action {} by {} failed: {}",
+                action.action, action.actor, e
+            ),
+        }
+        self.append_audit_entry(action.to_audit_entry()).await;
+    }
+
+    async fn append_audit_entry(&self, entry: ExecutionAuditEntry) {
+        // Stored off the execution path; a slow database must not delay orders
+        if let Some(store) = &self.history_store {
+            let store = store.clone();
+            let entry = entry.clone();
+            spawn_isolated("execution-history-append", async move {
+                if let Err(e) = store.append(&entry).await {
+                    warn!(
+                        "Failed to store execution history entry {}: {:#}",
+                        entry.id, e
+                    );
+                }
+            });
+        }
 
         let mut history = self.execution_history.write().await;
         history.push(entry);
@@ -569,6 +1620,7 @@ impl TradeExecutionOrchestrator {
             action.to_string(),
             rationale,
             Some(result.clone()),
+            result.tags.clone(),
         )
         .await;
     }
@@ -588,25 +1640,569 @@ impl TradeExecutionOrchestrator {
         matrix.insert(key, correlation);
     }
 
-    pub async fn get_execution_history(&self, limit: usize) -> Vec<ExecutionAuditEntry> {
-        let history = self.execution_history.read().await;
-        let start = if history.len() > limit {
-            history.len() - limit
+    /// Slippage of guarded market orders per account, breaches included
+    pub fn slippage_stats(&self) -> Vec<SlippageStats> {
+        self.slippage_guard.stats()
+    }
+
+    /// Platform latency per account, recorded by the platform health checks
+    pub fn latency_monitor(&self) -> LatencyMonitor {
+        self.latency_entry.monitor().clone()
+    }
+
+    pub async fn get_execution_history(&self, limit: usize) -> Vec<ExecutionAuditEntry> {
+        self.query_execution_history(limit, &TagFilter::default())
+            .await
+    }
+
+    /// The last `limit` audit entries carrying all of the filter's tags
+    pub async fn query_execution_history(
+        &self,
+        limit: usize,
+        filter: &TagFilter,
+    ) -> Vec<ExecutionAuditEntry> {
+        let history = self.execution_history.read().await;
+        let mut entries: Vec<ExecutionAuditEntry> = history
+            .iter()
+            .rev()
+            .filter(|entry| filter.matches(&entry.tags))
+            .take(limit)
+            .cloned()
+            .collect();
+        entries.reverse();
+        entries
+    }
+
+    /// A page of audit entries matching `query`, from the history store when there
+    /// is one and from the recent entries in memory otherwise
+    pub async fn search_execution_history(
+        &self,
+        query: &ExecutionHistoryQuery,
+    ) -> anyhow::Result<ExecutionHistoryPage> {
+        match &self.history_store {
+            Some(store) => store.query(query).await,
+            None => Ok(query.page(self.execution_history.read().await.iter())),
+        }
+    }
+
+    /// Audit a signal event that happens outside planning and execution, such as a
+    /// signal held back or expired before it was planned
+    pub async fn record_signal_event(
+        &self,
+        signal_id: &str,
+        action: &str,
+        rationale: String,
+        tags: Vec<String>,
+    ) {
+        self.log_audit_entry(
+            signal_id.to_string(),
+            action.to_string(),
+            rationale,
+            None,
+            tags,
+        )
+        .await;
+    }
+
+    /// Entry ladders opened for signals that asked for one
+    pub fn ladders(&self) -> Arc<LadderManager> {
+        self.ladders.clone()
+    }
+
+    /// Sync every active entry ladder with its account's platform, charging
+    /// each account for the rungs that filled
+    pub async fn poll_ladders(&self) {
+        let platforms = self.platforms.read().await.clone();
+        for fill in self.ladders.poll(&platforms).await {
+            let quantity = fill.quantity.to_f64().unwrap_or(0.0);
+            if fill.opens_position {
+                self.accounts
+                    .order_placed(&fill.account_id, quantity, fill.risk_per_unit)
+                    .await;
+            } else {
+                self.accounts
+                    .position_added(&fill.account_id, quantity, fill.risk_per_unit)
+                    .await;
+            }
+        }
+    }
+
+    /// Measure each account's daily and total drawdown from its platform's equity,
+    /// record them on its status and move it along the drawdown ladder when they
+    /// call for another rung. Returns the accounts that moved, with their new level.
+    pub async fn assess_drawdowns(&self) -> Vec<(String, RiskLevel)> {
+        let platforms = self.platforms.read().await.clone();
+        let now = Utc::now();
+        let mut moved = Vec::new();
+        for (account_id, platform) in platforms {
+            let equity = match platform.get_account_info().await {
+                Ok(info) => info.equity,
+                Err(e) => {
+                    debug!("Skipping drawdown assessment for {}: {}", account_id, e);
+                    continue;
+                }
+            };
+            let (daily, total) = self
+                .equity_marks
+                .write()
+                .await
+                .entry(account_id.clone())
+                .or_insert_with(|| EquityMarks::new(equity, now))
+                .update(equity, now);
+            let fraction = |pct: rust_decimal::Decimal| pct.to_f64().unwrap_or(0.0) / 100.0;
+            self.accounts
+                .set_drawdowns(&account_id, fraction(daily), fraction(total))
+                .await;
+
+            let Some(ladder) = &self.drawdown_ladder else {
+                continue;
+            };
+            let current = ladder.level(&account_id).map_or(0, |level| level.rung);
+            let Some(level) = ladder.assess(&account_id, daily, total, now) else {
+                continue;
+            };
+            ladder.move_to(&account_id, level.rung, now);
+            if level.rung > current {
+                warn!(
+                    "Account {} stepped down to drawdown rung {} at {:.2}% daily and {:.2}% total drawdown: risk per trade at {}x",
+                    account_id, level.rung, daily, total, level.risk_multiplier
+                );
+            } else {
+                info!(
+                    "Account {} recovered to drawdown rung {}",
+                    account_id, level.rung
+                );
+            }
+            moved.push((account_id, level));
+        }
+        moved
+    }
+
+    pub fn rejection_remediation(&self) -> Arc<RejectionRemediationConfig> {
+        self.rejection_remediation.clone()
+    }
+
+    /// Orders waiting for their market to open after being rejected as closed
+    pub async fn deferred_orders(&self) -> Vec<DeferredOrder> {
+        self.deferred_orders.read().await.clone()
+    }
+
+    /// Send deferred orders whose market has opened and drop those past their wait.
+    /// Returns the results of the orders sent.
+    pub async fn release_deferred_orders(&self) -> Vec<ExecutionResult> {
+        let deferred = std::mem::take(&mut *self.deferred_orders.write().await);
+        let platforms = self.platforms.read().await.clone();
+        let now = Utc::now();
+        let mut results = Vec::new();
+        let mut still_deferred = Vec::new();
+
+        for entry in deferred {
+            if entry.expires_at <= now {
+                warn!(
+                    "Deferred order for {} on account {} expired before the market opened",
+                    entry.plan.signal_id, entry.account_id
+                );
+                self.record_signal_event(
+                    &entry.plan.signal_id,
+                    "ORDER_DEFERRAL_EXPIRED",
+                    format!(
+                        "Order for account {} deferred at {} ({}) expired at {}",
+                        entry.account_id,
+                        entry.deferred_at.to_rfc3339(),
+                        entry.reason,
+                        entry.expires_at.to_rfc3339()
+                    ),
+                    entry.plan.tags.clone(),
+                )
+                .await;
+                continue;
+            }
+
+            let open = match platforms.get(&entry.account_id) {
+                Some(platform) => match platform.get_market_data(&entry.plan.symbol).await {
+                    Ok(quote) => !matches!(quote.session, Some(TradingSession::Closed)),
+                    Err(PlatformError::MarketClosed { .. }) => false,
+                    Err(e) => {
+                        debug!(
+                            "Deferred order for {} waits for a quote: {}",
+                            entry.account_id, e
+                        );
+                        false
+                    }
+                },
+                None => false,
+            };
+            if !open {
+                still_deferred.push(entry);
+                continue;
+            }
+
+            info!(
+                "Market open for {}; sending order deferred for account {}",
+                entry.plan.symbol, entry.account_id
+            );
+            results.extend(self.execute_plan(&entry.plan).await);
+
+            // Rejected as closed again: the wait still runs from the first deferral
+            let mut deferred = self.deferred_orders.write().await;
+            for again in deferred.iter_mut().filter(|again| {
+                again.plan.signal_id == entry.plan.signal_id && again.account_id == entry.account_id
+            }) {
+                again.deferred_at = entry.deferred_at;
+                again.expires_at = entry.expires_at;
+            }
+        }
+
+        let mut deferred = self.deferred_orders.write().await;
+        still_deferred.append(&mut deferred);
+        *deferred = still_deferred;
+        results
+    }
+
+    /// Tags of open positions, shared with exit management and the trade journal
+    pub fn tag_registry(&self) -> Arc<TagRegistry> {
+        self.tag_registry.clone()
+    }
+
+    /// Orders the engine sent, shared with exit management and drop-copy
+    /// reconciliation
+    pub fn order_tracker(&self) -> Arc<OrderTracker> {
+        self.order_tracker.clone()
+    }
+
+    /// Exit policies of orders placed on `account_id` since the last call, for exit management
+    pub async fn take_pending_exit_policies(&self, account_id: &str) -> Vec<PendingExitPolicy> {
+        self.pending_exit_policies
+            .write()
+            .await
+            .remove(account_id)
+            .unwrap_or_default()
+    }
+
+    pub async fn get_account_status(&self, account_id: &str) -> Option<AccountStatus> {
+        self.accounts.get(account_id).await
+    }
+
+    /// Every account as of one moment, by account id
+    pub async fn get_all_account_statuses(&self) -> Vec<AccountStatus> {
+        self.accounts.snapshot().await
+    }
+
+    pub async fn get_platforms(&self) -> Vec<(String, Arc<dyn ITradingPlatform + Send + Sync>)> {
+        let platforms = self.platforms.read().await;
+        platforms
+            .iter()
+            .map(|(account_id, platform)| (account_id.clone(), platform.clone()))
+            .collect()
+    }
+
+    pub fn is_accepting_signals(&self) -> bool {
+        self.accepting_signals.load(Ordering::SeqCst)
+    }
+
+    /// Reject any further signals; plans already created can still be executed
+    pub fn stop_accepting_signals(&self) {
+        if self.accepting_signals.swap(false, Ordering::SeqCst) {
+            info!("Orchestrator stopped accepting new signals");
+        }
+    }
+
+    pub async fn in_flight_executions(&self) -> Vec<String> {
+        self.active_executions
+            .read()
+            .await
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Wait up to `timeout` for in-flight executions to finish, then cancel the rest.
+    /// Returns the signal ids of the cancelled executions.
+    pub async fn drain_executions(&self, timeout: Duration) -> Vec<String> {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            if self.active_executions.read().await.is_empty() {
+                return Vec::new();
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        let _ = self.cancel_tx.send(true);
+        let cancelled: Vec<(String, ExecutionPlan)> =
+            self.active_executions.write().await.drain().collect();
+
+        for (signal_id, plan) in &cancelled {
+            warn!(
+                "Cancelled in-flight execution {} during shutdown",
+                signal_id
+            );
+            self.log_audit_entry(
+                signal_id.clone(),
+                "EXECUTION_CANCELLED".to_string(),
+                "In-flight execution did not complete before the shutdown deadline".to_string(),
+                None,
+                plan.tags.clone(),
+            )
+            .await;
+        }
+
+        cancelled
+            .into_iter()
+            .map(|(signal_id, _)| signal_id)
+            .collect()
+    }
+
+    pub async fn snapshot(&self) -> OrchestratorSnapshot {
+        OrchestratorSnapshot {
+            saved_at: SystemTime::now(),
+            accounts: self.get_all_account_statuses().await,
+            active_executions: self
+                .active_executions
+                .read()
+                .await
+                .values()
+                .cloned()
+                .collect(),
+            execution_history: self.execution_history.read().await.clone(),
+            unpublished_results: match &self.outbox {
+                Some(outbox) => outbox.pending().await,
+                None => Vec::new(),
+            },
+        }
+    }
+
+    /// Write a snapshot to `path`, replacing any previous one atomically
+    pub async fn persist_state(&self, path: &str) -> Result<(), String> {
+        self.persist_state_to(&FileOrchestratorStateStore::new(path))
+            .await
+    }
+
+    /// Save a snapshot in `store`, replacing any previous one
+    pub async fn persist_state_to(&self, store: &dyn OrchestratorStateStore) -> Result<(), String> {
+        let snapshot = self.snapshot().await;
+        store
+            .save(&snapshot)
+            .await
+            .map_err(|e| format!("Failed to persist orchestrator state: {:#}", e))?;
+
+        info!(
+            "Persisted orchestrator state ({} accounts)",
+            snapshot.accounts.len()
+        );
+        Ok(())
+    }
+
+    pub fn is_kill_switch_engaged(&self) -> bool {
+        self.kill_switch.load(Ordering::SeqCst)
+    }
+
+    pub async fn get_kill_switch(&self) -> KillSwitchState {
+        self.kill_switch_state.read().await.clone()
+    }
+
+    /// Block all new signals and any queued orders that have not been sent yet
+    pub async fn engage_kill_switch(&self, reason: String) {
+        self.set_kill_switch(true, reason).await;
+    }
+
+    pub async fn release_kill_switch(&self, reason: String) {
+        self.set_kill_switch(false, reason).await;
+    }
+
+    async fn set_kill_switch(&self, engaged: bool, reason: String) {
+        let mut state = self.kill_switch_state.write().await;
+        self.kill_switch.store(engaged, Ordering::SeqCst);
+        *state = KillSwitchState {
+            engaged,
+            reason: Some(reason.clone()),
+            changed_at: Some(SystemTime::now()),
+        };
+        drop(state);
+
+        let action = if engaged {
+            warn!("Kill switch engaged: {}", reason);
+            "KILL_SWITCH_ENGAGED"
         } else {
-            0
+            info!("Kill switch released: {}", reason);
+            "KILL_SWITCH_RELEASED"
         };
-        history[start..].to_vec()
+        self.log_audit_entry(String::new(), action.to_string(), reason, None, Vec::new())
+            .await;
     }
 
-    pub async fn get_account_status(&self, account_id: &str) -> Option<AccountStatus> {
-        let accounts = self.accounts.read().await;
-        accounts.get(account_id).cloned()
+    async fn platforms_for(
+        &self,
+        account_id: Option<&str>,
+    ) -> Result<Vec<(String, Arc<dyn ITradingPlatform + Send + Sync>)>, String> {
+        let mut platforms = self.get_platforms().await;
+        if let Some(account_id) = account_id {
+            platforms.retain(|(id, _)| id == account_id);
+            if platforms.is_empty() {
+                return Err(format!("Account {} not found", account_id));
+            }
+        }
+        platforms.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(platforms)
+    }
+
+    pub async fn get_open_positions(
+        &self,
+        account_id: Option<&str>,
+    ) -> Result<Vec<UnifiedPosition>, String> {
+        let mut positions = Vec::new();
+        for (account_id, platform) in self.platforms_for(account_id).await? {
+            let account_positions = platform
+                .get_positions()
+                .await
+                .map_err(|e| format!("Failed to get positions for {}: {}", account_id, e))?;
+            self.tag_registry.resolve(&account_id, &account_positions);
+            positions.extend(account_positions.into_iter().map(|mut position| {
+                position.account_id = account_id.clone();
+                position
+            }));
+        }
+        Ok(positions)
+    }
+
+    /// Open positions with their tags, keeping those that match `filter`
+    pub async fn get_tagged_positions(
+        &self,
+        account_id: Option<&str>,
+        filter: &TagFilter,
+    ) -> Result<Vec<TaggedPosition>, String> {
+        let positions = self.get_open_positions(account_id).await?;
+        Ok(positions
+            .into_iter()
+            .map(|position| TaggedPosition {
+                tags: self.tag_registry.tags_for(&position.position_id),
+                position,
+            })
+            .filter(|tagged| filter.matches(&tagged.tags))
+            .collect())
+    }
+
+    /// Orders that are still live on the platform: pending, new or partially filled
+    pub async fn get_working_orders(
+        &self,
+        account_id: Option<&str>,
+    ) -> Result<Vec<UnifiedOrderResponse>, String> {
+        let mut orders = Vec::new();
+        for (account_id, platform) in self.platforms_for(account_id).await? {
+            let account_orders = platform
+                .get_orders(None)
+                .await
+                .map_err(|e| format!("Failed to get orders for {}: {}", account_id, e))?;
+            orders.extend(account_orders.into_iter().filter(|order| {
+                matches!(
+                    order.status,
+                    UnifiedOrderStatus::Pending
+                        | UnifiedOrderStatus::New
+                        | UnifiedOrderStatus::PartiallyFilled
+                )
+            }));
+        }
+        Ok(orders)
+    }
+
+    /// Close every open position on one account, or on all accounts. Keeps going
+    /// past individual failures and reports the outcome per position.
+    pub async fn emergency_close(
+        &self,
+        account_id: Option<&str>,
+        reason: String,
+    ) -> Result<Vec<EmergencyCloseResult>, String> {
+        warn!("Emergency close requested: {}", reason);
+        let platforms = self.platforms_for(account_id).await?;
+        // Closes go through circuit breakers that would hold them back
+        let results = as_emergency(reason.clone(), self.close_all_positions(platforms)).await;
+
+        let closed = results.iter().filter(|r| r.success).count();
+        self.log_audit_entry(
+            String::new(),
+            "EMERGENCY_CLOSE".to_string(),
+            format!(
+                "{} ({} of {} positions closed)",
+                reason,
+                closed,
+                results.len()
+            ),
+            None,
+            Vec::new(),
+        )
+        .await;
+
+        Ok(results)
+    }
+
+    /// Close every position on `platforms` by ticket, reporting each one
+    async fn close_all_positions(
+        &self,
+        platforms: Vec<(String, Arc<dyn ITradingPlatform + Send + Sync>)>,
+    ) -> Vec<EmergencyCloseResult> {
+        let mut results = Vec::new();
+
+        for (account_id, platform) in platforms {
+            let positions = match platform.get_positions().await {
+                Ok(positions) => positions,
+                Err(e) => {
+                    results.push(EmergencyCloseResult {
+                        account_id: account_id.clone(),
+                        symbol: String::new(),
+                        success: false,
+                        order_id: None,
+                        error_message: Some(format!("Failed to get positions: {}", e)),
+                    });
+                    continue;
+                }
+            };
+
+            for position in positions {
+                // By ticket, so each ticket of a hedged symbol is closed and reported
+                let result = match platform
+                    .close_position_ticket(&position.position_id, None)
+                    .await
+                {
+                    Ok(response) => {
+                        self.order_tracker.record(TrackedOrder {
+                            account_id: account_id.clone(),
+                            platform_order_id: response.platform_order_id.clone(),
+                            client_order_id: Some(response.client_order_id.clone()),
+                            signal_id: None,
+                            symbol: position.symbol.clone(),
+                            origin: OrderOrigin::EmergencyClose,
+                            sent_at: Utc::now(),
+                        });
+                        EmergencyCloseResult {
+                            account_id: account_id.clone(),
+                            symbol: position.symbol.clone(),
+                            success: true,
+                            order_id: Some(response.platform_order_id),
+                            error_message: None,
+                        }
+                    }
+                    Err(e) => {
+                        error!(
+                            "Emergency close of {} on {} failed: {}",
+                            position.symbol, account_id, e
+                        );
+                        EmergencyCloseResult {
+                            account_id: account_id.clone(),
+                            symbol: position.symbol.clone(),
+                            success: false,
+                            order_id: None,
+                            error_message: Some(e.to_string()),
+                        }
+                    }
+                };
+                results.push(result);
+            }
+        }
+
+        results
     }
 
     pub async fn pause_account(&self, account_id: &str) -> Result<(), String> {
-        let mut accounts = self.accounts.write().await;
-        if let Some(account) = accounts.get_mut(account_id) {
-            account.is_active = false;
+        if self.accounts.set_active(account_id, false).await {
             info!("Paused account {}", account_id);
             Ok(())
         } else {
@@ -615,9 +2211,7 @@ impl TradeExecutionOrchestrator {
     }
 
     pub async fn resume_account(&self, account_id: &str) -> Result<(), String> {
-        let mut accounts = self.accounts.write().await;
-        if let Some(account) = accounts.get_mut(account_id) {
-            account.is_active = true;
+        if self.accounts.set_active(account_id, true).await {
             info!("Resumed account {}", account_id);
             Ok(())
         } else {
@@ -626,6 +2220,522 @@ impl TradeExecutionOrchestrator {
     }
 }
 
+/// Outcome of sending an order, with any remediation of rejections along the way
+/// One account's share of a plan, with what it needs from the orchestrator to
+/// run in its own task
+struct AssignmentRun {
+    signal_id: String,
+    symbol: String,
+    assignment: AccountAssignment,
+    platform: Option<Arc<dyn ITradingPlatform + Send + Sync>>,
+    exit_policy: Option<ExitPolicy>,
+    tags: Vec<String>,
+    entry_ladder: Option<LadderPlan>,
+    accounts: AccountActor,
+    ladders: Arc<LadderManager>,
+    pending_exit_policies: Arc<RwLock<HashMap<String, Vec<PendingExitPolicy>>>>,
+    tag_registry: Arc<TagRegistry>,
+    order_tracker: Arc<OrderTracker>,
+    kill_switch: Arc<AtomicBool>,
+    remediation: Arc<RejectionRemediationConfig>,
+    deferred_orders: Arc<RwLock<Vec<DeferredOrder>>>,
+    slippage_guard: SlippageGuard,
+    latency_entry: LatencyEntryPolicy,
+}
+
+impl AssignmentRun {
+    async fn execute(self, mut cancel_rx: watch::Receiver<bool>) -> ExecutionResult {
+        let queued_at = Instant::now();
+
+        // Orders still waiting on their timing delay are dropped when in-flight
+        // executions are cancelled during shutdown
+        let cancelled = tokio::select! {
+            biased;
+            Ok(_) = cancel_rx.wait_for(|cancelled| *cancelled) => true,
+            _ = tokio::time::sleep(self.assignment.entry_timing_delay) => false,
+        };
+        if cancelled {
+            return self.failed("Execution cancelled during shutdown", queued_at.elapsed());
+        }
+        if self.kill_switch.load(Ordering::SeqCst) {
+            return self.failed("Kill switch engaged", queued_at.elapsed());
+        }
+
+        let start_time = Instant::now();
+        let Some(platform) = self.platform.clone() else {
+            return self.failed("Platform not found", start_time.elapsed());
+        };
+        match &self.entry_ladder {
+            Some(ladder) => self.open_ladder(&platform, ladder, start_time).await,
+            None => self.place_entry(platform.as_ref(), start_time).await,
+        }
+    }
+
+    fn failed(
+        &self,
+        error_message: impl Into<String>,
+        execution_time: Duration,
+    ) -> ExecutionResult {
+        ExecutionResult::failed(
+            &self.signal_id,
+            &self.assignment.account_id,
+            error_message,
+            execution_time,
+            self.tags.clone(),
+        )
+    }
+
+    /// Place the ladder's rungs; the account is charged as they fill
+    async fn open_ladder(
+        &self,
+        platform: &Arc<dyn ITradingPlatform + Send + Sync>,
+        ladder: &LadderPlan,
+        start_time: Instant,
+    ) -> ExecutionResult {
+        let account_id = &self.assignment.account_id;
+        let opened = match rust_decimal::Decimal::from_f64_retain(self.assignment.position_size) {
+            Some(quantity) => {
+                LadderGroup::new(&self.signal_id, account_id, &self.symbol, ladder, quantity)
+                    .map(|group| group.with_risk_per_unit(self.assignment.risk_per_unit))
+            }
+            None => Err(format!(
+                "Invalid position size {}",
+                self.assignment.position_size
+            )),
+        };
+        let opened = match opened {
+            Ok(group) => self.ladders.open(platform, group, &self.tags).await,
+            Err(e) => Err(format!("Invalid entry ladder: {}", e)),
+        };
+        let group = match opened {
+            Ok(group) => group,
+            Err(e) => {
+                error!(
+                    "Failed to open entry ladder for account {}: {}",
+                    account_id, e
+                );
+                return self.failed(e, start_time.elapsed());
+            }
+        };
+
+        for order_id in group.rungs.iter().filter_map(|r| r.order_id.clone()) {
+            self.order_tracker.record(TrackedOrder {
+                account_id: account_id.clone(),
+                platform_order_id: order_id,
+                client_order_id: None,
+                signal_id: Some(self.signal_id.clone()),
+                symbol: group.symbol.clone(),
+                origin: OrderOrigin::Entry,
+                sent_at: Utc::now(),
+            });
+        }
+        // Rungs fill later; `poll_ladders` charges the account as they fill
+        ExecutionResult {
+            signal_id: self.signal_id.clone(),
+            account_id: account_id.clone(),
+            order_id: Some(group.key()),
+            success: true,
+            error_message: None,
+            execution_time: start_time.elapsed(),
+            actual_entry_price: None,
+            slippage: None,
+            requested_quantity: None,
+            filled_quantity: None,
+            rejection: None,
+            tags: self.tags.clone(),
+        }
+    }
+
+    fn entry_order(&self) -> UnifiedOrder {
+        UnifiedOrder {
+            client_order_id: Uuid::new_v4().to_string(),
+            symbol: self.symbol.clone(),
+            order_type: UnifiedOrderType::Market,
+            side: UnifiedOrderSide::Buy,
+            quantity: rust_decimal::Decimal::from_f64_retain(self.assignment.position_size)
+                .unwrap(),
+            price: None,
+            stop_price: None,
+            stop_loss: Some(rust_decimal::Decimal::from_f64_retain(1.0800).unwrap()),
+            take_profit: Some(rust_decimal::Decimal::from_f64_retain(1.1000).unwrap()),
+            time_in_force: crate::platforms::abstraction::models::UnifiedTimeInForce::Gtc,
+            account_id: Some(self.assignment.account_id.clone()),
+            metadata: crate::platforms::abstraction::models::OrderMetadata {
+                strategy_id: Some(
+                    strategy_tag(&self.tags).unwrap_or_else(|| self.signal_id.clone()),
+                ),
+                signal_id: Some(self.signal_id.clone()),
+                risk_parameters: HashMap::new(),
+                tags: self.tags.clone(),
+                expires_at: None,
+            },
+            max_slippage: None,
+        }
+    }
+
+    /// Send the entry as a market order, guarded against slippage, or as a limit
+    /// order while the platform is slow
+    async fn place_entry(
+        &self,
+        platform: &(dyn ITradingPlatform + Send + Sync),
+        start_time: Instant,
+    ) -> ExecutionResult {
+        let account_id = &self.assignment.account_id;
+        let mut order = self.entry_order();
+
+        // Sizes the platform cannot trade are snapped to its lot step, or
+        // rejected here rather than by the broker
+        let requested = order.quantity;
+        match platform.capabilities().normalize_order_quantity(&mut order) {
+            Ok(quantity) if quantity != requested => debug!(
+                "Order quantity for {} snapped from {} to {}",
+                account_id, requested, quantity
+            ),
+            Ok(_) => {}
+            Err(e) => {
+                warn!(
+                    "Order for account {} rejected before submission: {}",
+                    account_id, e
+                );
+                return self.failed(e.to_string(), start_time.elapsed());
+            }
+        }
+
+        let submission_quote = match self.slippage_guard.prepare(platform, &mut order).await {
+            Ok(quote) => quote,
+            Err(e) => {
+                warn!("Order for account {} held back: {}", account_id, e);
+                return self.failed(e, start_time.elapsed());
+            }
+        };
+
+        let conversion = self
+            .latency_entry
+            .convert(platform, account_id, &mut order)
+            .await;
+
+        let sent_at = Utc::now();
+        let Placement {
+            result,
+            rejection,
+            stop_widened_by,
+        } = place_with_remediation(platform, &mut order, &self.remediation).await;
+        let placed_order = match result {
+            Ok(placed_order) => placed_order,
+            Err(e) => return self.rejected(e, rejection, start_time).await,
+        };
+        let requested_quantity = order.quantity;
+        self.order_tracker.record(TrackedOrder {
+            account_id: account_id.clone(),
+            platform_order_id: placed_order.platform_order_id.clone(),
+            client_order_id: Some(order.client_order_id.clone()),
+            signal_id: Some(self.signal_id.clone()),
+            symbol: self.symbol.clone(),
+            origin: OrderOrigin::Entry,
+            sent_at,
+        });
+
+        let placed_order = match &conversion {
+            Some(conversion) => {
+                match self
+                    .settle_limit_entry(platform, placed_order, conversion)
+                    .await
+                {
+                    Ok(placed_order) => placed_order,
+                    Err((order_id, reason)) => {
+                        return ExecutionResult {
+                            order_id: Some(order_id),
+                            requested_quantity: requested_quantity.to_f64(),
+                            filled_quantity: Some(0.0),
+                            rejection,
+                            ..self.failed(reason, start_time.elapsed())
+                        };
+                    }
+                }
+            }
+            None => placed_order,
+        };
+        let filled_quantity = filled_quantity(&placed_order);
+        if filled_quantity < requested_quantity {
+            warn!(
+                "Order {} for account {} filled {} of {}",
+                placed_order.platform_order_id, account_id, filled_quantity, requested_quantity
+            );
+        }
+
+        let slippage_check = match submission_quote {
+            Some(quote) => {
+                self.slippage_guard
+                    .check(
+                        platform,
+                        account_id,
+                        &order,
+                        &placed_order,
+                        quote,
+                        rejection == Some(RejectionReason::Requote),
+                    )
+                    .await
+            }
+            None => None,
+        };
+        let slippage = slippage_check
+            .as_ref()
+            .and_then(|check| check.slippage.to_f64());
+        let actual_entry_price = placed_order
+            .average_fill_price
+            .or(placed_order.price)
+            .and_then(|p| p.to_f64());
+        // The position is gone, so nothing follows the fill
+        if let Some(flattened) = slippage_check.and_then(|check| check.flattened) {
+            return ExecutionResult {
+                order_id: Some(placed_order.platform_order_id),
+                actual_entry_price,
+                slippage,
+                requested_quantity: requested_quantity.to_f64(),
+                filled_quantity: filled_quantity.to_f64(),
+                rejection,
+                ..self.failed(format!("Order {}", flattened), start_time.elapsed())
+            };
+        }
+
+        let risk_per_unit = self.assignment.risk_per_unit + stop_widened_by.to_f64().unwrap_or(0.0);
+        self.record_fill(platform, filled_quantity, risk_per_unit, sent_at)
+            .await;
+        ExecutionResult {
+            signal_id: self.signal_id.clone(),
+            account_id: account_id.clone(),
+            order_id: Some(placed_order.platform_order_id),
+            success: true,
+            error_message: None,
+            execution_time: start_time.elapsed(),
+            actual_entry_price,
+            slippage,
+            requested_quantity: requested_quantity.to_f64(),
+            filled_quantity: filled_quantity.to_f64(),
+            rejection,
+            tags: self.tags.clone(),
+        }
+    }
+
+    /// Wait for a limit entry converted from a market order to fill. One that
+    /// does not is cancelled, and its id and the reason come back as the error.
+    async fn settle_limit_entry(
+        &self,
+        platform: &(dyn ITradingPlatform + Send + Sync),
+        placed_order: UnifiedOrderResponse,
+        conversion: &LimitConversion,
+    ) -> Result<UnifiedOrderResponse, (String, String)> {
+        let account_id = &self.assignment.account_id;
+        let placed_order = self
+            .latency_entry
+            .settle(platform, account_id, placed_order, conversion)
+            .await;
+        if matches!(
+            placed_order.status,
+            UnifiedOrderStatus::Filled | UnifiedOrderStatus::PartiallyFilled
+        ) {
+            return Ok(placed_order);
+        }
+        warn!(
+            "Limit entry {} for account {} not filled within {:?}",
+            placed_order.platform_order_id, account_id, conversion.fill_timeout
+        );
+        Err((
+            placed_order.platform_order_id,
+            format!(
+                "Limit entry at {} not filled within {} ms and cancelled; platform latency {} ms",
+                conversion.limit_price,
+                conversion.fill_timeout.as_millis(),
+                conversion.latency_ms
+            ),
+        ))
+    }
+
+    /// Hand the filled entry its exit policy and tags, and count it against the account
+    async fn record_fill(
+        &self,
+        platform: &(dyn ITradingPlatform + Send + Sync),
+        filled_quantity: rust_decimal::Decimal,
+        risk_per_unit: f64,
+        sent_at: chrono::DateTime<Utc>,
+    ) {
+        let account_id = &self.assignment.account_id;
+        if let Some(policy) = self.exit_policy.clone() {
+            let mut pending = self.pending_exit_policies.write().await;
+            let queue = pending.entry(account_id.clone()).or_default();
+            queue.retain(|p| !p.is_expired(sent_at));
+            queue.push(PendingExitPolicy {
+                signal_id: self.signal_id.clone(),
+                symbol: self.symbol.clone(),
+                policy,
+                created_at: sent_at,
+                quantity: Some(filled_quantity),
+            });
+        }
+
+        if !self.tags.is_empty() {
+            self.tag_registry
+                .expect(account_id, &self.symbol, self.tags.clone(), sent_at);
+            match platform.get_positions().await {
+                Ok(positions) => self.tag_registry.resolve(account_id, &positions),
+                Err(e) => debug!(
+                    "Tags for {} wait for the next position query: {}",
+                    account_id, e
+                ),
+            }
+        }
+
+        self.accounts
+            .order_placed(
+                account_id,
+                filled_quantity.to_f64().unwrap_or(0.0),
+                risk_per_unit,
+            )
+            .await;
+    }
+
+    /// Report a refused entry, holding it for the market open when its
+    /// rejection is remediated that way
+    async fn rejected(
+        &self,
+        error: PlatformError,
+        rejection: Option<RejectionReason>,
+        start_time: Instant,
+    ) -> ExecutionResult {
+        let account_id = &self.assignment.account_id;
+        let deferral = rejection.and_then(|reason| match self.remediation.strategy(reason) {
+            Remediation::DeferToOpen { max_wait_secs } => Some((reason, *max_wait_secs)),
+            _ => None,
+        });
+        let error_message = if let Some((reason, max_wait_secs)) = deferral {
+            let deferred_at = Utc::now();
+            let expires_at = deferred_at + chrono::Duration::seconds(max_wait_secs as i64);
+            warn!(
+                "Order for account {} deferred until {} opens, at most until {}: {}",
+                account_id, self.symbol, expires_at, error
+            );
+            self.deferred_orders.write().await.push(DeferredOrder {
+                plan: ExecutionPlan {
+                    signal_id: self.signal_id.clone(),
+                    symbol: self.symbol.clone(),
+                    account_assignments: vec![AccountAssignment {
+                        entry_timing_delay: Duration::ZERO,
+                        ..self.assignment.clone()
+                    }],
+                    timing_variance: HashMap::new(),
+                    size_variance: HashMap::new(),
+                    rationale: format!("Deferred after rejection: {}", error),
+                    exit_policy: self.exit_policy.clone(),
+                    tags: self.tags.clone(),
+                    entry_ladder: None,
+                },
+                account_id: account_id.clone(),
+                reason,
+                deferred_at,
+                expires_at,
+            });
+            format!("Deferred until the market opens: {}", error)
+        } else {
+            error!(
+                "Failed to execute order for account {}: {}",
+                account_id, error
+            );
+            error.to_string()
+        };
+        ExecutionResult {
+            rejection,
+            ..self.failed(error_message, start_time.elapsed())
+        }
+    }
+}
+
+struct Placement {
+    result: Result<UnifiedOrderResponse, PlatformError>,
+    rejection: Option<RejectionReason>,
+    /// Distance the stop-loss was moved out to get the order accepted
+    stop_widened_by: rust_decimal::Decimal,
+}
+
+/// Send `order`, resending it adjusted per `config` while the platform rejects it
+/// for a reason with a remediation. `order` is left as it was last sent.
+async fn place_with_remediation(
+    platform: &(dyn ITradingPlatform + Send + Sync),
+    order: &mut UnifiedOrder,
+    config: &RejectionRemediationConfig,
+) -> Placement {
+    let classifier = RejectionClassifier::for_platform(&platform.platform_type());
+    let mut placement = Placement {
+        result: Err(PlatformError::InternalError {
+            reason: "Order not sent".to_string(),
+        }),
+        rejection: None,
+        stop_widened_by: rust_decimal::Decimal::ZERO,
+    };
+
+    for attempt in 0..=config.max_attempts {
+        let error = match platform.place_order(order.clone()).await {
+            Ok(placed) => {
+                placement.result = Ok(placed);
+                return placement;
+            }
+            Err(e) => e,
+        };
+        let Some(reason) = classifier.classify(&error) else {
+            placement.result = Err(error);
+            return placement;
+        };
+        placement.rejection = Some(reason);
+        let remediation = config.strategy(reason);
+        if attempt == config.max_attempts
+            || matches!(
+                remediation,
+                Remediation::None | Remediation::DeferToOpen { .. }
+            )
+        {
+            placement.result = Err(error);
+            return placement;
+        }
+
+        match adjust_order(platform, order, remediation).await {
+            Ok(adjusted) => {
+                info!(
+                    "Order {} rejected ({}: {}); {} and resending",
+                    order.client_order_id, reason, error, adjusted.description
+                );
+                placement.stop_widened_by += adjusted.stop_widened_by;
+                // A fresh id so the resend is not taken for a duplicate
+                order.client_order_id = Uuid::new_v4().to_string();
+            }
+            Err(why) => {
+                warn!(
+                    "Order {} rejected ({}: {}) and cannot be remediated: {}",
+                    order.client_order_id, reason, error, why
+                );
+                placement.result = Err(error);
+                return placement;
+            }
+        }
+    }
+    placement
+}
+
+/// Size an accepted order has filled. Orders still working are counted in full
+/// until their fills are known.
+fn filled_quantity(order: &UnifiedOrderResponse) -> rust_decimal::Decimal {
+    match order.status {
+        UnifiedOrderStatus::PartiallyFilled => order.filled_quantity,
+        UnifiedOrderStatus::Filled if !order.filled_quantity.is_zero() => order.filled_quantity,
+        _ => order.quantity,
+    }
+}
+
+/// Value of the `strategy:` tag, reported to platforms as the order's strategy id
+fn strategy_tag(tags: &[String]) -> Option<String> {
+    tags.iter()
+        .find_map(|tag| tag.strip_prefix("strategy:"))
+        .map(str::to_string)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,145 @@
+// Orders the engine itself sent, by platform and client order id, so fills
+// reported by the broker can be told apart from trades placed by hand
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// How long sent orders are remembered
+const DEFAULT_RETENTION_HOURS: i64 = 7 * 24;
+
+/// Why the engine sent an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderOrigin {
+    Entry,
+    Exit,
+    EmergencyClose,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackedOrder {
+    pub account_id: String,
+    pub platform_order_id: String,
+    pub client_order_id: Option<String>,
+    pub signal_id: Option<String>,
+    pub symbol: String,
+    pub origin: OrderOrigin,
+    pub sent_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct Orders {
+    by_platform_id: HashMap<String, TrackedOrder>,
+    /// Client order id to platform order id
+    by_client_id: HashMap<String, String>,
+}
+
+#[derive(Debug)]
+pub struct OrderTracker {
+    orders: RwLock<Orders>,
+    retention: Duration,
+}
+
+impl Default for OrderTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderTracker {
+    pub fn new() -> Self {
+        Self {
+            orders: RwLock::new(Orders::default()),
+            retention: Duration::hours(DEFAULT_RETENTION_HOURS),
+        }
+    }
+
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Remember `order`, forgetting those sent longer ago than the retention
+    pub fn record(&self, order: TrackedOrder) {
+        let cutoff = Utc::now() - self.retention;
+        let mut orders = self.orders.write().unwrap();
+        orders.by_platform_id.retain(|_, o| o.sent_at >= cutoff);
+        let Orders {
+            by_platform_id,
+            by_client_id,
+        } = &mut *orders;
+        by_client_id.retain(|_, platform_id| by_platform_id.contains_key(platform_id));
+
+        if let Some(client_order_id) = &order.client_order_id {
+            by_client_id.insert(client_order_id.clone(), order.platform_order_id.clone());
+        }
+        by_platform_id.insert(order.platform_order_id.clone(), order);
+    }
+
+    /// The order sent as `platform_order_id` or, failing that, `client_order_id`
+    pub fn find(
+        &self,
+        platform_order_id: Option<&str>,
+        client_order_id: Option<&str>,
+    ) -> Option<TrackedOrder> {
+        let orders = self.orders.read().unwrap();
+        platform_order_id
+            .and_then(|id| orders.by_platform_id.get(id))
+            .or_else(|| {
+                client_order_id
+                    .and_then(|id| orders.by_client_id.get(id))
+                    .and_then(|id| orders.by_platform_id.get(id))
+            })
+            .cloned()
+    }
+
+    /// Whether an order of `origin` on `symbol` was sent for `account_id`
+    /// between `from` and `to`
+    pub fn sent_between(
+        &self,
+        account_id: &str,
+        symbol: &str,
+        origin: OrderOrigin,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> bool {
+        self.latest_sent(account_id, symbol, origin, from, to)
+            .is_some()
+    }
+
+    /// The last order of `origin` on `symbol` sent for `account_id` between
+    /// `from` and `to`
+    pub fn latest_sent(
+        &self,
+        account_id: &str,
+        symbol: &str,
+        origin: OrderOrigin,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Option<TrackedOrder> {
+        self.orders
+            .read()
+            .unwrap()
+            .by_platform_id
+            .values()
+            .filter(|o| {
+                o.account_id == account_id
+                    && o.symbol == symbol
+                    && o.origin == origin
+                    && o.sent_at >= from
+                    && o.sent_at <= to
+            })
+            .max_by_key(|o| o.sent_at)
+            .cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.read().unwrap().by_platform_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,238 @@
+// Account pacing: each account trades inside its own daily windows, spaced out
+// from its last trade and up to a number of trades drawn for the day, so accounts
+// do not all trade at the same times every day
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use dashmap::DashMap;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Time of day, in UTC, during which accounts may trade; a start after the end
+/// spans midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl ActivityWindow {
+    pub fn contains(&self, at: NaiveTime) -> bool {
+        if self.start <= self.end {
+            at >= self.start && at < self.end
+        } else {
+            at >= self.start || at < self.end
+        }
+    }
+
+    /// The window moved by `minutes`, wrapping around midnight
+    pub fn shifted(&self, minutes: i64) -> Self {
+        let by = Duration::minutes(minutes);
+        Self {
+            start: self.start.overflowing_add_signed(by).0,
+            end: self.end.overflowing_add_signed(by).0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PacingConfig {
+    /// Off, accounts trade whenever a signal arrives
+    pub enabled: bool,
+    /// Windows accounts trade in; none allows any time of day
+    pub windows: Vec<ActivityWindow>,
+    /// Windows replacing `windows` for an account, by account id
+    pub account_windows: HashMap<String, Vec<ActivityWindow>>,
+    /// Shortest time between two trades of an account
+    pub min_interval_secs: u64,
+    /// Each account's windows move by up to this many minutes either way, drawn
+    /// afresh every day
+    pub daily_shift_mins: u32,
+    /// Range the number of trades an account may take in a day is drawn from every
+    /// day; unset does not limit trades per day
+    pub daily_trades: Option<(u32, u32)>,
+}
+
+impl Default for PacingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            windows: Vec::new(),
+            account_windows: HashMap::new(),
+            min_interval_secs: 900,
+            daily_shift_mins: 30,
+            daily_trades: None,
+        }
+    }
+}
+
+impl PacingConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some((fewest, most)) = self.daily_trades {
+            if fewest == 0 || fewest > most {
+                return Err(format!(
+                    "Pacing daily_trades must be a range of at least 1 trade, not {}-{}",
+                    fewest, most
+                ));
+            }
+        }
+        let windows = self
+            .windows
+            .iter()
+            .chain(self.account_windows.values().flatten());
+        for window in windows {
+            if window.start == window.end {
+                return Err(format!(
+                    "Pacing window {}-{} is empty",
+                    window.start.format("%H:%M"),
+                    window.end.format("%H:%M")
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How an account trades on one day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailySchedule {
+    pub date: NaiveDate,
+    /// The configured windows moved by the day's shift
+    pub windows: Vec<ActivityWindow>,
+    pub shift_mins: i64,
+    /// Trades the account may take that day, when limited
+    pub trade_limit: Option<u32>,
+    pub trades: u32,
+    pub last_trade: Option<DateTime<Utc>>,
+}
+
+/// Applies `PacingConfig` to accounts and counts their trades. Cheap to clone;
+/// clones share their schedules.
+#[derive(Debug, Clone, Default)]
+pub struct AccountPacer {
+    config: PacingConfig,
+    /// Today's schedule by account, drawn on first use each day
+    schedules: Arc<DashMap<String, DailySchedule>>,
+}
+
+impl AccountPacer {
+    pub fn new(config: PacingConfig) -> Self {
+        Self {
+            config,
+            schedules: Arc::new(DashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &PacingConfig {
+        &self.config
+    }
+
+    /// The account's schedule for the day `at` falls on, or the latest drawn
+    pub fn schedule(&self, account_id: &str, at: DateTime<Utc>) -> DailySchedule {
+        self.today(account_id, at).clone()
+    }
+
+    /// Why the account may not trade at `at`, if it may not
+    pub fn check(&self, account_id: &str, at: DateTime<Utc>) -> Option<String> {
+        if !self.config.enabled {
+            return None;
+        }
+        let schedule = self.today(account_id, at);
+        let time = at.time();
+        if !schedule.windows.is_empty() && !schedule.windows.iter().any(|w| w.contains(time)) {
+            let windows: Vec<String> = schedule
+                .windows
+                .iter()
+                .map(|w| format!("{}-{}", w.start.format("%H:%M"), w.end.format("%H:%M")))
+                .collect();
+            return Some(format!(
+                "{} is outside its trading windows today ({} UTC)",
+                time.format("%H:%M"),
+                windows.join(", ")
+            ));
+        }
+        if let Some(limit) = schedule
+            .trade_limit
+            .filter(|limit| schedule.trades >= *limit)
+        {
+            return Some(format!(
+                "{} trades taken today, its limit for the day",
+                limit
+            ));
+        }
+        if let Some(last) = schedule.last_trade {
+            let since = at - last;
+            if since < Duration::seconds(self.config.min_interval_secs as i64) {
+                return Some(format!(
+                    "last trade {}s ago, within the {}s minimum between trades",
+                    since.num_seconds().max(0),
+                    self.config.min_interval_secs
+                ));
+            }
+        }
+        None
+    }
+
+    /// Count a trade of the account entered at `at`
+    pub fn record_trade(&self, account_id: &str, at: DateTime<Utc>) {
+        if !self.config.enabled {
+            return;
+        }
+        let mut schedule = self.today(account_id, at);
+        schedule.trades += 1;
+        schedule.last_trade = Some(schedule.last_trade.map_or(at, |last| last.max(at)));
+    }
+
+    fn today(
+        &self,
+        account_id: &str,
+        at: DateTime<Utc>,
+    ) -> dashmap::mapref::one::RefMut<'_, String, DailySchedule> {
+        let date = at.date_naive();
+        let mut entry = self
+            .schedules
+            .entry(account_id.to_string())
+            .or_insert_with(|| self.draw(account_id, date, None));
+        // Trades are recorded at their delayed entry, which may fall after midnight;
+        // the day only turns once checks reach it
+        if entry.date < date {
+            // The last trade carries over, so a trade just before midnight still
+            // spaces out the first one after it
+            let last_trade = entry.last_trade;
+            *entry = self.draw(account_id, date, last_trade);
+        }
+        entry
+    }
+
+    fn draw(
+        &self,
+        account_id: &str,
+        date: NaiveDate,
+        last_trade: Option<DateTime<Utc>>,
+    ) -> DailySchedule {
+        let mut rng = rand::thread_rng();
+        let max_shift = self.config.daily_shift_mins as i64;
+        let shift_mins = rng.gen_range(-max_shift..=max_shift);
+        let windows = self
+            .config
+            .account_windows
+            .get(account_id)
+            .unwrap_or(&self.config.windows)
+            .iter()
+            .map(|window| window.shifted(shift_mins))
+            .collect();
+        DailySchedule {
+            date,
+            windows,
+            shift_mins,
+            trade_limit: self
+                .config
+                .daily_trades
+                .map(|(fewest, most)| rng.gen_range(fewest..=most)),
+            trades: 0,
+            last_trade,
+        }
+    }
+}
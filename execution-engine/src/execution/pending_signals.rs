@@ -0,0 +1,393 @@
+// Holds signals that arrive while they cannot be traded and releases them once
+// they can, or expires them
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Notify, RwLock};
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, info};
+
+use crate::execution::orchestrator::{ExecutionResult, TradeExecutionOrchestrator, TradeSignal};
+use crate::execution::tags::tags_from_metadata;
+use crate::instruments::InstrumentMetadata;
+use crate::platforms::abstraction::errors::PlatformError;
+use crate::platforms::abstraction::events::EventType;
+use crate::platforms::abstraction::models::TradingSession;
+use crate::runtime::supervisor::{ShutdownSignal, Subsystem};
+
+/// Signal metadata key with an RFC 3339 time after which a held signal is dropped
+pub const EXPIRES_AT_KEY: &str = "expires_at";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingSignalConfig {
+    pub enabled: bool,
+    /// How often held signals are re-evaluated
+    #[serde(default = "default_check_interval_secs")]
+    pub check_interval_secs: u64,
+    /// How long a signal without an `expires_at` is held
+    #[serde(default = "default_ttl_secs")]
+    pub default_ttl_secs: u64,
+    /// Widest spread a signal is executed into; unset skips the check
+    #[serde(default)]
+    pub max_spread_pips: Option<f64>,
+    /// Per-symbol overrides of `max_spread_pips`
+    #[serde(default)]
+    pub symbol_max_spread_pips: HashMap<String, f64>,
+    #[serde(default = "default_max_pending")]
+    pub max_pending: usize,
+}
+
+fn default_check_interval_secs() -> u64 {
+    30
+}
+
+fn default_ttl_secs() -> u64 {
+    3600
+}
+
+fn default_max_pending() -> usize {
+    100
+}
+
+impl Default for PendingSignalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval_secs: default_check_interval_secs(),
+            default_ttl_secs: default_ttl_secs(),
+            max_spread_pips: None,
+            symbol_max_spread_pips: HashMap::new(),
+            max_pending: default_max_pending(),
+        }
+    }
+}
+
+impl PendingSignalConfig {
+    pub fn max_spread_pips(&self, symbol: &str) -> Option<f64> {
+        self.symbol_max_spread_pips
+            .get(symbol)
+            .copied()
+            .or(self.max_spread_pips)
+    }
+}
+
+/// Why a signal cannot be executed yet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HoldReason {
+    MarketClosed,
+    SpreadTooWide { spread_pips: f64, max_pips: f64 },
+    NoQuote { reason: String },
+}
+
+impl fmt::Display for HoldReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HoldReason::MarketClosed => write!(f, "market closed"),
+            HoldReason::SpreadTooWide {
+                spread_pips,
+                max_pips,
+            } => write!(
+                f,
+                "spread of {:.1} pips above the {:.1} pip maximum",
+                spread_pips, max_pips
+            ),
+            HoldReason::NoQuote { reason } => write!(f, "no quote: {}", reason),
+        }
+    }
+}
+
+/// A signal waiting for its market to become tradable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingSignal {
+    pub signal: TradeSignal,
+    pub queued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Why the signal was held at its last evaluation
+    pub reason: HoldReason,
+    pub evaluations: u32,
+}
+
+/// What became of a submitted or held signal
+#[derive(Debug, Clone)]
+pub enum SignalDisposition {
+    Executed(Vec<ExecutionResult>),
+    Held {
+        reason: HoldReason,
+        expires_at: DateTime<Utc>,
+    },
+    Expired,
+    Rejected(String),
+}
+
+/// Queue of signals that arrived while their market was closed or too expensive to
+/// trade. Held signals are re-evaluated on a schedule and whenever a platform
+/// reports a session opening, then executed or expired, with each step audited on
+/// the orchestrator.
+pub struct PendingSignalQueue {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    config: PendingSignalConfig,
+    pending: RwLock<Vec<PendingSignal>>,
+    market_open: Arc<Notify>,
+}
+
+impl PendingSignalQueue {
+    pub fn new(orchestrator: Arc<TradeExecutionOrchestrator>, config: PendingSignalConfig) -> Self {
+        Self {
+            orchestrator,
+            config,
+            pending: RwLock::new(Vec::new()),
+            market_open: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Execute `signal` now if its market is tradable, otherwise hold it until it
+    /// is or until it expires
+    pub async fn submit(&self, signal: TradeSignal) -> SignalDisposition {
+        let now = Utc::now();
+        let expires_at = match self.expires_at(&signal, now) {
+            Ok(expires_at) => expires_at,
+            Err(e) => return SignalDisposition::Rejected(e),
+        };
+        if expires_at <= now {
+            self.audit(
+                &signal,
+                "SIGNAL_EXPIRED",
+                "Signal arrived already expired".to_string(),
+            )
+            .await;
+            return SignalDisposition::Expired;
+        }
+
+        let Some(reason) = self.hold_reason(&signal.symbol).await else {
+            return self.execute(signal).await;
+        };
+
+        let mut pending = self.pending.write().await;
+        if pending.len() >= self.config.max_pending {
+            drop(pending);
+            let message = format!(
+                "Pending signal queue full ({} signals), signal not held",
+                self.config.max_pending
+            );
+            self.audit(&signal, "SIGNAL_REJECTED", message.clone())
+                .await;
+            return SignalDisposition::Rejected(message);
+        }
+        pending.push(PendingSignal {
+            signal: signal.clone(),
+            queued_at: now,
+            expires_at,
+            reason: reason.clone(),
+            evaluations: 0,
+        });
+        drop(pending);
+
+        info!(
+            "Holding signal {} for {} until {}: {}",
+            signal.id, signal.symbol, expires_at, reason
+        );
+        self.audit(
+            &signal,
+            "SIGNAL_HELD",
+            format!("Held until {}: {}", expires_at.to_rfc3339(), reason),
+        )
+        .await;
+        SignalDisposition::Held { reason, expires_at }
+    }
+
+    /// Expire held signals past their expiry and execute those whose market has
+    /// become tradable. Signals stay held while the orchestrator is not taking
+    /// signals. Returns what happened to every signal that left the queue.
+    pub async fn evaluate(&self, now: DateTime<Utc>) -> Vec<(String, SignalDisposition)> {
+        let held = std::mem::take(&mut *self.pending.write().await);
+        let mut outcomes = Vec::new();
+        let mut still_held = Vec::new();
+        let intake_open =
+            self.orchestrator.is_accepting_signals() && !self.orchestrator.is_kill_switch_engaged();
+
+        for mut entry in held {
+            if entry.expires_at <= now {
+                info!("Pending signal {} expired", entry.signal.id);
+                self.audit(
+                    &entry.signal,
+                    "SIGNAL_EXPIRED",
+                    format!(
+                        "Expired at {} after {} evaluations, last held for {}",
+                        entry.expires_at.to_rfc3339(),
+                        entry.evaluations,
+                        entry.reason
+                    ),
+                )
+                .await;
+                outcomes.push((entry.signal.id.clone(), SignalDisposition::Expired));
+                continue;
+            }
+            if !intake_open {
+                still_held.push(entry);
+                continue;
+            }
+
+            entry.evaluations += 1;
+            match self.hold_reason(&entry.signal.symbol).await {
+                Some(reason) => {
+                    debug!("Signal {} still held: {}", entry.signal.id, reason);
+                    entry.reason = reason;
+                    still_held.push(entry);
+                }
+                None => {
+                    self.audit(
+                        &entry.signal,
+                        "SIGNAL_RELEASED",
+                        format!(
+                            "Released after {} evaluations, held since {}",
+                            entry.evaluations,
+                            entry.queued_at.to_rfc3339()
+                        ),
+                    )
+                    .await;
+                    let signal_id = entry.signal.id.clone();
+                    outcomes.push((signal_id, self.execute(entry.signal).await));
+                }
+            }
+        }
+
+        // Signals submitted while this evaluation ran stay behind the older ones
+        let mut pending = self.pending.write().await;
+        still_held.append(&mut pending);
+        *pending = still_held;
+        outcomes
+    }
+
+    pub async fn pending(&self) -> Vec<PendingSignal> {
+        self.pending.read().await.clone()
+    }
+
+    /// Re-evaluate held signals now rather than at the next scheduled check
+    pub fn notify_market_open(&self) {
+        self.market_open.notify_one();
+    }
+
+    fn expires_at(
+        &self,
+        signal: &TradeSignal,
+        now: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>, String> {
+        match signal.metadata.get(EXPIRES_AT_KEY) {
+            Some(value) => DateTime::parse_from_rfc3339(value.trim())
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|e| format!("{}: {}", EXPIRES_AT_KEY, e)),
+            None => Ok(now + ChronoDuration::seconds(self.config.default_ttl_secs as i64)),
+        }
+    }
+
+    /// Why `symbol` cannot be traded right now, if it cannot. The first account
+    /// with a quote for the symbol decides.
+    async fn hold_reason(&self, symbol: &str) -> Option<HoldReason> {
+        let mut last_error = "no accounts registered".to_string();
+        for (account_id, platform) in self.orchestrator.get_platforms().await {
+            let quote = match platform.get_market_data(symbol).await {
+                Ok(quote) => quote,
+                Err(PlatformError::MarketClosed { .. }) => return Some(HoldReason::MarketClosed),
+                Err(e) => {
+                    last_error = format!("{} on {}", e, account_id);
+                    continue;
+                }
+            };
+            if matches!(quote.session, Some(TradingSession::Closed)) {
+                return Some(HoldReason::MarketClosed);
+            }
+            if let Some(max_pips) = self.config.max_spread_pips(symbol) {
+                let spread = (quote.ask - quote.bid).max(quote.spread);
+                let spread_pips = InstrumentMetadata::conventional(symbol)
+                    .price_to_pips(spread)
+                    .to_f64()
+                    .unwrap_or(f64::MAX);
+                if spread_pips > max_pips {
+                    return Some(HoldReason::SpreadTooWide {
+                        spread_pips,
+                        max_pips,
+                    });
+                }
+            }
+            return None;
+        }
+        Some(HoldReason::NoQuote { reason: last_error })
+    }
+
+    async fn execute(&self, signal: TradeSignal) -> SignalDisposition {
+        match self.orchestrator.process_signal(signal).await {
+            Ok(plan) => SignalDisposition::Executed(self.orchestrator.execute_plan(&plan).await),
+            Err(e) => SignalDisposition::Rejected(e),
+        }
+    }
+
+    async fn audit(&self, signal: &TradeSignal, action: &str, rationale: String) {
+        self.orchestrator
+            .record_signal_event(
+                &signal.id,
+                action,
+                rationale,
+                tags_from_metadata(&signal.metadata),
+            )
+            .await;
+    }
+
+    /// Wake the queue whenever a registered platform reports a session opening
+    async fn watch_sessions(&self) {
+        for (account_id, platform) in self.orchestrator.get_platforms().await {
+            let mut events = match platform.subscribe_events().await {
+                Ok(events) => events,
+                Err(e) => {
+                    debug!("No session events from {}: {}", account_id, e);
+                    continue;
+                }
+            };
+            let market_open = self.market_open.clone();
+            tokio::spawn(async move {
+                while let Some(event) = events.recv().await {
+                    if event.event_type == EventType::SessionOpened {
+                        market_open.notify_one();
+                    }
+                }
+            });
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for PendingSignalQueue {
+    fn name(&self) -> &str {
+        "pending-signals"
+    }
+
+    async fn start(&self) -> Result<()> {
+        self.watch_sessions().await;
+        Ok(())
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_secs(self.config.check_interval_secs));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = self.market_open.notified() => {
+                    debug!("Session opened, re-evaluating held signals");
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+            let outcomes = self.evaluate(Utc::now()).await;
+            if !outcomes.is_empty() {
+                info!("{} held signals left the queue", outcomes.len());
+            }
+        }
+    }
+}
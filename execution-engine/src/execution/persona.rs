@@ -0,0 +1,202 @@
+// Trading personas: accounts keep to a style of their own, sizing, timing and
+// exiting trades differently from each other so their footprints diverge. Each
+// account plays its persona with a jitter drawn from its id, the same on every run.
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::exit_management::{ExitPolicy, ProfitTakingConfig, ProfitTarget, TimeExitConfig};
+
+/// Prefix of the names of exit policies filled in from a persona
+pub const PERSONA_POLICY_PREFIX: &str = "persona:";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Persona {
+    /// 0 for cautious to 1 for aggressive: aggressive accounts size towards the top
+    /// of the size variance and enter early in the delay range, cautious ones the
+    /// other way round
+    pub aggressiveness: f64,
+    /// Hours a position is held before the time exit closes it
+    pub hold_hours: f64,
+    /// Share of a position closed at the first profit target; 0 takes no partials
+    pub partial_take: f64,
+    /// Risk reward the first profit target is set at
+    pub partial_take_rr: f64,
+}
+
+impl Default for Persona {
+    fn default() -> Self {
+        Self {
+            aggressiveness: 0.5,
+            hold_hours: 24.0,
+            partial_take: 0.5,
+            partial_take_rr: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersonaConfig {
+    /// Off, every account is sized, timed and exited alike
+    pub enabled: bool,
+    /// Personas by name
+    pub personas: HashMap<String, Persona>,
+    /// Persona of each account, by account id; accounts without one are unaffected
+    pub accounts: HashMap<String, String>,
+    /// How far an account strays from its persona: the aggressiveness and partial
+    /// share by up to this much, hold time and target by up to this fraction
+    pub jitter: f64,
+    /// Closed trades an account needs before the divergence report compares it
+    pub report_min_trades: usize,
+    /// Divergence two accounts' footprints must reach before the report passes them
+    pub min_divergence: f64,
+}
+
+impl Default for PersonaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            personas: HashMap::new(),
+            accounts: HashMap::new(),
+            jitter: 0.1,
+            report_min_trades: 10,
+            min_divergence: 0.1,
+        }
+    }
+}
+
+impl PersonaConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=0.5).contains(&self.jitter) {
+            return Err(format!(
+                "Persona jitter must be within 0 and 0.5, not {}",
+                self.jitter
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.min_divergence) {
+            return Err(format!(
+                "Persona min_divergence must be within 0 and 1, not {}",
+                self.min_divergence
+            ));
+        }
+        for (name, persona) in &self.personas {
+            if !(0.0..=1.0).contains(&persona.aggressiveness)
+                || !(0.0..=1.0).contains(&persona.partial_take)
+            {
+                return Err(format!(
+                    "Persona {} aggressiveness and partial_take must be within 0 and 1",
+                    name
+                ));
+            }
+            if persona.hold_hours <= 0.0 || persona.partial_take_rr <= 0.0 {
+                return Err(format!(
+                    "Persona {} hold_hours and partial_take_rr must be above 0",
+                    name
+                ));
+            }
+        }
+        if let Some((account_id, persona)) = self
+            .accounts
+            .iter()
+            .find(|(_, persona)| !self.personas.contains_key(*persona))
+        {
+            return Err(format!(
+                "Account {} has persona {}, which is not configured",
+                account_id, persona
+            ));
+        }
+        Ok(())
+    }
+
+    /// How the account plays its persona, when it has one
+    pub fn persona_of(&self, account_id: &str) -> Option<AccountPersona> {
+        if !self.enabled {
+            return None;
+        }
+        let name = self.accounts.get(account_id)?;
+        let persona = self.personas.get(name)?;
+        let jitter = |field: &str| self.jitter * unit(account_id, field);
+        Some(AccountPersona {
+            account_id: account_id.to_string(),
+            persona: name.clone(),
+            aggressiveness: (persona.aggressiveness + jitter("aggressiveness")).clamp(0.0, 1.0),
+            hold_hours: persona.hold_hours * (1.0 + jitter("hold_hours")),
+            partial_take: (persona.partial_take + jitter("partial_take")).clamp(0.0, 1.0),
+            partial_take_rr: persona.partial_take_rr * (1.0 + jitter("partial_take_rr")),
+        })
+    }
+}
+
+/// Number in -1..=1 fixed by `account_id` and `field`, by FNV-1a, which unlike the
+/// std hasher is the same on every build
+fn unit(account_id: &str, field: &str) -> f64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in account_id.bytes().chain([b':']).chain(field.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    (hash >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+}
+
+/// A persona as one account plays it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountPersona {
+    pub account_id: String,
+    pub persona: String,
+    pub aggressiveness: f64,
+    pub hold_hours: f64,
+    pub partial_take: f64,
+    pub partial_take_rr: f64,
+}
+
+impl AccountPersona {
+    /// The part of `min_ms..=max_ms` the account's entry delays are drawn from: the
+    /// first half for the most aggressive, the second half for the most cautious
+    pub fn delay_range(&self, min_ms: u64, max_ms: u64) -> (u64, u64) {
+        let span = max_ms.saturating_sub(min_ms) as f64;
+        let start = min_ms + (span * (1.0 - self.aggressiveness) / 2.0).round() as u64;
+        let end = max_ms - (span * self.aggressiveness / 2.0).round() as u64;
+        (start, end.max(start))
+    }
+
+    /// Range the account's size multiplier is drawn from, never beyond the size
+    /// variance of `max_variance_pct` either way
+    pub fn size_range(&self, max_variance_pct: f64) -> (f64, f64) {
+        (
+            1.0 - max_variance_pct * (1.0 - self.aggressiveness),
+            1.0 + max_variance_pct * self.aggressiveness,
+        )
+    }
+
+    /// `policy` with the time exit and profit taking the signal left unset filled
+    /// in from the persona
+    pub fn exit_policy(&self, policy: Option<ExitPolicy>) -> ExitPolicy {
+        let mut policy = policy.unwrap_or_default();
+        if policy.name.is_none() {
+            policy.name = Some(format!("{}{}", PERSONA_POLICY_PREFIX, self.persona));
+        }
+        if policy.time_exit.is_none() {
+            let max_hold = Duration::seconds((self.hold_hours * 3600.0).round() as i64);
+            policy.time_exit = Some(TimeExitConfig {
+                max_hold_duration: max_hold,
+                warning_duration: max_hold * 4 / 5,
+                ..TimeExitConfig::default()
+            });
+        }
+        if policy.profit_taking.is_none() {
+            policy.profit_taking = Some(ProfitTakingConfig {
+                profit_targets: vec![ProfitTarget {
+                    level: 1,
+                    risk_reward_ratio: self.partial_take_rr,
+                    close_percentage: self.partial_take,
+                }],
+                enabled: self.partial_take > 0.0,
+                ..ProfitTakingConfig::default()
+            });
+        }
+        policy
+    }
+}
@@ -0,0 +1,150 @@
+// What the orchestrator would do with a signal, and why, without placing orders
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::orchestrator::ExecutionPlan;
+
+/// How the planned size of one account was derived
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SizeDerivation {
+    pub risk_budget_remaining: f64,
+    pub available_margin: f64,
+    /// Factor on the 1% of margin risked, from the signal's confidence
+    pub confidence_multiplier: f64,
+    /// Factor on the 1% of margin risked, from the account's drawdown ladder rung
+    pub rung_multiplier: f64,
+    /// The lesser of the remaining budget and the share of margin
+    pub risk_per_trade: f64,
+    pub stop_distance: f64,
+    /// Factor for the account's daily drawdown
+    pub drawdown_adjustment: f64,
+    pub base_size: f64,
+    /// Random factor keeping accounts from trading identical sizes
+    pub variance_multiplier: f64,
+    pub planned_size: f64,
+}
+
+impl SizeDerivation {
+    fn explain(&self) -> String {
+        format!(
+            "risks {:.2}, the lesser of the remaining budget {:.2} and 1% of margin {:.2} x {:.2} for confidence x {:.2} for drawdown rung; \
+             over a stop distance of {} that is {:.2} units after x {:.2} for drawdown, \
+             planned at {:.2} after x {:.3} variance",
+            self.risk_per_trade,
+            self.risk_budget_remaining,
+            self.available_margin,
+            self.confidence_multiplier,
+            self.rung_multiplier,
+            self.stop_distance,
+            self.base_size,
+            self.drawdown_adjustment,
+            self.planned_size,
+            self.variance_multiplier
+        )
+    }
+}
+
+/// Whether and how one account takes part in the plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountDecision {
+    pub account_id: String,
+    pub eligible: bool,
+    /// Why the account is left out, when it is
+    pub exclusion: Option<String>,
+    pub sizing: Option<SizeDerivation>,
+    /// Size after the adjustments to the whole plan
+    pub final_size: Option<f64>,
+    /// Changes made to this account's assignment after sizing
+    pub adjustments: Vec<String>,
+    pub explanation: String,
+}
+
+/// The plan a signal would get, with the reasoning behind each account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanPreview {
+    pub signal_id: String,
+    /// None when the signal would be rejected
+    pub plan: Option<ExecutionPlan>,
+    pub rejection: Option<String>,
+    /// By account id
+    pub accounts: Vec<AccountDecision>,
+    /// Adjustments to the sizes and timing of all accounts, in the order applied
+    pub adjustments: Vec<String>,
+}
+
+/// Reasoning gathered while a plan is built
+#[derive(Debug, Default)]
+pub(crate) struct PlanTrace {
+    pub exclusions: Vec<(String, String)>,
+    pub sizing: HashMap<String, SizeDerivation>,
+    pub adjustments: Vec<String>,
+    pub account_adjustments: HashMap<String, Vec<String>>,
+}
+
+impl PlanTrace {
+    pub fn into_preview(
+        mut self,
+        signal_id: String,
+        plan: Result<ExecutionPlan, String>,
+    ) -> PlanPreview {
+        let (plan, rejection) = match plan {
+            Ok(plan) => (Some(plan), None),
+            Err(reason) => (None, Some(reason)),
+        };
+
+        let mut accounts: Vec<AccountDecision> = self
+            .exclusions
+            .into_iter()
+            .map(|(account_id, reason)| AccountDecision {
+                account_id,
+                eligible: false,
+                explanation: format!("Excluded: {}", reason),
+                exclusion: Some(reason),
+                sizing: None,
+                final_size: None,
+                adjustments: Vec::new(),
+            })
+            .collect();
+        for (account_id, sizing) in self.sizing {
+            let final_size = plan.as_ref().and_then(|plan| {
+                plan.account_assignments
+                    .iter()
+                    .find(|a| a.account_id == account_id)
+                    .map(|a| a.position_size)
+            });
+            let adjustments = self
+                .account_adjustments
+                .remove(&account_id)
+                .unwrap_or_default();
+            let mut explanation = format!("Eligible; {}", sizing.explain());
+            for adjustment in self.adjustments.iter().chain(&adjustments) {
+                explanation.push_str("; ");
+                explanation.push_str(adjustment);
+            }
+            match (final_size, &plan) {
+                (Some(size), _) => explanation.push_str(&format!("; final size {:.2}", size)),
+                (None, Some(_)) => explanation.push_str("; dropped from the plan"),
+                (None, None) => explanation.push_str("; not placed as the signal is rejected"),
+            }
+            accounts.push(AccountDecision {
+                account_id,
+                eligible: true,
+                exclusion: None,
+                sizing: Some(sizing),
+                final_size,
+                adjustments,
+                explanation,
+            });
+        }
+        accounts.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+
+        PlanPreview {
+            signal_id,
+            plan,
+            rejection,
+            accounts,
+            adjustments: self.adjustments,
+        }
+    }
+}
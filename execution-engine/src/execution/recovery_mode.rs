@@ -0,0 +1,204 @@
+// Recovery mode: for some days after a large loss an account only takes the most
+// confident signals, at a wider risk reward and one position at a time
+
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Audit action of plans recovery mode altered
+pub const RECOVERY_MODE_ACTION: &str = "RECOVERY_MODE";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecoveryModeConfig {
+    /// Off, accounts trade as usual after losses
+    pub enabled: bool,
+    /// Daily drawdown, as a fraction of the balance, that puts an account in
+    /// recovery; unset leaves it to the other triggers
+    pub trigger_daily_drawdown: Option<f64>,
+    /// Drawdown ladder rung that puts an account in recovery once reached
+    pub trigger_rung: Option<usize>,
+    /// Days an account stays in recovery after the trigger
+    pub days: u32,
+    /// Lowest signal confidence an account in recovery takes
+    pub min_confidence: f64,
+    /// Most positions an account in recovery may hold
+    pub max_positions: usize,
+    /// Lowest risk reward an account in recovery takes
+    pub min_risk_reward: f64,
+}
+
+impl Default for RecoveryModeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            trigger_daily_drawdown: Some(0.03),
+            trigger_rung: None,
+            days: 5,
+            min_confidence: 0.75,
+            max_positions: 1,
+            min_risk_reward: 2.5,
+        }
+    }
+}
+
+impl RecoveryModeConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(drawdown) = self.trigger_daily_drawdown {
+            if !(drawdown > 0.0 && drawdown < 1.0) {
+                return Err(format!(
+                    "Recovery mode trigger_daily_drawdown must be between 0 and 1, not {}",
+                    drawdown
+                ));
+            }
+        }
+        if self.trigger_rung == Some(0) {
+            return Err("Recovery mode trigger_rung must be 1 or deeper".to_string());
+        }
+        if self.days == 0 {
+            return Err("Recovery mode must last at least 1 day".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.min_confidence) {
+            return Err(format!(
+                "Recovery mode min_confidence must be within 0 and 1, not {}",
+                self.min_confidence
+            ));
+        }
+        if self.max_positions == 0 {
+            return Err("Recovery mode must allow at least 1 position".to_string());
+        }
+        if self.min_risk_reward < 0.0 {
+            return Err(format!(
+                "Recovery mode min_risk_reward must not be negative, not {}",
+                self.min_risk_reward
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// An account's stay in recovery mode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryPeriod {
+    pub account_id: String,
+    /// What put the account in recovery
+    pub reason: String,
+    pub started_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+/// Applies `RecoveryModeConfig` to accounts. Cheap to clone; clones share their
+/// recovery periods.
+#[derive(Debug, Clone, Default)]
+pub struct RecoveryMode {
+    config: RecoveryModeConfig,
+    /// Current recovery period by account id
+    periods: Arc<DashMap<String, RecoveryPeriod>>,
+}
+
+impl RecoveryMode {
+    pub fn new(config: RecoveryModeConfig) -> Self {
+        Self {
+            config,
+            periods: Arc::new(DashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &RecoveryModeConfig {
+        &self.config
+    }
+
+    /// The period an account put in recovery at `at` would have
+    pub fn starting(&self, account_id: &str, reason: String, at: DateTime<Utc>) -> RecoveryPeriod {
+        RecoveryPeriod {
+            account_id: account_id.to_string(),
+            reason,
+            started_at: at,
+            ends_at: at + Duration::days(self.config.days as i64),
+        }
+    }
+
+    /// Put the account in recovery from `at` for the configured days
+    pub fn enter(&self, account_id: &str, reason: String, at: DateTime<Utc>) -> RecoveryPeriod {
+        let period = self.starting(account_id, reason, at);
+        self.periods.insert(account_id.to_string(), period.clone());
+        period
+    }
+
+    /// The account's recovery period, while it lasts at `at`
+    pub fn period(&self, account_id: &str, at: DateTime<Utc>) -> Option<RecoveryPeriod> {
+        if !self.config.enabled {
+            return None;
+        }
+        self.periods
+            .remove_if(account_id, |_, period| period.ends_at <= at);
+        self.periods.get(account_id).map(|period| period.clone())
+    }
+
+    /// Every account in recovery at `at`, by account id
+    pub fn periods(&self, at: DateTime<Utc>) -> Vec<RecoveryPeriod> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+        self.periods.retain(|_, period| period.ends_at > at);
+        let mut periods: Vec<RecoveryPeriod> =
+            self.periods.iter().map(|p| p.value().clone()).collect();
+        periods.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+        periods
+    }
+
+    /// What puts an account with `daily_drawdown` on drawdown ladder `rung` in
+    /// recovery, if anything does
+    pub fn trigger(&self, daily_drawdown: f64, rung: Option<usize>) -> Option<String> {
+        if !self.config.enabled {
+            return None;
+        }
+        if let Some(limit) = self
+            .config
+            .trigger_daily_drawdown
+            .filter(|limit| daily_drawdown >= *limit)
+        {
+            return Some(format!(
+                "daily drawdown of {:.2}% reached {:.2}%",
+                daily_drawdown * 100.0,
+                limit * 100.0
+            ));
+        }
+        match (rung, self.config.trigger_rung) {
+            (Some(rung), Some(trigger)) if rung >= trigger => {
+                Some(format!("stepped down to drawdown rung {}", rung))
+            }
+            _ => None,
+        }
+    }
+
+    /// Why an account in recovery holding `open_positions` may not take a signal
+    /// of `confidence` and `risk_reward`, if it may not
+    pub fn refusal(
+        &self,
+        confidence: f64,
+        risk_reward: f64,
+        open_positions: usize,
+    ) -> Option<String> {
+        let config = &self.config;
+        if confidence < config.min_confidence {
+            Some(format!(
+                "confidence {:.2} is below the {:.2} required in recovery",
+                confidence, config.min_confidence
+            ))
+        } else if risk_reward < config.min_risk_reward {
+            Some(format!(
+                "risk reward {:.2} is below the {:.2} required in recovery",
+                risk_reward, config.min_risk_reward
+            ))
+        } else if open_positions >= config.max_positions {
+            Some(format!(
+                "{} positions are open, the most allowed in recovery",
+                open_positions
+            ))
+        } else {
+            None
+        }
+    }
+}
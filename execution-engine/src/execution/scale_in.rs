@@ -0,0 +1,77 @@
+// Signals that add to a position already open on the same side: how often an
+// account may add to one position and how large each addition is
+
+use serde::{Deserialize, Serialize};
+
+use crate::platforms::abstraction::models::{
+    UnifiedOrderSide, UnifiedPosition, UnifiedPositionSide,
+};
+
+/// Action of audit entries for plans that add to open positions
+pub const SCALE_IN_ACTION: &str = "SCALE_IN";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScaleInConfig {
+    /// Off, signals are sized as fresh entries whatever is open
+    pub enabled: bool,
+    /// Additions allowed to one position after its first entry
+    pub max_adds: u32,
+    /// Factor on the planned size of an addition
+    pub size_multiplier: f64,
+    /// Only add to positions showing an unrealized profit
+    pub require_profit: bool,
+}
+
+impl Default for ScaleInConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_adds: 2,
+            size_multiplier: 0.5,
+            require_profit: true,
+        }
+    }
+}
+
+impl ScaleInConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.size_multiplier > 0.0 && self.size_multiplier <= 1.0) {
+            return Err("Scale-in size_multiplier must be above 0 and at most 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Why an addition to `position`, its `adds`th so far, is not allowed, if it is not
+    pub fn refusal(&self, position: &UnifiedPosition, adds: u32) -> Option<String> {
+        if adds >= self.max_adds {
+            Some(format!(
+                "position {} has been added to {} times, the most allowed",
+                position.position_id, adds
+            ))
+        } else if self.require_profit && position.unrealized_pnl <= rust_decimal::Decimal::ZERO {
+            Some(format!(
+                "position {} is not in profit ({})",
+                position.position_id, position.unrealized_pnl
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// The open position on `symbol` that an order on `side` would add to
+pub fn position_added_to<'a>(
+    positions: &'a [UnifiedPosition],
+    symbol: &str,
+    side: &UnifiedOrderSide,
+) -> Option<&'a UnifiedPosition> {
+    let side = match side {
+        UnifiedOrderSide::Buy => UnifiedPositionSide::Long,
+        UnifiedOrderSide::Sell => UnifiedPositionSide::Short,
+    };
+    positions
+        .iter()
+        .filter(|p| p.symbol == symbol && p.side == side)
+        .min_by_key(|p| p.opened_at)
+}
@@ -0,0 +1,86 @@
+// Liquidity by forex session: thin sessions widen the spread of entry delays across
+// accounts, so their orders stand out less against little other flow, and the
+// slippage market orders are allowed
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::exit_management::MarketSession;
+
+/// Liquidity of each session relative to the London/New York overlap, the most
+/// liquid hours of the day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionLiquidityConfig {
+    /// Off, delays and slippage are the same at any hour
+    pub enabled: bool,
+    pub sydney: f64,
+    pub tokyo: f64,
+    pub london: f64,
+    pub london_new_york: f64,
+    pub new_york: f64,
+    /// The weekend close window
+    pub closed: f64,
+}
+
+impl Default for SessionLiquidityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sydney: 0.5,
+            tokyo: 0.6,
+            london: 0.9,
+            london_new_york: 1.0,
+            new_york: 0.8,
+            closed: 0.5,
+        }
+    }
+}
+
+impl SessionLiquidityConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        for (session, liquidity) in [
+            ("sydney", self.sydney),
+            ("tokyo", self.tokyo),
+            ("london", self.london),
+            ("london_new_york", self.london_new_york),
+            ("new_york", self.new_york),
+            ("closed", self.closed),
+        ] {
+            if !(liquidity > 0.0 && liquidity.is_finite()) {
+                return Err(format!(
+                    "Session liquidity of {} must be above 0, not {}",
+                    session, liquidity
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn liquidity(&self, session: MarketSession) -> f64 {
+        match session {
+            MarketSession::Sydney => self.sydney,
+            MarketSession::Tokyo => self.tokyo,
+            MarketSession::London => self.london,
+            MarketSession::LondonNewYork => self.london_new_york,
+            MarketSession::NewYork => self.new_york,
+            MarketSession::Closed => self.closed,
+        }
+    }
+
+    /// Factor delays and slippage allowances are scaled by at `at`: the inverse of
+    /// the session's liquidity, or 1 when off
+    pub fn scale_at(&self, at: DateTime<Utc>) -> f64 {
+        if !self.enabled {
+            return 1.0;
+        }
+        1.0 / self.liquidity(MarketSession::at(at))
+    }
+
+    /// Entry delays drawn at `at`, from `min_ms..=max_ms` scaled to the session
+    pub fn delay_range(&self, min_ms: u64, max_ms: u64, at: DateTime<Utc>) -> (u64, u64) {
+        let scale = self.scale_at(at);
+        let scaled = |ms: u64| (ms as f64 * scale).round() as u64;
+        (scaled(min_ms), scaled(max_ms).max(scaled(min_ms)))
+    }
+}
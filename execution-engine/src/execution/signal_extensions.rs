@@ -0,0 +1,196 @@
+// Typed view of the instructions a signal generator can put in `TradeSignal.metadata`
+
+use chrono::Duration;
+use std::collections::HashMap;
+use tracing::warn;
+
+use crate::execution::exit_management::{ExitPolicy, ProfitTakingConfig, ProfitTarget};
+use crate::execution::ladder::LadderConfig;
+use crate::execution::tags::tags_from_metadata;
+
+pub const EXIT_POLICY_KEY: &str = "exit_policy";
+pub const MAX_HOLDING_HOURS_KEY: &str = "max_holding_hours";
+pub const PARTIAL_LADDER_KEY: &str = "partial_ladder";
+pub const NO_WEEKEND_HOLD_KEY: &str = "no_weekend_hold";
+pub const ENTRY_LADDER_KEY: &str = "entry_ladder";
+
+/// Share of the max holding time after which the time-exit warning fires
+const WARNING_FRACTION: f64 = 0.8;
+
+/// Longest holding time a signal may ask for, a year
+pub const MAX_HOLDING_HOURS_LIMIT: f64 = 24.0 * 366.0;
+
+/// Exit instructions and tags carried by a signal. Other keys are ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SignalExtensions {
+    /// Full policy as JSON; the other extensions are applied on top of it
+    pub exit_policy: Option<ExitPolicy>,
+    pub max_holding_hours: Option<f64>,
+    /// Profit targets as a JSON array of `ProfitTarget`
+    pub partial_ladder: Option<Vec<ProfitTarget>>,
+    pub no_weekend_hold: bool,
+    /// Enter with a ladder of limit orders instead of one market order
+    pub entry_ladder: Option<LadderConfig>,
+    /// See `tags::tags_from_metadata`
+    pub tags: Vec<String>,
+}
+
+impl SignalExtensions {
+    pub fn parse(metadata: &HashMap<String, String>) -> Result<Self, String> {
+        let exit_policy = metadata
+            .get(EXIT_POLICY_KEY)
+            .map(|value| {
+                serde_json::from_str::<ExitPolicy>(value)
+                    .map_err(|e| format!("{}: {}", EXIT_POLICY_KEY, e))
+            })
+            .transpose()?;
+
+        let max_holding_hours = metadata
+            .get(MAX_HOLDING_HOURS_KEY)
+            .map(|value| match value.trim().parse::<f64>() {
+                Ok(hours) if hours > 0.0 && hours <= MAX_HOLDING_HOURS_LIMIT => Ok(hours),
+                _ => Err(format!(
+                    "{}: expected a positive number of hours up to {}, got {:?}",
+                    MAX_HOLDING_HOURS_KEY, MAX_HOLDING_HOURS_LIMIT, value
+                )),
+            })
+            .transpose()?;
+
+        let partial_ladder = metadata
+            .get(PARTIAL_LADDER_KEY)
+            .map(|value| parse_ladder(value))
+            .transpose()?;
+
+        let no_weekend_hold = match metadata.get(NO_WEEKEND_HOLD_KEY).map(|v| v.trim()) {
+            None | Some("false") => false,
+            Some("true") => true,
+            Some(other) => {
+                return Err(format!(
+                    "{}: expected true or false, got {:?}",
+                    NO_WEEKEND_HOLD_KEY, other
+                ))
+            }
+        };
+
+        let entry_ladder = metadata
+            .get(ENTRY_LADDER_KEY)
+            .map(|value| {
+                let ladder: LadderConfig = serde_json::from_str(value)
+                    .map_err(|e| format!("{}: {}", ENTRY_LADDER_KEY, e))?;
+                ladder
+                    .validate()
+                    .map_err(|e| format!("{}: {}", ENTRY_LADDER_KEY, e))?;
+                Ok::<_, String>(ladder)
+            })
+            .transpose()?;
+
+        Ok(Self {
+            exit_policy,
+            max_holding_hours,
+            partial_ladder,
+            no_weekend_hold,
+            entry_ladder,
+            tags: tags_from_metadata(metadata),
+        })
+    }
+
+    /// True if the signal leaves exits to the symbol configuration
+    pub fn has_no_exit_instructions(&self) -> bool {
+        self.exit_policy.is_none()
+            && self.max_holding_hours.is_none()
+            && self.partial_ladder.is_none()
+            && !self.no_weekend_hold
+    }
+
+    /// The exit policy the position opened by this signal should get, if any
+    pub fn exit_policy(&self, signal_id: &str) -> Option<ExitPolicy> {
+        if self.has_no_exit_instructions() {
+            return None;
+        }
+
+        let mut policy = self.exit_policy.clone().unwrap_or_default();
+        if policy.name.is_none() {
+            policy.name = Some(format!("signal {}", signal_id));
+        }
+
+        if let Some(ladder) = &self.partial_ladder {
+            policy.profit_taking = Some(ProfitTakingConfig {
+                profit_targets: ladder.clone(),
+                enabled: true,
+                take_profit_at_structure: false,
+            });
+        }
+
+        // `parse` only accepts holding times within the limit, but the field can be
+        // set directly to anything. A holding time of none at all would close the
+        // position the moment it opens, so it is ignored like one that is not a
+        // number.
+        let max_holding_hours = match self.max_holding_hours {
+            Some(hours) if !hours.is_finite() || hours <= 0.0 => {
+                warn!(
+                    "Ignoring max holding time {} of signal {}: not a positive number of hours",
+                    hours, signal_id
+                );
+                None
+            }
+            hours => hours,
+        };
+        if max_holding_hours.is_some() || self.no_weekend_hold {
+            let mut time_exit = policy.time_exit.take().unwrap_or_default();
+            if let Some(hours) = max_holding_hours {
+                let hours = hours.min(MAX_HOLDING_HOURS_LIMIT);
+                let max_hold = hours_duration(hours);
+                time_exit.enabled = true;
+                time_exit.max_hold_duration = max_hold;
+                if time_exit.warning_duration >= max_hold {
+                    time_exit.warning_duration = hours_duration(hours * WARNING_FRACTION);
+                }
+            }
+            if self.no_weekend_hold {
+                time_exit.enabled = true;
+                time_exit.close_before_weekend = true;
+            }
+            policy.time_exit = Some(time_exit);
+        }
+
+        Some(policy)
+    }
+}
+
+/// `hours` as a duration, to the second
+fn hours_duration(hours: f64) -> Duration {
+    Duration::try_seconds((hours * 3600.0).round() as i64).unwrap_or(Duration::MAX)
+}
+
+fn parse_ladder(value: &str) -> Result<Vec<ProfitTarget>, String> {
+    let ladder: Vec<ProfitTarget> =
+        serde_json::from_str(value).map_err(|e| format!("{}: {}", PARTIAL_LADDER_KEY, e))?;
+
+    if ladder.is_empty() {
+        return Err(format!("{}: ladder has no targets", PARTIAL_LADDER_KEY));
+    }
+    for target in &ladder {
+        if target.risk_reward_ratio <= 0.0 {
+            return Err(format!(
+                "{}: target {} needs a positive risk/reward ratio",
+                PARTIAL_LADDER_KEY, target.level
+            ));
+        }
+        if target.close_percentage <= 0.0 || target.close_percentage > 1.0 {
+            return Err(format!(
+                "{}: target {} must close between 0 and 100% of the position",
+                PARTIAL_LADDER_KEY, target.level
+            ));
+        }
+    }
+    let total: f64 = ladder.iter().map(|t| t.close_percentage).sum();
+    if total > 1.0 + f64::EPSILON {
+        return Err(format!(
+            "{}: targets close {:.0}% of the position",
+            PARTIAL_LADDER_KEY,
+            total * 100.0
+        ));
+    }
+
+    Ok(ladder)
+}
@@ -0,0 +1,103 @@
+// Risk per trade scaled by the confidence the signal generator puts in the signal
+
+use serde::{Deserialize, Serialize};
+
+/// Tag prefix recording the confidence a plan was sized at, to one decimal, so
+/// results can be grouped by it afterwards
+pub const CONFIDENCE_TAG_PREFIX: &str = "confidence:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConfidencePoint {
+    pub confidence: f64,
+    pub multiplier: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfidenceSizingConfig {
+    pub enabled: bool,
+    /// Risk multiplier at each confidence, interpolated linearly between points
+    /// and flat beyond the first and last
+    pub curve: Vec<ConfidencePoint>,
+    /// Floor and cap of the multiplier, whatever the curve says
+    pub min_multiplier: f64,
+    pub max_multiplier: f64,
+}
+
+impl Default for ConfidenceSizingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            curve: vec![
+                ConfidencePoint {
+                    confidence: 0.5,
+                    multiplier: 0.5,
+                },
+                ConfidencePoint {
+                    confidence: 0.7,
+                    multiplier: 1.0,
+                },
+                ConfidencePoint {
+                    confidence: 0.9,
+                    multiplier: 1.25,
+                },
+            ],
+            min_multiplier: 0.25,
+            max_multiplier: 1.5,
+        }
+    }
+}
+
+impl ConfidenceSizingConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_multiplier < 0.0 || self.min_multiplier > self.max_multiplier {
+            return Err(
+                "Confidence sizing needs 0 <= min_multiplier <= max_multiplier".to_string(),
+            );
+        }
+        if self
+            .curve
+            .windows(2)
+            .any(|pair| pair[0].confidence >= pair[1].confidence)
+        {
+            return Err("Confidence sizing curve must be in increasing confidence".to_string());
+        }
+        Ok(())
+    }
+
+    /// Factor on the risk per trade of a signal with `confidence`; 1 when
+    /// disabled. Confidence outside 0 to 1 is clamped into it.
+    pub fn multiplier(&self, confidence: f64) -> f64 {
+        if !self.enabled {
+            return 1.0;
+        }
+        let confidence = if confidence.is_finite() {
+            confidence.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let curve = match (self.curve.first(), self.curve.last()) {
+            (Some(first), _) if confidence <= first.confidence => first.multiplier,
+            (_, Some(last)) if confidence >= last.confidence => last.multiplier,
+            (None, _) => 1.0,
+            _ => self
+                .curve
+                .windows(2)
+                .find(|pair| confidence <= pair[1].confidence)
+                .map(|pair| {
+                    let (low, high) = (pair[0], pair[1]);
+                    let t = (confidence - low.confidence) / (high.confidence - low.confidence);
+                    low.multiplier + t * (high.multiplier - low.multiplier)
+                })
+                .unwrap_or(1.0),
+        };
+        curve.clamp(self.min_multiplier, self.max_multiplier)
+    }
+}
+
+/// `confidence:0.8` for a confidence of 0.83
+pub fn confidence_tag(confidence: f64) -> String {
+    let bucket = (confidence.clamp(0.0, 1.0) * 10.0).floor() / 10.0;
+    format!("{}{:.1}", CONFIDENCE_TAG_PREFIX, bucket)
+}
@@ -0,0 +1,416 @@
+// Market orders measured against the quote they were sent at: orders are held
+// back when the spread is too wide or the book too thin, and fills further than
+// the maximum slippage from the quote are flattened or alerted on
+
+use chrono::Utc;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use risk_types::AlertLevel;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, error, warn};
+use uuid::Uuid;
+
+use super::session_liquidity::SessionLiquidityConfig;
+use crate::alerting::{Alert, AlertGateway};
+use crate::instruments::InstrumentMetadata;
+use crate::platforms::abstraction::interfaces::ITradingPlatform;
+use crate::platforms::abstraction::models::{
+    UnifiedOrder, UnifiedOrderResponse, UnifiedOrderSide, UnifiedOrderType,
+};
+use crate::platforms::abstraction::PlatformFeature;
+
+/// Alert type raised for fills beyond their maximum slippage
+pub const SLIPPAGE_BREACH_ALERT_TYPE: &str = "slippage_breach";
+
+lazy_static! {
+    pub static ref SLIPPAGE_BREACHES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "execution_slippage_breaches_total",
+        "Market orders filled beyond their maximum slippage",
+        &["account_id", "action"]
+    )
+    .unwrap();
+}
+
+/// What is done with a fill beyond its maximum slippage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlippageBreachAction {
+    /// Close what filled straight away, and alert
+    #[default]
+    Flatten,
+    /// Keep the position and alert
+    Alert,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlippageGuardConfig {
+    /// Off, market orders are sent without a quote check and fill wherever they fill
+    pub enabled: bool,
+    /// Furthest a market order may fill from the quote it was sent at
+    pub max_slippage_pips: f64,
+    /// Widest spread an order is sent into; unset sends at any spread
+    pub max_spread_pips: Option<f64>,
+    /// Applied where the platform does not enforce the maximum itself
+    pub on_breach: SlippageBreachAction,
+    /// Best levels of the book a market order must fit into within its maximum
+    /// slippage, on platforms with market depth; 0 checks the quote only
+    pub depth_levels: usize,
+    /// Send what those levels hold when the order does not fit, instead of
+    /// holding it back
+    pub size_to_depth: bool,
+}
+
+impl Default for SlippageGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_slippage_pips: 2.0,
+            max_spread_pips: None,
+            on_breach: SlippageBreachAction::Flatten,
+            depth_levels: 0,
+            size_to_depth: false,
+        }
+    }
+}
+
+impl SlippageGuardConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_slippage_pips <= 0.0 {
+            return Err("Slippage guard max_slippage_pips must be above 0".to_string());
+        }
+        if self.max_spread_pips.is_some_and(|pips| pips <= 0.0) {
+            return Err("Slippage guard max_spread_pips must be above 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Slippage of a fill at `fill_price` against `quote`, positive when it filled worse
+pub fn adverse_slippage(side: &UnifiedOrderSide, quote: Decimal, fill_price: Decimal) -> Decimal {
+    match side {
+        UnifiedOrderSide::Buy => fill_price - quote,
+        UnifiedOrderSide::Sell => quote - fill_price,
+    }
+}
+
+/// Slippage of one account's guarded market orders, in pips
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlippageStats {
+    pub account_id: String,
+    pub fills: u64,
+    /// Sum of adverse slippage; fills better than the quote count negative
+    pub total_slippage_pips: f64,
+    pub worst_slippage_pips: f64,
+    /// Fills beyond the maximum slippage
+    pub breaches: u64,
+    /// Breaches closed straight away
+    pub flattened: u64,
+}
+
+impl SlippageStats {
+    pub fn average_slippage_pips(&self) -> Option<f64> {
+        (self.fills > 0).then(|| self.total_slippage_pips / self.fills as f64)
+    }
+}
+
+/// The quote an order was sent at, kept to measure its fill against
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SubmissionQuote {
+    pub price: Decimal,
+    pub max_slippage: Decimal,
+    /// Whether the platform refuses fills beyond the maximum itself
+    pub enforced: bool,
+}
+
+/// A guarded fill, measured
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlippageCheck {
+    pub slippage: Decimal,
+    pub breached: bool,
+    /// Why the fill was flattened, when it was
+    pub flattened: Option<String>,
+}
+
+/// Applies `SlippageGuardConfig` to market orders and keeps per-account slippage
+/// statistics. Cheap to clone; clones share their statistics.
+#[derive(Clone, Default)]
+pub struct SlippageGuard {
+    config: SlippageGuardConfig,
+    session_liquidity: SessionLiquidityConfig,
+    gateway: Option<Arc<AlertGateway>>,
+    stats: Arc<DashMap<String, SlippageStats>>,
+}
+
+impl SlippageGuard {
+    pub fn new(config: SlippageGuardConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Raise breaches to `gateway`
+    pub fn with_alert_gateway(mut self, gateway: Arc<AlertGateway>) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
+    /// Allow more slippage in thin sessions, per `config`
+    pub fn with_session_liquidity(mut self, config: SessionLiquidityConfig) -> Self {
+        self.session_liquidity = config;
+        self
+    }
+
+    pub fn config(&self) -> &SlippageGuardConfig {
+        &self.config
+    }
+
+    /// Maximum slippage of orders sent now, scaled to the session's liquidity
+    pub fn max_slippage_pips(&self) -> f64 {
+        self.config.max_slippage_pips * self.session_liquidity.scale_at(Utc::now())
+    }
+
+    /// Take the quote `order` is sent at and set its maximum slippage. Errs when
+    /// the spread is wider than allowed or the book cannot fill the order within
+    /// the maximum; no quote leaves the order unguarded.
+    pub async fn prepare(
+        &self,
+        platform: &(dyn ITradingPlatform + Send + Sync),
+        order: &mut UnifiedOrder,
+    ) -> Result<Option<SubmissionQuote>, String> {
+        if !self.config.enabled || order.order_type != UnifiedOrderType::Market {
+            return Ok(None);
+        }
+        let instrument = InstrumentMetadata::conventional(&order.symbol);
+        let max_slippage_pips = self.max_slippage_pips();
+        let max_slippage =
+            instrument.pips_to_price(Decimal::from_f64(max_slippage_pips).unwrap_or_default());
+        order.max_slippage = Some(max_slippage);
+
+        let quote = match platform.get_market_data(&order.symbol).await {
+            Ok(quote) => quote,
+            Err(e) => {
+                warn!(
+                    "No quote for {}, order {} fills unguarded: {}",
+                    order.symbol, order.client_order_id, e
+                );
+                return Ok(None);
+            }
+        };
+        if let Some(max_spread_pips) = self.config.max_spread_pips {
+            let spread_pips = instrument
+                .price_to_pips(quote.ask - quote.bid)
+                .to_f64()
+                .unwrap_or(0.0);
+            if spread_pips > max_spread_pips {
+                return Err(format!(
+                    "Spread on {} is {:.1} pips, wider than the {:.1} allowed",
+                    order.symbol, spread_pips, max_spread_pips
+                ));
+            }
+        }
+        let price = match order.side {
+            UnifiedOrderSide::Buy => quote.ask,
+            UnifiedOrderSide::Sell => quote.bid,
+        };
+        if self.config.depth_levels > 0 && platform.supports_feature(PlatformFeature::MarketDepth) {
+            self.fit_to_depth(platform, order, price, max_slippage)
+                .await?;
+        }
+        Ok(Some(SubmissionQuote {
+            price,
+            max_slippage,
+            enforced: platform
+                .capabilities()
+                .features
+                .contains(&PlatformFeature::MaxSlippage),
+        }))
+    }
+
+    /// Check `order` against the best `depth_levels` of the book, sizing it down to
+    /// what they hold within `max_slippage` of `price` when `size_to_depth` is set
+    async fn fit_to_depth(
+        &self,
+        platform: &(dyn ITradingPlatform + Send + Sync),
+        order: &mut UnifiedOrder,
+        price: Decimal,
+        max_slippage: Decimal,
+    ) -> Result<(), String> {
+        let levels = self.config.depth_levels;
+        let book = match platform.get_order_book(&order.symbol, levels).await {
+            Ok(book) => book,
+            Err(e) => {
+                warn!(
+                    "No order book for {}, order {} is checked against the quote only: {}",
+                    order.symbol, order.client_order_id, e
+                );
+                return Ok(());
+            }
+        };
+        let limit = match order.side {
+            UnifiedOrderSide::Buy => price + max_slippage,
+            UnifiedOrderSide::Sell => price - max_slippage,
+        };
+        let sweep = book.sweep(&order.side, order.quantity, levels, Some(limit));
+        if sweep.filled >= order.quantity {
+            return Ok(());
+        }
+        if !self.config.size_to_depth || sweep.filled.is_zero() {
+            return Err(format!(
+                "Best {} levels of {} hold {} within {:.1} pips of the quote, short of the {} ordered",
+                levels,
+                order.symbol,
+                sweep.filled,
+                InstrumentMetadata::conventional(&order.symbol)
+                    .price_to_pips(max_slippage)
+                    .to_f64()
+                    .unwrap_or(0.0),
+                order.quantity
+            ));
+        }
+
+        let requested = order.quantity;
+        order.quantity = sweep.filled;
+        platform
+            .capabilities()
+            .normalize_order_quantity(order)
+            .map_err(|e| e.to_string())?;
+        debug!(
+            "Order {} on {} sized from {} to {} to fit the best {} levels",
+            order.client_order_id, order.symbol, requested, order.quantity, levels
+        );
+        Ok(())
+    }
+
+    /// Measure the fill of `order` against `quote` and act on a breach. A platform
+    /// enforcing the maximum judges against its own quote when resending after a
+    /// requote, so such fills are checked here too.
+    pub async fn check(
+        &self,
+        platform: &(dyn ITradingPlatform + Send + Sync),
+        account_id: &str,
+        order: &UnifiedOrder,
+        placed: &UnifiedOrderResponse,
+        quote: SubmissionQuote,
+        requoted: bool,
+    ) -> Option<SlippageCheck> {
+        let fill_price = placed.average_fill_price.or(placed.price)?;
+        let slippage = adverse_slippage(&order.side, quote.price, fill_price);
+        let instrument = InstrumentMetadata::conventional(&order.symbol);
+        let slippage_pips = instrument.price_to_pips(slippage).to_f64().unwrap_or(0.0);
+        let max_slippage_pips = instrument
+            .price_to_pips(quote.max_slippage)
+            .to_f64()
+            .unwrap_or(0.0);
+        let breached = slippage > quote.max_slippage && (!quote.enforced || requoted);
+
+        let flattened = if breached && self.config.on_breach == SlippageBreachAction::Flatten {
+            let quantity = if placed.filled_quantity.is_zero() {
+                order.quantity
+            } else {
+                placed.filled_quantity
+            };
+            Some(
+                match platform.close_position(&order.symbol, Some(quantity)).await {
+                    Ok(_) => format!(
+                        "filled {:.1} pips from the {} quote, beyond the {:.1} allowed; flattened",
+                        slippage_pips, quote.price, max_slippage_pips
+                    ),
+                    Err(e) => {
+                        error!(
+                            "Failed to flatten order {} for {} after a slippage breach: {}",
+                            placed.platform_order_id, account_id, e
+                        );
+                        format!(
+                        "filled {:.1} pips from the {} quote, beyond the {:.1} allowed; flattening failed: {}",
+                        slippage_pips, quote.price, max_slippage_pips, e
+                    )
+                    }
+                },
+            )
+        } else {
+            None
+        };
+
+        {
+            let mut stats =
+                self.stats
+                    .entry(account_id.to_string())
+                    .or_insert_with(|| SlippageStats {
+                        account_id: account_id.to_string(),
+                        ..SlippageStats::default()
+                    });
+            stats.fills += 1;
+            stats.total_slippage_pips += slippage_pips;
+            if stats.fills == 1 || slippage_pips > stats.worst_slippage_pips {
+                stats.worst_slippage_pips = slippage_pips;
+            }
+            if breached {
+                stats.breaches += 1;
+            }
+            if flattened.is_some() {
+                stats.flattened += 1;
+            }
+        }
+
+        if breached {
+            let action = if flattened.is_some() {
+                "flatten"
+            } else {
+                "alert"
+            };
+            SLIPPAGE_BREACHES_TOTAL
+                .with_label_values(&[account_id, action])
+                .inc();
+            warn!(
+                "Order {} for {} on {} filled at {}, {:.1} pips from the {} quote",
+                placed.platform_order_id,
+                account_id,
+                order.symbol,
+                fill_price,
+                slippage_pips,
+                quote.price
+            );
+            if let Some(gateway) = &self.gateway {
+                gateway
+                    .submit(Alert {
+                        alert_type: SLIPPAGE_BREACH_ALERT_TYPE.to_string(),
+                        key: placed.platform_order_id.clone(),
+                        severity: AlertLevel::Warning,
+                        account_id: Uuid::parse_str(account_id).ok(),
+                        message: format!(
+                            "{:?} {} {} on account {}: {}",
+                            order.side,
+                            order.quantity,
+                            order.symbol,
+                            account_id,
+                            flattened.clone().unwrap_or_else(|| format!(
+                                "filled {:.1} pips from the {} quote, beyond the {:.1} allowed",
+                                slippage_pips, quote.price, max_slippage_pips
+                            ))
+                        ),
+                        raised_at: Utc::now(),
+                    })
+                    .await;
+            }
+        }
+
+        Some(SlippageCheck {
+            slippage,
+            breached,
+            flattened,
+        })
+    }
+
+    /// Slippage per account, by account
+    pub fn stats(&self) -> Vec<SlippageStats> {
+        let mut stats: Vec<SlippageStats> = self.stats.iter().map(|s| s.value().clone()).collect();
+        stats.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+        stats
+    }
+}
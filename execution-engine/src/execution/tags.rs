@@ -0,0 +1,195 @@
+// Tags carried from a signal's order to the positions, exits and results that follow from it
+
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::platforms::abstraction::models::UnifiedPosition;
+
+/// Signal metadata keys that become `key:value` tags
+pub const TAG_KEYS: [&str; 4] = ["strategy", "agent", "session", "experiment_id"];
+/// Signal metadata key holding free-form labels, comma separated
+pub const LABELS_KEY: &str = "tags";
+
+/// Tags from a signal's metadata, e.g. `strategy:breakout` or a bare label
+pub fn tags_from_metadata(metadata: &HashMap<String, String>) -> Vec<String> {
+    let mut tags: Vec<String> = TAG_KEYS
+        .iter()
+        .filter_map(|key| {
+            let value = metadata.get(*key)?.trim();
+            (!value.is_empty()).then(|| format!("{}:{}", key, value))
+        })
+        .collect();
+    for label in metadata
+        .get(LABELS_KEY)
+        .into_iter()
+        .flat_map(|l| split_tags(l))
+    {
+        if !tags.contains(&label) {
+            tags.push(label);
+        }
+    }
+    tags
+}
+
+fn split_tags(list: &str) -> impl Iterator<Item = String> + '_ {
+    list.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+}
+
+/// Tags an item must all carry to match a query; an empty filter matches everything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilter(Vec<String>);
+
+impl TagFilter {
+    pub fn new(tags: Vec<String>) -> Self {
+        Self(tags)
+    }
+
+    /// Parse a comma separated list as used in query strings
+    pub fn parse(list: Option<&str>) -> Self {
+        Self(list.map(|l| split_tags(l).collect()).unwrap_or_default())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.0
+    }
+
+    pub fn matches(&self, tags: &[String]) -> bool {
+        self.0.iter().all(|required| tags.contains(required))
+    }
+}
+
+/// An open position with the tags of the order that opened it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaggedPosition {
+    #[serde(flatten)]
+    pub position: UnifiedPosition,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct PositionTags {
+    account_id: String,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct PendingTags {
+    symbol: String,
+    tags: Vec<String>,
+    sent_at: DateTime<Utc>,
+}
+
+impl PendingTags {
+    /// Unclaimed tags are dropped after this long
+    fn ttl() -> Duration {
+        Duration::hours(1)
+    }
+
+    /// Allowance for the platform clock running behind ours
+    fn clock_tolerance() -> Duration {
+        Duration::seconds(5)
+    }
+
+    /// First position opened after the order was sent, else the latest within the tolerance
+    fn pick<'a>(&self, candidates: &[&'a UnifiedPosition]) -> Option<&'a UnifiedPosition> {
+        let on_symbol = candidates.iter().filter(|p| p.symbol == self.symbol);
+        on_symbol
+            .clone()
+            .filter(|p| p.opened_at >= self.sent_at)
+            .min_by_key(|p| p.opened_at)
+            .or_else(|| {
+                on_symbol
+                    .filter(|p| p.opened_at >= self.sent_at - Self::clock_tolerance())
+                    .max_by_key(|p| p.opened_at)
+            })
+            .copied()
+    }
+}
+
+/// Tags of open positions keyed by platform position id. Platforms do not keep order tags,
+/// so tags are held here when an order is sent and claimed by the position it opens.
+#[derive(Debug, Default)]
+pub struct TagRegistry {
+    positions: DashMap<String, PositionTags>,
+    pending: DashMap<String, Vec<PendingTags>>,
+}
+
+impl TagRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hold `tags` for the next position opened on `symbol` in `account_id`
+    pub fn expect(
+        &self,
+        account_id: &str,
+        symbol: &str,
+        tags: Vec<String>,
+        sent_at: DateTime<Utc>,
+    ) {
+        if tags.is_empty() {
+            return;
+        }
+        let mut pending = self.pending.entry(account_id.to_string()).or_default();
+        pending.retain(|p| sent_at - p.sent_at <= PendingTags::ttl());
+        pending.push(PendingTags {
+            symbol: symbol.to_string(),
+            tags,
+            sent_at,
+        });
+    }
+
+    /// Hand pending tags, oldest first, to the untagged positions their orders opened,
+    /// and forget positions of the account that are no longer open
+    pub fn resolve(&self, account_id: &str, positions: &[UnifiedPosition]) {
+        self.positions.retain(|position_id, entry| {
+            entry.account_id != account_id
+                || positions.iter().any(|p| p.position_id == *position_id)
+        });
+
+        let Some(mut pending) = self.pending.get_mut(account_id) else {
+            return;
+        };
+        let mut candidates: Vec<&UnifiedPosition> = positions
+            .iter()
+            .filter(|p| !self.positions.contains_key(&p.position_id))
+            .collect();
+
+        let now = Utc::now();
+        pending.retain(|p| now - p.sent_at <= PendingTags::ttl());
+        pending.retain(|p| match p.pick(&candidates) {
+            Some(position) => {
+                self.set(account_id, &position.position_id, p.tags.clone());
+                candidates.retain(|c| c.position_id != position.position_id);
+                false
+            }
+            None => true,
+        });
+    }
+
+    pub fn set(&self, account_id: &str, position_id: &str, tags: Vec<String>) {
+        self.positions.insert(
+            position_id.to_string(),
+            PositionTags {
+                account_id: account_id.to_string(),
+                tags,
+            },
+        );
+    }
+
+    pub fn tags_for(&self, position_id: &str) -> Vec<String> {
+        self.positions
+            .get(position_id)
+            .map(|entry| entry.tags.clone())
+            .unwrap_or_default()
+    }
+}
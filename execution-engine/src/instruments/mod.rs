@@ -0,0 +1,172 @@
+use dashmap::DashMap;
+use rust_decimal::{Decimal, RoundingStrategy};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::platforms::abstraction::models::Symbol;
+
+/// Price precision and stop rules of one instrument
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstrumentMetadata {
+    pub symbol: String,
+    /// Price distance of one pip, e.g. 0.0001 for EURUSD and 0.01 for USDJPY
+    pub pip_size: Decimal,
+    /// Decimal places the platform quotes prices to
+    pub price_precision: u32,
+    /// Closest the platform lets a new stop sit to the market, as a price distance
+    #[serde(default)]
+    pub min_stop_distance: Decimal,
+    /// Distance from the market inside which the platform refuses to modify a stop
+    #[serde(default)]
+    pub freeze_distance: Decimal,
+}
+
+impl InstrumentMetadata {
+    /// Conventional FX precision for `symbol`, used until the platform reports its own
+    pub fn conventional(symbol: &str) -> Self {
+        let symbol_upper = symbol.to_uppercase();
+        let (pip_size, price_precision) = if symbol_upper.starts_with("XAU") {
+            (dec!(0.1), 2)
+        } else if symbol_upper.starts_with("XAG") || symbol_upper.contains("JPY") {
+            (dec!(0.01), 3)
+        } else {
+            (dec!(0.0001), 5)
+        };
+        Self {
+            symbol: symbol.to_string(),
+            pip_size,
+            price_precision,
+            min_stop_distance: Decimal::ZERO,
+            freeze_distance: Decimal::ZERO,
+        }
+    }
+
+    /// Metadata from a platform's symbol list. Platforms quoting fractional pips
+    /// (an odd number of decimals) have a pip ten ticks wide.
+    pub fn from_symbol(symbol: &Symbol) -> Self {
+        let tick_size = symbol.tick_size.normalize();
+        let mut metadata = if tick_size <= Decimal::ZERO {
+            Self::conventional(&symbol.symbol)
+        } else {
+            let price_precision = tick_size.scale();
+            let pip_size = if price_precision % 2 == 1 {
+                tick_size * Decimal::TEN
+            } else {
+                tick_size
+            };
+            Self {
+                symbol: symbol.symbol.clone(),
+                pip_size,
+                price_precision,
+                min_stop_distance: Decimal::ZERO,
+                freeze_distance: Decimal::ZERO,
+            }
+        };
+        metadata.min_stop_distance = symbol.stops_level.unwrap_or_default();
+        metadata.freeze_distance = symbol.freeze_level.unwrap_or_default();
+        metadata
+    }
+
+    pub fn pips_to_price(&self, pips: Decimal) -> Decimal {
+        pips * self.pip_size
+    }
+
+    /// Number of pips in a price distance, to a tenth of a pip
+    pub fn price_to_pips(&self, distance: Decimal) -> Decimal {
+        (distance / self.pip_size).round_dp(1)
+    }
+
+    /// Round a price to the precision the platform accepts
+    pub fn round_price(&self, price: Decimal) -> Decimal {
+        price.round_dp(self.price_precision)
+    }
+
+    /// The stop nearest to `stop` the platform accepts on a position that closes at
+    /// `market` (the bid for longs, the ask for shorts), pulled back to the minimum
+    /// stop distance and rounded away from the market. None while the `current`
+    /// stop is inside the freeze distance and cannot be moved at all.
+    pub fn allowed_stop(
+        &self,
+        long: bool,
+        market: Decimal,
+        current: Option<Decimal>,
+        stop: Decimal,
+    ) -> Option<Decimal> {
+        let frozen = current.is_some_and(|current| {
+            self.freeze_distance > Decimal::ZERO && (market - current).abs() < self.freeze_distance
+        });
+        if frozen {
+            return None;
+        }
+        let stop = self.round_price(stop);
+        Some(if long {
+            stop.min(
+                (market - self.min_stop_distance).round_dp_with_strategy(
+                    self.price_precision,
+                    RoundingStrategy::ToNegativeInfinity,
+                ),
+            )
+        } else {
+            stop.max(
+                (market + self.min_stop_distance).round_dp_with_strategy(
+                    self.price_precision,
+                    RoundingStrategy::ToPositiveInfinity,
+                ),
+            )
+        })
+    }
+}
+
+/// Pip size, price precision and stop rules per symbol, falling back to conventional FX values
+/// for symbols no platform has reported
+#[derive(Debug, Default)]
+pub struct InstrumentMetadataService {
+    instruments: DashMap<String, InstrumentMetadata>,
+}
+
+impl InstrumentMetadataService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, metadata: InstrumentMetadata) {
+        self.instruments.insert(metadata.symbol.clone(), metadata);
+    }
+
+    /// Register every symbol a platform lists
+    pub fn register_symbols(&self, symbols: &[Symbol]) {
+        for symbol in symbols {
+            self.register(InstrumentMetadata::from_symbol(symbol));
+        }
+    }
+
+    pub fn get(&self, symbol: &str) -> InstrumentMetadata {
+        self.instruments
+            .get(symbol)
+            .map(|m| m.clone())
+            .unwrap_or_else(|| InstrumentMetadata::conventional(symbol))
+    }
+
+    pub fn pips_to_price(&self, symbol: &str, pips: Decimal) -> Decimal {
+        self.get(symbol).pips_to_price(pips)
+    }
+
+    pub fn price_to_pips(&self, symbol: &str, distance: Decimal) -> Decimal {
+        self.get(symbol).price_to_pips(distance)
+    }
+
+    pub fn round_price(&self, symbol: &str, price: Decimal) -> Decimal {
+        self.get(symbol).round_price(price)
+    }
+
+    pub fn allowed_stop(
+        &self,
+        symbol: &str,
+        long: bool,
+        market: Decimal,
+        current: Option<Decimal>,
+        stop: Decimal,
+    ) -> Option<Decimal> {
+        self.get(symbol).allowed_stop(long, market, current, stop)
+    }
+}
@@ -0,0 +1,678 @@
+// Trade journal: entries paired with their exits, for post-trade analysis
+
+pub mod store;
+
+pub use store::{FileJournalStore, JournalStore};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::execution::exit_management::ExitModificationType;
+use crate::execution::tags::{TagFilter, TagRegistry};
+use crate::platforms::abstraction::events::{
+    EventData, EventType, PlatformEvent, PositionCloseEventData,
+};
+use crate::platforms::abstraction::models::{UnifiedPosition, UnifiedPositionSide};
+
+/// Why (part of) a trade was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExitReason {
+    TrailingStop,
+    BreakEven,
+    PartialProfit,
+    TimeExit,
+    NewsProtection,
+    StopLoss,
+    TakeProfit,
+    Manual,
+}
+
+impl From<ExitModificationType> for ExitReason {
+    fn from(modification: ExitModificationType) -> Self {
+        match modification {
+            ExitModificationType::TrailingStop => ExitReason::TrailingStop,
+            ExitModificationType::BreakEven => ExitReason::BreakEven,
+            ExitModificationType::PartialProfit => ExitReason::PartialProfit,
+            ExitModificationType::TimeExit => ExitReason::TimeExit,
+            ExitModificationType::NewsProtection => ExitReason::NewsProtection,
+        }
+    }
+}
+
+impl ExitReason {
+    /// Whether an exit manager, rather than the initial levels or a person, caused the exit
+    pub fn is_exit_management(&self) -> bool {
+        matches!(
+            self,
+            ExitReason::TrailingStop
+                | ExitReason::BreakEven
+                | ExitReason::PartialProfit
+                | ExitReason::TimeExit
+                | ExitReason::NewsProtection
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TradeStatus {
+    Open,
+    Closed,
+}
+
+/// An order that was sent for a signal and is expected to open a position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingEntry {
+    pub account_id: String,
+    pub symbol: String,
+    pub signal_id: String,
+    pub order_id: Option<String>,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeEntry {
+    pub account_id: String,
+    pub position_id: String,
+    pub symbol: String,
+    pub side: UnifiedPositionSide,
+    pub quantity: Decimal,
+    pub entry_price: Decimal,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    pub signal_id: Option<String>,
+    pub order_id: Option<String>,
+    pub opened_at: DateTime<Utc>,
+    /// Tags of the order that opened the position
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeExit {
+    pub closing_order_id: String,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    pub realized_pnl: Decimal,
+    pub commission: Decimal,
+    pub reason: ExitReason,
+    pub closed_at: DateTime<Utc>,
+}
+
+/// A later fill that added to the position of an open trade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeAddition {
+    pub quantity: Decimal,
+    pub price: Decimal,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    /// Signal whose order added to the position, when known
+    pub signal_id: Option<String>,
+    pub added_at: DateTime<Utc>,
+}
+
+/// One position from open to close, with every addition and partial exit along the way
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeRecord {
+    pub id: String,
+    pub status: TradeStatus,
+    pub entry: TradeEntry,
+    /// Scale-ins after the entry, in the order they filled
+    #[serde(default)]
+    pub additions: Vec<TradeAddition>,
+    pub exits: Vec<TradeExit>,
+    pub realized_pnl: Decimal,
+    pub closed_quantity: Decimal,
+    pub average_exit_price: Option<Decimal>,
+    /// Largest move against the entry price while open, in price units
+    pub max_adverse_excursion: Decimal,
+    /// Largest move in favour of the entry price while open, in price units
+    pub max_favorable_excursion: Decimal,
+    /// Realized P&L divided by the risk taken at the initial stop
+    pub r_multiple: Option<Decimal>,
+    pub holding_time_secs: Option<i64>,
+    /// Reason for the exit that closed the position
+    pub exit_reason: Option<ExitReason>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TradeRecord {
+    pub fn open(entry: TradeEntry) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            status: TradeStatus::Open,
+            updated_at: entry.opened_at,
+            entry,
+            additions: Vec::new(),
+            exits: Vec::new(),
+            realized_pnl: Decimal::ZERO,
+            closed_quantity: Decimal::ZERO,
+            average_exit_price: None,
+            max_adverse_excursion: Decimal::ZERO,
+            max_favorable_excursion: Decimal::ZERO,
+            r_multiple: None,
+            holding_time_secs: None,
+            exit_reason: None,
+            closed_at: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.status == TradeStatus::Open
+    }
+
+    /// Quantity of the entry and every addition
+    pub fn total_quantity(&self) -> Decimal {
+        self.entry.quantity + self.additions.iter().map(|a| a.quantity).sum::<Decimal>()
+    }
+
+    pub fn remaining_quantity(&self) -> Decimal {
+        (self.total_quantity() - self.closed_quantity).max(Decimal::ZERO)
+    }
+
+    /// Entry price weighted over the entry and every addition
+    pub fn average_entry_price(&self) -> Decimal {
+        let total = self.total_quantity();
+        if total.is_zero() {
+            return self.entry.entry_price;
+        }
+        let notional = self.entry.entry_price * self.entry.quantity
+            + self
+                .additions
+                .iter()
+                .map(|a| a.price * a.quantity)
+                .sum::<Decimal>();
+        notional / total
+    }
+
+    /// Widen MAE/MFE with an observed price
+    pub fn observe_price(&mut self, price: Decimal) {
+        let favorable = match self.entry.side {
+            UnifiedPositionSide::Long => price - self.entry.entry_price,
+            UnifiedPositionSide::Short => self.entry.entry_price - price,
+        };
+        if favorable > self.max_favorable_excursion {
+            self.max_favorable_excursion = favorable;
+        }
+        if -favorable > self.max_adverse_excursion {
+            self.max_adverse_excursion = -favorable;
+        }
+    }
+
+    /// Money at risk between entry and the initial stop, if a stop was set. Each
+    /// addition adds the risk to its own stop, or to the entry's.
+    pub fn initial_risk(&self) -> Option<Decimal> {
+        let stop = self.entry.stop_loss?;
+        let risk = (self.entry.entry_price - stop).abs() * self.entry.quantity
+            + self
+                .additions
+                .iter()
+                .map(|a| (a.price - a.stop_loss.unwrap_or(stop)).abs() * a.quantity)
+                .sum::<Decimal>();
+        (risk > Decimal::ZERO).then_some(risk)
+    }
+
+    fn apply_exit(&mut self, exit: TradeExit, remaining_quantity: Decimal) {
+        self.observe_price(exit.price);
+
+        let previous_notional = self
+            .average_exit_price
+            .map(|price| price * self.closed_quantity)
+            .unwrap_or(Decimal::ZERO);
+        self.closed_quantity += exit.quantity;
+        self.realized_pnl += exit.realized_pnl;
+        if self.closed_quantity > Decimal::ZERO {
+            self.average_exit_price =
+                Some((previous_notional + exit.price * exit.quantity) / self.closed_quantity);
+        }
+        self.r_multiple = self.initial_risk().map(|risk| self.realized_pnl / risk);
+        self.updated_at = exit.closed_at;
+
+        if remaining_quantity <= Decimal::ZERO {
+            self.status = TradeStatus::Closed;
+            self.exit_reason = Some(exit.reason);
+            self.closed_at = Some(exit.closed_at);
+            self.holding_time_secs = Some((exit.closed_at - self.entry.opened_at).num_seconds());
+        }
+        self.exits.push(exit);
+    }
+
+    /// Stop loss or take profit when the close price is at or through one of them
+    fn infer_level_exit(&self, close_price: Decimal) -> Option<ExitReason> {
+        let (hit_stop, hit_target) = match self.entry.side {
+            UnifiedPositionSide::Long => (
+                self.entry.stop_loss.map(|sl| close_price <= sl),
+                self.entry.take_profit.map(|tp| close_price >= tp),
+            ),
+            UnifiedPositionSide::Short => (
+                self.entry.stop_loss.map(|sl| close_price >= sl),
+                self.entry.take_profit.map(|tp| close_price <= tp),
+            ),
+        };
+
+        if hit_target == Some(true) {
+            Some(ExitReason::TakeProfit)
+        } else if hit_stop == Some(true) {
+            Some(ExitReason::StopLoss)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TradeQuery {
+    pub account_id: Option<String>,
+    pub symbol: Option<String>,
+    pub status: Option<TradeStatus>,
+    pub exit_reason: Option<ExitReason>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Comma separated tags the trade must all carry
+    pub tags: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl TradeQuery {
+    fn matches(&self, record: &TradeRecord) -> bool {
+        self.account_id
+            .as_ref()
+            .map_or(true, |a| *a == record.entry.account_id)
+            && self
+                .symbol
+                .as_ref()
+                .map_or(true, |s| *s == record.entry.symbol)
+            && self.status.map_or(true, |s| s == record.status)
+            && self
+                .exit_reason
+                .map_or(true, |r| record.exit_reason == Some(r))
+            && self
+                .from
+                .map_or(true, |from| record.entry.opened_at >= from)
+            && self.to.map_or(true, |to| record.entry.opened_at <= to)
+            && TagFilter::parse(self.tags.as_deref()).matches(&record.entry.tags)
+    }
+}
+
+/// Pairs entries with their exits. Open trades are keyed by platform position id.
+#[derive(Debug)]
+pub struct TradeJournal {
+    store: Option<Arc<dyn JournalStore>>,
+    trades: RwLock<HashMap<String, TradeRecord>>,
+    open_positions: RwLock<HashMap<String, String>>,
+    pending_entries: RwLock<HashMap<(String, String), VecDeque<PendingEntry>>>,
+    last_exit_actions: RwLock<HashMap<String, ExitModificationType>>,
+    tag_registry: Option<Arc<TagRegistry>>,
+}
+
+impl TradeJournal {
+    pub fn new() -> Self {
+        Self {
+            store: None,
+            trades: RwLock::new(HashMap::new()),
+            open_positions: RwLock::new(HashMap::new()),
+            pending_entries: RwLock::new(HashMap::new()),
+            last_exit_actions: RwLock::new(HashMap::new()),
+            tag_registry: None,
+        }
+    }
+
+    pub fn with_store(mut self, store: Arc<dyn JournalStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Copy the tags of the order that opened a position onto its trade
+    pub fn with_tag_registry(mut self, tag_registry: Arc<TagRegistry>) -> Self {
+        self.tag_registry = Some(tag_registry);
+        self
+    }
+
+    fn tags_for(&self, position_id: &str) -> Vec<String> {
+        self.tag_registry
+            .as_ref()
+            .map(|registry| registry.tags_for(position_id))
+            .unwrap_or_default()
+    }
+
+    /// Reload persisted trades, including the open ones still awaiting exits
+    pub async fn load(&self) -> Result<usize> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+
+        let records = store.load().await?;
+        let mut trades = self.trades.write().await;
+        let mut open_positions = self.open_positions.write().await;
+        for record in records {
+            if record.is_open() {
+                open_positions.insert(record.entry.position_id.clone(), record.id.clone());
+            }
+            trades.insert(record.id.clone(), record);
+        }
+
+        info!("Loaded {} trades from the journal", trades.len());
+        Ok(trades.len())
+    }
+
+    async fn persist(&self, record: &TradeRecord) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.save(record).await {
+                warn!("Failed to persist trade {}: {}", record.id, e);
+            }
+        }
+    }
+
+    /// Remember which signal an order belongs to, so the position it opens is linked to it
+    pub async fn expect_entry(&self, pending: PendingEntry) {
+        self.pending_entries
+            .write()
+            .await
+            .entry((pending.account_id.clone(), pending.symbol.clone()))
+            .or_default()
+            .push_back(pending);
+    }
+
+    pub async fn handle_platform_event(&self, event: &PlatformEvent) {
+        match &event.data {
+            EventData::Position(data) if event.event_type == EventType::PositionOpened => {
+                self.record_open(&event.account_id, &data.position).await;
+            }
+            EventData::Position(data) => {
+                self.observe_price(&data.position.position_id, data.position.current_price)
+                    .await;
+            }
+            EventData::PositionClose(close) => {
+                self.record_close(&event.account_id, close).await;
+            }
+            _ => {}
+        }
+    }
+
+    /// Open a trade for a new position. Returns the trade id.
+    pub async fn record_open(&self, account_id: &str, position: &UnifiedPosition) -> String {
+        if let Some(id) = self.open_positions.read().await.get(&position.position_id) {
+            return id.clone();
+        }
+
+        let pending = self
+            .pending_entries
+            .write()
+            .await
+            .get_mut(&(account_id.to_string(), position.symbol.clone()))
+            .and_then(|queue| queue.pop_front());
+
+        let entry = TradeEntry {
+            account_id: account_id.to_string(),
+            position_id: position.position_id.clone(),
+            symbol: position.symbol.clone(),
+            side: position.side.clone(),
+            quantity: position.quantity,
+            entry_price: position.entry_price,
+            stop_loss: position
+                .stop_loss
+                .or(pending.as_ref().and_then(|p| p.stop_loss)),
+            take_profit: position
+                .take_profit
+                .or(pending.as_ref().and_then(|p| p.take_profit)),
+            signal_id: pending.as_ref().map(|p| p.signal_id.clone()),
+            order_id: pending.and_then(|p| p.order_id),
+            opened_at: position.opened_at,
+            tags: self.tags_for(&position.position_id),
+        };
+
+        let mut record = TradeRecord::open(entry);
+        record.observe_price(position.current_price);
+        let id = record.id.clone();
+
+        debug!(
+            "Journal opened trade {} for position {}",
+            id, position.position_id
+        );
+        self.persist(&record).await;
+        self.open_positions
+            .write()
+            .await
+            .insert(position.position_id.clone(), id.clone());
+        self.trades.write().await.insert(id.clone(), record);
+        id
+    }
+
+    pub async fn observe_price(&self, position_id: &str, price: Decimal) {
+        let Some(id) = self.open_positions.read().await.get(position_id).cloned() else {
+            return;
+        };
+        if let Some(record) = self.trades.write().await.get_mut(&id) {
+            record.observe_price(price);
+        }
+    }
+
+    /// Widen a trade's MAE/MFE with excursions tracked elsewhere, e.g. by exit management
+    pub async fn record_excursion(
+        &self,
+        position_id: &str,
+        max_adverse_excursion: Decimal,
+        max_favorable_excursion: Decimal,
+    ) {
+        let Some(id) = self.open_positions.read().await.get(position_id).cloned() else {
+            return;
+        };
+        if let Some(record) = self.trades.write().await.get_mut(&id) {
+            record.max_adverse_excursion = record.max_adverse_excursion.max(max_adverse_excursion);
+            record.max_favorable_excursion =
+                record.max_favorable_excursion.max(max_favorable_excursion);
+        }
+    }
+
+    /// Add `tags` the open trade of a position does not carry yet. Returns the trade id,
+    /// or None if the position has no open trade.
+    pub async fn add_tags(&self, position_id: &str, tags: &[String]) -> Option<String> {
+        let id = self.open_positions.read().await.get(position_id).cloned()?;
+        let record = {
+            let mut trades = self.trades.write().await;
+            let record = trades.get_mut(&id)?;
+            for tag in tags {
+                if !record.entry.tags.contains(tag) {
+                    record.entry.tags.push(tag.clone());
+                }
+            }
+            record.updated_at = Utc::now();
+            record.clone()
+        };
+        self.persist(&record).await;
+        Some(id)
+    }
+
+    /// Attribute a fill that added to a position to its open trade. Returns the
+    /// trade id, or None if the position has no open trade.
+    pub async fn record_addition(
+        &self,
+        position_id: &str,
+        addition: TradeAddition,
+    ) -> Option<String> {
+        let id = self.open_positions.read().await.get(position_id).cloned()?;
+        let record = {
+            let mut trades = self.trades.write().await;
+            let record = trades.get_mut(&id)?;
+            debug!(
+                "Journal trade {} added {} at {}",
+                id, addition.quantity, addition.price
+            );
+            record.updated_at = addition.added_at;
+            record.additions.push(addition);
+            record.clone()
+        };
+        self.persist(&record).await;
+        Some(id)
+    }
+
+    /// Note that an exit manager acted on a position, so the next exit is attributed to it
+    pub async fn record_exit_action(&self, position_id: &str, action: ExitModificationType) {
+        self.last_exit_actions
+            .write()
+            .await
+            .insert(position_id.to_string(), action);
+    }
+
+    async fn attribute_exit(&self, record: &TradeRecord, close_price: Decimal) -> ExitReason {
+        let inferred = record.infer_level_exit(close_price);
+        let mut actions = self.last_exit_actions.write().await;
+        let position_id = &record.entry.position_id;
+
+        match actions.get(position_id).cloned() {
+            // Managers that close positions themselves own the fill they caused
+            Some(
+                action @ (ExitModificationType::PartialProfit | ExitModificationType::TimeExit),
+            ) => {
+                actions.remove(position_id);
+                action.into()
+            }
+            // Stop-moving managers own a stop-out, but not a take-profit fill
+            Some(action) if inferred != Some(ExitReason::TakeProfit) => action.into(),
+            _ => inferred.unwrap_or(ExitReason::Manual),
+        }
+    }
+
+    /// Apply a close fill to its trade. Returns the trade id.
+    pub async fn record_close(&self, account_id: &str, close: &PositionCloseEventData) -> String {
+        let existing_id = self
+            .open_positions
+            .read()
+            .await
+            .get(&close.position_id)
+            .cloned();
+        let existing = match existing_id {
+            Some(id) => self.trades.read().await.get(&id).cloned(),
+            None => None,
+        };
+
+        let mut record = match existing {
+            Some(record) => record,
+            None => {
+                warn!(
+                    "Journal saw a close for untracked position {}; recording it from the fill",
+                    close.position_id
+                );
+                Self::record_from_close(account_id, close)
+            }
+        };
+
+        // Positions are often seen by the journal before their tags are resolved
+        if record.entry.tags.is_empty() {
+            record.entry.tags = self.tags_for(&close.position_id);
+        }
+
+        let reason = self.attribute_exit(&record, close.close_price).await;
+        record.apply_exit(
+            TradeExit {
+                closing_order_id: close.closing_order_id.clone(),
+                quantity: close.closed_quantity,
+                price: close.close_price,
+                realized_pnl: close.realized_pnl,
+                commission: close.commission,
+                reason,
+                closed_at: close.closed_at,
+            },
+            close.remaining_quantity,
+        );
+
+        if record.is_open() {
+            self.open_positions
+                .write()
+                .await
+                .insert(close.position_id.clone(), record.id.clone());
+        } else {
+            self.open_positions.write().await.remove(&close.position_id);
+            self.last_exit_actions
+                .write()
+                .await
+                .remove(&close.position_id);
+            info!(
+                "Trade {} closed: {} {} P&L {} ({:?})",
+                record.id,
+                record.entry.symbol,
+                record.entry.position_id,
+                record.realized_pnl,
+                reason
+            );
+        }
+
+        self.persist(&record).await;
+        let id = record.id.clone();
+        self.trades.write().await.insert(id.clone(), record);
+        id
+    }
+
+    /// A trade for a position whose opening was never seen, reconstructed from its close
+    fn record_from_close(account_id: &str, close: &PositionCloseEventData) -> TradeRecord {
+        TradeRecord::open(TradeEntry {
+            account_id: account_id.to_string(),
+            position_id: close.position_id.clone(),
+            symbol: close.symbol.clone(),
+            side: close.side.clone(),
+            quantity: close.closed_quantity + close.remaining_quantity,
+            entry_price: close.entry_price,
+            stop_loss: None,
+            take_profit: None,
+            signal_id: None,
+            order_id: None,
+            opened_at: close.closed_at,
+            tags: Vec::new(),
+        })
+    }
+
+    pub async fn get_trade(&self, id: &str) -> Option<TradeRecord> {
+        self.trades.read().await.get(id).cloned()
+    }
+
+    pub async fn get_trade_for_position(&self, position_id: &str) -> Option<TradeRecord> {
+        let trades = self.trades.read().await;
+        trades
+            .values()
+            .filter(|t| t.entry.position_id == position_id)
+            .max_by_key(|t| t.entry.opened_at)
+            .cloned()
+    }
+
+    /// Matching trades, most recently opened first
+    pub async fn query(&self, query: &TradeQuery) -> Vec<TradeRecord> {
+        let trades = self.trades.read().await;
+        let mut matching: Vec<TradeRecord> = trades
+            .values()
+            .filter(|t| query.matches(t))
+            .cloned()
+            .collect();
+        matching.sort_by_key(|t| std::cmp::Reverse(t.entry.opened_at));
+        if let Some(limit) = query.limit {
+            matching.truncate(limit);
+        }
+        matching
+    }
+
+    /// Trades opened or with an exit in `[from, to)`, oldest first
+    pub async fn trades_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<TradeRecord> {
+        let within = |at: DateTime<Utc>| at >= from && at < to;
+        let trades = self.trades.read().await;
+        let mut matching: Vec<TradeRecord> = trades
+            .values()
+            .filter(|t| within(t.entry.opened_at) || t.exits.iter().any(|e| within(e.closed_at)))
+            .cloned()
+            .collect();
+        matching.sort_by_key(|t| t.entry.opened_at);
+        matching
+    }
+}
+
+impl Default for TradeJournal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
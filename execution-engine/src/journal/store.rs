@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use super::TradeRecord;
+
+/// Durable storage for trade records. `save` is called every time a record
+/// changes; `load` returns the latest version of each record.
+#[async_trait]
+pub trait JournalStore: Send + Sync + std::fmt::Debug {
+    async fn save(&self, record: &TradeRecord) -> Result<()>;
+    async fn load(&self) -> Result<Vec<TradeRecord>>;
+}
+
+/// Append-only JSON lines file; later lines supersede earlier ones with the same id
+#[derive(Debug)]
+pub struct FileJournalStore {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl FileJournalStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl JournalStore for FileJournalStore {
+    async fn save(&self, record: &TradeRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .with_context(|| format!("Failed to open journal {}", self.path.display()))?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Vec<TradeRecord>> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut order = Vec::new();
+        let mut latest: HashMap<String, TradeRecord> = HashMap::new();
+        for (number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<TradeRecord>(line) {
+                Ok(record) => {
+                    if !latest.contains_key(&record.id) {
+                        order.push(record.id.clone());
+                    }
+                    latest.insert(record.id.clone(), record);
+                }
+                // A torn final line from a crash should not lose the rest of the journal
+                Err(e) => warn!(
+                    "Skipping unreadable journal line {} in {}: {}",
+                    number + 1,
+                    self.path.display(),
+                    e
+                ),
+            }
+        }
+
+        Ok(order
+            .into_iter()
+            .filter_map(|id| latest.remove(&id))
+            .collect())
+    }
+}
@@ -0,0 +1,687 @@
+// Position ledger: every fill, modification and close kept as an immutable event,
+// with positions and realized P&L derived by replaying them in order
+
+pub mod statements;
+pub mod store;
+
+pub use statements::{
+    reconcile_statement, FillDiscrepancy, StatementConfig, StatementReconciler,
+    StatementReconciliation, SwapAccrual, SwapRates,
+};
+pub use store::{FileLedgerStore, LedgerStore};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::platforms::abstraction::events::{EventData, EventType, PlatformEvent};
+use crate::platforms::abstraction::models::{
+    UnifiedOrderSide, UnifiedPosition, UnifiedPositionSide,
+};
+
+/// What happened to a position. Quantities are always positive; the side says
+/// which way.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerEventKind {
+    /// An order filled into a position, opening it, adding to it or, on the
+    /// opposite side, reducing it
+    Fill {
+        order_id: String,
+        position_id: String,
+        symbol: String,
+        side: UnifiedPositionSide,
+        quantity: Decimal,
+        price: Decimal,
+        commission: Decimal,
+    },
+    /// The position's stop loss or take profit changed
+    Modified {
+        position_id: String,
+        stop_loss: Option<Decimal>,
+        take_profit: Option<Decimal>,
+    },
+    /// A close order filled against the position, in full or in part
+    Closed {
+        position_id: String,
+        closing_order_id: String,
+        quantity: Decimal,
+        price: Decimal,
+        commission: Decimal,
+        /// P&L the platform reported for the close, used only when the ledger
+        /// never saw the position open
+        reported_pnl: Decimal,
+    },
+    /// Correction from reconciliation that sets the position to what the
+    /// platform reports; a zero quantity removes it. Realized P&L is untouched.
+    Adjusted {
+        position_id: String,
+        symbol: String,
+        side: UnifiedPositionSide,
+        quantity: Decimal,
+        price: Decimal,
+        reason: String,
+    },
+}
+
+impl LedgerEventKind {
+    pub fn position_id(&self) -> &str {
+        match self {
+            Self::Fill { position_id, .. }
+            | Self::Modified { position_id, .. }
+            | Self::Closed { position_id, .. }
+            | Self::Adjusted { position_id, .. } => position_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEvent {
+    /// Position in the ledger, starting at 1; replay applies events in this order
+    pub sequence: u64,
+    pub account_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Platform event this was recorded from, so a redelivered event is applied once
+    #[serde(default)]
+    pub source_event_id: Option<Uuid>,
+    pub kind: LedgerEventKind,
+}
+
+/// A position as derived from the ledger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerPosition {
+    pub account_id: String,
+    pub position_id: String,
+    pub symbol: String,
+    pub side: UnifiedPositionSide,
+    pub quantity: Decimal,
+    pub average_price: Decimal,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    /// Realized on this position so far, net of commission
+    pub realized_pnl: Decimal,
+    pub commission: Decimal,
+    pub opened_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Sequence of the last event applied to the position
+    pub last_sequence: u64,
+}
+
+impl LedgerPosition {
+    /// Quantity signed long positive
+    pub fn signed_quantity(&self) -> Decimal {
+        signed(&self.side, self.quantity)
+    }
+}
+
+fn signed(side: &UnifiedPositionSide, quantity: Decimal) -> Decimal {
+    match side {
+        UnifiedPositionSide::Long => quantity,
+        UnifiedPositionSide::Short => -quantity,
+    }
+}
+
+fn price_gain(side: &UnifiedPositionSide, entry: Decimal, exit: Decimal) -> Decimal {
+    match side {
+        UnifiedPositionSide::Long => exit - entry,
+        UnifiedPositionSide::Short => entry - exit,
+    }
+}
+
+/// Positions and realized P&L folded from ledger events
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LedgerState {
+    positions: HashMap<(String, String), LedgerPosition>,
+    realized_pnl: HashMap<String, Decimal>,
+    last_sequence: u64,
+}
+
+impl LedgerState {
+    /// Fold `events` from an empty ledger. The same events always give the same state.
+    pub fn replay<'a>(events: impl IntoIterator<Item = &'a LedgerEvent>) -> Self {
+        let mut state = Self::default();
+        for event in events {
+            state.apply(event);
+        }
+        state
+    }
+
+    pub fn last_sequence(&self) -> u64 {
+        self.last_sequence
+    }
+
+    pub fn position(&self, account_id: &str, position_id: &str) -> Option<&LedgerPosition> {
+        self.positions
+            .get(&(account_id.to_string(), position_id.to_string()))
+    }
+
+    pub fn positions(&self, account_id: &str) -> Vec<LedgerPosition> {
+        let mut positions: Vec<LedgerPosition> = self
+            .positions
+            .values()
+            .filter(|p| p.account_id == account_id)
+            .cloned()
+            .collect();
+        positions.sort_by_key(|p| p.opened_at);
+        positions
+    }
+
+    pub fn realized_pnl(&self, account_id: &str) -> Decimal {
+        self.realized_pnl
+            .get(account_id)
+            .copied()
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    pub fn apply(&mut self, event: &LedgerEvent) {
+        self.last_sequence = self.last_sequence.max(event.sequence);
+        let key = (
+            event.account_id.clone(),
+            event.kind.position_id().to_string(),
+        );
+
+        let realized = match &event.kind {
+            LedgerEventKind::Fill {
+                symbol,
+                side,
+                quantity,
+                price,
+                commission,
+                ..
+            } => self.apply_fill(key, event, symbol, side, *quantity, *price, *commission),
+            LedgerEventKind::Modified {
+                stop_loss,
+                take_profit,
+                ..
+            } => {
+                if let Some(position) = self.positions.get_mut(&key) {
+                    position.stop_loss = *stop_loss;
+                    position.take_profit = *take_profit;
+                    position.updated_at = event.timestamp;
+                    position.last_sequence = event.sequence;
+                }
+                Decimal::ZERO
+            }
+            LedgerEventKind::Closed {
+                quantity,
+                price,
+                commission,
+                reported_pnl,
+                ..
+            } => match self.positions.get_mut(&key) {
+                Some(position) => {
+                    let closed = (*quantity).min(position.quantity);
+                    let realized = price_gain(&position.side, position.average_price, *price)
+                        * closed
+                        - commission;
+                    position.quantity -= closed;
+                    position.realized_pnl += realized;
+                    position.commission += commission;
+                    position.updated_at = event.timestamp;
+                    position.last_sequence = event.sequence;
+                    if position.quantity.is_zero() {
+                        self.positions.remove(&key);
+                    }
+                    realized
+                }
+                None => *reported_pnl,
+            },
+            LedgerEventKind::Adjusted {
+                symbol,
+                side,
+                quantity,
+                price,
+                ..
+            } => {
+                if quantity.is_zero() {
+                    self.positions.remove(&key);
+                } else {
+                    let position =
+                        self.positions
+                            .entry(key.clone())
+                            .or_insert_with(|| LedgerPosition {
+                                account_id: key.0.clone(),
+                                position_id: key.1.clone(),
+                                symbol: symbol.clone(),
+                                side: side.clone(),
+                                quantity: Decimal::ZERO,
+                                average_price: *price,
+                                stop_loss: None,
+                                take_profit: None,
+                                realized_pnl: Decimal::ZERO,
+                                commission: Decimal::ZERO,
+                                opened_at: event.timestamp,
+                                updated_at: event.timestamp,
+                                last_sequence: event.sequence,
+                            });
+                    if position.side != *side {
+                        position.side = side.clone();
+                        position.average_price = *price;
+                    }
+                    position.quantity = *quantity;
+                    position.updated_at = event.timestamp;
+                    position.last_sequence = event.sequence;
+                }
+                Decimal::ZERO
+            }
+        };
+
+        if !realized.is_zero() {
+            *self
+                .realized_pnl
+                .entry(event.account_id.clone())
+                .or_insert(Decimal::ZERO) += realized;
+        }
+    }
+
+    /// Apply a fill and return what it realized
+    fn apply_fill(
+        &mut self,
+        key: (String, String),
+        event: &LedgerEvent,
+        symbol: &str,
+        side: &UnifiedPositionSide,
+        quantity: Decimal,
+        price: Decimal,
+        commission: Decimal,
+    ) -> Decimal {
+        let mut remaining = quantity;
+        let mut realized = -commission;
+
+        if let Some(position) = self.positions.get_mut(&key) {
+            position.commission += commission;
+            position.realized_pnl -= commission;
+            position.updated_at = event.timestamp;
+            position.last_sequence = event.sequence;
+            if position.side == *side {
+                let total = position.quantity + quantity;
+                position.average_price =
+                    (position.average_price * position.quantity + price * quantity) / total;
+                position.quantity = total;
+                return realized;
+            }
+
+            let closed = quantity.min(position.quantity);
+            let gain = price_gain(&position.side, position.average_price, price) * closed;
+            position.quantity -= closed;
+            position.realized_pnl += gain;
+            realized += gain;
+            remaining -= closed;
+            if position.quantity.is_zero() {
+                self.positions.remove(&key);
+            }
+            if remaining.is_zero() {
+                return realized;
+            }
+        }
+
+        // A new position, or what is left of a fill that flipped one
+        let opening_commission = if remaining == quantity {
+            commission
+        } else {
+            Decimal::ZERO
+        };
+        self.positions.insert(
+            key.clone(),
+            LedgerPosition {
+                account_id: key.0,
+                position_id: key.1,
+                symbol: symbol.to_string(),
+                side: side.clone(),
+                quantity: remaining,
+                average_price: price,
+                stop_loss: None,
+                take_profit: None,
+                realized_pnl: -opening_commission,
+                commission: opening_commission,
+                opened_at: event.timestamp,
+                updated_at: event.timestamp,
+                last_sequence: event.sequence,
+            },
+        );
+        realized
+    }
+}
+
+/// A position whose size in the ledger differs from what its platform reports
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionDrift {
+    pub account_id: String,
+    pub position_id: String,
+    pub symbol: String,
+    /// Signed long positive; zero when the ledger has no such position
+    pub ledger_quantity: Decimal,
+    /// Signed long positive; zero when the platform has no such position
+    pub platform_quantity: Decimal,
+}
+
+#[derive(Default)]
+struct LedgerInner {
+    events: Vec<LedgerEvent>,
+    state: LedgerState,
+    seen_sources: HashSet<Uuid>,
+}
+
+/// Records position events and serves the positions and P&L derived from them.
+/// Events are persisted before they are applied, so reloading the store after a
+/// crash rebuilds exactly the state that was last served.
+pub struct PositionLedger {
+    store: Option<Arc<dyn LedgerStore>>,
+    inner: RwLock<LedgerInner>,
+}
+
+impl PositionLedger {
+    pub fn new() -> Self {
+        Self {
+            store: None,
+            inner: RwLock::new(LedgerInner::default()),
+        }
+    }
+
+    pub fn with_store(mut self, store: Arc<dyn LedgerStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Rebuild positions and P&L by replaying every stored event. Returns how
+    /// many events were replayed.
+    pub async fn load(&self) -> Result<usize> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+        let events = store.load().await?;
+        let state = LedgerState::replay(&events);
+        let seen_sources = events.iter().filter_map(|e| e.source_event_id).collect();
+        let count = events.len();
+
+        *self.inner.write().await = LedgerInner {
+            events,
+            state,
+            seen_sources,
+        };
+        info!("Position ledger rebuilt from {} events", count);
+        Ok(count)
+    }
+
+    /// Append an event for `account_id` and apply it
+    pub async fn record(&self, account_id: &str, kind: LedgerEventKind) -> Result<LedgerEvent> {
+        self.append(account_id, kind, Utc::now(), None).await
+    }
+
+    async fn append(
+        &self,
+        account_id: &str,
+        kind: LedgerEventKind,
+        timestamp: DateTime<Utc>,
+        source_event_id: Option<Uuid>,
+    ) -> Result<LedgerEvent> {
+        // Held across the write so that the store sees events in sequence order
+        let mut inner = self.inner.write().await;
+        let event = LedgerEvent {
+            sequence: inner.state.last_sequence() + 1,
+            account_id: account_id.to_string(),
+            timestamp,
+            source_event_id,
+            kind,
+        };
+        if let Some(store) = &self.store {
+            store.append(&event).await?;
+        }
+
+        debug!(
+            "Ledger event {} for position {} on {}",
+            event.sequence,
+            event.kind.position_id(),
+            account_id
+        );
+        inner.state.apply(&event);
+        inner.seen_sources.extend(source_event_id);
+        inner.events.push(event.clone());
+        Ok(event)
+    }
+
+    /// Record order fills, stop/target changes and closes reported by a platform.
+    /// A fill against the opposite side of an open position is left to the
+    /// platform's close event, which carries what the close realized.
+    pub async fn handle_platform_event(&self, event: &PlatformEvent) {
+        if self
+            .inner
+            .read()
+            .await
+            .seen_sources
+            .contains(&event.event_id)
+        {
+            return;
+        }
+        let Some(kind) = self.event_kind(event).await else {
+            return;
+        };
+        if let Err(e) = self
+            .append(
+                &event.account_id,
+                kind,
+                event.timestamp,
+                Some(event.event_id),
+            )
+            .await
+        {
+            warn!(
+                "Failed to record {:?} from {} in the position ledger: {}",
+                event.event_type, event.account_id, e
+            );
+        }
+    }
+
+    async fn event_kind(&self, event: &PlatformEvent) -> Option<LedgerEventKind> {
+        match (&event.event_type, &event.data) {
+            (EventType::OrderFilled | EventType::OrderPartiallyFilled, EventData::Order(data)) => {
+                let order = &data.order;
+                let quantity = data.fill_quantity.unwrap_or(order.filled_quantity);
+                let price = data
+                    .fill_price
+                    .or(order.average_fill_price)
+                    .or(order.price)?;
+                if quantity <= Decimal::ZERO {
+                    return None;
+                }
+                let position_id = order
+                    .platform_specific
+                    .get("position_id")
+                    .and_then(|id| id.as_str())
+                    .unwrap_or(&order.platform_order_id)
+                    .to_string();
+                let side = match order.side {
+                    UnifiedOrderSide::Buy => UnifiedPositionSide::Long,
+                    UnifiedOrderSide::Sell => UnifiedPositionSide::Short,
+                };
+                if let Some(position) = self.position(&event.account_id, &position_id).await {
+                    if position.side != side {
+                        return None;
+                    }
+                }
+                // Commission is reported for the whole order, so it is booked with
+                // the fill that completes it
+                let completed = data
+                    .remaining_quantity
+                    .unwrap_or(order.remaining_quantity)
+                    .is_zero();
+                Some(LedgerEventKind::Fill {
+                    order_id: order.platform_order_id.clone(),
+                    position_id,
+                    symbol: order.symbol.clone(),
+                    side,
+                    quantity,
+                    price,
+                    commission: if completed {
+                        order.commission.unwrap_or(Decimal::ZERO)
+                    } else {
+                        Decimal::ZERO
+                    },
+                })
+            }
+            (EventType::PositionModified, EventData::Position(data)) => {
+                let reported = &data.position;
+                let position = self
+                    .position(&event.account_id, &reported.position_id)
+                    .await?;
+                if position.stop_loss == reported.stop_loss
+                    && position.take_profit == reported.take_profit
+                {
+                    return None;
+                }
+                Some(LedgerEventKind::Modified {
+                    position_id: reported.position_id.clone(),
+                    stop_loss: reported.stop_loss,
+                    take_profit: reported.take_profit,
+                })
+            }
+            (_, EventData::PositionClose(close)) => Some(LedgerEventKind::Closed {
+                position_id: close.position_id.clone(),
+                closing_order_id: close.closing_order_id.clone(),
+                quantity: close.closed_quantity,
+                price: close.close_price,
+                commission: close.commission,
+                reported_pnl: close.realized_pnl,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Compare the account's ledger positions with those its platform reports and
+    /// record an `Adjusted` event for each difference, taking the platform's side.
+    /// Returns the drift found before it was corrected.
+    pub async fn reconcile(
+        &self,
+        account_id: &str,
+        platform_positions: &[UnifiedPosition],
+    ) -> Result<Vec<PositionDrift>> {
+        let ledger: HashMap<String, LedgerPosition> = self
+            .positions(account_id)
+            .await
+            .into_iter()
+            .map(|p| (p.position_id.clone(), p))
+            .collect();
+
+        let mut drift = Vec::new();
+        for reported in platform_positions {
+            let platform_quantity = signed(&reported.side, reported.quantity);
+            let ledger_quantity = ledger
+                .get(&reported.position_id)
+                .map(|p| p.signed_quantity())
+                .unwrap_or(Decimal::ZERO);
+            if ledger_quantity == platform_quantity {
+                continue;
+            }
+            self.record(
+                account_id,
+                LedgerEventKind::Adjusted {
+                    position_id: reported.position_id.clone(),
+                    symbol: reported.symbol.clone(),
+                    side: reported.side.clone(),
+                    quantity: reported.quantity,
+                    price: reported.entry_price,
+                    reason: format!(
+                        "Platform reports {} where the ledger had {}",
+                        platform_quantity, ledger_quantity
+                    ),
+                },
+            )
+            .await?;
+            drift.push(PositionDrift {
+                account_id: account_id.to_string(),
+                position_id: reported.position_id.clone(),
+                symbol: reported.symbol.clone(),
+                ledger_quantity,
+                platform_quantity,
+            });
+        }
+
+        let reported: HashSet<&str> = platform_positions
+            .iter()
+            .map(|p| p.position_id.as_str())
+            .collect();
+        for position in ledger.values() {
+            if reported.contains(position.position_id.as_str()) {
+                continue;
+            }
+            self.record(
+                account_id,
+                LedgerEventKind::Adjusted {
+                    position_id: position.position_id.clone(),
+                    symbol: position.symbol.clone(),
+                    side: position.side.clone(),
+                    quantity: Decimal::ZERO,
+                    price: position.average_price,
+                    reason: "Platform no longer reports the position".to_string(),
+                },
+            )
+            .await?;
+            drift.push(PositionDrift {
+                account_id: account_id.to_string(),
+                position_id: position.position_id.clone(),
+                symbol: position.symbol.clone(),
+                ledger_quantity: position.signed_quantity(),
+                platform_quantity: Decimal::ZERO,
+            });
+        }
+
+        for d in &drift {
+            warn!(
+                "Position ledger drift on {} for {} ({}): ledger {}, platform {}",
+                d.account_id, d.position_id, d.symbol, d.ledger_quantity, d.platform_quantity
+            );
+        }
+        Ok(drift)
+    }
+
+    pub async fn positions(&self, account_id: &str) -> Vec<LedgerPosition> {
+        self.inner.read().await.state.positions(account_id)
+    }
+
+    pub async fn position(&self, account_id: &str, position_id: &str) -> Option<LedgerPosition> {
+        self.inner
+            .read()
+            .await
+            .state
+            .position(account_id, position_id)
+            .cloned()
+    }
+
+    /// Realized P&L for the account, net of commission
+    pub async fn realized_pnl(&self, account_id: &str) -> Decimal {
+        self.inner.read().await.state.realized_pnl(account_id)
+    }
+
+    pub async fn state(&self) -> LedgerState {
+        self.inner.read().await.state.clone()
+    }
+
+    /// Every recorded event, in sequence order
+    pub async fn events(&self) -> Vec<LedgerEvent> {
+        self.inner.read().await.events.clone()
+    }
+
+    /// The audit trail of one position
+    pub async fn position_history(&self, account_id: &str, position_id: &str) -> Vec<LedgerEvent> {
+        self.inner
+            .read()
+            .await
+            .events
+            .iter()
+            .filter(|e| e.account_id == account_id && e.kind.position_id() == position_id)
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for PositionLedger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,559 @@
+// Statement reconciliation: the platform's own record of an account, its
+// transaction history, checked against the ledger. Fills are matched by order,
+// commission totals compared, and every overnight swap charged set against the
+// positions the ledger held at that rollover and the configured swap rates.
+// Deposits and withdrawals on the statement go to the funding detector.
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, Utc, Weekday};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use super::{LedgerEvent, LedgerEventKind, LedgerState, PositionLedger};
+use crate::execution::TradeExecutionOrchestrator;
+use crate::platforms::abstraction::models::{
+    TransactionRange, UnifiedPositionSide, UnifiedTransaction, UnifiedTransactionKind,
+};
+use crate::platforms::abstraction::{ITradingPlatform, PlatformError};
+use crate::risk::funding::FundingDetector;
+use crate::risk::{TradingDayConfig, TradingDayRollover};
+
+/// Overnight financing per unit held through one rollover, in the account
+/// currency; negative for a charge
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SwapRates {
+    pub long: Decimal,
+    pub short: Decimal,
+}
+
+impl SwapRates {
+    pub fn for_side(&self, side: &UnifiedPositionSide) -> Decimal {
+        match side {
+            UnifiedPositionSide::Long => self.long,
+            UnifiedPositionSide::Short => self.short,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatementConfig {
+    pub enabled: bool,
+    /// Minutes between reconciliations
+    pub interval_mins: u64,
+    /// Hours of statement each reconciliation covers, up to now
+    pub lookback_hours: u64,
+    /// Fill prices further apart than this are a discrepancy
+    pub price_tolerance: Decimal,
+    /// Commission and swap totals further apart than this are a discrepancy
+    pub amount_tolerance: Decimal,
+    /// Rates by symbol; swaps on symbols without one are listed but not checked
+    pub swap_rates: HashMap<String, SwapRates>,
+    /// Trading day whose rollover is charged three nights, for the weekend
+    pub triple_swap_day: Weekday,
+}
+
+impl Default for StatementConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_mins: 60,
+            lookback_hours: 24,
+            price_tolerance: dec!(0.00001),
+            amount_tolerance: dec!(0.01),
+            swap_rates: HashMap::new(),
+            triple_swap_day: Weekday::Wed,
+        }
+    }
+}
+
+impl StatementConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_mins == 0 {
+            return Err("Statement interval_mins must be at least 1".to_string());
+        }
+        if self.lookback_hours == 0 {
+            return Err("Statement lookback_hours must be at least 1".to_string());
+        }
+        if self.price_tolerance < Decimal::ZERO || self.amount_tolerance < Decimal::ZERO {
+            return Err("Statement tolerances cannot be negative".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A fill the statement and the ledger disagree on
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FillDiscrepancy {
+    /// On the statement but never recorded by the ledger
+    MissingFromLedger {
+        order_id: String,
+        symbol: Option<String>,
+        quantity: Decimal,
+        price: Decimal,
+    },
+    /// Recorded by the ledger but not on the statement
+    MissingFromStatement {
+        order_id: String,
+        quantity: Decimal,
+        price: Decimal,
+    },
+    QuantityMismatch {
+        order_id: String,
+        ledger: Decimal,
+        statement: Decimal,
+    },
+    /// Volume-weighted prices of the order's fills
+    PriceMismatch {
+        order_id: String,
+        ledger: Decimal,
+        statement: Decimal,
+    },
+}
+
+/// Swap on one position at one rollover, as charged and as expected
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SwapAccrual {
+    pub rollover: DateTime<Utc>,
+    pub position_id: String,
+    pub symbol: String,
+    pub side: UnifiedPositionSide,
+    /// Quantity the ledger held through the rollover
+    pub quantity: Decimal,
+    /// None when no rate is configured for the symbol
+    pub expected: Option<Decimal>,
+    pub charged: Decimal,
+    /// Statement entries the charge is made of
+    pub charges: usize,
+}
+
+impl SwapAccrual {
+    /// Charged less expected, when a rate is known
+    pub fn difference(&self) -> Option<Decimal> {
+        self.expected.map(|expected| self.charged - expected)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatementReconciliation {
+    pub account_id: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub fills_matched: usize,
+    pub fill_discrepancies: Vec<FillDiscrepancy>,
+    /// Commission charged on the statement, as a positive amount
+    pub statement_commission: Decimal,
+    pub ledger_commission: Decimal,
+    pub swaps: Vec<SwapAccrual>,
+    /// Swaps that could not be put against a position the ledger held
+    pub unmatched_swaps: Vec<UnifiedTransaction>,
+    /// Deposits less withdrawals on the statement
+    pub net_transfers: Decimal,
+    /// Tolerance the swap and commission totals were held to
+    pub amount_tolerance: Decimal,
+}
+
+impl StatementReconciliation {
+    pub fn commission_difference(&self) -> Decimal {
+        self.statement_commission - self.ledger_commission
+    }
+
+    /// Swaps charged more than the tolerance away from the rate
+    pub fn swap_discrepancies(&self) -> impl Iterator<Item = &SwapAccrual> {
+        self.swaps.iter().filter(|swap| {
+            swap.difference()
+                .is_some_and(|difference| difference.abs() > self.amount_tolerance)
+        })
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.fill_discrepancies.is_empty()
+            && self.commission_difference().abs() <= self.amount_tolerance
+            && self.swap_discrepancies().next().is_none()
+            && self.unmatched_swaps.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} fills matched, {} fill discrepancies, commission {} vs {} in the ledger, {} of {} swaps off their rate, {} unmatched swaps",
+            self.fills_matched,
+            self.fill_discrepancies.len(),
+            self.statement_commission,
+            self.ledger_commission,
+            self.swap_discrepancies().count(),
+            self.swaps.len(),
+            self.unmatched_swaps.len()
+        )
+    }
+}
+
+/// Quantity and volume-weighted price of an order's fills
+#[derive(Debug, Clone, Copy, Default)]
+struct OrderFills {
+    quantity: Decimal,
+    notional: Decimal,
+}
+
+impl OrderFills {
+    fn add(&mut self, quantity: Decimal, price: Decimal) {
+        self.quantity += quantity;
+        self.notional += quantity * price;
+    }
+
+    fn price(&self) -> Decimal {
+        if self.quantity.is_zero() {
+            Decimal::ZERO
+        } else {
+            self.notional / self.quantity
+        }
+    }
+}
+
+/// The account's fills by order id, with the time of each order's first fill
+fn ledger_orders(events: &[&LedgerEvent]) -> BTreeMap<String, (OrderFills, DateTime<Utc>)> {
+    let mut orders: BTreeMap<String, (OrderFills, DateTime<Utc>)> = BTreeMap::new();
+    for event in events {
+        let (order_id, quantity, price) = match &event.kind {
+            LedgerEventKind::Fill {
+                order_id,
+                quantity,
+                price,
+                ..
+            } => (order_id, *quantity, *price),
+            LedgerEventKind::Closed {
+                closing_order_id,
+                quantity,
+                price,
+                ..
+            } => (closing_order_id, *quantity, *price),
+            _ => continue,
+        };
+        let (fills, first) = orders
+            .entry(order_id.clone())
+            .or_insert((OrderFills::default(), event.timestamp));
+        fills.add(quantity, price);
+        *first = (*first).min(event.timestamp);
+    }
+    orders
+}
+
+/// Rollovers between `from` and `to`, with how many nights each is charged.
+/// Weekend trading days roll without a charge of their own; their nights are
+/// charged on the triple swap day.
+fn rollovers(
+    rollover: &TradingDayRollover,
+    triple_swap_day: Weekday,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<(DateTime<Utc>, Decimal)> {
+    let mut rollovers = Vec::new();
+    let mut day = rollover.trading_day(from);
+    loop {
+        let (_, end) = rollover.bounds(day);
+        if end >= to {
+            break;
+        }
+        if !matches!(day.weekday(), Weekday::Sat | Weekday::Sun) {
+            let nights = if day.weekday() == triple_swap_day {
+                dec!(3)
+            } else {
+                dec!(1)
+            };
+            rollovers.push((end, nights));
+        }
+        match day.succ_opt() {
+            Some(next) => day = next,
+            None => break,
+        }
+    }
+    rollovers
+}
+
+/// Check an account's statement for `range` against the ledger's events. The
+/// ledger is looked up in full for orders on the statement, so a fill recorded
+/// just before the range still matches; only orders the ledger first filled
+/// inside the range are expected on the statement.
+pub fn reconcile_statement(
+    account_id: &str,
+    range: TransactionRange,
+    transactions: &[UnifiedTransaction],
+    events: &[LedgerEvent],
+    rollover: &TradingDayRollover,
+    config: &StatementConfig,
+) -> StatementReconciliation {
+    let events: Vec<&LedgerEvent> = events
+        .iter()
+        .filter(|event| event.account_id == account_id)
+        .collect();
+
+    // Fills, by order
+    let ledger = ledger_orders(&events);
+    let mut statement: BTreeMap<String, (OrderFills, Option<String>)> = BTreeMap::new();
+    for transaction in transactions {
+        if transaction.kind != UnifiedTransactionKind::Fill {
+            continue;
+        }
+        let (Some(quantity), Some(price)) = (transaction.quantity, transaction.price) else {
+            continue;
+        };
+        let order_id = transaction
+            .order_id
+            .clone()
+            .unwrap_or_else(|| transaction.transaction_id.clone());
+        let (fills, _) = statement
+            .entry(order_id)
+            .or_insert((OrderFills::default(), transaction.symbol.clone()));
+        fills.add(quantity, price);
+    }
+
+    let mut fills_matched = 0;
+    let mut fill_discrepancies = Vec::new();
+    for (order_id, (fills, symbol)) in &statement {
+        let Some((recorded, _)) = ledger.get(order_id) else {
+            fill_discrepancies.push(FillDiscrepancy::MissingFromLedger {
+                order_id: order_id.clone(),
+                symbol: symbol.clone(),
+                quantity: fills.quantity,
+                price: fills.price(),
+            });
+            continue;
+        };
+        if recorded.quantity != fills.quantity {
+            fill_discrepancies.push(FillDiscrepancy::QuantityMismatch {
+                order_id: order_id.clone(),
+                ledger: recorded.quantity,
+                statement: fills.quantity,
+            });
+        } else if (recorded.price() - fills.price()).abs() > config.price_tolerance {
+            fill_discrepancies.push(FillDiscrepancy::PriceMismatch {
+                order_id: order_id.clone(),
+                ledger: recorded.price(),
+                statement: fills.price(),
+            });
+        } else {
+            fills_matched += 1;
+        }
+    }
+    for (order_id, (fills, first)) in &ledger {
+        if range.contains(*first) && !statement.contains_key(order_id) {
+            fill_discrepancies.push(FillDiscrepancy::MissingFromStatement {
+                order_id: order_id.clone(),
+                quantity: fills.quantity,
+                price: fills.price(),
+            });
+        }
+    }
+
+    // Commission, with charges booked apart from their fills
+    let statement_commission: Decimal = transactions
+        .iter()
+        .map(|t| match t.kind {
+            UnifiedTransactionKind::Fill => t.commission.unwrap_or_default().abs(),
+            UnifiedTransactionKind::Commission => -t.amount,
+            _ => Decimal::ZERO,
+        })
+        .sum();
+    let ledger_commission: Decimal = events
+        .iter()
+        .filter(|event| range.contains(event.timestamp))
+        .map(|event| match &event.kind {
+            LedgerEventKind::Fill { commission, .. }
+            | LedgerEventKind::Closed { commission, .. } => *commission,
+            _ => Decimal::ZERO,
+        })
+        .sum();
+
+    // Swaps, against the positions held through each rollover
+    let mut swaps = Vec::new();
+    for (at, nights) in rollovers(rollover, config.triple_swap_day, range.from, range.to) {
+        let held = LedgerState::replay(events.iter().copied().filter(|e| e.timestamp < at));
+        for position in held.positions(account_id) {
+            let expected = config
+                .swap_rates
+                .get(&position.symbol)
+                .map(|rates| rates.for_side(&position.side) * position.quantity * nights);
+            swaps.push(SwapAccrual {
+                rollover: at,
+                position_id: position.position_id,
+                symbol: position.symbol,
+                side: position.side,
+                quantity: position.quantity,
+                expected,
+                charged: Decimal::ZERO,
+                charges: 0,
+            });
+        }
+    }
+    let mut unmatched_swaps = Vec::new();
+    for transaction in transactions {
+        if transaction.kind != UnifiedTransactionKind::Swap {
+            continue;
+        }
+        // Charged at the last rollover before it was booked
+        let rollover = swaps
+            .iter()
+            .map(|swap| swap.rollover)
+            .filter(|at| *at <= transaction.timestamp)
+            .max();
+        let candidates: Vec<usize> = swaps
+            .iter()
+            .enumerate()
+            .filter(|(_, swap)| Some(swap.rollover) == rollover)
+            .filter(|(_, swap)| match &transaction.position_id {
+                Some(position_id) => swap.position_id == *position_id,
+                None => transaction.symbol.as_ref() == Some(&swap.symbol),
+            })
+            .map(|(index, _)| index)
+            .collect();
+        match candidates.as_slice() {
+            [index] => {
+                swaps[*index].charged += transaction.amount;
+                swaps[*index].charges += 1;
+            }
+            _ => unmatched_swaps.push(transaction.clone()),
+        }
+    }
+
+    StatementReconciliation {
+        account_id: account_id.to_string(),
+        from: range.from,
+        to: range.to,
+        fills_matched,
+        fill_discrepancies,
+        statement_commission,
+        ledger_commission,
+        swaps,
+        unmatched_swaps,
+        net_transfers: transactions
+            .iter()
+            .filter(|t| t.kind == UnifiedTransactionKind::Transfer)
+            .map(|t| t.amount)
+            .sum(),
+        amount_tolerance: config.amount_tolerance,
+    }
+}
+
+/// Fetches every account's statement on an interval and reconciles it with
+/// the ledger, keeping the latest result per account
+pub struct StatementReconciler {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    ledger: Arc<PositionLedger>,
+    config: StatementConfig,
+    trading_days: TradingDayConfig,
+    funding: Option<Arc<FundingDetector>>,
+    latest: RwLock<HashMap<String, StatementReconciliation>>,
+}
+
+impl StatementReconciler {
+    pub fn new(
+        orchestrator: Arc<TradeExecutionOrchestrator>,
+        ledger: Arc<PositionLedger>,
+        config: StatementConfig,
+    ) -> Self {
+        Self {
+            orchestrator,
+            ledger,
+            config,
+            trading_days: TradingDayConfig::default(),
+            funding: None,
+            latest: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Rollovers swaps are charged at
+    pub fn with_trading_days(mut self, trading_days: TradingDayConfig) -> Self {
+        self.trading_days = trading_days;
+        self
+    }
+
+    /// Record the deposits and withdrawals on each statement
+    pub fn with_funding_detector(mut self, funding: Arc<FundingDetector>) -> Self {
+        self.funding = Some(funding);
+        self
+    }
+
+    pub fn config(&self) -> &StatementConfig {
+        &self.config
+    }
+
+    /// Reconcile one account's statement over `range`. None when its platform
+    /// keeps no transaction history.
+    pub async fn reconcile_account(
+        &self,
+        account_id: &str,
+        platform: &(dyn ITradingPlatform + Send + Sync),
+        range: TransactionRange,
+    ) -> Result<Option<StatementReconciliation>> {
+        let transactions = match platform.get_transactions(range).await {
+            Ok(transactions) => transactions,
+            Err(PlatformError::FeatureNotSupported { .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        if let Some(funding) = &self.funding {
+            funding
+                .record_transactions(account_id, &transactions)
+                .await?;
+        }
+
+        let reconciliation = reconcile_statement(
+            account_id,
+            range,
+            &transactions,
+            &self.ledger.events().await,
+            &self.trading_days.for_account(account_id),
+            &self.config,
+        );
+        if reconciliation.is_clean() {
+            info!(
+                "Statement of {} reconciled: {}",
+                account_id,
+                reconciliation.summary()
+            );
+        } else {
+            warn!(
+                "Statement of {} disagrees with the ledger: {}",
+                account_id,
+                reconciliation.summary()
+            );
+        }
+        self.latest
+            .write()
+            .await
+            .insert(account_id.to_string(), reconciliation.clone());
+        Ok(Some(reconciliation))
+    }
+
+    /// Reconcile every account over the lookback up to `now`
+    pub async fn reconcile_all(&self, now: DateTime<Utc>) -> Vec<StatementReconciliation> {
+        let range = TransactionRange::new(
+            now - Duration::hours(self.config.lookback_hours as i64),
+            now,
+        );
+        let mut reconciliations = Vec::new();
+        for (account_id, platform) in self.orchestrator.get_platforms().await {
+            match self
+                .reconcile_account(&account_id, platform.as_ref(), range)
+                .await
+            {
+                Ok(Some(reconciliation)) => reconciliations.push(reconciliation),
+                Ok(None) => debug!("{} keeps no transaction history", account_id),
+                Err(e) => warn!("Statement reconciliation failed for {}: {}", account_id, e),
+            }
+        }
+        reconciliations
+    }
+
+    /// The last reconciliation of each account, by account id
+    pub async fn latest(&self) -> Vec<StatementReconciliation> {
+        let mut latest: Vec<StatementReconciliation> =
+            self.latest.read().await.values().cloned().collect();
+        latest.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+        latest
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use super::LedgerEvent;
+
+/// Durable storage for ledger events. Events are never rewritten: `append` is
+/// called once per event and `load` returns every event in sequence order.
+#[async_trait]
+pub trait LedgerStore: Send + Sync + std::fmt::Debug {
+    async fn append(&self, event: &LedgerEvent) -> Result<()>;
+    async fn load(&self) -> Result<Vec<LedgerEvent>>;
+}
+
+/// Append-only JSON lines file, one event per line
+#[derive(Debug)]
+pub struct FileLedgerStore {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl FileLedgerStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl LedgerStore for FileLedgerStore {
+    async fn append(&self, event: &LedgerEvent) -> Result<()> {
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .with_context(|| format!("Failed to open ledger {}", self.path.display()))?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Vec<LedgerEvent>> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut events = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<LedgerEvent>(line) {
+                Ok(event) => events.push(event),
+                // A torn final line from a crash should not lose the rest of the ledger
+                Err(e) => warn!(
+                    "Skipping unreadable ledger line {} in {}: {}",
+                    number + 1,
+                    self.path.display(),
+                    e
+                ),
+            }
+        }
+        events.sort_by_key(|event| event.sequence);
+        Ok(events)
+    }
+}
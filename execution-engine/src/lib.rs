@@ -22,15 +22,32 @@
 #![allow(unused_mut)]
 #![allow(unused_assignments)]
 
+pub mod alerting;
+pub mod auth;
+pub mod ctl;
+pub mod dashboard;
 pub mod execution;
+pub mod instruments;
+pub mod journal;
+pub mod ledger;
+pub mod market_analysis;
+pub mod market_data;
+pub mod notifications;
 pub mod platforms;
+pub mod recording;
+pub mod reports;
 pub mod risk;
+pub mod runtime;
+pub mod storage;
+pub mod timeseries;
+#[cfg(any(test, feature = "test-support"))]
+pub mod testing;
 
 // Temporarily disabled problematic modules
-// pub mod api;
-// pub mod messaging;
-// pub mod utils;
-// pub mod monitoring;
+pub mod api;
+pub mod messaging;
+pub mod utils;
+pub mod monitoring;
 
 pub use platforms::PlatformType;
 pub use risk::*;
@@ -0,0 +1,7 @@
+// Price structure read from the in-process candles
+
+pub mod structure;
+
+pub use structure::{
+    MarketStructure, SrZone, StructureAnalyzer, StructureConfig, SwingKind, SwingPoint,
+};
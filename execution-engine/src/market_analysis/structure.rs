@@ -0,0 +1,216 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::market_data::{Candle, CandleBuilder, Timeframe};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SwingKind {
+    High,
+    Low,
+}
+
+/// A candle whose high (or low) is beyond that of its neighbours on both sides
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwingPoint {
+    pub kind: SwingKind,
+    pub price: Decimal,
+    pub open_time: DateTime<Utc>,
+}
+
+/// Fractal swing highs and lows in `candles`, oldest first. A swing needs
+/// `strength` candles on each side that do not reach it.
+pub fn fractal_swings(candles: &[Candle], strength: usize) -> Vec<SwingPoint> {
+    let mut swings = Vec::new();
+    if strength == 0 || candles.len() < 2 * strength + 1 {
+        return swings;
+    }
+    for i in strength..candles.len() - strength {
+        let neighbours = candles[i - strength..i]
+            .iter()
+            .chain(&candles[i + 1..=i + strength]);
+        let (mut is_high, mut is_low) = (true, true);
+        for neighbour in neighbours {
+            is_high &= neighbour.high < candles[i].high;
+            is_low &= neighbour.low > candles[i].low;
+        }
+        if is_high {
+            swings.push(SwingPoint {
+                kind: SwingKind::High,
+                price: candles[i].high,
+                open_time: candles[i].open_time,
+            });
+        }
+        if is_low {
+            swings.push(SwingPoint {
+                kind: SwingKind::Low,
+                price: candles[i].low,
+                open_time: candles[i].open_time,
+            });
+        }
+    }
+    swings
+}
+
+/// A price band where swings have turned, acting as support below the price and
+/// resistance above it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SrZone {
+    pub lower: Decimal,
+    pub upper: Decimal,
+    /// Swings that turned inside the zone
+    pub touches: u32,
+    pub last_touched: DateTime<Utc>,
+}
+
+impl SrZone {
+    pub fn contains(&self, price: Decimal) -> bool {
+        self.lower <= price && price <= self.upper
+    }
+}
+
+/// Group swings whose prices lie within `width` of the lowest swing in their
+/// group. Zones come back ordered by price.
+pub fn cluster_zones(swings: &[SwingPoint], width: Decimal) -> Vec<SrZone> {
+    let mut sorted: Vec<&SwingPoint> = swings.iter().collect();
+    sorted.sort_by_key(|swing| swing.price);
+
+    let mut zones: Vec<SrZone> = Vec::new();
+    for swing in sorted {
+        match zones.last_mut() {
+            Some(zone) if swing.price - zone.lower <= width => {
+                zone.upper = swing.price;
+                zone.touches += 1;
+                zone.last_touched = zone.last_touched.max(swing.open_time);
+            }
+            _ => zones.push(SrZone {
+                lower: swing.price,
+                upper: swing.price,
+                touches: 1,
+                last_touched: swing.open_time,
+            }),
+        }
+    }
+    zones
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StructureConfig {
+    /// Candles the structure is read from
+    pub timeframe: Timeframe,
+    /// Closed candles scanned for swings
+    pub lookback: usize,
+    /// Candles on each side a swing must stand out from
+    pub strength: usize,
+    /// Swings within this many ATRs of each other form one zone
+    pub zone_width_atr: f64,
+    pub atr_period: usize,
+    /// Swings a zone needs before exits act on it
+    pub min_touches: u32,
+}
+
+impl Default for StructureConfig {
+    fn default() -> Self {
+        Self {
+            timeframe: Timeframe::H1,
+            lookback: 200,
+            strength: 2,
+            zone_width_atr: 0.25,
+            atr_period: 14,
+            min_touches: 1,
+        }
+    }
+}
+
+/// Swings and support/resistance zones of a symbol, read against `price`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketStructure {
+    pub symbol: String,
+    pub timeframe: Timeframe,
+    pub price: Decimal,
+    pub swings: Vec<SwingPoint>,
+    pub zones: Vec<SrZone>,
+    /// Closest zone wholly below the price
+    pub support: Option<SrZone>,
+    /// Closest zone wholly above the price
+    pub resistance: Option<SrZone>,
+    pub analyzed_at: DateTime<Utc>,
+}
+
+impl MarketStructure {
+    /// Read the structure of closed `candles` at `price`, or `None` while there
+    /// are too few candles for a swing
+    pub fn from_candles(
+        symbol: &str,
+        candles: &[Candle],
+        price: Decimal,
+        atr: Option<Decimal>,
+        config: &StructureConfig,
+    ) -> Option<Self> {
+        let timeframe = candles.first()?.timeframe;
+        if candles.len() < 2 * config.strength + 1 {
+            return None;
+        }
+        let swings = fractal_swings(candles, config.strength);
+        let width = atr
+            .and_then(|atr| Decimal::from_f64(config.zone_width_atr).map(|f| atr * f))
+            .unwrap_or(Decimal::ZERO);
+        let zones: Vec<SrZone> = cluster_zones(&swings, width)
+            .into_iter()
+            .filter(|zone| zone.touches >= config.min_touches)
+            .collect();
+        let support = zones.iter().rev().find(|z| z.upper < price).cloned();
+        let resistance = zones.iter().find(|z| z.lower > price).cloned();
+
+        Some(Self {
+            symbol: symbol.to_string(),
+            timeframe,
+            price,
+            swings,
+            zones,
+            support,
+            resistance,
+            analyzed_at: Utc::now(),
+        })
+    }
+
+    /// Zones lying wholly between `from` and `to`, which price has broken through
+    /// moving from one to the other
+    pub fn zones_between(&self, from: Decimal, to: Decimal) -> impl Iterator<Item = &SrZone> {
+        let (low, high) = (from.min(to), from.max(to));
+        self.zones
+            .iter()
+            .filter(move |zone| zone.lower > low && zone.upper < high)
+    }
+}
+
+/// Reads market structure from the in-process candles for exit strategies
+#[derive(Debug)]
+pub struct StructureAnalyzer {
+    candles: Arc<CandleBuilder>,
+    config: StructureConfig,
+}
+
+impl StructureAnalyzer {
+    pub fn new(candles: Arc<CandleBuilder>, config: StructureConfig) -> Self {
+        Self { candles, config }
+    }
+
+    pub fn config(&self) -> &StructureConfig {
+        &self.config
+    }
+
+    pub fn analyze(&self, symbol: &str, price: Decimal) -> Option<MarketStructure> {
+        let candles = self
+            .candles
+            .candles(symbol, self.config.timeframe, self.config.lookback);
+        let atr = self
+            .candles
+            .atr(symbol, self.config.timeframe, self.config.atr_period);
+        MarketStructure::from_candles(symbol, &candles, price, atr, &self.config)
+    }
+}
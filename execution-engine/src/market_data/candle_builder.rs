@@ -0,0 +1,310 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+use tokio::sync::broadcast;
+
+use crate::market_analysis::structure::{fractal_swings, SwingPoint};
+use crate::platforms::abstraction::UnifiedMarketData;
+
+/// Candle width; candles are aligned to UTC, so D1 runs midnight to midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Timeframe {
+    M1,
+    M5,
+    M15,
+    M30,
+    H1,
+    H4,
+    D1,
+}
+
+impl Timeframe {
+    pub const ALL: [Timeframe; 7] = [
+        Timeframe::M1,
+        Timeframe::M5,
+        Timeframe::M15,
+        Timeframe::M30,
+        Timeframe::H1,
+        Timeframe::H4,
+        Timeframe::D1,
+    ];
+
+    pub fn seconds(&self) -> i64 {
+        match self {
+            Timeframe::M1 => 60,
+            Timeframe::M5 => 300,
+            Timeframe::M15 => 900,
+            Timeframe::M30 => 1_800,
+            Timeframe::H1 => 3_600,
+            Timeframe::H4 => 14_400,
+            Timeframe::D1 => 86_400,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::seconds(self.seconds())
+    }
+
+    /// Open time of the candle `at` falls in
+    pub fn bucket_start(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let start = at.timestamp().div_euclid(self.seconds()) * self.seconds();
+        Utc.timestamp_opt(start, 0).unwrap()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    pub symbol: String,
+    pub timeframe: Timeframe,
+    pub open_time: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Decimal,
+    pub ticks: u32,
+}
+
+impl Candle {
+    fn open(symbol: &str, timeframe: Timeframe, at: DateTime<Utc>, price: Decimal) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            timeframe,
+            open_time: timeframe.bucket_start(at),
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: Decimal::ZERO,
+            ticks: 0,
+        }
+    }
+
+    pub fn close_time(&self) -> DateTime<Utc> {
+        self.open_time + self.timeframe.duration()
+    }
+
+    fn update(&mut self, price: Decimal, volume: Decimal) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.volume += volume;
+        self.ticks += 1;
+    }
+
+    /// Largest of the candle's range and its gaps from the previous close
+    fn true_range(&self, previous_close: Option<Decimal>) -> Decimal {
+        let range = self.high - self.low;
+        match previous_close {
+            Some(close) => range
+                .max((self.high - close).abs())
+                .max((self.low - close).abs()),
+            None => range,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CandleConfig {
+    pub enabled: bool,
+    /// Symbols to build candles for, in unified names
+    pub symbols: Vec<String>,
+    pub timeframes: Vec<Timeframe>,
+    /// Closed candles kept per symbol and timeframe
+    pub history: usize,
+    /// Account whose quote stream feeds the candles; unset takes the first
+    /// registered account
+    pub source_account: Option<String>,
+    /// How often candles are closed when their symbol has stopped ticking
+    pub close_check_interval_secs: u64,
+}
+
+impl Default for CandleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            symbols: Vec::new(),
+            timeframes: Timeframe::ALL.to_vec(),
+            history: 500,
+            source_account: None,
+            close_check_interval_secs: 1,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Series {
+    closed: VecDeque<Candle>,
+    current: Option<Candle>,
+}
+
+/// Rolling OHLCV candles per symbol and timeframe, built from quote ticks so
+/// indicators can read price history without calling the platform
+#[derive(Debug)]
+pub struct CandleBuilder {
+    config: CandleConfig,
+    series: RwLock<HashMap<(String, Timeframe), Series>>,
+    closed: broadcast::Sender<Candle>,
+}
+
+impl CandleBuilder {
+    pub fn new(config: CandleConfig) -> Self {
+        let (closed, _) = broadcast::channel(1024);
+        Self {
+            config,
+            series: RwLock::new(HashMap::new()),
+            closed,
+        }
+    }
+
+    pub fn config(&self) -> &CandleConfig {
+        &self.config
+    }
+
+    /// Candles as they close, across every symbol and timeframe
+    pub fn subscribe(&self) -> broadcast::Receiver<Candle> {
+        self.closed.subscribe()
+    }
+
+    /// Add a quote at its mid price
+    pub fn on_quote(&self, quote: &UnifiedMarketData) {
+        self.on_tick(
+            &quote.symbol,
+            (quote.bid + quote.ask) / Decimal::TWO,
+            quote.volume.unwrap_or(Decimal::ZERO),
+            quote.timestamp,
+        );
+    }
+
+    /// Add a trade or quote at `price`. Ticks older than the forming candle are
+    /// ignored.
+    pub fn on_tick(&self, symbol: &str, price: Decimal, volume: Decimal, at: DateTime<Utc>) {
+        let mut closed = Vec::new();
+        {
+            let mut series = self.series.write().unwrap();
+            for timeframe in &self.config.timeframes {
+                let series = series.entry((symbol.to_string(), *timeframe)).or_default();
+                let bucket = timeframe.bucket_start(at);
+                match &mut series.current {
+                    Some(current) if current.open_time == bucket => current.update(price, volume),
+                    Some(current) if current.open_time > bucket => continue,
+                    None if series
+                        .closed
+                        .back()
+                        .is_some_and(|last| last.open_time >= bucket) =>
+                    {
+                        continue
+                    }
+                    _ => {
+                        let mut candle = Candle::open(symbol, *timeframe, at, price);
+                        candle.update(price, volume);
+                        if let Some(previous) = series.current.replace(candle) {
+                            closed.push(previous.clone());
+                            push_bounded(&mut series.closed, previous, self.config.history);
+                        }
+                    }
+                }
+            }
+        }
+        for candle in closed {
+            let _ = self.closed.send(candle);
+        }
+    }
+
+    /// Close every forming candle whose period ended before `now`, for symbols that
+    /// have stopped ticking. Returns the candles closed.
+    pub fn close_elapsed(&self, now: DateTime<Utc>) -> Vec<Candle> {
+        let mut closed = Vec::new();
+        {
+            let mut series = self.series.write().unwrap();
+            for series in series.values_mut() {
+                if series
+                    .current
+                    .as_ref()
+                    .is_some_and(|current| current.close_time() <= now)
+                {
+                    let candle = series.current.take().unwrap();
+                    closed.push(candle.clone());
+                    push_bounded(&mut series.closed, candle, self.config.history);
+                }
+            }
+        }
+        for candle in &closed {
+            let _ = self.closed.send(candle.clone());
+        }
+        closed
+    }
+
+    /// Up to `count` of the most recent closed candles, oldest first
+    pub fn candles(&self, symbol: &str, timeframe: Timeframe, count: usize) -> Vec<Candle> {
+        let series = self.series.read().unwrap();
+        series
+            .get(&(symbol.to_string(), timeframe))
+            .map(|series| {
+                let skip = series.closed.len().saturating_sub(count);
+                series.closed.iter().skip(skip).cloned().collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The candle still forming for `symbol`
+    pub fn current(&self, symbol: &str, timeframe: Timeframe) -> Option<Candle> {
+        let series = self.series.read().unwrap();
+        series
+            .get(&(symbol.to_string(), timeframe))
+            .and_then(|series| series.current.clone())
+    }
+
+    /// Every forming candle, by symbol then timeframe
+    pub fn forming(&self) -> Vec<Candle> {
+        let series = self.series.read().unwrap();
+        let mut forming: Vec<Candle> = series
+            .values()
+            .filter_map(|series| series.current.clone())
+            .collect();
+        forming.sort_by(|a, b| (&a.symbol, a.timeframe).cmp(&(&b.symbol, b.timeframe)));
+        forming
+    }
+
+    /// Average true range over the last `period` closed candles, once there are
+    /// that many
+    pub fn atr(&self, symbol: &str, timeframe: Timeframe, period: usize) -> Option<Decimal> {
+        if period == 0 {
+            return None;
+        }
+        let candles = self.candles(symbol, timeframe, period + 1);
+        if candles.len() < period {
+            return None;
+        }
+        let offset = candles.len() - period;
+        let total: Decimal = (offset..candles.len())
+            .map(|i| {
+                let previous = i.checked_sub(1).map(|p| candles[p].close);
+                candles[i].true_range(previous)
+            })
+            .sum();
+        Some(total / Decimal::from(period))
+    }
+
+    /// Swing highs and lows among the last `lookback` closed candles, oldest first.
+    /// A swing needs `strength` candles on each side that do not reach it.
+    pub fn swing_points(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        lookback: usize,
+        strength: usize,
+    ) -> Vec<SwingPoint> {
+        fractal_swings(&self.candles(symbol, timeframe, lookback), strength)
+    }
+}
+
+fn push_bounded(candles: &mut VecDeque<Candle>, candle: Candle, limit: usize) {
+    candles.push_back(candle);
+    while candles.len() > limit {
+        candles.pop_front();
+    }
+}
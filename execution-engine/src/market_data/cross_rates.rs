@@ -0,0 +1,233 @@
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::platforms::abstraction::models::UnifiedMarketData;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrossRateConfig {
+    pub enabled: bool,
+    /// Currency pairs whose quotes feed the rates, in unified names
+    pub symbols: Vec<String>,
+    /// Account whose quote stream feeds the rates; unset takes the first
+    /// registered account
+    pub source_account: Option<String>,
+    /// Oldest quote a rate is taken from; a derived rate is as old as its oldest leg
+    pub max_age_secs: u64,
+    /// Currencies pairs nobody quotes are derived through, in order of preference
+    pub vehicle_currencies: Vec<String>,
+}
+
+impl Default for CrossRateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            symbols: [
+                "EURUSD", "GBPUSD", "AUDUSD", "NZDUSD", "USDJPY", "USDCHF", "USDCAD",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            source_account: None,
+            max_age_secs: 60,
+            vehicle_currencies: vec!["USD".to_string(), "EUR".to_string()],
+        }
+    }
+}
+
+impl CrossRateConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_age_secs == 0 {
+            return Err("Cross rate max_age_secs must be above 0".to_string());
+        }
+        if let Some(symbol) = self.symbols.iter().find(|s| currency_pair(s).is_none()) {
+            return Err(format!(
+                "Cross rate symbol {} is not a currency pair",
+                symbol
+            ));
+        }
+        if let Some(currency) = self.vehicle_currencies.iter().find(|c| c.len() != 3) {
+            return Err(format!(
+                "Cross rate vehicle currency {} is not a currency code",
+                currency
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CrossRateError {
+    #[error("No rate for {from}/{to}")]
+    Unavailable { from: String, to: String },
+    #[error("Rate for {from}/{to} is {age_secs}s old")]
+    Stale {
+        from: String,
+        to: String,
+        age_secs: i64,
+    },
+}
+
+/// How many units of `to` one unit of `from` buys
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrossRate {
+    pub from: String,
+    pub to: String,
+    pub rate: Decimal,
+    /// Time of the oldest quote the rate was taken from
+    pub as_of: DateTime<Utc>,
+    /// Currency the rate was derived through, when no pair quotes it directly
+    pub via: Option<String>,
+}
+
+/// Base and quote currency of a six-letter pair such as "GBPJPY"
+pub fn currency_pair(symbol: &str) -> Option<(String, String)> {
+    let symbol = symbol.to_uppercase();
+    (symbol.len() == 6 && symbol.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| (symbol[..3].to_string(), symbol[3..].to_string()))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Mid {
+    price: Decimal,
+    at: DateTime<Utc>,
+}
+
+/// Exchange rates between currencies from the mid prices of quoted pairs. Pairs no
+/// platform quotes (GBPJPY from GBPUSD and USDJPY) are derived through the vehicle
+/// currencies. Cheap to clone; clones share their quotes.
+#[derive(Clone)]
+pub struct CrossRates {
+    config: CrossRateConfig,
+    mids: Arc<DashMap<String, Mid>>,
+}
+
+impl CrossRates {
+    pub fn new(config: CrossRateConfig) -> Self {
+        Self {
+            config,
+            mids: Arc::new(DashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &CrossRateConfig {
+        &self.config
+    }
+
+    /// Take the mid of a currency pair quote; other instruments are ignored
+    pub fn on_quote(&self, quote: &UnifiedMarketData) {
+        let Some((base, counter)) = currency_pair(&quote.symbol) else {
+            return;
+        };
+        if quote.bid <= Decimal::ZERO || quote.ask <= Decimal::ZERO {
+            return;
+        }
+        self.mids.insert(
+            format!("{}{}", base, counter),
+            Mid {
+                price: (quote.bid + quote.ask) / Decimal::TWO,
+                at: quote.timestamp,
+            },
+        );
+    }
+
+    pub fn rate(&self, from: &str, to: &str) -> Result<CrossRate, CrossRateError> {
+        self.rate_at(from, to, Utc::now())
+    }
+
+    /// Rate of `from` in `to` as of `now`: quoted directly or inverted when a fresh
+    /// quote has the pair, otherwise derived through the first vehicle currency
+    /// whose legs are both fresh
+    pub fn rate_at(
+        &self,
+        from: &str,
+        to: &str,
+        now: DateTime<Utc>,
+    ) -> Result<CrossRate, CrossRateError> {
+        let (from, to) = (from.to_uppercase(), to.to_uppercase());
+        if from == to {
+            return Ok(CrossRate {
+                from,
+                to,
+                rate: Decimal::ONE,
+                as_of: now,
+                via: None,
+            });
+        }
+
+        let mut freshest_stale: Option<DateTime<Utc>> = None;
+        let mut fresh = |leg: Option<Mid>| match leg {
+            Some(mid) if self.is_fresh(mid.at, now) => Some(mid),
+            Some(mid) => {
+                freshest_stale = Some(freshest_stale.map_or(mid.at, |at| at.max(mid.at)));
+                None
+            }
+            None => None,
+        };
+
+        if let Some(mid) = fresh(self.quoted(&from, &to)) {
+            return Ok(CrossRate {
+                from,
+                to,
+                rate: mid.price,
+                as_of: mid.at,
+                via: None,
+            });
+        }
+        for vehicle in &self.config.vehicle_currencies {
+            let vehicle = vehicle.to_uppercase();
+            if vehicle == from || vehicle == to {
+                continue;
+            }
+            let first = fresh(self.quoted(&from, &vehicle));
+            let second = fresh(self.quoted(&vehicle, &to));
+            if let (Some(first), Some(second)) = (first, second) {
+                return Ok(CrossRate {
+                    from,
+                    to,
+                    rate: first.price * second.price,
+                    as_of: first.at.min(second.at),
+                    via: Some(vehicle),
+                });
+            }
+        }
+
+        Err(match freshest_stale {
+            Some(at) => CrossRateError::Stale {
+                from,
+                to,
+                age_secs: (now - at).num_seconds(),
+            },
+            None => CrossRateError::Unavailable { from, to },
+        })
+    }
+
+    /// `amount` of `from` expressed in `to`
+    pub fn convert(
+        &self,
+        amount: Decimal,
+        from: &str,
+        to: &str,
+    ) -> Result<Decimal, CrossRateError> {
+        Ok(amount * self.rate(from, to)?.rate)
+    }
+
+    fn is_fresh(&self, at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        (now - at).num_seconds() <= self.config.max_age_secs as i64
+    }
+
+    /// Direct rate of `from` in `to` from the pair quoted either way round
+    fn quoted(&self, from: &str, to: &str) -> Option<Mid> {
+        if let Some(mid) = self.mids.get(&format!("{}{}", from, to)) {
+            return Some(*mid);
+        }
+        let inverse = self.mids.get(&format!("{}{}", to, from))?;
+        Some(Mid {
+            price: Decimal::ONE / inverse.price,
+            at: inverse.at,
+        })
+    }
+}
@@ -0,0 +1,8 @@
+// Price history built in-process from the platforms' quote streams
+
+pub mod candle_builder;
+pub mod cross_rates;
+
+pub use crate::market_analysis::{SwingKind, SwingPoint};
+pub use candle_builder::{Candle, CandleBuilder, CandleConfig, Timeframe};
+pub use cross_rates::{CrossRate, CrossRateConfig, CrossRateError, CrossRates};
@@ -1,4 +1,13 @@
 // Messaging integration for event streaming
+pub mod outbox;
+
+pub use outbox::{
+    configured_publisher, ExecutionOutbox, FileOutboxStore, HttpOutboxPublisher, OutboxConfig,
+    OutboxMessage, OutboxPublisher, OutboxRelay, OutboxStore, RelayReport,
+};
+#[cfg(feature = "nats")]
+pub use outbox::NatsOutboxPublisher;
+
 #[cfg(feature = "kafka")]
 pub mod kafka;
 
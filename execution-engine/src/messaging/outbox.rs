@@ -0,0 +1,649 @@
+// Transactional outbox for execution results: every result is persisted before
+// it is published, and a relay retries until the message bus accepts it
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{Mutex, RwLock};
+use tokio::time::{interval, Duration, MissedTickBehavior};
+use tracing::{debug, info, warn};
+
+use crate::execution::ExecutionResult;
+use crate::runtime::supervisor::{ShutdownSignal, Subsystem};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxConfig {
+    pub enabled: bool,
+    pub path: String,
+    pub topic: String,
+    /// How often the relay looks for messages that are due
+    #[serde(default = "default_relay_interval_ms")]
+    pub relay_interval_ms: u64,
+    /// Wait before the first retry of a failed publish; doubles with every failure
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+    /// Endpoint the relay posts results to
+    #[serde(default)]
+    pub publish_url: Option<String>,
+    /// NATS server the relay publishes to through JetStream, with `topic` as the
+    /// subject; takes precedence over `publish_url`. Needs the `nats` feature.
+    #[serde(default)]
+    pub nats_url: Option<String>,
+    /// Most results held at once; beyond it the oldest are dropped so an
+    /// unreachable bus cannot grow the outbox without bound
+    #[serde(default = "default_max_pending")]
+    pub max_pending: usize,
+    /// Published or dropped records appended to the store before it is
+    /// rewritten to just the pending messages
+    #[serde(default = "default_compact_after")]
+    pub compact_after: usize,
+}
+
+fn default_relay_interval_ms() -> u64 {
+    1000
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    500
+}
+
+fn default_max_backoff_secs() -> u64 {
+    60
+}
+
+fn default_max_pending() -> usize {
+    100_000
+}
+
+fn default_compact_after() -> usize {
+    1000
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: "data/execution_outbox.jsonl".to_string(),
+            topic: "execution.results".to_string(),
+            relay_interval_ms: default_relay_interval_ms(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_secs: default_max_backoff_secs(),
+            publish_url: None,
+            nats_url: None,
+            max_pending: default_max_pending(),
+            compact_after: default_compact_after(),
+        }
+    }
+}
+
+/// An execution result waiting to be published
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxMessage {
+    /// Idempotency key, the same on every delivery attempt; consumers drop
+    /// messages whose id they have already seen
+    pub id: String,
+    pub topic: String,
+    /// Partition key, so results for one signal stay in order
+    pub key: String,
+    pub payload: ExecutionResult,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+impl OutboxMessage {
+    /// Unique to one execution of a signal on an account, so executing the
+    /// signal again is not taken for a redelivery
+    pub fn idempotency_key(execution_id: &str, result: &ExecutionResult) -> String {
+        format!(
+            "{}:{}:{}",
+            result.signal_id, result.account_id, execution_id
+        )
+    }
+}
+
+/// Transport the relay publishes through, e.g. Kafka or NATS. Implementations
+/// should pass `message.id` on as the idempotency key.
+#[async_trait]
+pub trait OutboxPublisher: Send + Sync {
+    async fn publish(&self, message: &OutboxMessage) -> Result<()>;
+}
+
+/// The publisher `config` asks for: NATS when `nats_url` is set, otherwise HTTP
+/// when `publish_url` is. None when neither is, as nothing could relay results.
+pub async fn configured_publisher(
+    config: &OutboxConfig,
+) -> Result<Option<Arc<dyn OutboxPublisher>>> {
+    if let Some(url) = &config.nats_url {
+        #[cfg(feature = "nats")]
+        return Ok(Some(Arc::new(NatsOutboxPublisher::connect(url).await?)));
+        #[cfg(not(feature = "nats"))]
+        anyhow::bail!(
+            "Outbox nats_url {} is set but the engine was built without the nats feature",
+            url
+        );
+    }
+    match &config.publish_url {
+        Some(url) => Ok(Some(Arc::new(HttpOutboxPublisher::new(url.clone())?))),
+        None => Ok(None),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum OutboxRecord {
+    Enqueued(OutboxMessage),
+    Published { id: String },
+    Dropped { id: String },
+}
+
+/// Durable storage for the outbox. `load` returns the messages enqueued and
+/// neither published nor dropped, oldest first.
+#[async_trait]
+pub trait OutboxStore: Send + Sync + std::fmt::Debug {
+    async fn enqueue(&self, message: &OutboxMessage) -> Result<()>;
+    async fn mark_published(&self, id: &str) -> Result<()>;
+    /// Give up on a message that was never published
+    async fn mark_dropped(&self, id: &str) -> Result<()>;
+    async fn load(&self) -> Result<Vec<OutboxMessage>>;
+    /// Rewrite the store to hold just `pending`
+    async fn compact(&self, _pending: &[OutboxMessage]) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Append-only JSON lines file of enqueued, published and dropped records,
+/// compacted to the pending messages on load and whenever the outbox asks
+#[derive(Debug)]
+pub struct FileOutboxStore {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl FileOutboxStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    async fn append(&self, record: &OutboxRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .with_context(|| format!("Failed to open outbox {}", self.path.display()))?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Callers hold `write_lock`
+    async fn rewrite(&self, pending: &[OutboxMessage]) -> Result<()> {
+        let mut compacted = String::new();
+        for message in pending {
+            compacted.push_str(&serde_json::to_string(&OutboxRecord::Enqueued(
+                message.clone(),
+            ))?);
+            compacted.push('\n');
+        }
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let tmp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, compacted).await?;
+        tokio::fs::rename(&tmp_path, &self.path).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OutboxStore for FileOutboxStore {
+    async fn enqueue(&self, message: &OutboxMessage) -> Result<()> {
+        self.append(&OutboxRecord::Enqueued(message.clone())).await
+    }
+
+    async fn mark_published(&self, id: &str) -> Result<()> {
+        self.append(&OutboxRecord::Published { id: id.to_string() })
+            .await
+    }
+
+    async fn mark_dropped(&self, id: &str) -> Result<()> {
+        self.append(&OutboxRecord::Dropped { id: id.to_string() })
+            .await
+    }
+
+    async fn load(&self) -> Result<Vec<OutboxMessage>> {
+        let _guard = self.write_lock.lock().await;
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut pending = PendingMessages::default();
+        for (number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<OutboxRecord>(line) {
+                Ok(OutboxRecord::Enqueued(message)) => {
+                    pending.push(message);
+                }
+                Ok(OutboxRecord::Published { id }) | Ok(OutboxRecord::Dropped { id }) => {
+                    pending.remove(&id);
+                }
+                // A torn final line from a crash should not lose the rest of the outbox
+                Err(e) => warn!(
+                    "Skipping unreadable outbox line {} in {}: {}",
+                    number + 1,
+                    self.path.display(),
+                    e
+                ),
+            }
+        }
+
+        let pending: Vec<OutboxMessage> = pending.queue.into();
+        self.rewrite(&pending).await?;
+        Ok(pending)
+    }
+
+    async fn compact(&self, pending: &[OutboxMessage]) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        self.rewrite(pending).await
+    }
+}
+
+/// Pending messages oldest first, indexed by id
+#[derive(Debug, Default)]
+struct PendingMessages {
+    queue: VecDeque<OutboxMessage>,
+    ids: HashSet<String>,
+}
+
+impl PendingMessages {
+    /// False if a message with the same id is already pending
+    fn push(&mut self, message: OutboxMessage) -> bool {
+        if !self.ids.insert(message.id.clone()) {
+            return false;
+        }
+        self.queue.push_back(message);
+        true
+    }
+
+    fn get_mut(&mut self, id: &str) -> Option<&mut OutboxMessage> {
+        if !self.ids.contains(id) {
+            return None;
+        }
+        // Messages leave the outbox oldest first, so this is nearly always the front
+        self.queue.iter_mut().find(|m| m.id == id)
+    }
+
+    fn remove(&mut self, id: &str) -> bool {
+        if !self.ids.remove(id) {
+            return false;
+        }
+        if self.queue.front().is_some_and(|m| m.id == id) {
+            self.queue.pop_front();
+        } else {
+            self.queue.retain(|m| m.id != id);
+        }
+        true
+    }
+
+    fn pop_oldest(&mut self) -> Option<OutboxMessage> {
+        let message = self.queue.pop_front()?;
+        self.ids.remove(&message.id);
+        Some(message)
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+}
+
+/// What one relay pass did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelayReport {
+    pub published: usize,
+    pub failed: usize,
+    /// Messages still waiting after the pass
+    pub pending: usize,
+}
+
+/// Execution results recorded by the orchestrator and waiting for the relay.
+/// A result is persisted before the outbox accepts it and only dropped once the
+/// publisher has acknowledged it, so results are published at least once.
+///
+/// At most `max_pending` results are held; past that the oldest are dropped
+/// with a warning, as a bus that never comes back would otherwise grow the
+/// outbox for as long as the engine runs.
+pub struct ExecutionOutbox {
+    config: OutboxConfig,
+    store: Option<Arc<dyn OutboxStore>>,
+    pending: RwLock<PendingMessages>,
+    /// Held for a whole relay pass so two passes never publish the same message
+    relay_lock: Mutex<()>,
+    /// Published and dropped records appended to the store since it was compacted
+    settled_since_compaction: std::sync::atomic::AtomicUsize,
+}
+
+impl ExecutionOutbox {
+    pub fn new(config: OutboxConfig) -> Self {
+        Self {
+            config,
+            store: None,
+            pending: RwLock::new(PendingMessages::default()),
+            relay_lock: Mutex::new(()),
+            settled_since_compaction: std::sync::atomic::AtomicUsize::new(0),
+        }
+    }
+
+    pub fn with_store(mut self, store: Arc<dyn OutboxStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Restore the messages left unpublished by the previous run. Returns how many.
+    pub async fn load(&self) -> Result<usize> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+        let restored = store.load().await?;
+        let count = restored.len();
+        let mut pending = self.pending.write().await;
+        for message in restored {
+            pending.push(message);
+        }
+        self.enforce_cap(&mut pending).await;
+        if count > 0 {
+            info!("Restored {} unpublished execution results", count);
+        }
+        Ok(count)
+    }
+
+    /// Persist `result` of the execution `execution_id` for publishing.
+    /// Enqueueing the same result twice is a no-op.
+    pub async fn enqueue(
+        &self,
+        execution_id: &str,
+        result: &ExecutionResult,
+    ) -> Result<OutboxMessage> {
+        let id = OutboxMessage::idempotency_key(execution_id, result);
+        let mut pending = self.pending.write().await;
+        if let Some(existing) = pending.get_mut(&id) {
+            return Ok(existing.clone());
+        }
+
+        let now = Utc::now();
+        let message = OutboxMessage {
+            id,
+            topic: self.config.topic.clone(),
+            key: result.signal_id.clone(),
+            payload: result.clone(),
+            created_at: now,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+        };
+        if let Some(store) = &self.store {
+            store.enqueue(&message).await?;
+        }
+        pending.push(message.clone());
+        self.enforce_cap(&mut pending).await;
+        Ok(message)
+    }
+
+    pub async fn pending(&self) -> Vec<OutboxMessage> {
+        self.pending.read().await.queue.iter().cloned().collect()
+    }
+
+    /// Drop the oldest messages beyond `max_pending`
+    async fn enforce_cap(&self, pending: &mut PendingMessages) {
+        let max = self.config.max_pending.max(1);
+        let mut dropped = 0;
+        while pending.len() > max {
+            let Some(message) = pending.pop_oldest() else {
+                break;
+            };
+            if let Some(store) = &self.store {
+                if let Err(e) = store.mark_dropped(&message.id).await {
+                    warn!("Failed to record dropping {}: {}", message.id, e);
+                }
+            }
+            dropped += 1;
+        }
+        if dropped > 0 {
+            warn!(
+                "Outbox over its cap of {}: dropped the {} oldest unpublished execution results",
+                max, dropped
+            );
+            self.settled(dropped);
+        }
+    }
+
+    fn settled(&self, count: usize) {
+        self.settled_since_compaction
+            .fetch_add(count, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Rewrite the store to the pending messages once enough have settled
+    async fn compact_if_due(&self) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        let settled = self
+            .settled_since_compaction
+            .load(std::sync::atomic::Ordering::Relaxed);
+        if settled < self.config.compact_after.max(1) {
+            return;
+        }
+        // Holding the read lock keeps enqueues from appending mid-rewrite
+        let pending = self.pending.read().await;
+        let messages: Vec<OutboxMessage> = pending.queue.iter().cloned().collect();
+        match store.compact(&messages).await {
+            Ok(()) => {
+                self.settled_since_compaction
+                    .fetch_sub(settled, std::sync::atomic::Ordering::Relaxed);
+                debug!("Compacted outbox to {} pending results", messages.len());
+            }
+            Err(e) => warn!("Failed to compact outbox: {}", e),
+        }
+    }
+
+    /// Publish due messages, oldest first. A failure backs the message off and
+    /// ends the pass, and nothing behind it goes until it has been published, so
+    /// results are never published out of order.
+    pub async fn relay(&self, publisher: &dyn OutboxPublisher, now: DateTime<Utc>) -> RelayReport {
+        let _relay = self.relay_lock.lock().await;
+        let due: Vec<OutboxMessage> = self
+            .pending
+            .read()
+            .await
+            .queue
+            .iter()
+            .take_while(|m| m.next_attempt_at <= now)
+            .cloned()
+            .collect();
+
+        let mut report = RelayReport::default();
+        for message in due {
+            match publisher.publish(&message).await {
+                Ok(()) => {
+                    if let Some(store) = &self.store {
+                        // Left pending if this fails; consumers drop the redelivery
+                        if let Err(e) = store.mark_published(&message.id).await {
+                            warn!("Failed to mark {} as published: {}", message.id, e);
+                        }
+                    }
+                    self.pending.write().await.remove(&message.id);
+                    self.settled(1);
+                    debug!("Published execution result {}", message.id);
+                    report.published += 1;
+                }
+                Err(e) => {
+                    let backoff = self.backoff(message.attempts + 1);
+                    if let Some(pending) = self.pending.write().await.get_mut(&message.id) {
+                        pending.attempts += 1;
+                        pending.next_attempt_at = now + backoff;
+                        pending.last_error = Some(e.to_string());
+                    }
+                    warn!(
+                        "Failed to publish execution result {} (attempt {}): {}",
+                        message.id,
+                        message.attempts + 1,
+                        e
+                    );
+                    report.failed += 1;
+                    break;
+                }
+            }
+        }
+        self.compact_if_due().await;
+        report.pending = self.pending.read().await.len();
+        report
+    }
+
+    fn backoff(&self, attempts: u32) -> ChronoDuration {
+        let initial = self.config.initial_backoff_ms.max(1);
+        let max = self.config.max_backoff_secs.saturating_mul(1000);
+        let delay = initial
+            .saturating_mul(1u64 << attempts.saturating_sub(1).min(32))
+            .min(max.max(initial));
+        ChronoDuration::milliseconds(delay as i64)
+    }
+}
+
+/// Keeps publishing the outbox until shutdown, with a last pass on the way out
+pub struct OutboxRelay {
+    outbox: Arc<ExecutionOutbox>,
+    publisher: Arc<dyn OutboxPublisher>,
+}
+
+impl OutboxRelay {
+    pub fn new(outbox: Arc<ExecutionOutbox>, publisher: Arc<dyn OutboxPublisher>) -> Self {
+        Self { outbox, publisher }
+    }
+}
+
+#[async_trait]
+impl Subsystem for OutboxRelay {
+    fn name(&self) -> &str {
+        "outbox-relay"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_millis(
+            self.outbox.config.relay_interval_ms.max(1),
+        ));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    self.outbox.relay(self.publisher.as_ref(), Utc::now()).await;
+                }
+                _ = shutdown.recv() => {
+                    let report = self.outbox.relay(self.publisher.as_ref(), Utc::now()).await;
+                    if report.pending > 0 {
+                        info!(
+                            "{} execution results left in the outbox for the next start",
+                            report.pending
+                        );
+                    }
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Posts each message as JSON to an HTTP endpoint, with its id in the
+/// `Idempotency-Key` header. Only a 2xx response counts as published.
+pub struct HttpOutboxPublisher {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpOutboxPublisher {
+    pub fn new(url: String) -> Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()?,
+            url,
+        })
+    }
+}
+
+#[async_trait]
+impl OutboxPublisher for HttpOutboxPublisher {
+    async fn publish(&self, message: &OutboxMessage) -> Result<()> {
+        self.client
+            .post(&self.url)
+            .header("Idempotency-Key", &message.id)
+            .json(message)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Publishes each message to JetStream on the outbox topic, with its id as the
+/// `Nats-Msg-Id` header so the stream drops redeliveries inside its duplicate
+/// window. Only a stream acknowledgement counts as published.
+#[cfg(feature = "nats")]
+pub struct NatsOutboxPublisher {
+    jetstream: async_nats::jetstream::Context,
+}
+
+#[cfg(feature = "nats")]
+impl NatsOutboxPublisher {
+    /// Returns without waiting for the server; until it is reachable publishes
+    /// fail and the relay backs off
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = async_nats::ConnectOptions::new()
+            .retry_on_initial_connect()
+            .connect(url)
+            .await
+            .with_context(|| format!("Failed to connect to NATS at {}", url))?;
+        Ok(Self {
+            jetstream: async_nats::jetstream::new(client),
+        })
+    }
+}
+
+#[cfg(feature = "nats")]
+#[async_trait]
+impl OutboxPublisher for NatsOutboxPublisher {
+    async fn publish(&self, message: &OutboxMessage) -> Result<()> {
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("Nats-Msg-Id", message.id.as_str());
+        let payload = serde_json::to_vec(message)?;
+        self.jetstream
+            .publish_with_headers(message.topic.clone(), headers, payload.into())
+            .await?
+            .await?;
+        Ok(())
+    }
+}
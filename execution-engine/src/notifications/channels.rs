@@ -0,0 +1,145 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::Notification;
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Telegram rejects messages longer than this
+const TELEGRAM_MAX_CHARS: usize = 4096;
+/// Discord rejects webhook content longer than this
+const DISCORD_MAX_CHARS: usize = 2000;
+
+/// A chat destination for notifications
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    async fn send(&self, notification: &Notification) -> Result<()>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChannelTarget {
+    Telegram {
+        bot_token: String,
+        chat_id: String,
+        #[serde(default = "default_telegram_api_url")]
+        api_url: String,
+    },
+    Discord {
+        webhook_url: String,
+        #[serde(default)]
+        username: Option<String>,
+    },
+}
+
+fn default_telegram_api_url() -> String {
+    "https://api.telegram.org".to_string()
+}
+
+impl ChannelTarget {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ChannelTarget::Telegram {
+                bot_token, chat_id, ..
+            } if bot_token.is_empty() || chat_id.is_empty() => {
+                Err("telegram channel needs a bot token and chat id".to_string())
+            }
+            ChannelTarget::Telegram { api_url: url, .. }
+            | ChannelTarget::Discord {
+                webhook_url: url, ..
+            } => url
+                .parse::<reqwest::Url>()
+                .map(|_| ())
+                .map_err(|e| format!("invalid url {}: {}", url, e)),
+        }
+    }
+
+    pub fn build(&self) -> Result<Arc<dyn NotificationChannel>> {
+        let client = Client::builder().timeout(DELIVERY_TIMEOUT).build()?;
+        Ok(match self.clone() {
+            ChannelTarget::Telegram {
+                bot_token,
+                chat_id,
+                api_url,
+            } => Arc::new(TelegramChannel {
+                client,
+                url: format!(
+                    "{}/bot{}/sendMessage",
+                    api_url.trim_end_matches('/'),
+                    bot_token
+                ),
+                chat_id,
+            }),
+            ChannelTarget::Discord {
+                webhook_url,
+                username,
+            } => Arc::new(DiscordChannel {
+                client,
+                webhook_url,
+                username,
+            }),
+        })
+    }
+}
+
+/// Cut `text` to at most `max` characters, marking the cut
+fn truncate(text: String, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text;
+    }
+    let mut truncated: String = text.chars().take(max - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+pub struct TelegramChannel {
+    client: Client,
+    url: String,
+    chat_id: String,
+}
+
+#[async_trait]
+impl NotificationChannel for TelegramChannel {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        self.client
+            .post(&self.url)
+            .json(&json!({
+                "chat_id": self.chat_id,
+                "text": truncate(notification.text(), TELEGRAM_MAX_CHARS),
+                "disable_web_page_preview": true,
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+pub struct DiscordChannel {
+    client: Client,
+    webhook_url: String,
+    username: Option<String>,
+}
+
+#[async_trait]
+impl NotificationChannel for DiscordChannel {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let mut payload = json!({
+            "content": truncate(notification.text(), DISCORD_MAX_CHARS),
+        });
+        if let Some(username) = &self.username {
+            payload["username"] = json!(username);
+        }
+        self.client
+            .post(&self.webhook_url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
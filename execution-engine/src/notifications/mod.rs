@@ -0,0 +1,407 @@
+pub mod channels;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+pub use channels::{ChannelTarget, DiscordChannel, NotificationChannel, TelegramChannel};
+
+use crate::alerting::{AlertNotification, AlertSink};
+use crate::execution::EmergencyCloseResult;
+use crate::platforms::abstraction::events::{EventData, EventType, RiskType};
+use crate::platforms::abstraction::{PlatformEvent, UnifiedEventBus};
+use crate::runtime::spawn_isolated;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationTopic {
+    /// Entry fills and position closes
+    Fill,
+    EmergencyClose,
+    MarginAlert,
+    /// Risk alerts other than margin
+    RiskAlert,
+    DailySummary,
+}
+
+/// A message for the chat channels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub topic: NotificationTopic,
+    pub account_id: Option<String>,
+    pub title: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Notification {
+    pub fn new(
+        topic: NotificationTopic,
+        title: impl Into<String>,
+        body: impl Into<String>,
+    ) -> Self {
+        Self {
+            topic,
+            account_id: None,
+            title: title.into(),
+            body: body.into(),
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn for_account(mut self, account_id: impl Into<String>) -> Self {
+        self.account_id = Some(account_id.into());
+        self
+    }
+
+    pub fn at(mut self, created_at: DateTime<Utc>) -> Self {
+        self.created_at = created_at;
+        self
+    }
+
+    /// Plain text rendering shared by every channel
+    pub fn text(&self) -> String {
+        match &self.account_id {
+            Some(account_id) => format!("{} [{}]\n{}", self.title, account_id, self.body),
+            None => format!("{}\n{}", self.title, self.body),
+        }
+    }
+
+    /// Notification for the platform events worth a message; `None` for the rest
+    pub fn from_platform_event(event: &PlatformEvent) -> Option<Self> {
+        let notification = match (&event.event_type, &event.data) {
+            (EventType::OrderFilled | EventType::OrderPartiallyFilled, EventData::Order(data)) => {
+                let order = &data.order;
+                let title = if event.event_type == EventType::OrderFilled {
+                    "Order filled"
+                } else {
+                    "Order partially filled"
+                };
+                let quantity = data.fill_quantity.unwrap_or(order.filled_quantity);
+                let price = data
+                    .fill_price
+                    .or(order.average_fill_price)
+                    .or(order.price)
+                    .map(|price| format!(" @ {}", price))
+                    .unwrap_or_default();
+                Self::new(
+                    NotificationTopic::Fill,
+                    title,
+                    format!(
+                        "{} {} {}{}",
+                        format!("{:?}", order.side).to_uppercase(),
+                        quantity,
+                        order.symbol,
+                        price
+                    ),
+                )
+            }
+            (_, EventData::PositionClose(close)) => {
+                let title = if close.is_full_close() {
+                    "Position closed"
+                } else {
+                    "Position reduced"
+                };
+                Self::new(
+                    NotificationTopic::Fill,
+                    title,
+                    format!(
+                        "{:?} {} {} closed @ {}, P&L {}",
+                        close.side,
+                        close.closed_quantity,
+                        close.symbol,
+                        close.close_price,
+                        close.realized_pnl.round_dp(2)
+                    ),
+                )
+            }
+            (EventType::MarginCallTriggered | EventType::PositionMarginCall, _) => Self::new(
+                NotificationTopic::MarginAlert,
+                "Margin call",
+                risk_detail(event),
+            ),
+            (EventType::PositionStopOut | EventType::StopOutTriggered, _) => Self::new(
+                NotificationTopic::MarginAlert,
+                "Stop out",
+                risk_detail(event),
+            ),
+            (_, EventData::Risk(data)) => {
+                let topic = match data.risk_type {
+                    RiskType::MarginLevel => NotificationTopic::MarginAlert,
+                    _ => NotificationTopic::RiskAlert,
+                };
+                Self::new(
+                    topic,
+                    format!("{:?} limit breached", data.risk_type),
+                    risk_detail(event),
+                )
+            }
+            _ => return None,
+        };
+        Some(
+            notification
+                .for_account(event.account_id.clone())
+                .at(event.timestamp),
+        )
+    }
+
+    pub fn emergency_close(
+        account_id: Option<&str>,
+        reason: &str,
+        results: &[EmergencyCloseResult],
+    ) -> Self {
+        let mut body = format!(
+            "{}: {} of {} positions closed",
+            reason,
+            results.iter().filter(|r| r.success).count(),
+            results.len()
+        );
+        for failed in results.iter().filter(|r| !r.success) {
+            body.push_str(&format!(
+                "\nFailed {} {}: {}",
+                failed.account_id,
+                failed.symbol,
+                failed.error_message.as_deref().unwrap_or("unknown error")
+            ));
+        }
+        let notification = Self::new(NotificationTopic::EmergencyClose, "Emergency close", body);
+        match account_id {
+            Some(account_id) => notification.for_account(account_id),
+            None => notification,
+        }
+    }
+
+    pub fn from_alert(notification: &AlertNotification) -> Self {
+        let alert = &notification.alert;
+        let topic = if alert.alert_type == "margin" {
+            NotificationTopic::MarginAlert
+        } else {
+            NotificationTopic::RiskAlert
+        };
+        let message =
+            Self::new(topic, notification.title(), alert.message.clone()).at(alert.raised_at);
+        match alert.account_id {
+            Some(account_id) => message.for_account(account_id.to_string()),
+            None => message,
+        }
+    }
+}
+
+fn risk_detail(event: &PlatformEvent) -> String {
+    match &event.data {
+        EventData::Risk(data) => {
+            let mut detail = format!(
+                "{:?}: {} against a limit of {}",
+                data.severity, data.current_value, data.limit_value
+            );
+            if !data.affected_positions.is_empty() {
+                detail.push_str(&format!(
+                    "\nAffected positions: {}",
+                    data.affected_positions.join(", ")
+                ));
+            }
+            detail
+        }
+        EventData::Account(data) => format!(
+            "Margin level {}, equity {}",
+            data.account_info.margin_level.unwrap_or_default(),
+            data.account_info.equity
+        ),
+        _ => format!("{:?}", event.event_type),
+    }
+}
+
+/// Time of day, in a channel's timezone, when only urgent topics get through
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    /// IANA name such as "America/New_York"; daylight saving is followed
+    #[serde(default = "default_timezone")]
+    pub timezone: Tz,
+    /// Topics still delivered during quiet hours
+    #[serde(default = "default_quiet_hours_allow")]
+    pub allow: Vec<NotificationTopic>,
+}
+
+fn default_timezone() -> Tz {
+    Tz::UTC
+}
+
+fn default_quiet_hours_allow() -> Vec<NotificationTopic> {
+    vec![NotificationTopic::EmergencyClose]
+}
+
+impl QuietHours {
+    /// Whether `at` falls inside the window; a start after the end spans midnight
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let local = at.with_timezone(&self.timezone).time();
+        if self.start <= self.end {
+            local >= self.start && local < self.end
+        } else {
+            local >= self.start || local < self.end
+        }
+    }
+}
+
+/// Which notifications a channel receives
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelRules {
+    /// Topics sent to the channel; empty sends every topic
+    #[serde(default)]
+    pub topics: Vec<NotificationTopic>,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl ChannelRules {
+    pub fn accepts(&self, notification: &Notification) -> bool {
+        if !self.topics.is_empty() && !self.topics.contains(&notification.topic) {
+            return false;
+        }
+        match &self.quiet_hours {
+            Some(quiet) if quiet.contains(notification.created_at) => {
+                quiet.allow.contains(&notification.topic)
+            }
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelConfig {
+    #[serde(flatten)]
+    pub target: ChannelTarget,
+    #[serde(flatten)]
+    pub rules: ChannelRules,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    #[serde(default)]
+    pub channels: HashMap<String, ChannelConfig>,
+}
+
+impl NotificationsConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        self.channels.iter().try_for_each(|(name, channel)| {
+            channel
+                .target
+                .validate()
+                .map_err(|e| format!("Notification channel {}: {}", name, e))
+        })
+    }
+}
+
+struct RegisteredChannel {
+    name: String,
+    rules: ChannelRules,
+    channel: Arc<dyn NotificationChannel>,
+}
+
+/// Sends trade and risk events to Telegram and Discord channels, each with its own
+/// topic filter and quiet hours
+pub struct Notifier {
+    channels: Vec<RegisteredChannel>,
+}
+
+impl Notifier {
+    pub fn new() -> Self {
+        Self {
+            channels: Vec::new(),
+        }
+    }
+
+    pub fn from_config(config: &NotificationsConfig) -> Result<Self> {
+        let mut names: Vec<&String> = config.channels.keys().collect();
+        names.sort();
+        names.into_iter().try_fold(Self::new(), |notifier, name| {
+            let channel = &config.channels[name];
+            channel.target.validate().map_err(anyhow::Error::msg)?;
+            Ok(notifier.with_channel(name, channel.rules.clone(), channel.target.build()?))
+        })
+    }
+
+    pub fn with_channel(
+        mut self,
+        name: &str,
+        rules: ChannelRules,
+        channel: Arc<dyn NotificationChannel>,
+    ) -> Self {
+        self.channels.push(RegisteredChannel {
+            name: name.to_string(),
+            rules,
+            channel,
+        });
+        self
+    }
+
+    /// Send to every channel accepting the notification. Returns how many got it.
+    pub async fn notify(&self, notification: &Notification) -> usize {
+        let sends =
+            self.channels
+                .iter()
+                .filter(|registered| registered.rules.accepts(notification))
+                .map(|registered| async move {
+                    (registered, registered.channel.send(notification).await)
+                });
+
+        let mut delivered = 0;
+        for (registered, result) in join_all(sends).await {
+            match result {
+                Ok(()) => delivered += 1,
+                Err(e) => warn!(
+                    "Failed to send {:?} notification to {}: {}",
+                    notification.topic, registered.name, e
+                ),
+            }
+        }
+        debug!(
+            "{:?} notification sent to {} channels",
+            notification.topic, delivered
+        );
+        delivered
+    }
+
+    pub async fn handle_platform_event(&self, event: &PlatformEvent) {
+        if let Some(notification) = Notification::from_platform_event(event) {
+            self.notify(&notification).await;
+        }
+    }
+
+    /// Follow `bus` until it shuts down, notifying on every relevant event
+    pub fn subscribe(
+        self: &Arc<Self>,
+        bus: &mut UnifiedEventBus,
+    ) -> JoinHandle<Result<(), String>> {
+        let mut events = bus.subscribe();
+        let notifier = self.clone();
+        spawn_isolated("notifications", async move {
+            while let Some(event) = events.recv().await {
+                notifier.handle_platform_event(&event).await;
+            }
+        })
+    }
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lets the alert gateway route deduplicated risk alerts to the chat channels
+#[async_trait]
+impl AlertSink for Notifier {
+    async fn deliver(&self, notification: &AlertNotification) -> Result<()> {
+        self.notify(&Notification::from_alert(notification)).await;
+        Ok(())
+    }
+}
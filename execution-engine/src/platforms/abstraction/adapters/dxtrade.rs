@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use tokio::sync::mpsc;
 use std::collections::HashMap;
+use std::result::Result;
 
 use crate::platforms::{PlatformType, dxtrade::*};
 use super::super::interfaces::*;
@@ -10,41 +11,83 @@ use super::super::events::*;
 use super::super::capabilities::*;
 use super::{BaseAdapter, PlatformAdapter, AdapterInfo, PerformanceCharacteristics};
 use super::conversion_utils::*;
-use super::super::factory::RetryConfig;
+use super::super::retry::RetryConfig;
 
 /// DXTrade platform adapter implementing the unified interface
 pub struct DXTradeAdapter {
     client: DXTradeClient,
     base: BaseAdapter,
-    event_sender: Option<mpsc::UnboundedSender<PlatformEvent>>,
+    event_subscribers: std::sync::Mutex<Vec<mpsc::Sender<PlatformEvent>>>,
     capabilities: PlatformCapabilities,
     account_id: String,
 }
 
 impl DXTradeAdapter {
     pub fn new(client: DXTradeClient, retry_config: RetryConfig) -> Self {
-        // Extract account identifier from client
-        let account_id = "dxtrade_account".to_string(); // This would come from client configuration
-        
+        let account_id = client.account_id().to_string();
+
         Self {
             client,
             base: BaseAdapter::new(retry_config),
-            event_sender: None,
+            event_subscribers: std::sync::Mutex::new(Vec::new()),
             capabilities: dxtrade_capabilities(),
             account_id,
         }
     }
 
+    /// Hedging-mode account: every fill opens its own position and closes target
+    /// positions by id instead of netting per symbol
+    pub fn with_hedging(mut self) -> Self {
+        self.capabilities.features.remove(&PlatformFeature::NetPositions);
+        self.capabilities.features.insert(PlatformFeature::HedgedPositions);
+        self
+    }
+
+    /// The FIX session the adapter trades through, once connected
+    pub async fn fix_session(&self) -> Option<std::sync::Arc<FIXSession>> {
+        self.client.fix_session().await
+    }
+
+    /// Log the FIX session out and mark the adapter disconnected. Unlike
+    /// `disconnect` this works on an adapter shared behind an `Arc`.
+    pub async fn close(&self) -> Result<(), PlatformError> {
+        self.base.increment_operation_count();
+        
+        let result = self.client.disconnect().await.map_err(|e| {
+            PlatformError::ConnectionFailed { reason: e.to_string() }
+        });
+
+        self.base.set_connected(false);
+        
+        self.emit_event(
+            EventType::ConnectionLost,
+            EventData::Connection(ConnectionEventData {
+                status: ConnectionStatus::Disconnected,
+                reason: Some("Manual disconnect".to_string()),
+                server_info: None,
+                latency_ms: None,
+            }),
+        ).await;
+        
+        result
+    }
+
+    fn is_hedging(&self) -> bool {
+        self.capabilities.supports_feature(PlatformFeature::HedgedPositions)
+    }
+
     async fn emit_event(&self, event_type: EventType, data: EventData) {
-        if let Some(sender) = &self.event_sender {
-            let event = PlatformEvent::new(
-                event_type,
-                PlatformType::DXTrade,
-                self.account_id.clone(),
-                data,
-            );
-            let _ = sender.send(event);
-        }
+        let event = PlatformEvent::new(
+            event_type,
+            PlatformType::DXTrade,
+            self.account_id.clone(),
+            data,
+        );
+        // A subscriber that stopped reading loses events rather than stalling the adapter
+        self.event_subscribers
+            .lock()
+            .unwrap()
+            .retain(|sender| !matches!(sender.try_send(event.clone()), Err(mpsc::error::TrySendError::Closed(_))));
     }
 
     fn convert_order_to_unified(&self, order: DXTradeOrderResponse) -> UnifiedOrderResponse {
@@ -112,6 +155,48 @@ impl DXTradeAdapter {
         }
     }
 
+    fn convert_transaction_to_unified(&self, transaction: DXTradeTransaction) -> UnifiedTransaction {
+        let mut platform_specific = HashMap::new();
+        platform_specific.insert("dxtrade_account_id".to_string(), serde_json::Value::String(transaction.account_id));
+
+        UnifiedTransaction {
+            transaction_id: transaction.transaction_id,
+            account_id: self.account_id.clone(),
+            kind: convert_dx_transaction_kind(transaction.transaction_type),
+            symbol: transaction.symbol,
+            order_id: transaction.order_id,
+            position_id: transaction.position_id,
+            side: transaction.side.map(convert_dx_order_side),
+            quantity: transaction.quantity,
+            price: transaction.price,
+            commission: transaction.commission,
+            amount: transaction.amount,
+            currency: transaction.currency,
+            balance_after: transaction.balance,
+            timestamp: transaction.transaction_time,
+            description: transaction.comment.unwrap_or_default(),
+            platform_specific,
+        }
+    }
+
+    fn convert_instrument_to_unified(&self, instrument: DXTradeInstrument) -> Symbol {
+        Symbol {
+            instrument_type: convert_instrument_type(instrument.instrument_type.as_deref()),
+            symbol: instrument.symbol,
+            description: instrument.description,
+            base_currency: instrument.base_currency,
+            quote_currency: instrument.quote_currency,
+            min_trade_size: instrument.min_quantity,
+            max_trade_size: instrument.max_quantity,
+            tick_size: instrument.tick_size,
+            contract_size: instrument.contract_size,
+            trading_hours: Vec::new(),
+            is_tradeable: instrument.tradable,
+            stops_level: None,
+            freeze_level: None,
+        }
+    }
+
     fn convert_market_data_to_unified(&self, data: DXTradeMarketData) -> UnifiedMarketData {
         UnifiedMarketData {
             symbol: data.symbol,
@@ -129,12 +214,12 @@ impl DXTradeAdapter {
     }
 
     fn convert_unified_order_request(&self, order: UnifiedOrder) -> Result<DXTradeOrderRequest, PlatformError> {
-        let order_type = convert_to_dx_order_type(order.order_type)
+        let order_type = convert_to_dx_order_type(order.order_type.clone())
             .ok_or_else(|| PlatformError::FeatureNotSupported {
                 feature: format!("Order type {:?}", order.order_type)
             })?;
 
-        let time_in_force = convert_to_dx_time_in_force(order.time_in_force)
+        let time_in_force = convert_to_dx_time_in_force(order.time_in_force.clone())
             .ok_or_else(|| PlatformError::FeatureNotSupported {
                 feature: format!("Time in force {:?}", order.time_in_force)
             })?;
@@ -149,8 +234,87 @@ impl DXTradeAdapter {
             time_in_force,
             client_order_id: order.client_order_id,
             account_id: order.account_id.unwrap_or_else(|| self.account_id.clone()),
+            position_id: None,
         })
     }
+
+    async fn submit_order(&self, dx_order: DXTradeOrderRequest) -> Result<UnifiedOrderResponse, PlatformError> {
+        let result = self.base.retry_handler().execute_with_retry(|| async {
+            self.client.place_order(dx_order.clone()).await.map_err(|e| {
+                PlatformError::OrderRejected { 
+                    reason: e.to_string(),
+                    platform_code: None,
+                }
+            })
+        }).await;
+
+        match result {
+            Ok(response) => {
+                let unified_response = self.convert_order_to_unified(response);
+                
+                self.emit_event(
+                    EventType::OrderPlaced,
+                    EventData::Order(OrderEventData {
+                        order: unified_response.clone(),
+                        previous_status: None,
+                        fill_price: None,
+                        fill_quantity: None,
+                        remaining_quantity: Some(unified_response.remaining_quantity),
+                        rejection_reason: None,
+                    }),
+                ).await;
+                
+                Ok(unified_response)
+            }
+            Err(e) => {
+                self.base.increment_error_count();
+                Err(e)
+            }
+        }
+    }
+
+    /// Close all or part of one position; on a hedging account the close order
+    /// names the position so it does not open an opposite ticket instead
+    async fn close_ticket(&self, position: &UnifiedPosition, quantity: Option<rust_decimal::Decimal>) -> Result<UnifiedOrderResponse, PlatformError> {
+        let close_side = match position.side {
+            UnifiedPositionSide::Long => UnifiedOrderSide::Sell,
+            UnifiedPositionSide::Short => UnifiedOrderSide::Buy,
+        };
+
+        let close_order = UnifiedOrder {
+            client_order_id: format!("close_{}", uuid::Uuid::new_v4()),
+            symbol: position.symbol.clone(),
+            side: close_side,
+            order_type: UnifiedOrderType::Market,
+            quantity: quantity.unwrap_or(position.quantity),
+            price: None,
+            stop_price: None,
+            take_profit: None,
+            stop_loss: None,
+            time_in_force: UnifiedTimeInForce::Ioc,
+            account_id: Some(self.account_id.clone()),
+            metadata: OrderMetadata {
+                strategy_id: None,
+                signal_id: None,
+                risk_parameters: HashMap::new(),
+                tags: vec!["position_close".to_string()],
+                expires_at: None,
+            },
+            max_slippage: None,
+        };
+
+        let mut dx_order = self.convert_unified_order_request(close_order)?;
+        if self.is_hedging() {
+            dx_order.position_id = Some(position.position_id.clone());
+        }
+        let response = self.submit_order(dx_order).await?;
+
+        if let Some(close) = PositionCloseEventData::from_close_fill(position, &response) {
+            self.emit_event(close.event_type(), EventData::PositionClose(close)).await;
+        }
+
+        Ok(response)
+    }
 }
 
 #[async_trait]
@@ -208,25 +372,7 @@ impl ITradingPlatform for DXTradeAdapter {
     }
 
     async fn disconnect(&mut self) -> Result<(), PlatformError> {
-        self.base.increment_operation_count();
-        
-        let result = self.client.disconnect().await.map_err(|e| {
-            PlatformError::ConnectionFailed { reason: e.to_string() }
-        });
-
-        self.base.set_connected(false);
-        
-        self.emit_event(
-            EventType::ConnectionLost,
-            EventData::Connection(ConnectionEventData {
-                status: ConnectionStatus::Disconnected,
-                reason: Some("Manual disconnect".to_string()),
-                server_info: None,
-                latency_ms: None,
-            }),
-        ).await;
-        
-        result
+        self.close().await
     }
 
     async fn is_connected(&self) -> bool {
@@ -258,39 +404,7 @@ impl ITradingPlatform for DXTradeAdapter {
         self.base.increment_operation_count();
         
         let dx_order = self.convert_unified_order_request(order)?;
-        
-        let result = self.base.retry_handler().execute_with_retry(|| async {
-            self.client.place_order(dx_order.clone()).await.map_err(|e| {
-                PlatformError::OrderRejected { 
-                    reason: e.to_string(),
-                    platform_code: None,
-                }
-            })
-        }).await;
-
-        match result {
-            Ok(response) => {
-                let unified_response = self.convert_order_to_unified(response);
-                
-                self.emit_event(
-                    EventType::OrderPlaced,
-                    EventData::Order(OrderEventData {
-                        order: unified_response.clone(),
-                        previous_status: None,
-                        fill_price: None,
-                        fill_quantity: None,
-                        remaining_quantity: Some(unified_response.remaining_quantity),
-                        rejection_reason: None,
-                    }),
-                ).await;
-                
-                Ok(unified_response)
-            }
-            Err(e) => {
-                self.base.increment_error_count();
-                Err(e)
-            }
-        }
+        self.submit_order(dx_order).await
     }
 
     async fn modify_order(&self, order_id: &str, modifications: OrderModification) -> Result<UnifiedOrderResponse, PlatformError> {
@@ -440,45 +554,34 @@ impl ITradingPlatform for DXTradeAdapter {
     }
 
     async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
-        let positions = self.get_positions().await?;
-        Ok(positions.into_iter().find(|p| p.symbol == symbol))
+        Ok(self.get_position_tickets(symbol).await?.net())
     }
 
     async fn close_position(&self, symbol: &str, quantity: Option<rust_decimal::Decimal>) -> Result<UnifiedOrderResponse, PlatformError> {
         self.base.increment_operation_count();
         
-        // Get current position to determine close parameters
-        let position = self.get_position(symbol).await?
-            .ok_or_else(|| PlatformError::PositionNotFound { symbol: symbol.to_string() })?;
+        let tickets = self.get_position_tickets(symbol).await?.tickets;
+        if tickets.len() > 1 && quantity.is_some() {
+            return Err(PlatformError::PositionCloseFailed {
+                reason: format!("{} has {} open tickets; close part of one by ticket", symbol, tickets.len()),
+            });
+        }
 
-        let close_quantity = quantity.unwrap_or(position.quantity);
-        let close_side = match position.side {
-            UnifiedPositionSide::Long => UnifiedOrderSide::Sell,
-            UnifiedPositionSide::Short => UnifiedOrderSide::Buy,
-        };
+        let mut response = None;
+        for ticket in &tickets {
+            response = Some(self.close_ticket(ticket, quantity).await?);
+        }
+        response.ok_or_else(|| PlatformError::PositionNotFound { symbol: symbol.to_string() })
+    }
 
-        let close_order = UnifiedOrder {
-            client_order_id: format!("close_{}", uuid::Uuid::new_v4()),
-            symbol: symbol.to_string(),
-            side: close_side,
-            order_type: UnifiedOrderType::Market,
-            quantity: close_quantity,
-            price: None,
-            stop_price: None,
-            take_profit: None,
-            stop_loss: None,
-            time_in_force: UnifiedTimeInForce::Ioc,
-            account_id: Some(self.account_id.clone()),
-            metadata: OrderMetadata {
-                strategy_id: None,
-                signal_id: None,
-                risk_parameters: HashMap::new(),
-                tags: vec!["position_close".to_string()],
-                expires_at: None,
-            },
-        };
+    async fn close_position_ticket(&self, position_id: &str, quantity: Option<rust_decimal::Decimal>) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.base.increment_operation_count();
 
-        self.place_order(close_order).await
+        let position = self.get_positions().await?
+            .into_iter()
+            .find(|p| p.position_id == position_id)
+            .ok_or_else(|| PlatformError::TicketNotFound { position_id: position_id.to_string() })?;
+        self.close_ticket(&position, quantity).await
     }
 
     async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
@@ -515,6 +618,53 @@ impl ITradingPlatform for DXTradeAdapter {
         })
     }
 
+    async fn get_transactions(&self, range: TransactionRange) -> Result<Vec<UnifiedTransaction>, PlatformError> {
+        self.base.increment_operation_count();
+
+        let result = self.base.retry_handler().execute_with_retry(|| async {
+            self.client.get_transactions(range.from, range.to).await.map_err(|e| {
+                PlatformError::InternalError { reason: e.to_string() }
+            })
+        }).await;
+
+        match result {
+            Ok(transactions) => Ok(transactions.into_iter()
+                .filter(|t| range.contains(t.transaction_time))
+                .map(|t| self.convert_transaction_to_unified(t))
+                .collect()),
+            Err(e) => {
+                self.base.increment_error_count();
+                Err(e)
+            }
+        }
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<Symbol>, PlatformError> {
+        self.base.increment_operation_count();
+
+        let result = self.base.retry_handler().execute_with_retry(|| async {
+            self.client.get_instruments().await.map_err(|e| {
+                PlatformError::MarketDataUnavailable { reason: e.to_string() }
+            })
+        }).await;
+
+        match result {
+            Ok(instruments) => Ok(instruments.into_iter()
+                .map(|i| self.convert_instrument_to_unified(i))
+                .collect()),
+            Err(e) => {
+                self.base.increment_error_count();
+                Err(e)
+            }
+        }
+    }
+
+    async fn get_server_time(&self) -> Result<chrono::DateTime<chrono::Utc>, PlatformError> {
+        self.client.server_time().await.map_err(|e| {
+            PlatformError::NetworkError { reason: e.to_string() }
+        })
+    }
+
     async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
         self.base.increment_operation_count();
         
@@ -549,9 +699,8 @@ impl ITradingPlatform for DXTradeAdapter {
     }
 
     async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
-        let (tx, rx) = mpsc::unbounded_channel();
-        // Store the sender for event emission
-        // Note: This is simplified - in a real implementation, you'd need interior mutability
+        let (tx, rx) = mpsc::channel(1024);
+        self.event_subscribers.lock().unwrap().push(tx);
         Ok(rx)
     }
 
@@ -5,68 +5,10 @@ pub use tradelocker::TradeLockerAdapter;
 pub use dxtrade::DXTradeAdapter;
 
 use async_trait::async_trait;
-use std::time::Duration;
-use tokio::time::sleep;
 
 use super::errors::PlatformError;
-use super::factory::RetryConfig;
-
-/// Retry logic utility for platform operations
-pub struct RetryHandler {
-    config: RetryConfig,
-}
-
-impl RetryHandler {
-    pub fn new(config: RetryConfig) -> Self {
-        Self { config }
-    }
-
-    pub async fn execute_with_retry<T, F, Fut>(&self, mut operation: F) -> Result<T, PlatformError>
-    where
-        F: FnMut() -> Fut,
-        Fut: std::future::Future<Output = Result<T, PlatformError>>,
-    {
-        let mut attempt = 0;
-        let mut delay = self.config.initial_delay_ms;
-
-        loop {
-            match operation().await {
-                Ok(result) => return Ok(result),
-                Err(error) => {
-                    attempt += 1;
-
-                    // Check if error is recoverable and we haven't exceeded max retries
-                    if !error.is_recoverable() || attempt > self.config.max_retries {
-                        return Err(error);
-                    }
-
-                    // Calculate delay with exponential backoff
-                    let actual_delay = if self.config.jitter {
-                        self.add_jitter(delay)
-                    } else {
-                        delay
-                    };
-
-                    // Sleep before retry
-                    sleep(Duration::from_millis(actual_delay)).await;
-
-                    // Update delay for next attempt
-                    delay = (delay as f64 * self.config.backoff_multiplier) as u64;
-                    if delay > self.config.max_delay_ms {
-                        delay = self.config.max_delay_ms;
-                    }
-                }
-            }
-        }
-    }
-
-    fn add_jitter(&self, delay_ms: u64) -> u64 {
-        use rand::Rng;
-        let jitter_range = (delay_ms as f64 * 0.1) as u64; // 10% jitter
-        let mut rng = rand::thread_rng();
-        delay_ms + rng.gen_range(0..=jitter_range)
-    }
-}
+use super::retry::RetryConfig;
+pub use super::retry::RetryHandler;
 
 /// Common adapter functionality
 #[async_trait]
@@ -97,8 +39,8 @@ pub struct PerformanceCharacteristics {
 /// Base adapter implementation with common functionality
 pub struct BaseAdapter {
     retry_handler: RetryHandler,
-    is_connected: bool,
-    connection_start_time: Option<std::time::Instant>,
+    /// When the current connection was made; None while disconnected
+    connection_start_time: std::sync::Mutex<Option<std::time::Instant>>,
     operation_count: std::sync::atomic::AtomicU64,
     error_count: std::sync::atomic::AtomicU64,
 }
@@ -107,8 +49,7 @@ impl BaseAdapter {
     pub fn new(retry_config: RetryConfig) -> Self {
         Self {
             retry_handler: RetryHandler::new(retry_config),
-            is_connected: false,
-            connection_start_time: None,
+            connection_start_time: std::sync::Mutex::new(None),
             operation_count: std::sync::atomic::AtomicU64::new(0),
             error_count: std::sync::atomic::AtomicU64::new(0),
         }
@@ -119,20 +60,18 @@ impl BaseAdapter {
     }
 
     pub fn is_connected(&self) -> bool {
-        self.is_connected
+        self.connection_start_time.lock().unwrap().is_some()
     }
 
-    pub fn set_connected(&mut self, connected: bool) {
-        self.is_connected = connected;
-        if connected {
-            self.connection_start_time = Some(std::time::Instant::now());
-        } else {
-            self.connection_start_time = None;
-        }
+    pub fn set_connected(&self, connected: bool) {
+        *self.connection_start_time.lock().unwrap() =
+            connected.then(std::time::Instant::now);
     }
 
     pub fn uptime_seconds(&self) -> u64 {
         self.connection_start_time
+            .lock()
+            .unwrap()
             .map(|start| start.elapsed().as_secs())
             .unwrap_or(0)
     }
@@ -251,6 +190,18 @@ pub mod conversion_utils {
         }
     }
 
+    /// Convert TradeLocker transaction type to unified transaction kind
+    pub fn convert_tl_transaction_kind(transaction_type: crate::platforms::tradelocker::TransactionType) -> UnifiedTransactionKind {
+        match transaction_type {
+            crate::platforms::tradelocker::TransactionType::Trade => UnifiedTransactionKind::Fill,
+            crate::platforms::tradelocker::TransactionType::Commission => UnifiedTransactionKind::Commission,
+            crate::platforms::tradelocker::TransactionType::Swap => UnifiedTransactionKind::Swap,
+            crate::platforms::tradelocker::TransactionType::Deposit => UnifiedTransactionKind::Transfer,
+            crate::platforms::tradelocker::TransactionType::Withdrawal => UnifiedTransactionKind::Transfer,
+            crate::platforms::tradelocker::TransactionType::Adjustment => UnifiedTransactionKind::Adjustment,
+        }
+    }
+
     // DXTrade conversion functions
     /// Convert DXTrade order side to unified order side
     pub fn convert_dx_order_side(side: crate::platforms::dxtrade::OrderSide) -> UnifiedOrderSide {
@@ -343,7 +294,33 @@ pub mod conversion_utils {
         }
     }
 
+    /// Convert DXTrade transaction type to unified transaction kind
+    pub fn convert_dx_transaction_kind(transaction_type: crate::platforms::dxtrade::TransactionType) -> UnifiedTransactionKind {
+        match transaction_type {
+            crate::platforms::dxtrade::TransactionType::Trade => UnifiedTransactionKind::Fill,
+            crate::platforms::dxtrade::TransactionType::Commission => UnifiedTransactionKind::Commission,
+            crate::platforms::dxtrade::TransactionType::Financing => UnifiedTransactionKind::Swap,
+            crate::platforms::dxtrade::TransactionType::Deposit => UnifiedTransactionKind::Transfer,
+            crate::platforms::dxtrade::TransactionType::Withdrawal => UnifiedTransactionKind::Transfer,
+            crate::platforms::dxtrade::TransactionType::Adjustment => UnifiedTransactionKind::Adjustment,
+        }
+    }
+
     /// Safely convert decimal values between platforms
+    /// Platform instrument type names; anything unrecognised is taken for forex
+    pub fn convert_instrument_type(instrument_type: Option<&str>) -> InstrumentType {
+        match instrument_type.map(str::to_ascii_lowercase).as_deref() {
+            Some("stock") | Some("equity") | Some("share") => InstrumentType::Stock,
+            Some("index") => InstrumentType::Index,
+            Some("commodity") | Some("metal") | Some("energy") => InstrumentType::Commodity,
+            Some("crypto") | Some("cryptocurrency") => InstrumentType::Crypto,
+            Some("bond") => InstrumentType::Bond,
+            Some("future") | Some("futures") => InstrumentType::Future,
+            Some("option") => InstrumentType::Option,
+            _ => InstrumentType::Forex,
+        }
+    }
+
     pub fn safe_decimal_conversion(value: Option<Decimal>) -> Option<Decimal> {
         value.filter(|d| !d.is_zero() && d.is_sign_positive())
     }
@@ -361,6 +338,9 @@ pub mod conversion_utils {
             crate::platforms::PlatformType::MetaTrader5 => {
                 super::super::errors::PlatformError::MetaTrader { error: error_msg.to_string() }
             }
+            crate::platforms::PlatformType::Mock => {
+                super::super::errors::PlatformError::InternalError { reason: error_msg.to_string() }
+            }
         }
     }
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use tokio::sync::mpsc;
 use std::collections::HashMap;
+use std::result::Result;
 
 use crate::platforms::{PlatformType, tradelocker::*};
 use super::super::interfaces::*;
@@ -10,40 +11,62 @@ use super::super::events::*;
 use super::super::capabilities::*;
 use super::{BaseAdapter, PlatformAdapter, AdapterInfo, PerformanceCharacteristics};
 use super::conversion_utils::*;
-use super::super::factory::RetryConfig;
+use super::super::retry::RetryConfig;
 
 /// TradeLocker platform adapter implementing the unified interface
 pub struct TradeLockerAdapter {
     client: TradeLockerClient,
     base: BaseAdapter,
-    event_sender: Option<mpsc::UnboundedSender<PlatformEvent>>,
+    event_subscribers: std::sync::Mutex<Vec<mpsc::Sender<PlatformEvent>>>,
     capabilities: PlatformCapabilities,
     account_id: String,
 }
 
 impl TradeLockerAdapter {
-    pub fn new(client: TradeLockerClient, retry_config: RetryConfig) -> Self {
-        let account_id = client.account_id().to_string();
-        
+    /// Adapter for one account of a client that may serve several
+    pub fn new(client: TradeLockerClient, account_id: String, retry_config: RetryConfig) -> Self {
         Self {
             client,
             base: BaseAdapter::new(retry_config),
-            event_sender: None,
+            event_subscribers: std::sync::Mutex::new(Vec::new()),
             capabilities: tradelocker_capabilities(),
             account_id,
         }
     }
 
+    /// Mark the adapter disconnected. Unlike `disconnect` this works on an
+    /// adapter shared behind an `Arc`.
+    pub async fn close(&self) -> Result<(), PlatformError> {
+        self.base.increment_operation_count();
+        
+        // TradeLocker client doesn't have explicit disconnect, but we can mark as disconnected
+        self.base.set_connected(false);
+        
+        self.emit_event(
+            EventType::ConnectionLost,
+            EventData::Connection(ConnectionEventData {
+                status: ConnectionStatus::Disconnected,
+                reason: Some("Manual disconnect".to_string()),
+                server_info: None,
+                latency_ms: None,
+            }),
+        ).await;
+        
+        Ok(())
+    }
+
     async fn emit_event(&self, event_type: EventType, data: EventData) {
-        if let Some(sender) = &self.event_sender {
-            let event = PlatformEvent::new(
-                event_type,
-                PlatformType::TradeLocker,
-                self.account_id.clone(),
-                data,
-            );
-            let _ = sender.send(event);
-        }
+        let event = PlatformEvent::new(
+            event_type,
+            PlatformType::TradeLocker,
+            self.account_id.clone(),
+            data,
+        );
+        // A subscriber that stopped reading loses events rather than stalling the adapter
+        self.event_subscribers
+            .lock()
+            .unwrap()
+            .retain(|sender| !matches!(sender.try_send(event.clone()), Err(mpsc::error::TrySendError::Closed(_))));
     }
 
     fn convert_order_to_unified(&self, order: OrderResponse) -> UnifiedOrderResponse {
@@ -107,6 +130,45 @@ impl TradeLockerAdapter {
         }
     }
 
+    fn convert_transaction_to_unified(&self, transaction: crate::platforms::tradelocker::Transaction) -> UnifiedTransaction {
+        UnifiedTransaction {
+            transaction_id: transaction.transaction_id,
+            account_id: self.account_id.clone(),
+            kind: convert_tl_transaction_kind(transaction.transaction_type),
+            symbol: transaction.symbol,
+            order_id: transaction.order_id,
+            position_id: transaction.position_id,
+            side: transaction.side.map(convert_tl_order_side),
+            quantity: transaction.quantity,
+            price: transaction.price,
+            commission: transaction.commission,
+            amount: transaction.amount,
+            currency: transaction.currency,
+            balance_after: transaction.balance,
+            timestamp: transaction.timestamp,
+            description: transaction.note.unwrap_or_default(),
+            platform_specific: HashMap::new(),
+        }
+    }
+
+    fn convert_instrument_to_unified(&self, instrument: Instrument) -> Symbol {
+        Symbol {
+            instrument_type: convert_instrument_type(instrument.instrument_type.as_deref()),
+            symbol: instrument.symbol,
+            description: instrument.description,
+            base_currency: instrument.base_currency,
+            quote_currency: instrument.quote_currency,
+            min_trade_size: instrument.min_quantity,
+            max_trade_size: instrument.max_quantity,
+            tick_size: instrument.tick_size,
+            contract_size: instrument.contract_size,
+            trading_hours: Vec::new(),
+            is_tradeable: instrument.tradable,
+            stops_level: None,
+            freeze_level: None,
+        }
+    }
+
     fn convert_market_data_to_unified(&self, data: MarketData) -> UnifiedMarketData {
         UnifiedMarketData {
             symbol: data.symbol,
@@ -124,12 +186,12 @@ impl TradeLockerAdapter {
     }
 
     fn convert_unified_order_request(&self, order: UnifiedOrder) -> Result<OrderRequest, PlatformError> {
-        let order_type = convert_to_tl_order_type(order.order_type)
+        let order_type = convert_to_tl_order_type(order.order_type.clone())
             .ok_or_else(|| PlatformError::FeatureNotSupported {
                 feature: format!("Order type {:?}", order.order_type)
             })?;
 
-        let time_in_force = convert_to_tl_time_in_force(order.time_in_force)
+        let time_in_force = convert_to_tl_time_in_force(order.time_in_force.clone())
             .ok_or_else(|| PlatformError::FeatureNotSupported {
                 feature: format!("Time in force {:?}", order.time_in_force)
             })?;
@@ -167,7 +229,8 @@ impl ITradingPlatform for TradeLockerAdapter {
         self.base.increment_operation_count();
         
         let result = self.base.retry_handler().execute_with_retry(|| async {
-            self.client.connect().await.map_err(|e| {
+            // An authenticated account request proves both the credentials and the account
+            self.client.get_account_info(&self.account_id).await.map_err(|e| {
                 PlatformError::ConnectionFailed { reason: e.to_string() }
             })
         }).await;
@@ -204,22 +267,7 @@ impl ITradingPlatform for TradeLockerAdapter {
     }
 
     async fn disconnect(&mut self) -> Result<(), PlatformError> {
-        self.base.increment_operation_count();
-        
-        // TradeLocker client doesn't have explicit disconnect, but we can mark as disconnected
-        self.base.set_connected(false);
-        
-        self.emit_event(
-            EventType::ConnectionLost,
-            EventData::Connection(ConnectionEventData {
-                status: ConnectionStatus::Disconnected,
-                reason: Some("Manual disconnect".to_string()),
-                server_info: None,
-                latency_ms: None,
-            }),
-        ).await;
-        
-        Ok(())
+        self.close().await
     }
 
     async fn is_connected(&self) -> bool {
@@ -232,7 +280,7 @@ impl ITradingPlatform for TradeLockerAdapter {
         let start = std::time::Instant::now();
         
         // Use account info request as ping
-        let result = self.client.get_account().await.map_err(|e| {
+        let result = self.client.get_account_info(&self.account_id).await.map_err(|e| {
             PlatformError::NetworkError { reason: e.to_string() }
         });
 
@@ -253,7 +301,7 @@ impl ITradingPlatform for TradeLockerAdapter {
         let tl_order = self.convert_unified_order_request(order)?;
         
         let result = self.base.retry_handler().execute_with_retry(|| async {
-            self.client.place_order(tl_order.clone()).await.map_err(|e| {
+            self.client.place_order(&self.account_id, tl_order.clone()).await.map_err(|e| {
                 PlatformError::OrderRejected { 
                     reason: e.to_string(),
                     platform_code: None,
@@ -300,7 +348,7 @@ impl ITradingPlatform for TradeLockerAdapter {
         self.base.increment_operation_count();
         
         let result = self.base.retry_handler().execute_with_retry(|| async {
-            self.client.cancel_order(order_id).await.map_err(|e| {
+            self.client.cancel_order(&self.account_id, order_id).await.map_err(|e| {
                 PlatformError::OrderModificationFailed { reason: e.to_string() }
             })
         }).await;
@@ -357,7 +405,7 @@ impl ITradingPlatform for TradeLockerAdapter {
         self.base.increment_operation_count();
         
         let result = self.base.retry_handler().execute_with_retry(|| async {
-            self.client.get_orders().await.map_err(|e| {
+            self.client.get_orders(&self.account_id).await.map_err(|e| {
                 PlatformError::InternalError { reason: e.to_string() }
             })
         }).await;
@@ -380,7 +428,7 @@ impl ITradingPlatform for TradeLockerAdapter {
         self.base.increment_operation_count();
         
         let result = self.base.retry_handler().execute_with_retry(|| async {
-            self.client.get_positions().await.map_err(|e| {
+            self.client.get_positions(&self.account_id).await.map_err(|e| {
                 PlatformError::InternalError { reason: e.to_string() }
             })
         }).await;
@@ -436,16 +484,23 @@ impl ITradingPlatform for TradeLockerAdapter {
                 tags: vec!["position_close".to_string()],
                 expires_at: None,
             },
+            max_slippage: None,
         };
 
-        self.place_order(close_order).await
+        let response = self.place_order(close_order).await?;
+
+        if let Some(close) = PositionCloseEventData::from_close_fill(&position, &response) {
+            self.emit_event(close.event_type(), EventData::PositionClose(close)).await;
+        }
+
+        Ok(response)
     }
 
     async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
         self.base.increment_operation_count();
         
         let result = self.base.retry_handler().execute_with_retry(|| async {
-            self.client.get_account().await.map_err(|e| {
+            self.client.get_account_info(&self.account_id).await.map_err(|e| {
                 PlatformError::InternalError { reason: e.to_string() }
             })
         }).await;
@@ -475,11 +530,58 @@ impl ITradingPlatform for TradeLockerAdapter {
         })
     }
 
+    async fn get_transactions(&self, range: TransactionRange) -> Result<Vec<UnifiedTransaction>, PlatformError> {
+        self.base.increment_operation_count();
+
+        let result = self.base.retry_handler().execute_with_retry(|| async {
+            self.client.get_transactions(&self.account_id, range.from, range.to).await.map_err(|e| {
+                PlatformError::InternalError { reason: e.to_string() }
+            })
+        }).await;
+
+        match result {
+            Ok(transactions) => Ok(transactions.into_iter()
+                .filter(|t| range.contains(t.timestamp))
+                .map(|t| self.convert_transaction_to_unified(t))
+                .collect()),
+            Err(e) => {
+                self.base.increment_error_count();
+                Err(e)
+            }
+        }
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<Symbol>, PlatformError> {
+        self.base.increment_operation_count();
+
+        let result = self.base.retry_handler().execute_with_retry(|| async {
+            self.client.get_instruments(&self.account_id).await.map_err(|e| {
+                PlatformError::MarketDataUnavailable { reason: e.to_string() }
+            })
+        }).await;
+
+        match result {
+            Ok(instruments) => Ok(instruments.into_iter()
+                .map(|i| self.convert_instrument_to_unified(i))
+                .collect()),
+            Err(e) => {
+                self.base.increment_error_count();
+                Err(e)
+            }
+        }
+    }
+
+    async fn get_server_time(&self) -> Result<chrono::DateTime<chrono::Utc>, PlatformError> {
+        self.client.server_time().await.map_err(|e| {
+            PlatformError::NetworkError { reason: e.to_string() }
+        })
+    }
+
     async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
         self.base.increment_operation_count();
         
         let result = self.base.retry_handler().execute_with_retry(|| async {
-            self.client.get_market_data(symbol).await.map_err(|e| {
+            self.client.get_market_data(&self.account_id, symbol).await.map_err(|e| {
                 PlatformError::MarketDataUnavailable { reason: e.to_string() }
             })
         }).await;
@@ -509,9 +611,8 @@ impl ITradingPlatform for TradeLockerAdapter {
     }
 
     async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
-        let (tx, rx) = mpsc::unbounded_channel();
-        // Store the sender for event emission
-        // Note: This is simplified - in a real implementation, you'd need interior mutability
+        let (tx, rx) = mpsc::channel(1024);
+        self.event_subscribers.lock().unwrap().push(tx);
         Ok(rx)
     }
 
@@ -1,6 +1,10 @@
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
+use super::errors::ValidationError;
+use super::models::UnifiedOrder;
+
 /// Platform capability detection and management
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlatformCapabilities {
@@ -15,6 +19,9 @@ pub struct PlatformCapabilities {
     pub max_orders_per_second: Option<u32>,
     pub max_order_size: Option<rust_decimal::Decimal>,
     pub min_order_size: Option<rust_decimal::Decimal>,
+    /// Lot size rules order quantities are snapped to before submission
+    #[serde(default)]
+    pub quantity_rules: QuantityRules,
     pub supports_partial_fills: bool,
     pub supports_market_data_subscription: bool,
     pub supports_historical_data: bool,
@@ -38,6 +45,7 @@ impl PlatformCapabilities {
             max_orders_per_second: None,
             max_order_size: None,
             min_order_size: None,
+            quantity_rules: QuantityRules::default(),
             supports_partial_fills: false,
             supports_market_data_subscription: false,
             supports_historical_data: false,
@@ -96,6 +104,29 @@ impl PlatformCapabilities {
         Ok(())
     }
 
+    /// Quantity limits for `symbol`, with `min_order_size` and `max_order_size` filling
+    /// in bounds the quantity rules leave open
+    pub fn quantity_limits(&self, symbol: &str) -> QuantityLimits {
+        let limits = self.quantity_rules.for_symbol(symbol);
+        QuantityLimits {
+            min: limits.min.or(self.min_order_size),
+            max: limits.max.or(self.max_order_size),
+            step: limits.step,
+        }
+    }
+
+    /// Snap `order.quantity` to the platform's lot rules for its symbol, rejecting
+    /// sizes the platform could not accept. Returns the quantity that will be sent.
+    pub fn normalize_order_quantity(
+        &self,
+        order: &mut UnifiedOrder,
+    ) -> Result<Decimal, ValidationError> {
+        order.quantity = self
+            .quantity_limits(&order.symbol)
+            .normalize(&order.symbol, order.quantity)?;
+        Ok(order.quantity)
+    }
+
     pub fn estimate_latency(&self, operation: PlatformOperation) -> Option<u64> {
         self.latency_sla
             .as_ref()
@@ -103,6 +134,98 @@ impl PlatformCapabilities {
     }
 }
 
+/// Minimum, maximum and step of the quantities a platform accepts for a symbol, in
+/// the platform's own units (lots for FX brokers)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuantityLimits {
+    #[serde(default)]
+    pub min: Option<Decimal>,
+    #[serde(default)]
+    pub max: Option<Decimal>,
+    /// Quantities must be a whole multiple of this
+    #[serde(default)]
+    pub step: Option<Decimal>,
+}
+
+impl QuantityLimits {
+    pub fn new(min: Decimal, max: Option<Decimal>, step: Decimal) -> Self {
+        Self {
+            min: Some(min),
+            max,
+            step: Some(step),
+        }
+    }
+
+    /// Round `quantity` down to a whole number of steps, so a snapped order never
+    /// exceeds the size risk calculated, then check it against the bounds
+    pub fn normalize(&self, symbol: &str, quantity: Decimal) -> Result<Decimal, ValidationError> {
+        let untradable = |reason: String| ValidationError::UntradableQuantity {
+            symbol: symbol.to_string(),
+            quantity,
+            reason,
+        };
+        if quantity <= Decimal::ZERO {
+            return Err(untradable("quantity must be positive".to_string()));
+        }
+
+        let snapped = match self.step.filter(|step| *step > Decimal::ZERO) {
+            Some(step) => ((quantity / step).floor() * step).normalize(),
+            None => quantity,
+        };
+        if let Some(min) = self.min {
+            if snapped < min {
+                return Err(untradable(match self.step {
+                    Some(step) if snapped != quantity => format!(
+                        "{} after rounding down to the lot step of {} is below the minimum of {}",
+                        snapped, step, min
+                    ),
+                    _ => format!("below the minimum of {}", min),
+                }));
+            }
+        }
+        if let Some(max) = self.max {
+            if snapped > max {
+                return Err(untradable(format!("above the maximum of {}", max)));
+            }
+        }
+        if snapped.is_zero() {
+            return Err(untradable(format!(
+                "smaller than one lot step of {}",
+                self.step.unwrap_or_default()
+            )));
+        }
+        Ok(snapped)
+    }
+}
+
+/// Quantity limits for every symbol on a platform, with per-symbol overrides for
+/// instruments traded in other units (indices, metals)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuantityRules {
+    #[serde(flatten)]
+    pub default: QuantityLimits,
+    #[serde(default)]
+    pub symbols: HashMap<String, QuantityLimits>,
+}
+
+impl QuantityRules {
+    pub fn new(default: QuantityLimits) -> Self {
+        Self {
+            default,
+            symbols: HashMap::new(),
+        }
+    }
+
+    pub fn with_symbol(mut self, symbol: &str, limits: QuantityLimits) -> Self {
+        self.symbols.insert(symbol.to_string(), limits);
+        self
+    }
+
+    pub fn for_symbol(&self, symbol: &str) -> QuantityLimits {
+        self.symbols.get(symbol).copied().unwrap_or(self.default)
+    }
+}
+
 /// Platform features enumeration
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PlatformFeature {
@@ -118,6 +241,8 @@ pub enum PlatformFeature {
     OrderModification,
     OrderCancellation,
     PartialFills,
+    /// Refuses market fills beyond the order's `max_slippage`, usually as a requote
+    MaxSlippage,
 
     // Position Management
     NetPositions,
@@ -302,6 +427,11 @@ pub fn tradelocker_capabilities() -> PlatformCapabilities {
         .insert(crate::platforms::abstraction::models::InstrumentType::Commodity);
 
     // Limits
+    caps.quantity_rules = QuantityRules::new(QuantityLimits::new(
+        Decimal::new(1, 2),
+        None,
+        Decimal::new(1, 2),
+    ));
     caps.max_orders_per_second = Some(10);
     caps.supports_partial_fills = true;
     caps.supports_market_data_subscription = true;
@@ -383,6 +513,11 @@ pub fn dxtrade_capabilities() -> PlatformCapabilities {
         .insert(crate::platforms::abstraction::models::InstrumentType::Future);
 
     // Limits
+    caps.quantity_rules = QuantityRules::new(QuantityLimits::new(
+        Decimal::new(1, 2),
+        None,
+        Decimal::new(1, 2),
+    ));
     caps.max_orders_per_second = Some(20);
     caps.supports_partial_fills = true;
     caps.supports_market_data_subscription = false; // Uses FIX for streaming
@@ -1,9 +1,26 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use serde::{Deserialize, Serialize};
 
 use super::errors::PlatformError;
 
+tokio::task_local! {
+    static EMERGENCY: String;
+}
+
+/// Run `future` as an emergency: its platform calls go through circuit breakers
+/// that are not closed, and each such override is logged with `reason`
+pub async fn as_emergency<F: Future>(reason: impl Into<String>, future: F) -> F::Output {
+    EMERGENCY.scope(reason.into(), future).await
+}
+
+/// Why the current task's platform calls may override circuit breakers, if they may
+pub fn current_emergency() -> Option<String> {
+    EMERGENCY.try_with(|reason| reason.clone()).ok()
+}
+
 /// Circuit breaker states
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum CircuitBreakerState {
@@ -33,11 +50,11 @@ pub struct CircuitBreakerConfig {
 impl Default for CircuitBreakerConfig {
     fn default() -> Self {
         Self {
-            failure_threshold: 5,       // 5 failures to open
-            success_threshold: 3,       // 3 successes to close
+            failure_threshold: 5,                    // 5 failures to open
+            success_threshold: 3,                    // 3 successes to close
             failure_window: Duration::from_secs(60), // 1 minute window
             open_timeout: Duration::from_secs(30),   // 30 second timeout
-            half_open_max_operations: 3, // Allow 3 test operations
+            half_open_max_operations: 3,             // Allow 3 test operations
         }
     }
 }
@@ -134,20 +151,10 @@ impl CircuitBreaker {
         let mut data = self.data.lock().unwrap();
         let now = Instant::now();
 
+        self.apply_open_timeout(&mut data, now);
         match data.state {
             CircuitBreakerState::Closed => true,
-            CircuitBreakerState::Open => {
-                // Check if enough time has passed to transition to half-open
-                if now.duration_since(data.last_state_change) >= self.config.open_timeout {
-                    data.state = CircuitBreakerState::HalfOpen;
-                    data.last_state_change = now;
-                    data.half_open_operations = 0;
-                    data.success_count = 0;
-                    true
-                } else {
-                    false
-                }
-            }
+            CircuitBreakerState::Open => false,
             CircuitBreakerState::HalfOpen => {
                 // Allow limited operations in half-open state
                 data.half_open_operations < self.config.half_open_max_operations
@@ -155,11 +162,34 @@ impl CircuitBreaker {
         }
     }
 
+    /// Current state, after moving an open breaker whose timeout has passed to
+    /// half-open
+    pub fn poll_state(&self) -> CircuitBreakerState {
+        let mut data = self.data.lock().unwrap();
+        self.apply_open_timeout(&mut data, Instant::now());
+        data.state.clone()
+    }
+
+    fn apply_open_timeout(&self, data: &mut CircuitBreakerData, now: Instant) {
+        if data.state == CircuitBreakerState::Open
+            && now.duration_since(data.last_state_change) >= self.config.open_timeout
+        {
+            data.state = CircuitBreakerState::HalfOpen;
+            data.last_state_change = now;
+            data.half_open_operations = 0;
+            data.success_count = 0;
+        }
+    }
+
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
     /// Record a successful operation
     fn record_success(&self) {
         let mut data = self.data.lock().unwrap();
         let now = Instant::now();
-        
+
         data.total_operations += 1;
 
         match data.state {
@@ -171,7 +201,7 @@ impl CircuitBreaker {
             CircuitBreakerState::HalfOpen => {
                 data.success_count += 1;
                 data.half_open_operations += 1;
-                
+
                 // Check if we should transition to closed
                 if data.success_count >= self.config.success_threshold {
                     data.state = CircuitBreakerState::Closed;
@@ -193,7 +223,7 @@ impl CircuitBreaker {
     fn record_failure(&self) {
         let mut data = self.data.lock().unwrap();
         let now = Instant::now();
-        
+
         data.total_operations += 1;
         data.failure_count += 1;
         data.last_failure_time = Some(now);
@@ -205,9 +235,9 @@ impl CircuitBreaker {
                     data.failure_window_start = now;
                     data.current_failure_window_count = 0;
                 }
-                
+
                 data.current_failure_window_count += 1;
-                
+
                 // Check if we should open the circuit
                 if data.current_failure_window_count >= self.config.failure_threshold {
                     data.state = CircuitBreakerState::Open;
@@ -216,7 +246,7 @@ impl CircuitBreaker {
             }
             CircuitBreakerState::HalfOpen => {
                 data.half_open_operations += 1;
-                
+
                 // Transition back to open on any failure in half-open
                 data.state = CircuitBreakerState::Open;
                 data.last_state_change = now;
@@ -232,63 +262,13 @@ impl CircuitBreaker {
 
     /// Determine if an error should count as a failure for circuit breaking
     fn should_count_as_failure(&self, error: &PlatformError) -> bool {
-        match error {
-            // Network and connection errors should trigger circuit breaker
-            PlatformError::ConnectionFailed { .. } |
-            PlatformError::ConnectionTimeout { .. } |
-            PlatformError::Disconnected { .. } |
-            PlatformError::NetworkError { .. } |
-            PlatformError::RequestTimeout { .. } |
-            PlatformError::ApiLimitReached { .. } |
-            PlatformError::InternalError { .. } => true,
-
-            // Rate limiting might be temporary, count as failure but with lower weight
-            PlatformError::RateLimitExceeded { .. } => true,
-
-            // Authentication failures should trigger circuit breaker
-            PlatformError::AuthenticationFailed { .. } |
-            PlatformError::InvalidCredentials { .. } => true,
-
-            // Platform-specific errors should trigger circuit breaker
-            PlatformError::PlatformNotSupported { .. } |
-            PlatformError::InitializationFailed { .. } => true,
-
-            // Business logic errors shouldn't trigger circuit breaker
-            PlatformError::OrderValidationFailed { .. } |
-            PlatformError::OrderRejected { .. } |
-            PlatformError::OrderNotFound { .. } |
-            PlatformError::PositionNotFound { .. } |
-            PlatformError::InsufficientMargin { .. } |
-            PlatformError::InsufficientFunds { .. } |
-            PlatformError::TradingNotAllowed { .. } |
-            PlatformError::SymbolNotFound { .. } |
-            PlatformError::AccountNotFound { .. } |
-            PlatformError::FeatureNotSupported { .. } => false,
-
-            // Market data errors depend on context
-            PlatformError::MarketDataUnavailable { .. } |
-            PlatformError::SubscriptionFailed { .. } => true,
-
-            // Configuration errors should trigger circuit breaker
-            PlatformError::ConfigurationError { .. } => true,
-
-            // Platform-specific errors should trigger circuit breaker
-            PlatformError::TradeLocker { .. } |
-            PlatformError::DXTrade { .. } |
-            PlatformError::MetaTrader { .. } => true,
-
-            // Unknown errors should trigger circuit breaker to be safe
-            PlatformError::Unknown { .. } => true,
-
-            // Default case
-            _ => true,
-        }
+        error.trips_circuit_breaker()
     }
 
     /// Get current circuit breaker statistics
     pub fn get_stats(&self) -> CircuitBreakerStats {
         let data = self.data.lock().unwrap();
-        
+
         CircuitBreakerStats {
             state: data.state.clone(),
             failure_count: data.failure_count,
@@ -297,7 +277,8 @@ impl CircuitBreaker {
             last_failure_time: data.last_failure_time.map(|t| {
                 chrono::Utc::now() - chrono::Duration::from_std(t.elapsed()).unwrap_or_default()
             }),
-            last_state_change: chrono::Utc::now() - chrono::Duration::from_std(data.last_state_change.elapsed()).unwrap_or_default(),
+            last_state_change: chrono::Utc::now()
+                - chrono::Duration::from_std(data.last_state_change.elapsed()).unwrap_or_default(),
             current_failure_window_count: data.current_failure_window_count,
         }
     }
@@ -312,7 +293,7 @@ impl CircuitBreaker {
     pub fn reset(&self) {
         let mut data = self.data.lock().unwrap();
         let now = Instant::now();
-        
+
         data.state = CircuitBreakerState::Closed;
         data.failure_count = 0;
         data.success_count = 0;
@@ -344,17 +325,18 @@ impl CircuitBreaker {
     pub fn get_failure_rate(&self) -> f64 {
         let data = self.data.lock().unwrap();
         let now = Instant::now();
-        
+
         if data.total_operations == 0 {
             return 0.0;
         }
 
         // Calculate failure rate in current window
-        let window_operations = if now.duration_since(data.failure_window_start) <= self.config.failure_window {
-            data.current_failure_window_count as u64
-        } else {
-            0
-        };
+        let window_operations =
+            if now.duration_since(data.failure_window_start) <= self.config.failure_window {
+                data.current_failure_window_count as u64
+            } else {
+                0
+            };
 
         if window_operations == 0 {
             0.0
@@ -379,6 +361,113 @@ impl Clone for CircuitBreaker {
     }
 }
 
+/// Kind of platform operation, each with its own breaker so that a flapping
+/// endpoint only stops the calls that go to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum OperationClass {
+    /// Placing, changing and looking up orders and positions
+    Orders,
+    /// Quotes and instruments
+    MarketData,
+    /// Balance, margin, account events and pings
+    Account,
+}
+
+impl OperationClass {
+    pub const ALL: [OperationClass; 3] = [
+        OperationClass::Orders,
+        OperationClass::MarketData,
+        OperationClass::Account,
+    ];
+
+    /// Class of the `ITradingPlatform` method named `operation`
+    pub fn of(operation: &str) -> Self {
+        match operation {
+            "get_market_data"
+            | "subscribe_market_data"
+            | "unsubscribe_market_data"
+            | "get_instruments"
+            | "get_order_book"
+            | "subscribe_depth" => OperationClass::MarketData,
+            "get_account_info" | "get_balance" | "get_margin_info" | "get_event_history"
+            | "ping" | "health_check" | "get_server_time" => OperationClass::Account,
+            _ => OperationClass::Orders,
+        }
+    }
+}
+
+/// One circuit breaker per operation class, each with its own thresholds
+#[derive(Clone)]
+pub struct OperationBreakers {
+    breakers: HashMap<OperationClass, CircuitBreaker>,
+}
+
+impl OperationBreakers {
+    /// Every class with `config`
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            breakers: OperationClass::ALL
+                .into_iter()
+                .map(|class| (class, CircuitBreaker::with_config(config.clone())))
+                .collect(),
+        }
+    }
+
+    /// Replace the thresholds of `class`, resetting its breaker
+    pub fn with_config(mut self, class: OperationClass, config: CircuitBreakerConfig) -> Self {
+        self.breakers
+            .insert(class, CircuitBreaker::with_config(config));
+        self
+    }
+
+    pub fn breaker(&self, class: OperationClass) -> &CircuitBreaker {
+        &self.breakers[&class]
+    }
+
+    pub fn stats(&self) -> HashMap<OperationClass, CircuitBreakerStats> {
+        self.breakers
+            .iter()
+            .map(|(class, breaker)| (*class, breaker.get_stats()))
+            .collect()
+    }
+
+    /// Classes whose breaker is not closed, with their state
+    pub fn tripped(&self) -> Vec<(OperationClass, CircuitBreakerState)> {
+        let mut tripped: Vec<_> = self
+            .breakers
+            .iter()
+            .map(|(class, breaker)| (*class, breaker.get_state()))
+            .filter(|(_, state)| *state != CircuitBreakerState::Closed)
+            .collect();
+        tripped.sort_by_key(|(class, _)| *class);
+        tripped
+    }
+
+    /// The worst state of any class: open if one is open, else half-open if one is
+    pub fn combined_state(&self) -> CircuitBreakerState {
+        let states: Vec<_> = self.tripped().into_iter().map(|(_, s)| s).collect();
+        if states.contains(&CircuitBreakerState::Open) {
+            CircuitBreakerState::Open
+        } else if states.contains(&CircuitBreakerState::HalfOpen) {
+            CircuitBreakerState::HalfOpen
+        } else {
+            CircuitBreakerState::Closed
+        }
+    }
+
+    pub fn reset(&self) {
+        for breaker in self.breakers.values() {
+            breaker.reset();
+        }
+    }
+}
+
+impl Default for OperationBreakers {
+    fn default() -> Self {
+        Self::new(CircuitBreakerConfig::default())
+    }
+}
+
 /// Circuit breaker wrapper for platform adapters
 pub struct CircuitBreakerWrapper<T> {
     inner: T,
@@ -411,12 +500,17 @@ impl<T> CircuitBreakerWrapper<T> {
         &self.circuit_breaker
     }
 
-    pub async fn execute_with_circuit_breaker<R, F, Fut>(&self, operation: F) -> Result<R, PlatformError>
+    pub async fn execute_with_circuit_breaker<R, F, Fut>(
+        &self,
+        operation: F,
+    ) -> Result<R, PlatformError>
     where
         F: FnOnce(&T) -> Fut,
         Fut: std::future::Future<Output = Result<R, PlatformError>>,
     {
-        self.circuit_breaker.execute(|| operation(&self.inner)).await
+        self.circuit_breaker
+            .execute(|| operation(&self.inner))
+            .await
     }
 }
 
@@ -428,7 +522,7 @@ mod tests {
     #[tokio::test]
     async fn test_circuit_breaker_closed_state() {
         let circuit_breaker = CircuitBreaker::new();
-        
+
         // Should start in closed state
         assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Closed);
         assert!(circuit_breaker.is_operation_allowed());
@@ -442,22 +536,24 @@ mod tests {
             ..Default::default()
         };
         let circuit_breaker = CircuitBreaker::with_config(config);
-        
+
         // Simulate failures
         for i in 0..3 {
-            let result: Result<(), PlatformError> = circuit_breaker.execute(|| async {
-                Err(PlatformError::ConnectionFailed { 
-                    reason: format!("Test failure {}", i) 
+            let result: Result<(), PlatformError> = circuit_breaker
+                .execute(|| async {
+                    Err(PlatformError::ConnectionFailed {
+                        reason: format!("Test failure {}", i),
+                    })
                 })
-            }).await;
-            
+                .await;
+
             assert!(result.is_err());
-            
+
             if i < 2 {
                 assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Closed);
             }
         }
-        
+
         // Circuit should now be open
         assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Open);
         assert!(!circuit_breaker.is_operation_allowed());
@@ -468,14 +564,12 @@ mod tests {
     async fn test_circuit_breaker_rejects_operations_when_open() {
         let circuit_breaker = CircuitBreaker::new();
         circuit_breaker.force_open();
-        
+
         assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Open);
-        
+
         // Operations should be rejected
-        let result: Result<(), PlatformError> = circuit_breaker.execute(|| async {
-            Ok(())
-        }).await;
-        
+        let result: Result<(), PlatformError> = circuit_breaker.execute(|| async { Ok(()) }).await;
+
         assert!(result.is_err());
         match result.unwrap_err() {
             PlatformError::InternalError { reason } => {
@@ -493,28 +587,28 @@ mod tests {
             ..Default::default()
         };
         let circuit_breaker = CircuitBreaker::with_config(config);
-        
+
         // Force failures to open the circuit
         for _ in 0..2 {
-            let _ = circuit_breaker.execute(|| async {
-                Err(PlatformError::ConnectionFailed { 
-                    reason: "Test failure".to_string() 
+            let _ = circuit_breaker
+                .execute(|| async {
+                    Err(PlatformError::ConnectionFailed {
+                        reason: "Test failure".to_string(),
+                    })
                 })
-            }).await;
+                .await;
         }
-        
+
         assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Open);
-        
+
         // Wait for timeout
         sleep(TokioDuration::from_millis(150)).await;
-        
+
         // Next operation should transition to half-open
         assert!(circuit_breaker.is_operation_allowed());
-        
-        let result: Result<(), PlatformError> = circuit_breaker.execute(|| async {
-            Ok(())
-        }).await;
-        
+
+        let result: Result<(), PlatformError> = circuit_breaker.execute(|| async { Ok(()) }).await;
+
         assert!(result.is_ok());
         assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::HalfOpen);
     }
@@ -528,28 +622,29 @@ mod tests {
             ..Default::default()
         };
         let circuit_breaker = CircuitBreaker::with_config(config);
-        
+
         // Open the circuit
         for _ in 0..2 {
-            let _ = circuit_breaker.execute(|| async {
-                Err(PlatformError::NetworkError { 
-                    reason: "Test failure".to_string() 
+            let _ = circuit_breaker
+                .execute(|| async {
+                    Err(PlatformError::NetworkError {
+                        reason: "Test failure".to_string(),
+                    })
                 })
-            }).await;
+                .await;
         }
         assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Open);
-        
+
         // Wait and transition to half-open
         sleep(TokioDuration::from_millis(100)).await;
-        
+
         // Successful operations should close the circuit
         for _ in 0..2 {
-            let result: Result<(), PlatformError> = circuit_breaker.execute(|| async {
-                Ok(())
-            }).await;
+            let result: Result<(), PlatformError> =
+                circuit_breaker.execute(|| async { Ok(()) }).await;
             assert!(result.is_ok());
         }
-        
+
         assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Closed);
         assert!(circuit_breaker.is_healthy());
     }
@@ -557,44 +652,56 @@ mod tests {
     #[tokio::test]
     async fn test_circuit_breaker_failure_classification() {
         let circuit_breaker = CircuitBreaker::new();
-        
+
         // Connection errors should count as failures
-        assert!(circuit_breaker.should_count_as_failure(&PlatformError::ConnectionFailed { 
-            reason: "test".to_string() 
-        }));
-        
-        assert!(circuit_breaker.should_count_as_failure(&PlatformError::NetworkError { 
-            reason: "test".to_string() 
-        }));
-        
+        assert!(
+            circuit_breaker.should_count_as_failure(&PlatformError::ConnectionFailed {
+                reason: "test".to_string()
+            })
+        );
+
+        assert!(
+            circuit_breaker.should_count_as_failure(&PlatformError::NetworkError {
+                reason: "test".to_string()
+            })
+        );
+
         // Business logic errors shouldn't count as failures
-        assert!(!circuit_breaker.should_count_as_failure(&PlatformError::OrderRejected { 
-            reason: "test".to_string(),
-            platform_code: None,
-        }));
-        
-        assert!(!circuit_breaker.should_count_as_failure(&PlatformError::InsufficientMargin { 
-            required: rust_decimal::Decimal::new(100, 0),
-            available: rust_decimal::Decimal::new(50, 0),
-        }));
+        assert!(
+            !circuit_breaker.should_count_as_failure(&PlatformError::OrderRejected {
+                reason: "test".to_string(),
+                platform_code: None,
+            })
+        );
+
+        assert!(
+            !circuit_breaker.should_count_as_failure(&PlatformError::InsufficientMargin {
+                required: rust_decimal::Decimal::new(100, 0),
+                available: rust_decimal::Decimal::new(50, 0),
+            })
+        );
     }
 
     #[tokio::test]
     async fn test_circuit_breaker_stats() {
         let circuit_breaker = CircuitBreaker::new();
-        
+
         // Initial stats
         let stats = circuit_breaker.get_stats();
         assert_eq!(stats.state, CircuitBreakerState::Closed);
         assert_eq!(stats.total_operations, 0);
         assert_eq!(stats.failure_count, 0);
-        
+
         // Execute some operations
         let _ = circuit_breaker.execute(|| async { Ok(()) }).await;
-        let _ = circuit_breaker.execute(|| async { 
-            Err(PlatformError::ConnectionFailed { reason: "test".to_string() })
-        }).await;
-        
+        let _ = circuit_breaker
+            .execute(|| async {
+                Err(PlatformError::ConnectionFailed {
+                    reason: "test".to_string(),
+                })
+            })
+            .await;
+
         let stats = circuit_breaker.get_stats();
         assert_eq!(stats.total_operations, 2);
         assert_eq!(stats.failure_count, 1);
@@ -605,14 +712,14 @@ mod tests {
     async fn test_circuit_breaker_reset() {
         let circuit_breaker = CircuitBreaker::new();
         circuit_breaker.force_open();
-        
+
         assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Open);
-        
+
         // Reset should close the circuit
         circuit_breaker.reset();
         assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Closed);
         assert!(circuit_breaker.is_healthy());
-        
+
         let stats = circuit_breaker.get_stats();
         assert_eq!(stats.failure_count, 0);
         assert_eq!(stats.success_count, 0);
@@ -627,8 +734,8 @@ mod tests {
         impl MockService {
             async fn operation(&self) -> Result<String, PlatformError> {
                 if self.should_fail {
-                    Err(PlatformError::ConnectionFailed { 
-                        reason: "Mock failure".to_string() 
+                    Err(PlatformError::ConnectionFailed {
+                        reason: "Mock failure".to_string(),
                     })
                 } else {
                     Ok("Success".to_string())
@@ -638,16 +745,16 @@ mod tests {
 
         let service = MockService { should_fail: false };
         let wrapper = CircuitBreakerWrapper::new(service, "test_operation".to_string());
-        
+
         // Successful operation
-        let result = wrapper.execute_with_circuit_breaker(|service| async {
-            service.operation().await
-        }).await;
-        
+        let result = wrapper
+            .execute_with_circuit_breaker(|service| async { service.operation().await })
+            .await;
+
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "Success");
-        
+
         // Check circuit breaker is healthy
         assert!(wrapper.get_circuit_breaker().is_healthy());
     }
-}
\ No newline at end of file
+}
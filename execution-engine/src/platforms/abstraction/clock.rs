@@ -0,0 +1,315 @@
+// Skew between our clock and each platform server's. Order timestamps, GTD expiry
+// and quote ages assume the two agree; the offset measured here is reported,
+// alerted on, and applied by whatever times itself against the broker.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use lazy_static::lazy_static;
+use prometheus::{register_int_gauge_vec, IntGaugeVec};
+use risk_types::AlertLevel;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use super::capabilities::PlatformCapabilities;
+use super::decorator::PlatformDecorator;
+use super::errors::PlatformError;
+use super::events::PlatformEvent;
+use super::interfaces::{
+    DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter,
+};
+use super::models::*;
+use crate::alerting::{Alert, AlertGateway};
+use crate::platforms::PlatformType;
+use crate::runtime::spawn::spawn_isolated;
+
+lazy_static! {
+    pub static ref PLATFORM_CLOCK_OFFSET_MS: IntGaugeVec = register_int_gauge_vec!(
+        "platform_clock_offset_ms",
+        "Platform server time minus local time, as last measured",
+        &["account_id"]
+    )
+    .unwrap();
+}
+
+/// Alert type raised when an account's platform clock is too far from ours
+pub const CLOCK_SKEW_ALERT_TYPE: &str = "clock_skew";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockSyncConfig {
+    /// Measure the skew to every platform's server time
+    pub enabled: bool,
+    /// How often each platform's server time is read
+    pub interval_secs: u64,
+    /// Skew above which an alert is raised and the platform reports unhealthy
+    pub alert_threshold_ms: u64,
+}
+
+impl Default for ClockSyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 60,
+            alert_threshold_ms: 500,
+        }
+    }
+}
+
+impl ClockSyncConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_secs == 0 {
+            return Err("Clock sync interval_secs must be above 0".to_string());
+        }
+        if self.alert_threshold_ms == 0 {
+            return Err("Clock sync alert_threshold_ms must be above 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// One server time reading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockReading {
+    /// Server time minus local time
+    pub offset_ms: i64,
+    pub round_trip_ms: u64,
+    pub measured_at: DateTime<Utc>,
+}
+
+/// An account's view of its platform server's clock, shared by everything timed
+/// against the broker. Reads local time until the first measurement.
+#[derive(Debug, Clone, Default)]
+pub struct ServerClock {
+    reading: Arc<RwLock<Option<ClockReading>>>,
+}
+
+impl ServerClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The server's time now
+    pub fn now(&self) -> DateTime<Utc> {
+        Utc::now() + Duration::milliseconds(self.offset_ms())
+    }
+
+    pub fn offset_ms(&self) -> i64 {
+        self.reading
+            .read()
+            .unwrap()
+            .as_ref()
+            .map_or(0, |r| r.offset_ms)
+    }
+
+    pub fn reading(&self) -> Option<ClockReading> {
+        self.reading.read().unwrap().clone()
+    }
+
+    pub fn record(&self, reading: ClockReading) {
+        *self.reading.write().unwrap() = Some(reading);
+    }
+
+    /// Read `platform`'s server time, taking it as stamped halfway through the
+    /// round trip
+    pub async fn measure(
+        &self,
+        platform: &(dyn ITradingPlatform + Send + Sync),
+    ) -> Result<ClockReading, PlatformError> {
+        let sent = Utc::now();
+        let server_time = platform.get_server_time().await?;
+        let received = Utc::now();
+        let round_trip = received - sent;
+        let reading = ClockReading {
+            offset_ms: (server_time - (sent + round_trip / 2)).num_milliseconds(),
+            round_trip_ms: round_trip.num_milliseconds().max(0) as u64,
+            measured_at: received,
+        };
+        self.record(reading.clone());
+        Ok(reading)
+    }
+}
+
+/// Every account's server clock, handed out by account id
+#[derive(Debug, Clone, Default)]
+pub struct ServerClocks {
+    clocks: Arc<RwLock<HashMap<String, ServerClock>>>,
+}
+
+impl ServerClocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The account's clock, reading local time until it has been measured
+    pub fn clock(&self, account_id: &str) -> ServerClock {
+        if let Some(clock) = self.clocks.read().unwrap().get(account_id) {
+            return clock.clone();
+        }
+        self.clocks
+            .write()
+            .unwrap()
+            .entry(account_id.to_string())
+            .or_default()
+            .clone()
+    }
+}
+
+/// Platform that keeps the account's server clock measured, reporting the skew in
+/// its diagnostics and health and alerting while it is above the threshold
+pub struct ClockSyncPlatform {
+    inner: Arc<dyn ITradingPlatform + Send + Sync>,
+    account_id: String,
+    clock: ServerClock,
+    config: ClockSyncConfig,
+    gateway: Option<Arc<AlertGateway>>,
+    alerting: AtomicBool,
+}
+
+impl ClockSyncPlatform {
+    pub fn new(
+        inner: Arc<dyn ITradingPlatform + Send + Sync>,
+        account_id: String,
+        clock: ServerClock,
+        config: ClockSyncConfig,
+    ) -> Self {
+        Self {
+            inner,
+            account_id,
+            clock,
+            config,
+            gateway: None,
+            alerting: AtomicBool::new(false),
+        }
+    }
+
+    /// Alert when the skew goes above the threshold, resolved once it is back under
+    pub fn with_alert_gateway(mut self, gateway: Arc<AlertGateway>) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
+    pub fn clock(&self) -> &ServerClock {
+        &self.clock
+    }
+
+    fn is_skewed(&self, offset_ms: i64) -> bool {
+        offset_ms.unsigned_abs() > self.config.alert_threshold_ms
+    }
+
+    /// Measure the skew once
+    pub async fn sync(&self) -> Result<ClockReading, PlatformError> {
+        let reading = self.clock.measure(self.inner.as_ref()).await?;
+        PLATFORM_CLOCK_OFFSET_MS
+            .with_label_values(&[&self.account_id])
+            .set(reading.offset_ms);
+        debug!(
+            "Platform clock of account {} is {}ms off ours",
+            self.account_id, reading.offset_ms
+        );
+
+        let skewed = self.is_skewed(reading.offset_ms);
+        if skewed == self.alerting.swap(skewed, Ordering::SeqCst) {
+            return Ok(reading);
+        }
+        if skewed {
+            warn!(
+                "Platform clock of account {} is {}ms off ours, above the {}ms threshold",
+                self.account_id, reading.offset_ms, self.config.alert_threshold_ms
+            );
+        }
+        let Some(gateway) = self.gateway.clone() else {
+            return Ok(reading);
+        };
+        if !skewed {
+            gateway.resolve(CLOCK_SKEW_ALERT_TYPE, &self.account_id);
+            return Ok(reading);
+        }
+        let alert = Alert {
+            alert_type: CLOCK_SKEW_ALERT_TYPE.to_string(),
+            key: self.account_id.clone(),
+            severity: AlertLevel::Warning,
+            account_id: None,
+            message: format!(
+                "Platform clock of account {} is {}ms off ours; time-sensitive checks are adjusted by it",
+                self.account_id, reading.offset_ms
+            ),
+            raised_at: reading.measured_at,
+        };
+        spawn_isolated("clock-skew-alert-delivery", async move {
+            gateway.submit(alert).await;
+        });
+        Ok(reading)
+    }
+
+    /// Measure the skew every interval until the platform is dropped. Stops, with
+    /// a warning, on platforms that cannot report their server time.
+    pub fn spawn_syncing(self: &Arc<Self>) -> JoinHandle<Result<(), String>> {
+        let platform = Arc::downgrade(self);
+        let interval = std::time::Duration::from_secs(self.config.interval_secs);
+        spawn_isolated("platform-clock-sync", async move {
+            loop {
+                let Some(platform) = platform.upgrade() else {
+                    return;
+                };
+                match platform.sync().await {
+                    Ok(_) => {}
+                    Err(PlatformError::FeatureNotSupported { .. }) => {
+                        warn!(
+                            "{} cannot report its server time: clock skew of account {} is not measured",
+                            platform.inner.platform_name(),
+                            platform.account_id
+                        );
+                        return;
+                    }
+                    Err(e) => warn!(
+                        "Failed to read the server time of account {}: {}",
+                        platform.account_id, e
+                    ),
+                }
+                drop(platform);
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl PlatformDecorator for ClockSyncPlatform {
+    type Inner = dyn ITradingPlatform + Send + Sync;
+
+    fn inner(&self) -> &Self::Inner {
+        self.inner.as_ref()
+    }
+
+    /// The wrapped platform's health, unhealthy while the skew is above the threshold
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        let mut health = self.inner.health_check().await?;
+        let offset_ms = self.clock.offset_ms();
+        if self.is_skewed(offset_ms) {
+            health.is_healthy = false;
+            health
+                .issues
+                .push(format!("Platform clock is {}ms off ours", offset_ms));
+        }
+        Ok(health)
+    }
+
+    /// The wrapped platform's diagnostics with the last skew measured
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        let mut diagnostics = self.inner.get_diagnostics().await?;
+        diagnostics.platform_specific.insert(
+            "clock".to_string(),
+            serde_json::json!({
+                "reading": self.clock.reading(),
+                "alert_threshold_ms": self.config.alert_threshold_ms,
+            }),
+        );
+        Ok(diagnostics)
+    }
+}
@@ -5,6 +5,8 @@ use tokio::sync::{Mutex, RwLock, Semaphore};
 use serde::{Deserialize, Serialize};
 
 use super::interfaces::ITradingPlatform;
+use crate::runtime::spawn::{spawn_isolated, supervised_spawn};
+use crate::runtime::supervisor::RestartPolicy;
 use super::errors::PlatformError;
 use super::factory::{PlatformConfig, PlatformFactory};
 
@@ -273,42 +275,51 @@ impl ConnectionPool {
         let cleanup_stats = Arc::clone(&self.stats);
         let cleanup_config = self.config.clone();
         
-        let cleanup_handle = tokio::spawn(async move {
-            let mut interval = tokio::time::interval(cleanup_config.cleanup_interval);
-            
-            loop {
-                interval.tick().await;
-                
-                let mut connections_to_destroy = Vec::new();
-                let mut remaining_connections = VecDeque::new();
-                
-                {
-                    let mut connections = cleanup_connections.lock().await;
-                    
-                    while let Some(conn) = connections.pop_front() {
-                        if conn.is_idle(cleanup_config.max_idle_time) || 
-                           conn.is_expired(cleanup_config.max_connection_lifetime) ||
-                           !conn.is_healthy {
-                            connections_to_destroy.push(conn);
-                        } else {
-                            remaining_connections.push_back(conn);
+        let cleanup_handle = supervised_spawn(
+            "connection-pool-cleanup",
+            RestartPolicy::default(),
+            move || {
+                let cleanup_connections = Arc::clone(&cleanup_connections);
+                let cleanup_stats = Arc::clone(&cleanup_stats);
+                let cleanup_config = cleanup_config.clone();
+                async move {
+                    let mut interval = tokio::time::interval(cleanup_config.cleanup_interval);
+
+                    loop {
+                        interval.tick().await;
+
+                        let mut connections_to_destroy = Vec::new();
+                        let mut remaining_connections = VecDeque::new();
+
+                        {
+                            let mut connections = cleanup_connections.lock().await;
+
+                            while let Some(conn) = connections.pop_front() {
+                                if conn.is_idle(cleanup_config.max_idle_time) || 
+                                   conn.is_expired(cleanup_config.max_connection_lifetime) ||
+                                   !conn.is_healthy {
+                                    connections_to_destroy.push(conn);
+                                } else {
+                                    remaining_connections.push_back(conn);
+                                }
+                            }
+
+                            *connections = remaining_connections;
+                        }
+
+                        // Update stats and destroy expired connections
+                        if !connections_to_destroy.is_empty() {
+                            let mut stats = cleanup_stats.write().await;
+                            stats.total_destroyed += connections_to_destroy.len() as u64;
+                            stats.total_connections = stats.total_connections.saturating_sub(connections_to_destroy.len());
+                            stats.idle_connections = stats.idle_connections.saturating_sub(connections_to_destroy.len());
                         }
+
+                        // Cleanup happens when connections are dropped
                     }
-                    
-                    *connections = remaining_connections;
-                }
-                
-                // Update stats and destroy expired connections
-                if !connections_to_destroy.is_empty() {
-                    let mut stats = cleanup_stats.write().await;
-                    stats.total_destroyed += connections_to_destroy.len() as u64;
-                    stats.total_connections = stats.total_connections.saturating_sub(connections_to_destroy.len());
-                    stats.idle_connections = stats.idle_connections.saturating_sub(connections_to_destroy.len());
                 }
-                
-                // Cleanup happens when connections are dropped
-            }
-        });
+            },
+        );
         
         self.cleanup_handle = Some(cleanup_handle);
 
@@ -316,24 +327,32 @@ impl ConnectionPool {
         let health_connections = Arc::clone(&self.connections);
         let health_config = self.config.clone();
         
-        let health_handle = tokio::spawn(async move {
-            let mut interval = tokio::time::interval(health_config.health_check_interval);
-            
-            loop {
-                interval.tick().await;
-                
-                let mut connections = health_connections.lock().await;
-                let mut unhealthy_count = 0;
-                
-                for conn in connections.iter_mut() {
-                    if !conn.check_health().await {
-                        unhealthy_count += 1;
+        let health_handle = supervised_spawn(
+            "connection-pool-health",
+            RestartPolicy::default(),
+            move || {
+                let health_connections = Arc::clone(&health_connections);
+                let health_config = health_config.clone();
+                async move {
+                    let mut interval = tokio::time::interval(health_config.health_check_interval);
+
+                    loop {
+                        interval.tick().await;
+
+                        let mut connections = health_connections.lock().await;
+                        let mut unhealthy_count = 0;
+
+                        for conn in connections.iter_mut() {
+                            if !conn.check_health().await {
+                                unhealthy_count += 1;
+                            }
+                        }
+
+                        drop(connections);
                     }
                 }
-                
-                drop(connections);
-            }
-        });
+            },
+        );
         
         self.health_check_handle = Some(health_handle);
     }
@@ -468,7 +487,7 @@ impl Drop for ConnectionHandle {
             let pool_stats = Arc::clone(&self.pool_stats);
             
             // Return connection to pool asynchronously
-            tokio::spawn(async move {
+            spawn_isolated("connection-pool-return", async move {
                 // Update stats
                 {
                     let mut stats = pool_stats.write().await;
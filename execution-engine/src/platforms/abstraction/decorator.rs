@@ -0,0 +1,383 @@
+// Base for platforms that wrap another one: the wrapper names the platform it wraps
+// and overrides only the calls it changes, and every other call reaches the wrapped
+// platform untouched.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use tokio::sync::mpsc;
+
+use super::capabilities::{PlatformCapabilities, PlatformFeature};
+use super::errors::PlatformError;
+use super::events::PlatformEvent;
+use super::interfaces::{
+    DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter,
+};
+use super::models::*;
+use crate::platforms::PlatformType;
+
+/// A platform layered over `inner`. Every method defaults to the same call on
+/// `inner`, and `ITradingPlatform` is implemented for each decorator by handing
+/// each call to the method here, so a wrapper cannot drop a call by forgetting
+/// to forward it. A method added to `ITradingPlatform` is added here and to the
+/// impl below.
+///
+/// The wrapped platform is connected before it is wrapped and disconnected by
+/// whoever connected it (the runtime connectors register a shutdown step for
+/// each account), so `connect` and `disconnect` do nothing by default.
+#[async_trait]
+pub trait PlatformDecorator: Send + Sync {
+    type Inner: ITradingPlatform + ?Sized;
+
+    /// The platform calls go to unless overridden
+    fn inner(&self) -> &Self::Inner;
+
+    fn platform_type(&self) -> PlatformType {
+        self.inner().platform_type()
+    }
+
+    fn platform_name(&self) -> &str {
+        self.inner().platform_name()
+    }
+
+    fn platform_version(&self) -> &str {
+        self.inner().platform_version()
+    }
+
+    async fn connect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.inner().is_connected().await
+    }
+
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        self.inner().ping().await
+    }
+
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.inner().place_order(order).await
+    }
+
+    async fn modify_order(
+        &self,
+        order_id: &str,
+        modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.inner().modify_order(order_id, modifications).await
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<(), PlatformError> {
+        self.inner().cancel_order(order_id).await
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.inner().get_order(order_id).await
+    }
+
+    async fn get_orders(
+        &self,
+        filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        self.inner().get_orders(filter).await
+    }
+
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        self.inner().get_positions().await
+    }
+
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        self.inner().get_position(symbol).await
+    }
+
+    async fn close_position(
+        &self,
+        symbol: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.inner().close_position(symbol, quantity).await
+    }
+
+    async fn get_position_tickets(&self, symbol: &str) -> Result<PositionTickets, PlatformError> {
+        self.inner().get_position_tickets(symbol).await
+    }
+
+    async fn close_position_ticket(
+        &self,
+        position_id: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.inner()
+            .close_position_ticket(position_id, quantity)
+            .await
+    }
+
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        self.inner().get_account_info().await
+    }
+
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        self.inner().get_balance().await
+    }
+
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        self.inner().get_margin_info().await
+    }
+
+    async fn get_transactions(
+        &self,
+        range: TransactionRange,
+    ) -> Result<Vec<UnifiedTransaction>, PlatformError> {
+        self.inner().get_transactions(range).await
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        self.inner().get_market_data(symbol).await
+    }
+
+    async fn subscribe_market_data(
+        &self,
+        symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        self.inner().subscribe_market_data(symbols).await
+    }
+
+    async fn unsubscribe_market_data(&self, symbols: Vec<String>) -> Result<(), PlatformError> {
+        self.inner().unsubscribe_market_data(symbols).await
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<Symbol>, PlatformError> {
+        self.inner().get_instruments().await
+    }
+
+    async fn get_order_book(
+        &self,
+        symbol: &str,
+        levels: usize,
+    ) -> Result<UnifiedOrderBook, PlatformError> {
+        self.inner().get_order_book(symbol, levels).await
+    }
+
+    async fn subscribe_depth(
+        &self,
+        symbols: Vec<String>,
+        levels: usize,
+    ) -> Result<mpsc::Receiver<UnifiedOrderBook>, PlatformError> {
+        self.inner().subscribe_depth(symbols, levels).await
+    }
+
+    async fn get_server_time(&self) -> Result<DateTime<Utc>, PlatformError> {
+        self.inner().get_server_time().await
+    }
+
+    fn capabilities(&self) -> PlatformCapabilities {
+        self.inner().capabilities()
+    }
+
+    /// Judged on this decorator's capabilities, which may differ from the
+    /// wrapped platform's
+    fn supports_feature(&self, feature: PlatformFeature) -> bool {
+        PlatformDecorator::capabilities(self).supports_feature(feature)
+    }
+
+    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
+        self.inner().subscribe_events().await
+    }
+
+    async fn get_event_history(
+        &self,
+        filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        self.inner().get_event_history(filter).await
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        self.inner().health_check().await
+    }
+
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        self.inner().get_diagnostics().await
+    }
+}
+
+#[async_trait]
+impl<T: PlatformDecorator> ITradingPlatform for T {
+    fn platform_type(&self) -> PlatformType {
+        PlatformDecorator::platform_type(self)
+    }
+
+    fn platform_name(&self) -> &str {
+        PlatformDecorator::platform_name(self)
+    }
+
+    fn platform_version(&self) -> &str {
+        PlatformDecorator::platform_version(self)
+    }
+
+    async fn connect(&mut self) -> Result<(), PlatformError> {
+        PlatformDecorator::connect(self).await
+    }
+
+    async fn disconnect(&mut self) -> Result<(), PlatformError> {
+        PlatformDecorator::disconnect(self).await
+    }
+
+    async fn is_connected(&self) -> bool {
+        PlatformDecorator::is_connected(self).await
+    }
+
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        PlatformDecorator::ping(self).await
+    }
+
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        PlatformDecorator::place_order(self, order).await
+    }
+
+    async fn modify_order(
+        &self,
+        order_id: &str,
+        modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        PlatformDecorator::modify_order(self, order_id, modifications).await
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<(), PlatformError> {
+        PlatformDecorator::cancel_order(self, order_id).await
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        PlatformDecorator::get_order(self, order_id).await
+    }
+
+    async fn get_orders(
+        &self,
+        filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        PlatformDecorator::get_orders(self, filter).await
+    }
+
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        PlatformDecorator::get_positions(self).await
+    }
+
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        PlatformDecorator::get_position(self, symbol).await
+    }
+
+    async fn close_position(
+        &self,
+        symbol: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        PlatformDecorator::close_position(self, symbol, quantity).await
+    }
+
+    async fn get_position_tickets(&self, symbol: &str) -> Result<PositionTickets, PlatformError> {
+        PlatformDecorator::get_position_tickets(self, symbol).await
+    }
+
+    async fn close_position_ticket(
+        &self,
+        position_id: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        PlatformDecorator::close_position_ticket(self, position_id, quantity).await
+    }
+
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        PlatformDecorator::get_account_info(self).await
+    }
+
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        PlatformDecorator::get_balance(self).await
+    }
+
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        PlatformDecorator::get_margin_info(self).await
+    }
+
+    async fn get_transactions(
+        &self,
+        range: TransactionRange,
+    ) -> Result<Vec<UnifiedTransaction>, PlatformError> {
+        PlatformDecorator::get_transactions(self, range).await
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        PlatformDecorator::get_market_data(self, symbol).await
+    }
+
+    async fn subscribe_market_data(
+        &self,
+        symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        PlatformDecorator::subscribe_market_data(self, symbols).await
+    }
+
+    async fn unsubscribe_market_data(&self, symbols: Vec<String>) -> Result<(), PlatformError> {
+        PlatformDecorator::unsubscribe_market_data(self, symbols).await
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<Symbol>, PlatformError> {
+        PlatformDecorator::get_instruments(self).await
+    }
+
+    async fn get_order_book(
+        &self,
+        symbol: &str,
+        levels: usize,
+    ) -> Result<UnifiedOrderBook, PlatformError> {
+        PlatformDecorator::get_order_book(self, symbol, levels).await
+    }
+
+    async fn subscribe_depth(
+        &self,
+        symbols: Vec<String>,
+        levels: usize,
+    ) -> Result<mpsc::Receiver<UnifiedOrderBook>, PlatformError> {
+        PlatformDecorator::subscribe_depth(self, symbols, levels).await
+    }
+
+    async fn get_server_time(&self) -> Result<DateTime<Utc>, PlatformError> {
+        PlatformDecorator::get_server_time(self).await
+    }
+
+    fn capabilities(&self) -> PlatformCapabilities {
+        PlatformDecorator::capabilities(self)
+    }
+
+    fn supports_feature(&self, feature: PlatformFeature) -> bool {
+        PlatformDecorator::supports_feature(self, feature)
+    }
+
+    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
+        PlatformDecorator::subscribe_events(self).await
+    }
+
+    async fn get_event_history(
+        &self,
+        filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        PlatformDecorator::get_event_history(self, filter).await
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        PlatformDecorator::health_check(self).await
+    }
+
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        PlatformDecorator::get_diagnostics(self).await
+    }
+}
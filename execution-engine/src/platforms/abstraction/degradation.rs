@@ -0,0 +1,378 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use super::capabilities::PlatformCapabilities;
+use super::decorator::PlatformDecorator;
+use super::errors::{PlatformError, ValidationError};
+use super::events::PlatformEvent;
+use super::interfaces::{
+    DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter,
+};
+use super::models::*;
+use crate::platforms::PlatformType;
+
+/// Risk parameter holding a trailing stop's distance. Without it the distance is
+/// taken from the market price when the order is placed.
+pub const TRAILING_DISTANCE_PARAM: &str = "trailing_distance";
+/// Key under which substitutions are recorded in order metadata and responses
+pub const DEGRADATIONS_KEY: &str = "degradations";
+
+/// What to do with order features a platform does not support
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DegradationPolicy {
+    /// Place trailing stops as stop orders and trail them from the engine
+    pub emulate_trailing_stops: bool,
+    /// Place GTD orders as GTC and cancel them from the engine at expiry
+    pub emulate_gtd: bool,
+    /// How often emulated trailing stops check the market
+    pub trailing_poll_interval_ms: u64,
+}
+
+impl Default for DegradationPolicy {
+    fn default() -> Self {
+        Self {
+            emulate_trailing_stops: true,
+            emulate_gtd: true,
+            trailing_poll_interval_ms: 1000,
+        }
+    }
+}
+
+/// A feature of an order that was substituted because the platform lacks it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OrderDegradation {
+    /// Trailing stop placed as a stop order that the engine moves
+    TrailingStopEmulated { trail_distance: Decimal },
+    /// Good-till-date order placed as GTC and cancelled by the engine
+    GtdAsGtc { expires_at: DateTime<Utc> },
+}
+
+impl OrderDegradation {
+    /// Order tag marking the substitution
+    pub fn tag(&self) -> &'static str {
+        match self {
+            OrderDegradation::TrailingStopEmulated { .. } => "degraded:trailing_stop",
+            OrderDegradation::GtdAsGtc { .. } => "degraded:gtd_as_gtc",
+        }
+    }
+}
+
+/// Platform wrapper that rewrites orders using features the platform lacks into
+/// ones it has, according to a [`DegradationPolicy`], instead of letting the
+/// adapter reject them. Each substitution is recorded in the order's metadata and
+/// in the response's `platform_specific` data.
+pub struct DegradingPlatform {
+    inner: Arc<dyn ITradingPlatform + Send + Sync>,
+    policy: DegradationPolicy,
+}
+
+impl DegradingPlatform {
+    pub fn new(inner: Arc<dyn ITradingPlatform + Send + Sync>, policy: DegradationPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    pub fn policy(&self) -> &DegradationPolicy {
+        &self.policy
+    }
+
+    /// Rewrite `order` for the wrapped platform's capabilities, returning the
+    /// substitutions made. Orders the platform supports as they are come back
+    /// untouched.
+    pub async fn degrade(
+        &self,
+        order: &mut UnifiedOrder,
+    ) -> Result<Vec<OrderDegradation>, PlatformError> {
+        let capabilities = self.inner.capabilities();
+        let mut degradations = Vec::new();
+
+        if order.order_type == UnifiedOrderType::TrailingStop
+            && !capabilities.supports_order_type(&UnifiedOrderType::TrailingStop)
+        {
+            if !self.policy.emulate_trailing_stops
+                || !capabilities.supports_order_type(&UnifiedOrderType::Stop)
+            {
+                return Err(unsupported(&capabilities, "trailing stop orders"));
+            }
+            let trail_distance = self.trail_distance(order).await?;
+            if order.stop_price.is_none() {
+                let quote = self.inner.get_market_data(&order.symbol).await?;
+                order.stop_price = Some(trailed_stop(&quote, &order.side, trail_distance));
+            }
+            order.order_type = UnifiedOrderType::Stop;
+            degradations.push(OrderDegradation::TrailingStopEmulated { trail_distance });
+        }
+
+        if order.time_in_force == UnifiedTimeInForce::Gtd
+            && !capabilities.supports_time_in_force(&UnifiedTimeInForce::Gtd)
+        {
+            if !self.policy.emulate_gtd
+                || !capabilities.supports_time_in_force(&UnifiedTimeInForce::Gtc)
+            {
+                return Err(unsupported(&capabilities, "good-till-date orders"));
+            }
+            let expires_at = order.metadata.expires_at.ok_or_else(|| {
+                invalid(ValidationError::MissingRequiredField {
+                    field: "metadata.expires_at".to_string(),
+                })
+            })?;
+            order.time_in_force = UnifiedTimeInForce::Gtc;
+            degradations.push(OrderDegradation::GtdAsGtc { expires_at });
+        }
+
+        if !degradations.is_empty() {
+            order
+                .metadata
+                .tags
+                .extend(degradations.iter().map(|d| d.tag().to_string()));
+            order.metadata.risk_parameters.insert(
+                DEGRADATIONS_KEY.to_string(),
+                serde_json::to_value(&degradations).unwrap_or_default(),
+            );
+        }
+        Ok(degradations)
+    }
+
+    /// Distance from the risk parameters, or between the market and the stop
+    async fn trail_distance(&self, order: &UnifiedOrder) -> Result<Decimal, PlatformError> {
+        let configured = order
+            .metadata
+            .risk_parameters
+            .get(TRAILING_DISTANCE_PARAM)
+            .and_then(|v| match v {
+                serde_json::Value::String(s) => s.parse::<Decimal>().ok(),
+                serde_json::Value::Number(n) => n.to_string().parse::<Decimal>().ok(),
+                _ => None,
+            });
+        if let Some(distance) = configured.filter(|d| *d > Decimal::ZERO) {
+            return Ok(distance);
+        }
+
+        let stop_price = order.stop_price.ok_or_else(|| {
+            invalid(ValidationError::MissingRequiredField {
+                field: "stop_price".to_string(),
+            })
+        })?;
+        let quote = self.inner.get_market_data(&order.symbol).await?;
+        let distance = (reference_price(&quote, &order.side) - stop_price).abs();
+        if distance.is_zero() {
+            return Err(invalid(ValidationError::ConflictingParameters {
+                reason: format!("trailing stop at the market price of {}", stop_price),
+            }));
+        }
+        Ok(distance)
+    }
+
+    /// Start the engine-side work a degraded order needs once it is placed
+    fn supervise(&self, order: &UnifiedOrder, order_id: &str, degradations: &[OrderDegradation]) {
+        for degradation in degradations {
+            match degradation {
+                OrderDegradation::TrailingStopEmulated { trail_distance } => {
+                    tokio::spawn(trail_stop(
+                        self.inner.clone(),
+                        order_id.to_string(),
+                        order.symbol.clone(),
+                        order.side.clone(),
+                        order.stop_price,
+                        *trail_distance,
+                        Duration::from_millis(self.policy.trailing_poll_interval_ms),
+                    ));
+                }
+                OrderDegradation::GtdAsGtc { expires_at } => {
+                    tokio::spawn(cancel_at_expiry(
+                        self.inner.clone(),
+                        order_id.to_string(),
+                        *expires_at,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn invalid(violation: ValidationError) -> PlatformError {
+    PlatformError::OrderValidationFailed {
+        violations: vec![violation],
+    }
+}
+
+fn unsupported(capabilities: &PlatformCapabilities, feature: &str) -> PlatformError {
+    PlatformError::FeatureNotSupported {
+        feature: format!("{} on {}", feature, capabilities.platform_name),
+    }
+}
+
+/// Price a stop on `side` triggers against: sell stops protect longs and fill at
+/// the bid, buy stops at the ask
+fn reference_price(quote: &UnifiedMarketData, side: &UnifiedOrderSide) -> Decimal {
+    match side {
+        UnifiedOrderSide::Sell => quote.bid,
+        UnifiedOrderSide::Buy => quote.ask,
+    }
+}
+
+/// Stop `trail_distance` behind the market
+fn trailed_stop(
+    quote: &UnifiedMarketData,
+    side: &UnifiedOrderSide,
+    trail_distance: Decimal,
+) -> Decimal {
+    match side {
+        UnifiedOrderSide::Sell => quote.bid - trail_distance,
+        UnifiedOrderSide::Buy => quote.ask + trail_distance,
+    }
+}
+
+fn is_open(status: &UnifiedOrderStatus) -> bool {
+    matches!(
+        status,
+        UnifiedOrderStatus::Pending
+            | UnifiedOrderStatus::New
+            | UnifiedOrderStatus::PartiallyFilled
+            | UnifiedOrderStatus::PendingReplace
+    )
+}
+
+/// Move a stop order after the market until the order is no longer open. The stop
+/// only ever moves in the position's favour.
+async fn trail_stop(
+    platform: Arc<dyn ITradingPlatform + Send + Sync>,
+    order_id: String,
+    symbol: String,
+    side: UnifiedOrderSide,
+    mut stop: Option<Decimal>,
+    trail_distance: Decimal,
+    interval: Duration,
+) {
+    loop {
+        tokio::time::sleep(interval).await;
+
+        match platform.get_order(&order_id).await {
+            Ok(order) if is_open(&order.status) => {}
+            Ok(order) => {
+                debug!(
+                    "Stopped trailing order {} in status {:?}",
+                    order_id, order.status
+                );
+                return;
+            }
+            Err(PlatformError::OrderNotFound { .. }) => return,
+            Err(e) => {
+                warn!("Could not check trailed order {}: {}", order_id, e);
+                continue;
+            }
+        }
+
+        let quote = match platform.get_market_data(&symbol).await {
+            Ok(quote) => quote,
+            Err(e) => {
+                warn!("No price to trail order {} on {}: {}", order_id, symbol, e);
+                continue;
+            }
+        };
+        let candidate = trailed_stop(&quote, &side, trail_distance);
+        let improves = match (stop, &side) {
+            (None, _) => true,
+            (Some(current), UnifiedOrderSide::Sell) => candidate > current,
+            (Some(current), UnifiedOrderSide::Buy) => candidate < current,
+        };
+        if !improves {
+            continue;
+        }
+
+        let modification = OrderModification {
+            quantity: None,
+            price: None,
+            stop_price: Some(candidate),
+            take_profit: None,
+            stop_loss: None,
+            time_in_force: None,
+        };
+        match platform.modify_order(&order_id, modification).await {
+            Ok(_) => {
+                debug!("Trailed stop of order {} to {}", order_id, candidate);
+                stop = Some(candidate);
+            }
+            Err(e) => warn!("Failed to trail order {}: {}", order_id, e),
+        }
+    }
+}
+
+/// Cancel a GTC order standing in for a GTD one once its expiry passes
+async fn cancel_at_expiry(
+    platform: Arc<dyn ITradingPlatform + Send + Sync>,
+    order_id: String,
+    expires_at: DateTime<Utc>,
+) {
+    let wait = (expires_at - Utc::now()).to_std().unwrap_or(Duration::ZERO);
+    tokio::time::sleep(wait).await;
+
+    match platform.get_order(&order_id).await {
+        Ok(order) if !is_open(&order.status) => return,
+        Err(PlatformError::OrderNotFound { .. }) => return,
+        _ => {}
+    }
+    match platform.cancel_order(&order_id).await {
+        Ok(()) => info!(
+            "Cancelled order {} at its expiry of {}",
+            order_id, expires_at
+        ),
+        Err(e) => warn!("Failed to cancel expired order {}: {}", order_id, e),
+    }
+}
+
+#[async_trait]
+impl PlatformDecorator for DegradingPlatform {
+    type Inner = dyn ITradingPlatform + Send + Sync;
+
+    fn inner(&self) -> &Self::Inner {
+        self.inner.as_ref()
+    }
+
+    async fn place_order(
+        &self,
+        mut order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        let degradations = self.degrade(&mut order).await?;
+        let mut response = self.inner.place_order(order.clone()).await?;
+        if !degradations.is_empty() {
+            info!(
+                "Order {} on {} placed with substitutions: {:?}",
+                response.platform_order_id, order.symbol, degradations
+            );
+            self.supervise(&order, &response.platform_order_id, &degradations);
+            if let Some(recorded) = order.metadata.risk_parameters.get(DEGRADATIONS_KEY) {
+                response
+                    .platform_specific
+                    .insert(DEGRADATIONS_KEY.to_string(), recorded.clone());
+            }
+        }
+        Ok(response)
+    }
+
+    /// The wrapped platform's capabilities plus the features it emulates
+    fn capabilities(&self) -> PlatformCapabilities {
+        let mut capabilities = self.inner.capabilities();
+        if self.policy.emulate_trailing_stops
+            && capabilities.supports_order_type(&UnifiedOrderType::Stop)
+        {
+            capabilities
+                .order_types
+                .insert(UnifiedOrderType::TrailingStop);
+        }
+        if self.policy.emulate_gtd && capabilities.supports_time_in_force(&UnifiedTimeInForce::Gtc)
+        {
+            capabilities
+                .time_in_force_options
+                .insert(UnifiedTimeInForce::Gtd);
+        }
+        capabilities
+    }
+}
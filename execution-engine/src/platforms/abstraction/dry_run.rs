@@ -0,0 +1,402 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::capabilities::PlatformCapabilities;
+use super::decorator::PlatformDecorator;
+use super::errors::PlatformError;
+use super::events::PlatformEvent;
+use super::interfaces::{
+    DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter,
+};
+use super::models::*;
+use crate::platforms::PlatformType;
+
+/// Prefix of the order ids handed back for orders that were only recorded
+pub const DRY_RUN_ORDER_PREFIX: &str = "dry-run-";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DryRunConfig {
+    /// Record orders instead of sending them, on every account
+    pub enabled: bool,
+    /// Per-account switches, taking precedence over `enabled`
+    pub accounts: HashMap<String, bool>,
+    /// Recorded orders kept for inspection
+    pub history: usize,
+}
+
+impl Default for DryRunConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            accounts: HashMap::new(),
+            history: 1_000,
+        }
+    }
+}
+
+/// An order-changing call that was recorded instead of sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DryRunRequest {
+    PlaceOrder {
+        order: UnifiedOrder,
+    },
+    ModifyOrder {
+        order_id: String,
+        modification: OrderModification,
+    },
+    CancelOrder {
+        order_id: String,
+    },
+    ClosePosition {
+        symbol: String,
+        quantity: Option<Decimal>,
+    },
+    ClosePositionTicket {
+        position_id: String,
+        quantity: Option<Decimal>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunRecord {
+    pub account_id: String,
+    pub request: DryRunRequest,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Which accounts are in dry run, switchable at runtime, and the orders their
+/// platforms did not receive
+#[derive(Debug)]
+pub struct DryRunMode {
+    global: AtomicBool,
+    accounts: RwLock<HashMap<String, bool>>,
+    recorded: Mutex<VecDeque<DryRunRecord>>,
+    history: usize,
+}
+
+/// The dry-run switches, as reported by the admin API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunState {
+    pub global: bool,
+    pub accounts: HashMap<String, bool>,
+}
+
+impl DryRunMode {
+    pub fn new(config: &DryRunConfig) -> Self {
+        Self {
+            global: AtomicBool::new(config.enabled),
+            accounts: RwLock::new(config.accounts.clone()),
+            recorded: Mutex::new(VecDeque::new()),
+            history: config.history,
+        }
+    }
+
+    pub fn is_active(&self, account_id: &str) -> bool {
+        self.accounts
+            .read()
+            .unwrap()
+            .get(account_id)
+            .copied()
+            .unwrap_or_else(|| self.global.load(Ordering::SeqCst))
+    }
+
+    pub fn set_global(&self, enabled: bool) {
+        self.global.store(enabled, Ordering::SeqCst);
+        if enabled {
+            warn!("Dry run enabled: orders are recorded instead of sent");
+        } else {
+            info!("Dry run disabled: orders are sent to platforms");
+        }
+    }
+
+    /// Switch dry run for `account_id`, or clear its switch with `None` so the
+    /// global one applies
+    pub fn set_account(&self, account_id: &str, enabled: Option<bool>) {
+        let mut accounts = self.accounts.write().unwrap();
+        match enabled {
+            Some(enabled) => {
+                accounts.insert(account_id.to_string(), enabled);
+            }
+            None => {
+                accounts.remove(account_id);
+            }
+        }
+        info!("Dry run for account {} set to {:?}", account_id, enabled);
+    }
+
+    pub fn state(&self) -> DryRunState {
+        DryRunState {
+            global: self.global.load(Ordering::SeqCst),
+            accounts: self.accounts.read().unwrap().clone(),
+        }
+    }
+
+    fn record(&self, account_id: &str, request: DryRunRequest) {
+        info!("Dry run on account {}, not sent: {:?}", account_id, request);
+        let mut recorded = self.recorded.lock().unwrap();
+        recorded.push_back(DryRunRecord {
+            account_id: account_id.to_string(),
+            request,
+            recorded_at: Utc::now(),
+        });
+        while recorded.len() > self.history {
+            recorded.pop_front();
+        }
+    }
+
+    /// Up to `limit` recorded orders, most recent first, of one account or all
+    pub fn recorded(&self, account_id: Option<&str>, limit: usize) -> Vec<DryRunRecord> {
+        let recorded = self.recorded.lock().unwrap();
+        recorded
+            .iter()
+            .rev()
+            .filter(|record| account_id.map_or(true, |id| record.account_id == id))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Platform wrapper that, while its account is in dry run, records order
+/// changes instead of sending them and answers as the platform would have
+/// accepted them. Reads always reach the platform, so planning, risk checks and
+/// exit management run against the live account.
+pub struct DryRunPlatform {
+    inner: Arc<dyn ITradingPlatform + Send + Sync>,
+    account_id: String,
+    mode: Arc<DryRunMode>,
+}
+
+impl DryRunPlatform {
+    pub fn new(
+        inner: Arc<dyn ITradingPlatform + Send + Sync>,
+        account_id: impl Into<String>,
+        mode: Arc<DryRunMode>,
+    ) -> Self {
+        Self {
+            inner,
+            account_id: account_id.into(),
+            mode,
+        }
+    }
+
+    fn active(&self) -> bool {
+        self.mode.is_active(&self.account_id)
+    }
+
+    /// Mid price of `symbol`, if the platform has a quote
+    async fn mid(&self, symbol: &str) -> Option<Decimal> {
+        self.inner
+            .get_market_data(symbol)
+            .await
+            .ok()
+            .map(|quote| (quote.bid + quote.ask) / Decimal::TWO)
+    }
+
+    /// A fill of `position` at its current price, as closing it would have given
+    fn closed(
+        &self,
+        position: &UnifiedPosition,
+        quantity: Option<Decimal>,
+    ) -> UnifiedOrderResponse {
+        let now = Utc::now();
+        let quantity = quantity.unwrap_or(position.quantity);
+        UnifiedOrderResponse {
+            platform_order_id: dry_run_order_id(),
+            client_order_id: String::new(),
+            status: UnifiedOrderStatus::Filled,
+            symbol: position.symbol.clone(),
+            side: match position.side {
+                UnifiedPositionSide::Long => UnifiedOrderSide::Sell,
+                UnifiedPositionSide::Short => UnifiedOrderSide::Buy,
+            },
+            order_type: UnifiedOrderType::Market,
+            quantity,
+            filled_quantity: quantity,
+            remaining_quantity: Decimal::ZERO,
+            price: Some(position.current_price),
+            average_fill_price: Some(position.current_price),
+            commission: None,
+            created_at: now,
+            updated_at: now,
+            filled_at: Some(now),
+            platform_specific: dry_run_marker(),
+        }
+    }
+}
+
+fn dry_run_order_id() -> String {
+    format!("{}{}", DRY_RUN_ORDER_PREFIX, Uuid::new_v4())
+}
+
+fn dry_run_marker() -> HashMap<String, serde_json::Value> {
+    HashMap::from([("dry_run".to_string(), serde_json::Value::Bool(true))])
+}
+
+#[async_trait]
+impl PlatformDecorator for DryRunPlatform {
+    type Inner = dyn ITradingPlatform + Send + Sync;
+
+    fn inner(&self) -> &Self::Inner {
+        self.inner.as_ref()
+    }
+
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        if !self.active() {
+            return self.inner.place_order(order).await;
+        }
+        let price = match order.price {
+            Some(price) => Some(price),
+            None => self.mid(&order.symbol).await,
+        };
+        let now = Utc::now();
+        let response = UnifiedOrderResponse {
+            platform_order_id: dry_run_order_id(),
+            client_order_id: order.client_order_id.clone(),
+            status: UnifiedOrderStatus::New,
+            symbol: order.symbol.clone(),
+            side: order.side.clone(),
+            order_type: order.order_type.clone(),
+            quantity: order.quantity,
+            filled_quantity: Decimal::ZERO,
+            remaining_quantity: order.quantity,
+            price,
+            average_fill_price: None,
+            commission: None,
+            created_at: now,
+            updated_at: now,
+            filled_at: None,
+            platform_specific: dry_run_marker(),
+        };
+        self.mode
+            .record(&self.account_id, DryRunRequest::PlaceOrder { order });
+        Ok(response)
+    }
+
+    async fn modify_order(
+        &self,
+        order_id: &str,
+        modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        if !self.active() {
+            return self.inner.modify_order(order_id, modifications).await;
+        }
+        // Exit managers modify position tickets, which are not orders on every platform
+        let now = Utc::now();
+        let mut response = match self.inner.get_order(order_id).await {
+            Ok(order) => order,
+            Err(_) => UnifiedOrderResponse {
+                platform_order_id: order_id.to_string(),
+                client_order_id: String::new(),
+                status: UnifiedOrderStatus::New,
+                symbol: String::new(),
+                side: UnifiedOrderSide::Buy,
+                order_type: UnifiedOrderType::Market,
+                quantity: Decimal::ZERO,
+                filled_quantity: Decimal::ZERO,
+                remaining_quantity: Decimal::ZERO,
+                price: None,
+                average_fill_price: None,
+                commission: None,
+                created_at: now,
+                updated_at: now,
+                filled_at: None,
+                platform_specific: HashMap::new(),
+            },
+        };
+        response.status = UnifiedOrderStatus::New;
+        response.price = modifications.price.or(response.price);
+        response.quantity = modifications.quantity.unwrap_or(response.quantity);
+        response.updated_at = now;
+        response.platform_specific.extend(dry_run_marker());
+        self.mode.record(
+            &self.account_id,
+            DryRunRequest::ModifyOrder {
+                order_id: order_id.to_string(),
+                modification: modifications,
+            },
+        );
+        Ok(response)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<(), PlatformError> {
+        if !self.active() {
+            return self.inner.cancel_order(order_id).await;
+        }
+        self.mode.record(
+            &self.account_id,
+            DryRunRequest::CancelOrder {
+                order_id: order_id.to_string(),
+            },
+        );
+        Ok(())
+    }
+
+    async fn close_position(
+        &self,
+        symbol: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        if !self.active() {
+            return self.inner.close_position(symbol, quantity).await;
+        }
+        let position = self.inner.get_position(symbol).await?.ok_or_else(|| {
+            PlatformError::PositionNotFound {
+                symbol: symbol.to_string(),
+            }
+        })?;
+        let response = self.closed(&position, quantity);
+        self.mode.record(
+            &self.account_id,
+            DryRunRequest::ClosePosition {
+                symbol: symbol.to_string(),
+                quantity,
+            },
+        );
+        Ok(response)
+    }
+
+    async fn close_position_ticket(
+        &self,
+        position_id: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        if !self.active() {
+            return self
+                .inner
+                .close_position_ticket(position_id, quantity)
+                .await;
+        }
+        let position = self
+            .inner
+            .get_positions()
+            .await?
+            .into_iter()
+            .find(|p| p.position_id == position_id)
+            .ok_or_else(|| PlatformError::TicketNotFound {
+                position_id: position_id.to_string(),
+            })?;
+        let response = self.closed(&position, quantity);
+        self.mode.record(
+            &self.account_id,
+            DryRunRequest::ClosePositionTicket {
+                position_id: position_id.to_string(),
+                quantity,
+            },
+        );
+        Ok(response)
+    }
+}
@@ -38,6 +38,9 @@ pub enum PlatformError {
     #[error("Position not found: {symbol}")]
     PositionNotFound { symbol: String },
 
+    #[error("Position ticket not found: {position_id}")]
+    TicketNotFound { position_id: String },
+
     #[error("Insufficient margin: required {required}, available {available}")]
     InsufficientMargin {
         required: rust_decimal::Decimal,
@@ -129,43 +132,82 @@ pub enum PlatformError {
 }
 
 impl PlatformError {
+    /// Single source of truth for how an error should be handled
+    pub fn taxonomy(&self) -> ErrorTaxonomy {
+        use ErrorClass::*;
+        use ErrorSeverity::*;
+
+        let (class, retryable, severity, backoff_ms) = match self {
+            PlatformError::ConnectionFailed { .. } => (Connectivity, false, Critical, None),
+            PlatformError::ConnectionTimeout { .. } => (Connectivity, true, Medium, Some(5000)),
+            PlatformError::Disconnected { .. } => (Connectivity, false, High, None),
+            PlatformError::NetworkError { .. } => (Connectivity, true, Medium, Some(2000)),
+            PlatformError::AuthenticationFailed { .. } => (Authentication, false, Critical, None),
+            PlatformError::InvalidCredentials { .. } => (Authentication, false, Critical, None),
+            PlatformError::RateLimitExceeded { retry_after_ms } => {
+                (Throttling, true, Medium, Some(*retry_after_ms))
+            }
+            PlatformError::ApiLimitReached { .. } => (Throttling, false, High, None),
+            PlatformError::RequestTimeout { .. } => (Timeout, true, Low, Some(1000)),
+            PlatformError::MarketDataUnavailable { .. } => (MarketData, true, Medium, None),
+            PlatformError::SubscriptionFailed { .. } => (MarketData, false, Medium, None),
+            PlatformError::OrderValidationFailed { .. }
+            | PlatformError::OrderRejected { .. }
+            | PlatformError::MarketClosed { .. }
+            | PlatformError::TradingNotAllowed { .. } => (Rejection, false, Medium, None),
+            PlatformError::InsufficientMargin { .. } | PlatformError::InsufficientFunds { .. } => {
+                (Rejection, false, High, None)
+            }
+            PlatformError::OrderNotFound { .. }
+            | PlatformError::PositionNotFound { .. }
+            | PlatformError::TicketNotFound { .. }
+            | PlatformError::SymbolNotFound { .. }
+            | PlatformError::AccountNotFound { .. } => (NotFound, false, Medium, None),
+            PlatformError::FeatureNotSupported { .. } => (Unsupported, false, Low, None),
+            PlatformError::PlatformNotSupported { .. }
+            | PlatformError::PlatformNotFound { .. }
+            | PlatformError::ConfigurationError { .. }
+            | PlatformError::InitializationFailed { .. } => (Configuration, false, High, None),
+            PlatformError::OrderModificationFailed { .. }
+            | PlatformError::PositionCloseFailed { .. }
+            | PlatformError::InvalidResponse { .. }
+            | PlatformError::InternalError { .. }
+            | PlatformError::TradeLocker { .. }
+            | PlatformError::DXTrade { .. }
+            | PlatformError::MetaTrader { .. } => (Platform, false, Medium, None),
+            PlatformError::Unknown { .. } => (ErrorClass::Unknown, false, Medium, None),
+        };
+
+        ErrorTaxonomy {
+            class,
+            retryable,
+            severity,
+            backoff_ms,
+        }
+    }
+
+    pub fn class(&self) -> ErrorClass {
+        self.taxonomy().class
+    }
+
     /// Check if error is recoverable (can be retried)
     pub fn is_recoverable(&self) -> bool {
-        matches!(
-            self,
-            PlatformError::ConnectionTimeout { .. }
-                | PlatformError::NetworkError { .. }
-                | PlatformError::RequestTimeout { .. }
-                | PlatformError::RateLimitExceeded { .. }
-                | PlatformError::MarketDataUnavailable { .. }
-        )
+        self.taxonomy().retryable
     }
 
     /// Get error severity level
     pub fn severity(&self) -> ErrorSeverity {
-        match self {
-            PlatformError::ConnectionFailed { .. } => ErrorSeverity::Critical,
-            PlatformError::AuthenticationFailed { .. } => ErrorSeverity::Critical,
-            PlatformError::Disconnected { .. } => ErrorSeverity::High,
-            PlatformError::OrderValidationFailed { .. } => ErrorSeverity::Medium,
-            PlatformError::InsufficientMargin { .. } => ErrorSeverity::High,
-            PlatformError::InsufficientFunds { .. } => ErrorSeverity::High,
-            PlatformError::RateLimitExceeded { .. } => ErrorSeverity::Medium,
-            PlatformError::NetworkError { .. } => ErrorSeverity::Medium,
-            PlatformError::RequestTimeout { .. } => ErrorSeverity::Low,
-            _ => ErrorSeverity::Medium,
-        }
+        self.taxonomy().severity
     }
 
     /// Get suggested retry delay in milliseconds
     pub fn retry_delay(&self) -> Option<u64> {
-        match self {
-            PlatformError::ConnectionTimeout { .. } => Some(5000),
-            PlatformError::NetworkError { .. } => Some(2000),
-            PlatformError::RequestTimeout { .. } => Some(1000),
-            PlatformError::RateLimitExceeded { retry_after_ms } => Some(*retry_after_ms),
-            _ => None,
-        }
+        self.taxonomy().backoff_ms
+    }
+
+    /// Whether the error says something about platform health rather than the request
+    pub fn trips_circuit_breaker(&self) -> bool {
+        self.class().trips_circuit_breaker()
     }
 
     /// Convert to standardized error code
@@ -182,6 +224,7 @@ impl PlatformError {
             PlatformError::PositionNotFound { .. } => "E201".to_string(),
             PlatformError::InsufficientMargin { .. } => "E202".to_string(),
             PlatformError::PositionCloseFailed { .. } => "E203".to_string(),
+            PlatformError::TicketNotFound { .. } => "E204".to_string(),
             PlatformError::SymbolNotFound { .. } => "E301".to_string(),
             PlatformError::MarketDataUnavailable { .. } => "E302".to_string(),
             PlatformError::MarketClosed { .. } => "E303".to_string(),
@@ -217,6 +260,43 @@ pub enum ErrorSeverity {
     Critical,
 }
 
+/// Broad error classes shared by retry, circuit breaking and recovery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrorClass {
+    Connectivity,
+    Authentication,
+    Throttling,
+    Timeout,
+    MarketData,
+    /// The platform refused the request on business grounds
+    Rejection,
+    NotFound,
+    Unsupported,
+    Configuration,
+    Platform,
+    Unknown,
+}
+
+impl ErrorClass {
+    /// Request-level failures don't indicate an unhealthy platform
+    pub fn trips_circuit_breaker(&self) -> bool {
+        !matches!(
+            self,
+            ErrorClass::Rejection | ErrorClass::NotFound | ErrorClass::Unsupported
+        )
+    }
+}
+
+/// Handling metadata attached to every `PlatformError` variant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorTaxonomy {
+    pub class: ErrorClass,
+    pub retryable: bool,
+    pub severity: ErrorSeverity,
+    /// Minimum delay before retrying, when the error dictates one
+    pub backoff_ms: Option<u64>,
+}
+
 /// Order validation errors
 #[derive(Error, Debug, Clone, Serialize, Deserialize)]
 pub enum ValidationError {
@@ -235,6 +315,13 @@ pub enum ValidationError {
     #[error("Order size too large: maximum {max_size}")]
     OrderTooLarge { max_size: rust_decimal::Decimal },
 
+    #[error("Quantity {quantity} of {symbol} is not tradable: {reason}")]
+    UntradableQuantity {
+        symbol: String,
+        quantity: rust_decimal::Decimal,
+        reason: String,
+    },
+
     #[error("Invalid order type for symbol")]
     InvalidOrderTypeForSymbol,
 
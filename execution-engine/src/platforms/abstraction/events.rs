@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tokio::sync::mpsc;
 use uuid::Uuid;
 
 use super::models::*;
+use crate::runtime::channel::{
+    bounded, BoundedReceiver, BoundedSender, ChannelStats, OverflowPolicy,
+};
 
 /// Unified event system for all platform events
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +41,20 @@ impl PlatformEvent {
         }
     }
 
+    /// Event emitted by adapters when a close order fills against a position
+    pub fn position_close(
+        platform_type: crate::platforms::PlatformType,
+        account_id: String,
+        data: PositionCloseEventData,
+    ) -> Self {
+        Self::new(
+            data.event_type(),
+            platform_type,
+            account_id,
+            EventData::PositionClose(data),
+        )
+    }
+
     pub fn with_correlation_id(mut self, correlation_id: Uuid) -> Self {
         self.correlation_id = Some(correlation_id);
         self
@@ -72,6 +88,7 @@ pub enum EventType {
     // Position events
     PositionOpened,
     PositionClosed,
+    PositionReduced,
     PositionModified,
     PositionMarginCall,
     PositionStopOut,
@@ -113,6 +130,21 @@ pub enum EventType {
     Custom(String),
 }
 
+impl EventType {
+    /// Superseded updates may be dropped under backpressure; everything else is kept
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        match self {
+            Self::MarketDataUpdate
+            | Self::AccountBalanceUpdate
+            | Self::AccountMarginUpdate
+            | Self::AccountEquityUpdate
+            | Self::Heartbeat
+            | Self::PerformanceMetric => OverflowPolicy::DropOldest,
+            _ => OverflowPolicy::Block,
+        }
+    }
+}
+
 /// Event data union
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -120,6 +152,7 @@ pub enum EventData {
     Connection(ConnectionEventData),
     Order(OrderEventData),
     Position(PositionEventData),
+    PositionClose(PositionCloseEventData),
     MarketData(MarketDataEventData),
     Account(AccountEventData),
     Platform(PlatformEventData),
@@ -164,6 +197,70 @@ pub struct PositionEventData {
     pub pnl_change: Option<rust_decimal::Decimal>,
 }
 
+/// Realized outcome of a close order fill, full or partial
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionCloseEventData {
+    pub position_id: String,
+    pub symbol: String,
+    pub side: UnifiedPositionSide,
+    pub closing_order_id: String,
+    pub entry_price: rust_decimal::Decimal,
+    pub close_price: rust_decimal::Decimal,
+    pub closed_quantity: rust_decimal::Decimal,
+    pub remaining_quantity: rust_decimal::Decimal,
+    pub realized_pnl: rust_decimal::Decimal,
+    pub commission: rust_decimal::Decimal,
+    pub closed_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl PositionCloseEventData {
+    /// Build close data from the position being closed and the fill of its closing order.
+    /// Returns `None` if the order has not filled anything yet.
+    pub fn from_close_fill(
+        position: &UnifiedPosition,
+        fill: &UnifiedOrderResponse,
+    ) -> Option<Self> {
+        if fill.filled_quantity <= rust_decimal::Decimal::ZERO {
+            return None;
+        }
+
+        let close_price = fill.average_fill_price.or(fill.price)?;
+        let closed_quantity = fill.filled_quantity.min(position.quantity);
+        let price_diff = match position.side {
+            UnifiedPositionSide::Long => close_price - position.entry_price,
+            UnifiedPositionSide::Short => position.entry_price - close_price,
+        };
+        let commission = fill.commission.unwrap_or(rust_decimal::Decimal::ZERO);
+
+        Some(Self {
+            position_id: position.position_id.clone(),
+            symbol: position.symbol.clone(),
+            side: position.side.clone(),
+            closing_order_id: fill.platform_order_id.clone(),
+            entry_price: position.entry_price,
+            close_price,
+            closed_quantity,
+            remaining_quantity: position.quantity - closed_quantity,
+            realized_pnl: price_diff * closed_quantity - commission,
+            commission,
+            closed_at: fill.filled_at.unwrap_or(fill.updated_at),
+        })
+    }
+
+    pub fn is_full_close(&self) -> bool {
+        self.remaining_quantity <= rust_decimal::Decimal::ZERO
+    }
+
+    /// `PositionClosed` for a full close, `PositionReduced` otherwise
+    pub fn event_type(&self) -> EventType {
+        if self.is_full_close() {
+            EventType::PositionClosed
+        } else {
+            EventType::PositionReduced
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketDataEventData {
     pub market_data: UnifiedMarketData,
@@ -263,7 +360,8 @@ pub struct CustomEventData {
 
 /// Unified event bus for aggregating events from multiple platforms
 pub struct UnifiedEventBus {
-    publishers: Vec<mpsc::UnboundedSender<PlatformEvent>>,
+    publishers: Vec<BoundedSender<PlatformEvent>>,
+    subscriber_capacity: usize,
     sequence_counter: std::sync::atomic::AtomicU64,
     event_store: Option<Box<dyn EventStore>>,
     filters: Vec<EventFilter>,
@@ -273,6 +371,7 @@ impl UnifiedEventBus {
     pub fn new() -> Self {
         Self {
             publishers: Vec::new(),
+            subscriber_capacity: 1024,
             sequence_counter: std::sync::atomic::AtomicU64::new(0),
             event_store: None,
             filters: Vec::new(),
@@ -284,12 +383,24 @@ impl UnifiedEventBus {
         self
     }
 
-    pub fn subscribe(&mut self) -> mpsc::UnboundedReceiver<PlatformEvent> {
-        let (tx, rx) = mpsc::unbounded_channel();
+    /// Events each subscriber may have queued before overflow handling kicks in
+    pub fn with_subscriber_capacity(mut self, capacity: usize) -> Self {
+        self.subscriber_capacity = capacity;
+        self
+    }
+
+    pub fn subscribe(&mut self) -> BoundedReceiver<PlatformEvent> {
+        let name = format!("event-bus-subscriber-{}", self.publishers.len());
+        let (tx, rx) = bounded(&name, self.subscriber_capacity, OverflowPolicy::Block);
         self.publishers.push(tx);
         rx
     }
 
+    /// Queue depth of every subscriber
+    pub fn queue_stats(&self) -> Vec<ChannelStats> {
+        self.publishers.iter().map(|p| p.stats()).collect()
+    }
+
     pub async fn publish(&self, mut event: PlatformEvent) {
         // Set sequence number
         event.sequence_number = self
@@ -308,9 +419,11 @@ impl UnifiedEventBus {
             }
         }
 
-        // Publish to all subscribers
+        // Publish to all subscribers; a slow subscriber holds up order events but
+        // only loses stale market data
+        let policy = event.event_type.overflow_policy();
         for publisher in &self.publishers {
-            if let Err(_) = publisher.send(event.clone()) {
+            if let Err(_) = publisher.send_with(event.clone(), policy).await {
                 // Subscriber disconnected, could remove from list
             }
         }
@@ -476,6 +589,12 @@ impl EventDeduplicator {
                     event.account_id, data.position.symbol, data.position.quantity
                 )
             }
+            EventData::PositionClose(data) => {
+                format!(
+                    "position_close:{}:{}:{}",
+                    event.account_id, data.position_id, data.closing_order_id
+                )
+            }
             EventData::MarketData(data) => {
                 format!(
                     "market:{}:{}:{}",
@@ -6,6 +6,7 @@ use crate::platforms::{PlatformType, TradingPlatform};
 use super::interfaces::ITradingPlatform;
 use super::adapters::{TradeLockerAdapter, DXTradeAdapter};
 use super::errors::PlatformError;
+pub use super::retry::RetryConfig;
 
 /// Platform configuration union
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,28 +78,6 @@ impl PlatformConfig {
     }
 }
 
-/// Retry configuration for platform operations
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RetryConfig {
-    pub max_retries: u32,
-    pub initial_delay_ms: u64,
-    pub max_delay_ms: u64,
-    pub backoff_multiplier: f64,
-    pub jitter: bool,
-}
-
-impl Default for RetryConfig {
-    fn default() -> Self {
-        Self {
-            max_retries: 3,
-            initial_delay_ms: 1000,
-            max_delay_ms: 30000,
-            backoff_multiplier: 2.0,
-            jitter: true,
-        }
-    }
-}
-
 /// Factory for creating platform instances
 pub struct PlatformFactory {
     builders: HashMap<PlatformType, Box<dyn PlatformBuilder>>,
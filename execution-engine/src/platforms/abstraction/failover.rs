@@ -0,0 +1,618 @@
+// Warm standby connections to a broker's other endpoints (backup FIX gateways,
+// regional REST hosts). Every endpoint stays connected and health-probed; calls go
+// to the active one and move to a healthy standby when it fails. Orders in flight
+// at the switch are looked up on the new endpoint before they are sent again.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use risk_types::AlertLevel;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use super::capabilities::PlatformCapabilities;
+use super::decorator::PlatformDecorator;
+use super::errors::{ErrorClass, PlatformError};
+use super::events::PlatformEvent;
+use super::interfaces::{
+    DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter,
+};
+use super::models::*;
+use crate::alerting::{Alert, AlertGateway};
+use crate::platforms::PlatformType;
+use crate::runtime::spawn::spawn_isolated;
+
+lazy_static! {
+    pub static ref PLATFORM_FAILOVERS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "platform_failovers_total",
+        "Switches of an account's calls to another of its platform endpoints",
+        &["account_id", "endpoint"]
+    )
+    .unwrap();
+    pub static ref FAILOVER_ORDER_REPLAYS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "platform_failover_order_replays_total",
+        "Orders in flight at a failover, by whether the new endpoint already had them",
+        &["account_id", "outcome"]
+    )
+    .unwrap();
+}
+
+/// Alert type raised when an account fails over to a standby endpoint
+pub const FAILOVER_ALERT_TYPE: &str = "platform_failover";
+
+/// One way of reaching the broker
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlatformEndpoint {
+    pub name: String,
+    /// Host or base URL, as the platform's connector expects it
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FailoverConfig {
+    /// Endpoints in order of preference; empty connects the account the usual way
+    pub endpoints: Vec<PlatformEndpoint>,
+    /// How often every endpoint is health-checked
+    pub probe_interval_ms: u64,
+    /// Failed health checks in a row after which an endpoint is down
+    pub failure_threshold: u32,
+    /// Move back to a more preferred endpoint once it is healthy again
+    pub fail_back: bool,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            probe_interval_ms: 5000,
+            failure_threshold: 2,
+            fail_back: false,
+        }
+    }
+}
+
+impl FailoverConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let mut names = HashSet::new();
+        for endpoint in &self.endpoints {
+            if endpoint.url.is_empty() {
+                return Err(format!("Endpoint {} has no url", endpoint.name));
+            }
+            if !names.insert(endpoint.name.as_str()) {
+                return Err(format!("Duplicate endpoint {}", endpoint.name));
+            }
+        }
+        if self.probe_interval_ms == 0 {
+            return Err("Failover probe_interval_ms must be above 0".to_string());
+        }
+        if self.failure_threshold == 0 {
+            return Err("Failover failure_threshold must be above 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+struct Endpoint {
+    config: PlatformEndpoint,
+    platform: Arc<dyn ITradingPlatform + Send + Sync>,
+    consecutive_failures: AtomicU32,
+}
+
+/// Whether an error means the endpoint rather than the request is at fault
+fn fails_over(error: &PlatformError) -> bool {
+    matches!(
+        error.class(),
+        ErrorClass::Connectivity | ErrorClass::Timeout
+    )
+}
+
+/// Platform over every endpoint of one account. Reads that fail on the active
+/// endpoint for connectivity reasons are repeated on the standby taken over, and
+/// orders are replayed there unless they already reached the broker. Other writes
+/// only move later calls over, as repeating them could close or change a position
+/// twice.
+pub struct FailoverPlatform {
+    account_id: String,
+    config: FailoverConfig,
+    endpoints: Vec<Endpoint>,
+    active: AtomicUsize,
+    /// Orders sent whose answer has not come back, by client order id
+    unacknowledged: Arc<Mutex<HashMap<String, UnifiedOrder>>>,
+    /// Held while the active endpoint is being switched
+    switching: Mutex<()>,
+    gateway: Option<Arc<AlertGateway>>,
+}
+
+impl FailoverPlatform {
+    /// `endpoints` are the connected ones, in order of preference
+    pub fn new(
+        account_id: String,
+        endpoints: Vec<(PlatformEndpoint, Arc<dyn ITradingPlatform + Send + Sync>)>,
+        config: FailoverConfig,
+    ) -> Result<Self, PlatformError> {
+        if endpoints.is_empty() {
+            return Err(PlatformError::ConfigurationError {
+                reason: format!("No endpoint of account {} connected", account_id),
+            });
+        }
+        Ok(Self {
+            account_id,
+            config,
+            endpoints: endpoints
+                .into_iter()
+                .map(|(config, platform)| Endpoint {
+                    config,
+                    platform,
+                    consecutive_failures: AtomicU32::new(0),
+                })
+                .collect(),
+            active: AtomicUsize::new(0),
+            unacknowledged: Arc::new(Mutex::new(HashMap::new())),
+            switching: Mutex::new(()),
+            gateway: None,
+        })
+    }
+
+    /// Alert when the account fails over, resolved when it fails back
+    pub fn with_alert_gateway(mut self, gateway: Arc<AlertGateway>) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
+    pub fn active_endpoint(&self) -> &PlatformEndpoint {
+        &self.endpoints[self.active.load(Ordering::SeqCst)].config
+    }
+
+    /// Orders sent and not yet answered
+    pub fn unacknowledged_orders(&self) -> Vec<UnifiedOrder> {
+        self.unacknowledged
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Health-check every endpoint each probe interval until the platform is dropped
+    pub fn spawn_probing(self: &Arc<Self>) -> JoinHandle<Result<(), String>> {
+        let platform = Arc::downgrade(self);
+        let interval = Duration::from_millis(self.config.probe_interval_ms);
+        spawn_isolated("platform-failover-probe", async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(platform) = platform.upgrade() else {
+                    return;
+                };
+                platform.probe().await;
+            }
+        })
+    }
+
+    /// Health-check every endpoint once, then fail over from an active endpoint
+    /// that is down, or back to a preferred one that is up again
+    pub async fn probe(&self) {
+        let timeout = Duration::from_millis(self.config.probe_interval_ms);
+        for endpoint in &self.endpoints {
+            let healthy = matches!(
+                tokio::time::timeout(timeout, endpoint.platform.health_check()).await,
+                Ok(Ok(health)) if health.is_healthy
+            );
+            if healthy {
+                endpoint.consecutive_failures.store(0, Ordering::SeqCst);
+            } else {
+                endpoint.consecutive_failures.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let active = self.active.load(Ordering::SeqCst);
+        if !self.is_up(active) {
+            self.fail_over(active, "failed its health checks");
+        } else if self.config.fail_back {
+            if let Some(preferred) = (0..active).find(|i| self.is_up(*i)) {
+                self.switch(active, preferred, "is healthy again");
+            }
+        }
+    }
+
+    fn is_up(&self, index: usize) -> bool {
+        self.endpoints[index]
+            .consecutive_failures
+            .load(Ordering::SeqCst)
+            < self.config.failure_threshold
+    }
+
+    fn active(&self) -> (usize, Arc<dyn ITradingPlatform + Send + Sync>) {
+        let index = self.active.load(Ordering::SeqCst);
+        (index, self.endpoints[index].platform.clone())
+    }
+
+    /// Take `from` out of use and move to the first healthy standby. Returns the
+    /// endpoint now active, which another caller may already have switched to; none
+    /// when no standby is healthy.
+    fn fail_over(
+        &self,
+        from: usize,
+        reason: &str,
+    ) -> Option<Arc<dyn ITradingPlatform + Send + Sync>> {
+        let _switching = self.switching.lock().unwrap();
+        self.endpoints[from]
+            .consecutive_failures
+            .fetch_max(self.config.failure_threshold, Ordering::SeqCst);
+        let active = self.active.load(Ordering::SeqCst);
+        if active != from {
+            return Some(self.endpoints[active].platform.clone());
+        }
+        let Some(to) = (0..self.endpoints.len()).find(|i| *i != from && self.is_up(*i)) else {
+            warn!(
+                "Endpoint {} of account {} {}, and no standby is healthy",
+                self.endpoints[from].config.name, self.account_id, reason
+            );
+            return None;
+        };
+        self.switch_locked(from, to, reason);
+        Some(self.endpoints[to].platform.clone())
+    }
+
+    fn switch(&self, from: usize, to: usize, reason: &str) {
+        let _switching = self.switching.lock().unwrap();
+        if self.active.load(Ordering::SeqCst) == from {
+            self.switch_locked(from, to, reason);
+        }
+    }
+
+    fn switch_locked(&self, from: usize, to: usize, reason: &str) {
+        self.active.store(to, Ordering::SeqCst);
+        let (from, to) = (&self.endpoints[from].config, &self.endpoints[to].config);
+        let in_flight = self.unacknowledged.lock().unwrap().len();
+        warn!(
+            "Account {} switched from endpoint {} to {}: {} {}; {} orders in flight",
+            self.account_id, from.name, to.name, from.name, reason, in_flight
+        );
+        PLATFORM_FAILOVERS_TOTAL
+            .with_label_values(&[&self.account_id, &to.name])
+            .inc();
+
+        let Some(gateway) = self.gateway.clone() else {
+            return;
+        };
+        let key = self.account_id.clone();
+        if to == &self.endpoints[0].config {
+            gateway.resolve(FAILOVER_ALERT_TYPE, &key);
+            return;
+        }
+        let alert = Alert {
+            alert_type: FAILOVER_ALERT_TYPE.to_string(),
+            key,
+            severity: AlertLevel::Warning,
+            account_id: None,
+            message: format!(
+                "Account {} failed over from endpoint {} to {}: {} {}",
+                self.account_id, from.name, to.name, from.name, reason
+            ),
+            raised_at: Utc::now(),
+        };
+        spawn_isolated("failover-alert-delivery", async move {
+            gateway.submit(alert).await;
+        });
+    }
+
+    /// Make a read on the active endpoint, and once more on the standby taken over
+    /// when the endpoint fails
+    async fn read<T, F, Fut>(&self, request: F) -> Result<T, PlatformError>
+    where
+        F: Fn(Arc<dyn ITradingPlatform + Send + Sync>) -> Fut,
+        Fut: Future<Output = Result<T, PlatformError>>,
+    {
+        let (index, platform) = self.active();
+        match request(platform).await {
+            Err(e) if fails_over(&e) => match self.fail_over(index, &e.to_string()) {
+                Some(standby) => request(standby).await,
+                None => Err(e),
+            },
+            result => result,
+        }
+    }
+
+    /// Make a write on the active endpoint, moving later calls to a standby when
+    /// the endpoint fails
+    async fn write<T, F, Fut>(&self, request: F) -> Result<T, PlatformError>
+    where
+        F: FnOnce(Arc<dyn ITradingPlatform + Send + Sync>) -> Fut,
+        Fut: Future<Output = Result<T, PlatformError>>,
+    {
+        let (index, platform) = self.active();
+        let result = request(platform).await;
+        if let Err(e) = &result {
+            if fails_over(e) {
+                self.fail_over(index, &e.to_string());
+            }
+        }
+        result
+    }
+
+    /// Send an order that was in flight at a failover, unless it reached the broker
+    /// through the endpoint that failed
+    async fn replay(
+        &self,
+        platform: Arc<dyn ITradingPlatform + Send + Sync>,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        let filter = OrderFilter {
+            order_id: Some(order.client_order_id.clone()),
+            symbol: Some(order.symbol.clone()),
+            status: None,
+            side: None,
+            order_type: None,
+            from: None,
+            to: None,
+            limit: None,
+        };
+        // Unable to tell whether it arrived, it is not sent again
+        let existing = platform
+            .get_orders(Some(filter))
+            .await?
+            .into_iter()
+            .find(|o| o.client_order_id == order.client_order_id);
+        if let Some(existing) = existing {
+            info!(
+                "Order {} of account {} reached the broker before the failover",
+                order.client_order_id, self.account_id
+            );
+            FAILOVER_ORDER_REPLAYS_TOTAL
+                .with_label_values(&[&self.account_id, "acknowledged"])
+                .inc();
+            return Ok(existing);
+        }
+        info!(
+            "Resending order {} of account {} after the failover",
+            order.client_order_id, self.account_id
+        );
+        FAILOVER_ORDER_REPLAYS_TOTAL
+            .with_label_values(&[&self.account_id, "resent"])
+            .inc();
+        platform.place_order(order).await
+    }
+}
+
+/// Keeps an order among the unacknowledged ones until its call returns or is dropped
+struct InFlight {
+    orders: Arc<Mutex<HashMap<String, UnifiedOrder>>>,
+    client_order_id: String,
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.orders.lock().unwrap().remove(&self.client_order_id);
+    }
+}
+
+#[async_trait]
+impl PlatformDecorator for FailoverPlatform {
+    type Inner = dyn ITradingPlatform + Send + Sync;
+
+    /// The active endpoint; calls that can fail over override this
+    fn inner(&self) -> &Self::Inner {
+        self.endpoints[self.active.load(Ordering::SeqCst)]
+            .platform
+            .as_ref()
+    }
+
+    fn platform_type(&self) -> PlatformType {
+        self.endpoints[0].platform.platform_type()
+    }
+
+    fn platform_name(&self) -> &str {
+        self.endpoints[0].platform.platform_name()
+    }
+
+    fn platform_version(&self) -> &str {
+        self.endpoints[0].platform.platform_version()
+    }
+
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        self.read(|platform| async move { platform.ping().await })
+            .await
+    }
+
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.unacknowledged
+            .lock()
+            .unwrap()
+            .insert(order.client_order_id.clone(), order.clone());
+        let _in_flight = InFlight {
+            orders: self.unacknowledged.clone(),
+            client_order_id: order.client_order_id.clone(),
+        };
+
+        let (index, platform) = self.active();
+        match platform.place_order(order.clone()).await {
+            Err(e) if fails_over(&e) => match self.fail_over(index, &e.to_string()) {
+                Some(standby) => self.replay(standby, order).await,
+                None => Err(e),
+            },
+            result => result,
+        }
+    }
+
+    async fn modify_order(
+        &self,
+        order_id: &str,
+        modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.write(|platform| async move { platform.modify_order(order_id, modifications).await })
+            .await
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<(), PlatformError> {
+        self.write(|platform| async move { platform.cancel_order(order_id).await })
+            .await
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.read(|platform| async move { platform.get_order(order_id).await })
+            .await
+    }
+
+    async fn get_orders(
+        &self,
+        filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        self.read(|platform| {
+            let filter = filter.clone();
+            async move { platform.get_orders(filter).await }
+        })
+        .await
+    }
+
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        self.read(|platform| async move { platform.get_positions().await })
+            .await
+    }
+
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        self.read(|platform| async move { platform.get_position(symbol).await })
+            .await
+    }
+
+    async fn close_position(
+        &self,
+        symbol: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.write(|platform| async move { platform.close_position(symbol, quantity).await })
+            .await
+    }
+
+    async fn get_position_tickets(&self, symbol: &str) -> Result<PositionTickets, PlatformError> {
+        self.read(|platform| async move { platform.get_position_tickets(symbol).await })
+            .await
+    }
+
+    async fn close_position_ticket(
+        &self,
+        position_id: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.write(
+            |platform| async move { platform.close_position_ticket(position_id, quantity).await },
+        )
+        .await
+    }
+
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        self.read(|platform| async move { platform.get_account_info().await })
+            .await
+    }
+
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        self.read(|platform| async move { platform.get_balance().await })
+            .await
+    }
+
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        self.read(|platform| async move { platform.get_margin_info().await })
+            .await
+    }
+
+    async fn get_transactions(
+        &self,
+        range: TransactionRange,
+    ) -> Result<Vec<UnifiedTransaction>, PlatformError> {
+        self.read(|platform| async move { platform.get_transactions(range).await })
+            .await
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        self.read(|platform| async move { platform.get_market_data(symbol).await })
+            .await
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<Symbol>, PlatformError> {
+        self.read(|platform| async move { platform.get_instruments().await })
+            .await
+    }
+
+    async fn get_order_book(
+        &self,
+        symbol: &str,
+        levels: usize,
+    ) -> Result<UnifiedOrderBook, PlatformError> {
+        self.read(|platform| async move { platform.get_order_book(symbol, levels).await })
+            .await
+    }
+
+    async fn get_server_time(&self) -> Result<DateTime<Utc>, PlatformError> {
+        self.read(|platform| async move { platform.get_server_time().await })
+            .await
+    }
+
+    fn capabilities(&self) -> PlatformCapabilities {
+        self.endpoints[0].platform.capabilities()
+    }
+
+    async fn get_event_history(
+        &self,
+        filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        self.read(|platform| {
+            let filter = filter.clone();
+            async move { platform.get_event_history(filter).await }
+        })
+        .await
+    }
+
+    /// The active endpoint's health, with every standby that is down as an issue
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        let (active, platform) = self.active();
+        let mut health = platform.health_check().await?;
+        for (index, endpoint) in self.endpoints.iter().enumerate() {
+            if index != active && !self.is_up(index) {
+                health
+                    .issues
+                    .push(format!("Standby endpoint {} is down", endpoint.config.name));
+            }
+        }
+        Ok(health)
+    }
+
+    /// The active endpoint's diagnostics, with the state of every endpoint
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        let (active, platform) = self.active();
+        let mut diagnostics = platform.get_diagnostics().await?;
+        let endpoints: Vec<_> = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(index, endpoint)| {
+                serde_json::json!({
+                    "name": endpoint.config.name,
+                    "url": endpoint.config.url,
+                    "active": index == active,
+                    "up": self.is_up(index),
+                    "consecutive_failures": endpoint.consecutive_failures.load(Ordering::SeqCst),
+                })
+            })
+            .collect();
+        diagnostics.platform_specific.insert(
+            "failover".to_string(),
+            serde_json::json!({
+                "endpoints": endpoints,
+                "unacknowledged_orders": self.unacknowledged.lock().unwrap().len(),
+            }),
+        );
+        Ok(diagnostics)
+    }
+}
@@ -38,19 +38,65 @@ pub trait ITradingPlatform: Send + Sync {
         filter: Option<OrderFilter>,
     ) -> Result<Vec<UnifiedOrderResponse>, PlatformError>;
 
-    /// Position management
+    /// Position management. `get_positions` lists every open ticket; on hedging
+    /// accounts one symbol can have several.
     async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError>;
+    /// Net position on `symbol`; on hedging accounts the tickets folded together
+    /// with `PositionTickets::net`
     async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError>;
+    /// Close the position on `symbol`. On hedging accounts `None` closes every ticket
+    /// on the symbol, while a partial quantity is only accepted when a single ticket
+    /// is open; use `close_position_ticket` to target one.
     async fn close_position(
         &self,
         symbol: &str,
         quantity: Option<rust_decimal::Decimal>,
     ) -> Result<UnifiedOrderResponse, PlatformError>;
 
+    async fn get_position_tickets(&self, symbol: &str) -> Result<PositionTickets, PlatformError> {
+        Ok(PositionTickets::from_positions(
+            symbol,
+            self.get_positions().await?,
+        ))
+    }
+
+    /// Close all or part of the ticket `position_id`, leaving any other tickets on its
+    /// symbol open. Netting platforms hold one ticket per symbol, so by default this
+    /// closes by symbol; platforms supporting `HedgedPositions` must override it.
+    async fn close_position_ticket(
+        &self,
+        position_id: &str,
+        quantity: Option<rust_decimal::Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        if self.supports_feature(PlatformFeature::HedgedPositions) {
+            return Err(PlatformError::FeatureNotSupported {
+                feature: "close_position_ticket".to_string(),
+            });
+        }
+        let ticket = self
+            .get_positions()
+            .await?
+            .into_iter()
+            .find(|p| p.position_id == position_id)
+            .ok_or_else(|| PlatformError::TicketNotFound {
+                position_id: position_id.to_string(),
+            })?;
+        self.close_position(&ticket.symbol, quantity).await
+    }
+
     /// Account management
     async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError>;
     async fn get_balance(&self) -> Result<rust_decimal::Decimal, PlatformError>;
     async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError>;
+    /// The account's statement entries within `range`, oldest first
+    async fn get_transactions(
+        &self,
+        _range: TransactionRange,
+    ) -> Result<Vec<UnifiedTransaction>, PlatformError> {
+        Err(PlatformError::FeatureNotSupported {
+            feature: "get_transactions".to_string(),
+        })
+    }
 
     /// Market data
     async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError>;
@@ -59,6 +105,40 @@ pub trait ITradingPlatform: Send + Sync {
         symbols: Vec<String>,
     ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError>;
     async fn unsubscribe_market_data(&self, symbols: Vec<String>) -> Result<(), PlatformError>;
+    /// Instruments the platform lists, under the platform's own symbol names
+    async fn get_instruments(&self) -> Result<Vec<Symbol>, PlatformError> {
+        Err(PlatformError::FeatureNotSupported {
+            feature: "get_instruments".to_string(),
+        })
+    }
+    /// Best `levels` a side of the book on `symbol`, on platforms supporting
+    /// `MarketDepth`
+    async fn get_order_book(
+        &self,
+        _symbol: &str,
+        _levels: usize,
+    ) -> Result<UnifiedOrderBook, PlatformError> {
+        Err(PlatformError::FeatureNotSupported {
+            feature: "get_order_book".to_string(),
+        })
+    }
+    /// Stream of the best `levels` a side of the book on each of `symbols`, on
+    /// platforms supporting `MarketDepth`. Dropping the receiver ends it.
+    async fn subscribe_depth(
+        &self,
+        _symbols: Vec<String>,
+        _levels: usize,
+    ) -> Result<mpsc::Receiver<UnifiedOrderBook>, PlatformError> {
+        Err(PlatformError::FeatureNotSupported {
+            feature: "subscribe_depth".to_string(),
+        })
+    }
+    /// The platform server's current time
+    async fn get_server_time(&self) -> Result<chrono::DateTime<chrono::Utc>, PlatformError> {
+        Err(PlatformError::FeatureNotSupported {
+            feature: "get_server_time".to_string(),
+        })
+    }
 
     /// Platform capabilities
     fn capabilities(&self) -> PlatformCapabilities;
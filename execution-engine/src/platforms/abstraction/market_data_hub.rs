@@ -0,0 +1,446 @@
+// One upstream market data subscription per symbol and platform, fanned out to
+// every consumer. Candles, recording, time series and exit management all
+// subscribe to the same account; without the hub each of them would open its own
+// feed with the broker.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, register_int_gauge_vec, IntCounterVec, IntGaugeVec};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+use tracing::{debug, info, warn};
+
+use super::capabilities::PlatformCapabilities;
+use super::decorator::PlatformDecorator;
+use super::errors::PlatformError;
+use super::events::PlatformEvent;
+use super::interfaces::{
+    DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter,
+};
+use super::models::*;
+use super::quota::{current_caller, UNSCOPED_CALLER};
+use crate::platforms::PlatformType;
+use crate::runtime::spawn::spawn_isolated;
+
+lazy_static! {
+    pub static ref MARKET_DATA_HUB_SYMBOLS: IntGaugeVec = register_int_gauge_vec!(
+        "market_data_hub_upstream_symbols",
+        "Symbols subscribed upstream by an account's market data hub",
+        &["account_id"]
+    )
+    .unwrap();
+    pub static ref MARKET_DATA_HUB_DROPPED_QUOTES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "market_data_hub_dropped_quotes_total",
+        "Quotes a market data consumer never received: lagged behind the hub, conflated into a later quote, or over its throttle",
+        &["account_id", "consumer", "reason"]
+    )
+    .unwrap();
+}
+
+/// How one consumer, named by the caller its subscription is made under, is fed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsumerOptions {
+    /// Hand over only the latest quote of each symbol instead of every tick
+    pub conflate: bool,
+    /// With `conflate`, hand the latest quote over once per interval instead of
+    /// whenever the consumer is ready; 0 for whenever ready
+    pub conflation_interval_ms: u64,
+    /// Most quotes handed over per second across the subscription's symbols, 0 for
+    /// no limit. Conflating consumers get the latest quote at that pace, others
+    /// lose the excess.
+    pub max_quotes_per_sec: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarketDataHubConfig {
+    pub enabled: bool,
+    /// Ticks kept for each symbol for consumers that take every tick
+    pub channel_capacity: usize,
+    /// Options of each consumer by name (`candles`, `recording`, ...); others take
+    /// every tick
+    pub consumers: HashMap<String, ConsumerOptions>,
+}
+
+impl Default for MarketDataHubConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            channel_capacity: 1024,
+            consumers: HashMap::new(),
+        }
+    }
+}
+
+impl MarketDataHubConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.channel_capacity == 0 {
+            return Err("Market data hub channel_capacity must be above 0".to_string());
+        }
+        for (consumer, options) in &self.consumers {
+            if options.conflation_interval_ms > 0 && !options.conflate {
+                return Err(format!(
+                    "Market data consumer {} has a conflation interval without conflate",
+                    consumer
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Quotes of one symbol, as they arrive
+struct SymbolFeed {
+    ticks: broadcast::Sender<UnifiedMarketData>,
+}
+
+impl SymbolFeed {
+    fn consumers(&self) -> usize {
+        self.ticks.receiver_count()
+    }
+}
+
+/// Platform wrapper sharing one upstream subscription per symbol between every
+/// consumer of the account. A consumer stops by dropping its receiver; a symbol
+/// is unsubscribed upstream once none is left on it.
+pub struct MarketDataHub {
+    inner: Arc<dyn ITradingPlatform + Send + Sync>,
+    account_id: String,
+    config: MarketDataHubConfig,
+    feeds: Arc<Mutex<HashMap<String, SymbolFeed>>>,
+    /// Held while symbols are subscribed upstream, so two consumers asking for the
+    /// same symbol at once subscribe it once
+    subscribing: tokio::sync::Mutex<()>,
+}
+
+impl MarketDataHub {
+    pub fn new(
+        inner: Arc<dyn ITradingPlatform + Send + Sync>,
+        account_id: impl Into<String>,
+        config: MarketDataHubConfig,
+    ) -> Self {
+        Self {
+            inner,
+            account_id: account_id.into(),
+            config,
+            feeds: Arc::new(Mutex::new(HashMap::new())),
+            subscribing: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Symbols subscribed upstream, with the consumers on each
+    pub fn subscriptions(&self) -> HashMap<String, usize> {
+        self.feeds
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(symbol, feed)| (symbol.clone(), feed.consumers()))
+            .collect()
+    }
+
+    /// Subscribe upstream to those of `symbols` no consumer has asked for yet
+    async fn subscribe_upstream(&self, symbols: &[String]) -> Result<(), PlatformError> {
+        let _subscribing = self.subscribing.lock().await;
+        let mut missing: Vec<String> = {
+            let feeds = self.feeds.lock().unwrap();
+            symbols
+                .iter()
+                .filter(|symbol| !feeds.contains_key(*symbol))
+                .cloned()
+                .collect()
+        };
+        missing.sort();
+        missing.dedup();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let mut upstream = self.inner.subscribe_market_data(missing.clone()).await?;
+        let mut created = Vec::new();
+        {
+            let mut feeds = self.feeds.lock().unwrap();
+            for symbol in missing {
+                let ticks = broadcast::channel(self.config.channel_capacity).0;
+                created.push((symbol.clone(), ticks.clone()));
+                feeds.insert(symbol, SymbolFeed { ticks });
+            }
+            MARKET_DATA_HUB_SYMBOLS
+                .with_label_values(&[&self.account_id])
+                .set(feeds.len() as i64);
+        }
+        info!(
+            "Subscribed {} symbols upstream for account {}",
+            created.len(),
+            self.account_id
+        );
+
+        let feeds = self.feeds.clone();
+        let account_id = self.account_id.clone();
+        spawn_isolated(format!("market-data-hub-{}", self.account_id), async move {
+            while let Some(quote) = upstream.recv().await {
+                if let Some(feed) = feeds.lock().unwrap().get(&quote.symbol) {
+                    // No consumer on the symbol right now is not an error
+                    let _ = feed.ticks.send(quote);
+                }
+            }
+            // Dropping the feeds ends every consumer's stream, and the next
+            // subscription goes upstream again. Feeds of a later subscription to the
+            // same symbols are left alone.
+            let mut feeds = feeds.lock().unwrap();
+            for (symbol, ticks) in created {
+                if feeds
+                    .get(&symbol)
+                    .is_some_and(|feed| feed.ticks.same_channel(&ticks))
+                {
+                    feeds.remove(&symbol);
+                }
+            }
+            MARKET_DATA_HUB_SYMBOLS
+                .with_label_values(&[&account_id])
+                .set(feeds.len() as i64);
+            debug!("Upstream market data of account {} ended", account_id);
+        });
+        Ok(())
+    }
+}
+
+/// Token bucket capping the quotes handed to one subscription each second
+struct Throttle {
+    per_sec: f64,
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl Throttle {
+    fn new(per_sec: u32) -> Self {
+        Self {
+            per_sec: per_sec as f64,
+            tokens: per_sec as f64,
+            refilled: Instant::now(),
+        }
+    }
+
+    /// Take a token, or say how long until one is left
+    fn take(&mut self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_sec).min(self.per_sec);
+        self.refilled = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        Err(Duration::from_secs_f64((1.0 - self.tokens) / self.per_sec))
+    }
+}
+
+/// One subscription's end of the hub, shared by the tasks feeding its symbols
+#[derive(Clone)]
+struct Consumer {
+    account_id: String,
+    name: String,
+    sender: mpsc::Sender<UnifiedMarketData>,
+    throttle: Option<Arc<Mutex<Throttle>>>,
+}
+
+impl Consumer {
+    fn dropped(&self, reason: &str, count: u64) {
+        MARKET_DATA_HUB_DROPPED_QUOTES_TOTAL
+            .with_label_values(&[&self.account_id, &self.name, reason])
+            .inc_by(count);
+    }
+
+    /// Wait until the consumer may take its next quote: the conflation interval
+    /// has passed, the throttle has a token and its channel has room
+    async fn slot(
+        &self,
+        ticker: &mut Option<Interval>,
+    ) -> Result<mpsc::Permit<'_, UnifiedMarketData>, mpsc::error::SendError<()>> {
+        if let Some(ticker) = ticker {
+            ticker.tick().await;
+        }
+        if let Some(throttle) = &self.throttle {
+            loop {
+                let taken = throttle.lock().unwrap().take();
+                match taken {
+                    Ok(()) => break,
+                    Err(wait) => tokio::time::sleep(wait).await,
+                }
+            }
+        }
+        self.sender.reserve().await
+    }
+}
+
+/// Forward every tick of one symbol until the consumer goes away, dropping those
+/// over its throttle
+async fn forward_ticks(mut ticks: broadcast::Receiver<UnifiedMarketData>, consumer: Consumer) {
+    loop {
+        tokio::select! {
+            _ = consumer.sender.closed() => return,
+            tick = ticks.recv() => match tick {
+                Ok(quote) => {
+                    let throttled = consumer
+                        .throttle
+                        .as_ref()
+                        .is_some_and(|throttle| throttle.lock().unwrap().take().is_err());
+                    if throttled {
+                        consumer.dropped("throttled", 1);
+                    } else if consumer.sender.send(quote).await.is_err() {
+                        return;
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(
+                        "Market data consumer {} fell {} ticks behind",
+                        consumer.name, missed
+                    );
+                    consumer.dropped("lagged", missed);
+                }
+                Err(RecvError::Closed) => return,
+            },
+        }
+    }
+}
+
+/// Forward the latest quote of one symbol each time the consumer may take one;
+/// the quotes it replaces are dropped
+async fn forward_latest(
+    mut ticks: broadcast::Receiver<UnifiedMarketData>,
+    consumer: Consumer,
+    interval: Option<Duration>,
+) {
+    let mut ticker = interval.map(|every| {
+        let mut ticker = tokio::time::interval(every);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker
+    });
+    let mut pending: Option<UnifiedMarketData> = None;
+    loop {
+        tokio::select! {
+            // Take in every queued tick before handing the latest over
+            biased;
+            _ = consumer.sender.closed() => return,
+            tick = ticks.recv() => match tick {
+                Ok(quote) => {
+                    if pending.replace(quote).is_some() {
+                        consumer.dropped("conflated", 1);
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => consumer.dropped("conflated", missed),
+                Err(RecvError::Closed) => return,
+            },
+            slot = consumer.slot(&mut ticker), if pending.is_some() => match slot {
+                Ok(permit) => {
+                    if let Some(quote) = pending.take() {
+                        permit.send(quote);
+                    }
+                }
+                Err(_) => return,
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl PlatformDecorator for MarketDataHub {
+    type Inner = dyn ITradingPlatform + Send + Sync;
+
+    fn inner(&self) -> &Self::Inner {
+        self.inner.as_ref()
+    }
+
+    /// A stream of `symbols` fed from the shared upstream subscription, conflated
+    /// when the calling consumer is configured so
+    async fn subscribe_market_data(
+        &self,
+        symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        self.subscribe_upstream(&symbols).await?;
+
+        let consumer = current_caller().unwrap_or_else(|| UNSCOPED_CALLER.to_string());
+        let options = self
+            .config
+            .consumers
+            .get(&consumer)
+            .cloned()
+            .unwrap_or_default();
+        // A conflating consumer holds one quote per symbol at most
+        let capacity = if options.conflate {
+            symbols.len().max(1)
+        } else {
+            self.config.channel_capacity
+        };
+        let (sender, receiver) = mpsc::channel(capacity);
+        let consumer = Consumer {
+            account_id: self.account_id.clone(),
+            name: consumer,
+            sender,
+            throttle: (options.max_quotes_per_sec > 0)
+                .then(|| Arc::new(Mutex::new(Throttle::new(options.max_quotes_per_sec)))),
+        };
+        let interval = (options.conflation_interval_ms > 0)
+            .then(|| Duration::from_millis(options.conflation_interval_ms));
+        let feeds = self.feeds.lock().unwrap();
+        for symbol in &symbols {
+            let Some(feed) = feeds.get(symbol) else {
+                continue;
+            };
+            let ticks = feed.ticks.subscribe();
+            if options.conflate {
+                spawn_isolated(
+                    "market-data-hub-consumer",
+                    forward_latest(ticks, consumer.clone(), interval),
+                );
+            } else {
+                spawn_isolated(
+                    "market-data-hub-consumer",
+                    forward_ticks(ticks, consumer.clone()),
+                );
+            }
+        }
+        Ok(receiver)
+    }
+
+    /// Unsubscribe upstream from the symbols no consumer is left on; symbols still
+    /// streamed to others stay subscribed
+    async fn unsubscribe_market_data(&self, symbols: Vec<String>) -> Result<(), PlatformError> {
+        let _subscribing = self.subscribing.lock().await;
+        let unused: Vec<String> = {
+            let mut feeds = self.feeds.lock().unwrap();
+            let unused: Vec<String> = symbols
+                .into_iter()
+                .filter(|symbol| feeds.get(symbol).is_some_and(|f| f.consumers() == 0))
+                .collect();
+            for symbol in &unused {
+                feeds.remove(symbol);
+            }
+            MARKET_DATA_HUB_SYMBOLS
+                .with_label_values(&[&self.account_id])
+                .set(feeds.len() as i64);
+            unused
+        };
+        if unused.is_empty() {
+            return Ok(());
+        }
+        self.inner.unsubscribe_market_data(unused).await
+    }
+
+    /// The wrapped platform's diagnostics with the symbols subscribed upstream
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        let mut diagnostics = self.inner.get_diagnostics().await?;
+        diagnostics.platform_specific.insert(
+            "market_data_hub".to_string(),
+            serde_json::json!({ "subscriptions": self.subscriptions() }),
+        );
+        Ok(diagnostics)
+    }
+}
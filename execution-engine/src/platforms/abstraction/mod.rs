@@ -1,32 +1,73 @@
 pub mod capabilities;
+pub mod circuit_breaker;
+pub mod clock;
+pub mod decorator;
+pub mod degradation;
+pub mod dry_run;
 pub mod errors;
 pub mod events;
+pub mod failover;
 pub mod interfaces;
+pub mod market_data_hub;
 pub mod models;
+pub mod multi_account;
+pub mod performance;
+pub mod quota;
+pub mod quote_filter;
+pub mod recovery;
+pub mod rejections;
+pub mod resilient_adapter;
+pub mod retry;
+pub mod symbols;
 
 // Temporarily disabled problematic modules
 // pub mod factory;
-// pub mod adapters;
-// pub mod performance;
-// pub mod circuit_breaker;
+pub mod adapters;
 // pub mod connection_pool;
-// pub mod resilient_adapter;
 // pub mod integration_tests;
 
 pub use capabilities::*;
+pub use circuit_breaker::*;
+pub use clock::{
+    ClockReading, ClockSyncConfig, ClockSyncPlatform, ServerClock, ServerClocks,
+    CLOCK_SKEW_ALERT_TYPE,
+};
+pub use decorator::PlatformDecorator;
+pub use degradation::{DegradationPolicy, DegradingPlatform, OrderDegradation};
+pub use dry_run::{
+    DryRunConfig, DryRunMode, DryRunPlatform, DryRunRecord, DryRunRequest, DryRunState,
+    DRY_RUN_ORDER_PREFIX,
+};
 pub use errors::*;
 pub use events::{PlatformEvent, UnifiedEventBus};
+pub use failover::{FailoverConfig, FailoverPlatform, PlatformEndpoint, FAILOVER_ALERT_TYPE};
 pub use interfaces::{
     DiagnosticsInfo, HealthStatus, IAccountManager, IMarketDataProvider, IOrderManager,
     IPlatformEvents, IPositionManager, ITradingPlatform, OrderFilter,
 };
+pub use market_data_hub::{ConsumerOptions, MarketDataHub, MarketDataHubConfig};
 pub use models::*;
+pub use multi_account::{AccountHealth, AggregatedMetrics, MultiAccountManager};
+pub use performance::{
+    MonitoredPlatform, OperationStats, PerformanceConfig, PerformanceMetrics, PerformanceMonitor,
+    SLAComplianceReport,
+};
+pub use quota::{QuotaConfig, QuotaManager, QuotaPlatform, QuotaPriority};
+pub use quote_filter::{
+    QuarantinedQuote, QuoteFilterConfig, QuoteFilteringPlatform, QuoteRejection, QuoteValidator,
+};
+pub use recovery::{ErrorRecoveryManager, RecoveryHandler, RecoveryProgress, RecoveryState};
+pub use rejections::{
+    adjust_order, AdjustedOrder, RejectionClassifier, RejectionReason, RejectionRemediationConfig,
+    Remediation,
+};
+pub use resilient_adapter::{BreakerSettings, ResilienceConfig, ResilientPlatform};
+pub use retry::{BackoffStrategy, RetryBudget, RetryConfig, RetryHandler, RetryOverride};
+pub use symbols::{SymbolMapper, SymbolMappingConfig, SymbolMappingPlatform};
 
 // Temporarily disabled re-exports
 // pub use factory::*;
 // pub use adapters::*;
-// pub use performance::*;
-// pub use circuit_breaker::*;
 // pub use connection_pool::*;
 
 #[cfg(test)]
@@ -42,7 +83,6 @@ pub struct PlatformAbstractionLayer {
     event_bus: UnifiedEventBus,
     // Temporarily disabled
     // factory: PlatformFactory,
-    // performance_monitor: PerformanceMonitor,
 }
 
 impl PlatformAbstractionLayer {
@@ -52,7 +92,6 @@ impl PlatformAbstractionLayer {
             event_bus: UnifiedEventBus::new(),
             // Temporarily disabled
             // factory: PlatformFactory::new(),
-            // performance_monitor: PerformanceMonitor::new(),
         }
     }
 
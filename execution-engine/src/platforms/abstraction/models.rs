@@ -18,6 +18,11 @@ pub struct UnifiedOrder {
     pub time_in_force: UnifiedTimeInForce,
     pub account_id: Option<String>,
     pub metadata: OrderMetadata,
+    /// Furthest a market order may fill from the quote it was sent at, in price
+    /// units. Platforms with `PlatformFeature::MaxSlippage` refuse fills beyond it;
+    /// elsewhere the engine compares the fill to the quote afterwards.
+    #[serde(default)]
+    pub max_slippage: Option<Decimal>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -126,13 +131,117 @@ pub struct UnifiedPosition {
     pub platform_specific: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum UnifiedPositionSide {
     Long,
     Short,
 }
 
+impl UnifiedPosition {
+    /// Quantity signed by side: positive when long, negative when short
+    pub fn signed_quantity(&self) -> Decimal {
+        match self.side {
+            UnifiedPositionSide::Long => self.quantity,
+            UnifiedPositionSide::Short => -self.quantity,
+        }
+    }
+}
+
+/// Open tickets on one symbol. Netting accounts hold at most one ticket per symbol;
+/// hedging accounts (MetaTrader, DXTrade in hedging mode) can carry long and short
+/// tickets side by side, each with its own `position_id` and closed on its own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionTickets {
+    pub symbol: String,
+    /// Oldest first
+    pub tickets: Vec<UnifiedPosition>,
+}
+
+impl PositionTickets {
+    /// The tickets on `symbol` out of an account's open positions
+    pub fn from_positions(
+        symbol: &str,
+        positions: impl IntoIterator<Item = UnifiedPosition>,
+    ) -> Self {
+        let mut tickets: Vec<_> = positions
+            .into_iter()
+            .filter(|p| p.symbol == symbol)
+            .collect();
+        tickets.sort_by_key(|p| p.opened_at);
+        Self {
+            symbol: symbol.to_string(),
+            tickets,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tickets.is_empty()
+    }
+
+    /// Long and short tickets are open at the same time
+    pub fn is_hedged(&self) -> bool {
+        let has = |side: UnifiedPositionSide| self.tickets.iter().any(|p| p.side == side);
+        has(UnifiedPositionSide::Long) && has(UnifiedPositionSide::Short)
+    }
+
+    pub fn ticket(&self, position_id: &str) -> Option<&UnifiedPosition> {
+        self.tickets.iter().find(|p| p.position_id == position_id)
+    }
+
+    /// Long quantity minus short quantity
+    pub fn net_quantity(&self) -> Decimal {
+        self.tickets.iter().map(|p| p.signed_quantity()).sum()
+    }
+
+    /// The tickets folded into one net position, or `None` when they offset exactly.
+    /// Entry price is the quantity-weighted entry of the tickets on the net side, P&L,
+    /// margin and commission are summed across all tickets, and the oldest ticket on
+    /// the net side lends its id. Stops are only kept for a single ticket, since
+    /// separate tickets carry separate stops.
+    pub fn net(&self) -> Option<UnifiedPosition> {
+        let net_quantity = self.net_quantity();
+        if net_quantity.is_zero() {
+            return None;
+        }
+        let side = if net_quantity > Decimal::ZERO {
+            UnifiedPositionSide::Long
+        } else {
+            UnifiedPositionSide::Short
+        };
+        let same_side: Vec<_> = self.tickets.iter().filter(|p| p.side == side).collect();
+        let first = same_side[0];
+        if self.tickets.len() == 1 {
+            return Some(first.clone());
+        }
+
+        let same_side_quantity: Decimal = same_side.iter().map(|p| p.quantity).sum();
+        let entry_price = same_side
+            .iter()
+            .map(|p| p.entry_price * p.quantity)
+            .sum::<Decimal>()
+            / same_side_quantity;
+        Some(UnifiedPosition {
+            position_id: first.position_id.clone(),
+            symbol: self.symbol.clone(),
+            side,
+            quantity: net_quantity.abs(),
+            entry_price,
+            current_price: first.current_price,
+            unrealized_pnl: self.tickets.iter().map(|p| p.unrealized_pnl).sum(),
+            realized_pnl: self.tickets.iter().map(|p| p.realized_pnl).sum(),
+            margin_used: self.tickets.iter().map(|p| p.margin_used).sum(),
+            commission: self.tickets.iter().map(|p| p.commission).sum(),
+            stop_loss: None,
+            take_profit: None,
+            opened_at: first.opened_at,
+            updated_at: self.tickets.iter().map(|p| p.updated_at).max()?,
+            account_id: first.account_id.clone(),
+            platform_specific: HashMap::new(),
+        })
+    }
+}
+
 /// Unified account information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnifiedAccountInfo {
@@ -247,6 +356,62 @@ pub enum TransactionType {
     Adjustment,
 }
 
+/// One entry of an account's statement, as `ITradingPlatform::get_transactions`
+/// reports it across platforms
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnifiedTransaction {
+    pub transaction_id: String,
+    pub account_id: String,
+    pub kind: UnifiedTransactionKind,
+    pub symbol: Option<String>,
+    pub order_id: Option<String>,
+    pub position_id: Option<String>,
+    /// Fills only
+    pub side: Option<UnifiedOrderSide>,
+    pub quantity: Option<Decimal>,
+    pub price: Option<Decimal>,
+    /// Commission charged on a fill, when the platform reports it with the fill
+    pub commission: Option<Decimal>,
+    /// Effect on the balance: negative for charges and withdrawals, and the
+    /// realized P&L for closing fills
+    pub amount: Decimal,
+    pub currency: String,
+    pub balance_after: Option<Decimal>,
+    pub timestamp: DateTime<Utc>,
+    pub description: String,
+    pub platform_specific: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnifiedTransactionKind {
+    Fill,
+    Commission,
+    /// Overnight financing charged or paid at rollover
+    Swap,
+    /// Corrections and other balance changes the platform makes itself
+    Adjustment,
+    /// Deposits and withdrawals, told apart by the sign of the amount
+    Transfer,
+}
+
+/// Half-open span of time `[from, to)` to fetch transactions for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionRange {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+impl TransactionRange {
+    pub fn new(from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        Self { from, to }
+    }
+
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        at >= self.from && at < self.to
+    }
+}
+
 /// Symbol information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Symbol {
@@ -261,6 +426,13 @@ pub struct Symbol {
     pub contract_size: Option<Decimal>,
     pub trading_hours: Vec<TradingHours>,
     pub is_tradeable: bool,
+    /// Minimum distance between the market and a stop-loss or take-profit, as a
+    /// price distance, on platforms that enforce one
+    #[serde(default)]
+    pub stops_level: Option<Decimal>,
+    /// Distance from the market inside which orders and stops cannot be modified
+    #[serde(default)]
+    pub freeze_level: Option<Decimal>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -324,6 +496,94 @@ pub struct PriceLevel {
     pub order_count: Option<u32>,
 }
 
+/// Depth of book for one symbol, as platforms supporting `MarketDepth` report it.
+/// Both sides are ordered best price first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnifiedOrderBook {
+    pub symbol: String,
+    pub bids: Vec<PriceLevel>,
+    pub asks: Vec<PriceLevel>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// What the best levels of a book fill of an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookSweep {
+    /// Quantity the levels hold, up to the order's
+    pub filled: Decimal,
+    /// Volume-weighted price of `filled`, `None` when nothing fills
+    pub average_price: Option<Decimal>,
+    /// Price of the last level reached
+    pub worst_price: Option<Decimal>,
+}
+
+impl UnifiedOrderBook {
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.first().map(|level| level.price)
+    }
+
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.first().map(|level| level.price)
+    }
+
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()? - self.best_bid()?)
+    }
+
+    /// The book cut down to its best `levels` a side
+    pub fn top(&self, levels: usize) -> Self {
+        Self {
+            symbol: self.symbol.clone(),
+            bids: self.bids.iter().take(levels).cloned().collect(),
+            asks: self.asks.iter().take(levels).cloned().collect(),
+            timestamp: self.timestamp,
+        }
+    }
+
+    /// The levels an order on `side` takes liquidity from: asks for buys, bids
+    /// for sells
+    pub fn opposite(&self, side: &UnifiedOrderSide) -> &[PriceLevel] {
+        match side {
+            UnifiedOrderSide::Buy => &self.asks,
+            UnifiedOrderSide::Sell => &self.bids,
+        }
+    }
+
+    /// Fill `quantity` on `side` from the best `levels` levels, stopping at levels
+    /// priced worse than `limit` when one is given
+    pub fn sweep(
+        &self,
+        side: &UnifiedOrderSide,
+        quantity: Decimal,
+        levels: usize,
+        limit: Option<Decimal>,
+    ) -> BookSweep {
+        let mut sweep = BookSweep {
+            filled: Decimal::ZERO,
+            average_price: None,
+            worst_price: None,
+        };
+        let mut notional = Decimal::ZERO;
+        for level in self.opposite(side).iter().take(levels) {
+            let beyond_limit = limit.is_some_and(|limit| match side {
+                UnifiedOrderSide::Buy => level.price > limit,
+                UnifiedOrderSide::Sell => level.price < limit,
+            });
+            if beyond_limit || sweep.filled >= quantity {
+                break;
+            }
+            let take = level.volume.min(quantity - sweep.filled);
+            sweep.filled += take;
+            notional += take * level.price;
+            sweep.worst_price = Some(level.price);
+        }
+        if !sweep.filled.is_zero() {
+            sweep.average_price = Some(notional / sweep.filled);
+        }
+        sweep
+    }
+}
+
 /// Historical data models
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Candle {
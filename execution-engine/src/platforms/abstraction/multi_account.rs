@@ -0,0 +1,351 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use super::errors::PlatformError;
+use super::interfaces::{HealthStatus, ITradingPlatform};
+use super::models::UnifiedPositionSide;
+use crate::platforms::PlatformType;
+
+/// A registered account and the platform connection that serves it
+#[derive(Clone)]
+pub struct AccountSession {
+    pub account_id: String,
+    pub platform_type: PlatformType,
+    pub platform: Arc<dyn ITradingPlatform + Send + Sync>,
+    pub is_active: bool,
+    pub registered_at: chrono::DateTime<chrono::Utc>,
+    pub last_activity: chrono::DateTime<chrono::Utc>,
+}
+
+/// Latest known health of a single account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountHealth {
+    pub account_id: String,
+    pub platform_type: PlatformType,
+    pub is_active: bool,
+    pub is_connected: bool,
+    pub status: Option<HealthStatus>,
+    pub last_error: Option<String>,
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl AccountHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.is_active
+            && self.is_connected
+            && self.status.as_ref().map(|s| s.is_healthy).unwrap_or(false)
+    }
+}
+
+/// Manages account sessions across heterogeneous platforms
+pub struct MultiAccountManager {
+    sessions: Arc<RwLock<HashMap<String, AccountSession>>>,
+    health: Arc<RwLock<HashMap<String, AccountHealth>>>,
+}
+
+impl MultiAccountManager {
+    pub fn new() -> Self {
+        Self {
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            health: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn add_account(
+        &self,
+        account_id: &str,
+        platform: Arc<dyn ITradingPlatform + Send + Sync>,
+    ) -> Result<(), PlatformError> {
+        let mut sessions = self.sessions.write().await;
+        if sessions
+            .get(account_id)
+            .map(|s| s.is_active)
+            .unwrap_or(false)
+        {
+            return Err(PlatformError::ConfigurationError {
+                reason: format!("Account {} already has an active session", account_id),
+            });
+        }
+
+        let now = chrono::Utc::now();
+        let platform_type = platform.platform_type();
+        sessions.insert(
+            account_id.to_string(),
+            AccountSession {
+                account_id: account_id.to_string(),
+                platform_type: platform_type.clone(),
+                platform,
+                is_active: true,
+                registered_at: now,
+                last_activity: now,
+            },
+        );
+
+        info!("Registered account {} on {:?}", account_id, platform_type);
+        Ok(())
+    }
+
+    pub async fn remove_account(&self, account_id: &str) -> Result<(), PlatformError> {
+        self.sessions
+            .write()
+            .await
+            .remove(account_id)
+            .ok_or_else(|| PlatformError::AccountNotFound {
+                account_id: account_id.to_string(),
+            })?;
+        self.health.write().await.remove(account_id);
+
+        info!("Removed account {}", account_id);
+        Ok(())
+    }
+
+    pub async fn deactivate_account(&self, account_id: &str) -> Result<(), PlatformError> {
+        self.set_active(account_id, false).await
+    }
+
+    pub async fn activate_account(&self, account_id: &str) -> Result<(), PlatformError> {
+        self.set_active(account_id, true).await
+    }
+
+    async fn set_active(&self, account_id: &str, active: bool) -> Result<(), PlatformError> {
+        let mut sessions = self.sessions.write().await;
+        let session =
+            sessions
+                .get_mut(account_id)
+                .ok_or_else(|| PlatformError::AccountNotFound {
+                    account_id: account_id.to_string(),
+                })?;
+        session.is_active = active;
+        session.last_activity = chrono::Utc::now();
+        Ok(())
+    }
+
+    /// Platform for an active account, marking the session as used
+    pub async fn get_platform(
+        &self,
+        account_id: &str,
+    ) -> Result<Arc<dyn ITradingPlatform + Send + Sync>, PlatformError> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(account_id)
+            .filter(|s| s.is_active)
+            .ok_or_else(|| PlatformError::AccountNotFound {
+                account_id: account_id.to_string(),
+            })?;
+        session.last_activity = chrono::Utc::now();
+        Ok(session.platform.clone())
+    }
+
+    pub async fn get_session(&self, account_id: &str) -> Option<AccountSession> {
+        self.sessions.read().await.get(account_id).cloned()
+    }
+
+    pub async fn get_active_accounts(&self) -> Vec<String> {
+        self.sessions
+            .read()
+            .await
+            .values()
+            .filter(|s| s.is_active)
+            .map(|s| s.account_id.clone())
+            .collect()
+    }
+
+    pub async fn get_accounts_for_platform(&self, platform_type: &PlatformType) -> Vec<String> {
+        self.sessions
+            .read()
+            .await
+            .values()
+            .filter(|s| s.is_active && &s.platform_type == platform_type)
+            .map(|s| s.account_id.clone())
+            .collect()
+    }
+
+    /// Pick the active account with the fewest open positions
+    pub async fn select_least_loaded(&self, accounts: &[String]) -> Result<String, PlatformError> {
+        let mut best_account = None;
+        let mut min_positions = usize::MAX;
+
+        for account_id in accounts {
+            let Ok(platform) = self.get_platform(account_id).await else {
+                continue;
+            };
+            if let Ok(positions) = platform.get_positions().await {
+                if positions.len() < min_positions {
+                    min_positions = positions.len();
+                    best_account = Some(account_id.clone());
+                }
+            }
+        }
+
+        best_account.ok_or_else(|| PlatformError::InternalError {
+            reason: "No suitable account found".to_string(),
+        })
+    }
+
+    /// Aggregate equity, margin and exposure across every active account
+    pub async fn get_aggregated_metrics(&self) -> AggregatedMetrics {
+        let sessions: Vec<AccountSession> = self
+            .sessions
+            .read()
+            .await
+            .values()
+            .filter(|s| s.is_active)
+            .cloned()
+            .collect();
+        let mut metrics = AggregatedMetrics::default();
+
+        for session in sessions {
+            let account_info = match session.platform.get_account_info().await {
+                Ok(info) => info,
+                Err(e) => {
+                    warn!(
+                        "Skipping account {} in aggregation: {}",
+                        session.account_id, e
+                    );
+                    metrics
+                        .unavailable_accounts
+                        .push(session.account_id.clone());
+                    continue;
+                }
+            };
+
+            metrics.active_accounts += 1;
+            metrics.total_balance += account_info.balance;
+            metrics.total_equity += account_info.equity;
+            metrics.total_margin_used += account_info.margin_used;
+            metrics.total_margin_available += account_info.margin_available;
+            metrics.total_unrealized_pnl += account_info.unrealized_pnl;
+            metrics.total_realized_pnl += account_info.realized_pnl;
+            *metrics
+                .accounts_by_platform
+                .entry(format!("{:?}", session.platform_type))
+                .or_insert(0) += 1;
+
+            match session.platform.get_positions().await {
+                Ok(positions) => {
+                    metrics.total_positions += positions.len();
+                    for position in positions {
+                        let notional = position.quantity * position.current_price;
+                        let signed = match position.side {
+                            UnifiedPositionSide::Long => notional,
+                            UnifiedPositionSide::Short => -notional,
+                        };
+                        metrics.gross_exposure += notional.abs();
+                        *metrics
+                            .net_exposure_by_symbol
+                            .entry(position.symbol)
+                            .or_insert(Decimal::ZERO) += signed;
+                    }
+                }
+                Err(e) => warn!(
+                    "Failed to load positions for account {}: {}",
+                    session.account_id, e
+                ),
+            }
+        }
+
+        metrics
+    }
+
+    pub async fn check_account_health(
+        &self,
+        account_id: &str,
+    ) -> Result<AccountHealth, PlatformError> {
+        let session =
+            self.get_session(account_id)
+                .await
+                .ok_or_else(|| PlatformError::AccountNotFound {
+                    account_id: account_id.to_string(),
+                })?;
+
+        let is_connected = session.platform.is_connected().await;
+        let (status, last_error) = match session.platform.health_check().await {
+            Ok(status) => (Some(status), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+
+        let health = AccountHealth {
+            account_id: account_id.to_string(),
+            platform_type: session.platform_type,
+            is_active: session.is_active,
+            is_connected,
+            status,
+            last_error,
+            checked_at: chrono::Utc::now(),
+        };
+
+        if !health.is_healthy() && health.is_active {
+            warn!(
+                "Account {} is unhealthy: {:?}",
+                account_id, health.last_error
+            );
+        }
+
+        self.health
+            .write()
+            .await
+            .insert(account_id.to_string(), health.clone());
+        Ok(health)
+    }
+
+    pub async fn check_all_health(&self) -> HashMap<String, AccountHealth> {
+        let account_ids: Vec<String> = self.sessions.read().await.keys().cloned().collect();
+        let mut results = HashMap::new();
+
+        for account_id in account_ids {
+            if let Ok(health) = self.check_account_health(&account_id).await {
+                results.insert(account_id, health);
+            }
+        }
+
+        results
+    }
+
+    /// Last recorded health for an account without probing the platform
+    pub async fn get_account_health(&self, account_id: &str) -> Option<AccountHealth> {
+        self.health.read().await.get(account_id).cloned()
+    }
+}
+
+impl Default for MultiAccountManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Totals across accounts, summed as reported by each account whatever its
+/// currency; `dashboard::EquityConsolidator` converts them to one base currency
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AggregatedMetrics {
+    pub active_accounts: usize,
+    pub total_positions: usize,
+    pub total_balance: Decimal,
+    pub total_equity: Decimal,
+    pub total_margin_used: Decimal,
+    pub total_margin_available: Decimal,
+    pub total_unrealized_pnl: Decimal,
+    pub total_realized_pnl: Decimal,
+    pub gross_exposure: Decimal,
+    pub net_exposure_by_symbol: HashMap<String, Decimal>,
+    pub accounts_by_platform: HashMap<String, usize>,
+    pub unavailable_accounts: Vec<String>,
+}
+
+impl AggregatedMetrics {
+    pub fn total_pnl(&self) -> Decimal {
+        self.total_unrealized_pnl + self.total_realized_pnl
+    }
+
+    pub fn margin_utilization(&self) -> Decimal {
+        let total_margin = self.total_margin_available + self.total_margin_used;
+        if total_margin > Decimal::ZERO {
+            (self.total_margin_used / total_margin) * Decimal::from(100)
+        } else {
+            Decimal::ZERO
+        }
+    }
+}
@@ -1,15 +1,89 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use prometheus::{register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::warn;
 
-use super::capabilities::PlatformOperation;
+use super::capabilities::PlatformCapabilities;
+use super::decorator::PlatformDecorator;
 use super::errors::PlatformError;
+use super::events::PlatformEvent;
+use super::interfaces::{
+    DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter,
+};
+use super::models::*;
+use crate::platforms::PlatformType;
+
+lazy_static! {
+    pub static ref PLATFORM_OPERATION_SECONDS: HistogramVec = register_histogram_vec!(
+        "platform_operation_duration_seconds",
+        "Time platform adapters take to answer, by operation and outcome",
+        &["platform", "operation", "outcome"],
+        vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0]
+    )
+    .unwrap();
+    pub static ref PLATFORM_SLA_BREACHES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "platform_sla_breaches_total",
+        "Platform operations slower than their SLA",
+        &["platform", "operation"]
+    )
+    .unwrap();
+}
+
+/// Durations kept per operation for percentiles
+const DEFAULT_MAX_SAMPLES: usize = 1000;
+
+/// Timing of the calls made to each account's platform adapter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerformanceConfig {
+    /// Off, adapters are called without being timed
+    pub enabled: bool,
+    /// SLA per operation in milliseconds, replacing the default of those named
+    pub sla_ms: HashMap<String, u64>,
+    /// Durations kept per operation for percentiles
+    pub max_samples: usize,
+}
+
+impl Default for PerformanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sla_ms: HashMap::new(),
+            max_samples: DEFAULT_MAX_SAMPLES,
+        }
+    }
+}
+
+impl PerformanceConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_samples == 0 {
+            return Err("Platform performance max_samples must be above 0".to_string());
+        }
+        if let Some((operation, _)) = self.sla_ms.iter().find(|(_, ms)| **ms == 0) {
+            return Err(format!(
+                "Platform performance SLA of {} must be above 0 ms",
+                operation
+            ));
+        }
+        Ok(())
+    }
+}
 
-/// Performance monitoring and metrics collection
+/// Performance monitoring and metrics collection. Cheap to clone; clones share
+/// their metrics.
 pub struct PerformanceMonitor {
+    platform: String,
     metrics: Arc<Mutex<PerformanceMetrics>>,
-    operation_timers: Arc<Mutex<HashMap<String, Vec<Duration>>>>,
+    operation_timers: Arc<Mutex<HashMap<String, VecDeque<Duration>>>>,
+    max_samples: usize,
     error_tracker: Arc<Mutex<ErrorTracker>>,
     throughput_tracker: Arc<Mutex<ThroughputTracker>>,
     sla_monitor: SLAMonitor,
@@ -18,14 +92,52 @@ pub struct PerformanceMonitor {
 impl PerformanceMonitor {
     pub fn new() -> Self {
         Self {
-            metrics: Arc::new(Mutex::new(PerformanceMetrics::default())),
+            platform: "unknown".to_string(),
+            metrics: Arc::new(Mutex::new(PerformanceMetrics {
+                start_time: Some(Utc::now()),
+                ..PerformanceMetrics::default()
+            })),
             operation_timers: Arc::new(Mutex::new(HashMap::new())),
+            max_samples: DEFAULT_MAX_SAMPLES,
             error_tracker: Arc::new(Mutex::new(ErrorTracker::new())),
             throughput_tracker: Arc::new(Mutex::new(ThroughputTracker::new())),
             sla_monitor: SLAMonitor::new(),
         }
     }
 
+    pub fn from_config(config: &PerformanceConfig) -> Self {
+        config
+            .sla_ms
+            .iter()
+            .fold(Self::new(), |monitor, (operation, ms)| {
+                monitor.with_sla(operation, Duration::from_millis(*ms))
+            })
+            .with_max_samples(config.max_samples)
+    }
+
+    /// Label the Prometheus series of this monitor with `platform`, the account
+    /// the adapter serves when there is one per account
+    pub fn with_platform(mut self, platform: &str) -> Self {
+        self.platform = platform.to_string();
+        self
+    }
+
+    /// Override the SLA of `operation`
+    pub fn with_sla(mut self, operation: &str, limit: Duration) -> Self {
+        self.sla_monitor.set_sla(operation, limit);
+        self
+    }
+
+    /// Durations kept per operation for percentiles; the oldest are dropped first
+    pub fn with_max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = max_samples.max(1);
+        self
+    }
+
+    pub fn platform(&self) -> &str {
+        &self.platform
+    }
+
     /// Start timing an operation
     pub fn start_operation(&self, operation: &str) -> OperationTimer {
         OperationTimer::new(operation.to_string(), self.clone())
@@ -33,166 +145,197 @@ impl PerformanceMonitor {
 
     /// Record operation completion with duration
     pub fn record_operation(&self, operation: &str, duration: Duration, success: bool) {
-        // Record timing
         {
             let mut timers = self.operation_timers.lock().unwrap();
-            timers.entry(operation.to_string())
-                .or_insert_with(Vec::new)
-                .push(duration);
+            let samples = timers.entry(operation.to_string()).or_default();
+            if samples.len() >= self.max_samples {
+                samples.pop_front();
+            }
+            samples.push_back(duration);
         }
 
-        // Update metrics
+        let sla = self.sla_monitor.get_sla(operation);
+        let breached = sla.is_some_and(|sla| duration > sla);
         {
             let mut metrics = self.metrics.lock().unwrap();
             metrics.total_operations += 1;
-            
-            let operation_metrics = metrics.operations.entry(operation.to_string())
-                .or_insert_with(OperationMetrics::default);
-            
+            if !success {
+                metrics.total_errors += 1;
+            }
+
+            let operation_metrics = metrics.operations.entry(operation.to_string()).or_default();
             operation_metrics.count += 1;
             operation_metrics.total_duration += duration;
-            operation_metrics.min_duration = operation_metrics.min_duration
-                .map(|min| min.min(duration))
-                .or(Some(duration));
-            operation_metrics.max_duration = operation_metrics.max_duration
-                .map(|max| max.max(duration))
-                .or(Some(duration));
-            
+            operation_metrics.min_duration = Some(
+                operation_metrics
+                    .min_duration
+                    .map_or(duration, |min| min.min(duration)),
+            );
+            operation_metrics.max_duration = Some(
+                operation_metrics
+                    .max_duration
+                    .map_or(duration, |max| max.max(duration)),
+            );
             if !success {
                 operation_metrics.error_count += 1;
-                metrics.total_errors += 1;
+            }
+            if breached {
+                operation_metrics.sla_breaches += 1;
             }
         }
 
-        // Track throughput
-        {
-            let mut throughput = self.throughput_tracker.lock().unwrap();
-            throughput.record_operation();
-        }
-
-        // Track errors
+        self.throughput_tracker.lock().unwrap().record_operation();
         if !success {
-            let mut error_tracker = self.error_tracker.lock().unwrap();
-            error_tracker.record_error(operation);
+            self.error_tracker.lock().unwrap().record_error(operation);
         }
 
-        // Check SLA violations
-        if let Some(sla_duration) = self.sla_monitor.get_sla(operation) {
-            if duration > sla_duration {
-                self.record_sla_violation(operation, duration, sla_duration);
-            }
+        PLATFORM_OPERATION_SECONDS
+            .with_label_values(&[
+                &self.platform,
+                operation,
+                if success { "ok" } else { "error" },
+            ])
+            .observe(duration.as_secs_f64());
+        if let (true, Some(sla)) = (breached, sla) {
+            PLATFORM_SLA_BREACHES_TOTAL
+                .with_label_values(&[&self.platform, operation])
+                .inc();
+            warn!(
+                "{} {} took {:?}, over its {:?} SLA",
+                self.platform, operation, duration, sla
+            );
         }
     }
 
-    /// Record an error
+    /// Record the error an operation failed with. Counted by `record_operation`;
+    /// this only keeps what kind of error it was.
     pub fn record_error(&self, operation: &str, error: &PlatformError) {
-        let mut error_tracker = self.error_tracker.lock().unwrap();
-        error_tracker.record_platform_error(operation, error);
-
-        let mut metrics = self.metrics.lock().unwrap();
-        metrics.total_errors += 1;
-        
-        if let Some(operation_metrics) = metrics.operations.get_mut(operation) {
-            operation_metrics.error_count += 1;
-        }
+        self.error_tracker
+            .lock()
+            .unwrap()
+            .record_platform_error(operation, error);
     }
 
     /// Get current performance metrics
     pub fn get_metrics(&self) -> PerformanceMetrics {
-        let metrics = self.metrics.lock().unwrap();
-        metrics.clone()
+        self.metrics.lock().unwrap().clone()
     }
 
     /// Get operation statistics
     pub fn get_operation_stats(&self, operation: &str) -> Option<OperationStats> {
         let timers = self.operation_timers.lock().unwrap();
         let durations = timers.get(operation)?;
-        
         if durations.is_empty() {
             return None;
         }
 
-        let mut sorted_durations = durations.clone();
+        let mut sorted_durations: Vec<Duration> = durations.iter().copied().collect();
         sorted_durations.sort();
 
         let count = sorted_durations.len();
         let sum: Duration = sorted_durations.iter().sum();
-        let avg = sum / count as u32;
-        
-        let p50 = sorted_durations[count * 50 / 100];
-        let p95 = sorted_durations[count * 95 / 100];
-        let p99 = sorted_durations[count * 99 / 100];
-
         Some(OperationStats {
             operation: operation.to_string(),
             count: count as u64,
-            min: sorted_durations.first().copied().unwrap(),
-            max: sorted_durations.last().copied().unwrap(),
-            avg,
-            p50,
-            p95,
-            p99,
+            min: sorted_durations[0],
+            max: sorted_durations[count - 1],
+            avg: sum / count as u32,
+            p50: sorted_durations[count * 50 / 100],
+            p95: sorted_durations[count * 95 / 100],
+            p99: sorted_durations[count * 99 / 100],
             total: sum,
         })
     }
 
     /// Get current throughput
     pub fn get_throughput(&self) -> ThroughputMetrics {
-        let throughput = self.throughput_tracker.lock().unwrap();
-        throughput.get_metrics()
+        self.throughput_tracker.lock().unwrap().get_metrics()
     }
 
     /// Get error metrics
     pub fn get_error_metrics(&self) -> ErrorMetrics {
-        let error_tracker = self.error_tracker.lock().unwrap();
-        error_tracker.get_metrics()
+        self.error_tracker.lock().unwrap().get_metrics()
     }
 
     /// Reset all metrics
     pub fn reset_metrics(&self) {
-        let mut metrics = self.metrics.lock().unwrap();
-        *metrics = PerformanceMetrics::default();
-        
-        let mut timers = self.operation_timers.lock().unwrap();
-        timers.clear();
-        
-        let mut error_tracker = self.error_tracker.lock().unwrap();
-        error_tracker.reset();
-        
-        let mut throughput = self.throughput_tracker.lock().unwrap();
-        throughput.reset();
-    }
-
-    /// Check if platform is meeting SLA requirements
+        *self.metrics.lock().unwrap() = PerformanceMetrics {
+            start_time: Some(Utc::now()),
+            ..PerformanceMetrics::default()
+        };
+        self.operation_timers.lock().unwrap().clear();
+        self.error_tracker.lock().unwrap().reset();
+        self.throughput_tracker.lock().unwrap().reset();
+    }
+
+    /// Check if platform is meeting SLA requirements. An operation is in violation
+    /// when its average is over its SLA; compliance is the share of calls within it.
     pub fn check_sla_compliance(&self) -> SLAComplianceReport {
         let metrics = self.get_metrics();
         let mut violations = Vec::new();
-        
+        let mut compliance = HashMap::new();
+
         for (operation, operation_metrics) in &metrics.operations {
-            if let Some(sla_duration) = self.sla_monitor.get_sla(operation) {
-                let avg_duration = operation_metrics.total_duration / operation_metrics.count as u32;
-                if avg_duration > sla_duration {
-                    violations.push(SLAViolation {
-                        operation: operation.clone(),
-                        expected: sla_duration,
-                        actual: avg_duration,
-                        violation_percentage: (avg_duration.as_millis() as f64 / sla_duration.as_millis() as f64 - 1.0) * 100.0,
-                    });
-                }
+            let Some(sla_duration) = self.sla_monitor.get_sla(operation) else {
+                continue;
+            };
+            compliance.insert(operation.clone(), operation_metrics.sla_compliance());
+            let avg_duration = operation_metrics.avg_duration();
+            if avg_duration > sla_duration {
+                violations.push(SLAViolation {
+                    operation: operation.clone(),
+                    expected: sla_duration,
+                    actual: avg_duration,
+                    violation_percentage: (avg_duration.as_secs_f64() / sla_duration.as_secs_f64()
+                        - 1.0)
+                        * 100.0,
+                });
             }
         }
+        violations.sort_by(|a, b| a.operation.cmp(&b.operation));
 
         SLAComplianceReport {
             is_compliant: violations.is_empty(),
             violations,
+            compliance,
             overall_error_rate: metrics.error_rate(),
-            timestamp: chrono::Utc::now(),
+            timestamp: Utc::now(),
         }
     }
 
-    fn record_sla_violation(&self, operation: &str, actual: Duration, expected: Duration) {
-        // Could emit events or log SLA violations here
-        eprintln!("SLA violation for {}: expected {:?}, got {:?}", operation, expected, actual);
+    /// The latest errors, oldest first
+    pub fn recent_errors(&self) -> Vec<String> {
+        self.error_tracker.lock().unwrap().recent()
+    }
+
+    /// Everything above in the shape of `DiagnosticsInfo::performance_metrics`
+    pub fn diagnostics(&self) -> HashMap<String, serde_json::Value> {
+        let metrics = self.get_metrics();
+        let operations: HashMap<String, Option<OperationStats>> = metrics
+            .operations
+            .keys()
+            .map(|operation| (operation.clone(), self.get_operation_stats(operation)))
+            .collect();
+
+        let mut diagnostics = HashMap::new();
+        diagnostics.insert(
+            "total_operations".to_string(),
+            serde_json::json!(metrics.total_operations),
+        );
+        diagnostics.insert(
+            "error_rate".to_string(),
+            serde_json::json!(metrics.error_rate()),
+        );
+        diagnostics.insert("operations".to_string(), serde_json::json!(operations));
+        diagnostics.insert(
+            "sla".to_string(),
+            serde_json::json!(self.check_sla_compliance()),
+        );
+        diagnostics.insert(
+            "throughput".to_string(),
+            serde_json::json!(self.get_throughput()),
+        );
+        diagnostics
     }
 }
 
@@ -205,8 +348,10 @@ impl Default for PerformanceMonitor {
 impl Clone for PerformanceMonitor {
     fn clone(&self) -> Self {
         Self {
+            platform: self.platform.clone(),
             metrics: Arc::clone(&self.metrics),
             operation_timers: Arc::clone(&self.operation_timers),
+            max_samples: self.max_samples,
             error_tracker: Arc::clone(&self.error_tracker),
             throughput_tracker: Arc::clone(&self.throughput_tracker),
             sla_monitor: self.sla_monitor.clone(),
@@ -233,13 +378,15 @@ impl OperationTimer {
     /// Complete the operation successfully
     pub fn success(self) {
         let duration = self.start_time.elapsed();
-        self.monitor.record_operation(&self.operation, duration, true);
+        self.monitor
+            .record_operation(&self.operation, duration, true);
     }
 
     /// Complete the operation with an error
     pub fn error(self, error: &PlatformError) {
         let duration = self.start_time.elapsed();
-        self.monitor.record_operation(&self.operation, duration, false);
+        self.monitor
+            .record_operation(&self.operation, duration, false);
         self.monitor.record_error(&self.operation, error);
     }
 
@@ -255,7 +402,7 @@ pub struct PerformanceMetrics {
     pub total_operations: u64,
     pub total_errors: u64,
     pub operations: HashMap<String, OperationMetrics>,
-    pub start_time: Option<chrono::DateTime<chrono::Utc>>,
+    pub start_time: Option<DateTime<Utc>>,
 }
 
 impl PerformanceMetrics {
@@ -276,6 +423,9 @@ pub struct OperationMetrics {
     pub total_duration: Duration,
     pub min_duration: Option<Duration>,
     pub max_duration: Option<Duration>,
+    /// Calls slower than the operation's SLA
+    #[serde(default)]
+    pub sla_breaches: u64,
 }
 
 impl OperationMetrics {
@@ -294,6 +444,15 @@ impl OperationMetrics {
             self.error_count as f64 / self.count as f64
         }
     }
+
+    /// Share of calls answered within the SLA
+    pub fn sla_compliance(&self) -> f64 {
+        if self.count == 0 {
+            1.0
+        } else {
+            1.0 - self.sla_breaches as f64 / self.count as f64
+        }
+    }
 }
 
 /// Detailed operation statistics
@@ -314,7 +473,7 @@ pub struct OperationStats {
 struct ErrorTracker {
     error_counts: HashMap<String, u64>,
     error_types: HashMap<String, u64>,
-    recent_errors: Vec<(String, PlatformError, chrono::DateTime<chrono::Utc>)>,
+    recent_errors: VecDeque<(String, PlatformError, DateTime<Utc>)>,
     max_recent_errors: usize,
 }
 
@@ -323,7 +482,7 @@ impl ErrorTracker {
         Self {
             error_counts: HashMap::new(),
             error_types: HashMap::new(),
-            recent_errors: Vec::new(),
+            recent_errors: VecDeque::new(),
             max_recent_errors: 100,
         }
     }
@@ -333,18 +492,28 @@ impl ErrorTracker {
     }
 
     fn record_platform_error(&mut self, operation: &str, error: &PlatformError) {
-        self.record_error(operation);
-        
-        let error_type = format!("{:?}", std::mem::discriminant(error));
+        let error_type = format!("{:?}", error);
+        let error_type = error_type
+            .split([' ', '{', '('])
+            .next()
+            .unwrap_or_default()
+            .to_string();
         *self.error_types.entry(error_type).or_insert(0) += 1;
-        
-        self.recent_errors.push((operation.to_string(), error.clone(), chrono::Utc::now()));
-        
+
+        self.recent_errors
+            .push_back((operation.to_string(), error.clone(), Utc::now()));
         if self.recent_errors.len() > self.max_recent_errors {
-            self.recent_errors.remove(0);
+            self.recent_errors.pop_front();
         }
     }
 
+    fn recent(&self) -> Vec<String> {
+        self.recent_errors
+            .iter()
+            .map(|(operation, error, at)| format!("{} {}: {}", at.to_rfc3339(), operation, error))
+            .collect()
+    }
+
     fn get_metrics(&self) -> ErrorMetrics {
         ErrorMetrics {
             total_errors: self.error_counts.values().sum(),
@@ -372,33 +541,34 @@ pub struct ErrorMetrics {
 
 /// Throughput tracking
 struct ThroughputTracker {
-    operation_timestamps: Vec<Instant>,
+    operation_timestamps: VecDeque<Instant>,
     window_size: Duration,
 }
 
 impl ThroughputTracker {
     fn new() -> Self {
         Self {
-            operation_timestamps: Vec::new(),
-            window_size: Duration::from_secs(60), // 1-minute window
+            operation_timestamps: VecDeque::new(),
+            window_size: Duration::from_secs(60),
         }
     }
 
     fn record_operation(&mut self) {
         let now = Instant::now();
-        self.operation_timestamps.push(now);
-        
-        // Clean up old timestamps
-        let cutoff = now - self.window_size;
-        self.operation_timestamps.retain(|&timestamp| timestamp > cutoff);
+        self.operation_timestamps.push_back(now);
+        while self
+            .operation_timestamps
+            .front()
+            .is_some_and(|&timestamp| now.duration_since(timestamp) > self.window_size)
+        {
+            self.operation_timestamps.pop_front();
+        }
     }
 
     fn get_metrics(&self) -> ThroughputMetrics {
         let operations_per_minute = self.operation_timestamps.len() as f64;
-        let operations_per_second = operations_per_minute / 60.0;
-        
         ThroughputMetrics {
-            operations_per_second,
+            operations_per_second: operations_per_minute / 60.0,
             operations_per_minute,
             window_size_seconds: self.window_size.as_secs(),
         }
@@ -425,16 +595,18 @@ pub struct SLAMonitor {
 
 impl SLAMonitor {
     pub fn new() -> Self {
-        let mut sla_limits = HashMap::new();
-        
-        // Default SLA limits (can be overridden)
-        sla_limits.insert("place_order".to_string(), Duration::from_millis(100));
-        sla_limits.insert("modify_order".to_string(), Duration::from_millis(50));
-        sla_limits.insert("cancel_order".to_string(), Duration::from_millis(30));
-        sla_limits.insert("get_market_data".to_string(), Duration::from_millis(20));
-        sla_limits.insert("get_account_info".to_string(), Duration::from_millis(200));
-        sla_limits.insert("get_positions".to_string(), Duration::from_millis(100));
-        
+        let sla_limits = [
+            ("place_order", 100),
+            ("modify_order", 50),
+            ("cancel_order", 30),
+            ("close_position", 100),
+            ("get_market_data", 20),
+            ("get_account_info", 200),
+            ("get_positions", 100),
+        ]
+        .into_iter()
+        .map(|(operation, ms)| (operation.to_string(), Duration::from_millis(ms)))
+        .collect();
         Self { sla_limits }
     }
 
@@ -447,6 +619,12 @@ impl SLAMonitor {
     }
 }
 
+impl Default for SLAMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// SLA violation record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SLAViolation {
@@ -461,8 +639,200 @@ pub struct SLAViolation {
 pub struct SLAComplianceReport {
     pub is_compliant: bool,
     pub violations: Vec<SLAViolation>,
+    /// Share of calls within the SLA, per operation with one
+    #[serde(default)]
+    pub compliance: HashMap<String, f64>,
     pub overall_error_rate: f64,
-    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Platform wrapper that times every call to the wrapped adapter in a
+/// [`PerformanceMonitor`], and adds its figures to the adapter's diagnostics
+pub struct MonitoredPlatform {
+    inner: Arc<dyn ITradingPlatform + Send + Sync>,
+    monitor: PerformanceMonitor,
+}
+
+impl MonitoredPlatform {
+    pub fn new(
+        inner: Arc<dyn ITradingPlatform + Send + Sync>,
+        monitor: PerformanceMonitor,
+    ) -> Self {
+        Self { inner, monitor }
+    }
+
+    pub fn monitor(&self) -> &PerformanceMonitor {
+        &self.monitor
+    }
+
+    async fn call<T, F>(&self, operation: &str, request: F) -> Result<T, PlatformError>
+    where
+        F: Future<Output = Result<T, PlatformError>>,
+    {
+        let timer = self.monitor.start_operation(operation);
+        let result = request.await;
+        match &result {
+            Ok(_) => timer.success(),
+            Err(e) => timer.error(e),
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl PlatformDecorator for MonitoredPlatform {
+    type Inner = dyn ITradingPlatform + Send + Sync;
+
+    fn inner(&self) -> &Self::Inner {
+        self.inner.as_ref()
+    }
+
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        self.call("ping", self.inner.ping()).await
+    }
+
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call("place_order", self.inner.place_order(order))
+            .await
+    }
+
+    async fn modify_order(
+        &self,
+        order_id: &str,
+        modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call(
+            "modify_order",
+            self.inner.modify_order(order_id, modifications),
+        )
+        .await
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<(), PlatformError> {
+        self.call("cancel_order", self.inner.cancel_order(order_id))
+            .await
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call("get_order", self.inner.get_order(order_id)).await
+    }
+
+    async fn get_orders(
+        &self,
+        filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        self.call("get_orders", self.inner.get_orders(filter)).await
+    }
+
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        self.call("get_positions", self.inner.get_positions()).await
+    }
+
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        self.call("get_position", self.inner.get_position(symbol))
+            .await
+    }
+
+    async fn close_position(
+        &self,
+        symbol: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call(
+            "close_position",
+            self.inner.close_position(symbol, quantity),
+        )
+        .await
+    }
+
+    async fn get_position_tickets(&self, symbol: &str) -> Result<PositionTickets, PlatformError> {
+        self.call(
+            "get_position_tickets",
+            self.inner.get_position_tickets(symbol),
+        )
+        .await
+    }
+
+    async fn close_position_ticket(
+        &self,
+        position_id: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call(
+            "close_position_ticket",
+            self.inner.close_position_ticket(position_id, quantity),
+        )
+        .await
+    }
+
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        self.call("get_account_info", self.inner.get_account_info())
+            .await
+    }
+
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        self.call("get_balance", self.inner.get_balance()).await
+    }
+
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        self.call("get_margin_info", self.inner.get_margin_info())
+            .await
+    }
+
+    async fn get_transactions(
+        &self,
+        range: TransactionRange,
+    ) -> Result<Vec<UnifiedTransaction>, PlatformError> {
+        self.call("get_transactions", self.inner.get_transactions(range))
+            .await
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        self.call("get_market_data", self.inner.get_market_data(symbol))
+            .await
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<Symbol>, PlatformError> {
+        self.call("get_instruments", self.inner.get_instruments())
+            .await
+    }
+
+    async fn get_order_book(
+        &self,
+        symbol: &str,
+        levels: usize,
+    ) -> Result<UnifiedOrderBook, PlatformError> {
+        self.call("get_order_book", self.inner.get_order_book(symbol, levels))
+            .await
+    }
+
+    async fn get_server_time(&self) -> Result<DateTime<Utc>, PlatformError> {
+        self.call("get_server_time", self.inner.get_server_time())
+            .await
+    }
+
+    async fn get_event_history(
+        &self,
+        filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        self.call("get_event_history", self.inner.get_event_history(filter))
+            .await
+    }
+
+    /// The wrapped platform's diagnostics, with this wrapper's latency, SLA and
+    /// error figures added to `performance_metrics` and its recent errors to
+    /// `last_errors`
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        let mut diagnostics = self.inner.get_diagnostics().await?;
+        diagnostics
+            .performance_metrics
+            .extend(self.monitor.diagnostics());
+        diagnostics.last_errors.extend(self.monitor.recent_errors());
+        Ok(diagnostics)
+    }
 }
 
 /// Performance benchmark utilities
@@ -478,7 +848,7 @@ impl PerformanceBenchmark {
     ) -> BenchmarkResult
     where
         F: Fn() -> Fut,
-        Fut: std::future::Future<Output = Result<T, PlatformError>>,
+        Fut: Future<Output = Result<T, PlatformError>>,
     {
         let mut successful_runs = 0;
         let mut failed_runs = 0;
@@ -488,7 +858,7 @@ impl PerformanceBenchmark {
         for _ in 0..iterations {
             let timer = monitor.start_operation(operation_name);
             let operation_start = Instant::now();
-            
+
             match operation().await {
                 Ok(_) => {
                     successful_runs += 1;
@@ -499,12 +869,12 @@ impl PerformanceBenchmark {
                     timer.error(&e);
                 }
             }
-            
+
             total_duration += operation_start.elapsed();
         }
 
         let benchmark_duration = start_time.elapsed();
-        let avg_operation_time = total_duration / iterations;
+        let avg_operation_time = total_duration / iterations.max(1);
 
         BenchmarkResult {
             operation: operation_name.to_string(),
@@ -514,7 +884,7 @@ impl PerformanceBenchmark {
             avg_operation_time,
             total_benchmark_time: benchmark_duration,
             operations_per_second: iterations as f64 / benchmark_duration.as_secs_f64(),
-            success_rate: successful_runs as f64 / iterations as f64,
+            success_rate: successful_runs as f64 / iterations.max(1) as f64,
         }
     }
 }
@@ -530,4 +900,4 @@ pub struct BenchmarkResult {
     pub total_benchmark_time: Duration,
     pub operations_per_second: f64,
     pub success_rate: f64,
-}
\ No newline at end of file
+}
@@ -0,0 +1,565 @@
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use super::capabilities::PlatformCapabilities;
+use super::decorator::PlatformDecorator;
+use super::errors::PlatformError;
+use super::events::PlatformEvent;
+use super::interfaces::{
+    DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter,
+};
+use super::models::*;
+use crate::platforms::PlatformType;
+
+tokio::task_local! {
+    static QUOTA_CALLER: String;
+}
+
+/// Caller that platform calls are charged to outside any [`with_caller`] scope
+pub const UNSCOPED_CALLER: &str = "unscoped";
+
+/// Charge platform calls made by `future`, and by tasks it spawns through the
+/// runtime's spawn helpers, to `caller`
+pub async fn with_caller<F: Future>(caller: impl Into<String>, future: F) -> F::Output {
+    QUOTA_CALLER.scope(caller.into(), future).await
+}
+
+/// The caller the current task's platform calls are charged to, if any
+pub fn current_caller() -> Option<String> {
+    QUOTA_CALLER.try_with(|caller| caller.clone()).ok()
+}
+
+/// How readily a call gives way when the quota runs low
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaPriority {
+    /// Waits once less than twice the reserve is left
+    Low,
+    /// Waits once only the reserve is left
+    Normal,
+    /// Order changes: runs while any quota is left, whatever the caller's budget
+    High,
+}
+
+/// Request quota of one platform, shared by every subsystem calling it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuotaConfig {
+    /// Requests the platform allows per window
+    pub requests_per_window: u32,
+    pub window_secs: u64,
+    /// Share of each window a subsystem may use, by subsystem name. Subsystems
+    /// without a budget share whatever is left.
+    pub budgets: HashMap<String, f64>,
+    /// Priority of each subsystem's reads; unlisted subsystems are `Normal`
+    pub priorities: HashMap<String, QuotaPriority>,
+    /// Share of each window held back for high-priority calls
+    pub reserve: f64,
+    /// Longest a queued call waits before failing as rate limited
+    pub max_wait_ms: u64,
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_window: 600,
+            window_secs: 60,
+            budgets: HashMap::from([
+                ("orchestrator".to_string(), 0.4),
+                ("exit-management".to_string(), 0.3),
+                ("risk-monitor".to_string(), 0.2),
+            ]),
+            priorities: HashMap::from([("risk-monitor".to_string(), QuotaPriority::Low)]),
+            reserve: 0.1,
+            max_wait_ms: 10_000,
+        }
+    }
+}
+
+/// Limits the platform reported in its last response headers
+#[derive(Debug, Clone, Copy)]
+struct ReportedLimits {
+    limit: Option<u32>,
+    remaining: u32,
+    /// Requests this manager had granted when `remaining` was reported
+    used_at: u32,
+    reset_at: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct QuotaState {
+    window_start: Instant,
+    used: u32,
+    used_by_caller: HashMap<String, u32>,
+    queued: usize,
+    reported: Option<ReportedLimits>,
+    /// Set when the platform rejected a call as rate limited
+    blocked_until: Option<Instant>,
+}
+
+enum Admission {
+    Granted,
+    WaitUntil(Instant),
+}
+
+/// Hands out a platform's request quota between subsystems: each gets its
+/// budgeted share of the window, lower-priority calls queue once the quota runs
+/// low, and the platform's own rate-limit headers override the local count.
+#[derive(Debug)]
+pub struct QuotaManager {
+    config: QuotaConfig,
+    state: Mutex<QuotaState>,
+    released: Notify,
+}
+
+impl QuotaManager {
+    pub fn new(config: QuotaConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(QuotaState {
+                window_start: Instant::now(),
+                used: 0,
+                used_by_caller: HashMap::new(),
+                queued: 0,
+                reported: None,
+                blocked_until: None,
+            }),
+            released: Notify::new(),
+        }
+    }
+
+    pub fn config(&self) -> &QuotaConfig {
+        &self.config
+    }
+
+    fn window(&self) -> Duration {
+        Duration::from_secs(self.config.window_secs.max(1))
+    }
+
+    /// Priority of `caller`'s reads
+    pub fn priority_of(&self, caller: &str) -> QuotaPriority {
+        self.config
+            .priorities
+            .get(caller)
+            .copied()
+            .unwrap_or(QuotaPriority::Normal)
+    }
+
+    /// Wait until `caller` may make one request at `priority`, then charge it.
+    /// Fails as rate limited if that takes longer than `max_wait_ms`.
+    pub async fn acquire(
+        &self,
+        caller: &str,
+        priority: QuotaPriority,
+    ) -> Result<(), PlatformError> {
+        let deadline = Instant::now() + Duration::from_millis(self.config.max_wait_ms);
+        let mut queued: Option<QueuedCall> = None;
+
+        loop {
+            let released = self.released.notified();
+            let until = match self.try_admit(caller, priority, Instant::now()) {
+                Admission::Granted => return Ok(()),
+                Admission::WaitUntil(until) => until,
+            };
+            let now = Instant::now();
+            if now >= deadline {
+                warn!(
+                    "Platform call from {} gave up waiting for request quota",
+                    caller
+                );
+                return Err(PlatformError::RateLimitExceeded {
+                    retry_after_ms: until.saturating_duration_since(now).as_millis() as u64,
+                });
+            }
+            if queued.is_none() {
+                debug!("Queued {:?} platform call from {}", priority, caller);
+                queued = Some(QueuedCall::new(self));
+            }
+            tokio::select! {
+                _ = released => {}
+                _ = tokio::time::sleep_until(until.min(deadline)) => {}
+            }
+        }
+    }
+
+    fn try_admit(&self, caller: &str, priority: QuotaPriority, now: Instant) -> Admission {
+        let mut state = self.state.lock().unwrap();
+        let window_end = self.roll_window(&mut state, now);
+
+        if let Some(until) = state.blocked_until.filter(|until| *until > now) {
+            return Admission::WaitUntil(until);
+        }
+
+        let limit = self.limit(&state);
+        let mut remaining = limit.saturating_sub(state.used);
+        let mut reset_at = window_end;
+        if let Some(reported) = state.reported {
+            remaining = remaining.min(
+                reported
+                    .remaining
+                    .saturating_sub(state.used - reported.used_at),
+            );
+            reset_at = reported.reset_at.unwrap_or(window_end);
+        }
+        if remaining == 0 {
+            return Admission::WaitUntil(reset_at.max(now + Duration::from_millis(1)));
+        }
+
+        if priority < QuotaPriority::High {
+            let reserve = (limit as f64 * self.config.reserve).ceil() as u32;
+            let floor = match priority {
+                QuotaPriority::Low => reserve.saturating_mul(2),
+                _ => reserve,
+            };
+            if remaining <= floor {
+                return Admission::WaitUntil(window_end);
+            }
+            if let Some(share) = self.config.budgets.get(caller) {
+                let budget = (limit as f64 * share).floor() as u32;
+                if state.used_by_caller.get(caller).copied().unwrap_or(0) >= budget {
+                    return Admission::WaitUntil(window_end);
+                }
+            }
+        }
+
+        state.used += 1;
+        *state.used_by_caller.entry(caller.to_string()).or_insert(0) += 1;
+        Admission::Granted
+    }
+
+    /// Start a new window once the current one has passed; returns when the
+    /// current window ends
+    fn roll_window(&self, state: &mut QuotaState, now: Instant) -> Instant {
+        let window = self.window();
+        if state
+            .reported
+            .is_some_and(|reported| reported.reset_at.is_some_and(|reset_at| reset_at <= now))
+        {
+            state.reported = None;
+        }
+        if now >= state.window_start + window {
+            state.window_start = now;
+            state.used = 0;
+            state.used_by_caller.clear();
+            // Headers without a reset time only hold for the window they arrived in
+            state.reported = state
+                .reported
+                .filter(|reported| reported.reset_at.is_some())
+                .map(|reported| ReportedLimits {
+                    used_at: 0,
+                    ..reported
+                });
+        }
+        state.window_start + window
+    }
+
+    fn limit(&self, state: &QuotaState) -> u32 {
+        state
+            .reported
+            .and_then(|reported| reported.limit)
+            .unwrap_or(self.config.requests_per_window)
+    }
+
+    /// Take the remaining quota from rate-limit response headers such as
+    /// `X-RateLimit-Remaining`, `X-RateLimit-Limit` and `X-RateLimit-Reset`
+    /// (seconds until the window resets). Other headers are ignored.
+    pub fn observe_headers<K, V>(&self, headers: impl IntoIterator<Item = (K, V)>)
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let (mut limit, mut remaining, mut reset) = (None, None, None);
+        for (name, value) in headers {
+            let name = name.as_ref().to_ascii_lowercase();
+            let name = name.strip_prefix("x-").unwrap_or(&name);
+            let Ok(value) = value.as_ref().trim().parse::<f64>() else {
+                continue;
+            };
+            match name {
+                "ratelimit-limit" => limit = Some(value as u32),
+                "ratelimit-remaining" => remaining = Some(value as u32),
+                "ratelimit-reset" => reset = Some(value).filter(|secs| secs.is_finite()),
+                _ => {}
+            }
+        }
+        let Some(remaining) = remaining else {
+            return;
+        };
+
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        self.roll_window(&mut state, now);
+        state.reported = Some(ReportedLimits {
+            limit,
+            remaining,
+            used_at: state.used,
+            // A reset too far out to represent is no reset; the window still ends
+            reset_at: reset
+                .and_then(|secs| Duration::try_from_secs_f64(secs.max(0.0)).ok())
+                .and_then(|reset| now.checked_add(reset)),
+        });
+        drop(state);
+        self.released.notify_waiters();
+    }
+
+    /// Hold every call back for `retry_after` after the platform rejected one as
+    /// rate limited
+    pub fn throttle(&self, retry_after: Duration) {
+        let until = Instant::now() + retry_after;
+        let mut state = self.state.lock().unwrap();
+        state.blocked_until = Some(state.blocked_until.map_or(until, |b| b.max(until)));
+    }
+
+    /// Quota metrics in the form `DiagnosticsInfo.api_limits` uses
+    pub fn api_limits(&self) -> HashMap<String, String> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let window_end = self.roll_window(&mut state, now);
+        let limit = self.limit(&state);
+
+        let mut limits = HashMap::from([
+            ("quota.limit".to_string(), limit.to_string()),
+            (
+                "quota.window_secs".to_string(),
+                self.config.window_secs.to_string(),
+            ),
+            (
+                "quota.remaining".to_string(),
+                limit.saturating_sub(state.used).to_string(),
+            ),
+            (
+                "quota.resets_in_secs".to_string(),
+                window_end
+                    .saturating_duration_since(now)
+                    .as_secs()
+                    .to_string(),
+            ),
+            ("quota.queued".to_string(), state.queued.to_string()),
+        ]);
+        if let Some(reported) = state.reported {
+            limits.insert(
+                "quota.platform_remaining".to_string(),
+                reported
+                    .remaining
+                    .saturating_sub(state.used - reported.used_at)
+                    .to_string(),
+            );
+        }
+        for (caller, used) in &state.used_by_caller {
+            limits.insert(format!("quota.used.{}", caller), used.to_string());
+        }
+        for (caller, share) in &self.config.budgets {
+            limits.insert(
+                format!("quota.budget.{}", caller),
+                ((limit as f64 * share).floor() as u32).to_string(),
+            );
+        }
+        limits
+    }
+}
+
+/// Counts a call in the queue until it is granted, fails or is dropped
+struct QueuedCall<'a> {
+    manager: &'a QuotaManager,
+}
+
+impl<'a> QueuedCall<'a> {
+    fn new(manager: &'a QuotaManager) -> Self {
+        manager.state.lock().unwrap().queued += 1;
+        Self { manager }
+    }
+}
+
+impl Drop for QueuedCall<'_> {
+    fn drop(&mut self) {
+        self.manager.state.lock().unwrap().queued -= 1;
+    }
+}
+
+/// Platform wrapper that charges every request to the calling subsystem's budget
+/// in a shared [`QuotaManager`]. Order changes run at high priority; reads run
+/// at the caller's configured priority.
+pub struct QuotaPlatform {
+    inner: Arc<dyn ITradingPlatform + Send + Sync>,
+    manager: Arc<QuotaManager>,
+}
+
+impl QuotaPlatform {
+    pub fn new(inner: Arc<dyn ITradingPlatform + Send + Sync>, manager: Arc<QuotaManager>) -> Self {
+        Self { inner, manager }
+    }
+
+    pub fn manager(&self) -> &Arc<QuotaManager> {
+        &self.manager
+    }
+
+    async fn call<T, F>(
+        &self,
+        priority: Option<QuotaPriority>,
+        request: F,
+    ) -> Result<T, PlatformError>
+    where
+        F: Future<Output = Result<T, PlatformError>>,
+    {
+        let caller = current_caller().unwrap_or_else(|| UNSCOPED_CALLER.to_string());
+        let priority = priority.unwrap_or_else(|| self.manager.priority_of(&caller));
+        self.manager.acquire(&caller, priority).await?;
+
+        let result = request.await;
+        if let Err(PlatformError::RateLimitExceeded { retry_after_ms }) = &result {
+            self.manager
+                .throttle(Duration::from_millis(*retry_after_ms));
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl PlatformDecorator for QuotaPlatform {
+    type Inner = dyn ITradingPlatform + Send + Sync;
+
+    fn inner(&self) -> &Self::Inner {
+        self.inner.as_ref()
+    }
+
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        self.call(None, self.inner.ping()).await
+    }
+
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call(Some(QuotaPriority::High), self.inner.place_order(order))
+            .await
+    }
+
+    async fn modify_order(
+        &self,
+        order_id: &str,
+        modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call(
+            Some(QuotaPriority::High),
+            self.inner.modify_order(order_id, modifications),
+        )
+        .await
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<(), PlatformError> {
+        self.call(Some(QuotaPriority::High), self.inner.cancel_order(order_id))
+            .await
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call(None, self.inner.get_order(order_id)).await
+    }
+
+    async fn get_orders(
+        &self,
+        filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        self.call(None, self.inner.get_orders(filter)).await
+    }
+
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        self.call(None, self.inner.get_positions()).await
+    }
+
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        self.call(None, self.inner.get_position(symbol)).await
+    }
+
+    async fn close_position(
+        &self,
+        symbol: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call(
+            Some(QuotaPriority::High),
+            self.inner.close_position(symbol, quantity),
+        )
+        .await
+    }
+
+    async fn get_position_tickets(&self, symbol: &str) -> Result<PositionTickets, PlatformError> {
+        self.call(None, self.inner.get_position_tickets(symbol))
+            .await
+    }
+
+    async fn close_position_ticket(
+        &self,
+        position_id: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call(
+            Some(QuotaPriority::High),
+            self.inner.close_position_ticket(position_id, quantity),
+        )
+        .await
+    }
+
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        self.call(None, self.inner.get_account_info()).await
+    }
+
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        self.call(None, self.inner.get_balance()).await
+    }
+
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        self.call(None, self.inner.get_margin_info()).await
+    }
+
+    async fn get_transactions(
+        &self,
+        range: TransactionRange,
+    ) -> Result<Vec<UnifiedTransaction>, PlatformError> {
+        self.call(None, self.inner.get_transactions(range)).await
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        self.call(None, self.inner.get_market_data(symbol)).await
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<Symbol>, PlatformError> {
+        self.call(None, self.inner.get_instruments()).await
+    }
+
+    async fn get_order_book(
+        &self,
+        symbol: &str,
+        levels: usize,
+    ) -> Result<UnifiedOrderBook, PlatformError> {
+        self.call(None, self.inner.get_order_book(symbol, levels))
+            .await
+    }
+
+    async fn get_server_time(&self) -> Result<chrono::DateTime<chrono::Utc>, PlatformError> {
+        self.call(None, self.inner.get_server_time()).await
+    }
+
+    async fn get_event_history(
+        &self,
+        filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        self.call(None, self.inner.get_event_history(filter)).await
+    }
+
+    /// The wrapped platform's diagnostics, with the rate-limit headers it reports
+    /// fed into the quota and the quota's metrics added to `api_limits`
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        let mut diagnostics = self.inner.get_diagnostics().await?;
+        self.manager.observe_headers(&diagnostics.api_limits);
+        diagnostics.api_limits.extend(self.manager.api_limits());
+        Ok(diagnostics)
+    }
+}
@@ -0,0 +1,404 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use risk_types::AlertLevel;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use super::capabilities::PlatformCapabilities;
+use super::clock::ServerClock;
+use super::decorator::PlatformDecorator;
+use super::errors::PlatformError;
+use super::events::PlatformEvent;
+use super::interfaces::{
+    DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter,
+};
+use super::models::*;
+use crate::alerting::{Alert, AlertGateway};
+use crate::platforms::PlatformType;
+use crate::runtime::spawn::spawn_isolated;
+
+lazy_static! {
+    pub static ref QUARANTINED_QUOTES: IntCounterVec = register_int_counter_vec!(
+        "execution_engine_quarantined_quotes_total",
+        "Quotes held back from consumers by the price sanity filter",
+        &["reason"]
+    )
+    .unwrap();
+}
+
+/// Alert type raised for quarantined quotes
+pub const BAD_QUOTE_ALERT_TYPE: &str = "bad_quote";
+
+/// Quarantined quotes kept for inspection; the oldest are dropped beyond this
+const QUARANTINE_LIMIT: usize = 500;
+
+/// Bounds a quote must stay within before anything acts on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuoteFilterConfig {
+    pub enabled: bool,
+    /// Oldest a quote may be, measured from its own timestamp
+    pub max_age_ms: u64,
+    /// Largest move of the mid price from the last accepted quote, in percent
+    pub max_deviation_pct: f64,
+    /// Widest spread accepted, in percent of the mid price
+    pub max_spread_pct: f64,
+    /// Consecutive quotes agreeing on a new price level that are taken as a real
+    /// gap rather than bad ticks
+    pub confirm_after: u32,
+}
+
+impl Default for QuoteFilterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_age_ms: 10_000,
+            max_deviation_pct: 2.0,
+            max_spread_pct: 1.0,
+            confirm_after: 3,
+        }
+    }
+}
+
+/// Why a quote was quarantined
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteRejection {
+    Stale {
+        age_ms: i64,
+    },
+    NonPositive,
+    Crossed {
+        bid: Decimal,
+        ask: Decimal,
+    },
+    WideSpread {
+        spread_pct: f64,
+    },
+    Deviation {
+        last_mid: Decimal,
+        deviation_pct: f64,
+    },
+}
+
+impl QuoteRejection {
+    pub fn reason(&self) -> &'static str {
+        match self {
+            QuoteRejection::Stale { .. } => "stale",
+            QuoteRejection::NonPositive => "non_positive",
+            QuoteRejection::Crossed { .. } => "crossed",
+            QuoteRejection::WideSpread { .. } => "wide_spread",
+            QuoteRejection::Deviation { .. } => "deviation",
+        }
+    }
+}
+
+impl fmt::Display for QuoteRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuoteRejection::Stale { age_ms } => write!(f, "quote is {}ms old", age_ms),
+            QuoteRejection::NonPositive => write!(f, "quote has a non-positive price"),
+            QuoteRejection::Crossed { bid, ask } => {
+                write!(f, "bid {} is above ask {}", bid, ask)
+            }
+            QuoteRejection::WideSpread { spread_pct } => {
+                write!(f, "spread is {:.3}% of the price", spread_pct)
+            }
+            QuoteRejection::Deviation {
+                last_mid,
+                deviation_pct,
+            } => write!(f, "price moved {:.3}% from {}", deviation_pct, last_mid),
+        }
+    }
+}
+
+/// A quote held back from consumers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedQuote {
+    pub quote: UnifiedMarketData,
+    pub rejection: QuoteRejection,
+    pub quarantined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct SymbolState {
+    last_mid: Option<Decimal>,
+    /// Mid prices of consecutive quotes that deviated from `last_mid`
+    unconfirmed: Vec<Decimal>,
+}
+
+/// Checks quotes against their age, each other and the last accepted price
+#[derive(Debug)]
+pub struct QuoteValidator {
+    config: QuoteFilterConfig,
+    symbols: Mutex<HashMap<String, SymbolState>>,
+}
+
+impl QuoteValidator {
+    pub fn new(config: QuoteFilterConfig) -> Self {
+        Self {
+            config,
+            symbols: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &QuoteFilterConfig {
+        &self.config
+    }
+
+    /// Accept `quote` as the symbol's latest price, or say why it must not be
+    /// acted on. A deviating price becomes the new reference once `confirm_after`
+    /// consecutive quotes agree on it.
+    pub fn check(
+        &self,
+        quote: &UnifiedMarketData,
+        now: DateTime<Utc>,
+    ) -> Result<(), QuoteRejection> {
+        let age_ms = (now - quote.timestamp).num_milliseconds();
+        if age_ms > self.config.max_age_ms as i64 {
+            return Err(QuoteRejection::Stale { age_ms });
+        }
+        if quote.bid <= Decimal::ZERO || quote.ask <= Decimal::ZERO {
+            return Err(QuoteRejection::NonPositive);
+        }
+        if quote.bid > quote.ask {
+            return Err(QuoteRejection::Crossed {
+                bid: quote.bid,
+                ask: quote.ask,
+            });
+        }
+        let mid = (quote.bid + quote.ask) / Decimal::TWO;
+        let spread_pct = percent(quote.ask - quote.bid, mid);
+        if spread_pct > self.config.max_spread_pct {
+            return Err(QuoteRejection::WideSpread { spread_pct });
+        }
+
+        let mut symbols = self.symbols.lock().unwrap();
+        let state = symbols.entry(quote.symbol.clone()).or_default();
+        if let Some(last_mid) = state.last_mid {
+            let deviation_pct = percent(mid - last_mid, last_mid);
+            if deviation_pct > self.config.max_deviation_pct {
+                // A new level only counts while the deviating quotes agree with each other
+                if state.unconfirmed.last().is_some_and(|previous| {
+                    percent(mid - previous, *previous) > self.config.max_deviation_pct
+                }) {
+                    state.unconfirmed.clear();
+                }
+                state.unconfirmed.push(mid);
+                if state.unconfirmed.len() < self.config.confirm_after as usize {
+                    return Err(QuoteRejection::Deviation {
+                        last_mid,
+                        deviation_pct,
+                    });
+                }
+                debug!(
+                    "Accepting new price level {} for {} after {} confirming quotes",
+                    mid,
+                    quote.symbol,
+                    state.unconfirmed.len()
+                );
+            }
+        }
+        state.last_mid = Some(mid);
+        state.unconfirmed.clear();
+        Ok(())
+    }
+}
+
+fn percent(difference: Decimal, base: Decimal) -> f64 {
+    if base.is_zero() {
+        return f64::INFINITY;
+    }
+    (difference.abs() / base * Decimal::ONE_HUNDRED)
+        .to_f64()
+        .unwrap_or(f64::INFINITY)
+}
+
+/// Quarantines bad quotes and alerts on them, shared by the platform's request
+/// path and its market data stream
+struct QuoteQuarantine {
+    account_id: String,
+    validator: QuoteValidator,
+    gateway: Option<Arc<AlertGateway>>,
+    /// Quote ages are measured on the platform server's clock
+    clock: ServerClock,
+    quarantined: Mutex<VecDeque<QuarantinedQuote>>,
+    /// Symbols with an alert raised since their last good quote
+    alerting: Mutex<HashSet<String>>,
+}
+
+impl QuoteQuarantine {
+    fn new(
+        account_id: String,
+        config: QuoteFilterConfig,
+        gateway: Option<Arc<AlertGateway>>,
+        clock: ServerClock,
+    ) -> Self {
+        Self {
+            account_id,
+            validator: QuoteValidator::new(config),
+            gateway,
+            clock,
+            quarantined: Mutex::new(VecDeque::new()),
+            alerting: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn admit(&self, quote: &UnifiedMarketData) -> Result<(), QuoteRejection> {
+        let now = self.clock.now();
+        let alert_key = format!("{}:{}", self.account_id, quote.symbol);
+        let rejection = match self.validator.check(quote, now) {
+            Ok(()) => {
+                if self.alerting.lock().unwrap().remove(&quote.symbol) {
+                    if let Some(gateway) = &self.gateway {
+                        gateway.resolve(BAD_QUOTE_ALERT_TYPE, &alert_key);
+                    }
+                }
+                return Ok(());
+            }
+            Err(rejection) => rejection,
+        };
+
+        warn!(
+            "Quarantined {} quote on account {}: {}",
+            quote.symbol, self.account_id, rejection
+        );
+        QUARANTINED_QUOTES
+            .with_label_values(&[rejection.reason()])
+            .inc();
+        {
+            let mut quarantined = self.quarantined.lock().unwrap();
+            if quarantined.len() == QUARANTINE_LIMIT {
+                quarantined.pop_front();
+            }
+            quarantined.push_back(QuarantinedQuote {
+                quote: quote.clone(),
+                rejection: rejection.clone(),
+                quarantined_at: now,
+            });
+        }
+        self.alerting.lock().unwrap().insert(quote.symbol.clone());
+
+        if let Some(gateway) = &self.gateway {
+            let gateway = gateway.clone();
+            let alert = Alert {
+                alert_type: BAD_QUOTE_ALERT_TYPE.to_string(),
+                key: alert_key,
+                severity: AlertLevel::Warning,
+                account_id: None,
+                message: format!(
+                    "Quarantined {} quote on account {}: {}",
+                    quote.symbol, self.account_id, rejection
+                ),
+                raised_at: now,
+            };
+            spawn_isolated("bad-quote-alert-delivery", async move {
+                gateway.submit(alert).await;
+            });
+        }
+        Err(rejection)
+    }
+}
+
+/// Platform wrapper that validates every quote before consumers see it. Bad
+/// quotes are quarantined and alerted on: requests for them fail as market data
+/// unavailable and they are dropped from market data streams.
+pub struct QuoteFilteringPlatform {
+    inner: Arc<dyn ITradingPlatform + Send + Sync>,
+    quarantine: Arc<QuoteQuarantine>,
+}
+
+impl QuoteFilteringPlatform {
+    pub fn new(
+        inner: Arc<dyn ITradingPlatform + Send + Sync>,
+        account_id: impl Into<String>,
+        config: QuoteFilterConfig,
+    ) -> Self {
+        Self {
+            inner,
+            quarantine: Arc::new(QuoteQuarantine::new(
+                account_id.into(),
+                config,
+                None,
+                ServerClock::new(),
+            )),
+        }
+    }
+
+    /// Raise an alert through `gateway` for every quarantined quote
+    pub fn with_alert_gateway(mut self, gateway: Arc<AlertGateway>) -> Self {
+        self.quarantine = Arc::new(QuoteQuarantine::new(
+            self.quarantine.account_id.clone(),
+            self.quarantine.validator.config().clone(),
+            Some(gateway),
+            self.quarantine.clock.clone(),
+        ));
+        self
+    }
+
+    /// Age quotes on `clock`, the account's measure of its server's time
+    pub fn with_server_clock(mut self, clock: ServerClock) -> Self {
+        self.quarantine = Arc::new(QuoteQuarantine::new(
+            self.quarantine.account_id.clone(),
+            self.quarantine.validator.config().clone(),
+            self.quarantine.gateway.clone(),
+            clock,
+        ));
+        self
+    }
+
+    /// Quotes held back so far, oldest first
+    pub fn quarantined(&self) -> Vec<QuarantinedQuote> {
+        self.quarantine
+            .quarantined
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl PlatformDecorator for QuoteFilteringPlatform {
+    type Inner = dyn ITradingPlatform + Send + Sync;
+
+    fn inner(&self) -> &Self::Inner {
+        self.inner.as_ref()
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        let quote = self.inner.get_market_data(symbol).await?;
+        self.quarantine.admit(&quote).map_err(|rejection| {
+            PlatformError::MarketDataUnavailable {
+                reason: format!("{} quote quarantined: {}", symbol, rejection),
+            }
+        })?;
+        Ok(quote)
+    }
+
+    async fn subscribe_market_data(
+        &self,
+        symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        let mut upstream = self.inner.subscribe_market_data(symbols).await?;
+        let (sender, receiver) = mpsc::channel(upstream.max_capacity());
+        let quarantine = self.quarantine.clone();
+        spawn_isolated("quote-filter", async move {
+            while let Some(quote) = upstream.recv().await {
+                if quarantine.admit(&quote).is_ok() && sender.send(quote).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(receiver)
+    }
+}
@@ -0,0 +1,413 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+
+use super::errors::PlatformError;
+use super::interfaces::{DiagnosticsInfo, ITradingPlatform};
+use super::models::{UnifiedOrderResponse, UnifiedPosition};
+
+const DIAGNOSTICS_KEY: &str = "recovery";
+
+/// Snapshot of account state taken before recovery, used to reconcile afterwards
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryState {
+    pub account_id: String,
+    pub last_known_state: DateTime<Utc>,
+    pub pending_orders: Vec<UnifiedOrderResponse>,
+    pub open_positions: Vec<UnifiedPosition>,
+    pub last_balance: rust_decimal::Decimal,
+}
+
+impl RecoveryState {
+    pub async fn capture(
+        account_id: &str,
+        platform: &(dyn ITradingPlatform + Send + Sync),
+    ) -> Result<Self, PlatformError> {
+        Ok(Self {
+            account_id: account_id.to_string(),
+            last_known_state: Utc::now(),
+            pending_orders: platform.get_orders(None).await?,
+            open_positions: platform.get_positions().await?,
+            last_balance: platform.get_balance().await?,
+        })
+    }
+}
+
+/// Individual recovery actions, executed in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RecoveryStep {
+    Reconnect,
+    Reauthenticate,
+    Resubscribe,
+    ResyncState,
+}
+
+impl RecoveryStep {
+    /// Steps needed to recover from an error; empty when the error is not a session fault
+    pub fn plan_for(error: &PlatformError) -> Vec<RecoveryStep> {
+        match error {
+            PlatformError::AuthenticationFailed { .. }
+            | PlatformError::InvalidCredentials { .. } => {
+                vec![
+                    RecoveryStep::Reauthenticate,
+                    RecoveryStep::Resubscribe,
+                    RecoveryStep::ResyncState,
+                ]
+            }
+            PlatformError::ConnectionFailed { .. }
+            | PlatformError::ConnectionTimeout { .. }
+            | PlatformError::Disconnected { .. }
+            | PlatformError::NetworkError { .. } => vec![
+                RecoveryStep::Reconnect,
+                RecoveryStep::Resubscribe,
+                RecoveryStep::ResyncState,
+            ],
+            PlatformError::SubscriptionFailed { .. } => vec![RecoveryStep::Resubscribe],
+            PlatformError::RequestTimeout { .. } | PlatformError::InvalidResponse { .. } => {
+                vec![RecoveryStep::ResyncState]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RecoveryPhase {
+    Idle,
+    InProgress { step: RecoveryStep },
+    Recovered,
+    Failed,
+}
+
+/// Recovery progress for one account, surfaced through `DiagnosticsInfo`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryProgress {
+    pub account_id: String,
+    pub phase: RecoveryPhase,
+    pub trigger: Option<String>,
+    pub attempts: u32,
+    pub completed_steps: Vec<RecoveryStep>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub discrepancies: Vec<String>,
+}
+
+impl RecoveryProgress {
+    fn idle(account_id: &str) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            phase: RecoveryPhase::Idle,
+            trigger: None,
+            attempts: 0,
+            completed_steps: Vec::new(),
+            started_at: None,
+            finished_at: None,
+            last_error: None,
+            discrepancies: Vec::new(),
+        }
+    }
+
+    pub fn attach_to(&self, diagnostics: &mut DiagnosticsInfo) {
+        if let Ok(value) = serde_json::to_value(self) {
+            diagnostics
+                .platform_specific
+                .insert(DIAGNOSTICS_KEY.to_string(), value);
+        }
+    }
+
+    pub fn from_diagnostics(diagnostics: &DiagnosticsInfo) -> Option<Self> {
+        diagnostics
+            .platform_specific
+            .get(DIAGNOSTICS_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+}
+
+/// Platform-specific recovery actions each adapter plugs in
+#[async_trait]
+pub trait RecoveryHandler: Send + Sync {
+    async fn reconnect(&self, account_id: &str) -> Result<(), PlatformError>;
+
+    /// Defaults to a full reconnect, which re-authenticates on most platforms
+    async fn reauthenticate(&self, account_id: &str) -> Result<(), PlatformError> {
+        self.reconnect(account_id).await
+    }
+
+    async fn resubscribe(&self, _account_id: &str) -> Result<(), PlatformError> {
+        Ok(())
+    }
+
+    async fn fetch_state(&self, account_id: &str) -> Result<RecoveryState, PlatformError>;
+}
+
+#[derive(Debug, Clone)]
+pub struct RecoveryConfig {
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RecoveryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 30000,
+        }
+    }
+}
+
+/// Platform-agnostic recovery: reconnect, re-auth, resubscribe and state resync
+pub struct ErrorRecoveryManager {
+    config: RecoveryConfig,
+    handlers: Arc<RwLock<HashMap<String, Arc<dyn RecoveryHandler>>>>,
+    saved_states: Arc<RwLock<HashMap<String, RecoveryState>>>,
+    progress: Arc<RwLock<HashMap<String, RecoveryProgress>>>,
+}
+
+impl ErrorRecoveryManager {
+    pub fn new(config: RecoveryConfig) -> Self {
+        Self {
+            config,
+            handlers: Arc::new(RwLock::new(HashMap::new())),
+            saved_states: Arc::new(RwLock::new(HashMap::new())),
+            progress: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn register_handler(&self, account_id: &str, handler: Arc<dyn RecoveryHandler>) {
+        self.handlers
+            .write()
+            .await
+            .insert(account_id.to_string(), handler);
+        self.progress
+            .write()
+            .await
+            .insert(account_id.to_string(), RecoveryProgress::idle(account_id));
+    }
+
+    pub async fn save_state(&self, state: RecoveryState) {
+        self.saved_states
+            .write()
+            .await
+            .insert(state.account_id.clone(), state);
+    }
+
+    pub async fn get_progress(&self, account_id: &str) -> Option<RecoveryProgress> {
+        self.progress.read().await.get(account_id).cloned()
+    }
+
+    /// Add the account's recovery progress to diagnostics reported by its platform
+    pub async fn annotate_diagnostics(&self, account_id: &str, diagnostics: &mut DiagnosticsInfo) {
+        if let Some(progress) = self.get_progress(account_id).await {
+            progress.attach_to(diagnostics);
+        }
+    }
+
+    /// Run the recovery plan for `error`. Returns `Ok(false)` if the error needs no recovery.
+    pub async fn handle_error(
+        &self,
+        account_id: &str,
+        error: &PlatformError,
+    ) -> Result<bool, PlatformError> {
+        let steps = RecoveryStep::plan_for(error);
+        if steps.is_empty() {
+            return Ok(false);
+        }
+
+        let handler = self
+            .handlers
+            .read()
+            .await
+            .get(account_id)
+            .cloned()
+            .ok_or_else(|| PlatformError::AccountNotFound {
+                account_id: account_id.to_string(),
+            })?;
+
+        // Keep the pre-failure snapshot if one was saved; otherwise take what we can get
+        if !self.saved_states.read().await.contains_key(account_id) {
+            if let Ok(state) = handler.fetch_state(account_id).await {
+                self.save_state(state).await;
+            }
+        }
+
+        info!(
+            "Starting recovery for account {} after {}: {:?}",
+            account_id, error, steps
+        );
+        self.update_progress(account_id, |p| {
+            *p = RecoveryProgress::idle(account_id);
+            p.trigger = Some(error.to_string());
+            p.started_at = Some(Utc::now());
+        })
+        .await;
+
+        let mut backoff = self.config.initial_backoff_ms;
+        for attempt in 1..=self.config.max_attempts {
+            self.update_progress(account_id, |p| {
+                p.attempts = attempt;
+                p.completed_steps.clear();
+            })
+            .await;
+
+            match self.run_steps(account_id, handler.as_ref(), &steps).await {
+                Ok(()) => {
+                    self.update_progress(account_id, |p| {
+                        p.phase = RecoveryPhase::Recovered;
+                        p.finished_at = Some(Utc::now());
+                        p.last_error = None;
+                    })
+                    .await;
+                    info!(
+                        "Account {} recovered after {} attempt(s)",
+                        account_id, attempt
+                    );
+                    return Ok(true);
+                }
+                Err(e) => {
+                    warn!(
+                        "Recovery attempt {} for account {} failed: {}",
+                        attempt, account_id, e
+                    );
+                    self.update_progress(account_id, |p| p.last_error = Some(e.to_string()))
+                        .await;
+                }
+            }
+
+            if attempt < self.config.max_attempts {
+                tokio::time::sleep(Duration::from_millis(backoff)).await;
+                backoff = (backoff * 2).min(self.config.max_backoff_ms);
+            }
+        }
+
+        self.update_progress(account_id, |p| {
+            p.phase = RecoveryPhase::Failed;
+            p.finished_at = Some(Utc::now());
+        })
+        .await;
+        error!(
+            "Failed to recover account {} after {} attempts",
+            account_id, self.config.max_attempts
+        );
+        Err(PlatformError::ConnectionFailed {
+            reason: format!(
+                "Recovery failed after {} attempts",
+                self.config.max_attempts
+            ),
+        })
+    }
+
+    async fn run_steps(
+        &self,
+        account_id: &str,
+        handler: &dyn RecoveryHandler,
+        steps: &[RecoveryStep],
+    ) -> Result<(), PlatformError> {
+        for step in steps {
+            let step = *step;
+            self.update_progress(account_id, |p| p.phase = RecoveryPhase::InProgress { step })
+                .await;
+
+            match step {
+                RecoveryStep::Reconnect => handler.reconnect(account_id).await?,
+                RecoveryStep::Reauthenticate => handler.reauthenticate(account_id).await?,
+                RecoveryStep::Resubscribe => handler.resubscribe(account_id).await?,
+                RecoveryStep::ResyncState => self.resync_state(account_id, handler).await?,
+            }
+
+            self.update_progress(account_id, |p| p.completed_steps.push(step))
+                .await;
+        }
+        Ok(())
+    }
+
+    async fn resync_state(
+        &self,
+        account_id: &str,
+        handler: &dyn RecoveryHandler,
+    ) -> Result<(), PlatformError> {
+        let current = handler.fetch_state(account_id).await?;
+        let saved = self.saved_states.write().await.remove(account_id);
+
+        let discrepancies = saved
+            .map(|saved| Self::reconcile(&saved, &current))
+            .unwrap_or_default();
+        for discrepancy in &discrepancies {
+            warn!("Account {}: {}", account_id, discrepancy);
+        }
+
+        self.update_progress(account_id, |p| p.discrepancies = discrepancies)
+            .await;
+        Ok(())
+    }
+
+    fn reconcile(saved: &RecoveryState, current: &RecoveryState) -> Vec<String> {
+        let mut discrepancies = Vec::new();
+
+        for position in &saved.open_positions {
+            if !current
+                .open_positions
+                .iter()
+                .any(|p| p.position_id == position.position_id)
+            {
+                discrepancies.push(format!(
+                    "Position {} was closed during disconnection",
+                    position.position_id
+                ));
+            }
+        }
+        for position in &current.open_positions {
+            if !saved
+                .open_positions
+                .iter()
+                .any(|p| p.position_id == position.position_id)
+            {
+                discrepancies.push(format!(
+                    "Position {} was opened during disconnection",
+                    position.position_id
+                ));
+            }
+        }
+        for order in &saved.pending_orders {
+            if !current
+                .pending_orders
+                .iter()
+                .any(|o| o.platform_order_id == order.platform_order_id)
+            {
+                discrepancies.push(format!(
+                    "Order {} is no longer pending",
+                    order.platform_order_id
+                ));
+            }
+        }
+        if saved.last_balance != current.last_balance {
+            discrepancies.push(format!(
+                "Balance changed from {} to {}",
+                saved.last_balance, current.last_balance
+            ));
+        }
+
+        discrepancies
+    }
+
+    async fn update_progress(&self, account_id: &str, update: impl FnOnce(&mut RecoveryProgress)) {
+        let mut progress = self.progress.write().await;
+        let entry = progress
+            .entry(account_id.to_string())
+            .or_insert_with(|| RecoveryProgress::idle(account_id));
+        update(entry);
+    }
+}
+
+impl Default for ErrorRecoveryManager {
+    fn default() -> Self {
+        Self::new(RecoveryConfig::default())
+    }
+}
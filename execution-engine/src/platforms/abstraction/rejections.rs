@@ -0,0 +1,343 @@
+// Typed reasons for order rejections, read from each platform's codes and
+// messages, and the remediation tried for each before an order is given up
+
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use super::errors::{PlatformError, ValidationError};
+use super::interfaces::ITradingPlatform;
+use super::models::{UnifiedOrder, UnifiedOrderSide};
+use crate::instruments::InstrumentMetadata;
+use crate::platforms::PlatformType;
+
+/// Why a platform refused an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectionReason {
+    InsufficientMargin,
+    /// Stop-loss or take-profit too close to the market
+    InvalidStopDistance,
+    MarketClosed,
+    /// Quantity below the minimum, above the maximum or off the lot step
+    QuantityRule,
+    /// The price moved before the order filled, or beyond its maximum slippage
+    Requote,
+    Other,
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RejectionReason::InsufficientMargin => "insufficient margin",
+            RejectionReason::InvalidStopDistance => "invalid stop distance",
+            RejectionReason::MarketClosed => "market closed",
+            RejectionReason::QuantityRule => "quantity rule",
+            RejectionReason::Requote => "requote",
+            RejectionReason::Other => "other",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Phrases found in rejection messages across platforms, matched lowercase
+const COMMON_PHRASES: &[(&str, RejectionReason)] = &[
+    ("not enough money", RejectionReason::InsufficientMargin),
+    ("insufficient margin", RejectionReason::InsufficientMargin),
+    ("not enough margin", RejectionReason::InsufficientMargin),
+    ("insufficient funds", RejectionReason::InsufficientMargin),
+    ("invalid stops", RejectionReason::InvalidStopDistance),
+    ("invalid stop", RejectionReason::InvalidStopDistance),
+    ("stop loss too close", RejectionReason::InvalidStopDistance),
+    ("stop distance", RejectionReason::InvalidStopDistance),
+    ("market is closed", RejectionReason::MarketClosed),
+    ("market closed", RejectionReason::MarketClosed),
+    ("session closed", RejectionReason::MarketClosed),
+    ("trading is disabled", RejectionReason::MarketClosed),
+    ("invalid volume", RejectionReason::QuantityRule),
+    ("invalid quantity", RejectionReason::QuantityRule),
+    ("invalid lot", RejectionReason::QuantityRule),
+    ("lot size", RejectionReason::QuantityRule),
+    ("requote", RejectionReason::Requote),
+    ("off quotes", RejectionReason::Requote),
+    ("price changed", RejectionReason::Requote),
+    ("prices changed", RejectionReason::Requote),
+];
+
+/// Maps one platform's rejections to typed reasons, by platform code first and
+/// message text second
+#[derive(Debug, Clone)]
+pub struct RejectionClassifier {
+    codes: HashMap<String, RejectionReason>,
+    phrases: Vec<(String, RejectionReason)>,
+}
+
+impl RejectionClassifier {
+    pub fn for_platform(platform: &PlatformType) -> Self {
+        let codes: &[(&str, RejectionReason)] = match platform {
+            // Trade server return codes
+            PlatformType::MetaTrader4 => &[
+                ("130", RejectionReason::InvalidStopDistance),
+                ("131", RejectionReason::QuantityRule),
+                ("132", RejectionReason::MarketClosed),
+                ("134", RejectionReason::InsufficientMargin),
+                ("135", RejectionReason::Requote),
+                ("136", RejectionReason::Requote),
+                ("138", RejectionReason::Requote),
+            ],
+            PlatformType::MetaTrader5 => &[
+                ("10004", RejectionReason::Requote),
+                ("10014", RejectionReason::QuantityRule),
+                ("10016", RejectionReason::InvalidStopDistance),
+                ("10018", RejectionReason::MarketClosed),
+                ("10019", RejectionReason::InsufficientMargin),
+                ("10020", RejectionReason::Requote),
+                ("10034", RejectionReason::QuantityRule),
+            ],
+            // FIX OrdRejReason (103)
+            PlatformType::DXTrade => &[
+                ("2", RejectionReason::MarketClosed),
+                ("3", RejectionReason::QuantityRule),
+                ("13", RejectionReason::QuantityRule),
+            ],
+            PlatformType::TradeLocker | PlatformType::Mock => &[],
+        };
+        let mut classifier = Self {
+            codes: codes
+                .iter()
+                .map(|(code, reason)| (code.to_string(), *reason))
+                .collect(),
+            phrases: Vec::new(),
+        };
+        for (phrase, reason) in COMMON_PHRASES {
+            classifier = classifier.with_phrase(phrase, *reason);
+        }
+        classifier
+    }
+
+    /// Also read `code` as `reason`
+    pub fn with_code(mut self, code: &str, reason: RejectionReason) -> Self {
+        self.codes.insert(code.to_string(), reason);
+        self
+    }
+
+    /// Also read messages containing `phrase`, in any case, as `reason`
+    pub fn with_phrase(mut self, phrase: &str, reason: RejectionReason) -> Self {
+        self.phrases.push((phrase.to_lowercase(), reason));
+        self
+    }
+
+    /// The reason behind `error`, if it is the platform refusing the order rather
+    /// than failing to take it
+    pub fn classify(&self, error: &PlatformError) -> Option<RejectionReason> {
+        match error {
+            PlatformError::InsufficientMargin { .. } | PlatformError::InsufficientFunds { .. } => {
+                Some(RejectionReason::InsufficientMargin)
+            }
+            PlatformError::MarketClosed { .. } => Some(RejectionReason::MarketClosed),
+            PlatformError::OrderValidationFailed { violations } => {
+                Some(Self::classify_violations(violations))
+            }
+            PlatformError::OrderRejected {
+                reason,
+                platform_code,
+            } => Some(
+                platform_code
+                    .as_ref()
+                    .and_then(|code| self.codes.get(code.trim()).copied())
+                    .unwrap_or_else(|| self.classify_message(reason)),
+            ),
+            PlatformError::TradingNotAllowed { reason } => Some(self.classify_message(reason)),
+            PlatformError::TradeLocker { error }
+            | PlatformError::DXTrade { error }
+            | PlatformError::MetaTrader { error } => match self.classify_message(error) {
+                RejectionReason::Other => None,
+                reason => Some(reason),
+            },
+            _ => None,
+        }
+    }
+
+    fn classify_message(&self, message: &str) -> RejectionReason {
+        let message = message.to_lowercase();
+        self.phrases
+            .iter()
+            .find(|(phrase, _)| message.contains(phrase.as_str()))
+            .map(|(_, reason)| *reason)
+            .unwrap_or(RejectionReason::Other)
+    }
+
+    fn classify_violations(violations: &[ValidationError]) -> RejectionReason {
+        violations
+            .iter()
+            .find_map(|violation| match violation {
+                ValidationError::InvalidQuantity { .. }
+                | ValidationError::OrderTooSmall { .. }
+                | ValidationError::OrderTooLarge { .. }
+                | ValidationError::UntradableQuantity { .. } => Some(RejectionReason::QuantityRule),
+                ValidationError::MarketClosed { .. } => Some(RejectionReason::MarketClosed),
+                _ => None,
+            })
+            .unwrap_or(RejectionReason::Other)
+    }
+}
+
+/// What to do with an order rejected for a given reason
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum Remediation {
+    /// Give the order up
+    None,
+    /// Resend at `factor` of the rejected quantity, snapped to the lot rules
+    Resize { factor: f64 },
+    /// Move the stop-loss at least `min_distance_pips` from the market and resend
+    WidenStop { min_distance_pips: f64 },
+    /// Hold the order and send it once the market opens, for up to `max_wait_secs`
+    DeferToOpen { max_wait_secs: u64 },
+    /// Resend unchanged after `delay_ms`, to fill at the new price
+    Resend { delay_ms: u64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RejectionRemediationConfig {
+    pub enabled: bool,
+    /// Resends allowed per order, across all remediations
+    pub max_attempts: u32,
+    /// How often deferred orders are checked for their market opening
+    pub defer_check_interval_secs: u64,
+    /// Remediation per rejection reason; reasons not listed are given up
+    pub strategies: HashMap<RejectionReason, Remediation>,
+}
+
+impl Default for RejectionRemediationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_attempts: 2,
+            defer_check_interval_secs: 60,
+            strategies: HashMap::from([
+                (
+                    RejectionReason::InsufficientMargin,
+                    Remediation::Resize { factor: 0.5 },
+                ),
+                (
+                    RejectionReason::InvalidStopDistance,
+                    Remediation::WidenStop {
+                        min_distance_pips: 10.0,
+                    },
+                ),
+                (
+                    RejectionReason::MarketClosed,
+                    Remediation::DeferToOpen {
+                        max_wait_secs: 4 * 3600,
+                    },
+                ),
+                (
+                    RejectionReason::Requote,
+                    Remediation::Resend { delay_ms: 250 },
+                ),
+            ]),
+        }
+    }
+}
+
+impl RejectionRemediationConfig {
+    pub fn strategy(&self, reason: RejectionReason) -> &Remediation {
+        if !self.enabled {
+            return &Remediation::None;
+        }
+        self.strategies.get(&reason).unwrap_or(&Remediation::None)
+    }
+}
+
+/// How a rejected order was changed for resending
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdjustedOrder {
+    pub description: String,
+    /// Distance the stop moved away from the entry, adding to the risk per unit
+    pub stop_widened_by: Decimal,
+}
+
+/// Change `order` per `remediation` so it can be resent. Returns why it cannot be
+/// when the remediation does not apply or would leave the order as it was; only
+/// `Resend` resends it unchanged.
+/// Deferral is not an adjustment and is left to the caller.
+pub async fn adjust_order(
+    platform: &(dyn ITradingPlatform + Send + Sync),
+    order: &mut UnifiedOrder,
+    remediation: &Remediation,
+) -> Result<AdjustedOrder, String> {
+    match remediation {
+        Remediation::Resize { factor } => {
+            let factor = Decimal::from_f64(*factor)
+                .filter(|factor| *factor > Decimal::ZERO && *factor < Decimal::ONE)
+                .ok_or_else(|| format!("Resize factor {} must be between 0 and 1", factor))?;
+            let rejected = order.quantity;
+            order.quantity = rejected * factor;
+            platform
+                .capabilities()
+                .normalize_order_quantity(order)
+                .map_err(|e| {
+                    order.quantity = rejected;
+                    format!("No smaller size is tradable: {}", e)
+                })?;
+            if order.quantity >= rejected || order.quantity <= Decimal::ZERO {
+                order.quantity = rejected;
+                return Err(format!("{} cannot be made smaller", rejected));
+            }
+            Ok(AdjustedOrder {
+                description: format!("resized from {} to {}", rejected, order.quantity),
+                stop_widened_by: Decimal::ZERO,
+            })
+        }
+        Remediation::WidenStop { min_distance_pips } => {
+            let stop = order
+                .stop_loss
+                .ok_or_else(|| "Order has no stop-loss to widen".to_string())?;
+            let entry = match order.price {
+                Some(price) => price,
+                None => {
+                    let quote = platform
+                        .get_market_data(&order.symbol)
+                        .await
+                        .map_err(|e| format!("No quote to widen the stop from: {}", e))?;
+                    match order.side {
+                        UnifiedOrderSide::Buy => quote.ask,
+                        UnifiedOrderSide::Sell => quote.bid,
+                    }
+                }
+            };
+            let instrument = InstrumentMetadata::conventional(&order.symbol);
+            let distance = instrument
+                .pips_to_price(Decimal::from_f64(*min_distance_pips).unwrap_or(Decimal::ZERO));
+            let widened = instrument.round_price(match order.side {
+                UnifiedOrderSide::Buy => stop.min(entry - distance),
+                UnifiedOrderSide::Sell => stop.max(entry + distance),
+            });
+            if widened == stop {
+                return Err(format!(
+                    "Stop {} is already {} pips from {}",
+                    stop, min_distance_pips, entry
+                ));
+            }
+            order.stop_loss = Some(widened);
+            Ok(AdjustedOrder {
+                description: format!("stop-loss widened from {} to {}", stop, widened),
+                stop_widened_by: (widened - stop).abs(),
+            })
+        }
+        Remediation::Resend { delay_ms } => {
+            tokio::time::sleep(std::time::Duration::from_millis(*delay_ms)).await;
+            Ok(AdjustedOrder {
+                description: "resent at the new price".to_string(),
+                stop_widened_by: Decimal::ZERO,
+            })
+        }
+        Remediation::DeferToOpen { .. } | Remediation::None => {
+            Err("Remediation does not change the order".to_string())
+        }
+    }
+}
@@ -1,461 +1,552 @@
-use std::sync::Arc;
 use async_trait::async_trait;
-use tokio::sync::mpsc;
-
-use super::{
-    ITradingPlatform, PlatformError, CircuitBreaker, ConnectionPool, 
-    UnifiedOrder, UnifiedOrderResponse, UnifiedPosition, UnifiedAccountInfo,
-    UnifiedMarketData, OrderModification, PlatformEvent, MarginInfo,
-    PlatformConfig, ConnectionPoolConfig, CircuitBreakerConfig,
-    ConnectionPoolStats, CircuitBreakerStats, CircuitBreakerState
-};
-use super::interfaces::{OrderFilter, HealthStatus, DiagnosticsInfo};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tracing::{info, warn};
+
 use super::capabilities::PlatformCapabilities;
-use super::interfaces::EventFilter;
+use super::circuit_breaker::{
+    current_emergency, CircuitBreakerConfig, CircuitBreakerState, OperationBreakers, OperationClass,
+};
+use super::decorator::PlatformDecorator;
+use super::errors::PlatformError;
+use super::events::PlatformEvent;
+use super::interfaces::{
+    DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter,
+};
+use super::models::*;
+use super::performance::PerformanceMonitor;
+use super::retry::{RetryBudget, RetryConfig, RetryHandler};
 use crate::platforms::PlatformType;
 
-/// Resilient adapter that combines circuit breaker and connection pooling
-/// for high-frequency trading scenarios with fault tolerance
-pub struct ResilientPlatformAdapter {
-    account_id: String,
-    platform_type: PlatformType,
-    connection_pool: Arc<ConnectionPool>,
-    circuit_breaker: CircuitBreaker,
-    capabilities: PlatformCapabilities,
+lazy_static! {
+    pub static ref CIRCUIT_BREAKER_OVERRIDES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "platform_circuit_breaker_overrides_total",
+        "Emergency platform calls let through a circuit breaker that was not closed",
+        &["class", "operation"]
+    )
+    .unwrap();
+    pub static ref CIRCUIT_BREAKER_PROBES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "platform_circuit_breaker_probes_total",
+        "Canary calls made to test a half-open circuit breaker, by outcome",
+        &["class", "outcome"]
+    )
+    .unwrap();
 }
 
-impl ResilientPlatformAdapter {
-    /// Create a new resilient adapter with default configurations
-    pub async fn new(
-        account_id: String,
-        platform_config: PlatformConfig,
-    ) -> Result<Self, PlatformError> {
-        Self::with_configs(
-            account_id,
-            platform_config,
-            ConnectionPoolConfig::default(),
-            CircuitBreakerConfig::default(),
-        ).await
-    }
-
-    /// Create a new resilient adapter with custom configurations
-    pub async fn with_configs(
-        account_id: String,
-        platform_config: PlatformConfig,
-        pool_config: ConnectionPoolConfig,
-        circuit_config: CircuitBreakerConfig,
-    ) -> Result<Self, PlatformError> {
-        let platform_type = platform_config.platform_type();
-        
-        // Create connection pool
-        let connection_pool = ConnectionPool::with_config(platform_config, pool_config).await?;
-        let connection_pool = Arc::new(connection_pool);
-        
-        // Create circuit breaker
-        let circuit_breaker = CircuitBreaker::with_config(circuit_config);
-        
-        // Get capabilities from a test connection
-        let test_handle = connection_pool.get_connection().await?;
-        let capabilities = test_handle.platform().capabilities();
-        drop(test_handle);
-
-        Ok(Self {
-            account_id,
-            platform_type,
-            connection_pool,
-            circuit_breaker,
-            capabilities,
-        })
+/// Resilience policy of one account's platform calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResilienceConfig {
+    /// Off, calls go straight to the adapter
+    pub enabled: bool,
+    /// How long a single attempt may take before it fails as a timeout
+    pub timeout_ms: u64,
+    pub retry: RetryConfig,
+    /// Retry orders, modifications, cancels and closes too. Off, only reads are
+    /// retried, as a write that timed out may still have gone through.
+    pub retry_writes: bool,
+    /// Thresholds of the breaker of each operation class
+    pub breaker: BreakerSettings,
+    /// Thresholds replacing `breaker` for some operation classes
+    pub class_breakers: HashMap<OperationClass, BreakerSettings>,
+}
+
+impl Default for ResilienceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout_ms: 10_000,
+            retry: RetryConfig::default(),
+            retry_writes: false,
+            breaker: BreakerSettings::default(),
+            class_breakers: HashMap::new(),
+        }
     }
+}
 
-    /// Execute an operation with both circuit breaker and connection pooling
-    async fn execute_with_resilience<T, F, Fut>(&self, operation: F) -> Result<T, PlatformError>
-    where
-        F: FnOnce(&dyn ITradingPlatform) -> Fut,
-        Fut: std::future::Future<Output = Result<T, PlatformError>>,
-    {
-        self.circuit_breaker.execute(|| async {
-            let connection_handle = self.connection_pool.get_connection().await?;
-            let platform = connection_handle.platform();
-            operation(platform).await
-        }).await
+/// Circuit breaker thresholds in config units
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BreakerSettings {
+    /// Failures within the window that open the breaker
+    pub failure_threshold: u32,
+    pub failure_window_secs: u64,
+    /// How long the breaker stays open before letting trial calls through
+    pub open_timeout_secs: u64,
+    /// Trial calls that must succeed to close the breaker again
+    pub success_threshold: u32,
+    pub half_open_max_operations: u32,
+}
+
+impl Default for BreakerSettings {
+    fn default() -> Self {
+        let breaker = CircuitBreakerConfig::default();
+        Self {
+            failure_threshold: breaker.failure_threshold,
+            failure_window_secs: breaker.failure_window.as_secs(),
+            open_timeout_secs: breaker.open_timeout.as_secs(),
+            success_threshold: breaker.success_threshold,
+            half_open_max_operations: breaker.half_open_max_operations,
+        }
     }
+}
 
-    /// Get connection pool statistics
-    pub async fn get_pool_stats(&self) -> ConnectionPoolStats {
-        self.connection_pool.get_stats().await
+impl BreakerSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.failure_threshold == 0 || self.success_threshold == 0 {
+            return Err("Resilience breaker thresholds must be above 0".to_string());
+        }
+        if self.half_open_max_operations < self.success_threshold {
+            return Err(
+                "Resilience half_open_max_operations must allow success_threshold trial calls"
+                    .to_string(),
+            );
+        }
+        Ok(())
     }
+}
 
-    /// Get circuit breaker statistics
-    pub fn get_circuit_breaker_stats(&self) -> CircuitBreakerStats {
-        self.circuit_breaker.get_stats()
+impl From<&BreakerSettings> for CircuitBreakerConfig {
+    fn from(settings: &BreakerSettings) -> Self {
+        CircuitBreakerConfig {
+            failure_threshold: settings.failure_threshold,
+            success_threshold: settings.success_threshold,
+            failure_window: Duration::from_secs(settings.failure_window_secs),
+            open_timeout: Duration::from_secs(settings.open_timeout_secs),
+            half_open_max_operations: settings.half_open_max_operations,
+        }
     }
+}
 
-    /// Check if the adapter is in a healthy state
-    pub async fn is_adapter_healthy(&self) -> bool {
-        self.connection_pool.is_healthy().await && self.circuit_breaker.is_healthy()
+impl ResilienceConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timeout_ms == 0 {
+            return Err("Resilience timeout_ms must be above 0".to_string());
+        }
+        self.breaker.validate()?;
+        for (class, breaker) in &self.class_breakers {
+            breaker
+                .validate()
+                .map_err(|e| format!("{} for {:?}", e, class))?;
+        }
+        if self.retry.backoff_multiplier < 1.0 {
+            return Err("Resilience retry backoff_multiplier must be at least 1".to_string());
+        }
+        Ok(())
     }
 
-    /// Warm up the connection pool
-    pub async fn warm_up(&self) -> Result<(), PlatformError> {
-        self.connection_pool.warm_up().await
+    /// A breaker per operation class, with the class overrides applied
+    pub fn breakers(&self) -> OperationBreakers {
+        self.class_breakers.iter().fold(
+            OperationBreakers::new((&self.breaker).into()),
+            |breakers, (class, settings)| breakers.with_config(*class, settings.into()),
+        )
     }
+}
 
-    /// Reset the circuit breaker (for recovery scenarios)
-    pub fn reset_circuit_breaker(&self) {
-        self.circuit_breaker.reset();
+/// Platform wrapper applying one account's resilience policy to every call: each
+/// attempt is timed out, timed in the performance monitor and counted by the
+/// circuit breaker of its operation class, and failed attempts are retried while
+/// that breaker lets them through. Adapters themselves then only translate calls.
+///
+/// A half-open breaker lets reads through as trial calls, but holds writes back
+/// until canary calls have closed it, so the first call after an outage is never
+/// an order. Calls made under [`as_emergency`](super::circuit_breaker::as_emergency)
+/// skip the breaker altogether, and each time they do it is logged.
+pub struct ResilientPlatform<P: ?Sized = dyn ITradingPlatform + Send + Sync> {
+    inner: Arc<P>,
+    config: ResilienceConfig,
+    retry: RetryHandler,
+    breakers: OperationBreakers,
+    monitor: Option<PerformanceMonitor>,
+    /// Held while canary calls run, so concurrent writes wait for one probe
+    probing: Mutex<()>,
+}
+
+impl<P: ITradingPlatform + Send + Sync + ?Sized> ResilientPlatform<P> {
+    pub fn new(inner: Arc<P>, config: ResilienceConfig) -> Self {
+        Self {
+            inner,
+            retry: RetryHandler::new(config.retry.clone()),
+            breakers: config.breakers(),
+            monitor: None,
+            probing: Mutex::new(()),
+            config,
+        }
     }
 
-    /// Force circuit breaker open (for emergency scenarios)
-    pub fn emergency_stop(&self) {
-        self.circuit_breaker.force_open();
+    /// Time every attempt in `monitor`
+    pub fn with_monitor(mut self, monitor: PerformanceMonitor) -> Self {
+        self.monitor = Some(monitor);
+        self
     }
 
-    /// Get comprehensive health and performance metrics
-    pub async fn get_comprehensive_diagnostics(&self) -> ResilientAdapterDiagnostics {
-        let pool_stats = self.get_pool_stats().await;
-        let circuit_stats = self.get_circuit_breaker_stats();
-        let is_healthy = self.is_adapter_healthy().await;
+    /// Share a retry budget with the other accounts on the same platform
+    pub fn with_retry_budget(mut self, budget: Arc<RetryBudget>) -> Self {
+        self.retry = RetryHandler::new(self.config.retry.clone()).with_budget(budget);
+        self
+    }
 
-        ResilientAdapterDiagnostics {
-            account_id: self.account_id.clone(),
-            platform_type: self.platform_type.clone(),
-            is_healthy,
-            pool_stats,
-            circuit_stats,
-            timestamp: chrono::Utc::now(),
-        }
+    pub fn config(&self) -> &ResilienceConfig {
+        &self.config
     }
-}
 
-#[async_trait]
-impl ITradingPlatform for ResilientPlatformAdapter {
-    fn platform_type(&self) -> PlatformType {
-        self.platform_type.clone()
+    pub fn breakers(&self) -> &OperationBreakers {
+        &self.breakers
     }
 
-    fn platform_name(&self) -> &str {
-        "ResilientAdapter"
+    pub fn monitor(&self) -> Option<&PerformanceMonitor> {
+        self.monitor.as_ref()
     }
 
-    fn platform_version(&self) -> &str {
-        "2.0.0"
+    /// One attempt: timed out and timed, without the breaker
+    async fn attempt<T, Fut>(&self, operation: &str, request: Fut) -> Result<T, PlatformError>
+    where
+        Fut: Future<Output = Result<T, PlatformError>>,
+    {
+        let timer = self.monitor.as_ref().map(|m| m.start_operation(operation));
+        let result = match tokio::time::timeout(
+            Duration::from_millis(self.config.timeout_ms),
+            request,
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(_) => Err(PlatformError::RequestTimeout {
+                timeout_ms: self.config.timeout_ms,
+            }),
+        };
+        if let Some(timer) = timer {
+            match &result {
+                Ok(_) => timer.success(),
+                Err(e) => timer.error(e),
+            }
+        }
+        result
     }
 
-    async fn connect(&mut self) -> Result<(), PlatformError> {
-        // Connection management is handled by the pool
-        // This is a no-op for the resilient adapter
-        Ok(())
+    async fn call<T, F, Fut>(
+        &self,
+        operation: &str,
+        write: bool,
+        request: F,
+    ) -> Result<T, PlatformError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, PlatformError>>,
+    {
+        let class = OperationClass::of(operation);
+        let breaker = self.breakers.breaker(class);
+        let bypass = self.overrides_breaker(class, operation);
+        if write && !bypass && breaker.poll_state() == CircuitBreakerState::HalfOpen {
+            self.probe(class, operation).await?;
+        }
+
+        let guarded = || async {
+            if bypass {
+                self.attempt(operation, request()).await
+            } else {
+                breaker.execute(|| self.attempt(operation, request())).await
+            }
+        };
+        if write && !self.config.retry_writes {
+            guarded().await
+        } else {
+            // An open breaker fails as an internal error, which is not retried
+            self.retry.execute_with_retry(guarded).await
+        }
     }
 
-    async fn disconnect(&mut self) -> Result<(), PlatformError> {
-        // Graceful shutdown of pool would happen here
-        Ok(())
+    /// Whether the current task is an emergency that `class`'s breaker would
+    /// otherwise slow down or stop
+    fn overrides_breaker(&self, class: OperationClass, operation: &str) -> bool {
+        let Some(reason) = current_emergency() else {
+            return false;
+        };
+        let state = self.breakers.breaker(class).poll_state();
+        if state == CircuitBreakerState::Closed {
+            return false;
+        }
+        warn!(
+            "Overriding the {:?} {:?} circuit breaker of {} for {} in an emergency: {}",
+            state,
+            class,
+            self.inner.platform_name(),
+            operation,
+            reason
+        );
+        CIRCUIT_BREAKER_OVERRIDES_TOTAL
+            .with_label_values(&[&format!("{:?}", class), operation])
+            .inc();
+        true
+    }
+
+    /// Try to close `class`'s half-open breaker with canary calls before
+    /// `operation` is let through. Fails at the first canary that fails, which opens
+    /// the breaker again, or when the canaries allowed while half-open run out.
+    async fn probe(&self, class: OperationClass, operation: &str) -> Result<(), PlatformError> {
+        let _probing = self.probing.lock().await;
+        let breaker = self.breakers.breaker(class);
+        let mut last_error = None;
+        for _ in 0..breaker.config().half_open_max_operations {
+            // Closed by another caller's probe while this one waited, or opened again
+            if breaker.poll_state() != CircuitBreakerState::HalfOpen {
+                break;
+            }
+            let result = self.canary(class).await;
+            CIRCUIT_BREAKER_PROBES_TOTAL
+                .with_label_values(&[
+                    &format!("{:?}", class),
+                    if result.is_ok() { "ok" } else { "error" },
+                ])
+                .inc();
+            if let Err(e) = result {
+                last_error = Some(e);
+                break;
+            }
+        }
+
+        match breaker.poll_state() {
+            CircuitBreakerState::Closed => {
+                info!(
+                    "{:?} circuit breaker of {} closed by canary calls",
+                    class,
+                    self.inner.platform_name()
+                );
+                Ok(())
+            }
+            state => Err(PlatformError::InternalError {
+                reason: format!(
+                    "{:?} circuit breaker is {:?}; {} held back until canary calls succeed{}",
+                    class,
+                    state,
+                    operation,
+                    last_error
+                        .map(|e| format!(" (last canary failed: {})", e))
+                        .unwrap_or_default()
+                ),
+            }),
+        }
     }
 
-    async fn is_connected(&self) -> bool {
-        self.connection_pool.is_healthy().await
+    /// The cheapest read on the endpoints of `class`, through its breaker
+    async fn canary(&self, class: OperationClass) -> Result<(), PlatformError> {
+        let breaker = self.breakers.breaker(class);
+        match class {
+            OperationClass::Orders => breaker
+                .execute(|| self.attempt("get_orders", self.inner.get_orders(None)))
+                .await
+                .map(drop),
+            OperationClass::MarketData => breaker
+                .execute(|| self.attempt("ping", self.inner.ping()))
+                .await
+                .map(drop),
+            OperationClass::Account => breaker
+                .execute(|| self.attempt("get_account_info", self.inner.get_account_info()))
+                .await
+                .map(drop),
+        }
+    }
+}
+
+#[async_trait]
+impl<P: ITradingPlatform + Send + Sync + ?Sized> PlatformDecorator for ResilientPlatform<P> {
+    type Inner = P;
+
+    fn inner(&self) -> &P {
+        &self.inner
     }
 
     async fn ping(&self) -> Result<u64, PlatformError> {
-        self.execute_with_resilience(|platform| async move {
-            platform.ping().await
-        }).await
+        self.call("ping", false, || self.inner.ping()).await
     }
 
-    async fn place_order(&self, order: UnifiedOrder) -> Result<UnifiedOrderResponse, PlatformError> {
-        self.execute_with_resilience(|platform| async move {
-            platform.place_order(order).await
-        }).await
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call("place_order", true, || {
+            self.inner.place_order(order.clone())
+        })
+        .await
     }
 
-    async fn modify_order(&self, order_id: &str, modifications: OrderModification) -> Result<UnifiedOrderResponse, PlatformError> {
-        let order_id = order_id.to_string();
-        self.execute_with_resilience(|platform| async move {
-            platform.modify_order(&order_id, modifications).await
-        }).await
+    async fn modify_order(
+        &self,
+        order_id: &str,
+        modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call("modify_order", true, || {
+            self.inner.modify_order(order_id, modifications.clone())
+        })
+        .await
     }
 
     async fn cancel_order(&self, order_id: &str) -> Result<(), PlatformError> {
-        let order_id = order_id.to_string();
-        self.execute_with_resilience(|platform| async move {
-            platform.cancel_order(&order_id).await
-        }).await
+        self.call("cancel_order", true, || self.inner.cancel_order(order_id))
+            .await
     }
 
     async fn get_order(&self, order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
-        let order_id = order_id.to_string();
-        self.execute_with_resilience(|platform| async move {
-            platform.get_order(&order_id).await
-        }).await
+        self.call("get_order", false, || self.inner.get_order(order_id))
+            .await
     }
 
-    async fn get_orders(&self, filter: Option<OrderFilter>) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
-        self.execute_with_resilience(|platform| async move {
-            platform.get_orders(filter).await
-        }).await
+    async fn get_orders(
+        &self,
+        filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        self.call("get_orders", false, || {
+            self.inner.get_orders(filter.clone())
+        })
+        .await
     }
 
     async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
-        self.execute_with_resilience(|platform| async move {
-            platform.get_positions().await
-        }).await
+        self.call("get_positions", false, || self.inner.get_positions())
+            .await
     }
 
     async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
-        let symbol = symbol.to_string();
-        self.execute_with_resilience(|platform| async move {
-            platform.get_position(&symbol).await
-        }).await
+        self.call("get_position", false, || self.inner.get_position(symbol))
+            .await
     }
 
-    async fn close_position(&self, symbol: &str, quantity: Option<rust_decimal::Decimal>) -> Result<UnifiedOrderResponse, PlatformError> {
-        let symbol = symbol.to_string();
-        self.execute_with_resilience(|platform| async move {
-            platform.close_position(&symbol, quantity).await
-        }).await
+    async fn close_position(
+        &self,
+        symbol: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call("close_position", true, || {
+            self.inner.close_position(symbol, quantity)
+        })
+        .await
     }
 
-    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
-        self.execute_with_resilience(|platform| async move {
-            platform.get_account_info().await
-        }).await
+    async fn get_position_tickets(&self, symbol: &str) -> Result<PositionTickets, PlatformError> {
+        self.call("get_position_tickets", false, || {
+            self.inner.get_position_tickets(symbol)
+        })
+        .await
     }
 
-    async fn get_balance(&self) -> Result<rust_decimal::Decimal, PlatformError> {
-        self.execute_with_resilience(|platform| async move {
-            platform.get_balance().await
-        }).await
+    async fn close_position_ticket(
+        &self,
+        position_id: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.call("close_position_ticket", true, || {
+            self.inner.close_position_ticket(position_id, quantity)
+        })
+        .await
     }
 
-    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
-        self.execute_with_resilience(|platform| async move {
-            platform.get_margin_info().await
-        }).await
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        self.call("get_account_info", false, || self.inner.get_account_info())
+            .await
     }
 
-    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
-        let symbol = symbol.to_string();
-        self.execute_with_resilience(|platform| async move {
-            platform.get_market_data(&symbol).await
-        }).await
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        self.call("get_balance", false, || self.inner.get_balance())
+            .await
     }
 
-    async fn subscribe_market_data(&self, symbols: Vec<String>) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
-        self.execute_with_resilience(|platform| async move {
-            platform.subscribe_market_data(symbols).await
-        }).await
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        self.call("get_margin_info", false, || self.inner.get_margin_info())
+            .await
     }
 
-    async fn unsubscribe_market_data(&self, symbols: Vec<String>) -> Result<(), PlatformError> {
-        self.execute_with_resilience(|platform| async move {
-            platform.unsubscribe_market_data(symbols).await
-        }).await
+    async fn get_transactions(
+        &self,
+        range: TransactionRange,
+    ) -> Result<Vec<UnifiedTransaction>, PlatformError> {
+        self.call("get_transactions", false, || {
+            self.inner.get_transactions(range)
+        })
+        .await
     }
 
-    fn capabilities(&self) -> PlatformCapabilities {
-        self.capabilities.clone()
+    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        self.call("get_market_data", false, || {
+            self.inner.get_market_data(symbol)
+        })
+        .await
     }
 
-    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
-        self.execute_with_resilience(|platform| async move {
-            platform.subscribe_events().await
-        }).await
+    async fn get_instruments(&self) -> Result<Vec<Symbol>, PlatformError> {
+        self.call("get_instruments", false, || self.inner.get_instruments())
+            .await
     }
 
-    async fn get_event_history(&self, filter: EventFilter) -> Result<Vec<PlatformEvent>, PlatformError> {
-        self.execute_with_resilience(|platform| async move {
-            platform.get_event_history(filter).await
-        }).await
+    async fn get_order_book(
+        &self,
+        symbol: &str,
+        levels: usize,
+    ) -> Result<UnifiedOrderBook, PlatformError> {
+        self.call("get_order_book", false, || {
+            self.inner.get_order_book(symbol, levels)
+        })
+        .await
     }
 
-    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
-        // Combine platform health with adapter health
-        let platform_health = self.execute_with_resilience(|platform| async move {
-            platform.health_check().await
-        }).await?;
-
-        let adapter_healthy = self.is_adapter_healthy().await;
-        let circuit_stats = self.circuit_breaker.get_stats();
-        let pool_stats = self.connection_pool.get_stats().await;
-
-        let mut issues = platform_health.issues.clone();
-        
-        if !adapter_healthy {
-            issues.push("Adapter not healthy".to_string());
-        }
-        
-        if circuit_stats.state != super::CircuitBreakerState::Closed {
-            issues.push(format!("Circuit breaker is {:?}", circuit_stats.state));
-        }
-        
-        if pool_stats.unhealthy_connections > 0 {
-            issues.push(format!("{} unhealthy connections in pool", pool_stats.unhealthy_connections));
-        }
-
-        Ok(HealthStatus {
-            is_healthy: platform_health.is_healthy && adapter_healthy,
-            last_ping: platform_health.last_ping,
-            latency_ms: platform_health.latency_ms,
-            error_rate: circuit_stats.failure_count as f64 / (circuit_stats.total_operations.max(1) as f64),
-            uptime_seconds: platform_health.uptime_seconds,
-            issues,
-        })
+    async fn get_server_time(&self) -> Result<chrono::DateTime<chrono::Utc>, PlatformError> {
+        self.call("get_server_time", false, || self.inner.get_server_time())
+            .await
     }
 
-    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
-        let platform_diagnostics = self.execute_with_resilience(|platform| async move {
-            platform.get_diagnostics().await
-        }).await?;
-
-        let pool_stats = self.get_pool_stats().await;
-        let circuit_stats = self.get_circuit_breaker_stats();
-
-        let mut performance_metrics = platform_diagnostics.performance_metrics;
-        performance_metrics.insert("pool_total_connections".to_string(), serde_json::Value::Number((pool_stats.total_connections as u64).into()));
-        performance_metrics.insert("pool_active_connections".to_string(), serde_json::Value::Number((pool_stats.active_connections as u64).into()));
-        performance_metrics.insert("pool_hit_rate".to_string(), serde_json::Value::Number(
-            serde_json::Number::from_f64(
-                pool_stats.pool_hits as f64 / (pool_stats.pool_hits + pool_stats.pool_misses).max(1) as f64
-            ).unwrap_or(serde_json::Number::from(0))
-        ));
-        performance_metrics.insert("circuit_breaker_state".to_string(), serde_json::Value::String(format!("{:?}", circuit_stats.state)));
-        performance_metrics.insert("circuit_breaker_failure_count".to_string(), serde_json::Value::Number(circuit_stats.failure_count.into()));
-
-        Ok(DiagnosticsInfo {
-            connection_status: if self.is_adapter_healthy().await { "Healthy".to_string() } else { "Degraded".to_string() },
-            api_limits: platform_diagnostics.api_limits,
-            performance_metrics,
-            last_errors: platform_diagnostics.last_errors,
-            platform_specific: platform_diagnostics.platform_specific,
+    async fn get_event_history(
+        &self,
+        filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        self.call("get_event_history", false, || {
+            self.inner.get_event_history(filter.clone())
         })
+        .await
     }
-}
-
-/// Comprehensive diagnostics for the resilient adapter
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct ResilientAdapterDiagnostics {
-    pub account_id: String,
-    pub platform_type: PlatformType,
-    pub is_healthy: bool,
-    pub pool_stats: ConnectionPoolStats,
-    pub circuit_stats: CircuitBreakerStats,
-    pub timestamp: chrono::DateTime<chrono::Utc>,
-}
 
-impl ResilientAdapterDiagnostics {
-    /// Calculate overall performance score (0-100)
-    pub fn performance_score(&self) -> f64 {
-        let mut score = 100.0;
-
-        // Reduce score based on circuit breaker failures
-        if self.circuit_stats.total_operations > 0 {
-            let failure_rate = self.circuit_stats.failure_count as f64 / self.circuit_stats.total_operations as f64;
-            score -= failure_rate * 50.0; // Max 50 point reduction for failures
+    /// Checked past the breakers, so an open breaker does not hide how the
+    /// platform is doing. Each tripped breaker is an issue, and any open one makes
+    /// the platform unhealthy.
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        let mut health = self
+            .attempt("health_check", self.inner.health_check())
+            .await?;
+        for (class, state) in self.breakers.tripped() {
+            health
+                .issues
+                .push(format!("{:?} circuit breaker is {:?}", class, state));
         }
-
-        // Reduce score for circuit breaker being open
-        match self.circuit_stats.state {
-            CircuitBreakerState::Closed => {}, // No reduction
-            CircuitBreakerState::HalfOpen => score -= 10.0,
-            CircuitBreakerState::Open => score -= 30.0,
+        if self.breakers.combined_state() == CircuitBreakerState::Open {
+            health.is_healthy = false;
         }
-
-        // Reduce score for unhealthy connections
-        if self.pool_stats.total_connections > 0 {
-            let unhealthy_ratio = self.pool_stats.unhealthy_connections as f64 / self.pool_stats.total_connections as f64;
-            score -= unhealthy_ratio * 20.0; // Max 20 point reduction for unhealthy connections
-        }
-
-        // Bonus for good pool hit rate
-        if self.pool_stats.pool_hits + self.pool_stats.pool_misses > 0 {
-            let hit_rate = self.pool_stats.pool_hits as f64 / (self.pool_stats.pool_hits + self.pool_stats.pool_misses) as f64;
-            if hit_rate > 0.9 {
-                score += 5.0; // Bonus for high hit rate
-            }
-        }
-
-        score.max(0.0).min(100.0)
+        Ok(health)
     }
 
-    /// Get a human-readable status summary
-    pub fn status_summary(&self) -> String {
-        if !self.is_healthy {
-            return "UNHEALTHY".to_string();
+    /// The wrapped platform's diagnostics, with the breakers' states and, when
+    /// calls are timed, their latency, SLA and error figures
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        let mut diagnostics = self.inner.get_diagnostics().await?;
+        diagnostics.performance_metrics.insert(
+            "circuit_breaker".to_string(),
+            serde_json::json!(self.breakers.combined_state()),
+        );
+        diagnostics.performance_metrics.insert(
+            "circuit_breakers".to_string(),
+            serde_json::json!(self.breakers.stats()),
+        );
+        if let Some(monitor) = &self.monitor {
+            diagnostics
+                .performance_metrics
+                .extend(monitor.diagnostics());
+            diagnostics.last_errors.extend(monitor.recent_errors());
         }
-
-        match self.circuit_stats.state {
-            CircuitBreakerState::Closed => {
-                if self.pool_stats.unhealthy_connections == 0 {
-                    "HEALTHY"
-                } else {
-                    "DEGRADED"
-                }
-            }
-            CircuitBreakerState::HalfOpen => "RECOVERING",
-            CircuitBreakerState::Open => "CIRCUIT_OPEN",
-        }.to_string()
+        Ok(diagnostics)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_resilient_adapter_diagnostics() {
-        let diagnostics = ResilientAdapterDiagnostics {
-            account_id: "test_account".to_string(),
-            platform_type: PlatformType::TradeLocker,
-            is_healthy: true,
-            pool_stats: ConnectionPoolStats::default(),
-            circuit_stats: CircuitBreakerStats {
-                state: CircuitBreakerState::Closed,
-                failure_count: 0,
-                success_count: 10,
-                total_operations: 10,
-                last_failure_time: None,
-                last_state_change: chrono::Utc::now(),
-                current_failure_window_count: 0,
-            },
-            timestamp: chrono::Utc::now(),
-        };
-
-        assert_eq!(diagnostics.status_summary(), "HEALTHY");
-        assert!(diagnostics.performance_score() >= 95.0);
-    }
-
-    #[test]
-    fn test_performance_score_calculation() {
-        let mut diagnostics = ResilientAdapterDiagnostics {
-            account_id: "test".to_string(),
-            platform_type: PlatformType::TradeLocker,
-            is_healthy: false,
-            pool_stats: ConnectionPoolStats {
-                total_connections: 10,
-                unhealthy_connections: 2,
-                ..Default::default()
-            },
-            circuit_stats: CircuitBreakerStats {
-                state: CircuitBreakerState::Open,
-                failure_count: 5,
-                total_operations: 100,
-                ..Default::default()
-            },
-            timestamp: chrono::Utc::now(),
-        };
-
-        let score = diagnostics.performance_score();
-        assert!(score < 100.0); // Should be reduced due to issues
-        assert!(score > 0.0);   // But not zero
-
-        // Test healthy scenario
-        diagnostics.is_healthy = true;
-        diagnostics.circuit_stats.state = CircuitBreakerState::Closed;
-        diagnostics.circuit_stats.failure_count = 0;
-        diagnostics.pool_stats.unhealthy_connections = 0;
-        
-        let healthy_score = diagnostics.performance_score();
-        assert!(healthy_score > score); // Should be better
-    }
-}
\ No newline at end of file
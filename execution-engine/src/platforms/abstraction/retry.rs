@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::warn;
+
+use super::errors::{ErrorClass, PlatformError};
+
+/// How the delay between retries grows
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum BackoffStrategy {
+    /// `initial * multiplier^n`, with up to 10% added jitter when enabled
+    #[default]
+    Exponential,
+    /// Uniformly random between zero and the exponential delay
+    FullJitter,
+    /// Uniformly random between the initial delay and three times the previous delay
+    DecorrelatedJitter,
+    /// Always the initial delay
+    Fixed,
+    /// `initial * fib(n)`
+    Fibonacci,
+}
+
+/// Per-error-class replacement for the defaults in `RetryConfig`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetryOverride {
+    pub max_retries: Option<u32>,
+    pub initial_delay_ms: Option<u64>,
+    pub strategy: Option<BackoffStrategy>,
+}
+
+/// Retry configuration for platform operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub backoff_multiplier: f64,
+    pub jitter: bool,
+    #[serde(default)]
+    pub strategy: BackoffStrategy,
+    #[serde(default)]
+    pub class_overrides: HashMap<ErrorClass, RetryOverride>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay_ms: 1000,
+            max_delay_ms: 30000,
+            backoff_multiplier: 2.0,
+            jitter: true,
+            strategy: BackoffStrategy::Exponential,
+            class_overrides: HashMap::new(),
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn with_strategy(mut self, strategy: BackoffStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn with_class_override(mut self, class: ErrorClass, retry_override: RetryOverride) -> Self {
+        self.class_overrides.insert(class, retry_override);
+        self
+    }
+
+    fn max_retries_for(&self, class: ErrorClass) -> u32 {
+        self.class_overrides
+            .get(&class)
+            .and_then(|o| o.max_retries)
+            .unwrap_or(self.max_retries)
+    }
+
+    fn initial_delay_for(&self, class: ErrorClass) -> u64 {
+        self.class_overrides
+            .get(&class)
+            .and_then(|o| o.initial_delay_ms)
+            .unwrap_or(self.initial_delay_ms)
+    }
+
+    fn strategy_for(&self, class: ErrorClass) -> BackoffStrategy {
+        self.class_overrides
+            .get(&class)
+            .and_then(|o| o.strategy)
+            .unwrap_or(self.strategy)
+    }
+
+    /// Delay before retry number `attempt` (1-based), before any error-imposed minimum
+    pub fn delay_for(&self, class: ErrorClass, attempt: u32, previous_delay_ms: u64) -> u64 {
+        use rand::Rng;
+
+        let initial = self.initial_delay_for(class);
+        let exponent = attempt.saturating_sub(1).min(63) as i32;
+        let exponential = (initial as f64 * self.backoff_multiplier.powi(exponent))
+            .min(self.max_delay_ms as f64) as u64;
+        let mut rng = rand::thread_rng();
+
+        let delay = match self.strategy_for(class) {
+            BackoffStrategy::Exponential => {
+                if self.jitter {
+                    let jitter_range = (exponential as f64 * 0.1) as u64;
+                    exponential + rng.gen_range(0..=jitter_range)
+                } else {
+                    exponential
+                }
+            }
+            BackoffStrategy::FullJitter => rng.gen_range(0..=exponential),
+            BackoffStrategy::DecorrelatedJitter => {
+                let upper = previous_delay_ms.max(initial).saturating_mul(3);
+                rng.gen_range(initial..=upper.max(initial))
+            }
+            BackoffStrategy::Fixed => initial,
+            BackoffStrategy::Fibonacci => initial.saturating_mul(fibonacci(attempt)),
+        };
+
+        delay.min(self.max_delay_ms)
+    }
+}
+
+fn fibonacci(n: u32) -> u64 {
+    let (mut a, mut b) = (1u64, 1u64);
+    for _ in 1..n {
+        (a, b) = (b, a.saturating_add(b));
+    }
+    a
+}
+
+/// Token bucket shared by every operation against one platform so that a burst of
+/// failures can't turn into a retry storm. Each retry costs a token, each success
+/// refunds a fraction of one, and retries stop once the bucket is half empty.
+#[derive(Debug)]
+pub struct RetryBudget {
+    max_tokens: f64,
+    token_ratio: f64,
+    tokens: Mutex<f64>,
+}
+
+impl RetryBudget {
+    pub fn new(max_tokens: u32, token_ratio: f64) -> Self {
+        Self {
+            max_tokens: max_tokens as f64,
+            token_ratio,
+            tokens: Mutex::new(max_tokens as f64),
+        }
+    }
+
+    pub fn try_acquire(&self) -> bool {
+        let mut tokens = self.tokens.lock().unwrap();
+        if *tokens <= self.max_tokens / 2.0 {
+            return false;
+        }
+        *tokens -= 1.0;
+        true
+    }
+
+    pub fn record_success(&self) {
+        let mut tokens = self.tokens.lock().unwrap();
+        *tokens = (*tokens + self.token_ratio).min(self.max_tokens);
+    }
+
+    pub fn available(&self) -> f64 {
+        *self.tokens.lock().unwrap()
+    }
+}
+
+impl Default for RetryBudget {
+    fn default() -> Self {
+        Self::new(100, 0.1)
+    }
+}
+
+/// Retry logic utility for platform operations
+pub struct RetryHandler {
+    config: RetryConfig,
+    budget: Option<Arc<RetryBudget>>,
+}
+
+impl RetryHandler {
+    pub fn new(config: RetryConfig) -> Self {
+        Self {
+            config,
+            budget: None,
+        }
+    }
+
+    /// Share a retry budget with other handlers for the same platform
+    pub fn with_budget(mut self, budget: Arc<RetryBudget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    pub async fn execute_with_retry<T, F, Fut>(&self, mut operation: F) -> Result<T, PlatformError>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, PlatformError>>,
+    {
+        let mut attempt = 0;
+        let mut previous_delay = 0;
+
+        loop {
+            match operation().await {
+                Ok(result) => {
+                    if let Some(budget) = &self.budget {
+                        budget.record_success();
+                    }
+                    return Ok(result);
+                }
+                Err(error) => {
+                    attempt += 1;
+
+                    let taxonomy = error.taxonomy();
+                    if !taxonomy.retryable || attempt > self.config.max_retries_for(taxonomy.class)
+                    {
+                        return Err(error);
+                    }
+
+                    if let Some(budget) = &self.budget {
+                        if !budget.try_acquire() {
+                            warn!("Retry budget exhausted, giving up on: {}", error);
+                            return Err(error);
+                        }
+                    }
+
+                    let delay = self
+                        .config
+                        .delay_for(taxonomy.class, attempt, previous_delay)
+                        // Never retry sooner than the error itself asks for (e.g. rate limit retry-after)
+                        .max(taxonomy.backoff_ms.unwrap_or(0));
+                    previous_delay = delay;
+
+                    sleep(Duration::from_millis(delay)).await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,470 @@
+use async_trait::async_trait;
+use dashmap::{DashMap, DashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use super::capabilities::PlatformCapabilities;
+use super::decorator::PlatformDecorator;
+use super::errors::PlatformError;
+use super::events::{EventData, PlatformEvent};
+use super::interfaces::{
+    DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter,
+};
+use super::models::*;
+use crate::platforms::PlatformType;
+
+/// How an account's symbols are mapped to the platform's names
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolMappingConfig {
+    /// Map the symbols in the platform's instrument list to unified symbols
+    #[serde(default = "default_auto_discover")]
+    pub auto_discover: bool,
+    /// Platform symbol by unified symbol, e.g. "EURUSD" -> "EURUSD.r". Takes
+    /// precedence over discovery.
+    #[serde(default)]
+    pub overrides: HashMap<String, String>,
+}
+
+fn default_auto_discover() -> bool {
+    true
+}
+
+impl Default for SymbolMappingConfig {
+    fn default() -> Self {
+        Self {
+            auto_discover: default_auto_discover(),
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+/// Unified form of a platform symbol: upper case, separators removed and a broker
+/// suffix after '.' or '#' dropped, so "EUR/USD", "eur_usd" and "EURUSD.r" all
+/// become "EURUSD"
+pub fn normalize_symbol(raw: &str) -> String {
+    let name = raw.split(['.', '#']).next().unwrap_or(raw);
+    name.chars()
+        .filter(|c| !matches!(c, '/' | '_' | '-' | ' '))
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// Unified symbol of a listed instrument. Currency pairs are named after their
+/// currencies, which also catches suffixes without a separator ("EURUSDm").
+pub fn unified_symbol(symbol: &Symbol) -> String {
+    let pair = matches!(
+        symbol.instrument_type,
+        InstrumentType::Forex | InstrumentType::Crypto
+    );
+    if pair && !symbol.base_currency.is_empty() && !symbol.quote_currency.is_empty() {
+        format!("{}{}", symbol.base_currency, symbol.quote_currency).to_uppercase()
+    } else {
+        normalize_symbol(&symbol.symbol)
+    }
+}
+
+/// Bidirectional map between unified symbols and the names one platform uses.
+/// Symbols without a mapping pass through unchanged.
+#[derive(Debug, Default)]
+pub struct SymbolMapper {
+    to_platform: DashMap<String, String>,
+    to_unified: DashMap<String, String>,
+    /// Unified symbols mapped by configuration, which discovery leaves alone
+    pinned: DashSet<String>,
+}
+
+impl SymbolMapper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_config(config: &SymbolMappingConfig) -> Self {
+        let mapper = Self::new();
+        for (unified, platform) in &config.overrides {
+            mapper.insert(unified, platform);
+            mapper.pinned.insert(unified.clone());
+        }
+        mapper
+    }
+
+    /// Map `unified` to `platform`, replacing earlier mappings of either name
+    pub fn insert(&self, unified: &str, platform: &str) {
+        if let Some((_, previous)) = self.to_platform.remove(unified) {
+            self.to_unified.remove(&previous);
+        }
+        if let Some((_, previous)) = self.to_unified.remove(platform) {
+            self.to_platform.remove(&previous);
+        }
+        self.to_platform
+            .insert(unified.to_string(), platform.to_string());
+        self.to_unified
+            .insert(platform.to_string(), unified.to_string());
+    }
+
+    /// Map every instrument in a platform's list. When several instruments share a
+    /// unified symbol (a broker listing "EURUSD" and "EURUSD.r"), an exact name wins,
+    /// then a tradeable instrument, then the shortest name. Returns the number of
+    /// symbols whose platform name differs from the unified one.
+    pub fn discover(&self, symbols: &[Symbol]) -> usize {
+        let mut best: HashMap<String, &Symbol> = HashMap::new();
+        for symbol in symbols {
+            let unified = unified_symbol(symbol);
+            let rank = |s: &Symbol| (s.symbol != unified, !s.is_tradeable, s.symbol.len());
+            match best.get(&unified) {
+                Some(current) if rank(current) <= rank(symbol) => {}
+                _ => {
+                    best.insert(unified, symbol);
+                }
+            }
+        }
+
+        let mut renamed = 0;
+        for (unified, symbol) in best {
+            if self.pinned.contains(&unified) {
+                continue;
+            }
+            if symbol.symbol != unified {
+                debug!("Mapped {} to platform symbol {}", unified, symbol.symbol);
+                renamed += 1;
+            }
+            self.insert(&unified, &symbol.symbol);
+        }
+        renamed
+    }
+
+    pub fn to_platform(&self, unified: &str) -> String {
+        self.to_platform
+            .get(unified)
+            .map(|s| s.clone())
+            .unwrap_or_else(|| unified.to_string())
+    }
+
+    pub fn to_unified(&self, platform: &str) -> String {
+        self.to_unified
+            .get(platform)
+            .map(|s| s.clone())
+            .unwrap_or_else(|| platform.to_string())
+    }
+
+    /// Rename the symbols in an event from the platform's names to unified ones
+    pub fn unify_event(&self, mut event: PlatformEvent) -> PlatformEvent {
+        match &mut event.data {
+            EventData::Order(data) => data.order.symbol = self.to_unified(&data.order.symbol),
+            EventData::Position(data) => {
+                data.position.symbol = self.to_unified(&data.position.symbol)
+            }
+            EventData::PositionClose(data) => data.symbol = self.to_unified(&data.symbol),
+            EventData::MarketData(data) => {
+                data.market_data.symbol = self.to_unified(&data.market_data.symbol)
+            }
+            EventData::TradingSession(data) => {
+                if let Some(symbol) = &mut data.symbol {
+                    *symbol = self.to_unified(symbol);
+                }
+            }
+            _ => {}
+        }
+        event
+    }
+}
+
+/// Platform wrapper that speaks unified symbols to the engine and the platform's own
+/// names to the broker. Symbols are renamed on every request and response, including
+/// quotes and events, so nothing downstream caches a platform-specific name.
+pub struct SymbolMappingPlatform {
+    inner: Arc<dyn ITradingPlatform + Send + Sync>,
+    mapper: Arc<SymbolMapper>,
+}
+
+impl SymbolMappingPlatform {
+    pub fn new(inner: Arc<dyn ITradingPlatform + Send + Sync>, mapper: Arc<SymbolMapper>) -> Self {
+        Self { inner, mapper }
+    }
+
+    /// Wrap `inner` with the configured overrides and, when enabled, the symbols
+    /// discovered from its instrument list. Platforms that cannot list their
+    /// instruments keep the overrides only, with a warning.
+    pub async fn from_config(
+        inner: Arc<dyn ITradingPlatform + Send + Sync>,
+        config: &SymbolMappingConfig,
+    ) -> Self {
+        let platform = Self::new(inner, Arc::new(SymbolMapper::from_config(config)));
+        if config.auto_discover {
+            match platform.discover().await {
+                Ok(_) => {}
+                Err(PlatformError::FeatureNotSupported { .. }) => warn!(
+                    "{} cannot list its instruments, using configured symbol mappings only",
+                    platform.inner.platform_name()
+                ),
+                Err(e) => warn!(
+                    "Symbol discovery on {} failed, using configured mappings only: {}",
+                    platform.inner.platform_name(),
+                    e
+                ),
+            }
+        }
+        platform
+    }
+
+    /// Refresh the mapping from the platform's instrument list
+    pub async fn discover(&self) -> Result<usize, PlatformError> {
+        let symbols = self.inner.get_instruments().await?;
+        Ok(self.mapper.discover(&symbols))
+    }
+
+    pub fn mapper(&self) -> Arc<SymbolMapper> {
+        self.mapper.clone()
+    }
+
+    fn unify_order(&self, mut order: UnifiedOrderResponse) -> UnifiedOrderResponse {
+        order.symbol = self.mapper.to_unified(&order.symbol);
+        order
+    }
+
+    fn unify_position(&self, mut position: UnifiedPosition) -> UnifiedPosition {
+        position.symbol = self.mapper.to_unified(&position.symbol);
+        position
+    }
+
+    fn unify_market_data(&self, mut data: UnifiedMarketData) -> UnifiedMarketData {
+        data.symbol = self.mapper.to_unified(&data.symbol);
+        data
+    }
+}
+
+#[async_trait]
+impl PlatformDecorator for SymbolMappingPlatform {
+    type Inner = dyn ITradingPlatform + Send + Sync;
+
+    fn inner(&self) -> &Self::Inner {
+        self.inner.as_ref()
+    }
+
+    async fn place_order(
+        &self,
+        mut order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        order.symbol = self.mapper.to_platform(&order.symbol);
+        let response = self.inner.place_order(order).await?;
+        Ok(self.unify_order(response))
+    }
+
+    async fn modify_order(
+        &self,
+        order_id: &str,
+        modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        let response = self.inner.modify_order(order_id, modifications).await?;
+        Ok(self.unify_order(response))
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        let response = self.inner.get_order(order_id).await?;
+        Ok(self.unify_order(response))
+    }
+
+    async fn get_orders(
+        &self,
+        filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        let filter = filter.map(|mut filter| {
+            filter.symbol = filter.symbol.map(|s| self.mapper.to_platform(&s));
+            filter
+        });
+        let orders = self.inner.get_orders(filter).await?;
+        Ok(orders.into_iter().map(|o| self.unify_order(o)).collect())
+    }
+
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        let positions = self.inner.get_positions().await?;
+        Ok(positions
+            .into_iter()
+            .map(|p| self.unify_position(p))
+            .collect())
+    }
+
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        let position = self
+            .inner
+            .get_position(&self.mapper.to_platform(symbol))
+            .await?;
+        Ok(position.map(|p| self.unify_position(p)))
+    }
+
+    async fn close_position(
+        &self,
+        symbol: &str,
+        quantity: Option<rust_decimal::Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        let response = self
+            .inner
+            .close_position(&self.mapper.to_platform(symbol), quantity)
+            .await?;
+        Ok(self.unify_order(response))
+    }
+
+    async fn get_position_tickets(&self, symbol: &str) -> Result<PositionTickets, PlatformError> {
+        let tickets = self
+            .inner
+            .get_position_tickets(&self.mapper.to_platform(symbol))
+            .await?;
+        Ok(PositionTickets {
+            symbol: symbol.to_string(),
+            tickets: tickets
+                .tickets
+                .into_iter()
+                .map(|p| self.unify_position(p))
+                .collect(),
+        })
+    }
+
+    async fn close_position_ticket(
+        &self,
+        position_id: &str,
+        quantity: Option<rust_decimal::Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        let response = self
+            .inner
+            .close_position_ticket(position_id, quantity)
+            .await?;
+        Ok(self.unify_order(response))
+    }
+
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        let mut info = self.inner.get_margin_info().await?;
+        info.margin_requirements = info
+            .margin_requirements
+            .into_iter()
+            .map(|(symbol, margin)| (self.mapper.to_unified(&symbol), margin))
+            .collect();
+        Ok(info)
+    }
+
+    async fn get_transactions(
+        &self,
+        range: TransactionRange,
+    ) -> Result<Vec<UnifiedTransaction>, PlatformError> {
+        let transactions = self.inner.get_transactions(range).await?;
+        Ok(transactions
+            .into_iter()
+            .map(|mut t| {
+                t.symbol = t.symbol.map(|symbol| self.mapper.to_unified(&symbol));
+                t
+            })
+            .collect())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        let data = self
+            .inner
+            .get_market_data(&self.mapper.to_platform(symbol))
+            .await?;
+        Ok(self.unify_market_data(data))
+    }
+
+    async fn subscribe_market_data(
+        &self,
+        symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        let symbols = symbols.iter().map(|s| self.mapper.to_platform(s)).collect();
+        let mut platform_rx = self.inner.subscribe_market_data(symbols).await?;
+        let (tx, rx) = mpsc::channel(platform_rx.max_capacity());
+        let mapper = self.mapper.clone();
+        tokio::spawn(async move {
+            while let Some(mut data) = platform_rx.recv().await {
+                data.symbol = mapper.to_unified(&data.symbol);
+                if tx.send(data).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    async fn unsubscribe_market_data(&self, symbols: Vec<String>) -> Result<(), PlatformError> {
+        let symbols = symbols.iter().map(|s| self.mapper.to_platform(s)).collect();
+        self.inner.unsubscribe_market_data(symbols).await
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<Symbol>, PlatformError> {
+        let symbols = self.inner.get_instruments().await?;
+        Ok(symbols
+            .into_iter()
+            .map(|mut s| {
+                s.symbol = self.mapper.to_unified(&s.symbol);
+                s
+            })
+            .collect())
+    }
+
+    async fn get_order_book(
+        &self,
+        symbol: &str,
+        levels: usize,
+    ) -> Result<UnifiedOrderBook, PlatformError> {
+        let mut book = self
+            .inner
+            .get_order_book(&self.mapper.to_platform(symbol), levels)
+            .await?;
+        book.symbol = symbol.to_string();
+        Ok(book)
+    }
+
+    async fn subscribe_depth(
+        &self,
+        symbols: Vec<String>,
+        levels: usize,
+    ) -> Result<mpsc::Receiver<UnifiedOrderBook>, PlatformError> {
+        let symbols = symbols.iter().map(|s| self.mapper.to_platform(s)).collect();
+        let mut platform_rx = self.inner.subscribe_depth(symbols, levels).await?;
+        let (tx, rx) = mpsc::channel(platform_rx.max_capacity());
+        let mapper = self.mapper.clone();
+        tokio::spawn(async move {
+            while let Some(mut book) = platform_rx.recv().await {
+                book.symbol = mapper.to_unified(&book.symbol);
+                if tx.send(book).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    fn capabilities(&self) -> PlatformCapabilities {
+        let mut capabilities = self.inner.capabilities();
+        let rules = &mut capabilities.quantity_rules;
+        rules.symbols = std::mem::take(&mut rules.symbols)
+            .into_iter()
+            .map(|(symbol, limits)| (self.mapper.to_unified(&symbol), limits))
+            .collect();
+        capabilities
+    }
+
+    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
+        let mut platform_rx = self.inner.subscribe_events().await?;
+        let (tx, rx) = mpsc::channel(platform_rx.max_capacity());
+        let mapper = self.mapper.clone();
+        tokio::spawn(async move {
+            while let Some(event) = platform_rx.recv().await {
+                if tx.send(mapper.unify_event(event)).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    async fn get_event_history(
+        &self,
+        filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        let events = self.inner.get_event_history(filter).await?;
+        Ok(events
+            .into_iter()
+            .map(|e| self.mapper.unify_event(e))
+            .collect())
+    }
+}
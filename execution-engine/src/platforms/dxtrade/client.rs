@@ -1,7 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+
 use super::config::DXTradeConfig;
 use super::error::Result;
 use super::fix_client::FIXClient;
+use super::fix_session::FIXSession;
 use super::rest_client::RestClient;
+use super::{
+    DXTradeAccountInfo, DXTradeError, DXTradeInstrument, DXTradeMarketData, DXTradeOrderRequest,
+    DXTradeOrderResponse, DXTradePosition, DXTradeTransaction,
+};
 use crate::platforms::{PlatformType, TradingPlatform};
 
 pub struct DXTradeClient {
@@ -22,13 +31,89 @@ impl DXTradeClient {
         })
     }
 
+    pub fn account_id(&self) -> &str {
+        &self.config.credentials.account_id
+    }
+
+    /// Log on to the FIX gateway and open the REST session
     pub async fn connect(&self) -> Result<()> {
-        self.fix_client.connect().await
+        self.fix_client.connect().await?;
+        self.rest_client.login().await
     }
 
     pub async fn disconnect(&self) -> Result<()> {
         self.fix_client.disconnect().await
     }
+
+    /// The FIX session currently open, if any
+    pub async fn fix_session(&self) -> Option<Arc<FIXSession>> {
+        self.fix_client.session().await
+    }
+
+    /// Fails unless the FIX session is logged on
+    pub async fn heartbeat(&self) -> Result<()> {
+        if self.fix_client.is_connected().await {
+            Ok(())
+        } else {
+            Err(DXTradeError::ConnectionError(
+                "FIX session is not logged on".to_string(),
+            ))
+        }
+    }
+
+    pub async fn place_order(&self, order: DXTradeOrderRequest) -> Result<DXTradeOrderResponse> {
+        self.rest_client.place_order(&order).await
+    }
+
+    pub async fn modify_order<M: Serialize>(
+        &self,
+        order_id: &str,
+        modifications: &M,
+    ) -> Result<DXTradeOrderResponse> {
+        self.rest_client.modify_order(order_id, modifications).await
+    }
+
+    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        self.rest_client.cancel_order(order_id).await
+    }
+
+    pub async fn get_order(&self, order_id: &str) -> Result<DXTradeOrderResponse> {
+        self.rest_client.get_order(order_id).await
+    }
+
+    pub async fn get_orders(&self) -> Result<Vec<DXTradeOrderResponse>> {
+        self.rest_client.get_orders().await
+    }
+
+    pub async fn get_positions(&self) -> Result<Vec<DXTradePosition>> {
+        self.rest_client.get_positions().await
+    }
+
+    pub async fn get_account(&self) -> Result<DXTradeAccountInfo> {
+        self.rest_client.get_account().await
+    }
+
+    pub async fn get_market_data(&self, symbol: &str) -> Result<DXTradeMarketData> {
+        self.rest_client.get_market_data(symbol).await
+    }
+
+    pub async fn get_instruments(&self) -> Result<Vec<DXTradeInstrument>> {
+        self.rest_client.get_instruments().await
+    }
+
+    /// The FIX gateway's clock
+    pub async fn server_time(&self) -> Result<DateTime<Utc>> {
+        self.fix_client.server_time().await
+    }
+
+    /// Statement entries come over REST; the FIX sessions carry no history
+    pub async fn get_transactions(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<DXTradeTransaction>> {
+        self.rest_client.get_transactions(from, to).await
+    }
 }
 
 impl TradingPlatform for DXTradeClient {
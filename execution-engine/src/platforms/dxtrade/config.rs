@@ -6,11 +6,39 @@ use std::time::Duration;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DXTradeConfig {
     pub credentials: DXTradeCredentials,
+    #[serde(default)]
     pub connection: ConnectionConfig,
+    #[serde(default)]
     pub fix_settings: FIXSettings,
+    #[serde(default)]
     pub performance: PerformanceConfig,
+    #[serde(default)]
     pub ssl: SslConfig,
+    #[serde(default)]
     pub logging: LoggingConfig,
+    /// Concurrent sessions to the same CompIDs opened by the `SessionManager`
+    #[serde(default)]
+    pub sessions: Vec<FixSessionSpec>,
+}
+
+/// What a session's inbound application messages are for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionRole {
+    /// Orders are sent here and their execution reports come back
+    OrderEntry,
+    /// Copies of every execution report on the account, sent nothing
+    DropCopy,
+}
+
+/// One of several sessions sharing the CompIDs, told apart by its qualifier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixSessionSpec {
+    pub qualifier: String,
+    pub role: SessionRole,
+    /// Gateway port of the session, the environment's FIX port when unset
+    #[serde(default)]
+    pub port: Option<u16>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +49,17 @@ pub struct DXTradeCredentials {
     pub target_sub_id: Option<String>,
     pub environment: DXTradeEnvironment,
     pub account_id: String,
+    /// Login of the REST session that orders, positions and statements go through
+    #[serde(default)]
+    pub username: String,
+    #[serde(default = "default_domain")]
+    pub domain: String,
+    #[serde(default)]
+    pub password: String,
+}
+
+fn default_domain() -> String {
+    "default".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,12 +139,16 @@ impl Default for DXTradeConfig {
                 target_sub_id: None,
                 environment: DXTradeEnvironment::Test,
                 account_id: String::new(),
+                username: String::new(),
+                domain: default_domain(),
+                password: String::new(),
             },
             connection: ConnectionConfig::default(),
             fix_settings: FIXSettings::default(),
             performance: PerformanceConfig::default(),
             ssl: SslConfig::default(),
             logging: LoggingConfig::default(),
+            sessions: Vec::new(),
         }
     }
 }
@@ -212,6 +255,27 @@ impl DXTradeConfig {
             ));
         }
 
+        if self.credentials.username.is_empty() {
+            return Err(DXTradeError::ConfigurationError(
+                "REST username cannot be empty".to_string(),
+            ));
+        }
+
+        let mut qualifiers = std::collections::HashSet::new();
+        for spec in &self.sessions {
+            if spec.qualifier.is_empty() {
+                return Err(DXTradeError::ConfigurationError(
+                    "session qualifier cannot be empty".to_string(),
+                ));
+            }
+            if !qualifiers.insert(spec.qualifier.as_str()) {
+                return Err(DXTradeError::ConfigurationError(format!(
+                    "duplicate session qualifier: {}",
+                    spec.qualifier
+                )));
+            }
+        }
+
         if self.ssl.cert_file_path.is_empty() {
             return Err(DXTradeError::ConfigurationError(
                 "SSL certificate file path cannot be empty".to_string(),
@@ -4,14 +4,17 @@ use super::error::{DXTradeError, Result};
 use super::fix_messages::FIXMessage;
 use super::fix_session::{FIXSession, SessionState};
 use super::ssl_handler::SslHandler;
+use crate::runtime::watchdog::Watchdog;
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 pub struct FIXClient {
     config: Arc<DXTradeConfig>,
     auth: Arc<RwLock<DXTradeAuth>>,
-    session: Arc<RwLock<Option<FIXSession>>>,
+    session: Arc<RwLock<Option<Arc<FIXSession>>>>,
     ssl_handler: Arc<SslHandler>,
+    watchdog: Option<Arc<Watchdog>>,
 }
 
 impl FIXClient {
@@ -24,16 +27,26 @@ impl FIXClient {
             auth: Arc::new(RwLock::new(auth)),
             session: Arc::new(RwLock::new(None)),
             ssl_handler: Arc::new(ssl_handler),
+            watchdog: None,
         })
     }
 
+    /// Watch the heartbeat loop of every session this client opens
+    pub fn with_watchdog(mut self, watchdog: Arc<Watchdog>) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
     pub async fn connect(&self) -> Result<()> {
         let ssl_handler_clone = SslHandler::new(self.config.ssl.clone())?;
-        let session = FIXSession::new((*self.config).clone(), ssl_handler_clone)?;
+        let mut session = FIXSession::new((*self.config).clone(), ssl_handler_clone)?;
+        if let Some(watchdog) = &self.watchdog {
+            session = session.with_watchdog(watchdog.clone());
+        }
         session.connect().await?;
 
         let mut session_guard = self.session.write().await;
-        *session_guard = Some(session);
+        *session_guard = Some(Arc::new(session));
 
         Ok(())
     }
@@ -41,7 +54,10 @@ impl FIXClient {
     pub async fn disconnect(&self) -> Result<()> {
         let session_guard = self.session.read().await;
         if let Some(ref session) = *session_guard {
-            session.disconnect().await?;
+            // A session logged out at shutdown has nothing left to close
+            if session.get_session_state().await != SessionState::Disconnected {
+                session.disconnect().await?;
+            }
         }
 
         drop(session_guard);
@@ -51,6 +67,11 @@ impl FIXClient {
         Ok(())
     }
 
+    /// The session opened by the last `connect`, if it has not been disconnected
+    pub async fn session(&self) -> Option<Arc<FIXSession>> {
+        self.session.read().await.clone()
+    }
+
     pub async fn send_message(&self, message: FIXMessage) -> Result<()> {
         let session_guard = self.session.read().await;
         if let Some(ref session) = *session_guard {
@@ -71,6 +92,19 @@ impl FIXClient {
         }
     }
 
+    /// The gateway's clock, from the SendingTime (52) of the latest message it sent
+    pub async fn server_time(&self) -> Result<DateTime<Utc>> {
+        let session_guard = self.session.read().await;
+        let Some(ref session) = *session_guard else {
+            return Err(DXTradeError::FixSessionError(
+                "No active session".to_string(),
+            ));
+        };
+        session.server_time().await.ok_or_else(|| {
+            DXTradeError::FixSessionError("No message received from the gateway yet".to_string())
+        })
+    }
+
     pub async fn is_connected(&self) -> bool {
         matches!(self.get_session_state().await, Some(SessionState::LoggedIn))
     }
@@ -0,0 +1,162 @@
+use super::error::{DXTradeError, Result};
+
+const SOH: u8 = 0x01;
+const BEGIN_STRING: &[u8] = b"8=FIX";
+/// `10=NNN<SOH>`
+const TRAILER_LEN: usize = 7;
+/// Longest `8=FIXT.1.1<SOH>9=NNNNNNN<SOH>` header worth waiting for
+const MAX_HEADER_LEN: usize = 32;
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Splits a FIX byte stream into messages using BodyLength (9), so SOH or `10=`
+/// inside data fields cannot end a message early. Garbage and malformed frames
+/// are skipped up to the next BeginString instead of wedging the stream.
+#[derive(Debug)]
+pub struct FixFrameDecoder {
+    buffer: Vec<u8>,
+    max_message_size: usize,
+    discarded_bytes: u64,
+}
+
+impl Default for FixFrameDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FixFrameDecoder {
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            discarded_bytes: 0,
+        }
+    }
+
+    /// Reject frames whose BodyLength would make them longer than `max_message_size`
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size.max(MAX_HEADER_LEN + TRAILER_LEN);
+        self
+    }
+
+    pub fn extend(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Bytes waiting for the rest of their frame
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Bytes skipped so far as garbage or malformed frames
+    pub fn discarded_bytes(&self) -> u64 {
+        self.discarded_bytes
+    }
+
+    /// Next complete frame, an error for a malformed one (which is dropped), or
+    /// `None` until more bytes arrive
+    pub fn next_frame(&mut self) -> Option<Result<String>> {
+        if !self.skip_to_begin_string() {
+            return None;
+        }
+
+        let (header_len, body_length) = match self.read_header()? {
+            Ok(header) => header,
+            Err(e) => return Some(Err(self.drop_frame(e))),
+        };
+
+        let body_end = header_len.saturating_add(body_length);
+        let frame_len = body_end.saturating_add(TRAILER_LEN);
+        if frame_len > self.max_message_size {
+            return Some(Err(self.drop_frame(format!(
+                "BodyLength {} exceeds the {} byte message limit",
+                body_length, self.max_message_size
+            ))));
+        }
+        if self.buffer.len() < frame_len {
+            return None;
+        }
+
+        let trailer = &self.buffer[body_end..frame_len];
+        let trailer_ok = trailer.starts_with(b"10=")
+            && trailer[3..6].iter().all(u8::is_ascii_digit)
+            && trailer[6] == SOH;
+        if !trailer_ok {
+            return Some(Err(self.drop_frame(format!(
+                "no CheckSum field where BodyLength {} ends the message",
+                body_length
+            ))));
+        }
+
+        let frame: Vec<u8> = self.buffer.drain(..frame_len).collect();
+        Some(String::from_utf8(frame).map_err(|e| {
+            self.discarded_bytes += frame_len as u64;
+            DXTradeError::FixMessageError(format!("Message is not valid UTF-8: {}", e))
+        }))
+    }
+
+    /// Drop anything before the next BeginString. Keeps a possible partial
+    /// BeginString at the end of the buffer and returns whether one was found.
+    fn skip_to_begin_string(&mut self) -> bool {
+        if self.buffer.starts_with(BEGIN_STRING) {
+            return true;
+        }
+        let found = self
+            .buffer
+            .windows(BEGIN_STRING.len())
+            .position(|window| window == BEGIN_STRING);
+        let skip =
+            found.unwrap_or_else(|| self.buffer.len().saturating_sub(BEGIN_STRING.len() - 1));
+        if skip > 0 {
+            self.discarded_bytes += skip as u64;
+            self.buffer.drain(..skip);
+        }
+        found.is_some()
+    }
+
+    /// Length of `8=...<SOH>9=N<SOH>` and the BodyLength it declares, or `None`
+    /// while the header is incomplete
+    fn read_header(&self) -> Option<std::result::Result<(usize, usize), String>> {
+        let window = &self.buffer[..self.buffer.len().min(MAX_HEADER_LEN)];
+        let Some(begin_end) = window.iter().position(|&b| b == SOH) else {
+            return (window.len() == MAX_HEADER_LEN)
+                .then(|| Err("BeginString is not terminated".to_string()));
+        };
+
+        let rest = &window[begin_end + 1..];
+        let Some(length_end) = rest.iter().position(|&b| b == SOH) else {
+            return if rest.len() >= 2 && !rest.starts_with(b"9=") {
+                Some(Err("BodyLength must follow BeginString".to_string()))
+            } else {
+                (window.len() == MAX_HEADER_LEN)
+                    .then(|| Err("BodyLength is not terminated".to_string()))
+            };
+        };
+
+        let field = &rest[..length_end];
+        let digits = match field.strip_prefix(b"9=") {
+            Some(digits) if !digits.is_empty() && digits.iter().all(u8::is_ascii_digit) => digits,
+            _ => {
+                return Some(Err(format!(
+                    "invalid BodyLength field {:?}",
+                    String::from_utf8_lossy(field)
+                )))
+            }
+        };
+        let body_length = std::str::from_utf8(digits)
+            .ok()
+            .and_then(|digits| digits.parse::<usize>().ok());
+        Some(match body_length {
+            Some(body_length) => Ok((begin_end + 1 + length_end + 1, body_length)),
+            None => Err("BodyLength out of range".to_string()),
+        })
+    }
+
+    /// Skip past the current BeginString so the search resumes at the next message
+    fn drop_frame(&mut self, reason: String) -> DXTradeError {
+        let skip = BEGIN_STRING.len().min(self.buffer.len());
+        self.discarded_bytes += skip as u64;
+        self.buffer.drain(..skip);
+        DXTradeError::FixMessageError(format!("Malformed FIX frame: {}", reason))
+    }
+}
@@ -1,5 +1,5 @@
 use super::error::{DXTradeError, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -126,6 +126,21 @@ impl FIXMessageBuilder {
     }
 }
 
+/// Length field (e.g. RawDataLength 95) preceding a FIX data field (e.g. RawData 96)
+fn data_length_tag(tag: u32) -> Option<u32> {
+    match tag {
+        89 => Some(93),                                                       // Signature
+        91 => Some(90),                                                       // SecureData
+        96 => Some(95),                                                       // RawData
+        213 => Some(212),                                                     // XmlData
+        349 | 351 | 353 | 355 | 357 | 359 | 361 | 363 | 365 => Some(tag - 1), // Encoded*
+        446 => Some(445), // EncodedListStatusText
+        619 => Some(618), // EncodedLegIssuer
+        622 => Some(621), // EncodedLegSecurityDesc
+        _ => None,
+    }
+}
+
 impl MessageType {
     pub fn from_str(s: &str) -> Self {
         match s {
@@ -194,31 +209,59 @@ impl ToString for MessageType {
 
 impl FIXMessage {
     pub fn parse(raw_message: &str) -> Result<Self> {
-        let mut fields = HashMap::new();
-        let parts: Vec<&str> = raw_message.split(SOH).collect();
-
+        let mut fields: HashMap<u32, String> = HashMap::new();
         let mut msg_type = MessageType::Unknown("".to_string());
+        let mut pos = 0;
 
-        for part in parts {
-            if part.is_empty() {
-                continue;
-            }
+        while pos < raw_message.len() {
+            let part_start = pos;
+            let rest = &raw_message[part_start..];
+            let part_end = rest.find(SOH).unwrap_or(rest.len());
+            pos += part_end + 1;
 
-            let field_parts: Vec<&str> = part.splitn(2, '=').collect();
-            if field_parts.len() != 2 {
+            let Some((tag_str, value)) = rest[..part_end].split_once('=') else {
                 continue;
-            }
-
-            let tag: u32 = field_parts[0].parse().map_err(|_| {
-                DXTradeError::FixMessageError(format!("Invalid tag: {}", field_parts[0]))
-            })?;
-            let value = field_parts[1].to_string();
+            };
+            let tag: u32 = tag_str
+                .parse()
+                .map_err(|_| DXTradeError::FixMessageError(format!("Invalid tag: {}", tag_str)))?;
+
+            // Data fields carry their length in the preceding field and may contain SOH
+            let value = match data_length_tag(tag).and_then(|length_tag| fields.get(&length_tag)) {
+                Some(length) => {
+                    let value_start = part_start + tag_str.len() + 1;
+                    let value = length
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|length| value_start.checked_add(length))
+                        .and_then(|value_end| raw_message.get(value_start..value_end))
+                        .ok_or_else(|| {
+                            DXTradeError::FixMessageError(format!(
+                                "Data field {} does not match its length {}",
+                                tag, length
+                            ))
+                        })?;
+                    pos = value_start + value.len();
+                    match raw_message[pos..].chars().next() {
+                        None => {}
+                        Some(SOH) => pos += 1,
+                        Some(_) => {
+                            return Err(DXTradeError::FixMessageError(format!(
+                                "Data field {} runs past its length {}",
+                                tag, length
+                            )))
+                        }
+                    }
+                    value
+                }
+                None => value,
+            };
 
             if tag == 35 {
-                msg_type = MessageType::from_str(&value);
+                msg_type = MessageType::from_str(value);
             }
 
-            fields.insert(tag, value);
+            fields.insert(tag, value.to_string());
         }
 
         Ok(Self {
@@ -240,29 +283,40 @@ impl FIXMessage {
         self.get_field(tag).and_then(|s| s.parse().ok())
     }
 
+    /// FIX UTCTimestamp, with or without fractional seconds
     pub fn get_field_as_datetime(&self, tag: u32) -> Option<DateTime<Utc>> {
         self.get_field(tag)
-            .and_then(|s| DateTime::parse_from_str(s, "%Y%m%d-%H:%M:%S%.3f").ok())
-            .map(|dt| dt.with_timezone(&Utc))
+            .and_then(|s| NaiveDateTime::parse_from_str(s, "%Y%m%d-%H:%M:%S%.f").ok())
+            .map(|dt| dt.and_utc())
     }
 
+    /// True if the message ends in a three-digit CheckSum (10) matching the bytes before it
     pub fn validate_checksum(&self) -> bool {
-        let checksum_pos = self.raw_message.rfind("10=");
-        if let Some(pos) = checksum_pos {
-            let message_without_checksum = &self.raw_message[..pos];
-            let expected_checksum = message_without_checksum
-                .as_bytes()
-                .iter()
-                .map(|&b| b as u32)
-                .sum::<u32>()
-                % 256;
-
-            let actual_checksum_str = &self.raw_message[pos + 3..pos + 6];
-            if let Ok(actual_checksum) = actual_checksum_str.parse::<u32>() {
-                return expected_checksum == actual_checksum;
-            }
+        let raw = self.raw_message.as_bytes();
+        let Some(body) = raw.strip_suffix(b"\x01") else {
+            return false;
+        };
+        let Some(trailer_start) = body.len().checked_sub(6) else {
+            return false;
+        };
+        let (message_without_checksum, trailer) = body.split_at(trailer_start);
+        let starts_field = message_without_checksum
+            .last()
+            .is_none_or(|&b| b == SOH as u8);
+        if !starts_field || !trailer.starts_with(b"10=") {
+            return false;
         }
-        false
+
+        let expected_checksum = message_without_checksum
+            .iter()
+            .map(|&b| b as u32)
+            .sum::<u32>()
+            % 256;
+        std::str::from_utf8(&trailer[3..])
+            .ok()
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|digits| digits.parse::<u32>().ok())
+            == Some(expected_checksum)
     }
 
     pub fn calculate_checksum(&self) -> u32 {
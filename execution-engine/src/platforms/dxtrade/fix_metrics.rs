@@ -0,0 +1,218 @@
+// Prometheus metrics of FIX session health, labelled by session, so alerts can
+// fire on degrading broker connectivity before orders start failing
+
+use lazy_static::lazy_static;
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge_vec, HistogramVec,
+    IntCounterVec, IntGaugeVec,
+};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::fix_session::SessionState;
+
+lazy_static! {
+    pub static ref FIX_SESSION_STATE: IntGaugeVec = register_int_gauge_vec!(
+        "execution_engine_fix_session_state",
+        "1 for the state a FIX session is in, 0 for the others",
+        &["session", "state"]
+    )
+    .unwrap();
+    pub static ref FIX_STATE_TRANSITIONS: IntCounterVec = register_int_counter_vec!(
+        "execution_engine_fix_session_transitions_total",
+        "FIX session state changes",
+        &["session", "from", "to"]
+    )
+    .unwrap();
+    pub static ref FIX_SEQUENCE_GAPS: IntCounterVec = register_int_counter_vec!(
+        "execution_engine_fix_sequence_gaps_total",
+        "Inbound sequence numbers found ahead of the one expected",
+        &["session"]
+    )
+    .unwrap();
+    pub static ref FIX_MISSED_MESSAGES: IntCounterVec = register_int_counter_vec!(
+        "execution_engine_fix_missed_messages_total",
+        "Inbound messages skipped over by sequence gaps",
+        &["session"]
+    )
+    .unwrap();
+    pub static ref FIX_RESEND_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "execution_engine_fix_resend_requests_served_total",
+        "Resend requests from the counterparty that were answered",
+        &["session"]
+    )
+    .unwrap();
+    pub static ref FIX_RESENT_MESSAGES: IntCounterVec = register_int_counter_vec!(
+        "execution_engine_fix_resent_messages_total",
+        "Messages sent again to answer resend requests",
+        &["session"]
+    )
+    .unwrap();
+    pub static ref FIX_HEARTBEAT_RTT: HistogramVec = register_histogram_vec!(
+        "execution_engine_fix_heartbeat_rtt_seconds",
+        "Time from a test request to the heartbeat answering it",
+        &["session"],
+        vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+    )
+    .unwrap();
+    pub static ref FIX_LOGON_FAILURES: IntCounterVec = register_int_counter_vec!(
+        "execution_engine_fix_logon_failures_total",
+        "Logons that failed, by reason",
+        &["session", "reason"]
+    )
+    .unwrap();
+    pub static ref FIX_BYTES: IntCounterVec = register_int_counter_vec!(
+        "execution_engine_fix_bytes_total",
+        "Bytes read from and written to a FIX session",
+        &["session", "direction"]
+    )
+    .unwrap();
+}
+
+const STATES: [SessionState; 7] = [
+    SessionState::Disconnected,
+    SessionState::Connecting,
+    SessionState::LogonSent,
+    SessionState::LoggedIn,
+    SessionState::LogoutSent,
+    SessionState::Reconnecting,
+    SessionState::ShuttingDown,
+];
+
+impl SessionState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionState::Disconnected => "disconnected",
+            SessionState::Connecting => "connecting",
+            SessionState::LogonSent => "logon_sent",
+            SessionState::LoggedIn => "logged_in",
+            SessionState::LogoutSent => "logout_sent",
+            SessionState::Reconnecting => "reconnecting",
+            SessionState::ShuttingDown => "shutting_down",
+        }
+    }
+}
+
+/// Records the metrics of one session. Clones share the outstanding test
+/// request, so either of the session's loops can time the answer.
+#[derive(Debug, Clone)]
+pub struct FixSessionMetrics {
+    session: String,
+    pending_probe: Arc<Mutex<Option<(String, Instant)>>>,
+}
+
+impl FixSessionMetrics {
+    /// Metrics of the session between `sender_comp_id` and `target_comp_id`;
+    /// the label outlives reconnects, which get new session ids
+    pub fn new(sender_comp_id: &str, target_comp_id: &str) -> Self {
+        Self::labelled(format!("{}:{}", sender_comp_id, target_comp_id))
+    }
+
+    /// Metrics of the session `qualifier` among several to the same CompIDs
+    pub fn qualified(sender_comp_id: &str, target_comp_id: &str, qualifier: &str) -> Self {
+        Self::labelled(format!(
+            "{}:{}:{}",
+            sender_comp_id, target_comp_id, qualifier
+        ))
+    }
+
+    fn labelled(session: String) -> Self {
+        for state in STATES {
+            let value = i64::from(state == SessionState::Disconnected);
+            FIX_SESSION_STATE
+                .with_label_values(&[&session, state.as_str()])
+                .set(value);
+        }
+        Self {
+            session,
+            pending_probe: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    pub fn transition(&self, from: &SessionState, to: &SessionState) {
+        if from == to {
+            return;
+        }
+        FIX_STATE_TRANSITIONS
+            .with_label_values(&[&self.session, from.as_str(), to.as_str()])
+            .inc();
+        FIX_SESSION_STATE
+            .with_label_values(&[&self.session, from.as_str()])
+            .set(0);
+        FIX_SESSION_STATE
+            .with_label_values(&[&self.session, to.as_str()])
+            .set(1);
+    }
+
+    /// A message numbered `received` arrived while `expected` was due
+    pub fn sequence_gap(&self, expected: u32, received: u32) {
+        FIX_SEQUENCE_GAPS.with_label_values(&[&self.session]).inc();
+        FIX_MISSED_MESSAGES
+            .with_label_values(&[&self.session])
+            .inc_by(u64::from(received.saturating_sub(expected)));
+    }
+
+    pub fn resend_served(&self, messages: usize) {
+        FIX_RESEND_REQUESTS
+            .with_label_values(&[&self.session])
+            .inc();
+        FIX_RESENT_MESSAGES
+            .with_label_values(&[&self.session])
+            .inc_by(messages as u64);
+    }
+
+    pub fn logon_failed(&self, reason: &str) {
+        FIX_LOGON_FAILURES
+            .with_label_values(&[&self.session, reason])
+            .inc();
+    }
+
+    pub fn bytes_in(&self, bytes: usize) {
+        FIX_BYTES
+            .with_label_values(&[&self.session, "in"])
+            .inc_by(bytes as u64);
+    }
+
+    pub fn bytes_out(&self, bytes: usize) {
+        FIX_BYTES
+            .with_label_values(&[&self.session, "out"])
+            .inc_by(bytes as u64);
+    }
+
+    /// Whether a test request sent less than `timeout` ago is waiting for its
+    /// heartbeat; older ones are taken as lost
+    pub fn probe_pending(&self, timeout: Duration) -> bool {
+        self.pending_probe
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|(_, sent_at)| sent_at.elapsed() < timeout)
+    }
+
+    /// Time the test request `test_req_id`, just sent
+    pub fn probe_sent(&self, test_req_id: &str) {
+        *self.pending_probe.lock().unwrap() = Some((test_req_id.to_string(), Instant::now()));
+    }
+
+    /// A heartbeat arrived, answering `test_req_id` if set. Returns the round
+    /// trip when it answers the outstanding test request.
+    pub fn heartbeat_received(&self, test_req_id: Option<&str>) -> Option<Duration> {
+        let test_req_id = test_req_id?;
+        let mut pending = self.pending_probe.lock().unwrap();
+        match pending.as_ref() {
+            Some((id, sent_at)) if id == test_req_id => {
+                let rtt = sent_at.elapsed();
+                *pending = None;
+                FIX_HEARTBEAT_RTT
+                    .with_label_values(&[&self.session])
+                    .observe(rtt.as_secs_f64());
+                Some(rtt)
+            }
+            _ => None,
+        }
+    }
+}
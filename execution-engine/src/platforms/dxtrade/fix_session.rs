@@ -1,19 +1,50 @@
 use super::config::DXTradeConfig;
 use super::error::{DXTradeError, Result};
+use super::fix_framing::FixFrameDecoder;
 use super::fix_messages::{FIXMessage, MessageType};
+use super::fix_metrics::FixSessionMetrics;
 use super::ssl_handler::SslHandler;
-use chrono::Utc;
+use crate::runtime::channel::{
+    bounded, BoundedReceiver, BoundedSender, ChannelStats, OverflowPolicy,
+};
+use crate::runtime::spawn::supervised_spawn;
+use crate::runtime::supervisor::RestartPolicy;
+use crate::runtime::watchdog::{Heartbeat, Watchdog};
+use chrono::{DateTime, Utc};
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{Mutex, RwLock};
 use tokio::time;
 use tokio_native_tls::TlsStream;
 
-#[derive(Debug, Clone)]
+/// Application messages held for the consumer before the read loop waits for room
+const INBOUND_QUEUE_CAPACITY: usize = 10_000;
+
+/// Market data is superseded by the next refresh; order and position messages are
+/// kept for a consumer. Without one nothing drains the queue, so waiting for room
+/// would stall the read loop and with it the heartbeats.
+fn inbound_overflow_policy(msg_type: &MessageType, has_consumer: bool) -> OverflowPolicy {
+    match msg_type {
+        MessageType::MarketDataSnapshotFullRefresh | MessageType::MarketDataIncrementalRefresh => {
+            OverflowPolicy::DropOldest
+        }
+        _ if !has_consumer => OverflowPolicy::DropOldest,
+        _ => OverflowPolicy::Block,
+    }
+}
+
+/// Move the session to `to`, counting the transition
+async fn set_state(state: &RwLock<SessionState>, to: SessionState, metrics: &FixSessionMetrics) {
+    let mut state = state.write().await;
+    metrics.transition(&state, &to);
+    *state = to;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SessionState {
     Disconnected,
     Connecting,
@@ -24,6 +55,9 @@ pub enum SessionState {
     ShuttingDown,
 }
 
+/// SendingTime of an inbound message and when it arrived
+type SendingTime = (DateTime<Utc>, Instant);
+
 pub struct FIXSession {
     config: Arc<DXTradeConfig>,
     ssl_handler: Arc<SslHandler>,
@@ -36,9 +70,15 @@ pub struct FIXSession {
     sequence_store: Arc<Mutex<SequenceStore>>,
     last_heartbeat_sent: Arc<Mutex<Option<Instant>>>,
     last_heartbeat_received: Arc<Mutex<Option<Instant>>>,
-    message_sender: mpsc::UnboundedSender<FIXMessage>,
-    message_receiver: Arc<Mutex<mpsc::UnboundedReceiver<FIXMessage>>>,
+    last_sending_time: Arc<Mutex<Option<SendingTime>>>,
+    message_sender: BoundedSender<FIXMessage>,
+    message_receiver: Arc<Mutex<BoundedReceiver<FIXMessage>>>,
     session_id: String,
+    watchdog: Option<Arc<Watchdog>>,
+    metrics: FixSessionMetrics,
+    qualifier: Option<String>,
+    port: Option<u16>,
+    has_consumer: bool,
 }
 
 #[derive(Debug)]
@@ -50,11 +90,48 @@ struct SequenceStore {
 
 impl FIXSession {
     pub fn new(config: DXTradeConfig, ssl_handler: SslHandler) -> Result<Self> {
-        let (tx, rx) = mpsc::unbounded_channel();
-        let session_id = format!(
-            "{}_{}",
-            config.credentials.sender_comp_id,
-            Utc::now().timestamp()
+        Self::build(config, ssl_handler, None)
+    }
+
+    /// A session named `qualifier`, one of several to the same CompIDs. Each
+    /// keeps its own sequence numbers, stored messages and inbound queue.
+    pub fn new_qualified(
+        config: DXTradeConfig,
+        ssl_handler: SslHandler,
+        qualifier: &str,
+    ) -> Result<Self> {
+        Self::build(config, ssl_handler, Some(qualifier.to_string()))
+    }
+
+    fn build(
+        config: DXTradeConfig,
+        ssl_handler: SslHandler,
+        qualifier: Option<String>,
+    ) -> Result<Self> {
+        let credentials = &config.credentials;
+        let (session_id, metrics) = match &qualifier {
+            Some(qualifier) => (
+                format!(
+                    "{}_{}_{}",
+                    credentials.sender_comp_id,
+                    qualifier,
+                    Utc::now().timestamp()
+                ),
+                FixSessionMetrics::qualified(
+                    &credentials.sender_comp_id,
+                    &credentials.target_comp_id,
+                    qualifier,
+                ),
+            ),
+            None => (
+                format!("{}_{}", credentials.sender_comp_id, Utc::now().timestamp()),
+                FixSessionMetrics::new(&credentials.sender_comp_id, &credentials.target_comp_id),
+            ),
+        };
+        let (tx, rx) = bounded(
+            &format!("fix-inbound:{}", session_id),
+            INBOUND_QUEUE_CAPACITY,
+            OverflowPolicy::Block,
         );
 
         Ok(Self {
@@ -73,24 +150,60 @@ impl FIXSession {
             })),
             last_heartbeat_sent: Arc::new(Mutex::new(None)),
             last_heartbeat_received: Arc::new(Mutex::new(None)),
+            last_sending_time: Arc::new(Mutex::new(None)),
             message_sender: tx,
             message_receiver: Arc::new(Mutex::new(rx)),
             session_id,
+            watchdog: None,
+            metrics,
+            qualifier,
+            port: None,
+            has_consumer: false,
         })
     }
 
+    /// Hold order and position messages until the caller takes them with
+    /// [`recv_message`](Self::recv_message), which it must keep doing
+    pub fn with_consumer(mut self) -> Self {
+        self.has_consumer = true;
+        self
+    }
+
+    /// Connect to `port` of the gateway instead of the environment's FIX port
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Have `watchdog` restart the heartbeat loop if it stops while the session is active
+    pub fn with_watchdog(mut self, watchdog: Arc<Watchdog>) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
     pub async fn connect(&self) -> Result<()> {
-        {
-            let mut state = self.session_state.write().await;
-            *state = SessionState::Connecting;
-        }
+        set_state(&self.session_state, SessionState::Connecting, &self.metrics).await;
 
         let hostname = self.config.credentials.environment.fix_host();
-        let port = self.config.credentials.environment.fix_port();
+        let port = self
+            .port
+            .unwrap_or_else(|| self.config.credentials.environment.fix_port());
 
         tracing::info!("Connecting to DXtrade FIX gateway at {}:{}", hostname, port);
 
-        let tls_stream = self.ssl_handler.connect_to_server(hostname, port).await?;
+        let tls_stream = match self.ssl_handler.connect_to_server(hostname, port).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                self.metrics.logon_failed("connect");
+                set_state(
+                    &self.session_state,
+                    SessionState::Disconnected,
+                    &self.metrics,
+                )
+                .await;
+                return Err(e);
+            }
+        };
 
         {
             let mut connection = self.connection.lock().await;
@@ -99,21 +212,46 @@ impl FIXSession {
 
         self.is_active.store(true, Ordering::SeqCst);
 
-        self.send_logon().await?;
+        if let Err(e) = self.send_logon().await {
+            self.metrics.logon_failed("send");
+            self.handle_disconnect().await?;
+            return Err(e);
+        }
 
         let session_clone = self.clone_session_handles();
-        tokio::spawn(async move {
-            if let Err(e) = session_clone.message_processing_loop().await {
-                tracing::error!("Message processing loop error: {}", e);
-            }
-        });
+        supervised_spawn(
+            format!("fix-messages:{}", self.session_id),
+            RestartPolicy::default(),
+            move || {
+                let session = session_clone.clone();
+                async move {
+                    if let Err(e) = session.message_processing_loop().await {
+                        tracing::error!("Message processing loop error: {}", e);
+                    }
+                }
+            },
+        );
 
         let heartbeat_session = self.clone_session_handles();
-        tokio::spawn(async move {
-            if let Err(e) = heartbeat_session.heartbeat_loop().await {
-                tracing::error!("Heartbeat loop error: {}", e);
-            }
+        let heartbeat = self.watchdog.as_ref().map(|watchdog| {
+            let handles = heartbeat_session.clone();
+            watchdog.register_with_restart(
+                &format!("fix-heartbeat:{}", self.session_id),
+                self.config.heartbeat_interval() * 3,
+                Arc::new(move |heartbeat| {
+                    let active = handles
+                        .is_active
+                        .upgrade()
+                        .is_some_and(|active| active.load(Ordering::SeqCst));
+                    if active {
+                        spawn_heartbeat_loop(handles.clone(), Some(heartbeat));
+                    } else {
+                        heartbeat.stop();
+                    }
+                }),
+            )
         });
+        spawn_heartbeat_loop(heartbeat_session, heartbeat);
 
         Ok(())
     }
@@ -129,11 +267,7 @@ impl FIXSession {
         )?;
 
         self.send_message(logon_message).await?;
-
-        {
-            let mut state = self.session_state.write().await;
-            *state = SessionState::LogonSent;
-        }
+        set_state(&self.session_state, SessionState::LogonSent, &self.metrics).await;
 
         tracing::info!("Logon message sent");
         Ok(())
@@ -155,6 +289,7 @@ impl FIXSession {
                     .map_err(|e| {
                         DXTradeError::FixSessionError(format!("Failed to send message: {}", e))
                     })?;
+                self.metrics.bytes_out(message.raw_message.len());
             } else {
                 return Err(DXTradeError::FixSessionError(
                     "No active connection".to_string(),
@@ -178,7 +313,7 @@ impl FIXSession {
 
     async fn message_processing_loop(&self) -> Result<()> {
         let mut buffer = vec![0u8; 8192];
-        let mut incomplete_message = String::new();
+        let mut decoder = FixFrameDecoder::new();
 
         while self.is_active.load(Ordering::SeqCst) {
             let bytes_read = {
@@ -200,20 +335,17 @@ impl FIXSession {
 
             match bytes_read {
                 Ok(bytes_read) if bytes_read > 0 => {
-                    let data = String::from_utf8_lossy(&buffer[..bytes_read]);
-                    incomplete_message.push_str(&data);
-
-                    while let Some(end_pos) = incomplete_message.find("10=") {
-                        if let Some(msg_end) = incomplete_message[end_pos..].find('\x01') {
-                            let full_msg_end = end_pos + msg_end + 1;
-                            let message_str = incomplete_message[..full_msg_end].to_string();
-                            incomplete_message = incomplete_message[full_msg_end..].to_string();
-
-                            if let Err(e) = self.handle_incoming_message(&message_str).await {
-                                tracing::error!("Error handling message: {}", e);
+                    self.metrics.bytes_in(bytes_read);
+                    decoder.extend(&buffer[..bytes_read]);
+
+                    while let Some(frame) = decoder.next_frame() {
+                        match frame {
+                            Ok(message_str) => {
+                                if let Err(e) = self.handle_incoming_message(&message_str).await {
+                                    tracing::error!("Error handling message: {}", e);
+                                }
                             }
-                        } else {
-                            break;
+                            Err(e) => tracing::warn!("Dropping inbound FIX data: {}", e),
                         }
                     }
                 }
@@ -244,6 +376,7 @@ impl FIXSession {
             tracing::warn!("Received message with invalid checksum");
             return Ok(());
         }
+        record_sending_time(&self.last_sending_time, &message).await;
 
         let expected_seq = self.next_seq_num_in.load(Ordering::SeqCst);
         let received_seq = message.get_field_as_u32(34).unwrap_or(0);
@@ -254,6 +387,9 @@ impl FIXSession {
                 expected_seq,
                 received_seq
             );
+            if received_seq > expected_seq {
+                self.metrics.sequence_gap(expected_seq, received_seq);
+            }
             return self.handle_sequence_gap(expected_seq, received_seq).await;
         }
 
@@ -270,8 +406,14 @@ impl FIXSession {
             MessageType::SequenceReset => self.handle_sequence_reset(&message).await?,
             MessageType::Reject => self.handle_reject(&message).await?,
             _ => {
-                if let Err(e) = self.message_sender.send(message) {
-                    tracing::error!("Failed to queue message: {}", e);
+                let policy = inbound_overflow_policy(&message.msg_type, self.has_consumer);
+                if self
+                    .message_sender
+                    .send_with(message, policy)
+                    .await
+                    .is_err()
+                {
+                    tracing::error!("Failed to queue message: inbound queue closed");
                 }
             }
         }
@@ -286,11 +428,7 @@ impl FIXSession {
 
     async fn handle_logon_response(&self, _message: &FIXMessage) -> Result<()> {
         tracing::info!("Received logon response");
-
-        {
-            let mut state = self.session_state.write().await;
-            *state = SessionState::LoggedIn;
-        }
+        set_state(&self.session_state, SessionState::LoggedIn, &self.metrics).await;
 
         Ok(())
     }
@@ -298,6 +436,13 @@ impl FIXSession {
     async fn handle_logout(&self, _message: &FIXMessage) -> Result<()> {
         tracing::info!("Received logout message");
 
+        match *self.session_state.read().await {
+            // Confirmation of a logout we initiated, nothing to reply
+            SessionState::LogoutSent => return self.handle_disconnect().await,
+            SessionState::LogonSent => self.metrics.logon_failed("rejected"),
+            _ => {}
+        }
+
         let seq_num = self.next_seq_num_out.fetch_add(1, Ordering::SeqCst);
         let logout_response = FIXMessage::create_logout(
             self.config.credentials.sender_comp_id.clone(),
@@ -312,8 +457,10 @@ impl FIXSession {
         Ok(())
     }
 
-    async fn handle_heartbeat(&self, _message: &FIXMessage) -> Result<()> {
+    async fn handle_heartbeat(&self, message: &FIXMessage) -> Result<()> {
         tracing::debug!("Received heartbeat");
+        self.metrics
+            .heartbeat_received(message.get_field(112).map(String::as_str));
         Ok(())
     }
 
@@ -345,11 +492,14 @@ impl FIXSession {
         );
 
         let store = self.sequence_store.lock().await;
+        let mut resent = 0;
         for (seq_num, stored_message) in &store.sent_messages {
             if *seq_num >= begin_seq_no && (end_seq_no == 0 || *seq_num <= end_seq_no) {
                 self.send_message(stored_message.clone()).await?;
+                resent += 1;
             }
         }
+        self.metrics.resend_served(resent);
 
         Ok(())
     }
@@ -444,24 +594,28 @@ impl FIXSession {
                 }
             }
 
-            let should_send_test_request = {
+            let counterparty_silent = {
                 let last_received = self.last_heartbeat_received.lock().await;
                 last_received.map_or(false, |t| t.elapsed() >= test_request_delay)
             };
 
-            if should_send_test_request {
+            // A test request also goes out each interval the last one was
+            // answered, to time the round trip
+            if counterparty_silent || !self.metrics.probe_pending(heartbeat_interval) {
                 let seq_num = self.next_seq_num_out.fetch_add(1, Ordering::SeqCst);
+                let test_req_id = format!("TEST-{}", Utc::now().timestamp_millis());
                 let test_request = FIXMessage::create_test_request(
                     self.config.credentials.sender_comp_id.clone(),
                     self.config.credentials.target_comp_id.clone(),
                     seq_num,
-                    format!("TEST-{}", Utc::now().timestamp()),
+                    test_req_id.clone(),
                 )?;
 
                 if let Err(e) = self.send_message(test_request).await {
                     tracing::error!("Failed to send test request: {}", e);
                     break;
                 }
+                self.metrics.probe_sent(&test_req_id);
             }
         }
 
@@ -478,15 +632,24 @@ impl FIXSession {
             *connection = None;
         }
 
-        {
-            let mut state = self.session_state.write().await;
-            *state = SessionState::Disconnected;
-        }
+        set_state(
+            &self.session_state,
+            SessionState::Disconnected,
+            &self.metrics,
+        )
+        .await;
 
         Ok(())
     }
 
     pub async fn disconnect(&self) -> Result<()> {
+        self.logout(Duration::from_secs(2)).await?;
+        Ok(())
+    }
+
+    /// Send Logout and wait up to `wait` for the counterparty's confirming Logout
+    /// before dropping the connection. Returns whether the logout was confirmed.
+    pub async fn logout(&self, wait: Duration) -> Result<bool> {
         let seq_num = self.next_seq_num_out.fetch_add(1, Ordering::SeqCst);
         let logout_message = FIXMessage::create_logout(
             self.config.credentials.sender_comp_id.clone(),
@@ -495,32 +658,62 @@ impl FIXSession {
             Some("Session termination requested".to_string()),
         )?;
 
-        {
-            let mut state = self.session_state.write().await;
-            *state = SessionState::LogoutSent;
-        }
+        set_state(&self.session_state, SessionState::LogoutSent, &self.metrics).await;
 
         self.send_message(logout_message).await?;
 
-        tokio::time::sleep(Duration::from_secs(2)).await;
+        let deadline = Instant::now() + wait;
+        let mut confirmed = false;
+        while Instant::now() < deadline {
+            if *self.session_state.read().await == SessionState::Disconnected {
+                confirmed = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        if !confirmed {
+            tracing::warn!("Logout for session {} was not confirmed", self.session_id);
+        }
 
         self.handle_disconnect().await?;
 
-        Ok(())
+        Ok(confirmed)
     }
 
     pub async fn get_session_state(&self) -> SessionState {
         self.session_state.read().await.clone()
     }
 
+    /// Next application message received from the counterparty
+    pub async fn recv_message(&self) -> Option<FIXMessage> {
+        self.message_receiver.lock().await.recv().await
+    }
+
+    pub fn inbound_queue_stats(&self) -> ChannelStats {
+        self.message_sender.stats()
+    }
+
     pub fn get_session_id(&self) -> &str {
         &self.session_id
     }
 
+    pub fn qualifier(&self) -> Option<&str> {
+        self.qualifier.as_deref()
+    }
+
     pub fn get_next_seq_num_out(&self) -> u32 {
         self.next_seq_num_out.load(Ordering::SeqCst)
     }
 
+    /// The counterparty's clock, from the SendingTime of the latest inbound
+    /// message advanced by the time since it arrived. None before any message.
+    pub async fn server_time(&self) -> Option<DateTime<Utc>> {
+        let (sending_time, received_at) = (*self.last_sending_time.lock().await)?;
+        let elapsed = chrono::Duration::from_std(received_at.elapsed()).ok()?;
+        Some(sending_time + elapsed)
+    }
+
     pub fn get_next_seq_num_in(&self) -> u32 {
         self.next_seq_num_in.load(Ordering::SeqCst)
     }
@@ -538,12 +731,41 @@ impl FIXSession {
             sequence_store: Arc::downgrade(&self.sequence_store),
             last_heartbeat_sent: Arc::downgrade(&self.last_heartbeat_sent),
             last_heartbeat_received: Arc::downgrade(&self.last_heartbeat_received),
+            last_sending_time: Arc::downgrade(&self.last_sending_time),
             message_sender: self.message_sender.clone(),
             session_id: self.session_id.clone(),
+            metrics: self.metrics.clone(),
+            has_consumer: self.has_consumer,
         }
     }
 }
 
+async fn record_sending_time(
+    last_sending_time: &Mutex<Option<SendingTime>>,
+    message: &FIXMessage,
+) {
+    if let Some(sending_time) = message.get_field_as_datetime(52) {
+        *last_sending_time.lock().await = Some((sending_time, Instant::now()));
+    }
+}
+
+fn spawn_heartbeat_loop(handles: SessionHandles, heartbeat: Option<Heartbeat>) {
+    supervised_spawn(
+        format!("fix-heartbeat:{}", handles.session_id),
+        RestartPolicy::default(),
+        move || {
+            let handles = handles.clone();
+            let heartbeat = heartbeat.clone();
+            async move {
+                if let Err(e) = handles.heartbeat_loop(heartbeat.as_ref()).await {
+                    tracing::error!("Heartbeat loop error: {}", e);
+                }
+            }
+        },
+    );
+}
+
+#[derive(Clone)]
 struct SessionHandles {
     config: Weak<DXTradeConfig>,
     ssl_handler: Weak<SslHandler>,
@@ -556,8 +778,11 @@ struct SessionHandles {
     sequence_store: Weak<Mutex<SequenceStore>>,
     last_heartbeat_sent: Weak<Mutex<Option<Instant>>>,
     last_heartbeat_received: Weak<Mutex<Option<Instant>>>,
-    message_sender: mpsc::UnboundedSender<FIXMessage>,
+    last_sending_time: Weak<Mutex<Option<SendingTime>>>,
+    message_sender: BoundedSender<FIXMessage>,
     session_id: String,
+    metrics: FixSessionMetrics,
+    has_consumer: bool,
 }
 
 impl SessionHandles {
@@ -581,7 +806,7 @@ impl SessionHandles {
             .ok_or_else(|| DXTradeError::FixSessionError("Session was dropped".to_string()))?;
 
         let mut buffer = vec![0u8; 8192];
-        let mut incomplete_message = String::new();
+        let mut decoder = FixFrameDecoder::new();
 
         while is_active.load(Ordering::SeqCst) {
             // Check if references are still valid
@@ -609,20 +834,17 @@ impl SessionHandles {
 
             match bytes_read {
                 Ok(bytes_read) if bytes_read > 0 => {
-                    let data = String::from_utf8_lossy(&buffer[..bytes_read]);
-                    incomplete_message.push_str(&data);
-
-                    while let Some(end_pos) = incomplete_message.find("10=") {
-                        if let Some(msg_end) = incomplete_message[end_pos..].find('\x01') {
-                            let full_msg_end = end_pos + msg_end + 1;
-                            let message_str = incomplete_message[..full_msg_end].to_string();
-                            incomplete_message = incomplete_message[full_msg_end..].to_string();
-
-                            if let Err(e) = self.handle_incoming_message(&message_str).await {
-                                tracing::error!("Error handling message: {}", e);
+                    self.metrics.bytes_in(bytes_read);
+                    decoder.extend(&buffer[..bytes_read]);
+
+                    while let Some(frame) = decoder.next_frame() {
+                        match frame {
+                            Ok(message_str) => {
+                                if let Err(e) = self.handle_incoming_message(&message_str).await {
+                                    tracing::error!("Error handling message: {}", e);
+                                }
                             }
-                        } else {
-                            break;
+                            Err(e) => tracing::warn!("Dropping inbound FIX data: {}", e),
                         }
                     }
                 }
@@ -645,7 +867,7 @@ impl SessionHandles {
         Ok(())
     }
 
-    async fn heartbeat_loop(&self) -> Result<()> {
+    async fn heartbeat_loop(&self, heartbeat: Option<&Heartbeat>) -> Result<()> {
         // Upgrade weak references to strong ones, exit if session was dropped
         let is_active = self
             .is_active
@@ -679,6 +901,9 @@ impl SessionHandles {
             }
 
             tokio::time::sleep(heartbeat_interval).await;
+            if let Some(heartbeat) = heartbeat {
+                heartbeat.beat();
+            }
 
             let should_send_heartbeat = {
                 let last_sent = last_heartbeat_sent.lock().await;
@@ -704,24 +929,36 @@ impl SessionHandles {
                 }
             }
 
-            let should_send_test_request = {
+            let counterparty_silent = {
                 let last_received = last_heartbeat_received.lock().await;
                 last_received.map_or(false, |t| t.elapsed() >= test_request_delay)
             };
 
-            if should_send_test_request {
+            // A test request also goes out each interval the last one was
+            // answered, to time the round trip
+            if counterparty_silent || !self.metrics.probe_pending(heartbeat_interval) {
                 let seq_num = next_seq_num_out.fetch_add(1, Ordering::SeqCst);
+                let test_req_id = format!("TEST-{}", Utc::now().timestamp_millis());
                 let test_request = FIXMessage::create_test_request(
                     config.credentials.sender_comp_id.clone(),
                     config.credentials.target_comp_id.clone(),
                     seq_num,
-                    format!("TEST-{}", Utc::now().timestamp()),
+                    test_req_id.clone(),
                 )?;
 
                 if let Err(e) = self.send_message(test_request).await {
                     tracing::error!("Failed to send test request: {}", e);
                     break;
                 }
+                self.metrics.probe_sent(&test_req_id);
+            }
+        }
+
+        // Stopped on purpose; a loop that broke off while the session is active is
+        // left to the watchdog
+        if let Some(heartbeat) = heartbeat {
+            if !is_active.load(Ordering::SeqCst) {
+                heartbeat.stop();
             }
         }
 
@@ -745,6 +982,9 @@ impl SessionHandles {
             .last_heartbeat_received
             .upgrade()
             .ok_or_else(|| DXTradeError::FixSessionError("Session was dropped".to_string()))?;
+        if let Some(last_sending_time) = self.last_sending_time.upgrade() {
+            record_sending_time(&last_sending_time, &message).await;
+        }
 
         let expected_seq = next_seq_num_in.load(Ordering::SeqCst);
         let received_seq = message.get_field_as_u32(34).unwrap_or(0);
@@ -756,16 +996,51 @@ impl SessionHandles {
                 received_seq
             );
             // For simplicity, just log the gap in this handles version
+            if received_seq > expected_seq {
+                self.metrics.sequence_gap(expected_seq, received_seq);
+            }
         }
 
         if !message.is_admin_message() {
             next_seq_num_in.fetch_add(1, Ordering::SeqCst);
         }
 
+        match message.msg_type {
+            MessageType::Logon => {
+                if let Some(session_state) = self.session_state.upgrade() {
+                    set_state(&session_state, SessionState::LoggedIn, &self.metrics).await;
+                }
+            }
+            MessageType::Logout => {
+                if let Some(session_state) = self.session_state.upgrade() {
+                    let state = session_state.read().await.clone();
+                    if state == SessionState::LogoutSent {
+                        tracing::info!("Logout confirmed by counterparty");
+                        return self.handle_disconnect().await;
+                    }
+                    if state == SessionState::LogonSent {
+                        self.metrics.logon_failed("rejected");
+                    }
+                }
+            }
+            MessageType::Heartbeat => {
+                self.metrics
+                    .heartbeat_received(message.get_field(112).map(String::as_str));
+            }
+            MessageType::ResendRequest => self.serve_resend_request(&message).await?,
+            _ => {}
+        }
+
         // Send application messages to the main session
         if !message.is_admin_message() {
-            if let Err(e) = self.message_sender.send(message) {
-                tracing::error!("Failed to queue message: {}", e);
+            let policy = inbound_overflow_policy(&message.msg_type, self.has_consumer);
+            if self
+                .message_sender
+                .send_with(message, policy)
+                .await
+                .is_err()
+            {
+                tracing::error!("Failed to queue message: inbound queue closed");
             }
         }
 
@@ -777,6 +1052,32 @@ impl SessionHandles {
         Ok(())
     }
 
+    /// Send the stored application messages the counterparty asks for again
+    async fn serve_resend_request(&self, message: &FIXMessage) -> Result<()> {
+        let Some(sequence_store) = self.sequence_store.upgrade() else {
+            return Ok(());
+        };
+        let begin_seq_no = message.get_field_as_u32(7).unwrap_or(0);
+        let end_seq_no = message.get_field_as_u32(16).unwrap_or(0);
+        let resend: Vec<FIXMessage> = sequence_store
+            .lock()
+            .await
+            .sent_messages
+            .iter()
+            .filter(|(seq_num, _)| {
+                *seq_num >= begin_seq_no && (end_seq_no == 0 || *seq_num <= end_seq_no)
+            })
+            .map(|(_, message)| message.clone())
+            .collect();
+
+        let resent = resend.len();
+        for message in resend {
+            self.send_message(message).await?;
+        }
+        self.metrics.resend_served(resent);
+        Ok(())
+    }
+
     async fn send_message(&self, message: FIXMessage) -> Result<()> {
         let is_active = self
             .is_active
@@ -802,6 +1103,7 @@ impl SessionHandles {
                     .map_err(|e| {
                         DXTradeError::FixSessionError(format!("Failed to send message: {}", e))
                     })?;
+                self.metrics.bytes_out(message.raw_message.len());
             } else {
                 return Err(DXTradeError::FixSessionError(
                     "No active connection".to_string(),
@@ -826,8 +1128,7 @@ impl SessionHandles {
         }
 
         if let Some(session_state) = self.session_state.upgrade() {
-            let mut state = session_state.write().await;
-            *state = SessionState::Disconnected;
+            set_state(&session_state, SessionState::Disconnected, &self.metrics).await;
         }
 
         Ok(())
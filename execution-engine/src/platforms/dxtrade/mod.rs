@@ -3,7 +3,9 @@ pub mod client;
 pub mod config;
 pub mod error;
 pub mod fix_client;
+pub mod fix_framing;
 pub mod fix_messages;
+pub mod fix_metrics;
 pub mod fix_session;
 pub mod order_manager;
 pub mod position_manager;
@@ -16,15 +18,16 @@ mod tests;
 
 pub use auth::DXTradeAuth;
 pub use client::DXTradeClient;
-pub use config::DXTradeConfig;
+pub use config::{DXTradeConfig, FixSessionSpec, SessionRole};
 pub use error::{DXTradeError, Result};
 pub use fix_client::FIXClient;
 pub use fix_messages::{FIXMessage, MessageType};
+pub use fix_metrics::FixSessionMetrics;
 pub use fix_session::FIXSession;
 pub use order_manager::OrderManager;
 pub use position_manager::PositionManager;
 pub use rest_client::RestClient;
-pub use session_manager::SessionManager;
+pub use session_manager::{InboundRouter, RoutedMessage, SessionManager};
 
 use crate::platforms::{PlatformType, TradingPlatform};
 use chrono::{DateTime, Utc};
@@ -81,6 +84,10 @@ pub struct DXTradeOrderRequest {
     pub time_in_force: TimeInForce,
     pub client_order_id: String,
     pub account_id: String,
+    /// Position an order closes on a hedging account; without it the fill opens a
+    /// new position or, on a netting account, nets against the symbol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -178,6 +185,54 @@ pub struct DXTradeAccountInfo {
     pub buying_power: Decimal,
 }
 
+/// An entry of the account statement served by the REST API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DXTradeTransaction {
+    pub transaction_id: String,
+    pub account_id: String,
+    pub transaction_type: TransactionType,
+    pub symbol: Option<String>,
+    pub order_id: Option<String>,
+    pub position_id: Option<String>,
+    pub side: Option<OrderSide>,
+    pub quantity: Option<Decimal>,
+    pub price: Option<Decimal>,
+    pub commission: Option<Decimal>,
+    /// Signed change to the balance
+    pub amount: Decimal,
+    pub currency: String,
+    pub balance: Option<Decimal>,
+    pub transaction_time: DateTime<Utc>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TransactionType {
+    Trade,
+    Commission,
+    Financing,
+    Deposit,
+    Withdrawal,
+    Adjustment,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DXTradeInstrument {
+    pub symbol: String,
+    #[serde(default)]
+    pub description: String,
+    /// e.g. forex, index, commodity, crypto
+    #[serde(default)]
+    pub instrument_type: Option<String>,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub min_quantity: Decimal,
+    pub max_quantity: Option<Decimal>,
+    pub tick_size: Decimal,
+    pub contract_size: Option<Decimal>,
+    pub tradable: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DXTradeMarketData {
     pub symbol: String,
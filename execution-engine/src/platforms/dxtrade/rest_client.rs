@@ -1,9 +1,43 @@
+use chrono::{DateTime, Utc};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
 use super::config::DXTradeConfig;
 use super::error::{DXTradeError, Result};
+use super::{
+    DXTradeAccountInfo, DXTradeInstrument, DXTradeMarketData, DXTradeOrderRequest,
+    DXTradeOrderResponse, DXTradePosition, DXTradeTransaction,
+};
+
+/// Refresh the session this long before the gateway would expire it
+const SESSION_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Serialize)]
+struct LoginRequest<'a> {
+    username: &'a str,
+    domain: &'a str,
+    password: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoginResponse {
+    session_token: String,
+    /// Seconds of inactivity before the gateway drops the session
+    timeout: u64,
+}
+
+struct RestSession {
+    token: String,
+    expires_at: Instant,
+}
 
 pub struct RestClient {
     config: DXTradeConfig,
     client: reqwest::Client,
+    session: RwLock<Option<RestSession>>,
 }
 
 impl RestClient {
@@ -15,6 +49,172 @@ impl RestClient {
                 DXTradeError::RestApiError(format!("Failed to create HTTP client: {}", e))
             })?;
 
-        Ok(Self { config, client })
+        Ok(Self {
+            config,
+            client,
+            session: RwLock::new(None),
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}{}",
+            self.config.credentials.environment.rest_base_url(),
+            path
+        )
+    }
+
+    fn account_url(&self, path: &str) -> String {
+        self.url(&format!(
+            "/accounts/{}{}",
+            self.config.credentials.account_id, path
+        ))
+    }
+
+    /// Open a REST session with the account's login
+    pub async fn login(&self) -> Result<()> {
+        let credentials = &self.config.credentials;
+        let response = self
+            .client
+            .post(self.url("/login"))
+            .json(&LoginRequest {
+                username: &credentials.username,
+                domain: &credentials.domain,
+                password: &credentials.password,
+            })
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(DXTradeError::AuthenticationError(format!(
+                "REST login failed with {}",
+                response.status()
+            )));
+        }
+
+        let login: LoginResponse = response.json().await?;
+        let lifetime = Duration::from_secs(login.timeout).saturating_sub(SESSION_EXPIRY_MARGIN);
+        *self.session.write().await = Some(RestSession {
+            token: login.session_token,
+            expires_at: Instant::now() + lifetime,
+        });
+        Ok(())
+    }
+
+    async fn session_token(&self) -> Result<String> {
+        if let Some(session) = self.session.read().await.as_ref() {
+            if Instant::now() < session.expires_at {
+                return Ok(session.token.clone());
+            }
+        }
+        self.login().await?;
+        self.session
+            .read()
+            .await
+            .as_ref()
+            .map(|session| session.token.clone())
+            .ok_or_else(|| DXTradeError::AuthenticationError("No REST session".to_string()))
+    }
+
+    /// Send an authenticated request, logging in again once if the session was dropped
+    async fn execute(&self, request: impl Fn() -> RequestBuilder) -> Result<Response> {
+        let mut relogged = false;
+        loop {
+            let token = self.session_token().await?;
+            let response = request()
+                .header("Authorization", format!("DXAPI {}", token))
+                .send()
+                .await?;
+
+            if response.status() == StatusCode::UNAUTHORIZED && !relogged {
+                *self.session.write().await = None;
+                relogged = true;
+                continue;
+            }
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                return Err(DXTradeError::RestApiError(format!(
+                    "Request failed with {}: {}",
+                    status, body
+                )));
+            }
+            return Ok(response);
+        }
+    }
+
+    pub async fn place_order(&self, order: &DXTradeOrderRequest) -> Result<DXTradeOrderResponse> {
+        let url = self.account_url("/orders");
+        let response = self.execute(|| self.client.post(&url).json(order)).await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn modify_order<M: Serialize>(
+        &self,
+        order_id: &str,
+        modifications: &M,
+    ) -> Result<DXTradeOrderResponse> {
+        let url = self.account_url(&format!("/orders/{}", order_id));
+        let response = self
+            .execute(|| self.client.put(&url).json(modifications))
+            .await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let url = self.account_url(&format!("/orders/{}", order_id));
+        self.execute(|| self.client.delete(&url)).await?;
+        Ok(())
+    }
+
+    pub async fn get_order(&self, order_id: &str) -> Result<DXTradeOrderResponse> {
+        let url = self.account_url(&format!("/orders/{}", order_id));
+        let response = self.execute(|| self.client.get(&url)).await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn get_orders(&self) -> Result<Vec<DXTradeOrderResponse>> {
+        let url = self.account_url("/orders");
+        let response = self.execute(|| self.client.get(&url)).await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn get_positions(&self) -> Result<Vec<DXTradePosition>> {
+        let url = self.account_url("/positions");
+        let response = self.execute(|| self.client.get(&url)).await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn get_account(&self) -> Result<DXTradeAccountInfo> {
+        let url = self.account_url("/metrics");
+        let response = self.execute(|| self.client.get(&url)).await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn get_market_data(&self, symbol: &str) -> Result<DXTradeMarketData> {
+        let url = self.url(&format!("/marketdata/{}", symbol));
+        let response = self.execute(|| self.client.get(&url)).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Instruments the account can see
+    pub async fn get_instruments(&self) -> Result<Vec<DXTradeInstrument>> {
+        let url = self.url("/instruments");
+        let response = self.execute(|| self.client.get(&url)).await?;
+        Ok(response.json().await?)
+    }
+
+    /// The account's statement entries from `from` up to `to`, oldest first
+    pub async fn get_transactions(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<DXTradeTransaction>> {
+        let url = self.account_url("/transactions");
+        let query = [("from", from.to_rfc3339()), ("to", to.to_rfc3339())];
+        let response = self.execute(|| self.client.get(&url).query(&query)).await?;
+
+        let mut transactions: Vec<DXTradeTransaction> = response.json().await?;
+        transactions.sort_by_key(|t| t.transaction_time);
+        Ok(transactions)
     }
 }
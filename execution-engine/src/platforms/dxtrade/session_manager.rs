@@ -1,20 +1,307 @@
-use super::config::DXTradeConfig;
+use super::config::{DXTradeConfig, FixSessionSpec, SessionRole};
 use super::error::{DXTradeError, Result};
-use super::fix_session::FIXSession;
+use super::fix_messages::{FIXMessage, MessageType};
+use super::fix_session::{FIXSession, SessionState};
+use super::ssl_handler::SslHandler;
+use crate::runtime::channel::{bounded, BoundedReceiver, BoundedSender, OverflowPolicy};
+use crate::runtime::spawn::spawn_isolated;
+use crate::runtime::supervisor::{ShutdownSignal, Subsystem};
+use crate::runtime::watchdog::Watchdog;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::RwLock;
 
+/// Messages held for a consumer before routing waits for room
+const ROUTE_QUEUE_CAPACITY: usize = 10_000;
+
+/// How long each session is given to confirm the logout at shutdown
+const LOGOUT_WAIT: Duration = Duration::from_secs(5);
+
+/// How long the routing task waits for a message before checking the session
+/// still exists
+const ROUTE_POLL: Duration = Duration::from_secs(1);
+
+/// An inbound application message and the session it came in on
+#[derive(Debug, Clone)]
+pub struct RoutedMessage {
+    pub qualifier: String,
+    pub role: SessionRole,
+    pub message: FIXMessage,
+}
+
+struct Route {
+    role: SessionRole,
+    msg_types: Vec<MessageType>,
+    sender: BoundedSender<RoutedMessage>,
+}
+
+impl Route {
+    fn matches(&self, role: SessionRole, msg_type: &MessageType) -> bool {
+        self.role == role && (self.msg_types.is_empty() || self.msg_types.contains(msg_type))
+    }
+}
+
+/// Hands inbound messages to the consumers subscribed to the role of the
+/// session they came in on, so execution reports of the order-entry session
+/// reach order handling and those of the drop copy reach reconciliation
+#[derive(Default)]
+pub struct InboundRouter {
+    routes: std::sync::RwLock<Vec<Route>>,
+}
+
+impl InboundRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Messages of `msg_types`, all of them when empty, from sessions of `role`
+    pub fn subscribe(
+        &self,
+        name: &str,
+        role: SessionRole,
+        msg_types: &[MessageType],
+    ) -> BoundedReceiver<RoutedMessage> {
+        let (sender, receiver) = bounded(
+            &format!("fix-route:{}", name),
+            ROUTE_QUEUE_CAPACITY,
+            OverflowPolicy::Block,
+        );
+        self.routes.write().unwrap().push(Route {
+            role,
+            msg_types: msg_types.to_vec(),
+            sender,
+        });
+        receiver
+    }
+
+    /// Deliver `message` to every matching consumer, returning how many took it
+    pub async fn route(&self, qualifier: &str, role: SessionRole, message: FIXMessage) -> usize {
+        let senders: Vec<BoundedSender<RoutedMessage>> = {
+            let mut routes = self.routes.write().unwrap();
+            routes.retain(|route| !route.sender.is_closed());
+            routes
+                .iter()
+                .filter(|route| route.matches(role, &message.msg_type))
+                .map(|route| route.sender.clone())
+                .collect()
+        };
+
+        if senders.is_empty() {
+            tracing::debug!(
+                "No consumer for {:?} from FIX session {}",
+                message.msg_type,
+                qualifier
+            );
+            return 0;
+        }
+
+        let mut delivered = 0;
+        for sender in senders {
+            let routed = RoutedMessage {
+                qualifier: qualifier.to_string(),
+                role,
+                message: message.clone(),
+            };
+            if sender.send(routed).await.is_ok() {
+                delivered += 1;
+            }
+        }
+        delivered
+    }
+}
+
+struct ManagedSession {
+    role: SessionRole,
+    session: Arc<FIXSession>,
+}
+
+/// Concurrent FIX sessions to the same CompIDs, such as order entry and drop
+/// copy, each with its own sequence numbers, and the routing of what they
+/// receive
 pub struct SessionManager {
-    // Placeholder for session management functionality
+    config: DXTradeConfig,
+    watchdog: Option<Arc<Watchdog>>,
+    sessions: RwLock<HashMap<String, ManagedSession>>,
+    router: Arc<InboundRouter>,
 }
 
 impl SessionManager {
-    pub fn new(_config: DXTradeConfig) -> Self {
-        Self {}
+    pub fn new(config: DXTradeConfig) -> Self {
+        Self {
+            config,
+            watchdog: None,
+            sessions: RwLock::new(HashMap::new()),
+            router: Arc::new(InboundRouter::new()),
+        }
+    }
+
+    /// Watch the heartbeat loop of every session this manager opens
+    pub fn with_watchdog(mut self, watchdog: Arc<Watchdog>) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    pub fn router(&self) -> Arc<InboundRouter> {
+        self.router.clone()
+    }
+
+    /// Open every session of the configuration
+    pub async fn connect_all(&self) -> Result<()> {
+        for spec in self.config.sessions.clone() {
+            self.create_session(&spec).await?;
+        }
+        Ok(())
+    }
+
+    /// Open the session `spec` describes and route what it receives
+    pub async fn create_session(&self, spec: &FixSessionSpec) -> Result<Arc<FIXSession>> {
+        if self.sessions.read().await.contains_key(&spec.qualifier) {
+            return Err(DXTradeError::SessionManagementError(format!(
+                "Session {} already exists",
+                spec.qualifier
+            )));
+        }
+
+        let ssl_handler = SslHandler::new(self.config.ssl.clone())?;
+        // Drained by the routing task
+        let mut session =
+            FIXSession::new_qualified(self.config.clone(), ssl_handler, &spec.qualifier)?
+                .with_consumer();
+        if let Some(port) = spec.port {
+            session = session.with_port(port);
+        }
+        if let Some(watchdog) = &self.watchdog {
+            session = session.with_watchdog(watchdog.clone());
+        }
+        session.connect().await?;
+
+        let session = Arc::new(session);
+        self.spawn_routing(&spec.qualifier, spec.role, Arc::downgrade(&session));
+        self.sessions.write().await.insert(
+            spec.qualifier.clone(),
+            ManagedSession {
+                role: spec.role,
+                session: session.clone(),
+            },
+        );
+        Ok(session)
+    }
+
+    fn spawn_routing(&self, qualifier: &str, role: SessionRole, session: Weak<FIXSession>) {
+        let router = self.router.clone();
+        let qualifier = qualifier.to_string();
+        spawn_isolated(format!("fix-routing:{}", qualifier), async move {
+            while let Some(session) = session.upgrade() {
+                let received = tokio::time::timeout(ROUTE_POLL, session.recv_message()).await;
+                drop(session);
+                match received {
+                    Ok(Some(message)) => {
+                        router.route(&qualifier, role, message).await;
+                    }
+                    Ok(None) => break,
+                    Err(_) => {}
+                }
+            }
+        });
+    }
+
+    pub async fn session(&self, qualifier: &str) -> Option<Arc<FIXSession>> {
+        self.sessions
+            .read()
+            .await
+            .get(qualifier)
+            .map(|managed| managed.session.clone())
+    }
+
+    /// The qualifiers of the open sessions of `role`
+    pub async fn qualifiers(&self, role: SessionRole) -> Vec<String> {
+        let mut qualifiers: Vec<String> = self
+            .sessions
+            .read()
+            .await
+            .iter()
+            .filter(|(_, managed)| managed.role == role)
+            .map(|(qualifier, _)| qualifier.clone())
+            .collect();
+        qualifiers.sort();
+        qualifiers
+    }
+
+    pub async fn session_states(&self) -> HashMap<String, SessionState> {
+        let sessions = self.sessions.read().await;
+        let mut states = HashMap::new();
+        for (qualifier, managed) in sessions.iter() {
+            states.insert(qualifier.clone(), managed.session.get_session_state().await);
+        }
+        states
+    }
+
+    /// Send on the session `qualifier`; drop-copy sessions only receive
+    pub async fn send_message(&self, qualifier: &str, message: FIXMessage) -> Result<()> {
+        let session = {
+            let sessions = self.sessions.read().await;
+            let managed = sessions.get(qualifier).ok_or_else(|| {
+                DXTradeError::SessionManagementError(format!("No session {}", qualifier))
+            })?;
+            if managed.role == SessionRole::DropCopy {
+                return Err(DXTradeError::SessionManagementError(format!(
+                    "Session {} is a drop copy",
+                    qualifier
+                )));
+            }
+            managed.session.clone()
+        };
+        session.send_message(message).await
+    }
+
+    /// Log out of the session `qualifier` and forget it
+    pub async fn close_session(&self, qualifier: &str, wait: Duration) -> Result<()> {
+        let managed = self.sessions.write().await.remove(qualifier);
+        match managed {
+            Some(managed) => managed.session.logout(wait).await.map(|_| ()),
+            None => Err(DXTradeError::SessionManagementError(format!(
+                "No session {}",
+                qualifier
+            ))),
+        }
+    }
+
+    pub async fn close_all(&self, wait: Duration) {
+        let sessions: Vec<(String, ManagedSession)> = self.sessions.write().await.drain().collect();
+        for (qualifier, managed) in sessions {
+            if let Err(e) = managed.session.logout(wait).await {
+                tracing::warn!("Failed to log out of FIX session {}: {}", qualifier, e);
+            }
+        }
+    }
+}
+
+/// Opens the configured sessions on start and logs out of them on shutdown.
+/// A session that fails to open is logged rather than stopping the engine.
+#[async_trait]
+impl Subsystem for SessionManager {
+    fn name(&self) -> &str {
+        "fix-sessions"
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        for spec in self.config.sessions.clone() {
+            if let Err(e) = self.create_session(&spec).await {
+                tracing::error!("Failed to open FIX session {}: {}", spec.qualifier, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> anyhow::Result<()> {
+        shutdown.recv().await;
+        Ok(())
     }
 
-    pub async fn create_session(&self) -> Result<FIXSession> {
-        // TODO: Implement session creation and management
-        Err(DXTradeError::SessionManagementError(
-            "Not implemented".to_string(),
-        ))
+    async fn stop(&self) -> anyhow::Result<()> {
+        self.close_all(LOGOUT_WAIT).await;
+        Ok(())
     }
 }
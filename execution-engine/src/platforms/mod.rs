@@ -1,5 +1,5 @@
 // Temporarily disabled due to missing dependencies
-// pub mod tradelocker;
+pub mod tradelocker;
 pub mod abstraction;
 pub mod dxtrade;
 
@@ -11,7 +11,7 @@ pub enum PlatformType {
     MetaTrader4,
     MetaTrader5,
     DXTrade,
-    #[cfg(test)]
+    /// Simulated platforms used in tests
     Mock,
 }
 
@@ -23,6 +23,7 @@ pub trait TradingPlatform: Send + Sync {
 // Re-export key abstractions for easier usage
 pub use abstraction::{
     ITradingPlatform,
+    PerformanceMonitor,
     PlatformAbstractionLayer,
     PlatformCapabilities,
     // Temporarily disabled missing types
@@ -30,7 +31,6 @@ pub use abstraction::{
     // UnifiedPosition,
     // PlatformFactory,
     // PlatformRegistry,
-    // PerformanceMonitor,
     PlatformError,
     UnifiedAccountInfo,
     UnifiedMarketData,
@@ -6,7 +6,7 @@ use reqwest::Client;
 use tracing::{debug, error, info, warn};
 
 use crate::utils::vault::VaultClient;
-use super::{TradeLockerCredentials, TradeLockerError, Result};
+use super::{TradeLockerCredentials, TradeLockerEnvironment, TradeLockerError, Result};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthToken {
@@ -90,6 +90,17 @@ impl TradeLockerAuth {
         Ok(())
     }
 
+    /// Environment the account's credentials were issued for
+    pub async fn environment(&self, account_id: &str) -> Result<TradeLockerEnvironment> {
+        self.credentials
+            .read()
+            .await
+            .iter()
+            .find(|c| c.account_id == account_id)
+            .map(|c| c.environment.clone())
+            .ok_or_else(|| TradeLockerError::Auth(format!("No credentials for account: {}", account_id)))
+    }
+
     pub async fn authenticate(&self, account_id: &str) -> Result<AuthToken> {
         let credentials = self.credentials.read().await;
         let cred = credentials
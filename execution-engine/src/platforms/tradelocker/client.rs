@@ -9,7 +9,7 @@ use tokio::sync::Semaphore;
 use super::{
     TradeLockerAuth, TradeLockerConfig, TradeLockerError, Result,
     TradeLockerEnvironment, OrderRequest, OrderResponse, 
-    Position, AccountInfo
+    Position, AccountInfo, Transaction, MarketData, Instrument
 };
 use crate::monitoring::metrics::{TRADELOCKER_REQUEST_DURATION, TRADELOCKER_REQUEST_COUNT};
 
@@ -164,6 +164,13 @@ impl TradeLockerClient {
         Ok(())
     }
 
+    pub async fn get_orders(&self, account_id: &str) -> Result<Vec<OrderResponse>> {
+        let url = format!("{}/api/v1/orders", self.environment.base_url());
+        let request = self.client.get(&url);
+
+        self.execute_request::<Vec<OrderResponse>>(account_id, request).await
+    }
+
     pub async fn get_positions(&self, account_id: &str) -> Result<Vec<Position>> {
         let url = format!("{}/api/v1/positions", self.environment.base_url());
         let request = self.client.get(&url);
@@ -222,6 +229,35 @@ impl TradeLockerClient {
         self.execute_request::<Value>(account_id, request).await
     }
 
+    pub async fn get_transactions(
+        &self,
+        account_id: &str,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>
+    ) -> Result<Vec<Transaction>> {
+        let url = format!("{}/api/v1/account/transactions", self.environment.base_url());
+        let request = self.client.get(&url)
+            .query(&[("from", from.to_rfc3339()), ("to", to.to_rfc3339())]);
+
+        let mut transactions = self.execute_request::<Vec<Transaction>>(account_id, request).await?;
+        transactions.sort_by_key(|t| t.timestamp);
+        Ok(transactions)
+    }
+
+    pub async fn get_market_data(&self, account_id: &str, symbol: &str) -> Result<MarketData> {
+        let url = format!("{}/api/v1/quotes/{}", self.environment.base_url(), symbol);
+        let request = self.client.get(&url);
+
+        self.execute_request::<MarketData>(account_id, request).await
+    }
+
+    pub async fn get_instruments(&self, account_id: &str) -> Result<Vec<Instrument>> {
+        let url = format!("{}/api/v1/instruments", self.environment.base_url());
+        let request = self.client.get(&url);
+
+        self.execute_request::<Vec<Instrument>>(account_id, request).await
+    }
+
     fn validate_order(&self, order: &OrderRequest) -> Result<()> {
         use rust_decimal::Decimal;
         use std::str::FromStr;
@@ -234,21 +270,21 @@ impl TradeLockerClient {
         }
 
         // Validate limit orders have price
-        if matches!(order.order_type, super::OrderType::Limit | super::OrderType::StopLimit) {
-            if order.price.is_none() || order.price == Some(Decimal::from_str("0").unwrap()) {
-                return Err(TradeLockerError::InvalidRequest(
-                    "Limit orders require a valid price".into()
-                ));
-            }
+        if matches!(order.order_type, super::OrderType::Limit | super::OrderType::StopLimit)
+            && (order.price.is_none() || order.price == Some(Decimal::from_str("0").unwrap()))
+        {
+            return Err(TradeLockerError::InvalidRequest(
+                "Limit orders require a valid price".into()
+            ));
         }
 
         // Validate stop orders have stop price
-        if matches!(order.order_type, super::OrderType::Stop | super::OrderType::StopLimit) {
-            if order.stop_price.is_none() || order.stop_price == Some(Decimal::from_str("0").unwrap()) {
-                return Err(TradeLockerError::InvalidRequest(
-                    "Stop orders require a valid stop price".into()
-                ));
-            }
+        if matches!(order.order_type, super::OrderType::Stop | super::OrderType::StopLimit)
+            && (order.stop_price.is_none() || order.stop_price == Some(Decimal::from_str("0").unwrap()))
+        {
+            return Err(TradeLockerError::InvalidRequest(
+                "Stop orders require a valid stop price".into()
+            ));
         }
 
         // Validate stop loss and take profit
@@ -271,6 +307,32 @@ impl TradeLockerClient {
         Ok(())
     }
 
+    /// The server's clock from the `Date` header of an unauthenticated request,
+    /// taken as read halfway through the round trip. Whole seconds only.
+    pub async fn server_time(&self) -> Result<chrono::DateTime<chrono::Utc>> {
+        let url = format!("{}/health", self.environment.base_url());
+        let start = Instant::now();
+        let response = self.client
+            .get(&url)
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await?;
+        let round_trip = start.elapsed();
+
+        let date = response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| TradeLockerError::Api {
+                code: response.status().to_string(),
+                message: "Response has no Date header".into(),
+            })?;
+        let server_time = chrono::DateTime::parse_from_rfc2822(date)
+            .map_err(|e| TradeLockerError::Serialization(format!("Invalid Date header {}: {}", date, e)))?
+            .with_timezone(&chrono::Utc);
+        Ok(server_time + chrono::Duration::from_std(round_trip / 2).unwrap_or_default())
+    }
+
     pub async fn health_check(&self) -> Result<bool> {
         let url = format!("{}/health", self.environment.base_url());
         let response = self.client
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TradeLockerConfig {
     pub max_connections_per_account: usize,
     pub connection_timeout_ms: u64,
@@ -161,6 +161,52 @@ pub struct AccountInfo {
     pub margin_level: Option<Decimal>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    pub transaction_id: String,
+    pub transaction_type: TransactionType,
+    pub symbol: Option<String>,
+    pub order_id: Option<String>,
+    pub position_id: Option<String>,
+    pub side: Option<OrderSide>,
+    pub quantity: Option<Decimal>,
+    pub price: Option<Decimal>,
+    pub commission: Option<Decimal>,
+    pub amount: Decimal,  // Signed change to the balance
+    pub currency: String,
+    pub balance: Option<Decimal>,
+    pub timestamp: DateTime<Utc>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionType {
+    Trade,
+    Commission,
+    Swap,
+    Deposit,
+    Withdrawal,
+    Adjustment,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Instrument {
+    pub symbol: String,
+    #[serde(default)]
+    pub description: String,
+    /// e.g. forex, index, commodity, crypto
+    #[serde(default)]
+    pub instrument_type: Option<String>,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub min_quantity: Decimal,
+    pub max_quantity: Option<Decimal>,
+    pub tick_size: Decimal,
+    pub contract_size: Option<Decimal>,
+    pub tradable: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketData {
     pub symbol: String,
@@ -252,7 +252,7 @@ impl OrderManager {
 
     fn generate_client_order_id() -> String {
         use uuid::Uuid;
-        format!("TL_{}", Uuid::new_v4().to_string())
+        format!("TL_{}", Uuid::new_v4())
     }
 
     pub async fn clear_history(&self) {
@@ -140,9 +140,10 @@ impl PositionManager {
     }
 
     async fn update_metrics(&self, positions: &[Position]) {
-        let mut metrics = PositionMetrics::default();
-        
-        metrics.total_positions = positions.len();
+        let mut metrics = PositionMetrics {
+            total_positions: positions.len(),
+            ..PositionMetrics::default()
+        };
         
         let mut total_size = Decimal::ZERO;
         let mut max_size = Decimal::ZERO;
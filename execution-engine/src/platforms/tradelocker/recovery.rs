@@ -286,7 +286,7 @@ impl ErrorRecoveryManager {
                 .filter(|p| p.unrealized_pnl < rust_decimal::Decimal::ZERO)
                 .collect();
             
-            losing_positions.sort_by(|a, b| a.unrealized_pnl.cmp(&b.unrealized_pnl));
+            losing_positions.sort_by_key(|p| p.unrealized_pnl);
             
             // Close worst positions first
             for position in losing_positions.iter().take(3) {
@@ -1,6 +1,7 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::RwLock;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
@@ -12,6 +13,10 @@ use super::{
     TradeLockerAuth, TradeLockerConfig, TradeLockerError, Result,
     TradeLockerEnvironment, MarketData, Position, OrderResponse
 };
+use crate::runtime::channel::{bounded, BoundedReceiver, BoundedSender, OverflowPolicy};
+
+/// Events held for the consumer before the read loop waits for room
+const EVENT_QUEUE_CAPACITY: usize = 10_000;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -42,13 +47,43 @@ pub enum WebSocketEvent {
     Disconnected,
 }
 
+impl WebSocketEvent {
+    /// Quotes are superseded by the next one; order and position updates are kept
+    /// once a consumer has taken the receiver. Until then nothing drains the
+    /// queue, so waiting for room would stall the read loop.
+    fn overflow_policy(&self, has_consumer: bool) -> OverflowPolicy {
+        match self {
+            WebSocketEvent::MarketData(_) => OverflowPolicy::DropOldest,
+            _ if !has_consumer => OverflowPolicy::DropOldest,
+            _ => OverflowPolicy::Block,
+        }
+    }
+}
+
+/// The sending half of the event queue, applying each event's overflow policy
+#[derive(Debug, Clone)]
+struct EventSender {
+    sender: BoundedSender<WebSocketEvent>,
+    has_consumer: Arc<AtomicBool>,
+}
+
+impl EventSender {
+    async fn send(&self, event: WebSocketEvent) -> Result<()> {
+        let policy = event.overflow_policy(self.has_consumer.load(Ordering::SeqCst));
+        self.sender
+            .send_with(event, policy)
+            .await
+            .map_err(|_| TradeLockerError::WebSocket("Event receiver dropped".to_string()))
+    }
+}
+
 #[derive(Debug)]
 pub struct TradeLockerWebSocket {
     auth: Arc<TradeLockerAuth>,
     config: TradeLockerConfig,
     environment: TradeLockerEnvironment,
-    event_sender: mpsc::UnboundedSender<WebSocketEvent>,
-    event_receiver: Arc<RwLock<Option<mpsc::UnboundedReceiver<WebSocketEvent>>>>,
+    event_sender: EventSender,
+    event_receiver: Arc<RwLock<Option<BoundedReceiver<WebSocketEvent>>>>,
     is_connected: Arc<RwLock<bool>>,
     subscriptions: Arc<RwLock<Vec<String>>>,
 }
@@ -59,13 +94,20 @@ impl TradeLockerWebSocket {
         config: TradeLockerConfig,
         environment: TradeLockerEnvironment,
     ) -> Self {
-        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let (sender, event_receiver) = bounded(
+            "tradelocker-events",
+            EVENT_QUEUE_CAPACITY,
+            OverflowPolicy::Block,
+        );
 
         Self {
             auth,
             config,
             environment,
-            event_sender,
+            event_sender: EventSender {
+                sender,
+                has_consumer: Arc::new(AtomicBool::new(false)),
+            },
             event_receiver: Arc::new(RwLock::new(Some(event_receiver))),
             is_connected: Arc::new(RwLock::new(false)),
             subscriptions: Arc::new(RwLock::new(Vec::new())),
@@ -115,8 +157,7 @@ impl TradeLockerWebSocket {
 
         // Mark as connected
         *self.is_connected.write().await = true;
-        self.event_sender.send(WebSocketEvent::Connected)
-            .map_err(|e| TradeLockerError::WebSocket(format!("Event send failed: {}", e)))?;
+        self.event_sender.send(WebSocketEvent::Connected).await?;
 
         // Authenticate
         let auth_msg = WebSocketMessage::Auth {
@@ -174,7 +215,7 @@ impl TradeLockerWebSocket {
                     Ok(Message::Close(_)) => {
                         info!("WebSocket closed for account: {}", account_id);
                         *is_connected.write().await = false;
-                        let _ = event_sender.send(WebSocketEvent::Disconnected);
+                        let _ = event_sender.send(WebSocketEvent::Disconnected).await;
                         break;
                     }
                     Ok(Message::Pong(_)) => {
@@ -185,7 +226,7 @@ impl TradeLockerWebSocket {
                         *is_connected.write().await = false;
                         let _ = event_sender.send(WebSocketEvent::Error {
                             message: e.to_string(),
-                        });
+                        }).await;
                         break;
                     }
                     _ => {}
@@ -198,7 +239,7 @@ impl TradeLockerWebSocket {
 
     async fn handle_message(
         text: &str,
-        event_sender: &mpsc::UnboundedSender<WebSocketEvent>
+        event_sender: &EventSender
     ) -> Result<()> {
         let data: Value = serde_json::from_str(text)?;
         
@@ -237,8 +278,7 @@ impl TradeLockerWebSocket {
                 }
             };
 
-            event_sender.send(event)
-                .map_err(|e| TradeLockerError::WebSocket(format!("Event send failed: {}", e)))?;
+            event_sender.send(event).await?;
         }
 
         Ok(())
@@ -285,8 +325,13 @@ impl TradeLockerWebSocket {
         Ok(())
     }
 
-    pub async fn get_event_receiver(&self) -> Option<mpsc::UnboundedReceiver<WebSocketEvent>> {
-        self.event_receiver.write().await.take()
+    /// Take the event queue, which the caller must then keep draining
+    pub async fn get_event_receiver(&self) -> Option<BoundedReceiver<WebSocketEvent>> {
+        let receiver = self.event_receiver.write().await.take();
+        if receiver.is_some() {
+            self.event_sender.has_consumer.store(true, Ordering::SeqCst);
+        }
+        receiver
     }
 
     pub async fn is_connected(&self) -> bool {
@@ -295,7 +340,7 @@ impl TradeLockerWebSocket {
 
     pub async fn disconnect(&self) {
         *self.is_connected.write().await = false;
-        let _ = self.event_sender.send(WebSocketEvent::Disconnected);
+        let _ = self.event_sender.send(WebSocketEvent::Disconnected).await;
     }
 
     pub async fn reconnect(&self, account_id: &str) -> Result<()> {
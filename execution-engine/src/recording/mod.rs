@@ -0,0 +1,160 @@
+// Day recordings of what the engine saw: quotes, signals, news and the exit
+// audit trail, one JSON lines file per UTC date, for replaying bad days
+
+// Replays run on the simulated platforms of the test-support harness
+#[cfg(any(test, feature = "test-support"))]
+pub mod replay;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::execution::exit_management::{AuditEntry, NewsEvent};
+use crate::execution::orchestrator::TradeSignal;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingConfig {
+    pub enabled: bool,
+    /// Directory the `<date>.jsonl` files are written to
+    pub dir: String,
+    /// Symbols whose quotes are recorded, in unified names
+    pub symbols: Vec<String>,
+    /// Account whose quote stream is recorded; unset takes the first
+    /// registered account
+    pub source_account: Option<String>,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: "data/recordings".to_string(),
+            symbols: Vec::new(),
+            source_account: None,
+        }
+    }
+}
+
+// Externally tagged and not flattened into the entry: internally tagged enums
+// buffer their fields, which loses decimals written as arbitrary-precision numbers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedEvent {
+    /// Balance of an account when recording started or the day rolled over
+    Account {
+        account_id: String,
+        balance: Decimal,
+    },
+    Tick {
+        symbol: String,
+        bid: Decimal,
+        ask: Decimal,
+    },
+    Signal {
+        signal: TradeSignal,
+    },
+    /// Calendar event as fetched; the same event is seen on every fetch until it passes
+    News {
+        event: NewsEvent,
+    },
+    Audit {
+        entry: AuditEntry,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEntry {
+    pub at: DateTime<Utc>,
+    pub event: RecordedEvent,
+}
+
+/// Appends events to the file of the UTC date they happen on. Failures are
+/// logged and dropped: recording never holds up trading.
+#[derive(Debug)]
+pub struct EventRecorder {
+    dir: PathBuf,
+    file: Mutex<Option<(NaiveDate, tokio::fs::File)>>,
+}
+
+impl EventRecorder {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            file: Mutex::new(None),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub async fn record(&self, event: RecordedEvent) {
+        let entry = RecordedEntry {
+            at: Utc::now(),
+            event,
+        };
+        if let Err(e) = self.append(&entry).await {
+            warn!("Failed to record event in {}: {:#}", self.dir.display(), e);
+        }
+    }
+
+    async fn append(&self, entry: &RecordedEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let date = entry.at.date_naive();
+        let mut file = self.file.lock().await;
+        if file.as_ref().map_or(true, |(open, _)| *open != date) {
+            tokio::fs::create_dir_all(&self.dir).await?;
+            let path = day_path(&self.dir, date);
+            let opened = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .await
+                .with_context(|| format!("Failed to open recording {}", path.display()))?;
+            *file = Some((date, opened));
+        }
+        if let Some((_, file)) = file.as_mut() {
+            file.write_all(line.as_bytes()).await?;
+            file.flush().await?;
+        }
+        Ok(())
+    }
+}
+
+pub fn day_path(dir: impl AsRef<Path>, date: NaiveDate) -> PathBuf {
+    dir.as_ref()
+        .join(format!("{}.jsonl", date.format("%Y-%m-%d")))
+}
+
+/// Every event recorded on `date`, in the order they were recorded
+pub fn load_day(dir: impl AsRef<Path>, date: NaiveDate) -> Result<Vec<RecordedEntry>> {
+    let path = day_path(dir, date);
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("No recording at {}", path.display()))?;
+
+    let mut entries = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<RecordedEntry>(line) {
+            Ok(entry) => entries.push(entry),
+            // A torn final line from a crash should not lose the rest of the day
+            Err(e) => warn!(
+                "Skipping unreadable recording line {} in {}: {}",
+                number + 1,
+                path.display(),
+                e
+            ),
+        }
+    }
+    Ok(entries)
+}
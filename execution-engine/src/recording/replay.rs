@@ -0,0 +1,400 @@
+// Re-runs a recorded day through the simulation harness and compares the exit
+// audit trail it produces with the one recorded on the day
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::{load_day, RecordedEntry, RecordedEvent, RecordingConfig};
+use crate::execution::exit_management::{AuditEntry, ExitModificationType};
+use crate::testing::{
+    NewsStep, PriceStep, SignalStep, SimulatedAccount, SimulationHarness, SimulationReport,
+    SimulationScenario,
+};
+
+/// Decimal places exit levels are compared to
+const LEVEL_DECIMALS: u32 = 8;
+
+pub const USAGE: &str = "\
+Usage: replay --date YYYY-MM-DD [--dir DIR] [--speed N] [--json]
+
+Re-runs the ticks, signals and news recorded on a day through the engine against
+simulated platforms and diffs the exit audit trail with the recorded one.
+
+Options:
+  --date YYYY-MM-DD   UTC date of the recording
+  --dir DIR           Recordings directory, defaults to $EXECUTION_ENGINE_RECORDING_DIR,
+                      then data/recordings
+  --speed N           Replay N times faster than the day ran; 0, the default, runs
+                      every step back to back
+  --json              Print the report as JSON";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayOptions {
+    pub dir: PathBuf,
+    pub date: NaiveDate,
+    pub speed: f64,
+    pub json: bool,
+}
+
+/// Parse command-line arguments, excluding the program name. `Ok(None)` asks for help.
+pub fn parse_args<I>(args: I) -> Result<Option<ReplayOptions>, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut dir = std::env::var("EXECUTION_ENGINE_RECORDING_DIR")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .unwrap_or_else(|| RecordingConfig::default().dir);
+    let mut date = None;
+    let mut speed = 0.0;
+    let mut json = false;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--date" => {
+                let value = args.next().ok_or("--date requires a value")?;
+                date = Some(
+                    NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                        .map_err(|_| format!("Invalid date {}, expected YYYY-MM-DD", value))?,
+                );
+            }
+            "--dir" => dir = args.next().ok_or("--dir requires a value")?,
+            "--speed" => {
+                let value = args.next().ok_or("--speed requires a value")?;
+                speed = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|speed| speed.is_finite() && *speed >= 0.0)
+                    .ok_or_else(|| format!("Invalid speed {}", value))?;
+            }
+            "--json" => json = true,
+            "--help" | "help" => return Ok(None),
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+
+    Ok(Some(ReplayOptions {
+        dir: PathBuf::from(dir),
+        date: date.ok_or("--date is required")?,
+        speed,
+        json,
+    }))
+}
+
+/// Accounts, ticks, signals and news of a recorded day as a simulation scenario
+/// starting at its first recorded event
+pub fn scenario_from_day(date: NaiveDate, entries: &[RecordedEntry]) -> Result<SimulationScenario> {
+    let Some(start) = entries.first().map(|entry| entry.at) else {
+        bail!("Nothing was recorded on {}", date);
+    };
+    let offset = |at: DateTime<Utc>| (at - start).num_seconds().max(0) as u64;
+
+    let mut accounts: Vec<SimulatedAccount> = Vec::new();
+    let mut prices = Vec::new();
+    let mut signals = Vec::new();
+    let mut news = Vec::new();
+    let mut seen_news = HashSet::new();
+
+    for entry in entries {
+        match &entry.event {
+            // The first balance is the day's opening one
+            RecordedEvent::Account {
+                account_id,
+                balance,
+            } => {
+                if !accounts.iter().any(|account| &account.id == account_id) {
+                    accounts.push(SimulatedAccount {
+                        id: account_id.clone(),
+                        balance: *balance,
+                        chaos: None,
+                    });
+                }
+            }
+            RecordedEvent::Tick { symbol, bid, ask } => prices.push(PriceStep {
+                at: offset(entry.at),
+                symbol: symbol.clone(),
+                bid: *bid,
+                ask: *ask,
+            }),
+            RecordedEvent::Signal { signal } => signals.push(SignalStep {
+                at: offset(entry.at),
+                id: signal.id.clone(),
+                symbol: signal.symbol.clone(),
+                side: signal.side.clone(),
+                entry_price: signal.entry_price,
+                stop_loss: signal.stop_loss,
+                take_profit: signal.take_profit,
+                confidence: signal.confidence,
+                metadata: signal.metadata.clone(),
+            }),
+            // Every fetch and every account sees the event again; protection reacts
+            // to the first sighting
+            RecordedEvent::News { event } => {
+                if seen_news.insert(event.id.clone()) {
+                    news.push(NewsStep {
+                        at: offset(entry.at),
+                        id: event.id.clone(),
+                        currency: event.currency.clone(),
+                        description: event.description.clone(),
+                        impact: event.impact.clone(),
+                        release_in: (event.time - entry.at).num_seconds().max(0) as u64,
+                    });
+                }
+            }
+            RecordedEvent::Audit { .. } => {}
+        }
+    }
+
+    if accounts.is_empty() {
+        bail!("No account balances were recorded on {}", date);
+    }
+
+    Ok(SimulationScenario {
+        name: format!("replay {}", date),
+        description: format!("Recorded trading day {}", date),
+        start: Some(start),
+        accounts,
+        prices,
+        signals,
+        news,
+        risk: Default::default(),
+        expect: Default::default(),
+    })
+}
+
+/// Number of audit entries of one type on the day and in the replay
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditCount {
+    pub modification_type: ExitModificationType,
+    pub original: usize,
+    pub replayed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditValues {
+    pub old_value: Decimal,
+    pub new_value: Decimal,
+    pub reasoning: String,
+}
+
+impl From<&AuditEntry> for AuditValues {
+    fn from(entry: &AuditEntry) -> Self {
+        Self {
+            old_value: entry.old_value,
+            new_value: entry.new_value,
+            reasoning: entry.reasoning.clone(),
+        }
+    }
+}
+
+/// The `index`th audit entry of its type, missing on one side or moving the exit
+/// to a different level
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditChange {
+    pub modification_type: ExitModificationType,
+    pub index: usize,
+    /// When the original entry was made
+    pub at: Option<DateTime<Utc>>,
+    pub original: Option<AuditValues>,
+    pub replayed: Option<AuditValues>,
+}
+
+/// Differences between two exit audit trails. Position ids differ between a
+/// day and its replay, so entries are paired by type in the order they were made.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AuditDiff {
+    pub counts: Vec<AuditCount>,
+    pub changes: Vec<AuditChange>,
+}
+
+impl AuditDiff {
+    pub fn between(original: &[AuditEntry], replayed: &[AuditEntry]) -> Self {
+        let mut types: Vec<ExitModificationType> = Vec::new();
+        for entry in original.iter().chain(replayed) {
+            if !types.contains(&entry.modification_type) {
+                types.push(entry.modification_type.clone());
+            }
+        }
+
+        let mut diff = Self::default();
+        for modification_type in types {
+            let of_type = |entries: &[AuditEntry]| {
+                let mut entries: Vec<AuditEntry> = entries
+                    .iter()
+                    .filter(|entry| entry.modification_type == modification_type)
+                    .cloned()
+                    .collect();
+                entries.sort_by_key(|entry| entry.timestamp);
+                entries
+            };
+            let original = of_type(original);
+            let replayed = of_type(replayed);
+
+            for index in 0..original.len().max(replayed.len()) {
+                let before = original.get(index);
+                let after = replayed.get(index);
+                let values_differ = match (before, after) {
+                    (Some(before), Some(after)) => {
+                        !same_level(before.old_value, after.old_value)
+                            || !same_level(before.new_value, after.new_value)
+                    }
+                    _ => true,
+                };
+                if values_differ {
+                    diff.changes.push(AuditChange {
+                        modification_type: modification_type.clone(),
+                        index,
+                        at: before.map(|entry| entry.timestamp),
+                        original: before.map(AuditValues::from),
+                        replayed: after.map(AuditValues::from),
+                    });
+                }
+            }
+            diff.counts.push(AuditCount {
+                modification_type,
+                original: original.len(),
+                replayed: replayed.len(),
+            });
+        }
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Levels derived from float prices carry noise past the digits a recording keeps
+fn same_level(a: Decimal, b: Decimal) -> bool {
+    a.round_dp(LEVEL_DECIMALS) == b.round_dp(LEVEL_DECIMALS)
+}
+
+/// Outcome of replaying a day
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayReport {
+    pub date: NaiveDate,
+    pub accounts: usize,
+    pub ticks: usize,
+    pub signals: usize,
+    pub news: usize,
+    pub successful_executions: usize,
+    pub failed_executions: usize,
+    /// Signal id and reason for every signal the replay turned down
+    pub rejected_signals: Vec<(String, String)>,
+    pub audit: AuditDiff,
+    #[serde(skip)]
+    pub simulation: SimulationReport,
+}
+
+impl ReplayReport {
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "Replay of {}: {} accounts, {} ticks, {} signals, {} news events\n",
+            self.date, self.accounts, self.ticks, self.signals, self.news
+        );
+        out.push_str(&format!(
+            "Executions: {} successful, {} failed, {} signals rejected\n",
+            self.successful_executions,
+            self.failed_executions,
+            self.rejected_signals.len()
+        ));
+        for (signal_id, reason) in &self.rejected_signals {
+            out.push_str(&format!("  rejected {}: {}\n", signal_id, reason));
+        }
+
+        out.push_str("\nExit audit entries (original -> replayed)\n");
+        for count in &self.audit.counts {
+            out.push_str(&format!(
+                "  {:?}: {} -> {}\n",
+                count.modification_type, count.original, count.replayed
+            ));
+        }
+        if self.audit.is_empty() {
+            out.push_str("\nThe replay reproduced the recorded audit trail\n");
+            return out;
+        }
+
+        out.push_str(&format!("\n{} differences\n", self.audit.changes.len()));
+        for change in &self.audit.changes {
+            let describe = |values: &Option<AuditValues>| match values {
+                Some(values) => format!("{} -> {}", values.old_value, values.new_value),
+                None => "none".to_string(),
+            };
+            out.push_str(&format!(
+                "  {:?} #{}{}: original {}, replayed {}\n",
+                change.modification_type,
+                change.index + 1,
+                change
+                    .at
+                    .map(|at| format!(" at {}", at.format("%H:%M:%S")))
+                    .unwrap_or_default(),
+                describe(&change.original),
+                describe(&change.replayed)
+            ));
+        }
+        out
+    }
+}
+
+/// Replay `entries`, recorded on `date`, at `speed` times the pace of the day, or
+/// back to back when `speed` is zero
+pub async fn replay_entries(
+    date: NaiveDate,
+    entries: &[RecordedEntry],
+    speed: f64,
+) -> Result<ReplayReport> {
+    let scenario = scenario_from_day(date, entries)?;
+    let original: Vec<AuditEntry> = entries
+        .iter()
+        .filter_map(|entry| match &entry.event {
+            RecordedEvent::Audit { entry } => Some(entry.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut report = ReplayReport {
+        date,
+        accounts: scenario.accounts.len(),
+        ticks: scenario.prices.len(),
+        signals: scenario.signals.len(),
+        news: scenario.news.len(),
+        successful_executions: 0,
+        failed_executions: 0,
+        rejected_signals: Vec::new(),
+        audit: AuditDiff::default(),
+        simulation: SimulationReport::default(),
+    };
+    let simulation = SimulationHarness::new(scenario)
+        .await?
+        .with_speed(speed)
+        .run()
+        .await?;
+
+    let replayed: Vec<AuditEntry> = simulation
+        .audit_entries
+        .values()
+        .flatten()
+        .cloned()
+        .collect();
+    report.successful_executions = simulation.executions.iter().filter(|r| r.success).count();
+    report.failed_executions = simulation.executions.len() - report.successful_executions;
+    report.rejected_signals = simulation.rejected_signals.clone();
+    report.audit = AuditDiff::between(&original, &replayed);
+    report.simulation = simulation;
+    Ok(report)
+}
+
+/// Replay the recording of `date` in `dir`
+pub async fn replay_day(
+    dir: impl AsRef<Path>,
+    date: NaiveDate,
+    speed: f64,
+) -> Result<ReplayReport> {
+    let entries = load_day(dir, date)?;
+    replay_entries(date, &entries, speed).await
+}
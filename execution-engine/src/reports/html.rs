@@ -0,0 +1,120 @@
+use rust_decimal::Decimal;
+use std::fmt::Write;
+
+use super::{AccountDailyReport, DailyReport};
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn money(value: Decimal) -> String {
+    let class = if value < Decimal::ZERO {
+        "loss"
+    } else {
+        "gain"
+    };
+    format!("<td class=\"{}\">{}</td>", class, value.round_dp(2))
+}
+
+fn account_section(html: &mut String, account: &AccountDailyReport) {
+    let win_rate = account
+        .win_rate
+        .map(|rate| format!("{}%", (rate * Decimal::ONE_HUNDRED).round_dp(1)))
+        .unwrap_or_else(|| "n/a".to_string());
+
+    let _ = write!(
+        html,
+        "<h2>{}</h2>\n<table>\n\
+         <tr><th>Trades opened</th><td>{}</td></tr>\n\
+         <tr><th>Trades closed</th><td>{} ({} won, {} lost)</td></tr>\n\
+         <tr><th>Win rate</th><td>{}</td></tr>\n\
+         <tr><th>Net P&amp;L</th>{}</tr>\n\
+         <tr><th>Commission</th><td>{}</td></tr>\n\
+         <tr><th>Max drawdown</th><td>{}</td></tr>\n\
+         <tr><th>Exit management</th>{}</tr>\n\
+         </table>\n",
+        escape(&account.account_id),
+        account.trades_opened,
+        account.trades_closed,
+        account.wins,
+        account.losses,
+        win_rate,
+        money(account.net_pnl),
+        account.commission.round_dp(2),
+        account.max_drawdown.round_dp(2),
+        money(account.exit_management.realized_pnl),
+    );
+
+    if !account.exit_management.by_reason.is_empty() {
+        let mut reasons: Vec<_> = account.exit_management.by_reason.iter().collect();
+        reasons.sort_by_key(|(reason, _)| format!("{:?}", reason));
+        html.push_str("<table>\n<tr><th>Exit manager</th><th>P&amp;L</th></tr>\n");
+        for (reason, pnl) in reasons {
+            let _ = writeln!(html, "<tr><td>{:?}</td>{}</tr>", reason, money(*pnl));
+        }
+        html.push_str("</table>\n");
+    }
+
+    if !account.funding.is_empty() {
+        html.push_str("<table>\n<tr><th>Funding</th><th>Amount</th><th>Source</th></tr>\n");
+        for event in &account.funding {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td>{}<td>{:?}</td></tr>",
+                event.at.format("%H:%M UTC"),
+                money(event.amount),
+                event.source
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    if !account.risk_alerts.is_empty() {
+        html.push_str("<table>\n<tr><th>Risk alert</th><th>Raised</th></tr>\n");
+        for (alert_type, count) in &account.risk_alerts {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td></tr>",
+                escape(alert_type),
+                count
+            );
+        }
+        html.push_str("</table>\n");
+    }
+}
+
+/// Standalone HTML page for a daily report
+pub fn render(report: &DailyReport) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Daily trading summary {date}</title>\n\
+         <style>\n\
+         body {{ font-family: sans-serif; margin: 2em; }}\n\
+         table {{ border-collapse: collapse; margin-bottom: 1em; }}\n\
+         th, td {{ border: 1px solid #ccc; padding: 4px 10px; text-align: left; }}\n\
+         .gain {{ color: #1a7f37; }}\n\
+         .loss {{ color: #cf222e; }}\n\
+         </style>\n</head>\n<body>\n\
+         <h1>Daily trading summary {date}</h1>\n\
+         <p>{from} to {to}, generated {generated_at}</p>\n",
+        date = report.date,
+        from = report.from.format("%Y-%m-%d %H:%M UTC"),
+        to = report.to.format("%Y-%m-%d %H:%M UTC"),
+        generated_at = report.generated_at.format("%Y-%m-%d %H:%M:%S UTC"),
+    );
+
+    if report.accounts.is_empty() {
+        html.push_str("<p>No trading activity.</p>\n");
+    }
+    for account in &report.accounts {
+        account_section(&mut html, account);
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
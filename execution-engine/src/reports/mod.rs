@@ -0,0 +1,482 @@
+// Daily trading summaries compiled from the journal and the alert gateway, FIFO
+// realized P&L by tax lot from the position ledger, strategy scores, and the
+// divergence of account personas
+
+pub mod html;
+pub mod personas;
+pub mod signal_quality;
+pub mod tax_lots;
+
+pub use personas::{AccountFootprint, FootprintDivergence, PersonaDivergenceReport};
+pub use signal_quality::{
+    AllocationConfig, BucketPerformance, SignalQualityConfig, SignalQualityJob,
+    SignalQualityReport, StrategyScore, StrategyScores,
+};
+pub use tax_lots::{InstrumentRealized, RealizedLot, TaxLotQuery, TaxLotReport};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::alerting::{Alert, AlertGateway};
+use crate::journal::{ExitReason, TradeJournal, TradeRecord, TradeStatus};
+use crate::notifications::{Notification, NotificationTopic, Notifier};
+use crate::risk::funding::{FundingDetector, FundingEvent};
+use crate::risk::trading_day::TradingDayConfig;
+use crate::runtime::supervisor::{ShutdownSignal, Subsystem};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportsConfig {
+    pub enabled: bool,
+    /// How long after the last account's trading day rollover its report is published
+    #[serde(default = "default_publish_delay_mins")]
+    pub publish_delay_mins: u64,
+    /// Directory for the JSON and HTML renderings; unset only notifies
+    #[serde(default = "default_output_dir")]
+    pub output_dir: Option<String>,
+    #[serde(default = "default_check_interval_secs")]
+    pub check_interval_secs: u64,
+}
+
+fn default_publish_delay_mins() -> u64 {
+    5
+}
+
+fn default_output_dir() -> Option<String> {
+    Some("data/reports".to_string())
+}
+
+fn default_check_interval_secs() -> u64 {
+    60
+}
+
+impl Default for ReportsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            publish_delay_mins: default_publish_delay_mins(),
+            output_dir: default_output_dir(),
+            check_interval_secs: default_check_interval_secs(),
+        }
+    }
+}
+
+impl ReportsConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.check_interval_secs == 0 {
+            return Err("Report check interval must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// P&L of the exits taken by the exit managers
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExitContribution {
+    pub exits: usize,
+    pub realized_pnl: Decimal,
+    pub by_reason: HashMap<ExitReason, Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountDailyReport {
+    pub account_id: String,
+    /// The account's trading day, between its rollovers
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub trades_opened: usize,
+    pub trades_closed: usize,
+    pub wins: usize,
+    pub losses: usize,
+    /// Share of the trades closed during the day that made money
+    pub win_rate: Option<Decimal>,
+    /// Realized P&L of every exit during the day, partial ones included
+    pub net_pnl: Decimal,
+    pub commission: Decimal,
+    /// Largest peak-to-trough fall of the day's cumulative realized P&L
+    pub max_drawdown: Decimal,
+    /// Risk conditions raised during the day, by alert type
+    pub risk_alerts: BTreeMap<String, usize>,
+    pub exit_management: ExitContribution,
+    /// Deposits and withdrawals during the day; the day's equity moved by more
+    /// than its trading did
+    #[serde(default)]
+    pub funding: Vec<FundingEvent>,
+}
+
+impl AccountDailyReport {
+    fn new(account_id: &str, (from, to): (DateTime<Utc>, DateTime<Utc>)) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            from,
+            to,
+            trades_opened: 0,
+            trades_closed: 0,
+            wins: 0,
+            losses: 0,
+            win_rate: None,
+            net_pnl: Decimal::ZERO,
+            commission: Decimal::ZERO,
+            max_drawdown: Decimal::ZERO,
+            risk_alerts: BTreeMap::new(),
+            exit_management: ExitContribution::default(),
+            funding: Vec::new(),
+        }
+    }
+
+    /// Deposits less withdrawals during the day
+    pub fn net_funding(&self) -> Decimal {
+        self.funding.iter().map(|event| event.amount).sum()
+    }
+
+    pub fn alert_count(&self) -> usize {
+        self.risk_alerts.values().sum()
+    }
+
+    /// One-line digest used for chat notifications
+    pub fn summary(&self) -> String {
+        let win_rate = self
+            .win_rate
+            .map(|rate| format!("{}%", (rate * Decimal::ONE_HUNDRED).round_dp(1)))
+            .unwrap_or_else(|| "n/a".to_string());
+        let funding = if self.funding.is_empty() {
+            String::new()
+        } else {
+            format!(", funding {}", self.net_funding().round_dp(2))
+        };
+        format!(
+            "{} opened, {} closed, win rate {}, net P&L {}, max drawdown {}, {} risk alerts, exit management {} over {} exits{}",
+            self.trades_opened,
+            self.trades_closed,
+            win_rate,
+            self.net_pnl.round_dp(2),
+            self.max_drawdown.round_dp(2),
+            self.alert_count(),
+            self.exit_management.realized_pnl.round_dp(2),
+            self.exit_management.exits,
+            funding
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyReport {
+    pub date: NaiveDate,
+    /// From the earliest to the latest account rollover of the day
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    /// Accounts that traded or raised alerts during the day, by account id
+    pub accounts: Vec<AccountDailyReport>,
+}
+
+impl DailyReport {
+    /// Build the report for trading date `date`, each account covering its own trading
+    /// day, out of the trades and alerts around it
+    pub fn compile(
+        date: NaiveDate,
+        trading_days: &TradingDayConfig,
+        trades: &[TradeRecord],
+        alerts: &[Alert],
+    ) -> Self {
+        let (from, to) = trading_days.widest_bounds(date);
+        let mut accounts: BTreeMap<String, AccountDailyReport> = BTreeMap::new();
+        let mut exits_by_account: HashMap<String, Vec<(DateTime<Utc>, Decimal)>> = HashMap::new();
+
+        for trade in trades {
+            let account_id = &trade.entry.account_id;
+            let (day_start, day_end) = trading_days.for_account(account_id).bounds(date);
+            let within = |at: DateTime<Utc>| at >= day_start && at < day_end;
+            if !within(trade.entry.opened_at) && !trade.exits.iter().any(|e| within(e.closed_at)) {
+                continue;
+            }
+            let report = account_entry(&mut accounts, trading_days, date, account_id);
+
+            if within(trade.entry.opened_at) {
+                report.trades_opened += 1;
+            }
+            if trade.status == TradeStatus::Closed && trade.closed_at.is_some_and(within) {
+                report.trades_closed += 1;
+                if trade.realized_pnl > Decimal::ZERO {
+                    report.wins += 1;
+                } else if trade.realized_pnl < Decimal::ZERO {
+                    report.losses += 1;
+                }
+            }
+
+            for exit in trade.exits.iter().filter(|e| within(e.closed_at)) {
+                report.net_pnl += exit.realized_pnl;
+                report.commission += exit.commission;
+                if exit.reason.is_exit_management() {
+                    let contribution = &mut report.exit_management;
+                    contribution.exits += 1;
+                    contribution.realized_pnl += exit.realized_pnl;
+                    *contribution.by_reason.entry(exit.reason).or_default() += exit.realized_pnl;
+                }
+                exits_by_account
+                    .entry(account_id.clone())
+                    .or_default()
+                    .push((exit.closed_at, exit.realized_pnl));
+            }
+        }
+
+        for alert in alerts {
+            let Some(account_id) = alert.account_id.map(|id| id.to_string()) else {
+                continue;
+            };
+            let (day_start, day_end) = trading_days.for_account(&account_id).bounds(date);
+            if alert.raised_at < day_start || alert.raised_at >= day_end {
+                continue;
+            }
+            let report = account_entry(&mut accounts, trading_days, date, &account_id);
+            *report
+                .risk_alerts
+                .entry(alert.alert_type.clone())
+                .or_default() += 1;
+        }
+
+        for report in accounts.values_mut() {
+            if report.trades_closed > 0 {
+                report.win_rate =
+                    Some(Decimal::from(report.wins) / Decimal::from(report.trades_closed));
+            }
+            if let Some(exits) = exits_by_account.get_mut(&report.account_id) {
+                exits.sort_by_key(|(closed_at, _)| *closed_at);
+                report.max_drawdown = max_drawdown(exits.iter().map(|(_, pnl)| *pnl));
+            }
+        }
+
+        Self {
+            date,
+            from,
+            to,
+            generated_at: Utc::now(),
+            accounts: accounts.into_values().collect(),
+        }
+    }
+
+    /// Tag each account's day with the deposits and withdrawals made during
+    /// it, adding accounts that did nothing else
+    pub fn tag_funding(&mut self, trading_days: &TradingDayConfig, events: &[FundingEvent]) {
+        let mut accounts: BTreeMap<String, AccountDailyReport> = self
+            .accounts
+            .drain(..)
+            .map(|report| (report.account_id.clone(), report))
+            .collect();
+        for event in events {
+            let (day_start, day_end) = trading_days
+                .for_account(&event.account_id)
+                .bounds(self.date);
+            if event.at < day_start || event.at >= day_end {
+                continue;
+            }
+            account_entry(&mut accounts, trading_days, self.date, &event.account_id)
+                .funding
+                .push(event.clone());
+        }
+        self.accounts = accounts.into_values().collect();
+    }
+
+    pub fn to_html(&self) -> String {
+        html::render(self)
+    }
+
+    /// One notification per account
+    pub fn notifications(&self) -> Vec<Notification> {
+        self.accounts
+            .iter()
+            .map(|account| {
+                Notification::new(
+                    NotificationTopic::DailySummary,
+                    format!("Daily summary {}", self.date),
+                    account.summary(),
+                )
+                .for_account(account.account_id.clone())
+            })
+            .collect()
+    }
+}
+
+fn account_entry<'a>(
+    accounts: &'a mut BTreeMap<String, AccountDailyReport>,
+    trading_days: &TradingDayConfig,
+    date: NaiveDate,
+    account_id: &str,
+) -> &'a mut AccountDailyReport {
+    accounts.entry(account_id.to_string()).or_insert_with(|| {
+        AccountDailyReport::new(
+            account_id,
+            trading_days.for_account(account_id).bounds(date),
+        )
+    })
+}
+
+/// Peak-to-trough fall of the running sum of `pnls`, starting from zero
+fn max_drawdown(pnls: impl Iterator<Item = Decimal>) -> Decimal {
+    let mut cumulative = Decimal::ZERO;
+    let mut peak = Decimal::ZERO;
+    let mut drawdown = Decimal::ZERO;
+    for pnl in pnls {
+        cumulative += pnl;
+        peak = peak.max(cumulative);
+        drawdown = drawdown.max(peak - cumulative);
+    }
+    drawdown
+}
+
+/// Compiles each trading day's report once every account has rolled over, writes it
+/// out as JSON and HTML and sends each account's digest through the notifier
+pub struct DailyReportGenerator {
+    config: ReportsConfig,
+    journal: Arc<TradeJournal>,
+    gateway: Option<Arc<AlertGateway>>,
+    notifier: Option<Arc<Notifier>>,
+    trading_days: TradingDayConfig,
+    funding: Option<Arc<FundingDetector>>,
+    last_published: Mutex<Option<NaiveDate>>,
+}
+
+impl DailyReportGenerator {
+    pub fn new(config: ReportsConfig, journal: Arc<TradeJournal>) -> Self {
+        Self {
+            config,
+            journal,
+            gateway: None,
+            notifier: None,
+            trading_days: TradingDayConfig::default(),
+            funding: None,
+            last_published: Mutex::new(None),
+        }
+    }
+
+    /// Report each account's trading day between its rollovers instead of UTC days
+    pub fn with_trading_days(mut self, trading_days: TradingDayConfig) -> Self {
+        self.trading_days = trading_days;
+        self
+    }
+
+    /// Count the risk alerts raised through `gateway`
+    pub fn with_alert_gateway(mut self, gateway: Arc<AlertGateway>) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Tag the days accounts were funded or withdrawn from
+    pub fn with_funding_detector(mut self, funding: Arc<FundingDetector>) -> Self {
+        self.funding = Some(funding);
+        self
+    }
+
+    pub async fn generate(&self, date: NaiveDate) -> DailyReport {
+        let (from, to) = self.trading_days.widest_bounds(date);
+        let trades = self.journal.trades_between(from, to).await;
+        let alerts = self
+            .gateway
+            .as_ref()
+            .map(|gateway| gateway.raised_between(from, to))
+            .unwrap_or_default();
+        let mut report = DailyReport::compile(date, &self.trading_days, &trades, &alerts);
+        if let Some(funding) = &self.funding {
+            let events = funding.events_between(None, from, to).await;
+            report.tag_funding(&self.trading_days, &events);
+        }
+        report
+    }
+
+    fn report_path(&self, date: NaiveDate, extension: &str) -> Option<PathBuf> {
+        self.config
+            .output_dir
+            .as_ref()
+            .map(|dir| Path::new(dir).join(format!("daily-{}.{}", date, extension)))
+    }
+
+    /// Write the report files and notify. Returns the notifications sent.
+    pub async fn publish(&self, report: &DailyReport) -> Result<usize> {
+        if let (Some(json_path), Some(html_path)) = (
+            self.report_path(report.date, "json"),
+            self.report_path(report.date, "html"),
+        ) {
+            if let Some(dir) = json_path.parent() {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+            tokio::fs::write(&json_path, serde_json::to_vec_pretty(report)?).await?;
+            tokio::fs::write(&html_path, report.to_html()).await?;
+        }
+
+        let mut sent = 0;
+        if let Some(notifier) = &self.notifier {
+            for notification in report.notifications() {
+                sent += notifier.notify(&notification).await;
+            }
+        }
+        info!(
+            "Published daily report for {} covering {} accounts",
+            report.date,
+            report.accounts.len()
+        );
+        Ok(sent)
+    }
+
+    /// Publish the report of the last trading day every account has finished, once the
+    /// publish delay has passed and unless this process, or one writing to the same
+    /// directory, already published it
+    pub async fn publish_due(&self, now: DateTime<Utc>) -> Result<Option<DailyReport>> {
+        let settled = now - ChronoDuration::minutes(self.config.publish_delay_mins as i64);
+        let Some(date) = self.trading_days.default.trading_day(settled).pred_opt() else {
+            return Ok(None);
+        };
+        // Accounts rolling over later than the default may still be trading that day
+        if self.trading_days.widest_bounds(date).1 > settled {
+            return Ok(None);
+        }
+
+        let already_published = *self.last_published.lock().unwrap() == Some(date)
+            || self
+                .report_path(date, "json")
+                .is_some_and(|path| path.exists());
+        if already_published {
+            return Ok(None);
+        }
+
+        let report = self.generate(date).await;
+        self.publish(&report).await?;
+        *self.last_published.lock().unwrap() = Some(date);
+        Ok(Some(report))
+    }
+}
+
+#[async_trait]
+impl Subsystem for DailyReportGenerator {
+    fn name(&self) -> &str {
+        "daily-reports"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_secs(self.config.check_interval_secs));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if let Err(e) = self.publish_due(Utc::now()).await {
+                        warn!("Failed to publish daily report: {}", e);
+                    }
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
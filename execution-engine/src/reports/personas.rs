@@ -0,0 +1,155 @@
+// Footprint of each account's closed trades in the journal, and how far apart the
+// footprints of every two accounts are, to check that personas make accounts
+// trade distinctly
+
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::execution::persona::PersonaConfig;
+use crate::journal::{ExitReason, TradeRecord};
+
+/// How an account trades, from its closed trades
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountFootprint {
+    pub account_id: String,
+    pub persona: Option<String>,
+    pub trades: usize,
+    /// Mean quantity of the entries
+    pub average_size: f64,
+    pub average_hold_mins: Option<f64>,
+    /// Share of trades that took a partial profit before closing
+    pub partial_take_rate: f64,
+}
+
+/// How far apart the footprints of two accounts are, from 0 for alike to 1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FootprintDivergence {
+    pub first: String,
+    pub second: String,
+    pub divergence: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonaDivergenceReport {
+    pub generated_at: DateTime<Utc>,
+    /// By account id
+    pub footprints: Vec<AccountFootprint>,
+    /// Every two accounts with enough trades, least divergent first
+    pub pairs: Vec<FootprintDivergence>,
+    pub min_divergence: f64,
+    /// Pairs below the minimum divergence
+    pub converged: Vec<FootprintDivergence>,
+}
+
+impl PersonaDivergenceReport {
+    /// Footprints of the closed trades of `trades`, each account compared with the
+    /// others once it has `report_min_trades`
+    pub fn compile(
+        trades: &[TradeRecord],
+        config: &PersonaConfig,
+        generated_at: DateTime<Utc>,
+    ) -> Self {
+        let mut by_account: BTreeMap<&str, Vec<&TradeRecord>> = BTreeMap::new();
+        for trade in trades.iter().filter(|t| !t.is_open()) {
+            by_account
+                .entry(trade.entry.account_id.as_str())
+                .or_default()
+                .push(trade);
+        }
+
+        let footprints: Vec<AccountFootprint> = by_account
+            .into_iter()
+            .map(|(account_id, trades)| footprint(account_id, &trades, config))
+            .collect();
+
+        let compared: Vec<&AccountFootprint> = footprints
+            .iter()
+            .filter(|f| f.trades >= config.report_min_trades)
+            .collect();
+        let mut pairs = Vec::new();
+        for (i, first) in compared.iter().enumerate() {
+            for second in &compared[i + 1..] {
+                pairs.push(FootprintDivergence {
+                    first: first.account_id.clone(),
+                    second: second.account_id.clone(),
+                    divergence: divergence(first, second),
+                });
+            }
+        }
+        pairs.sort_by(|a, b| a.divergence.total_cmp(&b.divergence));
+        let converged = pairs
+            .iter()
+            .filter(|p| p.divergence < config.min_divergence)
+            .cloned()
+            .collect();
+
+        Self {
+            generated_at,
+            footprints,
+            pairs,
+            min_divergence: config.min_divergence,
+            converged,
+        }
+    }
+
+    /// Whether every two accounts compared trade distinctly enough
+    pub fn diverged(&self) -> bool {
+        self.converged.is_empty()
+    }
+}
+
+fn footprint(
+    account_id: &str,
+    trades: &[&TradeRecord],
+    config: &PersonaConfig,
+) -> AccountFootprint {
+    let count = trades.len().max(1) as f64;
+    let holds: Vec<f64> = trades
+        .iter()
+        .filter_map(|t| t.holding_time_secs)
+        .map(|secs| secs as f64 / 60.0)
+        .collect();
+    AccountFootprint {
+        account_id: account_id.to_string(),
+        persona: config.accounts.get(account_id).cloned(),
+        trades: trades.len(),
+        average_size: trades
+            .iter()
+            .map(|t| t.entry.quantity.to_f64().unwrap_or(0.0))
+            .sum::<f64>()
+            / count,
+        average_hold_mins: (!holds.is_empty())
+            .then(|| holds.iter().sum::<f64>() / holds.len() as f64),
+        partial_take_rate: trades
+            .iter()
+            .filter(|t| {
+                t.exits
+                    .iter()
+                    .any(|e| e.reason == ExitReason::PartialProfit)
+            })
+            .count() as f64
+            / count,
+    }
+}
+
+/// Mean relative difference of the measures both footprints have
+fn divergence(first: &AccountFootprint, second: &AccountFootprint) -> f64 {
+    let relative = |a: f64, b: f64| {
+        let scale = a.abs().max(b.abs());
+        if scale == 0.0 {
+            0.0
+        } else {
+            (a - b).abs() / scale
+        }
+    };
+    let mut measures = vec![
+        relative(first.average_size, second.average_size),
+        (first.partial_take_rate - second.partial_take_rate).abs(),
+    ];
+    if let (Some(a), Some(b)) = (first.average_hold_mins, second.average_hold_mins) {
+        measures.push(relative(a, b));
+    }
+    measures.iter().sum::<f64>() / measures.len() as f64
+}
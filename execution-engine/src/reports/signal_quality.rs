@@ -0,0 +1,370 @@
+// Scores of each strategy or signal source from its closed trades in the
+// journal, and the allocation multiplier the orchestrator may size by
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Timelike, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::debug;
+
+use crate::execution::sizing::CONFIDENCE_TAG_PREFIX;
+use crate::journal::{TradeJournal, TradeRecord};
+use crate::runtime::supervisor::{ShutdownSignal, Subsystem};
+
+/// Tag prefixes trades are grouped by, the first one present winning
+const GROUP_TAGS: [&str; 2] = ["strategy:", "agent:"];
+/// Group of trades carrying neither a strategy nor an agent tag
+pub const UNTAGGED: &str = "untagged";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignalQualityConfig {
+    pub enabled: bool,
+    /// Trades closed this long ago or less are scored
+    pub lookback_days: i64,
+    /// Trades a strategy needs before it is scored
+    pub min_trades: usize,
+    /// Trades' worth of weight pulling each score towards zero, so a short
+    /// lucky streak does not score like a long record
+    pub shrinkage_trades: usize,
+    pub refresh_interval_secs: u64,
+    pub allocation: AllocationConfig,
+}
+
+impl Default for SignalQualityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            lookback_days: 90,
+            min_trades: 20,
+            shrinkage_trades: 20,
+            refresh_interval_secs: 300,
+            allocation: AllocationConfig::default(),
+        }
+    }
+}
+
+/// Scaling of position sizes by the score of the signal's strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AllocationConfig {
+    pub enabled: bool,
+    /// Size added per R of score, so 0.5 sizes a strategy scoring 1R at 1.5x
+    pub per_r: f64,
+    pub min_multiplier: f64,
+    pub max_multiplier: f64,
+}
+
+impl Default for AllocationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            per_r: 0.5,
+            min_multiplier: 0.5,
+            max_multiplier: 1.5,
+        }
+    }
+}
+
+impl SignalQualityConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.lookback_days <= 0 || self.refresh_interval_secs == 0 {
+            return Err(
+                "Signal quality lookback and refresh interval must be positive".to_string(),
+            );
+        }
+        let allocation = &self.allocation;
+        if allocation.min_multiplier < 0.0 || allocation.min_multiplier > allocation.max_multiplier
+        {
+            return Err(
+                "Allocation multipliers must satisfy 0 <= min_multiplier <= max_multiplier"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Results of the trades of one hour of the day or confidence bucket
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BucketPerformance {
+    pub trades: usize,
+    pub wins: usize,
+    pub realized_pnl: Decimal,
+    pub average_r: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyScore {
+    /// The `strategy:` or `agent:` tag of the trades, or `untagged`
+    pub strategy: String,
+    pub trades: usize,
+    pub wins: usize,
+    pub hit_rate: Decimal,
+    pub realized_pnl: Decimal,
+    /// Mean R multiple of the trades that had an initial stop
+    pub average_r: Option<Decimal>,
+    /// R lost per trade to one basis point of adverse entry slippage; tight
+    /// stops make it larger
+    pub r_per_slippage_bp: Option<Decimal>,
+    /// Entry slippage, in basis points, that would wipe out the average R
+    pub breakeven_slippage_bps: Option<Decimal>,
+    /// Performance by the UTC hour trades were opened in
+    pub by_hour: BTreeMap<u32, BucketPerformance>,
+    /// Average R shrunk towards zero by the number of trades; None below the
+    /// minimum trade count
+    pub score: Option<Decimal>,
+    /// Factor applied to position sizes of the strategy's signals
+    pub allocation_multiplier: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignalQualityReport {
+    pub generated_at: DateTime<Utc>,
+    pub from: DateTime<Utc>,
+    /// Best score first, unscored strategies last
+    pub strategies: Vec<StrategyScore>,
+    /// Results of all strategies by the confidence bucket their signals were
+    /// sized at, e.g. `0.8`, to check that confidence sizing pays
+    #[serde(default)]
+    pub by_confidence: BTreeMap<String, BucketPerformance>,
+}
+
+impl SignalQualityReport {
+    /// Score the closed trades of `trades`, each grouped by its strategy tag
+    pub fn compile(
+        trades: &[TradeRecord],
+        config: &SignalQualityConfig,
+        from: DateTime<Utc>,
+        generated_at: DateTime<Utc>,
+    ) -> Self {
+        let closed: Vec<&TradeRecord> = trades.iter().filter(|t| !t.is_open()).collect();
+        let mut groups: HashMap<String, Vec<&TradeRecord>> = HashMap::new();
+        for trade in closed.iter().copied() {
+            groups
+                .entry(strategy_of(&trade.entry.tags))
+                .or_default()
+                .push(trade);
+        }
+
+        let mut strategies: Vec<StrategyScore> = groups
+            .into_iter()
+            .map(|(strategy, trades)| score(strategy, &trades, config))
+            .collect();
+        strategies.sort_by(|a, b| {
+            b.score
+                .is_some()
+                .cmp(&a.score.is_some())
+                .then(b.score.cmp(&a.score))
+                .then(a.strategy.cmp(&b.strategy))
+        });
+
+        let by_confidence = buckets(&closed, |trade| {
+            trade
+                .entry
+                .tags
+                .iter()
+                .find_map(|tag| tag.strip_prefix(CONFIDENCE_TAG_PREFIX))
+                .map(str::to_string)
+        });
+
+        Self {
+            generated_at,
+            from,
+            strategies,
+            by_confidence,
+        }
+    }
+
+    pub fn strategy(&self, strategy: &str) -> Option<&StrategyScore> {
+        self.strategies.iter().find(|s| s.strategy == strategy)
+    }
+}
+
+/// The group a trade or signal with `tags` is scored in
+pub fn strategy_of(tags: &[String]) -> String {
+    GROUP_TAGS
+        .iter()
+        .find_map(|prefix| tags.iter().find(|tag| tag.starts_with(prefix)))
+        .cloned()
+        .unwrap_or_else(|| UNTAGGED.to_string())
+}
+
+fn average(values: &[Decimal]) -> Option<Decimal> {
+    (!values.is_empty()).then(|| values.iter().sum::<Decimal>() / Decimal::from(values.len()))
+}
+
+/// Performance of `trades` grouped by `key`, leaving out those without one
+fn buckets<K: Ord>(
+    trades: &[&TradeRecord],
+    key: impl Fn(&TradeRecord) -> Option<K>,
+) -> BTreeMap<K, BucketPerformance> {
+    let mut buckets: BTreeMap<K, (BucketPerformance, Vec<Decimal>)> = BTreeMap::new();
+    for trade in trades {
+        let Some(key) = key(trade) else {
+            continue;
+        };
+        let (bucket, r) = buckets.entry(key).or_default();
+        bucket.trades += 1;
+        if trade.realized_pnl > Decimal::ZERO {
+            bucket.wins += 1;
+        }
+        bucket.realized_pnl += trade.realized_pnl;
+        r.extend(trade.r_multiple);
+    }
+    buckets
+        .into_iter()
+        .map(|(key, (mut bucket, r))| {
+            bucket.average_r = average(&r);
+            (key, bucket)
+        })
+        .collect()
+}
+
+fn score(strategy: String, trades: &[&TradeRecord], config: &SignalQualityConfig) -> StrategyScore {
+    let wins = trades
+        .iter()
+        .filter(|t| t.realized_pnl > Decimal::ZERO)
+        .count();
+    let r_multiples: Vec<Decimal> = trades.iter().filter_map(|t| t.r_multiple).collect();
+    let average_r = average(&r_multiples);
+
+    // A basis point of the entry price, as a share of the distance to the stop
+    let slippage_r: Vec<Decimal> = trades
+        .iter()
+        .filter_map(|t| {
+            let stop_distance = (t.entry.entry_price - t.entry.stop_loss?).abs();
+            (!stop_distance.is_zero())
+                .then(|| t.entry.entry_price * Decimal::new(1, 4) / stop_distance)
+        })
+        .collect();
+    let r_per_slippage_bp = average(&slippage_r);
+    let breakeven_slippage_bps = average_r
+        .zip(r_per_slippage_bp)
+        .filter(|(r, per_bp)| *r > Decimal::ZERO && !per_bp.is_zero())
+        .map(|(r, per_bp)| r / per_bp);
+
+    let by_hour = buckets(trades, |trade| Some(trade.entry.opened_at.hour()));
+
+    let score = average_r
+        .filter(|_| trades.len() >= config.min_trades.max(1))
+        .map(|r| {
+            r * Decimal::from(r_multiples.len())
+                / Decimal::from(r_multiples.len() + config.shrinkage_trades)
+        });
+    let allocation = &config.allocation;
+    let allocation_multiplier = match score.and_then(|s| s.to_f64()) {
+        Some(score) if allocation.enabled => (1.0 + score * allocation.per_r)
+            .clamp(allocation.min_multiplier, allocation.max_multiplier),
+        _ => 1.0,
+    };
+
+    StrategyScore {
+        strategy,
+        trades: trades.len(),
+        wins,
+        hit_rate: Decimal::from(wins) / Decimal::from(trades.len()),
+        realized_pnl: trades.iter().map(|t| t.realized_pnl).sum(),
+        average_r,
+        r_per_slippage_bp,
+        breakeven_slippage_bps,
+        by_hour,
+        score,
+        allocation_multiplier,
+    }
+}
+
+/// The latest scores, shared by the scoring job, the API and the orchestrator
+pub struct StrategyScores {
+    config: SignalQualityConfig,
+    report: RwLock<Option<SignalQualityReport>>,
+}
+
+impl StrategyScores {
+    pub fn new(config: SignalQualityConfig) -> Self {
+        Self {
+            config,
+            report: RwLock::new(None),
+        }
+    }
+
+    pub fn config(&self) -> &SignalQualityConfig {
+        &self.config
+    }
+
+    /// Score the closed trades among `trades`, over the lookback ending `now`
+    pub fn update(&self, trades: &[TradeRecord], now: DateTime<Utc>) -> SignalQualityReport {
+        let from = now - ChronoDuration::days(self.config.lookback_days);
+        let report = SignalQualityReport::compile(trades, &self.config, from, now);
+        *self.report.write().unwrap() = Some(report.clone());
+        report
+    }
+
+    /// None until the first scoring
+    pub fn report(&self) -> Option<SignalQualityReport> {
+        self.report.read().unwrap().clone()
+    }
+
+    /// Factor for the sizes of a signal tagged `tags`; 1 unless allocation is
+    /// enabled and the signal's strategy is scored
+    pub fn allocation_multiplier(&self, tags: &[String]) -> f64 {
+        if !self.config.allocation.enabled {
+            return 1.0;
+        }
+        let strategy = strategy_of(tags);
+        self.report
+            .read()
+            .unwrap()
+            .as_ref()
+            .and_then(|report| report.strategy(&strategy))
+            .map_or(1.0, |score| score.allocation_multiplier)
+    }
+}
+
+/// Rescores the journal's trades periodically
+pub struct SignalQualityJob {
+    scores: Arc<StrategyScores>,
+    journal: Arc<TradeJournal>,
+}
+
+impl SignalQualityJob {
+    pub fn new(scores: Arc<StrategyScores>, journal: Arc<TradeJournal>) -> Self {
+        Self { scores, journal }
+    }
+
+    pub async fn refresh(&self, now: DateTime<Utc>) -> SignalQualityReport {
+        let from = now - ChronoDuration::days(self.scores.config.lookback_days);
+        let trades = self.journal.trades_between(from, now).await;
+        let report = self.scores.update(&trades, now);
+        debug!("Scored {} strategies", report.strategies.len());
+        report
+    }
+}
+
+#[async_trait]
+impl Subsystem for SignalQualityJob {
+    fn name(&self) -> &str {
+        "signal-quality"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let interval_secs = self.scores.config.refresh_interval_secs;
+        let mut ticker = interval(Duration::from_secs(interval_secs));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    self.refresh(Utc::now()).await;
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
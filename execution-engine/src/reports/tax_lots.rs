@@ -0,0 +1,429 @@
+// Realized P&L by tax lot: fills replayed from the position ledger, each opening
+// a lot that later closes are matched against first in, first out, per account
+// and instrument
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::ledger::{LedgerEvent, LedgerEventKind, LedgerState};
+use crate::platforms::abstraction::models::UnifiedPositionSide;
+
+/// Quantity still open from one opening fill
+#[derive(Debug, Clone, PartialEq)]
+struct OpenLot {
+    quantity: Decimal,
+    price: Decimal,
+    /// Opening commission left on the open quantity
+    commission: Decimal,
+    opened_at: DateTime<Utc>,
+}
+
+/// Part of a lot closed by one fill
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RealizedLot {
+    pub account_id: String,
+    pub symbol: String,
+    /// Side of the lot: long lots are bought then sold, short lots the reverse
+    pub side: UnifiedPositionSide,
+    pub quantity: Decimal,
+    /// Unset when the ledger never saw the lot open
+    pub opened_at: Option<DateTime<Utc>>,
+    pub open_price: Option<Decimal>,
+    pub closed_at: DateTime<Utc>,
+    pub close_price: Decimal,
+    /// Opening and closing commission on the quantity
+    pub commission: Decimal,
+    /// Net of commission
+    pub realized_pnl: Decimal,
+}
+
+impl RealizedLot {
+    /// What the lot cost: the buy side, commission included
+    pub fn cost_basis(&self) -> Option<Decimal> {
+        let open = self.open_price?;
+        let buy = match self.side {
+            UnifiedPositionSide::Long => open,
+            UnifiedPositionSide::Short => self.close_price,
+        };
+        Some(buy * self.quantity + self.commission)
+    }
+
+    /// What the lot was sold for
+    pub fn proceeds(&self) -> Option<Decimal> {
+        let open = self.open_price?;
+        let sell = match self.side {
+            UnifiedPositionSide::Long => self.close_price,
+            UnifiedPositionSide::Short => open,
+        };
+        Some(sell * self.quantity)
+    }
+}
+
+/// Open lots of every account and instrument, in the order they opened
+#[derive(Debug, Default)]
+struct LotBook {
+    lots: HashMap<(String, String, UnifiedPositionSide), VecDeque<OpenLot>>,
+    realized: Vec<RealizedLot>,
+}
+
+impl LotBook {
+    fn open(&mut self, account_id: &str, symbol: &str, side: &UnifiedPositionSide, lot: OpenLot) {
+        if lot.quantity.is_zero() {
+            return;
+        }
+        self.lots
+            .entry((account_id.to_string(), symbol.to_string(), side.clone()))
+            .or_default()
+            .push_back(lot);
+    }
+
+    /// Match `quantity` closed at `price` against the oldest lots. Quantity no
+    /// lot covers is realized as `unmatched_pnl`, the P&L reported for it.
+    #[allow(clippy::too_many_arguments)]
+    fn close(
+        &mut self,
+        account_id: &str,
+        symbol: &str,
+        side: &UnifiedPositionSide,
+        quantity: Decimal,
+        price: Decimal,
+        commission: Decimal,
+        closed_at: DateTime<Utc>,
+        unmatched_pnl: Decimal,
+    ) {
+        if quantity.is_zero() {
+            return;
+        }
+        let lots = self
+            .lots
+            .entry((account_id.to_string(), symbol.to_string(), side.clone()))
+            .or_default();
+        let mut remaining = quantity;
+        while !remaining.is_zero() {
+            let Some(lot) = lots.front_mut() else { break };
+            let matched = remaining.min(lot.quantity);
+            let opening_commission = lot.commission * matched / lot.quantity;
+            let closing_commission = commission * matched / quantity;
+            let gain = match side {
+                UnifiedPositionSide::Long => price - lot.price,
+                UnifiedPositionSide::Short => lot.price - price,
+            } * matched;
+            self.realized.push(RealizedLot {
+                account_id: account_id.to_string(),
+                symbol: symbol.to_string(),
+                side: side.clone(),
+                quantity: matched,
+                opened_at: Some(lot.opened_at),
+                open_price: Some(lot.price),
+                closed_at,
+                close_price: price,
+                commission: opening_commission + closing_commission,
+                realized_pnl: gain - opening_commission - closing_commission,
+            });
+            lot.quantity -= matched;
+            lot.commission -= opening_commission;
+            remaining -= matched;
+            if lot.quantity.is_zero() {
+                lots.pop_front();
+            }
+        }
+
+        if !remaining.is_zero() {
+            let closing_commission = commission * remaining / quantity;
+            self.realized.push(RealizedLot {
+                account_id: account_id.to_string(),
+                symbol: symbol.to_string(),
+                side: side.clone(),
+                quantity: remaining,
+                opened_at: None,
+                open_price: None,
+                closed_at,
+                close_price: price,
+                commission: closing_commission,
+                realized_pnl: unmatched_pnl,
+            });
+        }
+    }
+
+    /// Take `quantity` off the oldest lots without realizing anything, as a
+    /// reconciliation correction does
+    fn remove(
+        &mut self,
+        account_id: &str,
+        symbol: &str,
+        side: &UnifiedPositionSide,
+        quantity: Decimal,
+    ) {
+        let Some(lots) =
+            self.lots
+                .get_mut(&(account_id.to_string(), symbol.to_string(), side.clone()))
+        else {
+            return;
+        };
+        let mut remaining = quantity;
+        while !remaining.is_zero() {
+            let Some(lot) = lots.front_mut() else { break };
+            let removed = remaining.min(lot.quantity);
+            lot.commission -= lot.commission * removed / lot.quantity;
+            lot.quantity -= removed;
+            remaining -= removed;
+            if lot.quantity.is_zero() {
+                lots.pop_front();
+            }
+        }
+    }
+
+    fn apply(&mut self, state: &LedgerState, event: &LedgerEvent) {
+        let account_id = event.account_id.as_str();
+        let position = state.position(account_id, event.kind.position_id());
+        match &event.kind {
+            LedgerEventKind::Fill {
+                symbol,
+                side,
+                quantity,
+                price,
+                commission,
+                ..
+            } => {
+                if quantity.is_zero() {
+                    return;
+                }
+                let mut opening = *quantity;
+                if let Some(position) = position.filter(|p| p.side != *side) {
+                    let closed = (*quantity).min(position.quantity);
+                    let closing_commission = *commission * closed / *quantity;
+                    self.close(
+                        account_id,
+                        symbol,
+                        &position.side,
+                        closed,
+                        *price,
+                        closing_commission,
+                        event.timestamp,
+                        -closing_commission,
+                    );
+                    opening -= closed;
+                }
+                self.open(
+                    account_id,
+                    symbol,
+                    side,
+                    OpenLot {
+                        quantity: opening,
+                        price: *price,
+                        commission: *commission * opening / *quantity,
+                        opened_at: event.timestamp,
+                    },
+                );
+            }
+            LedgerEventKind::Closed {
+                quantity,
+                price,
+                commission,
+                reported_pnl,
+                ..
+            } => match position {
+                Some(position) => self.close(
+                    account_id,
+                    &position.symbol,
+                    &position.side,
+                    (*quantity).min(position.quantity),
+                    *price,
+                    *commission,
+                    event.timestamp,
+                    -*commission,
+                ),
+                None => self.realized.push(RealizedLot {
+                    account_id: account_id.to_string(),
+                    symbol: String::new(),
+                    side: UnifiedPositionSide::Long,
+                    quantity: *quantity,
+                    opened_at: None,
+                    open_price: None,
+                    closed_at: event.timestamp,
+                    close_price: *price,
+                    commission: *commission,
+                    realized_pnl: *reported_pnl,
+                }),
+            },
+            LedgerEventKind::Adjusted {
+                symbol,
+                side,
+                quantity,
+                price,
+                ..
+            } => {
+                let mut held = Decimal::ZERO;
+                if let Some(position) = position {
+                    if position.side == *side {
+                        held = position.quantity;
+                    } else {
+                        self.remove(
+                            account_id,
+                            &position.symbol,
+                            &position.side,
+                            position.quantity,
+                        );
+                    }
+                }
+                if *quantity > held {
+                    self.open(
+                        account_id,
+                        symbol,
+                        side,
+                        OpenLot {
+                            quantity: *quantity - held,
+                            price: *price,
+                            commission: Decimal::ZERO,
+                            opened_at: event.timestamp,
+                        },
+                    );
+                } else {
+                    self.remove(account_id, symbol, side, held - *quantity);
+                }
+            }
+            LedgerEventKind::Modified { .. } => {}
+        }
+    }
+}
+
+/// Every lot closed by `events`, replayed in ledger order
+pub fn realized_lots<'a>(events: impl IntoIterator<Item = &'a LedgerEvent>) -> Vec<RealizedLot> {
+    let mut events: Vec<&LedgerEvent> = events.into_iter().collect();
+    events.sort_by_key(|e| e.sequence);
+    let mut state = LedgerState::default();
+    let mut book = LotBook::default();
+    for event in events {
+        book.apply(&state, event);
+        state.apply(event);
+    }
+    book.realized
+}
+
+/// Which closes a report covers; the period runs from `from` up to, not
+/// including, `to`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaxLotQuery {
+    pub account_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl TaxLotQuery {
+    pub fn matches(&self, lot: &RealizedLot) -> bool {
+        self.account_id
+            .as_ref()
+            .map_or(true, |account_id| lot.account_id == *account_id)
+            && self.from.map_or(true, |from| lot.closed_at >= from)
+            && self.to.map_or(true, |to| lot.closed_at < to)
+    }
+}
+
+/// Realized P&L of one instrument on one account over the period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstrumentRealized {
+    pub account_id: String,
+    pub symbol: String,
+    pub lots: usize,
+    pub quantity: Decimal,
+    pub commission: Decimal,
+    pub realized_pnl: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxLotReport {
+    pub query: TaxLotQuery,
+    pub lots: Vec<RealizedLot>,
+    pub instruments: Vec<InstrumentRealized>,
+    /// Realized P&L by account
+    pub accounts: BTreeMap<String, Decimal>,
+    pub realized_pnl: Decimal,
+}
+
+const CSV_HEADER: &str = "account_id,symbol,side,quantity,opened_at,open_price,closed_at,\
+close_price,cost_basis,proceeds,commission,realized_pnl";
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+impl TaxLotReport {
+    /// Replay the whole ledger, since lots closed in the period may have opened
+    /// before it, and keep the closes the query covers
+    pub fn compile<'a>(
+        events: impl IntoIterator<Item = &'a LedgerEvent>,
+        query: TaxLotQuery,
+    ) -> Self {
+        let lots: Vec<RealizedLot> = realized_lots(events)
+            .into_iter()
+            .filter(|lot| query.matches(lot))
+            .collect();
+
+        let mut instruments: BTreeMap<(String, String), InstrumentRealized> = BTreeMap::new();
+        let mut accounts: BTreeMap<String, Decimal> = BTreeMap::new();
+        for lot in &lots {
+            let instrument = instruments
+                .entry((lot.account_id.clone(), lot.symbol.clone()))
+                .or_insert_with(|| InstrumentRealized {
+                    account_id: lot.account_id.clone(),
+                    symbol: lot.symbol.clone(),
+                    lots: 0,
+                    quantity: Decimal::ZERO,
+                    commission: Decimal::ZERO,
+                    realized_pnl: Decimal::ZERO,
+                });
+            instrument.lots += 1;
+            instrument.quantity += lot.quantity;
+            instrument.commission += lot.commission;
+            instrument.realized_pnl += lot.realized_pnl;
+            *accounts.entry(lot.account_id.clone()).or_default() += lot.realized_pnl;
+        }
+
+        Self {
+            query,
+            realized_pnl: lots.iter().map(|lot| lot.realized_pnl).sum(),
+            lots,
+            instruments: instruments.into_values().collect(),
+            accounts,
+        }
+    }
+
+    /// One row per closed lot, times in RFC 3339 UTC
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(CSV_HEADER);
+        csv.push('\n');
+        for lot in &self.lots {
+            let side = match lot.side {
+                UnifiedPositionSide::Long => "long",
+                UnifiedPositionSide::Short => "short",
+            };
+            let row = [
+                csv_field(&lot.account_id),
+                csv_field(&lot.symbol),
+                side.to_string(),
+                lot.quantity.to_string(),
+                optional(lot.opened_at.map(|at| at.to_rfc3339())),
+                optional(lot.open_price),
+                lot.closed_at.to_rfc3339(),
+                lot.close_price.to_string(),
+                optional(lot.cost_basis()),
+                optional(lot.proceeds()),
+                lot.commission.to_string(),
+                lot.realized_pnl.to_string(),
+            ];
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
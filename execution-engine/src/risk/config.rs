@@ -3,12 +3,22 @@ use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::deleveraging::DeleveragingConfig;
+use super::drawdown_ladder::DrawdownLadderConfig;
+use super::portfolio_exposure::ExposureCluster;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskConfig {
     pub margin_thresholds: MarginThresholds,
     pub drawdown_thresholds: DrawdownThresholds,
     pub exposure_limits: ExposureLimits,
     pub risk_response_config: RiskResponseConfig,
+    /// Risk scaled down in steps as drawdown deepens, ahead of the lockout thresholds
+    #[serde(default)]
+    pub drawdown_ladder: DrawdownLadderConfig,
+    /// Order in which margin protection cuts positions, and how far
+    #[serde(default)]
+    pub deleveraging: DeleveragingConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +27,10 @@ pub struct MarginThresholds {
     pub critical_level: Decimal,
     pub stop_out_level: Decimal,
     pub monitoring_interval_secs: u64,
+    /// Drop accounts from plans whose combined sizes would take their margin
+    /// level below `warning_level`
+    #[serde(default)]
+    pub pre_trade_gate: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +39,10 @@ pub struct DrawdownThresholds {
     pub weekly_threshold: Decimal,
     pub max_threshold: Decimal,
     pub recovery_factor_threshold: Decimal,
+    /// Largest drawdown, in percent of the account's equity high-water mark,
+    /// before the account is locked out; unset leaves trailing drawdown unchecked
+    #[serde(default)]
+    pub trailing_threshold: Option<Decimal>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +52,9 @@ pub struct ExposureLimits {
     pub concentration_hhi_threshold: Decimal,
     pub pair_limits: HashMap<String, Decimal>,
     pub currency_limits: HashMap<String, Decimal>,
+    /// Combined caps on correlated symbols across every account
+    #[serde(default)]
+    pub clusters: Vec<ExposureCluster>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,12 +88,14 @@ impl Default for RiskConfig {
                 critical_level: dec!(120),
                 stop_out_level: dec!(100),
                 monitoring_interval_secs: 1,
+                pre_trade_gate: false,
             },
             drawdown_thresholds: DrawdownThresholds {
                 daily_threshold: dec!(5),
                 weekly_threshold: dec!(10),
                 max_threshold: dec!(20),
                 recovery_factor_threshold: dec!(2),
+                trailing_threshold: None,
             },
             exposure_limits: ExposureLimits {
                 max_exposure_per_symbol: dec!(25),
@@ -80,6 +103,7 @@ impl Default for RiskConfig {
                 concentration_hhi_threshold: dec!(0.25),
                 pair_limits,
                 currency_limits,
+                clusters: Vec::new(),
             },
             risk_response_config: RiskResponseConfig {
                 enable_automated_responses: true,
@@ -88,6 +112,8 @@ impl Default for RiskConfig {
                 circuit_breaker_enabled: true,
                 escalation_delay_minutes: 5,
             },
+            drawdown_ladder: DrawdownLadderConfig::default(),
+            deleveraging: DeleveragingConfig::default(),
         }
     }
 }
@@ -178,7 +204,8 @@ impl RiskConfig {
             return Err("Max exposure per symbol must be between 0% and 100%".to_string());
         }
 
-        Ok(())
+        self.drawdown_ladder.validate()?;
+        self.deleveraging.validate()
     }
 }
 
@@ -0,0 +1,213 @@
+// Which positions margin protection cuts first, and how far. Positions are
+// ranked by an ordered list of priorities, later ones breaking the ties of
+// earlier ones, and cut in that order until the account's margin level is
+// projected back at the target. Equity is taken as unchanged by a cut: closing
+// realises the unrealised P&L rather than adding to it.
+
+use chrono::{DateTime, Utc};
+use risk_types::{MarginInfo, Position, PositionId};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleveragePriority {
+    /// Deepest unrealised loss first
+    LargestLoser,
+    /// Lowest entry confidence first; positions without one count as the least convinced
+    LowestConviction,
+    /// Most margin held first
+    HighestMarginUsage,
+    /// Earliest opened first
+    Oldest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeleveragingConfig {
+    /// Ranking criteria, most important first
+    pub priorities: Vec<DeleveragePriority>,
+    /// Margin level, in percent, the plan cuts back to
+    pub target_margin_level: Decimal,
+    /// Close only the part of the last position needed to reach the target
+    pub partial_final_step: bool,
+}
+
+impl Default for DeleveragingConfig {
+    fn default() -> Self {
+        Self {
+            priorities: vec![
+                DeleveragePriority::LargestLoser,
+                DeleveragePriority::HighestMarginUsage,
+                DeleveragePriority::Oldest,
+            ],
+            target_margin_level: dec!(200),
+            partial_final_step: true,
+        }
+    }
+}
+
+impl DeleveragingConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.priorities.is_empty() {
+            return Err("Deleveraging needs at least one priority".to_string());
+        }
+        for (i, priority) in self.priorities.iter().enumerate() {
+            if self.priorities[..i].contains(priority) {
+                return Err(format!(
+                    "Deleveraging priority {:?} is listed twice",
+                    priority
+                ));
+            }
+        }
+        if self.target_margin_level <= dec!(0) {
+            return Err(format!(
+                "Deleveraging target_margin_level must be positive, not {}",
+                self.target_margin_level
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A position as the ranking sees it
+#[derive(Debug, Clone)]
+pub struct DeleverageCandidate {
+    pub position: Position,
+    pub margin: Decimal,
+    pub conviction: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReductionStep {
+    pub rank: usize,
+    pub position_id: PositionId,
+    pub symbol: String,
+    pub unrealized_pnl: Decimal,
+    pub conviction: Option<Decimal>,
+    pub opened_at: DateTime<Utc>,
+    /// All of the position's size unless this is a partial final step
+    pub size_to_close: Decimal,
+    pub margin_freed: Decimal,
+    pub cumulative_margin_freed: Decimal,
+    /// None once no margin is left in use
+    pub projected_margin_level: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReductionPlan {
+    pub current_margin_level: Decimal,
+    pub target_margin_level: Decimal,
+    /// Margin that has to be freed to reach the target
+    pub margin_to_free: Decimal,
+    pub steps: Vec<ReductionStep>,
+    /// False when cutting every position still leaves the account short of the target
+    pub reaches_target: bool,
+}
+
+impl ReductionPlan {
+    pub fn total_margin_freed(&self) -> Decimal {
+        self.steps
+            .last()
+            .map(|s| s.cumulative_margin_freed)
+            .unwrap_or_default()
+    }
+}
+
+/// Orders the candidates most-to-cut first. Every priority compares in the
+/// direction that puts the position to cut first; positions still tied after
+/// all of them are ordered by id, so the plan is stable.
+pub fn rank_candidates(priorities: &[DeleveragePriority], candidates: &mut [DeleverageCandidate]) {
+    candidates.sort_by(|a, b| {
+        priorities
+            .iter()
+            .map(|priority| compare(*priority, a, b))
+            .find(|ordering| *ordering != Ordering::Equal)
+            .unwrap_or_else(|| a.position.id.cmp(&b.position.id))
+    });
+}
+
+fn compare(
+    priority: DeleveragePriority,
+    a: &DeleverageCandidate,
+    b: &DeleverageCandidate,
+) -> Ordering {
+    match priority {
+        DeleveragePriority::LargestLoser => pnl(a).cmp(&pnl(b)),
+        DeleveragePriority::LowestConviction => a.conviction.cmp(&b.conviction),
+        DeleveragePriority::HighestMarginUsage => b.margin.cmp(&a.margin),
+        DeleveragePriority::Oldest => a.position.opened_at.cmp(&b.position.opened_at),
+    }
+}
+
+fn pnl(candidate: &DeleverageCandidate) -> Decimal {
+    candidate.position.unrealized_pnl.unwrap_or_default()
+}
+
+fn margin_level(equity: Decimal, used_margin: Decimal) -> Option<Decimal> {
+    (used_margin > Decimal::ZERO).then(|| equity / used_margin * dec!(100))
+}
+
+/// Ranks the candidates and cuts down the ranking until the projected margin
+/// level is back at the target. An account already at the target gets an
+/// empty plan.
+pub fn plan_reduction(
+    config: &DeleveragingConfig,
+    margin_info: &MarginInfo,
+    mut candidates: Vec<DeleverageCandidate>,
+) -> ReductionPlan {
+    rank_candidates(&config.priorities, &mut candidates);
+
+    let target = config.target_margin_level;
+    // Used margin at which equity sits exactly at the target level
+    let allowed_margin = (margin_info.equity * dec!(100) / target).max(Decimal::ZERO);
+    let margin_to_free = (margin_info.used_margin - allowed_margin).max(Decimal::ZERO);
+
+    let mut steps = Vec::new();
+    let mut freed = Decimal::ZERO;
+    for candidate in candidates {
+        let remaining = margin_to_free - freed;
+        if remaining <= Decimal::ZERO {
+            break;
+        }
+        if candidate.margin <= Decimal::ZERO {
+            continue;
+        }
+        let (size_to_close, margin_freed) =
+            if config.partial_final_step && candidate.margin > remaining {
+                // Whole units only, rounded up so the cut still reaches the target
+                let size = (candidate.position.size * remaining / candidate.margin)
+                    .ceil()
+                    .min(candidate.position.size);
+                (size, candidate.margin * size / candidate.position.size)
+            } else {
+                (candidate.position.size, candidate.margin)
+            };
+        freed += margin_freed;
+        steps.push(ReductionStep {
+            rank: steps.len() + 1,
+            position_id: candidate.position.id,
+            symbol: candidate.position.symbol.clone(),
+            unrealized_pnl: pnl(&candidate),
+            conviction: candidate.conviction,
+            opened_at: candidate.position.opened_at,
+            size_to_close,
+            margin_freed,
+            cumulative_margin_freed: freed,
+            projected_margin_level: margin_level(
+                margin_info.equity,
+                margin_info.used_margin - freed,
+            ),
+        });
+    }
+
+    ReductionPlan {
+        current_margin_level: margin_info.margin_level,
+        target_margin_level: target,
+        margin_to_free,
+        steps,
+        reaches_target: freed >= margin_to_free,
+    }
+}
@@ -0,0 +1,374 @@
+// Drawdown ladder: rather than locking an account out at one drawdown limit, each
+// rung its daily or total drawdown crosses cuts the risk per trade, the positions it
+// may hold and how long exits give trades, and the rungs are climbed back one at a
+// time once the drawdown has stayed clear of them for a while
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use dashmap::DashMap;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::execution::exit_management::ExitPolicy;
+
+/// Prefix of the names of exit policies tightened by the ladder
+pub const DRAWDOWN_RUNG_POLICY_PREFIX: &str = "drawdown-rung-";
+
+/// One step down the ladder, reached once either drawdown crosses its trigger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrawdownRung {
+    /// Daily drawdown, in percent, that reaches the rung; unset leaves it to the
+    /// total drawdown
+    #[serde(default)]
+    pub daily_drawdown_pct: Option<Decimal>,
+    /// Drawdown from the account's peak equity, in percent, that reaches the rung
+    #[serde(default)]
+    pub total_drawdown_pct: Option<Decimal>,
+    /// Factor the risk per trade is scaled by
+    pub risk_multiplier: Decimal,
+    /// Most positions the account may hold; unset keeps the usual limit
+    #[serde(default)]
+    pub max_positions: Option<usize>,
+    /// Factor hold times, break-even triggers, trails and profit targets are
+    /// scaled by, 1 leaving exits as they are
+    pub exit_tightening: Decimal,
+}
+
+impl DrawdownRung {
+    fn crossed(&self, daily_pct: Decimal, total_pct: Decimal) -> bool {
+        self.daily_drawdown_pct.is_some_and(|t| daily_pct >= t)
+            || self.total_drawdown_pct.is_some_and(|t| total_pct >= t)
+    }
+
+    /// Whether both drawdowns are at least `margin` below the triggers
+    fn cleared(&self, daily_pct: Decimal, total_pct: Decimal, margin: Decimal) -> bool {
+        self.daily_drawdown_pct
+            .is_none_or(|t| daily_pct < t - margin)
+            && self
+                .total_drawdown_pct
+                .is_none_or(|t| total_pct < t - margin)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DrawdownLadderConfig {
+    /// Off, drawdown only answers through the lockout thresholds
+    pub enabled: bool,
+    /// Rungs from the shallowest down
+    pub rungs: Vec<DrawdownRung>,
+    /// Points below a rung's triggers the drawdowns must fall to count as recovering
+    pub recovery_margin_pct: Decimal,
+    /// Minutes the drawdowns must stay recovered before the account climbs a rung
+    pub recovery_mins: u64,
+    /// Seconds between measurements of each account's drawdowns
+    pub assessment_interval_secs: u64,
+}
+
+impl Default for DrawdownLadderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rungs: vec![
+                DrawdownRung {
+                    daily_drawdown_pct: Some(dec!(2)),
+                    total_drawdown_pct: Some(dec!(5)),
+                    risk_multiplier: dec!(0.75),
+                    max_positions: Some(2),
+                    exit_tightening: dec!(0.8),
+                },
+                DrawdownRung {
+                    daily_drawdown_pct: Some(dec!(3)),
+                    total_drawdown_pct: Some(dec!(7)),
+                    risk_multiplier: dec!(0.5),
+                    max_positions: Some(1),
+                    exit_tightening: dec!(0.6),
+                },
+                DrawdownRung {
+                    daily_drawdown_pct: Some(dec!(4)),
+                    total_drawdown_pct: Some(dec!(9)),
+                    risk_multiplier: dec!(0.25),
+                    max_positions: Some(1),
+                    exit_tightening: dec!(0.5),
+                },
+            ],
+            recovery_margin_pct: dec!(0.5),
+            recovery_mins: 60,
+            assessment_interval_secs: 60,
+        }
+    }
+}
+
+impl DrawdownLadderConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.recovery_margin_pct < dec!(0) {
+            return Err(format!(
+                "Drawdown ladder recovery_margin_pct must not be negative, not {}",
+                self.recovery_margin_pct
+            ));
+        }
+        if self.assessment_interval_secs == 0 {
+            return Err("Drawdown ladder assessment_interval_secs must be above 0".to_string());
+        }
+        let unit = dec!(0)..=dec!(1);
+        for (i, rung) in self.rungs.iter().enumerate() {
+            let number = i + 1;
+            if rung.daily_drawdown_pct.is_none() && rung.total_drawdown_pct.is_none() {
+                return Err(format!(
+                    "Drawdown rung {} needs a daily or total drawdown trigger",
+                    number
+                ));
+            }
+            let triggers = [rung.daily_drawdown_pct, rung.total_drawdown_pct];
+            if triggers
+                .iter()
+                .flatten()
+                .any(|t| *t <= dec!(0) || *t >= dec!(100))
+            {
+                return Err(format!(
+                    "Drawdown rung {} triggers must be between 0% and 100%",
+                    number
+                ));
+            }
+            if !unit.contains(&rung.risk_multiplier)
+                || rung.risk_multiplier == dec!(0)
+                || !unit.contains(&rung.exit_tightening)
+                || rung.exit_tightening == dec!(0)
+            {
+                return Err(format!(
+                    "Drawdown rung {} risk_multiplier and exit_tightening must be above 0 and at most 1",
+                    number
+                ));
+            }
+            if rung.max_positions == Some(0) {
+                return Err(format!(
+                    "Drawdown rung {} must allow at least 1 position",
+                    number
+                ));
+            }
+            if let Some(previous) = i.checked_sub(1).map(|p| &self.rungs[p]) {
+                let deeper = |shallow: Option<Decimal>, deep: Option<Decimal>| match (shallow, deep)
+                {
+                    (Some(shallow), Some(deep)) => deep > shallow,
+                    _ => true,
+                };
+                if !deeper(previous.daily_drawdown_pct, rung.daily_drawdown_pct)
+                    || !deeper(previous.total_drawdown_pct, rung.total_drawdown_pct)
+                {
+                    return Err(format!(
+                        "Drawdown rung {} triggers must be deeper than rung {}",
+                        number, i
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The risk an account is held to on its rung
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskLevel {
+    /// 0 is normal trading, 1 the first rung down
+    pub rung: usize,
+    pub risk_multiplier: Decimal,
+    pub max_positions: Option<usize>,
+    pub exit_tightening: Decimal,
+    pub since: DateTime<Utc>,
+}
+
+impl RiskLevel {
+    pub fn normal(since: DateTime<Utc>) -> Self {
+        Self {
+            rung: 0,
+            risk_multiplier: dec!(1),
+            max_positions: None,
+            exit_tightening: dec!(1),
+            since,
+        }
+    }
+
+    fn on_rung(rungs: &[DrawdownRung], rung: usize, since: DateTime<Utc>) -> Self {
+        match rung.checked_sub(1).and_then(|i| rungs.get(i)) {
+            Some(r) => Self {
+                rung,
+                risk_multiplier: r.risk_multiplier,
+                max_positions: r.max_positions,
+                exit_tightening: r.exit_tightening,
+                since,
+            },
+            None => Self::normal(since),
+        }
+    }
+
+    /// `policy` with exits brought in by the level: hold times and the break-even
+    /// trigger always, the trail and profit targets where the policy has them
+    pub fn tighten(&self, policy: Option<ExitPolicy>) -> Option<ExitPolicy> {
+        if self.rung == 0 {
+            return policy;
+        }
+        let factor = self.exit_tightening.to_f64().unwrap_or(1.0);
+        let mut policy = policy.unwrap_or_default();
+        if policy.name.is_none() {
+            policy.name = Some(format!("{}{}", DRAWDOWN_RUNG_POLICY_PREFIX, self.rung));
+        }
+
+        let mut time_exit = policy.time_exit.take().unwrap_or_default();
+        let scale = |d: Duration| Duration::seconds((d.num_seconds() as f64 * factor) as i64);
+        time_exit.max_hold_duration = scale(time_exit.max_hold_duration);
+        time_exit.warning_duration = scale(time_exit.warning_duration);
+        time_exit.enabled = true;
+        policy.time_exit = Some(time_exit);
+
+        let mut break_even = policy.break_even.take().unwrap_or_default();
+        break_even.trigger_ratio *= factor;
+        break_even.enabled = true;
+        policy.break_even = Some(break_even);
+
+        if let Some(trailing) = &mut policy.trailing {
+            trailing.atr_multiplier *= factor;
+        }
+        if let Some(profit_taking) = &mut policy.profit_taking {
+            for target in &mut profit_taking.profit_targets {
+                target.risk_reward_ratio *= factor;
+            }
+        }
+        Some(policy)
+    }
+}
+
+/// Equity an account's drawdowns are measured from: what it opened the UTC day
+/// with and the highest it has reached
+#[derive(Debug, Clone)]
+pub struct EquityMarks {
+    day: NaiveDate,
+    day_open: Decimal,
+    peak: Decimal,
+}
+
+impl EquityMarks {
+    pub fn new(equity: Decimal, now: DateTime<Utc>) -> Self {
+        Self {
+            day: now.date_naive(),
+            day_open: equity,
+            peak: equity,
+        }
+    }
+
+    /// Record `equity` at `now`, returning the daily and total drawdowns in percent
+    pub fn update(&mut self, equity: Decimal, now: DateTime<Utc>) -> (Decimal, Decimal) {
+        if now.date_naive() != self.day {
+            self.day = now.date_naive();
+            self.day_open = equity;
+        }
+        self.peak = self.peak.max(equity);
+        let below = |mark: Decimal| {
+            if mark <= dec!(0) {
+                return dec!(0);
+            }
+            ((mark - equity) / mark * dec!(100)).max(dec!(0))
+        };
+        (below(self.day_open), below(self.peak))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LadderState {
+    level: RiskLevel,
+    /// Since when the drawdowns have been clear of the current rung
+    recovering_since: Option<DateTime<Utc>>,
+}
+
+/// Applies `DrawdownLadderConfig` to accounts, by the orchestrator's account ids.
+/// The orchestrator moves accounts on it from their measured drawdowns and sizes
+/// and exits their trades by it.
+#[derive(Debug, Default)]
+pub struct DrawdownLadder {
+    config: DrawdownLadderConfig,
+    states: DashMap<String, LadderState>,
+}
+
+impl DrawdownLadder {
+    pub fn new(config: DrawdownLadderConfig) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            states: DashMap::new(),
+        })
+    }
+
+    pub fn config(&self) -> &DrawdownLadderConfig {
+        &self.config
+    }
+
+    /// The level the account should move to at `now` for drawdowns of `daily_pct`
+    /// and `total_pct`, if it should move: straight down to the deepest rung
+    /// crossed, or up one rung once recovered for long enough
+    pub fn assess(
+        &self,
+        account_id: &str,
+        daily_pct: Decimal,
+        total_pct: Decimal,
+        now: DateTime<Utc>,
+    ) -> Option<RiskLevel> {
+        if !self.config.enabled {
+            return None;
+        }
+        let rungs = &self.config.rungs;
+        let deepest = rungs
+            .iter()
+            .rposition(|r| r.crossed(daily_pct, total_pct))
+            .map_or(0, |i| i + 1);
+
+        let mut state = self
+            .states
+            .entry(account_id.to_string())
+            .or_insert_with(|| LadderState {
+                level: RiskLevel::normal(now),
+                recovering_since: None,
+            });
+        let current = state.level.rung;
+        if deepest > current {
+            state.recovering_since = None;
+            return Some(RiskLevel::on_rung(rungs, deepest, now));
+        }
+        if current == 0 {
+            return None;
+        }
+
+        let recovered =
+            rungs[current - 1].cleared(daily_pct, total_pct, self.config.recovery_margin_pct);
+        if !recovered {
+            state.recovering_since = None;
+            return None;
+        }
+        let since = *state.recovering_since.get_or_insert(now);
+        (now - since >= Duration::minutes(self.config.recovery_mins as i64))
+            .then(|| RiskLevel::on_rung(rungs, current - 1, now))
+    }
+
+    /// Move the account to `rung`, 0 restoring normal trading
+    pub fn move_to(&self, account_id: &str, rung: usize, now: DateTime<Utc>) -> RiskLevel {
+        let level = RiskLevel::on_rung(&self.config.rungs, rung, now);
+        self.states.insert(
+            account_id.to_string(),
+            LadderState {
+                level: level.clone(),
+                recovering_since: None,
+            },
+        );
+        level
+    }
+
+    /// The account's level while it is down the ladder
+    pub fn level(&self, account_id: &str) -> Option<RiskLevel> {
+        if !self.config.enabled {
+            return None;
+        }
+        self.states
+            .get(account_id)
+            .map(|state| state.level.clone())
+            .filter(|level| level.rung > 0)
+    }
+}
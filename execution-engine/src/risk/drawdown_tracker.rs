@@ -1,4 +1,9 @@
+use crate::alerting::{Alert, AlertGateway};
 use crate::risk::config::DrawdownThresholds;
+use crate::risk::high_water_mark::{HighWaterMark, HighWaterMarkStore};
+use crate::risk::risk_response::RiskResponseSystem;
+use crate::risk::trading_day::TradingDayConfig;
+use crate::runtime::spawn_isolated;
 use anyhow::Result;
 use chrono::{DateTime, Duration, NaiveDate, Utc};
 use dashmap::DashMap;
@@ -13,6 +18,10 @@ pub struct DrawdownTracker {
     drawdown_cache: Arc<DashMap<AccountId, DrawdownMetrics>>,
     drawdown_alerts: Arc<DrawdownAlertManager>,
     thresholds: DrawdownThresholds,
+    trading_days: TradingDayConfig,
+    high_water_marks: Arc<DashMap<AccountId, HighWaterMark>>,
+    high_water_mark_store: Option<Arc<dyn HighWaterMarkStore>>,
+    risk_response: Option<Arc<RiskResponseSystem>>,
 }
 
 impl DrawdownTracker {
@@ -26,9 +35,123 @@ impl DrawdownTracker {
             drawdown_cache: Arc::new(DashMap::new()),
             drawdown_alerts,
             thresholds,
+            trading_days: TradingDayConfig::default(),
+            high_water_marks: Arc::new(DashMap::new()),
+            high_water_mark_store: None,
+            risk_response: None,
         }
     }
 
+    /// Measure daily drawdown from each account's trading day rollover instead of UTC midnight
+    pub fn with_trading_days(mut self, trading_days: TradingDayConfig) -> Self {
+        self.trading_days = trading_days;
+        self
+    }
+
+    /// Persist equity high-water marks to `store`; call `restore_high_water_marks`
+    /// before the first calculation to pick up where the last run left off
+    pub fn with_high_water_mark_store(mut self, store: Arc<dyn HighWaterMarkStore>) -> Self {
+        self.high_water_mark_store = Some(store);
+        self
+    }
+
+    /// Hand trailing drawdown breaches to `risk_response`, which stops the account
+    pub fn with_risk_response(mut self, risk_response: Arc<RiskResponseSystem>) -> Self {
+        self.risk_response = Some(risk_response);
+        self
+    }
+
+    /// Load the persisted high-water marks, returning how many accounts had one
+    pub async fn restore_high_water_marks(&self) -> Result<usize> {
+        let Some(store) = &self.high_water_mark_store else {
+            return Ok(0);
+        };
+        let marks = store.load_all().await?;
+        let restored = marks.len();
+        for mark in marks {
+            self.high_water_marks.insert(mark.account_id, mark);
+        }
+        info!("Restored {} equity high-water marks", restored);
+        Ok(restored)
+    }
+
+    pub fn high_water_mark(&self, account_id: AccountId) -> Option<HighWaterMark> {
+        self.high_water_marks
+            .get(&account_id)
+            .map(|mark| mark.clone())
+    }
+
+    /// Whether the account has breached its trailing drawdown limit and not
+    /// been cleared since
+    pub fn is_locked_out(&self, account_id: AccountId) -> bool {
+        self.high_water_marks
+            .get(&account_id)
+            .is_some_and(|mark| mark.breached_at.is_some())
+    }
+
+    /// Lift a trailing drawdown lockout, e.g. after the firm resets the account
+    pub async fn clear_lockout(&self, account_id: AccountId) -> Result<()> {
+        let mark = match self.high_water_marks.get_mut(&account_id) {
+            Some(mut mark) if mark.breached_at.is_some() => {
+                mark.breached_at = None;
+                mark.clone()
+            }
+            _ => return Ok(()),
+        };
+        info!(
+            "Cleared trailing drawdown lockout for account {}",
+            account_id
+        );
+        self.save_high_water_mark(&mark).await;
+        Ok(())
+    }
+
+    /// Take a deposit (positive `amount`) or withdrawal made at `at` out of the
+    /// drawdowns: the equity history before it and the high-water mark move by
+    /// the amount, and the cached metrics are dropped
+    pub async fn apply_funding(
+        &self,
+        account_id: AccountId,
+        amount: Decimal,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        self.equity_history
+            .apply_funding(account_id, amount, at)
+            .await?;
+        self.drawdown_cache.remove(&account_id);
+
+        let mark = match self.high_water_marks.get_mut(&account_id) {
+            Some(mut mark) => {
+                mark.equity += amount;
+                mark.clone()
+            }
+            None => return Ok(()),
+        };
+        info!(
+            "Moved high-water mark of account {} by {} of funding to {}",
+            account_id, amount, mark.equity
+        );
+        self.save_high_water_mark(&mark).await;
+        Ok(())
+    }
+
+    /// Drawdown of the account's latest equity from its high-water mark
+    pub async fn trailing_drawdown(&self, account_id: AccountId) -> Result<Option<DrawdownData>> {
+        let Some(mark) = self.high_water_mark(account_id) else {
+            return Ok(None);
+        };
+        let history = self
+            .equity_history
+            .get_history(account_id, Duration::days(30))
+            .await?;
+        let current_equity = history.last().map(|p| p.equity).unwrap_or(mark.equity);
+        Ok(Some(trailing_drawdown_data(
+            &mark,
+            current_equity,
+            Utc::now(),
+        )))
+    }
+
     pub async fn calculate_drawdowns(&self, account_id: AccountId) -> Result<DrawdownMetrics> {
         // Check cache first for performance
         if let Some(cached_metrics) = self.drawdown_cache.get(&account_id) {
@@ -77,11 +200,19 @@ impl DrawdownTracker {
         }
 
         // Optimize: Calculate all metrics in single pass for better performance
-        let metrics = self.calculate_all_drawdown_metrics(&equity_history).await?;
+        let metrics = self
+            .calculate_all_drawdown_metrics(account_id, &equity_history)
+            .await?;
 
         self.drawdown_cache.insert(account_id, metrics.clone());
 
+        let mark = self
+            .update_high_water_mark(account_id, &equity_history)
+            .await;
+
         self.check_drawdown_alerts(account_id, &metrics).await?;
+        self.check_trailing_drawdown(mark, metrics.maximum_drawdown.current_equity)
+            .await?;
 
         Ok(metrics)
     }
@@ -89,10 +220,14 @@ impl DrawdownTracker {
     /// Optimized single-pass calculation of all drawdown metrics
     async fn calculate_all_drawdown_metrics(
         &self,
+        account_id: AccountId,
         equity_history: &[EquityPoint],
     ) -> Result<DrawdownMetrics> {
         let now = Utc::now();
-        let today = now.date_naive();
+        let (day_start, _) = self
+            .trading_days
+            .for_account(&account_id.to_string())
+            .current_day(now);
         let one_week_ago = now - Duration::days(7);
 
         let mut daily_peak = dec!(0);
@@ -120,7 +255,7 @@ impl DrawdownTracker {
             let timestamp = point.timestamp;
 
             // Daily calculations
-            if timestamp.date_naive() == today {
+            if timestamp >= day_start {
                 if !is_daily_set {
                     daily_current = equity;
                     daily_peak = equity;
@@ -237,14 +372,115 @@ impl DrawdownTracker {
         })
     }
 
+    /// Raise the account's high-water mark to the peak of `equity_history`
+    async fn update_high_water_mark(
+        &self,
+        account_id: AccountId,
+        equity_history: &[EquityPoint],
+    ) -> HighWaterMark {
+        let peak = equity_history
+            .iter()
+            .fold(None::<&EquityPoint>, |peak, point| match peak {
+                Some(peak) if peak.equity >= point.equity => Some(peak),
+                _ => Some(point),
+            })
+            .expect("equity history is not empty");
+
+        let (mark, raised) = match self.high_water_marks.get_mut(&account_id) {
+            Some(mut mark) => {
+                let raised = mark.raise(peak.equity, peak.timestamp);
+                (mark.clone(), raised)
+            }
+            None => {
+                let mark = HighWaterMark::new(account_id, peak.equity, peak.timestamp);
+                self.high_water_marks.insert(account_id, mark.clone());
+                (mark, true)
+            }
+        };
+        if raised {
+            self.save_high_water_mark(&mark).await;
+        }
+        mark
+    }
+
+    async fn save_high_water_mark(&self, mark: &HighWaterMark) {
+        if let Some(store) = &self.high_water_mark_store {
+            if let Err(e) = store.save(mark).await {
+                error!(
+                    "Failed to persist high-water mark for account {}: {:#}",
+                    mark.account_id, e
+                );
+            }
+        }
+    }
+
+    async fn check_trailing_drawdown(
+        &self,
+        mark: HighWaterMark,
+        current_equity: Decimal,
+    ) -> Result<()> {
+        let Some(threshold) = self.thresholds.trailing_threshold else {
+            return Ok(());
+        };
+        let account_id = mark.account_id;
+        let (_, percentage) = mark.drawdown(current_equity);
+
+        if percentage <= threshold {
+            self.drawdown_alerts
+                .resolve(account_id, DrawdownAlertType::Trailing);
+            return Ok(());
+        }
+
+        self.drawdown_alerts
+            .send_alert(DrawdownAlert {
+                account_id,
+                alert_type: DrawdownAlertType::Trailing,
+                drawdown_percentage: percentage,
+                threshold,
+                message: format!(
+                    "Trailing drawdown from high-water mark {} exceeds threshold: {:.2}% > {:.2}%",
+                    mark.equity, percentage, threshold
+                ),
+                timestamp: Utc::now(),
+            })
+            .await?;
+
+        if mark.breached_at.is_some() {
+            return Ok(());
+        }
+        let mark = match self.high_water_marks.get_mut(&account_id) {
+            Some(mut mark) => {
+                mark.breached_at = Some(Utc::now());
+                mark.clone()
+            }
+            None => return Ok(()),
+        };
+        warn!(
+            "Account {} locked out: trailing drawdown {:.2}% from high-water mark {}",
+            account_id, percentage, mark.equity
+        );
+        self.save_high_water_mark(&mark).await;
+
+        if let Some(risk_response) = &self.risk_response {
+            risk_response
+                .handle_trailing_drawdown_risk(account_id, percentage, threshold)
+                .await?;
+        }
+        Ok(())
+    }
+
     async fn calculate_daily_drawdown(
         &self,
+        account_id: AccountId,
         equity_history: &[EquityPoint],
     ) -> Result<DrawdownData> {
-        let today = Utc::now().date_naive();
+        let (day_start, _) = self
+            .trading_days
+            .for_account(&account_id.to_string())
+            .current_day(Utc::now());
         let today_points: Vec<_> = equity_history
             .iter()
-            .filter(|point| point.timestamp.date_naive() == today)
+            .filter(|point| point.timestamp >= day_start)
             .collect();
 
         if today_points.is_empty() {
@@ -458,6 +694,9 @@ impl DrawdownTracker {
                     timestamp: Utc::now(),
                 })
                 .await?;
+        } else {
+            self.drawdown_alerts
+                .resolve(account_id, DrawdownAlertType::Daily);
         }
 
         if metrics.weekly_drawdown.percentage > self.thresholds.weekly_threshold {
@@ -474,6 +713,9 @@ impl DrawdownTracker {
                     timestamp: Utc::now(),
                 })
                 .await?;
+        } else {
+            self.drawdown_alerts
+                .resolve(account_id, DrawdownAlertType::Weekly);
         }
 
         if metrics.maximum_drawdown.percentage > self.thresholds.max_threshold {
@@ -490,6 +732,9 @@ impl DrawdownTracker {
                     timestamp: Utc::now(),
                 })
                 .await?;
+        } else {
+            self.drawdown_alerts
+                .resolve(account_id, DrawdownAlertType::Maximum);
         }
 
         Ok(())
@@ -549,6 +794,22 @@ impl DrawdownTracker {
     }
 }
 
+fn trailing_drawdown_data(
+    mark: &HighWaterMark,
+    current_equity: Decimal,
+    now: DateTime<Utc>,
+) -> DrawdownData {
+    let (amount, percentage) = mark.drawdown(current_equity);
+    DrawdownData {
+        amount,
+        percentage,
+        peak_equity: mark.equity,
+        current_equity,
+        start_time: mark.reached_at,
+        duration: now - mark.reached_at,
+    }
+}
+
 // Removed Default implementations for external types (DrawdownMetrics, DrawdownData)
 // These should be defined in the risk_types crate where the types are declared
 
@@ -601,22 +862,55 @@ impl EquityHistoryManager {
 
         Ok(())
     }
+
+    /// Move every point before `at` by `amount`, so a deposit or withdrawal
+    /// made then stops showing as a jump in equity
+    pub async fn apply_funding(
+        &self,
+        account_id: AccountId,
+        amount: Decimal,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        if let Some(mut history) = self.history.get_mut(&account_id) {
+            for point in history.iter_mut().filter(|p| p.timestamp < at) {
+                point.equity += amount;
+                point.balance += amount;
+            }
+        }
+        Ok(())
+    }
 }
 
 pub struct DrawdownAlertManager {
     alerts: Arc<DashMap<AccountId, Vec<DrawdownAlert>>>,
+    gateway: Option<Arc<AlertGateway>>,
 }
 
 impl DrawdownAlertManager {
     pub fn new() -> Self {
         Self {
             alerts: Arc::new(DashMap::new()),
+            gateway: None,
         }
     }
 
+    /// Forward alerts to `gateway` for deduplicated delivery
+    pub fn with_gateway(mut self, gateway: Arc<AlertGateway>) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
     pub async fn send_alert(&self, alert: DrawdownAlert) -> Result<()> {
         warn!("Drawdown Alert: {}", alert.message);
 
+        if let Some(gateway) = &self.gateway {
+            let gateway = gateway.clone();
+            let alert = Alert::from(&alert);
+            spawn_isolated("drawdown-alert-delivery", async move {
+                gateway.submit(alert).await;
+            });
+        }
+
         self.alerts
             .entry(alert.account_id)
             .or_insert_with(Vec::new)
@@ -624,6 +918,23 @@ impl DrawdownAlertManager {
 
         Ok(())
     }
+
+    /// The drawdown of `alert_type` is back within its threshold
+    pub fn resolve(&self, account_id: AccountId, alert_type: DrawdownAlertType) {
+        if let Some(gateway) = &self.gateway {
+            gateway.resolve(
+                DRAWDOWN_ALERT_TYPE,
+                &drawdown_alert_key(account_id, alert_type),
+            );
+        }
+    }
+
+    pub fn get_alerts(&self, account_id: AccountId) -> Vec<DrawdownAlert> {
+        self.alerts
+            .get(&account_id)
+            .map(|alerts| alerts.clone())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -641,4 +952,30 @@ pub enum DrawdownAlertType {
     Daily,
     Weekly,
     Maximum,
+    /// Drawdown from the equity high-water mark
+    Trailing,
+}
+
+const DRAWDOWN_ALERT_TYPE: &str = "drawdown";
+
+fn drawdown_alert_key(account_id: AccountId, alert_type: DrawdownAlertType) -> String {
+    format!("{}:{:?}", account_id, alert_type).to_lowercase()
+}
+
+impl From<&DrawdownAlert> for Alert {
+    fn from(alert: &DrawdownAlert) -> Self {
+        let severity = match alert.alert_type {
+            DrawdownAlertType::Daily => AlertLevel::Warning,
+            DrawdownAlertType::Weekly => AlertLevel::Critical,
+            DrawdownAlertType::Maximum | DrawdownAlertType::Trailing => AlertLevel::Emergency,
+        };
+        Self {
+            alert_type: DRAWDOWN_ALERT_TYPE.to_string(),
+            key: drawdown_alert_key(alert.account_id, alert.alert_type),
+            severity,
+            account_id: Some(alert.account_id),
+            message: alert.message.clone(),
+            raised_at: alert.timestamp,
+        }
+    }
 }
@@ -1,4 +1,6 @@
+use crate::market_data::CrossRates;
 use crate::risk::pnl_calculator::PositionTracker;
+use crate::risk::portfolio_exposure::{ClusterLimits, PortfolioExposure};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
@@ -14,6 +16,9 @@ pub struct ExposureMonitor {
     currency_exposure_calculator: Arc<CurrencyExposureCalculator>,
     exposure_limits: Arc<ExposureLimits>,
     exposure_alerts: Arc<ExposureAlertManager>,
+    cluster_limits: ClusterLimits,
+    /// Rates netting currency legs in one reporting currency, with that currency
+    cross_rates: Option<(CrossRates, String)>,
 }
 
 impl ExposureMonitor {
@@ -28,26 +33,48 @@ impl ExposureMonitor {
             currency_exposure_calculator,
             exposure_limits,
             exposure_alerts,
+            cluster_limits: ClusterLimits::default(),
+            cross_rates: None,
         }
     }
 
+    /// Net currency legs at `rates`, reporting every currency's exposure in
+    /// `reporting_currency`
+    pub fn with_cross_rates(mut self, rates: CrossRates, reporting_currency: &str) -> Self {
+        self.cross_rates = Some((rates, reporting_currency.to_uppercase()));
+        self
+    }
+
+    /// Also cap the combined exposure of correlated clusters across all accounts
+    pub fn with_cluster_limits(mut self, cluster_limits: ClusterLimits) -> Self {
+        self.cluster_limits = cluster_limits;
+        self
+    }
+
     pub async fn calculate_total_exposure(&self) -> Result<ExposureReport> {
         let all_positions = self.position_tracker.get_all_open_positions().await?;
 
         let pair_exposure = self.calculate_pair_exposure(&all_positions).await?;
 
-        let currency_exposure = self
-            .currency_exposure_calculator
-            .calculate_net_exposure(&all_positions)
-            .await?;
+        let currency_exposure = match &self.cross_rates {
+            Some((rates, reporting_currency)) => self
+                .currency_exposure_calculator
+                .calculate_net_exposure_in(&all_positions, rates, reporting_currency)?,
+            None => {
+                self.currency_exposure_calculator
+                    .calculate_net_exposure(&all_positions)
+                    .await?
+            }
+        };
 
         let total_exposure = self
             .calculate_total_portfolio_exposure(&all_positions)
             .await?;
 
-        let limit_violations = self
+        let mut limit_violations = self
             .check_exposure_limits(&pair_exposure, &currency_exposure)
             .await?;
+        limit_violations.extend(self.check_cluster_limits(&all_positions));
 
         let report = ExposureReport {
             pair_exposure,
@@ -159,6 +186,21 @@ impl ExposureMonitor {
         Ok(violations)
     }
 
+    fn check_cluster_limits(&self, positions: &[Position]) -> Vec<ExposureLimitViolation> {
+        let portfolio = PortfolioExposure::from_positions(positions);
+        self.cluster_limits
+            .evaluate(&portfolio)
+            .into_iter()
+            .filter(|cluster| cluster.breached)
+            .map(|cluster| ExposureLimitViolation {
+                limit_type: format!("cluster_exposure_{}", cluster.name),
+                current_value: cluster.exposure.abs(),
+                limit_value: cluster.max_exposure,
+                severity: self.determine_severity(cluster.exposure.abs(), cluster.max_exposure),
+            })
+            .collect()
+    }
+
     fn determine_severity(&self, current: Decimal, limit: Decimal) -> String {
         let ratio = current / limit;
         if ratio > dec!(1.5) {
@@ -316,6 +358,11 @@ impl ExposureMonitor {
             }
         }
 
+        recommendations.extend(
+            self.cluster_limits
+                .rebalance_recommendations(&PortfolioExposure::from_positions(&all_positions)),
+        );
+
         Ok(recommendations)
     }
 }
@@ -348,6 +395,36 @@ impl CurrencyExposureCalculator {
         Ok(currency_exposure)
     }
 
+    /// Net exposure per currency in `reporting_currency`. Each leg is taken in its
+    /// own currency, the size in the base and the notional at entry in the quote,
+    /// and converted at `rates`; a leg without a fresh rate fails the calculation
+    /// rather than netting at a wrong one.
+    pub fn calculate_net_exposure_in(
+        &self,
+        positions: &[Position],
+        rates: &CrossRates,
+        reporting_currency: &str,
+    ) -> Result<HashMap<String, Decimal>> {
+        let mut legs: HashMap<String, Decimal> = HashMap::new();
+        for position in positions {
+            let (base_currency, quote_currency) = self.parse_currency_pair(&position.symbol)?;
+            let sign = match position.position_type {
+                PositionType::Long => dec!(1),
+                PositionType::Short => dec!(-1),
+            };
+            *legs.entry(base_currency).or_insert(dec!(0)) += sign * position.size;
+            *legs.entry(quote_currency).or_insert(dec!(0)) -=
+                sign * position.size * position.entry_price;
+        }
+
+        legs.into_iter()
+            .map(|(currency, amount)| {
+                let converted = rates.convert(amount, &currency, reporting_currency)?;
+                Ok((currency, converted))
+            })
+            .collect()
+    }
+
     fn parse_currency_pair(&self, symbol: &str) -> Result<(String, String)> {
         if symbol.len() >= 6 {
             Ok((symbol[..3].to_string(), symbol[3..6].to_string()))
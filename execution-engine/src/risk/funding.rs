@@ -0,0 +1,351 @@
+// Deposits and withdrawals. Money moved in or out of an account shows up as a
+// jump in equity that no trade made, which would read as a drawdown or a gain
+// everywhere equity is tracked. Funding events come from the platform's
+// transaction history where it has one, and otherwise from balance changes the
+// account's realized P&L does not explain; either way they are recorded so the
+// equity baselines can be moved by them and the periods they fall in tagged.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::platforms::abstraction::models::{UnifiedTransaction, UnifiedTransactionKind};
+use crate::platforms::abstraction::UnifiedAccountInfo;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FundingConfig {
+    pub enabled: bool,
+    /// Unexplained balance changes smaller than this, in the account currency,
+    /// are not taken for funding
+    pub min_amount: Decimal,
+    /// Nor are those smaller than this percent of the balance before them
+    pub min_balance_pct: Decimal,
+    /// JSON lines file the events are appended to
+    pub path: String,
+}
+
+impl Default for FundingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_amount: dec!(50),
+            min_balance_pct: dec!(1),
+            path: "data/funding_events.jsonl".to_string(),
+        }
+    }
+}
+
+impl FundingConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_amount <= dec!(0) {
+            return Err(format!(
+                "Funding min_amount must be positive, not {}",
+                self.min_amount
+            ));
+        }
+        if self.min_balance_pct < dec!(0) || self.min_balance_pct >= dec!(100) {
+            return Err(format!(
+                "Funding min_balance_pct must be between 0% and 100%, not {}",
+                self.min_balance_pct
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FundingSource {
+    /// A deposit or withdrawal in the platform's transaction history
+    Platform,
+    /// A balance change the account's realized P&L does not explain
+    Heuristic,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundingEvent {
+    pub account_id: String,
+    /// Positive for a deposit, negative for a withdrawal
+    pub amount: Decimal,
+    pub currency: String,
+    pub at: DateTime<Utc>,
+    pub source: FundingSource,
+    /// None for events inferred from the balance
+    #[serde(default)]
+    pub transaction_id: Option<String>,
+}
+
+impl FundingEvent {
+    pub fn is_deposit(&self) -> bool {
+        self.amount > Decimal::ZERO
+    }
+}
+
+/// Durable storage for funding events, appended once each and never rewritten
+#[async_trait]
+pub trait FundingEventStore: Send + Sync + std::fmt::Debug {
+    async fn append(&self, event: &FundingEvent) -> Result<()>;
+    /// Every event, oldest first
+    async fn load(&self) -> Result<Vec<FundingEvent>>;
+}
+
+/// Append-only JSON lines file, one event per line
+#[derive(Debug)]
+pub struct FileFundingEventStore {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl FileFundingEventStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl FundingEventStore for FileFundingEventStore {
+    async fn append(&self, event: &FundingEvent) -> Result<()> {
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .with_context(|| format!("Failed to open funding events {}", self.path.display()))?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Vec<FundingEvent>> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut events = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<FundingEvent>(line) {
+                Ok(event) => events.push(event),
+                // A torn final line from a crash should not lose the earlier events
+                Err(e) => warn!(
+                    "Skipping unreadable funding event line {} in {}: {}",
+                    number + 1,
+                    self.path.display(),
+                    e
+                ),
+            }
+        }
+        events.sort_by_key(|event| event.at);
+        Ok(events)
+    }
+}
+
+/// Balance and realized P&L as last observed
+#[derive(Debug, Clone, Copy)]
+struct BalanceObservation {
+    balance: Decimal,
+    realized_pnl: Decimal,
+    at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+struct FundingState {
+    observations: HashMap<String, BalanceObservation>,
+    /// Platform events not yet matched against an observed balance, by account
+    unobserved: HashMap<String, Vec<FundingEvent>>,
+    events: Vec<FundingEvent>,
+    transaction_ids: HashSet<String>,
+}
+
+/// Detects and records each account's deposits and withdrawals
+pub struct FundingDetector {
+    config: FundingConfig,
+    store: Arc<dyn FundingEventStore>,
+    state: Mutex<FundingState>,
+}
+
+impl FundingDetector {
+    pub fn new(config: FundingConfig, store: Arc<dyn FundingEventStore>) -> Self {
+        Self {
+            config,
+            store,
+            state: Mutex::new(FundingState::default()),
+        }
+    }
+
+    pub fn config(&self) -> &FundingConfig {
+        &self.config
+    }
+
+    /// Read the events recorded so far, so platform transactions already seen
+    /// are not recorded again. Returns how many were read.
+    pub async fn load(&self) -> Result<usize> {
+        let events = self.store.load().await?;
+        let mut state = self.state.lock().await;
+        state.transaction_ids = events
+            .iter()
+            .filter_map(|e| e.transaction_id.clone())
+            .collect();
+        state.events = events;
+        info!("Loaded {} funding events", state.events.len());
+        Ok(state.events.len())
+    }
+
+    /// Record the deposits and withdrawals among an account's platform
+    /// transactions, skipping those already recorded, or already inferred from
+    /// the balance at or after the transaction. Returns the new events.
+    pub async fn record_transactions(
+        &self,
+        account_id: &str,
+        transactions: &[UnifiedTransaction],
+    ) -> Result<Vec<FundingEvent>> {
+        let mut state = self.state.lock().await;
+        let mut recorded = Vec::new();
+        for transaction in transactions {
+            if transaction.kind != UnifiedTransactionKind::Transfer || transaction.amount.is_zero()
+            {
+                continue;
+            }
+            let amount = transaction.amount;
+            if state.transaction_ids.contains(&transaction.transaction_id) {
+                continue;
+            }
+            let inferred = state.events.iter().any(|e| {
+                e.source == FundingSource::Heuristic
+                    && e.account_id == account_id
+                    && e.amount == amount
+                    && e.at >= transaction.timestamp
+            });
+            if inferred {
+                state
+                    .transaction_ids
+                    .insert(transaction.transaction_id.clone());
+                continue;
+            }
+            let event = FundingEvent {
+                account_id: account_id.to_string(),
+                amount,
+                currency: transaction.currency.clone(),
+                at: transaction.timestamp,
+                source: FundingSource::Platform,
+                transaction_id: Some(transaction.transaction_id.clone()),
+            };
+            self.store.append(&event).await?;
+            state
+                .transaction_ids
+                .insert(transaction.transaction_id.clone());
+            state
+                .unobserved
+                .entry(account_id.to_string())
+                .or_default()
+                .push(event.clone());
+            state.events.push(event.clone());
+            recorded.push(event);
+        }
+        state.events.sort_by_key(|event| event.at);
+        Ok(recorded)
+    }
+
+    /// Compare the account's balance with its last observation. Returns the
+    /// funding that moved it since: platform events recorded in between, and
+    /// any change beyond them that the realized P&L does not explain, which is
+    /// recorded as a heuristic event. The first observation of an account only
+    /// sets its baseline. Platforms are taken to report realized P&L as a
+    /// running total.
+    pub async fn observe(
+        &self,
+        account_id: &str,
+        info: &UnifiedAccountInfo,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<FundingEvent>> {
+        let mut state = self.state.lock().await;
+        let pending = state.unobserved.remove(account_id).unwrap_or_default();
+        let previous = state.observations.insert(
+            account_id.to_string(),
+            BalanceObservation {
+                balance: info.balance,
+                realized_pnl: info.realized_pnl,
+                at,
+            },
+        );
+        let Some(previous) = previous else {
+            return Ok(Vec::new());
+        };
+
+        // Events up to the last observation were already in its balance
+        let mut flows: Vec<FundingEvent> = pending
+            .into_iter()
+            .filter(|event| event.at > previous.at)
+            .collect();
+        let known: Decimal = flows.iter().map(|event| event.amount).sum();
+        let unexplained =
+            (info.balance - previous.balance) - (info.realized_pnl - previous.realized_pnl) - known;
+        let floor = previous.balance.abs() * self.config.min_balance_pct / dec!(100);
+        if unexplained.abs() >= self.config.min_amount && unexplained.abs() >= floor {
+            let event = FundingEvent {
+                account_id: account_id.to_string(),
+                amount: unexplained,
+                currency: info.currency.to_uppercase(),
+                at,
+                source: FundingSource::Heuristic,
+                transaction_id: None,
+            };
+            info!(
+                "Account {} balance moved {} beyond its realized P&L, taken as {}",
+                account_id,
+                unexplained,
+                if event.is_deposit() {
+                    "a deposit"
+                } else {
+                    "a withdrawal"
+                }
+            );
+            self.store.append(&event).await?;
+            state.events.push(event.clone());
+            flows.push(event);
+        }
+        Ok(flows)
+    }
+
+    /// Recorded events between `from` and `to`, of one account or all, oldest first
+    pub async fn events_between(
+        &self,
+        account_id: Option<&str>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<FundingEvent> {
+        self.state
+            .lock()
+            .await
+            .events
+            .iter()
+            .filter(|e| e.at >= from && e.at < to)
+            .filter(|e| account_id.is_none_or(|id| e.account_id == id))
+            .cloned()
+            .collect()
+    }
+}
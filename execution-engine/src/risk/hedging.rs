@@ -0,0 +1,439 @@
+// Hedging risk response: offsetting positions opened in place of closing
+// positions on accounts where early closes trip prop-firm consistency rules
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use risk_types::*;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::risk_response::PositionManager;
+use crate::platforms::abstraction::interfaces::ITradingPlatform;
+use crate::platforms::abstraction::models::{
+    OrderMetadata, UnifiedOrder, UnifiedOrderSide, UnifiedOrderType, UnifiedTimeInForce,
+};
+use crate::runtime::spawn::supervised_spawn;
+use crate::runtime::supervisor::RestartPolicy;
+
+/// Hedge a symbol through another instrument, e.g. EURUSD through GBPUSD
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorrelatedHedge {
+    pub symbol: String,
+    /// Correlation of the hedge instrument with the hedged symbol. Positive
+    /// correlations hedge with the opposite side, negative ones with the same side,
+    /// and the size is scaled up by `1 / |correlation|`.
+    pub correlation: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HedgingPolicy {
+    pub enabled: bool,
+    /// Accounts whose rules flag positions closed early; only these hedge
+    pub accounts: HashSet<AccountId>,
+    /// Share of each position's size to offset
+    pub hedge_ratio: Decimal,
+    /// Symbols hedged through a correlated instrument rather than themselves
+    pub correlated_instruments: HashMap<String, CorrelatedHedge>,
+    /// Close hedges once the breach that opened them has cleared
+    pub unwind_on_recovery: bool,
+    /// Close hedged pairs still open after this long, by when closing no longer
+    /// trips minimum holding rules
+    pub max_hedge_hours: Option<f64>,
+    /// How often open hedges are reviewed once the first one is placed
+    pub review_interval_secs: u64,
+}
+
+impl Default for HedgingPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            accounts: HashSet::new(),
+            hedge_ratio: dec!(1),
+            correlated_instruments: HashMap::new(),
+            unwind_on_recovery: true,
+            max_hedge_hours: Some(24.0),
+            review_interval_secs: 60,
+        }
+    }
+}
+
+/// An open hedge and the position it offsets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hedge {
+    pub hedge_position_id: PositionId,
+    pub hedged_position_id: PositionId,
+    pub account_id: AccountId,
+    pub symbol: String,
+    pub position_type: PositionType,
+    pub size: Decimal,
+    pub entry_price: Decimal,
+    /// Platform ticket of the hedge, when the platform reports one
+    pub ticket: Option<String>,
+    pub opened_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnwindReason {
+    /// The breach cleared; the hedge was closed and the position kept
+    Recovered,
+    /// The hedged position closed, so the hedge was closed too
+    HedgedPositionClosed,
+    /// The hedge itself was closed elsewhere and is no longer tracked
+    HedgeClosed,
+    /// Held past `max_hedge_hours`; both sides were closed
+    Expired,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HedgeUnwind {
+    pub hedge: Hedge,
+    pub reason: UnwindReason,
+}
+
+type Platform = Arc<dyn ITradingPlatform + Send + Sync>;
+
+/// Opens and unwinds hedges for `RiskResponseSystem` through each account's
+/// platform
+pub struct HedgeManager {
+    policy: HedgingPolicy,
+    /// Open hedges by hedged position
+    hedges: DashMap<PositionId, Hedge>,
+    platforms: DashMap<AccountId, Platform>,
+    review_scheduled: AtomicBool,
+}
+
+impl HedgeManager {
+    pub fn new(policy: HedgingPolicy) -> Self {
+        Self {
+            policy,
+            hedges: DashMap::new(),
+            platforms: DashMap::new(),
+            review_scheduled: AtomicBool::new(false),
+        }
+    }
+
+    /// Place the account's hedges on `platform`. Accounts without one are not
+    /// hedged.
+    pub fn register_platform(&self, account_id: AccountId, platform: Platform) {
+        self.platforms.insert(account_id, platform);
+    }
+
+    fn platform(&self, account_id: AccountId) -> Result<Platform> {
+        self.platforms
+            .get(&account_id)
+            .map(|p| p.clone())
+            .ok_or_else(|| anyhow!("No platform registered for account {}", account_id))
+    }
+
+    pub fn policy(&self) -> &HedgingPolicy {
+        &self.policy
+    }
+
+    /// True if breaches on this account are hedged instead of reduced
+    pub fn applies_to(&self, account_id: AccountId) -> bool {
+        self.policy.enabled && self.policy.accounts.contains(&account_id)
+    }
+
+    pub fn hedges(&self, account_id: AccountId) -> Vec<Hedge> {
+        self.hedges
+            .iter()
+            .filter(|h| h.account_id == account_id)
+            .map(|h| h.clone())
+            .collect()
+    }
+
+    fn is_hedge(&self, position_id: PositionId) -> bool {
+        self.hedges
+            .iter()
+            .any(|h| h.hedge_position_id == position_id)
+    }
+
+    /// Open a hedge for every position on the account that has none yet, and
+    /// start reviewing hedges once there are any
+    pub async fn hedge_account(
+        self: &Arc<Self>,
+        positions: &Arc<PositionManager>,
+        account_id: AccountId,
+        hedge_ratio: Decimal,
+    ) -> Result<ResponseExecutionResult> {
+        let platform = match self.platform(account_id) {
+            Ok(platform) => platform,
+            Err(e) => {
+                return Ok(ResponseExecutionResult::Failed {
+                    reason: e.to_string(),
+                })
+            }
+        };
+        let open = positions.get_account_positions(account_id).await?;
+        let mut hedges_opened = 0;
+        let mut hedged_size = Decimal::ZERO;
+
+        for position in open {
+            if self.hedges.contains_key(&position.id) || self.is_hedge(position.id) {
+                continue;
+            }
+            let opposite = match position.position_type {
+                PositionType::Long => PositionType::Short,
+                PositionType::Short => PositionType::Long,
+            };
+            let size = position.size * hedge_ratio;
+            let (symbol, position_type, size) = match self
+                .policy
+                .correlated_instruments
+                .get(&position.symbol)
+                .filter(|c| !c.correlation.is_zero())
+            {
+                Some(correlated) => {
+                    let position_type = if correlated.correlation > Decimal::ZERO {
+                        opposite
+                    } else {
+                        position.position_type
+                    };
+                    let size = size / correlated.correlation.abs();
+                    (correlated.symbol.clone(), position_type, size)
+                }
+                None => (position.symbol.clone(), opposite, size),
+            };
+
+            let (entry_price, ticket) = match self
+                .place_hedge(
+                    &platform,
+                    account_id,
+                    position.id,
+                    &symbol,
+                    position_type,
+                    size,
+                )
+                .await
+            {
+                Ok(placed) => placed,
+                Err(e) => {
+                    warn!(
+                        "Could not hedge position {} on account {}: {}",
+                        position.id, account_id, e
+                    );
+                    continue;
+                }
+            };
+            let hedge_position = positions
+                .open_position(account_id, &symbol, position_type, size, Some(entry_price))
+                .await?;
+            info!(
+                "Hedged position {} ({} {}) with {:?} {} {} at {} on account {}",
+                position.id,
+                position.size,
+                position.symbol,
+                position_type,
+                size,
+                symbol,
+                entry_price,
+                account_id
+            );
+            self.hedges.insert(
+                position.id,
+                Hedge {
+                    hedge_position_id: hedge_position.id,
+                    hedged_position_id: position.id,
+                    account_id,
+                    symbol,
+                    position_type,
+                    size,
+                    entry_price,
+                    ticket,
+                    opened_at: Utc::now(),
+                },
+            );
+            hedges_opened += 1;
+            hedged_size += size;
+        }
+
+        if hedges_opened > 0 {
+            self.schedule_review(positions);
+        }
+        Ok(ResponseExecutionResult::PositionsHedged {
+            hedges_opened,
+            hedged_size,
+        })
+    }
+
+    /// Send the hedge as a market order, returning its fill price and ticket. The
+    /// order is not sent without a quote to price it by.
+    async fn place_hedge(
+        &self,
+        platform: &Platform,
+        account_id: AccountId,
+        hedged_position_id: PositionId,
+        symbol: &str,
+        position_type: PositionType,
+        size: Decimal,
+    ) -> Result<(Decimal, Option<String>)> {
+        let side = match position_type {
+            PositionType::Long => UnifiedOrderSide::Buy,
+            PositionType::Short => UnifiedOrderSide::Sell,
+        };
+        let quote = platform.get_market_data(symbol).await?;
+        let quoted = match side {
+            UnifiedOrderSide::Buy => quote.ask,
+            UnifiedOrderSide::Sell => quote.bid,
+        };
+        if quoted <= Decimal::ZERO {
+            return Err(anyhow!("No usable quote for {}", symbol));
+        }
+
+        let order = UnifiedOrder {
+            client_order_id: Uuid::new_v4().to_string(),
+            symbol: symbol.to_string(),
+            side,
+            order_type: UnifiedOrderType::Market,
+            quantity: size,
+            price: None,
+            stop_price: None,
+            take_profit: None,
+            stop_loss: None,
+            time_in_force: UnifiedTimeInForce::Gtc,
+            account_id: Some(account_id.to_string()),
+            metadata: OrderMetadata {
+                strategy_id: None,
+                signal_id: None,
+                risk_parameters: HashMap::new(),
+                tags: vec!["hedge".to_string(), hedged_position_id.to_string()],
+                expires_at: None,
+            },
+            max_slippage: None,
+        };
+        let response = platform.place_order(order).await?;
+        let ticket = response
+            .platform_specific
+            .get("position_id")
+            .and_then(|id| id.as_str())
+            .map(str::to_string);
+        Ok((response.average_fill_price.unwrap_or(quoted), ticket))
+    }
+
+    /// Close the hedge on its platform, by ticket where known
+    async fn close_hedge(&self, hedge: &Hedge) -> Result<()> {
+        let platform = self.platform(hedge.account_id)?;
+        match &hedge.ticket {
+            Some(ticket) => platform.close_position_ticket(ticket, None).await?,
+            None => {
+                platform
+                    .close_position(&hedge.symbol, Some(hedge.size))
+                    .await?
+            }
+        };
+        Ok(())
+    }
+
+    /// Review hedges every `review_interval_secs` from the first one placed on
+    fn schedule_review(self: &Arc<Self>, positions: &Arc<PositionManager>) {
+        if self.review_scheduled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let every = std::time::Duration::from_secs(self.policy.review_interval_secs.max(1));
+        let manager = self.clone();
+        let positions = positions.clone();
+        supervised_spawn("hedge-review", RestartPolicy::default(), move || {
+            let manager = manager.clone();
+            let positions = positions.clone();
+            async move {
+                let mut interval = tokio::time::interval(every);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = manager.review(&positions, Utc::now()).await {
+                        warn!("Hedge review failed: {}", e);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Close the account's hedges after its breach has cleared, keeping the
+    /// positions they offset
+    pub async fn unwind_recovered(
+        &self,
+        positions: &PositionManager,
+        account_id: AccountId,
+    ) -> Result<Vec<HedgeUnwind>> {
+        if !self.policy.unwind_on_recovery {
+            return Ok(Vec::new());
+        }
+        let mut unwound = Vec::new();
+        for hedge in self.hedges(account_id) {
+            self.close_hedge(&hedge).await?;
+            positions.close_position(hedge.hedge_position_id).await?;
+            unwound.push(self.forget(hedge, UnwindReason::Recovered));
+        }
+        Ok(unwound)
+    }
+
+    /// Unwind hedges whose position has closed, whose own position has gone, or
+    /// that have been held past `max_hedge_hours`. Hours too large to represent
+    /// never expire.
+    pub async fn review(
+        &self,
+        positions: &PositionManager,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<HedgeUnwind>> {
+        let max_age = self
+            .policy
+            .max_hedge_hours
+            .filter(|hours| hours.is_finite() && *hours >= 0.0)
+            .and_then(|hours| Duration::try_seconds((hours * 3600.0) as i64));
+        let all: Vec<Hedge> = self.hedges.iter().map(|h| h.clone()).collect();
+        let mut unwound = Vec::new();
+
+        for hedge in all {
+            let open: HashMap<PositionId, Position> = positions
+                .get_account_positions(hedge.account_id)
+                .await?
+                .into_iter()
+                .map(|p| (p.id, p))
+                .collect();
+
+            let reason = if !open.contains_key(&hedge.hedge_position_id) {
+                warn!(
+                    "Hedge {} for position {} closed outside the risk response",
+                    hedge.hedge_position_id, hedge.hedged_position_id
+                );
+                UnwindReason::HedgeClosed
+            } else if !open.contains_key(&hedge.hedged_position_id) {
+                self.close_hedge(&hedge).await?;
+                positions.close_position(hedge.hedge_position_id).await?;
+                UnwindReason::HedgedPositionClosed
+            } else if max_age.is_some_and(|max_age| now - hedge.opened_at >= max_age) {
+                // The hedge goes first, leaving the hedged position the only
+                // ticket on a shared symbol
+                self.close_hedge(&hedge).await?;
+                let hedged = &open[&hedge.hedged_position_id];
+                self.platform(hedge.account_id)?
+                    .close_position(&hedged.symbol, Some(hedged.size))
+                    .await?;
+                positions.close_position(hedge.hedged_position_id).await?;
+                positions.close_position(hedge.hedge_position_id).await?;
+                UnwindReason::Expired
+            } else {
+                continue;
+            };
+            unwound.push(self.forget(hedge, reason));
+        }
+        Ok(unwound)
+    }
+
+    fn forget(&self, hedge: Hedge, reason: UnwindReason) -> HedgeUnwind {
+        self.hedges.remove(&hedge.hedged_position_id);
+        info!(
+            "Unwound hedge {} for position {} on account {}: {:?}",
+            hedge.hedge_position_id, hedge.hedged_position_id, hedge.account_id, reason
+        );
+        HedgeUnwind { hedge, reason }
+    }
+}
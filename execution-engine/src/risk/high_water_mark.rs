@@ -0,0 +1,139 @@
+// Per-account equity high-water marks for trailing drawdown rules, kept across
+// restarts so a redeploy never lowers the mark a prop firm measures against
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use risk_types::AccountId;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+
+/// Highest equity an account has reached, and whether it has since breached
+/// its trailing drawdown limit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighWaterMark {
+    pub account_id: AccountId,
+    pub equity: Decimal,
+    pub reached_at: DateTime<Utc>,
+    /// Set on the first breach; the account stays locked out until cleared
+    #[serde(default)]
+    pub breached_at: Option<DateTime<Utc>>,
+}
+
+impl HighWaterMark {
+    pub fn new(account_id: AccountId, equity: Decimal, reached_at: DateTime<Utc>) -> Self {
+        Self {
+            account_id,
+            equity,
+            reached_at,
+            breached_at: None,
+        }
+    }
+
+    /// Raise the mark to `equity` if it is higher, returning whether it moved
+    pub fn raise(&mut self, equity: Decimal, at: DateTime<Utc>) -> bool {
+        if equity > self.equity {
+            self.equity = equity;
+            self.reached_at = at;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// How far `equity` is below the mark, as an amount and a percentage of it
+    pub fn drawdown(&self, equity: Decimal) -> (Decimal, Decimal) {
+        let amount = (self.equity - equity).max(dec!(0));
+        let percentage = if self.equity > dec!(0) {
+            amount / self.equity * dec!(100)
+        } else {
+            dec!(0)
+        };
+        (amount, percentage)
+    }
+}
+
+/// Durable high-water marks keyed by account
+#[async_trait]
+pub trait HighWaterMarkStore: Send + Sync + std::fmt::Debug {
+    async fn save(&self, mark: &HighWaterMark) -> Result<()>;
+    async fn load_all(&self) -> Result<Vec<HighWaterMark>>;
+}
+
+/// Keeps every account's mark in one JSON file, rewritten atomically on change
+#[derive(Debug)]
+pub struct FileHighWaterMarkStore {
+    path: PathBuf,
+    // Loaded on first use so a save never overwrites marks it has not read
+    marks: Mutex<Option<HashMap<AccountId, HighWaterMark>>>,
+}
+
+impl FileHighWaterMarkStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            marks: Mutex::new(None),
+        }
+    }
+
+    async fn read_file(&self) -> Result<HashMap<AccountId, HighWaterMark>> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let marks: Vec<HighWaterMark> = serde_json::from_str(&content)
+            .with_context(|| format!("Corrupt high-water mark file {}", self.path.display()))?;
+        Ok(marks
+            .into_iter()
+            .map(|mark| (mark.account_id, mark))
+            .collect())
+    }
+
+    async fn write_file(&self, marks: &HashMap<AccountId, HighWaterMark>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let marks: Vec<&HighWaterMark> = marks.values().collect();
+        let content = serde_json::to_string_pretty(&marks)?;
+
+        let temp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&temp_path, content).await?;
+        tokio::fs::rename(&temp_path, &self.path)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to replace high-water mark file {}",
+                    self.path.display()
+                )
+            })?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HighWaterMarkStore for FileHighWaterMarkStore {
+    async fn save(&self, mark: &HighWaterMark) -> Result<()> {
+        let mut guard = self.marks.lock().await;
+        if guard.is_none() {
+            *guard = Some(self.read_file().await?);
+        }
+        let marks = guard.as_mut().expect("marks loaded above");
+        marks.insert(mark.account_id, mark.clone());
+        self.write_file(marks).await
+    }
+
+    async fn load_all(&self) -> Result<Vec<HighWaterMark>> {
+        let mut guard = self.marks.lock().await;
+        let marks = self.read_file().await?;
+        let loaded = marks.values().cloned().collect();
+        *guard = Some(marks);
+        Ok(loaded)
+    }
+}
@@ -1,10 +1,20 @@
+use crate::alerting::{Alert, AlertGateway};
+use crate::execution::ExecutionPlan;
+use crate::platforms::abstraction::models::{UnifiedOrderSide, UnifiedPositionSide};
+use crate::platforms::abstraction::{UnifiedAccountInfo, UnifiedPosition};
 use crate::risk::config::MarginThresholds;
+use crate::risk::deleveraging::{
+    plan_reduction, DeleverageCandidate, DeleveragingConfig, ReductionPlan,
+};
+use crate::runtime::spawn_isolated;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use risk_types::*;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::time::{interval, Duration};
 use tracing::{error, info, warn};
@@ -117,6 +127,10 @@ impl MarginMonitor {
         account: &Account,
         margin_info: &MarginInfo,
     ) -> Result<()> {
+        if margin_info.margin_level > self.margin_thresholds.warning_level {
+            self.margin_alerts.resolve(account.id);
+        }
+
         if margin_info.margin_level <= self.margin_thresholds.warning_level
             && margin_info.margin_level > self.margin_thresholds.critical_level
         {
@@ -188,8 +202,12 @@ impl MarginMonitor {
             account_id, margin_info.margin_level
         );
 
+        let positions = self
+            .account_manager
+            .get_account_positions(&account_id)
+            .await?;
         self.margin_protection
-            .protect_account(account_id, margin_info)
+            .protect_account(account_id, margin_info, &positions, &self.margin_calculator)
             .await?;
 
         Ok(())
@@ -388,12 +406,15 @@ impl MarginCalculator {
         }
     }
 
-    pub async fn calculate_position_margin(&self, position: &Position) -> Result<Decimal> {
-        let leverage = self
-            .leverage_map
-            .get(&position.symbol)
+    fn leverage(&self, symbol: &str) -> Decimal {
+        self.leverage_map
+            .get(symbol)
             .map(|l| *l)
-            .unwrap_or(self.default_leverage);
+            .unwrap_or(self.default_leverage)
+    }
+
+    pub async fn calculate_position_margin(&self, position: &Position) -> Result<Decimal> {
+        let leverage = self.leverage(&position.symbol);
 
         let nominal_value = position.size * position.entry_price;
         Ok(nominal_value / leverage)
@@ -403,25 +424,147 @@ impl MarginCalculator {
         &self,
         proposed: &ProposedPosition,
     ) -> Result<Decimal> {
-        let leverage = self
-            .leverage_map
-            .get(&proposed.symbol)
-            .map(|l| *l)
-            .unwrap_or(self.default_leverage);
+        let leverage = self.leverage(&proposed.symbol);
 
         let nominal_value = proposed.size * proposed.expected_entry_price;
         Ok(nominal_value / leverage)
     }
+
+    /// Margin the plan's sizes would add to each of its accounts at
+    /// `entry_price`, all at once. On netting accounts the sizes are netted with
+    /// each other and the account's open position in the symbol, so a basket
+    /// against an open position can free margin. Accounts are acceptable while
+    /// their projected margin level stays at `min_margin_level` or above;
+    /// accounts without a state are left out.
+    pub fn calculate_basket_margin_impact(
+        &self,
+        plan: &ExecutionPlan,
+        side: &UnifiedOrderSide,
+        entry_price: Decimal,
+        accounts: &[AccountMarginState],
+        min_margin_level: Decimal,
+    ) -> BasketMarginImpact {
+        let symbol = compact_symbol(&plan.symbol);
+        let per_unit = entry_price / self.leverage(&symbol);
+        let sign = match side {
+            UnifiedOrderSide::Buy => dec!(1),
+            UnifiedOrderSide::Sell => dec!(-1),
+        };
+
+        let mut sizes: Vec<(&str, Decimal)> = Vec::new();
+        for assignment in &plan.account_assignments {
+            let size = Decimal::from_f64_retain(assignment.position_size).unwrap_or_default();
+            match sizes
+                .iter_mut()
+                .find(|(account_id, _)| *account_id == assignment.account_id)
+            {
+                Some((_, total)) => *total += size,
+                None => sizes.push((&assignment.account_id, size)),
+            }
+        }
+
+        let mut impact = BasketMarginImpact::default();
+        for (account_id, size) in sizes {
+            let Some(state) = accounts.iter().find(|a| a.account_id == account_id) else {
+                continue;
+            };
+            let additional = if state.hedging {
+                size * per_unit
+            } else {
+                let open = state
+                    .net_positions
+                    .get(&symbol)
+                    .copied()
+                    .unwrap_or_default();
+                ((open + sign * size).abs() - open.abs()) * per_unit
+            };
+            let projected_used = (state.used_margin + additional).max(dec!(0));
+            let level = |used: Decimal| (used > dec!(0)).then(|| state.equity / used * dec!(100));
+            let projected_margin_level = level(projected_used);
+
+            impact.total_additional_margin += additional;
+            impact.accounts.push(AccountMarginImpact {
+                account_id: account_id.to_string(),
+                current_margin_level: level(state.used_margin),
+                projected_margin_level,
+                additional_margin_required: additional,
+                remaining_free_margin: state.equity - projected_used,
+                impact_acceptable: additional <= dec!(0)
+                    || projected_margin_level.is_none_or(|level| level >= min_margin_level),
+            });
+        }
+        impact
+    }
+}
+
+/// `EUR_USD` and `EUR/USD` as `EURUSD`, the way the leverage map names symbols
+fn compact_symbol(symbol: &str) -> String {
+    symbol.replace(['_', '/'], "").to_uppercase()
+}
+
+/// An account's margin and open positions, for a basket to be added to
+#[derive(Debug, Clone, Default)]
+pub struct AccountMarginState {
+    pub account_id: String,
+    pub equity: Decimal,
+    pub used_margin: Decimal,
+    /// Net open size by symbol, long positive
+    pub net_positions: HashMap<String, Decimal>,
+    /// Opposite positions are held side by side rather than netted, so every
+    /// size adds margin
+    pub hedging: bool,
+}
+
+impl AccountMarginState {
+    pub fn new(
+        account_id: &str,
+        info: &UnifiedAccountInfo,
+        positions: &[UnifiedPosition],
+        hedging: bool,
+    ) -> Self {
+        let mut net_positions = HashMap::new();
+        for position in positions {
+            let size = match position.side {
+                UnifiedPositionSide::Long => position.quantity,
+                UnifiedPositionSide::Short => -position.quantity,
+            };
+            *net_positions
+                .entry(compact_symbol(&position.symbol))
+                .or_insert(dec!(0)) += size;
+        }
+        Self {
+            account_id: account_id.to_string(),
+            equity: info.equity,
+            used_margin: info.margin_used,
+            net_positions,
+            hedging,
+        }
+    }
 }
 
 pub struct MarginAlertManager {
     alerts: Arc<DashMap<AccountId, Vec<MarginAlert>>>,
+    gateway: Option<Arc<AlertGateway>>,
 }
 
 impl MarginAlertManager {
     pub fn new() -> Self {
         Self {
             alerts: Arc::new(DashMap::new()),
+            gateway: None,
+        }
+    }
+
+    /// Forward alerts to `gateway` for deduplicated delivery
+    pub fn with_gateway(mut self, gateway: Arc<AlertGateway>) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
+    /// The account's margin is back above the warning level
+    pub fn resolve(&self, account_id: AccountId) {
+        if let Some(gateway) = &self.gateway {
+            gateway.resolve(MARGIN_ALERT_TYPE, &account_id.to_string());
         }
     }
 
@@ -441,6 +584,13 @@ impl MarginAlertManager {
     }
 
     async fn store_alert(&self, alert: MarginAlert) -> Result<()> {
+        if let Some(gateway) = &self.gateway {
+            let gateway = gateway.clone();
+            let alert = Alert::from(&alert);
+            spawn_isolated("margin-alert-delivery", async move {
+                gateway.submit(alert).await;
+            });
+        }
         self.alerts
             .entry(alert.account_id)
             .or_insert_with(Vec::new)
@@ -449,20 +599,100 @@ impl MarginAlertManager {
     }
 }
 
-pub struct MarginProtectionSystem;
+const MARGIN_ALERT_TYPE: &str = "margin";
+
+impl From<&MarginAlert> for Alert {
+    fn from(alert: &MarginAlert) -> Self {
+        Self {
+            alert_type: MARGIN_ALERT_TYPE.to_string(),
+            key: alert.account_id.to_string(),
+            severity: alert.level,
+            account_id: Some(alert.account_id),
+            message: alert.message.clone(),
+            raised_at: alert.timestamp,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MarginProtectionSystem {
+    deleveraging: DeleveragingConfig,
+    convictions: DashMap<PositionId, Decimal>,
+}
 
 impl MarginProtectionSystem {
+    pub fn new(deleveraging: DeleveragingConfig) -> Self {
+        Self {
+            deleveraging,
+            convictions: DashMap::new(),
+        }
+    }
+
+    /// Entry confidence of a position, for the lowest conviction priority
+    pub fn record_conviction(&self, position_id: PositionId, conviction: Decimal) {
+        self.convictions.insert(position_id, conviction);
+    }
+
+    pub fn forget_position(&self, position_id: &PositionId) {
+        self.convictions.remove(position_id);
+    }
+
+    /// Ranked cuts that bring the account back to the configured target
+    /// margin level, with the margin each one frees
+    pub async fn reduction_plan(
+        &self,
+        margin_info: &MarginInfo,
+        positions: &[Position],
+        calculator: &MarginCalculator,
+    ) -> Result<ReductionPlan> {
+        let mut candidates = Vec::with_capacity(positions.len());
+        for position in positions {
+            candidates.push(DeleverageCandidate {
+                margin: calculator.calculate_position_margin(position).await?,
+                conviction: self.convictions.get(&position.id).map(|c| *c),
+                position: position.clone(),
+            });
+        }
+        Ok(plan_reduction(&self.deleveraging, margin_info, candidates))
+    }
+
     pub async fn protect_account(
         &self,
         account_id: AccountId,
         margin_info: &MarginInfo,
-    ) -> Result<()> {
+        positions: &[Position],
+        calculator: &MarginCalculator,
+    ) -> Result<ReductionPlan> {
         info!(
             "Protecting account {} with margin level {:.2}%",
             account_id, margin_info.margin_level
         );
 
-        Ok(())
+        let plan = self
+            .reduction_plan(margin_info, positions, calculator)
+            .await?;
+        for step in &plan.steps {
+            info!(
+                "Reduction {} for account {}: close {} of {} ({}), freeing {:.2} margin",
+                step.rank,
+                account_id,
+                step.size_to_close,
+                step.symbol,
+                step.position_id,
+                step.margin_freed
+            );
+        }
+        if !plan.reaches_target {
+            warn!(
+                "Closing every position on account {} frees {:.2} of the {:.2} margin needed for {:.1}%",
+                account_id,
+                plan.total_margin_freed(),
+                plan.margin_to_free,
+                plan.target_margin_level
+            );
+        }
+
+        Ok(plan)
     }
 
     pub async fn emergency_stop_out(
@@ -505,6 +735,31 @@ pub struct MarginRequirements {
     pub timestamp: DateTime<Utc>,
 }
 
+/// One account's share of a basket's margin impact
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountMarginImpact {
+    pub account_id: String,
+    /// None without margin in use
+    pub current_margin_level: Option<Decimal>,
+    pub projected_margin_level: Option<Decimal>,
+    /// Negative when the basket frees margin
+    pub additional_margin_required: Decimal,
+    pub remaining_free_margin: Decimal,
+    pub impact_acceptable: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BasketMarginImpact {
+    pub accounts: Vec<AccountMarginImpact>,
+    pub total_additional_margin: Decimal,
+}
+
+impl BasketMarginImpact {
+    pub fn account(&self, account_id: &str) -> Option<&AccountMarginImpact> {
+        self.accounts.iter().find(|a| a.account_id == account_id)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct MarginImpact {
     pub current_margin_level: Decimal,
@@ -1,17 +1,48 @@
 pub mod config;
+pub mod deleveraging;
+pub mod drawdown_ladder;
 pub mod drawdown_tracker;
 pub mod exposure_monitor;
+pub mod funding;
+pub mod hedging;
+pub mod high_water_mark;
 pub mod margin_monitor;
 pub mod pnl_calculator;
+pub mod portfolio_exposure;
 pub mod risk_response;
 pub mod risk_reward_tracker;
+pub mod snapshots;
 pub mod standalone_types; // Keep for conversion functions
+pub mod trading_day;
 
 pub use config::{load_config, RiskConfig};
+pub use deleveraging::{DeleveragePriority, DeleveragingConfig, ReductionPlan, ReductionStep};
+pub use drawdown_ladder::{
+    DrawdownLadder, DrawdownLadderConfig, DrawdownRung, EquityMarks, RiskLevel,
+};
 pub use drawdown_tracker::DrawdownTracker;
 pub use exposure_monitor::ExposureMonitor;
-pub use margin_monitor::MarginMonitor;
+pub use funding::{
+    FileFundingEventStore, FundingConfig, FundingDetector, FundingEvent, FundingEventStore,
+    FundingSource,
+};
+pub use hedging::{HedgeManager, HedgingPolicy};
+pub use high_water_mark::{FileHighWaterMarkStore, HighWaterMark, HighWaterMarkStore};
+pub use margin_monitor::{
+    AccountMarginImpact, AccountMarginState, BasketMarginImpact, MarginCalculator, MarginMonitor,
+    MarginProtectionSystem,
+};
 pub use pnl_calculator::RealTimePnLCalculator;
-pub use risk_response::RiskResponseSystem;
+pub use portfolio_exposure::{ClusterLimits, ExposureCluster, PortfolioExposure};
+pub use risk_response::{
+    CircuitBreakerClient, PositionManager, ResponseExecutor, RiskAuditLogger, RiskResponseSystem,
+    RiskThresholds,
+};
 pub use risk_reward_tracker::RiskRewardTracker;
-// Re-export shared types\npub use risk_types::*;
+pub use snapshots::{
+    AccountRiskSnapshot, FileRiskSnapshotStore, RiskSnapshot, RiskSnapshotConfig,
+    RiskSnapshotQuery, RiskSnapshotStore, RiskSnapshotter,
+};
+pub use trading_day::{TradingDayConfig, TradingDayRollover};
+// Re-export shared types
+pub use risk_types::*;
@@ -9,6 +9,11 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::broadcast;
 use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::market_data::CrossRates;
+use crate::platforms::abstraction::events::{EventData, PlatformEvent, PositionCloseEventData};
+use crate::risk::trading_day::TradingDayConfig;
 
 #[derive(Debug, thiserror::Error)]
 pub enum PnLCalculationError {
@@ -93,7 +98,7 @@ impl RealTimePnLCalculator {
         )
         .await;
 
-        for (position, result) in positions.iter().zip(results.into_iter()) {
+        for (position, result) in positions.iter().zip(results) {
             let updated_pnl = result?;
 
             // Cache the result
@@ -328,6 +333,44 @@ impl RealTimePnLCalculator {
         Ok(())
     }
 
+    /// Fold realized P&L from position close events into the tracker
+    pub async fn handle_platform_event(&self, event: &PlatformEvent) -> Result<()> {
+        let EventData::PositionClose(close) = &event.data else {
+            return Ok(());
+        };
+
+        let account_id = Uuid::parse_str(&event.account_id).map_err(|e| {
+            anyhow!(PnLCalculationError::InconsistentPositionData {
+                details: format!("invalid account id {}: {}", event.account_id, e),
+            })
+        })?;
+
+        self.apply_position_close(account_id, close).await
+    }
+
+    pub async fn apply_position_close(
+        &self,
+        account_id: AccountId,
+        close: &PositionCloseEventData,
+    ) -> Result<()> {
+        self.position_tracker
+            .record_realized_pnl(account_id, close.realized_pnl, close.closed_at);
+
+        if close.is_full_close() {
+            if let Ok(position_id) = Uuid::parse_str(&close.position_id) {
+                self.position_tracker.remove_position(position_id);
+                self.pnl_cache.remove(&position_id);
+            }
+        }
+
+        info!(
+            "Realized P&L for position {} in {}: {}",
+            close.position_id, close.symbol, close.realized_pnl
+        );
+
+        Ok(())
+    }
+
     pub async fn get_account_pnl(&self, account_id: AccountId) -> Result<AccountPnL> {
         let positions = self
             .position_tracker
@@ -361,10 +404,14 @@ impl RealTimePnLCalculator {
     }
 }
 
+type RealizedPnLLedger = DashMap<AccountId, Vec<(DateTime<Utc>, Decimal)>>;
+
 pub struct PositionTracker {
     positions: Arc<DashMap<PositionId, Position>>,
     account_positions: Arc<DashMap<AccountId, Vec<PositionId>>>,
     symbol_positions: Arc<DashMap<String, Vec<PositionId>>>,
+    realized_pnl: Arc<RealizedPnLLedger>,
+    trading_days: TradingDayConfig,
 }
 
 impl PositionTracker {
@@ -373,9 +420,37 @@ impl PositionTracker {
             positions: Arc::new(DashMap::new()),
             account_positions: Arc::new(DashMap::new()),
             symbol_positions: Arc::new(DashMap::new()),
+            realized_pnl: Arc::new(DashMap::new()),
+            trading_days: TradingDayConfig::default(),
         }
     }
 
+    /// Reset each account's realized P&L for the day at its trading day rollover
+    pub fn with_trading_days(mut self, trading_days: TradingDayConfig) -> Self {
+        self.trading_days = trading_days;
+        self
+    }
+
+    pub fn record_realized_pnl(&self, account_id: AccountId, amount: Decimal, at: DateTime<Utc>) {
+        self.realized_pnl
+            .entry(account_id)
+            .or_default()
+            .push((at, amount));
+    }
+
+    pub fn remove_position(&self, position_id: PositionId) -> Option<Position> {
+        let (_, position) = self.positions.remove(&position_id)?;
+
+        if let Some(mut ids) = self.account_positions.get_mut(&position.account_id) {
+            ids.retain(|id| *id != position_id);
+        }
+        if let Some(mut ids) = self.symbol_positions.get_mut(&position.symbol) {
+            ids.retain(|id| *id != position_id);
+        }
+
+        Some(position)
+    }
+
     pub async fn get_positions_by_symbol(&self, symbol: &str) -> Result<Vec<Position>> {
         let position_ids = self
             .symbol_positions
@@ -418,8 +493,23 @@ impl PositionTracker {
             .collect())
     }
 
-    pub async fn get_realized_pnl_today(&self, _account_id: AccountId) -> Result<Decimal> {
-        Ok(Decimal::ZERO)
+    /// Realized P&L since the account's last trading day rollover
+    pub async fn get_realized_pnl_today(&self, account_id: AccountId) -> Result<Decimal> {
+        let (day_start, _) = self
+            .trading_days
+            .for_account(&account_id.to_string())
+            .current_day(Utc::now());
+        Ok(self
+            .realized_pnl
+            .get(&account_id)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter(|(at, _)| *at >= day_start)
+                    .map(|(_, amount)| *amount)
+                    .sum()
+            })
+            .unwrap_or(Decimal::ZERO))
     }
 }
 
@@ -495,6 +585,7 @@ pub struct AccountPnL {
 pub struct CurrencyConverter {
     exchange_rates: Arc<DashMap<String, Decimal>>,
     rate_cache_duration: chrono::Duration,
+    cross_rates: Option<CrossRates>,
 }
 
 impl CurrencyConverter {
@@ -502,9 +593,17 @@ impl CurrencyConverter {
         Self {
             exchange_rates: Arc::new(DashMap::new()),
             rate_cache_duration: chrono::Duration::minutes(1), // Cache rates for 1 minute
+            cross_rates: None,
         }
     }
 
+    /// Convert at rates from the platforms' quotes, derived for pairs none quotes.
+    /// A missing or stale rate fails the conversion instead of falling back.
+    pub fn with_cross_rates(mut self, cross_rates: CrossRates) -> Self {
+        self.cross_rates = Some(cross_rates);
+        self
+    }
+
     pub async fn convert_to_account_currency(
         &self,
         amount: Decimal,
@@ -557,6 +656,20 @@ impl CurrencyConverter {
     }
 
     async fn get_exchange_rate(&self, from: &str, to: &str) -> Result<Decimal> {
+        if let Some(cross_rates) = &self.cross_rates {
+            return cross_rates
+                .rate(from, to)
+                .map(|rate| rate.rate)
+                .map_err(|e| {
+                    PnLCalculationError::CurrencyConversionFailed {
+                        from: from.to_string(),
+                        to: to.to_string(),
+                        reason: e.to_string(),
+                    }
+                    .into()
+                });
+        }
+
         let rate_key = format!("{}/{}", from, to);
 
         // Check cache first
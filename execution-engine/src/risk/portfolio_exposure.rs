@@ -0,0 +1,280 @@
+// Portfolio-level exposure: positions from every account netted per symbol, with
+// combined caps on clusters of correlated exposure such as "all USD longs"
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::exposure_monitor::{RebalanceAction, RebalancePriority, RebalanceRecommendation};
+use crate::platforms::abstraction::interfaces::ITradingPlatform;
+use crate::platforms::abstraction::models::{UnifiedOrderSide, UnifiedPositionSide};
+use risk_types::{Position, PositionType};
+
+/// Which direction of a cluster's combined exposure its cap applies to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClusterSide {
+    #[default]
+    Both,
+    Long,
+    Short,
+}
+
+/// A group of symbols whose exposure is capped together
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExposureCluster {
+    pub name: String,
+    /// Symbols in the cluster and the weight their net long exposure counts with:
+    /// -1 for symbols whose longs move against the cluster, e.g. EURUSD in
+    /// "USD longs"
+    pub members: HashMap<String, Decimal>,
+    #[serde(default)]
+    pub side: ClusterSide,
+    /// Cap on the combined exposure, as notional in quote currency
+    pub max_exposure: Decimal,
+}
+
+impl ExposureCluster {
+    /// Combined exposure, positive in the cluster's long direction
+    pub fn exposure(&self, net_by_symbol: &HashMap<String, Decimal>) -> Decimal {
+        self.members
+            .iter()
+            .map(|(symbol, weight)| {
+                net_by_symbol.get(symbol).copied().unwrap_or(Decimal::ZERO) * weight
+            })
+            .sum()
+    }
+
+    /// The part of `exposure` the cap applies to
+    fn capped(&self, exposure: Decimal) -> Decimal {
+        match self.side {
+            ClusterSide::Both => exposure.abs(),
+            ClusterSide::Long => exposure.max(Decimal::ZERO),
+            ClusterSide::Short => (-exposure).max(Decimal::ZERO),
+        }
+    }
+
+    /// Share of a change of `signed_notional` in `symbol`'s net exposure that
+    /// fits under the cap. Changes that reduce the capped exposure always fit.
+    fn allowed_fraction(
+        &self,
+        net_by_symbol: &HashMap<String, Decimal>,
+        symbol: &str,
+        signed_notional: Decimal,
+    ) -> Decimal {
+        let Some(weight) = self.members.get(symbol) else {
+            return Decimal::ONE;
+        };
+        let current = self.exposure(net_by_symbol);
+        let delta = signed_notional * weight;
+        let after = self.capped(current + delta);
+        if after <= self.max_exposure || after <= self.capped(current) {
+            return Decimal::ONE;
+        }
+        let room = if delta > Decimal::ZERO {
+            self.max_exposure - current
+        } else {
+            self.max_exposure + current
+        };
+        (room / delta.abs()).clamp(Decimal::ZERO, Decimal::ONE)
+    }
+}
+
+/// Net exposure per symbol across every account, signed long positive
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PortfolioExposure {
+    pub net_by_symbol: HashMap<String, Decimal>,
+    pub by_account: HashMap<String, HashMap<String, Decimal>>,
+    /// Accounts whose positions could not be loaded and are left out
+    pub unavailable_accounts: Vec<String>,
+}
+
+impl PortfolioExposure {
+    /// Positions from every platform, valued at their current price
+    pub async fn collect(platforms: &[(String, Arc<dyn ITradingPlatform + Send + Sync>)]) -> Self {
+        let mut exposure = Self::default();
+        for (account_id, platform) in platforms {
+            match platform.get_positions().await {
+                Ok(positions) => {
+                    for position in positions {
+                        let notional = position.quantity * position.current_price;
+                        let signed = match position.side {
+                            UnifiedPositionSide::Long => notional,
+                            UnifiedPositionSide::Short => -notional,
+                        };
+                        exposure.add(account_id, &position.symbol, signed);
+                    }
+                }
+                Err(e) => {
+                    warn!(
+                        "Leaving account {} out of portfolio exposure: {}",
+                        account_id, e
+                    );
+                    exposure.unavailable_accounts.push(account_id.clone());
+                }
+            }
+        }
+        exposure
+    }
+
+    /// Tracked positions, valued at their entry price like `ExposureMonitor`
+    pub fn from_positions(positions: &[Position]) -> Self {
+        let mut exposure = Self::default();
+        for position in positions {
+            let notional = position.size * position.entry_price;
+            let signed = match position.position_type {
+                PositionType::Long => notional,
+                PositionType::Short => -notional,
+            };
+            exposure.add(&position.account_id.to_string(), &position.symbol, signed);
+        }
+        exposure
+    }
+
+    fn add(&mut self, account_id: &str, symbol: &str, signed_notional: Decimal) {
+        *self
+            .net_by_symbol
+            .entry(symbol.to_string())
+            .or_insert(Decimal::ZERO) += signed_notional;
+        *self
+            .by_account
+            .entry(account_id.to_string())
+            .or_default()
+            .entry(symbol.to_string())
+            .or_insert(Decimal::ZERO) += signed_notional;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterExposure {
+    pub name: String,
+    pub exposure: Decimal,
+    pub max_exposure: Decimal,
+    pub breached: bool,
+}
+
+/// How much of a new trade the cluster caps allow
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeAllowance {
+    /// Share of the requested size that fits, from zero to one
+    pub fraction: Decimal,
+    /// The cluster that allowed the least, when it allowed less than all of it
+    pub limiting_cluster: Option<String>,
+}
+
+/// Combined caps on correlated clusters, shared by the pre-trade check in the
+/// orchestrator and the rebalance recommendations of `ExposureMonitor`
+#[derive(Debug, Clone, Default)]
+pub struct ClusterLimits {
+    clusters: Vec<ExposureCluster>,
+}
+
+impl ClusterLimits {
+    pub fn new(clusters: Vec<ExposureCluster>) -> Self {
+        Self { clusters }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+
+    pub fn evaluate(&self, portfolio: &PortfolioExposure) -> Vec<ClusterExposure> {
+        self.clusters
+            .iter()
+            .map(|cluster| {
+                let exposure = cluster.exposure(&portfolio.net_by_symbol);
+                ClusterExposure {
+                    name: cluster.name.clone(),
+                    exposure,
+                    max_exposure: cluster.max_exposure,
+                    breached: cluster.capped(exposure) > cluster.max_exposure,
+                }
+            })
+            .collect()
+    }
+
+    /// The share of a new `notional` trade in `symbol` that every cluster has
+    /// room for
+    pub fn check_trade(
+        &self,
+        portfolio: &PortfolioExposure,
+        symbol: &str,
+        side: &UnifiedOrderSide,
+        notional: Decimal,
+    ) -> TradeAllowance {
+        let signed = match side {
+            UnifiedOrderSide::Buy => notional,
+            UnifiedOrderSide::Sell => -notional,
+        };
+        let mut allowance = TradeAllowance {
+            fraction: Decimal::ONE,
+            limiting_cluster: None,
+        };
+        for cluster in &self.clusters {
+            let fraction = cluster.allowed_fraction(&portfolio.net_by_symbol, symbol, signed);
+            if fraction < allowance.fraction {
+                allowance = TradeAllowance {
+                    fraction,
+                    limiting_cluster: Some(cluster.name.clone()),
+                };
+            }
+        }
+        allowance
+    }
+
+    /// For each breached cluster, reductions of the symbols pushing it over its
+    /// cap, shared in proportion to their contribution
+    pub fn rebalance_recommendations(
+        &self,
+        portfolio: &PortfolioExposure,
+    ) -> Vec<RebalanceRecommendation> {
+        let mut recommendations = Vec::new();
+        for cluster in &self.clusters {
+            let exposure = cluster.exposure(&portfolio.net_by_symbol);
+            let capped = cluster.capped(exposure);
+            if capped <= cluster.max_exposure || exposure.is_zero() {
+                continue;
+            }
+            let excess = capped - cluster.max_exposure;
+            let direction = if exposure > Decimal::ZERO {
+                Decimal::ONE
+            } else {
+                -Decimal::ONE
+            };
+
+            let contributions: Vec<(&String, Decimal, Decimal)> = cluster
+                .members
+                .iter()
+                .filter_map(|(symbol, weight)| {
+                    let net = *portfolio.net_by_symbol.get(symbol)?;
+                    let contribution = net * weight * direction;
+                    (contribution > Decimal::ZERO).then_some((symbol, net, contribution))
+                })
+                .collect();
+            let total: Decimal = contributions.iter().map(|(_, _, c)| *c).sum();
+
+            for (symbol, net, contribution) in contributions {
+                let reduction = (excess * contribution / total).min(contribution);
+                let target = net - net * reduction / contribution;
+                recommendations.push(RebalanceRecommendation {
+                    symbol: symbol.clone(),
+                    current_exposure: net,
+                    current_percentage: contribution / cluster.max_exposure * dec!(100),
+                    target_exposure: target,
+                    target_percentage: (contribution - reduction) / cluster.max_exposure
+                        * dec!(100),
+                    action: RebalanceAction::Reduce,
+                    priority: if capped > cluster.max_exposure * dec!(1.2) {
+                        RebalancePriority::High
+                    } else {
+                        RebalancePriority::Medium
+                    },
+                });
+            }
+        }
+        recommendations
+    }
+}
@@ -8,12 +8,20 @@ use std::sync::Arc;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use super::hedging::{HedgeManager, HedgeUnwind, HedgingPolicy};
+use crate::runtime::feature_flags::{Feature, FeatureFlags};
+
+/// Breaches that a hedge can answer in place of reducing positions
+const HEDGEABLE_RISKS: [&str; 2] = ["exposure_concentration", "correlation_risk"];
+
 pub struct RiskResponseSystem {
     risk_thresholds: Arc<RiskThresholds>,
     position_manager: Arc<PositionManager>,
     circuit_breaker: Arc<CircuitBreakerClient>,
     risk_logger: Arc<RiskAuditLogger>,
     response_executor: Arc<ResponseExecutor>,
+    hedging: Option<Arc<HedgeManager>>,
+    feature_flags: Option<Arc<FeatureFlags>>,
 }
 
 impl RiskResponseSystem {
@@ -30,9 +38,41 @@ impl RiskResponseSystem {
             circuit_breaker,
             risk_logger,
             response_executor,
+            hedging: None,
+            feature_flags: None,
         }
     }
 
+    /// Answer exposure breaches with offsetting positions on the policy's accounts,
+    /// placed on the platforms registered with `HedgeManager::register_platform`
+    pub fn with_hedging(mut self, policy: HedgingPolicy) -> Self {
+        self.hedging = Some(Arc::new(HedgeManager::new(policy)));
+        self
+    }
+
+    pub fn hedging(&self) -> Option<Arc<HedgeManager>> {
+        self.hedging.clone()
+    }
+
+    /// Hold back position reductions on accounts where `feature_flags` has switched
+    /// off automatic position reduction
+    pub fn with_feature_flags(mut self, feature_flags: Arc<FeatureFlags>) -> Self {
+        self.feature_flags = Some(feature_flags);
+        self
+    }
+
+    fn reduction_disabled(&self, action: &ResponseAction) -> bool {
+        let account_id = match action {
+            ResponseAction::ReducePositions { account_id, .. }
+            | ResponseAction::DiversifyPositions { account_id, .. }
+            | ResponseAction::ReduceCorrelatedPositions { account_id, .. } => account_id,
+            _ => return false,
+        };
+        self.feature_flags.as_ref().is_some_and(|flags| {
+            !flags.is_enabled(&account_id.to_string(), Feature::AutoPositionReduction)
+        })
+    }
+
     pub async fn evaluate_and_respond(&self, risk_event: RiskEvent) -> Result<RiskResponse> {
         let severity = self.assess_risk_severity(&risk_event).await?;
         let response_action = self
@@ -43,7 +83,17 @@ impl RiskResponseSystem {
             .log_risk_event(&risk_event, &response_action)
             .await?;
 
-        let execution_result = self.execute_response_action(&response_action).await?;
+        let execution_result = if self.reduction_disabled(&response_action) {
+            warn!(
+                "Automatic position reduction is disabled for account {}, not executing {:?}",
+                risk_event.account_id, response_action
+            );
+            ResponseExecutionResult::Failed {
+                reason: "Automatic position reduction is disabled for the account".to_string(),
+            }
+        } else {
+            self.execute_response_action(&response_action).await?
+        };
 
         self.risk_logger
             .log_response_execution(&response_action, &execution_result)
@@ -84,6 +134,8 @@ impl RiskResponseSystem {
                     RiskSeverity::Medium
                 }
             }
+            // A trailing drawdown breach fails a prop firm account outright
+            "trailing_drawdown_exceeded" => RiskSeverity::Extreme,
             "exposure_concentration" => {
                 if threshold_ratio > dec!(2.0) {
                     RiskSeverity::High
@@ -146,6 +198,21 @@ impl RiskResponseSystem {
         risk_event: &RiskEvent,
         severity: RiskSeverity,
     ) -> Result<ResponseAction> {
+        if let Some(hedging) = &self.hedging {
+            if hedging.applies_to(risk_event.account_id)
+                && HEDGEABLE_RISKS.contains(&risk_event.risk_type.as_str())
+                && matches!(
+                    severity,
+                    RiskSeverity::Medium | RiskSeverity::High | RiskSeverity::Critical
+                )
+            {
+                return Ok(ResponseAction::HedgeExposure {
+                    account_id: risk_event.account_id,
+                    hedge_ratio: hedging.policy().hedge_ratio,
+                });
+            }
+        }
+
         match (risk_event.risk_type.as_str(), severity) {
             ("margin_level", RiskSeverity::Critical) => Ok(ResponseAction::ReducePositions {
                 account_id: risk_event.account_id,
@@ -174,6 +241,12 @@ impl RiskResponseSystem {
                 reason: "Maximum drawdown exceeded - emergency stop activated".to_string(),
             }),
 
+            ("trailing_drawdown_exceeded", _) => Ok(ResponseAction::EmergencyStop {
+                scope: EmergencyStopScope::Account(risk_event.account_id),
+                reason: "Trailing drawdown from high-water mark exceeded - account locked out"
+                    .to_string(),
+            }),
+
             ("exposure_concentration", RiskSeverity::Medium) => {
                 Ok(ResponseAction::DiversifyPositions {
                     account_id: risk_event.account_id,
@@ -262,6 +335,20 @@ impl RiskResponseSystem {
                 })
             }
 
+            ResponseAction::HedgeExposure {
+                account_id,
+                hedge_ratio,
+            } => match &self.hedging {
+                Some(hedging) => {
+                    hedging
+                        .hedge_account(&self.position_manager, *account_id, *hedge_ratio)
+                        .await
+                }
+                None => Ok(ResponseExecutionResult::Failed {
+                    reason: "Hedging is not configured".to_string(),
+                }),
+            },
+
             ResponseAction::Monitor => Ok(ResponseExecutionResult::MonitoringContinued),
         }
     }
@@ -339,6 +426,76 @@ impl RiskResponseSystem {
         }
     }
 
+    /// Respond to a drawdown from the equity high-water mark above `max_trailing_drawdown`
+    pub async fn handle_trailing_drawdown_risk(
+        &self,
+        account_id: AccountId,
+        trailing_drawdown: Decimal,
+        max_trailing_drawdown: Decimal,
+    ) -> Result<Option<RiskResponse>> {
+        if trailing_drawdown > max_trailing_drawdown {
+            let risk_event = self
+                .create_risk_event(
+                    "trailing_drawdown_exceeded".to_string(),
+                    account_id,
+                    format!(
+                        "Trailing drawdown {:.2}% exceeds threshold {:.2}%",
+                        trailing_drawdown, max_trailing_drawdown
+                    ),
+                    trailing_drawdown,
+                    max_trailing_drawdown,
+                )
+                .await;
+
+            let response = self.evaluate_and_respond(risk_event).await?;
+            Ok(Some(response))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Respond to exposure above `max_exposure`, or unwind the account's hedges
+    /// once it is back within it
+    pub async fn handle_exposure_risk(
+        &self,
+        account_id: AccountId,
+        current_exposure: Decimal,
+        max_exposure: Decimal,
+    ) -> Result<Option<RiskResponse>> {
+        if current_exposure > max_exposure {
+            let risk_event = self
+                .create_risk_event(
+                    "exposure_concentration".to_string(),
+                    account_id,
+                    format!(
+                        "Exposure {:.2}% exceeds limit {:.2}%",
+                        current_exposure, max_exposure
+                    ),
+                    current_exposure,
+                    max_exposure,
+                )
+                .await;
+
+            let response = self.evaluate_and_respond(risk_event).await?;
+            Ok(Some(response))
+        } else {
+            if let Some(hedging) = &self.hedging {
+                hedging
+                    .unwind_recovered(&self.position_manager, account_id)
+                    .await?;
+            }
+            Ok(None)
+        }
+    }
+
+    /// Unwind hedges whose positions closed or that have been held too long
+    pub async fn review_hedges(&self) -> Result<Vec<HedgeUnwind>> {
+        match &self.hedging {
+            Some(hedging) => hedging.review(&self.position_manager, Utc::now()).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
     pub async fn handle_margin_risk(
         &self,
         account_id: AccountId,
@@ -410,6 +567,46 @@ impl PositionManager {
             .unwrap_or_default())
     }
 
+    pub async fn add_position(&self, position: Position) {
+        self.positions
+            .entry(position.account_id)
+            .or_default()
+            .push(position);
+    }
+
+    /// Open a position at `price`, or at market when none is known
+    pub async fn open_position(
+        &self,
+        account_id: AccountId,
+        symbol: &str,
+        position_type: PositionType,
+        size: Decimal,
+        price: Option<Decimal>,
+    ) -> Result<Position> {
+        info!(
+            "Opening {:?} position of {} {} for account {}",
+            position_type, size, symbol, account_id
+        );
+
+        let position = Position {
+            id: Uuid::new_v4(),
+            account_id,
+            symbol: symbol.to_string(),
+            position_type,
+            size,
+            entry_price: price.unwrap_or_default(),
+            current_price: price,
+            unrealized_pnl: None,
+            max_favorable_excursion: dec!(0),
+            max_adverse_excursion: dec!(0),
+            stop_loss: None,
+            take_profit: None,
+            opened_at: Utc::now(),
+        };
+        self.add_position(position.clone()).await;
+        Ok(position)
+    }
+
     pub async fn reduce_position_size(
         &self,
         position_id: PositionId,
@@ -428,6 +625,9 @@ impl PositionManager {
 
     pub async fn close_position(&self, position_id: PositionId) -> Result<PositionClosureResult> {
         info!("Closing position {}", position_id);
+        for mut positions in self.positions.iter_mut() {
+            positions.retain(|p| p.id != position_id);
+        }
 
         Ok(PositionClosureResult {
             position_id,
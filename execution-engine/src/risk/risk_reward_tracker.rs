@@ -1,4 +1,6 @@
+use crate::alerting::{Alert, AlertGateway};
 use crate::risk::pnl_calculator::PositionTracker;
+use crate::runtime::spawn_isolated;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
@@ -393,18 +395,34 @@ impl MarketDataProvider {
 
 pub struct RiskRewardAlertManager {
     alerts: Arc<DashMap<PositionId, Vec<RiskRewardAlert>>>,
+    gateway: Option<Arc<AlertGateway>>,
 }
 
 impl RiskRewardAlertManager {
     pub fn new() -> Self {
         Self {
             alerts: Arc::new(DashMap::new()),
+            gateway: None,
         }
     }
 
+    /// Forward alerts to `gateway` for deduplicated delivery
+    pub fn with_gateway(mut self, gateway: Arc<AlertGateway>) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
     pub async fn send_alert(&self, alert: RiskRewardAlert) -> Result<()> {
         warn!("Risk/Reward Alert: {}", alert.message);
 
+        if let Some(gateway) = &self.gateway {
+            let gateway = gateway.clone();
+            let alert = Alert::from(&alert);
+            spawn_isolated("risk-reward-alert-delivery", async move {
+                gateway.submit(alert).await;
+            });
+        }
+
         self.alerts
             .entry(alert.position_id)
             .or_insert_with(Vec::new)
@@ -432,6 +450,25 @@ pub enum RRAlertType {
     TargetAdjustmentNeeded,
 }
 
+/// Position-level alerts are never resolved explicitly; the gateway drops them once
+/// the position stops raising them
+impl From<&RiskRewardAlert> for Alert {
+    fn from(alert: &RiskRewardAlert) -> Self {
+        let severity = match alert.alert_type {
+            RRAlertType::HighMAE => AlertLevel::Critical,
+            _ => AlertLevel::Warning,
+        };
+        Self {
+            alert_type: "risk_reward".to_string(),
+            key: format!("{}:{:?}", alert.position_id, alert.alert_type).to_lowercase(),
+            severity,
+            account_id: Some(alert.account_id),
+            message: alert.message.clone(),
+            raised_at: alert.timestamp,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PortfolioRRSummary {
     pub portfolio_rr_ratio: Decimal,
@@ -0,0 +1,448 @@
+// Intraday risk snapshots: every few minutes each account's exposure, margin,
+// drawdown and the risk open to its stops are captured and appended to a store,
+// so how risk built up and came off during a session can be reviewed afterwards
+// rather than only as it stands now
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use super::funding::FundingDetector;
+use super::TradingDayConfig;
+use crate::dashboard::{live_accounts, to_account_currency, ExposureHeatmap};
+use crate::execution::TradeExecutionOrchestrator;
+use crate::market_data::cross_rates::currency_pair;
+use crate::market_data::CrossRates;
+use crate::platforms::abstraction::models::UnifiedPositionSide;
+use crate::platforms::abstraction::{UnifiedAccountInfo, UnifiedPosition};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskSnapshotConfig {
+    pub enabled: bool,
+    /// Minutes between snapshots
+    pub interval_mins: u64,
+    /// JSON lines file the snapshots are appended to
+    pub path: String,
+    /// Percent of equity one R stands for when open risk is given in R
+    pub r_pct: Decimal,
+}
+
+impl Default for RiskSnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_mins: 5,
+            path: "data/risk_snapshots.jsonl".to_string(),
+            r_pct: dec!(1),
+        }
+    }
+}
+
+impl RiskSnapshotConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_mins == 0 {
+            return Err("Risk snapshot interval_mins must be at least 1".to_string());
+        }
+        if self.r_pct <= dec!(0) || self.r_pct >= dec!(100) {
+            return Err(format!(
+                "Risk snapshot r_pct must be between 0% and 100%, not {}",
+                self.r_pct
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// One account's risk at the moment of a snapshot. Amounts are in the account
+/// currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountRiskSnapshot {
+    pub account_id: String,
+    pub currency: String,
+    pub balance: Decimal,
+    pub equity: Decimal,
+    pub margin_used: Decimal,
+    pub margin_available: Decimal,
+    /// Equity over margin used, in percent; None without margin in use
+    pub margin_level_pct: Option<Decimal>,
+    pub trading_day: NaiveDate,
+    /// Highest equity seen in the snapshots so far
+    pub peak_equity: Decimal,
+    /// Equity at the first snapshot of the trading day
+    pub day_open_equity: Decimal,
+    /// Percent below the peak equity
+    pub drawdown_pct: Decimal,
+    /// Percent below the day's opening equity
+    pub daily_drawdown_pct: Decimal,
+    /// Deposits less withdrawals since the previous snapshot, by which the peak
+    /// and day-open equity were moved before measuring
+    #[serde(default)]
+    pub funding: Decimal,
+    pub open_positions: usize,
+    /// Net notional by symbol, negative when short
+    pub exposure: BTreeMap<String, Decimal>,
+    /// Sum of the notionals regardless of direction
+    pub gross_exposure: Decimal,
+    /// What the open positions would lose were every stop hit from here
+    pub open_risk: Decimal,
+    /// `open_risk` in R; None without equity
+    pub open_r: Option<Decimal>,
+    /// Positions with no stop, whose risk has no bound
+    pub unprotected_positions: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskSnapshot {
+    pub taken_at: DateTime<Utc>,
+    /// By account id
+    pub accounts: Vec<AccountRiskSnapshot>,
+    /// Positions left out of exposure and open risk for want of a rate, as
+    /// `account_id:symbol`
+    pub unconverted: Vec<String>,
+    /// Accounts whose platform could not report them
+    pub unavailable_accounts: Vec<String>,
+}
+
+impl RiskSnapshot {
+    pub fn account(&self, account_id: &str) -> Option<&AccountRiskSnapshot> {
+        self.accounts.iter().find(|a| a.account_id == account_id)
+    }
+}
+
+/// Which snapshots to return, and of which account
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RiskSnapshotQuery {
+    pub account_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Durable storage for risk snapshots, appended once each and never rewritten
+#[async_trait]
+pub trait RiskSnapshotStore: Send + Sync + std::fmt::Debug {
+    async fn append(&self, snapshot: &RiskSnapshot) -> Result<()>;
+    /// Every snapshot, oldest first
+    async fn load(&self) -> Result<Vec<RiskSnapshot>>;
+}
+
+/// Append-only JSON lines file, one snapshot per line
+#[derive(Debug)]
+pub struct FileRiskSnapshotStore {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl FileRiskSnapshotStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl RiskSnapshotStore for FileRiskSnapshotStore {
+    async fn append(&self, snapshot: &RiskSnapshot) -> Result<()> {
+        let mut line = serde_json::to_string(snapshot)?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .with_context(|| format!("Failed to open risk snapshots {}", self.path.display()))?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Vec<RiskSnapshot>> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut snapshots = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<RiskSnapshot>(line) {
+                Ok(snapshot) => snapshots.push(snapshot),
+                // A torn final line from a crash should not lose the rest of the series
+                Err(e) => warn!(
+                    "Skipping unreadable risk snapshot line {} in {}: {}",
+                    number + 1,
+                    self.path.display(),
+                    e
+                ),
+            }
+        }
+        snapshots.sort_by_key(|snapshot| snapshot.taken_at);
+        Ok(snapshots)
+    }
+}
+
+/// Peak and day-open equity an account's drawdowns are measured from
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct EquityMarks {
+    peak: Decimal,
+    trading_day: NaiveDate,
+    day_open: Decimal,
+}
+
+/// Captures risk snapshots of every account registered with the orchestrator and
+/// serves them back as time series
+pub struct RiskSnapshotter {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    store: Arc<dyn RiskSnapshotStore>,
+    config: RiskSnapshotConfig,
+    trading_days: TradingDayConfig,
+    rates: Option<CrossRates>,
+    funding: Option<Arc<FundingDetector>>,
+    marks: Mutex<HashMap<String, EquityMarks>>,
+}
+
+impl RiskSnapshotter {
+    pub fn new(
+        orchestrator: Arc<TradeExecutionOrchestrator>,
+        store: Arc<dyn RiskSnapshotStore>,
+        config: RiskSnapshotConfig,
+    ) -> Self {
+        Self {
+            orchestrator,
+            store,
+            config,
+            trading_days: TradingDayConfig::default(),
+            rates: None,
+            funding: None,
+            marks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Measure daily drawdown from each account's own trading day rollover
+    pub fn with_trading_days(mut self, trading_days: TradingDayConfig) -> Self {
+        self.trading_days = trading_days;
+        self
+    }
+
+    /// Convert exposure and open risk in currencies other than the account's and
+    /// the position's pair at `rates`
+    pub fn with_cross_rates(mut self, rates: CrossRates) -> Self {
+        self.rates = Some(rates);
+        self
+    }
+
+    /// Watch each snapshot's balances for deposits and withdrawals, and keep
+    /// them out of the drawdowns
+    pub fn with_funding_detector(mut self, funding: Arc<FundingDetector>) -> Self {
+        self.funding = Some(funding);
+        self
+    }
+
+    pub fn config(&self) -> &RiskSnapshotConfig {
+        &self.config
+    }
+
+    /// Carry each account's peak and day-open equity over from the last stored
+    /// snapshot. Returns how many snapshots were read.
+    pub async fn load(&self) -> Result<usize> {
+        let snapshots = self.store.load().await?;
+        let mut marks = self.marks.lock().await;
+        for account in snapshots.iter().flat_map(|s| &s.accounts) {
+            marks.insert(
+                account.account_id.clone(),
+                EquityMarks {
+                    peak: account.peak_equity,
+                    trading_day: account.trading_day,
+                    day_open: account.day_open_equity,
+                },
+            );
+        }
+        info!(
+            "Risk snapshots resumed from {} stored snapshots",
+            snapshots.len()
+        );
+        Ok(snapshots.len())
+    }
+
+    /// Take a snapshot of every account now, without storing it
+    pub async fn capture(&self) -> RiskSnapshot {
+        let taken_at = Utc::now();
+        let (accounts, unavailable_accounts) = live_accounts(&self.orchestrator).await;
+        self.build(accounts, unavailable_accounts, taken_at).await
+    }
+
+    /// Take a snapshot of every account now and store it
+    pub async fn record(&self) -> Result<RiskSnapshot> {
+        let snapshot = self.capture().await;
+        self.store.append(&snapshot).await?;
+        Ok(snapshot)
+    }
+
+    /// Stored snapshots matching `query`, oldest first; with an account id, each
+    /// holds only that account
+    pub async fn series(&self, query: &RiskSnapshotQuery) -> Result<Vec<RiskSnapshot>> {
+        let mut snapshots = self.store.load().await?;
+        snapshots.retain(|s| {
+            query.from.is_none_or(|from| s.taken_at >= from)
+                && query.to.is_none_or(|to| s.taken_at <= to)
+        });
+        if let Some(account_id) = &query.account_id {
+            for snapshot in &mut snapshots {
+                snapshot.accounts.retain(|a| &a.account_id == account_id);
+                let prefix = format!("{}:", account_id);
+                snapshot.unconverted.retain(|p| p.starts_with(&prefix));
+                snapshot.unavailable_accounts.retain(|a| a == account_id);
+            }
+        }
+        Ok(snapshots)
+    }
+
+    /// The snapshot of `accounts`, by account id with their open positions, moving
+    /// the equity marks on
+    async fn build(
+        &self,
+        accounts: Vec<(String, UnifiedAccountInfo, Vec<UnifiedPosition>)>,
+        unavailable_accounts: Vec<String>,
+        taken_at: DateTime<Utc>,
+    ) -> RiskSnapshot {
+        let heatmap =
+            ExposureHeatmap::build(accounts.clone(), Vec::new(), self.rates.as_ref(), taken_at);
+        let mut marks = self.marks.lock().await;
+
+        let mut snapshots = Vec::new();
+        for (account_id, info, positions) in &accounts {
+            // The heatmap's columns are sorted by account id like its rows
+            let column = heatmap
+                .accounts
+                .iter()
+                .position(|a| &a.account_id == account_id)
+                .unwrap_or_default();
+            let exposure: BTreeMap<String, Decimal> = heatmap
+                .symbols
+                .iter()
+                .map(|row| (row.key.clone(), row.cells[column].notional))
+                .filter(|(_, notional)| !notional.is_zero())
+                .collect();
+
+            let account_currency = info.currency.to_uppercase();
+            let mut open_risk = Decimal::ZERO;
+            let mut unprotected_positions = 0;
+            for position in positions {
+                let Some(stop) = position.stop_loss else {
+                    unprotected_positions += 1;
+                    continue;
+                };
+                let per_unit = match position.side {
+                    UnifiedPositionSide::Long => position.current_price - stop,
+                    UnifiedPositionSide::Short => stop - position.current_price,
+                };
+                // A stop beyond the price has locked in profit and risks nothing
+                let loss = per_unit.max(Decimal::ZERO) * position.quantity;
+                let compact = position.symbol.replace(['_', '/'], "");
+                let loss = match currency_pair(&compact) {
+                    Some((base, quote)) => to_account_currency(
+                        loss,
+                        &quote,
+                        &account_currency,
+                        (&base, &quote, position.current_price),
+                        self.rates.as_ref(),
+                    ),
+                    None => Some(loss),
+                };
+                open_risk += loss.unwrap_or_default();
+            }
+
+            let trading_day = self
+                .trading_days
+                .for_account(account_id)
+                .trading_day(taken_at);
+            let funding = match &self.funding {
+                Some(detector) => match detector.observe(account_id, info, taken_at).await {
+                    Ok(events) => events.iter().map(|e| e.amount).sum(),
+                    Err(e) => {
+                        warn!("Failed to record funding of account {}: {}", account_id, e);
+                        Decimal::ZERO
+                    }
+                },
+                None => Decimal::ZERO,
+            };
+            let mark = match marks.entry(account_id.clone()) {
+                Entry::Occupied(entry) => {
+                    let mark = entry.into_mut();
+                    mark.peak += funding;
+                    mark.day_open += funding;
+                    mark
+                }
+                Entry::Vacant(entry) => entry.insert(EquityMarks {
+                    peak: info.equity,
+                    trading_day,
+                    day_open: info.equity,
+                }),
+            };
+            mark.peak = mark.peak.max(info.equity);
+            if mark.trading_day != trading_day {
+                mark.trading_day = trading_day;
+                mark.day_open = info.equity;
+            }
+            let below = |mark: Decimal| {
+                if mark > Decimal::ZERO {
+                    ((mark - info.equity) / mark * dec!(100)).max(Decimal::ZERO)
+                } else {
+                    Decimal::ZERO
+                }
+            };
+
+            snapshots.push(AccountRiskSnapshot {
+                account_id: account_id.clone(),
+                currency: account_currency,
+                balance: info.balance,
+                equity: info.equity,
+                margin_used: info.margin_used,
+                margin_available: info.margin_available,
+                margin_level_pct: (info.margin_used > Decimal::ZERO)
+                    .then(|| info.equity / info.margin_used * dec!(100)),
+                trading_day,
+                peak_equity: mark.peak,
+                day_open_equity: mark.day_open,
+                drawdown_pct: below(mark.peak),
+                daily_drawdown_pct: below(mark.day_open),
+                funding,
+                open_positions: positions.len(),
+                gross_exposure: exposure.values().map(|n| n.abs()).sum(),
+                exposure,
+                open_risk,
+                open_r: (info.equity > Decimal::ZERO)
+                    .then(|| open_risk / (info.equity * self.config.r_pct / dec!(100))),
+                unprotected_positions,
+            });
+        }
+        snapshots.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+
+        RiskSnapshot {
+            taken_at,
+            accounts: snapshots,
+            unconverted: heatmap.unconverted,
+            unavailable_accounts,
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Local time at which an account's trading day ends and the next one begins, e.g.
+/// 17:00 America/New_York for most FX brokers. Daylight saving is followed, so the
+/// rollover stays at the same local time while its UTC offset changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradingDayRollover {
+    #[serde(default = "default_rollover_time")]
+    pub time: NaiveTime,
+    /// IANA timezone name such as "America/New_York"
+    #[serde(default = "default_timezone")]
+    pub timezone: Tz,
+}
+
+fn default_rollover_time() -> NaiveTime {
+    NaiveTime::MIN
+}
+
+fn default_timezone() -> Tz {
+    Tz::UTC
+}
+
+impl Default for TradingDayRollover {
+    /// UTC midnight
+    fn default() -> Self {
+        Self {
+            time: default_rollover_time(),
+            timezone: default_timezone(),
+        }
+    }
+}
+
+impl TradingDayRollover {
+    pub fn new(time: NaiveTime, timezone: Tz) -> Self {
+        Self { time, timezone }
+    }
+
+    /// The 17:00 New York rollover used by FX brokers
+    pub fn new_york_close() -> Self {
+        Self::new(
+            NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            Tz::America__New_York,
+        )
+    }
+
+    /// Trading date of `at`, named after the local date on which its trading day ends.
+    /// With a 17:00 rollover, Monday 18:00 already belongs to Tuesday.
+    pub fn trading_day(&self, at: DateTime<Utc>) -> NaiveDate {
+        let local = at.with_timezone(&self.timezone);
+        let date = local.date_naive();
+        if self.time != NaiveTime::MIN && local.time() >= self.time {
+            date.succ_opt().unwrap_or(date)
+        } else {
+            date
+        }
+    }
+
+    /// When the trading day `date` begins
+    pub fn start_of(&self, date: NaiveDate) -> DateTime<Utc> {
+        let local_date = if self.time == NaiveTime::MIN {
+            date
+        } else {
+            date.pred_opt().unwrap_or(date)
+        };
+        self.local_instant(local_date.and_time(self.time))
+    }
+
+    /// Start and (exclusive) end of the trading day `date`; shorter or longer than
+    /// 24 hours on daylight saving changes
+    pub fn bounds(&self, date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        let next = date.succ_opt().unwrap_or(date);
+        (self.start_of(date), self.start_of(next))
+    }
+
+    /// Bounds of the trading day `at` falls in
+    pub fn current_day(&self, at: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        self.bounds(self.trading_day(at))
+    }
+
+    /// True from `lead` before the last rollover of the week until the market reopens
+    /// with the Monday trading day
+    pub fn is_weekend(&self, at: DateTime<Utc>, lead: Duration) -> bool {
+        let weekend = |date: NaiveDate| matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        weekend(self.trading_day(at)) || weekend(self.trading_day(at + lead))
+    }
+
+    /// A local time as an instant. Times skipped by a spring-forward move to the first
+    /// valid time after them; times repeated by a fall-back take the first occurrence.
+    fn local_instant(&self, mut local: chrono::NaiveDateTime) -> DateTime<Utc> {
+        loop {
+            if let Some(instant) = self.timezone.from_local_datetime(&local).earliest() {
+                return instant.with_timezone(&Utc);
+            }
+            local += Duration::minutes(15);
+        }
+    }
+}
+
+/// Trading day rollover for every account, with per-account overrides for accounts
+/// whose broker rolls over at a different time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TradingDayConfig {
+    #[serde(flatten)]
+    pub default: TradingDayRollover,
+    /// Rollover by account id
+    #[serde(default)]
+    pub accounts: HashMap<String, TradingDayRollover>,
+}
+
+impl TradingDayConfig {
+    pub fn for_account(&self, account_id: &str) -> TradingDayRollover {
+        self.accounts
+            .get(account_id)
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// From the earliest start to the latest end of `date` across all accounts
+    pub fn widest_bounds(&self, date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        self.accounts
+            .values()
+            .map(|rollover| rollover.bounds(date))
+            .fold(self.default.bounds(date), |(from, to), (start, end)| {
+                (from.min(start), to.max(end))
+            })
+    }
+}
@@ -0,0 +1,283 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use super::config::AccountBootstrap;
+use crate::alerting::AlertGateway;
+use crate::execution::TradeExecutionOrchestrator;
+use crate::platforms::abstraction::{
+    ClockSyncConfig, ClockSyncPlatform, DegradingPlatform, DryRunMode, DryRunPlatform,
+    FailoverPlatform, ITradingPlatform, MarketDataHub, MarketDataHubConfig, MonitoredPlatform,
+    PerformanceConfig, PerformanceMonitor, PlatformEndpoint, PlatformError, QuotaConfig,
+    QuotaManager, QuotaPlatform, QuoteFilteringPlatform, ResilientPlatform, ServerClocks,
+    SymbolMappingPlatform,
+};
+use crate::platforms::PlatformType;
+
+/// Creates a connected platform instance for a configured account
+#[async_trait]
+pub trait PlatformConnector: Send + Sync {
+    async fn connect(
+        &self,
+        account: &AccountBootstrap,
+    ) -> Result<Arc<dyn ITradingPlatform + Send + Sync>, PlatformError>;
+
+    /// Connect the account through one of its failover endpoints
+    async fn connect_endpoint(
+        &self,
+        account: &AccountBootstrap,
+        endpoint: &PlatformEndpoint,
+    ) -> Result<Arc<dyn ITradingPlatform + Send + Sync>, PlatformError> {
+        let _ = (account, endpoint);
+        Err(PlatformError::FeatureNotSupported {
+            feature: "failover endpoints".to_string(),
+        })
+    }
+}
+
+/// Connects configured accounts and registers them with the orchestrator
+#[derive(Default)]
+pub struct AccountBootstrapper {
+    connectors: HashMap<PlatformType, Arc<dyn PlatformConnector>>,
+    quotas: HashMap<PlatformType, Arc<QuotaManager>>,
+    alert_gateway: Option<Arc<AlertGateway>>,
+    dry_run: Option<Arc<DryRunMode>>,
+    performance: PerformanceConfig,
+    clock_sync: ClockSyncConfig,
+    clocks: ServerClocks,
+    market_data_hub: MarketDataHubConfig,
+}
+
+impl AccountBootstrapper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_connector(
+        mut self,
+        platform: PlatformType,
+        connector: Arc<dyn PlatformConnector>,
+    ) -> Self {
+        self.connectors.insert(platform, connector);
+        self
+    }
+
+    /// Share each platform's request quota between every account and subsystem
+    /// calling it
+    pub fn with_quotas(mut self, quotas: &HashMap<PlatformType, QuotaConfig>) -> Self {
+        self.quotas = quotas
+            .iter()
+            .map(|(platform, config)| {
+                (
+                    platform.clone(),
+                    Arc::new(QuotaManager::new(config.clone())),
+                )
+            })
+            .collect();
+        self
+    }
+
+    /// Alert on quotes quarantined by each account's quote filter, failovers and
+    /// clock skew
+    pub fn with_alert_gateway(mut self, gateway: Arc<AlertGateway>) -> Self {
+        self.alert_gateway = Some(gateway);
+        self
+    }
+
+    /// Record the orders of accounts in dry run instead of sending them
+    pub fn with_dry_run(mut self, dry_run: Arc<DryRunMode>) -> Self {
+        self.dry_run = Some(dry_run);
+        self
+    }
+
+    /// Time every call made to each account's adapter per `config`
+    pub fn with_performance(mut self, config: PerformanceConfig) -> Self {
+        self.performance = config;
+        self
+    }
+
+    /// Measure each account's skew to its platform's server time per `config`, into
+    /// the account's clock in `clocks`
+    pub fn with_clock_sync(mut self, config: ClockSyncConfig, clocks: ServerClocks) -> Self {
+        self.clock_sync = config;
+        self.clocks = clocks;
+        self
+    }
+
+    /// Share one upstream market data subscription per symbol between every
+    /// consumer of an account, fed per `config`
+    pub fn with_market_data_hub(mut self, config: MarketDataHubConfig) -> Self {
+        self.market_data_hub = config;
+        self
+    }
+
+    pub fn quota(&self, platform: &PlatformType) -> Option<&Arc<QuotaManager>> {
+        self.quotas.get(platform)
+    }
+
+    /// Connect every failover endpoint of the account that can be reached, the
+    /// first one active and the rest as warm standbys
+    async fn connect_endpoints(
+        &self,
+        connector: &dyn PlatformConnector,
+        account: &AccountBootstrap,
+    ) -> Result<Arc<dyn ITradingPlatform + Send + Sync>, PlatformError> {
+        let mut connected = Vec::new();
+        for endpoint in &account.failover.endpoints {
+            match connector.connect_endpoint(account, endpoint).await {
+                Ok(platform) => connected.push((endpoint.clone(), platform)),
+                Err(e) => warn!(
+                    "Failed to connect endpoint {} of account {}: {}",
+                    endpoint.name, account.account_id, e
+                ),
+            }
+        }
+        let mut failover = FailoverPlatform::new(
+            account.account_id.clone(),
+            connected,
+            account.failover.clone(),
+        )?;
+        if let Some(gateway) = &self.alert_gateway {
+            failover = failover.with_alert_gateway(gateway.clone());
+        }
+        let failover = Arc::new(failover);
+        failover.spawn_probing();
+        Ok(failover)
+    }
+
+    /// Register every enabled account that can be connected. Accounts that fail are
+    /// logged and skipped so one bad account does not keep the engine down.
+    /// Returns the ids of the accounts that were registered.
+    pub async fn bootstrap(
+        &self,
+        accounts: &[AccountBootstrap],
+        orchestrator: &TradeExecutionOrchestrator,
+    ) -> Vec<String> {
+        let mut registered = Vec::new();
+
+        for account in accounts.iter().filter(|a| a.enabled) {
+            let Some(connector) = self.connectors.get(&account.platform) else {
+                warn!(
+                    "No connector available for {:?}, skipping account {}",
+                    account.platform, account.account_id
+                );
+                continue;
+            };
+
+            let connected = if account.failover.endpoints.is_empty() {
+                connector.connect(account).await
+            } else {
+                self.connect_endpoints(connector.as_ref(), account).await
+            };
+            let platform = match connected {
+                Ok(platform) => platform,
+                Err(e) => {
+                    error!("Failed to connect account {}: {}", account.account_id, e);
+                    continue;
+                }
+            };
+            // Timed and retried innermost, so waits for quota are not put down to the
+            // platform and every retry is charged to the quota
+            let monitor = self.performance.enabled.then(|| {
+                PerformanceMonitor::from_config(&self.performance)
+                    .with_platform(&account.account_id)
+            });
+            let platform: Arc<dyn ITradingPlatform + Send + Sync> =
+                match (account.resilience.enabled, monitor) {
+                    (true, monitor) => {
+                        let resilient =
+                            ResilientPlatform::new(platform, account.resilience.clone());
+                        Arc::new(match monitor {
+                            Some(monitor) => resilient.with_monitor(monitor),
+                            None => resilient,
+                        })
+                    }
+                    (false, Some(monitor)) => Arc::new(MonitoredPlatform::new(platform, monitor)),
+                    (false, None) => platform,
+                };
+            let platform: Arc<dyn ITradingPlatform + Send + Sync> =
+                match self.quotas.get(&account.platform) {
+                    Some(quota) => Arc::new(QuotaPlatform::new(platform, quota.clone())),
+                    None => platform,
+                };
+            let clock = self.clocks.clock(&account.account_id);
+            let platform: Arc<dyn ITradingPlatform + Send + Sync> = if self.clock_sync.enabled {
+                let mut synced = ClockSyncPlatform::new(
+                    platform,
+                    account.account_id.clone(),
+                    clock.clone(),
+                    self.clock_sync.clone(),
+                );
+                if let Some(gateway) = &self.alert_gateway {
+                    synced = synced.with_alert_gateway(gateway.clone());
+                }
+                let synced = Arc::new(synced);
+                synced.spawn_syncing();
+                synced
+            } else {
+                platform
+            };
+            // Recorded orders skip the quota and carry the platform's symbols
+            let platform: Arc<dyn ITradingPlatform + Send + Sync> = match &self.dry_run {
+                Some(dry_run) => Arc::new(DryRunPlatform::new(
+                    platform,
+                    account.account_id.clone(),
+                    dry_run.clone(),
+                )),
+                None => platform,
+            };
+            // Everything past this point speaks unified symbols
+            let platform: Arc<dyn ITradingPlatform + Send + Sync> =
+                Arc::new(SymbolMappingPlatform::from_config(platform, &account.symbols).await);
+            // Nothing downstream acts on a quote that fails the sanity checks
+            let platform: Arc<dyn ITradingPlatform + Send + Sync> = if account.quote_filter.enabled
+            {
+                let mut filtered = QuoteFilteringPlatform::new(
+                    platform,
+                    account.account_id.clone(),
+                    account.quote_filter.clone(),
+                )
+                .with_server_clock(clock);
+                if let Some(gateway) = &self.alert_gateway {
+                    filtered = filtered.with_alert_gateway(gateway.clone());
+                }
+                Arc::new(filtered)
+            } else {
+                platform
+            };
+            let platform: Arc<dyn ITradingPlatform + Send + Sync> = if self.market_data_hub.enabled
+            {
+                Arc::new(MarketDataHub::new(
+                    platform,
+                    account.account_id.clone(),
+                    self.market_data_hub.clone(),
+                ))
+            } else {
+                platform
+            };
+            let platform: Arc<dyn ITradingPlatform + Send + Sync> = Arc::new(
+                DegradingPlatform::new(platform, account.degradation.clone()),
+            );
+
+            match orchestrator
+                .register_account(
+                    account.account_id.clone(),
+                    platform,
+                    account.initial_balance,
+                )
+                .await
+            {
+                Ok(()) => registered.push(account.account_id.clone()),
+                Err(e) => error!("Failed to register account {}: {}", account.account_id, e),
+            }
+        }
+
+        info!(
+            "Bootstrapped {} of {} configured accounts",
+            registered.len(),
+            accounts.len()
+        );
+        registered
+    }
+}
@@ -0,0 +1,326 @@
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, register_int_gauge_vec, IntCounterVec, IntGaugeVec};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+lazy_static! {
+    pub static ref CHANNEL_DEPTH: IntGaugeVec = register_int_gauge_vec!(
+        "execution_engine_channel_depth",
+        "Items waiting in a bounded channel",
+        &["channel", "instance"]
+    )
+    .unwrap();
+    pub static ref CHANNEL_DROPPED: IntCounterVec = register_int_counter_vec!(
+        "execution_engine_channel_dropped_total",
+        "Items dropped by a full bounded channel",
+        &["channel", "instance"]
+    )
+    .unwrap();
+    /// Instance labels in use under each channel name, so channels sharing a name,
+    /// such as one per account, report separately. A closed channel's label goes
+    /// to the next one opened under its name, keeping the series bounded.
+    static ref INSTANCES: Mutex<HashMap<String, BTreeSet<u64>>> = Mutex::new(HashMap::new());
+}
+
+/// The lowest instance label free under `name`
+fn claim_instance(name: &str) -> u64 {
+    let mut instances = INSTANCES.lock().unwrap();
+    let in_use = instances.entry(name.to_string()).or_default();
+    let instance = (1..).find(|i| !in_use.contains(i)).unwrap();
+    in_use.insert(instance);
+    instance
+}
+
+fn release_instance(name: &str, instance: u64) {
+    let mut instances = INSTANCES.lock().unwrap();
+    if let Some(in_use) = instances.get_mut(name) {
+        in_use.remove(&instance);
+        if in_use.is_empty() {
+            instances.remove(name);
+        }
+    }
+}
+
+/// What a send does when the channel is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverflowPolicy {
+    /// Wait for the receiver to make room; nothing is lost
+    Block,
+    /// Evict the oldest item that was itself sent with a dropping policy. If every
+    /// queued item must be kept, the new item is dropped instead.
+    DropOldest,
+    /// Drop the item being sent
+    DropNewest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelStats {
+    pub name: String,
+    /// Tells apart channels created under the same name
+    pub instance: String,
+    pub capacity: usize,
+    pub depth: usize,
+    pub max_depth: usize,
+    pub sent: u64,
+    pub dropped: u64,
+    /// Sends that had to wait for room
+    pub blocked: u64,
+}
+
+struct Queued<T> {
+    item: T,
+    evictable: bool,
+}
+
+struct Shared<T> {
+    name: String,
+    instance: String,
+    capacity: usize,
+    policy: OverflowPolicy,
+    queue: Mutex<VecDeque<Queued<T>>>,
+    item_ready: Notify,
+    space_ready: Notify,
+    senders: AtomicUsize,
+    receiver_alive: AtomicBool,
+    sent: AtomicU64,
+    dropped: AtomicU64,
+    blocked: AtomicU64,
+    max_depth: AtomicUsize,
+}
+
+impl<T> Shared<T> {
+    fn record_depth(&self, depth: usize) {
+        self.max_depth.fetch_max(depth, Ordering::Relaxed);
+        CHANNEL_DEPTH
+            .with_label_values(&[&self.name, &self.instance])
+            .set(depth as i64);
+    }
+
+    fn record_drop(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+        CHANNEL_DROPPED
+            .with_label_values(&[&self.name, &self.instance])
+            .inc();
+    }
+
+    fn stats(&self) -> ChannelStats {
+        ChannelStats {
+            name: self.name.clone(),
+            instance: self.instance.clone(),
+            capacity: self.capacity,
+            depth: self.queue.lock().unwrap().len(),
+            max_depth: self.max_depth.load(Ordering::Relaxed),
+            sent: self.sent.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            blocked: self.blocked.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    /// A closed channel's series would otherwise be reported forever
+    fn drop(&mut self) {
+        let _ = CHANNEL_DEPTH.remove_label_values(&[&self.name, &self.instance]);
+        let _ = CHANNEL_DROPPED.remove_label_values(&[&self.name, &self.instance]);
+        if let Ok(instance) = self.instance.parse() {
+            release_instance(&self.name, instance);
+        }
+    }
+}
+
+/// A channel holding at most `capacity` items, reported under `name` and the
+/// lowest instance number free under it in the queue metrics. `policy` applies to
+/// `send`; `send_with` picks one per item.
+pub fn bounded<T>(
+    name: &str,
+    capacity: usize,
+    policy: OverflowPolicy,
+) -> (BoundedSender<T>, BoundedReceiver<T>) {
+    let shared = Arc::new(Shared {
+        name: name.to_string(),
+        instance: claim_instance(name).to_string(),
+        capacity: capacity.max(1),
+        policy,
+        queue: Mutex::new(VecDeque::new()),
+        item_ready: Notify::new(),
+        space_ready: Notify::new(),
+        senders: AtomicUsize::new(1),
+        receiver_alive: AtomicBool::new(true),
+        sent: AtomicU64::new(0),
+        dropped: AtomicU64::new(0),
+        blocked: AtomicU64::new(0),
+        max_depth: AtomicUsize::new(0),
+    });
+    (
+        BoundedSender {
+            shared: shared.clone(),
+        },
+        BoundedReceiver { shared },
+    )
+}
+
+pub struct BoundedSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> BoundedSender<T> {
+    /// Send with the channel's policy; fails only once the receiver is gone
+    pub async fn send(&self, item: T) -> Result<(), T> {
+        self.send_with(item, self.shared.policy).await
+    }
+
+    /// Send with `policy`. A dropped item still counts as sent successfully.
+    pub async fn send_with(&self, item: T, policy: OverflowPolicy) -> Result<(), T> {
+        let shared = &self.shared;
+        let mut item = Some(item);
+        let mut waited = false;
+
+        loop {
+            let space = shared.space_ready.notified();
+            tokio::pin!(space);
+            space.as_mut().enable();
+
+            {
+                let mut queue = shared.queue.lock().unwrap();
+                if !shared.receiver_alive.load(Ordering::SeqCst) {
+                    return Err(item.take().unwrap());
+                }
+                let evictable = policy != OverflowPolicy::Block;
+                if queue.len() >= shared.capacity {
+                    match policy {
+                        OverflowPolicy::Block => {}
+                        OverflowPolicy::DropOldest => {
+                            match queue.iter().position(|queued| queued.evictable) {
+                                Some(oldest) => {
+                                    queue.remove(oldest);
+                                }
+                                None => {
+                                    shared.record_drop();
+                                    return Ok(());
+                                }
+                            }
+                            shared.record_drop();
+                        }
+                        OverflowPolicy::DropNewest => {
+                            shared.record_drop();
+                            return Ok(());
+                        }
+                    }
+                }
+                if queue.len() < shared.capacity {
+                    queue.push_back(Queued {
+                        item: item.take().unwrap(),
+                        evictable,
+                    });
+                    shared.sent.fetch_add(1, Ordering::Relaxed);
+                    shared.record_depth(queue.len());
+                    drop(queue);
+                    shared.item_ready.notify_one();
+                    return Ok(());
+                }
+            }
+
+            if !waited {
+                waited = true;
+                shared.blocked.fetch_add(1, Ordering::Relaxed);
+            }
+            space.await;
+        }
+    }
+
+    pub fn stats(&self) -> ChannelStats {
+        self.shared.stats()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        !self.shared.receiver_alive.load(Ordering::SeqCst)
+    }
+}
+
+impl<T> Clone for BoundedSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::SeqCst);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for BoundedSender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BoundedSender").field(&self.stats()).finish()
+    }
+}
+
+impl<T> Drop for BoundedSender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.shared.item_ready.notify_one();
+        }
+    }
+}
+
+pub struct BoundedReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> BoundedReceiver<T> {
+    /// Next item, or `None` once the queue is empty and every sender is gone
+    pub async fn recv(&mut self) -> Option<T> {
+        let shared = self.shared.clone();
+        loop {
+            let ready = shared.item_ready.notified();
+            tokio::pin!(ready);
+            ready.as_mut().enable();
+
+            if let Some(item) = self.try_recv() {
+                return Some(item);
+            }
+            if shared.senders.load(Ordering::SeqCst) == 0 {
+                return None;
+            }
+            ready.await;
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Option<T> {
+        let mut queue = self.shared.queue.lock().unwrap();
+        let queued = queue.pop_front()?;
+        self.shared.record_depth(queue.len());
+        drop(queue);
+        self.shared.space_ready.notify_one();
+        Some(queued.item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.queue.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> ChannelStats {
+        self.shared.stats()
+    }
+}
+
+impl<T> std::fmt::Debug for BoundedReceiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BoundedReceiver")
+            .field(&self.stats())
+            .finish()
+    }
+}
+
+impl<T> Drop for BoundedReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.receiver_alive.store(false, Ordering::SeqCst);
+        self.shared.queue.lock().unwrap().clear();
+        self.shared.record_depth(0);
+        self.shared.space_ready.notify_waiters();
+    }
+}
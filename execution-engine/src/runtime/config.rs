@@ -0,0 +1,611 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+
+use super::feature_flags::FeatureFlagConfig;
+use super::shutdown::ShutdownConfig;
+use super::supervisor::SupervisorConfig;
+use super::watchdog::WatchdogConfig;
+use crate::alerting::AlertingConfig;
+use crate::api::status::StatusPageConfig;
+use crate::auth::AuthConfig;
+use crate::dashboard::ConsolidationConfig;
+use crate::execution::exit_management::{
+    AdoptionConfig, MarketContextConfig, ScaleInPolicy, ShadowVariant, StopGuardianConfig,
+};
+use crate::execution::history::ExecutionHistoryConfig;
+use crate::execution::latency_entry::LatencyEntryConfig;
+use crate::execution::news_blackout::NewsBlackoutConfig;
+use crate::execution::pacing::PacingConfig;
+use crate::execution::pending_signals::PendingSignalConfig;
+use crate::execution::persona::PersonaConfig;
+use crate::execution::recovery_mode::RecoveryModeConfig;
+use crate::execution::scale_in::ScaleInConfig;
+use crate::execution::session_liquidity::SessionLiquidityConfig;
+use crate::execution::sizing::ConfidenceSizingConfig;
+use crate::execution::slippage::SlippageGuardConfig;
+use crate::ledger::StatementConfig;
+use crate::market_analysis::StructureConfig;
+use crate::market_data::{CandleConfig, CrossRateConfig};
+use crate::messaging::outbox::OutboxConfig;
+use crate::notifications::NotificationsConfig;
+use crate::platforms::abstraction::{
+    ClockSyncConfig, DegradationPolicy, DryRunConfig, FailoverConfig, MarketDataHubConfig,
+    PerformanceConfig, QuotaConfig, QuoteFilterConfig, RejectionRemediationConfig,
+    ResilienceConfig, SymbolMappingConfig,
+};
+use crate::platforms::dxtrade::DXTradeConfig;
+use crate::platforms::PlatformType;
+use crate::recording::RecordingConfig;
+use crate::reports::{ReportsConfig, SignalQualityConfig};
+use crate::risk::{FundingConfig, RiskConfig, RiskSnapshotConfig, TradingDayConfig};
+use crate::storage::{StorageConfig, StorageProfile};
+use crate::timeseries::TimeSeriesConfig;
+use crate::utils::config::ExecutionConfig;
+
+/// Top-level configuration for the execution engine binary
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EngineConfig {
+    #[serde(default)]
+    pub api: ApiConfig,
+    /// API keys, token validation and the roles they grant
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub supervisor: SupervisorConfig,
+    #[serde(default)]
+    pub shutdown: ShutdownConfig,
+    /// Whether state lives in per-component files or one embedded database
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
+    #[serde(default)]
+    pub accounts: Vec<AccountBootstrap>,
+    /// Request quota of each platform, shared by every account on it
+    #[serde(default)]
+    pub quotas: HashMap<PlatformType, QuotaConfig>,
+    /// Latency and SLA tracking of every platform call
+    #[serde(default)]
+    pub platform_performance: PerformanceConfig,
+    /// Skew checks of every platform's clock against ours
+    #[serde(default)]
+    pub clock_sync: ClockSyncConfig,
+    /// Fan-out of each account's market data to every consumer
+    #[serde(default)]
+    pub market_data_hub: MarketDataHubConfig,
+    #[serde(default)]
+    pub exit_management: ExitManagementConfig,
+    /// Accounts whose orders are recorded instead of sent, for trying out config
+    /// changes against live accounts
+    #[serde(default)]
+    pub dry_run: DryRunConfig,
+    /// Exit managers and risk responses switched off at startup
+    #[serde(default)]
+    pub feature_flags: FeatureFlagConfig,
+    #[serde(default)]
+    pub dashboard: DashboardConfig,
+    /// Base currency and fallback rates of the consolidated equity report
+    #[serde(default)]
+    pub consolidation: ConsolidationConfig,
+    #[serde(default)]
+    pub journal: JournalConfig,
+    #[serde(default)]
+    pub ledger: LedgerConfig,
+    /// Platform statements reconciled against the ledger, with swaps checked
+    /// against the configured rates
+    #[serde(default)]
+    pub statements: StatementConfig,
+    /// Execution audit entries kept in Postgres beyond the recent ones in memory
+    #[serde(default)]
+    pub execution_history: ExecutionHistoryConfig,
+    #[serde(default)]
+    pub outbox: OutboxConfig,
+    #[serde(default)]
+    pub candles: CandleConfig,
+    /// Currency rates for P&L conversion and exposure netting, derived through a
+    /// vehicle currency for pairs no platform quotes
+    #[serde(default)]
+    pub cross_rates: CrossRateConfig,
+    /// Quotes, signals, news and exit audit entries written per day for replay
+    #[serde(default)]
+    pub recording: RecordingConfig,
+    #[serde(default)]
+    pub structure: StructureConfig,
+    /// Ticks, P&L and equity streamed to TimescaleDB or ClickHouse for Grafana
+    #[serde(default)]
+    pub timeseries: TimeSeriesConfig,
+    #[serde(default)]
+    pub risk: RiskConfig,
+    /// Exposure, margin, drawdown and open risk of every account captured on an
+    /// interval and kept as time series
+    #[serde(default)]
+    pub risk_snapshots: RiskSnapshotConfig,
+    /// Deposits and withdrawals kept out of the drawdowns, detected at each
+    /// risk snapshot
+    #[serde(default)]
+    pub funding: FundingConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub alerting: AlertingConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub reports: ReportsConfig,
+    /// Strategy scores from the journal and the sizing they may drive
+    #[serde(default)]
+    pub signal_quality: SignalQualityConfig,
+    #[serde(default)]
+    pub pending_signals: PendingSignalConfig,
+    /// Risk per trade scaled by each signal's confidence
+    #[serde(default)]
+    pub confidence_sizing: ConfidenceSizingConfig,
+    /// Signals adding to a position already open on their side
+    #[serde(default)]
+    pub scale_in: ScaleInConfig,
+    /// Spread and slippage limits of market orders
+    #[serde(default)]
+    pub slippage_guard: SlippageGuardConfig,
+    /// Market entries sent as limit orders while a platform is slow
+    #[serde(default)]
+    pub latency_entry: LatencyEntryConfig,
+    /// New entries refused around economic releases, by impact level
+    #[serde(default)]
+    pub news_blackout: NewsBlackoutConfig,
+    /// Entry delay spread and slippage allowance by session liquidity
+    #[serde(default)]
+    pub session_liquidity: SessionLiquidityConfig,
+    /// Trading windows, spacing and daily trades of each account
+    #[serde(default)]
+    pub pacing: PacingConfig,
+    /// Sizing, timing and exit styles of accounts, so each trades distinctly
+    #[serde(default)]
+    pub personas: PersonaConfig,
+    /// Constraints on accounts for some days after a large loss
+    #[serde(default)]
+    pub recovery_mode: RecoveryModeConfig,
+    /// What is done with orders the platform rejects, by rejection reason
+    #[serde(default)]
+    pub rejection_remediation: RejectionRemediationConfig,
+    /// When each account's trading day rolls over; unset keeps UTC midnight and the
+    /// fixed UTC weekend window for time exits
+    #[serde(default)]
+    pub trading_day: Option<TradingDayConfig>,
+    /// FIX sessions to DXtrade, order entry and drop copy; unset when no DXtrade
+    /// account is traded
+    #[serde(default)]
+    pub dxtrade: Option<DXTradeConfig>,
+    /// TradeLocker client limits and the Vault holding its account credentials;
+    /// unset when no TradeLocker account is traded
+    #[serde(default)]
+    pub tradelocker: Option<ExecutionConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConfig {
+    pub bind_address: String,
+    /// Caching and rate limit of the unauthenticated `/status` summary
+    #[serde(default)]
+    pub status: StatusPageConfig,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: "0.0.0.0:8082".to_string(),
+            status: StatusPageConfig::default(),
+        }
+    }
+}
+
+/// An account to connect and register with the orchestrator at startup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountBootstrap {
+    pub account_id: String,
+    pub platform: PlatformType,
+    pub initial_balance: f64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// How unified symbols map onto this account's instrument names
+    #[serde(default)]
+    pub symbols: SymbolMappingConfig,
+    /// How orders using features the platform lacks are rewritten
+    #[serde(default)]
+    pub degradation: DegradationPolicy,
+    /// Bounds quotes must stay within before anything acts on them
+    #[serde(default)]
+    pub quote_filter: QuoteFilterConfig,
+    /// Timeout, retry and circuit breaker policy of every call to this account's platform
+    #[serde(default)]
+    pub resilience: ResilienceConfig,
+    /// Other endpoints of the broker kept connected as warm standbys
+    #[serde(default)]
+    pub failover: FailoverConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitManagementConfig {
+    pub enabled: bool,
+    /// Directory of per-account exit state checkpoints; unset keeps state in memory only
+    #[serde(default = "default_exit_state_dir")]
+    pub state_dir: Option<String>,
+    /// Alternative exit configurations evaluated in shadow mode next to the live ones
+    #[serde(default)]
+    pub shadow_variants: Vec<ShadowVariant>,
+    /// How the market context of exit audit entries is measured
+    #[serde(default)]
+    pub market_context: MarketContextConfig,
+    /// Periodic check that every open position still has its stop-loss
+    #[serde(default)]
+    pub stop_guardian: StopGuardianConfig,
+    /// Bringing positions opened outside the engine under exit management
+    #[serde(default)]
+    pub adoption: AdoptionConfig,
+    /// Where stops and targets go when a position is added to
+    #[serde(default)]
+    pub scale_in: ScaleInPolicy,
+}
+
+fn default_exit_state_dir() -> Option<String> {
+    Some("data/exit_state".to_string())
+}
+
+impl Default for ExitManagementConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            state_dir: default_exit_state_dir(),
+            shadow_variants: Vec::new(),
+            market_context: MarketContextConfig::default(),
+            stop_guardian: StopGuardianConfig::default(),
+            adoption: AdoptionConfig::default(),
+            scale_in: ScaleInPolicy::default(),
+        }
+    }
+}
+
+/// WebSocket feed of dashboard snapshots, served on its own port
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardConfig {
+    pub stream_enabled: bool,
+    pub stream_bind_address: String,
+    pub publish_interval_ms: u64,
+}
+
+impl Default for DashboardConfig {
+    fn default() -> Self {
+        Self {
+            stream_enabled: true,
+            stream_bind_address: "0.0.0.0:8083".to_string(),
+            publish_interval_ms: 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalConfig {
+    pub path: String,
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            path: "data/trade_journal.jsonl".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerConfig {
+    pub enabled: bool,
+    pub path: String,
+    /// How often ledger positions are reconciled against each platform
+    #[serde(default = "default_reconcile_interval_secs")]
+    pub reconcile_interval_secs: u64,
+}
+
+fn default_reconcile_interval_secs() -> u64 {
+    60
+}
+
+impl Default for LedgerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: "data/position_ledger.jsonl".to_string(),
+            reconcile_interval_secs: default_reconcile_interval_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// Log level per account id (e.g. "debug"), raising the default for that account only
+    #[serde(default)]
+    pub account_levels: HashMap<String, String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl EngineConfig {
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        let config: EngineConfig = toml::from_str(&content)?;
+        Ok(config)
+    }
+
+    pub fn from_env() -> Self {
+        let mut config = Self {
+            risk: crate::risk::load_config(),
+            ..Self::default()
+        };
+
+        if let Ok(port) = std::env::var("EXECUTION_ENGINE_PORT") {
+            if let Ok(port) = port.parse::<u16>() {
+                config.api.bind_address = format!("0.0.0.0:{}", port);
+            }
+        }
+
+        if let Ok(timeout) = std::env::var("EXECUTION_ENGINE_SHUTDOWN_TIMEOUT_SECS") {
+            if let Ok(timeout) = timeout.parse() {
+                config.supervisor.shutdown_timeout_secs = timeout;
+            }
+        }
+
+        if let Ok(path) = std::env::var("EXECUTION_ENGINE_STATE_PATH") {
+            config.shutdown.state_path = path;
+        }
+
+        if let Ok(enabled) = std::env::var("EXECUTION_ENGINE_EXIT_MANAGEMENT_ENABLED") {
+            config.exit_management.enabled = enabled.parse().unwrap_or(true);
+        }
+
+        if let Ok(enabled) = std::env::var("EXECUTION_ENGINE_DRY_RUN") {
+            config.dry_run.enabled = enabled.parse().unwrap_or(false);
+        }
+
+        if let Ok(currency) = std::env::var("EXECUTION_ENGINE_BASE_CURRENCY") {
+            if !currency.is_empty() {
+                config.consolidation.base_currency = currency.to_uppercase();
+            }
+        }
+
+        if let Ok(dir) = std::env::var("EXECUTION_ENGINE_EXIT_STATE_DIR") {
+            config.exit_management.state_dir = (!dir.is_empty()).then_some(dir);
+        }
+
+        if let Ok(dir) = std::env::var("EXECUTION_ENGINE_RECORDING_DIR") {
+            config.recording.enabled = !dir.is_empty();
+            if !dir.is_empty() {
+                config.recording.dir = dir;
+            }
+        }
+
+        if let Ok(url) = std::env::var("EXECUTION_ENGINE_HISTORY_DATABASE_URL") {
+            config.execution_history.enabled = !url.is_empty();
+            if !url.is_empty() {
+                config.execution_history.database_url = url;
+            }
+        }
+
+        // A path selects the embedded database; empty keeps per-component files
+        if let Ok(path) = std::env::var("EXECUTION_ENGINE_SQLITE_PATH") {
+            if path.is_empty() {
+                config.storage.profile = StorageProfile::Files;
+            } else {
+                config.storage.profile = StorageProfile::Sqlite;
+                config.storage.sqlite_path = path;
+            }
+        }
+
+        if let Ok(path) = std::env::var("EXECUTION_ENGINE_JOURNAL_PATH") {
+            config.journal.path = path;
+        }
+
+        if let Ok(path) = std::env::var("EXECUTION_ENGINE_LEDGER_PATH") {
+            config.ledger.path = path;
+        }
+
+        if let Ok(path) = std::env::var("EXECUTION_ENGINE_OUTBOX_PATH") {
+            config.outbox.path = path;
+        }
+
+        // Comma separated account=level pairs
+        if let Ok(levels) = std::env::var("EXECUTION_ENGINE_ACCOUNT_LOG_LEVELS") {
+            config.logging.account_levels = levels
+                .split(',')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(account, level)| (account.trim().to_string(), level.trim().to_string()))
+                .collect();
+        }
+
+        if let Ok(port) = std::env::var("EXECUTION_ENGINE_DASHBOARD_WS_PORT") {
+            if let Ok(port) = port.parse::<u16>() {
+                config.dashboard.stream_bind_address = format!("0.0.0.0:{}", port);
+            }
+        }
+
+        config
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.api
+            .bind_address
+            .parse::<SocketAddr>()
+            .map_err(|e| format!("Invalid API bind address {}: {}", self.api.bind_address, e))?;
+
+        if self.dashboard.stream_enabled {
+            self.dashboard
+                .stream_bind_address
+                .parse::<SocketAddr>()
+                .map_err(|e| {
+                    format!(
+                        "Invalid dashboard stream address {}: {}",
+                        self.dashboard.stream_bind_address, e
+                    )
+                })?;
+            if self.dashboard.publish_interval_ms == 0 {
+                return Err("Dashboard publish interval must be greater than zero".to_string());
+            }
+        }
+
+        if self.consolidation.base_currency.trim().is_empty() {
+            return Err("Consolidation base currency must be set".to_string());
+        }
+
+        if self.supervisor.shutdown_timeout_secs == 0 || self.shutdown.deadline_secs == 0 {
+            return Err("Shutdown timeouts must be greater than zero".to_string());
+        }
+
+        if self.shutdown.drain_timeout_secs >= self.shutdown.deadline_secs {
+            return Err(
+                "Execution drain timeout must be shorter than the shutdown deadline".to_string(),
+            );
+        }
+
+        let mut seen = HashSet::new();
+        for account in &self.accounts {
+            if !seen.insert(account.account_id.as_str()) {
+                return Err(format!("Duplicate account {}", account.account_id));
+            }
+            if account.initial_balance <= 0.0 {
+                return Err(format!(
+                    "Initial balance for account {} must be positive",
+                    account.account_id
+                ));
+            }
+            account
+                .resilience
+                .validate()
+                .map_err(|e| format!("{} of account {}", e, account.account_id))?;
+            account
+                .failover
+                .validate()
+                .map_err(|e| format!("{} of account {}", e, account.account_id))?;
+        }
+
+        if self.candles.enabled && self.candles.history == 0 {
+            return Err("Candle history must keep at least one candle".to_string());
+        }
+        if self.candles.enabled {
+            if self.structure.lookback == 0 || self.structure.strength == 0 {
+                return Err("Structure lookback and swing strength must be positive".to_string());
+            }
+            if !self.candles.timeframes.contains(&self.structure.timeframe) {
+                return Err(format!(
+                    "Structure timeframe {:?} is not among the candle timeframes",
+                    self.structure.timeframe
+                ));
+            }
+        }
+
+        for (platform, quota) in &self.quotas {
+            if quota.requests_per_window == 0 || quota.window_secs == 0 {
+                return Err(format!("Quota window for {:?} must be non-empty", platform));
+            }
+            if !(0.0..1.0).contains(&quota.reserve) {
+                return Err(format!("Quota reserve for {:?} must be below 1", platform));
+            }
+            if quota.budgets.values().sum::<f64>() > 1.0 {
+                return Err(format!(
+                    "Quota budgets for {:?} add up to more than the whole window",
+                    platform
+                ));
+            }
+        }
+
+        if let Some(account_id) = self
+            .feature_flags
+            .accounts
+            .keys()
+            .find(|account_id| !seen.contains(account_id.as_str()))
+        {
+            return Err(format!(
+                "Feature flags set for unconfigured account {}",
+                account_id
+            ));
+        }
+        if let Some(account_id) = self
+            .dry_run
+            .accounts
+            .keys()
+            .find(|account_id| !seen.contains(account_id.as_str()))
+        {
+            return Err(format!(
+                "Dry run set for unconfigured account {}",
+                account_id
+            ));
+        }
+        if let Some(account_id) = self
+            .recording
+            .source_account
+            .as_ref()
+            .filter(|account_id| !seen.contains(account_id.as_str()))
+        {
+            return Err(format!(
+                "Recording source account {} is not configured",
+                account_id
+            ));
+        }
+
+        if self.statements.enabled && !self.ledger.enabled {
+            return Err(
+                "Statement reconciliation checks the position ledger, which is disabled"
+                    .to_string(),
+            );
+        }
+
+        if self.funding.enabled && !self.risk_snapshots.enabled {
+            return Err(
+                "Funding detection runs with the risk snapshots, which are disabled".to_string(),
+            );
+        }
+
+        for (account_id, level) in &self.logging.account_levels {
+            level
+                .parse::<tracing::level_filters::LevelFilter>()
+                .map_err(|_| format!("Invalid log level {} for account {}", level, account_id))?;
+        }
+
+        self.auth.validate()?;
+        self.alerting.validate()?;
+        self.notifications.validate()?;
+        self.reports.validate()?;
+        self.signal_quality.validate()?;
+        self.confidence_sizing.validate()?;
+        self.scale_in.validate()?;
+        self.slippage_guard.validate()?;
+        self.latency_entry.validate()?;
+        self.news_blackout.validate()?;
+        self.session_liquidity.validate()?;
+        self.pacing.validate()?;
+        self.personas.validate()?;
+        self.recovery_mode.validate()?;
+        self.risk_snapshots.validate()?;
+        self.funding.validate()?;
+        self.statements.validate()?;
+        self.platform_performance.validate()?;
+        self.clock_sync.validate()?;
+        self.market_data_hub.validate()?;
+        self.cross_rates.validate()?;
+        self.risk.validate()
+    }
+}
+
+/// The config file named by `EXECUTION_ENGINE_CONFIG_PATH`, or the defaults with
+/// environment overrides. A config that was asked for but cannot be read or is
+/// invalid is an error, never replaced by the defaults.
+pub fn load_config() -> Result<EngineConfig, String> {
+    let config = match std::env::var("EXECUTION_ENGINE_CONFIG_PATH") {
+        Ok(config_path) => EngineConfig::from_file(&config_path)
+            .map_err(|e| format!("Failed to read config {}: {}", config_path, e))?,
+        Err(_) => EngineConfig::from_env(),
+    };
+    config
+        .validate()
+        .map_err(|e| format!("Invalid config: {}", e))?;
+    Ok(config)
+}
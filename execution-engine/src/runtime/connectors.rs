@@ -0,0 +1,174 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+use super::bootstrap::PlatformConnector;
+use super::config::AccountBootstrap;
+use super::shutdown::{DisconnectPlatform, FixSessionLogout, ShutdownCoordinator};
+use crate::platforms::abstraction::adapters::{DXTradeAdapter, TradeLockerAdapter};
+use crate::platforms::abstraction::{ITradingPlatform, PlatformError, RetryConfig};
+use crate::platforms::dxtrade::{DXTradeClient, DXTradeConfig};
+use crate::platforms::tradelocker::{TradeLockerAuth, TradeLockerClient};
+use crate::utils::config::ExecutionConfig;
+use crate::utils::vault::VaultClient;
+
+/// Connects TradeLocker accounts with the credentials stored for them in Vault
+pub struct TradeLockerConnector {
+    config: ExecutionConfig,
+    retry: RetryConfig,
+    auth: OnceCell<Arc<TradeLockerAuth>>,
+    shutdown: Option<Arc<ShutdownCoordinator>>,
+}
+
+impl TradeLockerConnector {
+    pub fn new(config: ExecutionConfig) -> Self {
+        Self {
+            config,
+            retry: RetryConfig::default(),
+            auth: OnceCell::new(),
+            shutdown: None,
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Disconnect each account when `shutdown` runs
+    pub fn with_shutdown(mut self, shutdown: Arc<ShutdownCoordinator>) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Credentials are read from Vault once and shared by every account
+    async fn auth(&self) -> Result<Arc<TradeLockerAuth>, PlatformError> {
+        self.auth
+            .get_or_try_init(|| async {
+                let vault = VaultClient::new(self.config.vault_endpoint.clone())
+                    .await
+                    .map_err(|e| PlatformError::InitializationFailed {
+                        reason: e.to_string(),
+                    })?;
+                let auth = TradeLockerAuth::new(Arc::new(vault)).await.map_err(|e| {
+                    PlatformError::InitializationFailed {
+                        reason: e.to_string(),
+                    }
+                })?;
+                auth.load_credentials()
+                    .await
+                    .map_err(|e| PlatformError::InvalidCredentials {
+                        reason: e.to_string(),
+                    })?;
+                Ok(Arc::new(auth))
+            })
+            .await
+            .cloned()
+    }
+}
+
+#[async_trait]
+impl PlatformConnector for TradeLockerConnector {
+    async fn connect(
+        &self,
+        account: &AccountBootstrap,
+    ) -> Result<Arc<dyn ITradingPlatform + Send + Sync>, PlatformError> {
+        let auth = self.auth().await?;
+        let environment = auth.environment(&account.account_id).await.map_err(|e| {
+            PlatformError::InvalidCredentials {
+                reason: e.to_string(),
+            }
+        })?;
+        let client = TradeLockerClient::new(auth, self.config.tradelocker.clone(), environment)
+            .map_err(|e| PlatformError::InitializationFailed {
+                reason: e.to_string(),
+            })?;
+
+        let mut adapter =
+            TradeLockerAdapter::new(client, account.account_id.clone(), self.retry.clone());
+        adapter.connect().await?;
+        let adapter = Arc::new(adapter);
+
+        if let Some(shutdown) = &self.shutdown {
+            let platform = adapter.clone();
+            shutdown.add_hook(Arc::new(DisconnectPlatform::new(
+                &account.account_id,
+                move || {
+                    let platform = platform.clone();
+                    async move { platform.close().await }
+                },
+            )));
+        }
+        Ok(adapter)
+    }
+}
+
+/// Connects DXtrade accounts through the engine's DXtrade login, one FIX and
+/// REST session per account
+pub struct DXTradeConnector {
+    config: DXTradeConfig,
+    retry: RetryConfig,
+    shutdown: Option<Arc<ShutdownCoordinator>>,
+}
+
+impl DXTradeConnector {
+    pub fn new(config: DXTradeConfig) -> Self {
+        Self {
+            config,
+            retry: RetryConfig::default(),
+            shutdown: None,
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Log each account's FIX session out and disconnect it when `shutdown` runs
+    pub fn with_shutdown(mut self, shutdown: Arc<ShutdownCoordinator>) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+}
+
+#[async_trait]
+impl PlatformConnector for DXTradeConnector {
+    async fn connect(
+        &self,
+        account: &AccountBootstrap,
+    ) -> Result<Arc<dyn ITradingPlatform + Send + Sync>, PlatformError> {
+        let mut config = self.config.clone();
+        config.credentials.account_id = account.account_id.clone();
+        config
+            .validate()
+            .map_err(|e| PlatformError::ConfigurationError {
+                reason: e.to_string(),
+            })?;
+        let client =
+            DXTradeClient::new(config).map_err(|e| PlatformError::InitializationFailed {
+                reason: e.to_string(),
+            })?;
+
+        let mut adapter = DXTradeAdapter::new(client, self.retry.clone());
+        adapter.connect().await?;
+        let adapter = Arc::new(adapter);
+
+        if let Some(shutdown) = &self.shutdown {
+            if let Some(session) = adapter.fix_session().await {
+                let wait = Duration::from_secs(shutdown.config().logout_wait_secs);
+                shutdown.add_hook(Arc::new(FixSessionLogout::new(session, wait)));
+            }
+            let platform = adapter.clone();
+            shutdown.add_hook(Arc::new(DisconnectPlatform::new(
+                &account.account_id,
+                move || {
+                    let platform = platform.clone();
+                    async move { platform.close().await }
+                },
+            )));
+        }
+        Ok(adapter)
+    }
+}
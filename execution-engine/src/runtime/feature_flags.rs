@@ -0,0 +1,298 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+use tracing::{info, warn};
+
+use super::health::{DependencyHealth, DependencyKind, HealthLevel, HealthProbe};
+
+/// A subsystem operators can switch off per account without redeploying
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    TrailingStops,
+    BreakEven,
+    PartialProfits,
+    TimeExits,
+    NewsProtection,
+    /// Risk responses that cut open positions on a breach
+    AutoPositionReduction,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 6] = [
+        Feature::TrailingStops,
+        Feature::BreakEven,
+        Feature::PartialProfits,
+        Feature::TimeExits,
+        Feature::NewsProtection,
+        Feature::AutoPositionReduction,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Feature::TrailingStops => "trailing_stops",
+            Feature::BreakEven => "break_even",
+            Feature::PartialProfits => "partial_profits",
+            Feature::TimeExits => "time_exits",
+            Feature::NewsProtection => "news_protection",
+            Feature::AutoPositionReduction => "auto_position_reduction",
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Flags the engine starts with. Features not flagged here are enabled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeatureFlagConfig {
+    /// Flags for every account
+    pub global: HashMap<Feature, bool>,
+    /// Flags for one account, taking precedence over the global ones
+    pub accounts: HashMap<String, HashMap<Feature, bool>>,
+    /// Flag changes kept for the audit history
+    pub history: usize,
+}
+
+impl Default for FeatureFlagConfig {
+    fn default() -> Self {
+        Self {
+            global: HashMap::new(),
+            accounts: HashMap::new(),
+            history: 500,
+        }
+    }
+}
+
+/// A feature switched on or off, for every account or for one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeatureFlag {
+    pub feature: Feature,
+    /// Unset for the global flag
+    pub account_id: Option<String>,
+    pub enabled: bool,
+    pub reason: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// One change to a flag, for the audit history. `None` is a flag that is not set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureFlagChange {
+    pub feature: Feature,
+    pub account_id: Option<String>,
+    pub previous: Option<bool>,
+    pub enabled: Option<bool>,
+    pub reason: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+type FlagKey = (Option<String>, Feature);
+
+/// Runtime switches for exit managers and risk responses. An account's own flag
+/// wins over the global one; a feature with neither is enabled.
+#[derive(Debug)]
+pub struct FeatureFlags {
+    flags: RwLock<HashMap<FlagKey, FeatureFlag>>,
+    history: Mutex<VecDeque<FeatureFlagChange>>,
+    history_limit: usize,
+}
+
+impl FeatureFlags {
+    pub fn new(config: FeatureFlagConfig) -> Self {
+        let now = Utc::now();
+        let configured = config
+            .global
+            .iter()
+            .map(|(feature, enabled)| (None, *feature, *enabled))
+            .chain(config.accounts.iter().flat_map(|(account_id, flags)| {
+                flags
+                    .iter()
+                    .map(move |(feature, enabled)| (Some(account_id.clone()), *feature, *enabled))
+            }));
+        let flags = configured
+            .map(|(account_id, feature, enabled)| {
+                let flag = FeatureFlag {
+                    feature,
+                    account_id: account_id.clone(),
+                    enabled,
+                    reason: "config".to_string(),
+                    changed_at: now,
+                };
+                ((account_id, feature), flag)
+            })
+            .collect();
+
+        Self {
+            flags: RwLock::new(flags),
+            history: Mutex::new(VecDeque::new()),
+            history_limit: config.history,
+        }
+    }
+
+    pub fn is_enabled(&self, account_id: &str, feature: Feature) -> bool {
+        let flags = self.flags.read().unwrap();
+        flags
+            .get(&(Some(account_id.to_string()), feature))
+            .or_else(|| flags.get(&(None, feature)))
+            .map_or(true, |flag| flag.enabled)
+    }
+
+    /// The flags `account_id` is checked against
+    pub fn for_account(self: &Arc<Self>, account_id: &str) -> AccountFeatures {
+        AccountFeatures {
+            flags: self.clone(),
+            account_id: account_id.to_string(),
+        }
+    }
+
+    /// Switch `feature` on or off for `account_id`, or for every account when unset
+    pub fn set(
+        &self,
+        account_id: Option<&str>,
+        feature: Feature,
+        enabled: bool,
+        reason: &str,
+    ) -> FeatureFlagChange {
+        self.apply(account_id, feature, Some(enabled), reason)
+    }
+
+    /// Remove a flag, so the feature falls back to the global flag or to enabled
+    pub fn clear(
+        &self,
+        account_id: Option<&str>,
+        feature: Feature,
+        reason: &str,
+    ) -> FeatureFlagChange {
+        self.apply(account_id, feature, None, reason)
+    }
+
+    fn apply(
+        &self,
+        account_id: Option<&str>,
+        feature: Feature,
+        enabled: Option<bool>,
+        reason: &str,
+    ) -> FeatureFlagChange {
+        let account_id = account_id.map(str::to_string);
+        let key = (account_id.clone(), feature);
+        let now = Utc::now();
+        let previous = {
+            let mut flags = self.flags.write().unwrap();
+            let previous = match enabled {
+                Some(enabled) => flags.insert(
+                    key,
+                    FeatureFlag {
+                        feature,
+                        account_id: account_id.clone(),
+                        enabled,
+                        reason: reason.to_string(),
+                        changed_at: now,
+                    },
+                ),
+                None => flags.remove(&key),
+            };
+            previous.map(|flag| flag.enabled)
+        };
+
+        let scope = account_id.as_deref().unwrap_or("all accounts");
+        match enabled {
+            Some(false) => warn!("Feature {} disabled for {}: {}", feature, scope, reason),
+            Some(true) => info!("Feature {} enabled for {}: {}", feature, scope, reason),
+            None => info!("Feature {} flag cleared for {}: {}", feature, scope, reason),
+        }
+
+        let change = FeatureFlagChange {
+            feature,
+            account_id,
+            previous,
+            enabled,
+            reason: reason.to_string(),
+            changed_at: now,
+        };
+        let mut history = self.history.lock().unwrap();
+        history.push_back(change.clone());
+        while history.len() > self.history_limit {
+            history.pop_front();
+        }
+        change
+    }
+
+    /// Every flag set, global flags first, then by account and feature
+    pub fn flags(&self) -> Vec<FeatureFlag> {
+        let mut flags: Vec<FeatureFlag> = self.flags.read().unwrap().values().cloned().collect();
+        flags.sort_by(|a, b| (&a.account_id, a.feature).cmp(&(&b.account_id, b.feature)));
+        flags
+    }
+
+    /// Up to `limit` flag changes, most recent first
+    pub fn history(&self, limit: usize) -> Vec<FeatureFlagChange> {
+        let history = self.history.lock().unwrap();
+        history.iter().rev().take(limit).cloned().collect()
+    }
+}
+
+/// One account's view of the feature flags
+#[derive(Debug, Clone)]
+pub struct AccountFeatures {
+    flags: Arc<FeatureFlags>,
+    account_id: String,
+}
+
+impl AccountFeatures {
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.flags.is_enabled(&self.account_id, feature)
+    }
+}
+
+/// Disabled features degrade the engine without making it unready: it keeps
+/// trading, but with less protection than configured
+#[async_trait]
+impl HealthProbe for FeatureFlags {
+    fn name(&self) -> &str {
+        "feature-flags"
+    }
+
+    fn kind(&self) -> DependencyKind {
+        DependencyKind::Other
+    }
+
+    fn critical(&self) -> bool {
+        false
+    }
+
+    async fn check(&self) -> Vec<DependencyHealth> {
+        let disabled: Vec<DependencyHealth> = self
+            .flags()
+            .into_iter()
+            .filter(|flag| !flag.enabled)
+            .map(|flag| {
+                let scope = flag.account_id.as_deref().unwrap_or("*");
+                let name = format!("feature:{}:{}", scope, flag.feature);
+                DependencyHealth::new(&name, DependencyKind::Other, HealthLevel::Degraded)
+                    .with_critical(false)
+                    .with_detail(format!("disabled: {}", flag.reason))
+            })
+            .collect();
+
+        if disabled.is_empty() {
+            vec![
+                DependencyHealth::new(self.name(), DependencyKind::Other, HealthLevel::Healthy)
+                    .with_critical(false)
+                    .with_detail("all features enabled"),
+            ]
+        } else {
+            disabled
+        }
+    }
+}
@@ -0,0 +1,418 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+
+use super::shutdown::ShutdownCoordinator;
+use super::supervisor::{SubsystemState, SubsystemStatus, SubsystemStatuses};
+use crate::execution::TradeExecutionOrchestrator;
+use crate::platforms::dxtrade::fix_session::{FIXSession, SessionState};
+
+/// Ordered from best to worst so the overall level is the maximum of its parts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum HealthLevel {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DependencyKind {
+    Subsystem,
+    Platform,
+    FixSession,
+    Messaging,
+    Database,
+    RiskMonitor,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyHealth {
+    pub name: String,
+    pub kind: DependencyKind,
+    pub level: HealthLevel,
+    /// An unhealthy critical dependency makes the engine not ready
+    pub critical: bool,
+    pub detail: Option<String>,
+    pub latency_ms: Option<u64>,
+}
+
+impl DependencyHealth {
+    pub fn new(name: &str, kind: DependencyKind, level: HealthLevel) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            level,
+            critical: true,
+            detail: None,
+            latency_ms: None,
+        }
+    }
+
+    pub fn with_critical(mut self, critical: bool) -> Self {
+        self.critical = critical;
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn with_latency_ms(mut self, latency_ms: u64) -> Self {
+        self.latency_ms = Some(latency_ms);
+        self
+    }
+
+    /// Contribution to the engine's overall level; non-critical failures only degrade it
+    fn effective_level(&self) -> HealthLevel {
+        match self.level {
+            HealthLevel::Unhealthy if !self.critical => HealthLevel::Degraded,
+            level => level,
+        }
+    }
+}
+
+/// Reports the health of one or more dependencies of the engine
+#[async_trait]
+pub trait HealthProbe: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn kind(&self) -> DependencyKind;
+
+    /// Used for the failure reported when the probe itself times out
+    fn critical(&self) -> bool {
+        true
+    }
+
+    async fn check(&self) -> Vec<DependencyHealth>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// False once the engine is unhealthy; kept for clients of the flat status
+    pub healthy: bool,
+    #[serde(default = "default_level")]
+    pub status: HealthLevel,
+    /// Whether the process should be left running (liveness probe)
+    #[serde(default = "default_true")]
+    pub live: bool,
+    /// Whether the engine should receive signals and requests (readiness probe)
+    #[serde(default = "default_true")]
+    pub ready: bool,
+    pub subsystems: Vec<SubsystemStatus>,
+    #[serde(default)]
+    pub dependencies: Vec<DependencyHealth>,
+    #[serde(default = "Utc::now")]
+    pub checked_at: DateTime<Utc>,
+}
+
+fn default_level() -> HealthLevel {
+    HealthLevel::Healthy
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Aggregates supervised subsystems and registered probes into liveness, readiness
+/// and an overall degradation level
+pub struct HealthChecker {
+    subsystems: SubsystemStatuses,
+    probes: Vec<Arc<dyn HealthProbe>>,
+    shutdown: Option<Arc<ShutdownCoordinator>>,
+    probe_timeout: Duration,
+}
+
+impl HealthChecker {
+    pub fn new(subsystems: SubsystemStatuses) -> Self {
+        Self {
+            subsystems,
+            probes: Vec::new(),
+            shutdown: None,
+            probe_timeout: Duration::from_secs(5),
+        }
+    }
+
+    pub fn with_probe(mut self, probe: Arc<dyn HealthProbe>) -> Self {
+        self.probes.push(probe);
+        self
+    }
+
+    /// Report not ready as soon as shutdown has been requested
+    pub fn with_shutdown(mut self, shutdown: Arc<ShutdownCoordinator>) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// A probe that takes longer is reported unhealthy
+    pub fn with_probe_timeout(mut self, probe_timeout: Duration) -> Self {
+        self.probe_timeout = probe_timeout;
+        self
+    }
+
+    async fn subsystem_statuses(&self) -> Vec<SubsystemStatus> {
+        let mut subsystems: Vec<SubsystemStatus> =
+            self.subsystems.read().await.values().cloned().collect();
+        subsystems.sort_by(|a, b| a.name.cmp(&b.name));
+        subsystems
+    }
+
+    /// Live unless a subsystem has failed for good, which only a restart can fix.
+    /// Does not run the probes, so it is cheap enough for frequent polling.
+    pub async fn is_live(&self) -> bool {
+        live(&self.subsystem_statuses().await)
+    }
+
+    pub async fn check(&self) -> HealthReport {
+        let subsystems = self.subsystem_statuses().await;
+        let mut dependencies: Vec<DependencyHealth> =
+            subsystems.iter().map(subsystem_health).collect();
+        let probe_results = join_all(self.probes.iter().map(|probe| self.run_probe(probe))).await;
+        dependencies.extend(probe_results.into_iter().flatten());
+
+        let status = dependencies
+            .iter()
+            .map(DependencyHealth::effective_level)
+            .max()
+            .unwrap_or(HealthLevel::Healthy);
+        let shutting_down = self.shutdown.as_ref().is_some_and(|s| s.is_requested());
+        let ready = !shutting_down
+            && dependencies
+                .iter()
+                .all(|d| !d.critical || d.level != HealthLevel::Unhealthy);
+
+        HealthReport {
+            healthy: status != HealthLevel::Unhealthy,
+            status,
+            live: live(&subsystems),
+            ready,
+            subsystems,
+            dependencies,
+            checked_at: Utc::now(),
+        }
+    }
+
+    async fn run_probe(&self, probe: &Arc<dyn HealthProbe>) -> Vec<DependencyHealth> {
+        let started = Instant::now();
+        match timeout(self.probe_timeout, probe.check()).await {
+            Ok(mut results) => {
+                let elapsed = started.elapsed().as_millis() as u64;
+                for result in results.iter_mut() {
+                    result.latency_ms.get_or_insert(elapsed);
+                }
+                results
+            }
+            Err(_) => {
+                vec![
+                    DependencyHealth::new(probe.name(), probe.kind(), HealthLevel::Unhealthy)
+                        .with_critical(probe.critical())
+                        .with_detail(format!("no answer within {:?}", self.probe_timeout)),
+                ]
+            }
+        }
+    }
+}
+
+fn live(subsystems: &[SubsystemStatus]) -> bool {
+    subsystems
+        .iter()
+        .all(|s| !matches!(s.state, SubsystemState::Failed))
+}
+
+fn subsystem_health(status: &SubsystemStatus) -> DependencyHealth {
+    let kind = match status.name.as_str() {
+        "messaging" => DependencyKind::Messaging,
+        "risk-monitor" => DependencyKind::RiskMonitor,
+        _ => DependencyKind::Subsystem,
+    };
+    let level = match status.state {
+        SubsystemState::Running | SubsystemState::Completed => HealthLevel::Healthy,
+        SubsystemState::Pending | SubsystemState::Restarting => HealthLevel::Degraded,
+        SubsystemState::Failed | SubsystemState::Stopped => HealthLevel::Unhealthy,
+    };
+    let detail = match &status.last_error {
+        Some(error) => format!("{:?}: {}", status.state, error),
+        None => format!("{:?}", status.state),
+    };
+    DependencyHealth::new(&status.name, kind, level).with_detail(detail)
+}
+
+/// Health of every trading platform registered with the orchestrator. Platforms are
+/// not critical: one broker being down degrades the engine without taking it out of service.
+/// The latency each platform answers with is recorded for the orchestrator's entries.
+pub struct PlatformHealthProbe {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    max_error_rate: f64,
+}
+
+impl PlatformHealthProbe {
+    pub fn new(orchestrator: Arc<TradeExecutionOrchestrator>) -> Self {
+        Self {
+            orchestrator,
+            max_error_rate: 0.1,
+        }
+    }
+
+    /// Error rate above which a platform that still answers is reported degraded
+    pub fn with_max_error_rate(mut self, max_error_rate: f64) -> Self {
+        self.max_error_rate = max_error_rate;
+        self
+    }
+}
+
+#[async_trait]
+impl HealthProbe for PlatformHealthProbe {
+    fn name(&self) -> &str {
+        "platforms"
+    }
+
+    fn kind(&self) -> DependencyKind {
+        DependencyKind::Platform
+    }
+
+    fn critical(&self) -> bool {
+        false
+    }
+
+    async fn check(&self) -> Vec<DependencyHealth> {
+        let platforms = self.orchestrator.get_platforms().await;
+        let checks = platforms.iter().map(|(account_id, platform)| async move {
+            let name = format!("platform:{}", account_id);
+            let health = match platform.health_check().await {
+                Ok(status) if !status.is_healthy => {
+                    DependencyHealth::new(&name, DependencyKind::Platform, HealthLevel::Unhealthy)
+                        .with_detail(status.issues.join("; "))
+                }
+                Ok(status) => {
+                    let level =
+                        if status.issues.is_empty() && status.error_rate <= self.max_error_rate {
+                            HealthLevel::Healthy
+                        } else {
+                            HealthLevel::Degraded
+                        };
+                    let mut health = DependencyHealth::new(&name, DependencyKind::Platform, level);
+                    if !status.issues.is_empty() {
+                        health = health.with_detail(status.issues.join("; "));
+                    } else if level == HealthLevel::Degraded {
+                        health = health
+                            .with_detail(format!("error rate {:.1}%", status.error_rate * 100.0));
+                    }
+                    if let Some(latency) = status.latency_ms {
+                        self.orchestrator
+                            .latency_monitor()
+                            .record(account_id, latency);
+                        health = health.with_latency_ms(latency);
+                    }
+                    health
+                }
+                Err(e) => {
+                    DependencyHealth::new(&name, DependencyKind::Platform, HealthLevel::Unhealthy)
+                        .with_detail(e.to_string())
+                }
+            };
+            health.with_critical(false)
+        });
+        join_all(checks).await
+    }
+}
+
+/// State of a FIX session: logged in is healthy, (re)connecting degraded, anything else down
+pub struct FixSessionProbe {
+    name: String,
+    session: Arc<FIXSession>,
+}
+
+impl FixSessionProbe {
+    pub fn new(name: &str, session: Arc<FIXSession>) -> Self {
+        Self {
+            name: name.to_string(),
+            session,
+        }
+    }
+}
+
+#[async_trait]
+impl HealthProbe for FixSessionProbe {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> DependencyKind {
+        DependencyKind::FixSession
+    }
+
+    async fn check(&self) -> Vec<DependencyHealth> {
+        let state = self.session.get_session_state().await;
+        let level = match state {
+            SessionState::LoggedIn => HealthLevel::Healthy,
+            SessionState::Connecting | SessionState::LogonSent | SessionState::Reconnecting => {
+                HealthLevel::Degraded
+            }
+            SessionState::Disconnected | SessionState::LogoutSent | SessionState::ShuttingDown => {
+                HealthLevel::Unhealthy
+            }
+        };
+        vec![
+            DependencyHealth::new(&self.name, DependencyKind::FixSession, level)
+                .with_detail(format!("{:?}", state)),
+        ]
+    }
+}
+
+/// Whether the directory a file store writes to exists and is writable. Not critical:
+/// trading continues, but records are not persisted.
+pub struct StorageProbe {
+    name: String,
+    path: PathBuf,
+}
+
+impl StorageProbe {
+    pub fn new(name: &str, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl HealthProbe for StorageProbe {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> DependencyKind {
+        DependencyKind::Database
+    }
+
+    fn critical(&self) -> bool {
+        false
+    }
+
+    async fn check(&self) -> Vec<DependencyHealth> {
+        let dir = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let health = match tokio::fs::metadata(&dir).await {
+            Ok(metadata) if metadata.permissions().readonly() => {
+                DependencyHealth::new(&self.name, DependencyKind::Database, HealthLevel::Unhealthy)
+                    .with_detail(format!("{} is read-only", dir.display()))
+            }
+            Ok(_) => {
+                DependencyHealth::new(&self.name, DependencyKind::Database, HealthLevel::Healthy)
+            }
+            Err(e) => {
+                DependencyHealth::new(&self.name, DependencyKind::Database, HealthLevel::Unhealthy)
+                    .with_detail(format!("{}: {}", dir.display(), e))
+            }
+        };
+        vec![health.with_critical(false)]
+    }
+}
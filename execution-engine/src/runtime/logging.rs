@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use tracing::field::{self, Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Metadata, Span, Subscriber};
+use tracing_subscriber::layer::{Context, Filter, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::platforms::PlatformType;
+
+/// Name of the spans created by [`LogContext::span`]
+pub const CONTEXT_SPAN: &str = "ctx";
+
+/// Install the global subscriber: RUST_LOG (or `default_directives`) for everything,
+/// raised per account through the returned handle
+pub fn init_logging(
+    default_directives: &str,
+) -> Result<AccountLogLevels, Box<dyn std::error::Error + Send + Sync>> {
+    let levels = AccountLogLevels::default();
+    let base =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_directives));
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_filter(AccountLevelFilter::new(base, levels.clone())),
+        )
+        .try_init()?;
+    Ok(levels)
+}
+
+/// Identifiers attached to every log emitted inside [`LogContext::span`], so lines from
+/// concurrent accounts can be told apart. Spans nest: a position context entered inside
+/// an account context logs both.
+#[derive(Debug, Clone, Default)]
+pub struct LogContext {
+    account_id: Option<String>,
+    platform: Option<PlatformType>,
+    signal_id: Option<String>,
+    position_id: Option<String>,
+}
+
+impl LogContext {
+    pub fn account(account_id: &str) -> Self {
+        Self {
+            account_id: Some(account_id.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn signal(signal_id: &str) -> Self {
+        Self::default().with_signal(signal_id)
+    }
+
+    pub fn position(position_id: impl Display) -> Self {
+        Self::default().with_position(position_id)
+    }
+
+    pub fn with_platform(mut self, platform: PlatformType) -> Self {
+        self.platform = Some(platform);
+        self
+    }
+
+    pub fn with_signal(mut self, signal_id: &str) -> Self {
+        self.signal_id = Some(signal_id.to_string());
+        self
+    }
+
+    pub fn with_position(mut self, position_id: impl Display) -> Self {
+        self.position_id = Some(position_id.to_string());
+        self
+    }
+
+    /// A span carrying the identifiers that are set, child of the current span
+    pub fn span(&self) -> Span {
+        let span = tracing::info_span!(
+            CONTEXT_SPAN,
+            account_id = field::Empty,
+            platform = field::Empty,
+            signal_id = field::Empty,
+            position_id = field::Empty,
+        );
+        if let Some(account_id) = &self.account_id {
+            span.record("account_id", account_id.as_str());
+        }
+        if let Some(platform) = &self.platform {
+            span.record("platform", field::debug(platform));
+        }
+        if let Some(signal_id) = &self.signal_id {
+            span.record("signal_id", signal_id.as_str());
+        }
+        if let Some(position_id) = &self.position_id {
+            span.record("position_id", position_id.as_str());
+        }
+        span
+    }
+}
+
+/// Log level overrides for single accounts, applied to everything logged inside their
+/// [`LogContext`] on top of the default directives
+#[derive(Debug, Clone, Default)]
+pub struct AccountLogLevels {
+    levels: Arc<RwLock<HashMap<String, LevelFilter>>>,
+}
+
+impl AccountLogLevels {
+    pub fn set(&self, account_id: &str, level: LevelFilter) {
+        self.levels
+            .write()
+            .unwrap()
+            .insert(account_id.to_string(), level);
+        // Callsites disabled under the previous levels are asked again
+        tracing::callsite::rebuild_interest_cache();
+    }
+
+    pub fn clear(&self, account_id: &str) {
+        self.levels.write().unwrap().remove(account_id);
+        tracing::callsite::rebuild_interest_cache();
+    }
+
+    pub fn get(&self, account_id: &str) -> Option<LevelFilter> {
+        self.levels.read().unwrap().get(account_id).copied()
+    }
+
+    /// Set every `account_id = level` pair, e.g. from the engine configuration
+    pub fn apply(&self, levels: &HashMap<String, String>) -> Result<(), String> {
+        for (account_id, level) in levels {
+            let level = LevelFilter::from_str(level)
+                .map_err(|_| format!("Invalid log level {} for account {}", level, account_id))?;
+            self.set(account_id, level);
+        }
+        Ok(())
+    }
+
+    fn max_level(&self) -> Option<LevelFilter> {
+        self.levels.read().unwrap().values().copied().max()
+    }
+}
+
+/// Per-layer filter enabling what `base` enables, plus events inside the context of an
+/// account whose override admits their level
+pub struct AccountLevelFilter {
+    base: EnvFilter,
+    levels: AccountLogLevels,
+}
+
+/// Account of a context span, kept in its extensions for event filtering
+struct AccountScope(String);
+
+#[derive(Default)]
+struct AccountIdVisitor(Option<String>);
+
+impl Visit for AccountIdVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "account_id" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "account_id" {
+            self.0 = Some(format!("{:?}", value));
+        }
+    }
+}
+
+impl AccountLevelFilter {
+    pub fn new(base: EnvFilter, levels: AccountLogLevels) -> Self {
+        Self { base, levels }
+    }
+
+    fn account_level<S>(&self, cx: &Context<'_, S>) -> Option<LevelFilter>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let account_id = cx.lookup_current()?.scope().find_map(|span| {
+            span.extensions()
+                .get::<AccountScope>()
+                .map(|scope| scope.0.clone())
+        })?;
+        self.levels.get(&account_id)
+    }
+
+    fn store_account<S>(&self, id: &Id, account_id: Option<String>, cx: &Context<'_, S>)
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        if let (Some(account_id), Some(span)) = (account_id, cx.span(id)) {
+            span.extensions_mut().replace(AccountScope(account_id));
+        }
+    }
+}
+
+/// Context spans are always created so their identifiers reach every enabled event
+fn is_context_span(metadata: &Metadata<'_>) -> bool {
+    metadata.is_span() && metadata.name() == CONTEXT_SPAN
+}
+
+impl<S> Filter<S> for AccountLevelFilter
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn enabled(&self, metadata: &Metadata<'_>, cx: &Context<'_, S>) -> bool {
+        if is_context_span(metadata) || Filter::<S>::enabled(&self.base, metadata, cx) {
+            return true;
+        }
+        metadata.is_event()
+            && self
+                .account_level(cx)
+                .is_some_and(|level| level >= *metadata.level())
+    }
+
+    fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if is_context_span(metadata) {
+            return Interest::always();
+        }
+        let interest = Filter::<S>::callsite_enabled(&self.base, metadata);
+        let overridden = self
+            .levels
+            .max_level()
+            .is_some_and(|level| level >= *metadata.level());
+        if metadata.is_event() && overridden && !interest.is_always() {
+            Interest::sometimes()
+        } else {
+            interest
+        }
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        let base = Filter::<S>::max_level_hint(&self.base)?;
+        Some(
+            self.levels
+                .max_level()
+                .map_or(base, |level| level.max(base)),
+        )
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, cx: Context<'_, S>) {
+        let mut visitor = AccountIdVisitor::default();
+        attrs.record(&mut visitor);
+        self.store_account(id, visitor.0, &cx);
+        Filter::on_new_span(&self.base, attrs, id, cx);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, cx: Context<'_, S>) {
+        let mut visitor = AccountIdVisitor::default();
+        values.record(&mut visitor);
+        self.store_account(id, visitor.0, &cx);
+        Filter::on_record(&self.base, id, values, cx);
+    }
+
+    fn on_enter(&self, id: &Id, cx: Context<'_, S>) {
+        Filter::on_enter(&self.base, id, cx);
+    }
+
+    fn on_exit(&self, id: &Id, cx: Context<'_, S>) {
+        Filter::on_exit(&self.base, id, cx);
+    }
+
+    fn on_close(&self, id: Id, cx: Context<'_, S>) {
+        Filter::on_close(&self.base, id, cx);
+    }
+}
@@ -0,0 +1,40 @@
+pub mod bootstrap;
+pub mod channel;
+pub mod config;
+pub mod connectors;
+pub mod feature_flags;
+pub mod health;
+pub mod logging;
+pub mod shutdown;
+pub mod spawn;
+pub mod subsystems;
+pub mod supervisor;
+pub mod watchdog;
+
+pub use bootstrap::{AccountBootstrapper, PlatformConnector};
+pub use channel::{bounded, BoundedReceiver, BoundedSender, ChannelStats, OverflowPolicy};
+pub use config::{
+    load_config, AccountBootstrap, ApiConfig, DashboardConfig, EngineConfig, ExitManagementConfig,
+    JournalConfig, LedgerConfig, LoggingConfig,
+};
+pub use connectors::{DXTradeConnector, TradeLockerConnector};
+pub use feature_flags::{
+    AccountFeatures, Feature, FeatureFlag, FeatureFlagChange, FeatureFlagConfig, FeatureFlags,
+};
+pub use health::{
+    DependencyHealth, DependencyKind, FixSessionProbe, HealthChecker, HealthLevel, HealthProbe,
+    HealthReport, PlatformHealthProbe, StorageProbe,
+};
+pub use logging::{init_logging, AccountLevelFilter, AccountLogLevels, LogContext};
+pub use shutdown::{
+    ShutdownConfig, ShutdownCoordinator, ShutdownHook, ShutdownPhase, ShutdownReport, StepStatus,
+};
+pub use spawn::{spawn_isolated, supervised_spawn};
+pub use supervisor::{
+    RestartPolicy, ShutdownSignal, Subsystem, SubsystemState, SubsystemStatus, SubsystemStatuses,
+    Supervisor, SupervisorConfig,
+};
+pub use watchdog::{
+    Heartbeat, RestartFn, Watchdog, WatchdogAction, WatchdogAlert, WatchdogConfig,
+    WatchedTaskStatus,
+};
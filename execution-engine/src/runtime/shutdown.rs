@@ -0,0 +1,442 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures_util::future::{BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, RwLock};
+use tokio::time::timeout;
+use tracing::{error, info, warn};
+
+use super::supervisor::Supervisor;
+use crate::execution::exit_management::ExitAuditLogger;
+use crate::execution::TradeExecutionOrchestrator;
+use crate::platforms::abstraction::PlatformError;
+use crate::platforms::dxtrade::fix_session::{FIXSession, SessionState};
+use crate::storage::{FileOrchestratorStateStore, OrchestratorStateStore};
+
+/// Steps of the shutdown protocol, run in declaration order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ShutdownPhase {
+    StopIntake,
+    DrainExecutions,
+    FlushBuffers,
+    PersistState,
+    CloseSessions,
+    DrainPools,
+}
+
+/// Work to do while the engine shuts down, before subsystems are stopped
+#[async_trait]
+pub trait ShutdownHook: Send + Sync {
+    fn name(&self) -> &str;
+    fn phase(&self) -> ShutdownPhase;
+    async fn run(&self) -> Result<()>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownConfig {
+    /// Upper bound on all hooks together; subsystems get their own deadline afterwards
+    pub deadline_secs: u64,
+    /// How long in-flight executions may take before they are cancelled
+    pub drain_timeout_secs: u64,
+    pub state_path: String,
+    /// How long each FIX session waits for the counterparty to confirm its logout
+    #[serde(default = "default_logout_wait_secs")]
+    pub logout_wait_secs: u64,
+}
+
+fn default_logout_wait_secs() -> u64 {
+    5
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            deadline_secs: 60,
+            drain_timeout_secs: 30,
+            state_path: "data/orchestrator_state.json".to_string(),
+            logout_wait_secs: default_logout_wait_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StepStatus {
+    Completed,
+    Failed,
+    TimedOut,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownStep {
+    pub hook: String,
+    pub phase: ShutdownPhase,
+    pub status: StepStatus,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    pub reason: String,
+    pub requested_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub steps: Vec<ShutdownStep>,
+}
+
+impl ShutdownReport {
+    pub fn is_clean(&self) -> bool {
+        self.steps.iter().all(|s| s.status == StepStatus::Completed)
+    }
+}
+
+/// Runs the shutdown protocol once, whoever asks for it first: an OS signal or
+/// the admin API
+pub struct ShutdownCoordinator {
+    config: ShutdownConfig,
+    hooks: std::sync::RwLock<Vec<Arc<dyn ShutdownHook>>>,
+    request_tx: watch::Sender<Option<String>>,
+    report: RwLock<Option<ShutdownReport>>,
+}
+
+impl ShutdownCoordinator {
+    pub fn new(config: ShutdownConfig) -> Self {
+        Self {
+            config,
+            hooks: std::sync::RwLock::new(Vec::new()),
+            request_tx: watch::channel(None).0,
+            report: RwLock::new(None),
+        }
+    }
+
+    pub fn config(&self) -> &ShutdownConfig {
+        &self.config
+    }
+
+    pub fn with_hook(self, hook: Arc<dyn ShutdownHook>) -> Self {
+        self.add_hook(hook);
+        self
+    }
+
+    /// Register a hook after construction, for sessions opened while the engine runs
+    pub fn add_hook(&self, hook: Arc<dyn ShutdownHook>) {
+        self.hooks.write().unwrap().push(hook);
+    }
+
+    /// Ask for shutdown. Returns false if it had already been requested.
+    pub fn request(&self, reason: &str) -> bool {
+        self.request_tx.send_if_modified(|current| {
+            if current.is_some() {
+                return false;
+            }
+            info!("Shutdown requested: {}", reason);
+            *current = Some(reason.to_string());
+            true
+        })
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.request_tx.borrow().is_some()
+    }
+
+    /// Resolves with the reason once shutdown has been requested
+    pub async fn wait_for_request(&self) -> String {
+        let mut rx = self.request_tx.subscribe();
+        let reason = match rx.wait_for(Option::is_some).await {
+            Ok(reason) => reason.clone().unwrap_or_default(),
+            Err(_) => String::new(),
+        };
+        reason
+    }
+
+    /// Progress of a shutdown in flight, or the outcome of a finished one
+    pub async fn report(&self) -> Option<ShutdownReport> {
+        self.report.read().await.clone()
+    }
+
+    /// Run every hook in phase order within the configured deadline, then stop the
+    /// supervised subsystems. Later calls return the first report.
+    pub async fn execute(&self, supervisor: &mut Supervisor) -> ShutdownReport {
+        if let Some(report) = self.report().await {
+            return report;
+        }
+
+        self.request("unspecified");
+        let reason = self.request_tx.borrow().clone().unwrap_or_default();
+
+        *self.report.write().await = Some(ShutdownReport {
+            reason,
+            requested_at: Utc::now(),
+            completed_at: None,
+            steps: Vec::new(),
+        });
+
+        let mut hooks = self.hooks.read().unwrap().clone();
+        hooks.sort_by_key(|hook| hook.phase());
+        let deadline = Instant::now() + Duration::from_secs(self.config.deadline_secs);
+
+        for hook in hooks {
+            let started = Instant::now();
+            let remaining = deadline.saturating_duration_since(started);
+            let (status, error) = match timeout(remaining, hook.run()).await {
+                Ok(Ok(())) => (StepStatus::Completed, None),
+                Ok(Err(e)) => {
+                    error!("Shutdown step {} failed: {}", hook.name(), e);
+                    (StepStatus::Failed, Some(e.to_string()))
+                }
+                Err(_) => {
+                    warn!("Shutdown step {} timed out", hook.name());
+                    (StepStatus::TimedOut, None)
+                }
+            };
+
+            if let Some(report) = self.report.write().await.as_mut() {
+                report.steps.push(ShutdownStep {
+                    hook: hook.name().to_string(),
+                    phase: hook.phase(),
+                    status,
+                    error,
+                    elapsed_ms: started.elapsed().as_millis() as u64,
+                });
+            }
+        }
+
+        supervisor.shutdown().await;
+
+        let mut report = self.report.write().await;
+        let report = report.as_mut().expect("report initialised above");
+        report.completed_at = Some(Utc::now());
+        report.clone()
+    }
+}
+
+/// Resolves on SIGINT or, on Unix, SIGTERM, returning the signal name
+pub async fn termination_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => tokio::select! {
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+                _ = sigterm.recv() => "SIGTERM",
+            },
+            Err(e) => {
+                warn!("Unable to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "SIGINT"
+    }
+}
+
+/// Stops the orchestrator from accepting new signals
+pub struct StopSignalIntake {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+}
+
+impl StopSignalIntake {
+    pub fn new(orchestrator: Arc<TradeExecutionOrchestrator>) -> Self {
+        Self { orchestrator }
+    }
+}
+
+#[async_trait]
+impl ShutdownHook for StopSignalIntake {
+    fn name(&self) -> &str {
+        "stop-signal-intake"
+    }
+
+    fn phase(&self) -> ShutdownPhase {
+        ShutdownPhase::StopIntake
+    }
+
+    async fn run(&self) -> Result<()> {
+        self.orchestrator.stop_accepting_signals();
+        Ok(())
+    }
+}
+
+/// Lets in-flight executions finish, cancelling whatever is left at the timeout
+pub struct DrainExecutions {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    timeout: Duration,
+}
+
+impl DrainExecutions {
+    pub fn new(orchestrator: Arc<TradeExecutionOrchestrator>, timeout: Duration) -> Self {
+        Self {
+            orchestrator,
+            timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl ShutdownHook for DrainExecutions {
+    fn name(&self) -> &str {
+        "drain-executions"
+    }
+
+    fn phase(&self) -> ShutdownPhase {
+        ShutdownPhase::DrainExecutions
+    }
+
+    async fn run(&self) -> Result<()> {
+        let cancelled = self.orchestrator.drain_executions(self.timeout).await;
+        if !cancelled.is_empty() {
+            warn!(
+                "Cancelled {} in-flight executions: {:?}",
+                cancelled.len(),
+                cancelled
+            );
+        }
+        Ok(())
+    }
+}
+
+pub struct FlushExitAudit {
+    exit_logger: Arc<ExitAuditLogger>,
+}
+
+impl FlushExitAudit {
+    pub fn new(exit_logger: Arc<ExitAuditLogger>) -> Self {
+        Self { exit_logger }
+    }
+}
+
+#[async_trait]
+impl ShutdownHook for FlushExitAudit {
+    fn name(&self) -> &str {
+        "flush-exit-audit"
+    }
+
+    fn phase(&self) -> ShutdownPhase {
+        ShutdownPhase::FlushBuffers
+    }
+
+    async fn run(&self) -> Result<()> {
+        self.exit_logger.flush().await
+    }
+}
+
+pub struct PersistOrchestratorState {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    store: Arc<dyn OrchestratorStateStore>,
+}
+
+impl PersistOrchestratorState {
+    pub fn new(orchestrator: Arc<TradeExecutionOrchestrator>, path: String) -> Self {
+        Self {
+            orchestrator,
+            store: Arc::new(FileOrchestratorStateStore::new(path)),
+        }
+    }
+
+    /// Save into `store` instead of the file at `path`
+    pub fn with_store(mut self, store: Arc<dyn OrchestratorStateStore>) -> Self {
+        self.store = store;
+        self
+    }
+}
+
+#[async_trait]
+impl ShutdownHook for PersistOrchestratorState {
+    fn name(&self) -> &str {
+        "persist-orchestrator-state"
+    }
+
+    fn phase(&self) -> ShutdownPhase {
+        ShutdownPhase::PersistState
+    }
+
+    async fn run(&self) -> Result<()> {
+        self.orchestrator
+            .persist_state_to(self.store.as_ref())
+            .await
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+/// Logs a FIX session out and waits for the counterparty to confirm
+pub struct FixSessionLogout {
+    name: String,
+    session: Arc<FIXSession>,
+    wait: Duration,
+}
+
+impl FixSessionLogout {
+    pub fn new(session: Arc<FIXSession>, wait: Duration) -> Self {
+        Self {
+            name: format!("fix-logout-{}", session.get_session_id()),
+            session,
+            wait,
+        }
+    }
+}
+
+#[async_trait]
+impl ShutdownHook for FixSessionLogout {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn phase(&self) -> ShutdownPhase {
+        ShutdownPhase::CloseSessions
+    }
+
+    async fn run(&self) -> Result<()> {
+        if self.session.get_session_state().await == SessionState::Disconnected {
+            return Ok(());
+        }
+
+        if !self.session.logout(self.wait).await? {
+            warn!("{} closed without logout confirmation", self.name);
+        }
+        Ok(())
+    }
+}
+
+/// Disconnects an account's platform once its sessions are closed
+pub struct DisconnectPlatform {
+    name: String,
+    disconnect: Box<dyn Fn() -> BoxFuture<'static, Result<(), PlatformError>> + Send + Sync>,
+}
+
+impl DisconnectPlatform {
+    /// `disconnect` closes the connection the platform's connector opened
+    pub fn new<F, Fut>(account_id: &str, disconnect: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), PlatformError>> + Send + 'static,
+    {
+        Self {
+            name: format!("disconnect-{}", account_id),
+            disconnect: Box::new(move || disconnect().boxed()),
+        }
+    }
+}
+
+#[async_trait]
+impl ShutdownHook for DisconnectPlatform {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn phase(&self) -> ShutdownPhase {
+        ShutdownPhase::DrainPools
+    }
+
+    async fn run(&self) -> Result<()> {
+        (self.disconnect)().await.map_err(|e| anyhow!(e))
+    }
+}
@@ -0,0 +1,116 @@
+use futures_util::FutureExt;
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use std::any::Any;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tracing::{error, warn, Instrument};
+
+use super::supervisor::RestartPolicy;
+use crate::platforms::abstraction::quota::{current_caller, with_caller, UNSCOPED_CALLER};
+
+lazy_static! {
+    pub static ref TASK_PANICS: IntCounterVec = register_int_counter_vec!(
+        "execution_engine_task_panics_total",
+        "Panics caught in spawned tasks",
+        &["task"]
+    )
+    .unwrap();
+    pub static ref TASK_RESTARTS: IntCounterVec = register_int_counter_vec!(
+        "execution_engine_task_restarts_total",
+        "Restarts of supervised spawned tasks",
+        &["task"]
+    )
+    .unwrap();
+}
+
+/// Panics caught so far in tasks spawned under `name`
+pub fn task_panics(name: &str) -> u64 {
+    TASK_PANICS.with_label_values(&[name]).get()
+}
+
+/// Restarts so far of supervised tasks spawned under `name`
+pub fn task_restarts(name: &str) -> u64 {
+    TASK_RESTARTS.with_label_values(&[name]).get()
+}
+
+/// Spawn a one-off task in the caller's span, charging its platform calls to the
+/// caller's quota budget. A panic is logged under `name` and counted, and the handle
+/// resolves to the panic message instead of a bare `JoinError`.
+pub fn spawn_isolated<T, Fut>(name: impl Into<String>, future: Fut) -> JoinHandle<Result<T, String>>
+where
+    T: Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+{
+    let name = name.into();
+    let caller = current_caller().unwrap_or_else(|| UNSCOPED_CALLER.to_string());
+    tokio::spawn(
+        async move {
+            with_caller(caller, AssertUnwindSafe(future).catch_unwind())
+                .await
+                .map_err(|payload| record_panic(&name, payload))
+        }
+        .in_current_span(),
+    )
+}
+
+/// Spawn a long-running task built by `task`, starting a fresh instance after a panic
+/// (or any return, for `RestartPolicy::Always`) until the policy gives up
+pub fn supervised_spawn<F, Fut>(
+    name: impl Into<String>,
+    policy: RestartPolicy,
+    mut task: F,
+) -> JoinHandle<()>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let name = name.into();
+    let caller = current_caller().unwrap_or_else(|| UNSCOPED_CALLER.to_string());
+    tokio::spawn(async move {
+        let mut restarts = 0;
+        loop {
+            let run = with_caller(caller.clone(), task());
+            let failed = match AssertUnwindSafe(run).catch_unwind().await {
+                Ok(()) => false,
+                Err(payload) => {
+                    record_panic(&name, payload);
+                    true
+                }
+            };
+
+            let Some(delay) = policy.restart_delay(failed, restarts) else {
+                if failed {
+                    error!("Task {} panicked and will not be restarted", name);
+                }
+                return;
+            };
+            restarts += 1;
+            TASK_RESTARTS.with_label_values(&[&name]).inc();
+            warn!(
+                "Restarting task {} in {:?} (restart {})",
+                name, delay, restarts
+            );
+            sleep(delay).await;
+        }
+    })
+}
+
+pub(crate) fn panic_payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+fn record_panic(name: &str, payload: Box<dyn Any + Send>) -> String {
+    let message = panic_payload_message(payload.as_ref());
+    TASK_PANICS.with_label_values(&[name]).inc();
+    error!("Task {} panicked: {}", name, message);
+    format!("task {} panicked: {}", name, message)
+}
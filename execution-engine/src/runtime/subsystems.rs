@@ -0,0 +1,1287 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use axum::Router;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, RwLock};
+use tokio::time::{interval, Duration, MissedTickBehavior};
+use tokio_tungstenite::tungstenite::handshake::server::{
+    Callback, ErrorResponse as HandshakeErrorResponse, Request as HandshakeRequest,
+    Response as HandshakeResponse,
+};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+use super::bootstrap::AccountBootstrapper;
+use super::config::AccountBootstrap;
+use super::feature_flags::FeatureFlags;
+use super::logging::LogContext;
+use super::spawn::spawn_isolated;
+use super::supervisor::{ShutdownSignal, Subsystem};
+use super::watchdog::Watchdog;
+use crate::api::access::authenticate_headers;
+use crate::auth::{AuthError, Authenticator};
+use crate::dashboard::{DashboardAggregator, ExitSystems};
+use crate::execution::exit_management::news_protection::EconomicCalendarClient;
+use crate::execution::exit_management::types::ImpactLevel;
+use crate::execution::exit_management::{
+    ExitAuditLogger, ExitManagementPlatformAdapter, ExitManagementSystem, ExitStateStore,
+    FileExitStateStore, MarketContextConfig, PositionAdopter, ScaleInPolicy, ShadowVariant,
+    StopLossGuardian,
+};
+use crate::execution::{NewsBlackoutConfig, NewsCalendar, TradeExecutionOrchestrator};
+use crate::ledger::{PositionLedger, StatementReconciler};
+use crate::market_analysis::StructureAnalyzer;
+use crate::market_data::{CandleBuilder, CrossRates};
+use crate::platforms::abstraction::{PlatformError, ServerClocks};
+use crate::recording::{EventRecorder, RecordedEvent, RecordingConfig};
+use crate::risk::{RealTimePnLCalculator, RiskSnapshotter, TradingDayConfig};
+use crate::storage::Storage;
+
+/// Serves the HTTP API until shutdown, letting in-flight requests finish
+pub struct ApiServerSubsystem {
+    bind_address: String,
+    router: Router,
+}
+
+impl ApiServerSubsystem {
+    pub fn new(bind_address: String, router: Router) -> Self {
+        Self {
+            bind_address,
+            router,
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for ApiServerSubsystem {
+    fn name(&self) -> &str {
+        "api-server"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(&self.bind_address).await?;
+        info!("API server listening on {}", self.bind_address);
+
+        // Peer addresses let the public status page rate limit per client
+        axum::serve(
+            listener,
+            self.router
+                .clone()
+                .into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(async move { shutdown.recv().await })
+        .await?;
+        Ok(())
+    }
+}
+
+/// Registers configured accounts with the orchestrator on start. The orchestrator
+/// itself is request-driven, so its run loop only waits for shutdown.
+pub struct OrchestratorSubsystem {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    bootstrapper: AccountBootstrapper,
+    accounts: Vec<AccountBootstrap>,
+}
+
+impl OrchestratorSubsystem {
+    pub fn new(
+        orchestrator: Arc<TradeExecutionOrchestrator>,
+        bootstrapper: AccountBootstrapper,
+        accounts: Vec<AccountBootstrap>,
+    ) -> Self {
+        Self {
+            orchestrator,
+            bootstrapper,
+            accounts,
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for OrchestratorSubsystem {
+    fn name(&self) -> &str {
+        "orchestrator"
+    }
+
+    async fn start(&self) -> Result<()> {
+        self.bootstrapper
+            .bootstrap(&self.accounts, &self.orchestrator)
+            .await;
+        Ok(())
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        shutdown.recv().await;
+        Ok(())
+    }
+}
+
+/// How long the exit management loop may go without completing a position check
+const EXIT_LOOP_STALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Runs exit management for every account registered with the orchestrator
+pub struct ExitManagementSubsystem {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    exit_logger: Arc<ExitAuditLogger>,
+    systems: ExitSystems,
+    state_dir: Option<PathBuf>,
+    storage: Option<Arc<dyn Storage>>,
+    shadow_variants: Vec<ShadowVariant>,
+    watchdog: Option<Arc<Watchdog>>,
+    trading_days: Option<TradingDayConfig>,
+    candles: Option<Arc<CandleBuilder>>,
+    structure: Option<Arc<StructureAnalyzer>>,
+    market_context: MarketContextConfig,
+    scale_in: ScaleInPolicy,
+    feature_flags: Option<Arc<FeatureFlags>>,
+    recorder: Option<Arc<EventRecorder>>,
+    server_clocks: Option<ServerClocks>,
+}
+
+impl ExitManagementSubsystem {
+    pub fn new(
+        orchestrator: Arc<TradeExecutionOrchestrator>,
+        exit_logger: Arc<ExitAuditLogger>,
+    ) -> Self {
+        Self {
+            orchestrator,
+            exit_logger,
+            systems: Arc::new(RwLock::new(HashMap::new())),
+            state_dir: None,
+            storage: None,
+            shadow_variants: Vec::new(),
+            watchdog: None,
+            trading_days: None,
+            candles: None,
+            structure: None,
+            market_context: MarketContextConfig::default(),
+            scale_in: ScaleInPolicy::default(),
+            feature_flags: None,
+            recorder: None,
+            server_clocks: None,
+        }
+    }
+
+    /// Persist exit state to `<state_dir>/<account_id>.json` and resume it on start
+    pub fn with_state_dir(mut self, state_dir: impl Into<PathBuf>) -> Self {
+        self.state_dir = Some(state_dir.into());
+        self
+    }
+
+    /// Persist exit state in `storage` and resume it on start, in place of
+    /// the state directory
+    pub fn with_storage(mut self, storage: Arc<dyn Storage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Evaluate these exit configurations in shadow mode on every account
+    pub fn with_shadow_variants(mut self, variants: Vec<ShadowVariant>) -> Self {
+        self.shadow_variants = variants;
+        self
+    }
+
+    /// Report the position check loop to `watchdog` if it stops cycling
+    pub fn with_watchdog(mut self, watchdog: Arc<Watchdog>) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Time weekend exits from each account's trading day rollover
+    pub fn with_trading_days(mut self, trading_days: TradingDayConfig) -> Self {
+        self.trading_days = Some(trading_days);
+        self
+    }
+
+    /// Measure trailing stop ATR on candles from the quote stream
+    pub fn with_candles(mut self, candles: Arc<CandleBuilder>) -> Self {
+        self.candles = Some(candles);
+        self
+    }
+
+    /// Break-even and take-profit on support and resistance read by `structure`
+    pub fn with_structure(mut self, structure: Arc<StructureAnalyzer>) -> Self {
+        self.structure = Some(structure);
+        self
+    }
+
+    /// Measure the market context of exit audit entries per `config`
+    pub fn with_market_context(mut self, config: MarketContextConfig) -> Self {
+        self.market_context = config;
+        self
+    }
+
+    /// Recompute stops and targets of positions added to per `policy`
+    pub fn with_scale_in_policy(mut self, policy: ScaleInPolicy) -> Self {
+        self.scale_in = policy;
+        self
+    }
+
+    /// Skip the exit managers switched off in `feature_flags` for each account
+    pub fn with_feature_flags(mut self, feature_flags: Arc<FeatureFlags>) -> Self {
+        self.feature_flags = Some(feature_flags);
+        self
+    }
+
+    /// Record the calendar events news protection acts on, for replaying the day
+    pub fn with_recorder(mut self, recorder: Arc<EventRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Time sessions, news windows and the weekend close on each account's
+    /// measure of its platform server's clock
+    pub fn with_server_clocks(mut self, server_clocks: ServerClocks) -> Self {
+        self.server_clocks = Some(server_clocks);
+        self
+    }
+
+    /// The per-account systems, shared so other components can read exit state
+    pub fn systems(&self) -> ExitSystems {
+        self.systems.clone()
+    }
+}
+
+#[async_trait]
+impl Subsystem for ExitManagementSubsystem {
+    fn name(&self) -> &str {
+        "exit-management"
+    }
+
+    async fn start(&self) -> Result<()> {
+        let platforms = self.orchestrator.get_platforms().await;
+        let mut systems = self.systems.write().await;
+        systems.clear();
+
+        for (account_id, platform) in platforms {
+            let span = LogContext::account(&account_id)
+                .with_platform(platform.platform_type())
+                .span();
+            let symbols = platform.get_instruments().await;
+            let adapter = Arc::new(
+                ExitManagementPlatformAdapter::new(platform)
+                    .with_order_tracker(self.orchestrator.order_tracker(), &account_id),
+            );
+            let mut system = ExitManagementSystem::new(adapter, self.exit_logger.clone());
+            // Pip sizes and stop distances the platform reports; conventional values
+            // stand in on platforms that list no instruments
+            match symbols {
+                Ok(symbols) => system.get_instruments().register_symbols(&symbols),
+                Err(PlatformError::FeatureNotSupported { .. }) => {}
+                Err(e) => warn!(
+                    "Failed to load instruments for account {}: {}",
+                    account_id, e
+                ),
+            }
+            if let Some(trading_days) = &self.trading_days {
+                system = system.with_trading_day(trading_days.for_account(&account_id));
+            }
+            if let Some(candles) = &self.candles {
+                system = system.with_candles(candles.clone());
+            }
+            if let Some(structure) = &self.structure {
+                system = system.with_structure(structure.clone());
+            }
+            system = system
+                .with_market_context(self.market_context.clone())
+                .with_scale_in_policy(self.scale_in)
+                .with_order_tracker(self.orchestrator.order_tracker(), &account_id);
+            if let Some(feature_flags) = &self.feature_flags {
+                system = system.with_feature_flags(feature_flags.for_account(&account_id));
+            }
+            if let Some(recorder) = &self.recorder {
+                system = system.with_recorder(recorder.clone());
+            }
+            if let Some(server_clocks) = &self.server_clocks {
+                system = system.with_server_clock(server_clocks.clock(&account_id));
+            }
+            let mut system = system
+                .with_shadow_variants(self.shadow_variants.clone())
+                .with_span(span);
+
+            let store = match &self.storage {
+                Some(storage) => storage.exit_state(&account_id),
+                None => self.state_dir.as_ref().map(|dir| {
+                    let path = dir.join(format!("{}.json", account_id));
+                    Arc::new(FileExitStateStore::new(path)) as Arc<dyn ExitStateStore>
+                }),
+            };
+            if let Some(store) = store {
+                system = system.with_state_store(store);
+                // Checks stay paused and the restore is retried until it succeeds
+                if let Err(e) = system.restore_state().await {
+                    warn!(
+                        "Failed to restore exit state for account {}: {}",
+                        account_id, e
+                    );
+                }
+            }
+
+            systems.insert(account_id, system);
+        }
+
+        info!("Exit management configured for {} accounts", systems.len());
+        Ok(())
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut position_ticker = interval(Duration::from_millis(500));
+        let mut schedule_ticker = interval(Duration::from_secs(30));
+        let heartbeat = self
+            .watchdog
+            .as_ref()
+            .map(|watchdog| watchdog.register(self.name(), EXIT_LOOP_STALL_TIMEOUT));
+
+        loop {
+            tokio::select! {
+                _ = position_ticker.tick() => {
+                    if let Some(heartbeat) = &heartbeat {
+                        heartbeat.beat();
+                    }
+                    for (account_id, system) in self.systems.read().await.iter() {
+                        for pending in self.orchestrator.take_pending_exit_policies(account_id).await {
+                            system.expect_position_policy(pending);
+                        }
+                        system.run_position_checks().await;
+                    }
+                }
+                _ = schedule_ticker.tick() => {
+                    for system in self.systems.read().await.values() {
+                        system.run_schedule_checks().await;
+                    }
+                }
+                _ = shutdown.recv() => {
+                    if let Some(heartbeat) = &heartbeat {
+                        heartbeat.stop();
+                    }
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Re-reads every account's open positions on an interval and restores stops
+/// that did not stick
+pub struct StopGuardianSubsystem {
+    guardian: Arc<StopLossGuardian>,
+    systems: ExitSystems,
+}
+
+impl StopGuardianSubsystem {
+    pub fn new(guardian: Arc<StopLossGuardian>, systems: ExitSystems) -> Self {
+        Self { guardian, systems }
+    }
+
+    pub async fn check(&self) {
+        for (account_id, system) in self.systems.read().await.iter() {
+            match self.guardian.verify(account_id, system).await {
+                Ok(report) if !report.reapplied.is_empty() => warn!(
+                    "Re-applied {} stop-losses on account {}",
+                    report.reapplied.len(),
+                    account_id
+                ),
+                Ok(_) => {}
+                Err(e) => warn!(
+                    "Failed to verify stop-losses for account {}: {:#}",
+                    account_id, e
+                ),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for StopGuardianSubsystem {
+    fn name(&self) -> &str {
+        "stop-guardian"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_secs(
+            self.guardian.config().check_interval_secs.max(1),
+        ));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.check().await,
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Periodically adopts positions opened outside the engine into the exit
+/// management of their account
+pub struct AdoptionSubsystem {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    adopter: Arc<PositionAdopter>,
+    systems: ExitSystems,
+}
+
+impl AdoptionSubsystem {
+    pub fn new(
+        orchestrator: Arc<TradeExecutionOrchestrator>,
+        adopter: Arc<PositionAdopter>,
+        systems: ExitSystems,
+    ) -> Self {
+        Self {
+            orchestrator,
+            adopter,
+            systems,
+        }
+    }
+
+    pub async fn check(&self) {
+        let systems = self.systems.read().await.clone();
+        for (account_id, system) in systems {
+            let positions = match self
+                .orchestrator
+                .get_open_positions(Some(&account_id))
+                .await
+            {
+                Ok(positions) => positions,
+                Err(e) => {
+                    debug!("Skipping position adoption for {}: {}", account_id, e);
+                    continue;
+                }
+            };
+            let adopted = self
+                .adopter
+                .reconcile(&account_id, &positions, &system)
+                .await;
+            if !adopted.is_empty() {
+                warn!(
+                    "Adopted {} externally opened positions on account {}",
+                    adopted.len(),
+                    account_id
+                );
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for AdoptionSubsystem {
+    fn name(&self) -> &str {
+        "position-adoption"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_secs(
+            self.adopter.config().check_interval_secs.max(1),
+        ));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.check().await,
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Sends orders deferred by a closed market once it opens
+pub struct DeferredOrderSubsystem {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+}
+
+impl DeferredOrderSubsystem {
+    pub fn new(orchestrator: Arc<TradeExecutionOrchestrator>) -> Self {
+        Self { orchestrator }
+    }
+}
+
+#[async_trait]
+impl Subsystem for DeferredOrderSubsystem {
+    fn name(&self) -> &str {
+        "deferred-orders"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_secs(
+            self.orchestrator
+                .rejection_remediation()
+                .defer_check_interval_secs
+                .max(1),
+        ));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    self.orchestrator.release_deferred_orders().await;
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// How often entry ladders are synced with their platforms
+const LADDER_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Keeps entry ladders' exits and unfilled rungs in step with their fills
+pub struct LadderSubsystem {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+}
+
+impl LadderSubsystem {
+    pub fn new(orchestrator: Arc<TradeExecutionOrchestrator>) -> Self {
+        Self { orchestrator }
+    }
+}
+
+#[async_trait]
+impl Subsystem for LadderSubsystem {
+    fn name(&self) -> &str {
+        "entry-ladders"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(LADDER_POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.orchestrator.poll_ladders().await,
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Measures every account's drawdowns and moves it along the drawdown ladder
+pub struct DrawdownLadderSubsystem {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    interval: Duration,
+}
+
+impl DrawdownLadderSubsystem {
+    pub fn new(orchestrator: Arc<TradeExecutionOrchestrator>, interval: Duration) -> Self {
+        Self {
+            orchestrator,
+            interval,
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for DrawdownLadderSubsystem {
+    fn name(&self) -> &str {
+        "drawdown-ladder"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    self.orchestrator.assess_drawdowns().await;
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Feeds every platform's events into the position ledger and periodically
+/// reconciles the ledger against the positions the platforms report
+pub struct PositionLedgerSubsystem {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    ledger: Arc<PositionLedger>,
+    reconcile_interval: Duration,
+}
+
+impl PositionLedgerSubsystem {
+    pub fn new(
+        orchestrator: Arc<TradeExecutionOrchestrator>,
+        ledger: Arc<PositionLedger>,
+        reconcile_interval: Duration,
+    ) -> Self {
+        Self {
+            orchestrator,
+            ledger,
+            reconcile_interval,
+        }
+    }
+
+    async fn reconcile(&self) {
+        for (account_id, platform) in self.orchestrator.get_platforms().await {
+            let positions = match platform.get_positions().await {
+                Ok(positions) => positions,
+                Err(e) => {
+                    debug!("Skipping ledger reconciliation for {}: {}", account_id, e);
+                    continue;
+                }
+            };
+            if let Err(e) = self.ledger.reconcile(&account_id, &positions).await {
+                warn!("Ledger reconciliation failed for {}: {}", account_id, e);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for PositionLedgerSubsystem {
+    fn name(&self) -> &str {
+        "position-ledger"
+    }
+
+    async fn start(&self) -> Result<()> {
+        for (account_id, platform) in self.orchestrator.get_platforms().await {
+            let mut events = match platform.subscribe_events().await {
+                Ok(events) => events,
+                Err(e) => {
+                    warn!("No ledger events from {}: {}", account_id, e);
+                    continue;
+                }
+            };
+            let ledger = self.ledger.clone();
+            spawn_isolated(format!("position-ledger-{}", account_id), async move {
+                while let Some(mut event) = events.recv().await {
+                    // Keyed like the orchestrator so reconciliation compares the same account
+                    event.account_id = account_id.clone();
+                    ledger.handle_platform_event(&event).await;
+                }
+            });
+        }
+        // Catch up on anything that changed while the engine was down
+        self.reconcile().await;
+        Ok(())
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(self.reconcile_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        ticker.tick().await;
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.reconcile().await,
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Feeds the candle builder from one account's quote stream and closes candles
+/// for symbols that stop ticking
+pub struct CandleSubsystem {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    candles: Arc<CandleBuilder>,
+}
+
+impl CandleSubsystem {
+    pub fn new(orchestrator: Arc<TradeExecutionOrchestrator>, candles: Arc<CandleBuilder>) -> Self {
+        Self {
+            orchestrator,
+            candles,
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for CandleSubsystem {
+    fn name(&self) -> &str {
+        "candles"
+    }
+
+    async fn start(&self) -> Result<()> {
+        let config = self.candles.config();
+        if config.symbols.is_empty() {
+            info!("No candle symbols configured");
+            return Ok(());
+        }
+        // One feed only: several accounts quoting the same symbol would count ticks twice
+        let mut platforms = self.orchestrator.get_platforms().await;
+        platforms.sort_by(|a, b| a.0.cmp(&b.0));
+        let source = platforms.into_iter().find(|(account_id, _)| {
+            config
+                .source_account
+                .as_ref()
+                .map_or(true, |source| source == account_id)
+        });
+        let Some((account_id, platform)) = source else {
+            warn!("No account available to feed candles");
+            return Ok(());
+        };
+
+        let mut quotes = platform
+            .subscribe_market_data(config.symbols.clone())
+            .await?;
+        let candles = self.candles.clone();
+        spawn_isolated(format!("candle-feed-{}", account_id), async move {
+            while let Some(quote) = quotes.recv().await {
+                candles.on_quote(&quote);
+            }
+        });
+        info!(
+            "Building candles for {} symbols from account {}",
+            config.symbols.len(),
+            account_id
+        );
+        Ok(())
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_secs(
+            self.candles.config().close_check_interval_secs.max(1),
+        ));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    self.candles.close_elapsed(chrono::Utc::now());
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Keeps the calendar entry blackouts are judged on filled with upcoming releases
+pub struct NewsCalendarSubsystem {
+    client: EconomicCalendarClient,
+    calendar: NewsCalendar,
+    config: NewsBlackoutConfig,
+}
+
+impl NewsCalendarSubsystem {
+    pub fn new(
+        client: EconomicCalendarClient,
+        calendar: NewsCalendar,
+        config: NewsBlackoutConfig,
+    ) -> Self {
+        Self {
+            client,
+            calendar,
+            config,
+        }
+    }
+
+    async fn refresh(&self) {
+        let horizon = chrono::Duration::hours(self.config.horizon_hours as i64);
+        match self
+            .client
+            .get_upcoming_events(horizon, ImpactLevel::Low)
+            .await
+        {
+            Ok(events) => {
+                debug!("News calendar refreshed with {} releases", events.len());
+                self.calendar.set_events(events);
+            }
+            // The previous events stay in place until a refresh succeeds
+            Err(e) => warn!("Failed to refresh the news calendar: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for NewsCalendarSubsystem {
+    fn name(&self) -> &str {
+        "news-calendar"
+    }
+
+    async fn start(&self) -> Result<()> {
+        self.refresh().await;
+        Ok(())
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_secs(self.config.refresh_interval_secs));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        ticker.tick().await;
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.refresh().await,
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Feeds the cross rates from one account's quotes of the configured pairs
+pub struct CrossRateSubsystem {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    rates: CrossRates,
+}
+
+impl CrossRateSubsystem {
+    pub fn new(orchestrator: Arc<TradeExecutionOrchestrator>, rates: CrossRates) -> Self {
+        Self {
+            orchestrator,
+            rates,
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for CrossRateSubsystem {
+    fn name(&self) -> &str {
+        "cross-rates"
+    }
+
+    async fn start(&self) -> Result<()> {
+        let config = self.rates.config();
+        if config.symbols.is_empty() {
+            info!("No cross rate symbols configured");
+            return Ok(());
+        }
+        let mut platforms = self.orchestrator.get_platforms().await;
+        platforms.sort_by(|a, b| a.0.cmp(&b.0));
+        let source = platforms.into_iter().find(|(account_id, _)| {
+            config
+                .source_account
+                .as_ref()
+                .map_or(true, |source| source == account_id)
+        });
+        let Some((account_id, platform)) = source else {
+            warn!("No account available to feed cross rates");
+            return Ok(());
+        };
+
+        let mut quotes = platform
+            .subscribe_market_data(config.symbols.clone())
+            .await?;
+        let rates = self.rates.clone();
+        spawn_isolated(format!("cross-rate-feed-{}", account_id), async move {
+            while let Some(quote) = quotes.recv().await {
+                rates.on_quote(&quote);
+            }
+        });
+        info!(
+            "Taking cross rates from {} pairs of account {}",
+            config.symbols.len(),
+            account_id
+        );
+        Ok(())
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        shutdown.recv().await;
+        Ok(())
+    }
+}
+
+/// Records the quote stream and account balances so the day can be replayed;
+/// signals, news and exit audit entries are recorded where they happen
+pub struct RecordingSubsystem {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    recorder: Arc<EventRecorder>,
+    config: RecordingConfig,
+}
+
+impl RecordingSubsystem {
+    pub fn new(
+        orchestrator: Arc<TradeExecutionOrchestrator>,
+        recorder: Arc<EventRecorder>,
+        config: RecordingConfig,
+    ) -> Self {
+        Self {
+            orchestrator,
+            recorder,
+            config,
+        }
+    }
+
+    /// Balances open each day's recording, so a replay starts from them
+    async fn record_balances(&self) {
+        for (account_id, platform) in self.orchestrator.get_platforms().await {
+            match platform.get_account_info().await {
+                Ok(info) => {
+                    self.recorder
+                        .record(RecordedEvent::Account {
+                            account_id,
+                            balance: info.balance,
+                        })
+                        .await
+                }
+                Err(e) => warn!("Failed to record balance of account {}: {}", account_id, e),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for RecordingSubsystem {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    async fn start(&self) -> Result<()> {
+        self.record_balances().await;
+        if self.config.symbols.is_empty() {
+            info!("No recording symbols configured, quotes are not recorded");
+            return Ok(());
+        }
+        // One feed only, as for candles: a replay applies each tick to every account
+        let mut platforms = self.orchestrator.get_platforms().await;
+        platforms.sort_by(|a, b| a.0.cmp(&b.0));
+        let source = platforms.into_iter().find(|(account_id, _)| {
+            self.config
+                .source_account
+                .as_ref()
+                .map_or(true, |source| source == account_id)
+        });
+        let Some((account_id, platform)) = source else {
+            warn!("No account available to record quotes from");
+            return Ok(());
+        };
+
+        let mut quotes = platform
+            .subscribe_market_data(self.config.symbols.clone())
+            .await?;
+        let recorder = self.recorder.clone();
+        spawn_isolated(format!("recording-feed-{}", account_id), async move {
+            while let Some(quote) = quotes.recv().await {
+                recorder
+                    .record(RecordedEvent::Tick {
+                        symbol: quote.symbol,
+                        bid: quote.bid,
+                        ask: quote.ask,
+                    })
+                    .await;
+            }
+        });
+        info!(
+            "Recording {} symbols from account {} to {}",
+            self.config.symbols.len(),
+            account_id,
+            self.recorder.dir().display()
+        );
+        Ok(())
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_secs(60));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut day = chrono::Utc::now().date_naive();
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    let today = chrono::Utc::now().date_naive();
+                    if today != day {
+                        day = today;
+                        self.record_balances().await;
+                    }
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Real-time P&L monitoring driven by the market data stream
+pub struct RiskMonitorSubsystem {
+    pnl_calculator: Arc<RealTimePnLCalculator>,
+}
+
+impl RiskMonitorSubsystem {
+    pub fn new(pnl_calculator: Arc<RealTimePnLCalculator>) -> Self {
+        Self { pnl_calculator }
+    }
+}
+
+#[async_trait]
+impl Subsystem for RiskMonitorSubsystem {
+    fn name(&self) -> &str {
+        "risk-monitor"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        tokio::select! {
+            result = self.pnl_calculator.start_pnl_monitoring() => result,
+            _ = shutdown.recv() => Ok(()),
+        }
+    }
+}
+
+/// Captures and stores a risk snapshot of every account on a fixed interval
+pub struct RiskSnapshotSubsystem {
+    snapshotter: Arc<RiskSnapshotter>,
+}
+
+impl RiskSnapshotSubsystem {
+    pub fn new(snapshotter: Arc<RiskSnapshotter>) -> Self {
+        Self { snapshotter }
+    }
+}
+
+#[async_trait]
+impl Subsystem for RiskSnapshotSubsystem {
+    fn name(&self) -> &str {
+        "risk-snapshots"
+    }
+
+    async fn start(&self) -> Result<()> {
+        // Drawdowns carry on from the stored peaks rather than restarting at zero
+        if let Err(e) = self.snapshotter.load().await {
+            warn!("Failed to read stored risk snapshots: {}", e);
+        }
+        Ok(())
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_secs(
+            self.snapshotter.config().interval_mins * 60,
+        ));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if let Err(e) = self.snapshotter.record().await {
+                        warn!("Failed to store risk snapshot: {}", e);
+                    }
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Reconciles every account's platform statement with the ledger on an interval
+pub struct StatementSubsystem {
+    reconciler: Arc<StatementReconciler>,
+}
+
+impl StatementSubsystem {
+    pub fn new(reconciler: Arc<StatementReconciler>) -> Self {
+        Self { reconciler }
+    }
+}
+
+#[async_trait]
+impl Subsystem for StatementSubsystem {
+    fn name(&self) -> &str {
+        "statements"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_secs(
+            self.reconciler.config().interval_mins * 60,
+        ));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        // The first tick fires at once, after the ledger caught up in its start
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    self.reconciler.reconcile_all(chrono::Utc::now()).await;
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Pushes a `DashboardSnapshot` to every WebSocket client on a fixed interval
+pub struct DashboardStreamSubsystem {
+    bind_address: String,
+    aggregator: Arc<DashboardAggregator>,
+    publish_interval: Duration,
+    auth: Arc<Authenticator>,
+}
+
+impl DashboardStreamSubsystem {
+    pub fn new(
+        bind_address: String,
+        aggregator: Arc<DashboardAggregator>,
+        publish_interval: Duration,
+    ) -> Self {
+        Self {
+            bind_address,
+            aggregator,
+            publish_interval,
+            auth: Arc::new(Authenticator::disabled()),
+        }
+    }
+
+    /// Admit only clients whose handshake carries the API's credentials
+    pub fn with_authenticator(mut self, auth: Arc<Authenticator>) -> Self {
+        self.auth = auth;
+        self
+    }
+}
+
+async fn serialized_snapshot(aggregator: &DashboardAggregator) -> Option<Arc<String>> {
+    match serde_json::to_string(&aggregator.snapshot().await) {
+        Ok(json) => Some(Arc::new(json)),
+        Err(e) => {
+            warn!("Failed to serialize dashboard snapshot: {}", e);
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for DashboardStreamSubsystem {
+    fn name(&self) -> &str {
+        "dashboard-stream"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(&self.bind_address).await?;
+        info!("Dashboard stream listening on {}", self.bind_address);
+
+        // Lagging clients skip to the newest snapshot rather than replaying stale ones
+        let (tx, _) = broadcast::channel::<Arc<String>>(1);
+        let mut ticker = interval(self.publish_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (stream, peer) = accepted?;
+                    spawn_isolated(
+                        format!("dashboard-client:{}", peer),
+                        stream_to_client(
+                            stream,
+                            self.auth.clone(),
+                            self.aggregator.clone(),
+                            tx.subscribe(),
+                            shutdown.clone(),
+                        ),
+                    );
+                    debug!("Dashboard client connected from {}", peer);
+                }
+                _ = ticker.tick() => {
+                    if tx.receiver_count() > 0 {
+                        if let Some(snapshot) = serialized_snapshot(&self.aggregator).await {
+                            let _ = tx.send(snapshot);
+                        }
+                    }
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Admits a client whose handshake carries credentials covering every account,
+/// as the snapshots do
+struct ClientAuthorization<'a>(&'a Authenticator);
+
+impl ClientAuthorization<'_> {
+    fn authorize(&self, request: &HandshakeRequest) -> Result<(), AuthError> {
+        if !self.0.is_enabled() {
+            return Ok(());
+        }
+        let principal = authenticate_headers(self.0, request.headers())?;
+        if principal.is_scoped() {
+            return Err(AuthError::AllAccountsRequired);
+        }
+        Ok(())
+    }
+}
+
+impl Callback for ClientAuthorization<'_> {
+    fn on_request(
+        self,
+        request: &HandshakeRequest,
+        response: HandshakeResponse,
+    ) -> Result<HandshakeResponse, HandshakeErrorResponse> {
+        self.authorize(request).map(|_| response).map_err(|e| {
+            let status = if e.is_unauthenticated() {
+                StatusCode::UNAUTHORIZED
+            } else {
+                StatusCode::FORBIDDEN
+            };
+            let mut refusal = HandshakeErrorResponse::new(Some(e.to_string()));
+            *refusal.status_mut() = status;
+            refusal
+        })
+    }
+}
+
+async fn stream_to_client(
+    stream: TcpStream,
+    auth: Arc<Authenticator>,
+    aggregator: Arc<DashboardAggregator>,
+    mut snapshots: broadcast::Receiver<Arc<String>>,
+    mut shutdown: ShutdownSignal,
+) {
+    let handshake = ClientAuthorization(&auth);
+    let socket = match tokio_tungstenite::accept_hdr_async(stream, handshake).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            debug!("Dashboard WebSocket handshake failed: {}", e);
+            return;
+        }
+    };
+    let (mut sink, mut incoming) = socket.split();
+
+    // Send the current state straight away instead of waiting for the next tick
+    if let Some(snapshot) = serialized_snapshot(&aggregator).await {
+        if sink
+            .send(Message::Text(snapshot.to_string()))
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+
+    loop {
+        tokio::select! {
+            snapshot = snapshots.recv() => match snapshot {
+                Ok(snapshot) => {
+                    if sink.send(Message::Text(snapshot.to_string())).await.is_err() {
+                        return;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = incoming.next() => match message {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => return,
+                Some(Ok(_)) => {}
+            },
+            _ = shutdown.recv() => break,
+        }
+    }
+
+    let _ = sink.send(Message::Close(None)).await;
+}
+
+/// Holds the message bus for the lifetime of the engine
+#[cfg(not(feature = "kafka"))]
+pub struct MessagingSubsystem {
+    _bus: crate::messaging::stub::MessageBus,
+}
+
+#[cfg(not(feature = "kafka"))]
+impl MessagingSubsystem {
+    pub fn new() -> Self {
+        Self {
+            _bus: crate::messaging::stub::MessageBus::new(),
+        }
+    }
+}
+
+#[cfg(not(feature = "kafka"))]
+#[async_trait]
+impl Subsystem for MessagingSubsystem {
+    fn name(&self) -> &str {
+        "messaging"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        shutdown.recv().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,361 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
+use tracing::{error, info, warn};
+
+use super::spawn::panic_payload_message;
+use crate::platforms::abstraction::quota::with_caller;
+
+/// A long-running part of the engine managed by the `Supervisor`
+#[async_trait]
+pub trait Subsystem: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// One-off initialisation, awaited before the next subsystem is started
+    async fn start(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Main loop; should return promptly once `shutdown` fires
+    async fn run(&self, shutdown: ShutdownSignal) -> Result<()>;
+
+    /// Cleanup after `run` has returned for the last time
+    async fn stop(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Cloneable view of the supervisor's shutdown flag
+#[derive(Debug, Clone)]
+pub struct ShutdownSignal {
+    receiver: watch::Receiver<bool>,
+}
+
+impl ShutdownSignal {
+    pub fn is_shutdown(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    /// Resolves once shutdown has been requested
+    pub async fn recv(&mut self) {
+        while !*self.receiver.borrow_and_update() {
+            if self.receiver.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RestartPolicy {
+    /// Leave the subsystem stopped whatever the outcome
+    Never,
+    /// Restart after an error or panic, up to `max_restarts` times
+    OnFailure { max_restarts: u32, backoff_ms: u64 },
+    /// Restart whenever `run` returns, up to `max_restarts` times
+    Always { max_restarts: u32, backoff_ms: u64 },
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self::OnFailure {
+            max_restarts: 5,
+            backoff_ms: 1000,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Backoff before the next restart, or `None` if the subsystem should stay down
+    pub(crate) fn restart_delay(&self, failed: bool, restarts: u32) -> Option<Duration> {
+        match *self {
+            Self::Never => None,
+            Self::OnFailure {
+                max_restarts,
+                backoff_ms,
+            } if failed && restarts < max_restarts => Some(Duration::from_millis(
+                backoff_ms.saturating_mul(restarts as u64 + 1),
+            )),
+            Self::Always {
+                max_restarts,
+                backoff_ms,
+            } if restarts < max_restarts => Some(Duration::from_millis(
+                backoff_ms.saturating_mul(restarts as u64 + 1),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisorConfig {
+    /// Upper bound on the whole shutdown sequence
+    pub shutdown_timeout_secs: u64,
+    pub default_restart_policy: RestartPolicy,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            shutdown_timeout_secs: 30,
+            default_restart_policy: RestartPolicy::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SubsystemState {
+    Pending,
+    Running,
+    Restarting,
+    Completed,
+    Failed,
+    Stopped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubsystemStatus {
+    pub name: String,
+    pub state: SubsystemState,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SubsystemStatus {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            state: SubsystemState::Pending,
+            restarts: 0,
+            last_error: None,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+pub type SubsystemStatuses = Arc<RwLock<HashMap<String, SubsystemStatus>>>;
+
+struct SupervisedEntry {
+    subsystem: Arc<dyn Subsystem>,
+    policy: RestartPolicy,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Starts subsystems in registration order, isolates their panics, restarts them
+/// according to their policy and stops them in reverse order
+pub struct Supervisor {
+    config: SupervisorConfig,
+    entries: Vec<SupervisedEntry>,
+    statuses: SubsystemStatuses,
+    shutdown_tx: watch::Sender<bool>,
+}
+
+impl Supervisor {
+    pub fn new(config: SupervisorConfig) -> Self {
+        let (shutdown_tx, _) = watch::channel(false);
+        Self {
+            config,
+            entries: Vec::new(),
+            statuses: Arc::new(RwLock::new(HashMap::new())),
+            shutdown_tx,
+        }
+    }
+
+    /// Register a subsystem with the configured default restart policy
+    pub fn add(&mut self, subsystem: Arc<dyn Subsystem>) {
+        let policy = self.config.default_restart_policy;
+        self.add_with_policy(subsystem, policy);
+    }
+
+    pub fn add_with_policy(&mut self, subsystem: Arc<dyn Subsystem>, policy: RestartPolicy) {
+        self.entries.push(SupervisedEntry {
+            subsystem,
+            policy,
+            handle: None,
+        });
+    }
+
+    pub fn statuses(&self) -> SubsystemStatuses {
+        self.statuses.clone()
+    }
+
+    pub fn shutdown_signal(&self) -> ShutdownSignal {
+        ShutdownSignal {
+            receiver: self.shutdown_tx.subscribe(),
+        }
+    }
+
+    /// Start every subsystem in order. If one fails to start, those already running
+    /// are shut down again and the error is returned.
+    pub async fn start(&mut self) -> Result<()> {
+        for index in 0..self.entries.len() {
+            let subsystem = self.entries[index].subsystem.clone();
+            let name = subsystem.name().to_string();
+            self.statuses
+                .write()
+                .await
+                .insert(name.clone(), SubsystemStatus::new(&name));
+
+            if let Err(e) = with_caller(name.clone(), subsystem.start()).await {
+                error!("Subsystem {} failed to start: {}", name, e);
+                self.set_state(&name, SubsystemState::Failed, Some(e.to_string()))
+                    .await;
+                self.shutdown().await;
+                return Err(anyhow!("Subsystem {} failed to start: {}", name, e));
+            }
+
+            let handle = tokio::spawn(supervise(
+                subsystem,
+                self.entries[index].policy,
+                self.shutdown_signal(),
+                self.statuses.clone(),
+            ));
+            self.entries[index].handle = Some(handle);
+            info!("Started subsystem {}", name);
+        }
+
+        Ok(())
+    }
+
+    /// Signal shutdown and stop subsystems in reverse start order within the
+    /// configured deadline
+    pub async fn shutdown(&mut self) {
+        let _ = self.shutdown_tx.send(true);
+        let deadline =
+            tokio::time::Instant::now() + Duration::from_secs(self.config.shutdown_timeout_secs);
+
+        for entry in self.entries.iter_mut().rev() {
+            let Some(handle) = entry.handle.take() else {
+                continue;
+            };
+            let name = entry.subsystem.name().to_string();
+
+            if timeout_at(deadline, handle).await.is_none() {
+                warn!(
+                    "Subsystem {} did not stop before the shutdown deadline",
+                    name
+                );
+                continue;
+            }
+
+            match timeout_at(deadline, entry.subsystem.stop()).await {
+                Some(Ok(())) => info!("Stopped subsystem {}", name),
+                Some(Err(e)) => error!("Subsystem {} failed to stop cleanly: {}", name, e),
+                None => warn!("Subsystem {} cleanup timed out", name),
+            }
+        }
+    }
+
+    async fn set_state(&self, name: &str, state: SubsystemState, error: Option<String>) {
+        update_status(&self.statuses, name, state, error).await;
+    }
+}
+
+async fn timeout_at<F: std::future::Future>(
+    deadline: tokio::time::Instant,
+    future: F,
+) -> Option<F::Output> {
+    timeout(
+        deadline.saturating_duration_since(tokio::time::Instant::now()),
+        future,
+    )
+    .await
+    .ok()
+}
+
+async fn update_status(
+    statuses: &SubsystemStatuses,
+    name: &str,
+    state: SubsystemState,
+    error: Option<String>,
+) {
+    if let Some(status) = statuses.write().await.get_mut(name) {
+        status.state = state;
+        status.updated_at = Utc::now();
+        if error.is_some() {
+            status.last_error = error;
+        }
+    }
+}
+
+/// Run a subsystem in its own task so that a panic surfaces as a `JoinError`
+/// instead of taking the process down, restarting it as the policy allows
+async fn supervise(
+    subsystem: Arc<dyn Subsystem>,
+    policy: RestartPolicy,
+    mut shutdown: ShutdownSignal,
+    statuses: SubsystemStatuses,
+) {
+    let name = subsystem.name().to_string();
+    let mut restarts = 0;
+
+    loop {
+        update_status(&statuses, &name, SubsystemState::Running, None).await;
+
+        let task = {
+            let subsystem = subsystem.clone();
+            let shutdown = shutdown.clone();
+            // Platform calls made by the subsystem count against its quota budget
+            tokio::spawn(with_caller(name.clone(), async move {
+                subsystem.run(shutdown).await
+            }))
+        };
+
+        let failure = match task.await {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(e) if e.is_panic() => Some(format!("panicked: {}", panic_message(e))),
+            Err(e) => Some(e.to_string()),
+        };
+
+        if shutdown.is_shutdown() {
+            update_status(&statuses, &name, SubsystemState::Stopped, failure).await;
+            return;
+        }
+
+        let failed = failure.is_some();
+        if let Some(reason) = &failure {
+            error!("Subsystem {} failed: {}", name, reason);
+        }
+
+        let Some(delay) = policy.restart_delay(failed, restarts) else {
+            let state = if failed {
+                SubsystemState::Failed
+            } else {
+                SubsystemState::Completed
+            };
+            update_status(&statuses, &name, state, failure).await;
+            return;
+        };
+
+        restarts += 1;
+        update_status(&statuses, &name, SubsystemState::Restarting, failure).await;
+        if let Some(status) = statuses.write().await.get_mut(&name) {
+            status.restarts = restarts;
+        }
+        warn!(
+            "Restarting subsystem {} in {:?} (restart {})",
+            name, delay, restarts
+        );
+
+        tokio::select! {
+            _ = sleep(delay) => {}
+            _ = shutdown.recv() => {
+                update_status(&statuses, &name, SubsystemState::Stopped, None).await;
+                return;
+            }
+        }
+    }
+}
+
+fn panic_message(error: tokio::task::JoinError) -> String {
+    panic_payload_message(error.into_panic().as_ref())
+}
@@ -0,0 +1,312 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{interval, Instant, MissedTickBehavior};
+use tracing::{error, info, warn};
+
+use super::health::{DependencyHealth, DependencyKind, HealthLevel, HealthProbe};
+use super::supervisor::{ShutdownSignal, Subsystem};
+
+/// Number of alerts kept for inspection
+const MAX_RECENT_ALERTS: usize = 100;
+
+/// Restarts a stalled task; receives the heartbeat the new instance should keep beating
+pub type RestartFn = Arc<dyn Fn(Heartbeat) + Send + Sync>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchdogConfig {
+    pub check_interval_ms: u64,
+    /// Restarts attempted per registration before the task is left stalled
+    pub max_restarts: u32,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_ms: 1000,
+            max_restarts: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WatchdogAction {
+    /// The task has no restart function; only the alert is raised
+    AlertOnly,
+    Restarted,
+    RestartsExhausted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchdogAlert {
+    pub task: String,
+    pub silent_for_ms: u64,
+    pub action: WatchdogAction,
+    pub raised_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchedTaskStatus {
+    pub name: String,
+    pub silent_for_ms: u64,
+    pub timeout_ms: u64,
+    pub stalled: bool,
+    pub restarts: u32,
+}
+
+struct WatchedTask {
+    id: u64,
+    last_beat: Arc<Mutex<Instant>>,
+    timeout: Duration,
+    restart: Option<RestartFn>,
+    restarts: u32,
+    /// Set once the stall has been alerted and nothing more will be done about it
+    stalled: bool,
+}
+
+impl WatchedTask {
+    fn silent_for(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(*self.last_beat.lock().unwrap())
+    }
+}
+
+/// Handle a watched task uses to report that it is still making progress
+#[derive(Clone)]
+pub struct Heartbeat {
+    name: String,
+    id: u64,
+    last_beat: Arc<Mutex<Instant>>,
+    tasks: Arc<DashMap<String, WatchedTask>>,
+}
+
+impl Heartbeat {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn beat(&self) {
+        *self.last_beat.lock().unwrap() = Instant::now();
+    }
+
+    /// Stop watching the task, e.g. when it finishes on purpose
+    pub fn stop(&self) {
+        self.tasks
+            .remove_if(&self.name, |_, task| task.id == self.id);
+    }
+}
+
+/// Detects long-running tasks that stopped beating their heartbeat, raises an alert
+/// and restarts them if they registered a way to do so
+pub struct Watchdog {
+    config: WatchdogConfig,
+    tasks: Arc<DashMap<String, WatchedTask>>,
+    next_id: AtomicU64,
+    alerts: Mutex<VecDeque<WatchdogAlert>>,
+}
+
+impl Watchdog {
+    pub fn new(config: WatchdogConfig) -> Self {
+        Self {
+            config,
+            tasks: Arc::new(DashMap::new()),
+            next_id: AtomicU64::new(0),
+            alerts: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Watch `name`, alerting if it goes `timeout` without a heartbeat. Registering
+    /// an existing name replaces the previous registration.
+    pub fn register(&self, name: &str, timeout: Duration) -> Heartbeat {
+        self.insert(name, timeout, None)
+    }
+
+    /// Like `register`, but a stalled task is restarted with `restart`
+    pub fn register_with_restart(
+        &self,
+        name: &str,
+        timeout: Duration,
+        restart: RestartFn,
+    ) -> Heartbeat {
+        self.insert(name, timeout, Some(restart))
+    }
+
+    fn insert(&self, name: &str, timeout: Duration, restart: Option<RestartFn>) -> Heartbeat {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let last_beat = Arc::new(Mutex::new(Instant::now()));
+        self.tasks.insert(
+            name.to_string(),
+            WatchedTask {
+                id,
+                last_beat: last_beat.clone(),
+                timeout,
+                restart,
+                restarts: 0,
+                stalled: false,
+            },
+        );
+        Heartbeat {
+            name: name.to_string(),
+            id,
+            last_beat,
+            tasks: self.tasks.clone(),
+        }
+    }
+
+    pub fn statuses(&self) -> Vec<WatchedTaskStatus> {
+        let now = Instant::now();
+        let mut statuses: Vec<WatchedTaskStatus> = self
+            .tasks
+            .iter()
+            .map(|entry| {
+                let silent_for = entry.silent_for(now);
+                WatchedTaskStatus {
+                    name: entry.key().clone(),
+                    silent_for_ms: silent_for.as_millis() as u64,
+                    timeout_ms: entry.timeout.as_millis() as u64,
+                    stalled: silent_for > entry.timeout,
+                    restarts: entry.restarts,
+                }
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        statuses
+    }
+
+    pub fn recent_alerts(&self) -> Vec<WatchdogAlert> {
+        self.alerts.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Check every task once, returning the alerts raised
+    pub fn check(&self) -> Vec<WatchdogAlert> {
+        let now = Instant::now();
+        let mut alerts = Vec::new();
+        let mut restarts = Vec::new();
+
+        for mut entry in self.tasks.iter_mut() {
+            let name = entry.key().clone();
+            let task = entry.value_mut();
+            let silent_for = task.silent_for(now);
+
+            if silent_for <= task.timeout {
+                if task.stalled {
+                    info!("Task {} is beating again", name);
+                    task.stalled = false;
+                }
+                continue;
+            }
+            if task.stalled {
+                continue;
+            }
+
+            let action = match &task.restart {
+                Some(restart) if task.restarts < self.config.max_restarts => {
+                    task.restarts += 1;
+                    // Give the new instance a full timeout before judging it
+                    *task.last_beat.lock().unwrap() = now;
+                    restarts.push((
+                        restart.clone(),
+                        Heartbeat {
+                            name: name.clone(),
+                            id: task.id,
+                            last_beat: task.last_beat.clone(),
+                            tasks: self.tasks.clone(),
+                        },
+                    ));
+                    WatchdogAction::Restarted
+                }
+                Some(_) => {
+                    task.stalled = true;
+                    WatchdogAction::RestartsExhausted
+                }
+                None => {
+                    task.stalled = true;
+                    WatchdogAction::AlertOnly
+                }
+            };
+
+            error!(
+                "Task {} missed its heartbeat for {:?} (timeout {:?}): {:?}",
+                name, silent_for, task.timeout, action
+            );
+            alerts.push(WatchdogAlert {
+                task: name,
+                silent_for_ms: silent_for.as_millis() as u64,
+                action,
+                raised_at: Utc::now(),
+            });
+        }
+
+        // Restart outside the map so restart functions may use the watchdog
+        for (restart, heartbeat) in restarts {
+            warn!("Restarting stalled task {}", heartbeat.name());
+            restart(heartbeat);
+        }
+
+        if !alerts.is_empty() {
+            let mut recent = self.alerts.lock().unwrap();
+            recent.extend(alerts.iter().cloned());
+            while recent.len() > MAX_RECENT_ALERTS {
+                recent.pop_front();
+            }
+        }
+        alerts
+    }
+}
+
+#[async_trait]
+impl Subsystem for Watchdog {
+    fn name(&self) -> &str {
+        "watchdog"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(Duration::from_millis(self.config.check_interval_ms));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    self.check();
+                }
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// A task past its timeout is unhealthy and takes the engine out of service
+#[async_trait]
+impl HealthProbe for Watchdog {
+    fn name(&self) -> &str {
+        "watchdog"
+    }
+
+    fn kind(&self) -> DependencyKind {
+        DependencyKind::Subsystem
+    }
+
+    async fn check(&self) -> Vec<DependencyHealth> {
+        self.statuses()
+            .into_iter()
+            .map(|status| {
+                let level = if status.stalled {
+                    HealthLevel::Unhealthy
+                } else {
+                    HealthLevel::Healthy
+                };
+                DependencyHealth::new(&status.name, DependencyKind::Subsystem, level).with_detail(
+                    format!(
+                        "last heartbeat {}ms ago, {} restarts",
+                        status.silent_for_ms, status.restarts
+                    ),
+                )
+            })
+            .collect()
+    }
+}
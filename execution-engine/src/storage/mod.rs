@@ -0,0 +1,214 @@
+// Persistence profiles: one `Storage` hands out the stores for orchestrator
+// state, the execution audit trail, the trade journal and exit manager
+// checkpoints, so a deployment picks its backend once in config
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStorage;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::execution::exit_management::{ExitStateStore, FileExitStateStore};
+use crate::execution::{connect_history_store, ExecutionHistoryStore, OrchestratorSnapshot};
+use crate::journal::{FileJournalStore, JournalStore};
+use crate::runtime::config::EngineConfig;
+
+/// Where the engine keeps what must survive a restart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageProfile {
+    /// JSON files at the paths of each component's config, with execution
+    /// history in Postgres when enabled
+    #[default]
+    Files,
+    /// Everything in one embedded SQLite database, for single-box deployments.
+    /// Needs the `sqlite` feature.
+    Sqlite,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub profile: StorageProfile,
+    /// Database file of the `sqlite` profile
+    pub sqlite_path: String,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            profile: StorageProfile::Files,
+            sqlite_path: "data/tmt.db".to_string(),
+        }
+    }
+}
+
+/// Durable copy of the orchestrator's state, written at shutdown
+#[async_trait]
+pub trait OrchestratorStateStore: Send + Sync + std::fmt::Debug {
+    async fn save(&self, snapshot: &OrchestratorSnapshot) -> Result<()>;
+    /// The last snapshot saved, if any
+    async fn load(&self) -> Result<Option<OrchestratorSnapshot>>;
+}
+
+/// A persistence backend for every component that keeps state
+pub trait Storage: Send + Sync + std::fmt::Debug {
+    fn profile(&self) -> StorageProfile;
+    fn orchestrator_state(&self) -> Arc<dyn OrchestratorStateStore>;
+    /// `None` when audit entries are kept in memory only
+    fn execution_history(&self) -> Option<Arc<dyn ExecutionHistoryStore>>;
+    fn journal(&self) -> Arc<dyn JournalStore>;
+    /// Exit manager checkpoints of one account; `None` when they are not kept
+    fn exit_state(&self, account_id: &str) -> Option<Arc<dyn ExitStateStore>>;
+}
+
+/// Snapshot in a JSON file, replaced atomically on each save
+#[derive(Debug)]
+pub struct FileOrchestratorStateStore {
+    path: PathBuf,
+}
+
+impl FileOrchestratorStateStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl OrchestratorStateStore for FileOrchestratorStateStore {
+    async fn save(&self, snapshot: &OrchestratorSnapshot) -> Result<()> {
+        let content = serde_json::to_string_pretty(snapshot)
+            .context("Failed to serialize orchestrator state")?;
+        if let Some(parent) = self.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+        }
+
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        tokio::fs::write(&tmp_path, content)
+            .await
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        tokio::fs::rename(&tmp_path, &self.path)
+            .await
+            .with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Option<OrchestratorSnapshot>> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let snapshot = serde_json::from_str(&content)
+            .with_context(|| format!("Corrupt orchestrator state {}", self.path.display()))?;
+        Ok(Some(snapshot))
+    }
+}
+
+/// The `files` profile
+#[derive(Debug)]
+pub struct FileStorage {
+    orchestrator_state_path: PathBuf,
+    journal_path: PathBuf,
+    exit_state_dir: Option<PathBuf>,
+    execution_history: Option<Arc<dyn ExecutionHistoryStore>>,
+}
+
+impl FileStorage {
+    pub fn new(
+        orchestrator_state_path: impl Into<PathBuf>,
+        journal_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            orchestrator_state_path: orchestrator_state_path.into(),
+            journal_path: journal_path.into(),
+            exit_state_dir: None,
+            execution_history: None,
+        }
+    }
+
+    /// Keep exit checkpoints in `<dir>/<account_id>.json`
+    pub fn with_exit_state_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.exit_state_dir = Some(dir.into());
+        self
+    }
+
+    pub fn with_execution_history(mut self, store: Arc<dyn ExecutionHistoryStore>) -> Self {
+        self.execution_history = Some(store);
+        self
+    }
+}
+
+impl Storage for FileStorage {
+    fn profile(&self) -> StorageProfile {
+        StorageProfile::Files
+    }
+
+    fn orchestrator_state(&self) -> Arc<dyn OrchestratorStateStore> {
+        Arc::new(FileOrchestratorStateStore::new(
+            &self.orchestrator_state_path,
+        ))
+    }
+
+    fn execution_history(&self) -> Option<Arc<dyn ExecutionHistoryStore>> {
+        self.execution_history.clone()
+    }
+
+    fn journal(&self) -> Arc<dyn JournalStore> {
+        Arc::new(FileJournalStore::new(&self.journal_path))
+    }
+
+    fn exit_state(&self, account_id: &str) -> Option<Arc<dyn ExitStateStore>> {
+        self.exit_state_dir.as_ref().map(|dir| {
+            Arc::new(FileExitStateStore::new(
+                dir.join(format!("{}.json", account_id)),
+            )) as Arc<dyn ExitStateStore>
+        })
+    }
+}
+
+/// The storage `config` selects. A history database that cannot be reached
+/// leaves the history in memory rather than stopping the engine.
+pub async fn open_storage(config: &EngineConfig) -> Result<Arc<dyn Storage>> {
+    match config.storage.profile {
+        StorageProfile::Files => {
+            let mut storage = FileStorage::new(&config.shutdown.state_path, &config.journal.path);
+            if let Some(dir) = &config.exit_management.state_dir {
+                storage = storage.with_exit_state_dir(dir);
+            }
+            if config.execution_history.enabled {
+                match connect_history_store(&config.execution_history).await {
+                    Ok(store) => storage = storage.with_execution_history(store),
+                    Err(e) => warn!("Execution history kept in memory only: {:#}", e),
+                }
+            }
+            Ok(Arc::new(storage))
+        }
+        StorageProfile::Sqlite => {
+            #[cfg(feature = "sqlite")]
+            {
+                let storage = SqliteStorage::open(&config.storage.sqlite_path).await?;
+                info!("Storing engine state in {}", config.storage.sqlite_path);
+                Ok(Arc::new(storage))
+            }
+            #[cfg(not(feature = "sqlite"))]
+            {
+                anyhow::bail!(
+                    "The sqlite storage profile needs the engine built with the sqlite feature"
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,446 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{OrchestratorStateStore, Storage, StorageProfile};
+use crate::execution::exit_management::{ExitStateStore, PositionExitCheckpoint, PositionId};
+use crate::execution::{
+    ExecutionAuditEntry, ExecutionHistoryPage, ExecutionHistoryQuery, ExecutionHistoryStore,
+    OrchestratorSnapshot, RetentionPolicy,
+};
+use crate::journal::{JournalStore, TradeRecord};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS orchestrator_state (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    saved_at INTEGER NOT NULL,
+    snapshot TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS execution_history (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    id TEXT NOT NULL UNIQUE,
+    recorded_at INTEGER NOT NULL,
+    signal_id TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    tags TEXT NOT NULL DEFAULT '[]',
+    entry TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS execution_history_signal_idx
+    ON execution_history (signal_id, recorded_at);
+CREATE INDEX IF NOT EXISTS execution_history_account_idx
+    ON execution_history (account_id, recorded_at);
+CREATE INDEX IF NOT EXISTS execution_history_recorded_at_idx
+    ON execution_history (recorded_at);
+CREATE TABLE IF NOT EXISTS trade_journal (
+    id TEXT PRIMARY KEY,
+    record TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS exit_state (
+    account_id TEXT NOT NULL,
+    position_id TEXT NOT NULL,
+    checkpoint TEXT NOT NULL,
+    PRIMARY KEY (account_id, position_id)
+);
+";
+
+/// Microseconds since the epoch, as times are stored
+fn micros(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_micros() as i64)
+        .unwrap_or(0)
+}
+
+/// One connection shared by every store, used off the async runtime
+#[derive(Debug, Clone)]
+struct Database {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl Database {
+    async fn call<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+    {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut connection = connection
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            f(&mut connection)
+        })
+        .await
+        .context("SQLite task failed")?
+    }
+}
+
+/// The `sqlite` profile: every store in one database file, created with its
+/// tables on open. Records are kept whole as JSON next to the columns queries
+/// filter on, as in the Postgres execution history.
+#[derive(Debug, Clone)]
+pub struct SqliteStorage {
+    database: Database,
+}
+
+impl SqliteStorage {
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+        }
+        let connection = tokio::task::spawn_blocking(move || -> Result<Connection> {
+            let connection = Connection::open(&path)
+                .with_context(|| format!("Failed to open {}", path.display()))?;
+            // Readers do not block the writer, and a crash loses no committed write
+            connection.pragma_update(None, "journal_mode", "WAL")?;
+            connection.pragma_update(None, "synchronous", "NORMAL")?;
+            connection
+                .execute_batch(SCHEMA)
+                .context("Failed to create the storage tables")?;
+            Ok(connection)
+        })
+        .await??;
+        Ok(Self {
+            database: Database {
+                connection: Arc::new(Mutex::new(connection)),
+            },
+        })
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn profile(&self) -> StorageProfile {
+        StorageProfile::Sqlite
+    }
+
+    fn orchestrator_state(&self) -> Arc<dyn OrchestratorStateStore> {
+        Arc::new(SqliteOrchestratorStateStore {
+            database: self.database.clone(),
+        })
+    }
+
+    fn execution_history(&self) -> Option<Arc<dyn ExecutionHistoryStore>> {
+        Some(Arc::new(SqliteExecutionHistoryStore {
+            database: self.database.clone(),
+        }))
+    }
+
+    fn journal(&self) -> Arc<dyn JournalStore> {
+        Arc::new(SqliteJournalStore {
+            database: self.database.clone(),
+        })
+    }
+
+    fn exit_state(&self, account_id: &str) -> Option<Arc<dyn ExitStateStore>> {
+        Some(Arc::new(SqliteExitStateStore {
+            database: self.database.clone(),
+            account_id: account_id.to_string(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct SqliteOrchestratorStateStore {
+    database: Database,
+}
+
+#[async_trait]
+impl OrchestratorStateStore for SqliteOrchestratorStateStore {
+    async fn save(&self, snapshot: &OrchestratorSnapshot) -> Result<()> {
+        let saved_at = micros(snapshot.saved_at);
+        let snapshot = serde_json::to_string(snapshot)?;
+        self.database
+            .call(move |connection| {
+                connection
+                    .execute(
+                        "INSERT INTO orchestrator_state (id, saved_at, snapshot) VALUES (1, ?1, ?2)
+                         ON CONFLICT (id) DO UPDATE
+                         SET saved_at = excluded.saved_at, snapshot = excluded.snapshot",
+                        params![saved_at, snapshot],
+                    )
+                    .context("Failed to store orchestrator state")?;
+                Ok(())
+            })
+            .await
+    }
+
+    async fn load(&self) -> Result<Option<OrchestratorSnapshot>> {
+        let snapshot: Option<String> = self
+            .database
+            .call(|connection| {
+                Ok(connection
+                    .query_row(
+                        "SELECT snapshot FROM orchestrator_state WHERE id = 1",
+                        [],
+                        |row| row.get(0),
+                    )
+                    .optional()?)
+            })
+            .await?;
+        snapshot
+            .map(|snapshot| {
+                serde_json::from_str(&snapshot).context("Unreadable orchestrator state")
+            })
+            .transpose()
+    }
+}
+
+#[derive(Debug)]
+struct SqliteExecutionHistoryStore {
+    database: Database,
+}
+
+/// `WHERE` clause and its parameters for the filters of `query`
+fn filters(query: &ExecutionHistoryQuery) -> (String, Vec<Value>) {
+    let mut clauses = Vec::new();
+    let mut params = Vec::new();
+    let mut add = |clause: &str, param: Value| {
+        params.push(param);
+        clauses.push(clause.replace('?', &format!("?{}", params.len())));
+    };
+
+    if let Some(signal_id) = &query.signal_id {
+        add("signal_id = ?", Value::Text(signal_id.clone()));
+    }
+    if let Some(account_id) = &query.account_id {
+        add("account_id = ?", Value::Text(account_id.clone()));
+    }
+    if let Some(action) = &query.action {
+        add("action = ?", Value::Text(action.clone()));
+    }
+    if let Some(since) = query.since {
+        add("recorded_at >= ?", Value::Integer(micros(since)));
+    }
+    if let Some(until) = query.until {
+        add("recorded_at < ?", Value::Integer(micros(until)));
+    }
+    for tag in &query.tags {
+        add(
+            "EXISTS (SELECT 1 FROM json_each(tags) WHERE value = ?)",
+            Value::Text(tag.clone()),
+        );
+    }
+
+    if clauses.is_empty() {
+        (String::new(), params)
+    } else {
+        (format!(" WHERE {}", clauses.join(" AND ")), params)
+    }
+}
+
+fn cutoff(now: SystemTime, days: u32) -> i64 {
+    micros(now - Duration::from_secs(u64::from(days) * 86_400))
+}
+
+#[async_trait]
+impl ExecutionHistoryStore for SqliteExecutionHistoryStore {
+    async fn append(&self, entry: &ExecutionAuditEntry) -> Result<()> {
+        let id = entry.id.clone();
+        let row = (
+            entry.id.clone(),
+            micros(entry.timestamp),
+            entry.signal_id.clone(),
+            entry.account_id.clone(),
+            entry.action.clone(),
+            serde_json::to_string(&entry.tags)?,
+            serde_json::to_string(entry)?,
+        );
+        self.database
+            .call(move |connection| {
+                connection
+                    .execute(
+                        "INSERT INTO execution_history
+                            (id, recorded_at, signal_id, account_id, action, tags, entry)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                         ON CONFLICT (id) DO NOTHING",
+                        params![row.0, row.1, row.2, row.3, row.4, row.5, row.6],
+                    )
+                    .with_context(|| format!("Failed to store execution history entry {}", id))?;
+                Ok(())
+            })
+            .await
+    }
+
+    async fn query(&self, query: &ExecutionHistoryQuery) -> Result<ExecutionHistoryPage> {
+        let (clause, mut params) = filters(query);
+        let (limit, offset) = (query.limit as i64, query.offset as i64);
+        let (total, rows) = self
+            .database
+            .call(move |connection| {
+                let total: i64 = connection.query_row(
+                    &format!("SELECT COUNT(*) FROM execution_history{}", clause),
+                    params_from_iter(params.iter()),
+                    |row| row.get(0),
+                )?;
+                params.push(Value::Integer(limit));
+                params.push(Value::Integer(offset));
+                let mut statement = connection.prepare(&format!(
+                    "SELECT entry FROM execution_history{} \
+                     ORDER BY recorded_at DESC, seq DESC LIMIT ?{} OFFSET ?{}",
+                    clause,
+                    params.len() - 1,
+                    params.len()
+                ))?;
+                let rows = statement
+                    .query_map(params_from_iter(params.iter()), |row| row.get(0))?
+                    .collect::<rusqlite::Result<Vec<String>>>()?;
+                Ok((total, rows))
+            })
+            .await?;
+
+        let mut entries = rows
+            .iter()
+            .map(|row| {
+                serde_json::from_str::<ExecutionAuditEntry>(row)
+                    .context("Unreadable execution history entry")
+            })
+            .collect::<Result<Vec<_>>>()?;
+        entries.reverse();
+        Ok(ExecutionHistoryPage::new(entries, total as usize, query))
+    }
+
+    async fn purge(&self, policy: &RetentionPolicy, now: SystemTime) -> Result<u64> {
+        let policy = policy.clone();
+        self.database
+            .call(move |connection| {
+                let transaction = connection.transaction()?;
+                let mut purged = 0;
+                for (action, days) in &policy.actions {
+                    if *days == 0 {
+                        continue;
+                    }
+                    purged += transaction.execute(
+                        "DELETE FROM execution_history WHERE action = ?1 AND recorded_at < ?2",
+                        params![action, cutoff(now, *days)],
+                    )?;
+                }
+                if policy.default_days > 0 {
+                    let overridden: Vec<&String> = policy.actions.keys().collect();
+                    purged += transaction.execute(
+                        "DELETE FROM execution_history
+                         WHERE action NOT IN (SELECT value FROM json_each(?1))
+                         AND recorded_at < ?2",
+                        params![
+                            serde_json::to_string(&overridden)?,
+                            cutoff(now, policy.default_days)
+                        ],
+                    )?;
+                }
+                transaction.commit()?;
+                Ok(purged as u64)
+            })
+            .await
+    }
+}
+
+#[derive(Debug)]
+struct SqliteJournalStore {
+    database: Database,
+}
+
+#[async_trait]
+impl JournalStore for SqliteJournalStore {
+    async fn save(&self, record: &TradeRecord) -> Result<()> {
+        let id = record.id.clone();
+        let json = serde_json::to_string(record)?;
+        self.database
+            .call(move |connection| {
+                // Updated in place, so records keep the order they were first saved in
+                connection
+                    .execute(
+                        "INSERT INTO trade_journal (id, record) VALUES (?1, ?2)
+                         ON CONFLICT (id) DO UPDATE SET record = excluded.record",
+                        params![id, json],
+                    )
+                    .with_context(|| format!("Failed to store trade record {}", id))?;
+                Ok(())
+            })
+            .await
+    }
+
+    async fn load(&self) -> Result<Vec<TradeRecord>> {
+        let rows = self
+            .database
+            .call(|connection| {
+                let mut statement =
+                    connection.prepare("SELECT record FROM trade_journal ORDER BY rowid")?;
+                let rows = statement
+                    .query_map([], |row| row.get(0))?
+                    .collect::<rusqlite::Result<Vec<String>>>()?;
+                Ok(rows)
+            })
+            .await?;
+        rows.iter()
+            .map(|row| serde_json::from_str(row).context("Unreadable trade record"))
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+struct SqliteExitStateStore {
+    database: Database,
+    account_id: String,
+}
+
+#[async_trait]
+impl ExitStateStore for SqliteExitStateStore {
+    async fn save(&self, checkpoint: &PositionExitCheckpoint) -> Result<()> {
+        let account_id = self.account_id.clone();
+        let position_id = checkpoint.position_id.to_string();
+        let json = serde_json::to_string(checkpoint)?;
+        self.database
+            .call(move |connection| {
+                connection
+                    .execute(
+                        "INSERT INTO exit_state (account_id, position_id, checkpoint)
+                         VALUES (?1, ?2, ?3)
+                         ON CONFLICT (account_id, position_id)
+                         DO UPDATE SET checkpoint = excluded.checkpoint",
+                        params![account_id, position_id, json],
+                    )
+                    .context("Failed to store exit state")?;
+                Ok(())
+            })
+            .await
+    }
+
+    async fn remove(&self, position_id: PositionId) -> Result<()> {
+        let account_id = self.account_id.clone();
+        self.database
+            .call(move |connection| {
+                connection.execute(
+                    "DELETE FROM exit_state WHERE account_id = ?1 AND position_id = ?2",
+                    params![account_id, position_id.to_string()],
+                )?;
+                Ok(())
+            })
+            .await
+    }
+
+    async fn load_all(&self) -> Result<Vec<PositionExitCheckpoint>> {
+        let account_id = self.account_id.clone();
+        let rows = self
+            .database
+            .call(move |connection| {
+                let mut statement = connection
+                    .prepare("SELECT checkpoint FROM exit_state WHERE account_id = ?1")?;
+                let rows = statement
+                    .query_map(params![account_id], |row| row.get(0))?
+                    .collect::<rusqlite::Result<Vec<String>>>()?;
+                Ok(rows)
+            })
+            .await?;
+        rows.iter()
+            .map(|row| serde_json::from_str(row).context("Unreadable exit state"))
+            .collect()
+    }
+}
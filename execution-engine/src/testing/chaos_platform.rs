@@ -0,0 +1,1208 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::platforms::abstraction::{
+    capabilities::PlatformCapabilities,
+    errors::PlatformError,
+    events::{
+        ConnectionEventData, ConnectionStatus, EventData, EventType, OrderEventData, PlatformEvent,
+    },
+    interfaces::{DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter},
+    models::{
+        AccountType, MarginInfo, OrderModification, UnifiedAccountInfo, UnifiedMarketData,
+        UnifiedOrder, UnifiedOrderResponse, UnifiedOrderSide, UnifiedOrderStatus, UnifiedOrderType,
+        UnifiedPosition, UnifiedPositionSide,
+    },
+};
+use crate::platforms::PlatformType;
+
+/// Platform calls a fault can be injected into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Connect,
+    Ping,
+    PlaceOrder,
+    ModifyOrder,
+    CancelOrder,
+    GetOrders,
+    GetPositions,
+    ClosePosition,
+    GetAccountInfo,
+    GetMarketData,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 10] = [
+        Operation::Connect,
+        Operation::Ping,
+        Operation::PlaceOrder,
+        Operation::ModifyOrder,
+        Operation::CancelOrder,
+        Operation::GetOrders,
+        Operation::GetPositions,
+        Operation::ClosePosition,
+        Operation::GetAccountInfo,
+        Operation::GetMarketData,
+    ];
+
+    pub(crate) fn is_order_operation(self) -> bool {
+        matches!(
+            self,
+            Operation::PlaceOrder
+                | Operation::ModifyOrder
+                | Operation::CancelOrder
+                | Operation::ClosePosition
+        )
+    }
+
+    fn fills(self) -> bool {
+        matches!(self, Operation::PlaceOrder | Operation::ClosePosition)
+    }
+}
+
+/// One injected failure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Fault {
+    /// Delay the call before it runs
+    LatencySpike { ms: u64 },
+    /// Fill only `ratio` of the order quantity
+    PartialFill { ratio: Decimal },
+    /// Deliver the fill event twice
+    DuplicateFill,
+    /// Hold the fill event back until after the next event
+    OutOfOrder,
+    /// Drop the connection; every call fails until `duration_ms` has passed
+    Disconnect { duration_ms: u64 },
+    /// Reject the call as the broker would
+    Reject { reason: String },
+    /// Fail with a rate limit asking to retry after `retry_after_ms`
+    Throttle { retry_after_ms: u64 },
+}
+
+/// Probabilities of random faults, rolled per call from the scenario seed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaultProfile {
+    /// Latency added to every call
+    pub base_latency_ms: u64,
+    pub latency_spike_probability: f64,
+    pub latency_spike_ms: u64,
+    pub partial_fill_probability: f64,
+    /// Portion of the quantity filled on a partial fill
+    pub partial_fill_ratio: Decimal,
+    pub duplicate_fill_probability: f64,
+    pub out_of_order_probability: f64,
+    pub disconnect_probability: f64,
+    pub disconnect_duration_ms: u64,
+    /// Only rolled for order placement, modification, cancellation and closes
+    pub reject_probability: f64,
+    pub throttle_probability: f64,
+    pub throttle_retry_after_ms: u64,
+}
+
+impl Default for FaultProfile {
+    fn default() -> Self {
+        Self {
+            base_latency_ms: 0,
+            latency_spike_probability: 0.0,
+            latency_spike_ms: 500,
+            partial_fill_probability: 0.0,
+            partial_fill_ratio: dec!(0.5),
+            duplicate_fill_probability: 0.0,
+            out_of_order_probability: 0.0,
+            disconnect_probability: 0.0,
+            disconnect_duration_ms: 1000,
+            reject_probability: 0.0,
+            throttle_probability: 0.0,
+            throttle_retry_after_ms: 100,
+        }
+    }
+}
+
+/// Fault injected on a specific call. With an `operation`, `call` counts calls of
+/// that operation only; without one it counts every call to the platform.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptedFault {
+    /// 1-based call number
+    pub call: u64,
+    #[serde(default)]
+    pub operation: Option<Operation>,
+    pub fault: Fault,
+}
+
+/// Named fault profile plus scripted faults, loaded from a TOML scenario file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChaosScenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Seed for the random faults, so a failing run can be replayed exactly
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub profile: FaultProfile,
+    #[serde(default)]
+    pub script: Vec<ScriptedFault>,
+}
+
+impl ChaosScenario {
+    /// Scenario injecting no faults
+    pub fn calm(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: String::new(),
+            seed: 0,
+            profile: FaultProfile::default(),
+            script: Vec::new(),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_profile(mut self, profile: FaultProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Inject `fault` on the `call`th call of `operation`
+    pub fn with_fault_at(mut self, operation: Operation, call: u64, fault: Fault) -> Self {
+        self.script.push(ScriptedFault {
+            call,
+            operation: Some(operation),
+            fault,
+        });
+        self
+    }
+
+    pub fn from_toml_str(contents: &str) -> Result<Self> {
+        toml::from_str(contents).context("Invalid chaos scenario")
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read chaos scenario {}", path.display()))?;
+        Self::from_toml_str(&contents).with_context(|| format!("In {}", path.display()))
+    }
+}
+
+/// A fault as it was injected
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InjectedFault {
+    /// 1-based number of the call across all operations
+    pub call: u64,
+    pub operation: Operation,
+    pub fault: Fault,
+}
+
+/// Counts of calls and injected faults
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChaosStats {
+    pub calls: u64,
+    /// Calls failed because the connection was down
+    pub failed_while_disconnected: u64,
+    pub latency_spikes: u64,
+    pub partial_fills: u64,
+    pub duplicate_fills: u64,
+    pub reordered_events: u64,
+    pub disconnects: u64,
+    pub rejections: u64,
+    pub throttles: u64,
+}
+
+impl ChaosStats {
+    fn record(&mut self, fault: &Fault) {
+        match fault {
+            Fault::LatencySpike { .. } => self.latency_spikes += 1,
+            Fault::PartialFill { .. } => self.partial_fills += 1,
+            Fault::DuplicateFill => self.duplicate_fills += 1,
+            Fault::OutOfOrder => self.reordered_events += 1,
+            Fault::Disconnect { .. } => self.disconnects += 1,
+            Fault::Reject { .. } => self.rejections += 1,
+            Fault::Throttle { .. } => self.throttles += 1,
+        }
+    }
+}
+
+/// Fill-time faults left over once a call is allowed through
+#[derive(Debug, Default)]
+struct FillFaults {
+    partial_ratio: Option<Decimal>,
+    duplicate: bool,
+    out_of_order: bool,
+}
+
+#[derive(Debug, Default)]
+struct ChaosState {
+    calls: u64,
+    calls_by_operation: HashMap<Operation, u64>,
+    /// Connection is down until this instant; `None` while connected
+    disconnected_until: Option<Instant>,
+    manually_disconnected: bool,
+    orders: Vec<UnifiedOrderResponse>,
+    positions: Vec<UnifiedPosition>,
+    quotes: HashMap<String, (Decimal, Decimal)>,
+    subscribers: Vec<mpsc::Sender<PlatformEvent>>,
+    /// Event held back by an out-of-order fault
+    held_event: Option<PlatformEvent>,
+    event_sequence: u64,
+    /// Realized P&L of every reduced or closed position
+    realized_pnl: Decimal,
+    history: Vec<PlatformEvent>,
+    injected: Vec<InjectedFault>,
+    stats: ChaosStats,
+}
+
+/// Simulated broker that fills orders against fixed quotes while injecting the
+/// faults of a `ChaosScenario`. Random faults are drawn from a seeded generator, so
+/// the same scenario and call sequence always produce the same failures.
+pub struct ChaosPlatform {
+    name: String,
+    account_id: String,
+    balance: Decimal,
+    scenario: ChaosScenario,
+    rng: Mutex<StdRng>,
+    state: Mutex<ChaosState>,
+}
+
+impl std::fmt::Debug for ChaosPlatform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChaosPlatform")
+            .field("name", &self.name)
+            .field("scenario", &self.scenario.name)
+            .finish()
+    }
+}
+
+impl ChaosPlatform {
+    pub fn new(name: &str, scenario: ChaosScenario) -> Self {
+        Self {
+            name: name.to_string(),
+            account_id: name.to_string(),
+            balance: dec!(10000),
+            rng: Mutex::new(StdRng::seed_from_u64(scenario.seed)),
+            scenario,
+            state: Mutex::new(ChaosState::default()),
+        }
+    }
+
+    pub fn with_account_id(mut self, account_id: &str) -> Self {
+        self.account_id = account_id.to_string();
+        self
+    }
+
+    pub fn with_balance(mut self, balance: Decimal) -> Self {
+        self.balance = balance;
+        self
+    }
+
+    pub fn with_quote(self, symbol: &str, bid: Decimal, ask: Decimal) -> Self {
+        self.set_quote(symbol, bid, ask);
+        self
+    }
+
+    pub fn scenario(&self) -> &ChaosScenario {
+        &self.scenario
+    }
+
+    /// Move the market; open positions are marked to the new quote and closed at
+    /// any stop or target it crosses
+    pub fn set_quote(&self, symbol: &str, bid: Decimal, ask: Decimal) {
+        let mut state = self.state.lock().unwrap();
+        state.quotes.insert(symbol.to_string(), (bid, ask));
+        for position in state.positions.iter_mut().filter(|p| p.symbol == symbol) {
+            position.current_price = match position.side {
+                UnifiedPositionSide::Long => bid,
+                UnifiedPositionSide::Short => ask,
+            };
+            position.unrealized_pnl = unrealized_pnl(position);
+            position.updated_at = Utc::now();
+        }
+
+        let triggered: Vec<(UnifiedOrderSide, Decimal, Decimal)> = state
+            .positions
+            .iter()
+            .filter(|p| p.symbol == symbol)
+            .filter_map(|p| {
+                let level = match p.side {
+                    UnifiedPositionSide::Long => p
+                        .stop_loss
+                        .filter(|&sl| bid <= sl)
+                        .or(p.take_profit.filter(|&tp| bid >= tp)),
+                    UnifiedPositionSide::Short => p
+                        .stop_loss
+                        .filter(|&sl| ask >= sl)
+                        .or(p.take_profit.filter(|&tp| ask <= tp)),
+                }?;
+                let side = match p.side {
+                    UnifiedPositionSide::Long => UnifiedOrderSide::Sell,
+                    UnifiedPositionSide::Short => UnifiedOrderSide::Buy,
+                };
+                Some((side, p.quantity, level))
+            })
+            .collect();
+        for (side, quantity, level) in triggered {
+            self.apply_fill(&mut state, symbol, side, quantity, level);
+        }
+    }
+
+    /// Open a position directly, as if it was filled before the test started
+    pub fn open_position(
+        &self,
+        symbol: &str,
+        side: UnifiedPositionSide,
+        quantity: Decimal,
+        entry_price: Decimal,
+        stop_loss: Option<Decimal>,
+    ) -> String {
+        let position_id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let mut position = UnifiedPosition {
+            position_id: position_id.clone(),
+            symbol: symbol.to_string(),
+            side,
+            quantity,
+            entry_price,
+            current_price: entry_price,
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_used: Decimal::ZERO,
+            commission: Decimal::ZERO,
+            stop_loss,
+            take_profit: None,
+            opened_at: now,
+            updated_at: now,
+            account_id: self.account_id.clone(),
+            platform_specific: HashMap::new(),
+        };
+        let mut state = self.state.lock().unwrap();
+        if let Some(&(bid, ask)) = state.quotes.get(symbol) {
+            position.current_price = match position.side {
+                UnifiedPositionSide::Long => bid,
+                UnifiedPositionSide::Short => ask,
+            };
+            position.unrealized_pnl = unrealized_pnl(&position);
+        }
+        state.positions.push(position);
+        position_id
+    }
+
+    /// Bring the connection back before a scripted or random outage would end
+    pub fn restore_connection(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.disconnected_until.is_some() || state.manually_disconnected {
+            state.disconnected_until = None;
+            state.manually_disconnected = false;
+            let event = self.connection_event(EventType::ConnectionRestored, None);
+            self.deliver(&mut state, event);
+        }
+    }
+
+    /// Deliver an event held back by an out-of-order fault with nothing behind it
+    pub fn flush_events(&self) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(event) = state.held_event.take() {
+            self.send(&mut state, event);
+        }
+    }
+
+    pub fn stats(&self) -> ChaosStats {
+        self.state.lock().unwrap().stats.clone()
+    }
+
+    pub fn injected_faults(&self) -> Vec<InjectedFault> {
+        self.state.lock().unwrap().injected.clone()
+    }
+
+    /// Gate every faultable call: counts it, fails it while disconnected, and rolls
+    /// and applies the scenario's faults. Fill faults are returned for the caller.
+    async fn before(&self, operation: Operation) -> Result<FillFaults, PlatformError> {
+        let (faults, call) = {
+            let mut state = self.state.lock().unwrap();
+            state.calls += 1;
+            state.stats.calls += 1;
+            let call = state.calls;
+            let operation_call = {
+                let count = state.calls_by_operation.entry(operation).or_default();
+                *count += 1;
+                *count
+            };
+
+            if self.connection_down(&mut state) {
+                state.stats.failed_while_disconnected += 1;
+                return Err(PlatformError::Disconnected {
+                    reason: format!("{} connection is down", self.name),
+                });
+            }
+
+            let mut faults: Vec<Fault> = self
+                .scenario
+                .script
+                .iter()
+                .filter(|s| match s.operation {
+                    Some(op) => op == operation && s.call == operation_call,
+                    None => s.call == call,
+                })
+                .map(|s| s.fault.clone())
+                .collect();
+            // A scripted fault replaces a random one of the same kind
+            let rolled: Vec<Fault> = self
+                .roll_faults(operation)
+                .into_iter()
+                .filter(|rolled| {
+                    !faults
+                        .iter()
+                        .any(|f| std::mem::discriminant(f) == std::mem::discriminant(rolled))
+                })
+                .collect();
+            faults.extend(rolled);
+            (faults, call)
+        };
+
+        // A call fails with its first failing fault; fill faults only apply to calls
+        // that go through
+        let fails = |fault: &Fault| {
+            matches!(
+                fault,
+                Fault::Disconnect { .. } | Fault::Reject { .. } | Fault::Throttle { .. }
+            )
+        };
+        let failing = faults.iter().position(fails);
+        let applied = faults
+            .into_iter()
+            .enumerate()
+            .filter(|(i, fault)| match fault {
+                Fault::LatencySpike { .. } => true,
+                Fault::PartialFill { .. } | Fault::DuplicateFill | Fault::OutOfOrder => {
+                    failing.is_none() && operation.fills()
+                }
+                _ => failing == Some(*i),
+            });
+
+        let mut latency = self.scenario.profile.base_latency_ms;
+        let mut fill = FillFaults::default();
+        let mut failure = None;
+        {
+            let mut state = self.state.lock().unwrap();
+            for (_, fault) in applied {
+                match &fault {
+                    Fault::LatencySpike { ms } => latency += ms,
+                    Fault::PartialFill { ratio } => fill.partial_ratio = Some(*ratio),
+                    Fault::DuplicateFill => fill.duplicate = true,
+                    Fault::OutOfOrder => fill.out_of_order = true,
+                    Fault::Disconnect { duration_ms } => {
+                        state.disconnected_until =
+                            Some(Instant::now() + Duration::from_millis(*duration_ms));
+                        let event = self.connection_event(
+                            EventType::ConnectionLost,
+                            Some(format!("Injected disconnect for {}ms", duration_ms)),
+                        );
+                        self.deliver(&mut state, event);
+                        failure = Some(PlatformError::Disconnected {
+                            reason: format!("{} connection dropped", self.name),
+                        });
+                    }
+                    Fault::Reject { reason } => {
+                        failure = Some(PlatformError::OrderRejected {
+                            reason: reason.clone(),
+                            platform_code: Some("CHAOS".to_string()),
+                        });
+                    }
+                    Fault::Throttle { retry_after_ms } => {
+                        failure = Some(PlatformError::RateLimitExceeded {
+                            retry_after_ms: *retry_after_ms,
+                        });
+                    }
+                }
+                state.stats.record(&fault);
+                state.injected.push(InjectedFault {
+                    call,
+                    operation,
+                    fault,
+                });
+            }
+        }
+
+        if latency > 0 {
+            tokio::time::sleep(Duration::from_millis(latency)).await;
+        }
+        match failure {
+            Some(error) => Err(error),
+            None => Ok(fill),
+        }
+    }
+
+    fn roll_faults(&self, operation: Operation) -> Vec<Fault> {
+        let profile = &self.scenario.profile;
+        let mut rng = self.rng.lock().unwrap();
+        let mut roll = |probability: f64| probability > 0.0 && rng.gen_bool(probability.min(1.0));
+
+        let mut faults = Vec::new();
+        if roll(profile.latency_spike_probability) {
+            faults.push(Fault::LatencySpike {
+                ms: profile.latency_spike_ms,
+            });
+        }
+        if roll(profile.disconnect_probability) {
+            faults.push(Fault::Disconnect {
+                duration_ms: profile.disconnect_duration_ms,
+            });
+        }
+        if roll(profile.throttle_probability) {
+            faults.push(Fault::Throttle {
+                retry_after_ms: profile.throttle_retry_after_ms,
+            });
+        }
+        if operation.is_order_operation() && roll(profile.reject_probability) {
+            faults.push(Fault::Reject {
+                reason: "Injected rejection".to_string(),
+            });
+        }
+        if operation.fills() {
+            if roll(profile.partial_fill_probability) {
+                faults.push(Fault::PartialFill {
+                    ratio: profile.partial_fill_ratio,
+                });
+            }
+            if roll(profile.duplicate_fill_probability) {
+                faults.push(Fault::DuplicateFill);
+            }
+            if roll(profile.out_of_order_probability) {
+                faults.push(Fault::OutOfOrder);
+            }
+        }
+        faults
+    }
+
+    /// Whether the connection is down, restoring it once an outage has run its course
+    fn connection_down(&self, state: &mut ChaosState) -> bool {
+        if state.manually_disconnected {
+            return true;
+        }
+        match state.disconnected_until {
+            Some(until) if Instant::now() < until => true,
+            Some(_) => {
+                state.disconnected_until = None;
+                let event = self.connection_event(EventType::ConnectionRestored, None);
+                self.deliver(state, event);
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn quote(&self, state: &ChaosState, symbol: &str) -> Result<(Decimal, Decimal), PlatformError> {
+        state
+            .quotes
+            .get(symbol)
+            .copied()
+            .ok_or_else(|| PlatformError::MarketDataUnavailable {
+                reason: format!("No quote for {}", symbol),
+            })
+    }
+
+    fn connection_event(&self, event_type: EventType, reason: Option<String>) -> PlatformEvent {
+        let status = match event_type {
+            EventType::ConnectionLost => ConnectionStatus::Disconnected,
+            _ => ConnectionStatus::Connected,
+        };
+        PlatformEvent::new(
+            event_type,
+            PlatformType::Mock,
+            self.account_id.clone(),
+            EventData::Connection(ConnectionEventData {
+                status,
+                reason,
+                server_info: Some(self.name.clone()),
+                latency_ms: None,
+            }),
+        )
+    }
+
+    /// Number an event and deliver it, releasing any event held back behind it
+    fn deliver(&self, state: &mut ChaosState, mut event: PlatformEvent) {
+        state.event_sequence += 1;
+        event.sequence_number = state.event_sequence;
+        self.send(state, event);
+        if let Some(held) = state.held_event.take() {
+            self.send(state, held);
+        }
+    }
+
+    fn send(&self, state: &mut ChaosState, event: PlatformEvent) {
+        state.history.push(event.clone());
+        state
+            .subscribers
+            .retain(|subscriber| match subscriber.try_send(event.clone()) {
+                Ok(()) | Err(mpsc::error::TrySendError::Full(_)) => true,
+                Err(mpsc::error::TrySendError::Closed(_)) => false,
+            });
+    }
+
+    /// Emit the fill event for `order`, duplicated or held back as the faults say
+    fn emit_fill(&self, state: &mut ChaosState, order: &UnifiedOrderResponse, fill: &FillFaults) {
+        let event_type = if order.status == UnifiedOrderStatus::PartiallyFilled {
+            EventType::OrderPartiallyFilled
+        } else {
+            EventType::OrderFilled
+        };
+        let mut event = PlatformEvent::new(
+            event_type,
+            PlatformType::Mock,
+            self.account_id.clone(),
+            EventData::Order(OrderEventData {
+                order: order.clone(),
+                previous_status: Some(UnifiedOrderStatus::New),
+                fill_price: order.average_fill_price,
+                fill_quantity: Some(order.filled_quantity),
+                remaining_quantity: Some(order.remaining_quantity),
+                rejection_reason: None,
+            }),
+        );
+
+        state.event_sequence += 1;
+        event.sequence_number = state.event_sequence;
+        let duplicate = fill.duplicate.then(|| event.clone());
+        if fill.out_of_order && state.held_event.is_none() {
+            state.held_event = Some(event);
+        } else {
+            self.send(state, event);
+            if let Some(held) = state.held_event.take() {
+                self.send(state, held);
+            }
+        }
+        if let Some(duplicate) = duplicate {
+            self.send(state, duplicate);
+        }
+    }
+
+    /// Fill `quantity` of `symbol` on `side` against the current quote, netting it
+    /// into the open position for the symbol
+    fn fill(
+        &self,
+        state: &mut ChaosState,
+        client_order_id: String,
+        symbol: &str,
+        side: UnifiedOrderSide,
+        order_type: UnifiedOrderType,
+        quantity: Decimal,
+        fill: &FillFaults,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        let (bid, ask) = self.quote(state, symbol)?;
+        let price = match side {
+            UnifiedOrderSide::Buy => ask,
+            UnifiedOrderSide::Sell => bid,
+        };
+        let filled = match fill.partial_ratio {
+            Some(ratio) => (quantity * ratio).round_dp(2).min(quantity),
+            None => quantity,
+        };
+        let now = Utc::now();
+        let response = UnifiedOrderResponse {
+            platform_order_id: format!("CHAOS_{}", state.orders.len() + 1),
+            client_order_id,
+            status: if filled < quantity {
+                UnifiedOrderStatus::PartiallyFilled
+            } else {
+                UnifiedOrderStatus::Filled
+            },
+            symbol: symbol.to_string(),
+            side: side.clone(),
+            order_type,
+            quantity,
+            filled_quantity: filled,
+            remaining_quantity: quantity - filled,
+            price: Some(price),
+            average_fill_price: Some(price),
+            commission: Some(Decimal::ZERO),
+            created_at: now,
+            updated_at: now,
+            filled_at: Some(now),
+            platform_specific: HashMap::new(),
+        };
+
+        self.apply_fill(state, symbol, side, filled, price);
+        state.orders.push(response.clone());
+        self.emit_fill(state, &response, fill);
+        Ok(response)
+    }
+
+    fn apply_fill(
+        &self,
+        state: &mut ChaosState,
+        symbol: &str,
+        side: UnifiedOrderSide,
+        quantity: Decimal,
+        price: Decimal,
+    ) {
+        let side = match side {
+            UnifiedOrderSide::Buy => UnifiedPositionSide::Long,
+            UnifiedOrderSide::Sell => UnifiedPositionSide::Short,
+        };
+        let mut remaining = quantity;
+        if let Some(index) = state.positions.iter().position(|p| p.symbol == symbol) {
+            let position = &mut state.positions[index];
+            if position.side == side {
+                let total = position.quantity + quantity;
+                position.entry_price =
+                    (position.entry_price * position.quantity + price * quantity) / total;
+                position.quantity = total;
+                position.updated_at = Utc::now();
+                return;
+            }
+            let reduced = position.quantity.min(quantity);
+            let direction = match position.side {
+                UnifiedPositionSide::Long => Decimal::ONE,
+                UnifiedPositionSide::Short => -Decimal::ONE,
+            };
+            let realized = (price - position.entry_price) * reduced * direction;
+            position.realized_pnl += realized;
+            position.quantity -= reduced;
+            position.updated_at = Utc::now();
+            remaining -= reduced;
+            if position.quantity.is_zero() {
+                state.positions.remove(index);
+            }
+            state.realized_pnl += realized;
+        }
+        if remaining > Decimal::ZERO {
+            self.open_position_locked(state, symbol, side, remaining, price);
+        }
+    }
+
+    fn open_position_locked(
+        &self,
+        state: &mut ChaosState,
+        symbol: &str,
+        side: UnifiedPositionSide,
+        quantity: Decimal,
+        price: Decimal,
+    ) {
+        let now = Utc::now();
+        state.positions.push(UnifiedPosition {
+            position_id: Uuid::new_v4().to_string(),
+            symbol: symbol.to_string(),
+            side,
+            quantity,
+            entry_price: price,
+            current_price: price,
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_used: Decimal::ZERO,
+            commission: Decimal::ZERO,
+            stop_loss: None,
+            take_profit: None,
+            opened_at: now,
+            updated_at: now,
+            account_id: self.account_id.clone(),
+            platform_specific: HashMap::new(),
+        });
+    }
+}
+
+fn unrealized_pnl(position: &UnifiedPosition) -> Decimal {
+    let move_ = position.current_price - position.entry_price;
+    match position.side {
+        UnifiedPositionSide::Long => move_ * position.quantity,
+        UnifiedPositionSide::Short => -move_ * position.quantity,
+    }
+}
+
+#[async_trait]
+impl ITradingPlatform for ChaosPlatform {
+    fn platform_type(&self) -> PlatformType {
+        PlatformType::Mock
+    }
+
+    fn platform_name(&self) -> &str {
+        &self.name
+    }
+
+    fn platform_version(&self) -> &str {
+        "1.0.0-chaos"
+    }
+
+    async fn connect(&mut self) -> Result<(), PlatformError> {
+        self.restore_connection();
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), PlatformError> {
+        let mut state = self.state.lock().unwrap();
+        state.manually_disconnected = true;
+        Ok(())
+    }
+
+    async fn is_connected(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        !self.connection_down(&mut state)
+    }
+
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        let started = Instant::now();
+        self.before(Operation::Ping).await?;
+        Ok(started.elapsed().as_millis() as u64)
+    }
+
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        let fill = self.before(Operation::PlaceOrder).await?;
+        let mut state = self.state.lock().unwrap();
+        let response = self.fill(
+            &mut state,
+            order.client_order_id,
+            &order.symbol,
+            order.side,
+            order.order_type,
+            order.quantity,
+            &fill,
+        )?;
+
+        // Stops and targets sent with the order attach to the resulting position
+        let side = match response.side {
+            UnifiedOrderSide::Buy => UnifiedPositionSide::Long,
+            UnifiedOrderSide::Sell => UnifiedPositionSide::Short,
+        };
+        if let Some(position) = state
+            .positions
+            .iter_mut()
+            .find(|p| p.symbol == order.symbol && p.side == side)
+        {
+            if order.stop_loss.is_some() {
+                position.stop_loss = order.stop_loss;
+            }
+            if order.take_profit.is_some() {
+                position.take_profit = order.take_profit;
+            }
+        }
+        Ok(response)
+    }
+
+    /// Accepts either an order id or a position id; the exit manager modifies
+    /// stops by position
+    async fn modify_order(
+        &self,
+        order_id: &str,
+        modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.before(Operation::ModifyOrder).await?;
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(position) = state
+            .positions
+            .iter_mut()
+            .find(|p| p.position_id == order_id)
+        {
+            if modifications.stop_loss.is_some() {
+                position.stop_loss = modifications.stop_loss;
+            }
+            if modifications.take_profit.is_some() {
+                position.take_profit = modifications.take_profit;
+            }
+            position.updated_at = Utc::now();
+            let now = Utc::now();
+            return Ok(UnifiedOrderResponse {
+                platform_order_id: position.position_id.clone(),
+                client_order_id: position.position_id.clone(),
+                status: UnifiedOrderStatus::Filled,
+                symbol: position.symbol.clone(),
+                side: match position.side {
+                    UnifiedPositionSide::Long => UnifiedOrderSide::Buy,
+                    UnifiedPositionSide::Short => UnifiedOrderSide::Sell,
+                },
+                order_type: UnifiedOrderType::Market,
+                quantity: position.quantity,
+                filled_quantity: position.quantity,
+                remaining_quantity: Decimal::ZERO,
+                price: Some(position.entry_price),
+                average_fill_price: Some(position.entry_price),
+                commission: None,
+                created_at: position.opened_at,
+                updated_at: now,
+                filled_at: Some(position.opened_at),
+                platform_specific: HashMap::new(),
+            });
+        }
+
+        let order = state
+            .orders
+            .iter_mut()
+            .find(|o| o.platform_order_id == order_id || o.client_order_id == order_id)
+            .ok_or_else(|| PlatformError::OrderNotFound {
+                order_id: order_id.to_string(),
+            })?;
+        if let Some(price) = modifications.price {
+            order.price = Some(price);
+        }
+        order.updated_at = Utc::now();
+        Ok(order.clone())
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<(), PlatformError> {
+        self.before(Operation::CancelOrder).await?;
+        let mut state = self.state.lock().unwrap();
+        let order = state
+            .orders
+            .iter_mut()
+            .find(|o| o.platform_order_id == order_id || o.client_order_id == order_id)
+            .ok_or_else(|| PlatformError::OrderNotFound {
+                order_id: order_id.to_string(),
+            })?;
+        if order.status == UnifiedOrderStatus::Filled {
+            return Err(PlatformError::OrderRejected {
+                reason: "Order already filled".to_string(),
+                platform_code: None,
+            });
+        }
+        order.status = UnifiedOrderStatus::Canceled;
+        order.updated_at = Utc::now();
+        Ok(())
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.before(Operation::GetOrders).await?;
+        let state = self.state.lock().unwrap();
+        state
+            .orders
+            .iter()
+            .find(|o| o.platform_order_id == order_id || o.client_order_id == order_id)
+            .cloned()
+            .ok_or_else(|| PlatformError::OrderNotFound {
+                order_id: order_id.to_string(),
+            })
+    }
+
+    async fn get_orders(
+        &self,
+        filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        self.before(Operation::GetOrders).await?;
+        let state = self.state.lock().unwrap();
+        let Some(filter) = filter else {
+            return Ok(state.orders.clone());
+        };
+        let filtered = state.orders.iter().filter(|order| {
+            filter.order_id.as_ref().map_or(true, |id| {
+                order.platform_order_id == *id || order.client_order_id == *id
+            }) && filter.symbol.as_ref().map_or(true, |s| order.symbol == *s)
+                && filter.status.as_ref().map_or(true, |s| order.status == *s)
+        });
+        Ok(filtered
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        self.before(Operation::GetPositions).await?;
+        Ok(self.state.lock().unwrap().positions.clone())
+    }
+
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        self.before(Operation::GetPositions).await?;
+        let state = self.state.lock().unwrap();
+        Ok(state.positions.iter().find(|p| p.symbol == symbol).cloned())
+    }
+
+    async fn close_position(
+        &self,
+        symbol: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        let fill = self.before(Operation::ClosePosition).await?;
+        let mut state = self.state.lock().unwrap();
+        let position = state
+            .positions
+            .iter()
+            .find(|p| p.symbol == symbol)
+            .ok_or_else(|| PlatformError::PositionNotFound {
+                symbol: symbol.to_string(),
+            })?;
+        let side = match position.side {
+            UnifiedPositionSide::Long => UnifiedOrderSide::Sell,
+            UnifiedPositionSide::Short => UnifiedOrderSide::Buy,
+        };
+        let quantity = quantity.unwrap_or(position.quantity).min(position.quantity);
+        self.fill(
+            &mut state,
+            format!("close_{}", Uuid::new_v4()),
+            symbol,
+            side,
+            UnifiedOrderType::Market,
+            quantity,
+            &fill,
+        )
+    }
+
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        self.before(Operation::GetAccountInfo).await?;
+        let state = self.state.lock().unwrap();
+        let unrealized: Decimal = state.positions.iter().map(|p| p.unrealized_pnl).sum();
+        let realized = state.realized_pnl;
+        Ok(UnifiedAccountInfo {
+            account_id: self.account_id.clone(),
+            account_name: Some(self.name.clone()),
+            currency: "USD".to_string(),
+            balance: self.balance + realized,
+            equity: self.balance + realized + unrealized,
+            margin_used: Decimal::ZERO,
+            margin_available: self.balance + realized + unrealized,
+            buying_power: self.balance + realized + unrealized,
+            unrealized_pnl: unrealized,
+            realized_pnl: realized,
+            margin_level: None,
+            account_type: AccountType::Demo,
+            last_updated: Utc::now(),
+            platform_specific: HashMap::new(),
+        })
+    }
+
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        Ok(self.get_account_info().await?.balance)
+    }
+
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        self.before(Operation::GetAccountInfo).await?;
+        Ok(MarginInfo {
+            initial_margin: Decimal::ZERO,
+            maintenance_margin: Decimal::ZERO,
+            margin_call_level: None,
+            stop_out_level: None,
+            margin_requirements: HashMap::new(),
+        })
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        self.before(Operation::GetMarketData).await?;
+        let state = self.state.lock().unwrap();
+        let (bid, ask) = self.quote(&state, symbol)?;
+        Ok(UnifiedMarketData {
+            symbol: symbol.to_string(),
+            bid,
+            ask,
+            spread: ask - bid,
+            last_price: Some((bid + ask) / Decimal::TWO),
+            volume: None,
+            high: None,
+            low: None,
+            timestamp: Utc::now(),
+            session: None,
+            platform_specific: HashMap::new(),
+        })
+    }
+
+    async fn subscribe_market_data(
+        &self,
+        _symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        let (_tx, rx) = mpsc::channel(100);
+        Ok(rx)
+    }
+
+    async fn unsubscribe_market_data(&self, _symbols: Vec<String>) -> Result<(), PlatformError> {
+        Ok(())
+    }
+
+    fn capabilities(&self) -> PlatformCapabilities {
+        PlatformCapabilities::new(self.name.clone())
+    }
+
+    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
+        let (tx, rx) = mpsc::channel(1024);
+        self.state.lock().unwrap().subscribers.push(tx);
+        Ok(rx)
+    }
+
+    async fn get_event_history(
+        &self,
+        filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        let state = self.state.lock().unwrap();
+        let events = state.history.iter().filter(|event| {
+            filter
+                .event_type
+                .as_ref()
+                .map_or(true, |t| event.event_type == *t)
+                && filter
+                    .from_time
+                    .map_or(true, |from| event.timestamp >= from)
+                && filter.to_time.map_or(true, |to| event.timestamp <= to)
+        });
+        Ok(events
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect())
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        let mut state = self.state.lock().unwrap();
+        let connected = !self.connection_down(&mut state);
+        let injected = state.injected.len() as f64;
+        Ok(HealthStatus {
+            is_healthy: connected,
+            last_ping: Some(Utc::now()),
+            latency_ms: Some(self.scenario.profile.base_latency_ms),
+            error_rate: if state.calls == 0 {
+                0.0
+            } else {
+                injected / state.calls as f64
+            },
+            uptime_seconds: 0,
+            issues: if connected {
+                Vec::new()
+            } else {
+                vec!["Connection down".to_string()]
+            },
+        })
+    }
+
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        let mut state = self.state.lock().unwrap();
+        let connection_status = if self.connection_down(&mut state) {
+            "DISCONNECTED"
+        } else {
+            "CONNECTED"
+        };
+        let mut platform_specific = HashMap::new();
+        platform_specific.insert(
+            "scenario".to_string(),
+            serde_json::Value::String(self.scenario.name.clone()),
+        );
+        platform_specific.insert(
+            "stats".to_string(),
+            serde_json::to_value(&state.stats).unwrap_or_default(),
+        );
+        Ok(DiagnosticsInfo {
+            connection_status: connection_status.to_string(),
+            api_limits: HashMap::new(),
+            performance_metrics: HashMap::new(),
+            last_errors: state
+                .injected
+                .iter()
+                .rev()
+                .take(10)
+                .map(|f| format!("call {} {:?}: {:?}", f.call, f.operation, f.fault))
+                .collect(),
+            platform_specific,
+        })
+    }
+}
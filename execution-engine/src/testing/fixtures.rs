@@ -0,0 +1,103 @@
+// Ready-made values for tests that only care about a field or two: take one and
+// override the rest with struct update syntax
+
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use super::mock_platform::MockTradingPlatform;
+use crate::execution::exit_management::Position;
+use crate::execution::orchestrator::{TradeExecutionOrchestrator, TradeSignal};
+use crate::platforms::abstraction::interfaces::ITradingPlatform;
+use crate::platforms::abstraction::models::{
+    UnifiedOrderSide, UnifiedPosition, UnifiedPositionSide,
+};
+
+/// EURUSD buy at 1.1 with a 100 pip stop and a 200 pip target
+pub fn signal(id: &str) -> TradeSignal {
+    TradeSignal {
+        id: id.to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Buy,
+        entry_price: 1.1,
+        stop_loss: 1.09,
+        take_profit: 1.12,
+        confidence: 0.8,
+        risk_reward_ratio: 2.0,
+        signal_time: SystemTime::now(),
+        metadata: HashMap::new(),
+    }
+}
+
+/// 10,000 EURUSD long from 1.1000, 60 pips in profit with a 50 pip stop
+pub fn long_position() -> Position {
+    Position {
+        id: Uuid::new_v4(),
+        order_id: "order-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        position_type: UnifiedPositionSide::Long,
+        volume: dec!(10000),
+        entry_price: dec!(1.1000),
+        current_price: dec!(1.1060),
+        stop_loss: Some(dec!(1.0950)),
+        take_profit: None,
+        unrealized_pnl: dec!(60.0),
+        swap: dec!(0.0),
+        commission: dec!(0.0),
+        open_time: Utc::now(),
+        magic_number: None,
+        comment: None,
+    }
+}
+
+/// `long_position` before the price has moved
+pub fn long_position_at_entry() -> Position {
+    Position {
+        current_price: dec!(1.1000),
+        unrealized_pnl: dec!(0.0),
+        ..long_position()
+    }
+}
+
+/// 10,000 `symbol` long from 1.1000 as a platform reports it, opened half an hour
+/// ago with a 50 pip stop and a 100 pip target
+pub fn platform_long_position(symbol: &str) -> UnifiedPosition {
+    UnifiedPosition {
+        position_id: Uuid::new_v4().to_string(),
+        symbol: symbol.to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity: dec!(10000),
+        entry_price: dec!(1.1000),
+        current_price: dec!(1.1000),
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        stop_loss: Some(dec!(1.0950)),
+        take_profit: Some(dec!(1.1100)),
+        opened_at: Utc::now() - Duration::minutes(30),
+        updated_at: Utc::now(),
+        account_id: String::new(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+/// An orchestrator with each platform registered as an account under its name,
+/// with 10,000 of initial capital
+pub async fn orchestrator(
+    platforms: Vec<Arc<MockTradingPlatform>>,
+) -> Arc<TradeExecutionOrchestrator> {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    for platform in platforms {
+        let account_id = platform.platform_name().to_string();
+        orchestrator
+            .register_account(account_id, platform, 10000.0)
+            .await
+            .unwrap();
+    }
+    orchestrator
+}
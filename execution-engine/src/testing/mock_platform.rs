@@ -0,0 +1,1147 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use super::chaos_platform::Operation;
+use crate::platforms::abstraction::{
+    capabilities::{PlatformCapabilities, PlatformFeature},
+    errors::PlatformError,
+    events::PlatformEvent,
+    interfaces::{DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter},
+    models::{
+        AccountType, MarginInfo, OrderMetadata, OrderModification, PositionTickets, Symbol,
+        TransactionRange, UnifiedAccountInfo, UnifiedMarketData, UnifiedOrder, UnifiedOrderBook,
+        UnifiedOrderResponse, UnifiedOrderSide, UnifiedOrderStatus, UnifiedOrderType,
+        UnifiedPosition, UnifiedPositionSide, UnifiedTimeInForce, UnifiedTransaction,
+        UnifiedTransactionKind,
+    },
+};
+use crate::platforms::PlatformType;
+
+#[derive(Default)]
+struct MockState {
+    connected: bool,
+    connection_attempts: u32,
+    calls: HashMap<Operation, u64>,
+    /// One-shot errors, returned by the next calls of the operation in order
+    scripted: HashMap<Operation, VecDeque<PlatformError>>,
+    /// Errors returned by every call of the operation until cleared
+    failing: HashMap<Operation, PlatformError>,
+    orders: Vec<UnifiedOrderResponse>,
+    /// Limit and stop orders waiting for `fill_order`, by platform order id
+    resting: HashMap<String, UnifiedOrder>,
+    positions: Vec<UnifiedPosition>,
+    quotes: HashMap<String, (Decimal, Decimal)>,
+    /// Distance market orders fill worse than the quote
+    slippage: Decimal,
+    modifications: Vec<(String, OrderModification)>,
+    closes: Vec<(String, Option<Decimal>)>,
+    ticket_closes: Vec<(String, Option<Decimal>)>,
+    realized_pnl: Decimal,
+    /// Deposits less withdrawals since the account was created
+    transfers: Decimal,
+    /// The account's statement, oldest first
+    transactions: Vec<UnifiedTransaction>,
+    /// Open market data streams and their symbols; `set_quote` feeds them
+    quote_streams: Vec<(Vec<String>, mpsc::Sender<UnifiedMarketData>)>,
+    market_data_subscriptions: Vec<Vec<String>>,
+    /// Depth of book by symbol
+    books: HashMap<String, UnifiedOrderBook>,
+    /// Open depth streams, their symbols and levels; `set_order_book` feeds them
+    depth_streams: Vec<(Vec<String>, usize, mpsc::Sender<UnifiedOrderBook>)>,
+}
+
+/// In-memory broker for unit and integration tests. Market orders fill against
+/// the configured quotes (or the order price when the symbol has none) and net
+/// into one position per symbol, or open a ticket each on a hedging account;
+/// limit and stop orders rest until filled with `fill_order` or cancelled.
+/// Failures are scripted per operation, and every modification and close is
+/// recorded for assertions.
+pub struct MockTradingPlatform {
+    name: String,
+    platform_type: PlatformType,
+    account_id: String,
+    currency: String,
+    balance: Decimal,
+    latency: Duration,
+    capabilities: Option<PlatformCapabilities>,
+    hedging: bool,
+    instruments: Option<Vec<Symbol>>,
+    clock_offset: chrono::Duration,
+    /// Set with the first order book, which advertises `MarketDepth`
+    market_depth: AtomicBool,
+    state: Mutex<MockState>,
+}
+
+impl MockTradingPlatform {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            platform_type: PlatformType::Mock,
+            account_id: name.to_string(),
+            currency: "USD".to_string(),
+            balance: Decimal::from(10000),
+            latency: Duration::ZERO,
+            capabilities: None,
+            hedging: false,
+            instruments: None,
+            clock_offset: chrono::Duration::zero(),
+            market_depth: AtomicBool::new(false),
+            state: Mutex::new(MockState {
+                connected: true,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Report a real platform type, for code that branches on it
+    pub fn with_platform_type(mut self, platform_type: PlatformType) -> Self {
+        self.platform_type = platform_type;
+        self
+    }
+
+    pub fn with_account_id(mut self, account_id: &str) -> Self {
+        self.account_id = account_id.to_string();
+        self
+    }
+
+    /// Denominate the account in `currency` instead of USD
+    pub fn with_currency(mut self, currency: &str) -> Self {
+        self.currency = currency.to_string();
+        self
+    }
+
+    pub fn with_balance(mut self, balance: Decimal) -> Self {
+        self.balance = balance;
+        self
+    }
+
+    /// Delay every order operation by `latency`
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Advertise a real platform's capabilities instead of the empty default set
+    pub fn with_capabilities(mut self, capabilities: PlatformCapabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Book every fill as its own ticket, as a hedging-mode account does, and
+    /// advertise `HedgedPositions`
+    pub fn with_hedging(mut self) -> Self {
+        self.hedging = true;
+        self
+    }
+
+    /// List instruments under the platform's own names; without any, instrument
+    /// listing is unsupported
+    pub fn with_instruments(mut self, instruments: Vec<Symbol>) -> Self {
+        self.instruments = Some(instruments);
+        self
+    }
+
+    /// Run the server clock `offset` ahead of ours, behind when negative. Quotes are
+    /// stamped on it.
+    pub fn with_clock_offset(mut self, offset: chrono::Duration) -> Self {
+        self.clock_offset = offset;
+        self
+    }
+
+    pub fn with_quote(self, symbol: &str, bid: Decimal, ask: Decimal) -> Self {
+        self.set_quote(symbol, bid, ask);
+        self
+    }
+
+    pub fn with_order_book(self, book: UnifiedOrderBook) -> Self {
+        self.set_order_book(book);
+        self
+    }
+
+    pub fn with_position(self, position: UnifiedPosition) -> Self {
+        self.add_position(position);
+        self
+    }
+
+    /// Start disconnected; calls fail until `connect` succeeds
+    pub fn disconnected(self) -> Self {
+        self.state.lock().unwrap().connected = false;
+        self
+    }
+
+    /// Move the market; open positions are marked to the new quote, and the quote
+    /// is streamed to subscribers of the symbol
+    pub fn set_quote(&self, symbol: &str, bid: Decimal, ask: Decimal) {
+        let mut state = self.state.lock().unwrap();
+        state.quotes.insert(symbol.to_string(), (bid, ask));
+        let quote = self.quote(symbol, bid, ask);
+        state.quote_streams.retain(|(symbols, sender)| {
+            if symbols.iter().any(|s| s == symbol) {
+                let _ = sender.try_send(quote.clone());
+            }
+            !sender.is_closed()
+        });
+        for position in state.positions.iter_mut().filter(|p| p.symbol == symbol) {
+            let price = match position.side {
+                UnifiedPositionSide::Long => bid,
+                UnifiedPositionSide::Short => ask,
+            };
+            mark(position, price);
+        }
+    }
+
+    /// Replace the book on its symbol and stream it to depth subscribers of the
+    /// symbol, cut to the levels each asked for
+    pub fn set_order_book(&self, book: UnifiedOrderBook) {
+        let mut state = self.state.lock().unwrap();
+        state.depth_streams.retain(|(symbols, levels, sender)| {
+            if symbols.contains(&book.symbol) {
+                let _ = sender.try_send(book.top(*levels));
+            }
+            !sender.is_closed()
+        });
+        state.books.insert(book.symbol.clone(), book);
+        self.market_depth.store(true, Ordering::Relaxed);
+    }
+
+    /// Symbols of every `subscribe_market_data` call, in order
+    pub fn market_data_subscriptions(&self) -> Vec<Vec<String>> {
+        self.state.lock().unwrap().market_data_subscriptions.clone()
+    }
+
+    fn quote(&self, symbol: &str, bid: Decimal, ask: Decimal) -> UnifiedMarketData {
+        UnifiedMarketData {
+            symbol: symbol.to_string(),
+            bid,
+            ask,
+            spread: ask - bid,
+            last_price: Some((bid + ask) / Decimal::TWO),
+            volume: None,
+            high: None,
+            low: None,
+            timestamp: Utc::now() + self.clock_offset,
+            session: None,
+            platform_specific: HashMap::new(),
+        }
+    }
+
+    /// Fill market orders `slippage` worse than the quote. Orders with a smaller
+    /// `max_slippage` are requoted when `PlatformFeature::MaxSlippage` is advertised.
+    pub fn set_slippage(&self, slippage: Decimal) {
+        self.state.lock().unwrap().slippage = slippage;
+    }
+
+    pub fn add_position(&self, position: UnifiedPosition) {
+        self.state.lock().unwrap().positions.push(position);
+    }
+
+    /// Mark one position to `price` without touching the quotes
+    pub fn set_position_price(&self, position_id: &str, price: Decimal) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(position) = state
+            .positions
+            .iter_mut()
+            .find(|p| p.position_id == position_id)
+        {
+            mark(position, price);
+        }
+    }
+
+    /// Fail the next call of `operation` with `error`; queued errors are returned in order
+    pub fn script_error(&self, operation: Operation, error: PlatformError) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .scripted
+            .entry(operation)
+            .or_default()
+            .push_back(error);
+    }
+
+    /// Fail every call of `operation` with `error` until `clear_failures`
+    pub fn fail_operation(&self, operation: Operation, error: PlatformError) {
+        self.state.lock().unwrap().failing.insert(operation, error);
+    }
+
+    /// Fail every call, as a broker that is up but refusing all requests
+    pub fn fail_all(&self, error: PlatformError) {
+        let mut state = self.state.lock().unwrap();
+        for operation in Operation::ALL {
+            state.failing.insert(operation, error.clone());
+        }
+    }
+
+    pub fn clear_failures(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.scripted.clear();
+        state.failing.clear();
+    }
+
+    /// Deposit a positive `amount` or withdraw a negative one
+    pub fn transfer(&self, amount: Decimal) {
+        let mut state = self.state.lock().unwrap();
+        state.transfers += amount;
+        let transaction = self.transaction(
+            &state,
+            UnifiedTransactionKind::Transfer,
+            amount,
+            if amount > Decimal::ZERO {
+                "Deposit"
+            } else {
+                "Withdrawal"
+            },
+        );
+        state.transactions.push(transaction);
+    }
+
+    /// Append an entry, such as a swap or commission charge, to the account's
+    /// statement. The balance is left as it is.
+    pub fn add_transaction(&self, transaction: UnifiedTransaction) {
+        self.state.lock().unwrap().transactions.push(transaction);
+    }
+
+    /// Statement entries, oldest first
+    pub fn transactions(&self) -> Vec<UnifiedTransaction> {
+        self.state.lock().unwrap().transactions.clone()
+    }
+
+    /// Drop or restore the connection without going through `connect`
+    pub fn set_connected(&self, connected: bool) {
+        self.state.lock().unwrap().connected = connected;
+    }
+
+    pub fn calls(&self, operation: Operation) -> u64 {
+        let state = self.state.lock().unwrap();
+        state.calls.get(&operation).copied().unwrap_or(0)
+    }
+
+    pub fn connection_attempts(&self) -> u32 {
+        self.state.lock().unwrap().connection_attempts
+    }
+
+    pub fn orders(&self) -> Vec<UnifiedOrderResponse> {
+        self.state.lock().unwrap().orders.clone()
+    }
+
+    pub fn positions(&self) -> Vec<UnifiedPosition> {
+        self.state.lock().unwrap().positions.clone()
+    }
+
+    /// Every accepted modification, keyed by the order or position id it targeted
+    pub fn modifications(&self) -> Vec<(String, OrderModification)> {
+        self.state.lock().unwrap().modifications.clone()
+    }
+
+    /// Every accepted close, by symbol and requested quantity
+    pub fn closes(&self) -> Vec<(String, Option<Decimal>)> {
+        self.state.lock().unwrap().closes.clone()
+    }
+
+    /// Every accepted ticket close, by position id and requested quantity
+    pub fn ticket_closes(&self) -> Vec<(String, Option<Decimal>)> {
+        self.state.lock().unwrap().ticket_closes.clone()
+    }
+
+    /// Fill a resting limit or stop order at its own price, as if the market had
+    /// reached it
+    pub fn fill_order(&self, order_id: &str) -> Result<(), PlatformError> {
+        let mut state = self.state.lock().unwrap();
+        let order = state
+            .resting
+            .remove(order_id)
+            .ok_or_else(|| PlatformError::OrderNotFound {
+                order_id: order_id.to_string(),
+            })?;
+        let price =
+            order
+                .price
+                .or(order.stop_price)
+                .ok_or_else(|| PlatformError::OrderRejected {
+                    reason: "Resting order has no price".to_string(),
+                    platform_code: None,
+                })?;
+        let position_id = self.apply_fill(&mut state, order_id, &order, price);
+        let now = Utc::now();
+        if let Some(response) = state
+            .orders
+            .iter_mut()
+            .find(|o| o.platform_order_id == order_id)
+        {
+            response.status = UnifiedOrderStatus::Filled;
+            response.filled_quantity = response.quantity;
+            response.remaining_quantity = Decimal::ZERO;
+            response.average_fill_price = Some(price);
+            response.filled_at = Some(now);
+            response.updated_at = now;
+            if let Some(position_id) = position_id {
+                response.platform_specific.insert(
+                    "position_id".to_string(),
+                    serde_json::Value::String(position_id),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn before(&self, operation: Operation) -> Result<(), PlatformError> {
+        {
+            let mut state = self.state.lock().unwrap();
+            *state.calls.entry(operation).or_default() += 1;
+            if let Some(error) = state
+                .scripted
+                .get_mut(&operation)
+                .and_then(|queue| queue.pop_front())
+            {
+                return Err(error);
+            }
+            if let Some(error) = state.failing.get(&operation) {
+                return Err(error.clone());
+            }
+            if !state.connected && operation != Operation::Connect {
+                return Err(PlatformError::Disconnected {
+                    reason: format!("{} is not connected", self.name),
+                });
+            }
+        }
+        if operation.is_order_operation() && !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+        Ok(())
+    }
+
+    fn fill_price(
+        state: &MockState,
+        symbol: &str,
+        side: &UnifiedOrderSide,
+        fallback: Option<Decimal>,
+    ) -> Result<Decimal, PlatformError> {
+        match (state.quotes.get(symbol), side) {
+            (Some((_, ask)), UnifiedOrderSide::Buy) => Ok(*ask),
+            (Some((bid, _)), UnifiedOrderSide::Sell) => Ok(*bid),
+            (None, _) => fallback.ok_or_else(|| PlatformError::MarketDataUnavailable {
+                reason: format!("No quote for {}", symbol),
+            }),
+        }
+    }
+
+    /// A statement entry with only the kind, balance effect and description set
+    fn transaction(
+        &self,
+        state: &MockState,
+        kind: UnifiedTransactionKind,
+        amount: Decimal,
+        description: &str,
+    ) -> UnifiedTransaction {
+        UnifiedTransaction {
+            transaction_id: format!("MOCK_TX_{}", state.transactions.len() + 1),
+            account_id: self.account_id.clone(),
+            kind,
+            symbol: None,
+            order_id: None,
+            position_id: None,
+            side: None,
+            quantity: None,
+            price: None,
+            commission: None,
+            amount,
+            currency: self.currency.clone(),
+            balance_after: None,
+            timestamp: Utc::now(),
+            description: description.to_string(),
+            platform_specific: HashMap::new(),
+        }
+    }
+
+    /// Book a fill on the statement, with the P&L it realized as its amount
+    #[allow(clippy::too_many_arguments)]
+    fn record_fill(
+        &self,
+        state: &mut MockState,
+        order_id: &str,
+        position_id: &str,
+        symbol: &str,
+        side: &UnifiedOrderSide,
+        quantity: Decimal,
+        price: Decimal,
+        realized: Decimal,
+    ) {
+        let transaction = UnifiedTransaction {
+            symbol: Some(symbol.to_string()),
+            order_id: Some(order_id.to_string()),
+            position_id: Some(position_id.to_string()),
+            side: Some(side.clone()),
+            quantity: Some(quantity),
+            price: Some(price),
+            commission: Some(Decimal::ZERO),
+            ..self.transaction(state, UnifiedTransactionKind::Fill, realized, "Fill")
+        };
+        state.transactions.push(transaction);
+    }
+
+    /// Net a fill into the open position for the symbol; on a hedging account
+    /// every fill opens a new ticket
+    fn apply_fill(
+        &self,
+        state: &mut MockState,
+        order_id: &str,
+        order: &UnifiedOrder,
+        price: Decimal,
+    ) -> Option<String> {
+        let side = match order.side {
+            UnifiedOrderSide::Buy => UnifiedPositionSide::Long,
+            UnifiedOrderSide::Sell => UnifiedPositionSide::Short,
+        };
+        let mut remaining = order.quantity;
+        let netted = if self.hedging {
+            None
+        } else {
+            state
+                .positions
+                .iter()
+                .position(|p| p.symbol == order.symbol)
+        };
+        if let Some(index) = netted {
+            let position = &mut state.positions[index];
+            if position.side == side {
+                let total = position.quantity + order.quantity;
+                position.entry_price =
+                    (position.entry_price * position.quantity + price * order.quantity) / total;
+                position.quantity = total;
+                position.stop_loss = order.stop_loss.or(position.stop_loss);
+                position.take_profit = order.take_profit.or(position.take_profit);
+                position.updated_at = Utc::now();
+                let position_id = position.position_id.clone();
+                self.record_fill(
+                    state,
+                    order_id,
+                    &position_id,
+                    &order.symbol,
+                    &order.side,
+                    order.quantity,
+                    price,
+                    Decimal::ZERO,
+                );
+                return Some(position_id);
+            }
+            let reduced = position.quantity.min(order.quantity);
+            let realized = match position.side {
+                UnifiedPositionSide::Long => price - position.entry_price,
+                UnifiedPositionSide::Short => position.entry_price - price,
+            } * reduced;
+            position.realized_pnl += realized;
+            position.quantity -= reduced;
+            position.updated_at = Utc::now();
+            remaining -= reduced;
+            let position_id = position.position_id.clone();
+            if position.quantity.is_zero() {
+                state.positions.remove(index);
+            }
+            state.realized_pnl += realized;
+            self.record_fill(
+                state,
+                order_id,
+                &position_id,
+                &order.symbol,
+                &order.side,
+                reduced,
+                price,
+                realized,
+            );
+        }
+        if remaining.is_zero() {
+            return None;
+        }
+
+        let now = Utc::now();
+        let position_id = Uuid::new_v4().to_string();
+        state.positions.push(UnifiedPosition {
+            position_id: position_id.clone(),
+            symbol: order.symbol.clone(),
+            side,
+            quantity: remaining,
+            entry_price: price,
+            current_price: price,
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_used: Decimal::ZERO,
+            commission: Decimal::ZERO,
+            stop_loss: order.stop_loss,
+            take_profit: order.take_profit,
+            opened_at: now,
+            updated_at: now,
+            account_id: self.account_id.clone(),
+            platform_specific: HashMap::new(),
+        });
+        self.record_fill(
+            state,
+            order_id,
+            &position_id,
+            &order.symbol,
+            &order.side,
+            remaining,
+            price,
+            Decimal::ZERO,
+        );
+        Some(position_id)
+    }
+
+    /// Close all or part of the ticket at `index` against the current quote
+    fn close_ticket(
+        &self,
+        state: &mut MockState,
+        index: usize,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        let position = &state.positions[index];
+        let side = position_side_to_order(&position.side);
+        let price = Self::fill_price(state, &position.symbol, &side, Some(position.current_price))?;
+
+        let position = &mut state.positions[index];
+        let closed = quantity.unwrap_or(position.quantity).min(position.quantity);
+        let realized = match position.side {
+            UnifiedPositionSide::Long => price - position.entry_price,
+            UnifiedPositionSide::Short => position.entry_price - price,
+        } * closed;
+        position.realized_pnl += realized;
+        position.quantity -= closed;
+        position.updated_at = Utc::now();
+        let symbol = position.symbol.clone();
+        let position_id = position.position_id.clone();
+        if position.quantity.is_zero() {
+            state.positions.remove(index);
+        }
+        state.realized_pnl += realized;
+
+        let now = Utc::now();
+        let order_id = format!("MOCK_{}", state.orders.len() + 1);
+        self.record_fill(
+            state,
+            &order_id,
+            &position_id,
+            &symbol,
+            &side,
+            closed,
+            price,
+            realized,
+        );
+        let response = UnifiedOrderResponse {
+            platform_order_id: order_id,
+            client_order_id: format!("close_{}", Uuid::new_v4()),
+            status: UnifiedOrderStatus::Filled,
+            symbol,
+            side,
+            order_type: UnifiedOrderType::Market,
+            quantity: closed,
+            filled_quantity: closed,
+            remaining_quantity: Decimal::ZERO,
+            price: Some(price),
+            average_fill_price: Some(price),
+            commission: Some(Decimal::ZERO),
+            created_at: now,
+            updated_at: now,
+            filled_at: Some(now),
+            platform_specific: HashMap::new(),
+        };
+        state.orders.push(response.clone());
+        Ok(response)
+    }
+}
+
+fn mark(position: &mut UnifiedPosition, price: Decimal) {
+    position.current_price = price;
+    position.unrealized_pnl = match position.side {
+        UnifiedPositionSide::Long => price - position.entry_price,
+        UnifiedPositionSide::Short => position.entry_price - price,
+    } * position.quantity;
+    position.updated_at = Utc::now();
+}
+
+fn position_side_to_order(side: &UnifiedPositionSide) -> UnifiedOrderSide {
+    match side {
+        UnifiedPositionSide::Long => UnifiedOrderSide::Sell,
+        UnifiedPositionSide::Short => UnifiedOrderSide::Buy,
+    }
+}
+
+#[async_trait]
+impl ITradingPlatform for MockTradingPlatform {
+    fn platform_type(&self) -> PlatformType {
+        self.platform_type.clone()
+    }
+
+    fn platform_name(&self) -> &str {
+        &self.name
+    }
+
+    fn platform_version(&self) -> &str {
+        "1.0.0-mock"
+    }
+
+    async fn connect(&mut self) -> Result<(), PlatformError> {
+        self.state.lock().unwrap().connection_attempts += 1;
+        self.before(Operation::Connect).await?;
+        self.state.lock().unwrap().connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), PlatformError> {
+        self.state.lock().unwrap().connected = false;
+        Ok(())
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.state.lock().unwrap().connected
+    }
+
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        self.before(Operation::Ping).await?;
+        Ok(self.latency.as_millis() as u64)
+    }
+
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.before(Operation::PlaceOrder).await?;
+        let mut state = self.state.lock().unwrap();
+        let now = Utc::now();
+        let mut response = UnifiedOrderResponse {
+            platform_order_id: format!("MOCK_{}", state.orders.len() + 1),
+            client_order_id: order.client_order_id.clone(),
+            status: UnifiedOrderStatus::New,
+            symbol: order.symbol.clone(),
+            side: order.side.clone(),
+            order_type: order.order_type.clone(),
+            quantity: order.quantity,
+            filled_quantity: Decimal::ZERO,
+            remaining_quantity: order.quantity,
+            price: order.price,
+            average_fill_price: None,
+            commission: Some(Decimal::ZERO),
+            created_at: now,
+            updated_at: now,
+            filled_at: None,
+            platform_specific: HashMap::new(),
+        };
+
+        if order.order_type == UnifiedOrderType::Market {
+            let quote = Self::fill_price(&state, &order.symbol, &order.side, order.price)?;
+            if order.max_slippage.is_some_and(|max| state.slippage > max)
+                && self
+                    .capabilities()
+                    .features
+                    .contains(&PlatformFeature::MaxSlippage)
+            {
+                return Err(PlatformError::OrderRejected {
+                    reason: "Requote".to_string(),
+                    platform_code: None,
+                });
+            }
+            let price = match order.side {
+                UnifiedOrderSide::Buy => quote + state.slippage,
+                UnifiedOrderSide::Sell => quote - state.slippage,
+            };
+            response.status = UnifiedOrderStatus::Filled;
+            response.filled_quantity = order.quantity;
+            response.remaining_quantity = Decimal::ZERO;
+            response.price = Some(price);
+            response.average_fill_price = Some(price);
+            response.filled_at = Some(now);
+            if let Some(position_id) =
+                self.apply_fill(&mut state, &response.platform_order_id, &order, price)
+            {
+                response.platform_specific.insert(
+                    "position_id".to_string(),
+                    serde_json::Value::String(position_id),
+                );
+            }
+        } else {
+            state
+                .resting
+                .insert(response.platform_order_id.clone(), order);
+        }
+
+        state.orders.push(response.clone());
+        Ok(response)
+    }
+
+    /// Accepts either an order id or a position id; the exit manager modifies
+    /// stops by position
+    async fn modify_order(
+        &self,
+        order_id: &str,
+        modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.before(Operation::ModifyOrder).await?;
+        let mut state = self.state.lock().unwrap();
+        let now = Utc::now();
+
+        let response = if let Some(position) = state
+            .positions
+            .iter_mut()
+            .find(|p| p.position_id == order_id)
+        {
+            position.stop_loss = modifications.stop_loss.or(position.stop_loss);
+            position.take_profit = modifications.take_profit.or(position.take_profit);
+            position.updated_at = now;
+            UnifiedOrderResponse {
+                platform_order_id: position.position_id.clone(),
+                client_order_id: position.position_id.clone(),
+                status: UnifiedOrderStatus::Filled,
+                symbol: position.symbol.clone(),
+                side: match position.side {
+                    UnifiedPositionSide::Long => UnifiedOrderSide::Buy,
+                    UnifiedPositionSide::Short => UnifiedOrderSide::Sell,
+                },
+                order_type: UnifiedOrderType::Market,
+                quantity: position.quantity,
+                filled_quantity: position.quantity,
+                remaining_quantity: Decimal::ZERO,
+                price: Some(position.entry_price),
+                average_fill_price: Some(position.entry_price),
+                commission: None,
+                created_at: position.opened_at,
+                updated_at: now,
+                filled_at: Some(position.opened_at),
+                platform_specific: HashMap::new(),
+            }
+        } else {
+            let order = state
+                .orders
+                .iter_mut()
+                .find(|o| o.platform_order_id == order_id || o.client_order_id == order_id)
+                .ok_or_else(|| PlatformError::OrderNotFound {
+                    order_id: order_id.to_string(),
+                })?;
+            if let Some(quantity) = modifications.quantity {
+                order.quantity = quantity;
+                order.remaining_quantity = quantity - order.filled_quantity;
+            }
+            order.price = modifications.price.or(order.price);
+            order.updated_at = now;
+            order.clone()
+        };
+
+        state
+            .modifications
+            .push((order_id.to_string(), modifications));
+        Ok(response)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<(), PlatformError> {
+        self.before(Operation::CancelOrder).await?;
+        let mut state = self.state.lock().unwrap();
+        let order = state
+            .orders
+            .iter_mut()
+            .find(|o| o.platform_order_id == order_id || o.client_order_id == order_id)
+            .ok_or_else(|| PlatformError::OrderNotFound {
+                order_id: order_id.to_string(),
+            })?;
+        if order.status == UnifiedOrderStatus::Filled {
+            return Err(PlatformError::OrderRejected {
+                reason: "Order already filled".to_string(),
+                platform_code: None,
+            });
+        }
+        order.status = UnifiedOrderStatus::Canceled;
+        order.updated_at = Utc::now();
+        let platform_order_id = order.platform_order_id.clone();
+        state.resting.remove(&platform_order_id);
+        Ok(())
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.before(Operation::GetOrders).await?;
+        let state = self.state.lock().unwrap();
+        state
+            .orders
+            .iter()
+            .find(|o| o.platform_order_id == order_id || o.client_order_id == order_id)
+            .cloned()
+            .ok_or_else(|| PlatformError::OrderNotFound {
+                order_id: order_id.to_string(),
+            })
+    }
+
+    async fn get_orders(
+        &self,
+        filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        self.before(Operation::GetOrders).await?;
+        let state = self.state.lock().unwrap();
+        let Some(filter) = filter else {
+            return Ok(state.orders.clone());
+        };
+        let filtered = state.orders.iter().filter(|order| {
+            filter.order_id.as_ref().map_or(true, |id| {
+                order.platform_order_id == *id || order.client_order_id == *id
+            }) && filter.symbol.as_ref().map_or(true, |s| order.symbol == *s)
+                && filter.status.as_ref().map_or(true, |s| order.status == *s)
+        });
+        Ok(filtered
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        self.before(Operation::GetPositions).await?;
+        Ok(self.positions())
+    }
+
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        self.before(Operation::GetPositions).await?;
+        Ok(PositionTickets::from_positions(symbol, self.positions()).net())
+    }
+
+    async fn close_position(
+        &self,
+        symbol: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.before(Operation::ClosePosition).await?;
+        let mut state = self.state.lock().unwrap();
+        let tickets: Vec<usize> = state
+            .positions
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.symbol == symbol)
+            .map(|(index, _)| index)
+            .collect();
+        if tickets.len() > 1 && quantity.is_some() {
+            return Err(PlatformError::PositionCloseFailed {
+                reason: format!(
+                    "{} has {} open tickets; close part of one by ticket",
+                    symbol,
+                    tickets.len()
+                ),
+            });
+        }
+
+        // Last index first, so removing a closed ticket leaves the others in place
+        let mut response = None;
+        for index in tickets.into_iter().rev() {
+            response = Some(self.close_ticket(&mut state, index, quantity)?);
+        }
+        let response = response.ok_or_else(|| PlatformError::PositionNotFound {
+            symbol: symbol.to_string(),
+        })?;
+        state.closes.push((symbol.to_string(), quantity));
+        Ok(response)
+    }
+
+    async fn close_position_ticket(
+        &self,
+        position_id: &str,
+        quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.before(Operation::ClosePosition).await?;
+        let mut state = self.state.lock().unwrap();
+        let index = state
+            .positions
+            .iter()
+            .position(|p| p.position_id == position_id)
+            .ok_or_else(|| PlatformError::TicketNotFound {
+                position_id: position_id.to_string(),
+            })?;
+        let response = self.close_ticket(&mut state, index, quantity)?;
+        state
+            .ticket_closes
+            .push((position_id.to_string(), quantity));
+        Ok(response)
+    }
+
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        self.before(Operation::GetAccountInfo).await?;
+        let state = self.state.lock().unwrap();
+        let unrealized: Decimal = state.positions.iter().map(|p| p.unrealized_pnl).sum();
+        let balance = self.balance + state.realized_pnl + state.transfers;
+        Ok(UnifiedAccountInfo {
+            account_id: self.account_id.clone(),
+            account_name: Some(self.name.clone()),
+            currency: self.currency.clone(),
+            balance,
+            equity: balance + unrealized,
+            margin_used: Decimal::ZERO,
+            margin_available: balance + unrealized,
+            buying_power: balance + unrealized,
+            unrealized_pnl: unrealized,
+            realized_pnl: state.realized_pnl,
+            margin_level: None,
+            account_type: AccountType::Demo,
+            last_updated: Utc::now(),
+            platform_specific: HashMap::new(),
+        })
+    }
+
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        Ok(self.get_account_info().await?.balance)
+    }
+
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        self.before(Operation::GetAccountInfo).await?;
+        Ok(MarginInfo {
+            initial_margin: Decimal::ZERO,
+            maintenance_margin: Decimal::ZERO,
+            margin_call_level: Some(Decimal::from(100)),
+            stop_out_level: Some(Decimal::from(50)),
+            margin_requirements: HashMap::new(),
+        })
+    }
+
+    async fn get_transactions(
+        &self,
+        range: TransactionRange,
+    ) -> Result<Vec<UnifiedTransaction>, PlatformError> {
+        self.before(Operation::GetAccountInfo).await?;
+        Ok(self
+            .transactions()
+            .into_iter()
+            .filter(|t| range.contains(t.timestamp))
+            .collect())
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<Symbol>, PlatformError> {
+        self.before(Operation::GetMarketData).await?;
+        self.instruments
+            .clone()
+            .ok_or_else(|| PlatformError::FeatureNotSupported {
+                feature: "get_instruments".to_string(),
+            })
+    }
+
+    async fn get_server_time(&self) -> Result<DateTime<Utc>, PlatformError> {
+        self.before(Operation::Ping).await?;
+        Ok(Utc::now() + self.clock_offset)
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        self.before(Operation::GetMarketData).await?;
+        let state = self.state.lock().unwrap();
+        let (bid, ask) = state.quotes.get(symbol).copied().ok_or_else(|| {
+            PlatformError::MarketDataUnavailable {
+                reason: format!("No quote for {}", symbol),
+            }
+        })?;
+        Ok(self.quote(symbol, bid, ask))
+    }
+
+    async fn subscribe_market_data(
+        &self,
+        symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        let (tx, rx) = mpsc::channel(100);
+        let mut state = self.state.lock().unwrap();
+        state.market_data_subscriptions.push(symbols.clone());
+        state.quote_streams.push((symbols, tx));
+        Ok(rx)
+    }
+
+    async fn unsubscribe_market_data(&self, _symbols: Vec<String>) -> Result<(), PlatformError> {
+        Ok(())
+    }
+
+    async fn get_order_book(
+        &self,
+        symbol: &str,
+        levels: usize,
+    ) -> Result<UnifiedOrderBook, PlatformError> {
+        self.before(Operation::GetMarketData).await?;
+        let state = self.state.lock().unwrap();
+        if state.books.is_empty() {
+            return Err(PlatformError::FeatureNotSupported {
+                feature: "get_order_book".to_string(),
+            });
+        }
+        state
+            .books
+            .get(symbol)
+            .map(|book| book.top(levels))
+            .ok_or_else(|| PlatformError::MarketDataUnavailable {
+                reason: format!("No order book for {}", symbol),
+            })
+    }
+
+    async fn subscribe_depth(
+        &self,
+        symbols: Vec<String>,
+        levels: usize,
+    ) -> Result<mpsc::Receiver<UnifiedOrderBook>, PlatformError> {
+        let mut state = self.state.lock().unwrap();
+        if state.books.is_empty() {
+            return Err(PlatformError::FeatureNotSupported {
+                feature: "subscribe_depth".to_string(),
+            });
+        }
+        let (tx, rx) = mpsc::channel(100);
+        state.depth_streams.push((symbols, levels, tx));
+        Ok(rx)
+    }
+
+    fn capabilities(&self) -> PlatformCapabilities {
+        let mut capabilities = self
+            .capabilities
+            .clone()
+            .unwrap_or_else(|| PlatformCapabilities::new(self.name.clone()));
+        if self.hedging {
+            capabilities
+                .features
+                .insert(PlatformFeature::HedgedPositions);
+        }
+        if self.market_depth.load(Ordering::Relaxed) {
+            capabilities.features.insert(PlatformFeature::MarketDepth);
+        }
+        capabilities
+    }
+
+    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
+        let (_tx, rx) = mpsc::channel(100);
+        Ok(rx)
+    }
+
+    async fn get_event_history(
+        &self,
+        _filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        Ok(Vec::new())
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        let state = self.state.lock().unwrap();
+        let failing = !state.failing.is_empty();
+        Ok(HealthStatus {
+            is_healthy: state.connected && !failing,
+            last_ping: Some(Utc::now()),
+            latency_ms: Some(self.latency.as_millis() as u64),
+            error_rate: if failing { 1.0 } else { 0.0 },
+            uptime_seconds: 0,
+            issues: state
+                .failing
+                .iter()
+                .map(|(operation, error)| format!("{:?} failing: {}", operation, error))
+                .collect(),
+        })
+    }
+
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        let state = self.state.lock().unwrap();
+        Ok(DiagnosticsInfo {
+            connection_status: if state.connected {
+                "CONNECTED".to_string()
+            } else {
+                "DISCONNECTED".to_string()
+            },
+            api_limits: HashMap::new(),
+            performance_metrics: HashMap::new(),
+            last_errors: Vec::new(),
+            platform_specific: HashMap::new(),
+        })
+    }
+}
@@ -0,0 +1,21 @@
+// Test doubles and an end-to-end simulation harness, built for unit tests and behind the
+// `test-support` feature for integration tests
+
+pub mod chaos_platform;
+pub mod fixtures;
+pub mod mock_platform;
+pub mod simulation;
+
+pub use chaos_platform::{
+    ChaosPlatform, ChaosScenario, ChaosStats, Fault, FaultProfile, InjectedFault, Operation,
+    ScriptedFault,
+};
+pub use fixtures::{
+    long_position, long_position_at_entry, orchestrator, platform_long_position, signal,
+};
+pub use mock_platform::MockTradingPlatform;
+pub use simulation::{
+    run_scenario_file, DrawdownLimits, Expectations, ExpectedAudit, ExpectedPosition,
+    ExpectedRiskAlert, NewsStep, PriceStep, RiskAlert, SignalStep, SimulatedAccount,
+    SimulationHarness, SimulationReport, SimulationScenario, TimelineEntry, VirtualClock,
+};
@@ -0,0 +1,671 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use super::chaos_platform::{ChaosPlatform, ChaosScenario};
+use crate::execution::exit_management::exit_logger::{
+    AuditDatabase, InMemoryAuditDatabase, TimeRange,
+};
+use crate::execution::exit_management::{
+    AuditEntry, ExitAuditLogger, ExitManagementPlatformAdapter, ExitManagementSystem,
+    ExitModificationType, ImpactLevel, NewsEvent,
+};
+use crate::execution::orchestrator::{ExecutionResult, TradeExecutionOrchestrator, TradeSignal};
+use crate::platforms::abstraction::models::UnifiedPositionSide;
+use crate::platforms::abstraction::{ITradingPlatform, UnifiedOrderSide, UnifiedPosition};
+use crate::risk::config::DrawdownThresholds;
+use crate::risk::drawdown_tracker::{DrawdownAlertManager, DrawdownTracker, EquityHistoryManager};
+
+fn default_balance() -> Decimal {
+    dec!(10000)
+}
+
+fn default_confidence() -> f64 {
+    0.8
+}
+
+fn default_impact() -> ImpactLevel {
+    ImpactLevel::High
+}
+
+/// Account traded in a simulation, optionally behind a chaos fault profile
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulatedAccount {
+    pub id: String,
+    #[serde(default = "default_balance")]
+    pub balance: Decimal,
+    #[serde(default)]
+    pub chaos: Option<ChaosScenario>,
+}
+
+/// Quote change at `at` seconds into the scenario
+#[derive(Debug, Clone, Deserialize)]
+pub struct PriceStep {
+    pub at: u64,
+    pub symbol: String,
+    pub bid: Decimal,
+    pub ask: Decimal,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SignalStep {
+    pub at: u64,
+    pub id: String,
+    pub symbol: String,
+    pub side: UnifiedOrderSide,
+    pub entry_price: f64,
+    pub stop_loss: f64,
+    pub take_profit: f64,
+    #[serde(default = "default_confidence")]
+    pub confidence: f64,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+/// Economic release that news protection reacts to at `at`. The release itself
+/// happens `release_in` seconds later.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewsStep {
+    pub at: u64,
+    pub id: String,
+    pub currency: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_impact")]
+    pub impact: ImpactLevel,
+    #[serde(default)]
+    pub release_in: u64,
+}
+
+/// Drawdown percentages above which risk alerts are raised
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DrawdownLimits {
+    pub daily: Decimal,
+    pub weekly: Decimal,
+    pub maximum: Decimal,
+}
+
+impl Default for DrawdownLimits {
+    fn default() -> Self {
+        Self {
+            daily: dec!(5),
+            weekly: dec!(10),
+            maximum: dec!(20),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpectedPosition {
+    pub account: String,
+    pub symbol: String,
+    pub side: UnifiedPositionSide,
+    #[serde(default)]
+    pub quantity: Option<Decimal>,
+    #[serde(default)]
+    pub stop_loss: Option<Decimal>,
+}
+
+/// Number of exit audit entries of one type, across all accounts unless one is named
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpectedAudit {
+    pub modification_type: ExitModificationType,
+    #[serde(default)]
+    pub account: Option<String>,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpectedRiskAlert {
+    pub account: String,
+    /// `daily`, `weekly` or `maximum`
+    pub kind: String,
+}
+
+/// What must hold once the scenario has run. Positions and risk alerts are
+/// matched exactly when listed; executions only when given.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Expectations {
+    pub positions: Option<Vec<ExpectedPosition>>,
+    pub successful_executions: Option<usize>,
+    pub failed_executions: Option<usize>,
+    pub rejected_signals: Option<Vec<String>>,
+    pub audit: Vec<ExpectedAudit>,
+    pub risk_alerts: Option<Vec<ExpectedRiskAlert>>,
+}
+
+/// Accounts, price path, signals and news for an end-to-end run, loaded from YAML
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulationScenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Virtual time the scenario starts at; defaults to the time of the run
+    #[serde(default)]
+    pub start: Option<DateTime<Utc>>,
+    pub accounts: Vec<SimulatedAccount>,
+    #[serde(default)]
+    pub prices: Vec<PriceStep>,
+    #[serde(default)]
+    pub signals: Vec<SignalStep>,
+    #[serde(default)]
+    pub news: Vec<NewsStep>,
+    #[serde(default)]
+    pub risk: DrawdownLimits,
+    #[serde(default)]
+    pub expect: Expectations,
+}
+
+impl SimulationScenario {
+    pub fn from_yaml_str(contents: &str) -> Result<Self> {
+        config::Config::builder()
+            .add_source(config::File::from_str(contents, config::FileFormat::Yaml))
+            .build()
+            .and_then(|config| config.try_deserialize())
+            .context("Invalid simulation scenario")
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read simulation scenario {}", path.display()))?;
+        Self::from_yaml_str(&contents).with_context(|| format!("In {}", path.display()))
+    }
+}
+
+/// Simulated time. Steps run back to back unless the harness is given a speed, so
+/// a scenario spanning hours finishes in milliseconds; the clock only orders the
+/// steps and stamps the timeline.
+#[derive(Debug, Clone)]
+pub struct VirtualClock {
+    start: DateTime<Utc>,
+    elapsed: Duration,
+}
+
+impl VirtualClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            elapsed: Duration::zero(),
+        }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.start + self.elapsed
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Move to `seconds` after the start; the clock never goes backwards
+    pub fn advance_to(&mut self, seconds: u64) {
+        self.elapsed = self.elapsed.max(Duration::seconds(seconds as i64));
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TimelineEntry {
+    pub at: DateTime<Utc>,
+    pub description: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RiskAlert {
+    pub account: String,
+    pub kind: String,
+    pub drawdown_percentage: Decimal,
+    pub message: String,
+}
+
+/// Outcome of a simulation run
+#[derive(Debug, Clone, Default)]
+pub struct SimulationReport {
+    pub scenario: String,
+    pub timeline: Vec<TimelineEntry>,
+    pub executions: Vec<ExecutionResult>,
+    /// Signal id and reason for every signal the orchestrator turned down
+    pub rejected_signals: Vec<(String, String)>,
+    pub positions: BTreeMap<String, Vec<UnifiedPosition>>,
+    pub audit_entries: BTreeMap<String, Vec<AuditEntry>>,
+    pub risk_alerts: Vec<RiskAlert>,
+}
+
+impl SimulationReport {
+    /// Every expectation that does not hold, in one error
+    pub fn verify(&self, expect: &Expectations) -> Result<()> {
+        let mut failures = Vec::new();
+
+        if let Some(expected) = &expect.positions {
+            let actual: Vec<(&String, &UnifiedPosition)> = self
+                .positions
+                .iter()
+                .flat_map(|(account, positions)| positions.iter().map(move |p| (account, p)))
+                .collect();
+            if actual.len() != expected.len() {
+                failures.push(format!(
+                    "expected {} open positions, found {}",
+                    expected.len(),
+                    actual.len()
+                ));
+            }
+            for position in expected {
+                let found = actual.iter().any(|(account, p)| {
+                    **account == position.account
+                        && p.symbol == position.symbol
+                        && p.side == position.side
+                        && position.quantity.map_or(true, |q| p.quantity == q)
+                        && position
+                            .stop_loss
+                            .map_or(true, |sl| p.stop_loss == Some(sl))
+                });
+                if !found {
+                    failures.push(format!("no open position matching {:?}", position));
+                }
+            }
+        }
+
+        let successful = self.executions.iter().filter(|r| r.success).count();
+        if let Some(expected) = expect.successful_executions {
+            if successful != expected {
+                failures.push(format!(
+                    "expected {} successful executions, found {}",
+                    expected, successful
+                ));
+            }
+        }
+        if let Some(expected) = expect.failed_executions {
+            let failed = self.executions.len() - successful;
+            if failed != expected {
+                failures.push(format!(
+                    "expected {} failed executions, found {}",
+                    expected, failed
+                ));
+            }
+        }
+
+        if let Some(expected) = &expect.rejected_signals {
+            let rejected: Vec<&String> = self.rejected_signals.iter().map(|(id, _)| id).collect();
+            if rejected
+                .iter()
+                .map(|id| id.as_str())
+                .ne(expected.iter().map(|id| id.as_str()))
+            {
+                failures.push(format!(
+                    "expected rejected signals {:?}, found {:?}",
+                    expected, rejected
+                ));
+            }
+        }
+
+        for audit in &expect.audit {
+            let count = self
+                .audit_entries
+                .iter()
+                .filter(|(account, _)| audit.account.as_ref().map_or(true, |a| a == *account))
+                .flat_map(|(_, entries)| entries)
+                .filter(|entry| entry.modification_type == audit.modification_type)
+                .count();
+            if count != audit.count {
+                failures.push(format!(
+                    "expected {} {:?} audit entries{}, found {}",
+                    audit.count,
+                    audit.modification_type,
+                    audit
+                        .account
+                        .as_ref()
+                        .map(|a| format!(" for {}", a))
+                        .unwrap_or_default(),
+                    count
+                ));
+            }
+        }
+
+        if let Some(expected) = &expect.risk_alerts {
+            if self.risk_alerts.len() != expected.len() {
+                failures.push(format!(
+                    "expected {} risk alerts, found {:?}",
+                    expected.len(),
+                    self.risk_alerts
+                        .iter()
+                        .map(|a| &a.message)
+                        .collect::<Vec<_>>()
+                ));
+            }
+            for alert in expected {
+                let found = self
+                    .risk_alerts
+                    .iter()
+                    .any(|a| a.account == alert.account && a.kind == alert.kind);
+                if !found {
+                    failures.push(format!(
+                        "no {} drawdown alert for {}",
+                        alert.kind, alert.account
+                    ));
+                }
+            }
+        }
+
+        if !failures.is_empty() {
+            bail!(
+                "Scenario {} failed:\n  {}",
+                self.scenario,
+                failures.join("\n  ")
+            );
+        }
+        Ok(())
+    }
+}
+
+struct AccountUnderTest {
+    id: String,
+    risk_id: Uuid,
+    platform: Arc<ChaosPlatform>,
+    exits: ExitManagementSystem,
+    audit: Arc<InMemoryAuditDatabase>,
+}
+
+enum Step<'a> {
+    Price(&'a PriceStep),
+    News(&'a NewsStep),
+    Signal(&'a SignalStep),
+}
+
+impl Step<'_> {
+    /// Seconds into the scenario, then prices before news before signals at the
+    /// same instant so signals execute against the quote of their moment
+    fn sort_key(&self) -> (u64, u8) {
+        match self {
+            Step::Price(step) => (step.at, 0),
+            Step::News(step) => (step.at, 1),
+            Step::Signal(step) => (step.at, 2),
+        }
+    }
+}
+
+/// Runs a scenario through the orchestrator, exit management and drawdown
+/// tracking against one simulated platform per account
+pub struct SimulationHarness {
+    scenario: SimulationScenario,
+    clock: VirtualClock,
+    orchestrator: TradeExecutionOrchestrator,
+    accounts: Vec<AccountUnderTest>,
+    equity_history: Arc<EquityHistoryManager>,
+    drawdown_alerts: Arc<DrawdownAlertManager>,
+    drawdowns: DrawdownTracker,
+    report: SimulationReport,
+    speed: Option<f64>,
+}
+
+impl SimulationHarness {
+    pub async fn new(scenario: SimulationScenario) -> Result<Self> {
+        let orchestrator = TradeExecutionOrchestrator::new();
+        let equity_history = Arc::new(EquityHistoryManager::new());
+        let drawdown_alerts = Arc::new(DrawdownAlertManager::new());
+        let drawdowns = DrawdownTracker::new(
+            equity_history.clone(),
+            drawdown_alerts.clone(),
+            DrawdownThresholds {
+                daily_threshold: scenario.risk.daily,
+                weekly_threshold: scenario.risk.weekly,
+                max_threshold: scenario.risk.maximum,
+                recovery_factor_threshold: dec!(2),
+                trailing_threshold: None,
+            },
+        );
+
+        let mut accounts = Vec::new();
+        for account in &scenario.accounts {
+            let chaos = account
+                .chaos
+                .clone()
+                .unwrap_or_else(|| ChaosScenario::calm(&account.id));
+            let platform =
+                Arc::new(ChaosPlatform::new(&account.id, chaos).with_balance(account.balance));
+            orchestrator
+                .register_account(
+                    account.id.clone(),
+                    platform.clone(),
+                    account.balance.try_into().unwrap_or(0.0),
+                )
+                .await
+                .map_err(anyhow::Error::msg)
+                .with_context(|| format!("Failed to register {}", account.id))?;
+
+            let audit = Arc::new(InMemoryAuditDatabase::new());
+            let exits = ExitManagementSystem::new(
+                Arc::new(ExitManagementPlatformAdapter::new(platform.clone())),
+                Arc::new(ExitAuditLogger::with_database(audit.clone())),
+            );
+            let risk_id = Uuid::new_v4();
+            equity_history
+                .record_equity(risk_id, account.balance, account.balance)
+                .await?;
+
+            accounts.push(AccountUnderTest {
+                id: account.id.clone(),
+                risk_id,
+                platform,
+                exits,
+                audit,
+            });
+        }
+
+        let start = scenario.start.unwrap_or_else(Utc::now);
+        Ok(Self {
+            report: SimulationReport {
+                scenario: scenario.name.clone(),
+                ..Default::default()
+            },
+            scenario,
+            clock: VirtualClock::new(start),
+            orchestrator,
+            accounts,
+            equity_history,
+            drawdown_alerts,
+            drawdowns,
+            speed: None,
+        })
+    }
+
+    /// Wait out the gaps between steps, sped up `speed` times, instead of running
+    /// them back to back
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = (speed > 0.0).then_some(speed);
+        self
+    }
+
+    pub fn orchestrator(&self) -> &TradeExecutionOrchestrator {
+        &self.orchestrator
+    }
+
+    pub fn platform(&self, account_id: &str) -> Option<Arc<ChaosPlatform>> {
+        self.accounts
+            .iter()
+            .find(|a| a.id == account_id)
+            .map(|a| a.platform.clone())
+    }
+
+    pub fn clock(&self) -> &VirtualClock {
+        &self.clock
+    }
+
+    /// Run every step in time order and collect the outcome
+    pub async fn run(mut self) -> Result<SimulationReport> {
+        let scenario = self.scenario.clone();
+        let mut steps: Vec<Step> = scenario
+            .prices
+            .iter()
+            .map(Step::Price)
+            .chain(scenario.news.iter().map(Step::News))
+            .chain(scenario.signals.iter().map(Step::Signal))
+            .collect();
+        steps.sort_by_key(|step| step.sort_key());
+
+        for step in steps {
+            let at = step.sort_key().0;
+            if let Some(speed) = self.speed {
+                let gap = at.saturating_sub(self.clock.elapsed().num_seconds() as u64);
+                if gap > 0 {
+                    tokio::time::sleep(std::time::Duration::from_secs_f64(gap as f64 / speed))
+                        .await;
+                }
+            }
+            self.clock.advance_to(at);
+            match step {
+                Step::Price(price) => self.apply_price(price).await?,
+                Step::News(news) => self.apply_news(news).await,
+                Step::Signal(signal) => self.apply_signal(signal).await,
+            }
+        }
+
+        self.finish().await
+    }
+
+    async fn apply_price(&mut self, step: &PriceStep) -> Result<()> {
+        self.log(format!("{} {}/{}", step.symbol, step.bid, step.ask));
+        for account in &self.accounts {
+            account.platform.set_quote(&step.symbol, step.bid, step.ask);
+            account.exits.run_position_checks().await;
+            match account.platform.get_account_info().await {
+                Ok(info) => {
+                    self.equity_history
+                        .record_equity(account.risk_id, info.equity, info.balance)
+                        .await?
+                }
+                Err(e) => self.report.timeline.push(TimelineEntry {
+                    at: self.clock.now(),
+                    description: format!("{}: equity unavailable: {}", account.id, e),
+                }),
+            }
+        }
+        Ok(())
+    }
+
+    async fn apply_news(&mut self, step: &NewsStep) {
+        self.log(format!("News {} ({})", step.id, step.currency));
+        let event = NewsEvent {
+            id: step.id.clone(),
+            description: step.description.clone(),
+            currency: step.currency.clone(),
+            impact: step.impact.clone(),
+            time: self.clock.now() + Duration::seconds(step.release_in as i64),
+        };
+        for account in &self.accounts {
+            if let Err(e) = account
+                .exits
+                .get_news_protection()
+                .apply_news_protection(&event)
+                .await
+            {
+                self.report.timeline.push(TimelineEntry {
+                    at: self.clock.now(),
+                    description: format!("{}: news protection failed: {}", account.id, e),
+                });
+            }
+        }
+    }
+
+    /// Plans run immediately: entry delays are virtual time the clock skips over
+    async fn apply_signal(&mut self, step: &SignalStep) {
+        self.log(format!(
+            "Signal {} {:?} {}",
+            step.id, step.side, step.symbol
+        ));
+        let signal = TradeSignal {
+            id: step.id.clone(),
+            symbol: step.symbol.clone(),
+            side: step.side.clone(),
+            entry_price: step.entry_price,
+            stop_loss: step.stop_loss,
+            take_profit: step.take_profit,
+            confidence: step.confidence,
+            risk_reward_ratio: (step.take_profit - step.entry_price).abs()
+                / (step.entry_price - step.stop_loss).abs().max(f64::EPSILON),
+            signal_time: SystemTime::from(self.clock.now()),
+            metadata: step.metadata.clone(),
+        };
+
+        let mut plan = match self.orchestrator.process_signal(signal).await {
+            Ok(plan) => plan,
+            Err(reason) => {
+                self.log(format!("Signal {} rejected: {}", step.id, reason));
+                self.report.rejected_signals.push((step.id.clone(), reason));
+                return;
+            }
+        };
+        for assignment in &mut plan.account_assignments {
+            assignment.entry_timing_delay = std::time::Duration::ZERO;
+        }
+
+        let results = self.orchestrator.execute_plan(&plan).await;
+        for account in &self.accounts {
+            for pending in self
+                .orchestrator
+                .take_pending_exit_policies(&account.id)
+                .await
+            {
+                account.exits.expect_position_policy(pending);
+            }
+        }
+        self.report.executions.extend(results);
+    }
+
+    async fn finish(mut self) -> Result<SimulationReport> {
+        for account in &self.accounts {
+            self.drawdowns.calculate_drawdowns(account.risk_id).await?;
+            for alert in self.drawdown_alerts.get_alerts(account.risk_id) {
+                self.report.risk_alerts.push(RiskAlert {
+                    account: account.id.clone(),
+                    kind: format!("{:?}", alert.alert_type).to_lowercase(),
+                    drawdown_percentage: alert.drawdown_percentage,
+                    message: alert.message,
+                });
+            }
+
+            let positions = account
+                .platform
+                .get_positions()
+                .await
+                .with_context(|| format!("Failed to read final positions of {}", account.id))?;
+            self.report.positions.insert(account.id.clone(), positions);
+
+            let entries = account
+                .audit
+                .get_entries_in_range(TimeRange {
+                    start: DateTime::<Utc>::MIN_UTC,
+                    end: DateTime::<Utc>::MAX_UTC,
+                })
+                .await?;
+            self.report
+                .audit_entries
+                .insert(account.id.clone(), entries);
+        }
+        Ok(self.report)
+    }
+
+    fn log(&mut self, description: String) {
+        self.report.timeline.push(TimelineEntry {
+            at: self.clock.now(),
+            description,
+        });
+    }
+}
+
+/// Load, run and verify a scenario file against its own expectations
+pub async fn run_scenario_file(path: impl AsRef<Path>) -> Result<SimulationReport> {
+    let scenario = SimulationScenario::load(path)?;
+    let expect = scenario.expect.clone();
+    let report = SimulationHarness::new(scenario).await?.run().await?;
+    report.verify(&expect)?;
+    Ok(report)
+}
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use std::time::Duration;
+
+use super::{float, Series, TimeSeriesConfig, TimeSeriesPoint, TimeSeriesWriter};
+
+/// Column list and sort key of each series' table
+fn columns(series: Series) -> (&'static str, &'static str) {
+    match series {
+        Series::Ticks => (
+            "at DateTime64(3, 'UTC'), symbol LowCardinality(String), bid Float64, ask Float64",
+            "(symbol, at)",
+        ),
+        Series::Pnl => (
+            "at DateTime64(3, 'UTC'), account_id LowCardinality(String), \
+             unrealized_pnl Float64, realized_pnl_today Float64, open_positions UInt32",
+            "(account_id, at)",
+        ),
+        Series::Equity => (
+            "at DateTime64(3, 'UTC'), account_id LowCardinality(String), \
+             balance Float64, equity Float64, margin_used Float64",
+            "(account_id, at)",
+        ),
+    }
+}
+
+fn timestamp(at: &DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+}
+
+/// One `JSONEachRow` line
+fn row(point: &TimeSeriesPoint) -> String {
+    let row = match point {
+        TimeSeriesPoint::Tick {
+            at,
+            symbol,
+            bid,
+            ask,
+        } => json!({
+            "at": timestamp(at),
+            "symbol": symbol,
+            "bid": float(*bid),
+            "ask": float(*ask),
+        }),
+        TimeSeriesPoint::Pnl {
+            at,
+            account_id,
+            unrealized_pnl,
+            realized_pnl_today,
+            open_positions,
+        } => json!({
+            "at": timestamp(at),
+            "account_id": account_id,
+            "unrealized_pnl": float(*unrealized_pnl),
+            "realized_pnl_today": float(*realized_pnl_today),
+            "open_positions": open_positions,
+        }),
+        TimeSeriesPoint::Equity {
+            at,
+            account_id,
+            balance,
+            equity,
+            margin_used,
+        } => json!({
+            "at": timestamp(at),
+            "account_id": account_id,
+            "balance": float(*balance),
+            "equity": float(*equity),
+            "margin_used": float(*margin_used),
+        }),
+    };
+    row.to_string()
+}
+
+/// Series in MergeTree tables, created on connect and written with one
+/// `INSERT ... FORMAT JSONEachRow` per series and batch over HTTP
+#[derive(Debug)]
+pub struct ClickHouseWriter {
+    client: reqwest::Client,
+    url: String,
+    table_prefix: String,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+impl ClickHouseWriter {
+    pub async fn connect(config: &TimeSeriesConfig) -> Result<Self> {
+        let writer = Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()?,
+            url: config.url.clone(),
+            table_prefix: config.table_prefix.clone(),
+            username: config.username.clone(),
+            password: config.password.clone(),
+        };
+        for series in Series::ALL {
+            let (columns, order_by) = columns(series);
+            writer
+                .execute(
+                    &format!(
+                        "CREATE TABLE IF NOT EXISTS {} ({}) ENGINE = MergeTree ORDER BY {}",
+                        series.table(&writer.table_prefix),
+                        columns,
+                        order_by
+                    ),
+                    String::new(),
+                )
+                .await
+                .context("Failed to create the time series tables")?;
+        }
+        Ok(writer)
+    }
+
+    async fn execute(&self, query: &str, body: String) -> Result<()> {
+        let mut request = self.client.post(&self.url).query(&[("query", query)]);
+        if let Some(username) = &self.username {
+            request = request.header("X-ClickHouse-User", username);
+        }
+        if let Some(password) = &self.password {
+            request = request.header("X-ClickHouse-Key", password);
+        }
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .context("ClickHouse unreachable")?;
+        let status = response.status();
+        if !status.is_success() {
+            let error = response.text().await.unwrap_or_default();
+            anyhow::bail!("ClickHouse returned {}: {}", status, error.trim());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TimeSeriesWriter for ClickHouseWriter {
+    async fn write(&self, batch: &[TimeSeriesPoint]) -> Result<()> {
+        for series in Series::ALL {
+            let rows: Vec<String> = batch
+                .iter()
+                .filter(|point| point.series() == series)
+                .map(row)
+                .collect();
+            if rows.is_empty() {
+                continue;
+            }
+            self.execute(
+                &format!(
+                    "INSERT INTO {} FORMAT JSONEachRow",
+                    series.table(&self.table_prefix)
+                ),
+                rows.join("\n"),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,497 @@
+// Time series export for Grafana: ticks, per-second P&L and equity snapshots
+// queued without waiting and written to TimescaleDB or ClickHouse in batches,
+// so a slow database costs dropped points rather than a slower engine
+
+pub mod clickhouse;
+#[cfg(feature = "postgres")]
+pub mod timescale;
+
+pub use clickhouse::ClickHouseWriter;
+#[cfg(feature = "postgres")]
+pub use timescale::TimescaleWriter;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex, Notify};
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::dashboard::DashboardAggregator;
+use crate::execution::TradeExecutionOrchestrator;
+use crate::runtime::spawn::spawn_isolated;
+use crate::runtime::supervisor::{ShutdownSignal, Subsystem};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeSeriesBackend {
+    /// Hypertables in a TimescaleDB (Postgres) database. Needs the `postgres` feature.
+    #[default]
+    Timescale,
+    /// MergeTree tables written over ClickHouse's HTTP interface
+    #[serde(rename = "clickhouse")]
+    ClickHouse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeSeriesConfig {
+    pub enabled: bool,
+    pub backend: TimeSeriesBackend,
+    /// Postgres connection string for Timescale, HTTP endpoint for ClickHouse,
+    /// e.g. `http://localhost:8123`
+    pub url: String,
+    /// ClickHouse credentials; Timescale takes them in the connection string
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Put before the `ticks`, `pnl` and `equity` table names; a ClickHouse
+    /// database goes here as `db.`
+    pub table_prefix: String,
+    /// Most points written in one insert per series
+    pub batch_size: usize,
+    /// Longest a point waits in the queue before it is written
+    pub flush_interval_ms: u64,
+    /// Points held while the database is slow or down; more are dropped
+    pub queue_capacity: usize,
+    /// Attempts at a failed insert after the first, with doubling backoff
+    pub max_retries: u32,
+    /// Symbols whose ticks are exported, in unified names
+    pub symbols: Vec<String>,
+    /// Account whose quote stream is exported; unset takes the first
+    /// registered account
+    pub source_account: Option<String>,
+    pub pnl_interval_ms: u64,
+    pub equity_interval_secs: u64,
+}
+
+impl Default for TimeSeriesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: TimeSeriesBackend::Timescale,
+            url: "postgres://localhost/tmt".to_string(),
+            username: None,
+            password: None,
+            table_prefix: "tmt_".to_string(),
+            batch_size: 500,
+            flush_interval_ms: 1000,
+            queue_capacity: 10_000,
+            max_retries: 3,
+            symbols: Vec::new(),
+            source_account: None,
+            pnl_interval_ms: 1000,
+            equity_interval_secs: 60,
+        }
+    }
+}
+
+/// One of the exported series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Series {
+    Ticks,
+    Pnl,
+    Equity,
+}
+
+impl Series {
+    pub const ALL: [Series; 3] = [Series::Ticks, Series::Pnl, Series::Equity];
+
+    pub fn table(&self, prefix: &str) -> String {
+        let name = match self {
+            Series::Ticks => "ticks",
+            Series::Pnl => "pnl",
+            Series::Equity => "equity",
+        };
+        format!("{}{}", prefix, name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeSeriesPoint {
+    Tick {
+        at: DateTime<Utc>,
+        symbol: String,
+        bid: Decimal,
+        ask: Decimal,
+    },
+    Pnl {
+        at: DateTime<Utc>,
+        account_id: String,
+        unrealized_pnl: Decimal,
+        realized_pnl_today: Decimal,
+        open_positions: usize,
+    },
+    Equity {
+        at: DateTime<Utc>,
+        account_id: String,
+        balance: Decimal,
+        equity: Decimal,
+        margin_used: Decimal,
+    },
+}
+
+impl TimeSeriesPoint {
+    pub fn series(&self) -> Series {
+        match self {
+            TimeSeriesPoint::Tick { .. } => Series::Ticks,
+            TimeSeriesPoint::Pnl { .. } => Series::Pnl,
+            TimeSeriesPoint::Equity { .. } => Series::Equity,
+        }
+    }
+}
+
+/// Series columns are floats: dashboards plot them, nothing is settled from them
+pub(crate) fn float(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
+
+/// A time series database, written one batch at a time
+#[async_trait]
+pub trait TimeSeriesWriter: Send + Sync + std::fmt::Debug {
+    async fn write(&self, batch: &[TimeSeriesPoint]) -> Result<()>;
+}
+
+/// The writer `config` points at
+pub async fn connect_writer(config: &TimeSeriesConfig) -> Result<Arc<dyn TimeSeriesWriter>> {
+    match config.backend {
+        TimeSeriesBackend::ClickHouse => Ok(Arc::new(ClickHouseWriter::connect(config).await?)),
+        TimeSeriesBackend::Timescale => {
+            #[cfg(feature = "postgres")]
+            {
+                Ok(Arc::new(TimescaleWriter::connect(config).await?))
+            }
+            #[cfg(not(feature = "postgres"))]
+            {
+                anyhow::bail!(
+                    "The timescale backend needs the engine built with the postgres feature"
+                )
+            }
+        }
+    }
+}
+
+/// Writer for tests; keeps every batch it is given
+#[derive(Debug, Default)]
+pub struct InMemoryTimeSeriesWriter {
+    batches: std::sync::Mutex<Vec<Vec<TimeSeriesPoint>>>,
+}
+
+impl InMemoryTimeSeriesWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn batches(&self) -> Vec<Vec<TimeSeriesPoint>> {
+        self.batches.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl TimeSeriesWriter for InMemoryTimeSeriesWriter {
+    async fn write(&self, batch: &[TimeSeriesPoint]) -> Result<()> {
+        self.batches.lock().unwrap().push(batch.to_vec());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeSeriesStats {
+    pub queued: usize,
+    pub written: u64,
+    /// Points refused with the queue full or given up on after retries
+    pub dropped: u64,
+    pub failed_batches: u64,
+}
+
+/// Bounded queue of points in front of a [`TimeSeriesWriter`]. Offering never
+/// waits: with the queue full the point is dropped, so producers on the hot
+/// path are never held up by the database.
+pub struct TimeSeriesSink {
+    writer: Arc<dyn TimeSeriesWriter>,
+    sender: mpsc::Sender<TimeSeriesPoint>,
+    receiver: Mutex<mpsc::Receiver<TimeSeriesPoint>>,
+    batch_ready: Notify,
+    batch_size: usize,
+    flush_interval: Duration,
+    max_retries: u32,
+    written: AtomicU64,
+    dropped: AtomicU64,
+    failed_batches: AtomicU64,
+}
+
+impl TimeSeriesSink {
+    pub fn new(config: &TimeSeriesConfig, writer: Arc<dyn TimeSeriesWriter>) -> Self {
+        let (sender, receiver) = mpsc::channel(config.queue_capacity.max(1));
+        Self {
+            writer,
+            sender,
+            receiver: Mutex::new(receiver),
+            batch_ready: Notify::new(),
+            batch_size: config.batch_size.max(1),
+            flush_interval: Duration::from_millis(config.flush_interval_ms.max(1)),
+            max_retries: config.max_retries,
+            written: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            failed_batches: AtomicU64::new(0),
+        }
+    }
+
+    /// Queue `point`, returning false when it was dropped
+    pub fn offer(&self, point: TimeSeriesPoint) -> bool {
+        if self.sender.try_send(point).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        if self.queued() >= self.batch_size {
+            self.batch_ready.notify_one();
+        }
+        true
+    }
+
+    fn queued(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    pub fn stats(&self) -> TimeSeriesStats {
+        TimeSeriesStats {
+            queued: self.queued(),
+            written: self.written.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            failed_batches: self.failed_batches.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Write everything queued, in batches of at most `batch_size`, returning
+    /// how many points were written
+    pub async fn flush(&self) -> usize {
+        let mut receiver = self.receiver.lock().await;
+        let mut written = 0;
+        loop {
+            let mut batch = Vec::with_capacity(self.batch_size);
+            while batch.len() < self.batch_size {
+                match receiver.try_recv() {
+                    Ok(point) => batch.push(point),
+                    Err(_) => break,
+                }
+            }
+            if batch.is_empty() {
+                return written;
+            }
+            let full = batch.len() == self.batch_size;
+            written += self.write(&batch).await;
+            if !full {
+                return written;
+            }
+        }
+    }
+
+    /// Write one batch, retrying with backoff; while it retries the queue
+    /// fills and further points are shed at `offer`
+    async fn write(&self, batch: &[TimeSeriesPoint]) -> usize {
+        let mut backoff = Duration::from_millis(200);
+        for attempt in 0..=self.max_retries {
+            match self.writer.write(batch).await {
+                Ok(()) => {
+                    self.written
+                        .fetch_add(batch.len() as u64, Ordering::Relaxed);
+                    return batch.len();
+                }
+                Err(e) if attempt < self.max_retries => {
+                    warn!("Time series insert failed, retrying: {:#}", e);
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => {
+                    warn!(
+                        "Dropping {} time series points after {} attempts: {:#}",
+                        batch.len(),
+                        attempt + 1,
+                        e
+                    );
+                }
+            }
+        }
+        self.failed_batches.fetch_add(1, Ordering::Relaxed);
+        self.dropped
+            .fetch_add(batch.len() as u64, Ordering::Relaxed);
+        0
+    }
+}
+
+#[async_trait]
+impl Subsystem for TimeSeriesSink {
+    fn name(&self) -> &str {
+        "timeseries-sink"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut ticker = interval(self.flush_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut reported_drops = 0;
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = self.batch_ready.notified() => {}
+                _ = shutdown.recv() => {
+                    self.flush().await;
+                    return Ok(());
+                }
+            }
+            self.flush().await;
+
+            let dropped = self.dropped.load(Ordering::Relaxed);
+            if dropped > reported_drops {
+                warn!(
+                    "{} time series points dropped, the database is not keeping up",
+                    dropped - reported_drops
+                );
+                reported_drops = dropped;
+            }
+        }
+    }
+}
+
+/// Feeds the sink: ticks from one account's quote stream, P&L of every account
+/// from the dashboard each `pnl_interval_ms` and balances from the platforms
+/// each `equity_interval_secs`
+pub struct TimeSeriesSampler {
+    orchestrator: Arc<TradeExecutionOrchestrator>,
+    sink: Arc<TimeSeriesSink>,
+    config: TimeSeriesConfig,
+    dashboard: Option<Arc<DashboardAggregator>>,
+}
+
+impl TimeSeriesSampler {
+    pub fn new(
+        orchestrator: Arc<TradeExecutionOrchestrator>,
+        sink: Arc<TimeSeriesSink>,
+        config: TimeSeriesConfig,
+    ) -> Self {
+        Self {
+            orchestrator,
+            sink,
+            config,
+            dashboard: None,
+        }
+    }
+
+    /// Sample P&L from the dashboard's view of accounts; without one no P&L is exported
+    pub fn with_dashboard(mut self, dashboard: Arc<DashboardAggregator>) -> Self {
+        self.dashboard = Some(dashboard);
+        self
+    }
+
+    pub async fn sample_pnl(&self) {
+        let Some(dashboard) = &self.dashboard else {
+            return;
+        };
+        let snapshot = dashboard.snapshot().await;
+        for account in snapshot.accounts {
+            let unrealized_pnl = match &account.risk {
+                Some(risk) => risk.unrealized_pnl,
+                None => account
+                    .positions
+                    .iter()
+                    .map(|p| p.position.unrealized_pnl)
+                    .sum(),
+            };
+            self.sink.offer(TimeSeriesPoint::Pnl {
+                at: snapshot.generated_at,
+                account_id: account.status.account_id,
+                unrealized_pnl,
+                realized_pnl_today: account
+                    .risk
+                    .map_or(Decimal::ZERO, |risk| risk.realized_pnl_today),
+                open_positions: account.positions.len(),
+            });
+        }
+    }
+
+    pub async fn sample_equity(&self) {
+        for (account_id, platform) in self.orchestrator.get_platforms().await {
+            match platform.get_account_info().await {
+                Ok(info) => {
+                    self.sink.offer(TimeSeriesPoint::Equity {
+                        at: Utc::now(),
+                        account_id,
+                        balance: info.balance,
+                        equity: info.equity,
+                        margin_used: info.margin_used,
+                    });
+                }
+                Err(e) => warn!("Failed to sample equity of account {}: {}", account_id, e),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Subsystem for TimeSeriesSampler {
+    fn name(&self) -> &str {
+        "timeseries-sampler"
+    }
+
+    async fn start(&self) -> Result<()> {
+        if self.config.symbols.is_empty() {
+            info!("No time series symbols configured, ticks are not exported");
+            return Ok(());
+        }
+        // One feed only, as for candles and recordings
+        let mut platforms = self.orchestrator.get_platforms().await;
+        platforms.sort_by(|a, b| a.0.cmp(&b.0));
+        let source = platforms.into_iter().find(|(account_id, _)| {
+            self.config
+                .source_account
+                .as_ref()
+                .map_or(true, |source| source == account_id)
+        });
+        let Some((account_id, platform)) = source else {
+            warn!("No account available to export ticks from");
+            return Ok(());
+        };
+
+        let mut quotes = platform
+            .subscribe_market_data(self.config.symbols.clone())
+            .await?;
+        let sink = self.sink.clone();
+        spawn_isolated(format!("timeseries-feed-{}", account_id), async move {
+            while let Some(quote) = quotes.recv().await {
+                sink.offer(TimeSeriesPoint::Tick {
+                    at: quote.timestamp,
+                    symbol: quote.symbol,
+                    bid: quote.bid,
+                    ask: quote.ask,
+                });
+            }
+        });
+        info!(
+            "Exporting ticks of {} symbols from account {}",
+            self.config.symbols.len(),
+            account_id
+        );
+        Ok(())
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        let mut pnl_ticker = interval(Duration::from_millis(self.config.pnl_interval_ms.max(1)));
+        pnl_ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut equity_ticker =
+            interval(Duration::from_secs(self.config.equity_interval_secs.max(1)));
+        equity_ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = pnl_ticker.tick() => self.sample_pnl().await,
+                _ = equity_ticker.tick() => self.sample_equity().await,
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::time::SystemTime;
+use tokio_postgres::{Client, NoTls};
+use tracing::error;
+
+use super::{float, Series, TimeSeriesConfig, TimeSeriesPoint, TimeSeriesWriter};
+use crate::runtime::spawn::spawn_isolated;
+
+/// Column list of each series' table
+fn columns(series: Series) -> &'static str {
+    match series {
+        Series::Ticks => {
+            "at TIMESTAMPTZ NOT NULL, symbol TEXT NOT NULL,
+             bid DOUBLE PRECISION NOT NULL, ask DOUBLE PRECISION NOT NULL"
+        }
+        Series::Pnl => {
+            "at TIMESTAMPTZ NOT NULL, account_id TEXT NOT NULL,
+             unrealized_pnl DOUBLE PRECISION NOT NULL, realized_pnl_today DOUBLE PRECISION NOT NULL,
+             open_positions INTEGER NOT NULL"
+        }
+        Series::Equity => {
+            "at TIMESTAMPTZ NOT NULL, account_id TEXT NOT NULL,
+             balance DOUBLE PRECISION NOT NULL, equity DOUBLE PRECISION NOT NULL,
+             margin_used DOUBLE PRECISION NOT NULL"
+        }
+    }
+}
+
+/// Series in hypertables, created on connect. Each batch is one transaction
+/// with one `INSERT ... SELECT FROM UNNEST` per series, so a retried batch
+/// is never half written.
+#[derive(Debug)]
+pub struct TimescaleWriter {
+    client: tokio::sync::Mutex<Client>,
+    table_prefix: String,
+}
+
+impl TimescaleWriter {
+    pub async fn connect(config: &TimeSeriesConfig) -> Result<Self> {
+        let (client, connection) = tokio_postgres::connect(&config.url, NoTls)
+            .await
+            .context("Failed to connect to the time series database")?;
+        spawn_isolated("timeseries-connection", async move {
+            if let Err(e) = connection.await {
+                error!("Time series database connection closed: {}", e);
+            }
+        });
+        for series in Series::ALL {
+            let table = series.table(&config.table_prefix);
+            client
+                .batch_execute(&format!(
+                    "CREATE TABLE IF NOT EXISTS {table} ({columns});
+                     SELECT create_hypertable('{table}', 'at', if_not_exists => TRUE);",
+                    table = table,
+                    columns = columns(series)
+                ))
+                .await
+                .with_context(|| format!("Failed to create hypertable {}", table))?;
+        }
+        Ok(Self {
+            client: tokio::sync::Mutex::new(client),
+            table_prefix: config.table_prefix.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl TimeSeriesWriter for TimescaleWriter {
+    async fn write(&self, batch: &[TimeSeriesPoint]) -> Result<()> {
+        let mut at: [Vec<SystemTime>; 3] = Default::default();
+        let mut keys: [Vec<String>; 3] = Default::default();
+        let mut values: [[Vec<f64>; 3]; 3] = Default::default();
+        let mut open_positions: Vec<i32> = Vec::new();
+        for point in batch {
+            let (series, time, key, row) = match point {
+                TimeSeriesPoint::Tick {
+                    at,
+                    symbol,
+                    bid,
+                    ask,
+                } => (0, at, symbol, [float(*bid), float(*ask), 0.0]),
+                TimeSeriesPoint::Pnl {
+                    at,
+                    account_id,
+                    unrealized_pnl,
+                    realized_pnl_today,
+                    open_positions: open,
+                } => {
+                    open_positions.push(*open as i32);
+                    (
+                        1,
+                        at,
+                        account_id,
+                        [float(*unrealized_pnl), float(*realized_pnl_today), 0.0],
+                    )
+                }
+                TimeSeriesPoint::Equity {
+                    at,
+                    account_id,
+                    balance,
+                    equity,
+                    margin_used,
+                } => (
+                    2,
+                    at,
+                    account_id,
+                    [float(*balance), float(*equity), float(*margin_used)],
+                ),
+            };
+            at[series].push((*time).into());
+            keys[series].push(key.clone());
+            for (column, value) in row.into_iter().enumerate() {
+                values[series][column].push(value);
+            }
+        }
+
+        let mut client = self.client.lock().await;
+        let transaction = client.transaction().await?;
+        if !at[0].is_empty() {
+            transaction
+                .execute(
+                    &format!(
+                        "INSERT INTO {} (at, symbol, bid, ask)
+                         SELECT * FROM UNNEST($1::timestamptz[], $2::text[], $3::float8[], $4::float8[])",
+                        Series::Ticks.table(&self.table_prefix)
+                    ),
+                    &[&at[0], &keys[0], &values[0][0], &values[0][1]],
+                )
+                .await
+                .context("Failed to insert ticks")?;
+        }
+        if !at[1].is_empty() {
+            transaction
+                .execute(
+                    &format!(
+                        "INSERT INTO {} (at, account_id, unrealized_pnl, realized_pnl_today, open_positions)
+                         SELECT * FROM UNNEST($1::timestamptz[], $2::text[], $3::float8[], $4::float8[], $5::int4[])",
+                        Series::Pnl.table(&self.table_prefix)
+                    ),
+                    &[&at[1], &keys[1], &values[1][0], &values[1][1], &open_positions],
+                )
+                .await
+                .context("Failed to insert P&L")?;
+        }
+        if !at[2].is_empty() {
+            transaction
+                .execute(
+                    &format!(
+                        "INSERT INTO {} (at, account_id, balance, equity, margin_used)
+                         SELECT * FROM UNNEST($1::timestamptz[], $2::text[], $3::float8[], $4::float8[], $5::float8[])",
+                        Series::Equity.table(&self.table_prefix)
+                    ),
+                    &[&at[2], &keys[2], &values[2][0], &values[2][1], &values[2][2]],
+                )
+                .await
+                .context("Failed to insert equity")?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+}
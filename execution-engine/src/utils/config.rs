@@ -2,6 +2,7 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ExecutionConfig {
     pub tradelocker: super::super::platforms::tradelocker::TradeLockerConfig,
     pub vault_endpoint: String,
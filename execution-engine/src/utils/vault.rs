@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use reqwest::{Client, Method, StatusCode};
 use serde_json::Value;
 use thiserror::Error;
 
@@ -6,45 +7,105 @@ use thiserror::Error;
 pub enum VaultError {
     #[error("Connection error: {0}")]
     Connection(String),
-    
+
     #[error("Authentication error: {0}")]
     Auth(String),
-    
+
     #[error("Secret not found: {0}")]
     NotFound(String),
-    
+
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
 }
 
+/// Client of the KV version 2 secrets engine mounted at `secret/`, authenticated
+/// with the token in `VAULT_TOKEN`
 #[derive(Debug)]
 pub struct VaultClient {
-    // Implementation details would go here
     endpoint: String,
+    token: Option<String>,
+    client: Client,
 }
 
 impl VaultClient {
     pub async fn new(endpoint: String) -> Result<Self, VaultError> {
-        Ok(Self { endpoint })
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .map_err(|e| VaultError::Connection(e.to_string()))?;
+
+        Ok(Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            token: std::env::var("VAULT_TOKEN").ok(),
+            client,
+        })
     }
 
-    pub async fn list_secrets(&self, _path: &str) -> Result<HashMap<String, Value>, VaultError> {
-        // Stub implementation - would connect to actual Vault
-        Ok(HashMap::new())
+    async fn request(&self, method: Method, path: &str, body: Option<Value>) -> Result<Option<Value>, VaultError> {
+        let token = self.token.as_deref()
+            .ok_or_else(|| VaultError::Auth("VAULT_TOKEN is not set".to_string()))?;
+
+        let mut request = self.client
+            .request(method, format!("{}/v1/secret/{}", self.endpoint, path))
+            .header("X-Vault-Token", token);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request.send().await
+            .map_err(|e| VaultError::Connection(e.to_string()))?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Err(VaultError::NotFound(path.to_string())),
+            StatusCode::FORBIDDEN => Err(VaultError::PermissionDenied(path.to_string())),
+            StatusCode::UNAUTHORIZED => Err(VaultError::Auth(format!("token rejected for {}", path))),
+            StatusCode::NO_CONTENT => Ok(None),
+            status if status.is_success() => response.json().await
+                .map(Some)
+                .map_err(|e| VaultError::Connection(e.to_string())),
+            status => Err(VaultError::Connection(format!("{} returned {}", path, status))),
+        }
     }
 
-    pub async fn store_secret(&self, _key: &str, _value: Value) -> Result<(), VaultError> {
-        // Stub implementation
+    /// Every secret directly under `path`, by key
+    pub async fn list_secrets(&self, path: &str) -> Result<HashMap<String, Value>, VaultError> {
+        let listing = match self.request(Method::from_bytes(b"LIST").unwrap(), &format!("metadata/{}", path), None).await {
+            Ok(listing) => listing,
+            Err(VaultError::NotFound(_)) => return Ok(HashMap::new()),
+            Err(e) => return Err(e),
+        };
+        let keys: Vec<String> = listing
+            .and_then(|l| l["data"]["keys"].as_array().cloned())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|k| k.as_str().map(str::to_string))
+            .filter(|k| !k.ends_with('/'))
+            .collect();
+
+        let mut secrets = HashMap::new();
+        for key in keys {
+            let secret = self.get_secret(&format!("{}/{}", path, key)).await?;
+            secrets.insert(key, secret);
+        }
+        Ok(secrets)
+    }
+
+    pub async fn store_secret(&self, key: &str, value: Value) -> Result<(), VaultError> {
+        self.request(Method::POST, &format!("data/{}", key), Some(serde_json::json!({ "data": value })))
+            .await?;
         Ok(())
     }
 
     pub async fn get_secret(&self, key: &str) -> Result<Value, VaultError> {
-        // Stub implementation
-        Err(VaultError::NotFound(key.to_string()))
+        self.request(Method::GET, &format!("data/{}", key), None)
+            .await?
+            .map(|secret| secret["data"]["data"].clone())
+            .ok_or_else(|| VaultError::NotFound(key.to_string()))
     }
 
-    pub async fn delete_secret(&self, _key: &str) -> Result<(), VaultError> {
-        // Stub implementation
-        Ok(())
+    pub async fn delete_secret(&self, key: &str) -> Result<(), VaultError> {
+        match self.request(Method::DELETE, &format!("metadata/{}", key), None).await {
+            Ok(_) | Err(VaultError::NotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,453 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use execution_engine::api::status::{StatusPage, StatusPageConfig};
+use execution_engine::api::{self, ApiState};
+use execution_engine::auth::Authenticator;
+use execution_engine::ctl::{parse_args, Command, EngineClient, KillSwitchAction};
+use execution_engine::dashboard::DashboardAggregator;
+use execution_engine::execution::exit_management::ExitAuditLogger;
+use execution_engine::execution::TradeExecutionOrchestrator;
+use execution_engine::journal::TradeJournal;
+use execution_engine::notifications::Notifier;
+use execution_engine::platforms::abstraction::{
+    capabilities::PlatformCapabilities,
+    errors::PlatformError,
+    events::PlatformEvent,
+    interfaces::{DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter},
+    models::*,
+    DryRunConfig, DryRunMode,
+};
+use execution_engine::platforms::PlatformType;
+use execution_engine::runtime::{
+    FeatureFlagConfig, FeatureFlags, HealthChecker, ShutdownConfig, ShutdownCoordinator,
+    Supervisor, SupervisorConfig,
+};
+use execution_engine::testing::signal;
+
+struct MockPlatform {
+    positions: Vec<UnifiedPosition>,
+    orders: Vec<UnifiedOrderResponse>,
+    closed: Mutex<Vec<String>>,
+}
+
+impl MockPlatform {
+    fn new() -> Self {
+        Self {
+            positions: Vec::new(),
+            orders: Vec::new(),
+            closed: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn with_position(mut self, symbol: &str) -> Self {
+        self.positions.push(UnifiedPosition {
+            position_id: format!("{}-{}", symbol, self.positions.len()),
+            symbol: symbol.to_string(),
+            side: UnifiedPositionSide::Long,
+            quantity: dec!(10000),
+            entry_price: dec!(1.0850),
+            current_price: dec!(1.0860),
+            unrealized_pnl: dec!(10),
+            realized_pnl: Decimal::ZERO,
+            margin_used: Decimal::ZERO,
+            commission: Decimal::ZERO,
+            stop_loss: None,
+            take_profit: None,
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            account_id: String::new(),
+            platform_specific: HashMap::new(),
+        });
+        self
+    }
+
+    fn with_order(mut self, order_id: &str, status: UnifiedOrderStatus) -> Self {
+        self.orders.push(order_response(order_id, "EURUSD", status));
+        self
+    }
+
+    fn unsupported<T>() -> Result<T, PlatformError> {
+        Err(PlatformError::FeatureNotSupported {
+            feature: "mock".to_string(),
+        })
+    }
+}
+
+fn order_response(
+    order_id: &str,
+    symbol: &str,
+    status: UnifiedOrderStatus,
+) -> UnifiedOrderResponse {
+    UnifiedOrderResponse {
+        platform_order_id: order_id.to_string(),
+        client_order_id: order_id.to_string(),
+        status,
+        symbol: symbol.to_string(),
+        side: UnifiedOrderSide::Buy,
+        order_type: UnifiedOrderType::Limit,
+        quantity: dec!(10000),
+        filled_quantity: Decimal::ZERO,
+        remaining_quantity: dec!(10000),
+        price: Some(dec!(1.0800)),
+        average_fill_price: None,
+        commission: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        filled_at: None,
+        platform_specific: HashMap::new(),
+    }
+}
+
+#[async_trait]
+impl ITradingPlatform for MockPlatform {
+    fn platform_type(&self) -> PlatformType {
+        PlatformType::DXTrade
+    }
+    fn platform_name(&self) -> &str {
+        "mock"
+    }
+    fn platform_version(&self) -> &str {
+        "1.0.0"
+    }
+    async fn connect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn disconnect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn is_connected(&self) -> bool {
+        true
+    }
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        Ok(1)
+    }
+    async fn place_order(
+        &self,
+        _order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn modify_order(
+        &self,
+        _order_id: &str,
+        _modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn cancel_order(&self, _order_id: &str) -> Result<(), PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_order(&self, _order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_orders(
+        &self,
+        _filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        Ok(self.orders.clone())
+    }
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        Ok(self.positions.clone())
+    }
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        Ok(self.positions.iter().find(|p| p.symbol == symbol).cloned())
+    }
+    async fn close_position(
+        &self,
+        symbol: &str,
+        _quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.closed.lock().unwrap().push(symbol.to_string());
+        Ok(order_response(
+            &format!("close-{}", symbol),
+            symbol,
+            UnifiedOrderStatus::Filled,
+        ))
+    }
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        Ok(UnifiedAccountInfo {
+            account_id: "mock".to_string(),
+            account_name: None,
+            currency: "USD".to_string(),
+            balance: dec!(100000),
+            equity: dec!(100000),
+            margin_used: Decimal::ZERO,
+            margin_available: dec!(100000),
+            buying_power: dec!(100000),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_level: None,
+            account_type: AccountType::Demo,
+            last_updated: Utc::now(),
+            platform_specific: HashMap::new(),
+        })
+    }
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        Ok(dec!(100000))
+    }
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_market_data(&self, _symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        Self::unsupported()
+    }
+    async fn subscribe_market_data(
+        &self,
+        _symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn unsubscribe_market_data(&self, _symbols: Vec<String>) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    fn capabilities(&self) -> PlatformCapabilities {
+        PlatformCapabilities::new("mock".to_string())
+    }
+    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_event_history(
+        &self,
+        _filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        Self::unsupported()
+    }
+}
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_string).collect()
+}
+
+/// Serve the admin API on an ephemeral port and return a client pointed at it
+async fn serve(orchestrator: Arc<TradeExecutionOrchestrator>) -> EngineClient {
+    let router = api::router(ApiState {
+        dashboard: Arc::new(DashboardAggregator::new(orchestrator.clone())),
+        orchestrator,
+        health: Arc::new(HealthChecker::new(
+            Supervisor::new(SupervisorConfig::default()).statuses(),
+        )),
+        shutdown: Arc::new(ShutdownCoordinator::new(ShutdownConfig::default())),
+        journal: Arc::new(TradeJournal::new()),
+        exit_systems: Default::default(),
+        exit_logger: Arc::new(ExitAuditLogger::new()),
+        notifier: Arc::new(Notifier::new()),
+        feature_flags: Arc::new(FeatureFlags::new(FeatureFlagConfig::default())),
+        dry_run: Arc::new(DryRunMode::new(&DryRunConfig::default())),
+        auth: Arc::new(Authenticator::disabled()),
+        status: Arc::new(StatusPage::new(StatusPageConfig::default())),
+        drop_copy: None,
+        ledger: None,
+        signal_quality: None,
+        adoption: None,
+        risk_snapshots: None,
+        statements: None,
+    });
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    EngineClient::new(&format!("http://{}", address)).unwrap()
+}
+
+#[test]
+fn test_parses_commands_and_global_options() {
+    let options = parse_args(args("--url http://engine:9000 --json pause acc-1")).unwrap();
+    assert_eq!(options.url, "http://engine:9000");
+    assert!(options.json);
+    assert_eq!(
+        options.command,
+        Command::Pause {
+            account_id: "acc-1".to_string()
+        }
+    );
+
+    let options = parse_args(args("kill-switch on --reason news")).unwrap();
+    assert_eq!(
+        options.command,
+        Command::KillSwitch {
+            action: KillSwitchAction::Engage,
+            reason: "news".to_string()
+        }
+    );
+
+    let options = parse_args(args("audit --follow --limit 10 --interval 5")).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Audit {
+            limit: 10,
+            follow: true,
+            interval: Duration::from_secs(5)
+        }
+    );
+
+    let options = parse_args(args("positions --account acc-2")).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Positions {
+            account_id: Some("acc-2".to_string())
+        }
+    );
+}
+
+#[test]
+fn test_rejects_invalid_arguments() {
+    let error = parse_args(args("emergency-close --account acc-1")).unwrap_err();
+    assert!(error.contains("--yes"));
+
+    assert!(parse_args(args("pause")).is_err());
+    assert!(parse_args(args("accounts extra")).is_err());
+    assert!(parse_args(args("orders --bogus 1")).is_err());
+    assert!(parse_args(args("kill-switch sideways")).is_err());
+    assert!(parse_args(args("audit --limit many")).is_err());
+    assert!(parse_args(args("frobnicate")).is_err());
+}
+
+#[tokio::test]
+async fn test_client_pauses_and_lists_accounts() {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    orchestrator
+        .register_account("acc-1".to_string(), Arc::new(MockPlatform::new()), 100000.0)
+        .await
+        .unwrap();
+    let client = serve(orchestrator).await;
+
+    let accounts = client.accounts().await.unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert!(accounts[0].is_active);
+
+    let paused = client.pause_account("acc-1").await.unwrap();
+    assert!(!paused.is_active);
+    assert!(!client.account("acc-1").await.unwrap().is_active);
+    assert!(client.resume_account("acc-1").await.unwrap().is_active);
+
+    let error = client.pause_account("missing").await.unwrap_err();
+    assert!(error.to_string().contains("not found"));
+    assert!(client.account("missing").await.is_err());
+}
+
+#[tokio::test]
+async fn test_client_reads_positions_and_working_orders() {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    let platform = MockPlatform::new()
+        .with_position("EURUSD")
+        .with_order("ord-1", UnifiedOrderStatus::New)
+        .with_order("ord-2", UnifiedOrderStatus::Filled)
+        .with_order("ord-3", UnifiedOrderStatus::PartiallyFilled);
+    orchestrator
+        .register_account("acc-1".to_string(), Arc::new(platform), 100000.0)
+        .await
+        .unwrap();
+    let client = serve(orchestrator).await;
+
+    let positions = client.positions(None).await.unwrap();
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].account_id, "acc-1");
+
+    let orders = client.orders(Some("acc-1")).await.unwrap();
+    let ids: Vec<&str> = orders
+        .iter()
+        .map(|o| o.platform_order_id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["ord-1", "ord-3"]);
+
+    assert!(client.positions(Some("missing")).await.is_err());
+}
+
+#[tokio::test]
+async fn test_kill_switch_blocks_signals_and_is_audited() {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    let client = serve(orchestrator.clone()).await;
+
+    assert!(!client.kill_switch().await.unwrap().engaged);
+    let state = client
+        .set_kill_switch(true, "runaway strategy")
+        .await
+        .unwrap();
+    assert!(state.engaged);
+    assert_eq!(state.reason.as_deref(), Some("runaway strategy"));
+
+    let error = orchestrator
+        .process_signal(signal("sig-1"))
+        .await
+        .unwrap_err();
+    assert!(error.contains("Kill switch"));
+
+    assert!(
+        !client
+            .set_kill_switch(false, "resolved")
+            .await
+            .unwrap()
+            .engaged
+    );
+    let actions: Vec<String> = client
+        .executions(10)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.action)
+        .collect();
+    assert_eq!(
+        actions,
+        vec![
+            "CONTROL_KILL_SWITCH",
+            "KILL_SWITCH_ENGAGED",
+            "CONTROL_KILL_SWITCH",
+            "KILL_SWITCH_RELEASED"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_emergency_close_closes_every_position() {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    let first = Arc::new(
+        MockPlatform::new()
+            .with_position("EURUSD")
+            .with_position("GBPUSD"),
+    );
+    let second = Arc::new(MockPlatform::new().with_position("USDJPY"));
+    orchestrator
+        .register_account("acc-1".to_string(), first.clone(), 100000.0)
+        .await
+        .unwrap();
+    orchestrator
+        .register_account("acc-2".to_string(), second.clone(), 100000.0)
+        .await
+        .unwrap();
+    let client = serve(orchestrator).await;
+
+    let results = client
+        .emergency_close(Some("acc-1"), "drill")
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|r| r.success));
+    assert_eq!(*first.closed.lock().unwrap(), vec!["EURUSD", "GBPUSD"]);
+    assert!(second.closed.lock().unwrap().is_empty());
+
+    let results = client.emergency_close(None, "drill").await.unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(*second.closed.lock().unwrap(), vec!["USDJPY"]);
+
+    let history = client.executions(10).await.unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .filter(|e| e.action == "EMERGENCY_CLOSE")
+            .count(),
+        2
+    );
+}
@@ -0,0 +1,385 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use execution_engine::alerting::{
+    Alert, AlertGateway, AlertNotification, AlertOutcome, AlertRoute, AlertSink, AlertingConfig,
+    EmailSink, SinkConfig, WebhookSink,
+};
+use execution_engine::risk::margin_monitor::MarginAlertManager;
+use execution_engine::runtime::EngineConfig;
+use risk_types::{AlertLevel, MarginAlert};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use uuid::Uuid;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[derive(Default)]
+struct RecordingSink(Mutex<Vec<AlertNotification>>);
+
+impl RecordingSink {
+    fn delivered(&self) -> Vec<AlertNotification> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl AlertSink for RecordingSink {
+    async fn deliver(&self, notification: &AlertNotification) -> Result<()> {
+        self.0.lock().unwrap().push(notification.clone());
+        Ok(())
+    }
+}
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, 3, 9, 0, 0).unwrap()
+}
+
+fn alert(key: &str, severity: AlertLevel, minutes: i64) -> Alert {
+    Alert {
+        alert_type: "margin".to_string(),
+        key: key.to_string(),
+        severity,
+        account_id: None,
+        message: format!("{:?} margin on {}", severity, key),
+        raised_at: start() + Duration::minutes(minutes),
+    }
+}
+
+fn gateway(config: AlertingConfig) -> (AlertGateway, Arc<RecordingSink>) {
+    let sink = Arc::new(RecordingSink::default());
+    let gateway = AlertGateway::new(config).with_sink("ops", sink.clone());
+    (gateway, sink)
+}
+
+fn routed(route: AlertRoute) -> AlertingConfig {
+    AlertingConfig {
+        routes: HashMap::from([("default".to_string(), route)]),
+        ..AlertingConfig::default()
+    }
+}
+
+fn to_ops() -> AlertRoute {
+    AlertRoute {
+        sinks: vec!["ops".to_string()],
+        ..AlertRoute::default()
+    }
+}
+
+#[tokio::test]
+async fn repeats_inside_the_dedup_window_are_suppressed() {
+    let (gateway, sink) = gateway(routed(to_ops()));
+
+    assert_eq!(
+        gateway.submit(alert("acc-1", AlertLevel::Warning, 0)).await,
+        AlertOutcome::Delivered
+    );
+    for minute in 1..5 {
+        assert_eq!(
+            gateway
+                .submit(alert("acc-1", AlertLevel::Warning, minute))
+                .await,
+            AlertOutcome::Suppressed
+        );
+    }
+    // A different key is a different condition
+    assert_eq!(
+        gateway.submit(alert("acc-2", AlertLevel::Warning, 1)).await,
+        AlertOutcome::Delivered
+    );
+    // Past the 5 minute window the condition is reported again
+    assert_eq!(
+        gateway.submit(alert("acc-1", AlertLevel::Warning, 5)).await,
+        AlertOutcome::Delivered
+    );
+
+    let delivered = sink.delivered();
+    assert_eq!(delivered.len(), 3);
+    assert_eq!(delivered[2].occurrences, 6);
+}
+
+#[tokio::test]
+async fn higher_severity_bypasses_dedup() {
+    let (gateway, sink) = gateway(routed(to_ops()));
+
+    gateway.submit(alert("acc-1", AlertLevel::Warning, 0)).await;
+    assert_eq!(
+        gateway
+            .submit(alert("acc-1", AlertLevel::Critical, 1))
+            .await,
+        AlertOutcome::Delivered
+    );
+    assert_eq!(
+        gateway.submit(alert("acc-1", AlertLevel::Warning, 2)).await,
+        AlertOutcome::Suppressed
+    );
+
+    let severities: Vec<AlertLevel> = sink.delivered().iter().map(|n| n.alert.severity).collect();
+    assert_eq!(severities, vec![AlertLevel::Warning, AlertLevel::Critical]);
+}
+
+#[tokio::test]
+async fn deliveries_are_rate_limited_per_severity() {
+    let mut config = routed(to_ops());
+    config.rate_limits = HashMap::from([(AlertLevel::Warning, 2)]);
+    let (gateway, sink) = gateway(config);
+
+    let outcomes = [
+        gateway.submit(alert("a", AlertLevel::Warning, 0)).await,
+        gateway.submit(alert("b", AlertLevel::Warning, 0)).await,
+        gateway.submit(alert("c", AlertLevel::Warning, 0)).await,
+    ];
+    assert_eq!(
+        outcomes,
+        [
+            AlertOutcome::Delivered,
+            AlertOutcome::Delivered,
+            AlertOutcome::RateLimited
+        ]
+    );
+    // Severities without a limit are always delivered
+    assert_eq!(
+        gateway.submit(alert("d", AlertLevel::Emergency, 0)).await,
+        AlertOutcome::Delivered
+    );
+
+    // The held back alert goes out once the window has passed
+    let retried = gateway
+        .run_escalations(start() + Duration::seconds(30))
+        .await;
+    assert!(retried.is_empty());
+    gateway.submit(alert("c", AlertLevel::Warning, 1)).await;
+    assert_eq!(sink.delivered().len(), 4);
+    assert_eq!(sink.delivered()[3].alert.key, "c");
+    assert_eq!(sink.delivered()[3].escalation, 0);
+}
+
+#[tokio::test]
+async fn unresolved_alerts_escalate_until_resolved() {
+    let (gateway, sink) = gateway(routed(AlertRoute {
+        escalate_after_minutes: Some(3),
+        dedup_window_secs: Some(600),
+        ..to_ops()
+    }));
+
+    gateway
+        .submit(alert("acc-1", AlertLevel::Critical, 0))
+        .await;
+    for minute in 1..=7 {
+        gateway
+            .submit(alert("acc-1", AlertLevel::Critical, minute))
+            .await;
+        gateway
+            .run_escalations(start() + Duration::minutes(minute))
+            .await;
+    }
+
+    let escalations: Vec<u32> = sink.delivered().iter().map(|n| n.escalation).collect();
+    assert_eq!(escalations, vec![0, 1, 2]);
+    assert!(sink.delivered()[2].title().contains("[ESCALATION 2]"));
+
+    assert!(gateway.resolve("margin", "acc-1"));
+    assert!(gateway.active_alerts().is_empty());
+    gateway
+        .run_escalations(start() + Duration::minutes(20))
+        .await;
+    assert_eq!(sink.delivered().len(), 3);
+}
+
+#[tokio::test]
+async fn alerts_no_longer_raised_are_dropped() {
+    let (gateway, sink) = gateway(routed(AlertRoute {
+        escalate_after_minutes: Some(1),
+        ..to_ops()
+    }));
+
+    gateway.submit(alert("acc-1", AlertLevel::Warning, 0)).await;
+    gateway
+        .run_escalations(start() + Duration::minutes(5))
+        .await;
+
+    assert!(gateway.active_alerts().is_empty());
+    assert_eq!(sink.delivered().len(), 1);
+}
+
+#[tokio::test]
+async fn alerts_below_the_route_threshold_are_not_delivered() {
+    let (gateway, sink) = gateway(routed(AlertRoute {
+        min_severity: AlertLevel::Critical,
+        ..to_ops()
+    }));
+
+    assert_eq!(
+        gateway.submit(alert("acc-1", AlertLevel::Warning, 0)).await,
+        AlertOutcome::Ignored
+    );
+    assert!(sink.delivered().is_empty());
+}
+
+#[test]
+fn alerting_config_parses_from_toml() {
+    let config: EngineConfig = toml::from_str(
+        r#"
+        [alerting]
+        dedup_window_secs = 120
+        rate_limit_window_secs = 60
+        escalation_check_interval_secs = 15
+
+        [alerting.rate_limits]
+        Warning = 3
+        Critical = 10
+
+        [alerting.sinks.ops-slack]
+        type = "slack"
+        webhook_url = "https://hooks.slack.com/services/T000/B000/XXX"
+
+        [alerting.sinks.risk-desk]
+        type = "email"
+        smtp_host = "localhost"
+        from = "engine@example.com"
+        to = ["risk@example.com"]
+
+        [alerting.routes.margin]
+        sinks = ["ops-slack", "risk-desk"]
+        escalate_after_minutes = 15
+        "#,
+    )
+    .unwrap();
+
+    assert!(config.validate().is_ok());
+    assert_eq!(config.alerting.rate_limits[&AlertLevel::Warning], 3);
+    assert!(matches!(
+        config.alerting.sinks["risk-desk"],
+        SinkConfig::Email { smtp_port: 25, .. }
+    ));
+    assert_eq!(
+        config.alerting.routes["margin"].escalate_after_minutes,
+        Some(15)
+    );
+}
+
+#[test]
+fn routes_must_refer_to_configured_sinks() {
+    let config = EngineConfig {
+        alerting: routed(AlertRoute {
+            sinks: vec!["pager".to_string()],
+            ..AlertRoute::default()
+        }),
+        ..EngineConfig::default()
+    };
+
+    assert!(config
+        .validate()
+        .unwrap_err()
+        .contains("unknown sink pager"));
+}
+
+#[tokio::test]
+async fn webhook_sink_posts_the_notification() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/alerts"))
+        .and(header("x-api-key", "secret"))
+        .and(body_partial_json(serde_json::json!({
+            "alert": { "alert_type": "margin", "key": "acc-1" },
+            "escalation": 0
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let sink = WebhookSink::new(
+        format!("{}/alerts", server.uri()),
+        HashMap::from([("x-api-key".to_string(), "secret".to_string())]),
+    )
+    .unwrap();
+    let gateway = AlertGateway::new(routed(to_ops())).with_sink("ops", Arc::new(sink));
+
+    gateway
+        .submit(alert("acc-1", AlertLevel::Critical, 0))
+        .await;
+}
+
+#[tokio::test]
+async fn email_sink_speaks_smtp() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let mut received = Vec::new();
+
+        writer.write_all(b"220 relay ready\r\n").await.unwrap();
+        while let Some(line) = lines.next_line().await.unwrap() {
+            let reply: &[u8] = match line.as_str() {
+                l if l.starts_with("EHLO") => b"250-relay\r\n250 OK\r\n",
+                "DATA" => b"354 go ahead\r\n",
+                "." => b"250 queued\r\n",
+                "QUIT" => b"221 bye\r\n",
+                l if l.starts_with("MAIL") || l.starts_with("RCPT") => b"250 OK\r\n",
+                _ => b"",
+            };
+            received.push(line);
+            writer.write_all(reply).await.unwrap();
+        }
+        received
+    });
+
+    let sink = EmailSink::new(
+        "127.0.0.1".to_string(),
+        port,
+        "engine@example.com".to_string(),
+        vec!["risk@example.com".to_string()],
+    );
+    sink.deliver(&AlertNotification {
+        alert: alert("acc-1", AlertLevel::Emergency, 0),
+        first_raised_at: start(),
+        occurrences: 1,
+        escalation: 0,
+    })
+    .await
+    .unwrap();
+
+    let received = server.await.unwrap();
+    assert!(received.contains(&"RCPT TO:<risk@example.com>".to_string()));
+    assert!(received.contains(&"Subject: [Emergency] margin alert".to_string()));
+}
+
+#[tokio::test]
+async fn margin_alert_manager_forwards_through_the_gateway() {
+    let (gateway, sink) = gateway(routed(to_ops()));
+    let gateway = Arc::new(gateway);
+    let manager = MarginAlertManager::new().with_gateway(gateway.clone());
+    let account_id = Uuid::new_v4();
+
+    for _ in 0..3 {
+        manager
+            .send_warning_alert(MarginAlert {
+                account_id,
+                level: AlertLevel::Warning,
+                margin_level: dec!(140),
+                threshold: dec!(150),
+                message: "Margin level at 140.00%".to_string(),
+                timestamp: Utc::now(),
+            })
+            .await
+            .unwrap();
+    }
+
+    // Delivery happens off the monitoring path
+    for _ in 0..50 {
+        if gateway.active_alerts().first().map(|a| a.occurrences) == Some(3) {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(sink.delivered().len(), 1);
+    assert_eq!(sink.delivered()[0].alert.account_id, Some(account_id));
+
+    manager.resolve(account_id);
+    assert!(gateway.active_alerts().is_empty());
+}
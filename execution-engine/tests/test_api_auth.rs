@@ -0,0 +1,448 @@
+use chrono::Duration;
+use reqwest::StatusCode;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+use execution_engine::api::status::{EngineMode, EngineStatus, StatusPage, StatusPageConfig};
+use execution_engine::api::{self, ApiState};
+use execution_engine::auth::{
+    hash_api_key, ApiKeyConfig, AuthConfig, Authenticator, JwtConfig, Principal, Role,
+    API_KEY_HEADER,
+};
+use execution_engine::ctl::EngineClient;
+use execution_engine::dashboard::DashboardAggregator;
+use execution_engine::execution::exit_management::ExitAuditLogger;
+use execution_engine::execution::{ExecutionAuditEntry, TradeExecutionOrchestrator};
+use execution_engine::journal::TradeJournal;
+use execution_engine::notifications::Notifier;
+use execution_engine::platforms::abstraction::{DryRunConfig, DryRunMode};
+use execution_engine::runtime::{
+    FeatureFlagConfig, FeatureFlags, HealthChecker, ShutdownConfig, ShutdownCoordinator,
+    Supervisor, SupervisorConfig,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+fn key(name: &str, role: Role, accounts: Option<&[&str]>) -> ApiKeyConfig {
+    ApiKeyConfig {
+        name: name.to_string(),
+        key_sha256: hash_api_key(&format!("{}-secret", name)),
+        role,
+        accounts: accounts.map(|ids| ids.iter().map(|id| id.to_string()).collect()),
+    }
+}
+
+fn config() -> AuthConfig {
+    AuthConfig {
+        enabled: true,
+        api_keys: vec![
+            key("dashboard", Role::Viewer, None),
+            key("desk", Role::Operator, None),
+            key("ops", Role::Admin, None),
+            key("tenant", Role::Admin, Some(&["acc-1"])),
+        ],
+        jwt: Some(JwtConfig {
+            secret: "signing-secret".to_string(),
+            issuer: Some("tmt".to_string()),
+            ..Default::default()
+        }),
+    }
+}
+
+struct Server {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl Server {
+    async fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        key: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> reqwest::Response {
+        let mut request = self.http.request(method, format!("{}{}", self.url, path));
+        if let Some(key) = key {
+            request = request.header(API_KEY_HEADER, format!("{}-secret", key));
+        }
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        request.send().await.unwrap()
+    }
+
+    async fn get(&self, path: &str, key: Option<&str>) -> StatusCode {
+        self.request(reqwest::Method::GET, path, key, None)
+            .await
+            .status()
+    }
+
+    async fn post(&self, path: &str, key: &str, body: serde_json::Value) -> StatusCode {
+        self.request(reqwest::Method::POST, path, Some(key), Some(body))
+            .await
+            .status()
+    }
+}
+
+/// Serve the API for two accounts under `config` on an ephemeral port
+async fn serve(config: &AuthConfig) -> Server {
+    serve_with_status(config, StatusPageConfig::default()).await
+}
+
+async fn serve_with_status(config: &AuthConfig, status: StatusPageConfig) -> Server {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    for account_id in ["acc-1", "acc-2"] {
+        let platform = MockTradingPlatform::new(account_id)
+            .with_account_id(account_id)
+            .with_balance(dec!(10000));
+        orchestrator
+            .register_account(account_id.to_string(), Arc::new(platform), 10000.0)
+            .await
+            .unwrap();
+    }
+    let router = api::router(ApiState {
+        dashboard: Arc::new(DashboardAggregator::new(orchestrator.clone())),
+        orchestrator,
+        health: Arc::new(HealthChecker::new(
+            Supervisor::new(SupervisorConfig::default()).statuses(),
+        )),
+        shutdown: Arc::new(ShutdownCoordinator::new(ShutdownConfig::default())),
+        journal: Arc::new(TradeJournal::new()),
+        exit_systems: Default::default(),
+        exit_logger: Arc::new(ExitAuditLogger::new()),
+        notifier: Arc::new(Notifier::new()),
+        feature_flags: Arc::new(FeatureFlags::new(FeatureFlagConfig::default())),
+        dry_run: Arc::new(DryRunMode::new(&DryRunConfig::default())),
+        auth: Arc::new(Authenticator::new(config)),
+        status: Arc::new(StatusPage::new(status)),
+        drop_copy: None,
+        ledger: None,
+        signal_quality: None,
+        adoption: None,
+        risk_snapshots: None,
+        statements: None,
+    });
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    Server {
+        url: format!("http://{}", address),
+        http: reqwest::Client::new(),
+    }
+}
+
+#[tokio::test]
+async fn viewer_reads_but_cannot_act() {
+    let server = serve(&config()).await;
+
+    assert_eq!(server.get("/health/live", None).await, StatusCode::OK);
+    assert_eq!(
+        server.get("/accounts", None).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        server.get("/accounts", Some("intruder")).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        server.get("/dashboard/state", Some("dashboard")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        server
+            .post("/accounts/acc-1/pause", "dashboard", serde_json::json!({}))
+            .await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        server
+            .post("/accounts/acc-1/pause", "desk", serde_json::json!({}))
+            .await,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn only_admins_trigger_emergency_actions() {
+    let server = serve(&config()).await;
+    let close = serde_json::json!({ "reason": "test" });
+
+    assert_eq!(
+        server
+            .post("/admin/emergency-close", "desk", close.clone())
+            .await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        server
+            .post(
+                "/admin/kill-switch",
+                "desk",
+                serde_json::json!({ "engaged": true })
+            )
+            .await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        server.post("/admin/emergency-close", "ops", close).await,
+        StatusCode::OK
+    );
+
+    // The CLI passes its key along
+    let client = EngineClient::new(&server.url)
+        .unwrap()
+        .with_api_key("ops-secret");
+    assert_eq!(client.accounts().await.unwrap().len(), 2);
+    let client = EngineClient::new(&server.url).unwrap();
+    assert!(client.accounts().await.is_err());
+}
+
+#[tokio::test]
+async fn scoped_key_is_confined_to_its_accounts() {
+    let server = serve(&config()).await;
+
+    let accounts: Vec<serde_json::Value> = server
+        .request(reqwest::Method::GET, "/accounts", Some("tenant"), None)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0]["account_id"], "acc-1");
+
+    assert_eq!(
+        server.get("/accounts/acc-1", Some("tenant")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        server.get("/accounts/acc-2", Some("tenant")).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        server.get("/positions", Some("tenant")).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        server
+            .get("/positions?account_id=acc-1", Some("tenant"))
+            .await,
+        StatusCode::OK
+    );
+
+    // Naming its own account does not confine routes that span every account
+    for path in [
+        "/exits?account_id=acc-1",
+        "/dashboard/state?account_id=acc-1",
+        "/dashboard/equity?account_id=acc-1",
+        "/admin/kill-switch?account_id=acc-1",
+    ] {
+        assert_eq!(
+            server.get(path, Some("tenant")).await,
+            StatusCode::FORBIDDEN,
+            "{}",
+            path
+        );
+    }
+    assert_eq!(
+        server
+            .post(
+                "/admin/shutdown?account_id=acc-1",
+                "tenant",
+                serde_json::json!({})
+            )
+            .await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        server.get("/dashboard/state", Some("ops")).await,
+        StatusCode::OK
+    );
+
+    // Closing every account would reach past its own
+    assert_eq!(
+        server
+            .post("/admin/emergency-close", "tenant", serde_json::json!({}))
+            .await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        server
+            .post(
+                "/admin/emergency-close",
+                "tenant",
+                serde_json::json!({ "account_id": "acc-2" })
+            )
+            .await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        server
+            .post(
+                "/admin/emergency-close",
+                "tenant",
+                serde_json::json!({ "account_id": "acc-1" })
+            )
+            .await,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn bearer_tokens_carry_role_and_scope() {
+    let config = config();
+    let server = serve(&config).await;
+    let issuer = Authenticator::new(&config);
+    let bearer = |token: &str| {
+        server
+            .http
+            .get(format!("{}/accounts/acc-2", server.url))
+            .bearer_auth(token)
+    };
+
+    let viewer = Principal {
+        subject: "grafana".to_string(),
+        role: Role::Viewer,
+        accounts: None,
+    };
+    let token = issuer.issue_token(&viewer, Duration::minutes(5)).unwrap();
+    assert_eq!(
+        bearer(&token).send().await.unwrap().status(),
+        StatusCode::OK
+    );
+
+    let scoped = Principal {
+        accounts: Some(vec!["acc-1".to_string()]),
+        ..viewer.clone()
+    };
+    let token = issuer.issue_token(&scoped, Duration::minutes(5)).unwrap();
+    assert_eq!(
+        bearer(&token).send().await.unwrap().status(),
+        StatusCode::FORBIDDEN
+    );
+
+    let expired = issuer.issue_token(&viewer, Duration::minutes(-5)).unwrap();
+    assert_eq!(
+        bearer(&expired).send().await.unwrap().status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let forged = Authenticator::new(&AuthConfig {
+        jwt: Some(JwtConfig {
+            secret: "other-secret".to_string(),
+            issuer: Some("tmt".to_string()),
+            ..Default::default()
+        }),
+        ..config.clone()
+    })
+    .issue_token(&viewer, Duration::minutes(5))
+    .unwrap();
+    assert_eq!(
+        bearer(&forged).send().await.unwrap().status(),
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[test]
+fn an_empty_jwt_secret_is_rejected() {
+    let mut config = config();
+    assert!(config.validate().is_ok());
+
+    config.jwt = Some(JwtConfig::default());
+    assert!(config.validate().is_err());
+    // Nor are tokens signed with it accepted
+    let auth = Authenticator::new(&config);
+    let viewer = Principal {
+        subject: "grafana".to_string(),
+        role: Role::Viewer,
+        accounts: None,
+    };
+    assert!(auth.issue_token(&viewer, Duration::minutes(5)).is_err());
+}
+
+#[tokio::test]
+async fn control_actions_are_audited_with_their_caller() {
+    let server = serve(&config()).await;
+    server
+        .post("/accounts/acc-1/pause", "desk", serde_json::json!({}))
+        .await;
+    server
+        .post("/accounts/acc-9/resume", "ops", serde_json::json!({}))
+        .await;
+    server
+        .post(
+            "/admin/kill-switch",
+            "ops",
+            serde_json::json!({ "engaged": true, "reason": "drill" }),
+        )
+        .await;
+
+    let entries: Vec<ExecutionAuditEntry> = server
+        .request(
+            reqwest::Method::GET,
+            "/executions?tags=control",
+            Some("dashboard"),
+            None,
+        )
+        .await
+        .json()
+        .await
+        .unwrap();
+    let actions: Vec<&str> = entries.iter().map(|e| e.action.as_str()).collect();
+    assert_eq!(
+        actions,
+        [
+            "CONTROL_PAUSE_ACCOUNT",
+            "CONTROL_RESUME_ACCOUNT",
+            "CONTROL_KILL_SWITCH"
+        ]
+    );
+
+    let pause = &entries[0];
+    assert_eq!(pause.account_id, "acc-1");
+    assert_eq!(pause.metadata["actor"], "desk");
+    assert_eq!(pause.metadata["role"], "operator");
+    assert_eq!(pause.metadata["outcome"], "succeeded");
+    assert_eq!(entries[1].metadata["outcome"], "failed");
+    assert_eq!(entries[2].metadata["param.reason"], "drill");
+    assert_eq!(entries[2].metadata["param.engaged"], "true");
+}
+
+#[tokio::test]
+async fn status_page_is_public_cached_and_rate_limited() {
+    let server = serve_with_status(
+        &config(),
+        StatusPageConfig {
+            cache_ttl_ms: 60_000,
+            requests_per_minute: 3,
+        },
+    )
+    .await;
+    let status = || async {
+        server
+            .request(reqwest::Method::GET, "/status", None, None)
+            .await
+    };
+
+    let first: EngineStatus = status().await.json().await.unwrap();
+    assert_eq!(first.mode, EngineMode::Live);
+    assert!(!first.kill_switch_engaged);
+    assert_eq!(first.platforms_total, 2);
+
+    // Served from the cache while it is fresh
+    server
+        .post(
+            "/admin/kill-switch",
+            "ops",
+            serde_json::json!({ "engaged": true }),
+        )
+        .await;
+    let second: EngineStatus = status().await.json().await.unwrap();
+    assert!(!second.kill_switch_engaged);
+    assert_eq!(second.generated_at, first.generated_at);
+
+    assert_eq!(status().await.status(), StatusCode::OK);
+    let limited = status().await;
+    assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(limited.headers().contains_key("retry-after"));
+}
@@ -0,0 +1,166 @@
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use execution_engine::execution::TradeExecutionOrchestrator;
+use execution_engine::platforms::abstraction::quota::with_caller;
+use execution_engine::platforms::abstraction::{
+    ITradingPlatform, PlatformError, QuotaConfig, QuotaManager, QuotaPlatform, QuotaPriority,
+};
+use execution_engine::testing::{signal, MockTradingPlatform};
+
+fn quota(requests_per_window: u32, budgets: &[(&str, f64)], reserve: f64) -> QuotaConfig {
+    QuotaConfig {
+        requests_per_window,
+        window_secs: 60,
+        budgets: budgets
+            .iter()
+            .map(|(caller, share)| (caller.to_string(), *share))
+            .collect(),
+        priorities: HashMap::from([("risk-monitor".to_string(), QuotaPriority::Low)]),
+        reserve,
+        max_wait_ms: 0,
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn subsystems_are_held_to_their_budget_while_orders_go_through() {
+    let manager = Arc::new(QuotaManager::new(quota(
+        20,
+        &[("risk-monitor", 0.2), ("orchestrator", 0.5)],
+        0.0,
+    )));
+    let mock = MockTradingPlatform::new("acc-1").with_quote("EURUSD", dec!(1.1000), dec!(1.1001));
+    let platform = Arc::new(QuotaPlatform::new(Arc::new(mock), manager.clone()));
+
+    // 20% of 20 requests
+    for _ in 0..4 {
+        with_caller("risk-monitor", platform.get_balance())
+            .await
+            .unwrap();
+    }
+    let exhausted = with_caller("risk-monitor", platform.get_balance()).await;
+    assert!(matches!(
+        exhausted,
+        Err(PlatformError::RateLimitExceeded { .. })
+    ));
+
+    // The orchestrator has its own budget
+    let orchestrator = TradeExecutionOrchestrator::new();
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+    let plan = orchestrator.process_signal(signal("sig-1")).await.unwrap();
+    let results = orchestrator.execute_plan(&plan).await;
+    assert!(results[0].success, "{:?}", results[0].error_message);
+
+    let limits = manager.api_limits();
+    assert_eq!(limits["quota.used.risk-monitor"], "4");
+    assert_eq!(limits["quota.budget.risk-monitor"], "4");
+    assert!(limits["quota.used.orchestrator"].parse::<u32>().unwrap() >= 1);
+
+    // Budgets refill with the window
+    tokio::time::advance(Duration::from_secs(60)).await;
+    with_caller("risk-monitor", platform.get_balance())
+        .await
+        .unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn low_priority_calls_queue_when_the_quota_runs_low() {
+    let manager = Arc::new(QuotaManager::new(QuotaConfig {
+        max_wait_ms: 120_000,
+        ..quota(10, &[], 0.2)
+    }));
+    for _ in 0..6 {
+        manager
+            .acquire("orchestrator", QuotaPriority::High)
+            .await
+            .unwrap();
+    }
+
+    // Four requests are left: low-priority reads keep clear of twice the reserve
+    let queued = {
+        let manager = manager.clone();
+        tokio::spawn(async move { manager.acquire("risk-monitor", QuotaPriority::Low).await })
+    };
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(!queued.is_finished());
+    assert_eq!(manager.api_limits()["quota.queued"], "1");
+
+    // Normal reads may still dip down to the reserve, order changes below it
+    manager
+        .acquire("exit-management", QuotaPriority::Normal)
+        .await
+        .unwrap();
+    manager
+        .acquire("exit-management", QuotaPriority::Normal)
+        .await
+        .unwrap();
+    let mut waiting = Box::pin(manager.acquire("exit-management", QuotaPriority::Normal));
+    assert!(
+        tokio::time::timeout(Duration::from_millis(10), &mut waiting)
+            .await
+            .is_err()
+    );
+    drop(waiting);
+    manager
+        .acquire("orchestrator", QuotaPriority::High)
+        .await
+        .unwrap();
+
+    // The queued call runs once the window resets
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    queued.await.unwrap().unwrap();
+    let limits = manager.api_limits();
+    assert_eq!(limits["quota.queued"], "0");
+    assert_eq!(limits["quota.used.risk-monitor"], "1");
+}
+
+#[tokio::test(start_paused = true)]
+async fn rate_limit_headers_override_the_local_count() {
+    let manager = Arc::new(QuotaManager::new(quota(100, &[], 0.0)));
+    let platform = QuotaPlatform::new(Arc::new(MockTradingPlatform::new("acc-1")), manager.clone());
+
+    manager.observe_headers([
+        ("X-RateLimit-Limit", "100"),
+        ("X-RateLimit-Remaining", "2"),
+        ("X-RateLimit-Reset", "30"),
+    ]);
+    platform.get_positions().await.unwrap();
+    platform.get_positions().await.unwrap();
+    assert!(matches!(
+        platform.get_positions().await,
+        Err(PlatformError::RateLimitExceeded { retry_after_ms }) if retry_after_ms == 30_000
+    ));
+
+    let diagnostics = platform.get_diagnostics().await.unwrap();
+    assert_eq!(diagnostics.api_limits["quota.platform_remaining"], "0");
+    assert_eq!(diagnostics.api_limits["quota.remaining"], "98");
+    assert_eq!(diagnostics.api_limits["quota.used.unscoped"], "2");
+
+    // The platform's reset frees the quota before the local window ends
+    tokio::time::advance(Duration::from_secs(30)).await;
+    platform.get_positions().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn unusable_reset_headers_fall_back_to_the_local_window() {
+    for reset in ["inf", "NaN", "1e300", "soon"] {
+        let manager = Arc::new(QuotaManager::new(quota(100, &[], 0.0)));
+        let platform =
+            QuotaPlatform::new(Arc::new(MockTradingPlatform::new("acc-1")), manager.clone());
+
+        manager.observe_headers([("X-RateLimit-Remaining", "0"), ("X-RateLimit-Reset", reset)]);
+        assert!(
+            matches!(
+                platform.get_positions().await,
+                Err(PlatformError::RateLimitExceeded { retry_after_ms }) if retry_after_ms == 60_000
+            ),
+            "reset {}",
+            reset
+        );
+    }
+}
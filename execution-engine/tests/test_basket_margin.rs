@@ -0,0 +1,188 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::execution::orchestrator::{
+    AccountAssignment, ExecutionPlan, TradeExecutionOrchestrator, TradeSignal,
+};
+use execution_engine::platforms::abstraction::models::{UnifiedOrderSide, UnifiedPositionSide};
+use execution_engine::platforms::abstraction::UnifiedPosition;
+use execution_engine::risk::{AccountMarginState, MarginCalculator};
+use execution_engine::testing::MockTradingPlatform;
+
+fn assignment(account_id: &str, position_size: f64) -> AccountAssignment {
+    AccountAssignment {
+        account_id: account_id.to_string(),
+        position_size,
+        entry_timing_delay: std::time::Duration::ZERO,
+        priority: 1,
+        risk_per_unit: 0.01,
+    }
+}
+
+fn plan(assignments: Vec<AccountAssignment>) -> ExecutionPlan {
+    ExecutionPlan {
+        signal_id: "sig-1".to_string(),
+        symbol: "EUR_USD".to_string(),
+        account_assignments: assignments,
+        timing_variance: HashMap::new(),
+        size_variance: HashMap::new(),
+        rationale: "basket".to_string(),
+        exit_policy: None,
+        tags: Vec::new(),
+        entry_ladder: None,
+    }
+}
+
+fn state(
+    account_id: &str,
+    used_margin: Decimal,
+    open: Decimal,
+    hedging: bool,
+) -> AccountMarginState {
+    AccountMarginState {
+        account_id: account_id.to_string(),
+        equity: dec!(10000),
+        used_margin,
+        net_positions: HashMap::from([("EURUSD".to_string(), open)]),
+        hedging,
+    }
+}
+
+fn short_eurusd(quantity: Decimal) -> UnifiedPosition {
+    UnifiedPosition {
+        position_id: "pos-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedPositionSide::Short,
+        quantity,
+        entry_price: dec!(1.1),
+        current_price: dec!(1.1),
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: "acc-1".to_string(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+#[test]
+fn a_basket_is_netted_per_account_against_open_positions() {
+    let calculator = MarginCalculator::new();
+    let accounts = vec![
+        // Short 20000, so the basket's 30000 long nets to 10000 long
+        state("acc-1", dec!(220), dec!(-20000), false),
+        // The same position held side by side with the new one
+        state("acc-2", dec!(220), dec!(-20000), true),
+        state("acc-3", dec!(0), dec!(0), false),
+    ];
+    let basket = plan(vec![
+        assignment("acc-1", 10000.0),
+        assignment("acc-2", 30000.0),
+        assignment("acc-3", 100000.0),
+        assignment("acc-1", 20000.0),
+        assignment("acc-4", 10000.0),
+    ]);
+
+    let impact = calculator.calculate_basket_margin_impact(
+        &basket,
+        &UnifiedOrderSide::Buy,
+        dec!(1.1),
+        &accounts,
+        dec!(1000),
+    );
+
+    // EURUSD at 100:1, so 1000 units take 11 of margin
+    let netted = impact.account("acc-1").unwrap();
+    assert_eq!(netted.additional_margin_required, dec!(-110));
+    assert_eq!(
+        netted.projected_margin_level,
+        Some(dec!(10000) / dec!(110) * dec!(100))
+    );
+    assert!(netted.impact_acceptable);
+
+    let hedged = impact.account("acc-2").unwrap();
+    assert_eq!(hedged.additional_margin_required, dec!(330));
+    assert_eq!(hedged.remaining_free_margin, dec!(9450));
+    assert!(hedged.impact_acceptable);
+
+    let flat = impact.account("acc-3").unwrap();
+    assert_eq!(flat.current_margin_level, None);
+    assert_eq!(flat.additional_margin_required, dec!(1100));
+    assert!(!flat.impact_acceptable);
+
+    // Accounts without a known state are left out
+    assert!(impact.account("acc-4").is_none());
+    assert_eq!(impact.total_additional_margin, dec!(1320));
+}
+
+#[tokio::test]
+async fn the_gate_drops_accounts_the_basket_would_take_below_the_margin_level() {
+    let orchestrator = TradeExecutionOrchestrator::new()
+        .with_margin_gate(Arc::new(MarginCalculator::new()), dec!(20000));
+    let platforms = [
+        (
+            "acc-1",
+            MockTradingPlatform::new("acc-1")
+                .with_balance(dec!(10000))
+                .with_position(short_eurusd(dec!(50000))),
+        ),
+        (
+            "acc-2",
+            MockTradingPlatform::new("acc-2").with_balance(dec!(10000)),
+        ),
+    ];
+    for (account_id, platform) in platforms {
+        orchestrator
+            .register_account(account_id.to_string(), Arc::new(platform), 10000.0)
+            .await
+            .unwrap();
+    }
+    let signal = TradeSignal {
+        id: "sig-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Buy,
+        entry_price: 1.1,
+        stop_loss: 1.09,
+        take_profit: 1.12,
+        confidence: 0.8,
+        risk_reward_ratio: 2.0,
+        signal_time: std::time::SystemTime::now(),
+        metadata: HashMap::new(),
+    };
+
+    // Buying against acc-1's short frees margin; acc-2 would go below 20000%
+    let preview = orchestrator.preview_signal(signal.clone()).await;
+    let notes = |account_id: &str| {
+        preview
+            .accounts
+            .iter()
+            .find(|a| a.account_id == account_id)
+            .unwrap()
+            .adjustments
+            .clone()
+    };
+    assert!(notes("acc-1")
+        .iter()
+        .any(|note| note.starts_with("margin level unused -> unused for -")));
+    assert!(notes("acc-2")
+        .iter()
+        .any(|note| note.ends_with("below the 20000.0% required")));
+
+    let plan = orchestrator.process_signal(signal).await.unwrap();
+    let accounts: Vec<&str> = plan
+        .account_assignments
+        .iter()
+        .map(|a| a.account_id.as_str())
+        .collect();
+    assert_eq!(accounts, vec!["acc-1"]);
+    let history = orchestrator.get_execution_history(10).await;
+    assert!(history.iter().any(|e| e.action == "MARGIN_GATE_REFUSED"
+        && e.decision_rationale == "Margin level would fall below 20000.0% on acc-2"));
+}
@@ -0,0 +1,245 @@
+use prometheus::core::Collector;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use execution_engine::platforms::abstraction::events::{CustomEventData, EventData, EventType};
+use execution_engine::platforms::abstraction::{PlatformEvent, UnifiedEventBus};
+use execution_engine::platforms::PlatformType;
+use execution_engine::runtime::channel::{CHANNEL_DEPTH, CHANNEL_DROPPED};
+use execution_engine::runtime::{bounded, OverflowPolicy};
+
+fn event(event_type: EventType, name: &str) -> PlatformEvent {
+    PlatformEvent::new(
+        event_type,
+        PlatformType::DXTrade,
+        "acct-1".to_string(),
+        EventData::Custom(CustomEventData {
+            event_name: name.to_string(),
+            payload: HashMap::new(),
+        }),
+    )
+}
+
+/// Depth reported for every open channel named `name`, by instance
+fn depths(name: &str) -> Vec<(String, i64)> {
+    let mut depths: Vec<_> = CHANNEL_DEPTH
+        .collect()
+        .iter()
+        .flat_map(|family| family.get_metric())
+        .filter(|metric| metric.get_label().iter().any(|l| l.get_value() == name))
+        .map(|metric| {
+            let instance = metric
+                .get_label()
+                .iter()
+                .find(|l| l.get_name() == "instance")
+                .unwrap()
+                .get_value()
+                .to_string();
+            (instance, metric.get_gauge().get_value() as i64)
+        })
+        .collect();
+    depths.sort();
+    depths
+}
+
+fn event_name(event: &PlatformEvent) -> &str {
+    match &event.data {
+        EventData::Custom(data) => &data.event_name,
+        _ => unreachable!(),
+    }
+}
+
+#[tokio::test]
+async fn test_block_policy_waits_for_room() {
+    let (tx, mut rx) = bounded("test-block", 2, OverflowPolicy::Block);
+    tx.send(1).await.unwrap();
+    tx.send(2).await.unwrap();
+
+    let blocked = tokio::spawn(async move {
+        tx.send(3).await.unwrap();
+        tx
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(!blocked.is_finished());
+    assert_eq!(rx.len(), 2);
+
+    assert_eq!(rx.recv().await, Some(1));
+    let tx = tokio::time::timeout(Duration::from_secs(1), blocked)
+        .await
+        .expect("send should complete once there is room")
+        .unwrap();
+
+    let stats = tx.stats();
+    assert_eq!(stats.sent, 3);
+    assert_eq!(stats.dropped, 0);
+    assert_eq!(stats.blocked, 1);
+    assert_eq!(stats.max_depth, 2);
+
+    drop(tx);
+    assert_eq!(rx.recv().await, Some(2));
+    assert_eq!(rx.recv().await, Some(3));
+    assert_eq!(rx.recv().await, None);
+}
+
+#[tokio::test]
+async fn test_drop_oldest_only_evicts_droppable_items() {
+    let (tx, mut rx) = bounded("test-drop-oldest", 3, OverflowPolicy::DropOldest);
+    tx.send_with("fill", OverflowPolicy::Block).await.unwrap();
+    tx.send("quote-1").await.unwrap();
+    tx.send("quote-2").await.unwrap();
+
+    // The order fill survives; the oldest quote makes room
+    tx.send("quote-3").await.unwrap();
+    assert_eq!(rx.try_recv(), Some("fill"));
+    assert_eq!(rx.try_recv(), Some("quote-2"));
+    assert_eq!(rx.try_recv(), Some("quote-3"));
+    assert!(rx.is_empty());
+
+    // With nothing evictable queued, the new quote is the one dropped
+    for fill in ["fill-1", "fill-2", "fill-3"] {
+        tx.send_with(fill, OverflowPolicy::Block).await.unwrap();
+    }
+    tx.send("quote-4").await.unwrap();
+    assert_eq!(rx.len(), 3);
+    assert_eq!(rx.try_recv(), Some("fill-1"));
+
+    assert_eq!(tx.stats().dropped, 2);
+    assert_eq!(
+        CHANNEL_DROPPED
+            .with_label_values(&["test-drop-oldest", &tx.stats().instance])
+            .get(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_channels_sharing_a_name_report_their_own_depth() {
+    let (first, first_rx) = bounded("test-per-account", 4, OverflowPolicy::Block);
+    let (second, second_rx) = bounded("test-per-account", 4, OverflowPolicy::Block);
+    first.send(1).await.unwrap();
+    first.send(2).await.unwrap();
+    second.send(3).await.unwrap();
+
+    let (first_instance, second_instance) = (first.stats().instance, second.stats().instance);
+    assert_ne!(first_instance, second_instance);
+    assert_eq!(depths("test-per-account"), {
+        let mut depths = vec![(first_instance.clone(), 2), (second_instance, 1)];
+        depths.sort();
+        depths
+    });
+
+    // A closed channel stops being reported
+    drop((second, second_rx));
+    assert_eq!(depths("test-per-account"), vec![(first_instance, 2)]);
+    drop((first, first_rx));
+}
+
+#[tokio::test]
+async fn test_closed_channels_release_their_series_and_instance() {
+    let (tx, rx) = bounded("test-reopened", 1, OverflowPolicy::DropNewest);
+    tx.send(1).await.unwrap();
+    tx.send(2).await.unwrap();
+    let instance = tx.stats().instance;
+    drop((tx, rx));
+
+    let dropped_series = CHANNEL_DROPPED
+        .collect()
+        .iter()
+        .flat_map(|family| family.get_metric())
+        .filter(|metric| {
+            metric
+                .get_label()
+                .iter()
+                .any(|l| l.get_value() == "test-reopened")
+        })
+        .count();
+    assert_eq!(dropped_series, 0);
+    assert!(depths("test-reopened").is_empty());
+
+    // The next channel under the name takes over the label instead of adding one
+    let (tx, _rx) = bounded::<u8>("test-reopened", 1, OverflowPolicy::DropNewest);
+    assert_eq!(tx.stats().instance, instance);
+}
+
+#[tokio::test]
+async fn test_drop_newest_keeps_the_queue() {
+    let (tx, mut rx) = bounded("test-drop-newest", 1, OverflowPolicy::DropNewest);
+    tx.send(1).await.unwrap();
+    tx.send(2).await.unwrap();
+    assert_eq!(rx.try_recv(), Some(1));
+    assert_eq!(rx.try_recv(), None);
+    assert_eq!(rx.stats().dropped, 1);
+}
+
+#[tokio::test]
+async fn test_send_fails_once_receiver_is_gone() {
+    let (tx, rx) = bounded("test-closed", 1, OverflowPolicy::Block);
+    tx.send(1).await.unwrap();
+
+    let waiting = tx.clone();
+    let blocked = tokio::spawn(async move { waiting.send(2).await });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    drop(rx);
+    assert!(tx.is_closed());
+    assert_eq!(blocked.await.unwrap(), Err(2));
+    assert_eq!(tx.send(3).await, Err(3));
+    assert_eq!(tx.stats().depth, 0);
+}
+
+#[tokio::test]
+async fn test_event_bus_drops_market_data_but_keeps_order_events() {
+    let mut bus = UnifiedEventBus::new().with_subscriber_capacity(2);
+    let mut rx = bus.subscribe();
+
+    bus.publish(event(EventType::MarketDataUpdate, "tick-1"))
+        .await;
+    bus.publish(event(EventType::OrderFilled, "fill-1")).await;
+    bus.publish(event(EventType::MarketDataUpdate, "tick-2"))
+        .await;
+
+    let stats = bus.queue_stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].depth, 2);
+    assert_eq!(stats[0].dropped, 1);
+
+    let received: Vec<String> = std::iter::from_fn(|| rx.try_recv())
+        .map(|event| event_name(&event).to_string())
+        .collect();
+    assert_eq!(received, vec!["fill-1", "tick-2"]);
+
+    // A full queue holds up order events until the subscriber catches up
+    bus.publish(event(EventType::OrderFilled, "fill-2")).await;
+    bus.publish(event(EventType::OrderFilled, "fill-3")).await;
+    let publish = bus.publish(event(EventType::OrderRejected, "reject-1"));
+    tokio::pin!(publish);
+    assert!(
+        tokio::time::timeout(Duration::from_millis(20), publish.as_mut())
+            .await
+            .is_err()
+    );
+    assert_eq!(event_name(&rx.recv().await.unwrap()), "fill-2");
+    publish.await;
+    assert_eq!(bus.queue_stats()[0].dropped, 1);
+    assert_eq!(rx.len(), 2);
+}
+
+#[test]
+fn test_overflow_policy_by_event_type() {
+    assert_eq!(
+        EventType::MarketDataUpdate.overflow_policy(),
+        OverflowPolicy::DropOldest
+    );
+    assert_eq!(
+        EventType::AccountEquityUpdate.overflow_policy(),
+        OverflowPolicy::DropOldest
+    );
+    assert_eq!(
+        EventType::OrderPlaced.overflow_policy(),
+        OverflowPolicy::Block
+    );
+    assert_eq!(
+        EventType::PositionClosed.overflow_policy(),
+        OverflowPolicy::Block
+    );
+}
@@ -0,0 +1,213 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use rust_decimal_macros::dec;
+use std::sync::{Arc, Mutex};
+
+use execution_engine::execution::exit_management::{
+    BreakEvenConfig, BreakEvenManager, ClosePositionRequest, ClosePositionResult, ExitAuditLogger,
+    ExitModificationType, MarketData, OrderModifyRequest, OrderModifyResult, PartialCloseRequest,
+    Position, TradingPlatform,
+};
+use execution_engine::platforms::abstraction::QuantityLimits;
+use execution_engine::testing::long_position;
+
+/// Records the order of platform calls so tests can check the stop moves before the partial
+#[derive(Debug, Default)]
+struct MockPlatform {
+    positions: Mutex<Vec<Position>>,
+    calls: Mutex<Vec<String>>,
+    reject_modify: bool,
+    fail_partial: bool,
+    quantity_limits: QuantityLimits,
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: dec!(1.10595),
+            ask: dec!(1.10605),
+            spread: dec!(0.0001),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("modify {:.4}", request.new_stop_loss.unwrap()));
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: !self.reject_modify,
+            message: if self.reject_modify {
+                "stop too close".to_string()
+            } else {
+                "modified".to_string()
+            },
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        Err(anyhow!("unexpected full close of {}", request.position_id))
+    }
+
+    fn quantity_limits(&self, _symbol: &str) -> QuantityLimits {
+        self.quantity_limits
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("partial {}", request.volume));
+        if self.fail_partial {
+            return Err(anyhow!("market closed"));
+        }
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: dec!(1.1060),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+}
+
+fn manager(platform: MockPlatform) -> (Arc<MockPlatform>, Arc<ExitAuditLogger>, BreakEvenManager) {
+    manager_closing(platform, 0.5)
+}
+
+fn manager_closing(
+    platform: MockPlatform,
+    partial_close_percent: f64,
+) -> (Arc<MockPlatform>, Arc<ExitAuditLogger>, BreakEvenManager) {
+    let position = long_position();
+    platform.positions.lock().unwrap().push(position);
+    let platform = Arc::new(platform);
+    let logger = Arc::new(ExitAuditLogger::new());
+    let mut manager = BreakEvenManager::new(platform.clone(), logger.clone());
+    manager.configure_symbol(
+        "EURUSD".to_string(),
+        BreakEvenConfig {
+            partial_close_percent: Some(partial_close_percent),
+            ..Default::default()
+        },
+    );
+    (platform, logger, manager)
+}
+
+#[tokio::test]
+async fn test_stop_moves_before_partial_close_and_both_are_audited() {
+    let (platform, logger, manager) = manager(MockPlatform::default());
+
+    manager.check_break_even_triggers().await.unwrap();
+
+    let position = platform.positions.lock().unwrap()[0].clone();
+    assert!(manager.is_break_even_active(position.id));
+    assert_eq!(
+        *platform.calls.lock().unwrap(),
+        vec!["modify 1.1005".to_string(), "partial 5000.0".to_string()]
+    );
+
+    let entries = logger
+        .get_exits_by_type(ExitModificationType::BreakEven, None)
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|e| e.position_id == position.id));
+    assert!(entries
+        .iter()
+        .any(|e| e.reasoning.starts_with("Break-even partial close")));
+
+    // Already at break-even: nothing happens on the next pass
+    manager.check_break_even_triggers().await.unwrap();
+    assert_eq!(platform.calls.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_partial_close_is_rounded_down_to_the_lot_step() {
+    let lots = QuantityLimits::new(dec!(1000), None, dec!(1000));
+    let (platform, _, manager) = manager_closing(
+        MockPlatform {
+            quantity_limits: lots,
+            ..Default::default()
+        },
+        0.35,
+    );
+    let position = platform.positions.lock().unwrap()[0].clone();
+    let preview = manager
+        .preview_break_even(&position)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(preview.close_volume, Some(dec!(3000)));
+
+    manager.check_break_even_triggers().await.unwrap();
+    assert_eq!(
+        *platform.calls.lock().unwrap(),
+        vec!["modify 1.1005".to_string(), "partial 3000".to_string()]
+    );
+
+    // Below the smallest lot nothing is closed, but the stop still moves
+    let (platform, _, manager) = manager_closing(
+        MockPlatform {
+            quantity_limits: lots,
+            ..Default::default()
+        },
+        0.05,
+    );
+    manager.check_break_even_triggers().await.unwrap();
+    assert_eq!(
+        *platform.calls.lock().unwrap(),
+        vec!["modify 1.1005".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn test_rejected_stop_skips_partial_close() {
+    let (platform, logger, manager) = manager(MockPlatform {
+        reject_modify: true,
+        ..Default::default()
+    });
+    let position_id = platform.positions.lock().unwrap()[0].id;
+
+    let error = manager.force_break_even(position_id).await.unwrap_err();
+    assert!(error.to_string().contains("stop too close"));
+    assert!(!manager.is_break_even_active(position_id));
+    assert_eq!(platform.calls.lock().unwrap().len(), 1);
+    assert!(logger
+        .get_exits_by_type(ExitModificationType::BreakEven, None)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_failed_partial_leaves_position_protected_at_break_even() {
+    let (platform, logger, manager) = manager(MockPlatform {
+        fail_partial: true,
+        ..Default::default()
+    });
+    let position_id = platform.positions.lock().unwrap()[0].id;
+
+    let error = manager.force_break_even(position_id).await.unwrap_err();
+    assert!(format!("{:#}", error).contains("market closed"));
+    assert!(manager.is_break_even_active(position_id));
+
+    // Only the stop move is in the audit trail, and the partial is not retried
+    let entries = logger
+        .get_exits_by_type(ExitModificationType::BreakEven, None)
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    manager.check_break_even_triggers().await.unwrap();
+    assert_eq!(platform.calls.lock().unwrap().len(), 2);
+}
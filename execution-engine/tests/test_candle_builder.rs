@@ -0,0 +1,160 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+use execution_engine::dashboard::DashboardAggregator;
+use execution_engine::execution::TradeExecutionOrchestrator;
+use execution_engine::market_data::{CandleBuilder, CandleConfig, SwingKind, Timeframe};
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, 3, 9, 0, 0).unwrap()
+}
+
+fn builder(timeframes: &[Timeframe]) -> CandleBuilder {
+    CandleBuilder::new(CandleConfig {
+        timeframes: timeframes.to_vec(),
+        ..CandleConfig::default()
+    })
+}
+
+/// One candle per minute with the given high and low, opening and closing mid-range
+fn minute_candles(builder: &CandleBuilder, ranges: &[(Decimal, Decimal)]) {
+    for (minute, (high, low)) in ranges.iter().enumerate() {
+        let open = start() + Duration::minutes(minute as i64);
+        let mid = (high + low) / Decimal::TWO;
+        builder.on_tick("EURUSD", mid, Decimal::ZERO, open);
+        builder.on_tick("EURUSD", *high, Decimal::ZERO, open + Duration::seconds(10));
+        builder.on_tick("EURUSD", *low, Decimal::ZERO, open + Duration::seconds(20));
+        builder.on_tick("EURUSD", mid, Decimal::ZERO, open + Duration::seconds(30));
+    }
+    builder.close_elapsed(start() + Duration::minutes(ranges.len() as i64));
+}
+
+#[test]
+fn ticks_roll_up_into_every_configured_timeframe() {
+    let builder = builder(&[Timeframe::M1, Timeframe::M5]);
+    let mut closed = builder.subscribe();
+
+    let ticks = [
+        (0, dec!(1.1000), dec!(100)),
+        (20, dec!(1.1010), dec!(50)),
+        (45, dec!(1.0995), dec!(25)),
+        (70, dec!(1.1005), dec!(10)),
+        (310, dec!(1.1020), dec!(5)),
+    ];
+    for (seconds, price, volume) in ticks {
+        builder.on_tick(
+            "EURUSD",
+            price,
+            volume,
+            start() + Duration::seconds(seconds),
+        );
+    }
+    // A tick arriving after its candle has closed is ignored
+    builder.on_tick(
+        "EURUSD",
+        dec!(2.0),
+        dec!(1),
+        start() + Duration::seconds(30),
+    );
+
+    let m1 = builder.candles("EURUSD", Timeframe::M1, 10);
+    assert_eq!(m1.len(), 2);
+    assert_eq!(
+        (m1[0].open, m1[0].high, m1[0].low, m1[0].close),
+        (dec!(1.1000), dec!(1.1010), dec!(1.0995), dec!(1.0995))
+    );
+    assert_eq!((m1[0].volume, m1[0].ticks), (dec!(175), 3));
+    assert_eq!(m1[1].open_time, start() + Duration::minutes(1));
+
+    let m5 = builder.candles("EURUSD", Timeframe::M5, 10);
+    assert_eq!(m5.len(), 1);
+    assert_eq!(
+        (m5[0].open, m5[0].high, m5[0].low, m5[0].close),
+        (dec!(1.1000), dec!(1.1010), dec!(1.0995), dec!(1.1005))
+    );
+    assert_eq!(m5[0].volume, dec!(185));
+    let forming = builder.current("EURUSD", Timeframe::M5).unwrap();
+    assert_eq!(forming.open_time, start() + Duration::minutes(5));
+    assert_eq!(forming.close, dec!(1.1020));
+
+    let mut published = Vec::new();
+    while let Ok(candle) = closed.try_recv() {
+        published.push((candle.timeframe, candle.open_time));
+    }
+    assert_eq!(
+        published,
+        vec![
+            (Timeframe::M1, start()),
+            (Timeframe::M1, start() + Duration::minutes(1)),
+            (Timeframe::M5, start()),
+        ]
+    );
+
+    // Candles close on time even when the symbol stops ticking
+    let closed = builder.close_elapsed(start() + Duration::minutes(10));
+    assert_eq!(closed.len(), 2);
+    assert!(builder.forming().is_empty());
+}
+
+#[test]
+fn atr_and_swings_come_from_closed_candles() {
+    let builder = builder(&[Timeframe::M1]);
+    assert_eq!(builder.atr("EURUSD", Timeframe::M1, 3), None);
+
+    minute_candles(
+        &builder,
+        &[
+            (dec!(1.1010), dec!(1.1000)),
+            (dec!(1.1030), dec!(1.1010)),
+            (dec!(1.1020), dec!(1.1005)),
+            (dec!(1.1015), dec!(1.0990)),
+            (dec!(1.1025), dec!(1.1000)),
+        ],
+    );
+
+    // Ranges of the last three candles: 15, 25 and 25 pips, none gapping past the
+    // previous close
+    assert_eq!(
+        builder.atr("EURUSD", Timeframe::M1, 3),
+        Some(dec!(0.0065) / dec!(3))
+    );
+
+    let swings = builder.swing_points("EURUSD", Timeframe::M1, 5, 1);
+    let found: Vec<_> = swings.iter().map(|s| (s.kind, s.price)).collect();
+    assert_eq!(
+        found,
+        vec![
+            (SwingKind::High, dec!(1.1030)),
+            (SwingKind::Low, dec!(1.0990)),
+        ]
+    );
+    assert_eq!(swings[0].open_time, start() + Duration::minutes(1));
+}
+
+#[tokio::test]
+async fn the_dashboard_feed_carries_forming_candles() {
+    let candles = Arc::new(builder(&[Timeframe::M1, Timeframe::H1]));
+    candles.on_tick("EURUSD", dec!(1.1000), Decimal::ZERO, Utc::now());
+    candles.on_tick("GBPUSD", dec!(1.2700), Decimal::ZERO, Utc::now());
+
+    let snapshot = DashboardAggregator::new(Arc::new(TradeExecutionOrchestrator::new()))
+        .with_candles(candles)
+        .snapshot()
+        .await;
+    let forming: Vec<_> = snapshot
+        .candles
+        .iter()
+        .map(|c| (c.symbol.as_str(), c.timeframe))
+        .collect();
+    assert_eq!(
+        forming,
+        vec![
+            ("EURUSD", Timeframe::M1),
+            ("EURUSD", Timeframe::H1),
+            ("GBPUSD", Timeframe::M1),
+            ("GBPUSD", Timeframe::H1),
+        ]
+    );
+}
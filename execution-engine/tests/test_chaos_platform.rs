@@ -0,0 +1,369 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use execution_engine::execution::exit_management::{
+    BreakEvenManager, ExitAuditLogger, ExitManagementPlatformAdapter,
+};
+use execution_engine::execution::{AccountAssignment, ExecutionPlan, TradeExecutionOrchestrator};
+use execution_engine::platforms::abstraction::events::{EventData, EventType};
+use execution_engine::platforms::abstraction::{
+    CircuitBreaker, CircuitBreakerConfig, CircuitBreakerState, ITradingPlatform, OrderMetadata,
+    PlatformError, RetryConfig, RetryHandler, UnifiedOrder, UnifiedOrderSide, UnifiedOrderStatus,
+    UnifiedOrderType, UnifiedPositionSide, UnifiedTimeInForce,
+};
+use execution_engine::testing::{ChaosPlatform, ChaosScenario, Fault, Operation};
+
+fn scenario(name: &str) -> ChaosScenario {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/scenarios/chaos")
+        .join(format!("{}.toml", name));
+    ChaosScenario::load(path).unwrap()
+}
+
+fn platform(name: &str, scenario: ChaosScenario) -> Arc<ChaosPlatform> {
+    Arc::new(ChaosPlatform::new(name, scenario).with_quote("EURUSD", dec!(1.1000), dec!(1.1002)))
+}
+
+fn market_order(quantity: Decimal) -> UnifiedOrder {
+    UnifiedOrder {
+        client_order_id: Uuid::new_v4().to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Buy,
+        order_type: UnifiedOrderType::Market,
+        quantity,
+        price: None,
+        stop_price: None,
+        take_profit: None,
+        stop_loss: None,
+        time_in_force: UnifiedTimeInForce::Gtc,
+        account_id: None,
+        metadata: OrderMetadata {
+            strategy_id: None,
+            signal_id: None,
+            risk_parameters: HashMap::new(),
+            tags: Vec::new(),
+            expires_at: None,
+        },
+        max_slippage: None,
+    }
+}
+
+#[test]
+fn test_scenario_files_load() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/scenarios/chaos");
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let scenario = ChaosScenario::load(entry.unwrap().path()).unwrap();
+        assert!(!scenario.description.is_empty(), "{}", scenario.name);
+        names.push(scenario.name);
+    }
+    names.sort();
+    assert_eq!(
+        names,
+        vec!["disconnect-storm", "fill-chaos", "throttled-broker"]
+    );
+
+    let fills = scenario("fill_chaos");
+    assert_eq!(fills.profile.partial_fill_ratio, dec!(0.4));
+    assert_eq!(
+        fills.script[0].fault,
+        Fault::PartialFill { ratio: dec!(0.25) }
+    );
+}
+
+#[tokio::test]
+async fn test_same_seed_injects_the_same_faults() {
+    let run = || async {
+        let platform = platform("acct-1", scenario("fill_chaos"));
+        for _ in 0..50 {
+            platform
+                .place_order(market_order(dec!(1000)))
+                .await
+                .unwrap();
+        }
+        platform.injected_faults()
+    };
+
+    let first = run().await;
+    assert_eq!(first, run().await);
+    assert!(first.len() > 10);
+
+    let reseeded = platform("acct-1", scenario("fill_chaos").with_seed(12));
+    for _ in 0..50 {
+        reseeded
+            .place_order(market_order(dec!(1000)))
+            .await
+            .unwrap();
+    }
+    assert_ne!(first, reseeded.injected_faults());
+}
+
+#[tokio::test]
+async fn test_fill_faults_reach_the_event_stream() {
+    let platform = platform("acct-1", scenario("fill_chaos"));
+    let mut events = platform.subscribe_events().await.unwrap();
+
+    // Scripted: a quarter fill, delivered twice
+    let order = platform.place_order(market_order(dec!(4))).await.unwrap();
+    assert_eq!(order.status, UnifiedOrderStatus::PartiallyFilled);
+    assert_eq!(order.filled_quantity, dec!(1));
+    assert_eq!(order.remaining_quantity, dec!(3));
+
+    let first = events.recv().await.unwrap();
+    let duplicate = events.recv().await.unwrap();
+    assert_eq!(first.event_type, EventType::OrderPartiallyFilled);
+    assert_eq!(first.event_id, duplicate.event_id);
+    match &first.data {
+        EventData::Order(data) => assert_eq!(data.fill_quantity, Some(dec!(1))),
+        _ => panic!("expected an order event"),
+    }
+
+    let positions = platform.get_positions().await.unwrap();
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].quantity, dec!(1));
+    assert_eq!(platform.stats().partial_fills, 1);
+    assert_eq!(platform.stats().duplicate_fills, 1);
+}
+
+#[tokio::test]
+async fn test_out_of_order_fill_arrives_after_the_next_event() {
+    let scenario =
+        ChaosScenario::calm("reorder").with_fault_at(Operation::PlaceOrder, 1, Fault::OutOfOrder);
+    let platform = platform("acct-1", scenario);
+    let mut events = platform.subscribe_events().await.unwrap();
+
+    let first = platform.place_order(market_order(dec!(1))).await.unwrap();
+    assert!(events.try_recv().is_err());
+    let second = platform.place_order(market_order(dec!(1))).await.unwrap();
+
+    let order_id =
+        |event: &execution_engine::platforms::abstraction::PlatformEvent| match &event.data {
+            EventData::Order(data) => data.order.platform_order_id.clone(),
+            _ => panic!("expected an order event"),
+        };
+    let delivered = [events.recv().await.unwrap(), events.recv().await.unwrap()];
+    assert_eq!(order_id(&delivered[0]), second.platform_order_id);
+    assert_eq!(order_id(&delivered[1]), first.platform_order_id);
+    assert!(delivered[0].sequence_number > delivered[1].sequence_number);
+}
+
+#[tokio::test]
+async fn test_retry_handler_rides_out_throttling() {
+    let platform = platform("acct-1", scenario("throttled_broker"));
+    let retry = RetryHandler::new(RetryConfig {
+        max_retries: 10,
+        initial_delay_ms: 1,
+        max_delay_ms: 10,
+        jitter: false,
+        ..Default::default()
+    });
+
+    for _ in 0..5 {
+        let placed = retry
+            .execute_with_retry(|| platform.place_order(market_order(dec!(1000))))
+            .await;
+        match placed {
+            Ok(order) => assert_eq!(order.status, UnifiedOrderStatus::Filled),
+            // Rejections are final and must not be retried
+            Err(e) => assert!(matches!(e, PlatformError::OrderRejected { .. })),
+        }
+    }
+
+    let stats = platform.stats();
+    assert!(stats.throttles >= 2);
+    assert!(stats.latency_spikes > 0);
+    assert_eq!(
+        stats.calls,
+        5 + stats.throttles,
+        "every throttled call is retried exactly once more"
+    );
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_opens_during_disconnect_storm() {
+    let scenario = ChaosScenario::calm("outage").with_fault_at(
+        Operation::GetPositions,
+        1,
+        Fault::Disconnect {
+            duration_ms: 60_000,
+        },
+    );
+    let platform = platform("acct-1", scenario);
+    let mut events = platform.subscribe_events().await.unwrap();
+    let breaker = CircuitBreaker::with_config(CircuitBreakerConfig {
+        failure_threshold: 3,
+        success_threshold: 1,
+        failure_window: Duration::from_secs(60),
+        open_timeout: Duration::from_millis(20),
+        half_open_max_operations: 1,
+    });
+
+    for _ in 0..6 {
+        let result = breaker.execute(|| platform.get_positions()).await;
+        assert!(result.is_err());
+    }
+    assert_eq!(breaker.get_state(), CircuitBreakerState::Open);
+    // Once open, the breaker stops calls reaching the platform
+    assert_eq!(platform.stats().calls, 3);
+    assert_eq!(platform.stats().failed_while_disconnected, 2);
+    assert_eq!(
+        events.recv().await.unwrap().event_type,
+        EventType::ConnectionLost
+    );
+
+    platform.restore_connection();
+    assert_eq!(
+        events.recv().await.unwrap().event_type,
+        EventType::ConnectionRestored
+    );
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    breaker.execute(|| platform.get_positions()).await.unwrap();
+    assert_eq!(breaker.get_state(), CircuitBreakerState::Closed);
+}
+
+#[tokio::test]
+async fn test_disconnects_recover_on_their_own() {
+    let platform = platform("acct-1", scenario("disconnect_storm"));
+    let mut failures = 0;
+    for _ in 0..40 {
+        match platform.get_market_data("EURUSD").await {
+            Ok(_) => {}
+            Err(PlatformError::Disconnected { .. }) => {
+                failures += 1;
+                tokio::time::sleep(Duration::from_millis(25)).await;
+            }
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    }
+
+    let stats = platform.stats();
+    assert!(stats.disconnects > 0);
+    assert_eq!(
+        failures,
+        stats.disconnects + stats.failed_while_disconnected
+    );
+    assert!(platform.is_connected().await);
+}
+
+#[tokio::test]
+async fn test_orchestrator_isolates_failing_accounts() {
+    let orchestrator = TradeExecutionOrchestrator::new();
+    let accounts = [
+        ("calm", ChaosScenario::calm("calm")),
+        (
+            "rejecting",
+            ChaosScenario::calm("rejecting").with_fault_at(
+                Operation::PlaceOrder,
+                1,
+                Fault::Reject {
+                    reason: "Market closed".to_string(),
+                },
+            ),
+        ),
+        (
+            "disconnecting",
+            ChaosScenario::calm("disconnecting").with_fault_at(
+                Operation::PlaceOrder,
+                1,
+                Fault::Disconnect {
+                    duration_ms: 60_000,
+                },
+            ),
+        ),
+        (
+            "slow",
+            ChaosScenario::calm("slow")
+                .with_fault_at(Operation::PlaceOrder, 1, Fault::LatencySpike { ms: 50 })
+                .with_fault_at(
+                    Operation::PlaceOrder,
+                    1,
+                    Fault::PartialFill { ratio: dec!(0.5) },
+                ),
+        ),
+    ];
+    let mut platforms = HashMap::new();
+    for (account_id, scenario) in accounts {
+        let platform = platform(account_id, scenario);
+        orchestrator
+            .register_account(account_id.to_string(), platform.clone(), 10000.0)
+            .await
+            .unwrap();
+        platforms.insert(account_id, platform);
+    }
+
+    let plan = ExecutionPlan {
+        signal_id: "chaos-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        account_assignments: platforms
+            .keys()
+            .map(|account_id| AccountAssignment {
+                account_id: account_id.to_string(),
+                position_size: 1000.0,
+                entry_timing_delay: Duration::ZERO,
+                priority: 1,
+                risk_per_unit: 0.0,
+            })
+            .collect(),
+        timing_variance: HashMap::new(),
+        size_variance: HashMap::new(),
+        rationale: "chaos".to_string(),
+        exit_policy: None,
+        tags: Vec::new(),
+        entry_ladder: None,
+    };
+
+    let results: HashMap<String, _> = orchestrator
+        .execute_plan(&plan)
+        .await
+        .into_iter()
+        .map(|result| (result.account_id.clone(), result))
+        .collect();
+    assert_eq!(results.len(), 4);
+    assert!(results["calm"].success);
+    assert!(results["slow"].success);
+    assert!(!results["rejecting"].success);
+    assert!(results["rejecting"]
+        .error_message
+        .as_ref()
+        .unwrap()
+        .contains("Market closed"));
+    assert!(!results["disconnecting"].success);
+
+    let slow_positions = platforms["slow"].get_positions().await.unwrap();
+    assert_eq!(slow_positions[0].quantity, dec!(500));
+    assert!(!platforms["disconnecting"].is_connected().await);
+}
+
+#[tokio::test]
+async fn test_break_even_retries_after_a_dropped_modification() {
+    let scenario = ChaosScenario::calm("flaky-modify").with_fault_at(
+        Operation::ModifyOrder,
+        1,
+        Fault::Disconnect { duration_ms: 0 },
+    );
+    let platform = platform("acct-1", scenario);
+    platform.open_position(
+        "EURUSD",
+        UnifiedPositionSide::Long,
+        dec!(10000),
+        dec!(1.0940),
+        Some(dec!(1.0890)),
+    );
+    let adapter = Arc::new(ExitManagementPlatformAdapter::new(platform.clone()));
+    let manager = BreakEvenManager::new(adapter, Arc::new(ExitAuditLogger::new()));
+
+    // The first attempt is lost to the disconnect and leaves the stop where it was
+    manager.check_break_even_triggers().await.unwrap();
+    let position = platform.get_position("EURUSD").await.unwrap().unwrap();
+    assert_eq!(position.stop_loss, Some(dec!(1.0890)));
+    assert_eq!(platform.stats().disconnects, 1);
+
+    manager.check_break_even_triggers().await.unwrap();
+    let position = platform.get_position("EURUSD").await.unwrap().unwrap();
+    assert_eq!(position.stop_loss, Some(dec!(1.0945)));
+}
@@ -0,0 +1,73 @@
+use chrono::{Duration, Utc};
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::{
+    ClockSyncConfig, ClockSyncPlatform, QuoteFilterConfig, QuoteFilteringPlatform, ServerClock,
+    ServerClocks,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+fn broker_ahead_by(offset: Duration) -> Arc<MockTradingPlatform> {
+    Arc::new(
+        MockTradingPlatform::new("acc-1")
+            .with_clock_offset(offset)
+            .with_quote("EURUSD", dec!(1.0999), dec!(1.1001)),
+    )
+}
+
+fn synced(mock: Arc<MockTradingPlatform>, clock: ServerClock) -> ClockSyncPlatform {
+    ClockSyncPlatform::new(mock, "acc-1".to_string(), clock, ClockSyncConfig::default())
+}
+
+#[tokio::test]
+async fn skew_above_the_threshold_is_measured_and_reported() {
+    let clocks = ServerClocks::new();
+    let platform = synced(broker_ahead_by(Duration::seconds(2)), clocks.clock("acc-1"));
+
+    let reading = platform.sync().await.unwrap();
+    assert!((reading.offset_ms - 2000).abs() < 50, "{:?}", reading);
+    // Everyone holding the account's clock sees the offset
+    let clock = clocks.clock("acc-1");
+    assert!((clock.offset_ms() - 2000).abs() < 50);
+    assert!(clock.now() - Utc::now() > Duration::milliseconds(1900));
+
+    let health = platform.health_check().await.unwrap();
+    assert!(!health.is_healthy);
+    assert!(health.issues[0].starts_with("Platform clock is"));
+    let diagnostics = platform.get_diagnostics().await.unwrap();
+    let skew = &diagnostics.platform_specific["clock"];
+    assert_eq!(skew["alert_threshold_ms"], 500);
+    assert!(skew["reading"]["offset_ms"].as_i64().unwrap() > 1900);
+}
+
+#[tokio::test]
+async fn small_skew_leaves_the_platform_healthy() {
+    let platform = synced(
+        broker_ahead_by(Duration::milliseconds(100)),
+        ServerClock::new(),
+    );
+
+    platform.sync().await.unwrap();
+    assert!(platform.health_check().await.unwrap().is_healthy);
+}
+
+#[tokio::test]
+async fn quotes_are_aged_on_the_server_clock() {
+    // The broker runs half a minute behind, so its fresh quotes look stale to us
+    let mock = broker_ahead_by(Duration::seconds(-30));
+    let config = QuoteFilterConfig {
+        max_age_ms: 10_000,
+        ..QuoteFilterConfig::default()
+    };
+
+    let unadjusted = QuoteFilteringPlatform::new(mock.clone(), "acc-1", config.clone());
+    assert!(unadjusted.get_market_data("EURUSD").await.is_err());
+
+    let clock = ServerClock::new();
+    clock.measure(mock.as_ref()).await.unwrap();
+    let adjusted =
+        QuoteFilteringPlatform::new(mock.clone(), "acc-1", config).with_server_clock(clock);
+    adjusted.get_market_data("EURUSD").await.unwrap();
+}
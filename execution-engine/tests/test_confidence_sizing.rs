@@ -0,0 +1,117 @@
+use chrono::{TimeZone, Utc};
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+use execution_engine::execution::{
+    ConfidenceSizingConfig, TradeExecutionOrchestrator, TradeSignal,
+};
+use execution_engine::journal::{TradeEntry, TradeRecord, TradeStatus};
+use execution_engine::platforms::abstraction::models::UnifiedPositionSide;
+use execution_engine::reports::{SignalQualityConfig, SignalQualityReport};
+use execution_engine::testing::{fixtures, MockTradingPlatform};
+
+fn enabled() -> ConfidenceSizingConfig {
+    ConfidenceSizingConfig {
+        enabled: true,
+        ..ConfidenceSizingConfig::default()
+    }
+}
+
+fn signal(id: &str, confidence: f64) -> TradeSignal {
+    TradeSignal {
+        confidence,
+        ..fixtures::signal(id)
+    }
+}
+
+#[test]
+fn the_curve_is_interpolated_and_bounded() {
+    let config = ConfidenceSizingConfig {
+        min_multiplier: 0.6,
+        ..enabled()
+    };
+
+    assert_eq!(config.multiplier(0.7), 1.0);
+    assert!((config.multiplier(0.8) - 1.125).abs() < 1e-9);
+    assert_eq!(config.multiplier(0.95), 1.25);
+    // The curve gives 0.5 below 0.5 confidence, floored at 0.6
+    assert_eq!(config.multiplier(0.2), 0.6);
+    assert_eq!(config.multiplier(f64::NAN), 0.6);
+    assert_eq!(ConfidenceSizingConfig::default().multiplier(0.2), 1.0);
+
+    let unordered = ConfidenceSizingConfig {
+        curve: enabled().curve.into_iter().rev().collect(),
+        ..enabled()
+    };
+    assert!(unordered.validate().is_err());
+}
+
+#[tokio::test]
+async fn signals_are_sized_by_their_confidence() {
+    let orchestrator = TradeExecutionOrchestrator::new().with_confidence_sizing(enabled());
+    orchestrator
+        .register_account(
+            "acc-1".to_string(),
+            Arc::new(MockTradingPlatform::new("acc-1")),
+            100000.0,
+        )
+        .await
+        .unwrap();
+
+    let confident = orchestrator
+        .process_signal(signal("sig-high", 0.93))
+        .await
+        .unwrap();
+    let doubtful = orchestrator
+        .process_signal(signal("sig-low", 0.4))
+        .await
+        .unwrap();
+
+    let ratio = confident.account_assignments[0].position_size
+        / doubtful.account_assignments[0].position_size;
+    // 1.25x against 0.5x, blurred by up to 15% of size variance each way
+    assert!(ratio > 2.5 * 0.85 / 1.15, "ratio {}", ratio);
+    assert!(confident.rationale.contains("1.25x for confidence 0.93"));
+    assert!(confident.tags.contains(&"confidence:0.9".to_string()));
+    assert!(doubtful.tags.contains(&"confidence:0.4".to_string()));
+}
+
+#[test]
+fn results_are_attributed_to_the_confidence_they_were_sized_at() {
+    let trade = |tag: &str, r| {
+        let mut record = TradeRecord::open(TradeEntry {
+            account_id: "acc-1".to_string(),
+            position_id: format!("P-{}", uuid::Uuid::new_v4()),
+            symbol: "EURUSD".to_string(),
+            side: UnifiedPositionSide::Long,
+            quantity: dec!(10000),
+            entry_price: dec!(1.1),
+            stop_loss: Some(dec!(1.09)),
+            take_profit: None,
+            signal_id: None,
+            order_id: None,
+            opened_at: Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap(),
+            tags: vec!["strategy:breakout".to_string(), tag.to_string()],
+        });
+        record.status = TradeStatus::Closed;
+        record.r_multiple = Some(r);
+        record.realized_pnl = r * dec!(100);
+        record
+    };
+    let trades = vec![
+        trade("confidence:0.9", dec!(2)),
+        trade("confidence:0.9", dec!(1)),
+        trade("confidence:0.5", dec!(-1)),
+    ];
+
+    let report = SignalQualityReport::compile(
+        &trades,
+        &SignalQualityConfig::default(),
+        Utc::now(),
+        Utc::now(),
+    );
+
+    assert_eq!(report.by_confidence["0.9"].trades, 2);
+    assert_eq!(report.by_confidence["0.9"].average_r, Some(dec!(1.5)));
+    assert_eq!(report.by_confidence["0.5"].wins, 0);
+}
@@ -0,0 +1,130 @@
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::dashboard::{ConsolidationConfig, DashboardAggregator, RateSource};
+use execution_engine::testing::{orchestrator, MockTradingPlatform};
+
+fn config(base_currency: &str, rates: &[(&str, rust_decimal::Decimal)]) -> ConsolidationConfig {
+    ConsolidationConfig {
+        base_currency: base_currency.to_string(),
+        rates: rates
+            .iter()
+            .map(|(pair, rate)| (pair.to_string(), *rate))
+            .collect::<HashMap<_, _>>(),
+        ..ConsolidationConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn accounts_are_converted_at_the_live_mid() {
+    let orchestrator = orchestrator(vec![
+        Arc::new(MockTradingPlatform::new("usd-1").with_balance(dec!(10000))),
+        Arc::new(
+            MockTradingPlatform::new("eur-1")
+                .with_currency("EUR")
+                .with_balance(dec!(20000))
+                .with_quote("EURUSD", dec!(1.0999), dec!(1.1001)),
+        ),
+    ])
+    .await;
+    let dashboard = DashboardAggregator::new(orchestrator).with_consolidation(config("USD", &[]));
+
+    let equity = dashboard.consolidated_equity().await.unwrap();
+
+    assert_eq!(equity.base_currency, "USD");
+    assert_eq!(equity.totals.balance, dec!(32000));
+    assert_eq!(equity.totals.equity, dec!(32000));
+    let eur = equity
+        .accounts
+        .iter()
+        .find(|a| a.account_id == "eur-1")
+        .unwrap();
+    assert_eq!(eur.native.balance, dec!(20000));
+    let rate = eur.rate.as_ref().unwrap();
+    assert_eq!(rate.rate, dec!(1.1));
+    assert_eq!(
+        rate.source,
+        RateSource::Quote {
+            symbol: "EURUSD".to_string(),
+            account_id: "eur-1".to_string(),
+            inverted: false,
+        }
+    );
+    let usd = equity
+        .accounts
+        .iter()
+        .find(|a| a.account_id == "usd-1")
+        .unwrap();
+    assert_eq!(usd.rate.as_ref().unwrap().source, RateSource::Identity);
+}
+
+#[tokio::test]
+async fn quotes_of_other_platforms_are_inverted_when_quoted_in_the_account_currency() {
+    let orchestrator = orchestrator(vec![
+        Arc::new(
+            MockTradingPlatform::new("jpy-1")
+                .with_currency("JPY")
+                .with_balance(dec!(1500000)),
+        ),
+        Arc::new(MockTradingPlatform::new("usd-1").with_quote("USDJPY", dec!(150), dec!(150))),
+    ])
+    .await;
+    let dashboard = DashboardAggregator::new(orchestrator).with_consolidation(config("USD", &[]));
+
+    let equity = dashboard.consolidated_equity().await.unwrap();
+
+    let jpy = equity
+        .accounts
+        .iter()
+        .find(|a| a.account_id == "jpy-1")
+        .unwrap();
+    assert_eq!(
+        jpy.rate.as_ref().unwrap().source,
+        RateSource::Quote {
+            symbol: "USDJPY".to_string(),
+            account_id: "usd-1".to_string(),
+            inverted: true,
+        }
+    );
+    assert_eq!(jpy.converted.unwrap().balance.round_dp(2), dec!(10000));
+}
+
+#[tokio::test]
+async fn configured_rates_back_up_missing_quotes_and_unconverted_accounts_are_left_out() {
+    let orchestrator = orchestrator(vec![
+        Arc::new(
+            MockTradingPlatform::new("gbp-1")
+                .with_currency("GBP")
+                .with_balance(dec!(1000)),
+        ),
+        Arc::new(
+            MockTradingPlatform::new("chf-1")
+                .with_currency("CHF")
+                .with_balance(dec!(5000)),
+        ),
+    ])
+    .await;
+    let dashboard = DashboardAggregator::new(orchestrator)
+        .with_consolidation(config("EUR", &[("EURGBP", dec!(0.8))]));
+
+    let equity = dashboard.consolidated_equity().await.unwrap();
+
+    assert_eq!(equity.totals.balance, dec!(1250));
+    assert_eq!(equity.unconverted_accounts, vec!["chf-1".to_string()]);
+    let gbp = equity
+        .accounts
+        .iter()
+        .find(|a| a.account_id == "gbp-1")
+        .unwrap();
+    assert_eq!(
+        gbp.rate.as_ref().unwrap().source,
+        RateSource::Configured {
+            symbol: "EURGBP".to_string(),
+            inverted: true,
+        }
+    );
+
+    let snapshot = dashboard.snapshot().await;
+    assert_eq!(snapshot.consolidated.unwrap().totals.balance, dec!(1250));
+}
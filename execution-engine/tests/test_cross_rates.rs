@@ -0,0 +1,122 @@
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use execution_engine::market_data::{CrossRateConfig, CrossRateError, CrossRates};
+use execution_engine::platforms::abstraction::models::UnifiedMarketData;
+use execution_engine::risk::exposure_monitor::CurrencyExposureCalculator;
+use execution_engine::risk::pnl_calculator::CurrencyConverter;
+use execution_engine::risk::{Position, PositionType};
+
+fn quote(symbol: &str, bid: Decimal, ask: Decimal, age_secs: i64) -> UnifiedMarketData {
+    UnifiedMarketData {
+        symbol: symbol.to_string(),
+        bid,
+        ask,
+        spread: ask - bid,
+        last_price: None,
+        volume: None,
+        high: None,
+        low: None,
+        timestamp: Utc::now() - Duration::seconds(age_secs),
+        session: None,
+        platform_specific: HashMap::new(),
+    }
+}
+
+/// GBPUSD at 1.2500 and USDJPY at 150.00 mid
+fn rates() -> CrossRates {
+    let rates = CrossRates::new(CrossRateConfig::default());
+    rates.on_quote(&quote("GBPUSD", dec!(1.2499), dec!(1.2501), 0));
+    rates.on_quote(&quote("USDJPY", dec!(149.99), dec!(150.01), 0));
+    rates
+}
+
+#[test]
+fn unquoted_pairs_are_derived_through_the_vehicle_currency() {
+    let rates = rates();
+
+    let gbpjpy = rates.rate("GBP", "JPY").unwrap();
+    assert_eq!(gbpjpy.rate, dec!(187.5));
+    assert_eq!(gbpjpy.via.as_deref(), Some("USD"));
+
+    // Quoted pairs are used directly, either way round
+    assert_eq!(rates.rate("USD", "JPY").unwrap().via, None);
+    assert_eq!(rates.rate("USD", "GBP").unwrap().rate, dec!(0.8));
+    assert_eq!(rates.convert(dec!(100), "JPY", "JPY").unwrap(), dec!(100));
+
+    assert_eq!(
+        rates.rate("CHF", "JPY").unwrap_err(),
+        CrossRateError::Unavailable {
+            from: "CHF".to_string(),
+            to: "JPY".to_string()
+        }
+    );
+}
+
+#[test]
+fn a_stale_leg_stales_the_derived_rate() {
+    let rates = rates();
+    rates.on_quote(&quote("GBPUSD", dec!(1.2499), dec!(1.2501), 120));
+
+    assert!(matches!(
+        rates.rate("GBP", "JPY"),
+        Err(CrossRateError::Stale { age_secs, .. }) if age_secs >= 120
+    ));
+    // The fresh leg still converts on its own
+    rates.rate("USD", "JPY").unwrap();
+}
+
+#[tokio::test]
+async fn pnl_conversion_fails_on_stale_rates_instead_of_guessing() {
+    let rates = rates();
+    let converter = CurrencyConverter::new().with_cross_rates(rates.clone());
+    assert_eq!(
+        converter
+            .convert_pip_value(dec!(1000), "GBPJPY", "USD")
+            .await
+            .unwrap()
+            .round_dp(4),
+        dec!(6.6667)
+    );
+
+    rates.on_quote(&quote("USDJPY", dec!(149.99), dec!(150.01), 300));
+    assert!(converter
+        .convert_pip_value(dec!(1000), "GBPJPY", "USD")
+        .await
+        .is_err());
+}
+
+#[test]
+fn currency_legs_net_in_the_reporting_currency() {
+    let position = |symbol: &str, position_type, size, entry_price| Position {
+        id: Uuid::new_v4(),
+        account_id: Uuid::new_v4(),
+        symbol: symbol.to_string(),
+        position_type,
+        size,
+        entry_price,
+        current_price: None,
+        unrealized_pnl: None,
+        max_favorable_excursion: dec!(0),
+        max_adverse_excursion: dec!(0),
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now(),
+    };
+    let positions = vec![
+        position("GBPJPY", PositionType::Long, dec!(10000), dec!(187.5)),
+        position("USDJPY", PositionType::Short, dec!(12500), dec!(150)),
+    ];
+
+    let exposure = CurrencyExposureCalculator
+        .calculate_net_exposure_in(&positions, &rates(), "USD")
+        .unwrap();
+
+    // The yen legs offset once both are in dollars
+    assert_eq!(exposure["GBP"], dec!(12500));
+    assert_eq!(exposure["USD"], dec!(-12500));
+    assert_eq!(exposure["JPY"].round_dp(6), dec!(0));
+}
@@ -0,0 +1,366 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use execution_engine::alerting::{Alert, AlertGateway, AlertingConfig};
+use execution_engine::execution::exit_management::ExitModificationType;
+use execution_engine::journal::{ExitReason, TradeJournal};
+use execution_engine::notifications::{
+    ChannelRules, Notification, NotificationChannel, NotificationTopic, Notifier,
+};
+use execution_engine::platforms::abstraction::events::PositionCloseEventData;
+use execution_engine::platforms::abstraction::models::*;
+use execution_engine::reports::{DailyReportGenerator, ReportsConfig};
+use execution_engine::risk::{TradingDayConfig, TradingDayRollover};
+use risk_types::AlertLevel;
+
+#[derive(Default)]
+struct RecordingChannel(Mutex<Vec<Notification>>);
+
+#[async_trait]
+impl NotificationChannel for RecordingChannel {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        self.0.lock().unwrap().push(notification.clone());
+        Ok(())
+    }
+}
+
+fn at(day: u32, hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap()
+}
+
+fn report_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, 3).unwrap()
+}
+
+fn position(opened_at: DateTime<Utc>) -> UnifiedPosition {
+    UnifiedPosition {
+        position_id: Uuid::new_v4().to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity: dec!(10000),
+        entry_price: dec!(1.1000),
+        current_price: dec!(1.1000),
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        stop_loss: Some(dec!(1.0950)),
+        take_profit: Some(dec!(1.1100)),
+        opened_at,
+        updated_at: opened_at,
+        account_id: String::new(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+async fn close(
+    journal: &TradeJournal,
+    account_id: &str,
+    position: &UnifiedPosition,
+    quantity: Decimal,
+    price: Decimal,
+    closed_at: DateTime<Utc>,
+) {
+    journal
+        .record_close(
+            account_id,
+            &PositionCloseEventData {
+                position_id: position.position_id.clone(),
+                symbol: position.symbol.clone(),
+                side: position.side.clone(),
+                closing_order_id: Uuid::new_v4().to_string(),
+                entry_price: position.entry_price,
+                close_price: price,
+                closed_quantity: quantity,
+                remaining_quantity: position.quantity - quantity,
+                realized_pnl: (price - position.entry_price) * quantity,
+                commission: dec!(1),
+                closed_at,
+            },
+        )
+        .await;
+}
+
+/// On 2025-03-03: a take-profit win (+100), a stop-loss loss (-50), a partial profit
+/// (+20) on a trade left open, and the close of a trade opened the day before (+10).
+/// A trade entirely on 2025-03-02 must not show up.
+async fn trading_day(journal: &TradeJournal) {
+    let winner = position(at(3, 9));
+    journal.record_open("acc-1", &winner).await;
+    close(
+        journal,
+        "acc-1",
+        &winner,
+        dec!(10000),
+        dec!(1.1100),
+        at(3, 10),
+    )
+    .await;
+
+    let loser = position(at(3, 10));
+    journal.record_open("acc-1", &loser).await;
+    close(
+        journal,
+        "acc-1",
+        &loser,
+        dec!(10000),
+        dec!(1.0950),
+        at(3, 11),
+    )
+    .await;
+
+    let partial = position(at(3, 11));
+    journal.record_open("acc-1", &partial).await;
+    journal
+        .record_exit_action(&partial.position_id, ExitModificationType::PartialProfit)
+        .await;
+    close(
+        journal,
+        "acc-1",
+        &partial,
+        dec!(5000),
+        dec!(1.1040),
+        at(3, 12),
+    )
+    .await;
+
+    let overnight = position(at(2, 20));
+    journal.record_open("acc-2", &overnight).await;
+    close(
+        journal,
+        "acc-2",
+        &overnight,
+        dec!(10000),
+        dec!(1.1010),
+        at(3, 1),
+    )
+    .await;
+
+    let yesterday = position(at(2, 9));
+    journal.record_open("acc-1", &yesterday).await;
+    close(
+        journal,
+        "acc-1",
+        &yesterday,
+        dec!(10000),
+        dec!(1.1100),
+        at(2, 10),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn report_summarizes_each_account_day() {
+    let journal = Arc::new(TradeJournal::new());
+    trading_day(&journal).await;
+    let generator = DailyReportGenerator::new(ReportsConfig::default(), journal);
+
+    let report = generator.generate(report_date()).await;
+    assert_eq!(report.from, at(3, 0));
+    assert_eq!(report.to, at(4, 0));
+    assert_eq!(report.accounts.len(), 2);
+
+    let acc1 = &report.accounts[0];
+    assert_eq!(acc1.account_id, "acc-1");
+    assert_eq!(acc1.trades_opened, 3);
+    assert_eq!(acc1.trades_closed, 2);
+    assert_eq!((acc1.wins, acc1.losses), (1, 1));
+    assert_eq!(acc1.win_rate, Some(dec!(0.5)));
+    assert_eq!(acc1.net_pnl, dec!(70));
+    assert_eq!(acc1.commission, dec!(3));
+    // +100, then -50, then +20
+    assert_eq!(acc1.max_drawdown, dec!(50));
+    assert_eq!(acc1.exit_management.exits, 1);
+    assert_eq!(acc1.exit_management.realized_pnl, dec!(20));
+    assert_eq!(
+        acc1.exit_management
+            .by_reason
+            .get(&ExitReason::PartialProfit),
+        Some(&dec!(20))
+    );
+
+    let acc2 = &report.accounts[1];
+    assert_eq!(acc2.trades_opened, 0);
+    assert_eq!(acc2.trades_closed, 1);
+    assert_eq!(acc2.net_pnl, dec!(10));
+    assert_eq!(acc2.max_drawdown, Decimal::ZERO);
+}
+
+#[tokio::test]
+async fn report_counts_alerts_raised_during_the_day() {
+    let gateway = Arc::new(AlertGateway::new(AlertingConfig::default()));
+    let account_id = Uuid::new_v4();
+    let alert = |alert_type: &str, raised_at| Alert {
+        alert_type: alert_type.to_string(),
+        key: account_id.to_string(),
+        severity: AlertLevel::Warning,
+        account_id: Some(account_id),
+        message: "test".to_string(),
+        raised_at,
+    };
+    gateway.submit(alert("margin", at(3, 9))).await;
+    // A repeat of an active condition is not a new alert
+    gateway
+        .submit(alert("margin", at(3, 9) + Duration::minutes(1)))
+        .await;
+    gateway.submit(alert("drawdown", at(3, 10))).await;
+    gateway.submit(alert("risk_reward", at(4, 9))).await;
+
+    let generator =
+        DailyReportGenerator::new(ReportsConfig::default(), Arc::new(TradeJournal::new()))
+            .with_alert_gateway(gateway);
+    let report = generator.generate(report_date()).await;
+
+    assert_eq!(report.accounts.len(), 1);
+    let account = &report.accounts[0];
+    assert_eq!(account.account_id, account_id.to_string());
+    assert_eq!(account.alert_count(), 2);
+    assert_eq!(account.risk_alerts.get("margin"), Some(&1));
+    assert_eq!(account.win_rate, None);
+}
+
+#[tokio::test]
+async fn report_covers_each_accounts_trading_day() {
+    let journal = Arc::new(TradeJournal::new());
+    trading_day(&journal).await;
+    // 18:00 New York, after the 17:00 rollover, so part of the 2025-03-04 trading day
+    let after_rollover = position(at(3, 22));
+    journal.record_open("acc-2", &after_rollover).await;
+    close(
+        &journal,
+        "acc-2",
+        &after_rollover,
+        dec!(10000),
+        dec!(1.1100),
+        at(3, 23),
+    )
+    .await;
+
+    let trading_days = TradingDayConfig {
+        accounts: HashMap::from([("acc-2".to_string(), TradingDayRollover::new_york_close())]),
+        ..TradingDayConfig::default()
+    };
+    let generator = DailyReportGenerator::new(ReportsConfig::default(), journal)
+        .with_trading_days(trading_days);
+    let report = generator.generate(report_date()).await;
+
+    assert_eq!(report.from, at(2, 22));
+    assert_eq!(report.to, at(4, 0));
+    let acc2 = &report.accounts[1];
+    assert_eq!((acc2.from, acc2.to), (at(2, 22), at(3, 22)));
+    assert_eq!(acc2.trades_closed, 1);
+    assert_eq!(acc2.net_pnl, dec!(10));
+}
+
+#[tokio::test]
+async fn due_report_is_written_and_notified_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = ReportsConfig {
+        output_dir: Some(dir.path().to_string_lossy().into_owned()),
+        ..ReportsConfig::default()
+    };
+    let journal = Arc::new(TradeJournal::new());
+    trading_day(&journal).await;
+    let channel = Arc::new(RecordingChannel::default());
+    let notifier = Arc::new(Notifier::new().with_channel(
+        "desk",
+        ChannelRules {
+            topics: vec![NotificationTopic::DailySummary],
+            quiet_hours: None,
+        },
+        channel.clone(),
+    ));
+    let generator =
+        DailyReportGenerator::new(config.clone(), journal.clone()).with_notifier(notifier);
+
+    // Right after startup the last finished trading day is caught up on
+    let previous = generator.publish_due(at(3, 23)).await.unwrap().unwrap();
+    assert_eq!(previous.date, report_date().pred_opt().unwrap());
+    // Not before the publish delay has passed since the rollover
+    assert!(generator.publish_due(at(4, 0)).await.unwrap().is_none());
+
+    let report = generator.publish_due(at(4, 1)).await.unwrap().unwrap();
+    assert_eq!(report.date, report_date());
+    assert!(generator.publish_due(at(4, 2)).await.unwrap().is_none());
+
+    let json = std::fs::read_to_string(dir.path().join("daily-2025-03-03.json")).unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed["accounts"][0]["account_id"], "acc-1");
+    let html = std::fs::read_to_string(dir.path().join("daily-2025-03-03.html")).unwrap();
+    assert!(html.contains("<h2>acc-1</h2>"));
+    assert!(html.contains("PartialProfit"));
+
+    let sent = channel.0.lock().unwrap()[previous.accounts.len()..].to_vec();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[0].title, "Daily summary 2025-03-03");
+    assert_eq!(sent[0].account_id.as_deref(), Some("acc-1"));
+    assert!(sent[0].body.contains("win rate 50.0%"));
+
+    // A restarted engine finds the report already written
+    let restarted = DailyReportGenerator::new(config, journal);
+    assert!(restarted.publish_due(at(4, 3)).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn report_waits_for_the_last_account_rollover() {
+    let trading_days = TradingDayConfig {
+        accounts: HashMap::from([(
+            "acc-la".to_string(),
+            TradingDayRollover::new(chrono::NaiveTime::MIN, chrono_tz::America::Los_Angeles),
+        )]),
+        ..TradingDayConfig::default()
+    };
+    let generator = DailyReportGenerator::new(
+        ReportsConfig {
+            output_dir: None,
+            ..ReportsConfig::default()
+        },
+        Arc::new(TradeJournal::new()),
+    )
+    .with_trading_days(trading_days);
+    generator.publish_due(at(3, 9)).await.unwrap();
+
+    // Los Angeles midnight is 08:00 UTC
+    assert!(generator.publish_due(at(4, 1)).await.unwrap().is_none());
+    let report = generator.publish_due(at(4, 9)).await.unwrap().unwrap();
+    assert_eq!(report.date, report_date());
+    assert_eq!(report.to, at(4, 8));
+}
+
+#[test]
+fn reports_config_parses_from_toml() {
+    let config: execution_engine::runtime::EngineConfig = toml::from_str(
+        r#"
+        [reports]
+        enabled = true
+        publish_delay_mins = 15
+
+        [trading_day]
+        time = "17:00"
+        timezone = "America/New_York"
+
+        [trading_day.accounts.acc-1]
+        timezone = "Europe/London"
+        "#,
+    )
+    .unwrap();
+
+    assert!(config.validate().is_ok());
+    assert_eq!(config.reports.publish_delay_mins, 15);
+    assert_eq!(config.reports.output_dir.as_deref(), Some("data/reports"));
+    let trading_days = config.trading_day.unwrap();
+    assert_eq!(
+        trading_days.for_account("acc-2"),
+        TradingDayRollover::new_york_close()
+    );
+    assert_eq!(
+        trading_days.for_account("acc-1"),
+        TradingDayRollover::new(chrono::NaiveTime::MIN, chrono_tz::Europe::London)
+    );
+}
@@ -0,0 +1,400 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use futures_util::{Stream, StreamExt};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use uuid::Uuid;
+
+use execution_engine::auth::{
+    hash_api_key, ApiKeyConfig, AuthConfig, Authenticator, Role, API_KEY_HEADER,
+};
+use execution_engine::dashboard::{DashboardAggregator, DashboardSnapshot};
+use execution_engine::execution::exit_management::{
+    ExitAuditLogger, ExitManagementPlatformAdapter, ExitManagementSystem,
+};
+use execution_engine::execution::TradeExecutionOrchestrator;
+use execution_engine::platforms::abstraction::{
+    capabilities::PlatformCapabilities,
+    errors::PlatformError,
+    events::PlatformEvent,
+    interfaces::{DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter},
+    models::*,
+};
+use execution_engine::platforms::PlatformType;
+use execution_engine::runtime::subsystems::DashboardStreamSubsystem;
+use execution_engine::runtime::{Supervisor, SupervisorConfig};
+
+struct MockPlatform {
+    positions: Vec<UnifiedPosition>,
+    unreachable: bool,
+}
+
+impl MockPlatform {
+    fn new() -> Self {
+        Self {
+            positions: Vec::new(),
+            unreachable: false,
+        }
+    }
+
+    fn with_position(mut self, position_id: &str, unrealized_pnl: Decimal) -> Self {
+        self.positions.push(UnifiedPosition {
+            position_id: position_id.to_string(),
+            symbol: "EURUSD".to_string(),
+            side: UnifiedPositionSide::Long,
+            quantity: dec!(10000),
+            entry_price: dec!(1.0850),
+            current_price: dec!(1.0860),
+            unrealized_pnl,
+            realized_pnl: Decimal::ZERO,
+            margin_used: Decimal::ZERO,
+            commission: Decimal::ZERO,
+            stop_loss: Some(dec!(1.0800)),
+            take_profit: None,
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            account_id: String::new(),
+            platform_specific: HashMap::new(),
+        });
+        self
+    }
+
+    fn unreachable(mut self) -> Self {
+        self.unreachable = true;
+        self
+    }
+
+    fn unsupported<T>() -> Result<T, PlatformError> {
+        Err(PlatformError::FeatureNotSupported {
+            feature: "mock".to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl ITradingPlatform for MockPlatform {
+    fn platform_type(&self) -> PlatformType {
+        PlatformType::DXTrade
+    }
+    fn platform_name(&self) -> &str {
+        "mock"
+    }
+    fn platform_version(&self) -> &str {
+        "1.0.0"
+    }
+    async fn connect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn disconnect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn is_connected(&self) -> bool {
+        !self.unreachable
+    }
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        Ok(1)
+    }
+    async fn place_order(
+        &self,
+        _order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn modify_order(
+        &self,
+        _order_id: &str,
+        _modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn cancel_order(&self, _order_id: &str) -> Result<(), PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_order(&self, _order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_orders(
+        &self,
+        _filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        if self.unreachable {
+            return Err(PlatformError::ConnectionFailed {
+                reason: "mock offline".to_string(),
+            });
+        }
+        Ok(self.positions.clone())
+    }
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        Ok(self.positions.iter().find(|p| p.symbol == symbol).cloned())
+    }
+    async fn close_position(
+        &self,
+        _symbol: &str,
+        _quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        Ok(UnifiedAccountInfo {
+            account_id: "mock".to_string(),
+            account_name: None,
+            currency: "USD".to_string(),
+            balance: dec!(100000),
+            equity: dec!(100000),
+            margin_used: Decimal::ZERO,
+            margin_available: dec!(100000),
+            buying_power: dec!(100000),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_level: None,
+            account_type: AccountType::Demo,
+            last_updated: Utc::now(),
+            platform_specific: HashMap::new(),
+        })
+    }
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        Ok(dec!(100000))
+    }
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_market_data(&self, _symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        Self::unsupported()
+    }
+    async fn subscribe_market_data(
+        &self,
+        _symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn unsubscribe_market_data(&self, _symbols: Vec<String>) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    fn capabilities(&self) -> PlatformCapabilities {
+        PlatformCapabilities::new("mock".to_string())
+    }
+    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_event_history(
+        &self,
+        _filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        Self::unsupported()
+    }
+}
+
+async fn register(
+    orchestrator: &TradeExecutionOrchestrator,
+    account_id: &str,
+    platform: MockPlatform,
+) -> Arc<dyn ITradingPlatform + Send + Sync> {
+    let platform: Arc<dyn ITradingPlatform + Send + Sync> = Arc::new(platform);
+    orchestrator
+        .register_account(account_id.to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+    platform
+}
+
+async fn next_snapshot<S>(socket: &mut S) -> DashboardSnapshot
+where
+    S: Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    let message = tokio::time::timeout(Duration::from_secs(2), socket.next())
+        .await
+        .expect("snapshot within timeout")
+        .unwrap()
+        .unwrap();
+    serde_json::from_str(message.to_text().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_snapshot_joins_accounts_positions_and_exit_state() {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    let tracked = Uuid::new_v4().to_string();
+    let platform = register(
+        &orchestrator,
+        "acc-1",
+        MockPlatform::new()
+            .with_position(&tracked, dec!(25))
+            .with_position("broker-123", dec!(-5)),
+    )
+    .await;
+    register(&orchestrator, "acc-2", MockPlatform::new().unreachable()).await;
+    orchestrator.pause_account("acc-2").await.unwrap();
+
+    let exit_system = ExitManagementSystem::new(
+        Arc::new(ExitManagementPlatformAdapter::new(platform)),
+        Arc::new(ExitAuditLogger::new()),
+    );
+    let exit_systems = Arc::new(RwLock::new(HashMap::from([(
+        "acc-1".to_string(),
+        exit_system,
+    )])));
+
+    let snapshot = DashboardAggregator::new(orchestrator)
+        .with_exit_systems(exit_systems)
+        .snapshot()
+        .await;
+
+    assert_eq!(snapshot.totals.accounts, 2);
+    assert_eq!(snapshot.totals.active_accounts, 1);
+    assert_eq!(snapshot.totals.open_positions, 2);
+    assert_eq!(snapshot.totals.unrealized_pnl, dec!(20));
+    assert!(!snapshot.kill_switch.engaged);
+
+    let first = &snapshot.accounts[0];
+    assert_eq!(first.status.account_id, "acc-1");
+    assert!(first.positions_error.is_none());
+    assert!(first
+        .positions
+        .iter()
+        .all(|p| p.position.account_id == "acc-1"));
+    let exit = first.positions[0].exit.as_ref().unwrap();
+    assert!(exit.trailing_stop.is_none());
+    assert!(!exit.break_even_active);
+    // Broker ids that are not UUIDs cannot be matched to exit management state
+    assert!(first.positions[1].exit.is_none());
+
+    let second = &snapshot.accounts[1];
+    assert!(!second.status.is_active);
+    assert!(second.positions.is_empty());
+    assert!(second
+        .positions_error
+        .as_deref()
+        .unwrap()
+        .contains("mock offline"));
+}
+
+#[tokio::test]
+async fn test_stream_pushes_snapshots_to_websocket_clients() {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    register(
+        &orchestrator,
+        "acc-1",
+        MockPlatform::new().with_position(&Uuid::new_v4().to_string(), dec!(10)),
+    )
+    .await;
+
+    // Reserve a free port for the stream
+    let address = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let mut supervisor = Supervisor::new(SupervisorConfig::default());
+    supervisor.add(Arc::new(DashboardStreamSubsystem::new(
+        address.to_string(),
+        Arc::new(DashboardAggregator::new(orchestrator.clone())),
+        Duration::from_millis(50),
+    )));
+    supervisor.start().await.unwrap();
+
+    let url = format!("ws://{}", address);
+    let mut socket = None;
+    for _ in 0..50 {
+        match tokio_tungstenite::connect_async(url.as_str()).await {
+            Ok((stream, _)) => {
+                socket = Some(stream);
+                break;
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+        }
+    }
+    let mut socket = socket.expect("dashboard stream should accept connections");
+
+    let initial = next_snapshot(&mut socket).await;
+    assert_eq!(initial.totals.open_positions, 1);
+
+    orchestrator.pause_account("acc-1").await.unwrap();
+    let mut paused = false;
+    for _ in 0..10 {
+        if next_snapshot(&mut socket).await.totals.active_accounts == 0 {
+            paused = true;
+            break;
+        }
+    }
+    assert!(paused, "stream should reflect the paused account");
+
+    supervisor.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_stream_admits_only_credentials_for_every_account() {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    let address = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let key = |name: &str, accounts: Option<Vec<String>>| ApiKeyConfig {
+        name: name.to_string(),
+        key_sha256: hash_api_key(&format!("{}-secret", name)),
+        role: Role::Viewer,
+        accounts,
+    };
+    let auth = Authenticator::new(&AuthConfig {
+        enabled: true,
+        api_keys: vec![
+            key("dashboard", None),
+            key("tenant", Some(vec!["acc-1".to_string()])),
+        ],
+        jwt: None,
+    });
+
+    let mut supervisor = Supervisor::new(SupervisorConfig::default());
+    supervisor.add(Arc::new(
+        DashboardStreamSubsystem::new(
+            address.to_string(),
+            Arc::new(DashboardAggregator::new(orchestrator)),
+            Duration::from_millis(50),
+        )
+        .with_authenticator(Arc::new(auth)),
+    ));
+    supervisor.start().await.unwrap();
+
+    let connect = |key: Option<&str>| {
+        let mut request = format!("ws://{}", address).into_client_request().unwrap();
+        if let Some(key) = key {
+            request
+                .headers_mut()
+                .insert(API_KEY_HEADER, format!("{}-secret", key).parse().unwrap());
+        }
+        tokio_tungstenite::connect_async(request)
+    };
+    let refused = |result: Result<_, WsError>| match result {
+        Err(WsError::Http(response)) => response.status().as_u16(),
+        Err(e) => panic!("unexpected error {}", e),
+        Ok(_) => 101,
+    };
+
+    // Wait for the listener
+    for _ in 0..50 {
+        match connect(Some("dashboard")).await {
+            Ok(_) => break,
+            Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+        }
+    }
+    assert_eq!(refused(connect(None).await), 401);
+    assert_eq!(refused(connect(Some("intruder")).await), 401);
+    assert_eq!(refused(connect(Some("tenant")).await), 403);
+    let (mut socket, _) = connect(Some("dashboard")).await.unwrap();
+    next_snapshot(&mut socket).await;
+
+    supervisor.shutdown().await;
+}
@@ -0,0 +1,151 @@
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use execution_engine::risk::{
+    DeleveragePriority, DeleveragingConfig, MarginCalculator, MarginInfo, MarginProtectionSystem,
+    Position, PositionType,
+};
+
+fn position(symbol: &str, size: Decimal, pnl: Decimal, age_mins: i64) -> Position {
+    Position {
+        id: Uuid::new_v4(),
+        account_id: Uuid::nil(),
+        symbol: symbol.to_string(),
+        position_type: PositionType::Long,
+        size,
+        entry_price: dec!(1),
+        current_price: None,
+        unrealized_pnl: Some(pnl),
+        max_favorable_excursion: Decimal::ZERO,
+        max_adverse_excursion: Decimal::ZERO,
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now() - Duration::minutes(age_mins),
+    }
+}
+
+fn margin_info(equity: Decimal, used_margin: Decimal) -> MarginInfo {
+    MarginInfo {
+        account_id: Uuid::nil(),
+        balance: equity,
+        equity,
+        used_margin,
+        free_margin: equity - used_margin,
+        margin_level: equity / used_margin * dec!(100),
+        positions_count: 3,
+        timestamp: Utc::now(),
+    }
+}
+
+fn config(priorities: Vec<DeleveragePriority>) -> DeleveragingConfig {
+    DeleveragingConfig {
+        priorities,
+        ..DeleveragingConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn positions_are_cut_in_priority_order_until_the_target_level() {
+    let calculator = MarginCalculator::new();
+    // EURUSD at 100:1 and the rest at 50:1, all at a price of 1
+    let loser = position("EURUSD", dec!(50000), dec!(-300), 10);
+    let heavy = position("AUDCAD", dec!(30000), dec!(-100), 30);
+    let oldest = position("GBPUSD", dec!(40000), dec!(50), 60);
+    let positions = vec![oldest.clone(), heavy.clone(), loser.clone()];
+    // 1500 margin in use against 2400 equity is 160%; 200% allows 1200
+    let info = margin_info(dec!(2400), dec!(1500));
+
+    let plan = MarginProtectionSystem::default()
+        .reduction_plan(&info, &positions, &calculator)
+        .await
+        .unwrap();
+    assert_eq!(plan.margin_to_free, dec!(300));
+    let ids: Vec<Uuid> = plan.steps.iter().map(|s| s.position_id).collect();
+    assert_eq!(ids, vec![loser.id]);
+    // The whole loser frees 500; 30000 units of it free the 300 needed
+    assert_eq!(plan.steps[0].size_to_close, dec!(30000));
+    assert_eq!(plan.steps[0].margin_freed, dec!(300));
+    assert_eq!(plan.steps[0].projected_margin_level, Some(dec!(200)));
+    assert!(plan.reaches_target);
+
+    let system = MarginProtectionSystem::new(DeleveragingConfig {
+        partial_final_step: false,
+        target_margin_level: dec!(400),
+        ..config(vec![DeleveragePriority::HighestMarginUsage])
+    });
+    let plan = system
+        .reduction_plan(&info, &positions, &calculator)
+        .await
+        .unwrap();
+    // 600 held by AUDCAD, then 500 by EURUSD, against 900 to free
+    let ids: Vec<Uuid> = plan.steps.iter().map(|s| s.position_id).collect();
+    assert_eq!(ids, vec![heavy.id, loser.id]);
+    let freed: Vec<Decimal> = plan.steps.iter().map(|s| s.margin_freed).collect();
+    assert_eq!(freed, vec![dec!(600), dec!(500)]);
+    assert_eq!(plan.total_margin_freed(), dec!(1100));
+    assert_eq!(plan.steps[1].projected_margin_level, Some(dec!(600)));
+
+    let plan = MarginProtectionSystem::new(config(vec![DeleveragePriority::Oldest]))
+        .reduction_plan(
+            &margin_info(dec!(2400), dec!(1000)),
+            &positions,
+            &calculator,
+        )
+        .await
+        .unwrap();
+    assert!(plan.steps.is_empty());
+    assert!(plan.reaches_target);
+}
+
+#[tokio::test]
+async fn conviction_ranks_unknown_positions_first_and_later_priorities_break_ties() {
+    let calculator = MarginCalculator::new();
+    let strong = position("EURUSD", dec!(10000), dec!(0), 5);
+    let weak_new = position("EURUSD", dec!(10000), dec!(0), 5);
+    let weak_old = position("EURUSD", dec!(10000), dec!(0), 50);
+    let unknown = position("EURUSD", dec!(10000), dec!(0), 1);
+    let positions = vec![
+        strong.clone(),
+        weak_new.clone(),
+        weak_old.clone(),
+        unknown.clone(),
+    ];
+
+    let system = MarginProtectionSystem::new(DeleveragingConfig {
+        target_margin_level: dec!(100000),
+        ..config(vec![
+            DeleveragePriority::LowestConviction,
+            DeleveragePriority::Oldest,
+        ])
+    });
+    system.record_conviction(strong.id, dec!(0.9));
+    system.record_conviction(weak_new.id, dec!(0.6));
+    system.record_conviction(weak_old.id, dec!(0.6));
+
+    // Half the margin in use is held by positions it does not know of, so
+    // cutting all of these still falls short of the target
+    let plan = system
+        .reduction_plan(&margin_info(dec!(1000), dec!(800)), &positions, &calculator)
+        .await
+        .unwrap();
+    let ids: Vec<Uuid> = plan.steps.iter().map(|s| s.position_id).collect();
+    assert_eq!(ids, vec![unknown.id, weak_old.id, weak_new.id, strong.id]);
+    let cumulative: Vec<Decimal> = plan
+        .steps
+        .iter()
+        .map(|s| s.cumulative_margin_freed)
+        .collect();
+    assert_eq!(cumulative, vec![dec!(100), dec!(200), dec!(300), dec!(400)]);
+    assert_eq!(plan.steps[3].projected_margin_level, Some(dec!(250)));
+    assert!(!plan.reaches_target);
+
+    system.forget_position(&strong.id);
+    assert!(
+        config(vec![DeleveragePriority::Oldest, DeleveragePriority::Oldest])
+            .validate()
+            .is_err()
+    );
+    assert!(config(Vec::new()).validate().is_err());
+}
@@ -0,0 +1,203 @@
+use chrono::{Duration, Utc};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::execution::exit_management::{ExitPolicy, TrailingConfig};
+use execution_engine::execution::orchestrator::{
+    AccountAssignment, ExecutionPlan, TradeExecutionOrchestrator,
+};
+use execution_engine::platforms::abstraction::models::{UnifiedPosition, UnifiedPositionSide};
+use execution_engine::risk::{DrawdownLadder, DrawdownLadderConfig};
+use execution_engine::testing::{signal, ChaosPlatform, ChaosScenario, MockTradingPlatform};
+
+fn enabled() -> DrawdownLadderConfig {
+    DrawdownLadderConfig {
+        enabled: true,
+        ..DrawdownLadderConfig::default()
+    }
+}
+
+#[test]
+fn accounts_step_straight_down_and_climb_back_a_rung_at_a_time() {
+    let ladder = DrawdownLadder::new(enabled());
+    let start = Utc::now();
+
+    assert!(ladder.assess("acc-1", dec!(1), dec!(2), start).is_none());
+    // A 3.5% daily drawdown crosses the first two rungs at once
+    let level = ladder.assess("acc-1", dec!(3.5), dec!(4), start).unwrap();
+    assert_eq!((level.rung, level.risk_multiplier), (2, dec!(0.5)));
+    ladder.move_to("acc-1", level.rung, start);
+    assert_eq!(ladder.level("acc-1").unwrap().max_positions, Some(1));
+    assert!(ladder.level("acc-2").is_none());
+
+    // Within the 0.5 point margin of the 3% trigger is not yet recovering
+    let later = start + Duration::minutes(90);
+    assert!(ladder.assess("acc-1", dec!(2.8), dec!(4), later).is_none());
+    assert!(ladder.assess("acc-1", dec!(2), dec!(4), later).is_none());
+    assert!(ladder
+        .assess("acc-1", dec!(2), dec!(4), later + Duration::minutes(30))
+        .is_none());
+    let level = ladder
+        .assess("acc-1", dec!(2), dec!(4), later + Duration::minutes(60))
+        .unwrap();
+    assert_eq!(level.rung, 1);
+
+    let mut config = enabled();
+    config.rungs.swap(0, 1);
+    assert!(config.validate().is_err());
+    let mut config = enabled();
+    config.rungs[0].risk_multiplier = dec!(1.5);
+    assert!(config.validate().is_err());
+    assert!(DrawdownLadder::new(DrawdownLadderConfig::default())
+        .assess("acc-1", dec!(10), dec!(10), start)
+        .is_none());
+}
+
+#[tokio::test]
+async fn the_orchestrator_moves_accounts_along_the_ladder_by_their_equity() {
+    let ladder = DrawdownLadder::new(DrawdownLadderConfig {
+        recovery_mins: 0,
+        ..enabled()
+    });
+    let orchestrator = TradeExecutionOrchestrator::new().with_drawdown_ladder(ladder.clone());
+    let platform = Arc::new(MockTradingPlatform::new("acc-1").with_balance(dec!(100000)));
+    platform.add_position(UnifiedPosition {
+        position_id: "pos-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity: dec!(1000000),
+        entry_price: dec!(1.1000),
+        current_price: dec!(1.1000),
+        unrealized_pnl: dec!(0),
+        realized_pnl: dec!(0),
+        margin_used: dec!(0),
+        commission: dec!(0),
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: "acc-1".to_string(),
+        platform_specific: HashMap::new(),
+    });
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+
+    // The first measurement sets the marks the drawdowns are taken from
+    assert!(orchestrator.assess_drawdowns().await.is_empty());
+
+    // A 2.5% loss crosses the first rung's 2% daily trigger
+    platform.set_position_price("pos-1", dec!(1.0975));
+    let moved = orchestrator.assess_drawdowns().await;
+    assert_eq!(moved.len(), 1);
+    assert_eq!(
+        (
+            moved[0].0.as_str(),
+            moved[0].1.rung,
+            moved[0].1.max_positions
+        ),
+        ("acc-1", 1, Some(2))
+    );
+    assert_eq!(ladder.level("acc-1").unwrap().rung, 1);
+    let status = orchestrator.get_account_status("acc-1").await.unwrap();
+    assert!((status.daily_drawdown - 0.025).abs() < 1e-9);
+    assert!((status.max_drawdown - 0.025).abs() < 1e-9);
+
+    // Within the recovery margin the account stays on its rung
+    platform.set_position_price("pos-1", dec!(1.0980));
+    assert!(orchestrator.assess_drawdowns().await.is_empty());
+
+    platform.set_position_price("pos-1", dec!(1.0990));
+    let moved = orchestrator.assess_drawdowns().await;
+    assert_eq!(moved[0].1.rung, 0);
+    assert!(ladder.level("acc-1").is_none());
+    let status = orchestrator.get_account_status("acc-1").await.unwrap();
+    assert!((status.daily_drawdown - 0.01).abs() < 1e-9);
+    assert!((status.max_drawdown - 0.025).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn accounts_down_the_ladder_trade_smaller_fewer_and_tighter() {
+    let ladder = DrawdownLadder::new(enabled());
+    let orchestrator = TradeExecutionOrchestrator::new().with_drawdown_ladder(ladder.clone());
+    for account_id in ["acc-1", "acc-2"] {
+        let platform = ChaosPlatform::new(account_id, ChaosScenario::calm("calm")).with_quote(
+            "EURUSD",
+            dec!(1.1000),
+            dec!(1.1002),
+        );
+        orchestrator
+            .register_account(account_id.to_string(), Arc::new(platform), 100000.0)
+            .await
+            .unwrap();
+    }
+    ladder.move_to("acc-2", 2, Utc::now());
+
+    let preview = orchestrator.preview_signal(signal("sig-1")).await;
+    let sizing = |account_id: &str| {
+        preview
+            .accounts
+            .iter()
+            .find(|a| a.account_id == account_id)
+            .unwrap()
+            .clone()
+    };
+    let normal = sizing("acc-1").sizing.unwrap();
+    let reduced = sizing("acc-2");
+    assert_eq!(normal.rung_multiplier, 1.0);
+    assert_eq!(reduced.sizing.as_ref().unwrap().rung_multiplier, 0.5);
+    assert!((reduced.sizing.unwrap().risk_per_trade - normal.risk_per_trade * 0.5).abs() < 1e-9);
+    assert_eq!(
+        reduced.adjustments,
+        vec!["risk per trade at 0.5x on drawdown rung 2".to_string()]
+    );
+
+    let plan = ExecutionPlan {
+        signal_id: "sig-2".to_string(),
+        symbol: "EURUSD".to_string(),
+        account_assignments: vec![AccountAssignment {
+            account_id: "acc-2".to_string(),
+            position_size: 1000.0,
+            entry_timing_delay: std::time::Duration::ZERO,
+            priority: 1,
+            risk_per_unit: 0.01,
+        }],
+        timing_variance: HashMap::new(),
+        size_variance: HashMap::new(),
+        rationale: "ladder".to_string(),
+        exit_policy: Some(ExitPolicy {
+            trailing: Some(TrailingConfig::default()),
+            ..ExitPolicy::default()
+        }),
+        tags: Vec::new(),
+        entry_ladder: None,
+    };
+    assert!(orchestrator.execute_plan(&plan).await[0].success);
+
+    let pending = orchestrator.take_pending_exit_policies("acc-2").await;
+    let policy = &pending[0].policy;
+    assert_eq!(policy.name.as_deref(), Some("drawdown-rung-2"));
+    assert_eq!(
+        policy.time_exit.as_ref().unwrap().max_hold_duration,
+        Duration::hours(24) * 6 / 10
+    );
+    assert_eq!(
+        policy.trailing.as_ref().unwrap().atr_multiplier,
+        TrailingConfig::default().atr_multiplier * 0.6
+    );
+
+    // Rung 2 allows a single position, which acc-2 now holds
+    let preview = orchestrator.preview_signal(signal("sig-3")).await;
+    let excluded = preview
+        .accounts
+        .iter()
+        .find(|a| a.account_id == "acc-2")
+        .unwrap();
+    assert!(!excluded.eligible);
+    assert_eq!(
+        excluded.exclusion.as_deref(),
+        Some("1 positions are open, the most allowed")
+    );
+}
@@ -0,0 +1,152 @@
+use chrono::Utc;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use execution_engine::execution::{
+    DropCopyMatch, DropCopyReconciler, OrderOrigin, OrderTracker, TrackedOrder,
+    TradeExecutionOrchestrator,
+};
+use execution_engine::platforms::abstraction::models::*;
+use execution_engine::platforms::dxtrade::fix_messages::FIXMessageBuilder;
+use execution_engine::platforms::dxtrade::{FIXMessage, MessageType};
+use execution_engine::testing::MockTradingPlatform;
+
+fn execution_report(exec_id: &str, order_id: &str, exec_type: &str) -> FIXMessage {
+    FIXMessageBuilder::new("BROKER".to_string(), "TMT-DC".to_string(), 1)
+        .with_field(17, exec_id.to_string())
+        .with_field(37, order_id.to_string())
+        .with_field(11, format!("cl-{}", order_id))
+        .with_field(150, exec_type.to_string())
+        .with_field(55, "EURUSD".to_string())
+        .with_field(54, "1".to_string())
+        .with_field(32, "10000".to_string())
+        .with_field(31, "1.1012".to_string())
+        .build(MessageType::ExecutionReport)
+        .unwrap()
+}
+
+fn tracked(order_id: &str, client_order_id: Option<&str>) -> TrackedOrder {
+    TrackedOrder {
+        account_id: "acc-1".to_string(),
+        platform_order_id: order_id.to_string(),
+        client_order_id: client_order_id.map(str::to_string),
+        signal_id: Some("sig-1".to_string()),
+        symbol: "EURUSD".to_string(),
+        origin: OrderOrigin::Entry,
+        sent_at: Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn fills_of_orders_we_sent_are_matched_and_others_flagged() {
+    let tracker = Arc::new(OrderTracker::new());
+    tracker.record(tracked("ORD-1", None));
+    let reconciler = DropCopyReconciler::new(tracker.clone(), "acc-1").with_grace(Duration::ZERO);
+
+    // Acknowledgements without a fill are not reconciled
+    assert!(reconciler
+        .ingest(&execution_report("E-0", "ORD-1", "0"))
+        .is_none());
+
+    let matched = reconciler
+        .ingest(&execution_report("E-1", "ORD-1", "F"))
+        .unwrap();
+    assert!(
+        matches!(matched, DropCopyMatch::Ours(order) if order.signal_id.as_deref() == Some("sig-1"))
+    );
+    assert_eq!(
+        reconciler.ingest(&execution_report("E-1", "ORD-1", "F")),
+        Some(DropCopyMatch::Duplicate)
+    );
+
+    // Placed by hand on the account
+    assert_eq!(
+        reconciler.ingest(&execution_report("E-2", "MANUAL-7", "F")),
+        Some(DropCopyMatch::Pending)
+    );
+    let flagged = reconciler.sweep().await;
+    assert_eq!(flagged.len(), 1);
+    assert_eq!(flagged[0].platform_order_id.as_deref(), Some("MANUAL-7"));
+    assert_eq!(flagged[0].side, Some(UnifiedOrderSide::Buy));
+    assert_eq!(flagged[0].quantity, dec!(10000));
+    assert_eq!(flagged[0].price, dec!(1.1012));
+
+    let report = reconciler.report();
+    assert_eq!(
+        (
+            report.fills,
+            report.matched,
+            report.duplicates,
+            report.pending
+        ),
+        (2, 1, 1, 0)
+    );
+    assert_eq!(report.unrecognized, flagged);
+    let account = &report.accounts["acc-1"];
+    assert_eq!((account.matched, account.unrecognized), (1, 1));
+}
+
+#[tokio::test]
+async fn fills_arriving_before_their_order_is_recorded_wait_for_it() {
+    let tracker = Arc::new(OrderTracker::new());
+    let reconciler =
+        DropCopyReconciler::new(tracker.clone(), "acc-1").with_grace(Duration::from_secs(60));
+
+    assert_eq!(
+        reconciler.ingest(&execution_report("E-1", "ORD-9", "F")),
+        Some(DropCopyMatch::Pending)
+    );
+    assert!(reconciler.sweep().await.is_empty());
+    assert_eq!(reconciler.report().pending, 1);
+
+    // Known by client order id only, as a ladder rung or a resend may be
+    tracker.record(tracked("ORD-OTHER", Some("cl-ORD-9")));
+    assert!(reconciler.sweep().await.is_empty());
+    let report = reconciler.report();
+    assert_eq!((report.matched, report.pending), (1, 0));
+    assert!(report.unrecognized.is_empty());
+}
+
+#[tokio::test]
+async fn orders_the_orchestrator_sends_are_tracked() {
+    let orchestrator = TradeExecutionOrchestrator::new();
+    let position = UnifiedPosition {
+        position_id: "T-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity: dec!(10000),
+        entry_price: dec!(1.1),
+        current_price: dec!(1.1),
+        unrealized_pnl: dec!(0),
+        realized_pnl: dec!(0),
+        margin_used: dec!(0),
+        commission: dec!(0),
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: "acc-1".to_string(),
+        platform_specific: HashMap::new(),
+    };
+    let platform = MockTradingPlatform::new("acc-1")
+        .with_account_id("acc-1")
+        .with_position(position);
+    orchestrator
+        .register_account("acc-1".to_string(), Arc::new(platform), 10000.0)
+        .await
+        .unwrap();
+
+    let results = orchestrator
+        .emergency_close(Some("acc-1"), "drill".to_string())
+        .await
+        .unwrap();
+    let order_id = results[0].order_id.clone().unwrap();
+    let order = orchestrator
+        .order_tracker()
+        .find(Some(&order_id), None)
+        .unwrap();
+    assert_eq!(order.origin, OrderOrigin::EmergencyClose);
+    assert_eq!(order.account_id, "acc-1");
+}
@@ -0,0 +1,197 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::execution::exit_management::{
+    ClosePositionRequest, ExitManagementPlatformAdapter, OrderModifyRequest, TradingPlatform,
+};
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::*;
+use execution_engine::platforms::abstraction::{
+    DryRunConfig, DryRunMode, DryRunPlatform, DryRunRequest, DRY_RUN_ORDER_PREFIX,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+fn market_order() -> UnifiedOrder {
+    UnifiedOrder {
+        client_order_id: uuid::Uuid::new_v4().to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Buy,
+        order_type: UnifiedOrderType::Market,
+        quantity: dec!(1),
+        price: None,
+        stop_price: None,
+        stop_loss: Some(dec!(1.0950)),
+        take_profit: None,
+        time_in_force: UnifiedTimeInForce::Gtc,
+        account_id: None,
+        metadata: OrderMetadata {
+            strategy_id: None,
+            signal_id: None,
+            risk_parameters: HashMap::new(),
+            tags: Vec::new(),
+            expires_at: None,
+        },
+        max_slippage: None,
+    }
+}
+
+fn long_ticket(id: &str) -> UnifiedPosition {
+    let opened_at = Utc::now();
+    UnifiedPosition {
+        position_id: id.to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity: dec!(1),
+        entry_price: dec!(1.0950),
+        current_price: dec!(1.1001),
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: dec!(100),
+        commission: Decimal::ZERO,
+        stop_loss: Some(dec!(1.0900)),
+        take_profit: None,
+        opened_at,
+        updated_at: opened_at,
+        account_id: "acc-1".to_string(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+fn mock() -> Arc<MockTradingPlatform> {
+    Arc::new(
+        MockTradingPlatform::new("live")
+            .with_quote("EURUSD", dec!(1.1000), dec!(1.1002))
+            .with_position(long_ticket("1001")),
+    )
+}
+
+#[tokio::test]
+async fn orders_are_recorded_instead_of_sent_while_active() {
+    let inner = mock();
+    let mode = Arc::new(DryRunMode::new(&DryRunConfig {
+        enabled: true,
+        ..Default::default()
+    }));
+    let platform = DryRunPlatform::new(inner.clone(), "acc-1", mode.clone());
+
+    let response = platform.place_order(market_order()).await.unwrap();
+    assert!(response.platform_order_id.starts_with(DRY_RUN_ORDER_PREFIX));
+    assert_eq!(response.status, UnifiedOrderStatus::New);
+    assert_eq!(response.price, Some(dec!(1.1001)));
+    assert_eq!(
+        response.platform_specific.get("dry_run"),
+        Some(&serde_json::Value::Bool(true))
+    );
+    platform
+        .cancel_order(&response.platform_order_id)
+        .await
+        .unwrap();
+    assert!(inner.orders().is_empty());
+
+    // Reads still reach the live account
+    assert_eq!(platform.get_positions().await.unwrap().len(), 1);
+
+    let recorded = mode.recorded(Some("acc-1"), 10);
+    assert_eq!(recorded.len(), 2);
+    assert!(matches!(
+        recorded[0].request,
+        DryRunRequest::CancelOrder { .. }
+    ));
+    assert!(matches!(
+        recorded[1].request,
+        DryRunRequest::PlaceOrder { .. }
+    ));
+
+    // Switching dry run off sends orders again
+    mode.set_global(false);
+    platform.place_order(market_order()).await.unwrap();
+    assert_eq!(inner.orders().len(), 1);
+    assert_eq!(mode.recorded(None, 10).len(), 2);
+}
+
+#[tokio::test]
+async fn account_switches_take_precedence_over_the_global_one() {
+    let mode = Arc::new(DryRunMode::new(&DryRunConfig {
+        enabled: false,
+        accounts: HashMap::from([("acc-1".to_string(), true)]),
+        history: 1,
+    }));
+    assert!(mode.is_active("acc-1"));
+    assert!(!mode.is_active("acc-2"));
+
+    mode.set_global(true);
+    mode.set_account("acc-1", Some(false));
+    assert!(!mode.is_active("acc-1"));
+    assert!(mode.is_active("acc-2"));
+
+    // Clearing the override falls back to the global switch
+    mode.set_account("acc-1", None);
+    assert!(mode.is_active("acc-1"));
+    let state = mode.state();
+    assert!(state.global);
+    assert!(state.accounts.is_empty());
+
+    // Only the configured number of records is kept
+    let first = DryRunPlatform::new(mock(), "acc-1", mode.clone());
+    let second = DryRunPlatform::new(mock(), "acc-2", mode.clone());
+    first.place_order(market_order()).await.unwrap();
+    second.place_order(market_order()).await.unwrap();
+    let recorded = mode.recorded(None, 10);
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(recorded[0].account_id, "acc-2");
+    assert!(mode.recorded(Some("acc-1"), 10).is_empty());
+}
+
+#[tokio::test]
+async fn exit_management_changes_are_recorded_against_the_live_position() {
+    let inner = mock();
+    let mode = Arc::new(DryRunMode::new(&DryRunConfig {
+        enabled: true,
+        ..Default::default()
+    }));
+    let adapter = ExitManagementPlatformAdapter::new(Arc::new(DryRunPlatform::new(
+        inner.clone(),
+        "acc-1",
+        mode.clone(),
+    )));
+
+    let positions = adapter.get_positions().await.unwrap();
+    assert_eq!(positions.len(), 1);
+    let modified = adapter
+        .modify_order(OrderModifyRequest {
+            order_id: positions[0].order_id.clone(),
+            new_stop_loss: Some(dec!(1.0950)),
+            new_take_profit: None,
+        })
+        .await
+        .unwrap();
+    assert!(modified.success);
+
+    let closed = adapter
+        .close_position(ClosePositionRequest {
+            position_id: positions[0].id,
+            reason: "time exit".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(closed.close_price, dec!(1.1001));
+
+    // The live position is untouched
+    assert!(inner.modifications().is_empty());
+    assert!(inner.ticket_closes().is_empty());
+    assert_eq!(inner.positions().len(), 1);
+
+    let recorded = mode.recorded(Some("acc-1"), 10);
+    assert!(matches!(
+        &recorded[0].request,
+        DryRunRequest::ClosePositionTicket { position_id, quantity: None } if position_id == "1001"
+    ));
+    assert!(matches!(
+        &recorded[1].request,
+        DryRunRequest::ModifyOrder { modification, .. }
+            if modification.stop_loss == Some(dec!(1.0950))
+    ));
+}
@@ -0,0 +1,224 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::execution::ladder::{LadderState, RungStatus, SizeDistribution};
+use execution_engine::execution::signal_extensions::ENTRY_LADDER_KEY;
+use execution_engine::execution::{
+    LadderConfig, SignalExtensions, TradeExecutionOrchestrator, TradeSignal,
+};
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::{UnifiedOrderSide, UnifiedOrderType};
+use execution_engine::testing::{fixtures, MockTradingPlatform};
+
+fn ladder(rungs: usize, spacing_pips: f64) -> LadderConfig {
+    LadderConfig {
+        rungs,
+        spacing_pips,
+        distribution: SizeDistribution::Equal,
+        centered: false,
+    }
+}
+
+fn signal(id: &str, ladder: &LadderConfig) -> TradeSignal {
+    TradeSignal {
+        stop_loss: 1.0950,
+        take_profit: 1.1100,
+        metadata: HashMap::from([(
+            ENTRY_LADDER_KEY.to_string(),
+            serde_json::to_string(ladder).unwrap(),
+        )]),
+        ..fixtures::signal(id)
+    }
+}
+
+async fn setup(
+    platform: MockTradingPlatform,
+) -> (Arc<MockTradingPlatform>, Arc<TradeExecutionOrchestrator>) {
+    let platform = Arc::new(platform.with_quote("EURUSD", dec!(1.1000), dec!(1.1001)));
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+    (platform, orchestrator)
+}
+
+#[test]
+fn rungs_step_towards_better_prices_with_the_configured_sizes() {
+    let buy = ladder(3, 10.0);
+    assert_eq!(
+        buy.rung_prices("EURUSD", &UnifiedOrderSide::Buy, dec!(1.1000)),
+        vec![dec!(1.1000), dec!(1.0990), dec!(1.0980)]
+    );
+    assert_eq!(buy.weights(), vec![1.0 / 3.0; 3]);
+
+    let centered = LadderConfig {
+        centered: true,
+        distribution: SizeDistribution::Pyramid,
+        ..ladder(3, 10.0)
+    };
+    assert_eq!(
+        centered.rung_prices("EURUSD", &UnifiedOrderSide::Sell, dec!(1.1000)),
+        vec![dec!(1.0990), dec!(1.1000), dec!(1.1010)]
+    );
+    assert_eq!(centered.weights(), vec![1.0 / 6.0, 2.0 / 6.0, 3.0 / 6.0]);
+}
+
+#[test]
+fn invalid_ladders_are_rejected_with_the_signal() {
+    let parse = |value: &str| {
+        SignalExtensions::parse(&HashMap::from([(
+            ENTRY_LADDER_KEY.to_string(),
+            value.to_string(),
+        )]))
+    };
+
+    let parsed =
+        parse(r#"{"rungs": 4, "spacing_pips": 5, "distribution": {"weights": [1, 1, 2, 2]}}"#)
+            .unwrap();
+    assert_eq!(
+        parsed.entry_ladder.unwrap().distribution,
+        SizeDistribution::Weights(vec![1.0, 1.0, 2.0, 2.0])
+    );
+
+    for invalid in [
+        r#"{"rungs": 1, "spacing_pips": 5}"#,
+        r#"{"rungs": 3, "spacing_pips": 0}"#,
+        r#"{"rungs": 3, "spacing_pips": 5, "distribution": {"weights": [1, 2]}}"#,
+        r#"{"rungs": 3}"#,
+    ] {
+        let error = parse(invalid).unwrap_err();
+        assert!(error.starts_with(ENTRY_LADDER_KEY), "{}", error);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn filled_rungs_move_the_exits_and_the_stop_cancels_the_rest() {
+    let (platform, orchestrator) = setup(MockTradingPlatform::new("acc-1")).await;
+
+    let plan = orchestrator
+        .process_signal(signal("sig-1", &ladder(3, 10.0)))
+        .await
+        .unwrap();
+    let results = orchestrator.execute_plan(&plan).await;
+    assert!(results[0].success, "{:?}", results[0].error_message);
+    assert_eq!(results[0].order_id.as_deref(), Some("sig-1:acc-1"));
+
+    let orders = platform.orders();
+    assert_eq!(orders.len(), 3);
+    assert!(orders
+        .iter()
+        .all(|o| o.order_type == UnifiedOrderType::Limit));
+    let prices: Vec<_> = orders.iter().map(|o| o.price.unwrap()).collect();
+    assert_eq!(prices, vec![dec!(1.1000), dec!(1.0990), dec!(1.0980)]);
+
+    // Nothing has filled, so the account holds no position yet
+    let account = orchestrator.get_account_status("acc-1").await.unwrap();
+    assert_eq!((account.open_positions, account.open_exposure), (0, 0.0));
+    let budget = account.risk_budget_remaining;
+
+    // Two of the three rungs fill: the average entry is 1.0995 and the exits
+    // keep the signal's 50 pip stop and 100 pip target from it
+    platform.fill_order(&orders[0].platform_order_id).unwrap();
+    orchestrator.poll_ladders().await;
+    platform.fill_order(&orders[1].platform_order_id).unwrap();
+    platform.set_quote("EURUSD", dec!(1.0990), dec!(1.0991));
+    orchestrator.poll_ladders().await;
+
+    // Both fills count towards one position
+    let account = orchestrator.get_account_status("acc-1").await.unwrap();
+    let filled: f64 = orders[..2]
+        .iter()
+        .map(|o| o.quantity.to_f64().unwrap())
+        .sum();
+    assert_eq!(account.open_positions, 1);
+    assert!((account.open_exposure - filled).abs() < 1e-9);
+    assert!(account.risk_budget_remaining < budget);
+
+    let group = orchestrator
+        .ladders()
+        .group("sig-1", "acc-1")
+        .await
+        .unwrap();
+    assert_eq!(group.average_entry(), Some(dec!(1.0995)));
+    assert_eq!(
+        (group.stop_loss, group.take_profit),
+        (dec!(1.0945), dec!(1.1095))
+    );
+    let position = platform.get_position("EURUSD").await.unwrap().unwrap();
+    assert_eq!(position.stop_loss, Some(dec!(1.0945)));
+    assert_eq!(position.take_profit, Some(dec!(1.1095)));
+    assert_eq!(group.state, LadderState::Active);
+
+    // The bid reaches the stop before the last rung fills
+    platform.set_quote("EURUSD", dec!(1.0945), dec!(1.0946));
+    orchestrator.poll_ladders().await;
+
+    let group = orchestrator
+        .ladders()
+        .group("sig-1", "acc-1")
+        .await
+        .unwrap();
+    assert_eq!(group.state, LadderState::Stopped);
+    assert_eq!(group.rungs[2].status, RungStatus::Cancelled);
+    assert!(platform.fill_order(&orders[2].platform_order_id).is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn closing_the_position_cancels_unfilled_rungs_on_hedging_accounts() {
+    let (platform, orchestrator) = setup(MockTradingPlatform::new("acc-1").with_hedging()).await;
+
+    let plan = orchestrator
+        .process_signal(signal("sig-2", &ladder(3, 10.0)))
+        .await
+        .unwrap();
+    orchestrator.execute_plan(&plan).await;
+    let orders = platform.orders();
+
+    platform.fill_order(&orders[0].platform_order_id).unwrap();
+    platform.fill_order(&orders[1].platform_order_id).unwrap();
+    orchestrator.poll_ladders().await;
+    // One ticket per rung, each moved to the group's exits
+    let positions = platform.positions();
+    assert_eq!(positions.len(), 2);
+    assert!(positions
+        .iter()
+        .all(|p| p.stop_loss == Some(dec!(1.0945)) && p.take_profit == Some(dec!(1.1095))));
+
+    for position in positions {
+        platform
+            .close_position_ticket(&position.position_id, None)
+            .await
+            .unwrap();
+    }
+    orchestrator.poll_ladders().await;
+
+    let group = orchestrator
+        .ladders()
+        .group("sig-2", "acc-1")
+        .await
+        .unwrap();
+    assert_eq!(group.state, LadderState::Closed);
+    assert_eq!(group.rungs[2].status, RungStatus::Cancelled);
+}
+
+#[tokio::test(start_paused = true)]
+async fn ladders_reaching_past_the_stop_fail_the_execution() {
+    let (platform, orchestrator) = setup(MockTradingPlatform::new("acc-1")).await;
+
+    let plan = orchestrator
+        .process_signal(signal("sig-3", &ladder(3, 30.0)))
+        .await
+        .unwrap();
+    let results = orchestrator.execute_plan(&plan).await;
+    assert!(!results[0].success);
+    assert!(results[0]
+        .error_message
+        .as_deref()
+        .unwrap()
+        .contains("beyond the stop loss"));
+    assert!(platform.orders().is_empty());
+    assert!(orchestrator.ladders().groups().await.is_empty());
+}
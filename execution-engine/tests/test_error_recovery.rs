@@ -0,0 +1,163 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use execution_engine::platforms::abstraction::errors::PlatformError;
+use execution_engine::platforms::abstraction::interfaces::DiagnosticsInfo;
+use execution_engine::platforms::abstraction::recovery::{
+    ErrorRecoveryManager, RecoveryConfig, RecoveryHandler, RecoveryPhase, RecoveryProgress,
+    RecoveryState, RecoveryStep,
+};
+
+struct FlakyHandler {
+    failures_before_success: u32,
+    reconnects: AtomicU32,
+    resubscribes: AtomicU32,
+}
+
+impl FlakyHandler {
+    fn new(failures_before_success: u32) -> Self {
+        Self {
+            failures_before_success,
+            reconnects: AtomicU32::new(0),
+            resubscribes: AtomicU32::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl RecoveryHandler for FlakyHandler {
+    async fn reconnect(&self, _account_id: &str) -> Result<(), PlatformError> {
+        let attempt = self.reconnects.fetch_add(1, Ordering::SeqCst) + 1;
+        if attempt <= self.failures_before_success {
+            return Err(PlatformError::NetworkError {
+                reason: "still down".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    async fn resubscribe(&self, _account_id: &str) -> Result<(), PlatformError> {
+        self.resubscribes.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn fetch_state(&self, account_id: &str) -> Result<RecoveryState, PlatformError> {
+        Ok(RecoveryState {
+            account_id: account_id.to_string(),
+            last_known_state: Utc::now(),
+            pending_orders: Vec::new(),
+            open_positions: Vec::new(),
+            last_balance: dec!(1000),
+        })
+    }
+}
+
+fn fast_config(max_attempts: u32) -> RecoveryConfig {
+    RecoveryConfig {
+        max_attempts,
+        initial_backoff_ms: 1,
+        max_backoff_ms: 2,
+    }
+}
+
+#[test]
+fn test_recovery_plan_depends_on_error() {
+    let auth = PlatformError::AuthenticationFailed {
+        reason: "expired".to_string(),
+    };
+    assert_eq!(
+        RecoveryStep::plan_for(&auth)[0],
+        RecoveryStep::Reauthenticate
+    );
+
+    let rejected = PlatformError::OrderRejected {
+        reason: "bad price".to_string(),
+        platform_code: None,
+    };
+    assert!(RecoveryStep::plan_for(&rejected).is_empty());
+}
+
+#[tokio::test]
+async fn test_connection_recovery_retries_until_success() {
+    let manager = ErrorRecoveryManager::new(fast_config(5));
+    let handler = Arc::new(FlakyHandler::new(2));
+    manager.register_handler("acc", handler.clone()).await;
+
+    let recovered = manager
+        .handle_error(
+            "acc",
+            &PlatformError::Disconnected {
+                reason: "socket closed".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(recovered);
+    assert_eq!(handler.reconnects.load(Ordering::SeqCst), 3);
+    assert_eq!(handler.resubscribes.load(Ordering::SeqCst), 1);
+
+    let progress = manager.get_progress("acc").await.unwrap();
+    assert_eq!(progress.phase, RecoveryPhase::Recovered);
+    assert_eq!(progress.attempts, 3);
+    assert_eq!(
+        progress.completed_steps,
+        vec![
+            RecoveryStep::Reconnect,
+            RecoveryStep::Resubscribe,
+            RecoveryStep::ResyncState
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_recovery_failure_surfaces_in_diagnostics() {
+    let manager = ErrorRecoveryManager::new(fast_config(2));
+    manager
+        .register_handler("acc", Arc::new(FlakyHandler::new(10)))
+        .await;
+
+    let result = manager
+        .handle_error(
+            "acc",
+            &PlatformError::ConnectionFailed {
+                reason: "refused".to_string(),
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    let mut diagnostics = DiagnosticsInfo {
+        connection_status: "DISCONNECTED".to_string(),
+        api_limits: HashMap::new(),
+        performance_metrics: HashMap::new(),
+        last_errors: Vec::new(),
+        platform_specific: HashMap::new(),
+    };
+    manager.annotate_diagnostics("acc", &mut diagnostics).await;
+
+    let progress = RecoveryProgress::from_diagnostics(&diagnostics).unwrap();
+    assert_eq!(progress.phase, RecoveryPhase::Failed);
+    assert_eq!(progress.attempts, 2);
+    assert!(progress.last_error.is_some());
+}
+
+#[tokio::test]
+async fn test_non_session_errors_are_not_recovered() {
+    let manager = ErrorRecoveryManager::new(fast_config(1));
+    let recovered = manager
+        .handle_error(
+            "unknown",
+            &PlatformError::InsufficientMargin {
+                required: dec!(100),
+                available: dec!(10),
+            },
+        )
+        .await
+        .unwrap();
+    assert!(!recovered);
+}
@@ -0,0 +1,119 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use execution_engine::platforms::abstraction::circuit_breaker::{
+    CircuitBreaker, CircuitBreakerConfig, CircuitBreakerState,
+};
+use execution_engine::platforms::abstraction::errors::{ErrorClass, ErrorSeverity, PlatformError};
+use execution_engine::platforms::abstraction::retry::{RetryConfig, RetryHandler};
+
+fn no_delay_retry(max_retries: u32) -> RetryHandler {
+    RetryHandler::new(RetryConfig {
+        max_retries,
+        initial_delay_ms: 1,
+        max_delay_ms: 1,
+        backoff_multiplier: 1.0,
+        jitter: false,
+        ..RetryConfig::default()
+    })
+}
+
+#[test]
+fn test_taxonomy_drives_legacy_helpers() {
+    let rate_limited = PlatformError::RateLimitExceeded {
+        retry_after_ms: 750,
+    };
+    let taxonomy = rate_limited.taxonomy();
+
+    assert_eq!(taxonomy.class, ErrorClass::Throttling);
+    assert!(taxonomy.retryable);
+    assert_eq!(rate_limited.retry_delay(), Some(750));
+    assert_eq!(rate_limited.severity(), ErrorSeverity::Medium);
+
+    let auth = PlatformError::AuthenticationFailed {
+        reason: "expired".to_string(),
+    };
+    assert_eq!(auth.class(), ErrorClass::Authentication);
+    assert!(!auth.is_recoverable());
+    assert_eq!(auth.severity(), ErrorSeverity::Critical);
+}
+
+#[test]
+fn test_rejections_do_not_trip_circuit_breaker() {
+    let rejected = PlatformError::OrderRejected {
+        reason: "invalid stop".to_string(),
+        platform_code: None,
+    };
+    assert!(!rejected.trips_circuit_breaker());
+
+    let network = PlatformError::NetworkError {
+        reason: "reset".to_string(),
+    };
+    assert!(network.trips_circuit_breaker());
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_ignores_business_errors() {
+    let breaker = CircuitBreaker::with_config(CircuitBreakerConfig {
+        failure_threshold: 2,
+        success_threshold: 1,
+        failure_window: Duration::from_secs(60),
+        open_timeout: Duration::from_secs(60),
+        half_open_max_operations: 1,
+    });
+
+    for _ in 0..5 {
+        let _ = breaker
+            .execute(|| async {
+                Err::<(), _>(PlatformError::MarketClosed {
+                    symbol: "EURUSD".to_string(),
+                })
+            })
+            .await;
+    }
+    assert_eq!(breaker.get_stats().state, CircuitBreakerState::Closed);
+
+    for _ in 0..2 {
+        let _ = breaker
+            .execute(|| async {
+                Err::<(), _>(PlatformError::ConnectionFailed {
+                    reason: "refused".to_string(),
+                })
+            })
+            .await;
+    }
+    assert_eq!(breaker.get_stats().state, CircuitBreakerState::Open);
+}
+
+#[tokio::test]
+async fn test_retry_handler_only_retries_retryable_errors() {
+    let handler = no_delay_retry(3);
+
+    let calls = AtomicU32::new(0);
+    let result: Result<(), _> = handler
+        .execute_with_retry(|| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(PlatformError::OrderRejected {
+                reason: "no".to_string(),
+                platform_code: None,
+            })
+        })
+        .await;
+    assert!(result.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let calls = AtomicU32::new(0);
+    let result = handler
+        .execute_with_retry(|| async {
+            if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(PlatformError::MarketDataUnavailable {
+                    reason: "stale".to_string(),
+                })
+            } else {
+                Ok(42)
+            }
+        })
+        .await;
+    assert_eq!(result.unwrap(), 42);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,188 @@
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use execution_engine::execution::exit_management::{
+    ClosePositionRequest, ClosePositionResult, ExcursionTracker, ExitAuditLogger,
+    ExitManagementSystem, MarketData, OrderModifyRequest, OrderModifyResult, PartialCloseRequest,
+    Position, TradingPlatform, TrailingConfig, TrailingStopManager, UnifiedPositionSide,
+};
+use execution_engine::journal::TradeJournal;
+use execution_engine::platforms::abstraction::models::UnifiedPosition;
+use execution_engine::testing::long_position_at_entry;
+
+#[derive(Debug, Default)]
+struct MockPlatform {
+    positions: Mutex<Vec<Position>>,
+    price: Mutex<Decimal>,
+    modifications: Mutex<Vec<OrderModifyRequest>>,
+}
+
+impl MockPlatform {
+    fn with_position(position: Position) -> Arc<Self> {
+        Arc::new(Self {
+            price: Mutex::new(position.entry_price),
+            positions: Mutex::new(vec![position]),
+            modifications: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn set_price(&self, price: Decimal) {
+        *self.price.lock().unwrap() = price;
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        let price = *self.price.lock().unwrap();
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: price - dec!(0.00005),
+            ask: price + dec!(0.00005),
+            spread: dec!(0.0001),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        self.modifications.lock().unwrap().push(request.clone());
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: true,
+            message: "modified".to_string(),
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: *self.price.lock().unwrap(),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: *self.price.lock().unwrap(),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_tracker_records_excursions_and_feeds_journal() {
+    let position = long_position_at_entry();
+    let platform = MockPlatform::with_position(position.clone());
+    let journal = Arc::new(TradeJournal::new());
+    let logger = Arc::new(ExitAuditLogger::new().with_trade_journal(journal.clone()));
+    let system = ExitManagementSystem::new(platform.clone(), logger);
+
+    journal
+        .record_open(
+            "acc-1",
+            &UnifiedPosition {
+                position_id: position.id.to_string(),
+                symbol: position.symbol.clone(),
+                side: UnifiedPositionSide::Long,
+                quantity: dec!(10000),
+                entry_price: dec!(1.1000),
+                current_price: dec!(1.1000),
+                unrealized_pnl: dec!(0),
+                realized_pnl: dec!(0),
+                margin_used: dec!(0),
+                commission: dec!(0),
+                stop_loss: Some(dec!(1.0950)),
+                take_profit: None,
+                opened_at: Utc::now(),
+                updated_at: Utc::now(),
+                account_id: "acc-1".to_string(),
+                platform_specific: HashMap::new(),
+            },
+        )
+        .await;
+
+    for price in [dec!(1.0980), dec!(1.1060), dec!(1.1030)] {
+        platform.set_price(price);
+        system.run_position_checks().await;
+    }
+
+    let excursion = system
+        .position_exit_state(position.id)
+        .excursion
+        .expect("excursion should be tracked");
+    assert_eq!(excursion.max_adverse_excursion, dec!(0.0020));
+    assert_eq!(excursion.max_favorable_excursion, dec!(0.0060));
+    assert_eq!(excursion.mfe_retracement(), Some(0.5));
+
+    let trade = journal
+        .get_trade_for_position(&position.id.to_string())
+        .await
+        .unwrap();
+    assert_eq!(trade.max_adverse_excursion, dec!(0.0020));
+    assert_eq!(trade.max_favorable_excursion, dec!(0.0060));
+
+    // Closed positions stop being tracked
+    platform.positions.lock().unwrap().clear();
+    system.run_position_checks().await;
+    assert!(system.get_excursion_tracker().get_excursions().is_empty());
+}
+
+#[tokio::test]
+async fn test_trail_tightens_after_mfe_retracement() {
+    let position = long_position_at_entry();
+    let platform = MockPlatform::with_position(position.clone());
+    let logger = Arc::new(ExitAuditLogger::new());
+    let tracker = Arc::new(ExcursionTracker::new(platform.clone(), logger.clone()));
+    let mut trailing =
+        TrailingStopManager::new(platform.clone(), logger).with_excursion_tracker(tracker.clone());
+    trailing.configure_symbol(
+        "EURUSD".to_string(),
+        TrailingConfig {
+            mfe_retracement_threshold: Some(0.4),
+            ..Default::default()
+        },
+    );
+
+    // Trail activates 10 pips behind price
+    platform.set_price(dec!(1.1020));
+    tracker.update_excursions().await.unwrap();
+    trailing.activate_trailing_stop(&position).await.unwrap();
+    assert_eq!(
+        trailing.get_active_trail(position.id).unwrap().trail_level,
+        dec!(1.1010)
+    );
+
+    // A retracement within the threshold keeps the full trail distance
+    platform.set_price(dec!(1.1060));
+    tracker.update_excursions().await.unwrap();
+    platform.set_price(dec!(1.1045));
+    tracker.update_excursions().await.unwrap();
+    trailing.update_trailing_stops().await.unwrap();
+    let level = trailing.get_active_trail(position.id).unwrap().trail_level;
+    assert_eq!(level, dec!(1.1035));
+
+    // Past the threshold the trail distance is halved
+    platform.set_price(dec!(1.1100));
+    tracker.update_excursions().await.unwrap();
+    platform.set_price(dec!(1.1055));
+    tracker.update_excursions().await.unwrap();
+    trailing.update_trailing_stops().await.unwrap();
+    let trail = trailing.get_active_trail(position.id).unwrap();
+    assert_eq!(trail.trail_level, dec!(1.1050));
+    assert_eq!(trail.update_count, 2);
+    assert_eq!(platform.modifications.lock().unwrap().len(), 2);
+}
@@ -0,0 +1,214 @@
+use execution_engine::execution::{
+    ExecutionAuditEntry, ExecutionHistoryConfig, ExecutionHistoryQuery, ExecutionHistoryRetention,
+    ExecutionHistoryStore, InMemoryExecutionHistoryStore, RetentionPolicy,
+    TradeExecutionOrchestrator,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+const DAY: Duration = Duration::from_secs(86_400);
+
+fn entry(
+    id: &str,
+    signal_id: &str,
+    account_id: &str,
+    action: &str,
+    age: Duration,
+) -> ExecutionAuditEntry {
+    ExecutionAuditEntry {
+        id: id.to_string(),
+        timestamp: SystemTime::now() - age,
+        signal_id: signal_id.to_string(),
+        account_id: account_id.to_string(),
+        action: action.to_string(),
+        decision_rationale: String::new(),
+        result: None,
+        metadata: HashMap::new(),
+        tags: vec![format!("signal:{}", signal_id)],
+    }
+}
+
+async fn store_with_history() -> InMemoryExecutionHistoryStore {
+    let store = InMemoryExecutionHistoryStore::new();
+    for (i, age) in (0..10u64).rev().enumerate() {
+        let account = if i % 2 == 0 { "alpha" } else { "beta" };
+        let action = if i == 3 {
+            "EXECUTION_FAILED"
+        } else {
+            "EXECUTION_SUCCESS"
+        };
+        store
+            .append(&entry(
+                &format!("e{}", i),
+                &format!("sig-{}", i / 2),
+                account,
+                action,
+                DAY * age as u32,
+            ))
+            .await
+            .unwrap();
+    }
+    store
+}
+
+fn ids(entries: &[ExecutionAuditEntry]) -> Vec<&str> {
+    entries.iter().map(|entry| entry.id.as_str()).collect()
+}
+
+#[tokio::test]
+async fn pages_walk_back_from_newest() {
+    let store = store_with_history().await;
+
+    let first = store
+        .query(&ExecutionHistoryQuery::recent(4))
+        .await
+        .unwrap();
+    assert_eq!(ids(&first.entries), ["e6", "e7", "e8", "e9"]);
+    assert_eq!(first.total, 10);
+    assert_eq!(first.next_offset, Some(4));
+
+    let last = store
+        .query(&ExecutionHistoryQuery {
+            offset: 8,
+            ..ExecutionHistoryQuery::recent(4)
+        })
+        .await
+        .unwrap();
+    assert_eq!(ids(&last.entries), ["e0", "e1"]);
+    assert_eq!(last.next_offset, None);
+}
+
+#[tokio::test]
+async fn queries_filter_by_signal_account_action_and_time() {
+    let store = store_with_history().await;
+
+    let by_signal = store
+        .query(&ExecutionHistoryQuery {
+            signal_id: Some("sig-1".to_string()),
+            ..ExecutionHistoryQuery::recent(10)
+        })
+        .await
+        .unwrap();
+    assert_eq!(ids(&by_signal.entries), ["e2", "e3"]);
+
+    let by_account = store
+        .query(&ExecutionHistoryQuery {
+            account_id: Some("beta".to_string()),
+            action: Some("EXECUTION_SUCCESS".to_string()),
+            ..ExecutionHistoryQuery::recent(10)
+        })
+        .await
+        .unwrap();
+    assert_eq!(ids(&by_account.entries), ["e1", "e5", "e7", "e9"]);
+
+    let recent = store
+        .query(&ExecutionHistoryQuery {
+            since: Some(SystemTime::now() - DAY * 2 - Duration::from_secs(60)),
+            tags: vec!["signal:sig-4".to_string()],
+            ..ExecutionHistoryQuery::recent(10)
+        })
+        .await
+        .unwrap();
+    assert_eq!(ids(&recent.entries), ["e8", "e9"]);
+}
+
+#[tokio::test]
+async fn retention_keeps_overridden_actions_longer() {
+    let store = Arc::new(store_with_history().await);
+    let policy = RetentionPolicy {
+        default_days: 5,
+        actions: HashMap::from([("EXECUTION_FAILED".to_string(), 30)]),
+    };
+
+    // e0..e4 are 9 to 5 days old; the failure among them survives
+    let purged = store.purge(&policy, SystemTime::now()).await.unwrap();
+    assert_eq!(purged, 4);
+    let remaining = store
+        .query(&ExecutionHistoryQuery::recent(10))
+        .await
+        .unwrap();
+    assert_eq!(
+        ids(&remaining.entries),
+        ["e3", "e5", "e6", "e7", "e8", "e9"]
+    );
+
+    let forever = RetentionPolicy {
+        default_days: 0,
+        actions: HashMap::new(),
+    };
+    assert_eq!(store.purge(&forever, SystemTime::now()).await.unwrap(), 0);
+
+    let retention = ExecutionHistoryRetention::new(
+        store.clone(),
+        &ExecutionHistoryConfig {
+            retention: RetentionPolicy {
+                default_days: 1,
+                actions: HashMap::new(),
+            },
+            ..Default::default()
+        },
+    );
+    retention.purge().await;
+    assert_eq!(store.len().await, 1);
+}
+
+#[tokio::test]
+async fn orchestrator_writes_audit_entries_to_store() {
+    let store = Arc::new(InMemoryExecutionHistoryStore::new());
+    let orchestrator = TradeExecutionOrchestrator::new().with_history_store(store.clone());
+
+    for i in 0..3 {
+        orchestrator
+            .record_signal_event(
+                &format!("sig-{}", i),
+                "SIGNAL_EXPIRED",
+                "Expired before an account could take it".to_string(),
+                Vec::new(),
+            )
+            .await;
+    }
+
+    // Appends run off the execution path
+    for _ in 0..50 {
+        if store.len().await == 3 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let page = orchestrator
+        .search_execution_history(&ExecutionHistoryQuery {
+            signal_id: Some("sig-1".to_string()),
+            ..ExecutionHistoryQuery::recent(10)
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.total, 1);
+    assert_eq!(page.entries[0].action, "SIGNAL_EXPIRED");
+}
+
+#[tokio::test]
+async fn orchestrator_without_store_pages_recent_memory() {
+    let orchestrator = TradeExecutionOrchestrator::new();
+    for i in 0..5 {
+        orchestrator
+            .record_signal_event(
+                &format!("sig-{}", i),
+                "SIGNAL_HELD",
+                String::new(),
+                Vec::new(),
+            )
+            .await;
+    }
+
+    let page = orchestrator
+        .search_execution_history(&ExecutionHistoryQuery {
+            offset: 2,
+            ..ExecutionHistoryQuery::recent(2)
+        })
+        .await
+        .unwrap();
+    let signals: Vec<&str> = page.entries.iter().map(|e| e.signal_id.as_str()).collect();
+    assert_eq!(signals, ["sig-1", "sig-2"]);
+    assert_eq!(page.next_offset, Some(4));
+}
@@ -0,0 +1,307 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use rust_decimal_macros::dec;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use execution_engine::execution::{ExecutionResult, TradeExecutionOrchestrator};
+use execution_engine::messaging::{
+    ExecutionOutbox, FileOutboxStore, HttpOutboxPublisher, OutboxConfig, OutboxMessage,
+    OutboxPublisher,
+};
+use execution_engine::testing::{signal, MockTradingPlatform};
+
+#[derive(Default)]
+struct RecordingPublisher {
+    down: AtomicBool,
+    published: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl OutboxPublisher for RecordingPublisher {
+    async fn publish(&self, message: &OutboxMessage) -> Result<()> {
+        if self.down.load(Ordering::SeqCst) {
+            return Err(anyhow!("broker unavailable"));
+        }
+        self.published.lock().unwrap().push(message.id.clone());
+        Ok(())
+    }
+}
+
+fn result(signal_id: &str, account_id: &str) -> ExecutionResult {
+    ExecutionResult {
+        signal_id: signal_id.to_string(),
+        account_id: account_id.to_string(),
+        order_id: Some("MOCK_1".to_string()),
+        success: true,
+        error_message: None,
+        execution_time: std::time::Duration::from_millis(40),
+        actual_entry_price: Some(1.1001),
+        slippage: None,
+        requested_quantity: None,
+        filled_quantity: None,
+        rejection: None,
+        tags: Vec::new(),
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn results_are_kept_until_the_bus_accepts_them() {
+    let outbox = Arc::new(ExecutionOutbox::new(OutboxConfig::default()));
+    let orchestrator = TradeExecutionOrchestrator::new().with_outbox(outbox.clone());
+    let platform = Arc::new(MockTradingPlatform::new("acc-1").with_quote(
+        "EURUSD",
+        dec!(1.1000),
+        dec!(1.1001),
+    ));
+    orchestrator
+        .register_account("acc-1".to_string(), platform, 100000.0)
+        .await
+        .unwrap();
+
+    let plan = orchestrator.process_signal(signal("sig-1")).await.unwrap();
+    let results = orchestrator.execute_plan(&plan).await;
+    assert!(results[0].success, "{:?}", results[0].error_message);
+
+    // The order went through but the broker is down
+    let publisher = RecordingPublisher::default();
+    publisher.down.store(true, Ordering::SeqCst);
+    let now = Utc::now();
+    let report = outbox.relay(&publisher, now).await;
+    assert_eq!((report.published, report.failed, report.pending), (0, 1, 1));
+
+    let pending = outbox.pending().await;
+    let id = pending[0].id.clone();
+    assert!(id.starts_with("sig-1:acc-1:"), "{}", id);
+    assert_eq!(pending[0].attempts, 1);
+    assert_eq!(pending[0].last_error.as_deref(), Some("broker unavailable"));
+    let snapshot = orchestrator.snapshot().await;
+    assert_eq!(snapshot.unpublished_results.len(), 1);
+
+    // Nothing is retried before the backoff has passed
+    publisher.down.store(false, Ordering::SeqCst);
+    assert_eq!(outbox.relay(&publisher, now).await.published, 0);
+
+    let report = outbox
+        .relay(&publisher, now + Duration::milliseconds(500))
+        .await;
+    assert_eq!((report.published, report.pending), (1, 0));
+    assert_eq!(*publisher.published.lock().unwrap(), vec![id]);
+    assert!(orchestrator.snapshot().await.unpublished_results.is_empty());
+}
+
+#[tokio::test]
+async fn unpublished_results_survive_a_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("outbox.jsonl");
+    let outbox = || {
+        ExecutionOutbox::new(OutboxConfig::default())
+            .with_store(Arc::new(FileOutboxStore::new(&path)))
+    };
+
+    let before_crash = outbox();
+    before_crash
+        .enqueue("run-1", &result("sig-1", "acc-1"))
+        .await
+        .unwrap();
+    before_crash
+        .enqueue("run-1", &result("sig-1", "acc-2"))
+        .await
+        .unwrap();
+    // Enqueueing the same result again keeps one message
+    before_crash
+        .enqueue("run-1", &result("sig-1", "acc-1"))
+        .await
+        .unwrap();
+    let publisher = RecordingPublisher::default();
+    before_crash.relay(&publisher, Utc::now()).await;
+    before_crash
+        .enqueue("run-1", &result("sig-2", "acc-1"))
+        .await
+        .unwrap();
+
+    let restarted = outbox();
+    assert_eq!(restarted.load().await.unwrap(), 1);
+    let pending = restarted.pending().await;
+    assert_eq!(pending[0].id, "sig-2:acc-1:run-1");
+    assert_eq!(pending[0].payload.actual_entry_price, Some(1.1001));
+
+    restarted.relay(&publisher, Utc::now()).await;
+    assert_eq!(
+        *publisher.published.lock().unwrap(),
+        vec![
+            "sig-1:acc-1:run-1",
+            "sig-1:acc-2:run-1",
+            "sig-2:acc-1:run-1"
+        ]
+    );
+    assert_eq!(outbox().load().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn retries_back_off_exponentially_and_keep_results_in_order() {
+    let outbox = ExecutionOutbox::new(OutboxConfig {
+        initial_backoff_ms: 1000,
+        max_backoff_secs: 3,
+        ..OutboxConfig::default()
+    });
+    outbox
+        .enqueue("run-1", &result("sig-1", "acc-1"))
+        .await
+        .unwrap();
+    outbox
+        .enqueue("run-1", &result("sig-2", "acc-1"))
+        .await
+        .unwrap();
+
+    let publisher = RecordingPublisher::default();
+    publisher.down.store(true, Ordering::SeqCst);
+    let start = Utc::now();
+    let mut now = start;
+    let mut delays = Vec::new();
+    for _ in 0..4 {
+        let report = outbox.relay(&publisher, now).await;
+        // The first failure ends the pass, so the second result waits behind it
+        assert_eq!(report.failed, 1);
+        let next = outbox.pending().await[0].next_attempt_at;
+        delays.push((next - now).num_milliseconds());
+        now = next;
+    }
+    assert_eq!(delays, vec![1000, 2000, 3000, 3000]);
+    assert_eq!(outbox.pending().await[1].attempts, 0);
+}
+
+#[tokio::test]
+async fn executing_a_signal_again_is_not_taken_for_a_redelivery() {
+    let outbox = Arc::new(ExecutionOutbox::new(OutboxConfig::default()));
+    let orchestrator = TradeExecutionOrchestrator::new().with_outbox(outbox.clone());
+    let platform = Arc::new(MockTradingPlatform::new("acc-1").with_quote(
+        "EURUSD",
+        dec!(1.1000),
+        dec!(1.1001),
+    ));
+    orchestrator
+        .register_account("acc-1".to_string(), platform, 100000.0)
+        .await
+        .unwrap();
+
+    let plan = orchestrator.process_signal(signal("sig-1")).await.unwrap();
+    orchestrator.execute_plan(&plan).await;
+    orchestrator.execute_plan(&plan).await;
+
+    let pending = outbox.pending().await;
+    assert_eq!(pending.len(), 2);
+    assert_ne!(pending[0].id, pending[1].id);
+}
+
+#[tokio::test]
+async fn http_publisher_posts_messages_under_their_idempotency_key() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let router = {
+        let received = received.clone();
+        axum::Router::new().route(
+            "/results",
+            axum::routing::post(
+                move |headers: axum::http::HeaderMap, body: axum::Json<OutboxMessage>| async move {
+                    let key = headers["idempotency-key"].to_str().unwrap().to_string();
+                    received.lock().unwrap().push((key, body.0.id));
+                    axum::http::StatusCode::ACCEPTED
+                },
+            ),
+        )
+    };
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let outbox = ExecutionOutbox::new(OutboxConfig::default());
+    outbox
+        .enqueue("run-1", &result("sig-1", "acc-1"))
+        .await
+        .unwrap();
+
+    // Refused deliveries stay in the outbox
+    let missing = HttpOutboxPublisher::new(format!("http://{}/missing", address)).unwrap();
+    assert_eq!(outbox.relay(&missing, Utc::now()).await.failed, 1);
+
+    let publisher = HttpOutboxPublisher::new(format!("http://{}/results", address)).unwrap();
+    let report = outbox
+        .relay(&publisher, Utc::now() + Duration::seconds(1))
+        .await;
+    assert_eq!((report.published, report.pending), (1, 0));
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![(
+            "sig-1:acc-1:run-1".to_string(),
+            "sig-1:acc-1:run-1".to_string()
+        )]
+    );
+}
+
+#[tokio::test]
+async fn the_oldest_results_are_dropped_beyond_the_cap() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("outbox.jsonl");
+    let config = OutboxConfig {
+        max_pending: 2,
+        ..OutboxConfig::default()
+    };
+    let outbox =
+        ExecutionOutbox::new(config.clone()).with_store(Arc::new(FileOutboxStore::new(&path)));
+    for signal_id in ["sig-1", "sig-2", "sig-3"] {
+        outbox
+            .enqueue("run-1", &result(signal_id, "acc-1"))
+            .await
+            .unwrap();
+    }
+
+    let ids = |messages: Vec<OutboxMessage>| -> Vec<String> {
+        messages.into_iter().map(|m| m.id).collect()
+    };
+    assert_eq!(
+        ids(outbox.pending().await),
+        vec!["sig-2:acc-1:run-1", "sig-3:acc-1:run-1"]
+    );
+    // The dropped result does not come back on restart
+    let restarted = ExecutionOutbox::new(config).with_store(Arc::new(FileOutboxStore::new(&path)));
+    assert_eq!(restarted.load().await.unwrap(), 2);
+    assert_eq!(
+        ids(restarted.pending().await),
+        vec!["sig-2:acc-1:run-1", "sig-3:acc-1:run-1"]
+    );
+}
+
+#[tokio::test]
+async fn the_store_is_compacted_once_enough_results_are_published() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("outbox.jsonl");
+    let outbox = ExecutionOutbox::new(OutboxConfig {
+        compact_after: 2,
+        ..OutboxConfig::default()
+    })
+    .with_store(Arc::new(FileOutboxStore::new(&path)));
+    let publisher = RecordingPublisher::default();
+    let lines = || std::fs::read_to_string(&path).unwrap().lines().count();
+
+    outbox
+        .enqueue("run-1", &result("sig-1", "acc-1"))
+        .await
+        .unwrap();
+    outbox.relay(&publisher, Utc::now()).await;
+    // One enqueued and one published record, below the threshold
+    assert_eq!(lines(), 2);
+
+    outbox
+        .enqueue("run-1", &result("sig-2", "acc-1"))
+        .await
+        .unwrap();
+    outbox.relay(&publisher, Utc::now()).await;
+    assert_eq!(lines(), 0);
+
+    outbox
+        .enqueue("run-1", &result("sig-3", "acc-1"))
+        .await
+        .unwrap();
+    assert_eq!(lines(), 1);
+}
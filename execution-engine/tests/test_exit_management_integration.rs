@@ -1,320 +1,42 @@
 use chrono::Utc;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::time::{sleep, Duration};
 
-use async_trait::async_trait;
 use execution_engine::execution::exit_management::types::*;
 use execution_engine::execution::exit_management::{
     BreakEvenManager, ExitAuditLogger, ExitManagementIntegration, ExitManagementSystem,
     NewsEventProtection, PartialProfitManager, TimeBasedExitManager, TrailingStopManager,
 };
-use execution_engine::platforms::abstraction::errors::PlatformError;
 use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
-use execution_engine::platforms::abstraction::models::{
-    OrderModification, TradingSession, UnifiedMarketData, UnifiedOrderResponse, UnifiedOrderSide,
-    UnifiedOrderStatus, UnifiedOrderType, UnifiedPosition, UnifiedPositionSide,
-};
-
-/// Comprehensive mock platform for full integration testing
-struct ComprehensiveMockPlatform {
-    positions: std::sync::RwLock<Vec<UnifiedPosition>>,
-    market_data: std::sync::RwLock<HashMap<String, UnifiedMarketData>>,
-    order_modifications: std::sync::RwLock<Vec<OrderModification>>,
-    position_closes: std::sync::RwLock<Vec<(String, Option<Decimal>)>>,
-}
-
-impl ComprehensiveMockPlatform {
-    fn new() -> Self {
-        let mut market_data = HashMap::new();
-
-        // Setup realistic market data for multiple symbols
-        let symbols = vec![
-            ("EURUSD", 1.0800, 0.0002),
-            ("GBPUSD", 1.2500, 0.0003),
-            ("USDJPY", 150.50, 0.003),
-            ("AUDUSD", 0.6650, 0.0002),
-        ];
-
-        for (symbol, mid_price, spread) in symbols {
-            let spread_decimal = Decimal::from_f64_retain(spread).unwrap();
-            let mid_decimal = Decimal::from_f64_retain(mid_price).unwrap();
-
-            market_data.insert(
-                symbol.to_string(),
-                UnifiedMarketData {
-                    symbol: symbol.to_string(),
-                    bid: mid_decimal - (spread_decimal / Decimal::from(2)),
-                    ask: mid_decimal + (spread_decimal / Decimal::from(2)),
-                    spread: spread_decimal,
-                    last_price: Some(mid_decimal),
-                    volume: Some(Decimal::from(1000)),
-                    high: Some(mid_decimal * Decimal::from_f64_retain(1.002).unwrap()),
-                    low: Some(mid_decimal * Decimal::from_f64_retain(0.998).unwrap()),
-                    timestamp: Utc::now(),
-                    session: Some(TradingSession::Regular),
-                    platform_specific: HashMap::new(),
-                },
-            );
-        }
-
-        Self {
-            positions: std::sync::RwLock::new(Vec::new()),
-            market_data: std::sync::RwLock::new(market_data),
-            order_modifications: std::sync::RwLock::new(Vec::new()),
-            position_closes: std::sync::RwLock::new(Vec::new()),
-        }
-    }
-
-    fn add_position(&self, position: UnifiedPosition) {
-        let mut positions = self.positions.write().unwrap();
-        positions.push(position);
-    }
-
-    fn simulate_price_movement(&self, symbol: &str, new_price: f64) {
-        // Update market data
-        {
-            let mut data = self.market_data.write().unwrap();
-            if let Some(market_data) = data.get_mut(symbol) {
-                let new_price_decimal = Decimal::from_f64_retain(new_price).unwrap();
-                let spread = market_data.spread;
-                market_data.bid = new_price_decimal - (spread / Decimal::from(2));
-                market_data.ask = new_price_decimal + (spread / Decimal::from(2));
-                market_data.last_price = Some(new_price_decimal);
-                market_data.timestamp = Utc::now();
-            }
-        }
-
-        // Update position prices
-        {
-            let mut positions = self.positions.write().unwrap();
-            for position in positions.iter_mut() {
-                if position.symbol == symbol {
-                    position.current_price = Decimal::from_f64_retain(new_price).unwrap();
-                    // Recalculate unrealized PnL
-                    let price_diff = match position.side {
-                        UnifiedPositionSide::Long => position.current_price - position.entry_price,
-                        UnifiedPositionSide::Short => position.entry_price - position.current_price,
-                    };
-                    position.unrealized_pnl = price_diff * position.quantity;
-                }
-            }
-        }
-    }
-
-    fn get_order_modification_count(&self) -> usize {
-        self.order_modifications.read().unwrap().len()
-    }
-
-    fn get_position_close_count(&self) -> usize {
-        self.position_closes.read().unwrap().len()
-    }
+use execution_engine::platforms::abstraction::models::{UnifiedPosition, UnifiedPositionSide};
+use execution_engine::testing::MockTradingPlatform;
+
+/// Symbols quoted by the mock platform, with their starting mid price and spread
+const SYMBOLS: [(&str, Decimal, Decimal); 4] = [
+    ("EURUSD", dec!(1.0800), dec!(0.0002)),
+    ("GBPUSD", dec!(1.2500), dec!(0.0003)),
+    ("USDJPY", dec!(150.50), dec!(0.003)),
+    ("AUDUSD", dec!(0.6650), dec!(0.0002)),
+];
+
+fn comprehensive_platform() -> MockTradingPlatform {
+    let platform = MockTradingPlatform::new("ComprehensiveMockPlatform")
+        .with_platform_type(execution_engine::platforms::PlatformType::MetaTrader5);
+    for (symbol, mid, spread) in SYMBOLS {
+        platform.set_quote(symbol, mid - spread / dec!(2), mid + spread / dec!(2));
+    }
+    platform
 }
 
-#[async_trait]
-impl ITradingPlatform for ComprehensiveMockPlatform {
-    fn platform_type(&self) -> execution_engine::platforms::PlatformType {
-        execution_engine::platforms::PlatformType::MetaTrader5
-    }
-
-    fn platform_name(&self) -> &str {
-        "ComprehensiveMockPlatform"
-    }
-    fn platform_version(&self) -> &str {
-        "2.0.0"
-    }
-
-    async fn connect(&mut self) -> Result<(), PlatformError> {
-        Ok(())
-    }
-    async fn disconnect(&mut self) -> Result<(), PlatformError> {
-        Ok(())
-    }
-    async fn is_connected(&self) -> bool {
-        true
-    }
-    async fn ping(&self) -> Result<u64, PlatformError> {
-        Ok(5)
-    }
-
-    async fn place_order(
-        &self,
-        _order: execution_engine::platforms::abstraction::UnifiedOrder,
-    ) -> Result<UnifiedOrderResponse, PlatformError> {
-        unimplemented!("place_order not needed for exit management integration tests")
-    }
-
-    async fn modify_order(
-        &self,
-        order_id: &str,
-        modifications: OrderModification,
-    ) -> Result<UnifiedOrderResponse, PlatformError> {
-        // Record the modification
-        {
-            let mut mods = self.order_modifications.write().unwrap();
-            mods.push(modifications.clone());
-        }
-
-        // Simulate successful modification
-        Ok(UnifiedOrderResponse {
-            platform_order_id: order_id.to_string(),
-            client_order_id: order_id.to_string(),
-            status: UnifiedOrderStatus::New,
-            symbol: "EURUSD".to_string(),
-            side: UnifiedOrderSide::Buy,
-            order_type: UnifiedOrderType::Market,
-            quantity: Decimal::from(1),
-            filled_quantity: Decimal::ZERO,
-            remaining_quantity: Decimal::from(1),
-            price: None,
-            average_fill_price: modifications.price,
-            commission: Some(Decimal::from_f64_retain(2.0).unwrap()),
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-            filled_at: None,
-            platform_specific: HashMap::new(),
-        })
-    }
-
-    async fn cancel_order(&self, _order_id: &str) -> Result<(), PlatformError> {
-        Ok(())
-    }
-
-    async fn get_order(&self, _order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
-        unimplemented!("get_order not needed for integration tests")
-    }
-
-    async fn get_orders(
-        &self,
-        _filter: Option<execution_engine::platforms::abstraction::OrderFilter>,
-    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
-        Ok(Vec::new())
-    }
-
-    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
-        Ok(self.positions.read().unwrap().clone())
-    }
-
-    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
-        let positions = self.positions.read().unwrap();
-        Ok(positions.iter().find(|p| p.symbol == symbol).cloned())
-    }
-
-    async fn close_position(
-        &self,
-        symbol: &str,
-        quantity: Option<Decimal>,
-    ) -> Result<UnifiedOrderResponse, PlatformError> {
-        // Record the close
-        {
-            let mut closes = self.position_closes.write().unwrap();
-            closes.push((symbol.to_string(), quantity));
-        }
-
-        let positions = self.positions.read().unwrap();
-        let position = positions
-            .iter()
-            .find(|p| p.symbol == symbol)
-            .ok_or_else(|| PlatformError::PositionNotFound {
-                symbol: symbol.to_string(),
-            })?;
-
-        let close_quantity = quantity.unwrap_or(position.quantity);
-
-        Ok(UnifiedOrderResponse {
-            platform_order_id: format!("close_{}", position.position_id),
-            client_order_id: format!("close_{}", position.position_id),
-            status: UnifiedOrderStatus::Filled,
-            symbol: symbol.to_string(),
-            side: match position.side {
-                UnifiedPositionSide::Long => UnifiedOrderSide::Sell,
-                UnifiedPositionSide::Short => UnifiedOrderSide::Buy,
-            },
-            order_type: UnifiedOrderType::Market,
-            quantity: close_quantity,
-            filled_quantity: close_quantity,
-            remaining_quantity: Decimal::ZERO,
-            price: None,
-            average_fill_price: Some(position.current_price),
-            commission: Some(Decimal::from_f64_retain(2.0).unwrap()),
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-            filled_at: Some(Utc::now()),
-            platform_specific: HashMap::new(),
-        })
-    }
-
-    async fn get_account_info(
-        &self,
-    ) -> Result<execution_engine::platforms::abstraction::UnifiedAccountInfo, PlatformError> {
-        unimplemented!("get_account_info not needed for integration tests")
-    }
-
-    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
-        Ok(Decimal::from(50000))
-    }
-
-    async fn get_margin_info(
-        &self,
-    ) -> Result<execution_engine::platforms::abstraction::MarginInfo, PlatformError> {
-        unimplemented!("get_margin_info not needed for integration tests")
-    }
-
-    async fn get_market_data(&self, symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
-        self.market_data
-            .read()
-            .unwrap()
-            .get(symbol)
-            .cloned()
-            .ok_or_else(|| PlatformError::SymbolNotFound {
-                symbol: symbol.to_string(),
-            })
-    }
-
-    async fn subscribe_market_data(
-        &self,
-        _symbols: Vec<String>,
-    ) -> Result<tokio::sync::mpsc::Receiver<UnifiedMarketData>, PlatformError> {
-        unimplemented!("subscribe_market_data not needed for integration tests")
-    }
-
-    async fn unsubscribe_market_data(&self, _symbols: Vec<String>) -> Result<(), PlatformError> {
-        Ok(())
-    }
-
-    fn capabilities(&self) -> execution_engine::platforms::abstraction::PlatformCapabilities {
-        unimplemented!("capabilities not needed for integration tests")
-    }
-
-    async fn subscribe_events(
-        &self,
-    ) -> Result<
-        tokio::sync::mpsc::Receiver<execution_engine::platforms::abstraction::PlatformEvent>,
-        PlatformError,
-    > {
-        unimplemented!("subscribe_events not needed for integration tests")
-    }
-
-    async fn get_event_history(
-        &self,
-        _filter: execution_engine::platforms::abstraction::EventFilter,
-    ) -> Result<Vec<execution_engine::platforms::abstraction::PlatformEvent>, PlatformError> {
-        Ok(Vec::new())
-    }
-
-    async fn health_check(
-        &self,
-    ) -> Result<execution_engine::platforms::abstraction::HealthStatus, PlatformError> {
-        unimplemented!("health_check not needed for integration tests")
-    }
-
-    async fn get_diagnostics(
-        &self,
-    ) -> Result<execution_engine::platforms::abstraction::DiagnosticsInfo, PlatformError> {
-        unimplemented!("get_diagnostics not needed for integration tests")
-    }
+/// Move the mid price of `symbol`, keeping its spread
+fn simulate_price_movement(platform: &MockTradingPlatform, symbol: &str, new_price: f64) {
+    let (_, _, spread) = SYMBOLS.iter().find(|(s, _, _)| *s == symbol).unwrap();
+    let mid = Decimal::from_f64_retain(new_price).unwrap();
+    platform.set_quote(symbol, mid - spread / dec!(2), mid + spread / dec!(2));
 }
 
 fn create_realistic_position(
@@ -361,7 +83,7 @@ fn create_realistic_position(
 
 #[tokio::test]
 async fn test_comprehensive_exit_management_workflow() {
-    let mock_platform = Arc::new(ComprehensiveMockPlatform::new());
+    let mock_platform = Arc::new(comprehensive_platform());
 
     // Setup multiple positions with different scenarios
     let positions = vec![
@@ -485,20 +207,20 @@ async fn test_comprehensive_exit_management_workflow() {
         let _ = exit_management.update_position(internal_position).await;
     }
 
-    let initial_modifications = mock_platform.get_order_modification_count();
+    let initial_modifications = mock_platform.modifications().len();
     println!("Initial order modifications: {}", initial_modifications);
 
     // Simulate price movements to trigger different exit strategies
     println!("Simulating price movements...");
 
     // Move EURUSD higher to trigger trailing stops
-    mock_platform.simulate_price_movement("EURUSD", 1.0840);
+    simulate_price_movement(&mock_platform, "EURUSD", 1.0840);
 
     // Move GBPUSD to optimal break-even position
-    mock_platform.simulate_price_movement("GBPUSD", 1.2475);
+    simulate_price_movement(&mock_platform, "GBPUSD", 1.2475);
 
     // Move USDJPY to partial profit level
-    mock_platform.simulate_price_movement("USDJPY", 150.60);
+    simulate_price_movement(&mock_platform, "USDJPY", 150.60);
 
     // Process positions again after price movements
     println!("Processing positions after price movements...");
@@ -523,7 +245,7 @@ async fn test_comprehensive_exit_management_workflow() {
     }
 
     // Verify system responded to price movements
-    let final_modifications = mock_platform.get_order_modification_count();
+    let final_modifications = mock_platform.modifications().len();
     println!("Final order modifications: {}", final_modifications);
 
     // We expect at least some order modifications due to break-even and trailing stops
@@ -552,7 +274,7 @@ async fn test_comprehensive_exit_management_workflow() {
 
 #[tokio::test]
 async fn test_exit_management_error_resilience() {
-    let mock_platform = Arc::new(ComprehensiveMockPlatform::new());
+    let mock_platform = Arc::new(comprehensive_platform());
 
     // Add a position
     let position = create_realistic_position(
@@ -597,7 +319,7 @@ async fn test_exit_management_error_resilience() {
 
 #[tokio::test]
 async fn test_exit_management_performance_metrics() {
-    let mock_platform = Arc::new(ComprehensiveMockPlatform::new());
+    let mock_platform = Arc::new(comprehensive_platform());
 
     // Add several positions for performance testing
     for i in 1..=10 {
@@ -623,9 +345,9 @@ async fn test_exit_management_performance_metrics() {
     for (i, position) in positions.iter().enumerate() {
         let config = TrailingConfig {
             atr_multiplier: 2.0,
-            min_trail_distance: 0.001,
-            max_trail_distance: 0.01,
-            activation_threshold: 0.002,
+            min_trail_distance: dec!(0.001),
+            max_trail_distance: dec!(0.01),
+            activation_threshold: dec!(0.002),
             symbol: position.symbol.clone(),
             timeframe: "M15".to_string(),
         };
@@ -652,7 +374,7 @@ async fn test_exit_management_performance_metrics() {
 
 #[tokio::test]
 async fn test_exit_management_audit_logging() {
-    let mock_platform = Arc::new(ComprehensiveMockPlatform::new());
+    let mock_platform = Arc::new(comprehensive_platform());
 
     // Add a position that will trigger modifications
     let position = create_realistic_position(
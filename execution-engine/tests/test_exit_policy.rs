@@ -0,0 +1,169 @@
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal_macros::dec;
+use std::sync::{Arc, Mutex};
+
+use execution_engine::execution::exit_management::{
+    ClosePositionRequest, ClosePositionResult, ExitAuditLogger, ExitManagementSystem, ExitPolicy,
+    FileExitStateStore, MarketData, OrderModifyRequest, OrderModifyResult, PartialCloseRequest,
+    Position, ProfitTakingConfig, ProfitTarget, TradingPlatform, TrailingConfig,
+};
+use execution_engine::testing::fixtures;
+
+#[derive(Debug, Default)]
+struct MockPlatform {
+    positions: Mutex<Vec<Position>>,
+    partials: Mutex<Vec<PartialCloseRequest>>,
+}
+
+impl MockPlatform {
+    fn with_positions(positions: Vec<Position>) -> Arc<Self> {
+        Arc::new(Self {
+            positions: Mutex::new(positions),
+            partials: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: dec!(1.10595),
+            ask: dec!(1.10605),
+            spread: dec!(0.0001),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: true,
+            message: "modified".to_string(),
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: dec!(1.1060),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        self.partials.lock().unwrap().push(request.clone());
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: dec!(1.1060),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+}
+
+fn long_position(symbol: &str) -> Position {
+    Position {
+        symbol: symbol.to_string(),
+        ..fixtures::long_position()
+    }
+}
+
+/// Trails 5 pips closer and takes a third of the position off at 1:1
+fn news_policy() -> ExitPolicy {
+    ExitPolicy {
+        name: Some("news-trade".to_string()),
+        trailing: Some(TrailingConfig {
+            min_trail_distance: dec!(0.0005),
+            ..Default::default()
+        }),
+        profit_taking: Some(ProfitTakingConfig {
+            profit_targets: vec![ProfitTarget {
+                level: 1,
+                risk_reward_ratio: 1.0,
+                close_percentage: 1.0 / 3.0,
+            }],
+            enabled: true,
+            take_profit_at_structure: false,
+        }),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_position_policy_overrides_symbol_trail() {
+    let news = long_position("EURUSD");
+    let regular = long_position("EURUSD");
+    let platform = MockPlatform::with_positions(vec![news.clone(), regular.clone()]);
+    let system = ExitManagementSystem::new(platform, Arc::new(ExitAuditLogger::new()));
+    system.set_position_policy(news.id, news_policy());
+
+    let trailing = system.get_trailing_stop_manager();
+    trailing.activate_trailing_stop(&news).await.unwrap();
+    trailing.activate_trailing_stop(&regular).await.unwrap();
+
+    assert_eq!(
+        trailing.get_active_trail(news.id).unwrap().trail_level,
+        dec!(1.10550)
+    );
+    assert_eq!(
+        trailing.get_active_trail(regular.id).unwrap().trail_level,
+        dec!(1.10500)
+    );
+
+    // Clearing the policy returns the position to the symbol configuration
+    assert!(system.clear_position_policy(news.id).is_some());
+    assert!(system.get_position_policy(news.id).is_none());
+}
+
+#[tokio::test]
+async fn test_policy_ladder_applies_to_unconfigured_symbol() {
+    let news = long_position("GBPUSD");
+    let regular = long_position("GBPUSD");
+    let platform = MockPlatform::with_positions(vec![news.clone(), regular]);
+    let system = ExitManagementSystem::new(platform.clone(), Arc::new(ExitAuditLogger::new()));
+    system.set_position_policy(news.id, news_policy());
+
+    system.run_position_checks().await;
+
+    let partials = platform.partials.lock().unwrap().clone();
+    assert_eq!(partials.len(), 1);
+    assert_eq!(partials[0].position_id, news.id);
+    assert_eq!(
+        system.position_exit_state(news.id).profit_targets_hit,
+        vec![1]
+    );
+}
+
+#[tokio::test]
+async fn test_policy_survives_restart_and_is_dropped_once_closed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("acc-1.json");
+    let position = long_position("EURUSD");
+    let platform = MockPlatform::with_positions(vec![position.clone()]);
+
+    let before = ExitManagementSystem::new(platform.clone(), Arc::new(ExitAuditLogger::new()))
+        .with_state_store(Arc::new(FileExitStateStore::new(&path)));
+    before.restore_state().await.unwrap();
+    before.set_position_policy(position.id, news_policy());
+    before.checkpoint_state().await;
+
+    let after = ExitManagementSystem::new(platform.clone(), Arc::new(ExitAuditLogger::new()))
+        .with_state_store(Arc::new(FileExitStateStore::new(&path)));
+    assert_eq!(after.restore_state().await.unwrap(), 1);
+    assert_eq!(after.get_position_policy(position.id), Some(news_policy()));
+
+    platform.positions.lock().unwrap().clear();
+    after.run_schedule_checks().await;
+    assert!(after.get_position_policy(position.id).is_none());
+}
@@ -0,0 +1,193 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use rust_decimal_macros::dec;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use execution_engine::execution::exit_management::{
+    BreakEvenConfig, BreakEvenManager, ClosePositionRequest, ClosePositionResult, ExcursionTracker,
+    ExitAuditLogger, ExitManagementSystem, ExitPolicies, MarketData, NewsEventProtection,
+    OrderModifyRequest, OrderModifyResult, PartialClosePreview, PartialCloseRequest,
+    PartialProfitManager, Position, ProfitTakingConfig, TimeBasedExitManager, TradingPlatform,
+    TrailingStopManager,
+};
+use execution_engine::runtime::{Feature, FeatureFlagConfig, FeatureFlags};
+use execution_engine::testing::fixtures;
+
+#[derive(Debug, Default)]
+struct MockPlatform {
+    positions: Mutex<Vec<Position>>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl MockPlatform {
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: dec!(1.10595),
+            ask: dec!(1.10605),
+            spread: dec!(0.0001),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("modify {:?}", request.new_stop_loss));
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: true,
+            message: "modified".to_string(),
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        self.calls.lock().unwrap().push("close".to_string());
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: dec!(1.1060),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("partial {}", request.volume));
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: dec!(1.1060),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+}
+
+/// Long EURUSD at 1.1000 with 50 pips of risk, 60 pips up and held for 25 hours
+fn long_position() -> Position {
+    Position {
+        open_time: Utc::now() - Duration::hours(25),
+        ..fixtures::long_position()
+    }
+}
+
+fn system(platform: Arc<MockPlatform>) -> ExitManagementSystem {
+    let logger = Arc::new(ExitAuditLogger::new());
+    let mut break_even = BreakEvenManager::new(platform.clone(), logger.clone());
+    break_even.configure_symbol(
+        "EURUSD".to_string(),
+        BreakEvenConfig {
+            partial_close_percent: Some(0.2),
+            ..BreakEvenConfig::default()
+        },
+    );
+    let mut partial_profits = PartialProfitManager::new(platform.clone(), logger.clone());
+    partial_profits.configure_symbol("EURUSD".to_string(), ProfitTakingConfig::default());
+
+    ExitManagementSystem::from_components(
+        platform.clone(),
+        Arc::new(TrailingStopManager::new(platform.clone(), logger.clone())),
+        Arc::new(break_even),
+        Arc::new(partial_profits),
+        Arc::new(TimeBasedExitManager::new(platform.clone(), logger.clone())),
+        Arc::new(NewsEventProtection::new(platform.clone(), logger.clone())),
+        Arc::new(ExcursionTracker::new(platform, logger.clone())),
+        Arc::new(ExitPolicies::new()),
+        logger,
+    )
+}
+
+#[tokio::test]
+async fn preview_reports_what_each_manager_would_do_without_touching_the_position() {
+    let position = long_position();
+    let platform = Arc::new(MockPlatform {
+        positions: Mutex::new(vec![position.clone()]),
+        ..MockPlatform::default()
+    });
+    let system = system(platform.clone());
+
+    let preview = system.preview_position(position.id).await.unwrap().unwrap();
+    assert!(preview.would_act());
+    assert_eq!(preview.current_price, dec!(1.1060));
+    assert!(preview.trailing_stop.is_none());
+
+    let break_even = preview.break_even.unwrap();
+    assert_eq!(break_even.current_stop, Some(dec!(1.0950)));
+    assert_eq!(break_even.new_stop, dec!(1.1005));
+    assert_eq!(break_even.close_volume, Some(dec!(2000.0)));
+    assert!(break_even.reason.starts_with("Profit 60.0 pips"));
+
+    assert_eq!(
+        preview.partial_profits,
+        vec![PartialClosePreview {
+            level: 1,
+            risk_reward_ratio: 1.0,
+            current_risk_reward: 1.2,
+            close_volume: dec!(5000.0),
+        }]
+    );
+
+    let time_exit = preview.time_exit.unwrap();
+    assert!(time_exit.would_warn);
+    assert!(time_exit.would_exit);
+    assert!((-3601..=-3599).contains(&time_exit.exits_in_secs));
+
+    // Nothing was sent to the platform, and nothing was recorded as done
+    assert!(platform.calls().is_empty());
+    let state = system.position_exit_state(position.id);
+    assert!(!state.break_even_active);
+    assert!(state.remaining_volume.is_none());
+    assert!(!state.time_exit_warned);
+
+    // The checks then do what the preview said
+    system.run_position_checks().await;
+    assert_eq!(
+        platform.calls(),
+        vec!["modify Some(1.1005)", "partial 2000.0", "partial 5000.0"]
+    );
+    let preview = system.preview_position(position.id).await.unwrap().unwrap();
+    assert!(preview.break_even.is_none());
+    assert!(preview.partial_profits.is_empty());
+
+    assert!(system
+        .preview_position(Uuid::new_v4())
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn managers_switched_off_for_the_account_are_left_out() {
+    let position = long_position();
+    let platform = Arc::new(MockPlatform {
+        positions: Mutex::new(vec![position.clone()]),
+        ..MockPlatform::default()
+    });
+    let flags = Arc::new(FeatureFlags::new(FeatureFlagConfig::default()));
+    flags.set(Some("acc-1"), Feature::BreakEven, false, "maintenance");
+    flags.set(Some("acc-1"), Feature::TimeExits, false, "maintenance");
+    let system = system(platform).with_feature_flags(flags.for_account("acc-1"));
+
+    let preview = system.preview_position(position.id).await.unwrap().unwrap();
+    assert!(preview.break_even.is_none());
+    assert!(preview.time_exit.is_none());
+    assert_eq!(preview.partial_profits.len(), 1);
+}
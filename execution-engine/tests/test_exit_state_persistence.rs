@@ -0,0 +1,168 @@
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal_macros::dec;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use execution_engine::execution::exit_management::{
+    BreakEvenManager, ClosePositionRequest, ClosePositionResult, ExcursionTracker, ExitAuditLogger,
+    ExitManagementSystem, ExitPolicies, ExitStateStore, FileExitStateStore, MarketData,
+    NewsEventProtection, OrderModifyRequest, OrderModifyResult, PartialCloseRequest,
+    PartialProfitManager, Position, ProfitTakingConfig, TimeBasedExitManager, TradingPlatform,
+    TrailingStopManager,
+};
+use execution_engine::testing::long_position;
+
+#[derive(Debug, Default)]
+struct MockPlatform {
+    positions: Mutex<Vec<Position>>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl MockPlatform {
+    fn with_position(position: Position) -> Arc<Self> {
+        Arc::new(Self {
+            positions: Mutex::new(vec![position]),
+            calls: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: dec!(1.10595),
+            ask: dec!(1.10605),
+            spread: dec!(0.0001),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        self.calls.lock().unwrap().push("modify".to_string());
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: true,
+            message: "modified".to_string(),
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        self.calls.lock().unwrap().push("close".to_string());
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: dec!(1.1060),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("partial {}", request.volume));
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: dec!(1.1060),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+}
+
+/// An exit management system that takes partial profits on EURUSD and checkpoints to `path`
+fn system(platform: Arc<MockPlatform>, path: &Path) -> ExitManagementSystem {
+    let logger = Arc::new(ExitAuditLogger::new());
+    let mut partial_profits = PartialProfitManager::new(platform.clone(), logger.clone());
+    partial_profits.configure_symbol("EURUSD".to_string(), ProfitTakingConfig::default());
+    let excursions = Arc::new(ExcursionTracker::new(platform.clone(), logger.clone()));
+
+    ExitManagementSystem::from_components(
+        platform.clone(),
+        Arc::new(TrailingStopManager::new(platform.clone(), logger.clone())),
+        Arc::new(BreakEvenManager::new(platform.clone(), logger.clone())),
+        Arc::new(partial_profits),
+        Arc::new(TimeBasedExitManager::new(platform.clone(), logger.clone())),
+        Arc::new(NewsEventProtection::new(platform, logger.clone())),
+        excursions,
+        Arc::new(ExitPolicies::new()),
+        logger,
+    )
+    .with_state_store(Arc::new(FileExitStateStore::new(path)))
+}
+
+#[tokio::test]
+async fn test_restart_resumes_state_without_retaking_partials() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("exit_state").join("acc-1.json");
+    let position = long_position();
+
+    let platform = MockPlatform::with_position(position.clone());
+    let before = system(platform.clone(), &path);
+    assert_eq!(before.restore_state().await.unwrap(), 0);
+    before.run_position_checks().await;
+    assert_eq!(platform.calls(), vec!["modify", "partial 5000.0"]);
+
+    // Restart against the platform, which now shows the reduced position
+    platform.positions.lock().unwrap()[0].volume = dec!(5000);
+    platform.calls.lock().unwrap().clear();
+    let after = system(platform.clone(), &path);
+    assert_eq!(after.restore_state().await.unwrap(), 1);
+
+    let state = after.position_exit_state(position.id);
+    assert!(state.break_even_active);
+    assert_eq!(state.profit_targets_hit, vec![1]);
+    assert_eq!(state.remaining_volume, Some(dec!(5000)));
+
+    after.run_position_checks().await;
+    assert!(platform.calls().is_empty());
+}
+
+#[tokio::test]
+async fn test_state_of_positions_closed_while_offline_is_discarded() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("acc-1.json");
+    let platform = MockPlatform::with_position(long_position());
+
+    let before = system(platform.clone(), &path);
+    before.run_position_checks().await;
+    let store = FileExitStateStore::new(&path);
+    assert_eq!(store.load_all().await.unwrap().len(), 1);
+
+    platform.positions.lock().unwrap().clear();
+    let after = system(platform, &path);
+    assert_eq!(after.restore_state().await.unwrap(), 0);
+    assert!(store.load_all().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_checks_pause_until_state_is_restored() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("acc-1.json");
+    tokio::fs::write(&path, "not json").await.unwrap();
+    let platform = MockPlatform::with_position(long_position());
+
+    let system = system(platform.clone(), &path);
+    assert!(system.restore_state().await.is_err());
+    system.run_position_checks().await;
+    assert!(platform.calls().is_empty());
+
+    // Once the state can be read the next check restores it and carries on
+    tokio::fs::write(&path, "[]").await.unwrap();
+    system.run_position_checks().await;
+    assert_eq!(platform.calls(), vec!["modify", "partial 5000.0"]);
+}
@@ -0,0 +1,188 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::dashboard::{DashboardAggregator, ExposureCell, ExposureHeatmap, HeatmapRow};
+use execution_engine::market_data::{CrossRateConfig, CrossRates};
+use execution_engine::platforms::abstraction::models::{UnifiedMarketData, UnifiedPositionSide};
+use execution_engine::platforms::abstraction::{ITradingPlatform, UnifiedPosition};
+use execution_engine::testing::{orchestrator, MockTradingPlatform};
+
+fn position(
+    account_id: &str,
+    symbol: &str,
+    side: UnifiedPositionSide,
+    quantity: Decimal,
+    price: Decimal,
+) -> UnifiedPosition {
+    UnifiedPosition {
+        position_id: format!("{}-{}", account_id, symbol),
+        symbol: symbol.to_string(),
+        side,
+        quantity,
+        entry_price: price,
+        current_price: price,
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: account_id.to_string(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+fn quote(symbol: &str, mid: Decimal) -> UnifiedMarketData {
+    UnifiedMarketData {
+        symbol: symbol.to_string(),
+        bid: mid,
+        ask: mid,
+        spread: Decimal::ZERO,
+        last_price: None,
+        volume: None,
+        high: None,
+        low: None,
+        timestamp: Utc::now(),
+        session: None,
+        platform_specific: HashMap::new(),
+    }
+}
+
+fn row<'a>(rows: &'a [HeatmapRow], key: &str) -> &'a [ExposureCell] {
+    &rows.iter().find(|r| r.key == key).unwrap().cells
+}
+
+fn notionals(cells: &[ExposureCell]) -> Vec<Decimal> {
+    cells.iter().map(|c| c.notional).collect()
+}
+
+#[tokio::test]
+async fn exposure_is_netted_by_symbol_and_currency_leg_in_each_account_currency() {
+    let down = Arc::new(MockTradingPlatform::new("down-1"));
+    let orchestrator = orchestrator(vec![
+        Arc::new(
+            MockTradingPlatform::new("usd-1")
+                .with_balance(dec!(10000))
+                .with_position(position(
+                    "usd-1",
+                    "EUR_USD",
+                    UnifiedPositionSide::Long,
+                    dec!(10000),
+                    dec!(1.1),
+                ))
+                .with_position(position(
+                    "usd-1",
+                    "GBPUSD",
+                    UnifiedPositionSide::Short,
+                    dec!(5000),
+                    dec!(1.25),
+                )),
+        ),
+        Arc::new(
+            MockTradingPlatform::new("eur-1")
+                .with_currency("EUR")
+                .with_balance(dec!(20000))
+                .with_position(position(
+                    "eur-1",
+                    "EURUSD",
+                    UnifiedPositionSide::Long,
+                    dec!(10000),
+                    dec!(1.1),
+                )),
+        ),
+        down.clone(),
+    ])
+    .await;
+    down.set_connected(false);
+
+    let heatmap = DashboardAggregator::new(orchestrator)
+        .exposure_heatmap()
+        .await;
+
+    let columns: Vec<&str> = heatmap
+        .accounts
+        .iter()
+        .map(|a| a.account_id.as_str())
+        .collect();
+    assert_eq!(columns, vec!["eur-1", "usd-1"]);
+    assert_eq!(heatmap.unavailable_accounts, vec!["down-1".to_string()]);
+
+    let keys: Vec<&str> = heatmap.symbols.iter().map(|r| r.key.as_str()).collect();
+    assert_eq!(keys, vec!["EURUSD", "GBPUSD"]);
+    let eurusd = row(&heatmap.symbols, "EURUSD");
+    assert_eq!(notionals(eurusd), vec![dec!(10000), dec!(11000)]);
+    assert_eq!(eurusd[0].equity_pct, Some(dec!(50)));
+    assert_eq!(eurusd[1].equity_pct, Some(dec!(110)));
+    // The EUR account holds no GBPUSD, yet has its cell
+    assert_eq!(
+        notionals(row(&heatmap.symbols, "GBPUSD")),
+        vec![dec!(0), dec!(-6250)]
+    );
+
+    assert_eq!(
+        notionals(row(&heatmap.currencies, "EUR")),
+        vec![dec!(10000), dec!(11000)]
+    );
+    assert_eq!(
+        notionals(row(&heatmap.currencies, "USD")),
+        vec![dec!(-10000), dec!(-4750)]
+    );
+    assert_eq!(
+        notionals(row(&heatmap.currencies, "GBP")),
+        vec![dec!(0), dec!(-6250)]
+    );
+    assert!(heatmap.unconverted.is_empty());
+}
+
+#[tokio::test]
+async fn legs_outside_the_pair_need_cross_rates() {
+    let account = MockTradingPlatform::new("jpy-1")
+        .with_currency("JPY")
+        .with_balance(dec!(3300000))
+        .get_account_info()
+        .await
+        .unwrap();
+    let positions = vec![
+        position(
+            "jpy-1",
+            "EURUSD",
+            UnifiedPositionSide::Long,
+            dec!(10000),
+            dec!(1.1),
+        ),
+        position(
+            "jpy-1",
+            "US30",
+            UnifiedPositionSide::Short,
+            dec!(2),
+            dec!(35000),
+        ),
+    ];
+    let accounts = vec![("jpy-1".to_string(), account, positions)];
+
+    let heatmap = ExposureHeatmap::build(accounts.clone(), Vec::new(), None, Utc::now());
+    assert_eq!(heatmap.unconverted, vec!["jpy-1:EURUSD".to_string()]);
+    assert!(heatmap.currencies.is_empty());
+    // Symbols that are not pairs are priced in the account currency
+    assert_eq!(notionals(row(&heatmap.symbols, "US30")), vec![dec!(-70000)]);
+
+    let rates = CrossRates::new(CrossRateConfig::default());
+    rates.on_quote(&quote("EURUSD", dec!(1.1)));
+    rates.on_quote(&quote("USDJPY", dec!(150)));
+    let heatmap = ExposureHeatmap::build(accounts, Vec::new(), Some(&rates), Utc::now());
+    assert!(heatmap.unconverted.is_empty());
+    let eurusd = row(&heatmap.symbols, "EURUSD");
+    assert_eq!(eurusd[0].notional, dec!(1650000));
+    assert_eq!(eurusd[0].equity_pct, Some(dec!(50)));
+    assert_eq!(
+        notionals(row(&heatmap.currencies, "USD")),
+        vec![dec!(-1650000)]
+    );
+    let keys: Vec<&str> = heatmap.currencies.iter().map(|r| r.key.as_str()).collect();
+    assert_eq!(keys, vec!["EUR", "USD"]);
+}
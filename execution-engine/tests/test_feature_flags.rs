@@ -0,0 +1,225 @@
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use execution_engine::execution::exit_management::{
+    ClosePositionRequest, ClosePositionResult, ExitAuditLogger, ExitManagementSystem, ExitPolicy,
+    MarketData, OrderModifyRequest, OrderModifyResult, PartialCloseRequest, Position,
+    ProfitTakingConfig, ProfitTarget, TradingPlatform,
+};
+use execution_engine::risk::{
+    CircuitBreakerClient, PositionManager, ResponseAction, ResponseExecutionResult,
+    ResponseExecutor, RiskAuditLogger, RiskResponseSystem, RiskThresholds,
+};
+use execution_engine::runtime::{
+    Feature, FeatureFlagConfig, FeatureFlags, HealthLevel, HealthProbe,
+};
+use execution_engine::testing::fixtures;
+
+#[derive(Debug)]
+struct MockPlatform {
+    positions: Mutex<Vec<Position>>,
+    partials: Mutex<Vec<PartialCloseRequest>>,
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: dec!(1.10595),
+            ask: dec!(1.10605),
+            spread: dec!(0.0001),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: true,
+            message: "modified".to_string(),
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: dec!(1.1060),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        self.partials.lock().unwrap().push(request.clone());
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: dec!(1.1060),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+}
+
+fn long_position() -> Position {
+    Position {
+        symbol: "GBPUSD".to_string(),
+        ..fixtures::long_position()
+    }
+}
+
+/// Takes a third of the position off at 1:1
+fn take_a_third() -> ExitPolicy {
+    ExitPolicy {
+        profit_taking: Some(ProfitTakingConfig {
+            profit_targets: vec![ProfitTarget {
+                level: 1,
+                risk_reward_ratio: 1.0,
+                close_percentage: 1.0 / 3.0,
+            }],
+            enabled: true,
+            take_profit_at_structure: false,
+        }),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn account_flags_override_global_ones_and_every_change_is_audited() {
+    let flags = FeatureFlags::new(FeatureFlagConfig {
+        global: HashMap::from([(Feature::NewsProtection, false)]),
+        accounts: HashMap::from([(
+            "acc-1".to_string(),
+            HashMap::from([(Feature::NewsProtection, true)]),
+        )]),
+        history: 2,
+    });
+    assert!(flags.is_enabled("acc-1", Feature::NewsProtection));
+    assert!(!flags.is_enabled("acc-2", Feature::NewsProtection));
+    assert!(flags.is_enabled("acc-2", Feature::TrailingStops));
+
+    flags.set(
+        Some("acc-2"),
+        Feature::TrailingStops,
+        false,
+        "stops misbehaving",
+    );
+    assert!(!flags.is_enabled("acc-2", Feature::TrailingStops));
+    assert!(flags.is_enabled("acc-1", Feature::TrailingStops));
+
+    // Clearing the account flag falls back to the global one
+    let cleared = flags.clear(Some("acc-1"), Feature::NewsProtection, "back to default");
+    assert_eq!((cleared.previous, cleared.enabled), (Some(true), None));
+    assert!(!flags.is_enabled("acc-1", Feature::NewsProtection));
+
+    // Only the last two changes are kept, most recent first
+    flags.set(None, Feature::NewsProtection, true, "calendar fixed");
+    let history = flags.history(10);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].reason, "calendar fixed");
+    assert_eq!(history[0].previous, Some(false));
+    assert_eq!(history[1].reason, "back to default");
+
+    // Disabled features show up in health diagnostics
+    let health = flags.check().await;
+    assert_eq!(health.len(), 1);
+    assert_eq!(health[0].name, "feature:acc-2:trailing_stops");
+    assert_eq!(health[0].level, HealthLevel::Degraded);
+    assert!(!health[0].critical);
+    assert_eq!(
+        health[0].detail.as_deref(),
+        Some("disabled: stops misbehaving")
+    );
+
+    flags.clear(Some("acc-2"), Feature::TrailingStops, "fixed");
+    let health = flags.check().await;
+    assert_eq!(health[0].level, HealthLevel::Healthy);
+}
+
+#[tokio::test]
+async fn disabled_exit_managers_are_skipped_until_reenabled() {
+    let position = long_position();
+    let platform = Arc::new(MockPlatform {
+        positions: Mutex::new(vec![position.clone()]),
+        partials: Mutex::new(Vec::new()),
+    });
+    let flags = Arc::new(FeatureFlags::new(FeatureFlagConfig::default()));
+    flags.set(Some("acc-1"), Feature::PartialProfits, false, "maintenance");
+
+    let system = ExitManagementSystem::new(platform.clone(), Arc::new(ExitAuditLogger::new()))
+        .with_feature_flags(flags.for_account("acc-1"));
+    system.set_position_policy(position.id, take_a_third());
+
+    system.run_position_checks().await;
+    assert!(platform.partials.lock().unwrap().is_empty());
+    assert!(!system.feature_enabled(Feature::PartialProfits));
+
+    // Other accounts and features are unaffected
+    assert!(system.feature_enabled(Feature::TrailingStops));
+    assert!(flags.is_enabled("acc-2", Feature::PartialProfits));
+
+    flags.set(
+        Some("acc-1"),
+        Feature::PartialProfits,
+        true,
+        "maintenance over",
+    );
+    system.run_position_checks().await;
+    assert_eq!(platform.partials.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn position_reduction_is_held_back_when_switched_off() {
+    let account_id = Uuid::new_v4();
+    let flags = Arc::new(FeatureFlags::new(FeatureFlagConfig::default()));
+    let system = RiskResponseSystem::new(
+        Arc::new(RiskThresholds::default()),
+        Arc::new(PositionManager::new()),
+        Arc::new(CircuitBreakerClient),
+        Arc::new(RiskAuditLogger::new()),
+        Arc::new(ResponseExecutor),
+    )
+    .with_feature_flags(flags.clone());
+
+    flags.set(
+        Some(&account_id.to_string()),
+        Feature::AutoPositionReduction,
+        false,
+        "manual handling",
+    );
+    let response = system
+        .handle_margin_risk(account_id, dec!(100), dec!(120))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        response.action_taken,
+        ResponseAction::ReducePositions { .. }
+    ));
+    assert!(matches!(
+        response.execution_result,
+        ResponseExecutionResult::Failed { .. }
+    ));
+
+    // Another account still has its positions reduced
+    let response = system
+        .handle_margin_risk(Uuid::new_v4(), dec!(100), dec!(120))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        response.execution_result,
+        ResponseExecutionResult::PositionsReduced { .. }
+    ));
+}
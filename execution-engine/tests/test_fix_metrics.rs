@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use execution_engine::platforms::dxtrade::fix_metrics::{
+    FIX_BYTES, FIX_HEARTBEAT_RTT, FIX_LOGON_FAILURES, FIX_MISSED_MESSAGES, FIX_RESEND_REQUESTS,
+    FIX_RESENT_MESSAGES, FIX_SEQUENCE_GAPS, FIX_SESSION_STATE, FIX_STATE_TRANSITIONS,
+};
+use execution_engine::platforms::dxtrade::fix_session::SessionState;
+use execution_engine::platforms::dxtrade::FixSessionMetrics;
+
+fn state(session: &str, state: SessionState) -> i64 {
+    FIX_SESSION_STATE
+        .with_label_values(&[session, state.as_str()])
+        .get()
+}
+
+#[test]
+fn transitions_move_the_state_gauge() {
+    let metrics = FixSessionMetrics::new("TMT-A", "BROKER");
+    let session = metrics.session().to_string();
+    assert_eq!(session, "TMT-A:BROKER");
+    assert_eq!(state(&session, SessionState::Disconnected), 1);
+
+    metrics.transition(&SessionState::Disconnected, &SessionState::Connecting);
+    metrics.transition(&SessionState::Connecting, &SessionState::LogonSent);
+    metrics.transition(&SessionState::LogonSent, &SessionState::LoggedIn);
+    metrics.transition(&SessionState::LoggedIn, &SessionState::LoggedIn);
+
+    assert_eq!(state(&session, SessionState::Disconnected), 0);
+    assert_eq!(state(&session, SessionState::LogonSent), 0);
+    assert_eq!(state(&session, SessionState::LoggedIn), 1);
+    let to_logged_in = FIX_STATE_TRANSITIONS
+        .with_label_values(&[&session, "logon_sent", "logged_in"])
+        .get();
+    assert_eq!(to_logged_in, 1);
+}
+
+#[test]
+fn gaps_resends_logon_failures_and_bytes_are_counted() {
+    let metrics = FixSessionMetrics::new("TMT-B", "BROKER");
+    let session = metrics.session().to_string();
+
+    metrics.sequence_gap(10, 14);
+    metrics.sequence_gap(20, 21);
+    metrics.resend_served(3);
+    metrics.logon_failed("rejected");
+    metrics.bytes_in(120);
+    metrics.bytes_out(80);
+    metrics.bytes_out(20);
+
+    assert_eq!(FIX_SEQUENCE_GAPS.with_label_values(&[&session]).get(), 2);
+    assert_eq!(FIX_MISSED_MESSAGES.with_label_values(&[&session]).get(), 5);
+    assert_eq!(FIX_RESEND_REQUESTS.with_label_values(&[&session]).get(), 1);
+    assert_eq!(FIX_RESENT_MESSAGES.with_label_values(&[&session]).get(), 3);
+    let rejected = FIX_LOGON_FAILURES
+        .with_label_values(&[&session, "rejected"])
+        .get();
+    assert_eq!(rejected, 1);
+    assert_eq!(FIX_BYTES.with_label_values(&[&session, "in"]).get(), 120);
+    assert_eq!(FIX_BYTES.with_label_values(&[&session, "out"]).get(), 100);
+}
+
+#[test]
+fn heartbeats_answering_the_test_request_are_timed() {
+    let metrics = FixSessionMetrics::new("TMT-C", "BROKER");
+    let session = metrics.session().to_string();
+    let rtt = || FIX_HEARTBEAT_RTT.with_label_values(&[&session]);
+
+    assert!(!metrics.probe_pending(Duration::from_secs(30)));
+    metrics.probe_sent("TEST-1");
+    assert!(metrics.probe_pending(Duration::from_secs(30)));
+    // A lost test request stops blocking the next one
+    assert!(!metrics.probe_pending(Duration::ZERO));
+
+    // Unsolicited heartbeats and answers to other requests are not timed
+    assert!(metrics.heartbeat_received(None).is_none());
+    assert!(metrics.heartbeat_received(Some("TEST-0")).is_none());
+    assert_eq!(rtt().get_sample_count(), 0);
+
+    // Clones share the outstanding request
+    assert!(metrics.clone().heartbeat_received(Some("TEST-1")).is_some());
+    assert_eq!(rtt().get_sample_count(), 1);
+    assert!(!metrics.probe_pending(Duration::from_secs(30)));
+    assert!(metrics.heartbeat_received(Some("TEST-1")).is_none());
+}
+
+#[test]
+fn metrics_are_in_the_prometheus_exposition() {
+    let metrics = FixSessionMetrics::new("TMT-D", "BROKER");
+    metrics.bytes_in(1);
+    let text = prometheus::TextEncoder::new()
+        .encode_to_string(&prometheus::gather())
+        .unwrap();
+    assert!(text.contains("execution_engine_fix_session_state{session=\"TMT-D:BROKER\""));
+    assert!(text.contains("execution_engine_fix_bytes_total"));
+}
@@ -0,0 +1,230 @@
+use proptest::prelude::*;
+
+use execution_engine::platforms::dxtrade::fix_framing::FixFrameDecoder;
+use execution_engine::platforms::dxtrade::fix_messages::FIXMessageBuilder;
+use execution_engine::platforms::dxtrade::{FIXMessage, MessageType};
+
+/// Execution report with free text and a RawData field that may hold SOH or `10=`
+fn execution_report(seq: u32, text: &str, raw_data: &str) -> String {
+    FIXMessageBuilder::new("BROKER".to_string(), "ENGINE".to_string(), seq)
+        .with_field(58, text.to_string())
+        .with_field(95, raw_data.len().to_string())
+        .with_field(96, raw_data.to_string())
+        .with_field(110, "1000".to_string())
+        .build(MessageType::ExecutionReport)
+        .unwrap()
+        .raw_message
+}
+
+fn decode_all(decoder: &mut FixFrameDecoder) -> (Vec<String>, usize) {
+    let mut frames = Vec::new();
+    let mut errors = 0;
+    while let Some(frame) = decoder.next_frame() {
+        match frame {
+            Ok(frame) => frames.push(frame),
+            Err(_) => errors += 1,
+        }
+    }
+    (frames, errors)
+}
+
+fn raw_data() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[ -~\x01]{0,40}",
+        Just("10=000\x01".to_string()),
+        Just("\x0110=123\x018=FIX.4.4\x01".to_string()),
+    ]
+}
+
+fn messages() -> impl Strategy<Value = Vec<String>> {
+    prop::collection::vec(("\\PC{0,20}", raw_data()), 1..5).prop_map(|fields| {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, (text, data))| execution_report(i as u32 + 1, text, data))
+            .collect()
+    })
+}
+
+proptest! {
+    #[test]
+    fn test_parse_never_panics(input in any::<String>()) {
+        if let Ok(message) = FIXMessage::parse(&input) {
+            let _ = message.validate_checksum();
+        }
+    }
+
+    #[test]
+    fn test_parse_never_panics_on_fix_like_input(
+        input in "(([0-9]{1,3}|95|96)=[ -~\x01]{0,8}\x01){0,12}"
+    ) {
+        if let Ok(message) = FIXMessage::parse(&input) {
+            let _ = message.validate_checksum();
+        }
+    }
+
+    #[test]
+    fn test_built_messages_round_trip(text in "\\PC{0,20}", data in raw_data()) {
+        let raw = execution_report(7, &text, &data);
+        let message = FIXMessage::parse(&raw).unwrap();
+        prop_assert!(message.validate_checksum());
+        prop_assert_eq!(&message.msg_type, &MessageType::ExecutionReport);
+        prop_assert_eq!(message.get_field(58), Some(&text));
+        prop_assert_eq!(message.get_field(96), Some(&data));
+        prop_assert_eq!(message.get_field_as_u32(34), Some(7));
+    }
+
+    #[test]
+    fn test_corrupted_byte_fails_checksum(
+        text in "[ -~]{0,20}",
+        index in any::<prop::sample::Index>(),
+        replacement in 0x20u8..0x7f,
+    ) {
+        let raw = execution_report(1, &text, "");
+        let checksum_start = raw.len() - 7;
+        let index = index.index(checksum_start);
+        prop_assume!(raw.as_bytes()[index] != replacement);
+
+        let mut bytes = raw.into_bytes();
+        bytes[index] = replacement;
+        let corrupted = String::from_utf8(bytes).unwrap();
+        if let Ok(message) = FIXMessage::parse(&corrupted) {
+            prop_assert!(!message.validate_checksum());
+        }
+    }
+
+    #[test]
+    fn test_decoder_reassembles_split_stream(
+        messages in messages(),
+        splits in prop::collection::vec(any::<prop::sample::Index>(), 0..8),
+    ) {
+        let stream = messages.concat().into_bytes();
+        let mut cuts: Vec<usize> = splits.iter().map(|s| s.index(stream.len() + 1)).collect();
+        cuts.sort_unstable();
+
+        let mut decoder = FixFrameDecoder::new();
+        let mut frames = Vec::new();
+        let mut start = 0;
+        for cut in cuts.into_iter().chain([stream.len()]) {
+            decoder.extend(&stream[start..cut]);
+            start = cut;
+            let (decoded, errors) = decode_all(&mut decoder);
+            prop_assert_eq!(errors, 0);
+            frames.extend(decoded);
+        }
+
+        prop_assert_eq!(frames, messages);
+        prop_assert_eq!(decoder.buffered_len(), 0);
+        prop_assert_eq!(decoder.discarded_bytes(), 0);
+    }
+
+    #[test]
+    fn test_decoder_skips_garbage_between_messages(
+        messages in messages(),
+        garbage in prop::collection::vec("[ -~\x01]{0,32}", 5),
+    ) {
+        prop_assume!(garbage.iter().all(|g| !g.contains("8=FIX")));
+
+        let mut decoder = FixFrameDecoder::new();
+        let mut garbage_len = 0;
+        for (message, garbage) in messages.iter().zip(garbage.iter().cycle()) {
+            decoder.extend(garbage.as_bytes());
+            decoder.extend(message.as_bytes());
+            garbage_len += garbage.len();
+        }
+
+        let (frames, errors) = decode_all(&mut decoder);
+        prop_assert_eq!(errors, 0);
+        prop_assert_eq!(frames, messages);
+        prop_assert_eq!(decoder.discarded_bytes(), garbage_len as u64);
+    }
+
+    #[test]
+    fn test_decoder_stays_bounded_on_arbitrary_bytes(
+        chunks in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..64), 0..16),
+    ) {
+        let mut decoder = FixFrameDecoder::new().with_max_message_size(256);
+        for chunk in &chunks {
+            decoder.extend(chunk);
+            let (frames, _) = decode_all(&mut decoder);
+            for frame in frames {
+                let _ = FIXMessage::parse(&frame);
+            }
+            prop_assert!(decoder.buffered_len() <= 256 + 64);
+        }
+    }
+}
+
+#[test]
+fn test_checksum_and_embedded_fields_do_not_end_a_frame() {
+    // MinQty (110=) and a RawData field holding SOH and `10=` used to cut the message short
+    let raw = execution_report(1, "partial fill", "a\x0110=000\x01b");
+    let mut decoder = FixFrameDecoder::new();
+    decoder.extend(raw.as_bytes());
+
+    let frame = decoder.next_frame().unwrap().unwrap();
+    assert_eq!(frame, raw);
+    let message = FIXMessage::parse(&frame).unwrap();
+    assert!(message.validate_checksum());
+    assert_eq!(message.get_field(96).unwrap(), "a\x0110=000\x01b");
+    assert_eq!(message.get_field(110).unwrap(), "1000");
+}
+
+#[test]
+fn test_malformed_frames_are_dropped_and_the_stream_recovers() {
+    let valid = execution_report(2, "ok", "");
+    let bad_checksum = {
+        let raw = execution_report(1, "bad checksum", "");
+        let checksum_start = raw.len() - 4;
+        let digits = &raw[checksum_start..checksum_start + 3];
+        let wrong = (digits.parse::<u32>().unwrap() + 1) % 256;
+        format!("{}{:03}\x01", &raw[..checksum_start], wrong)
+    };
+
+    let mut decoder = FixFrameDecoder::new();
+    decoder.extend(bad_checksum.as_bytes());
+    decoder.extend(b"8=FIX.4.4\x019=abc\x0135=0\x0110=000\x01");
+    decoder.extend(b"8=FIX.4.4\x019=5\x0135=0\x01garbage-past-body-length");
+    decoder.extend(b"8=FIX.4.4\x019=99999999\x0135=0\x01");
+    decoder.extend(valid.as_bytes());
+
+    // A well-framed message with a bad checksum is framed, then rejected on validation
+    let frame = decoder.next_frame().unwrap().unwrap();
+    assert!(!FIXMessage::parse(&frame).unwrap().validate_checksum());
+
+    // Bad BodyLength, a missing trailer and an oversized frame are each reported once
+    let (frames, errors) = decode_all(&mut decoder);
+    assert_eq!(errors, 3);
+    assert_eq!(frames, vec![valid]);
+    assert_eq!(decoder.buffered_len(), 0);
+}
+
+#[test]
+fn test_truncated_messages_fail_checksum_validation() {
+    for raw in [
+        "",
+        "10=",
+        "10=1\x01",
+        "8=FIX.4.4\x0110=12",
+        "8=FIX.4.4\x01110=123\x01",
+        "8=FIX.4.4\x0110=1a3\x01",
+    ] {
+        let message = FIXMessage::parse(raw).unwrap();
+        assert!(!message.validate_checksum(), "{:?}", raw);
+    }
+    assert!(FIXMessage::parse("8=FIX.4.4\x0195=10\x0196=short\x01").is_err());
+}
+
+#[test]
+fn test_sending_time_parses_with_and_without_milliseconds() {
+    let message = FIXMessage::parse(&execution_report(1, "fill", "")).unwrap();
+    let sending_time = message.get_field_as_datetime(52).unwrap();
+    assert!((chrono::Utc::now() - sending_time).num_seconds().abs() < 5);
+
+    let mut message = message;
+    message.fields.insert(52, "20240102-03:04:05".to_string());
+    assert_eq!(
+        message.get_field_as_datetime(52).unwrap().to_rfc3339(),
+        "2024-01-02T03:04:05+00:00"
+    );
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use execution_engine::platforms::dxtrade::fix_messages::FIXMessageBuilder;
+use execution_engine::platforms::dxtrade::{
+    DXTradeConfig, FIXMessage, FixSessionSpec, InboundRouter, MessageType, SessionManager,
+    SessionRole,
+};
+
+fn message(msg_type: MessageType, seq_num: u32) -> FIXMessage {
+    FIXMessageBuilder::new("BROKER".to_string(), "TMT".to_string(), seq_num)
+        .with_field(37, format!("ORD-{}", seq_num))
+        .build(msg_type)
+        .unwrap()
+}
+
+fn spec(qualifier: &str, role: SessionRole) -> FixSessionSpec {
+    FixSessionSpec {
+        qualifier: qualifier.to_string(),
+        role,
+        port: None,
+    }
+}
+
+#[tokio::test]
+async fn execution_reports_reach_the_consumer_of_their_session_role() {
+    let router = InboundRouter::new();
+    let mut orders = router.subscribe(
+        "orders",
+        SessionRole::OrderEntry,
+        &[MessageType::ExecutionReport, MessageType::OrderCancelReject],
+    );
+    let mut reconciliation = router.subscribe("reconciliation", SessionRole::DropCopy, &[]);
+
+    let routed = router
+        .route(
+            "trading",
+            SessionRole::OrderEntry,
+            message(MessageType::ExecutionReport, 1),
+        )
+        .await;
+    assert_eq!(routed, 1);
+    let routed = router
+        .route(
+            "copy",
+            SessionRole::DropCopy,
+            message(MessageType::ExecutionReport, 1),
+        )
+        .await;
+    assert_eq!(routed, 1);
+    // Nobody takes position reports of the order-entry session
+    let routed = router
+        .route(
+            "trading",
+            SessionRole::OrderEntry,
+            message(MessageType::PositionReport, 2),
+        )
+        .await;
+    assert_eq!(routed, 0);
+
+    let order = orders.try_recv().unwrap();
+    assert_eq!(order.qualifier, "trading");
+    assert_eq!(order.message.msg_type, MessageType::ExecutionReport);
+    assert!(orders.try_recv().is_none());
+
+    let copy = reconciliation.try_recv().unwrap();
+    assert_eq!(
+        (copy.qualifier.as_str(), copy.role),
+        ("copy", SessionRole::DropCopy)
+    );
+    assert!(reconciliation.try_recv().is_none());
+}
+
+#[tokio::test]
+async fn dropped_consumers_are_unsubscribed() {
+    let router = InboundRouter::new();
+    let orders = router.subscribe("orders", SessionRole::OrderEntry, &[]);
+    let mut audit = router.subscribe("audit", SessionRole::OrderEntry, &[]);
+    drop(orders);
+
+    let routed = router
+        .route(
+            "trading",
+            SessionRole::OrderEntry,
+            message(MessageType::ExecutionReport, 1),
+        )
+        .await;
+    assert_eq!(routed, 1);
+    assert!(audit.try_recv().is_some());
+}
+
+#[test]
+fn session_qualifiers_must_be_unique() {
+    let mut config = DXTradeConfig::default();
+    config.credentials.sender_comp_id = "TMT".to_string();
+    config.credentials.target_comp_id = "BROKER".to_string();
+    config.credentials.account_id = "acc-1".to_string();
+    config.credentials.username = "trader".to_string();
+    config.sessions = vec![
+        spec("trading", SessionRole::OrderEntry),
+        spec("trading", SessionRole::DropCopy),
+    ];
+    let error = config.validate().unwrap_err().to_string();
+    assert!(
+        error.contains("duplicate session qualifier: trading"),
+        "{}",
+        error
+    );
+
+    let specs: Vec<FixSessionSpec> = serde_json::from_str(
+        r#"[{"qualifier": "trading", "role": "order_entry"},
+            {"qualifier": "copy", "role": "drop_copy", "port": 9443}]"#,
+    )
+    .unwrap();
+    assert_eq!(specs[1].role, SessionRole::DropCopy);
+    assert_eq!(specs[1].port, Some(9443));
+}
+
+#[tokio::test]
+async fn sending_needs_an_open_session() {
+    let manager = SessionManager::new(DXTradeConfig::default());
+    assert!(manager.session("trading").await.is_none());
+    assert!(manager.qualifiers(SessionRole::OrderEntry).await.is_empty());
+    assert!(manager
+        .send_message("trading", message(MessageType::NewOrderSingle, 1))
+        .await
+        .is_err());
+    assert!(manager
+        .close_session("trading", Duration::from_millis(10))
+        .await
+        .is_err());
+}
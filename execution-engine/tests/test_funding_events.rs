@@ -0,0 +1,190 @@
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::execution::TradeExecutionOrchestrator;
+use execution_engine::platforms::abstraction::models::{
+    UnifiedPositionSide, UnifiedTransaction, UnifiedTransactionKind,
+};
+use execution_engine::platforms::abstraction::{ITradingPlatform, UnifiedPosition};
+use execution_engine::reports::DailyReport;
+use execution_engine::risk::{
+    FileFundingEventStore, FileRiskSnapshotStore, FundingConfig, FundingDetector, FundingSource,
+    RiskSnapshotConfig, RiskSnapshotter, TradingDayConfig,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+fn eurusd_long() -> UnifiedPosition {
+    UnifiedPosition {
+        position_id: "pos-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity: dec!(10000),
+        entry_price: dec!(1.1),
+        current_price: dec!(1.1),
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: "usd-1".to_string(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+fn transaction(id: &str, kind: UnifiedTransactionKind, amount: Decimal) -> UnifiedTransaction {
+    UnifiedTransaction {
+        transaction_id: id.to_string(),
+        account_id: "usd-1".to_string(),
+        kind,
+        symbol: None,
+        order_id: None,
+        position_id: None,
+        side: None,
+        quantity: None,
+        price: None,
+        commission: None,
+        amount,
+        currency: "USD".to_string(),
+        balance_after: None,
+        timestamp: Utc::now(),
+        description: String::new(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+fn detector(path: &std::path::Path) -> Arc<FundingDetector> {
+    Arc::new(FundingDetector::new(
+        FundingConfig {
+            enabled: true,
+            ..FundingConfig::default()
+        },
+        Arc::new(FileFundingEventStore::new(path)),
+    ))
+}
+
+#[tokio::test]
+async fn a_withdrawal_moves_the_drawdown_baselines_while_closed_trades_do_not() {
+    let platform = Arc::new(
+        MockTradingPlatform::new("usd-1")
+            .with_balance(dec!(10000))
+            .with_position(eurusd_long()),
+    );
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    orchestrator
+        .register_account("usd-1".to_string(), platform.clone(), 10000.0)
+        .await
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let funding = detector(&dir.path().join("funding.jsonl"));
+    let snapshotter = RiskSnapshotter::new(
+        orchestrator,
+        Arc::new(FileRiskSnapshotStore::new(dir.path().join("risk.jsonl"))),
+        RiskSnapshotConfig::default(),
+    )
+    .with_funding_detector(funding.clone());
+
+    let account = snapshotter.capture().await.accounts.remove(0);
+    assert_eq!(account.funding, Decimal::ZERO);
+
+    // 2000 taken out reads as a 20% drawdown without the funding event
+    platform.transfer(dec!(-2000));
+    platform.set_position_price("pos-1", dec!(1.095));
+    let account = snapshotter.capture().await.accounts.remove(0);
+    assert_eq!(account.equity, dec!(7950));
+    assert_eq!(account.funding, dec!(-2000));
+    assert_eq!(account.peak_equity, dec!(8000));
+    assert_eq!(account.day_open_equity, dec!(8000));
+    assert_eq!(account.drawdown_pct, dec!(0.625));
+
+    // Realized P&L moves the balance without being funding
+    platform.set_position_price("pos-1", dec!(1.12));
+    platform.close_position("EURUSD", None).await.unwrap();
+    let account = snapshotter.capture().await.accounts.remove(0);
+    assert_eq!(account.balance, dec!(8200));
+    assert_eq!(account.funding, Decimal::ZERO);
+    assert_eq!(account.peak_equity, dec!(8200));
+
+    // Changes below the thresholds are left alone
+    platform.transfer(dec!(40));
+    let account = snapshotter.capture().await.accounts.remove(0);
+    assert_eq!(account.funding, Decimal::ZERO);
+
+    let events = funding
+        .events_between(
+            Some("usd-1"),
+            Utc::now() - Duration::hours(1),
+            Utc::now() + Duration::hours(1),
+        )
+        .await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].amount, dec!(-2000));
+    assert_eq!(events[0].source, FundingSource::Heuristic);
+    assert!(!events[0].is_deposit());
+}
+
+#[tokio::test]
+async fn platform_transactions_are_recorded_once_and_tag_the_daily_report() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("funding.jsonl");
+    let funding = detector(&path);
+    let account = MockTradingPlatform::new("usd-1").with_balance(dec!(10000));
+
+    let info = account.get_account_info().await.unwrap();
+    assert!(funding
+        .observe("usd-1", &info, Utc::now() - Duration::minutes(5))
+        .await
+        .unwrap()
+        .is_empty());
+
+    let transactions = vec![
+        transaction("tx-1", UnifiedTransactionKind::Transfer, dec!(5000)),
+        transaction("tx-2", UnifiedTransactionKind::Commission, dec!(-7)),
+    ];
+    let recorded = funding
+        .record_transactions("usd-1", &transactions)
+        .await
+        .unwrap();
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(recorded[0].source, FundingSource::Platform);
+    assert!(funding
+        .record_transactions("usd-1", &transactions)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // The deposit explains the balance change, so nothing is inferred on top
+    account.transfer(dec!(5000));
+    let info = account.get_account_info().await.unwrap();
+    let flows = funding.observe("usd-1", &info, Utc::now()).await.unwrap();
+    assert_eq!(flows, recorded);
+
+    // A restart remembers the transactions already recorded
+    let restarted = detector(&path);
+    assert_eq!(restarted.load().await.unwrap(), 1);
+    let withdrawal = transaction("tx-3", UnifiedTransactionKind::Transfer, dec!(-300));
+    let recorded = restarted
+        .record_transactions("usd-1", &[transactions[0].clone(), withdrawal])
+        .await
+        .unwrap();
+    let amounts: Vec<Decimal> = recorded.iter().map(|e| e.amount).collect();
+    assert_eq!(amounts, vec![dec!(-300)]);
+
+    let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+    let mut events = restarted
+        .events_between(None, Utc::now() - Duration::hours(1), Utc::now())
+        .await;
+    events[0].at = Utc.with_ymd_and_hms(2025, 3, 3, 12, 0, 0).unwrap();
+    let trading_days = TradingDayConfig::default();
+    let mut report = DailyReport::compile(date, &trading_days, &[], &[]);
+    report.tag_funding(&trading_days, &events);
+    assert_eq!(report.accounts.len(), 1);
+    assert_eq!(report.accounts[0].net_funding(), dec!(5000));
+    assert!(report.accounts[0].summary().ends_with(", funding 5000"));
+    assert!(report.to_html().contains("<th>Funding</th>"));
+}
@@ -0,0 +1,242 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use execution_engine::execution::{
+    AccountAssignment, ExecutionPlan, OrchestratorSnapshot, TradeExecutionOrchestrator,
+};
+use execution_engine::runtime::shutdown::DisconnectPlatform;
+use execution_engine::runtime::{
+    ShutdownConfig, ShutdownCoordinator, ShutdownHook, ShutdownPhase, StepStatus, Supervisor,
+    SupervisorConfig,
+};
+use execution_engine::testing::signal;
+
+struct RecordingHook {
+    name: String,
+    phase: ShutdownPhase,
+    delay: Duration,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl ShutdownHook for RecordingHook {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn phase(&self) -> ShutdownPhase {
+        self.phase
+    }
+
+    async fn run(&self) -> Result<()> {
+        tokio::time::sleep(self.delay).await;
+        self.log.lock().unwrap().push(self.name.clone());
+        Ok(())
+    }
+}
+
+fn hook(
+    name: &str,
+    phase: ShutdownPhase,
+    delay: Duration,
+    log: &Arc<Mutex<Vec<String>>>,
+) -> Arc<dyn ShutdownHook> {
+    Arc::new(RecordingHook {
+        name: name.to_string(),
+        phase,
+        delay,
+        log: log.clone(),
+    })
+}
+
+#[tokio::test]
+async fn test_hooks_run_in_phase_order_within_deadline() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let coordinator = ShutdownCoordinator::new(ShutdownConfig {
+        deadline_secs: 1,
+        ..ShutdownConfig::default()
+    })
+    .with_hook(hook(
+        "logout",
+        ShutdownPhase::CloseSessions,
+        Duration::from_secs(5),
+        &log,
+    ))
+    .with_hook(hook(
+        "persist",
+        ShutdownPhase::PersistState,
+        Duration::ZERO,
+        &log,
+    ))
+    .with_hook(hook(
+        "intake",
+        ShutdownPhase::StopIntake,
+        Duration::ZERO,
+        &log,
+    ));
+
+    assert!(coordinator.request("test"));
+    assert!(!coordinator.request("second request"));
+
+    let mut supervisor = Supervisor::new(SupervisorConfig::default());
+    let report = coordinator.execute(&mut supervisor).await;
+
+    assert_eq!(report.reason, "test");
+    assert_eq!(*log.lock().unwrap(), vec!["intake", "persist"]);
+    let statuses: Vec<StepStatus> = report.steps.iter().map(|s| s.status).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            StepStatus::Completed,
+            StepStatus::Completed,
+            StepStatus::TimedOut
+        ]
+    );
+    assert!(!report.is_clean());
+    assert!(report.completed_at.is_some());
+}
+
+#[tokio::test]
+async fn test_platforms_connected_later_are_disconnected_last() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let coordinator = ShutdownCoordinator::new(ShutdownConfig::default()).with_hook(hook(
+        "persist",
+        ShutdownPhase::PersistState,
+        Duration::ZERO,
+        &log,
+    ));
+
+    // Registered the way connectors do once an account has connected
+    let disconnected = log.clone();
+    coordinator.add_hook(Arc::new(DisconnectPlatform::new("acc-1", move || {
+        let disconnected = disconnected.clone();
+        async move {
+            disconnected.lock().unwrap().push("disconnect".to_string());
+            Ok(())
+        }
+    })));
+    coordinator.add_hook(hook(
+        "logout",
+        ShutdownPhase::CloseSessions,
+        Duration::ZERO,
+        &log,
+    ));
+
+    let mut supervisor = Supervisor::new(SupervisorConfig::default());
+    let report = coordinator.execute(&mut supervisor).await;
+
+    assert!(report.is_clean());
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["persist", "logout", "disconnect"]
+    );
+    let last = report.steps.last().unwrap();
+    assert_eq!(
+        (last.hook.as_str(), last.phase),
+        ("disconnect-acc-1", ShutdownPhase::DrainPools)
+    );
+}
+
+#[tokio::test]
+async fn test_wait_for_request_resolves_with_reason() {
+    let coordinator = Arc::new(ShutdownCoordinator::new(ShutdownConfig::default()));
+    let waiter = {
+        let coordinator = coordinator.clone();
+        tokio::spawn(async move { coordinator.wait_for_request().await })
+    };
+
+    coordinator.request("SIGTERM");
+    assert_eq!(waiter.await.unwrap(), "SIGTERM");
+}
+
+#[tokio::test]
+async fn test_orchestrator_rejects_signals_after_intake_stops() {
+    let orchestrator = TradeExecutionOrchestrator::new();
+    orchestrator.stop_accepting_signals();
+
+    let error = orchestrator
+        .process_signal(signal("sig-1"))
+        .await
+        .unwrap_err();
+    assert!(error.contains("shutting down"));
+}
+
+#[tokio::test]
+async fn test_drain_cancels_executions_past_deadline() {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    let plan = ExecutionPlan {
+        signal_id: "sig-slow".to_string(),
+        symbol: "EURUSD".to_string(),
+        account_assignments: vec![AccountAssignment {
+            account_id: "acc-1".to_string(),
+            position_size: 1.0,
+            entry_timing_delay: Duration::from_secs(30),
+            priority: 1,
+            risk_per_unit: 0.0,
+        }],
+        timing_variance: HashMap::new(),
+        size_variance: HashMap::new(),
+        rationale: "test".to_string(),
+        exit_policy: None,
+        tags: vec![],
+        entry_ladder: None,
+    };
+
+    let execution = {
+        let orchestrator = orchestrator.clone();
+        tokio::spawn(async move { orchestrator.execute_plan(&plan).await })
+    };
+    while orchestrator.in_flight_executions().await.is_empty() {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    let cancelled = orchestrator
+        .drain_executions(Duration::from_millis(100))
+        .await;
+    assert_eq!(cancelled, vec!["sig-slow".to_string()]);
+
+    let results = tokio::time::timeout(Duration::from_secs(1), execution)
+        .await
+        .expect("cancelled execution should finish promptly")
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(!results[0].success);
+    assert!(results[0]
+        .error_message
+        .as_deref()
+        .unwrap()
+        .contains("cancelled"));
+
+    let history = orchestrator.get_execution_history(10).await;
+    assert!(history.iter().any(|e| e.action == "EXECUTION_CANCELLED"));
+    assert!(orchestrator.in_flight_executions().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_drain_returns_immediately_when_idle() {
+    let orchestrator = TradeExecutionOrchestrator::new();
+    let cancelled = tokio::time::timeout(
+        Duration::from_millis(500),
+        orchestrator.drain_executions(Duration::from_secs(30)),
+    )
+    .await
+    .unwrap();
+    assert!(cancelled.is_empty());
+}
+
+#[tokio::test]
+async fn test_persisted_state_round_trips() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state/orchestrator.json");
+    let path = path.to_str().unwrap();
+
+    let orchestrator = TradeExecutionOrchestrator::new();
+    orchestrator.persist_state(path).await.unwrap();
+
+    let snapshot = OrchestratorSnapshot::load(path).unwrap();
+    assert!(snapshot.accounts.is_empty());
+    assert!(snapshot.active_executions.is_empty());
+}
@@ -0,0 +1,155 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use execution_engine::runtime::{
+    DependencyHealth, DependencyKind, HealthChecker, HealthLevel, HealthProbe, ShutdownConfig,
+    ShutdownCoordinator, SubsystemState, SubsystemStatus, SubsystemStatuses,
+};
+
+/// Reports a fixed level, optionally after a delay
+struct FixedProbe {
+    name: String,
+    level: HealthLevel,
+    critical: bool,
+    delay: Duration,
+}
+
+impl FixedProbe {
+    fn new(name: &str, level: HealthLevel, critical: bool) -> Arc<Self> {
+        Arc::new(Self {
+            name: name.to_string(),
+            level,
+            critical,
+            delay: Duration::ZERO,
+        })
+    }
+
+    fn slow(name: &str) -> Arc<Self> {
+        Arc::new(Self {
+            name: name.to_string(),
+            level: HealthLevel::Healthy,
+            critical: true,
+            delay: Duration::from_secs(5),
+        })
+    }
+}
+
+#[async_trait]
+impl HealthProbe for FixedProbe {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> DependencyKind {
+        DependencyKind::Database
+    }
+
+    async fn check(&self) -> Vec<DependencyHealth> {
+        tokio::time::sleep(self.delay).await;
+        vec![
+            DependencyHealth::new(&self.name, DependencyKind::Database, self.level)
+                .with_critical(self.critical),
+        ]
+    }
+}
+
+fn statuses(states: &[(&str, SubsystemState)]) -> SubsystemStatuses {
+    let statuses = states
+        .iter()
+        .map(|(name, state)| {
+            (
+                name.to_string(),
+                SubsystemStatus {
+                    name: name.to_string(),
+                    state: *state,
+                    restarts: 0,
+                    last_error: None,
+                    updated_at: Utc::now(),
+                },
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    Arc::new(RwLock::new(statuses))
+}
+
+fn dependency<'a>(
+    report: &'a execution_engine::runtime::HealthReport,
+    name: &str,
+) -> &'a DependencyHealth {
+    report
+        .dependencies
+        .iter()
+        .find(|d| d.name == name)
+        .unwrap_or_else(|| panic!("no dependency {}", name))
+}
+
+#[tokio::test]
+async fn test_non_critical_failures_degrade_without_losing_readiness() {
+    let checker = HealthChecker::new(statuses(&[
+        ("messaging", SubsystemState::Running),
+        ("risk-monitor", SubsystemState::Restarting),
+    ]))
+    .with_probe(FixedProbe::new("journal", HealthLevel::Unhealthy, false));
+
+    let report = checker.check().await;
+    assert_eq!(report.status, HealthLevel::Degraded);
+    assert!(report.healthy && report.live && report.ready);
+    assert_eq!(report.dependencies.len(), 3);
+    assert_eq!(
+        dependency(&report, "messaging").kind,
+        DependencyKind::Messaging
+    );
+    let risk = dependency(&report, "risk-monitor");
+    assert_eq!(risk.kind, DependencyKind::RiskMonitor);
+    assert_eq!(risk.level, HealthLevel::Degraded);
+    assert_eq!(dependency(&report, "journal").level, HealthLevel::Unhealthy);
+}
+
+#[tokio::test]
+async fn test_critical_failures_affect_readiness_and_liveness_separately() {
+    // A critical dependency being down takes the engine out of service but a restart won't help
+    let checker = HealthChecker::new(statuses(&[("api-server", SubsystemState::Running)]))
+        .with_probe(FixedProbe::new("fix-session", HealthLevel::Unhealthy, true));
+    let report = checker.check().await;
+    assert_eq!(report.status, HealthLevel::Unhealthy);
+    assert!(!report.healthy && !report.ready);
+    assert!(report.live);
+
+    // A subsystem the supervisor gave up on needs the process restarted
+    let checker = HealthChecker::new(statuses(&[
+        ("api-server", SubsystemState::Running),
+        ("exit-management", SubsystemState::Failed),
+    ]));
+    assert!(!checker.is_live().await);
+    let report = checker.check().await;
+    assert!(!report.live && !report.ready);
+}
+
+#[tokio::test]
+async fn test_slow_probes_time_out_and_shutdown_clears_readiness() {
+    let shutdown = Arc::new(ShutdownCoordinator::new(ShutdownConfig::default()));
+    let checker = HealthChecker::new(statuses(&[("api-server", SubsystemState::Running)]))
+        .with_shutdown(shutdown.clone())
+        .with_probe_timeout(Duration::from_millis(50))
+        .with_probe(FixedProbe::new("journal", HealthLevel::Healthy, true))
+        .with_probe(FixedProbe::slow("database"));
+
+    let report = checker.check().await;
+    let database = dependency(&report, "database");
+    assert_eq!(database.level, HealthLevel::Unhealthy);
+    assert!(database.detail.as_deref().unwrap().contains("no answer"));
+    assert!(dependency(&report, "journal").latency_ms.is_some());
+    assert!(!report.ready);
+
+    let checker = HealthChecker::new(statuses(&[("api-server", SubsystemState::Running)]))
+        .with_shutdown(shutdown.clone());
+    assert!(checker.check().await.ready);
+    shutdown.request("test");
+    let report = checker.check().await;
+    assert!(!report.ready);
+    assert_eq!(report.status, HealthLevel::Healthy);
+}
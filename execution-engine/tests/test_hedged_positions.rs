@@ -0,0 +1,219 @@
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::execution::exit_management::{
+    ClosePositionRequest, ExitManagementPlatformAdapter, PartialCloseRequest, TradingPlatform,
+};
+use execution_engine::platforms::abstraction::capabilities::PlatformFeature;
+use execution_engine::platforms::abstraction::errors::PlatformError;
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::{
+    PositionTickets, UnifiedPosition, UnifiedPositionSide,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+/// A MetaTrader-style ticket on EURUSD, opened `age_mins` ago
+fn ticket(
+    id: &str,
+    side: UnifiedPositionSide,
+    quantity: Decimal,
+    entry: Decimal,
+    age_mins: i64,
+) -> UnifiedPosition {
+    let opened_at = Utc::now() - Duration::minutes(age_mins);
+    UnifiedPosition {
+        position_id: id.to_string(),
+        symbol: "EURUSD".to_string(),
+        side,
+        quantity,
+        entry_price: entry,
+        current_price: dec!(1.1000),
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: dec!(100),
+        commission: Decimal::ZERO,
+        stop_loss: None,
+        take_profit: None,
+        opened_at,
+        updated_at: opened_at,
+        account_id: "hedged".to_string(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+/// Two longs and a short on EURUSD
+fn hedged_platform() -> Arc<MockTradingPlatform> {
+    Arc::new(
+        MockTradingPlatform::new("hedged")
+            .with_hedging()
+            .with_quote("EURUSD", dec!(1.1000), dec!(1.1002))
+            .with_position(ticket(
+                "1001",
+                UnifiedPositionSide::Long,
+                dec!(2),
+                dec!(1.0900),
+                30,
+            ))
+            .with_position(ticket(
+                "1002",
+                UnifiedPositionSide::Short,
+                dec!(1),
+                dec!(1.1050),
+                20,
+            ))
+            .with_position(ticket(
+                "1003",
+                UnifiedPositionSide::Long,
+                dec!(1),
+                dec!(1.0960),
+                10,
+            )),
+    )
+}
+
+#[test]
+fn tickets_fold_into_a_net_position() {
+    let tickets = PositionTickets::from_positions(
+        "EURUSD",
+        vec![
+            ticket("1003", UnifiedPositionSide::Long, dec!(1), dec!(1.0960), 10),
+            ticket("1001", UnifiedPositionSide::Long, dec!(2), dec!(1.0900), 30),
+            ticket(
+                "1002",
+                UnifiedPositionSide::Short,
+                dec!(1),
+                dec!(1.1050),
+                20,
+            ),
+        ],
+    );
+
+    assert!(tickets.is_hedged());
+    assert_eq!(tickets.tickets[0].position_id, "1001");
+    assert_eq!(tickets.net_quantity(), dec!(2));
+
+    let net = tickets.net().unwrap();
+    assert_eq!(net.side, UnifiedPositionSide::Long);
+    assert_eq!(net.quantity, dec!(2));
+    assert_eq!(net.position_id, "1001");
+    assert_eq!(net.entry_price, dec!(1.0920));
+    assert_eq!(net.margin_used, dec!(300));
+
+    // Fully offset tickets have no net position
+    let flat = PositionTickets::from_positions(
+        "EURUSD",
+        vec![
+            ticket("1", UnifiedPositionSide::Long, dec!(1), dec!(1.0900), 2),
+            ticket("2", UnifiedPositionSide::Short, dec!(1), dec!(1.0950), 1),
+        ],
+    );
+    assert!(flat.net().is_none());
+}
+
+#[tokio::test]
+async fn hedging_accounts_close_tickets_individually() {
+    let platform = hedged_platform();
+    assert!(platform.supports_feature(PlatformFeature::HedgedPositions));
+
+    let net = platform.get_position("EURUSD").await.unwrap().unwrap();
+    assert_eq!(net.quantity, dec!(2));
+
+    // Closing the short leaves both longs open
+    let response = platform.close_position_ticket("1002", None).await.unwrap();
+    assert_eq!(response.quantity, dec!(1));
+    let tickets = platform.get_position_tickets("EURUSD").await.unwrap();
+    assert_eq!(tickets.tickets.len(), 2);
+    assert!(!tickets.is_hedged());
+
+    // Partial ticket close
+    platform
+        .close_position_ticket("1001", Some(dec!(0.5)))
+        .await
+        .unwrap();
+    let tickets = platform.get_position_tickets("EURUSD").await.unwrap();
+    assert_eq!(tickets.ticket("1001").unwrap().quantity, dec!(1.5));
+
+    let error = platform
+        .close_position_ticket("1002", None)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, PlatformError::TicketNotFound { .. }));
+    assert_eq!(
+        platform.ticket_closes(),
+        vec![
+            ("1002".to_string(), None),
+            ("1001".to_string(), Some(dec!(0.5)))
+        ]
+    );
+}
+
+#[tokio::test]
+async fn closing_a_hedged_symbol_needs_a_ticket_for_partial_quantities() {
+    let platform = hedged_platform();
+
+    let error = platform
+        .close_position("EURUSD", Some(dec!(1)))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, PlatformError::PositionCloseFailed { .. }));
+    assert_eq!(platform.positions().len(), 3);
+
+    platform.close_position("EURUSD", None).await.unwrap();
+    assert!(platform.positions().is_empty());
+    assert!(platform.get_position("EURUSD").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn exit_management_supervises_each_ticket() {
+    let platform = hedged_platform();
+    let adapter = ExitManagementPlatformAdapter::new(platform.clone());
+
+    let positions = adapter.get_positions().await.unwrap();
+    assert_eq!(positions.len(), 3);
+    // Integer tickets keep the same id from one poll to the next
+    let again = adapter.get_positions().await.unwrap();
+    let ids: Vec<_> = positions.iter().map(|p| p.id).collect();
+    assert_eq!(ids, again.iter().map(|p| p.id).collect::<Vec<_>>());
+
+    let short = positions.iter().find(|p| p.order_id == "1002").unwrap();
+    adapter
+        .close_position(ClosePositionRequest {
+            position_id: short.id,
+            reason: "time exit".to_string(),
+        })
+        .await
+        .unwrap();
+    let long = positions.iter().find(|p| p.order_id == "1003").unwrap();
+    adapter
+        .close_position_partial(PartialCloseRequest {
+            position_id: long.id,
+            volume: dec!(0.5),
+            reason: "partial profit".to_string(),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        platform.ticket_closes(),
+        vec![
+            ("1002".to_string(), None),
+            ("1003".to_string(), Some(dec!(0.5)))
+        ]
+    );
+    assert!(platform.closes().is_empty());
+    let remaining: Vec<_> = platform
+        .positions()
+        .into_iter()
+        .map(|p| (p.position_id, p.quantity))
+        .collect();
+    assert_eq!(
+        remaining,
+        vec![
+            ("1001".to_string(), dec!(2)),
+            ("1003".to_string(), dec!(0.5))
+        ]
+    );
+}
@@ -0,0 +1,184 @@
+use execution_engine::risk::config::DrawdownThresholds;
+use execution_engine::risk::drawdown_tracker::{
+    DrawdownAlertManager, DrawdownAlertType, DrawdownTracker, EquityHistoryManager,
+};
+use execution_engine::risk::{
+    CircuitBreakerClient, EmergencyStopScope, FileHighWaterMarkStore, HighWaterMarkStore,
+    PositionManager, ResponseAction, ResponseExecutor, RiskAuditLogger, RiskResponseSystem,
+    RiskSeverity, RiskThresholds,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::path::Path;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn thresholds(trailing: Option<Decimal>) -> DrawdownThresholds {
+    DrawdownThresholds {
+        daily_threshold: dec!(50),
+        weekly_threshold: dec!(50),
+        max_threshold: dec!(50),
+        recovery_factor_threshold: dec!(2),
+        trailing_threshold: trailing,
+    }
+}
+
+fn risk_response() -> Arc<RiskResponseSystem> {
+    Arc::new(RiskResponseSystem::new(
+        Arc::new(RiskThresholds::default()),
+        Arc::new(PositionManager::new()),
+        Arc::new(CircuitBreakerClient),
+        Arc::new(RiskAuditLogger::new()),
+        Arc::new(ResponseExecutor),
+    ))
+}
+
+struct Setup {
+    history: Arc<EquityHistoryManager>,
+    alerts: Arc<DrawdownAlertManager>,
+    tracker: DrawdownTracker,
+}
+
+fn tracker(store_path: &Path) -> Setup {
+    let history = Arc::new(EquityHistoryManager::new());
+    let alerts = Arc::new(DrawdownAlertManager::new());
+    let tracker = DrawdownTracker::new(history.clone(), alerts.clone(), thresholds(Some(dec!(10))))
+        .with_high_water_mark_store(Arc::new(FileHighWaterMarkStore::new(store_path)))
+        .with_risk_response(risk_response());
+    Setup {
+        history,
+        alerts,
+        tracker,
+    }
+}
+
+async fn record(history: &EquityHistoryManager, account_id: Uuid, equity: &[Decimal]) {
+    for equity in equity {
+        history
+            .record_equity(account_id, *equity, *equity)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn trailing_drawdown_is_measured_from_high_water_mark() {
+    let dir = tempfile::tempdir().unwrap();
+    let setup = tracker(&dir.path().join("hwm.json"));
+    let account_id = Uuid::new_v4();
+    record(
+        &setup.history,
+        account_id,
+        &[dec!(10000), dec!(12000), dec!(11000)],
+    )
+    .await;
+
+    setup.tracker.calculate_drawdowns(account_id).await.unwrap();
+
+    let mark = setup.tracker.high_water_mark(account_id).unwrap();
+    assert_eq!(mark.equity, dec!(12000));
+    let trailing = setup
+        .tracker
+        .trailing_drawdown(account_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(trailing.amount, dec!(1000));
+    assert_eq!(trailing.peak_equity, dec!(12000));
+    assert!(trailing.percentage > dec!(8.3) && trailing.percentage < dec!(8.4));
+    assert!(!setup.tracker.is_locked_out(account_id));
+    assert!(setup.alerts.get_alerts(account_id).is_empty());
+}
+
+#[tokio::test]
+async fn breach_locks_out_account_and_survives_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("hwm.json");
+    let account_id = Uuid::new_v4();
+
+    let setup = tracker(&path);
+    record(
+        &setup.history,
+        account_id,
+        &[dec!(10000), dec!(12000), dec!(10500)],
+    )
+    .await;
+    setup.tracker.calculate_drawdowns(account_id).await.unwrap();
+
+    assert!(setup.tracker.is_locked_out(account_id));
+    let alerts = setup.alerts.get_alerts(account_id);
+    assert_eq!(alerts.len(), 1);
+    assert!(matches!(alerts[0].alert_type, DrawdownAlertType::Trailing));
+    assert_eq!(alerts[0].drawdown_percentage, dec!(12.5));
+
+    // A restarted tracker with only recent, lower equity keeps the mark and the lockout
+    let restarted = tracker(&path);
+    assert_eq!(
+        restarted.tracker.restore_high_water_marks().await.unwrap(),
+        1
+    );
+    record(&restarted.history, account_id, &[dec!(11500)]).await;
+    restarted
+        .tracker
+        .calculate_drawdowns(account_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        restarted
+            .tracker
+            .high_water_mark(account_id)
+            .unwrap()
+            .equity,
+        dec!(12000)
+    );
+    assert!(restarted.tracker.is_locked_out(account_id));
+
+    restarted.tracker.clear_lockout(account_id).await.unwrap();
+    assert!(!restarted.tracker.is_locked_out(account_id));
+    let stored = FileHighWaterMarkStore::new(&path).load_all().await.unwrap();
+    assert_eq!(stored.len(), 1);
+    assert!(stored[0].breached_at.is_none());
+}
+
+#[tokio::test]
+async fn trailing_drawdown_unchecked_without_threshold() {
+    let history = Arc::new(EquityHistoryManager::new());
+    let alerts = Arc::new(DrawdownAlertManager::new());
+    let tracker = DrawdownTracker::new(history.clone(), alerts.clone(), thresholds(None));
+    let account_id = Uuid::new_v4();
+    record(&history, account_id, &[dec!(10000), dec!(5000)]).await;
+
+    tracker.calculate_drawdowns(account_id).await.unwrap();
+
+    assert_eq!(
+        tracker.high_water_mark(account_id).unwrap().equity,
+        dec!(10000)
+    );
+    assert!(!tracker.is_locked_out(account_id));
+    assert!(alerts.get_alerts(account_id).is_empty());
+}
+
+#[tokio::test]
+async fn trailing_breach_stops_account() {
+    let account_id = Uuid::new_v4();
+    let system = risk_response();
+
+    assert!(system
+        .handle_trailing_drawdown_risk(account_id, dec!(4), dec!(5))
+        .await
+        .unwrap()
+        .is_none());
+
+    let response = system
+        .handle_trailing_drawdown_risk(account_id, dec!(5.5), dec!(5))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(response.severity, RiskSeverity::Extreme);
+    match response.action_taken {
+        ResponseAction::EmergencyStop { scope, .. } => {
+            assert_eq!(scope, EmergencyStopScope::Account(account_id));
+        }
+        other => panic!("Expected EmergencyStop, got {:?}", other),
+    }
+}
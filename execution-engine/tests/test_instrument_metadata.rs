@@ -0,0 +1,318 @@
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use execution_engine::execution::exit_management::{
+    BreakEvenManager, ClosePositionRequest, ClosePositionResult, ExitAuditLogger,
+    ExitManagementSystem, MarketData, OrderModifyRequest, OrderModifyResult, PartialCloseRequest,
+    Position, TradingPlatform, TrailingConfig, TrailingStopManager, UnifiedPositionSide,
+};
+use execution_engine::instruments::{InstrumentMetadata, InstrumentMetadataService};
+use execution_engine::platforms::abstraction::models::{InstrumentType, Symbol};
+
+#[derive(Debug)]
+struct MockPlatform {
+    positions: Mutex<Vec<Position>>,
+    mid: Mutex<Decimal>,
+    modifications: Mutex<Vec<OrderModifyRequest>>,
+}
+
+impl MockPlatform {
+    fn new(position: Position, mid: Decimal) -> Arc<Self> {
+        Arc::new(Self {
+            positions: Mutex::new(vec![position]),
+            mid: Mutex::new(mid),
+            modifications: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        let mid = *self.mid.lock().unwrap();
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: mid - dec!(0.005),
+            ask: mid + dec!(0.005),
+            spread: dec!(0.01),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        self.modifications.lock().unwrap().push(request.clone());
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: true,
+            message: "modified".to_string(),
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: *self.mid.lock().unwrap(),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: *self.mid.lock().unwrap(),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+}
+
+fn usdjpy_long() -> Position {
+    Position {
+        id: Uuid::new_v4(),
+        order_id: "order-1".to_string(),
+        symbol: "USDJPY".to_string(),
+        position_type: UnifiedPositionSide::Long,
+        volume: dec!(10000),
+        entry_price: dec!(150.000),
+        current_price: dec!(150.000),
+        stop_loss: Some(dec!(149.500)),
+        take_profit: None,
+        unrealized_pnl: Decimal::ZERO,
+        swap: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        open_time: Utc::now(),
+        magic_number: None,
+        comment: None,
+    }
+}
+
+fn symbol(name: &str, tick_size: Decimal) -> Symbol {
+    Symbol {
+        symbol: name.to_string(),
+        description: name.to_string(),
+        instrument_type: InstrumentType::Forex,
+        base_currency: name[..3].to_string(),
+        quote_currency: name[3..].to_string(),
+        min_trade_size: dec!(1000),
+        max_trade_size: None,
+        tick_size,
+        contract_size: None,
+        trading_hours: Vec::new(),
+        is_tradeable: true,
+        stops_level: None,
+        freeze_level: None,
+    }
+}
+
+fn usdjpy_with_stop_rules(stops_level: Decimal, freeze_level: Decimal) -> Symbol {
+    Symbol {
+        stops_level: Some(stops_level),
+        freeze_level: Some(freeze_level),
+        ..symbol("USDJPY", dec!(0.001))
+    }
+}
+
+fn trailing_manager(platform: Arc<MockPlatform>, symbols: &[Symbol]) -> TrailingStopManager {
+    let instruments = Arc::new(InstrumentMetadataService::new());
+    instruments.register_symbols(symbols);
+    let mut trailing = TrailingStopManager::new(platform, Arc::new(ExitAuditLogger::new()))
+        .with_instruments(instruments);
+    trailing.configure_symbol(
+        "USDJPY".to_string(),
+        TrailingConfig {
+            min_trail_distance: dec!(0.333),
+            max_trail_distance: dec!(0.333),
+            activation_threshold: dec!(0.15),
+            ..Default::default()
+        },
+    );
+    trailing
+}
+
+#[test]
+fn test_conventional_pip_sizes() {
+    let eurusd = InstrumentMetadata::conventional("EURUSD");
+    assert_eq!(eurusd.pip_size, dec!(0.0001));
+    assert_eq!(eurusd.pips_to_price(dec!(5)), dec!(0.0005));
+    assert_eq!(eurusd.price_to_pips(dec!(0.00123)), dec!(12.3));
+    assert_eq!(eurusd.round_price(dec!(1.1000049)), dec!(1.10000));
+
+    let usdjpy = InstrumentMetadata::conventional("USDJPY");
+    assert_eq!(usdjpy.pips_to_price(dec!(5)), dec!(0.05));
+    assert_eq!(usdjpy.round_price(dec!(150.0456)), dec!(150.046));
+
+    let gold = InstrumentMetadata::conventional("XAUUSD");
+    assert_eq!(gold.price_to_pips(dec!(2.5)), dec!(25));
+}
+
+#[test]
+fn test_platform_symbols_override_conventions() {
+    let service = InstrumentMetadataService::new();
+    service.register_symbols(&[
+        symbol("EURUSD", dec!(0.00001)),
+        symbol("USDJPY", dec!(0.01)),
+    ]);
+
+    // Fractional pip quotes: the pip is ten ticks
+    let eurusd = service.get("EURUSD");
+    assert_eq!(eurusd.pip_size, dec!(0.0001));
+    assert_eq!(eurusd.price_precision, 5);
+
+    // A platform quoting whole pips rounds to them
+    assert_eq!(service.round_price("USDJPY", dec!(150.046)), dec!(150.05));
+    assert_eq!(service.pips_to_price("USDJPY", dec!(5)), dec!(0.05));
+
+    // Unlisted symbols fall back to conventional values
+    assert_eq!(service.get("GBPJPY").pip_size, dec!(0.01));
+}
+
+#[tokio::test]
+async fn test_break_even_buffer_uses_instrument_pip_size() {
+    let position = usdjpy_long();
+    let platform = MockPlatform::new(position.clone(), dec!(150.600));
+    let manager = BreakEvenManager::new(platform.clone(), Arc::new(ExitAuditLogger::new()));
+
+    manager.check_break_even_triggers().await.unwrap();
+
+    // Five pips on a JPY pair is 0.05, not the 0.0005 an EURUSD pip would give
+    let modifications = platform.modifications.lock().unwrap();
+    assert_eq!(modifications.len(), 1);
+    assert_eq!(modifications[0].new_stop_loss, Some(dec!(150.050)));
+}
+
+#[tokio::test]
+async fn test_trail_levels_are_exact_at_platform_precision() {
+    let position = usdjpy_long();
+    let platform = MockPlatform::new(position.clone(), dec!(150.555));
+    let system = ExitManagementSystem::new(platform.clone(), Arc::new(ExitAuditLogger::new()));
+    system
+        .get_instruments()
+        .register_symbols(&[symbol("USDJPY", dec!(0.01))]);
+
+    let logger = Arc::new(ExitAuditLogger::new());
+    let mut trailing = TrailingStopManager::new(platform.clone(), logger)
+        .with_instruments(system.get_instruments());
+    trailing.configure_symbol(
+        "USDJPY".to_string(),
+        TrailingConfig {
+            min_trail_distance: dec!(0.333),
+            max_trail_distance: dec!(0.333),
+            activation_threshold: dec!(0.15),
+            ..Default::default()
+        },
+    );
+
+    trailing.activate_trailing_stop(&position).await.unwrap();
+
+    // 150.555 - 0.333 = 150.222, rounded to the platform's two decimals
+    let trail = trailing.get_active_trail(position.id).unwrap();
+    assert_eq!(trail.trail_level, dec!(150.22));
+    assert_eq!(trail.trail_level.scale(), 2);
+}
+
+#[test]
+fn test_stops_are_clamped_to_platform_distances() {
+    let usdjpy = InstrumentMetadata::from_symbol(&usdjpy_with_stop_rules(dec!(0.2), dec!(0.05)));
+    assert_eq!(usdjpy.min_stop_distance, dec!(0.2));
+
+    // Pulled back to the minimum distance, rounded away from the market
+    assert_eq!(
+        usdjpy.allowed_stop(true, dec!(150.5555), None, dec!(150.5)),
+        Some(dec!(150.355))
+    );
+    assert_eq!(
+        usdjpy.allowed_stop(false, dec!(150.5555), None, dec!(150.6)),
+        Some(dec!(150.756))
+    );
+    // Far enough already
+    assert_eq!(
+        usdjpy.allowed_stop(true, dec!(150.555), Some(dec!(149.5)), dec!(150.1)),
+        Some(dec!(150.100))
+    );
+    // A stop inside the freeze distance cannot be moved
+    assert_eq!(
+        usdjpy.allowed_stop(true, dec!(150.555), Some(dec!(150.52)), dec!(150.3)),
+        None
+    );
+
+    // Platforms reporting no rules leave stops as they are
+    let eurusd = InstrumentMetadata::conventional("EURUSD");
+    assert_eq!(
+        eurusd.allowed_stop(true, dec!(1.1), Some(dec!(1.09999)), dec!(1.09995)),
+        Some(dec!(1.09995))
+    );
+}
+
+#[tokio::test]
+async fn test_trail_is_held_at_minimum_stop_distance() {
+    let position = usdjpy_long();
+    let platform = MockPlatform::new(position.clone(), dec!(150.555));
+    let trailing = trailing_manager(
+        platform.clone(),
+        &[usdjpy_with_stop_rules(dec!(0.5), Decimal::ZERO)],
+    );
+
+    trailing.activate_trailing_stop(&position).await.unwrap();
+
+    // 150.555 - 0.333 is inside the 0.5 the platform requires from the 150.550 bid
+    let trail = trailing.get_active_trail(position.id).unwrap();
+    assert_eq!(trail.trail_level, dec!(150.050));
+
+    *platform.mid.lock().unwrap() = dec!(151.555);
+    trailing.update_trailing_stops().await.unwrap();
+
+    let modifications = platform.modifications.lock().unwrap();
+    assert_eq!(modifications.len(), 1);
+    assert_eq!(modifications[0].new_stop_loss, Some(dec!(151.050)));
+}
+
+#[tokio::test]
+async fn test_frozen_stop_is_not_trailed() {
+    let position = Position {
+        stop_loss: Some(dec!(150.520)),
+        ..usdjpy_long()
+    };
+    let platform = MockPlatform::new(position.clone(), dec!(150.555));
+    let trailing = trailing_manager(
+        platform.clone(),
+        &[usdjpy_with_stop_rules(Decimal::ZERO, dec!(0.1))],
+    );
+
+    trailing.activate_trailing_stop(&position).await.unwrap();
+
+    assert!(trailing.get_active_trail(position.id).is_none());
+}
+
+#[tokio::test]
+async fn test_break_even_waits_for_minimum_stop_distance() {
+    let position = usdjpy_long();
+    let platform = MockPlatform::new(position.clone(), dec!(150.600));
+    let instruments = Arc::new(InstrumentMetadataService::new());
+    instruments.register_symbols(&[usdjpy_with_stop_rules(dec!(0.6), Decimal::ZERO)]);
+    let manager = BreakEvenManager::new(platform.clone(), Arc::new(ExitAuditLogger::new()))
+        .with_instruments(instruments);
+
+    // Break-even at 150.050 is closer than 0.6 to the 150.595 bid
+    manager.check_break_even_triggers().await.unwrap();
+    assert!(platform.modifications.lock().unwrap().is_empty());
+    assert!(!manager.is_break_even_active(position.id));
+    assert!(manager.force_break_even(position.id).await.is_err());
+
+    *platform.mid.lock().unwrap() = dec!(150.700);
+    manager.check_break_even_triggers().await.unwrap();
+    let modifications = platform.modifications.lock().unwrap();
+    assert_eq!(modifications.len(), 1);
+    assert_eq!(modifications[0].new_stop_loss, Some(dec!(150.050)));
+}
@@ -0,0 +1,111 @@
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+
+use execution_engine::execution::{LatencyEntryConfig, TradeExecutionOrchestrator};
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::{UnifiedOrderStatus, UnifiedOrderType};
+use execution_engine::runtime::{HealthProbe, PlatformHealthProbe};
+use execution_engine::testing::{signal, MockTradingPlatform};
+
+async fn orchestrator(
+    fill_timeout_ms: u64,
+    platform: MockTradingPlatform,
+) -> (Arc<TradeExecutionOrchestrator>, Arc<MockTradingPlatform>) {
+    let orchestrator = Arc::new(
+        TradeExecutionOrchestrator::new()
+            .with_timing_variance(0, 0)
+            .with_latency_entry(LatencyEntryConfig {
+                enabled: true,
+                max_latency_ms: 100,
+                fill_timeout_ms,
+                ..LatencyEntryConfig::default()
+            }),
+    );
+    let platform = Arc::new(platform.with_quote("EURUSD", dec!(1.0999), dec!(1.1001)));
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+    (orchestrator, platform)
+}
+
+#[tokio::test]
+async fn slow_platforms_get_marketable_limit_entries() {
+    let (orchestrator, platform) = orchestrator(
+        3000,
+        MockTradingPlatform::new("acc-1").with_latency(Duration::from_millis(150)),
+    )
+    .await;
+    // The health check measures the latency entries are judged by
+    PlatformHealthProbe::new(orchestrator.clone()).check().await;
+    assert_eq!(
+        orchestrator
+            .latency_monitor()
+            .latest("acc-1")
+            .unwrap()
+            .latency_ms,
+        150
+    );
+
+    let filler = {
+        let platform = platform.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Some(order) = platform.orders().first() {
+                    return platform.fill_order(&order.platform_order_id);
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+    };
+    let plan = orchestrator
+        .process_signal(signal("sig-latency"))
+        .await
+        .unwrap();
+    let result = &orchestrator.execute_plan(&plan).await[0];
+    filler.await.unwrap().unwrap();
+
+    assert!(result.success, "{:?}", result.error_message);
+    // One pip through the ask
+    assert_eq!(result.actual_entry_price, Some(1.1002));
+    let order = &platform.orders()[0];
+    assert_eq!(order.order_type, UnifiedOrderType::Limit);
+    assert_eq!(order.price, Some(dec!(1.1002)));
+    assert_eq!(platform.get_positions().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn unfilled_limit_entries_are_cancelled_at_the_timeout() {
+    let (orchestrator, platform) = orchestrator(200, MockTradingPlatform::new("acc-1")).await;
+    orchestrator.latency_monitor().record("acc-1", 800);
+
+    let plan = orchestrator
+        .process_signal(signal("sig-latency"))
+        .await
+        .unwrap();
+    let result = &orchestrator.execute_plan(&plan).await[0];
+
+    assert!(!result.success);
+    assert_eq!(
+        result.error_message.as_deref(),
+        Some(
+            "Limit entry at 1.1002 not filled within 200 ms and cancelled; platform latency 800 ms"
+        )
+    );
+    assert_eq!(platform.orders()[0].status, UnifiedOrderStatus::Canceled);
+    assert!(platform.get_positions().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn fast_platforms_keep_market_entries() {
+    let (orchestrator, platform) = orchestrator(200, MockTradingPlatform::new("acc-1")).await;
+    PlatformHealthProbe::new(orchestrator.clone()).check().await;
+
+    let plan = orchestrator
+        .process_signal(signal("sig-latency"))
+        .await
+        .unwrap();
+    assert!(orchestrator.execute_plan(&plan).await[0].success);
+    assert_eq!(platform.orders()[0].order_type, UnifiedOrderType::Market);
+}
@@ -0,0 +1,127 @@
+use execution_engine::platforms::PlatformType;
+use execution_engine::runtime::{AccountLevelFilter, AccountLogLevels, LogContext};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Captured {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Captured {
+    type Writer = Captured;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Run `f` under a subscriber filtered at info with `levels` on top
+fn capture(levels: &AccountLogLevels, f: impl FnOnce()) -> Vec<String> {
+    let captured = Captured::default();
+    let subscriber = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .with_writer(captured.clone())
+            .with_ansi(false)
+            .with_filter(AccountLevelFilter::new(
+                EnvFilter::new("info"),
+                levels.clone(),
+            )),
+    );
+    tracing::subscriber::with_default(subscriber, f);
+    captured.lines()
+}
+
+#[test]
+fn context_fields_appear_on_every_line() {
+    let lines = capture(&AccountLogLevels::default(), || {
+        let account = LogContext::account("acc-1")
+            .with_platform(PlatformType::Mock)
+            .span();
+        let _account = account.enter();
+        tracing::info!("placing order");
+
+        let position = LogContext::position(42).with_signal("sig-7").span();
+        let _position = position.enter();
+        tracing::warn!("stop moved");
+    });
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains("account_id=\"acc-1\""));
+    assert!(lines[0].contains("platform=Mock"));
+    assert!(lines[0].contains("placing order"));
+    assert!(lines[1].contains("account_id=\"acc-1\""));
+    assert!(lines[1].contains("signal_id=\"sig-7\""));
+    assert!(lines[1].contains("position_id=\"42\""));
+}
+
+#[test]
+fn account_override_raises_only_that_account() {
+    let levels = AccountLogLevels::default();
+    levels.set("acc-debug", LevelFilter::DEBUG);
+
+    let lines = capture(&levels, || {
+        for account_id in ["acc-debug", "acc-quiet"] {
+            let span = LogContext::account(account_id).span();
+            let _entered = span.enter();
+            tracing::debug!(account_id, "evaluating position");
+            tracing::trace!(account_id, "raw quote");
+        }
+        tracing::debug!("outside any account");
+    });
+
+    assert_eq!(lines.len(), 1, "{:?}", lines);
+    assert!(lines[0].contains("DEBUG"));
+    assert!(lines[0].contains("account_id=\"acc-debug\""));
+}
+
+#[test]
+fn cleared_override_falls_back_to_default() {
+    let levels = AccountLogLevels::default();
+    levels.set("acc-1", LevelFilter::DEBUG);
+    levels.clear("acc-1");
+    assert_eq!(levels.get("acc-1"), None);
+
+    let lines = capture(&levels, || {
+        let span = LogContext::account("acc-1").span();
+        let _entered = span.enter();
+        tracing::debug!("evaluating position");
+    });
+    assert!(lines.is_empty(), "{:?}", lines);
+}
+
+#[test]
+fn apply_parses_levels_and_rejects_unknown_ones() {
+    let levels = AccountLogLevels::default();
+    let configured = HashMap::from([("acc-1".to_string(), "trace".to_string())]);
+    levels.apply(&configured).unwrap();
+    assert_eq!(levels.get("acc-1"), Some(LevelFilter::TRACE));
+
+    let invalid = HashMap::from([("acc-2".to_string(), "loud".to_string())]);
+    assert!(levels.apply(&invalid).is_err());
+    assert_eq!(levels.get("acc-2"), None);
+}
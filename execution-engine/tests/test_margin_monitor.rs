@@ -12,7 +12,7 @@ async fn test_margin_level_calculation() {
     let account_manager = Arc::new(AccountManager::new());
     let margin_calculator = Arc::new(MarginCalculator::new());
     let margin_alerts = Arc::new(MarginAlertManager::new());
-    let margin_protection = Arc::new(MarginProtectionSystem);
+    let margin_protection = Arc::new(MarginProtectionSystem::default());
 
     let monitor = MarginMonitor::new(
         account_manager.clone(),
@@ -62,7 +62,7 @@ async fn test_margin_thresholds() {
     let account_manager = Arc::new(AccountManager::new());
     let margin_calculator = Arc::new(MarginCalculator::new());
     let margin_alerts = Arc::new(MarginAlertManager::new());
-    let margin_protection = Arc::new(MarginProtectionSystem);
+    let margin_protection = Arc::new(MarginProtectionSystem::default());
 
     let monitor = MarginMonitor::new(
         account_manager.clone(),
@@ -115,7 +115,7 @@ async fn test_margin_requirements() {
     let account_manager = Arc::new(AccountManager::new());
     let margin_calculator = Arc::new(MarginCalculator::new());
     let margin_alerts = Arc::new(MarginAlertManager::new());
-    let margin_protection = Arc::new(MarginProtectionSystem);
+    let margin_protection = Arc::new(MarginProtectionSystem::default());
 
     let monitor = MarginMonitor::new(
         account_manager.clone(),
@@ -152,7 +152,7 @@ async fn test_margin_impact_simulation() {
     let account_manager = Arc::new(AccountManager::new());
     let margin_calculator = Arc::new(MarginCalculator::new());
     let margin_alerts = Arc::new(MarginAlertManager::new());
-    let margin_protection = Arc::new(MarginProtectionSystem);
+    let margin_protection = Arc::new(MarginProtectionSystem::default());
 
     let monitor = MarginMonitor::new(
         account_manager.clone(),
@@ -216,7 +216,7 @@ async fn test_margin_impact_rejection() {
     let account_manager = Arc::new(AccountManager::new());
     let margin_calculator = Arc::new(MarginCalculator::new());
     let margin_alerts = Arc::new(MarginAlertManager::new());
-    let margin_protection = Arc::new(MarginProtectionSystem);
+    let margin_protection = Arc::new(MarginProtectionSystem::default());
 
     let monitor = MarginMonitor::new(
         account_manager.clone(),
@@ -0,0 +1,230 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use execution_engine::execution::exit_management::{
+    ClosePositionRequest, ClosePositionResult, ExitAuditLogger, ExitManagementSystem,
+    ExitModificationType, ImpactLevel, MarketContextConfig, MarketContextProvider, MarketData,
+    MarketSession, NewsEvent, OrderModifyRequest, OrderModifyResult, PartialCloseRequest, Position,
+    TradingPlatform, UnifiedPositionSide,
+};
+use execution_engine::market_data::{CandleBuilder, CandleConfig, Timeframe};
+
+/// Quotes EURUSD at 1.1049/1.1051 and accepts every modification
+#[derive(Debug, Default)]
+struct MockPlatform {
+    positions: Mutex<Vec<Position>>,
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: dec!(1.1049),
+            ask: dec!(1.1051),
+            spread: dec!(0.0002),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: true,
+            message: "modified".to_string(),
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        Err(anyhow!("unexpected close of {}", request.position_id))
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        Err(anyhow!(
+            "unexpected partial close of {}",
+            request.position_id
+        ))
+    }
+}
+
+fn at(day: u32, hour: u32) -> DateTime<Utc> {
+    // 3 March 2025 is a Monday
+    Utc.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap()
+}
+
+/// Five minutes climbing steadily from 1.1000 to 1.1060
+fn candles() -> Arc<CandleBuilder> {
+    let builder = CandleBuilder::new(CandleConfig {
+        timeframes: vec![Timeframe::M1],
+        ..CandleConfig::default()
+    });
+    let ranges = [
+        (dec!(1.1010), dec!(1.1000)),
+        (dec!(1.1030), dec!(1.1010)),
+        (dec!(1.1040), dec!(1.1020)),
+        (dec!(1.1050), dec!(1.1030)),
+        (dec!(1.1060), dec!(1.1040)),
+    ];
+    for (minute, (high, low)) in ranges.iter().enumerate() {
+        let open = at(3, 9) + Duration::minutes(minute as i64);
+        let mid = (high + low) / Decimal::TWO;
+        builder.on_tick("EURUSD", mid, Decimal::ZERO, open);
+        builder.on_tick("EURUSD", *high, Decimal::ZERO, open + Duration::seconds(10));
+        builder.on_tick("EURUSD", *low, Decimal::ZERO, open + Duration::seconds(20));
+        builder.on_tick("EURUSD", mid, Decimal::ZERO, open + Duration::seconds(30));
+    }
+    builder.close_elapsed(at(3, 10));
+    Arc::new(builder)
+}
+
+fn config() -> MarketContextConfig {
+    MarketContextConfig {
+        timeframe: Timeframe::M1,
+        atr_period: 3,
+        range_candles: 5,
+        ..MarketContextConfig::default()
+    }
+}
+
+fn news(currency: &str, minutes_from_now: i64) -> NewsEvent {
+    NewsEvent {
+        id: format!("{}-{}", currency, minutes_from_now),
+        description: format!("{} release", currency),
+        currency: currency.to_string(),
+        impact: ImpactLevel::High,
+        // A few seconds of slack so whole minutes survive the time the test takes
+        time: Utc::now() + Duration::minutes(minutes_from_now) + Duration::seconds(10),
+    }
+}
+
+#[tokio::test]
+async fn context_is_measured_from_candles_and_the_live_quote() {
+    let candles = candles();
+    let provider = MarketContextProvider::new(Arc::new(MockPlatform::default()))
+        .with_candles(candles.clone())
+        .with_config(config());
+
+    let context = provider.capture("EURUSD").await.unwrap();
+    let atr = candles.atr("EURUSD", Timeframe::M1, 3).unwrap();
+    assert_eq!(context.current_price, dec!(1.1050));
+    assert_eq!(context.spread, dec!(0.0002));
+    assert_eq!(context.atr_14, atr);
+    assert!((context.volatility - 0.0020 / 1.1050).abs() < 1e-9);
+    assert_eq!(context.recent_range, Some(dec!(0.0060)));
+    // Every close is above the one before
+    assert_eq!(context.trend_strength, 1.0);
+    assert!(context.session.is_some());
+
+    // A fill price replaces the mid but the quote still gives the spread
+    let fill = provider.capture_at("EURUSD", dec!(1.1047)).await;
+    assert_eq!(
+        (fill.current_price, fill.spread),
+        (dec!(1.1047), dec!(0.0002))
+    );
+
+    // Without candle history there is nothing to measure
+    let bare = MarketContextProvider::new(Arc::new(MockPlatform::default()))
+        .capture("EURUSD")
+        .await
+        .unwrap();
+    assert_eq!((bare.atr_14, bare.volatility), (Decimal::ZERO, 0.0));
+    assert_eq!(bare.recent_range, None);
+
+    assert_eq!(MarketSession::at(at(5, 3)), MarketSession::Tokyo);
+    assert_eq!(MarketSession::at(at(5, 9)), MarketSession::London);
+    assert_eq!(MarketSession::at(at(5, 13)), MarketSession::LondonNewYork);
+    assert_eq!(MarketSession::at(at(5, 18)), MarketSession::NewYork);
+    assert_eq!(MarketSession::at(at(5, 23)), MarketSession::Sydney);
+    assert_eq!(MarketSession::at(at(8, 12)), MarketSession::Closed);
+}
+
+#[tokio::test]
+async fn the_nearest_news_for_either_currency_is_recorded() {
+    let provider = MarketContextProvider::new(Arc::new(MockPlatform::default()));
+    assert!(provider.capture("EURUSD").await.unwrap().news.is_none());
+
+    provider.set_news_events(vec![
+        news("USD", 90),
+        news("GBP", 5),
+        news("EUR", -30),
+        news("EUR", 600),
+    ]);
+
+    // Clones share the calendar news protection keeps up to date
+    let nearest = provider
+        .clone()
+        .capture("EURUSD")
+        .await
+        .unwrap()
+        .news
+        .unwrap();
+    assert_eq!(nearest.event.currency, "EUR");
+    assert_eq!(nearest.minutes_until, -29);
+
+    provider.set_news_events(vec![news("USD", 90)]);
+    let nearest = provider.capture("EURUSD").await.unwrap().news.unwrap();
+    assert_eq!(
+        (nearest.event.currency.as_str(), nearest.minutes_until),
+        ("USD", 90)
+    );
+    assert!(provider.capture("GBPJPY").await.unwrap().news.is_none());
+}
+
+#[tokio::test]
+async fn exit_audit_entries_carry_the_captured_context() {
+    let position = Position {
+        id: Uuid::new_v4(),
+        order_id: "order-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        position_type: UnifiedPositionSide::Long,
+        volume: dec!(10000),
+        entry_price: dec!(1.1000),
+        current_price: dec!(1.1050),
+        stop_loss: Some(dec!(1.0950)),
+        take_profit: None,
+        unrealized_pnl: dec!(50.0),
+        swap: dec!(0.0),
+        commission: dec!(0.0),
+        open_time: Utc::now(),
+        magic_number: None,
+        comment: None,
+    };
+    let platform = Arc::new(MockPlatform {
+        positions: Mutex::new(vec![position.clone()]),
+    });
+    let logger = Arc::new(ExitAuditLogger::new());
+    let candles = candles();
+    let system = ExitManagementSystem::new(platform, logger.clone())
+        .with_candles(candles.clone())
+        .with_market_context(config());
+
+    system
+        .get_break_even_manager()
+        .force_break_even(position.id)
+        .await
+        .unwrap();
+
+    let entries = logger
+        .get_exits_by_type(ExitModificationType::BreakEven, None)
+        .await
+        .unwrap();
+    let context = &entries[0].market_context;
+    assert_eq!(
+        context.atr_14,
+        candles.atr("EURUSD", Timeframe::M1, 3).unwrap()
+    );
+    assert_eq!(context.spread, dec!(0.0002));
+    assert_eq!(context.recent_range, Some(dec!(0.0060)));
+    assert!(context.volatility > 0.0);
+}
@@ -0,0 +1,231 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::market_data_hub::MARKET_DATA_HUB_DROPPED_QUOTES_TOTAL;
+use execution_engine::platforms::abstraction::quota::with_caller;
+use execution_engine::platforms::abstraction::{
+    ConsumerOptions, MarketDataHub, MarketDataHubConfig,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+fn hub(config: MarketDataHubConfig) -> (Arc<MockTradingPlatform>, MarketDataHub) {
+    hub_of("acc-1", config)
+}
+
+fn hub_of(
+    account_id: &str,
+    config: MarketDataHubConfig,
+) -> (Arc<MockTradingPlatform>, MarketDataHub) {
+    let mock = Arc::new(MockTradingPlatform::new(account_id));
+    let hub = MarketDataHub::new(mock.clone(), account_id, config);
+    (mock, hub)
+}
+
+fn consumer(name: &str, options: ConsumerOptions) -> MarketDataHubConfig {
+    MarketDataHubConfig {
+        consumers: [(name.to_string(), options)].into_iter().collect(),
+        ..MarketDataHubConfig::default()
+    }
+}
+
+fn dropped(account_id: &str, consumer: &str, reason: &str) -> u64 {
+    MARKET_DATA_HUB_DROPPED_QUOTES_TOTAL
+        .with_label_values(&[account_id, consumer, reason])
+        .get()
+}
+
+fn push_quotes(mock: &MockTradingPlatform, count: u32) {
+    for pips in 0..count {
+        let bid = dec!(1.1000) + Decimal::from(pips) * dec!(0.0001);
+        mock.set_quote("EURUSD", bid, bid + dec!(0.0002));
+    }
+}
+
+fn symbols(symbols: &[&str]) -> Vec<String> {
+    symbols.iter().map(|s| s.to_string()).collect()
+}
+
+/// Let the hub's tasks hand quotes over and notice dropped consumers
+async fn settle() {
+    tokio::time::sleep(Duration::from_millis(20)).await;
+}
+
+#[tokio::test]
+async fn consumers_share_one_upstream_subscription() {
+    let (mock, hub) = hub(MarketDataHubConfig::default());
+
+    let mut candles = with_caller("candles", hub.subscribe_market_data(symbols(&["EURUSD"])))
+        .await
+        .unwrap();
+    let mut recording = with_caller(
+        "recording",
+        hub.subscribe_market_data(symbols(&["EURUSD", "GBPUSD"])),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        mock.market_data_subscriptions(),
+        [symbols(&["EURUSD"]), symbols(&["GBPUSD"])]
+    );
+
+    mock.set_quote("EURUSD", dec!(1.1000), dec!(1.1002));
+    mock.set_quote("GBPUSD", dec!(1.2500), dec!(1.2502));
+    assert_eq!(candles.recv().await.unwrap().bid, dec!(1.1000));
+    assert_eq!(recording.recv().await.unwrap().symbol, "EURUSD");
+    assert_eq!(recording.recv().await.unwrap().symbol, "GBPUSD");
+    settle().await;
+    assert!(candles.try_recv().is_err());
+    assert_eq!(hub.subscriptions()["EURUSD"], 2);
+}
+
+#[tokio::test]
+async fn conflating_consumers_get_the_latest_quote_only() {
+    let (mock, hub) = hub_of(
+        "acc-conflate",
+        consumer(
+            "dashboard",
+            ConsumerOptions {
+                conflate: true,
+                ..ConsumerOptions::default()
+            },
+        ),
+    );
+    let mut dashboard = with_caller("dashboard", hub.subscribe_market_data(symbols(&["EURUSD"])))
+        .await
+        .unwrap();
+    let mut exits = with_caller("exits", hub.subscribe_market_data(symbols(&["EURUSD"])))
+        .await
+        .unwrap();
+
+    push_quotes(&mock, 5);
+    settle().await;
+
+    assert_eq!(dashboard.recv().await.unwrap().bid, dec!(1.1004));
+    assert!(dashboard.try_recv().is_err());
+    assert_eq!(dropped("acc-conflate", "dashboard", "conflated"), 4);
+    assert_eq!(dropped("acc-conflate", "exits", "conflated"), 0);
+    for pips in 0..5 {
+        let bid = dec!(1.1000) + Decimal::from(pips) * dec!(0.0001);
+        assert_eq!(exits.recv().await.unwrap().bid, bid);
+    }
+}
+
+#[tokio::test]
+async fn symbols_stay_subscribed_while_a_consumer_is_left() {
+    let (_mock, hub) = hub(MarketDataHubConfig::default());
+    let candles = with_caller("candles", hub.subscribe_market_data(symbols(&["EURUSD"])))
+        .await
+        .unwrap();
+    let recording = with_caller("recording", hub.subscribe_market_data(symbols(&["EURUSD"])))
+        .await
+        .unwrap();
+
+    drop(candles);
+    settle().await;
+    hub.unsubscribe_market_data(symbols(&["EURUSD"]))
+        .await
+        .unwrap();
+    assert_eq!(hub.subscriptions()["EURUSD"], 1);
+
+    drop(recording);
+    settle().await;
+    hub.unsubscribe_market_data(symbols(&["EURUSD"]))
+        .await
+        .unwrap();
+    assert!(hub.subscriptions().is_empty());
+}
+
+#[tokio::test]
+async fn conflation_intervals_pace_the_latest_quote() {
+    let (mock, hub) = hub_of(
+        "acc-interval",
+        consumer(
+            "websocket",
+            ConsumerOptions {
+                conflate: true,
+                conflation_interval_ms: 100,
+                ..ConsumerOptions::default()
+            },
+        ),
+    );
+    let mut websocket = with_caller("websocket", hub.subscribe_market_data(symbols(&["EURUSD"])))
+        .await
+        .unwrap();
+
+    // The first interval ticks straight away
+    push_quotes(&mock, 1);
+    assert_eq!(websocket.recv().await.unwrap().bid, dec!(1.1000));
+
+    // Everything within the next interval collapses into its last quote
+    push_quotes(&mock, 3);
+    settle().await;
+    assert!(websocket.try_recv().is_err());
+    assert_eq!(websocket.recv().await.unwrap().bid, dec!(1.1002));
+    assert_eq!(dropped("acc-interval", "websocket", "conflated"), 2);
+}
+
+#[tokio::test]
+async fn throttled_consumers_lose_the_excess_ticks() {
+    let (mock, hub) = hub_of(
+        "acc-throttle",
+        consumer(
+            "recording",
+            ConsumerOptions {
+                max_quotes_per_sec: 2,
+                ..ConsumerOptions::default()
+            },
+        ),
+    );
+    let mut recording = with_caller("recording", hub.subscribe_market_data(symbols(&["EURUSD"])))
+        .await
+        .unwrap();
+
+    push_quotes(&mock, 5);
+    settle().await;
+
+    assert_eq!(recording.recv().await.unwrap().bid, dec!(1.1000));
+    assert_eq!(recording.recv().await.unwrap().bid, dec!(1.1001));
+    assert!(recording.try_recv().is_err());
+    assert_eq!(dropped("acc-throttle", "recording", "throttled"), 3);
+}
+
+#[tokio::test]
+async fn consumers_falling_behind_the_hub_are_counted() {
+    let (mock, hub) = hub_of(
+        "acc-lagged",
+        MarketDataHubConfig {
+            channel_capacity: 2,
+            ..MarketDataHubConfig::default()
+        },
+    );
+    let mut candles = with_caller("candles", hub.subscribe_market_data(symbols(&["EURUSD"])))
+        .await
+        .unwrap();
+
+    // Nobody reads, so the consumer's queue and then the hub's fill up
+    push_quotes(&mock, 10);
+    settle().await;
+    while candles.try_recv().is_ok() {}
+    push_quotes(&mock, 1);
+    settle().await;
+
+    assert!(dropped("acc-lagged", "candles", "lagged") > 0);
+}
+
+#[test]
+fn conflation_intervals_need_conflation() {
+    let config = consumer(
+        "websocket",
+        ConsumerOptions {
+            conflation_interval_ms: 100,
+            ..ConsumerOptions::default()
+        },
+    );
+    assert_eq!(
+        config.validate().unwrap_err(),
+        "Market data consumer websocket has a conflation interval without conflate"
+    );
+}
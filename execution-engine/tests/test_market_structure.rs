@@ -0,0 +1,270 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::{Arc, Mutex};
+
+use execution_engine::execution::exit_management::{
+    BreakEvenConfig, BreakEvenManager, ClosePositionRequest, ClosePositionResult, ExitAuditLogger,
+    ExitModificationType, MarketContextProvider, MarketData, OrderModifyRequest, OrderModifyResult,
+    PartialCloseRequest, PartialProfitManager, Position, ProfitTakingConfig, TradingPlatform,
+};
+use execution_engine::market_analysis::{MarketStructure, StructureAnalyzer, StructureConfig};
+use execution_engine::market_data::{Candle, CandleBuilder, CandleConfig, Timeframe};
+use execution_engine::testing::fixtures;
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, 3, 9, 0, 0).unwrap()
+}
+
+/// Swing highs at 1.1050 and 1.1052, swing lows at 1.0990 and 1.1005
+const RANGES: [(Decimal, Decimal); 7] = [
+    (dec!(1.1010), dec!(1.1000)),
+    (dec!(1.1050), dec!(1.1020)),
+    (dec!(1.1030), dec!(1.0990)),
+    (dec!(1.1040), dec!(1.1000)),
+    (dec!(1.1052), dec!(1.1010)),
+    (dec!(1.1030), dec!(1.1005)),
+    (dec!(1.1020), dec!(1.1010)),
+];
+
+fn config() -> StructureConfig {
+    StructureConfig {
+        timeframe: Timeframe::M1,
+        strength: 1,
+        atr_period: 3,
+        ..StructureConfig::default()
+    }
+}
+
+fn candles() -> Arc<CandleBuilder> {
+    let builder = CandleBuilder::new(CandleConfig {
+        timeframes: vec![Timeframe::M1],
+        ..CandleConfig::default()
+    });
+    for (minute, (high, low)) in RANGES.iter().enumerate() {
+        let open = start() + Duration::minutes(minute as i64);
+        let mid = (high + low) / Decimal::TWO;
+        builder.on_tick("EURUSD", mid, Decimal::ZERO, open);
+        builder.on_tick("EURUSD", *high, Decimal::ZERO, open + Duration::seconds(10));
+        builder.on_tick("EURUSD", *low, Decimal::ZERO, open + Duration::seconds(20));
+        builder.on_tick("EURUSD", mid, Decimal::ZERO, open + Duration::seconds(30));
+    }
+    builder.close_elapsed(start() + Duration::minutes(RANGES.len() as i64));
+    Arc::new(builder)
+}
+
+/// Serves a fixed mid price and applies order modifications to its positions
+#[derive(Debug)]
+struct MockPlatform {
+    positions: Mutex<Vec<Position>>,
+    modifications: Mutex<Vec<(Option<Decimal>, Option<Decimal>)>>,
+    mid: Decimal,
+}
+
+impl MockPlatform {
+    fn new(position: Position, mid: Decimal) -> Arc<Self> {
+        Arc::new(Self {
+            positions: Mutex::new(vec![position]),
+            modifications: Mutex::new(Vec::new()),
+            mid,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: self.mid - dec!(0.00005),
+            ask: self.mid + dec!(0.00005),
+            spread: dec!(0.0001),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        self.modifications
+            .lock()
+            .unwrap()
+            .push((request.new_stop_loss, request.new_take_profit));
+        for position in self.positions.lock().unwrap().iter_mut() {
+            if position.order_id == request.order_id {
+                position.stop_loss = request.new_stop_loss;
+                position.take_profit = request.new_take_profit;
+            }
+        }
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: true,
+            message: "modified".to_string(),
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        Err(anyhow!("unexpected close of {}", request.position_id))
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        Err(anyhow!(
+            "unexpected partial close of {}",
+            request.position_id
+        ))
+    }
+}
+
+fn long_position(stop_loss: Decimal, take_profit: Option<Decimal>) -> Position {
+    Position {
+        current_price: dec!(1.1030),
+        stop_loss: Some(stop_loss),
+        take_profit,
+        unrealized_pnl: dec!(30.0),
+        ..fixtures::long_position()
+    }
+}
+
+#[test]
+fn nearby_swings_merge_into_zones_around_the_price() {
+    let closed: Vec<Candle> = candles().candles("EURUSD", Timeframe::M1, 100);
+
+    // 0.25 ATR of 10 pips merges the two highs two pips apart
+    let structure = MarketStructure::from_candles(
+        "EURUSD",
+        &closed,
+        dec!(1.1030),
+        Some(dec!(0.0010)),
+        &config(),
+    )
+    .unwrap();
+    assert_eq!(structure.swings.len(), 4);
+    let zones: Vec<_> = structure
+        .zones
+        .iter()
+        .map(|z| (z.lower, z.upper, z.touches))
+        .collect();
+    assert_eq!(
+        zones,
+        vec![
+            (dec!(1.0990), dec!(1.0990), 1),
+            (dec!(1.1005), dec!(1.1005), 1),
+            (dec!(1.1050), dec!(1.1052), 2),
+        ]
+    );
+    assert_eq!(structure.support.as_ref().unwrap().lower, dec!(1.1005));
+    assert_eq!(structure.resistance.as_ref().unwrap().upper, dec!(1.1052));
+    assert_eq!(
+        structure
+            .zones_between(dec!(1.1000), dec!(1.1030))
+            .map(|z| z.lower)
+            .collect::<Vec<_>>(),
+        vec![dec!(1.1005)]
+    );
+
+    // Single-swing zones drop out once zones need two touches
+    let strict = MarketStructure::from_candles(
+        "EURUSD",
+        &closed,
+        dec!(1.1030),
+        Some(dec!(0.0010)),
+        &StructureConfig {
+            min_touches: 2,
+            ..config()
+        },
+    )
+    .unwrap();
+    assert_eq!(strict.zones.len(), 1);
+    assert!(strict.support.is_none());
+
+    // Too few candles for a swing on either side
+    assert!(
+        MarketStructure::from_candles("EURUSD", &closed[..2], dec!(1.1030), None, &config())
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn break_even_triggers_once_price_clears_a_zone_above_entry() {
+    // 30 pips of profit against 100 pips of risk is short of the 1:1 trigger
+    let analyzer = Arc::new(StructureAnalyzer::new(candles(), config()));
+    let platform = MockPlatform::new(long_position(dec!(1.0900), None), dec!(1.1030));
+    let logger = Arc::new(ExitAuditLogger::new());
+    let market_context =
+        MarketContextProvider::new(platform.clone()).with_structure(analyzer.clone());
+    let mut manager = BreakEvenManager::new(platform.clone(), logger.clone())
+        .with_structure(analyzer)
+        .with_market_context(Arc::new(market_context));
+    manager.configure_symbol("EURUSD".to_string(), BreakEvenConfig::default());
+
+    manager.check_break_even_triggers().await.unwrap();
+    assert!(platform.modifications.lock().unwrap().is_empty());
+
+    // The higher low at 1.1005 is structure the stop can now sit behind
+    manager.configure_symbol(
+        "EURUSD".to_string(),
+        BreakEvenConfig {
+            structure_trigger: true,
+            ..Default::default()
+        },
+    );
+    manager.check_break_even_triggers().await.unwrap();
+    assert_eq!(
+        *platform.modifications.lock().unwrap(),
+        vec![(Some(dec!(1.1005)), None)]
+    );
+
+    let entries = logger
+        .get_exits_by_type(ExitModificationType::BreakEven, None)
+        .await
+        .unwrap();
+    let structure = entries[0].market_context.structure.as_ref().unwrap();
+    assert_eq!(structure.price, dec!(1.1030));
+    assert_eq!(structure.resistance.as_ref().unwrap().lower, dec!(1.1050));
+}
+
+#[tokio::test]
+async fn take_profit_is_pulled_in_to_the_nearest_resistance() {
+    let analyzer = Arc::new(StructureAnalyzer::new(candles(), config()));
+    let platform = MockPlatform::new(
+        long_position(dec!(1.0950), Some(dec!(1.1100))),
+        dec!(1.1030),
+    );
+    let logger = Arc::new(ExitAuditLogger::new());
+    let mut manager =
+        PartialProfitManager::new(platform.clone(), logger.clone()).with_structure(analyzer);
+    manager.configure_symbol(
+        "EURUSD".to_string(),
+        ProfitTakingConfig {
+            take_profit_at_structure: true,
+            ..Default::default()
+        },
+    );
+
+    manager.check_profit_targets().await.unwrap();
+    assert_eq!(
+        *platform.modifications.lock().unwrap(),
+        vec![(Some(dec!(1.0950)), Some(dec!(1.1050)))]
+    );
+
+    let entries = logger
+        .get_exits_by_type(ExitModificationType::PartialProfit, None)
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(
+        (entries[0].old_value, entries[0].new_value),
+        (dec!(1.1100), dec!(1.1050))
+    );
+    assert!(entries[0].market_context.structure.is_some());
+
+    // Already at the zone: the take-profit is left alone
+    manager.check_profit_targets().await.unwrap();
+    assert_eq!(platform.modifications.lock().unwrap().len(), 1);
+}
@@ -0,0 +1,196 @@
+use execution_engine::platforms::abstraction::errors::PlatformError;
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::{
+    OrderMetadata, OrderModification, UnifiedOrder, UnifiedOrderSide, UnifiedOrderStatus,
+    UnifiedOrderType, UnifiedPositionSide, UnifiedTimeInForce,
+};
+use execution_engine::testing::{MockTradingPlatform, Operation};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+
+fn order(side: UnifiedOrderSide, order_type: UnifiedOrderType, quantity: Decimal) -> UnifiedOrder {
+    UnifiedOrder {
+        client_order_id: uuid::Uuid::new_v4().to_string(),
+        symbol: "EURUSD".to_string(),
+        side,
+        order_type,
+        quantity,
+        price: None,
+        stop_price: None,
+        stop_loss: Some(dec!(1.0800)),
+        take_profit: None,
+        time_in_force: UnifiedTimeInForce::Gtc,
+        account_id: None,
+        metadata: OrderMetadata {
+            strategy_id: None,
+            signal_id: None,
+            risk_parameters: HashMap::new(),
+            tags: Vec::new(),
+            expires_at: None,
+        },
+        max_slippage: None,
+    }
+}
+
+fn platform() -> MockTradingPlatform {
+    MockTradingPlatform::new("mock").with_quote("EURUSD", dec!(1.0900), dec!(1.0902))
+}
+
+#[tokio::test]
+async fn market_orders_fill_at_the_quote_and_net_into_one_position() {
+    let platform = platform();
+
+    let buy = platform
+        .place_order(order(
+            UnifiedOrderSide::Buy,
+            UnifiedOrderType::Market,
+            dec!(2),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(buy.status, UnifiedOrderStatus::Filled);
+    assert_eq!(buy.average_fill_price, Some(dec!(1.0902)));
+
+    platform.set_quote("EURUSD", dec!(1.0950), dec!(1.0952));
+    platform
+        .place_order(order(
+            UnifiedOrderSide::Sell,
+            UnifiedOrderType::Market,
+            dec!(0.5),
+        ))
+        .await
+        .unwrap();
+
+    let positions = platform.get_positions().await.unwrap();
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].side, UnifiedPositionSide::Long);
+    assert_eq!(positions[0].quantity, dec!(1.5));
+    assert_eq!(positions[0].stop_loss, Some(dec!(1.0800)));
+
+    let account = platform.get_account_info().await.unwrap();
+    assert_eq!(
+        account.realized_pnl,
+        dec!(0.5) * (dec!(1.0950) - dec!(1.0902))
+    );
+}
+
+#[tokio::test]
+async fn limit_orders_rest_until_cancelled() {
+    let platform = platform();
+    let mut limit = order(UnifiedOrderSide::Buy, UnifiedOrderType::Limit, dec!(1));
+    limit.price = Some(dec!(1.0850));
+
+    let response = platform.place_order(limit).await.unwrap();
+    assert_eq!(response.status, UnifiedOrderStatus::New);
+    assert!(platform.positions().is_empty());
+
+    platform
+        .cancel_order(&response.platform_order_id)
+        .await
+        .unwrap();
+    let cancelled = platform
+        .get_order(&response.platform_order_id)
+        .await
+        .unwrap();
+    assert_eq!(cancelled.status, UnifiedOrderStatus::Canceled);
+}
+
+#[tokio::test]
+async fn scripted_errors_fail_the_next_calls_only() {
+    let platform = platform();
+    platform.script_error(
+        Operation::PlaceOrder,
+        PlatformError::RateLimitExceeded { retry_after_ms: 10 },
+    );
+
+    let throttled = platform
+        .place_order(order(
+            UnifiedOrderSide::Buy,
+            UnifiedOrderType::Market,
+            dec!(1),
+        ))
+        .await;
+    assert!(matches!(
+        throttled,
+        Err(PlatformError::RateLimitExceeded { .. })
+    ));
+    assert!(platform
+        .place_order(order(
+            UnifiedOrderSide::Buy,
+            UnifiedOrderType::Market,
+            dec!(1)
+        ))
+        .await
+        .is_ok());
+    assert_eq!(platform.calls(Operation::PlaceOrder), 2);
+}
+
+#[tokio::test]
+async fn failing_operations_fail_until_cleared() {
+    let platform = platform();
+    platform.fail_operation(
+        Operation::GetPositions,
+        PlatformError::NetworkError {
+            reason: "timeout".to_string(),
+        },
+    );
+
+    assert!(platform.get_positions().await.is_err());
+    assert!(platform.get_positions().await.is_err());
+    assert!(platform.ping().await.is_ok());
+    assert!(!platform.health_check().await.unwrap().is_healthy);
+
+    platform.clear_failures();
+    assert!(platform.get_positions().await.is_ok());
+}
+
+#[tokio::test]
+async fn disconnected_platform_rejects_calls_until_connected() {
+    let mut platform = platform().disconnected();
+
+    assert!(matches!(
+        platform.ping().await,
+        Err(PlatformError::Disconnected { .. })
+    ));
+
+    platform.connect().await.unwrap();
+    assert!(platform.ping().await.is_ok());
+    assert_eq!(platform.connection_attempts(), 1);
+}
+
+#[tokio::test]
+async fn modifications_and_closes_are_recorded() {
+    let platform = platform();
+    platform
+        .place_order(order(
+            UnifiedOrderSide::Buy,
+            UnifiedOrderType::Market,
+            dec!(1),
+        ))
+        .await
+        .unwrap();
+    let position_id = platform.positions()[0].position_id.clone();
+
+    platform
+        .modify_order(
+            &position_id,
+            OrderModification {
+                quantity: None,
+                price: None,
+                stop_price: None,
+                take_profit: None,
+                stop_loss: Some(dec!(1.0902)),
+                time_in_force: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(platform.positions()[0].stop_loss, Some(dec!(1.0902)));
+
+    platform.close_position("EURUSD", None).await.unwrap();
+    assert!(platform.positions().is_empty());
+    assert_eq!(platform.modifications().len(), 1);
+    assert_eq!(platform.modifications()[0].0, position_id);
+    assert_eq!(platform.closes(), vec![("EURUSD".to_string(), None)]);
+}
@@ -0,0 +1,286 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use execution_engine::platforms::abstraction::{
+    capabilities::PlatformCapabilities,
+    errors::PlatformError,
+    events::PlatformEvent,
+    interfaces::{DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter},
+    models::*,
+    multi_account::MultiAccountManager,
+};
+use execution_engine::platforms::PlatformType;
+
+struct MockPlatform {
+    platform_type: PlatformType,
+    healthy: bool,
+    equity: Decimal,
+    positions: Vec<UnifiedPosition>,
+}
+
+impl MockPlatform {
+    fn new(platform_type: PlatformType, equity: Decimal) -> Self {
+        Self {
+            platform_type,
+            healthy: true,
+            equity,
+            positions: Vec::new(),
+        }
+    }
+
+    fn with_position(mut self, symbol: &str, side: UnifiedPositionSide, quantity: Decimal) -> Self {
+        self.positions.push(UnifiedPosition {
+            position_id: format!("{}-{}", symbol, self.positions.len()),
+            symbol: symbol.to_string(),
+            side,
+            quantity,
+            entry_price: dec!(1.0),
+            current_price: dec!(1.0),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_used: Decimal::ZERO,
+            commission: Decimal::ZERO,
+            stop_loss: None,
+            take_profit: None,
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            account_id: "mock".to_string(),
+            platform_specific: HashMap::new(),
+        });
+        self
+    }
+
+    fn unhealthy(mut self) -> Self {
+        self.healthy = false;
+        self
+    }
+
+    fn unsupported<T>() -> Result<T, PlatformError> {
+        Err(PlatformError::FeatureNotSupported {
+            feature: "mock".to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl ITradingPlatform for MockPlatform {
+    fn platform_type(&self) -> PlatformType {
+        self.platform_type.clone()
+    }
+    fn platform_name(&self) -> &str {
+        "mock"
+    }
+    fn platform_version(&self) -> &str {
+        "1.0.0"
+    }
+    async fn connect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn disconnect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn is_connected(&self) -> bool {
+        self.healthy
+    }
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        Ok(1)
+    }
+    async fn place_order(
+        &self,
+        _order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn modify_order(
+        &self,
+        _order_id: &str,
+        _modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn cancel_order(&self, _order_id: &str) -> Result<(), PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_order(&self, _order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_orders(
+        &self,
+        _filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        Ok(self.positions.clone())
+    }
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        Ok(self.positions.iter().find(|p| p.symbol == symbol).cloned())
+    }
+    async fn close_position(
+        &self,
+        _symbol: &str,
+        _quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        Ok(UnifiedAccountInfo {
+            account_id: "mock".to_string(),
+            account_name: None,
+            currency: "USD".to_string(),
+            balance: self.equity,
+            equity: self.equity,
+            margin_used: dec!(100),
+            margin_available: self.equity - dec!(100),
+            buying_power: self.equity,
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_level: None,
+            account_type: AccountType::Demo,
+            last_updated: Utc::now(),
+            platform_specific: HashMap::new(),
+        })
+    }
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        Ok(self.equity)
+    }
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_market_data(&self, _symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        Self::unsupported()
+    }
+    async fn subscribe_market_data(
+        &self,
+        _symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn unsubscribe_market_data(&self, _symbols: Vec<String>) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    fn capabilities(&self) -> PlatformCapabilities {
+        PlatformCapabilities::new("mock".to_string())
+    }
+    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_event_history(
+        &self,
+        _filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        Ok(HealthStatus {
+            is_healthy: self.healthy,
+            last_ping: Some(Utc::now()),
+            latency_ms: Some(1),
+            error_rate: 0.0,
+            uptime_seconds: 60,
+            issues: Vec::new(),
+        })
+    }
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        Self::unsupported()
+    }
+}
+
+#[tokio::test]
+async fn test_aggregates_metrics_across_platforms() {
+    let manager = MultiAccountManager::new();
+    manager
+        .add_account(
+            "tl-1",
+            Arc::new(
+                MockPlatform::new(PlatformType::TradeLocker, dec!(10000)).with_position(
+                    "EURUSD",
+                    UnifiedPositionSide::Long,
+                    dec!(1000),
+                ),
+            ),
+        )
+        .await
+        .unwrap();
+    manager
+        .add_account(
+            "dx-1",
+            Arc::new(
+                MockPlatform::new(PlatformType::DXTrade, dec!(5000)).with_position(
+                    "EURUSD",
+                    UnifiedPositionSide::Short,
+                    dec!(400),
+                ),
+            ),
+        )
+        .await
+        .unwrap();
+
+    let metrics = manager.get_aggregated_metrics().await;
+
+    assert_eq!(metrics.active_accounts, 2);
+    assert_eq!(metrics.total_equity, dec!(15000));
+    assert_eq!(metrics.total_margin_used, dec!(200));
+    assert_eq!(metrics.total_positions, 2);
+    assert_eq!(metrics.gross_exposure, dec!(1400));
+    assert_eq!(metrics.net_exposure_by_symbol["EURUSD"], dec!(600));
+    assert_eq!(metrics.accounts_by_platform.len(), 2);
+}
+
+#[tokio::test]
+async fn test_inactive_accounts_are_excluded() {
+    let manager = MultiAccountManager::new();
+    manager
+        .add_account(
+            "tl-1",
+            Arc::new(MockPlatform::new(PlatformType::TradeLocker, dec!(10000))),
+        )
+        .await
+        .unwrap();
+    manager.deactivate_account("tl-1").await.unwrap();
+
+    assert!(manager.get_platform("tl-1").await.is_err());
+    assert_eq!(manager.get_aggregated_metrics().await.active_accounts, 0);
+
+    manager.activate_account("tl-1").await.unwrap();
+    assert!(manager.get_platform("tl-1").await.is_ok());
+}
+
+#[tokio::test]
+async fn test_per_account_health() {
+    let manager = MultiAccountManager::new();
+    manager
+        .add_account(
+            "ok",
+            Arc::new(MockPlatform::new(PlatformType::TradeLocker, dec!(1000))),
+        )
+        .await
+        .unwrap();
+    manager
+        .add_account(
+            "bad",
+            Arc::new(MockPlatform::new(PlatformType::DXTrade, dec!(1000)).unhealthy()),
+        )
+        .await
+        .unwrap();
+
+    let health = manager.check_all_health().await;
+
+    assert!(health["ok"].is_healthy());
+    assert!(!health["bad"].is_healthy());
+    assert!(manager.get_account_health("bad").await.is_some());
+}
+
+#[tokio::test]
+async fn test_duplicate_active_account_rejected() {
+    let manager = MultiAccountManager::new();
+    let platform = Arc::new(MockPlatform::new(PlatformType::TradeLocker, dec!(1000)));
+
+    manager.add_account("a", platform.clone()).await.unwrap();
+    assert!(manager.add_account("a", platform).await.is_err());
+}
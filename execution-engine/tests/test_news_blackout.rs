@@ -0,0 +1,120 @@
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::execution::exit_management::{ImpactLevel, NewsEvent};
+use execution_engine::execution::news_blackout::NEWS_BLACKOUT_ACTION;
+use execution_engine::execution::{
+    BlackoutWindow, BlackoutWindows, NewsBlackoutConfig, NewsCalendar, TradeExecutionOrchestrator,
+    TradeSignal,
+};
+use execution_engine::testing::{fixtures, MockTradingPlatform};
+
+fn enabled() -> NewsBlackoutConfig {
+    NewsBlackoutConfig {
+        enabled: true,
+        ..NewsBlackoutConfig::default()
+    }
+}
+
+fn event(currency: &str, impact: ImpactLevel, in_mins: i64) -> NewsEvent {
+    NewsEvent {
+        id: format!("{}-{:?}", currency, impact),
+        description: "Non-Farm Payrolls".to_string(),
+        currency: currency.to_string(),
+        impact,
+        time: Utc::now() + Duration::minutes(in_mins),
+    }
+}
+
+fn signal(id: &str, symbol: &str, strategy: Option<&str>) -> TradeSignal {
+    TradeSignal {
+        symbol: symbol.to_string(),
+        metadata: strategy
+            .map(|s| HashMap::from([("strategy".to_string(), s.to_string())]))
+            .unwrap_or_default(),
+        ..fixtures::signal(id)
+    }
+}
+
+#[test]
+fn blackouts_cover_the_symbol_legs_within_the_impact_window() {
+    let calendar = NewsCalendar::new();
+    let windows = enabled().windows;
+    let now = Utc::now();
+
+    calendar.set_events(vec![event("USD", ImpactLevel::High, 10)]);
+    let blackout = calendar.blackout("EURUSD", &windows, now).unwrap();
+    assert_eq!(
+        blackout.ends_at(),
+        blackout.event.time + Duration::minutes(15)
+    );
+    assert!(calendar.blackout("XAUUSD", &windows, now).is_some());
+    assert!(calendar.blackout("EURGBP", &windows, now).is_none());
+    // Further out than the 30 minutes before a high impact release
+    assert!(calendar
+        .blackout("EURUSD", &windows, now - Duration::minutes(25))
+        .is_none());
+
+    // Medium impact releases have no window by default
+    calendar.set_events(vec![event("USD", ImpactLevel::Medium, 5)]);
+    assert!(calendar.blackout("EURUSD", &windows, now).is_none());
+
+    let mut config = enabled();
+    config.windows.medium = Some(BlackoutWindow {
+        before_mins: 0,
+        after_mins: 0,
+    });
+    assert!(config.validate().is_err());
+}
+
+#[tokio::test]
+async fn entries_are_refused_around_releases_unless_the_strategy_overrides() {
+    let calendar = NewsCalendar::new();
+    calendar.set_events(vec![event("USD", ImpactLevel::High, 10)]);
+    let mut config = enabled();
+    config
+        .strategy_overrides
+        .insert("news_fade".to_string(), BlackoutWindows::default());
+
+    let orchestrator =
+        TradeExecutionOrchestrator::new().with_news_blackout(config, calendar.clone());
+    orchestrator
+        .register_account(
+            "acc-1".to_string(),
+            Arc::new(MockTradingPlatform::new("acc-1")),
+            100000.0,
+        )
+        .await
+        .unwrap();
+
+    let refused = orchestrator
+        .process_signal(signal("sig-1", "EURUSD", Some("breakout")))
+        .await
+        .unwrap_err();
+    assert!(refused.starts_with("Entries on EURUSD are blacked out"));
+    assert!(refused.contains("Non-Farm Payrolls"));
+
+    let history = orchestrator.get_execution_history(10).await;
+    let entry = history
+        .iter()
+        .find(|e| e.action == NEWS_BLACKOUT_ACTION)
+        .unwrap();
+    assert_eq!(entry.signal_id, "sig-1");
+    assert_eq!(entry.tags, vec!["strategy:breakout".to_string()]);
+
+    assert!(orchestrator
+        .process_signal(signal("sig-2", "EURUSD", Some("news_fade")))
+        .await
+        .is_ok());
+    assert!(orchestrator
+        .process_signal(signal("sig-3", "EURGBP", None))
+        .await
+        .is_ok());
+
+    calendar.set_events(vec![event("USD", ImpactLevel::High, 90)]);
+    assert!(orchestrator
+        .process_signal(signal("sig-4", "EURUSD", None))
+        .await
+        .is_ok());
+}
@@ -0,0 +1,371 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use execution_engine::alerting::{
+    Alert, AlertGateway, AlertRoute, AlertingConfig, NOTIFICATIONS_SINK,
+};
+use execution_engine::execution::EmergencyCloseResult;
+use execution_engine::notifications::{
+    ChannelRules, ChannelTarget, Notification, NotificationChannel, NotificationTopic, Notifier,
+    QuietHours,
+};
+use execution_engine::platforms::abstraction::events::{
+    EventData, EventType, OrderEventData, PlatformEvent, RiskEventData, RiskSeverity, RiskType,
+};
+use execution_engine::platforms::abstraction::models::*;
+use execution_engine::platforms::abstraction::UnifiedEventBus;
+use execution_engine::platforms::PlatformType;
+use execution_engine::runtime::EngineConfig;
+use risk_types::AlertLevel;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[derive(Default)]
+struct RecordingChannel(Mutex<Vec<Notification>>);
+
+impl RecordingChannel {
+    fn sent(&self) -> Vec<Notification> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for RecordingChannel {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        self.0.lock().unwrap().push(notification.clone());
+        Ok(())
+    }
+}
+
+fn quiet_nights(timezone: &str) -> QuietHours {
+    QuietHours {
+        start: "22:00:00".parse().unwrap(),
+        end: "07:00:00".parse().unwrap(),
+        timezone: timezone.parse().unwrap(),
+        allow: vec![NotificationTopic::EmergencyClose],
+    }
+}
+
+fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, month, day, hour, minute, 0)
+        .unwrap()
+}
+
+fn filled_order() -> UnifiedOrderResponse {
+    UnifiedOrderResponse {
+        platform_order_id: "ord-1".to_string(),
+        client_order_id: "client-1".to_string(),
+        status: UnifiedOrderStatus::Filled,
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Buy,
+        order_type: UnifiedOrderType::Market,
+        quantity: dec!(10000),
+        filled_quantity: dec!(10000),
+        remaining_quantity: dec!(0),
+        price: None,
+        average_fill_price: Some(dec!(1.0850)),
+        commission: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        filled_at: Some(Utc::now()),
+        platform_specific: HashMap::new(),
+    }
+}
+
+fn fill_event() -> PlatformEvent {
+    PlatformEvent::new(
+        EventType::OrderFilled,
+        PlatformType::TradeLocker,
+        "acc-1".to_string(),
+        EventData::Order(OrderEventData {
+            order: filled_order(),
+            previous_status: Some(UnifiedOrderStatus::New),
+            fill_price: None,
+            fill_quantity: None,
+            remaining_quantity: None,
+            rejection_reason: None,
+        }),
+    )
+}
+
+fn risk_event(event_type: EventType, risk_type: RiskType) -> PlatformEvent {
+    PlatformEvent::new(
+        event_type,
+        PlatformType::TradeLocker,
+        "acc-1".to_string(),
+        EventData::Risk(RiskEventData {
+            risk_type,
+            current_value: dec!(95),
+            limit_value: dec!(100),
+            severity: RiskSeverity::Emergency,
+            affected_positions: vec!["pos-1".to_string()],
+        }),
+    )
+}
+
+#[test]
+fn quiet_hours_span_midnight_in_the_channel_timezone() {
+    let quiet = quiet_nights("America/New_York");
+
+    // 22:30 New York is 02:30 UTC in summer and 03:30 UTC in winter
+    assert!(quiet.contains(utc(7, 1, 2, 30)));
+    assert!(quiet.contains(utc(1, 15, 3, 30)));
+    // 21:30 New York in winter is 02:30 UTC, before quiet hours start
+    assert!(!quiet.contains(utc(1, 15, 2, 30)));
+    // 06:59 and 07:00 New York in summer
+    assert!(quiet.contains(utc(7, 1, 10, 59)));
+    assert!(!quiet.contains(utc(7, 1, 11, 0)));
+}
+
+#[test]
+fn channel_rules_filter_topics_and_quiet_hours() {
+    let rules = ChannelRules {
+        topics: vec![NotificationTopic::Fill, NotificationTopic::EmergencyClose],
+        quiet_hours: Some(quiet_nights("UTC")),
+    };
+    let fill = |at| Notification::new(NotificationTopic::Fill, "Order filled", "").at(at);
+
+    assert!(rules.accepts(&fill(utc(3, 3, 12, 0))));
+    assert!(!rules.accepts(&fill(utc(3, 3, 23, 0))));
+    assert!(rules.accepts(
+        &Notification::new(NotificationTopic::EmergencyClose, "Emergency close", "")
+            .at(utc(3, 3, 23, 0))
+    ));
+    assert!(!rules.accepts(
+        &Notification::new(NotificationTopic::DailySummary, "Daily summary", "")
+            .at(utc(3, 3, 12, 0))
+    ));
+}
+
+#[test]
+fn platform_events_are_formatted_by_topic() {
+    let fill = Notification::from_platform_event(&fill_event()).unwrap();
+    assert_eq!(fill.topic, NotificationTopic::Fill);
+    assert_eq!(
+        fill.text(),
+        "Order filled [acc-1]\nBUY 10000 EURUSD @ 1.0850"
+    );
+
+    let margin_call = Notification::from_platform_event(&risk_event(
+        EventType::MarginCallTriggered,
+        RiskType::MarginLevel,
+    ))
+    .unwrap();
+    assert_eq!(margin_call.topic, NotificationTopic::MarginAlert);
+    assert_eq!(margin_call.title, "Margin call");
+    assert!(margin_call.body.contains("Affected positions: pos-1"));
+
+    let daily_loss = Notification::from_platform_event(&risk_event(
+        EventType::RiskLimitBreached,
+        RiskType::DailyLoss,
+    ))
+    .unwrap();
+    assert_eq!(daily_loss.topic, NotificationTopic::RiskAlert);
+
+    let heartbeat = PlatformEvent::new(
+        EventType::Heartbeat,
+        PlatformType::TradeLocker,
+        "acc-1".to_string(),
+        fill_event().data,
+    );
+    assert!(Notification::from_platform_event(&heartbeat).is_none());
+}
+
+#[test]
+fn emergency_close_lists_failures() {
+    let results = vec![
+        EmergencyCloseResult {
+            account_id: "acc-1".to_string(),
+            symbol: "EURUSD".to_string(),
+            success: true,
+            order_id: Some("ord-1".to_string()),
+            error_message: None,
+        },
+        EmergencyCloseResult {
+            account_id: "acc-1".to_string(),
+            symbol: "GBPUSD".to_string(),
+            success: false,
+            order_id: None,
+            error_message: Some("market closed".to_string()),
+        },
+    ];
+
+    let notification = Notification::emergency_close(Some("acc-1"), "admin API", &results);
+    assert_eq!(notification.topic, NotificationTopic::EmergencyClose);
+    assert_eq!(
+        notification.body,
+        "admin API: 1 of 2 positions closed\nFailed acc-1 GBPUSD: market closed"
+    );
+}
+
+#[tokio::test]
+async fn notifier_sends_only_to_channels_accepting_the_topic() {
+    let fills = Arc::new(RecordingChannel::default());
+    let risk = Arc::new(RecordingChannel::default());
+    let notifier = Notifier::new()
+        .with_channel(
+            "fills",
+            ChannelRules {
+                topics: vec![NotificationTopic::Fill],
+                quiet_hours: None,
+            },
+            fills.clone(),
+        )
+        .with_channel(
+            "risk",
+            ChannelRules {
+                topics: vec![NotificationTopic::MarginAlert],
+                quiet_hours: None,
+            },
+            risk.clone(),
+        );
+
+    notifier.handle_platform_event(&fill_event()).await;
+    notifier
+        .handle_platform_event(&risk_event(
+            EventType::StopOutTriggered,
+            RiskType::MarginLevel,
+        ))
+        .await;
+
+    assert_eq!(fills.sent().len(), 1);
+    assert_eq!(risk.sent().len(), 1);
+    assert_eq!(risk.sent()[0].title, "Stop out");
+}
+
+#[tokio::test]
+async fn notifier_follows_the_event_bus() {
+    let channel = Arc::new(RecordingChannel::default());
+    let notifier =
+        Arc::new(Notifier::new().with_channel("all", ChannelRules::default(), channel.clone()));
+    let mut bus = UnifiedEventBus::new();
+    let subscription = notifier.subscribe(&mut bus);
+
+    bus.publish(fill_event()).await;
+    drop(bus);
+    subscription.await.unwrap().unwrap();
+
+    assert_eq!(channel.sent().len(), 1);
+}
+
+#[tokio::test]
+async fn gateway_alerts_reach_the_notifier() {
+    let channel = Arc::new(RecordingChannel::default());
+    let notifier = Arc::new(Notifier::new().with_channel(
+        "risk",
+        ChannelRules {
+            topics: vec![NotificationTopic::MarginAlert],
+            quiet_hours: None,
+        },
+        channel.clone(),
+    ));
+    let config = AlertingConfig {
+        routes: HashMap::from([(
+            "margin".to_string(),
+            AlertRoute {
+                sinks: vec![NOTIFICATIONS_SINK.to_string()],
+                ..AlertRoute::default()
+            },
+        )]),
+        ..AlertingConfig::default()
+    };
+    assert!(config.validate().is_ok());
+    let gateway = AlertGateway::new(config).with_sink(NOTIFICATIONS_SINK, notifier);
+
+    let account_id = Uuid::new_v4();
+    gateway
+        .submit(Alert {
+            alert_type: "margin".to_string(),
+            key: account_id.to_string(),
+            severity: AlertLevel::Critical,
+            account_id: Some(account_id),
+            message: "Margin level at 115.00%".to_string(),
+            raised_at: Utc::now(),
+        })
+        .await;
+
+    let sent = channel.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].title, "[Critical] margin alert");
+    assert_eq!(sent[0].account_id, Some(account_id.to_string()));
+}
+
+#[tokio::test]
+async fn telegram_and_discord_channels_post_messages() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/bot123:abc/sendMessage"))
+        .and(body_partial_json(serde_json::json!({
+            "chat_id": "-1001",
+            "text": "Order filled [acc-1]\nBUY 10000 EURUSD @ 1.0850"
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/discord/webhook"))
+        .and(body_partial_json(
+            serde_json::json!({ "username": "engine" }),
+        ))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let telegram = ChannelTarget::Telegram {
+        bot_token: "123:abc".to_string(),
+        chat_id: "-1001".to_string(),
+        api_url: server.uri(),
+    };
+    let discord = ChannelTarget::Discord {
+        webhook_url: format!("{}/discord/webhook", server.uri()),
+        username: Some("engine".to_string()),
+    };
+    let notifier = Notifier::new()
+        .with_channel(
+            "telegram",
+            ChannelRules::default(),
+            telegram.build().unwrap(),
+        )
+        .with_channel("discord", ChannelRules::default(), discord.build().unwrap());
+
+    let notification = Notification::from_platform_event(&fill_event()).unwrap();
+    assert_eq!(notifier.notify(&notification).await, 2);
+}
+
+#[test]
+fn notification_channels_parse_from_toml() {
+    let config: EngineConfig = toml::from_str(
+        r#"
+        [notifications.channels.desk]
+        type = "telegram"
+        bot_token = "123:abc"
+        chat_id = "-1001"
+        topics = ["fill", "emergency_close"]
+
+        [notifications.channels.desk.quiet_hours]
+        start = "22:00"
+        end = "07:00"
+        timezone = "Europe/London"
+
+        [notifications.channels.ops]
+        type = "discord"
+        webhook_url = "https://discord.com/api/webhooks/1/abc"
+        "#,
+    )
+    .unwrap();
+
+    assert!(config.validate().is_ok());
+    let desk = &config.notifications.channels["desk"];
+    assert_eq!(desk.rules.topics.len(), 2);
+    let quiet = desk.rules.quiet_hours.as_ref().unwrap();
+    assert_eq!(quiet.timezone, chrono_tz::Europe::London);
+    assert_eq!(quiet.allow, vec![NotificationTopic::EmergencyClose]);
+    assert!(config.notifications.channels["ops"].rules.topics.is_empty());
+    assert!(Notifier::from_config(&config.notifications).is_ok());
+}
@@ -0,0 +1,313 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use execution_engine::execution::{AccountAssignment, ExecutionPlan, TradeExecutionOrchestrator};
+use execution_engine::platforms::abstraction::{
+    capabilities::PlatformCapabilities,
+    errors::PlatformError,
+    events::PlatformEvent,
+    interfaces::{DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter},
+    models::*,
+};
+use execution_engine::platforms::PlatformType;
+
+/// Fills every order after `latency`
+struct MockPlatform {
+    latency: Duration,
+}
+
+impl MockPlatform {
+    fn unsupported<T>() -> Result<T, PlatformError> {
+        Err(PlatformError::FeatureNotSupported {
+            feature: "mock".to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl ITradingPlatform for MockPlatform {
+    fn platform_type(&self) -> PlatformType {
+        PlatformType::DXTrade
+    }
+    fn platform_name(&self) -> &str {
+        "mock"
+    }
+    fn platform_version(&self) -> &str {
+        "1.0.0"
+    }
+    async fn connect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn disconnect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn is_connected(&self) -> bool {
+        true
+    }
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        Ok(1)
+    }
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        tokio::time::sleep(self.latency).await;
+        Ok(UnifiedOrderResponse {
+            platform_order_id: Uuid::new_v4().to_string(),
+            client_order_id: order.client_order_id,
+            status: UnifiedOrderStatus::Filled,
+            symbol: order.symbol,
+            side: order.side,
+            order_type: order.order_type,
+            quantity: order.quantity,
+            filled_quantity: order.quantity,
+            remaining_quantity: Decimal::ZERO,
+            price: Some(dec!(1.0850)),
+            average_fill_price: Some(dec!(1.0850)),
+            commission: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            filled_at: Some(Utc::now()),
+            platform_specific: HashMap::new(),
+        })
+    }
+    async fn modify_order(
+        &self,
+        _order_id: &str,
+        _modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn cancel_order(&self, _order_id: &str) -> Result<(), PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_order(&self, _order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_orders(
+        &self,
+        _filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn get_position(&self, _symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        Ok(None)
+    }
+    async fn close_position(
+        &self,
+        _symbol: &str,
+        _quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        Ok(UnifiedAccountInfo {
+            account_id: "acc-1".to_string(),
+            account_name: None,
+            currency: "USD".to_string(),
+            balance: dec!(100000),
+            equity: dec!(100000),
+            margin_used: Decimal::ZERO,
+            margin_available: dec!(100000),
+            buying_power: dec!(100000),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_level: None,
+            account_type: AccountType::Demo,
+            last_updated: Utc::now(),
+            platform_specific: HashMap::new(),
+        })
+    }
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        Ok(dec!(100000))
+    }
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_market_data(&self, _symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        Self::unsupported()
+    }
+    async fn subscribe_market_data(
+        &self,
+        _symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn unsubscribe_market_data(&self, _symbols: Vec<String>) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    fn capabilities(&self) -> PlatformCapabilities {
+        PlatformCapabilities::new("mock".to_string())
+    }
+    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_event_history(
+        &self,
+        _filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        Self::unsupported()
+    }
+}
+
+async fn orchestrator(accounts: usize, latency: Duration) -> Arc<TradeExecutionOrchestrator> {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    for i in 0..accounts {
+        orchestrator
+            .register_account(
+                format!("acc-{}", i),
+                Arc::new(MockPlatform { latency }),
+                100000.0,
+            )
+            .await
+            .unwrap();
+    }
+    orchestrator
+}
+
+fn plan(signal_id: &str, accounts: &[usize]) -> ExecutionPlan {
+    ExecutionPlan {
+        signal_id: signal_id.to_string(),
+        symbol: "EURUSD".to_string(),
+        account_assignments: accounts
+            .iter()
+            .map(|i| AccountAssignment {
+                account_id: format!("acc-{}", i),
+                position_size: 1.0,
+                entry_timing_delay: Duration::ZERO,
+                priority: 1,
+                risk_per_unit: 0.0,
+            })
+            .collect(),
+        timing_variance: HashMap::new(),
+        size_variance: HashMap::new(),
+        rationale: "test".to_string(),
+        exit_policy: None,
+        tags: Vec::new(),
+        entry_ladder: None,
+    }
+}
+
+#[tokio::test]
+async fn test_registration_is_not_blocked_by_orders_in_flight() {
+    let orchestrator = orchestrator(2, Duration::from_millis(500)).await;
+    let executing = {
+        let orchestrator = orchestrator.clone();
+        tokio::spawn(async move { orchestrator.execute_plan(&plan("sig-1", &[0, 1])).await })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    tokio::time::timeout(
+        Duration::from_millis(200),
+        orchestrator.register_account(
+            "acc-late".to_string(),
+            Arc::new(MockPlatform {
+                latency: Duration::ZERO,
+            }),
+            100000.0,
+        ),
+    )
+    .await
+    .expect("registration should not wait for orders in flight")
+    .unwrap();
+
+    let results = executing.await.unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|r| r.success));
+}
+
+#[tokio::test]
+async fn test_account_updates_are_applied_when_plans_return() {
+    let orchestrator = orchestrator(4, Duration::from_millis(5)).await;
+
+    let plans: Vec<_> = (0..20)
+        .map(|n| {
+            let orchestrator = orchestrator.clone();
+            tokio::spawn(async move {
+                orchestrator
+                    .execute_plan(&plan(&format!("sig-{}", n), &[0, 1, 2, 3]))
+                    .await
+            })
+        })
+        .collect();
+    for handle in plans {
+        let results = handle.await.unwrap();
+        assert!(results.iter().all(|r| r.success));
+    }
+
+    for i in 0..4 {
+        let status = orchestrator
+            .get_account_status(&format!("acc-{}", i))
+            .await
+            .unwrap();
+        assert_eq!(status.open_positions, 20);
+        assert!(status.last_trade_time.is_some());
+    }
+
+    // Orders for unknown accounts fail without touching any account
+    let results = orchestrator.execute_plan(&plan("sig-missing", &[9])).await;
+    assert_eq!(
+        results[0].error_message.as_deref(),
+        Some("Platform not found")
+    );
+}
+
+#[tokio::test]
+async fn test_snapshots_never_see_an_update_half_applied() {
+    let orchestrator = orchestrator(4, Duration::from_millis(1)).await;
+
+    let plans: Vec<_> = (0..20)
+        .map(|n| {
+            let orchestrator = orchestrator.clone();
+            tokio::spawn(async move {
+                orchestrator
+                    .execute_plan(&plan(&format!("sig-{}", n), &[0, 1, 2, 3]))
+                    .await
+            })
+        })
+        .collect();
+    let toggles = {
+        let orchestrator = orchestrator.clone();
+        tokio::spawn(async move {
+            for n in 0..50 {
+                orchestrator.pause_account("acc-0").await.unwrap();
+                if n % 2 == 0 {
+                    orchestrator.resume_account("acc-0").await.unwrap();
+                }
+            }
+        })
+    };
+
+    while plans.iter().any(|handle| !handle.is_finished()) {
+        for status in orchestrator.get_all_account_statuses().await {
+            // Every order filled one unit, so exposure moves with the position count
+            assert_eq!(status.open_exposure, status.open_positions as f64);
+            assert_eq!(status.last_trade_time.is_some(), status.open_positions > 0);
+        }
+        tokio::task::yield_now().await;
+    }
+    toggles.await.unwrap();
+
+    let statuses = orchestrator.get_all_account_statuses().await;
+    let ids: Vec<&str> = statuses.iter().map(|s| s.account_id.as_str()).collect();
+    assert_eq!(ids, ["acc-0", "acc-1", "acc-2", "acc-3"]);
+    assert!(statuses.iter().all(|s| s.open_positions == 20));
+    assert!(!statuses[0].is_active);
+    assert!(orchestrator.pause_account("acc-missing").await.is_err());
+}
@@ -0,0 +1,106 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::{
+    PriceLevel, UnifiedOrderBook, UnifiedOrderSide,
+};
+use execution_engine::platforms::abstraction::{
+    PlatformError, PlatformFeature, SymbolMapper, SymbolMappingPlatform,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+fn levels(levels: &[(Decimal, Decimal)]) -> Vec<PriceLevel> {
+    levels
+        .iter()
+        .map(|&(price, volume)| PriceLevel {
+            price,
+            volume,
+            order_count: None,
+        })
+        .collect()
+}
+
+fn book(symbol: &str) -> UnifiedOrderBook {
+    UnifiedOrderBook {
+        symbol: symbol.to_string(),
+        bids: levels(&[
+            (dec!(1.0999), dec!(100000)),
+            (dec!(1.0998), dec!(200000)),
+            (dec!(1.0996), dec!(500000)),
+        ]),
+        asks: levels(&[
+            (dec!(1.1001), dec!(100000)),
+            (dec!(1.1002), dec!(300000)),
+            (dec!(1.1005), dec!(500000)),
+        ]),
+        timestamp: Utc::now(),
+    }
+}
+
+#[test]
+fn sweeps_fill_from_the_best_levels_down() {
+    let book = book("EURUSD");
+    assert_eq!(book.spread(), Some(dec!(0.0002)));
+
+    let sweep = book.sweep(&UnifiedOrderSide::Buy, dec!(200000), 3, None);
+    assert_eq!(sweep.filled, dec!(200000));
+    assert_eq!(sweep.average_price, Some(dec!(1.10015)));
+    assert_eq!(sweep.worst_price, Some(dec!(1.1002)));
+
+    // Only the best two levels, and nothing priced below the limit
+    let sweep = book.sweep(&UnifiedOrderSide::Sell, dec!(1000000), 2, None);
+    assert_eq!(sweep.filled, dec!(300000));
+    let sweep = book.sweep(
+        &UnifiedOrderSide::Sell,
+        dec!(1000000),
+        3,
+        Some(dec!(1.0998)),
+    );
+    assert_eq!(sweep.filled, dec!(300000));
+    assert_eq!(sweep.worst_price, Some(dec!(1.0998)));
+}
+
+#[tokio::test]
+async fn depth_is_only_offered_by_platforms_with_market_depth() {
+    let mock = MockTradingPlatform::new("acc-1");
+    assert!(!mock.supports_feature(PlatformFeature::MarketDepth));
+    assert!(matches!(
+        mock.subscribe_depth(vec!["EURUSD".to_string()], 5).await,
+        Err(PlatformError::FeatureNotSupported { .. })
+    ));
+
+    let mock = mock.with_order_book(book("EURUSD"));
+    assert!(mock.supports_feature(PlatformFeature::MarketDepth));
+    assert_eq!(
+        mock.get_order_book("EURUSD", 2).await.unwrap().asks.len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn depth_streams_speak_unified_symbols() {
+    let broker = Arc::new(MockTradingPlatform::new("acc-1").with_order_book(book("EURUSD.r")));
+    let mapper = SymbolMapper::new();
+    mapper.insert("EURUSD", "EURUSD.r");
+    let platform = SymbolMappingPlatform::new(broker.clone(), Arc::new(mapper));
+
+    let mut depth = platform
+        .subscribe_depth(vec!["EURUSD".to_string()], 1)
+        .await
+        .unwrap();
+    let mut moved = book("EURUSD.r");
+    moved.asks.remove(0);
+    broker.set_order_book(moved);
+
+    let update = depth.recv().await.unwrap();
+    assert_eq!(update.symbol, "EURUSD");
+    assert_eq!(update.best_ask(), Some(dec!(1.1002)));
+    assert_eq!((update.bids.len(), update.asks.len()), (1, 1));
+    assert_eq!(
+        platform.get_order_book("EURUSD", 5).await.unwrap().symbol,
+        "EURUSD"
+    );
+}
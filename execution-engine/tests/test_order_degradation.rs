@@ -0,0 +1,162 @@
+use chrono::Utc;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use execution_engine::platforms::abstraction::capabilities::PlatformCapabilities;
+use execution_engine::platforms::abstraction::degradation::{
+    DegradationPolicy, DegradingPlatform, OrderDegradation, DEGRADATIONS_KEY,
+};
+use execution_engine::platforms::abstraction::errors::PlatformError;
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::{
+    OrderMetadata, UnifiedOrder, UnifiedOrderSide, UnifiedOrderStatus, UnifiedOrderType,
+    UnifiedTimeInForce,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+/// A platform with plain stop and limit orders, GTC only
+fn basic_broker() -> Arc<MockTradingPlatform> {
+    let mut capabilities = PlatformCapabilities::new("basic".to_string());
+    capabilities.order_types.extend([
+        UnifiedOrderType::Market,
+        UnifiedOrderType::Limit,
+        UnifiedOrderType::Stop,
+    ]);
+    capabilities
+        .time_in_force_options
+        .insert(UnifiedTimeInForce::Gtc);
+    Arc::new(
+        MockTradingPlatform::new("basic")
+            .with_capabilities(capabilities)
+            .with_quote("EURUSD", dec!(1.1000), dec!(1.1002)),
+    )
+}
+
+fn order(order_type: UnifiedOrderType, time_in_force: UnifiedTimeInForce) -> UnifiedOrder {
+    UnifiedOrder {
+        client_order_id: uuid::Uuid::new_v4().to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Sell,
+        order_type,
+        quantity: dec!(1),
+        price: None,
+        stop_price: None,
+        stop_loss: None,
+        take_profit: None,
+        time_in_force,
+        account_id: None,
+        metadata: OrderMetadata {
+            strategy_id: None,
+            signal_id: None,
+            risk_parameters: HashMap::new(),
+            tags: Vec::new(),
+            expires_at: None,
+        },
+        max_slippage: None,
+    }
+}
+
+fn fast_policy() -> DegradationPolicy {
+    DegradationPolicy {
+        trailing_poll_interval_ms: 10,
+        ..DegradationPolicy::default()
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn gtd_orders_become_gtc_and_are_cancelled_at_expiry() {
+    let broker = basic_broker();
+    let platform = DegradingPlatform::new(broker.clone(), fast_policy());
+    assert!(platform
+        .capabilities()
+        .supports_time_in_force(&UnifiedTimeInForce::Gtd));
+
+    let mut limit = order(UnifiedOrderType::Limit, UnifiedTimeInForce::Gtd);
+    limit.price = Some(dec!(1.1100));
+    let expires_at = Utc::now() + chrono::Duration::seconds(60);
+    limit.metadata.expires_at = Some(expires_at);
+
+    let response = platform.place_order(limit).await.unwrap();
+    let recorded: Vec<OrderDegradation> =
+        serde_json::from_value(response.platform_specific[DEGRADATIONS_KEY].clone()).unwrap();
+    assert_eq!(recorded, vec![OrderDegradation::GtdAsGtc { expires_at }]);
+    assert_eq!(broker.orders()[0].status, UnifiedOrderStatus::New);
+
+    tokio::time::sleep(Duration::from_secs(61)).await;
+    assert_eq!(broker.orders()[0].status, UnifiedOrderStatus::Canceled);
+
+    // Without an expiry there is nothing to schedule
+    let error = platform
+        .place_order(order(UnifiedOrderType::Limit, UnifiedTimeInForce::Gtd))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, PlatformError::OrderValidationFailed { .. }));
+}
+
+#[tokio::test(start_paused = true)]
+async fn trailing_stops_are_emulated_with_a_ratcheting_stop_order() {
+    let broker = basic_broker();
+    let platform = DegradingPlatform::new(broker.clone(), fast_policy());
+
+    let mut trailing = order(UnifiedOrderType::TrailingStop, UnifiedTimeInForce::Gtc);
+    trailing.stop_price = Some(dec!(1.0950));
+    let response = platform.place_order(trailing).await.unwrap();
+    assert_eq!(response.order_type, UnifiedOrderType::Stop);
+    assert!(response.platform_specific.contains_key(DEGRADATIONS_KEY));
+
+    // The stop follows the bid up 50 pips behind it
+    broker.set_quote("EURUSD", dec!(1.1030), dec!(1.1032));
+    tokio::time::sleep(Duration::from_millis(25)).await;
+    // ...and stays put when the market falls back
+    broker.set_quote("EURUSD", dec!(1.1010), dec!(1.1012));
+    tokio::time::sleep(Duration::from_millis(25)).await;
+
+    let stops: Vec<_> = broker
+        .modifications()
+        .into_iter()
+        .map(|(id, m)| (id, m.stop_price))
+        .collect();
+    assert_eq!(
+        stops,
+        vec![(response.platform_order_id.clone(), Some(dec!(1.0980)))]
+    );
+
+    // Trailing ends with the order
+    broker
+        .cancel_order(&response.platform_order_id)
+        .await
+        .unwrap();
+    broker.set_quote("EURUSD", dec!(1.1100), dec!(1.1102));
+    tokio::time::sleep(Duration::from_millis(25)).await;
+    assert_eq!(broker.modifications().len(), 1);
+}
+
+#[tokio::test]
+async fn disabled_emulation_reports_the_missing_feature() {
+    let broker = basic_broker();
+    let platform = DegradingPlatform::new(
+        broker.clone(),
+        DegradationPolicy {
+            emulate_trailing_stops: false,
+            ..DegradationPolicy::default()
+        },
+    );
+    assert!(!platform
+        .capabilities()
+        .supports_order_type(&UnifiedOrderType::TrailingStop));
+
+    let mut trailing = order(UnifiedOrderType::TrailingStop, UnifiedTimeInForce::Gtc);
+    trailing.stop_price = Some(dec!(1.0950));
+    let error = platform.place_order(trailing).await.unwrap_err();
+    assert!(matches!(error, PlatformError::FeatureNotSupported { .. }));
+    assert!(broker.orders().is_empty());
+
+    // Supported orders go through untouched
+    let response = platform
+        .place_order(order(UnifiedOrderType::Market, UnifiedTimeInForce::Gtc))
+        .await
+        .unwrap();
+    assert!(!response.platform_specific.contains_key(DEGRADATIONS_KEY));
+}
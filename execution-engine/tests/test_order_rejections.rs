@@ -0,0 +1,200 @@
+use execution_engine::execution::exit_management::ExitPolicy;
+use execution_engine::execution::orchestrator::{
+    AccountAssignment, ExecutionPlan, TradeExecutionOrchestrator,
+};
+use execution_engine::platforms::abstraction::{
+    ITradingPlatform, PlatformError, RejectionClassifier, RejectionReason,
+    RejectionRemediationConfig, Remediation, ValidationError,
+};
+use execution_engine::platforms::PlatformType;
+use execution_engine::testing::{ChaosPlatform, ChaosScenario, Fault, Operation};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn rejected(reason: &str, platform_code: Option<&str>) -> PlatformError {
+    PlatformError::OrderRejected {
+        reason: reason.to_string(),
+        platform_code: platform_code.map(str::to_string),
+    }
+}
+
+fn plan() -> ExecutionPlan {
+    ExecutionPlan {
+        signal_id: "reject-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        account_assignments: vec![AccountAssignment {
+            account_id: "acc-1".to_string(),
+            position_size: 10.0,
+            entry_timing_delay: Duration::ZERO,
+            priority: 1,
+            risk_per_unit: 0.005,
+        }],
+        timing_variance: HashMap::new(),
+        size_variance: HashMap::new(),
+        rationale: "rejections".to_string(),
+        exit_policy: Some(ExitPolicy::default()),
+        tags: Vec::new(),
+        entry_ladder: None,
+    }
+}
+
+/// Orchestrator on a platform that rejects the first order with `reason`
+async fn rejecting(
+    reason: &str,
+    config: RejectionRemediationConfig,
+) -> (TradeExecutionOrchestrator, Arc<ChaosPlatform>) {
+    let orchestrator = TradeExecutionOrchestrator::new().with_rejection_remediation(config);
+    let platform = Arc::new(
+        ChaosPlatform::new(
+            "acc-1",
+            ChaosScenario::calm("rejections").with_fault_at(
+                Operation::PlaceOrder,
+                1,
+                Fault::Reject {
+                    reason: reason.to_string(),
+                },
+            ),
+        )
+        .with_balance(dec!(10000))
+        .with_quote("EURUSD", dec!(1.1000), dec!(1.1002)),
+    );
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 10000.0)
+        .await
+        .unwrap();
+    (orchestrator, platform)
+}
+
+#[test]
+fn classifier_reads_platform_codes_before_messages() {
+    let mt5 = RejectionClassifier::for_platform(&PlatformType::MetaTrader5);
+    assert_eq!(
+        mt5.classify(&rejected("Request rejected", Some("10019"))),
+        Some(RejectionReason::InsufficientMargin)
+    );
+    assert_eq!(
+        mt5.classify(&rejected("Invalid stops", Some("10016"))),
+        Some(RejectionReason::InvalidStopDistance)
+    );
+
+    let dxtrade = RejectionClassifier::for_platform(&PlatformType::DXTrade);
+    assert_eq!(
+        dxtrade.classify(&rejected("Exchange closed", Some("2"))),
+        Some(RejectionReason::MarketClosed)
+    );
+
+    let tradelocker = RejectionClassifier::for_platform(&PlatformType::TradeLocker)
+        .with_phrase("INSTRUMENT_HALTED", RejectionReason::MarketClosed);
+    assert_eq!(
+        tradelocker.classify(&rejected("Invalid lot size 0.001", None)),
+        Some(RejectionReason::QuantityRule)
+    );
+    assert_eq!(
+        tradelocker.classify(&rejected("instrument_halted", None)),
+        Some(RejectionReason::MarketClosed)
+    );
+    assert_eq!(
+        tradelocker.classify(&rejected("Something odd", None)),
+        Some(RejectionReason::Other)
+    );
+    assert_eq!(
+        tradelocker.classify(&PlatformError::OrderValidationFailed {
+            violations: vec![ValidationError::OrderTooSmall {
+                min_size: dec!(0.01)
+            }],
+        }),
+        Some(RejectionReason::QuantityRule)
+    );
+
+    // Failures to reach the platform are not rejections
+    assert_eq!(
+        tradelocker.classify(&PlatformError::NetworkError {
+            reason: "reset".to_string()
+        }),
+        None
+    );
+}
+
+#[tokio::test]
+async fn margin_rejection_resends_smaller_order() {
+    let (orchestrator, _) =
+        rejecting("Not enough money", RejectionRemediationConfig::default()).await;
+
+    let results = orchestrator.execute_plan(&plan()).await;
+
+    assert!(results[0].success);
+    assert_eq!(
+        results[0].rejection,
+        Some(RejectionReason::InsufficientMargin)
+    );
+    assert_eq!(results[0].requested_quantity, Some(5.0));
+    assert_eq!(results[0].filled_quantity, Some(5.0));
+}
+
+#[tokio::test]
+async fn stop_distance_rejection_widens_stop() {
+    let mut config = RejectionRemediationConfig::default();
+    config.strategies.insert(
+        RejectionReason::InvalidStopDistance,
+        Remediation::WidenStop {
+            min_distance_pips: 300.0,
+        },
+    );
+    let (orchestrator, platform) = rejecting("Invalid stops", config).await;
+    let budget = orchestrator
+        .get_account_status("acc-1")
+        .await
+        .unwrap()
+        .risk_budget_remaining;
+
+    let results = orchestrator.execute_plan(&plan()).await;
+
+    assert!(results[0].success);
+    let positions = platform.get_positions().await.unwrap();
+    assert_eq!(positions[0].stop_loss, Some(dec!(1.0702)));
+    // Charged at the wider stop: 10 units at 0.005 plus the 0.0098 it moved
+    let account = orchestrator.get_account_status("acc-1").await.unwrap();
+    assert!((budget - account.risk_budget_remaining - 0.148).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn closed_market_rejection_defers_until_open() {
+    let (orchestrator, platform) =
+        rejecting("Market is closed", RejectionRemediationConfig::default()).await;
+
+    let results = orchestrator.execute_plan(&plan()).await;
+
+    assert!(!results[0].success);
+    assert_eq!(results[0].rejection, Some(RejectionReason::MarketClosed));
+    let deferred = orchestrator.deferred_orders().await;
+    assert_eq!(deferred.len(), 1);
+    assert_eq!(deferred[0].account_id, "acc-1");
+
+    // The chaos platform quotes the symbol, so the market reads as open
+    let released = orchestrator.release_deferred_orders().await;
+    assert_eq!(released.len(), 1);
+    assert!(released[0].success);
+    assert!(orchestrator.deferred_orders().await.is_empty());
+    assert_eq!(platform.get_positions().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn rejections_without_remediation_fail_once() {
+    let config = RejectionRemediationConfig {
+        enabled: false,
+        ..Default::default()
+    };
+    let (orchestrator, platform) = rejecting("Not enough money", config).await;
+
+    let results = orchestrator.execute_plan(&plan()).await;
+
+    assert!(!results[0].success);
+    assert_eq!(
+        results[0].rejection,
+        Some(RejectionReason::InsufficientMargin)
+    );
+    assert!(orchestrator.deferred_orders().await.is_empty());
+    assert!(platform.get_positions().await.unwrap().is_empty());
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, NaiveTime, TimeZone, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::execution::{
+    AccountPacer, ActivityWindow, PacingConfig, TradeExecutionOrchestrator,
+};
+use execution_engine::testing::{signal, MockTradingPlatform};
+
+fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, day, hour, minute, 0).unwrap()
+}
+
+fn window(start: NaiveTime, end: NaiveTime) -> ActivityWindow {
+    ActivityWindow { start, end }
+}
+
+#[test]
+fn accounts_trade_inside_their_windows_shifted_daily() {
+    let overnight = window(time(22, 0), time(2, 0));
+    assert!(overnight.contains(time(23, 30)) && overnight.contains(time(1, 0)));
+    assert!(!overnight.contains(time(12, 0)));
+    assert_eq!(
+        window(time(23, 50), time(1, 0)).shifted(20),
+        window(time(0, 10), time(1, 20))
+    );
+
+    let pacer = AccountPacer::new(PacingConfig {
+        enabled: true,
+        windows: vec![window(time(8, 0), time(12, 0))],
+        account_windows: HashMap::from([(
+            "acc-asia".to_string(),
+            vec![window(time(1, 0), time(5, 0))],
+        )]),
+        daily_shift_mins: 0,
+        ..PacingConfig::default()
+    });
+    assert!(pacer.check("acc-1", at(5, 9, 0)).is_none());
+    let reason = pacer.check("acc-1", at(5, 13, 0)).unwrap();
+    assert_eq!(
+        reason,
+        "13:00 is outside its trading windows today (08:00-12:00 UTC)"
+    );
+    assert!(pacer.check("acc-asia", at(5, 3, 0)).is_none());
+    assert!(pacer.check("acc-asia", at(5, 9, 0)).is_some());
+
+    let shifting = AccountPacer::new(PacingConfig {
+        enabled: true,
+        windows: vec![window(time(8, 0), time(12, 0))],
+        daily_shift_mins: 30,
+        ..PacingConfig::default()
+    });
+    for day in 4..=8 {
+        let schedule = shifting.schedule("acc-1", at(day, 0, 0));
+        assert_eq!(schedule.date, at(day, 0, 0).date_naive());
+        assert!(schedule.shift_mins.abs() <= 30);
+        assert_eq!(
+            schedule.windows,
+            vec![window(time(8, 0), time(12, 0)).shifted(schedule.shift_mins)]
+        );
+    }
+}
+
+#[test]
+fn trades_are_spaced_and_limited_per_day() {
+    let pacer = AccountPacer::new(PacingConfig {
+        enabled: true,
+        min_interval_secs: 900,
+        daily_trades: Some((2, 2)),
+        ..PacingConfig::default()
+    });
+
+    pacer.record_trade("acc-1", at(5, 9, 0));
+    assert_eq!(
+        pacer.check("acc-1", at(5, 9, 10)).unwrap(),
+        "last trade 600s ago, within the 900s minimum between trades"
+    );
+    assert!(pacer.check("acc-2", at(5, 9, 10)).is_none());
+    assert!(pacer.check("acc-1", at(5, 9, 20)).is_none());
+
+    pacer.record_trade("acc-1", at(5, 9, 20));
+    assert_eq!(
+        pacer.check("acc-1", at(5, 15, 0)).unwrap(),
+        "2 trades taken today, its limit for the day"
+    );
+    assert!(pacer.check("acc-1", at(6, 9, 0)).is_none());
+    assert_eq!(pacer.schedule("acc-1", at(6, 9, 0)).trades, 0);
+
+    assert!(PacingConfig {
+        daily_trades: Some((3, 2)),
+        ..PacingConfig::default()
+    }
+    .validate()
+    .is_err());
+}
+
+#[tokio::test]
+async fn paced_accounts_sit_out_signals_until_their_interval_passes() {
+    let orchestrator = TradeExecutionOrchestrator::new().with_pacing(PacingConfig {
+        enabled: true,
+        min_interval_secs: 3600,
+        ..PacingConfig::default()
+    });
+    for account_id in ["acc-1", "acc-2"] {
+        orchestrator
+            .register_account(
+                account_id.to_string(),
+                Arc::new(MockTradingPlatform::new(account_id)),
+                100000.0,
+            )
+            .await
+            .unwrap();
+    }
+
+    let plan = orchestrator.process_signal(signal("sig-1")).await.unwrap();
+    assert_eq!(plan.account_assignments.len(), 2);
+
+    let preview = orchestrator.preview_signal(signal("sig-2")).await;
+    assert!(preview.plan.is_none());
+    assert!(preview.accounts.iter().all(|a| a
+        .exclusion
+        .as_deref()
+        .is_some_and(|reason| reason.ends_with("within the 3600s minimum between trades"))));
+}
@@ -0,0 +1,180 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::{Arc, Mutex};
+
+use execution_engine::execution::exit_management::{
+    ClosePositionRequest, ClosePositionResult, ExitAuditLogger, ExitModificationType, MarketData,
+    OrderModifyRequest, OrderModifyResult, PartialCloseRequest, PartialProfitManager, Position,
+    ProfitTakingConfig, TradingPlatform,
+};
+use execution_engine::testing::long_position;
+
+/// A platform whose partial closes can re-average the position, drop its stop
+/// and leave more volume than asked
+#[derive(Debug)]
+struct MockPlatform {
+    positions: Mutex<Vec<Position>>,
+    price: Mutex<Decimal>,
+    calls: Mutex<Vec<String>>,
+    reaverage_to: Option<Decimal>,
+    drops_stop: bool,
+    closes_short_by: Decimal,
+}
+
+impl MockPlatform {
+    fn new(position: Position) -> Self {
+        Self {
+            positions: Mutex::new(vec![position]),
+            price: Mutex::new(dec!(1.1060)),
+            calls: Mutex::new(Vec::new()),
+            reaverage_to: None,
+            drops_stop: false,
+            closes_short_by: Decimal::ZERO,
+        }
+    }
+
+    fn position(&self) -> Position {
+        self.positions.lock().unwrap()[0].clone()
+    }
+
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        let price = *self.price.lock().unwrap();
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: price,
+            ask: price,
+            spread: Decimal::ZERO,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("modify {:?}", request.new_stop_loss));
+        self.positions.lock().unwrap()[0].stop_loss = request.new_stop_loss;
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: true,
+            message: "modified".to_string(),
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        Err(anyhow!("unexpected full close of {}", request.position_id))
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("partial {}", request.volume));
+        let mut positions = self.positions.lock().unwrap();
+        positions[0].volume -= request.volume - self.closes_short_by;
+        if let Some(entry) = self.reaverage_to {
+            positions[0].entry_price = entry;
+        }
+        if self.drops_stop {
+            positions[0].stop_loss = None;
+        }
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: *self.price.lock().unwrap(),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+}
+
+fn manager(platform: Arc<MockPlatform>, logger: Arc<ExitAuditLogger>) -> PartialProfitManager {
+    let mut manager = PartialProfitManager::new(platform, logger);
+    manager.configure_symbol("EURUSD".to_string(), ProfitTakingConfig::default());
+    manager
+}
+
+#[tokio::test]
+async fn targets_stay_measured_from_the_original_entry_after_the_platform_re_averages() {
+    let position = long_position();
+    let platform = Arc::new(MockPlatform {
+        reaverage_to: Some(dec!(1.1010)),
+        drops_stop: true,
+        ..MockPlatform::new(position.clone())
+    });
+    let logger = Arc::new(ExitAuditLogger::new());
+    let manager = manager(platform.clone(), logger.clone());
+
+    // 1.2R takes the first target, and the stop the close dropped goes back
+    manager.check_profit_targets().await.unwrap();
+    assert_eq!(
+        platform.calls(),
+        vec!["partial 5000.0", "modify Some(1.0950)"]
+    );
+    assert_eq!(platform.position().stop_loss, Some(dec!(1.0950)));
+
+    let status = manager.get_position_target_status(position.id).unwrap();
+    assert_eq!(status.remaining_volume, dec!(5000.0));
+    assert_eq!(status.entry_price, Some(dec!(1.1000)));
+    let verification = status.last_verification.unwrap();
+    assert!(verification.stop_restored);
+    assert_eq!(verification.platform_entry, Some(dec!(1.1010)));
+    assert_eq!(verification.discrepancies().len(), 2);
+
+    let entries = logger
+        .get_exits_by_type(ExitModificationType::PartialProfit, None)
+        .await
+        .unwrap();
+    assert!(entries.iter().any(|e| e
+        .reasoning
+        .starts_with("Partial close verification: entry re-averaged")));
+
+    // 2R from the original entry, though only 1.5R from the platform's average
+    *platform.price.lock().unwrap() = dec!(1.1100);
+    manager.check_profit_targets().await.unwrap();
+    assert_eq!(platform.calls()[2], "partial 1250.000");
+    assert_eq!(
+        manager
+            .get_position_target_status(position.id)
+            .unwrap()
+            .targets_hit,
+        vec![1, 2]
+    );
+}
+
+#[tokio::test]
+async fn tracking_takes_the_volume_the_platform_left() {
+    let position = long_position();
+    let platform = Arc::new(MockPlatform {
+        closes_short_by: dec!(1000),
+        ..MockPlatform::new(position.clone())
+    });
+    let manager = manager(platform.clone(), Arc::new(ExitAuditLogger::new()));
+
+    manager.check_profit_targets().await.unwrap();
+    assert_eq!(platform.calls(), vec!["partial 5000.0"]);
+
+    let status = manager.get_position_target_status(position.id).unwrap();
+    assert_eq!(status.remaining_volume, dec!(6000.0));
+    let verification = status.last_verification.unwrap();
+    assert!(!verification.stop_restored);
+    assert_eq!(
+        verification.discrepancies(),
+        vec!["volume 6000.0 where 5000.0 was expected".to_string()]
+    );
+}
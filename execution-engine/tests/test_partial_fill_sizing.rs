@@ -0,0 +1,128 @@
+use chrono::Utc;
+use execution_engine::execution::exit_management::{
+    ExitPolicies, ExitPolicy, PendingExitPolicy, Position, UnifiedPositionSide,
+};
+use execution_engine::execution::orchestrator::{
+    AccountAssignment, ExecutionPlan, TradeExecutionOrchestrator,
+};
+use execution_engine::testing::{ChaosPlatform, ChaosScenario, Fault, Operation};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+fn plan(position_size: f64) -> ExecutionPlan {
+    ExecutionPlan {
+        signal_id: "partial-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        account_assignments: vec![AccountAssignment {
+            account_id: "acc-1".to_string(),
+            position_size,
+            entry_timing_delay: Duration::ZERO,
+            priority: 1,
+            risk_per_unit: 0.005,
+        }],
+        timing_variance: HashMap::new(),
+        size_variance: HashMap::new(),
+        rationale: "partial fill".to_string(),
+        exit_policy: Some(ExitPolicy::default()),
+        tags: Vec::new(),
+        entry_ladder: None,
+    }
+}
+
+async fn orchestrator(scenario: ChaosScenario) -> TradeExecutionOrchestrator {
+    let orchestrator = TradeExecutionOrchestrator::new();
+    let platform = ChaosPlatform::new("acc-1", scenario)
+        .with_balance(dec!(10000))
+        .with_quote("EURUSD", dec!(1.1000), dec!(1.1002));
+    orchestrator
+        .register_account("acc-1".to_string(), Arc::new(platform), 10000.0)
+        .await
+        .unwrap();
+    orchestrator
+}
+
+#[tokio::test]
+async fn partial_fill_charges_account_for_filled_size_only() {
+    let orchestrator = orchestrator(ChaosScenario::calm("partial").with_fault_at(
+        Operation::PlaceOrder,
+        1,
+        Fault::PartialFill { ratio: dec!(0.4) },
+    ))
+    .await;
+    let budget = orchestrator
+        .get_account_status("acc-1")
+        .await
+        .unwrap()
+        .risk_budget_remaining;
+
+    let results = orchestrator.execute_plan(&plan(10.0)).await;
+
+    assert!(results[0].success);
+    assert_eq!(results[0].requested_quantity, Some(10.0));
+    assert_eq!(results[0].filled_quantity, Some(4.0));
+
+    let account = orchestrator.get_account_status("acc-1").await.unwrap();
+    assert_eq!(account.open_positions, 1);
+    assert_eq!(account.open_exposure, 4.0);
+    assert!((budget - account.risk_budget_remaining - 0.02).abs() < 1e-9);
+
+    let pending = orchestrator.take_pending_exit_policies("acc-1").await;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].quantity, Some(dec!(4)));
+}
+
+#[tokio::test]
+async fn full_fill_charges_whole_assignment() {
+    let orchestrator = orchestrator(ChaosScenario::calm("calm")).await;
+
+    let results = orchestrator.execute_plan(&plan(2.0)).await;
+
+    assert_eq!(results[0].filled_quantity, Some(2.0));
+    let account = orchestrator.get_account_status("acc-1").await.unwrap();
+    assert_eq!(account.open_exposure, 2.0);
+}
+
+fn position(volume: Decimal, opened_secs_ago: i64) -> Position {
+    Position {
+        id: Uuid::new_v4(),
+        order_id: "order-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        position_type: UnifiedPositionSide::Long,
+        volume,
+        entry_price: dec!(1.1000),
+        current_price: dec!(1.1000),
+        stop_loss: Some(dec!(1.0950)),
+        take_profit: None,
+        unrealized_pnl: dec!(0),
+        swap: dec!(0),
+        commission: dec!(0),
+        open_time: Utc::now() - chrono::Duration::seconds(opened_secs_ago),
+        magic_number: None,
+        comment: None,
+    }
+}
+
+#[test]
+fn pending_policy_claims_position_of_filled_size() {
+    let policies = ExitPolicies::new();
+    let created_at = Utc::now() - chrono::Duration::seconds(10);
+    policies.expect(PendingExitPolicy {
+        signal_id: "partial-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        policy: ExitPolicy::default(),
+        created_at,
+        quantity: Some(dec!(4)),
+    });
+
+    // Another order's full-size position opened first
+    let other = position(dec!(10), 8);
+    let own = position(dec!(4), 5);
+    let claimed = policies.claim(&[other.clone(), own.clone()]);
+
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].0, own.id);
+}
@@ -0,0 +1,166 @@
+use chrono::{Duration as ChronoDuration, Utc};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use execution_engine::execution::pending_signals::{
+    HoldReason, PendingSignalConfig, PendingSignalQueue, SignalDisposition, EXPIRES_AT_KEY,
+};
+use execution_engine::execution::{TradeExecutionOrchestrator, TradeSignal};
+use execution_engine::platforms::abstraction::errors::PlatformError;
+use execution_engine::runtime::{Supervisor, SupervisorConfig};
+use execution_engine::testing::{fixtures, MockTradingPlatform, Operation};
+
+fn signal(id: &str, metadata: HashMap<String, String>) -> TradeSignal {
+    TradeSignal {
+        stop_loss: 1.0950,
+        take_profit: 1.1100,
+        metadata,
+        ..fixtures::signal(id)
+    }
+}
+
+async fn setup(
+    config: PendingSignalConfig,
+) -> (
+    Arc<MockTradingPlatform>,
+    Arc<TradeExecutionOrchestrator>,
+    Arc<PendingSignalQueue>,
+) {
+    let platform = Arc::new(MockTradingPlatform::new("acc-1").with_quote(
+        "EURUSD",
+        dec!(1.1000),
+        dec!(1.1001),
+    ));
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+    let queue = Arc::new(PendingSignalQueue::new(orchestrator.clone(), config));
+    (platform, orchestrator, queue)
+}
+
+async fn audit_actions(orchestrator: &TradeExecutionOrchestrator, signal_id: &str) -> Vec<String> {
+    orchestrator
+        .get_execution_history(100)
+        .await
+        .into_iter()
+        .filter(|e| e.signal_id == signal_id)
+        .map(|e| e.action)
+        .collect()
+}
+
+#[tokio::test(start_paused = true)]
+async fn signals_for_closed_markets_run_once_the_market_opens() {
+    let (platform, orchestrator, queue) = setup(PendingSignalConfig::default()).await;
+    platform.fail_operation(
+        Operation::GetMarketData,
+        PlatformError::MarketClosed {
+            symbol: "EURUSD".to_string(),
+        },
+    );
+
+    let disposition = queue.submit(signal("sig-1", HashMap::new())).await;
+    assert!(matches!(
+        disposition,
+        SignalDisposition::Held {
+            reason: HoldReason::MarketClosed,
+            ..
+        }
+    ));
+    assert!(queue.evaluate(Utc::now()).await.is_empty());
+    assert_eq!(queue.pending().await[0].evaluations, 1);
+    assert!(platform.orders().is_empty());
+
+    platform.clear_failures();
+    let outcomes = queue.evaluate(Utc::now()).await;
+    assert_eq!(outcomes.len(), 1);
+    let SignalDisposition::Executed(results) = &outcomes[0].1 else {
+        panic!("expected execution, got {:?}", outcomes[0].1);
+    };
+    assert!(results[0].success, "{:?}", results[0].error_message);
+    assert_eq!(platform.orders().len(), 1);
+    assert!(queue.pending().await.is_empty());
+
+    let actions = audit_actions(&orchestrator, "sig-1").await;
+    assert_eq!(actions[0], "SIGNAL_HELD");
+    assert_eq!(actions[1], "SIGNAL_RELEASED");
+    assert!(actions.contains(&"EXECUTION_SUCCESS".to_string()));
+}
+
+#[tokio::test]
+async fn held_signals_expire_with_an_audit_entry() {
+    let (platform, orchestrator, queue) = setup(PendingSignalConfig {
+        max_spread_pips: Some(2.0),
+        ..PendingSignalConfig::default()
+    })
+    .await;
+    platform.set_quote("EURUSD", dec!(1.1000), dec!(1.1005));
+
+    let expires_at = Utc::now() + ChronoDuration::minutes(10);
+    let metadata = HashMap::from([(EXPIRES_AT_KEY.to_string(), expires_at.to_rfc3339())]);
+    match queue.submit(signal("sig-2", metadata)).await {
+        SignalDisposition::Held {
+            reason: HoldReason::SpreadTooWide { spread_pips, .. },
+            ..
+        } => assert_eq!(spread_pips, 5.0),
+        other => panic!("expected a held signal, got {:?}", other),
+    }
+
+    let outcomes = queue
+        .evaluate(expires_at + ChronoDuration::seconds(1))
+        .await;
+    assert!(matches!(outcomes[0].1, SignalDisposition::Expired));
+    assert!(queue.pending().await.is_empty());
+    assert!(platform.orders().is_empty());
+    assert_eq!(
+        audit_actions(&orchestrator, "sig-2").await,
+        vec!["SIGNAL_HELD", "SIGNAL_EXPIRED"]
+    );
+
+    let stale = HashMap::from([(
+        EXPIRES_AT_KEY.to_string(),
+        (Utc::now() - ChronoDuration::minutes(1)).to_rfc3339(),
+    )]);
+    assert!(matches!(
+        queue.submit(signal("sig-3", stale)).await,
+        SignalDisposition::Expired
+    ));
+    let invalid = HashMap::from([(EXPIRES_AT_KEY.to_string(), "tomorrow".to_string())]);
+    assert!(matches!(
+        queue.submit(signal("sig-4", invalid)).await,
+        SignalDisposition::Rejected(_)
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn market_open_notices_release_signals_before_the_next_check() {
+    let (platform, _orchestrator, queue) = setup(PendingSignalConfig {
+        check_interval_secs: 3600,
+        ..PendingSignalConfig::default()
+    })
+    .await;
+    platform.fail_operation(
+        Operation::GetMarketData,
+        PlatformError::MarketClosed {
+            symbol: "EURUSD".to_string(),
+        },
+    );
+    queue.submit(signal("sig-5", HashMap::new())).await;
+
+    let mut supervisor = Supervisor::new(SupervisorConfig::default());
+    supervisor.add(queue.clone());
+    supervisor.start().await.unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(queue.pending().await.len(), 1);
+
+    platform.clear_failures();
+    queue.notify_market_open();
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    assert!(queue.pending().await.is_empty());
+    assert_eq!(platform.orders().len(), 1);
+
+    supervisor.shutdown().await;
+}
@@ -0,0 +1,212 @@
+use chrono::{Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::execution::exit_management::{ExitPolicy, TrailingConfig};
+use execution_engine::execution::{Persona, PersonaConfig, TradeExecutionOrchestrator};
+use execution_engine::journal::{ExitReason, TradeEntry, TradeExit, TradeRecord, TradeStatus};
+use execution_engine::platforms::abstraction::models::UnifiedPositionSide;
+use execution_engine::reports::PersonaDivergenceReport;
+use execution_engine::testing::{signal, MockTradingPlatform};
+
+fn config(jitter: f64) -> PersonaConfig {
+    PersonaConfig {
+        enabled: true,
+        personas: HashMap::from([
+            (
+                "scalper".to_string(),
+                Persona {
+                    aggressiveness: 1.0,
+                    hold_hours: 2.0,
+                    partial_take: 0.7,
+                    partial_take_rr: 0.8,
+                },
+            ),
+            (
+                "swing".to_string(),
+                Persona {
+                    aggressiveness: 0.0,
+                    hold_hours: 48.0,
+                    partial_take: 0.0,
+                    partial_take_rr: 2.0,
+                },
+            ),
+        ]),
+        accounts: HashMap::from([
+            ("acc-fast".to_string(), "scalper".to_string()),
+            ("acc-fast-2".to_string(), "scalper".to_string()),
+            ("acc-slow".to_string(), "swing".to_string()),
+        ]),
+        jitter,
+        report_min_trades: 2,
+        ..PersonaConfig::default()
+    }
+}
+
+fn trade(account_id: &str, quantity: Decimal, hold_mins: i64, partial: bool) -> TradeRecord {
+    let opened_at = Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+    let mut record = TradeRecord::open(TradeEntry {
+        account_id: account_id.to_string(),
+        position_id: format!("P-{}", uuid::Uuid::new_v4()),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity,
+        entry_price: dec!(1.1),
+        stop_loss: Some(dec!(1.09)),
+        take_profit: None,
+        signal_id: None,
+        order_id: None,
+        opened_at,
+        tags: Vec::new(),
+    });
+    if partial {
+        record.exits.push(TradeExit {
+            closing_order_id: "close-1".to_string(),
+            quantity: quantity / dec!(2),
+            price: dec!(1.11),
+            realized_pnl: dec!(50),
+            commission: Decimal::ZERO,
+            reason: ExitReason::PartialProfit,
+            closed_at: opened_at + Duration::minutes(hold_mins / 2),
+        });
+    }
+    record.status = TradeStatus::Closed;
+    record.holding_time_secs = Some(hold_mins * 60);
+    record
+}
+
+#[test]
+fn accounts_play_their_persona_with_a_jitter_of_their_own() {
+    let config = config(0.1);
+    let fast = config.persona_of("acc-fast").unwrap();
+    let fast_again = config.persona_of("acc-fast").unwrap();
+    let fast_2 = config.persona_of("acc-fast-2").unwrap();
+    assert_eq!(fast, fast_again);
+    assert_ne!(fast.hold_hours, fast_2.hold_hours);
+    for persona in [&fast, &fast_2] {
+        assert!((1.8..=2.2).contains(&persona.hold_hours));
+        assert!((0.6..=0.8).contains(&persona.partial_take));
+        assert!(persona.aggressiveness >= 0.9);
+    }
+    assert!(config.persona_of("acc-other").is_none());
+    assert!(PersonaConfig {
+        enabled: false,
+        ..config.clone()
+    }
+    .persona_of("acc-fast")
+    .is_none());
+
+    let mut unknown = config.clone();
+    unknown
+        .accounts
+        .insert("acc-x".to_string(), "daytrader".to_string());
+    assert!(unknown.validate().is_err());
+}
+
+#[test]
+fn personas_set_delays_sizes_and_unset_exit_sections() {
+    let config = config(0.0);
+    let fast = config.persona_of("acc-fast").unwrap();
+    let slow = config.persona_of("acc-slow").unwrap();
+
+    assert_eq!(fast.delay_range(1000, 31000), (1000, 16000));
+    assert_eq!(slow.delay_range(1000, 31000), (16000, 31000));
+    assert_eq!(fast.size_range(0.15), (1.0, 1.15));
+    assert_eq!(slow.size_range(0.15), (0.85, 1.0));
+
+    let policy = slow.exit_policy(None);
+    assert_eq!(policy.name.as_deref(), Some("persona:swing"));
+    assert_eq!(
+        policy.time_exit.unwrap().max_hold_duration,
+        Duration::hours(48)
+    );
+    assert!(!policy.profit_taking.unwrap().enabled);
+
+    let signal_policy = ExitPolicy {
+        name: Some("news-trade".to_string()),
+        trailing: Some(TrailingConfig::default()),
+        ..ExitPolicy::default()
+    };
+    let policy = fast.exit_policy(Some(signal_policy));
+    assert_eq!(policy.name.as_deref(), Some("news-trade"));
+    assert!(policy.trailing.is_some());
+    let taking = policy.profit_taking.unwrap();
+    assert_eq!(taking.profit_targets[0].close_percentage, 0.7);
+    assert_eq!(taking.profit_targets[0].risk_reward_ratio, 0.8);
+}
+
+#[tokio::test]
+async fn plans_size_and_time_accounts_by_persona() {
+    let orchestrator = TradeExecutionOrchestrator::new().with_personas(config(0.0));
+    for account_id in ["acc-fast", "acc-slow"] {
+        orchestrator
+            .register_account(
+                account_id.to_string(),
+                Arc::new(MockTradingPlatform::new(account_id)),
+                100000.0,
+            )
+            .await
+            .unwrap();
+    }
+
+    for i in 0..5 {
+        let preview = orchestrator
+            .preview_signal(signal(&format!("sig-{}", i)))
+            .await;
+        let plan = preview.plan.unwrap();
+        for assignment in &plan.account_assignments {
+            let delay_ms = assignment.entry_timing_delay.as_millis();
+            let decision = preview
+                .accounts
+                .iter()
+                .find(|a| a.account_id == assignment.account_id)
+                .unwrap();
+            let multiplier = decision.sizing.as_ref().unwrap().variance_multiplier;
+            if assignment.account_id == "acc-fast" {
+                assert!(delay_ms <= 15500 && multiplier >= 1.0);
+            } else {
+                assert!(delay_ms >= 15500 && multiplier <= 1.0);
+            }
+            assert!(decision.adjustments[0].starts_with("sized and timed as persona"));
+        }
+    }
+}
+
+#[test]
+fn the_report_flags_accounts_trading_alike() {
+    let trades = vec![
+        trade("acc-fast", dec!(12000), 90, true),
+        trade("acc-fast", dec!(11000), 150, true),
+        trade("acc-slow", dec!(8000), 2800, false),
+        trade("acc-slow", dec!(9000), 2900, false),
+        trade("acc-copy", dec!(11500), 120, true),
+        trade("acc-copy", dec!(11500), 120, true),
+        trade("acc-new", dec!(5000), 60, false),
+    ];
+    let report = PersonaDivergenceReport::compile(&trades, &config(0.1), Utc::now());
+
+    assert_eq!(report.footprints.len(), 4);
+    let fast = report
+        .footprints
+        .iter()
+        .find(|f| f.account_id == "acc-fast")
+        .unwrap();
+    assert_eq!(fast.persona.as_deref(), Some("scalper"));
+    assert_eq!(fast.average_hold_mins, Some(120.0));
+    assert_eq!(fast.partial_take_rate, 1.0);
+
+    // acc-new has too few trades to be compared
+    assert_eq!(report.pairs.len(), 3);
+    assert!(!report.diverged());
+    assert_eq!(report.converged.len(), 1);
+    assert_eq!(
+        (
+            report.converged[0].first.as_str(),
+            report.converged[0].second.as_str()
+        ),
+        ("acc-copy", "acc-fast")
+    );
+    assert!(report.pairs.last().unwrap().divergence > 0.5);
+}
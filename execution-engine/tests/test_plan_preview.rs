@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use execution_engine::execution::TradeExecutionOrchestrator;
+use execution_engine::testing::{signal, MockTradingPlatform};
+
+async fn orchestrator(
+    accounts: &[&str],
+) -> (TradeExecutionOrchestrator, Vec<Arc<MockTradingPlatform>>) {
+    let orchestrator = TradeExecutionOrchestrator::new();
+    let mut platforms = Vec::new();
+    for account_id in accounts {
+        let platform = Arc::new(MockTradingPlatform::new(account_id));
+        orchestrator
+            .register_account(account_id.to_string(), platform.clone(), 100000.0)
+            .await
+            .unwrap();
+        platforms.push(platform);
+    }
+    (orchestrator, platforms)
+}
+
+#[tokio::test]
+async fn preview_explains_each_account_without_side_effects() {
+    let (orchestrator, platforms) = orchestrator(&["acc-1", "acc-2", "acc-3"]).await;
+    orchestrator.pause_account("acc-3").await.unwrap();
+    orchestrator
+        .update_correlation_matrix("acc-1", "acc-2", 0.9)
+        .await;
+    let history_before = orchestrator.get_execution_history(100).await.len();
+
+    let preview = orchestrator.preview_signal(signal("sig-preview")).await;
+
+    let plan = preview.plan.expect("the signal would be planned");
+    assert_eq!(plan.account_assignments.len(), 2);
+    assert_eq!(preview.rejection, None);
+    assert_eq!(preview.accounts.len(), 3);
+
+    let paused = &preview.accounts[2];
+    assert_eq!(paused.account_id, "acc-3");
+    assert!(!paused.eligible);
+    assert_eq!(paused.exclusion.as_deref(), Some("the account is inactive"));
+
+    for decision in &preview.accounts[..2] {
+        let sizing = decision.sizing.as_ref().unwrap();
+        // 1% of the mock's 10000 margin over a 0.01 stop
+        assert!((sizing.risk_per_trade - 100.0).abs() < 1e-6);
+        assert!((sizing.base_size - 10000.0).abs() < 0.01);
+        let assignment = plan
+            .account_assignments
+            .iter()
+            .find(|a| a.account_id == decision.account_id)
+            .unwrap();
+        assert_eq!(decision.final_size, Some(assignment.position_size));
+        assert!(decision.explanation.starts_with("Eligible; risks 100.00"));
+    }
+    // The later of the correlated pair is delayed and sized down
+    let adjusted: Vec<_> = preview
+        .accounts
+        .iter()
+        .filter(|d| !d.adjustments.is_empty())
+        .collect();
+    assert_eq!(adjusted.len(), 1);
+    assert!(adjusted[0].adjustments[0].contains("correlation 0.90"));
+
+    assert_eq!(
+        orchestrator.get_execution_history(100).await.len(),
+        history_before
+    );
+    assert!(platforms.iter().all(|p| p.orders().is_empty()));
+}
+
+#[tokio::test]
+async fn preview_reports_why_a_signal_would_be_rejected() {
+    let (orchestrator, _platforms) = orchestrator(&["acc-1"]).await;
+    orchestrator.engage_kill_switch("drill".to_string()).await;
+
+    let preview = orchestrator.preview_signal(signal("sig-preview")).await;
+
+    assert!(preview.plan.is_none());
+    assert_eq!(
+        preview.rejection.as_deref(),
+        Some("Kill switch engaged, signal rejected")
+    );
+    assert_eq!(preview.accounts[0].final_size, None);
+    assert!(preview.accounts[0]
+        .explanation
+        .ends_with("not placed as the signal is rejected"));
+}
@@ -0,0 +1,83 @@
+use execution_engine::platforms::abstraction::PlatformError;
+use execution_engine::platforms::dxtrade::DXTradeConfig;
+use execution_engine::runtime::{
+    load_config, AccountBootstrap, DXTradeConnector, EngineConfig, PlatformConnector,
+    TradeLockerConnector,
+};
+use execution_engine::utils::config::ExecutionConfig;
+
+fn account(platform: &str) -> AccountBootstrap {
+    toml::from_str(&format!(
+        r#"
+        account_id = "acc-1"
+        platform = "{}"
+        initial_balance = 10000.0
+        "#,
+        platform
+    ))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn connectors_report_why_an_account_cannot_be_connected() {
+    // No Vault to read the account's credentials from
+    let tradelocker = TradeLockerConnector::new(ExecutionConfig {
+        vault_endpoint: "http://127.0.0.1:1".to_string(),
+        ..ExecutionConfig::default()
+    });
+    assert!(matches!(
+        tradelocker.connect(&account("TradeLocker")).await,
+        Err(PlatformError::InvalidCredentials { .. })
+    ));
+
+    // No CompIDs, login or certificates
+    let dxtrade = DXTradeConnector::new(DXTradeConfig::default());
+    assert!(matches!(
+        dxtrade.connect(&account("DXTrade")).await,
+        Err(PlatformError::ConfigurationError { .. })
+    ));
+}
+
+#[test]
+fn platform_sections_are_optional_and_fill_in_defaults() {
+    let config: EngineConfig = toml::from_str(
+        r#"
+        [tradelocker]
+        vault_endpoint = "https://vault.internal:8200"
+
+        [tradelocker.tradelocker]
+        max_retries = 5
+        "#,
+    )
+    .unwrap();
+    let tradelocker = config.tradelocker.unwrap();
+    assert_eq!(tradelocker.vault_endpoint, "https://vault.internal:8200");
+    assert_eq!(tradelocker.tradelocker.max_retries, 5);
+    assert_eq!(tradelocker.tradelocker.request_timeout_ms, 3000);
+    assert!(config.dxtrade.is_none());
+}
+
+#[test]
+fn an_explicit_config_that_cannot_be_used_fails_startup() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("engine.toml");
+    std::env::set_var("EXECUTION_ENGINE_CONFIG_PATH", &path);
+
+    // Missing file
+    assert!(load_config().unwrap_err().starts_with("Failed to read config"));
+
+    // Parses, but fails validation
+    std::fs::write(
+        &path,
+        r#"
+        [api]
+        bind_address = "not an address"
+        "#,
+    )
+    .unwrap();
+    assert!(load_config().unwrap_err().starts_with("Invalid config"));
+
+    std::fs::write(&path, "").unwrap();
+    assert!(load_config().is_ok());
+    std::env::remove_var("EXECUTION_ENGINE_CONFIG_PATH");
+}
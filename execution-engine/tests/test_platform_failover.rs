@@ -0,0 +1,167 @@
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+use execution_engine::platforms::abstraction::errors::PlatformError;
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::{
+    OrderMetadata, UnifiedOrder, UnifiedOrderSide, UnifiedOrderType, UnifiedTimeInForce,
+};
+use execution_engine::platforms::abstraction::{
+    FailoverConfig, FailoverPlatform, PlatformEndpoint,
+};
+use execution_engine::testing::{MockTradingPlatform, Operation};
+
+fn market_order(id: &str) -> UnifiedOrder {
+    UnifiedOrder {
+        client_order_id: id.to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Buy,
+        order_type: UnifiedOrderType::Market,
+        quantity: dec!(10000),
+        price: None,
+        stop_price: None,
+        take_profit: None,
+        stop_loss: None,
+        time_in_force: UnifiedTimeInForce::Ioc,
+        account_id: Some("acc-1".to_string()),
+        metadata: OrderMetadata {
+            strategy_id: None,
+            signal_id: None,
+            risk_parameters: std::collections::HashMap::new(),
+            tags: Vec::new(),
+            expires_at: None,
+        },
+        max_slippage: None,
+    }
+}
+
+fn endpoint(name: &str) -> PlatformEndpoint {
+    PlatformEndpoint {
+        name: name.to_string(),
+        url: format!("https://{}.broker.example", name),
+    }
+}
+
+fn disconnected() -> PlatformError {
+    PlatformError::Disconnected {
+        reason: "gateway went away".to_string(),
+    }
+}
+
+/// A primary and a backup endpoint of one account
+fn endpoints(
+    config: FailoverConfig,
+) -> (
+    Arc<MockTradingPlatform>,
+    Arc<MockTradingPlatform>,
+    FailoverPlatform,
+) {
+    let primary = Arc::new(MockTradingPlatform::new("acc-1").with_quote(
+        "EURUSD",
+        dec!(1.0999),
+        dec!(1.1001),
+    ));
+    let backup = Arc::new(MockTradingPlatform::new("acc-1").with_quote(
+        "EURUSD",
+        dec!(1.0999),
+        dec!(1.1001),
+    ));
+    let platform = FailoverPlatform::new(
+        "acc-1".to_string(),
+        vec![
+            (endpoint("primary"), primary.clone()),
+            (endpoint("backup"), backup.clone()),
+        ],
+        config,
+    )
+    .unwrap();
+    (primary, backup, platform)
+}
+
+#[tokio::test]
+async fn reads_move_to_the_standby_when_the_endpoint_drops() {
+    let (primary, backup, platform) = endpoints(FailoverConfig::default());
+    primary.fail_operation(Operation::GetPositions, disconnected());
+
+    platform.get_positions().await.unwrap();
+    assert_eq!(platform.active_endpoint().name, "backup");
+    assert_eq!(primary.calls(Operation::GetPositions), 1);
+    assert_eq!(backup.calls(Operation::GetPositions), 1);
+
+    // Later calls go straight to the standby
+    platform.get_account_info().await.unwrap();
+    assert_eq!(primary.calls(Operation::GetAccountInfo), 0);
+
+    // Errors about the request itself leave the endpoint in use
+    backup.script_error(
+        Operation::GetPositions,
+        PlatformError::InternalError {
+            reason: "bad request".to_string(),
+        },
+    );
+    assert!(platform.get_positions().await.is_err());
+    assert_eq!(platform.active_endpoint().name, "backup");
+}
+
+#[tokio::test]
+async fn orders_in_flight_are_only_resent_when_the_standby_lacks_them() {
+    let (primary, backup, platform) = endpoints(FailoverConfig::default());
+    // The first order reached the broker before the primary went away
+    backup.place_order(market_order("o-1")).await.unwrap();
+    primary.script_error(Operation::PlaceOrder, disconnected());
+
+    let response = platform.place_order(market_order("o-1")).await.unwrap();
+    assert_eq!(response.client_order_id, "o-1");
+    assert_eq!(backup.calls(Operation::PlaceOrder), 1);
+    assert_eq!(backup.orders().len(), 1);
+
+    // The second did not, so it is sent again on the standby
+    let (primary, backup, platform) = endpoints(FailoverConfig::default());
+    primary.script_error(Operation::PlaceOrder, disconnected());
+    platform.place_order(market_order("o-2")).await.unwrap();
+    assert_eq!(backup.orders()[0].client_order_id, "o-2");
+    assert!(primary.orders().is_empty());
+    assert!(platform.unacknowledged_orders().is_empty());
+}
+
+#[tokio::test]
+async fn writes_other_than_orders_are_not_repeated() {
+    let (primary, backup, platform) = endpoints(FailoverConfig::default());
+    primary.script_error(Operation::ClosePosition, disconnected());
+
+    assert!(platform.close_position("EURUSD", None).await.is_err());
+    assert_eq!(backup.calls(Operation::ClosePosition), 0);
+    assert_eq!(platform.active_endpoint().name, "backup");
+}
+
+#[tokio::test]
+async fn health_probes_fail_over_and_back() {
+    let (primary, _backup, platform) = endpoints(FailoverConfig {
+        failure_threshold: 2,
+        fail_back: true,
+        ..FailoverConfig::default()
+    });
+    primary.fail_operation(Operation::GetAccountInfo, disconnected());
+
+    platform.probe().await;
+    assert_eq!(platform.active_endpoint().name, "primary");
+    platform.probe().await;
+    assert_eq!(platform.active_endpoint().name, "backup");
+    let diagnostics = platform.get_diagnostics().await.unwrap();
+    let failover = &diagnostics.platform_specific["failover"];
+    assert_eq!(failover["endpoints"][0]["up"], false);
+    assert_eq!(failover["endpoints"][1]["active"], true);
+
+    primary.clear_failures();
+    platform.probe().await;
+    assert_eq!(platform.active_endpoint().name, "primary");
+}
+
+#[test]
+fn endpoints_must_be_named_uniquely() {
+    let config = FailoverConfig {
+        endpoints: vec![endpoint("primary"), endpoint("primary")],
+        ..FailoverConfig::default()
+    };
+    assert_eq!(config.validate().unwrap_err(), "Duplicate endpoint primary");
+}
@@ -0,0 +1,99 @@
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::{
+    OrderMetadata, UnifiedOrder, UnifiedOrderSide, UnifiedOrderType, UnifiedTimeInForce,
+};
+use execution_engine::platforms::abstraction::{
+    MonitoredPlatform, PerformanceConfig, PerformanceMonitor,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+fn market_order(id: &str) -> UnifiedOrder {
+    UnifiedOrder {
+        client_order_id: id.to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Buy,
+        order_type: UnifiedOrderType::Market,
+        quantity: dec!(10000),
+        price: None,
+        stop_price: None,
+        take_profit: None,
+        stop_loss: None,
+        time_in_force: UnifiedTimeInForce::Ioc,
+        account_id: Some("acc-1".to_string()),
+        metadata: OrderMetadata {
+            strategy_id: None,
+            signal_id: None,
+            risk_parameters: std::collections::HashMap::new(),
+            tags: Vec::new(),
+            expires_at: None,
+        },
+        max_slippage: None,
+    }
+}
+
+fn monitored(platform: MockTradingPlatform, monitor: PerformanceMonitor) -> MonitoredPlatform {
+    MonitoredPlatform::new(
+        Arc::new(platform.with_quote("EURUSD", dec!(1.0999), dec!(1.1001))),
+        monitor.with_platform("acc-1"),
+    )
+}
+
+#[tokio::test]
+async fn every_call_is_timed_per_operation() {
+    let platform = monitored(MockTradingPlatform::new("acc-1"), PerformanceMonitor::new());
+
+    for _ in 0..3 {
+        platform.get_account_info().await.unwrap();
+    }
+    platform.get_market_data("EURUSD").await.unwrap();
+    assert!(platform.get_market_data("GBPJPY").await.is_err());
+
+    let monitor = platform.monitor();
+    assert_eq!(
+        monitor
+            .get_operation_stats("get_account_info")
+            .unwrap()
+            .count,
+        3
+    );
+    let metrics = monitor.get_metrics();
+    assert_eq!(metrics.total_operations, 5);
+    assert_eq!(metrics.total_errors, 1);
+    assert_eq!(metrics.operations["get_market_data"].error_count, 1);
+}
+
+#[tokio::test]
+async fn calls_slower_than_their_sla_lower_compliance() {
+    let platform = monitored(
+        MockTradingPlatform::new("acc-1").with_latency(Duration::from_millis(20)),
+        PerformanceMonitor::from_config(&PerformanceConfig {
+            sla_ms: [("place_order".to_string(), 5)].into_iter().collect(),
+            ..PerformanceConfig::default()
+        }),
+    );
+
+    platform.place_order(market_order("o-1")).await.unwrap();
+    platform.get_account_info().await.unwrap();
+
+    let report = platform.monitor().check_sla_compliance();
+    assert!(!report.is_compliant);
+    assert_eq!(report.violations[0].operation, "place_order");
+    assert_eq!(report.compliance["place_order"], 0.0);
+    assert_eq!(report.compliance["get_account_info"], 1.0);
+}
+
+#[tokio::test]
+async fn diagnostics_carry_the_operation_figures() {
+    let platform = monitored(MockTradingPlatform::new("acc-1"), PerformanceMonitor::new());
+    platform.get_positions().await.unwrap();
+
+    let diagnostics = platform.get_diagnostics().await.unwrap();
+    let metrics = &diagnostics.performance_metrics;
+    assert_eq!(metrics["total_operations"], 1);
+    assert_eq!(metrics["operations"]["get_positions"]["count"], 1);
+    assert_eq!(metrics["sla"]["compliance"]["get_positions"], 1.0);
+}
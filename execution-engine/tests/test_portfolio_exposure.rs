@@ -0,0 +1,228 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use execution_engine::execution::{TradeExecutionOrchestrator, TradeSignal};
+use execution_engine::platforms::abstraction::models::{
+    UnifiedOrderSide, UnifiedPosition, UnifiedPositionSide,
+};
+use execution_engine::risk::portfolio_exposure::ClusterSide;
+use execution_engine::risk::{
+    ClusterLimits, ExposureCluster, PortfolioExposure, Position, PositionType,
+};
+use execution_engine::testing::{fixtures, MockTradingPlatform};
+
+fn usd_longs(max_exposure: Decimal) -> ExposureCluster {
+    ExposureCluster {
+        name: "usd_longs".to_string(),
+        members: HashMap::from([
+            ("USDJPY".to_string(), dec!(1)),
+            ("EURUSD".to_string(), dec!(-1)),
+            ("GBPUSD".to_string(), dec!(-1)),
+        ]),
+        side: ClusterSide::Long,
+        max_exposure,
+    }
+}
+
+fn position(
+    account_id: Uuid,
+    symbol: &str,
+    position_type: PositionType,
+    size: Decimal,
+    price: Decimal,
+) -> Position {
+    Position {
+        id: Uuid::new_v4(),
+        account_id,
+        symbol: symbol.to_string(),
+        position_type,
+        size,
+        entry_price: price,
+        current_price: Some(price),
+        unrealized_pnl: None,
+        max_favorable_excursion: dec!(0),
+        max_adverse_excursion: dec!(0),
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now(),
+    }
+}
+
+fn platform_position(
+    symbol: &str,
+    side: UnifiedPositionSide,
+    quantity: Decimal,
+) -> UnifiedPosition {
+    UnifiedPosition {
+        position_id: Uuid::new_v4().to_string(),
+        symbol: symbol.to_string(),
+        side,
+        quantity,
+        entry_price: dec!(1),
+        current_price: dec!(1),
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: String::new(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+fn signal(id: &str, symbol: &str) -> TradeSignal {
+    TradeSignal {
+        symbol: symbol.to_string(),
+        entry_price: 1.0,
+        stop_loss: 0.995,
+        take_profit: 1.01,
+        ..fixtures::signal(id)
+    }
+}
+
+#[test]
+fn clusters_combine_exposure_across_accounts() {
+    let (acc_1, acc_2) = (Uuid::new_v4(), Uuid::new_v4());
+    // Short EURUSD and long USDJPY both add to the USD long
+    let portfolio = PortfolioExposure::from_positions(&[
+        position(acc_1, "EURUSD", PositionType::Short, dec!(20000), dec!(1.5)),
+        position(acc_2, "USDJPY", PositionType::Long, dec!(500), dec!(100)),
+    ]);
+    assert_eq!(portfolio.by_account.len(), 2);
+
+    let limits = ClusterLimits::new(vec![usd_longs(dec!(100000))]);
+    let clusters = limits.evaluate(&portfolio);
+    assert_eq!(clusters[0].exposure, dec!(80000));
+    assert!(!clusters[0].breached);
+
+    // 40000 more would take the cluster 20000 over its cap
+    let allowance = limits.check_trade(&portfolio, "USDJPY", &UnifiedOrderSide::Buy, dec!(40000));
+    assert_eq!(allowance.fraction, dec!(0.5));
+    assert_eq!(allowance.limiting_cluster.as_deref(), Some("usd_longs"));
+
+    // Trades that take USD the other way, or outside the cluster, are untouched
+    for (symbol, side) in [
+        ("USDJPY", UnifiedOrderSide::Sell),
+        ("EURUSD", UnifiedOrderSide::Buy),
+        ("AUDNZD", UnifiedOrderSide::Buy),
+    ] {
+        let allowance = limits.check_trade(&portfolio, symbol, &side, dec!(500000));
+        assert_eq!(allowance.fraction, Decimal::ONE, "{} {:?}", symbol, side);
+    }
+}
+
+#[test]
+fn breached_clusters_recommend_proportional_reductions() {
+    let account_id = Uuid::new_v4();
+    let portfolio = PortfolioExposure::from_positions(&[
+        position(
+            account_id,
+            "EURUSD",
+            PositionType::Short,
+            dec!(20000),
+            dec!(1.5),
+        ),
+        position(
+            account_id,
+            "USDJPY",
+            PositionType::Long,
+            dec!(500),
+            dec!(100),
+        ),
+        position(
+            account_id,
+            "GBPUSD",
+            PositionType::Short,
+            dec!(40000),
+            dec!(1),
+        ),
+    ]);
+    let limits = ClusterLimits::new(vec![usd_longs(dec!(100000))]);
+    assert!(limits.evaluate(&portfolio)[0].breached);
+
+    let recommendations = limits.rebalance_recommendations(&portfolio);
+    assert_eq!(recommendations.len(), 3);
+    let eurusd = recommendations
+        .iter()
+        .find(|r| r.symbol == "EURUSD")
+        .unwrap();
+    // EURUSD holds a quarter of the cluster, so it takes a quarter of the 20000 excess
+    assert_eq!(eurusd.current_exposure, dec!(-30000));
+    assert_eq!(eurusd.target_exposure, dec!(-25000));
+
+    let reduced: Decimal = recommendations
+        .iter()
+        .map(|r| (r.current_exposure - r.target_exposure).abs())
+        .sum();
+    assert_eq!(reduced.round_dp(6), dec!(20000));
+}
+
+#[tokio::test(start_paused = true)]
+async fn signals_are_scaled_to_the_room_left_in_the_cluster() {
+    let cluster = ExposureCluster {
+        name: "eur_gbp_longs".to_string(),
+        members: HashMap::from([
+            ("EURUSD".to_string(), dec!(1)),
+            ("GBPUSD".to_string(), dec!(1)),
+        ]),
+        side: ClusterSide::Both,
+        max_exposure: dec!(110000),
+    };
+    let orchestrator =
+        TradeExecutionOrchestrator::new().with_exposure_clusters(ClusterLimits::new(vec![cluster]));
+    for (account_id, symbol) in [("acc-1", "EURUSD"), ("acc-2", "GBPUSD")] {
+        let platform = Arc::new(MockTradingPlatform::new(account_id).with_position(
+            platform_position(symbol, UnifiedPositionSide::Long, dec!(50000)),
+        ));
+        orchestrator
+            .register_account(account_id.to_string(), platform, 100000.0)
+            .await
+            .unwrap();
+    }
+
+    // 100000 of the cap is used across the two accounts, leaving 10000
+    let plan = orchestrator
+        .process_signal(signal("sig-1", "EURUSD"))
+        .await
+        .unwrap();
+    let planned: f64 = plan
+        .account_assignments
+        .iter()
+        .map(|a| a.position_size)
+        .sum();
+    assert!((planned - 10000.0).abs() < 0.01, "planned {}", planned);
+    let history = orchestrator.get_execution_history(10).await;
+    assert!(history.iter().any(|e| e.action == "EXPOSURE_CAPPED"));
+
+    // Once the cluster is full, further signals are rejected
+    let orchestrator = TradeExecutionOrchestrator::new()
+        .with_exposure_clusters(ClusterLimits::new(vec![usd_longs(dec!(40000))]));
+    let platform = Arc::new(
+        MockTradingPlatform::new("acc-1").with_position(platform_position(
+            "EURUSD",
+            UnifiedPositionSide::Short,
+            dec!(50000),
+        )),
+    );
+    orchestrator
+        .register_account("acc-1".to_string(), platform, 100000.0)
+        .await
+        .unwrap();
+    let error = orchestrator
+        .process_signal(TradeSignal {
+            side: UnifiedOrderSide::Sell,
+            stop_loss: 1.005,
+            take_profit: 0.99,
+            ..signal("sig-2", "GBPUSD")
+        })
+        .await
+        .unwrap_err();
+    assert!(error.contains("usd_longs"), "{}", error);
+}
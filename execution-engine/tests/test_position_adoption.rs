@@ -0,0 +1,190 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::execution::exit_management::{
+    AdoptionConfig, ExitAuditLogger, ExitManagementPlatformAdapter, ExitManagementSystem,
+    ExitPolicy, PositionAdopter, EXTERNAL_ORIGIN_TAG,
+};
+use execution_engine::execution::{TradeExecutionOrchestrator, TradeSignal};
+use execution_engine::journal::TradeJournal;
+use execution_engine::platforms::abstraction::models::{
+    UnifiedOrderSide, UnifiedPosition, UnifiedPositionSide,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+fn manual_position(symbol: &str) -> UnifiedPosition {
+    UnifiedPosition {
+        position_id: uuid::Uuid::new_v4().to_string(),
+        symbol: symbol.to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity: dec!(5000),
+        entry_price: dec!(1.2650),
+        current_price: dec!(1.2650),
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: "acc-1".to_string(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+struct Fixture {
+    orchestrator: TradeExecutionOrchestrator,
+    platform: Arc<MockTradingPlatform>,
+    system: ExitManagementSystem,
+    journal: Arc<TradeJournal>,
+    adopter: PositionAdopter,
+}
+
+async fn fixture(auto_adopt: bool) -> Fixture {
+    let orchestrator = TradeExecutionOrchestrator::new();
+    let platform = Arc::new(
+        MockTradingPlatform::new("acc-1")
+            .with_quote("EURUSD", dec!(1.0999), dec!(1.1001))
+            .with_quote("GBPUSD", dec!(1.2649), dec!(1.2651)),
+    );
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+    let system = ExitManagementSystem::new(
+        Arc::new(ExitManagementPlatformAdapter::new(platform.clone())),
+        Arc::new(ExitAuditLogger::new()),
+    );
+    let journal = Arc::new(TradeJournal::new().with_tag_registry(orchestrator.tag_registry()));
+    let adopter = PositionAdopter::new(
+        AdoptionConfig {
+            auto_adopt,
+            min_age_secs: 0,
+            ..AdoptionConfig::default()
+        },
+        orchestrator.order_tracker(),
+    )
+    .with_journal(journal.clone())
+    .with_tag_registry(orchestrator.tag_registry());
+    Fixture {
+        orchestrator,
+        platform,
+        system,
+        journal,
+        adopter,
+    }
+}
+
+async fn open_engine_position(orchestrator: &TradeExecutionOrchestrator) {
+    let plan = orchestrator
+        .process_signal(TradeSignal {
+            id: "sig-1".to_string(),
+            symbol: "EURUSD".to_string(),
+            side: UnifiedOrderSide::Buy,
+            entry_price: 1.1,
+            stop_loss: 1.09,
+            take_profit: 1.12,
+            confidence: 0.8,
+            risk_reward_ratio: 2.0,
+            signal_time: std::time::SystemTime::now(),
+            metadata: HashMap::new(),
+        })
+        .await
+        .unwrap();
+    orchestrator.execute_plan(&plan).await;
+}
+
+#[tokio::test]
+async fn reconciliation_adopts_only_positions_the_engine_did_not_open() {
+    let f = fixture(true).await;
+    open_engine_position(&f.orchestrator).await;
+    let manual = manual_position("GBPUSD");
+    f.platform.add_position(manual.clone());
+
+    let positions = f
+        .orchestrator
+        .get_open_positions(Some("acc-1"))
+        .await
+        .unwrap();
+    assert_eq!(positions.len(), 2);
+    let adopted = f.adopter.reconcile("acc-1", &positions, &f.system).await;
+
+    assert_eq!(adopted.len(), 1);
+    assert_eq!(adopted[0].position_id, manual.position_id);
+    assert!(adopted[0].automatic);
+    let policy = f
+        .system
+        .get_position_policy(adopted[0].exit_position_id)
+        .unwrap();
+    assert_eq!(policy.name.as_deref(), Some("adopted"));
+
+    let trade = f
+        .journal
+        .get_trade_for_position(&manual.position_id)
+        .await
+        .unwrap();
+    assert_eq!(adopted[0].trade_id.as_deref(), Some(trade.id.as_str()));
+    assert!(trade.entry.tags.contains(&EXTERNAL_ORIGIN_TAG.to_string()));
+    assert!(f
+        .orchestrator
+        .tag_registry()
+        .tags_for(&manual.position_id)
+        .contains(&EXTERNAL_ORIGIN_TAG.to_string()));
+
+    // Judged once; nothing more to adopt on the next pass
+    assert!(f
+        .adopter
+        .reconcile("acc-1", &positions, &f.system)
+        .await
+        .is_empty());
+    assert_eq!(f.adopter.adopted().len(), 1);
+}
+
+#[tokio::test]
+async fn operators_adopt_positions_with_their_own_policy() {
+    let f = fixture(false).await;
+    let manual = manual_position("GBPUSD");
+    f.platform.add_position(manual.clone());
+    let positions = f
+        .orchestrator
+        .get_open_positions(Some("acc-1"))
+        .await
+        .unwrap();
+
+    // Automatic adoption is off
+    assert!(f
+        .adopter
+        .reconcile("acc-1", &positions, &f.system)
+        .await
+        .is_empty());
+
+    let policy = ExitPolicy {
+        name: Some("swing".to_string()),
+        ..ExitPolicy::default()
+    };
+    let adopted = f
+        .adopter
+        .adopt(
+            "acc-1",
+            &positions[0],
+            &f.system,
+            Some(policy.clone()),
+            false,
+        )
+        .await;
+
+    assert!(!adopted.automatic);
+    assert_eq!(
+        f.system.get_position_policy(adopted.exit_position_id),
+        Some(policy)
+    );
+    assert!(f.adopter.is_adopted(&manual.position_id));
+
+    // Forgotten once the position closes
+    f.adopter.reconcile("acc-1", &[], &f.system).await;
+    assert!(f.adopter.adopted().is_empty());
+}
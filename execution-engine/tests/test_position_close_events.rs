@@ -0,0 +1,127 @@
+use chrono::Utc;
+use execution_engine::execution::exit_management::ExitAuditLogger;
+use execution_engine::platforms::abstraction::events::{
+    EventData, EventType, PlatformEvent, PositionCloseEventData,
+};
+use execution_engine::platforms::abstraction::models::*;
+use execution_engine::platforms::PlatformType;
+use execution_engine::risk::pnl_calculator::{
+    CurrencyConverter, KafkaProducer, MarketDataStream, PositionTracker, RealTimePnLCalculator,
+    WebSocketPublisher,
+};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn long_position(quantity: rust_decimal::Decimal) -> UnifiedPosition {
+    UnifiedPosition {
+        position_id: Uuid::new_v4().to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity,
+        entry_price: dec!(1.1000),
+        current_price: dec!(1.1050),
+        unrealized_pnl: dec!(0),
+        realized_pnl: dec!(0),
+        margin_used: dec!(0),
+        commission: dec!(0),
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: "acc".to_string(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+fn close_fill(filled: rust_decimal::Decimal, price: rust_decimal::Decimal) -> UnifiedOrderResponse {
+    UnifiedOrderResponse {
+        platform_order_id: "close-1".to_string(),
+        client_order_id: "close_1".to_string(),
+        status: UnifiedOrderStatus::Filled,
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Sell,
+        order_type: UnifiedOrderType::Market,
+        quantity: filled,
+        filled_quantity: filled,
+        remaining_quantity: dec!(0),
+        price: None,
+        average_fill_price: Some(price),
+        commission: Some(dec!(2)),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        filled_at: Some(Utc::now()),
+        platform_specific: HashMap::new(),
+    }
+}
+
+#[test]
+fn test_full_close_emits_position_closed() {
+    let position = long_position(dec!(10000));
+    let close =
+        PositionCloseEventData::from_close_fill(&position, &close_fill(dec!(10000), dec!(1.1050)))
+            .unwrap();
+
+    assert!(close.is_full_close());
+    assert_eq!(close.realized_pnl, dec!(48)); // 50 gross - 2 commission
+    assert_eq!(close.event_type(), EventType::PositionClosed);
+}
+
+#[test]
+fn test_partial_close_emits_position_reduced() {
+    let position = long_position(dec!(10000));
+    let close =
+        PositionCloseEventData::from_close_fill(&position, &close_fill(dec!(4000), dec!(1.1050)))
+            .unwrap();
+
+    assert_eq!(close.remaining_quantity, dec!(6000));
+    assert_eq!(close.event_type(), EventType::PositionReduced);
+
+    let event = PlatformEvent::position_close(PlatformType::TradeLocker, "acc".to_string(), close);
+    assert_eq!(event.event_type, EventType::PositionReduced);
+    assert!(matches!(event.data, EventData::PositionClose(_)));
+}
+
+#[test]
+fn test_unfilled_close_order_produces_no_event() {
+    let position = long_position(dec!(10000));
+    assert!(
+        PositionCloseEventData::from_close_fill(&position, &close_fill(dec!(0), dec!(1.1050)))
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_consumers_record_realized_pnl() {
+    let position = long_position(dec!(10000));
+    let close =
+        PositionCloseEventData::from_close_fill(&position, &close_fill(dec!(4000), dec!(1.1050)))
+            .unwrap();
+    let account_id = Uuid::new_v4();
+    let event = PlatformEvent::position_close(
+        PlatformType::TradeLocker,
+        account_id.to_string(),
+        close.clone(),
+    );
+
+    let logger = ExitAuditLogger::new();
+    logger.handle_platform_event(&event).await.unwrap();
+    assert_eq!(
+        logger.get_realized_pnl(&close.position_id).await,
+        close.realized_pnl
+    );
+
+    let tracker = Arc::new(PositionTracker::new());
+    let calculator = RealTimePnLCalculator::new(
+        tracker.clone(),
+        Arc::new(MarketDataStream::new()),
+        Arc::new(WebSocketPublisher::new()),
+        Arc::new(KafkaProducer),
+        Arc::new(CurrencyConverter::new()),
+    );
+    calculator.handle_platform_event(&event).await.unwrap();
+
+    let account_pnl = calculator.get_account_pnl(account_id).await.unwrap();
+    assert_eq!(account_pnl.realized_pnl_today, close.realized_pnl);
+}
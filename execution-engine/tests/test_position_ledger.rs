@@ -0,0 +1,338 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::ledger::{FileLedgerStore, LedgerEventKind, LedgerState, PositionLedger};
+use execution_engine::platforms::abstraction::events::{
+    EventData, EventType, OrderEventData, PlatformEvent, PositionCloseEventData, PositionEventData,
+};
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::*;
+use execution_engine::platforms::PlatformType;
+use execution_engine::testing::MockTradingPlatform;
+
+fn fill(
+    order_id: &str,
+    position_id: &str,
+    side: UnifiedOrderSide,
+    quantity: Decimal,
+    price: Decimal,
+    commission: Decimal,
+) -> PlatformEvent {
+    let now = Utc::now();
+    let order = UnifiedOrderResponse {
+        platform_order_id: order_id.to_string(),
+        client_order_id: order_id.to_string(),
+        status: UnifiedOrderStatus::Filled,
+        symbol: "EURUSD".to_string(),
+        side,
+        order_type: UnifiedOrderType::Market,
+        quantity,
+        filled_quantity: quantity,
+        remaining_quantity: Decimal::ZERO,
+        price: Some(price),
+        average_fill_price: Some(price),
+        commission: Some(commission),
+        created_at: now,
+        updated_at: now,
+        filled_at: Some(now),
+        platform_specific: HashMap::from([(
+            "position_id".to_string(),
+            serde_json::Value::String(position_id.to_string()),
+        )]),
+    };
+    PlatformEvent::new(
+        EventType::OrderFilled,
+        PlatformType::Mock,
+        "acc-1".to_string(),
+        EventData::Order(OrderEventData {
+            order,
+            previous_status: None,
+            fill_price: Some(price),
+            fill_quantity: Some(quantity),
+            remaining_quantity: Some(Decimal::ZERO),
+            rejection_reason: None,
+        }),
+    )
+}
+
+fn close(
+    position_id: &str,
+    quantity: Decimal,
+    remaining: Decimal,
+    price: Decimal,
+) -> PlatformEvent {
+    PlatformEvent::position_close(
+        PlatformType::Mock,
+        "acc-1".to_string(),
+        PositionCloseEventData {
+            position_id: position_id.to_string(),
+            symbol: "EURUSD".to_string(),
+            side: UnifiedPositionSide::Long,
+            closing_order_id: format!("close-{}", position_id),
+            entry_price: dec!(1.1000),
+            close_price: price,
+            closed_quantity: quantity,
+            remaining_quantity: remaining,
+            realized_pnl: (price - dec!(1.1000)) * quantity,
+            commission: Decimal::ZERO,
+            closed_at: Utc::now(),
+        },
+    )
+}
+
+fn platform_position(
+    position_id: &str,
+    side: UnifiedPositionSide,
+    quantity: Decimal,
+) -> UnifiedPosition {
+    UnifiedPosition {
+        position_id: position_id.to_string(),
+        symbol: "EURUSD".to_string(),
+        side,
+        quantity,
+        entry_price: dec!(1.1000),
+        current_price: dec!(1.1000),
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        stop_loss: Some(dec!(1.0950)),
+        take_profit: None,
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: "acc-1".to_string(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+#[tokio::test]
+async fn positions_and_pnl_are_derived_from_fills_modifications_and_closes() {
+    let ledger = PositionLedger::new();
+
+    let first = fill(
+        "ord-1",
+        "pos-1",
+        UnifiedOrderSide::Buy,
+        dec!(10000),
+        dec!(1.1000),
+        dec!(2),
+    );
+    ledger.handle_platform_event(&first).await;
+    ledger
+        .handle_platform_event(&fill(
+            "ord-2",
+            "pos-1",
+            UnifiedOrderSide::Buy,
+            dec!(10000),
+            dec!(1.1010),
+            Decimal::ZERO,
+        ))
+        .await;
+    // A redelivered event is only applied once
+    ledger.handle_platform_event(&first).await;
+
+    let mut modified = platform_position("pos-1", UnifiedPositionSide::Long, dec!(20000));
+    modified.take_profit = Some(dec!(1.1100));
+    ledger
+        .handle_platform_event(&PlatformEvent::new(
+            EventType::PositionModified,
+            PlatformType::Mock,
+            "acc-1".to_string(),
+            EventData::Position(PositionEventData {
+                position: modified,
+                previous_state: None,
+                trigger_price: None,
+                pnl_change: None,
+            }),
+        ))
+        .await;
+
+    // The close order's own fill is left to the close event
+    ledger
+        .handle_platform_event(&fill(
+            "close-pos-1",
+            "pos-1",
+            UnifiedOrderSide::Sell,
+            dec!(5000),
+            dec!(1.1025),
+            Decimal::ZERO,
+        ))
+        .await;
+    ledger
+        .handle_platform_event(&close("pos-1", dec!(5000), dec!(15000), dec!(1.1025)))
+        .await;
+
+    let position = ledger.position("acc-1", "pos-1").await.unwrap();
+    assert_eq!(position.quantity, dec!(15000));
+    assert_eq!(position.average_price, dec!(1.1005));
+    assert_eq!(
+        (position.stop_loss, position.take_profit),
+        (Some(dec!(1.0950)), Some(dec!(1.1100)))
+    );
+    // 5000 closed 20 pips above the average entry, less the opening commission
+    assert_eq!(ledger.realized_pnl("acc-1").await, dec!(8));
+
+    let history = ledger.position_history("acc-1", "pos-1").await;
+    let kinds: Vec<_> = history
+        .iter()
+        .map(|e| match e.kind {
+            LedgerEventKind::Fill { .. } => "fill",
+            LedgerEventKind::Modified { .. } => "modified",
+            LedgerEventKind::Closed { .. } => "closed",
+            LedgerEventKind::Adjusted { .. } => "adjusted",
+        })
+        .collect();
+    assert_eq!(kinds, vec!["fill", "fill", "modified", "closed"]);
+    assert_eq!(
+        history.iter().map(|e| e.sequence).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4]
+    );
+
+    ledger
+        .handle_platform_event(&close("pos-1", dec!(15000), Decimal::ZERO, dec!(1.0995)))
+        .await;
+    assert!(ledger.positions("acc-1").await.is_empty());
+    assert_eq!(ledger.realized_pnl("acc-1").await, dec!(-7));
+}
+
+#[tokio::test]
+async fn ledgers_rebuild_the_same_state_from_their_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ledger").join("events.jsonl");
+
+    let ledger = PositionLedger::new().with_store(Arc::new(FileLedgerStore::new(&path)));
+    ledger
+        .handle_platform_event(&fill(
+            "ord-1",
+            "pos-1",
+            UnifiedOrderSide::Buy,
+            dec!(10000),
+            dec!(1.1000),
+            dec!(1.5),
+        ))
+        .await;
+    ledger
+        .handle_platform_event(&fill(
+            "ord-2",
+            "pos-2",
+            UnifiedOrderSide::Sell,
+            dec!(3000),
+            dec!(1.1020),
+            Decimal::ZERO,
+        ))
+        .await;
+    ledger
+        .handle_platform_event(&close("pos-1", dec!(4000), dec!(6000), dec!(1.1030)))
+        .await;
+    let before = ledger.state().await;
+
+    // A crash mid-write leaves a torn final line
+    let mut content = std::fs::read_to_string(&path).unwrap();
+    content.push_str("{\"sequence\": 4, \"account_");
+    std::fs::write(&path, content).unwrap();
+
+    let rebuilt = PositionLedger::new().with_store(Arc::new(FileLedgerStore::new(&path)));
+    assert_eq!(rebuilt.load().await.unwrap(), 3);
+    assert_eq!(rebuilt.state().await, before);
+    assert_eq!(LedgerState::replay(&rebuilt.events().await), before);
+    assert_eq!(rebuilt.realized_pnl("acc-1").await, dec!(10.5));
+
+    // Events replayed from the store are not recorded again, and new ones
+    // continue the sequence
+    let events = rebuilt.events().await;
+    let closed = close("pos-2", dec!(3000), Decimal::ZERO, dec!(1.1020));
+    rebuilt.handle_platform_event(&closed).await;
+    let latest = rebuilt.events().await;
+    assert_eq!(latest.len(), events.len() + 1);
+    assert_eq!(latest.last().unwrap().sequence, 4);
+}
+
+#[tokio::test]
+async fn reconciliation_corrects_drift_from_platform_positions() {
+    let platform = MockTradingPlatform::new("acc-1")
+        .with_position(platform_position(
+            "pos-1",
+            UnifiedPositionSide::Long,
+            dec!(10000),
+        ))
+        .with_position(platform_position(
+            "pos-2",
+            UnifiedPositionSide::Long,
+            dec!(5000),
+        ))
+        .with_position(platform_position(
+            "pos-4",
+            UnifiedPositionSide::Short,
+            dec!(2000),
+        ));
+    let ledger = PositionLedger::new();
+    for (position_id, quantity) in [
+        ("pos-1", dec!(10000)),
+        ("pos-2", dec!(8000)),
+        ("pos-3", dec!(1000)),
+    ] {
+        ledger
+            .handle_platform_event(&fill(
+                &format!("ord-{}", position_id),
+                position_id,
+                UnifiedOrderSide::Buy,
+                quantity,
+                dec!(1.1000),
+                Decimal::ZERO,
+            ))
+            .await;
+    }
+
+    let positions = platform.get_positions().await.unwrap();
+    let mut drift = ledger.reconcile("acc-1", &positions).await.unwrap();
+    drift.sort_by(|a, b| a.position_id.cmp(&b.position_id));
+    let found: Vec<_> = drift
+        .iter()
+        .map(|d| {
+            (
+                d.position_id.as_str(),
+                d.ledger_quantity,
+                d.platform_quantity,
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("pos-2", dec!(8000), dec!(5000)),
+            ("pos-3", dec!(1000), Decimal::ZERO),
+            ("pos-4", Decimal::ZERO, dec!(-2000)),
+        ]
+    );
+
+    // The ledger now matches the platform, with the corrections in its history
+    assert!(ledger
+        .reconcile("acc-1", &positions)
+        .await
+        .unwrap()
+        .is_empty());
+    let mut quantities: Vec<_> = ledger
+        .positions("acc-1")
+        .await
+        .into_iter()
+        .map(|p| (p.position_id.clone(), p.signed_quantity()))
+        .collect();
+    quantities.sort();
+    assert_eq!(
+        quantities,
+        vec![
+            ("pos-1".to_string(), dec!(10000)),
+            ("pos-2".to_string(), dec!(5000)),
+            ("pos-4".to_string(), dec!(-2000)),
+        ]
+    );
+    let history = ledger.position_history("acc-1", "pos-3").await;
+    assert!(matches!(
+        history.last().unwrap().kind,
+        LedgerEventKind::Adjusted { quantity, .. } if quantity.is_zero()
+    ));
+    assert_eq!(ledger.realized_pnl("acc-1").await, Decimal::ZERO);
+}
@@ -0,0 +1,383 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use execution_engine::execution::exit_management::{
+    ExitAuditLogger, ExitModification, ExitModificationType, MarketContext,
+};
+use execution_engine::execution::tags::tags_from_metadata;
+use execution_engine::execution::{
+    TagFilter, TagRegistry, TradeExecutionOrchestrator, TradeSignal,
+};
+use execution_engine::journal::{TradeJournal, TradeQuery};
+use execution_engine::platforms::abstraction::{
+    capabilities::PlatformCapabilities,
+    errors::PlatformError,
+    events::PlatformEvent,
+    interfaces::{DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter},
+    models::*,
+};
+use execution_engine::platforms::PlatformType;
+use execution_engine::testing::fixtures;
+
+/// Opens a position for every order it receives
+#[derive(Default)]
+struct MockPlatform {
+    positions: Mutex<Vec<UnifiedPosition>>,
+    orders: Mutex<Vec<UnifiedOrder>>,
+}
+
+impl MockPlatform {
+    fn open_position(&self, symbol: &str) -> UnifiedPosition {
+        let position = UnifiedPosition {
+            position_id: Uuid::new_v4().to_string(),
+            symbol: symbol.to_string(),
+            side: UnifiedPositionSide::Long,
+            quantity: dec!(10000),
+            entry_price: dec!(1.0850),
+            current_price: dec!(1.0850),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_used: Decimal::ZERO,
+            commission: Decimal::ZERO,
+            stop_loss: Some(dec!(1.0800)),
+            take_profit: None,
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            account_id: "acc-1".to_string(),
+            platform_specific: HashMap::new(),
+        };
+        self.positions.lock().unwrap().push(position.clone());
+        position
+    }
+
+    fn unsupported<T>() -> Result<T, PlatformError> {
+        Err(PlatformError::FeatureNotSupported {
+            feature: "mock".to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl ITradingPlatform for MockPlatform {
+    fn platform_type(&self) -> PlatformType {
+        PlatformType::DXTrade
+    }
+    fn platform_name(&self) -> &str {
+        "mock"
+    }
+    fn platform_version(&self) -> &str {
+        "1.0.0"
+    }
+    async fn connect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn disconnect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn is_connected(&self) -> bool {
+        true
+    }
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        Ok(1)
+    }
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        self.orders.lock().unwrap().push(order.clone());
+        let position = self.open_position(&order.symbol);
+        Ok(UnifiedOrderResponse {
+            platform_order_id: position.position_id,
+            client_order_id: order.client_order_id,
+            status: UnifiedOrderStatus::Filled,
+            symbol: order.symbol,
+            side: order.side,
+            order_type: order.order_type,
+            quantity: order.quantity,
+            filled_quantity: order.quantity,
+            remaining_quantity: Decimal::ZERO,
+            price: Some(dec!(1.0850)),
+            average_fill_price: Some(dec!(1.0850)),
+            commission: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            filled_at: Some(Utc::now()),
+            platform_specific: HashMap::new(),
+        })
+    }
+    async fn modify_order(
+        &self,
+        _order_id: &str,
+        _modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn cancel_order(&self, _order_id: &str) -> Result<(), PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_order(&self, _order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_orders(
+        &self,
+        _filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        Ok(self
+            .positions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|p| p.symbol == symbol)
+            .cloned())
+    }
+    async fn close_position(
+        &self,
+        _symbol: &str,
+        _quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        Ok(UnifiedAccountInfo {
+            account_id: "acc-1".to_string(),
+            account_name: None,
+            currency: "USD".to_string(),
+            balance: dec!(100000),
+            equity: dec!(100000),
+            margin_used: Decimal::ZERO,
+            margin_available: dec!(100000),
+            buying_power: dec!(100000),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_level: None,
+            account_type: AccountType::Demo,
+            last_updated: Utc::now(),
+            platform_specific: HashMap::new(),
+        })
+    }
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        Ok(dec!(100000))
+    }
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_market_data(&self, _symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        Self::unsupported()
+    }
+    async fn subscribe_market_data(
+        &self,
+        _symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn unsubscribe_market_data(&self, _symbols: Vec<String>) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    fn capabilities(&self) -> PlatformCapabilities {
+        PlatformCapabilities::new("mock".to_string())
+    }
+    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_event_history(
+        &self,
+        _filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        Self::unsupported()
+    }
+}
+
+fn metadata(entries: &[(&str, &str)]) -> HashMap<String, String> {
+    entries
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn signal(id: &str, symbol: &str, metadata: HashMap<String, String>) -> TradeSignal {
+    TradeSignal {
+        symbol: symbol.to_string(),
+        entry_price: 1.0850,
+        stop_loss: 1.0800,
+        take_profit: 1.0950,
+        metadata,
+        ..fixtures::signal(id)
+    }
+}
+
+fn strings(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|t| t.to_string()).collect()
+}
+
+async fn execute(orchestrator: &TradeExecutionOrchestrator, signal: TradeSignal) {
+    let mut plan = orchestrator.process_signal(signal).await.unwrap();
+    for assignment in &mut plan.account_assignments {
+        assignment.entry_timing_delay = Duration::ZERO;
+    }
+    let results = orchestrator.execute_plan(&plan).await;
+    assert!(results.iter().all(|r| r.success && r.tags == plan.tags));
+}
+
+#[test]
+fn test_tags_from_metadata_and_filter() {
+    let tags = tags_from_metadata(&metadata(&[
+        ("strategy", "breakout"),
+        ("experiment_id", "exp-7"),
+        ("agent", " "),
+        ("tags", "london, news,,london"),
+        ("confidence_source", "ignored"),
+    ]));
+    assert_eq!(
+        tags,
+        strings(&["strategy:breakout", "experiment_id:exp-7", "london", "news"])
+    );
+
+    assert!(TagFilter::parse(None).matches(&[]));
+    assert!(TagFilter::parse(Some("experiment_id:exp-7, news")).matches(&tags));
+    assert!(!TagFilter::parse(Some("experiment_id:exp-7,asia")).matches(&tags));
+}
+
+#[tokio::test]
+async fn test_experiments_on_one_account_are_told_apart() {
+    let platform = Arc::new(MockPlatform::default());
+    let orchestrator = TradeExecutionOrchestrator::new();
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+
+    execute(
+        &orchestrator,
+        signal(
+            "sig-a",
+            "EURUSD",
+            metadata(&[("strategy", "breakout"), ("experiment_id", "a")]),
+        ),
+    )
+    .await;
+    execute(
+        &orchestrator,
+        signal(
+            "sig-b",
+            "EURUSD",
+            metadata(&[("strategy", "breakout"), ("experiment_id", "b")]),
+        ),
+    )
+    .await;
+
+    // Orders carry the tags, and the strategy tag becomes the strategy id
+    let orders = platform.orders.lock().unwrap().clone();
+    assert_eq!(
+        orders[0].metadata.tags,
+        strings(&["strategy:breakout", "experiment_id:a"])
+    );
+    assert_eq!(orders[0].metadata.strategy_id.as_deref(), Some("breakout"));
+
+    let experiment_a = TagFilter::parse(Some("experiment_id:a"));
+    let positions = orchestrator
+        .get_tagged_positions(Some("acc-1"), &experiment_a)
+        .await
+        .unwrap();
+    assert_eq!(positions.len(), 1);
+    assert_eq!(
+        positions[0].position.position_id,
+        platform.positions.lock().unwrap()[0].position_id
+    );
+    let all = orchestrator
+        .get_tagged_positions(None, &TagFilter::parse(Some("strategy:breakout")))
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 2);
+
+    let history = orchestrator
+        .query_execution_history(100, &TagFilter::parse(Some("experiment_id:b")))
+        .await;
+    let actions: Vec<(&str, &str)> = history
+        .iter()
+        .map(|e| (e.signal_id.as_str(), e.action.as_str()))
+        .collect();
+    assert_eq!(
+        actions,
+        vec![("sig-b", "PLAN_CREATED"), ("sig-b", "EXECUTION_SUCCESS")]
+    );
+    assert_eq!(orchestrator.get_execution_history(100).await.len(), 4);
+}
+
+#[tokio::test]
+async fn test_exit_audit_and_journal_carry_position_tags() {
+    let registry = Arc::new(TagRegistry::new());
+    let tagged = Uuid::new_v4();
+    let untagged = Uuid::new_v4();
+    registry.set("acc-1", &tagged.to_string(), strings(&["experiment_id:a"]));
+
+    let logger = ExitAuditLogger::new().with_tag_registry(registry.clone());
+    for position_id in [tagged, untagged] {
+        logger
+            .log_exit_modification(ExitModification {
+                position_id,
+                modification_type: ExitModificationType::BreakEven,
+                old_value: dec!(1.0800),
+                new_value: dec!(1.0850),
+                reasoning: "test".to_string(),
+                market_context: MarketContext {
+                    current_price: dec!(1.0900),
+                    atr_14: dec!(0.001),
+                    trend_strength: 0.5,
+                    volatility: 0.01,
+                    spread: dec!(0.0001),
+                    timestamp: Utc::now(),
+                    structure: None,
+                    session: None,
+                    recent_range: None,
+                    news: None,
+                },
+            })
+            .await
+            .unwrap();
+    }
+    let exits = logger
+        .get_recent_exits_matching(10, &TagFilter::parse(Some("experiment_id:a")))
+        .await
+        .unwrap();
+    assert_eq!(exits.len(), 1);
+    assert_eq!(exits[0].position_id, tagged);
+    assert_eq!(exits[0].tags, strings(&["experiment_id:a"]));
+    assert_eq!(logger.get_recent_exits(10).await.unwrap().len(), 2);
+
+    let platform = MockPlatform::default();
+    let mut position = platform.open_position("EURUSD");
+    position.position_id = tagged.to_string();
+    let journal = TradeJournal::new().with_tag_registry(registry);
+    journal.record_open("acc-1", &position).await;
+    journal
+        .record_open("acc-1", &platform.open_position("EURUSD"))
+        .await;
+
+    let trades = journal
+        .query(&TradeQuery {
+            tags: Some("experiment_id:a".to_string()),
+            ..Default::default()
+        })
+        .await;
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].entry.position_id, tagged.to_string());
+}
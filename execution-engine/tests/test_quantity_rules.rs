@@ -0,0 +1,147 @@
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use execution_engine::execution::{TradeExecutionOrchestrator, TradeSignal};
+use execution_engine::platforms::abstraction::capabilities::{
+    tradelocker_capabilities, PlatformCapabilities, QuantityLimits, QuantityRules,
+};
+use execution_engine::platforms::abstraction::errors::ValidationError;
+use execution_engine::platforms::abstraction::models::{
+    OrderMetadata, UnifiedOrder, UnifiedOrderSide, UnifiedOrderType, UnifiedTimeInForce,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+fn order(symbol: &str, quantity: rust_decimal::Decimal) -> UnifiedOrder {
+    UnifiedOrder {
+        client_order_id: uuid::Uuid::new_v4().to_string(),
+        symbol: symbol.to_string(),
+        side: UnifiedOrderSide::Buy,
+        order_type: UnifiedOrderType::Market,
+        quantity,
+        price: None,
+        stop_price: None,
+        stop_loss: None,
+        take_profit: None,
+        time_in_force: UnifiedTimeInForce::Gtc,
+        account_id: None,
+        metadata: OrderMetadata {
+            strategy_id: None,
+            signal_id: None,
+            risk_parameters: HashMap::new(),
+            tags: Vec::new(),
+            expires_at: None,
+        },
+        max_slippage: None,
+    }
+}
+
+#[test]
+fn quantities_round_down_to_the_lot_step() {
+    let limits = QuantityLimits::new(dec!(0.01), Some(dec!(50)), dec!(0.01));
+
+    assert_eq!(limits.normalize("EURUSD", dec!(0.257)).unwrap(), dec!(0.25));
+    assert_eq!(limits.normalize("EURUSD", dec!(1.00)).unwrap(), dec!(1));
+    assert_eq!(limits.normalize("EURUSD", dec!(50)).unwrap(), dec!(50));
+
+    for quantity in [dec!(0.004), dec!(50.01), dec!(0), dec!(-1)] {
+        let error = limits.normalize("EURUSD", quantity).unwrap_err();
+        assert!(
+            matches!(error, ValidationError::UntradableQuantity { .. }),
+            "{:?}",
+            error
+        );
+    }
+    let error = limits.normalize("EURUSD", dec!(0.004)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Quantity 0.004 of EURUSD is not tradable: 0 after rounding down to the lot step of 0.01 is below the minimum of 0.01"
+    );
+
+    // A step finer than the minimum still rejects sizes that snap below it
+    let limits = QuantityLimits::new(dec!(0.1), None, dec!(0.01));
+    assert!(limits.normalize("EURUSD", dec!(0.099)).is_err());
+    assert_eq!(limits.normalize("EURUSD", dec!(0.109)).unwrap(), dec!(0.1));
+}
+
+#[test]
+fn symbols_override_the_platform_rules() {
+    let mut capabilities = PlatformCapabilities::new("broker".to_string());
+    capabilities.max_order_size = Some(dec!(20));
+    capabilities.quantity_rules = QuantityRules::new(QuantityLimits {
+        min: Some(dec!(0.01)),
+        max: None,
+        step: Some(dec!(0.01)),
+    })
+    .with_symbol("US30", QuantityLimits::new(dec!(1), Some(dec!(5)), dec!(1)));
+
+    // The platform-wide maximum fills in the bound the rules leave open
+    assert_eq!(capabilities.quantity_limits("EURUSD").max, Some(dec!(20)));
+    assert_eq!(capabilities.quantity_limits("US30").max, Some(dec!(5)));
+
+    let mut index = order("US30", dec!(2.7));
+    assert_eq!(
+        capabilities.normalize_order_quantity(&mut index).unwrap(),
+        dec!(2)
+    );
+    assert_eq!(index.quantity, dec!(2));
+    assert!(capabilities
+        .normalize_order_quantity(&mut order("US30", dec!(0.5)))
+        .is_err());
+
+    // Without any rules quantities pass through untouched
+    let open = PlatformCapabilities::new("open".to_string());
+    let mut fx = order("EURUSD", dec!(0.12345));
+    open.normalize_order_quantity(&mut fx).unwrap();
+    assert_eq!(fx.quantity, dec!(0.12345));
+}
+
+#[tokio::test]
+async fn orchestrator_snaps_sizes_and_rejects_untradable_ones() {
+    let platform = Arc::new(
+        MockTradingPlatform::new("acc-1")
+            .with_capabilities(tradelocker_capabilities())
+            .with_quote("GBPUSD", dec!(1.2500), dec!(1.2502)),
+    );
+    let orchestrator = TradeExecutionOrchestrator::new();
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+
+    let mut plan = orchestrator
+        .process_signal(TradeSignal {
+            id: "sig-1".to_string(),
+            symbol: "GBPUSD".to_string(),
+            side: UnifiedOrderSide::Buy,
+            entry_price: 1.2500,
+            stop_loss: 1.2450,
+            take_profit: 1.2600,
+            confidence: 0.8,
+            risk_reward_ratio: 2.0,
+            signal_time: SystemTime::now(),
+            metadata: HashMap::new(),
+        })
+        .await
+        .unwrap();
+    for assignment in &mut plan.account_assignments {
+        assignment.entry_timing_delay = Duration::ZERO;
+        assignment.position_size = 0.257;
+    }
+    let results = orchestrator.execute_plan(&plan).await;
+    assert!(results[0].success, "{:?}", results[0].error_message);
+    assert_eq!(platform.orders()[0].quantity, dec!(0.25));
+
+    for assignment in &mut plan.account_assignments {
+        assignment.position_size = 0.004;
+    }
+    let results = orchestrator.execute_plan(&plan).await;
+    assert!(!results[0].success);
+    assert!(results[0]
+        .error_message
+        .as_deref()
+        .unwrap()
+        .contains("not tradable"));
+    assert_eq!(platform.orders().len(), 1);
+}
@@ -0,0 +1,126 @@
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::alerting::{AlertGateway, AlertingConfig};
+use execution_engine::platforms::abstraction::{
+    ITradingPlatform, PlatformError, QuoteFilterConfig, QuoteFilteringPlatform, QuoteRejection,
+    QuoteValidator, UnifiedMarketData,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+fn quote(bid: Decimal, ask: Decimal, age_ms: i64) -> UnifiedMarketData {
+    UnifiedMarketData {
+        symbol: "EURUSD".to_string(),
+        bid,
+        ask,
+        spread: ask - bid,
+        last_price: None,
+        volume: None,
+        high: None,
+        low: None,
+        timestamp: Utc::now() - Duration::milliseconds(age_ms),
+        session: None,
+        platform_specific: HashMap::new(),
+    }
+}
+
+#[test]
+fn malformed_and_stale_quotes_are_rejected() {
+    let validator = QuoteValidator::new(QuoteFilterConfig::default());
+    let now = Utc::now();
+
+    assert!(matches!(
+        validator.check(&quote(dec!(1.1000), dec!(1.1001), 30_000), now),
+        Err(QuoteRejection::Stale { age_ms }) if age_ms > 29_000
+    ));
+    assert_eq!(
+        validator.check(&quote(Decimal::ZERO, dec!(1.1001), 0), now),
+        Err(QuoteRejection::NonPositive)
+    );
+    assert_eq!(
+        validator.check(&quote(dec!(1.1005), dec!(1.1001), 0), now),
+        Err(QuoteRejection::Crossed {
+            bid: dec!(1.1005),
+            ask: dec!(1.1001)
+        })
+    );
+    assert!(matches!(
+        validator.check(&quote(dec!(1.0500), dec!(1.1500), 0), now),
+        Err(QuoteRejection::WideSpread { .. })
+    ));
+    assert!(validator
+        .check(&quote(dec!(1.1000), dec!(1.1001), 500), now)
+        .is_ok());
+}
+
+#[test]
+fn deviating_prices_need_confirmation_before_they_are_accepted() {
+    let validator = QuoteValidator::new(QuoteFilterConfig::default());
+    let now = Utc::now();
+    validator
+        .check(&quote(dec!(1.1000), dec!(1.1002), 0), now)
+        .unwrap();
+
+    // A fat-fingered print is dropped and the old level still holds
+    assert!(matches!(
+        validator.check(&quote(dec!(11.000), dec!(11.002), 0), now),
+        Err(QuoteRejection::Deviation { last_mid, .. }) if last_mid == dec!(1.1001)
+    ));
+    validator
+        .check(&quote(dec!(1.1004), dec!(1.1006), 0), now)
+        .unwrap();
+
+    // Scattered bad ticks never confirm each other
+    for bid in [dec!(1.3000), dec!(0.9000), dec!(1.3000)] {
+        assert!(validator.check(&quote(bid, bid, 0), now).is_err());
+    }
+
+    // A real gap is accepted once enough quotes agree on it
+    let gap = [dec!(1.1500), dec!(1.1502), dec!(1.1501)];
+    assert!(validator.check(&quote(gap[0], gap[0], 0), now).is_err());
+    assert!(validator.check(&quote(gap[1], gap[1], 0), now).is_err());
+    assert!(validator.check(&quote(gap[2], gap[2], 0), now).is_ok());
+    assert!(validator
+        .check(&quote(dec!(1.1503), dec!(1.1505), 0), now)
+        .is_ok());
+}
+
+#[tokio::test]
+async fn bad_quotes_are_quarantined_and_alerted_instead_of_served() {
+    let mock = Arc::new(MockTradingPlatform::new("acc-1").with_quote(
+        "EURUSD",
+        dec!(1.1000),
+        dec!(1.1002),
+    ));
+    let gateway = Arc::new(AlertGateway::new(AlertingConfig::default()));
+    let platform = QuoteFilteringPlatform::new(mock.clone(), "acc-1", QuoteFilterConfig::default())
+        .with_alert_gateway(gateway.clone());
+
+    assert_eq!(
+        platform.get_market_data("EURUSD").await.unwrap().bid,
+        dec!(1.1000)
+    );
+
+    mock.set_quote("EURUSD", dec!(1.1030), dec!(1.1010));
+    let error = platform.get_market_data("EURUSD").await.unwrap_err();
+    assert!(matches!(error, PlatformError::MarketDataUnavailable { .. }));
+
+    let quarantined = platform.quarantined();
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].rejection.reason(), "crossed");
+    assert_eq!(quarantined[0].quote.ask, dec!(1.1010));
+
+    tokio::task::yield_now().await;
+    let active = gateway.active_alerts();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].alert.alert_type, "bad_quote");
+    assert_eq!(active[0].alert.key, "acc-1:EURUSD");
+
+    // The next good quote clears the alert
+    mock.set_quote("EURUSD", dec!(1.1001), dec!(1.1003));
+    platform.get_market_data("EURUSD").await.unwrap();
+    assert!(gateway.active_alerts().is_empty());
+}
@@ -0,0 +1,164 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use execution_engine::execution::recovery_mode::RECOVERY_MODE_ACTION;
+use execution_engine::execution::{
+    RecoveryMode, RecoveryModeConfig, TradeExecutionOrchestrator, TradeSignal,
+};
+use execution_engine::risk::{DrawdownLadder, DrawdownLadderConfig};
+use execution_engine::testing::{fixtures, MockTradingPlatform};
+
+fn enabled() -> RecoveryModeConfig {
+    RecoveryModeConfig {
+        enabled: true,
+        ..RecoveryModeConfig::default()
+    }
+}
+
+fn signal(id: &str, confidence: f64, risk_reward_ratio: f64) -> TradeSignal {
+    TradeSignal {
+        take_profit: 1.1 + 0.01 * risk_reward_ratio,
+        confidence,
+        risk_reward_ratio,
+        ..fixtures::signal(id)
+    }
+}
+
+async fn orchestrator(config: RecoveryModeConfig) -> TradeExecutionOrchestrator {
+    let orchestrator = TradeExecutionOrchestrator::new().with_recovery_mode(config);
+    for account_id in ["acc-1", "acc-2"] {
+        orchestrator
+            .register_account(
+                account_id.to_string(),
+                Arc::new(MockTradingPlatform::new(account_id)),
+                100000.0,
+            )
+            .await
+            .unwrap();
+    }
+    orchestrator
+}
+
+#[test]
+fn recovery_lasts_its_days_and_holds_signals_to_its_constraints() {
+    let recovery = RecoveryMode::new(enabled());
+    let now = Utc::now();
+
+    assert!(recovery.trigger(0.02, None).is_none());
+    assert_eq!(
+        recovery.trigger(0.035, None).as_deref(),
+        Some("daily drawdown of 3.50% reached 3.00%")
+    );
+    let period = recovery.enter("acc-1", "large loss".to_string(), now);
+    assert_eq!(period.ends_at, now + Duration::days(5));
+    assert!(recovery.period("acc-1", now + Duration::days(4)).is_some());
+    assert!(recovery.period("acc-1", now + Duration::days(5)).is_none());
+    assert!(recovery.periods(now).is_empty());
+
+    assert!(recovery.refusal(0.8, 3.0, 0).is_none());
+    assert!(recovery
+        .refusal(0.7, 3.0, 0)
+        .unwrap()
+        .starts_with("confidence 0.70"));
+    assert!(recovery
+        .refusal(0.8, 2.0, 0)
+        .unwrap()
+        .starts_with("risk reward 2.00"));
+    assert!(recovery.refusal(0.8, 3.0, 1).is_some());
+
+    assert!(RecoveryModeConfig {
+        max_positions: 0,
+        ..enabled()
+    }
+    .validate()
+    .is_err());
+    assert!(RecoveryMode::new(RecoveryModeConfig::default())
+        .trigger(0.5, None)
+        .is_none());
+}
+
+#[tokio::test]
+async fn accounts_in_recovery_drop_out_of_plans_the_signal_falls_short_for() {
+    let orchestrator = orchestrator(enabled()).await;
+    orchestrator
+        .recovery_mode()
+        .enter("acc-2", "large loss".to_string(), Utc::now());
+
+    let plan = orchestrator
+        .process_signal(signal("sig-1", 0.8, 2.0))
+        .await
+        .unwrap();
+    let accounts: Vec<&str> = plan
+        .account_assignments
+        .iter()
+        .map(|a| a.account_id.as_str())
+        .collect();
+    assert_eq!(accounts, vec!["acc-1"]);
+
+    let history = orchestrator.get_execution_history(10).await;
+    let entry = history
+        .iter()
+        .find(|e| e.action == RECOVERY_MODE_ACTION)
+        .unwrap();
+    assert!(entry
+        .decision_rationale
+        .starts_with("acc-2: recovery mode until"));
+    assert!(entry
+        .decision_rationale
+        .ends_with("refused the signal: risk reward 2.00 is below the 2.50 required in recovery"));
+
+    let plan = orchestrator
+        .process_signal(signal("sig-2", 0.9, 3.0))
+        .await
+        .unwrap();
+    assert_eq!(plan.account_assignments.len(), 2);
+
+    orchestrator
+        .recovery_mode()
+        .enter("acc-1", "large loss".to_string(), Utc::now());
+    let refused = orchestrator
+        .process_signal(signal("sig-3", 0.6, 3.0))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        refused,
+        "Recovery mode refused the signal for every account"
+    );
+}
+
+#[tokio::test]
+async fn stepping_down_the_ladder_starts_recovery_but_a_preview_does_not() {
+    let ladder = DrawdownLadder::new(DrawdownLadderConfig {
+        enabled: true,
+        ..DrawdownLadderConfig::default()
+    });
+    let orchestrator = orchestrator(RecoveryModeConfig {
+        trigger_rung: Some(2),
+        ..enabled()
+    })
+    .await
+    .with_drawdown_ladder(ladder.clone());
+    ladder.move_to("acc-1", 1, Utc::now());
+    ladder.move_to("acc-2", 2, Utc::now());
+
+    let preview = orchestrator.preview_signal(signal("sig-1", 0.5, 3.0)).await;
+    let decision = preview
+        .accounts
+        .iter()
+        .find(|a| a.account_id == "acc-2")
+        .unwrap();
+    assert!(decision
+        .adjustments
+        .iter()
+        .any(|note| note.contains("(stepped down to drawdown rung 2) refused the signal")));
+    assert_eq!(preview.plan.unwrap().account_assignments.len(), 1);
+    assert!(orchestrator.recovery_mode().periods(Utc::now()).is_empty());
+
+    orchestrator
+        .process_signal(signal("sig-2", 0.5, 3.0))
+        .await
+        .unwrap();
+    let periods = orchestrator.recovery_mode().periods(Utc::now());
+    assert_eq!(periods.len(), 1);
+    assert_eq!(periods[0].account_id, "acc-2");
+}
@@ -0,0 +1,273 @@
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use execution_engine::execution::exit_management::{ExitModificationType, NewsEvent};
+use execution_engine::execution::orchestrator::TradeSignal;
+use execution_engine::recording::replay::{self, AuditDiff};
+use execution_engine::recording::{load_day, EventRecorder, RecordedEntry, RecordedEvent};
+use execution_engine::testing::{SimulationHarness, SimulationScenario};
+use rust_decimal_macros::dec;
+use std::time::SystemTime;
+
+fn scenario_path(name: &str) -> String {
+    format!(
+        "{}/tests/scenarios/simulation/{}.yaml",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    )
+}
+
+/// The recording the engine would have made of a day that ran like `scenario`,
+/// with the audit trail the scenario produces
+async fn record_scenario(scenario: &SimulationScenario) -> Vec<RecordedEntry> {
+    let start = scenario.start.unwrap();
+    let at = |seconds: u64| start + Duration::seconds(seconds as i64);
+
+    let mut entries: Vec<RecordedEntry> = scenario
+        .accounts
+        .iter()
+        .map(|account| RecordedEntry {
+            at: start,
+            event: RecordedEvent::Account {
+                account_id: account.id.clone(),
+                balance: account.balance,
+            },
+        })
+        .collect();
+    for price in &scenario.prices {
+        entries.push(RecordedEntry {
+            at: at(price.at),
+            event: RecordedEvent::Tick {
+                symbol: price.symbol.clone(),
+                bid: price.bid,
+                ask: price.ask,
+            },
+        });
+    }
+    for signal in &scenario.signals {
+        entries.push(RecordedEntry {
+            at: at(signal.at),
+            event: RecordedEvent::Signal {
+                signal: TradeSignal {
+                    id: signal.id.clone(),
+                    symbol: signal.symbol.clone(),
+                    side: signal.side.clone(),
+                    entry_price: signal.entry_price,
+                    stop_loss: signal.stop_loss,
+                    take_profit: signal.take_profit,
+                    confidence: signal.confidence,
+                    risk_reward_ratio: 2.0,
+                    signal_time: SystemTime::from(at(signal.at)),
+                    metadata: signal.metadata.clone(),
+                },
+            },
+        });
+    }
+    for news in &scenario.news {
+        let event = NewsEvent {
+            id: news.id.clone(),
+            description: news.description.clone(),
+            currency: news.currency.clone(),
+            impact: news.impact.clone(),
+            time: at(news.at + news.release_in),
+        };
+        // Fetched again a minute later, as the calendar poll would
+        for seen in [news.at, news.at + 60] {
+            entries.push(RecordedEntry {
+                at: at(seen),
+                event: RecordedEvent::News {
+                    event: event.clone(),
+                },
+            });
+        }
+    }
+
+    let report = SimulationHarness::new(scenario.clone())
+        .await
+        .unwrap()
+        .run()
+        .await
+        .unwrap();
+    for entry in report.audit_entries.values().flatten() {
+        entries.push(RecordedEntry {
+            at: entry.timestamp,
+            event: RecordedEvent::Audit {
+                entry: entry.clone(),
+            },
+        });
+    }
+    entries.sort_by_key(|entry| entry.at);
+    entries
+}
+
+fn recorded_scenario() -> SimulationScenario {
+    let mut scenario = SimulationScenario::load(scenario_path("news_then_break_even")).unwrap();
+    scenario.start = Some(Utc.with_ymd_and_hms(2026, 3, 10, 8, 0, 0).unwrap());
+    scenario
+}
+
+#[tokio::test]
+async fn recorded_day_converts_to_scenario() {
+    let scenario = recorded_scenario();
+    let entries = record_scenario(&scenario).await;
+    let date = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+
+    let replayed = replay::scenario_from_day(date, &entries).unwrap();
+    assert_eq!(replayed.start, scenario.start);
+    assert_eq!(replayed.accounts.len(), 2);
+    assert_eq!(replayed.prices.len(), 3);
+    assert_eq!(replayed.signals[0].at, 60);
+    // Repeated fetches of the same release are one news step
+    assert_eq!(replayed.news.len(), 2);
+    assert_eq!(replayed.news[1].at, 1800);
+    assert_eq!(replayed.news[1].release_in, 900);
+}
+
+#[tokio::test]
+async fn replay_reproduces_recorded_audit_trail() {
+    let entries = record_scenario(&recorded_scenario()).await;
+    let date = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|entry| serde_json::to_string(entry).unwrap())
+        .collect();
+    std::fs::write(
+        execution_engine::recording::day_path(dir.path(), date),
+        lines.join("\n"),
+    )
+    .unwrap();
+
+    let report = replay::replay_day(dir.path(), date, 0.0).await.unwrap();
+    assert_eq!(report.successful_executions, 2);
+    assert!(report.audit.is_empty(), "{}", report.render());
+    assert!(report
+        .audit
+        .counts
+        .iter()
+        .all(|count| count.original == count.replayed));
+    assert!(report
+        .render()
+        .contains("reproduced the recorded audit trail"));
+}
+
+#[tokio::test]
+async fn replay_reports_diverging_audit_entries() {
+    let mut entries = record_scenario(&recorded_scenario()).await;
+    // On the day, one break-even moved the stop elsewhere and the other never happened
+    let mut break_evens = entries
+        .iter_mut()
+        .filter_map(|entry| match &mut entry.event {
+            RecordedEvent::Audit { entry }
+                if entry.modification_type == ExitModificationType::BreakEven =>
+            {
+                Some(entry)
+            }
+            _ => None,
+        });
+    break_evens.next().unwrap().new_value = dec!(1.0900);
+    let dropped = break_evens.next().unwrap().entry_id;
+    entries.retain(|entry| {
+        !matches!(&entry.event, RecordedEvent::Audit { entry } if entry.entry_id == dropped)
+    });
+    let date = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+
+    let report = replay::replay_entries(date, &entries, 0.0).await.unwrap();
+    let changes: Vec<_> = report
+        .audit
+        .changes
+        .iter()
+        .filter(|change| change.modification_type == ExitModificationType::BreakEven)
+        .collect();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(
+        changes[0].original.as_ref().unwrap().new_value,
+        dec!(1.0900)
+    );
+    assert!(changes[1].original.is_none());
+    assert!(changes[1].replayed.is_some());
+    assert!(report.render().contains("2 differences"));
+}
+
+#[test]
+fn identical_trails_have_no_diff() {
+    let diff = AuditDiff::between(&[], &[]);
+    assert!(diff.is_empty());
+    assert!(diff.counts.is_empty());
+}
+
+#[tokio::test]
+async fn recorder_writes_day_files_that_load_back() {
+    let dir = tempfile::tempdir().unwrap();
+    let recorder = EventRecorder::new(dir.path());
+    recorder
+        .record(RecordedEvent::Account {
+            account_id: "alpha".to_string(),
+            balance: dec!(10000),
+        })
+        .await;
+    recorder
+        .record(RecordedEvent::Tick {
+            symbol: "EURUSD".to_string(),
+            bid: dec!(1.0850),
+            ask: dec!(1.0852),
+        })
+        .await;
+
+    let today = Utc::now().date_naive();
+    // A torn line from a crash mid-write is skipped
+    let path = execution_engine::recording::day_path(dir.path(), today);
+    let mut content = std::fs::read_to_string(&path).unwrap();
+    content.push_str("{\"at\":\"2026-");
+    std::fs::write(&path, content).unwrap();
+
+    let entries = load_day(dir.path(), today).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert!(matches!(
+        &entries[1].event,
+        RecordedEvent::Tick { symbol, .. } if symbol == "EURUSD"
+    ));
+
+    let error = replay::replay_day(dir.path(), today.pred_opt().unwrap(), 0.0)
+        .await
+        .unwrap_err();
+    assert!(format!("{:#}", error).contains("No recording"));
+}
+
+#[tokio::test]
+async fn replay_needs_recorded_balances() {
+    let entries = vec![RecordedEntry {
+        at: Utc::now(),
+        event: RecordedEvent::Tick {
+            symbol: "EURUSD".to_string(),
+            bid: dec!(1.0850),
+            ask: dec!(1.0852),
+        },
+    }];
+    let error = replay::scenario_from_day(Utc::now().date_naive(), &entries).unwrap_err();
+    assert!(error.to_string().contains("No account balances"));
+}
+
+#[test]
+fn parse_replay_arguments() {
+    let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+    let options = replay::parse_args(args(&[
+        "--date",
+        "2026-03-10",
+        "--dir",
+        "/tmp/rec",
+        "--speed",
+        "60",
+        "--json",
+    ]))
+    .unwrap()
+    .unwrap();
+    assert_eq!(options.date, NaiveDate::from_ymd_opt(2026, 3, 10).unwrap());
+    assert_eq!(options.dir, std::path::PathBuf::from("/tmp/rec"));
+    assert_eq!(options.speed, 60.0);
+    assert!(options.json);
+
+    assert!(replay::parse_args(args(&["--help"])).unwrap().is_none());
+    assert!(replay::parse_args(args(&["--dir", "/tmp"])).is_err());
+    assert!(replay::parse_args(args(&["--date", "10/03/2026"])).is_err());
+    assert!(replay::parse_args(args(&["--date", "2026-03-10", "--speed", "-1"])).is_err());
+}
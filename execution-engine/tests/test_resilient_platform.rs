@@ -0,0 +1,321 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use execution_engine::execution::TradeExecutionOrchestrator;
+use execution_engine::platforms::abstraction::errors::PlatformError;
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::{
+    OrderMetadata, UnifiedOrder, UnifiedOrderSide, UnifiedOrderType, UnifiedPosition,
+    UnifiedPositionSide, UnifiedTimeInForce,
+};
+use execution_engine::platforms::abstraction::{
+    as_emergency, BreakerSettings, CircuitBreakerState, OperationClass, PerformanceMonitor,
+    ResilienceConfig, ResilientPlatform, RetryConfig,
+};
+use execution_engine::testing::{MockTradingPlatform, Operation};
+
+fn config() -> ResilienceConfig {
+    ResilienceConfig {
+        enabled: true,
+        timeout_ms: 100,
+        retry: RetryConfig {
+            max_retries: 2,
+            initial_delay_ms: 1,
+            max_delay_ms: 5,
+            jitter: false,
+            ..RetryConfig::default()
+        },
+        breaker: BreakerSettings {
+            failure_threshold: 3,
+            open_timeout_secs: 60,
+            ..BreakerSettings::default()
+        },
+        ..ResilienceConfig::default()
+    }
+}
+
+/// Retryable, and asks for next to no wait before the retry
+fn throttled() -> PlatformError {
+    PlatformError::RateLimitExceeded { retry_after_ms: 1 }
+}
+
+fn market_order() -> UnifiedOrder {
+    UnifiedOrder {
+        client_order_id: "o-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Buy,
+        order_type: UnifiedOrderType::Market,
+        quantity: dec!(10000),
+        price: None,
+        stop_price: None,
+        take_profit: None,
+        stop_loss: None,
+        time_in_force: UnifiedTimeInForce::Ioc,
+        account_id: Some("acc-1".to_string()),
+        metadata: OrderMetadata {
+            strategy_id: None,
+            signal_id: None,
+            risk_parameters: std::collections::HashMap::new(),
+            tags: Vec::new(),
+            expires_at: None,
+        },
+        max_slippage: None,
+    }
+}
+
+#[tokio::test]
+async fn reads_are_retried_and_every_attempt_is_timed() {
+    let mock = Arc::new(MockTradingPlatform::new("acc-1"));
+    let platform = ResilientPlatform::new(mock.clone(), config())
+        .with_monitor(PerformanceMonitor::new().with_platform("acc-1"));
+    mock.script_error(Operation::GetAccountInfo, throttled());
+    mock.script_error(Operation::GetAccountInfo, throttled());
+
+    platform.get_account_info().await.unwrap();
+
+    assert_eq!(mock.calls(Operation::GetAccountInfo), 3);
+    let metrics = platform.monitor().unwrap().get_metrics();
+    assert_eq!(metrics.operations["get_account_info"].count, 3);
+    assert_eq!(metrics.operations["get_account_info"].error_count, 2);
+    assert_eq!(
+        platform.breakers().combined_state(),
+        CircuitBreakerState::Closed
+    );
+}
+
+#[tokio::test]
+async fn writes_are_not_retried_unless_configured() {
+    let mock = Arc::new(MockTradingPlatform::new("acc-1").with_quote(
+        "EURUSD",
+        dec!(1.0999),
+        dec!(1.1001),
+    ));
+    let platform = ResilientPlatform::new(mock.clone(), config());
+    mock.script_error(Operation::PlaceOrder, throttled());
+
+    assert!(platform.place_order(market_order()).await.is_err());
+    assert_eq!(mock.calls(Operation::PlaceOrder), 1);
+    assert!(mock.orders().is_empty());
+
+    let platform = ResilientPlatform::new(
+        mock.clone(),
+        ResilienceConfig {
+            retry_writes: true,
+            ..config()
+        },
+    );
+    mock.script_error(Operation::PlaceOrder, throttled());
+    platform.place_order(market_order()).await.unwrap();
+    assert_eq!(mock.calls(Operation::PlaceOrder), 3);
+    assert_eq!(mock.orders().len(), 1);
+}
+
+#[tokio::test]
+async fn slow_calls_time_out() {
+    let mock = Arc::new(MockTradingPlatform::new("acc-1").with_latency(Duration::from_millis(300)));
+    let platform = ResilientPlatform::new(mock.clone(), config());
+
+    let error = platform.place_order(market_order()).await.unwrap_err();
+    assert!(
+        matches!(error, PlatformError::RequestTimeout { timeout_ms: 100 }),
+        "{:?}",
+        error
+    );
+}
+
+#[tokio::test]
+async fn the_breaker_opens_after_repeated_failures_and_stops_calls() {
+    let mock = Arc::new(MockTradingPlatform::new("acc-1"));
+    let platform = ResilientPlatform::new(mock.clone(), config());
+    mock.fail_operation(Operation::GetPositions, throttled());
+
+    // One call and its two retries make three failures
+    assert!(platform.get_positions().await.is_err());
+    assert_eq!(
+        platform.breakers().combined_state(),
+        CircuitBreakerState::Open
+    );
+    assert_eq!(mock.calls(Operation::GetPositions), 3);
+
+    mock.clear_failures();
+    assert!(platform.get_positions().await.is_err());
+    assert_eq!(mock.calls(Operation::GetPositions), 3);
+
+    let health = platform.health_check().await.unwrap();
+    assert!(!health.is_healthy);
+    assert_eq!(health.issues, ["Orders circuit breaker is Open"]);
+    let diagnostics = platform.get_diagnostics().await.unwrap();
+    assert_eq!(diagnostics.performance_metrics["circuit_breaker"], "Open");
+    assert_eq!(
+        diagnostics.performance_metrics["circuit_breakers"]["Orders"]["state"],
+        "Open"
+    );
+}
+
+#[tokio::test]
+async fn a_flapping_market_data_endpoint_leaves_orders_alone() {
+    let mock = Arc::new(MockTradingPlatform::new("acc-1").with_quote(
+        "EURUSD",
+        dec!(1.0999),
+        dec!(1.1001),
+    ));
+    let platform = ResilientPlatform::new(
+        mock.clone(),
+        ResilienceConfig {
+            class_breakers: [(
+                OperationClass::MarketData,
+                BreakerSettings {
+                    failure_threshold: 1,
+                    open_timeout_secs: 60,
+                    ..BreakerSettings::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+            ..config()
+        },
+    );
+    mock.script_error(Operation::GetMarketData, throttled());
+
+    // Its own threshold of one opens the market data breaker on the first failure
+    assert!(platform.get_market_data("EURUSD").await.is_err());
+    assert!(platform.get_market_data("EURUSD").await.is_err());
+    assert_eq!(mock.calls(Operation::GetMarketData), 1);
+
+    platform.place_order(market_order()).await.unwrap();
+    platform.get_account_info().await.unwrap();
+    let breakers = platform.breakers();
+    assert_eq!(
+        breakers.tripped(),
+        [(OperationClass::MarketData, CircuitBreakerState::Open)]
+    );
+    assert_eq!(
+        breakers.breaker(OperationClass::Orders).get_state(),
+        CircuitBreakerState::Closed
+    );
+    let health = platform.health_check().await.unwrap();
+    assert_eq!(health.issues, ["MarketData circuit breaker is Open"]);
+}
+
+/// Opens on one failure and turns half-open straight away, so the next call finds
+/// it half-open
+fn half_opening() -> ResilienceConfig {
+    ResilienceConfig {
+        retry: RetryConfig {
+            max_retries: 0,
+            ..config().retry
+        },
+        breaker: BreakerSettings {
+            failure_threshold: 1,
+            success_threshold: 2,
+            half_open_max_operations: 3,
+            open_timeout_secs: 0,
+            ..BreakerSettings::default()
+        },
+        ..config()
+    }
+}
+
+fn quoted_mock() -> Arc<MockTradingPlatform> {
+    Arc::new(MockTradingPlatform::new("acc-1").with_quote("EURUSD", dec!(1.0999), dec!(1.1001)))
+}
+
+#[tokio::test]
+async fn half_open_breakers_send_canaries_before_orders() {
+    let mock = quoted_mock();
+    let platform = ResilientPlatform::new(mock.clone(), half_opening());
+    mock.script_error(Operation::GetPositions, throttled());
+    assert!(platform.get_positions().await.is_err());
+    let orders = platform.breakers().breaker(OperationClass::Orders);
+    assert_eq!(orders.poll_state(), CircuitBreakerState::HalfOpen);
+
+    // Two canary order lookups close the breaker before the order goes out
+    platform.place_order(market_order()).await.unwrap();
+    assert_eq!(mock.calls(Operation::GetOrders), 2);
+    assert_eq!(mock.calls(Operation::PlaceOrder), 1);
+    assert_eq!(orders.get_state(), CircuitBreakerState::Closed);
+
+    // A failed canary keeps the order back
+    mock.script_error(Operation::GetPositions, throttled());
+    assert!(platform.get_positions().await.is_err());
+    mock.script_error(Operation::GetOrders, throttled());
+    let error = platform.place_order(market_order()).await.unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("place_order held back until canary calls succeed"),
+        "{}",
+        error
+    );
+    assert_eq!(mock.calls(Operation::PlaceOrder), 1);
+
+    // Reads are trial calls of their own
+    platform.get_positions().await.unwrap();
+}
+
+#[tokio::test]
+async fn emergencies_go_through_open_breakers() {
+    let mock = Arc::new(
+        MockTradingPlatform::new("acc-1").with_position(UnifiedPosition {
+            position_id: "pos-1".to_string(),
+            symbol: "EURUSD".to_string(),
+            side: UnifiedPositionSide::Long,
+            quantity: dec!(10000),
+            entry_price: dec!(1.1000),
+            current_price: dec!(1.1000),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_used: Decimal::ZERO,
+            commission: Decimal::ZERO,
+            stop_loss: None,
+            take_profit: None,
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            account_id: "acc-1".to_string(),
+            platform_specific: HashMap::new(),
+        }),
+    );
+    let platform = Arc::new(ResilientPlatform::new(
+        mock.clone(),
+        ResilienceConfig {
+            breaker: BreakerSettings {
+                open_timeout_secs: 60,
+                ..half_opening().breaker
+            },
+            ..half_opening()
+        },
+    ));
+    let orchestrator = TradeExecutionOrchestrator::new();
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+
+    mock.script_error(Operation::GetPositions, throttled());
+    assert!(platform.get_positions().await.is_err());
+    assert!(platform.get_positions().await.is_err());
+    assert_eq!(mock.calls(Operation::GetPositions), 1);
+
+    let results = orchestrator
+        .emergency_close(Some("acc-1"), "drill".to_string())
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].success, "{:?}", results[0].error_message);
+    assert!(mock.positions().is_empty());
+    // Overrides leave the breaker as it was
+    assert_eq!(
+        platform.breakers().combined_state(),
+        CircuitBreakerState::Open
+    );
+    assert!(platform.get_positions().await.is_err());
+
+    let positions = as_emergency("drill", platform.get_positions())
+        .await
+        .unwrap();
+    assert!(positions.is_empty());
+}
@@ -0,0 +1,122 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use execution_engine::platforms::abstraction::errors::{ErrorClass, PlatformError};
+use execution_engine::platforms::abstraction::retry::{
+    BackoffStrategy, RetryBudget, RetryConfig, RetryHandler, RetryOverride,
+};
+
+fn config(strategy: BackoffStrategy) -> RetryConfig {
+    RetryConfig {
+        max_retries: 5,
+        initial_delay_ms: 100,
+        max_delay_ms: 1000,
+        backoff_multiplier: 2.0,
+        jitter: false,
+        ..RetryConfig::default()
+    }
+    .with_strategy(strategy)
+}
+
+fn transient() -> PlatformError {
+    PlatformError::MarketDataUnavailable {
+        reason: "stale".to_string(),
+    }
+}
+
+#[test]
+fn test_deterministic_strategies() {
+    let class = ErrorClass::MarketData;
+
+    let exponential = config(BackoffStrategy::Exponential);
+    let delays: Vec<u64> = (1..=5)
+        .map(|n| exponential.delay_for(class, n, 0))
+        .collect();
+    assert_eq!(delays, vec![100, 200, 400, 800, 1000]);
+
+    let fixed = config(BackoffStrategy::Fixed);
+    assert_eq!(fixed.delay_for(class, 4, 0), 100);
+
+    let fibonacci = config(BackoffStrategy::Fibonacci);
+    let delays: Vec<u64> = (1..=6).map(|n| fibonacci.delay_for(class, n, 0)).collect();
+    assert_eq!(delays, vec![100, 100, 200, 300, 500, 800]);
+}
+
+#[test]
+fn test_jitter_strategies_stay_in_bounds() {
+    let class = ErrorClass::MarketData;
+    let full = config(BackoffStrategy::FullJitter);
+    let decorrelated = config(BackoffStrategy::DecorrelatedJitter);
+
+    for _ in 0..100 {
+        assert!(full.delay_for(class, 3, 0) <= 400);
+
+        let delay = decorrelated.delay_for(class, 3, 200);
+        assert!((100..=600).contains(&delay));
+    }
+}
+
+#[test]
+fn test_class_override_replaces_defaults() {
+    let config = config(BackoffStrategy::Exponential).with_class_override(
+        ErrorClass::Timeout,
+        RetryOverride {
+            max_retries: Some(1),
+            initial_delay_ms: Some(10),
+            strategy: Some(BackoffStrategy::Fixed),
+        },
+    );
+
+    assert_eq!(config.delay_for(ErrorClass::Timeout, 3, 0), 10);
+    assert_eq!(config.delay_for(ErrorClass::MarketData, 3, 0), 400);
+}
+
+#[tokio::test]
+async fn test_class_override_limits_attempts() {
+    let config = config(BackoffStrategy::Fixed).with_class_override(
+        ErrorClass::MarketData,
+        RetryOverride {
+            max_retries: Some(1),
+            initial_delay_ms: Some(1),
+            strategy: None,
+        },
+    );
+    let handler = RetryHandler::new(config);
+
+    let calls = AtomicU32::new(0);
+    let result: Result<(), _> = handler
+        .execute_with_retry(|| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(transient())
+        })
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_shared_budget_prevents_retry_storm() {
+    let budget = Arc::new(RetryBudget::new(4, 0.5));
+    let mut fast = config(BackoffStrategy::Fixed);
+    fast.initial_delay_ms = 1;
+
+    let first = RetryHandler::new(fast.clone()).with_budget(budget.clone());
+    let second = RetryHandler::new(fast).with_budget(budget.clone());
+
+    let calls = AtomicU32::new(0);
+    let failing = || async {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Err::<(), _>(transient())
+    };
+
+    let _ = first.execute_with_retry(failing).await;
+    let _ = second.execute_with_retry(failing).await;
+
+    // Bucket of 4 allows retries only while above 2 tokens: two retries in total
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+    assert_eq!(budget.available(), 2.0);
+
+    let _ = first.execute_with_retry(|| async { Ok(()) }).await;
+    assert_eq!(budget.available(), 2.5);
+}
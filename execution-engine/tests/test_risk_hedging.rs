@@ -0,0 +1,347 @@
+use chrono::Utc;
+use execution_engine::platforms::abstraction::models::{
+    UnifiedOrderSide, UnifiedPosition, UnifiedPositionSide,
+};
+use execution_engine::risk::hedging::{CorrelatedHedge, UnwindReason};
+use execution_engine::risk::{
+    AccountId, CircuitBreakerClient, HedgingPolicy, Position, PositionManager, PositionType,
+    ResponseAction, ResponseExecutionResult, ResponseExecutor, RiskAuditLogger, RiskResponseSystem,
+    RiskThresholds,
+};
+use execution_engine::testing::MockTradingPlatform;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+fn response_system(
+    policy: HedgingPolicy,
+) -> (
+    RiskResponseSystem,
+    Arc<PositionManager>,
+    Arc<MockTradingPlatform>,
+) {
+    let position_manager = Arc::new(PositionManager::new());
+    let accounts: Vec<AccountId> = policy.accounts.iter().copied().collect();
+    let system = RiskResponseSystem::new(
+        Arc::new(RiskThresholds::default()),
+        position_manager.clone(),
+        Arc::new(CircuitBreakerClient),
+        Arc::new(RiskAuditLogger::new()),
+        Arc::new(ResponseExecutor),
+    )
+    .with_hedging(policy);
+    let platform = Arc::new(
+        MockTradingPlatform::new("hedged")
+            .with_hedging()
+            .with_quote("EURUSD", dec!(1.0950), dec!(1.0952))
+            .with_quote("GBPUSD", dec!(1.2700), dec!(1.2702)),
+    );
+    for account_id in accounts {
+        system
+            .hedging()
+            .unwrap()
+            .register_platform(account_id, platform.clone());
+    }
+    (system, position_manager, platform)
+}
+
+fn policy(account_id: AccountId) -> HedgingPolicy {
+    HedgingPolicy {
+        enabled: true,
+        accounts: HashSet::from([account_id]),
+        ..HedgingPolicy::default()
+    }
+}
+
+async fn open_long(
+    positions: &PositionManager,
+    platform: &MockTradingPlatform,
+    account_id: AccountId,
+    symbol: &str,
+) -> Position {
+    let position = Position {
+        id: Uuid::new_v4(),
+        account_id,
+        symbol: symbol.to_string(),
+        position_type: PositionType::Long,
+        size: dec!(10000),
+        entry_price: dec!(1.1000),
+        current_price: Some(dec!(1.0950)),
+        unrealized_pnl: None,
+        max_favorable_excursion: dec!(0),
+        max_adverse_excursion: dec!(0),
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now(),
+    };
+    positions.add_position(position.clone()).await;
+    platform.add_position(UnifiedPosition {
+        position_id: position.id.to_string(),
+        symbol: symbol.to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity: position.size,
+        entry_price: position.entry_price,
+        current_price: dec!(1.0950),
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        stop_loss: None,
+        take_profit: None,
+        opened_at: position.opened_at,
+        updated_at: position.opened_at,
+        account_id: account_id.to_string(),
+        platform_specific: HashMap::new(),
+    });
+    position
+}
+
+#[tokio::test]
+async fn exposure_breaches_are_hedged_on_flagged_accounts_and_unwound_on_recovery() {
+    let account_id = Uuid::new_v4();
+    let (system, positions, platform) = response_system(policy(account_id));
+    let position = open_long(&positions, &platform, account_id, "EURUSD").await;
+
+    let response = system
+        .handle_exposure_risk(account_id, dec!(35), dec!(20))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        response.action_taken,
+        ResponseAction::HedgeExposure { hedge_ratio, .. } if hedge_ratio == dec!(1)
+    ));
+    match response.execution_result {
+        ResponseExecutionResult::PositionsHedged {
+            hedges_opened,
+            hedged_size,
+        } => assert_eq!((hedges_opened, hedged_size), (1, dec!(10000))),
+        other => panic!("Expected PositionsHedged, got {:?}", other),
+    }
+
+    let open = positions.get_account_positions(account_id).await.unwrap();
+    assert_eq!(open.len(), 2);
+    let hedge = open.iter().find(|p| p.id != position.id).unwrap();
+    assert_eq!(
+        (hedge.symbol.as_str(), hedge.position_type, hedge.size),
+        ("EURUSD", PositionType::Short, dec!(10000))
+    );
+    // The hedge is a real sell on the platform, entered at its fill
+    let orders = platform.orders();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(
+        (
+            orders[0].symbol.as_str(),
+            &orders[0].side,
+            orders[0].quantity
+        ),
+        ("EURUSD", &UnifiedOrderSide::Sell, dec!(10000))
+    );
+    assert_eq!(hedge.entry_price, dec!(1.0950));
+    let ticket = system.hedging().unwrap().hedges(account_id)[0]
+        .ticket
+        .clone()
+        .unwrap();
+
+    // A repeated breach does not hedge the same position twice
+    let response = system
+        .handle_exposure_risk(account_id, dec!(35), dec!(20))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        response.execution_result,
+        ResponseExecutionResult::PositionsHedged {
+            hedges_opened: 0,
+            ..
+        }
+    ));
+
+    // Back within the limit the hedge closes and the position stays
+    assert!(system
+        .handle_exposure_risk(account_id, dec!(15), dec!(20))
+        .await
+        .unwrap()
+        .is_none());
+    let open = positions.get_account_positions(account_id).await.unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].id, position.id);
+    assert!(system.hedging().unwrap().hedges(account_id).is_empty());
+    assert_eq!(platform.ticket_closes(), vec![(ticket, None)]);
+    assert_eq!(platform.positions().len(), 1);
+}
+
+#[tokio::test]
+async fn other_accounts_keep_reducing_positions() {
+    let flagged = Uuid::new_v4();
+    let account_id = Uuid::new_v4();
+    let (system, positions, _) = response_system(policy(flagged));
+    let platform = MockTradingPlatform::new("unhedged");
+    open_long(&positions, &platform, account_id, "EURUSD").await;
+
+    let response = system
+        .handle_exposure_risk(account_id, dec!(35), dec!(20))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        response.action_taken,
+        ResponseAction::DiversifyPositions { .. }
+    ));
+    assert_eq!(
+        positions
+            .get_account_positions(account_id)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn correlated_instruments_hedge_with_scaled_size() {
+    let account_id = Uuid::new_v4();
+    let (system, positions, platform) = response_system(HedgingPolicy {
+        correlated_instruments: HashMap::from([(
+            "EURUSD".to_string(),
+            CorrelatedHedge {
+                symbol: "GBPUSD".to_string(),
+                correlation: dec!(0.8),
+            },
+        )]),
+        ..policy(account_id)
+    });
+    open_long(&positions, &platform, account_id, "EURUSD").await;
+
+    system
+        .handle_exposure_risk(account_id, dec!(35), dec!(20))
+        .await
+        .unwrap();
+
+    let hedges = system.hedging().unwrap().hedges(account_id);
+    assert_eq!(hedges.len(), 1);
+    assert_eq!(
+        (
+            hedges[0].symbol.as_str(),
+            hedges[0].position_type,
+            hedges[0].size
+        ),
+        ("GBPUSD", PositionType::Short, dec!(12500))
+    );
+    assert_eq!(hedges[0].entry_price, dec!(1.2700));
+    assert_eq!(platform.orders()[0].symbol, "GBPUSD");
+}
+
+#[tokio::test]
+async fn hedges_unwind_with_their_position_or_when_held_too_long() {
+    let account_id = Uuid::new_v4();
+    let (system, positions, platform) = response_system(policy(account_id));
+    let position = open_long(&positions, &platform, account_id, "EURUSD").await;
+    system
+        .handle_exposure_risk(account_id, dec!(35), dec!(20))
+        .await
+        .unwrap();
+    assert!(system.review_hedges().await.unwrap().is_empty());
+
+    positions.close_position(position.id).await.unwrap();
+    let unwound = system.review_hedges().await.unwrap();
+    assert_eq!(unwound.len(), 1);
+    assert_eq!(unwound[0].reason, UnwindReason::HedgedPositionClosed);
+    assert_eq!(platform.ticket_closes().len(), 1);
+    assert!(positions
+        .get_account_positions(account_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let (system, positions, platform) = response_system(HedgingPolicy {
+        max_hedge_hours: Some(0.0),
+        ..policy(account_id)
+    });
+    open_long(&positions, &platform, account_id, "EURUSD").await;
+    system
+        .handle_exposure_risk(account_id, dec!(35), dec!(20))
+        .await
+        .unwrap();
+
+    let unwound = system.review_hedges().await.unwrap();
+    assert_eq!(unwound[0].reason, UnwindReason::Expired);
+    assert!(positions
+        .get_account_positions(account_id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(platform.ticket_closes().len(), 1);
+    assert_eq!(
+        platform.closes(),
+        vec![("EURUSD".to_string(), Some(dec!(10000)))]
+    );
+    assert!(platform.positions().is_empty());
+}
+
+#[tokio::test]
+async fn accounts_without_a_platform_are_not_hedged() {
+    let account_id = Uuid::new_v4();
+    let (system, positions, platform) = response_system(HedgingPolicy::default());
+    let system = system.with_hedging(policy(account_id));
+    open_long(&positions, &platform, account_id, "EURUSD").await;
+
+    let response = system
+        .handle_exposure_risk(account_id, dec!(35), dec!(20))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        response.execution_result,
+        ResponseExecutionResult::Failed { .. }
+    ));
+    assert_eq!(
+        positions
+            .get_account_positions(account_id)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(platform.orders().is_empty());
+}
+
+#[tokio::test]
+async fn hedge_hours_too_large_to_represent_never_expire() {
+    let account_id = Uuid::new_v4();
+    let (system, positions, platform) = response_system(HedgingPolicy {
+        max_hedge_hours: Some(f64::MAX),
+        ..policy(account_id)
+    });
+    open_long(&positions, &platform, account_id, "EURUSD").await;
+    system
+        .handle_exposure_risk(account_id, dec!(35), dec!(20))
+        .await
+        .unwrap();
+
+    assert!(system.review_hedges().await.unwrap().is_empty());
+    assert_eq!(system.hedging().unwrap().hedges(account_id).len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn hedges_are_reviewed_on_a_schedule_once_placed() {
+    let account_id = Uuid::new_v4();
+    let (system, positions, platform) = response_system(HedgingPolicy {
+        max_hedge_hours: Some(0.0),
+        review_interval_secs: 5,
+        ..policy(account_id)
+    });
+    open_long(&positions, &platform, account_id, "EURUSD").await;
+    system
+        .handle_exposure_risk(account_id, dec!(35), dec!(20))
+        .await
+        .unwrap();
+    assert_eq!(system.hedging().unwrap().hedges(account_id).len(), 1);
+
+    tokio::time::sleep(Duration::from_secs(6)).await;
+    assert!(system.hedging().unwrap().hedges(account_id).is_empty());
+    assert!(platform.positions().is_empty());
+}
@@ -0,0 +1,160 @@
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::platforms::abstraction::models::UnifiedPositionSide;
+use execution_engine::platforms::abstraction::UnifiedPosition;
+use execution_engine::risk::{
+    FileRiskSnapshotStore, RiskSnapshotConfig, RiskSnapshotQuery, RiskSnapshotter,
+};
+use execution_engine::testing::{orchestrator, MockTradingPlatform};
+
+fn position(
+    position_id: &str,
+    symbol: &str,
+    side: UnifiedPositionSide,
+    price: Decimal,
+    stop_loss: Option<Decimal>,
+) -> UnifiedPosition {
+    UnifiedPosition {
+        position_id: position_id.to_string(),
+        symbol: symbol.to_string(),
+        side,
+        quantity: dec!(10000),
+        entry_price: price,
+        current_price: price,
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        stop_loss,
+        take_profit: None,
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: "usd-1".to_string(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+fn usd_account() -> Arc<MockTradingPlatform> {
+    Arc::new(
+        MockTradingPlatform::new("usd-1")
+            .with_balance(dec!(10000))
+            .with_position(position(
+                "pos-1",
+                "EURUSD",
+                UnifiedPositionSide::Long,
+                dec!(1.1),
+                Some(dec!(1.09)),
+            ))
+            .with_position(position(
+                "pos-2",
+                "GBPUSD",
+                UnifiedPositionSide::Short,
+                dec!(1.25),
+                None,
+            )),
+    )
+}
+
+#[tokio::test]
+async fn snapshots_carry_exposure_open_risk_and_drawdown_from_the_peak() {
+    let platform = usd_account();
+    let orchestrator = orchestrator(vec![platform.clone()]).await;
+    let dir = tempfile::tempdir().unwrap();
+    let snapshotter = RiskSnapshotter::new(
+        orchestrator,
+        Arc::new(FileRiskSnapshotStore::new(dir.path().join("risk.jsonl"))),
+        RiskSnapshotConfig::default(),
+    );
+
+    let snapshot = snapshotter.capture().await;
+    let account = snapshot.account("usd-1").unwrap();
+    assert_eq!(account.equity, dec!(10000));
+    assert_eq!(account.margin_level_pct, None);
+    assert_eq!(account.exposure["EURUSD"], dec!(11000));
+    assert_eq!(account.exposure["GBPUSD"], dec!(-12500));
+    assert_eq!(account.gross_exposure, dec!(23500));
+    // 10000 units a cent above the stop, and one position without a stop
+    assert_eq!(account.open_risk, dec!(100));
+    assert_eq!(account.open_r, Some(dec!(1)));
+    assert_eq!(account.unprotected_positions, 1);
+    assert_eq!(account.drawdown_pct, Decimal::ZERO);
+
+    platform.set_position_price("pos-1", dec!(1.095));
+    let account = snapshotter.capture().await.accounts.remove(0);
+    assert_eq!(account.equity, dec!(9950));
+    assert_eq!(account.peak_equity, dec!(10000));
+    assert_eq!(account.drawdown_pct, dec!(0.5));
+    assert_eq!(account.daily_drawdown_pct, dec!(0.5));
+    assert_eq!(account.open_risk, dec!(50));
+
+    // A stop beyond the price risks nothing
+    platform.set_position_price("pos-1", dec!(1.08));
+    let account = snapshotter.capture().await.accounts.remove(0);
+    assert_eq!(account.open_risk, Decimal::ZERO);
+
+    assert!(RiskSnapshotConfig {
+        interval_mins: 0,
+        ..RiskSnapshotConfig::default()
+    }
+    .validate()
+    .is_err());
+}
+
+#[tokio::test]
+async fn stored_snapshots_come_back_as_a_series_and_keep_the_peak_across_restarts() {
+    let platform = usd_account();
+    let orchestrator = orchestrator(vec![
+        platform.clone(),
+        Arc::new(MockTradingPlatform::new("eur-1").with_currency("EUR")),
+    ])
+    .await;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("risk.jsonl");
+    let snapshotter = |orchestrator| {
+        RiskSnapshotter::new(
+            orchestrator,
+            Arc::new(FileRiskSnapshotStore::new(&path)),
+            RiskSnapshotConfig::default(),
+        )
+    };
+
+    let first = snapshotter(orchestrator.clone()).record().await.unwrap();
+    assert_eq!(first.accounts.len(), 2);
+
+    platform.set_position_price("pos-1", dec!(1.09));
+    let restarted = snapshotter(orchestrator);
+    assert_eq!(restarted.load().await.unwrap(), 1);
+    let second = restarted.record().await.unwrap();
+    let account = second.account("usd-1").unwrap();
+    assert_eq!(account.peak_equity, dec!(10000));
+    assert_eq!(account.drawdown_pct, dec!(1));
+
+    let series = restarted
+        .series(&RiskSnapshotQuery {
+            account_id: Some("usd-1".to_string()),
+            ..RiskSnapshotQuery::default()
+        })
+        .await
+        .unwrap();
+    let equity: Vec<Decimal> = series
+        .iter()
+        .map(|s| {
+            assert_eq!(s.accounts.len(), 1);
+            s.accounts[0].equity
+        })
+        .collect();
+    assert_eq!(equity, vec![dec!(10000), dec!(9900)]);
+
+    let since_restart = restarted
+        .series(&RiskSnapshotQuery {
+            from: Some(first.taken_at + Duration::nanoseconds(1)),
+            ..RiskSnapshotQuery::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(since_restart, vec![second]);
+}
@@ -0,0 +1,197 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::execution::exit_management::{
+    ExitAuditLogger, ExitManagementPlatformAdapter, ExitManagementSystem, ScaleInPolicy,
+    ScaleInStopPolicy, ScaleInTargetPolicy,
+};
+use execution_engine::execution::{ScaleInConfig, TradeExecutionOrchestrator};
+use execution_engine::journal::{TradeAddition, TradeJournal};
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::UnifiedPosition;
+use execution_engine::testing::{signal, MockTradingPlatform};
+
+async fn orchestrator(
+    config: ScaleInConfig,
+) -> (TradeExecutionOrchestrator, Arc<MockTradingPlatform>) {
+    let orchestrator = TradeExecutionOrchestrator::new()
+        .with_timing_variance(0, 0)
+        .with_scale_in(config);
+    let platform = Arc::new(MockTradingPlatform::new("acc-1").with_quote(
+        "EURUSD",
+        dec!(1.0999),
+        dec!(1.1001),
+    ));
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+    (orchestrator, platform)
+}
+
+async fn open_position(platform: &MockTradingPlatform) -> UnifiedPosition {
+    platform.get_positions().await.unwrap().remove(0)
+}
+
+#[tokio::test]
+async fn additions_are_sized_down_and_limited() {
+    let (orchestrator, platform) = orchestrator(ScaleInConfig {
+        enabled: true,
+        max_adds: 1,
+        ..ScaleInConfig::default()
+    })
+    .await;
+
+    let first = orchestrator.process_signal(signal("sig-1")).await.unwrap();
+    assert!(orchestrator
+        .execute_plan(&first)
+        .await
+        .iter()
+        .all(|r| r.success));
+    let entry_size = first.account_assignments[0].position_size;
+
+    // Bought at the ask, marked at the bid: not in profit yet
+    let refused = orchestrator
+        .process_signal(signal("sig-2"))
+        .await
+        .unwrap_err();
+    assert_eq!(refused, "No account may scale in to its open position");
+
+    platform.set_quote("EURUSD", dec!(1.1049), dec!(1.1051));
+    let preview = orchestrator.preview_signal(signal("sig-3")).await;
+    assert!(preview.accounts[0].adjustments[0].starts_with(&format!(
+        "adds to position {} (addition 1 of 1), sized at 0.50x",
+        open_position(&platform).await.position_id
+    )));
+    let addition = orchestrator.process_signal(signal("sig-3")).await.unwrap();
+    assert!(addition.account_assignments[0].position_size < entry_size);
+    orchestrator.execute_plan(&addition).await;
+    assert_eq!(platform.get_positions().await.unwrap().len(), 1);
+
+    // One addition is all this position may take
+    assert!(orchestrator.process_signal(signal("sig-4")).await.is_err());
+    let preview = orchestrator.preview_signal(signal("sig-5")).await;
+    assert!(preview.accounts[0]
+        .adjustments
+        .iter()
+        .any(|note| note.contains("added to 1 times")));
+}
+
+#[tokio::test]
+async fn exit_management_moves_levels_of_positions_added_to() {
+    let (orchestrator, platform) = orchestrator(ScaleInConfig {
+        enabled: true,
+        require_profit: false,
+        ..ScaleInConfig::default()
+    })
+    .await;
+    let journal = Arc::new(TradeJournal::new());
+    let system = ExitManagementSystem::new(
+        Arc::new(ExitManagementPlatformAdapter::new(platform.clone())),
+        Arc::new(ExitAuditLogger::new().with_trade_journal(journal.clone())),
+    )
+    .with_scale_in_policy(ScaleInPolicy {
+        stop: ScaleInStopPolicy::PreserveDistance,
+        target: ScaleInTargetPolicy::PreserveRiskReward,
+    })
+    .with_order_tracker(orchestrator.order_tracker(), "acc-1");
+
+    let first = orchestrator.process_signal(signal("sig-1")).await.unwrap();
+    orchestrator.execute_plan(&first).await;
+    let position = open_position(&platform).await;
+    journal.record_open("acc-1", &position).await;
+    system.run_position_checks().await;
+
+    platform.set_quote("EURUSD", dec!(1.1049), dec!(1.1051));
+    let addition = orchestrator.process_signal(signal("sig-2")).await.unwrap();
+    orchestrator.execute_plan(&addition).await;
+    system.run_position_checks().await;
+
+    let scaled = system.get_scale_in_manager().scaled_positions();
+    assert_eq!(scaled.len(), 1);
+    let scaled = &scaled[0];
+    assert_eq!(scaled.entries.len(), 2);
+    assert_eq!(scaled.entries[1].price, dec!(1.1051));
+    assert_eq!(scaled.entries[1].signal_id.as_deref(), Some("sig-2"));
+
+    // The stop keeps the first entry's distance and the target its reward to risk
+    let first = &scaled.entries[0];
+    let risk = first.price - first.stop_loss.unwrap();
+    let reward = first.take_profit.unwrap() - first.price;
+    let position = open_position(&platform).await;
+    assert_eq!(position.entry_price, scaled.average_price);
+    assert_eq!(position.stop_loss, Some(scaled.average_price - risk));
+    let target = position.take_profit.unwrap() - scaled.average_price;
+    assert!((target - reward).abs() < dec!(0.00000001));
+
+    let trade = journal
+        .get_trade_for_position(&position.position_id)
+        .await
+        .unwrap();
+    assert_eq!(trade.additions.len(), 1);
+    assert_eq!(trade.total_quantity(), position.quantity);
+    // Additions are journaled at the 8 dp price they were worked out at
+    assert!((trade.average_entry_price() - position.entry_price).abs() < dec!(0.00000001));
+}
+
+#[tokio::test]
+async fn journal_risk_counts_every_addition_against_its_own_stop() {
+    let journal = TradeJournal::new();
+    let position = UnifiedPosition {
+        position_id: "pos-1".to_string(),
+        symbol: "EURUSD".to_string(),
+        side: execution_engine::platforms::abstraction::models::UnifiedPositionSide::Long,
+        quantity: dec!(10000),
+        entry_price: dec!(1.10),
+        current_price: dec!(1.10),
+        unrealized_pnl: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        margin_used: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        stop_loss: Some(dec!(1.09)),
+        take_profit: None,
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: "acc-1".to_string(),
+        platform_specific: HashMap::new(),
+    };
+    let trade_id = journal.record_open("acc-1", &position).await;
+
+    let recorded = journal
+        .record_addition(
+            "pos-1",
+            TradeAddition {
+                quantity: dec!(5000),
+                price: dec!(1.13),
+                stop_loss: Some(dec!(1.11)),
+                take_profit: None,
+                signal_id: Some("sig-2".to_string()),
+                added_at: Utc::now(),
+            },
+        )
+        .await;
+    assert_eq!(recorded.as_deref(), Some(trade_id.as_str()));
+    assert!(journal
+        .record_addition(
+            "pos-unknown",
+            TradeAddition {
+                quantity: dec!(1),
+                price: dec!(1),
+                stop_loss: None,
+                take_profit: None,
+                signal_id: None,
+                added_at: Utc::now(),
+            },
+        )
+        .await
+        .is_none());
+
+    let trade = journal.get_trade_for_position("pos-1").await.unwrap();
+    assert_eq!(trade.total_quantity(), dec!(15000));
+    assert_eq!(trade.average_entry_price(), dec!(1.11));
+    // 10000 risking 0.01 plus 5000 risking 0.02
+    assert_eq!(trade.initial_risk(), Some(dec!(200)));
+}
@@ -0,0 +1,80 @@
+use chrono::{TimeZone, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+
+use execution_engine::execution::{
+    SessionLiquidityConfig, SlippageGuard, SlippageGuardConfig, TradeExecutionOrchestrator,
+};
+use execution_engine::testing::{signal, MockTradingPlatform};
+
+/// Every session at `liquidity`, so results do not depend on the hour tests run at
+fn flat(liquidity: f64) -> SessionLiquidityConfig {
+    SessionLiquidityConfig {
+        enabled: true,
+        sydney: liquidity,
+        tokyo: liquidity,
+        london: liquidity,
+        london_new_york: liquidity,
+        new_york: liquidity,
+        closed: liquidity,
+    }
+}
+
+#[test]
+fn delays_widen_as_sessions_thin() {
+    let config = SessionLiquidityConfig {
+        enabled: true,
+        ..SessionLiquidityConfig::default()
+    };
+    // A Tuesday, in Tokyo hours and then in the London/New York overlap
+    let tokyo = Utc.with_ymd_and_hms(2024, 3, 5, 3, 0, 0).unwrap();
+    let overlap = Utc.with_ymd_and_hms(2024, 3, 5, 13, 0, 0).unwrap();
+
+    assert_eq!(config.delay_range(1000, 30000, tokyo), (1667, 50000));
+    assert_eq!(config.delay_range(1000, 30000, overlap), (1000, 30000));
+    assert_eq!(
+        SessionLiquidityConfig::default().delay_range(1000, 30000, tokyo),
+        (1000, 30000)
+    );
+
+    let guard = SlippageGuard::new(SlippageGuardConfig {
+        enabled: true,
+        ..SlippageGuardConfig::default()
+    })
+    .with_session_liquidity(flat(0.5));
+    assert_eq!(guard.max_slippage_pips(), 4.0);
+
+    assert!(SessionLiquidityConfig {
+        tokyo: 0.0,
+        ..config
+    }
+    .validate()
+    .is_err());
+}
+
+#[tokio::test]
+async fn plans_draw_delays_from_the_session_range() {
+    let orchestrator = TradeExecutionOrchestrator::new().with_session_liquidity(flat(0.25));
+    for account_id in ["acc-1", "acc-2", "acc-3"] {
+        orchestrator
+            .register_account(
+                account_id.to_string(),
+                Arc::new(MockTradingPlatform::new(account_id)),
+                100000.0,
+            )
+            .await
+            .unwrap();
+    }
+
+    let preview = orchestrator.preview_signal(signal("sig-1")).await;
+    let plan = preview.plan.unwrap();
+    assert_eq!(plan.account_assignments.len(), 3);
+    for assignment in &plan.account_assignments {
+        assert!(assignment.entry_timing_delay >= Duration::from_millis(4000));
+        assert!(assignment.entry_timing_delay <= Duration::from_millis(120000));
+    }
+    assert!(preview
+        .adjustments
+        .iter()
+        .any(|a| a.starts_with("Entry delays drawn from 4000-120000 ms")));
+}
@@ -0,0 +1,216 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::{Arc, Mutex};
+
+use execution_engine::execution::exit_management::exit_logger::TimeRange;
+use execution_engine::execution::exit_management::{
+    BreakEvenConfig, ClosePositionRequest, ClosePositionResult, ExitAuditLogger,
+    ExitManagementSystem, ExitPolicy, MarketData, OrderModifyRequest, OrderModifyResult,
+    PartialCloseRequest, Position, ProfitTakingConfig, ProfitTarget, ShadowExitEvaluator,
+    ShadowVariant, TradingPlatform, TrailingConfig, UnifiedPositionSide,
+};
+use execution_engine::platforms::abstraction::events::PositionCloseEventData;
+use execution_engine::testing::long_position_at_entry;
+
+/// Quotes a settable mid price; orders are accepted and ignored
+#[derive(Debug)]
+struct MockPlatform {
+    positions: Mutex<Vec<Position>>,
+    mid: Mutex<Decimal>,
+}
+
+impl MockPlatform {
+    fn new(positions: Vec<Position>) -> Arc<Self> {
+        Arc::new(Self {
+            positions: Mutex::new(positions),
+            mid: Mutex::new(dec!(1.1000)),
+        })
+    }
+
+    fn set_mid(&self, mid: Decimal) {
+        *self.mid.lock().unwrap() = mid;
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        let mid = *self.mid.lock().unwrap();
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: mid - dec!(0.00005),
+            ask: mid + dec!(0.00005),
+            spread: dec!(0.0001),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: true,
+            message: "modified".to_string(),
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: *self.mid.lock().unwrap(),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: *self.mid.lock().unwrap(),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+}
+
+/// Trails at a fixed distance with break-even switched off
+fn trail_variant(name: &str, distance: Decimal) -> ShadowVariant {
+    ShadowVariant {
+        name: name.to_string(),
+        policy: ExitPolicy {
+            trailing: Some(TrailingConfig {
+                min_trail_distance: distance,
+                max_trail_distance: distance,
+                ..Default::default()
+            }),
+            break_even: Some(BreakEvenConfig {
+                enabled: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    }
+}
+
+fn last_day() -> TimeRange {
+    TimeRange {
+        start: Utc::now() - Duration::days(1),
+        end: Utc::now() + Duration::minutes(1),
+    }
+}
+
+#[tokio::test]
+async fn test_variants_report_counterfactual_pnl() {
+    let position = long_position_at_entry();
+    let platform = MockPlatform::new(vec![position.clone()]);
+    let exit_logger = Arc::new(ExitAuditLogger::new());
+    let system = ExitManagementSystem::new(platform.clone(), exit_logger.clone())
+        .with_shadow_variants(vec![
+            trail_variant("tight", dec!(0.0010)),
+            trail_variant("wide", dec!(0.0050)),
+        ]);
+
+    for mid in [dec!(1.1000), dec!(1.1040), dec!(1.1020), dec!(1.1080)] {
+        platform.set_mid(mid);
+        system.run_position_checks().await;
+    }
+
+    // The tight trail stopped out at 1.1030; the wide one is still riding the move
+    let results = exit_logger.get_shadow_results(position.id).await;
+    assert_eq!(results.len(), 2);
+    let (tight, wide) = (&results[0], &results[1]);
+    assert_eq!(tight.variant, "tight");
+    assert!(tight.shadow_closed);
+    assert_eq!(tight.shadow_exit_reason.as_deref(), Some("trailing_stop"));
+    assert_eq!(tight.shadow_pnl, dec!(30));
+    assert_eq!(tight.live_pnl, dec!(80));
+    assert_eq!(tight.pnl_difference, dec!(-50));
+    assert!(!wide.shadow_closed);
+    assert_eq!(wide.pnl_difference, dec!(0));
+
+    // The live position closes at 1.1060; the wide variant is followed until it stops out
+    platform.positions.lock().unwrap().clear();
+    exit_logger
+        .log_position_close(PositionCloseEventData {
+            position_id: position.id.to_string(),
+            symbol: "EURUSD".to_string(),
+            side: UnifiedPositionSide::Long,
+            closing_order_id: "close-1".to_string(),
+            entry_price: dec!(1.1000),
+            close_price: dec!(1.1060),
+            closed_quantity: dec!(10000),
+            remaining_quantity: Decimal::ZERO,
+            realized_pnl: dec!(60),
+            commission: Decimal::ZERO,
+            closed_at: Utc::now(),
+        })
+        .await;
+    platform.set_mid(dec!(1.1100));
+    system.run_position_checks().await;
+    platform.set_mid(dec!(1.1040));
+    system.run_position_checks().await;
+
+    let report = exit_logger
+        .generate_exit_performance_report(last_day())
+        .await
+        .unwrap();
+    assert_eq!(report.shadow_results.len(), 2);
+    let wide = report
+        .shadow_results
+        .iter()
+        .find(|r| r.variant == "wide")
+        .unwrap();
+    assert!(wide.live_closed && wide.shadow_closed);
+    assert_eq!(wide.live_pnl, dec!(60));
+    assert_eq!(wide.shadow_pnl, dec!(50));
+    assert_eq!(wide.pnl_difference, dec!(-10));
+}
+
+#[tokio::test]
+async fn test_unset_sections_follow_live_configuration() {
+    let position = long_position_at_entry();
+    let platform = MockPlatform::new(vec![position.clone()]);
+    let exit_logger = Arc::new(ExitAuditLogger::new());
+    let evaluator = ShadowExitEvaluator::new(
+        platform.clone(),
+        exit_logger.clone(),
+        vec![ShadowVariant {
+            name: "ladder".to_string(),
+            policy: ExitPolicy {
+                profit_taking: Some(ProfitTakingConfig {
+                    profit_targets: vec![ProfitTarget {
+                        level: 1,
+                        risk_reward_ratio: 1.0,
+                        close_percentage: 0.5,
+                    }],
+                    enabled: true,
+                    take_profit_at_structure: false,
+                }),
+                ..Default::default()
+            },
+        }],
+    );
+
+    // Half closes past 1R, then the default break-even and trail lift the stop to 1.1050
+    for mid in [dec!(1.1060), dec!(1.0950)] {
+        platform.set_mid(mid);
+        evaluator.evaluate().await.unwrap();
+    }
+
+    let results = exit_logger.get_shadow_results(position.id).await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        results[0].shadow_exit_reason.as_deref(),
+        Some("trailing_stop")
+    );
+    assert_eq!(results[0].shadow_pnl, dec!(55));
+    assert_eq!(results[0].live_pnl, dec!(-50));
+}
@@ -0,0 +1,380 @@
+use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use execution_engine::execution::exit_management::time_exits::is_weekend_close_window;
+use execution_engine::execution::exit_management::{
+    ExitAuditLogger, ExitManagementPlatformAdapter, ExitManagementSystem,
+};
+use execution_engine::execution::{SignalExtensions, TradeExecutionOrchestrator, TradeSignal};
+use execution_engine::platforms::abstraction::{
+    capabilities::PlatformCapabilities,
+    errors::PlatformError,
+    events::PlatformEvent,
+    interfaces::{DiagnosticsInfo, EventFilter, HealthStatus, ITradingPlatform, OrderFilter},
+    models::*,
+};
+use execution_engine::platforms::PlatformType;
+use execution_engine::testing::fixtures;
+
+/// Opens a position for every order it receives
+#[derive(Default)]
+struct MockPlatform {
+    positions: Mutex<Vec<UnifiedPosition>>,
+}
+
+impl MockPlatform {
+    fn open_position(&self, symbol: &str) -> UnifiedPosition {
+        let position = UnifiedPosition {
+            position_id: Uuid::new_v4().to_string(),
+            symbol: symbol.to_string(),
+            side: UnifiedPositionSide::Long,
+            quantity: dec!(10000),
+            entry_price: dec!(1.0850),
+            current_price: dec!(1.0850),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_used: Decimal::ZERO,
+            commission: Decimal::ZERO,
+            stop_loss: Some(dec!(1.0800)),
+            take_profit: None,
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            account_id: "acc-1".to_string(),
+            platform_specific: HashMap::new(),
+        };
+        self.positions.lock().unwrap().push(position.clone());
+        position
+    }
+
+    fn unsupported<T>() -> Result<T, PlatformError> {
+        Err(PlatformError::FeatureNotSupported {
+            feature: "mock".to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl ITradingPlatform for MockPlatform {
+    fn platform_type(&self) -> PlatformType {
+        PlatformType::DXTrade
+    }
+    fn platform_name(&self) -> &str {
+        "mock"
+    }
+    fn platform_version(&self) -> &str {
+        "1.0.0"
+    }
+    async fn connect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn disconnect(&mut self) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    async fn is_connected(&self) -> bool {
+        true
+    }
+    async fn ping(&self) -> Result<u64, PlatformError> {
+        Ok(1)
+    }
+    async fn place_order(
+        &self,
+        order: UnifiedOrder,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        let position = self.open_position(&order.symbol);
+        Ok(UnifiedOrderResponse {
+            platform_order_id: position.position_id,
+            client_order_id: order.client_order_id,
+            status: UnifiedOrderStatus::Filled,
+            symbol: order.symbol,
+            side: order.side,
+            order_type: order.order_type,
+            quantity: order.quantity,
+            filled_quantity: order.quantity,
+            remaining_quantity: Decimal::ZERO,
+            price: Some(dec!(1.0850)),
+            average_fill_price: Some(dec!(1.0850)),
+            commission: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            filled_at: Some(Utc::now()),
+            platform_specific: HashMap::new(),
+        })
+    }
+    async fn modify_order(
+        &self,
+        _order_id: &str,
+        _modifications: OrderModification,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn cancel_order(&self, _order_id: &str) -> Result<(), PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_order(&self, _order_id: &str) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_orders(
+        &self,
+        _filter: Option<OrderFilter>,
+    ) -> Result<Vec<UnifiedOrderResponse>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn get_positions(&self) -> Result<Vec<UnifiedPosition>, PlatformError> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+    async fn get_position(&self, symbol: &str) -> Result<Option<UnifiedPosition>, PlatformError> {
+        Ok(self
+            .positions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|p| p.symbol == symbol)
+            .cloned())
+    }
+    async fn close_position(
+        &self,
+        _symbol: &str,
+        _quantity: Option<Decimal>,
+    ) -> Result<UnifiedOrderResponse, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_account_info(&self) -> Result<UnifiedAccountInfo, PlatformError> {
+        Ok(UnifiedAccountInfo {
+            account_id: "acc-1".to_string(),
+            account_name: None,
+            currency: "USD".to_string(),
+            balance: dec!(100000),
+            equity: dec!(100000),
+            margin_used: Decimal::ZERO,
+            margin_available: dec!(100000),
+            buying_power: dec!(100000),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            margin_level: None,
+            account_type: AccountType::Demo,
+            last_updated: Utc::now(),
+            platform_specific: HashMap::new(),
+        })
+    }
+    async fn get_balance(&self) -> Result<Decimal, PlatformError> {
+        Ok(dec!(100000))
+    }
+    async fn get_margin_info(&self) -> Result<MarginInfo, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_market_data(&self, _symbol: &str) -> Result<UnifiedMarketData, PlatformError> {
+        Self::unsupported()
+    }
+    async fn subscribe_market_data(
+        &self,
+        _symbols: Vec<String>,
+    ) -> Result<mpsc::Receiver<UnifiedMarketData>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn unsubscribe_market_data(&self, _symbols: Vec<String>) -> Result<(), PlatformError> {
+        Ok(())
+    }
+    fn capabilities(&self) -> PlatformCapabilities {
+        PlatformCapabilities::new("mock".to_string())
+    }
+    async fn subscribe_events(&self) -> Result<mpsc::Receiver<PlatformEvent>, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_event_history(
+        &self,
+        _filter: EventFilter,
+    ) -> Result<Vec<PlatformEvent>, PlatformError> {
+        Ok(Vec::new())
+    }
+    async fn health_check(&self) -> Result<HealthStatus, PlatformError> {
+        Self::unsupported()
+    }
+    async fn get_diagnostics(&self) -> Result<DiagnosticsInfo, PlatformError> {
+        Self::unsupported()
+    }
+}
+
+fn metadata(entries: &[(&str, &str)]) -> HashMap<String, String> {
+    entries
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn signal(metadata: HashMap<String, String>) -> TradeSignal {
+    TradeSignal {
+        symbol: "GBPUSD".to_string(),
+        entry_price: 1.0850,
+        stop_loss: 1.0800,
+        take_profit: 1.0950,
+        metadata,
+        ..fixtures::signal("sig-1")
+    }
+}
+
+#[test]
+fn test_extensions_build_exit_policy() {
+    let extensions = SignalExtensions::parse(&metadata(&[
+        (
+            "exit_policy",
+            r#"{"name":"news-trade","trailing":{"atr_multiplier":1.0,"min_trail_distance":0.0005,"max_trail_distance":0.005,"activation_threshold":0.001,"symbol":"GBPUSD","timeframe":"M15"}}"#,
+        ),
+        ("max_holding_hours", "4"),
+        (
+            "partial_ladder",
+            r#"[{"level":1,"risk_reward_ratio":1.5,"close_percentage":0.6}]"#,
+        ),
+        ("no_weekend_hold", "true"),
+        ("source", "ignored"),
+    ]))
+    .unwrap();
+
+    let policy = extensions.exit_policy("sig-1").unwrap();
+    assert_eq!(policy.name.as_deref(), Some("news-trade"));
+    assert_eq!(policy.trailing.unwrap().min_trail_distance, dec!(0.0005));
+    let ladder = policy.profit_taking.unwrap();
+    assert_eq!(ladder.profit_targets.len(), 1);
+    assert_eq!(ladder.profit_targets[0].close_percentage, 0.6);
+    let time_exit = policy.time_exit.unwrap();
+    assert_eq!(time_exit.max_hold_duration, ChronoDuration::hours(4));
+    assert!(time_exit.warning_duration < time_exit.max_hold_duration);
+    assert!(time_exit.close_before_weekend);
+
+    assert!(SignalExtensions::parse(&HashMap::new())
+        .unwrap()
+        .exit_policy("sig-1")
+        .is_none());
+
+    // Built without parsing, a holding time past the limit is capped to it
+    let extensions = SignalExtensions {
+        max_holding_hours: Some(1e300),
+        ..SignalExtensions::default()
+    };
+    let time_exit = extensions.exit_policy("sig-1").unwrap().time_exit.unwrap();
+    assert_eq!(time_exit.max_hold_duration, ChronoDuration::hours(24 * 366));
+
+    // and one that is not a positive number is ignored rather than closing the
+    // position on open
+    for hours in [f64::NAN, 0.0, -2.0] {
+        let extensions = SignalExtensions {
+            max_holding_hours: Some(hours),
+            ..SignalExtensions::default()
+        };
+        assert!(
+            extensions.exit_policy("sig-1").unwrap().time_exit.is_none(),
+            "{}",
+            hours
+        );
+    }
+    for (key, value) in [
+        ("max_holding_hours", "-2"),
+        ("max_holding_hours", "1e300"),
+        ("max_holding_hours", "NaN"),
+        ("no_weekend_hold", "yes"),
+        ("exit_policy", "{"),
+        (
+            "partial_ladder",
+            r#"[{"level":1,"risk_reward_ratio":1.0,"close_percentage":0.7},{"level":2,"risk_reward_ratio":2.0,"close_percentage":0.7}]"#,
+        ),
+    ] {
+        let error = SignalExtensions::parse(&metadata(&[(key, value)])).unwrap_err();
+        assert!(error.starts_with(key), "{}", error);
+    }
+}
+
+#[test]
+fn test_weekend_close_window() {
+    let at = |d, h| Utc.with_ymd_and_hms(2026, 10, d, h, 0, 0).unwrap();
+    // 16 October 2026 is a Friday
+    assert!(!is_weekend_close_window(at(16, 19)));
+    assert!(is_weekend_close_window(at(16, 20)));
+    assert!(is_weekend_close_window(at(17, 12)));
+    assert!(is_weekend_close_window(at(18, 21)));
+    assert!(!is_weekend_close_window(at(18, 22)));
+    assert!(!is_weekend_close_window(at(19, 9)));
+}
+
+#[tokio::test]
+async fn test_signal_policy_reaches_the_position_it_opens() {
+    let platform = Arc::new(MockPlatform::default());
+    let orchestrator = TradeExecutionOrchestrator::new();
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+
+    // An earlier position on the same symbol must not pick up the policy
+    let existing = platform.open_position("GBPUSD");
+
+    let mut plan = orchestrator
+        .process_signal(signal(metadata(&[
+            ("max_holding_hours", "6"),
+            ("no_weekend_hold", "true"),
+        ])))
+        .await
+        .unwrap();
+    let expected = plan
+        .exit_policy
+        .clone()
+        .expect("plan should carry the policy");
+    for assignment in &mut plan.account_assignments {
+        assignment.entry_timing_delay = Duration::ZERO;
+    }
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let results = orchestrator.execute_plan(&plan).await;
+    assert!(results[0].success);
+
+    let system = ExitManagementSystem::new(
+        Arc::new(ExitManagementPlatformAdapter::new(platform.clone())),
+        Arc::new(ExitAuditLogger::new()),
+    );
+    for pending in orchestrator.take_pending_exit_policies("acc-1").await {
+        system.expect_position_policy(pending);
+    }
+    assert!(orchestrator
+        .take_pending_exit_policies("acc-1")
+        .await
+        .is_empty());
+    system.run_position_checks().await;
+
+    let opened = platform.positions.lock().unwrap()[1].clone();
+    assert_eq!(opened.symbol, "GBPUSD");
+    let attached = system
+        .get_position_policy(opened.position_id.parse().unwrap())
+        .expect("new position should carry the signal policy");
+    assert_eq!(attached, expected);
+    assert_eq!(attached.name.as_deref(), Some("signal sig-1"));
+    assert!(system
+        .get_position_policy(existing.position_id.parse().unwrap())
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_invalid_metadata_rejects_signal() {
+    let platform = Arc::new(MockPlatform::default());
+    let orchestrator = TradeExecutionOrchestrator::new();
+    orchestrator
+        .register_account("acc-1".to_string(), platform, 100000.0)
+        .await
+        .unwrap();
+
+    let error = orchestrator
+        .process_signal(signal(metadata(&[("max_holding_hours", "soon")])))
+        .await
+        .unwrap_err();
+    assert!(error.contains("Invalid signal metadata"));
+
+    // Without extensions the position keeps the symbol configuration
+    let plan = orchestrator
+        .process_signal(signal(HashMap::new()))
+        .await
+        .unwrap();
+    assert!(plan.exit_policy.is_none());
+}
@@ -0,0 +1,165 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+use execution_engine::execution::{TradeExecutionOrchestrator, TradeSignal};
+use execution_engine::journal::{TradeEntry, TradeRecord, TradeStatus};
+use execution_engine::platforms::abstraction::models::{UnifiedOrderSide, UnifiedPositionSide};
+use execution_engine::reports::{
+    AllocationConfig, SignalQualityConfig, SignalQualityReport, StrategyScores,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+fn opened_at(hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 4, hour, 15, 0).unwrap()
+}
+
+/// A closed EURUSD trade risking 0.0050 on 10000 units, i.e. 50 per R
+fn trade(tag: &str, hour: u32, r: Decimal) -> TradeRecord {
+    let mut record = TradeRecord::open(TradeEntry {
+        account_id: "acc-1".to_string(),
+        position_id: format!("P-{}", uuid::Uuid::new_v4()),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity: dec!(10000),
+        entry_price: dec!(1.1000),
+        stop_loss: Some(dec!(1.0950)),
+        take_profit: None,
+        signal_id: None,
+        order_id: None,
+        opened_at: opened_at(hour),
+        tags: vec![tag.to_string()],
+    });
+    record.status = TradeStatus::Closed;
+    record.realized_pnl = r * dec!(50);
+    record.r_multiple = Some(r);
+    record.closed_quantity = dec!(10000);
+    record.closed_at = Some(opened_at(hour) + Duration::hours(1));
+    record
+}
+
+fn config(min_trades: usize, allocation: bool) -> SignalQualityConfig {
+    SignalQualityConfig {
+        min_trades,
+        shrinkage_trades: 2,
+        allocation: AllocationConfig {
+            enabled: allocation,
+            ..AllocationConfig::default()
+        },
+        ..SignalQualityConfig::default()
+    }
+}
+
+#[test]
+fn strategies_are_scored_from_their_closed_trades() {
+    let mut trades = vec![
+        trade("strategy:breakout", 8, dec!(2)),
+        trade("strategy:breakout", 8, dec!(-1)),
+        trade("strategy:breakout", 14, dec!(2)),
+        trade("strategy:breakout", 14, dec!(1)),
+        trade("agent:wyckoff", 9, dec!(-1)),
+    ];
+    let mut open = trade("strategy:breakout", 8, dec!(5));
+    open.status = TradeStatus::Open;
+    trades.push(open);
+
+    let report = SignalQualityReport::compile(&trades, &config(3, false), opened_at(0), Utc::now());
+
+    let breakout = report.strategy("strategy:breakout").unwrap();
+    assert_eq!(breakout.trades, 4);
+    assert_eq!(breakout.wins, 3);
+    assert_eq!(breakout.hit_rate, dec!(0.75));
+    assert_eq!(breakout.realized_pnl, dec!(200));
+    assert_eq!(breakout.average_r, Some(dec!(1)));
+    // Shrunk by two trades' worth of weight: 1R * 4 / (4 + 2)
+    assert_eq!(breakout.score.unwrap().round_dp(4), dec!(0.6667));
+    assert_eq!(breakout.by_hour[&8].trades, 2);
+    assert_eq!(breakout.by_hour[&8].average_r, Some(dec!(0.5)));
+    assert_eq!(breakout.by_hour[&14].average_r, Some(dec!(1.5)));
+    // A basis point of 1.1 is 0.00011, or 0.022R against a 0.0050 stop
+    assert_eq!(breakout.r_per_slippage_bp, Some(dec!(0.022)));
+    assert_eq!(
+        breakout.breakeven_slippage_bps.unwrap().round_dp(2),
+        dec!(45.45)
+    );
+    assert_eq!(breakout.allocation_multiplier, 1.0);
+
+    let wyckoff = report.strategy("agent:wyckoff").unwrap();
+    assert_eq!(wyckoff.score, None);
+    assert_eq!(wyckoff.breakeven_slippage_bps, None);
+    assert_eq!(report.strategies[0].strategy, "strategy:breakout");
+}
+
+#[test]
+fn allocation_follows_the_score_within_its_bounds() {
+    let trades: Vec<TradeRecord> = (0..8)
+        .map(|_| trade("strategy:trend", 10, dec!(3)))
+        .chain((0..8).map(|_| trade("strategy:fade", 10, dec!(-0.5))))
+        .collect();
+    let scores = StrategyScores::new(config(5, true));
+    scores.update(&trades, Utc::now());
+
+    let trend = scores.allocation_multiplier(&["strategy:trend".to_string()]);
+    let fade = scores.allocation_multiplier(&["strategy:fade".to_string()]);
+    let unknown = scores.allocation_multiplier(&["strategy:new".to_string()]);
+
+    assert_eq!(trend, 1.5);
+    // -0.5R * 8 / 10 = -0.4R, sized at 1 - 0.4 * 0.5
+    assert!((fade - 0.8).abs() < 1e-9);
+    assert_eq!(unknown, 1.0);
+}
+
+#[tokio::test]
+async fn the_orchestrator_sizes_signals_by_their_strategy_score() {
+    let trades: Vec<TradeRecord> = (0..10)
+        .map(|_| trade("strategy:fade", 10, dec!(-2)))
+        .collect();
+    let scores = Arc::new(StrategyScores::new(config(5, true)));
+    scores.update(&trades, Utc::now());
+
+    let signal = |tags: &str| TradeSignal {
+        id: format!("sig-{}", tags),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedOrderSide::Buy,
+        entry_price: 1.1,
+        stop_loss: 1.09,
+        take_profit: 1.12,
+        confidence: 0.8,
+        risk_reward_ratio: 2.0,
+        signal_time: std::time::SystemTime::now(),
+        metadata: [("strategy".to_string(), tags.to_string())]
+            .into_iter()
+            .collect(),
+    };
+
+    let plain = TradeExecutionOrchestrator::new();
+    let scored = TradeExecutionOrchestrator::new().with_strategy_scores(scores);
+    for orchestrator in [&plain, &scored] {
+        orchestrator
+            .register_account(
+                "acc-1".to_string(),
+                Arc::new(MockTradingPlatform::new("acc-1")),
+                100000.0,
+            )
+            .await
+            .unwrap();
+    }
+
+    let plain_size = plain
+        .process_signal(signal("fade"))
+        .await
+        .unwrap()
+        .account_assignments[0]
+        .position_size;
+    let scored_plan = scored.process_signal(signal("fade")).await.unwrap();
+    let scored_size = scored_plan.account_assignments[0].position_size;
+
+    // Both carry random size variance of 5-15%, so compare against its range
+    assert!(scored_size < plain_size * 0.5 * 1.15 / 0.85 + 0.01);
+    assert!(scored
+        .get_execution_history(10)
+        .await
+        .iter()
+        .any(|entry| entry.action == "STRATEGY_ALLOCATION"));
+}
@@ -0,0 +1,111 @@
+use execution_engine::testing::{
+    run_scenario_file, Expectations, SimulationHarness, SimulationScenario,
+};
+use rust_decimal_macros::dec;
+
+fn scenario_path(name: &str) -> String {
+    format!(
+        "{}/tests/scenarios/simulation/{}.yaml",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    )
+}
+
+#[test]
+fn scenario_files_load() {
+    for name in ["news_then_break_even", "stop_out_drawdown"] {
+        let scenario = SimulationScenario::load(scenario_path(name))
+            .unwrap_or_else(|e| panic!("{}: {:#}", name, e));
+        assert_eq!(scenario.name, name);
+        assert!(!scenario.accounts.is_empty());
+    }
+
+    let scenario = SimulationScenario::load(scenario_path("stop_out_drawdown")).unwrap();
+    assert_eq!(scenario.risk.daily, dec!(0.3));
+    assert!(scenario.accounts[1].chaos.is_some());
+}
+
+#[tokio::test]
+async fn news_tightens_stops_before_break_even() {
+    let report = run_scenario_file(scenario_path("news_then_break_even"))
+        .await
+        .unwrap_or_else(|e| panic!("{:#}", e));
+
+    // The second release finds the stops already at break-even and leaves them there
+    for entries in report.audit_entries.values() {
+        assert_eq!(entries.len(), 2);
+    }
+    assert!(report.rejected_signals.is_empty());
+}
+
+#[tokio::test]
+async fn stop_out_raises_drawdown_alert_on_filled_account_only() {
+    let report = run_scenario_file(scenario_path("stop_out_drawdown"))
+        .await
+        .unwrap_or_else(|e| panic!("{:#}", e));
+
+    let failed: Vec<_> = report.executions.iter().filter(|r| !r.success).collect();
+    assert_eq!(failed[0].account_id, "rejecting");
+    assert!(report.risk_alerts[0].drawdown_percentage > dec!(0.3));
+}
+
+#[tokio::test]
+async fn verify_reports_every_mismatch() {
+    let mut scenario = SimulationScenario::load(scenario_path("news_then_break_even")).unwrap();
+    scenario.expect = Expectations::default();
+    let report = SimulationHarness::new(scenario)
+        .await
+        .unwrap()
+        .run()
+        .await
+        .unwrap();
+
+    let wrong = SimulationScenario::from_yaml_str(
+        r#"
+name: wrong
+accounts: []
+expect:
+  successful_executions: 3
+  positions: []
+  risk_alerts:
+    - { account: alpha, kind: maximum }
+"#,
+    )
+    .unwrap()
+    .expect;
+
+    let message = format!("{:#}", report.verify(&wrong).unwrap_err());
+    assert!(message.contains("expected 3 successful executions, found 2"));
+    assert!(message.contains("expected 0 open positions, found 2"));
+    assert!(message.contains("no maximum drawdown alert for alpha"));
+}
+
+#[tokio::test]
+async fn signal_rejected_when_no_account_is_eligible() {
+    let scenario = SimulationScenario::from_yaml_str(
+        r#"
+name: underfunded
+accounts:
+  - { id: small, balance: 500 }
+prices:
+  - { at: 0, symbol: EURUSD, bid: 1.0850, ask: 1.0852 }
+signals:
+  - { at: 10, id: sig-1, symbol: EURUSD, side: buy, entry_price: 1.0852, stop_loss: 1.0752, take_profit: 1.1052 }
+expect:
+  successful_executions: 0
+  rejected_signals: [sig-1]
+  positions: []
+"#,
+    )
+    .unwrap();
+    let expect = scenario.expect.clone();
+
+    let harness = SimulationHarness::new(scenario).await.unwrap();
+    let report = harness.run().await.unwrap();
+    report.verify(&expect).unwrap();
+    assert_eq!(report.timeline.len(), 3);
+    assert_eq!(
+        (report.timeline[2].at - report.timeline[0].at).num_seconds(),
+        10
+    );
+}
@@ -0,0 +1,228 @@
+use chrono::Utc;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+use execution_engine::execution::{
+    SlippageBreachAction, SlippageGuardConfig, TradeExecutionOrchestrator,
+};
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::{PriceLevel, UnifiedOrderBook};
+use execution_engine::platforms::abstraction::{
+    PlatformCapabilities, PlatformError, PlatformFeature, RejectionReason,
+};
+use execution_engine::testing::{signal, MockTradingPlatform, Operation};
+
+async fn orchestrator(
+    config: SlippageGuardConfig,
+    platform: MockTradingPlatform,
+) -> (TradeExecutionOrchestrator, Arc<MockTradingPlatform>) {
+    let orchestrator = TradeExecutionOrchestrator::new()
+        .with_timing_variance(0, 0)
+        .with_slippage_guard(SlippageGuardConfig {
+            enabled: true,
+            ..config
+        });
+    let platform = Arc::new(platform.with_quote("EURUSD", dec!(1.0999), dec!(1.1001)));
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 100000.0)
+        .await
+        .unwrap();
+    (orchestrator, platform)
+}
+
+fn enforcing_platform() -> MockTradingPlatform {
+    let mut capabilities = PlatformCapabilities::new("mock".to_string());
+    capabilities.features.insert(PlatformFeature::MaxSlippage);
+    MockTradingPlatform::new("acc-1").with_capabilities(capabilities)
+}
+
+#[tokio::test]
+async fn fills_beyond_the_maximum_are_flattened() {
+    let (orchestrator, platform) = orchestrator(
+        SlippageGuardConfig::default(),
+        MockTradingPlatform::new("acc-1"),
+    )
+    .await;
+    platform.set_slippage(dec!(0.0005));
+
+    let plan = orchestrator
+        .process_signal(signal("sig-slippage"))
+        .await
+        .unwrap();
+    let result = &orchestrator.execute_plan(&plan).await[0];
+
+    assert!(!result.success);
+    assert!(result.order_id.is_some());
+    assert!(result
+        .error_message
+        .as_ref()
+        .unwrap()
+        .ends_with("; flattened"));
+    assert!((result.slippage.unwrap() - 0.0005).abs() < 1e-9);
+    assert!(platform.get_positions().await.unwrap().is_empty());
+
+    let stats = orchestrator.slippage_stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(
+        (stats[0].fills, stats[0].breaches, stats[0].flattened),
+        (1, 1, 1)
+    );
+    assert!((stats[0].worst_slippage_pips - 5.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn alert_only_breaches_keep_the_position() {
+    let (orchestrator, platform) = orchestrator(
+        SlippageGuardConfig {
+            on_breach: SlippageBreachAction::Alert,
+            ..SlippageGuardConfig::default()
+        },
+        MockTradingPlatform::new("acc-1").with_hedging(),
+    )
+    .await;
+
+    platform.set_slippage(dec!(0.0001));
+    let plan = orchestrator
+        .process_signal(signal("sig-slippage"))
+        .await
+        .unwrap();
+    assert!(orchestrator.execute_plan(&plan).await[0].success);
+
+    platform.set_slippage(dec!(0.0003));
+    let plan = orchestrator
+        .process_signal(signal("sig-slippage"))
+        .await
+        .unwrap();
+    let result = &orchestrator.execute_plan(&plan).await[0];
+    assert!(result.success);
+    assert_eq!(result.actual_entry_price, Some(1.1004));
+
+    assert_eq!(platform.get_positions().await.unwrap().len(), 2);
+    let stats = &orchestrator.slippage_stats()[0];
+    assert_eq!((stats.fills, stats.breaches, stats.flattened), (2, 1, 0));
+    assert!((stats.average_slippage_pips().unwrap() - 2.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn platforms_enforcing_the_maximum_requote_instead() {
+    let (orchestrator, platform) =
+        orchestrator(SlippageGuardConfig::default(), enforcing_platform()).await;
+
+    // A requote is resent at the new price
+    platform.script_error(
+        Operation::PlaceOrder,
+        PlatformError::OrderRejected {
+            reason: "Requote".to_string(),
+            platform_code: None,
+        },
+    );
+    let plan = orchestrator
+        .process_signal(signal("sig-slippage"))
+        .await
+        .unwrap();
+    let result = &orchestrator.execute_plan(&plan).await[0];
+    assert!(result.success);
+    assert_eq!(result.rejection, Some(RejectionReason::Requote));
+    assert_eq!(result.slippage, Some(0.0));
+
+    // The platform refuses every fill beyond the maximum
+    platform.set_slippage(dec!(0.0005));
+    let plan = orchestrator
+        .process_signal(signal("sig-slippage"))
+        .await
+        .unwrap();
+    let result = &orchestrator.execute_plan(&plan).await[0];
+    assert!(!result.success);
+    assert_eq!(result.rejection, Some(RejectionReason::Requote));
+    assert_eq!(orchestrator.slippage_stats()[0].breaches, 0);
+}
+
+#[tokio::test]
+async fn orders_are_held_back_from_wide_spreads() {
+    let (orchestrator, platform) = orchestrator(
+        SlippageGuardConfig {
+            max_spread_pips: Some(1.5),
+            ..SlippageGuardConfig::default()
+        },
+        MockTradingPlatform::new("acc-1"),
+    )
+    .await;
+    platform.set_quote("EURUSD", dec!(1.0995), dec!(1.1005));
+
+    let plan = orchestrator
+        .process_signal(signal("sig-slippage"))
+        .await
+        .unwrap();
+    let result = &orchestrator.execute_plan(&plan).await[0];
+
+    assert!(!result.success);
+    assert_eq!(
+        result.error_message.as_deref(),
+        Some("Spread on EURUSD is 10.0 pips, wider than the 1.5 allowed")
+    );
+    assert!(platform.orders().is_empty());
+}
+
+/// A book with 1000 at the quote and the rest of the liquidity 9 pips away
+fn thin_book() -> UnifiedOrderBook {
+    let level = |price, volume| PriceLevel {
+        price,
+        volume,
+        order_count: None,
+    };
+    UnifiedOrderBook {
+        symbol: "EURUSD".to_string(),
+        bids: vec![level(dec!(1.0999), dec!(1000))],
+        asks: vec![
+            level(dec!(1.1001), dec!(1000)),
+            level(dec!(1.1010), dec!(10000000)),
+        ],
+        timestamp: Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn orders_are_held_back_from_thin_books() {
+    let (orchestrator, platform) = orchestrator(
+        SlippageGuardConfig {
+            depth_levels: 5,
+            ..SlippageGuardConfig::default()
+        },
+        MockTradingPlatform::new("acc-1").with_order_book(thin_book()),
+    )
+    .await;
+
+    let plan = orchestrator
+        .process_signal(signal("sig-slippage"))
+        .await
+        .unwrap();
+    let result = &orchestrator.execute_plan(&plan).await[0];
+
+    assert!(!result.success);
+    assert!(result
+        .error_message
+        .as_deref()
+        .unwrap()
+        .starts_with("Best 5 levels of EURUSD hold 1000 within 2.0 pips of the quote"));
+    assert!(platform.orders().is_empty());
+}
+
+#[tokio::test]
+async fn orders_can_be_sized_to_the_book() {
+    let (orchestrator, platform) = orchestrator(
+        SlippageGuardConfig {
+            depth_levels: 5,
+            size_to_depth: true,
+            ..SlippageGuardConfig::default()
+        },
+        MockTradingPlatform::new("acc-1").with_order_book(thin_book()),
+    )
+    .await;
+
+    let plan = orchestrator
+        .process_signal(signal("sig-slippage"))
+        .await
+        .unwrap();
+    assert!(orchestrator.execute_plan(&plan).await[0].success);
+    assert_eq!(platform.orders()[0].quantity, dec!(1000));
+}
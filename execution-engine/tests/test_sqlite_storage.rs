@@ -0,0 +1,203 @@
+#![cfg(feature = "sqlite")]
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+use execution_engine::execution::exit_management::PositionExitCheckpoint;
+use execution_engine::execution::{
+    ExecutionAuditEntry, ExecutionHistoryQuery, RetentionPolicy, TradeExecutionOrchestrator,
+};
+use execution_engine::journal::{TradeJournal, TradeQuery, TradeStatus};
+use execution_engine::platforms::abstraction::events::PositionCloseEventData;
+use execution_engine::runtime::config::EngineConfig;
+use execution_engine::storage::{
+    open_storage, SqliteStorage, Storage, StorageConfig, StorageProfile,
+};
+use execution_engine::testing::platform_long_position;
+
+const DAY: Duration = Duration::from_secs(86_400);
+
+fn entry(id: &str, account_id: &str, action: &str, age: Duration) -> ExecutionAuditEntry {
+    ExecutionAuditEntry {
+        id: id.to_string(),
+        timestamp: SystemTime::now() - age,
+        signal_id: format!("sig-{}", id),
+        account_id: account_id.to_string(),
+        action: action.to_string(),
+        decision_rationale: String::new(),
+        result: None,
+        metadata: HashMap::new(),
+        tags: vec![format!("account:{}", account_id)],
+    }
+}
+
+fn checkpoint() -> PositionExitCheckpoint {
+    PositionExitCheckpoint {
+        position_id: Uuid::new_v4(),
+        trailing_stop: None,
+        break_even_active: true,
+        profit_targets: None,
+        time_exit_warned: false,
+        exit_policy: None,
+        saved_at: Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn journal_survives_reopening_the_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state").join("tmt.db");
+
+    let storage = SqliteStorage::open(&path).await.unwrap();
+    let journal = TradeJournal::new().with_store(storage.journal());
+    let open = platform_long_position("EURUSD");
+    let closed = platform_long_position("GBPUSD");
+    journal.record_open("acc-1", &open).await;
+    journal.record_open("acc-2", &closed).await;
+    journal
+        .record_close(
+            "acc-2",
+            &PositionCloseEventData {
+                position_id: closed.position_id.clone(),
+                symbol: closed.symbol.clone(),
+                side: closed.side.clone(),
+                closing_order_id: Uuid::new_v4().to_string(),
+                entry_price: closed.entry_price,
+                close_price: dec!(1.1100),
+                closed_quantity: dec!(10000),
+                remaining_quantity: dec!(0),
+                realized_pnl: dec!(100),
+                commission: Decimal::ZERO,
+                closed_at: Utc::now(),
+            },
+        )
+        .await;
+    drop(journal);
+    drop(storage);
+
+    let storage = SqliteStorage::open(&path).await.unwrap();
+    let reloaded = TradeJournal::new().with_store(storage.journal());
+    assert_eq!(reloaded.load().await.unwrap(), 2);
+    let closed_trades = reloaded
+        .query(&TradeQuery {
+            status: Some(TradeStatus::Closed),
+            ..Default::default()
+        })
+        .await;
+    assert_eq!(closed_trades.len(), 1);
+    assert_eq!(closed_trades[0].entry.symbol, "GBPUSD");
+}
+
+#[tokio::test]
+async fn execution_history_is_queried_and_purged_in_the_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let storage = SqliteStorage::open(dir.path().join("tmt.db"))
+        .await
+        .unwrap();
+    let history = storage.execution_history().unwrap();
+
+    for (i, age) in (0..6u32).rev().enumerate() {
+        let account = if i % 2 == 0 { "alpha" } else { "beta" };
+        let action = if i == 1 {
+            "EXECUTION_FAILED"
+        } else {
+            "EXECUTION_SUCCESS"
+        };
+        let entry = entry(&format!("e{}", i), account, action, DAY * age * 30);
+        history.append(&entry).await.unwrap();
+        // Appending twice keeps one copy
+        history.append(&entry).await.unwrap();
+    }
+
+    let page = history
+        .query(&ExecutionHistoryQuery::recent(2))
+        .await
+        .unwrap();
+    assert_eq!(page.total, 6);
+    let ids: Vec<&str> = page.entries.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, vec!["e4", "e5"]);
+    assert_eq!(page.next_offset, Some(2));
+
+    let alpha = history
+        .query(&ExecutionHistoryQuery {
+            tags: vec!["account:alpha".to_string()],
+            limit: 10,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let ids: Vec<&str> = alpha.entries.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, vec!["e0", "e2", "e4"]);
+
+    // Failures are kept a year, everything else 90 days
+    let policy = RetentionPolicy {
+        default_days: 90,
+        actions: HashMap::from([("EXECUTION_FAILED".to_string(), 365)]),
+    };
+    assert_eq!(history.purge(&policy, SystemTime::now()).await.unwrap(), 2);
+    let remaining = history
+        .query(&ExecutionHistoryQuery::recent(10))
+        .await
+        .unwrap();
+    let ids: Vec<&str> = remaining.entries.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, vec!["e1", "e3", "e4", "e5"]);
+}
+
+#[tokio::test]
+async fn exit_checkpoints_are_kept_per_account() {
+    let dir = tempfile::tempdir().unwrap();
+    let storage = SqliteStorage::open(dir.path().join("tmt.db"))
+        .await
+        .unwrap();
+    let acc1 = storage.exit_state("acc-1").unwrap();
+    let acc2 = storage.exit_state("acc-2").unwrap();
+
+    let first = checkpoint();
+    let second = checkpoint();
+    acc1.save(&first).await.unwrap();
+    acc1.save(&second).await.unwrap();
+    acc1.save(&second).await.unwrap();
+
+    assert_eq!(acc1.load_all().await.unwrap().len(), 2);
+    assert!(acc2.load_all().await.unwrap().is_empty());
+
+    acc1.remove(first.position_id).await.unwrap();
+    let remaining = acc1.load_all().await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].position_id, second.position_id);
+    assert!(remaining[0].break_even_active);
+}
+
+#[tokio::test]
+async fn config_selects_the_sqlite_profile_for_orchestrator_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = EngineConfig {
+        storage: StorageConfig {
+            profile: StorageProfile::Sqlite,
+            sqlite_path: dir.path().join("tmt.db").display().to_string(),
+        },
+        ..Default::default()
+    };
+
+    let storage = open_storage(&config).await.unwrap();
+    assert_eq!(storage.profile(), StorageProfile::Sqlite);
+    assert!(storage.orchestrator_state().load().await.unwrap().is_none());
+
+    let orchestrator = TradeExecutionOrchestrator::new();
+    orchestrator
+        .persist_state_to(storage.orchestrator_state().as_ref())
+        .await
+        .unwrap();
+    orchestrator
+        .persist_state_to(storage.orchestrator_state().as_ref())
+        .await
+        .unwrap();
+
+    let reopened = open_storage(&config).await.unwrap();
+    let snapshot = reopened.orchestrator_state().load().await.unwrap().unwrap();
+    assert!(snapshot.accounts.is_empty());
+}
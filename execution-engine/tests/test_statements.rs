@@ -0,0 +1,415 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::execution::TradeExecutionOrchestrator;
+use execution_engine::ledger::{
+    reconcile_statement, FillDiscrepancy, LedgerEvent, LedgerEventKind, PositionLedger,
+    StatementConfig, StatementReconciler, SwapRates,
+};
+use execution_engine::platforms::abstraction::models::*;
+use execution_engine::platforms::abstraction::{ITradingPlatform, PlatformError};
+use execution_engine::risk::{
+    FileFundingEventStore, FundingConfig, FundingDetector, FundingSource, TradingDayRollover,
+};
+use execution_engine::testing::{MockTradingPlatform, Operation};
+
+fn at(day: u32, hour: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, day, hour, min, 0).unwrap()
+}
+
+fn event(sequence: u64, timestamp: DateTime<Utc>, kind: LedgerEventKind) -> LedgerEvent {
+    LedgerEvent {
+        sequence,
+        account_id: "acc-1".to_string(),
+        timestamp,
+        source_event_id: None,
+        kind,
+    }
+}
+
+fn opened(
+    order_id: &str,
+    position_id: &str,
+    symbol: &str,
+    side: UnifiedPositionSide,
+    quantity: Decimal,
+    price: Decimal,
+    commission: Decimal,
+) -> LedgerEventKind {
+    LedgerEventKind::Fill {
+        order_id: order_id.to_string(),
+        position_id: position_id.to_string(),
+        symbol: symbol.to_string(),
+        side,
+        quantity,
+        price,
+        commission,
+    }
+}
+
+fn entry(
+    id: &str,
+    kind: UnifiedTransactionKind,
+    amount: Decimal,
+    timestamp: DateTime<Utc>,
+) -> UnifiedTransaction {
+    UnifiedTransaction {
+        transaction_id: id.to_string(),
+        account_id: "acc-1".to_string(),
+        kind,
+        symbol: None,
+        order_id: None,
+        position_id: None,
+        side: None,
+        quantity: None,
+        price: None,
+        commission: None,
+        amount,
+        currency: "USD".to_string(),
+        balance_after: None,
+        timestamp,
+        description: String::new(),
+        platform_specific: HashMap::new(),
+    }
+}
+
+fn fill(
+    id: &str,
+    order_id: &str,
+    symbol: &str,
+    quantity: Decimal,
+    price: Decimal,
+    commission: Decimal,
+    timestamp: DateTime<Utc>,
+) -> UnifiedTransaction {
+    UnifiedTransaction {
+        symbol: Some(symbol.to_string()),
+        order_id: Some(order_id.to_string()),
+        quantity: Some(quantity),
+        price: Some(price),
+        commission: Some(commission),
+        ..entry(id, UnifiedTransactionKind::Fill, Decimal::ZERO, timestamp)
+    }
+}
+
+fn swap(
+    id: &str,
+    position_id: Option<&str>,
+    symbol: &str,
+    amount: Decimal,
+    timestamp: DateTime<Utc>,
+) -> UnifiedTransaction {
+    UnifiedTransaction {
+        symbol: Some(symbol.to_string()),
+        position_id: position_id.map(str::to_string),
+        ..entry(id, UnifiedTransactionKind::Swap, amount, timestamp)
+    }
+}
+
+#[test]
+fn fills_commission_and_swaps_are_checked_against_the_ledger() {
+    let events = vec![
+        event(
+            1,
+            at(4, 13, 0),
+            opened(
+                "o-1",
+                "p-1",
+                "EURUSD",
+                UnifiedPositionSide::Long,
+                dec!(10000),
+                dec!(1.1),
+                dec!(3),
+            ),
+        ),
+        event(
+            2,
+            at(4, 14, 0),
+            opened(
+                "o-2",
+                "p-2",
+                "GBPUSD",
+                UnifiedPositionSide::Short,
+                dec!(5000),
+                dec!(1.25),
+                dec!(2),
+            ),
+        ),
+        event(
+            3,
+            at(5, 10, 0),
+            LedgerEventKind::Closed {
+                position_id: "p-2".to_string(),
+                closing_order_id: "o-3".to_string(),
+                quantity: dec!(5000),
+                price: dec!(1.24),
+                commission: dec!(2),
+                reported_pnl: Decimal::ZERO,
+            },
+        ),
+        event(
+            4,
+            at(6, 1, 0),
+            opened(
+                "o-4",
+                "p-3",
+                "EURUSD",
+                UnifiedPositionSide::Long,
+                dec!(1000),
+                dec!(1.2),
+                Decimal::ZERO,
+            ),
+        ),
+    ];
+    let transactions = vec![
+        // o-1 filled in two parts
+        fill(
+            "t-1",
+            "o-1",
+            "EURUSD",
+            dec!(6000),
+            dec!(1.1),
+            dec!(1.5),
+            at(4, 13, 0),
+        ),
+        fill(
+            "t-2",
+            "o-1",
+            "EURUSD",
+            dec!(4000),
+            dec!(1.1),
+            dec!(1.5),
+            at(4, 13, 0),
+        ),
+        fill(
+            "t-3",
+            "o-2",
+            "GBPUSD",
+            dec!(5000),
+            dec!(1.2501),
+            dec!(2),
+            at(4, 14, 0),
+        ),
+        fill(
+            "t-4",
+            "o-3",
+            "GBPUSD",
+            dec!(5000),
+            dec!(1.24),
+            dec!(2),
+            at(5, 10, 0),
+        ),
+        fill(
+            "t-5",
+            "o-5",
+            "EURUSD",
+            dec!(2000),
+            dec!(1.1),
+            Decimal::ZERO,
+            at(5, 11, 0),
+        ),
+        entry(
+            "t-6",
+            UnifiedTransactionKind::Commission,
+            dec!(-1),
+            at(5, 12, 0),
+        ),
+        swap("t-7", Some("p-1"), "EURUSD", dec!(-1), at(5, 0, 5)),
+        // Booked by symbol only
+        swap("t-8", None, "GBPUSD", dec!(-0.4), at(5, 0, 5)),
+        // Wednesday's rollover charges three nights; this is one
+        swap("t-9", Some("p-1"), "EURUSD", dec!(-1), at(6, 0, 5)),
+        swap("t-10", None, "USDJPY", dec!(-2), at(6, 0, 10)),
+        entry(
+            "t-11",
+            UnifiedTransactionKind::Transfer,
+            dec!(500),
+            at(5, 15, 0),
+        ),
+    ];
+    let config = StatementConfig {
+        swap_rates: HashMap::from([(
+            "EURUSD".to_string(),
+            SwapRates {
+                long: dec!(-0.0001),
+                short: dec!(0.00005),
+            },
+        )]),
+        ..StatementConfig::default()
+    };
+
+    let reconciliation = reconcile_statement(
+        "acc-1",
+        TransactionRange::new(at(4, 12, 0), at(6, 12, 0)),
+        &transactions,
+        &events,
+        &TradingDayRollover::default(),
+        &config,
+    );
+
+    assert_eq!(reconciliation.fills_matched, 2);
+    assert_eq!(
+        reconciliation.fill_discrepancies,
+        vec![
+            FillDiscrepancy::PriceMismatch {
+                order_id: "o-2".to_string(),
+                ledger: dec!(1.25),
+                statement: dec!(1.2501),
+            },
+            FillDiscrepancy::MissingFromLedger {
+                order_id: "o-5".to_string(),
+                symbol: Some("EURUSD".to_string()),
+                quantity: dec!(2000),
+                price: dec!(1.1),
+            },
+            FillDiscrepancy::MissingFromStatement {
+                order_id: "o-4".to_string(),
+                quantity: dec!(1000),
+                price: dec!(1.2),
+            },
+        ]
+    );
+    assert_eq!(reconciliation.statement_commission, dec!(8));
+    assert_eq!(reconciliation.ledger_commission, dec!(7));
+
+    // Tuesday's rollover held both positions, Wednesday's only EURUSD
+    let swaps: Vec<(&str, Option<Decimal>, Decimal)> = reconciliation
+        .swaps
+        .iter()
+        .map(|s| (s.position_id.as_str(), s.expected, s.charged))
+        .collect();
+    assert_eq!(
+        swaps,
+        vec![
+            ("p-1", Some(dec!(-1)), dec!(-1)),
+            ("p-2", None, dec!(-0.4)),
+            ("p-1", Some(dec!(-3)), dec!(-1)),
+        ]
+    );
+    let off: Vec<Option<Decimal>> = reconciliation
+        .swap_discrepancies()
+        .map(|s| s.difference())
+        .collect();
+    assert_eq!(off, vec![Some(dec!(2))]);
+    assert_eq!(reconciliation.unmatched_swaps.len(), 1);
+    assert_eq!(reconciliation.unmatched_swaps[0].transaction_id, "t-10");
+    assert_eq!(reconciliation.net_transfers, dec!(500));
+    assert!(!reconciliation.is_clean());
+}
+
+#[tokio::test]
+async fn the_reconciler_matches_platform_fills_and_records_transfers_as_funding() {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    let platform = Arc::new(MockTradingPlatform::new("acc-1"));
+    let legacy = Arc::new(MockTradingPlatform::new("acc-2"));
+    orchestrator
+        .register_account("acc-1".to_string(), platform.clone(), 10000.0)
+        .await
+        .unwrap();
+    orchestrator
+        .register_account("acc-2".to_string(), legacy.clone(), 10000.0)
+        .await
+        .unwrap();
+
+    let response = platform
+        .place_order(UnifiedOrder {
+            client_order_id: "c-1".to_string(),
+            symbol: "EURUSD".to_string(),
+            side: UnifiedOrderSide::Buy,
+            order_type: UnifiedOrderType::Market,
+            quantity: dec!(10000),
+            price: Some(dec!(1.1)),
+            stop_price: None,
+            take_profit: None,
+            stop_loss: None,
+            time_in_force: UnifiedTimeInForce::Gtc,
+            account_id: None,
+            metadata: OrderMetadata {
+                strategy_id: None,
+                signal_id: None,
+                risk_parameters: HashMap::new(),
+                tags: Vec::new(),
+                expires_at: None,
+            },
+            max_slippage: None,
+        })
+        .await
+        .unwrap();
+    platform.transfer(dec!(-200));
+
+    let ledger = Arc::new(PositionLedger::new());
+    ledger
+        .record(
+            "acc-1",
+            opened(
+                &response.platform_order_id,
+                response.platform_specific["position_id"].as_str().unwrap(),
+                "EURUSD",
+                UnifiedPositionSide::Long,
+                dec!(10000),
+                dec!(1.1),
+                Decimal::ZERO,
+            ),
+        )
+        .await
+        .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let funding = Arc::new(FundingDetector::new(
+        FundingConfig {
+            enabled: true,
+            ..FundingConfig::default()
+        },
+        Arc::new(FileFundingEventStore::new(dir.path().join("funding.jsonl"))),
+    ));
+    let reconciler = StatementReconciler::new(
+        orchestrator,
+        ledger,
+        StatementConfig {
+            enabled: true,
+            ..StatementConfig::default()
+        },
+    )
+    .with_funding_detector(funding.clone());
+
+    // A platform without a transaction history is skipped
+    legacy.script_error(
+        Operation::GetAccountInfo,
+        PlatformError::FeatureNotSupported {
+            feature: "get_transactions".to_string(),
+        },
+    );
+    let now = Utc::now() + Duration::seconds(1);
+    let reconciliations = reconciler.reconcile_all(now).await;
+    assert_eq!(reconciliations.len(), 1);
+    let reconciliation = &reconciliations[0];
+    assert_eq!(reconciliation.account_id, "acc-1");
+    assert_eq!(reconciliation.fills_matched, 1);
+    assert_eq!(reconciliation.net_transfers, dec!(-200));
+    assert!(reconciliation.is_clean());
+    assert_eq!(reconciler.latest().await, reconciliations);
+
+    let events = funding
+        .events_between(Some("acc-1"), now - Duration::hours(1), now)
+        .await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].amount, dec!(-200));
+    assert_eq!(events[0].source, FundingSource::Platform);
+
+    // The statement comes back through the platform wrappers too
+    let transactions = platform
+        .get_transactions(TransactionRange::new(now - Duration::hours(1), now))
+        .await
+        .unwrap();
+    let kinds: Vec<UnifiedTransactionKind> = transactions.iter().map(|t| t.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            UnifiedTransactionKind::Fill,
+            UnifiedTransactionKind::Transfer
+        ]
+    );
+}
@@ -0,0 +1,218 @@
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use execution_engine::alerting::{AlertGateway, AlertingConfig};
+use execution_engine::execution::exit_management::{
+    ActiveTrail, ClosePositionRequest, ClosePositionResult, ExitAuditLogger, ExitManagementSystem,
+    MarketData, OrderModifyRequest, OrderModifyResult, PartialCloseRequest, Position,
+    StopGuardianConfig, StopLossGuardian, TradingPlatform, UnifiedPositionSide,
+    NAKED_POSITION_ALERT_TYPE,
+};
+use execution_engine::testing::fixtures;
+
+/// Platform whose stop modifications are acknowledged but only stick when `apply` is set
+#[derive(Debug)]
+struct MockPlatform {
+    positions: Mutex<Vec<Position>>,
+    modifications: Mutex<Vec<OrderModifyRequest>>,
+    apply: bool,
+}
+
+impl MockPlatform {
+    fn new(positions: Vec<Position>, apply: bool) -> Arc<Self> {
+        Arc::new(Self {
+            positions: Mutex::new(positions),
+            modifications: Mutex::new(Vec::new()),
+            apply,
+        })
+    }
+
+    fn set_stop(&self, stop: Option<Decimal>) {
+        for position in self.positions.lock().unwrap().iter_mut() {
+            position.stop_loss = stop;
+        }
+    }
+
+    fn stops_sent(&self) -> Vec<Option<Decimal>> {
+        self.modifications
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| request.new_stop_loss)
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self.positions.lock().unwrap().clone())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: dec!(1.1060),
+            ask: dec!(1.1061),
+            spread: dec!(0.0001),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        if self.apply {
+            for position in self.positions.lock().unwrap().iter_mut() {
+                if position.order_id == request.order_id {
+                    position.stop_loss = request.new_stop_loss;
+                }
+            }
+        }
+        self.modifications.lock().unwrap().push(request.clone());
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: true,
+            message: "modified".to_string(),
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: dec!(1.1060),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: dec!(1.1060),
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+}
+
+fn long_position(stop_loss: Option<Decimal>) -> Position {
+    Position {
+        stop_loss,
+        take_profit: Some(dec!(1.1200)),
+        ..fixtures::long_position()
+    }
+}
+
+fn position_id(platform: &MockPlatform) -> Uuid {
+    platform.positions.lock().unwrap()[0].id
+}
+
+fn system(platform: Arc<MockPlatform>) -> ExitManagementSystem {
+    ExitManagementSystem::new(platform, Arc::new(ExitAuditLogger::new()))
+}
+
+fn guardian(grace_period_secs: i64) -> StopLossGuardian {
+    StopLossGuardian::new(StopGuardianConfig {
+        grace_period_secs,
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn vanished_stop_is_put_back() {
+    let platform = MockPlatform::new(vec![long_position(Some(dec!(1.0950)))], true);
+    let system = system(platform.clone());
+    let guardian = guardian(60);
+
+    let report = guardian.verify("acc-1", &system).await.unwrap();
+    assert_eq!(report.checked, 1);
+    assert!(report.reapplied.is_empty());
+
+    platform.set_stop(None);
+    let report = guardian.verify("acc-1", &system).await.unwrap();
+    assert_eq!(report.reapplied.len(), 1);
+    assert_eq!(report.naked.len(), 1);
+    assert_eq!(platform.stops_sent(), [Some(dec!(1.0950))]);
+
+    // Seen protected again on the next pass
+    let report = guardian.verify("acc-1", &system).await.unwrap();
+    assert!(report.naked.is_empty());
+    assert!(guardian.naked_since(position_id(&platform)).is_none());
+}
+
+#[tokio::test]
+async fn stop_behind_trail_level_is_moved_up() {
+    let position = long_position(Some(dec!(1.0950)));
+    let platform = MockPlatform::new(vec![position.clone()], true);
+    let system = system(platform.clone());
+    system
+        .get_trailing_stop_manager()
+        .restore_trail(ActiveTrail {
+            position_id: position.id,
+            trail_level: dec!(1.1030),
+            original_stop: dec!(1.0950),
+            position_type: UnifiedPositionSide::Long,
+            last_updated: Utc::now(),
+            update_count: 3,
+            activation_price: dec!(1.1040),
+            native_order_id: None,
+        });
+    let guardian = guardian(60);
+
+    let report = guardian.verify("acc-1", &system).await.unwrap();
+    assert_eq!(report.reapplied, [position.id]);
+    assert_eq!(platform.stops_sent(), [Some(dec!(1.1030))]);
+
+    // Within tolerance of the trail is left alone
+    platform.set_stop(Some(dec!(1.10303)));
+    let report = guardian.verify("acc-1", &system).await.unwrap();
+    assert!(report.reapplied.is_empty());
+}
+
+#[tokio::test]
+async fn naked_position_alerts_after_grace_and_resolves_on_close() {
+    let platform = MockPlatform::new(vec![long_position(Some(dec!(1.0950)))], false);
+    let system = system(platform.clone());
+    let gateway = Arc::new(AlertGateway::new(AlertingConfig::default()));
+    let guardian = guardian(0).with_alert_gateway(gateway.clone());
+
+    guardian.verify("acc-1", &system).await.unwrap();
+    platform.set_stop(None);
+
+    // The re-applied stop never sticks
+    for _ in 0..2 {
+        let report = guardian.verify("acc-1", &system).await.unwrap();
+        assert_eq!(report.naked.len(), 1);
+    }
+    assert_eq!(platform.stops_sent().len(), 2);
+    let active = gateway.active_alerts();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].alert.alert_type, NAKED_POSITION_ALERT_TYPE);
+    assert_eq!(active[0].alert.key, position_id(&platform).to_string());
+
+    platform.positions.lock().unwrap().clear();
+    guardian.verify("acc-1", &system).await.unwrap();
+    assert!(gateway.active_alerts().is_empty());
+}
+
+#[tokio::test]
+async fn naked_position_within_grace_is_not_alerted() {
+    let platform = MockPlatform::new(vec![long_position(None)], false);
+    let system = system(platform.clone());
+    let gateway = Arc::new(AlertGateway::new(AlertingConfig::default()));
+    let guardian = guardian(60).with_alert_gateway(gateway.clone());
+
+    let report = guardian.verify("acc-1", &system).await.unwrap();
+
+    // No level to restore a stop that was never set
+    assert_eq!(report.naked.len(), 1);
+    assert!(report.reapplied.is_empty());
+    assert!(gateway.active_alerts().is_empty());
+    assert!(guardian.naked_since(position_id(&platform)).is_some());
+}
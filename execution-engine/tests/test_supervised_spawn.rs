@@ -0,0 +1,117 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use execution_engine::runtime::spawn::{task_panics, task_restarts};
+use execution_engine::runtime::{spawn_isolated, supervised_spawn, RestartPolicy};
+
+fn on_failure(max_restarts: u32) -> RestartPolicy {
+    RestartPolicy::OnFailure {
+        max_restarts,
+        backoff_ms: 1,
+    }
+}
+
+#[tokio::test]
+async fn test_panicking_task_is_restarted_until_it_completes() {
+    let runs = Arc::new(AtomicU32::new(0));
+    let counter = runs.clone();
+    let handle = supervised_spawn("flaky-loop", on_failure(5), move || {
+        let counter = counter.clone();
+        async move {
+            if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                panic!("lost connection state");
+            }
+        }
+    });
+
+    tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .expect("task should finish")
+        .unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+    assert_eq!(task_panics("flaky-loop"), 2);
+    assert_eq!(task_restarts("flaky-loop"), 2);
+}
+
+#[tokio::test]
+async fn test_restart_policy_limits_restarts() {
+    let runs = Arc::new(AtomicU32::new(0));
+    let counter = runs.clone();
+    let handle = supervised_spawn("always-panics", on_failure(2), move || {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            panic!("broken");
+        }
+    });
+    handle.await.unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+    assert_eq!(task_panics("always-panics"), 3);
+
+    // Never restarts, and a clean return is not restarted under OnFailure either
+    let runs = Arc::new(AtomicU32::new(0));
+    let counter = runs.clone();
+    supervised_spawn("one-shot", RestartPolicy::Never, move || {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            panic!("once");
+        }
+    })
+    .await
+    .unwrap();
+    let counter = runs.clone();
+    supervised_spawn("finishes", on_failure(2), move || {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    assert_eq!(task_restarts("one-shot"), 0);
+    assert_eq!(task_restarts("finishes"), 0);
+}
+
+#[tokio::test]
+async fn test_isolated_task_reports_its_panic() {
+    let ok = spawn_isolated("isolated-ok", async { 42 }).await.unwrap();
+    assert_eq!(ok, Ok(42));
+
+    let failed = spawn_isolated("isolated-panic", async {
+        if true {
+            panic!("order builder failed");
+        }
+        0
+    })
+    .await
+    .unwrap();
+    let message = failed.unwrap_err();
+    assert!(message.contains("isolated-panic"));
+    assert!(message.contains("order builder failed"));
+    assert_eq!(task_panics("isolated-panic"), 1);
+}
+
+#[tokio::test]
+async fn test_aborting_the_handle_stops_the_task() {
+    let runs = Arc::new(AtomicU32::new(0));
+    let counter = runs.clone();
+    let handle = supervised_spawn("aborted-loop", RestartPolicy::default(), move || {
+        let counter = counter.clone();
+        async move {
+            loop {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }
+    });
+
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    handle.abort();
+    let _ = handle.await;
+    let stopped_at = runs.load(Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), stopped_at);
+}
@@ -0,0 +1,215 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use execution_engine::runtime::{
+    RestartPolicy, ShutdownSignal, Subsystem, SubsystemState, Supervisor, SupervisorConfig,
+};
+
+type EventLog = Arc<Mutex<Vec<String>>>;
+
+/// Waits for shutdown, recording lifecycle calls
+struct Recording {
+    name: String,
+    log: EventLog,
+    fail_start: bool,
+}
+
+impl Recording {
+    fn new(name: &str, log: &EventLog) -> Arc<Self> {
+        Arc::new(Self {
+            name: name.to_string(),
+            log: log.clone(),
+            fail_start: false,
+        })
+    }
+}
+
+#[async_trait]
+impl Subsystem for Recording {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn start(&self) -> Result<()> {
+        if self.fail_start {
+            return Err(anyhow!("boom"));
+        }
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("start {}", self.name));
+        Ok(())
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        shutdown.recv().await;
+        Ok(())
+    }
+
+    async fn stop(&self) -> Result<()> {
+        self.log.lock().unwrap().push(format!("stop {}", self.name));
+        Ok(())
+    }
+}
+
+/// Panics on the first `panics` runs, then waits for shutdown
+struct Crashing {
+    panics: u32,
+    runs: AtomicU32,
+}
+
+#[async_trait]
+impl Subsystem for Crashing {
+    fn name(&self) -> &str {
+        "crashing"
+    }
+
+    async fn run(&self, mut shutdown: ShutdownSignal) -> Result<()> {
+        if self.runs.fetch_add(1, Ordering::SeqCst) < self.panics {
+            panic!("subsystem crashed");
+        }
+        shutdown.recv().await;
+        Ok(())
+    }
+}
+
+fn config() -> SupervisorConfig {
+    SupervisorConfig {
+        shutdown_timeout_secs: 5,
+        ..SupervisorConfig::default()
+    }
+}
+
+async fn wait_for_state(supervisor: &Supervisor, name: &str, state: SubsystemState) {
+    for _ in 0..100 {
+        if supervisor
+            .statuses()
+            .read()
+            .await
+            .get(name)
+            .map(|s| s.state)
+            == Some(state)
+        {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("{} never reached {:?}", name, state);
+}
+
+#[tokio::test]
+async fn test_ordered_startup_and_reverse_shutdown() {
+    let log = EventLog::default();
+    let mut supervisor = Supervisor::new(config());
+    for name in ["messaging", "orchestrator", "api"] {
+        supervisor.add(Recording::new(name, &log));
+    }
+
+    supervisor.start().await.unwrap();
+    supervisor.shutdown().await;
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "start messaging",
+            "start orchestrator",
+            "start api",
+            "stop api",
+            "stop orchestrator",
+            "stop messaging",
+        ]
+    );
+    let statuses = supervisor.statuses();
+    assert!(statuses
+        .read()
+        .await
+        .values()
+        .all(|s| s.state == SubsystemState::Stopped));
+}
+
+#[tokio::test]
+async fn test_panic_is_isolated_and_restarted() {
+    let log = EventLog::default();
+    let crashing = Arc::new(Crashing {
+        panics: 2,
+        runs: AtomicU32::new(0),
+    });
+
+    let mut supervisor = Supervisor::new(config());
+    supervisor.add(Recording::new("steady", &log));
+    supervisor.add_with_policy(
+        crashing.clone(),
+        RestartPolicy::OnFailure {
+            max_restarts: 3,
+            backoff_ms: 1,
+        },
+    );
+    supervisor.start().await.unwrap();
+
+    wait_for_state(&supervisor, "crashing", SubsystemState::Running).await;
+    for _ in 0..100 {
+        if crashing.runs.load(Ordering::SeqCst) == 3 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    {
+        let statuses = supervisor.statuses();
+        let statuses = statuses.read().await;
+        let status = &statuses["crashing"];
+        assert_eq!(status.restarts, 2);
+        assert!(status
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("subsystem crashed"));
+        assert_eq!(statuses["steady"].state, SubsystemState::Running);
+    }
+
+    supervisor.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_restart_limit_leaves_subsystem_failed() {
+    let mut supervisor = Supervisor::new(config());
+    supervisor.add_with_policy(
+        Arc::new(Crashing {
+            panics: u32::MAX,
+            runs: AtomicU32::new(0),
+        }),
+        RestartPolicy::OnFailure {
+            max_restarts: 1,
+            backoff_ms: 1,
+        },
+    );
+    supervisor.start().await.unwrap();
+
+    wait_for_state(&supervisor, "crashing", SubsystemState::Failed).await;
+    assert_eq!(supervisor.statuses().read().await["crashing"].restarts, 1);
+
+    supervisor.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_failed_start_rolls_back_started_subsystems() {
+    let log = EventLog::default();
+    let mut supervisor = Supervisor::new(config());
+    supervisor.add(Recording::new("first", &log));
+    supervisor.add(Arc::new(Recording {
+        name: "second".to_string(),
+        log: log.clone(),
+        fail_start: true,
+    }));
+    supervisor.add(Recording::new("third", &log));
+
+    assert!(supervisor.start().await.is_err());
+    assert_eq!(*log.lock().unwrap(), vec!["start first", "stop first"]);
+    assert_eq!(
+        supervisor.statuses().read().await["second"].state,
+        SubsystemState::Failed
+    );
+}
@@ -0,0 +1,180 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use execution_engine::platforms::abstraction::adapters::conversion_utils::convert_instrument_type;
+use execution_engine::platforms::abstraction::interfaces::ITradingPlatform;
+use execution_engine::platforms::abstraction::models::{
+    InstrumentType, OrderMetadata, Symbol, UnifiedOrder, UnifiedOrderSide, UnifiedOrderType,
+    UnifiedTimeInForce,
+};
+use execution_engine::platforms::abstraction::symbols::{
+    normalize_symbol, unified_symbol, SymbolMapper, SymbolMappingConfig, SymbolMappingPlatform,
+};
+use execution_engine::testing::MockTradingPlatform;
+
+fn instrument(name: &str, instrument_type: InstrumentType, tradeable: bool) -> Symbol {
+    let (base, quote) = match instrument_type {
+        InstrumentType::Forex => (name[..3].to_string(), name[3..6].to_string()),
+        _ => (String::new(), String::new()),
+    };
+    Symbol {
+        symbol: name.to_string(),
+        description: String::new(),
+        instrument_type,
+        base_currency: base,
+        quote_currency: quote,
+        min_trade_size: dec!(0.01),
+        max_trade_size: None,
+        tick_size: dec!(0.00001),
+        contract_size: None,
+        trading_hours: Vec::new(),
+        is_tradeable: tradeable,
+        stops_level: None,
+        freeze_level: None,
+    }
+}
+
+fn market_buy(symbol: &str) -> UnifiedOrder {
+    UnifiedOrder {
+        client_order_id: uuid::Uuid::new_v4().to_string(),
+        symbol: symbol.to_string(),
+        side: UnifiedOrderSide::Buy,
+        order_type: UnifiedOrderType::Market,
+        quantity: dec!(1),
+        price: None,
+        stop_price: None,
+        stop_loss: None,
+        take_profit: None,
+        time_in_force: UnifiedTimeInForce::Gtc,
+        account_id: None,
+        metadata: OrderMetadata {
+            strategy_id: None,
+            signal_id: None,
+            risk_parameters: HashMap::new(),
+            tags: Vec::new(),
+            expires_at: None,
+        },
+        max_slippage: None,
+    }
+}
+
+/// A broker with suffixed forex names and an index under its own name
+fn suffixed_broker() -> Arc<MockTradingPlatform> {
+    Arc::new(
+        MockTradingPlatform::new("suffixed")
+            .with_instruments(vec![
+                instrument("EURUSD.r", InstrumentType::Forex, true),
+                instrument("GBPUSDm", InstrumentType::Forex, true),
+                instrument("US30.cash", InstrumentType::Index, true),
+            ])
+            .with_quote("EURUSD.r", dec!(1.1000), dec!(1.1002))
+            .with_quote("GBPUSDm", dec!(1.2500), dec!(1.2502)),
+    )
+}
+
+#[test]
+fn platform_names_normalize_to_unified_symbols() {
+    assert_eq!(normalize_symbol("EUR/USD"), "EURUSD");
+    assert_eq!(normalize_symbol("eur_usd"), "EURUSD");
+    assert_eq!(normalize_symbol("EURUSD.r"), "EURUSD");
+    assert_eq!(normalize_symbol("US30#"), "US30");
+    assert_eq!(
+        unified_symbol(&instrument("EURUSDm", InstrumentType::Forex, true)),
+        "EURUSD"
+    );
+    assert_eq!(
+        unified_symbol(&instrument("US30.cash", InstrumentType::Index, true)),
+        "US30"
+    );
+}
+
+#[test]
+fn discovery_prefers_exact_then_tradeable_names() {
+    let mapper = SymbolMapper::new();
+    mapper.discover(&[
+        instrument("EURUSD.pro", InstrumentType::Forex, true),
+        instrument("EURUSD", InstrumentType::Forex, false),
+        instrument("GBPUSD.ecn", InstrumentType::Forex, false),
+        instrument("GBPUSD.r", InstrumentType::Forex, true),
+    ]);
+    assert_eq!(mapper.to_platform("EURUSD"), "EURUSD");
+    assert_eq!(mapper.to_platform("GBPUSD"), "GBPUSD.r");
+    assert_eq!(mapper.to_unified("GBPUSD.r"), "GBPUSD");
+    // Unknown symbols pass through
+    assert_eq!(mapper.to_platform("XAUUSD"), "XAUUSD");
+
+    // Overrides survive rediscovery
+    let config = SymbolMappingConfig {
+        overrides: HashMap::from([("GBPUSD".to_string(), "GBPUSD.ecn".to_string())]),
+        ..SymbolMappingConfig::default()
+    };
+    let mapper = SymbolMapper::from_config(&config);
+    mapper.discover(&[
+        instrument("GBPUSD.ecn", InstrumentType::Forex, false),
+        instrument("GBPUSD.r", InstrumentType::Forex, true),
+    ]);
+    assert_eq!(mapper.to_platform("GBPUSD"), "GBPUSD.ecn");
+    assert_eq!(mapper.to_unified("GBPUSD.r"), "GBPUSD.r");
+}
+
+#[test]
+fn platform_instrument_types_map_to_unified_ones() {
+    assert_eq!(
+        convert_instrument_type(Some("Index")),
+        InstrumentType::Index
+    );
+    assert_eq!(
+        convert_instrument_type(Some("metal")),
+        InstrumentType::Commodity
+    );
+    assert_eq!(
+        convert_instrument_type(Some("crypto")),
+        InstrumentType::Crypto
+    );
+    assert_eq!(convert_instrument_type(Some("cfd")), InstrumentType::Forex);
+    assert_eq!(convert_instrument_type(None), InstrumentType::Forex);
+}
+
+#[tokio::test]
+async fn wrapped_platforms_speak_unified_symbols() {
+    let broker = suffixed_broker();
+    let platform =
+        SymbolMappingPlatform::from_config(broker.clone(), &SymbolMappingConfig::default()).await;
+
+    let quote = platform.get_market_data("EURUSD").await.unwrap();
+    assert_eq!(quote.symbol, "EURUSD");
+    assert_eq!(quote.bid, dec!(1.1000));
+
+    let response = platform.place_order(market_buy("GBPUSD")).await.unwrap();
+    assert_eq!(response.symbol, "GBPUSD");
+    assert_eq!(broker.orders()[0].symbol, "GBPUSDm");
+
+    let positions = platform.get_positions().await.unwrap();
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].symbol, "GBPUSD");
+    assert!(platform.get_position("GBPUSD").await.unwrap().is_some());
+
+    platform.close_position("GBPUSD", None).await.unwrap();
+    assert!(broker.positions().is_empty());
+    assert_eq!(broker.closes()[0].0, "GBPUSDm");
+
+    let instruments = platform.get_instruments().await.unwrap();
+    let names: Vec<_> = instruments.iter().map(|s| s.symbol.as_str()).collect();
+    assert_eq!(names, vec!["EURUSD", "GBPUSD", "US30"]);
+}
+
+#[tokio::test]
+async fn platforms_without_instrument_lists_use_overrides() {
+    let broker =
+        Arc::new(MockTradingPlatform::new("plain").with_quote("EURUSD-x", dec!(1.1), dec!(1.1)));
+    let config: SymbolMappingConfig =
+        serde_json::from_str(r#"{ "overrides": { "EURUSD": "EURUSD-x" } }"#).unwrap();
+    assert!(config.auto_discover);
+
+    let platform = SymbolMappingPlatform::from_config(broker, &config).await;
+    let quote = platform.get_market_data("EURUSD").await.unwrap();
+    assert_eq!(quote.symbol, "EURUSD");
+    assert_eq!(quote.spread, Decimal::ZERO);
+}
@@ -0,0 +1,205 @@
+use chrono::{DateTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use execution_engine::ledger::{LedgerEvent, LedgerEventKind};
+use execution_engine::platforms::abstraction::models::UnifiedPositionSide;
+use execution_engine::reports::{TaxLotQuery, TaxLotReport};
+
+fn day(day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap()
+}
+
+struct Ledger(Vec<LedgerEvent>);
+
+impl Ledger {
+    fn push(&mut self, account_id: &str, timestamp: DateTime<Utc>, kind: LedgerEventKind) {
+        self.0.push(LedgerEvent {
+            sequence: self.0.len() as u64 + 1,
+            account_id: account_id.to_string(),
+            timestamp,
+            source_event_id: None,
+            kind,
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn fill(
+        &mut self,
+        at: DateTime<Utc>,
+        position_id: &str,
+        symbol: &str,
+        side: UnifiedPositionSide,
+        quantity: Decimal,
+        price: Decimal,
+        commission: Decimal,
+    ) {
+        self.push(
+            "acc-1",
+            at,
+            LedgerEventKind::Fill {
+                order_id: format!("o-{}", self.0.len()),
+                position_id: position_id.to_string(),
+                symbol: symbol.to_string(),
+                side,
+                quantity,
+                price,
+                commission,
+            },
+        );
+    }
+
+    fn close(
+        &mut self,
+        at: DateTime<Utc>,
+        position_id: &str,
+        quantity: Decimal,
+        price: Decimal,
+        commission: Decimal,
+    ) {
+        self.push(
+            "acc-1",
+            at,
+            LedgerEventKind::Closed {
+                position_id: position_id.to_string(),
+                closing_order_id: format!("c-{}", self.0.len()),
+                quantity,
+                price,
+                commission,
+                reported_pnl: dec!(-999),
+            },
+        );
+    }
+}
+
+fn ledger() -> Ledger {
+    let mut ledger = Ledger(Vec::new());
+    let long = UnifiedPositionSide::Long;
+    ledger.fill(
+        day(2),
+        "P1",
+        "EURUSD",
+        long.clone(),
+        dec!(10000),
+        dec!(1.1000),
+        dec!(2),
+    );
+    ledger.fill(
+        day(3),
+        "P2",
+        "EURUSD",
+        long,
+        dec!(5000),
+        dec!(1.1200),
+        Decimal::ZERO,
+    );
+    // Closing the newer position still realizes the oldest lot first
+    ledger.close(day(4), "P2", dec!(5000), dec!(1.1300), dec!(1));
+    ledger.fill(
+        day(5),
+        "P3",
+        "GBPUSD",
+        UnifiedPositionSide::Short,
+        dec!(2000),
+        dec!(1.2500),
+        Decimal::ZERO,
+    );
+    ledger.close(day(6), "P3", dec!(2000), dec!(1.2600), Decimal::ZERO);
+    ledger.close(day(7), "P1", dec!(10000), dec!(1.1100), Decimal::ZERO);
+    ledger
+}
+
+#[test]
+fn closes_are_matched_first_in_first_out_per_instrument() {
+    let report = TaxLotReport::compile(&ledger().0, TaxLotQuery::default());
+    let lots: Vec<(&str, Decimal, Option<Decimal>, Decimal)> = report
+        .lots
+        .iter()
+        .map(|lot| {
+            (
+                lot.symbol.as_str(),
+                lot.quantity,
+                lot.open_price,
+                lot.realized_pnl,
+            )
+        })
+        .collect();
+    assert_eq!(
+        lots,
+        vec![
+            // 150 gained less the lot's half of the opening commission and the close's
+            ("EURUSD", dec!(5000), Some(dec!(1.1000)), dec!(148)),
+            ("GBPUSD", dec!(2000), Some(dec!(1.2500)), dec!(-20)),
+            ("EURUSD", dec!(5000), Some(dec!(1.1000)), dec!(49)),
+            ("EURUSD", dec!(5000), Some(dec!(1.1200)), dec!(-50)),
+        ]
+    );
+    assert_eq!(report.lots[0].opened_at, Some(day(2)));
+    assert_eq!(report.realized_pnl, dec!(127));
+    assert_eq!(report.accounts["acc-1"], dec!(127));
+
+    let eurusd = &report.instruments[0];
+    assert_eq!(
+        (
+            eurusd.symbol.as_str(),
+            eurusd.lots,
+            eurusd.quantity,
+            eurusd.realized_pnl
+        ),
+        ("EURUSD", 3, dec!(15000), dec!(147))
+    );
+}
+
+#[test]
+fn the_period_keeps_closes_inside_it_whenever_their_lots_opened() {
+    let report = TaxLotReport::compile(
+        &ledger().0,
+        TaxLotQuery {
+            account_id: Some("acc-1".to_string()),
+            from: Some(day(5)),
+            to: Some(day(7)),
+        },
+    );
+    assert_eq!(report.lots.len(), 1);
+    assert_eq!(report.lots[0].symbol, "GBPUSD");
+    assert_eq!(report.lots[0].side, UnifiedPositionSide::Short);
+
+    let other = TaxLotReport::compile(
+        &ledger().0,
+        TaxLotQuery {
+            account_id: Some("acc-2".to_string()),
+            ..Default::default()
+        },
+    );
+    assert!(other.lots.is_empty());
+    assert_eq!(other.realized_pnl, Decimal::ZERO);
+}
+
+#[test]
+fn closes_of_positions_never_seen_open_use_the_reported_pnl() {
+    let mut ledger = Ledger(Vec::new());
+    ledger.close(day(2), "unknown", dec!(1000), dec!(1.3), Decimal::ZERO);
+    let report = TaxLotReport::compile(&ledger.0, TaxLotQuery::default());
+    assert_eq!(report.lots[0].opened_at, None);
+    assert_eq!(report.lots[0].realized_pnl, dec!(-999));
+    assert_eq!(report.lots[0].cost_basis(), None);
+}
+
+#[test]
+fn csv_export_has_one_row_per_lot() {
+    let report = TaxLotReport::compile(&ledger().0, TaxLotQuery::default());
+    let csv = report.to_csv();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 5);
+    assert_eq!(
+        rows[0],
+        "account_id,symbol,side,quantity,opened_at,open_price,closed_at,close_price,\
+         cost_basis,proceeds,commission,realized_pnl"
+    );
+    assert_eq!(
+        rows[1],
+        "acc-1,EURUSD,long,5000,2026-03-02T12:00:00+00:00,1.1000,\
+         2026-03-04T12:00:00+00:00,1.1300,5502.0000,5650.0000,2,148.0000"
+    );
+    assert!(rows[2].starts_with("acc-1,GBPUSD,short,2000,"));
+}
@@ -0,0 +1,189 @@
+use anyhow::Result;
+use axum::extract::{Query, State};
+use axum::routing::post;
+use axum::Router;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use execution_engine::dashboard::DashboardAggregator;
+use execution_engine::execution::TradeExecutionOrchestrator;
+use execution_engine::platforms::abstraction::models::*;
+use execution_engine::testing::MockTradingPlatform;
+use execution_engine::timeseries::{
+    connect_writer, InMemoryTimeSeriesWriter, TimeSeriesBackend, TimeSeriesConfig, TimeSeriesPoint,
+    TimeSeriesSampler, TimeSeriesSink, TimeSeriesWriter,
+};
+
+fn tick(bid: Decimal) -> TimeSeriesPoint {
+    TimeSeriesPoint::Tick {
+        at: Utc::now(),
+        symbol: "EURUSD".to_string(),
+        bid,
+        ask: bid + dec!(0.0001),
+    }
+}
+
+fn config(batch_size: usize, queue_capacity: usize) -> TimeSeriesConfig {
+    TimeSeriesConfig {
+        enabled: true,
+        batch_size,
+        queue_capacity,
+        max_retries: 0,
+        ..Default::default()
+    }
+}
+
+#[derive(Debug)]
+struct FailingWriter;
+
+#[async_trait::async_trait]
+impl TimeSeriesWriter for FailingWriter {
+    async fn write(&self, _batch: &[TimeSeriesPoint]) -> Result<()> {
+        anyhow::bail!("database down")
+    }
+}
+
+#[tokio::test]
+async fn queued_points_are_written_in_batches() {
+    let writer = Arc::new(InMemoryTimeSeriesWriter::new());
+    let sink = TimeSeriesSink::new(&config(2, 100), writer.clone());
+    for i in 0..5 {
+        assert!(sink.offer(tick(Decimal::from(i))));
+    }
+    assert_eq!(sink.stats().queued, 5);
+
+    assert_eq!(sink.flush().await, 5);
+    let sizes: Vec<usize> = writer.batches().iter().map(Vec::len).collect();
+    assert_eq!(sizes, vec![2, 2, 1]);
+    let stats = sink.stats();
+    assert_eq!((stats.queued, stats.written, stats.dropped), (0, 5, 0));
+}
+
+#[tokio::test]
+async fn a_full_queue_or_failing_database_drops_points() {
+    let sink = TimeSeriesSink::new(&config(10, 3), Arc::new(FailingWriter));
+    let offered: Vec<bool> = (0..5).map(|i| sink.offer(tick(Decimal::from(i)))).collect();
+    assert_eq!(offered, vec![true, true, true, false, false]);
+
+    assert_eq!(sink.flush().await, 0);
+    let stats = sink.stats();
+    assert_eq!(stats.dropped, 5);
+    assert_eq!(stats.failed_batches, 1);
+    // The queue has room again once the batch is given up on
+    assert!(sink.offer(tick(dec!(1.1))));
+}
+
+#[tokio::test]
+async fn sampler_exports_equity_and_pnl_of_every_account() {
+    let orchestrator = Arc::new(TradeExecutionOrchestrator::new());
+    let position = UnifiedPosition {
+        position_id: Uuid::new_v4().to_string(),
+        symbol: "EURUSD".to_string(),
+        side: UnifiedPositionSide::Long,
+        quantity: dec!(10000),
+        entry_price: dec!(1.1000),
+        current_price: dec!(1.1025),
+        unrealized_pnl: dec!(25),
+        realized_pnl: Decimal::ZERO,
+        margin_used: Decimal::ZERO,
+        commission: Decimal::ZERO,
+        stop_loss: None,
+        take_profit: None,
+        opened_at: Utc::now(),
+        updated_at: Utc::now(),
+        account_id: String::new(),
+        platform_specific: HashMap::new(),
+    };
+    let platform = MockTradingPlatform::new("acc-1")
+        .with_account_id("acc-1")
+        .with_balance(dec!(10000))
+        .with_position(position);
+    orchestrator
+        .register_account("acc-1".to_string(), Arc::new(platform), 10000.0)
+        .await
+        .unwrap();
+
+    let writer = Arc::new(InMemoryTimeSeriesWriter::new());
+    let sink = Arc::new(TimeSeriesSink::new(&config(100, 100), writer.clone()));
+    let sampler = TimeSeriesSampler::new(orchestrator.clone(), sink.clone(), config(100, 100))
+        .with_dashboard(Arc::new(DashboardAggregator::new(orchestrator)));
+    sampler.sample_pnl().await;
+    sampler.sample_equity().await;
+    sink.flush().await;
+
+    let points = writer.batches().concat();
+    assert!(matches!(
+        &points[0],
+        TimeSeriesPoint::Pnl { account_id, unrealized_pnl, open_positions: 1, .. }
+            if account_id == "acc-1" && *unrealized_pnl == dec!(25)
+    ));
+    assert!(matches!(
+        &points[1],
+        TimeSeriesPoint::Equity { balance, equity, .. }
+            if *balance == dec!(10000) && *equity == dec!(10025)
+    ));
+}
+
+type Requests = Arc<Mutex<Vec<(String, String)>>>;
+
+async fn capture(
+    State(requests): State<Requests>,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) {
+    let query = params.get("query").cloned().unwrap_or_default();
+    requests.lock().unwrap().push((query, body));
+}
+
+#[tokio::test]
+async fn clickhouse_writer_creates_tables_and_inserts_json_rows() {
+    let requests: Requests = Arc::default();
+    let app = Router::new()
+        .route("/", post(capture))
+        .with_state(requests.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let writer = connect_writer(&TimeSeriesConfig {
+        backend: TimeSeriesBackend::ClickHouse,
+        url,
+        table_prefix: "grafana.tmt_".to_string(),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    writer
+        .write(&[
+            tick(dec!(1.1)),
+            TimeSeriesPoint::Equity {
+                at: Utc::now(),
+                account_id: "acc-1".to_string(),
+                balance: dec!(10000),
+                equity: dec!(10025.5),
+                margin_used: dec!(0),
+            },
+            tick(dec!(1.2)),
+        ])
+        .await
+        .unwrap();
+
+    let requests = requests.lock().unwrap().clone();
+    let created: Vec<&str> = requests[..3].iter().map(|(q, _)| q.as_str()).collect();
+    assert!(created
+        .iter()
+        .all(|q| q.starts_with("CREATE TABLE IF NOT EXISTS grafana.tmt_")));
+
+    let (query, body) = &requests[3];
+    assert_eq!(query, "INSERT INTO grafana.tmt_ticks FORMAT JSONEachRow");
+    assert_eq!(body.lines().count(), 2);
+    let (query, body) = &requests[4];
+    assert_eq!(query, "INSERT INTO grafana.tmt_equity FORMAT JSONEachRow");
+    let row: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(row["account_id"], "acc-1");
+    assert_eq!(row["equity"], 10025.5);
+}
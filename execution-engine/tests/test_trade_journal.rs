@@ -0,0 +1,270 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use execution_engine::execution::exit_management::{
+    ExitAuditLogger, ExitModification, ExitModificationType, MarketContext,
+};
+use execution_engine::journal::{
+    ExitReason, FileJournalStore, PendingEntry, TradeJournal, TradeQuery, TradeStatus,
+};
+use execution_engine::platforms::abstraction::events::{
+    EventData, EventType, PlatformEvent, PositionCloseEventData, PositionEventData,
+};
+use execution_engine::platforms::abstraction::models::*;
+use execution_engine::platforms::PlatformType;
+use execution_engine::testing::platform_long_position;
+
+fn close(
+    position: &UnifiedPosition,
+    quantity: Decimal,
+    remaining: Decimal,
+    price: Decimal,
+) -> PositionCloseEventData {
+    PositionCloseEventData {
+        position_id: position.position_id.clone(),
+        symbol: position.symbol.clone(),
+        side: position.side.clone(),
+        closing_order_id: Uuid::new_v4().to_string(),
+        entry_price: position.entry_price,
+        close_price: price,
+        closed_quantity: quantity,
+        remaining_quantity: remaining,
+        realized_pnl: (price - position.entry_price) * quantity,
+        commission: Decimal::ZERO,
+        closed_at: Utc::now(),
+    }
+}
+
+fn modification(position_id: &str, modification_type: ExitModificationType) -> ExitModification {
+    ExitModification {
+        position_id: Uuid::parse_str(position_id).unwrap(),
+        modification_type,
+        old_value: dec!(1.0950),
+        new_value: dec!(1.1000),
+        reasoning: "test".to_string(),
+        market_context: MarketContext {
+            current_price: dec!(1.1040),
+            atr_14: dec!(0.001),
+            trend_strength: 0.5,
+            volatility: 0.01,
+            spread: dec!(0.0001),
+            timestamp: Utc::now(),
+            structure: None,
+            session: None,
+            recent_range: None,
+            news: None,
+        },
+    }
+}
+
+#[tokio::test]
+async fn test_partial_and_final_exit_pair_into_one_trade() {
+    let journal = Arc::new(TradeJournal::new());
+    let logger = ExitAuditLogger::new().with_trade_journal(journal.clone());
+    let position = platform_long_position("EURUSD");
+
+    logger
+        .handle_platform_event(&PlatformEvent::new(
+            EventType::PositionOpened,
+            PlatformType::DXTrade,
+            "acc-1".to_string(),
+            EventData::Position(PositionEventData {
+                position: position.clone(),
+                previous_state: None,
+                trigger_price: None,
+                pnl_change: None,
+            }),
+        ))
+        .await
+        .unwrap();
+    journal
+        .observe_price(&position.position_id, dec!(1.0980))
+        .await;
+    journal
+        .observe_price(&position.position_id, dec!(1.1060))
+        .await;
+
+    // The partial profit manager takes half off
+    logger
+        .log_exit_modification(modification(
+            &position.position_id,
+            ExitModificationType::PartialProfit,
+        ))
+        .await
+        .unwrap();
+    let partial = close(&position, dec!(5000), dec!(5000), dec!(1.1050));
+    logger
+        .handle_platform_event(&PlatformEvent::new(
+            partial.event_type(),
+            PlatformType::DXTrade,
+            "acc-1".to_string(),
+            EventData::PositionClose(partial),
+        ))
+        .await
+        .unwrap();
+
+    let trade = journal
+        .get_trade_for_position(&position.position_id)
+        .await
+        .unwrap();
+    assert_eq!(trade.status, TradeStatus::Open);
+    assert_eq!(trade.remaining_quantity(), dec!(5000));
+    assert_eq!(trade.exits[0].reason, ExitReason::PartialProfit);
+
+    // The rest is stopped out at break-even after the trailing stop moved up
+    journal
+        .record_exit_action(&position.position_id, ExitModificationType::TrailingStop)
+        .await;
+    journal
+        .record_close(
+            "acc-1",
+            &close(&position, dec!(5000), dec!(0), dec!(1.1000)),
+        )
+        .await;
+
+    let trade = journal.get_trade(&trade.id).await.unwrap();
+    assert_eq!(trade.status, TradeStatus::Closed);
+    assert_eq!(trade.exits.len(), 2);
+    assert_eq!(trade.exit_reason, Some(ExitReason::TrailingStop));
+    assert_eq!(trade.realized_pnl, dec!(25));
+    assert_eq!(trade.average_exit_price, Some(dec!(1.1025)));
+    // Risk was 50 pips on 10000 units
+    assert_eq!(trade.r_multiple, Some(dec!(0.5)));
+    assert_eq!(trade.max_adverse_excursion, dec!(0.0020));
+    assert_eq!(trade.max_favorable_excursion, dec!(0.0060));
+    assert!(trade.holding_time_secs.unwrap() >= 30 * 60);
+}
+
+#[tokio::test]
+async fn test_exit_reason_attribution() {
+    let journal = TradeJournal::new();
+
+    // Price through the target wins over a stop-moving manager
+    let target = platform_long_position("EURUSD");
+    journal.record_open("acc-1", &target).await;
+    journal
+        .record_exit_action(&target.position_id, ExitModificationType::BreakEven)
+        .await;
+    let id = journal
+        .record_close("acc-1", &close(&target, dec!(10000), dec!(0), dec!(1.1100)))
+        .await;
+    assert_eq!(
+        journal.get_trade(&id).await.unwrap().exit_reason,
+        Some(ExitReason::TakeProfit)
+    );
+
+    // Without any manager action, the initial stop is inferred
+    let stopped = platform_long_position("GBPUSD");
+    journal.record_open("acc-1", &stopped).await;
+    let id = journal
+        .record_close(
+            "acc-1",
+            &close(&stopped, dec!(10000), dec!(0), dec!(1.0950)),
+        )
+        .await;
+    let trade = journal.get_trade(&id).await.unwrap();
+    assert_eq!(trade.exit_reason, Some(ExitReason::StopLoss));
+    assert_eq!(trade.r_multiple, Some(dec!(-1)));
+
+    // A close between the levels with no manager involved is manual
+    let manual = platform_long_position("USDJPY");
+    journal.record_open("acc-1", &manual).await;
+    let id = journal
+        .record_close("acc-1", &close(&manual, dec!(10000), dec!(0), dec!(1.1020)))
+        .await;
+    assert_eq!(
+        journal.get_trade(&id).await.unwrap().exit_reason,
+        Some(ExitReason::Manual)
+    );
+}
+
+#[tokio::test]
+async fn test_pending_entry_links_signal_and_untracked_close_is_recorded() {
+    let journal = TradeJournal::new();
+    let mut position = platform_long_position("EURUSD");
+    position.stop_loss = None;
+    journal
+        .expect_entry(PendingEntry {
+            account_id: "acc-1".to_string(),
+            symbol: "EURUSD".to_string(),
+            signal_id: "signal-42".to_string(),
+            order_id: Some("order-7".to_string()),
+            stop_loss: Some(dec!(1.0900)),
+            take_profit: None,
+        })
+        .await;
+
+    let id = journal.record_open("acc-1", &position).await;
+    let trade = journal.get_trade(&id).await.unwrap();
+    assert_eq!(trade.entry.signal_id.as_deref(), Some("signal-42"));
+    assert_eq!(trade.entry.order_id.as_deref(), Some("order-7"));
+    assert_eq!(trade.entry.stop_loss, Some(dec!(1.0900)));
+    // A second open of the same position is the same trade
+    assert_eq!(journal.record_open("acc-1", &position).await, id);
+
+    let unknown = platform_long_position("AUDUSD");
+    let id = journal
+        .record_close(
+            "acc-2",
+            &close(&unknown, dec!(10000), dec!(0), dec!(1.1010)),
+        )
+        .await;
+    let trade = journal.get_trade(&id).await.unwrap();
+    assert_eq!(trade.entry.account_id, "acc-2");
+    assert_eq!(trade.entry.quantity, dec!(10000));
+    assert_eq!(trade.status, TradeStatus::Closed);
+}
+
+#[tokio::test]
+async fn test_file_store_round_trip_and_queries() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("journal").join("trades.jsonl");
+
+    let journal = TradeJournal::new().with_store(Arc::new(FileJournalStore::new(&path)));
+    let open = platform_long_position("EURUSD");
+    let closed = platform_long_position("GBPUSD");
+    journal.record_open("acc-1", &open).await;
+    journal.record_open("acc-2", &closed).await;
+    journal
+        .record_close("acc-2", &close(&closed, dec!(10000), dec!(0), dec!(1.1100)))
+        .await;
+
+    let reloaded = TradeJournal::new().with_store(Arc::new(FileJournalStore::new(&path)));
+    assert_eq!(reloaded.load().await.unwrap(), 2);
+
+    let all = reloaded.query(&TradeQuery::default()).await;
+    assert_eq!(all.len(), 2);
+
+    let closed_trades = reloaded
+        .query(&TradeQuery {
+            status: Some(TradeStatus::Closed),
+            ..Default::default()
+        })
+        .await;
+    assert_eq!(closed_trades.len(), 1);
+    assert_eq!(closed_trades[0].entry.symbol, "GBPUSD");
+    assert_eq!(closed_trades[0].exit_reason, Some(ExitReason::TakeProfit));
+
+    let by_account = reloaded
+        .query(&TradeQuery {
+            account_id: Some("acc-1".to_string()),
+            ..Default::default()
+        })
+        .await;
+    assert_eq!(by_account.len(), 1);
+    assert!(by_account[0].is_open());
+
+    // Open trades keep pairing exits after a restart
+    reloaded
+        .record_close("acc-1", &close(&open, dec!(10000), dec!(0), dec!(1.1020)))
+        .await;
+    let trade = reloaded
+        .get_trade_for_position(&open.position_id)
+        .await
+        .unwrap();
+    assert_eq!(trade.id, by_account[0].id);
+    assert_eq!(trade.status, TradeStatus::Closed);
+}
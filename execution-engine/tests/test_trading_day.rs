@@ -0,0 +1,122 @@
+use chrono::{Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use execution_engine::execution::exit_management::time_exits::weekend_close_window;
+use execution_engine::risk::pnl_calculator::PositionTracker;
+use execution_engine::risk::{TradingDayConfig, TradingDayRollover};
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn trading_day_is_named_after_the_day_it_ends() {
+    let rollover = TradingDayRollover::new_york_close();
+
+    // 16:59 and 17:00 New York on Monday 2025-06-02 (EDT, UTC-4)
+    let before = Utc.with_ymd_and_hms(2025, 6, 2, 20, 59, 0).unwrap();
+    let after = Utc.with_ymd_and_hms(2025, 6, 2, 21, 0, 0).unwrap();
+    assert_eq!(rollover.trading_day(before), date(2025, 6, 2));
+    assert_eq!(rollover.trading_day(after), date(2025, 6, 3));
+
+    // UTC midnight keeps calendar days
+    let utc = TradingDayRollover::default();
+    assert_eq!(utc.trading_day(after), date(2025, 6, 2));
+    assert_eq!(
+        utc.bounds(date(2025, 6, 2)).0,
+        Utc.with_ymd_and_hms(2025, 6, 2, 0, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn rollover_follows_daylight_saving() {
+    let rollover = TradingDayRollover::new_york_close();
+
+    // Clocks go forward on Sunday 2025-03-09: 17:00 EST on Saturday is 22:00 UTC,
+    // 17:00 EDT on Sunday is 21:00 UTC
+    let (start, end) = rollover.bounds(date(2025, 3, 9));
+    assert_eq!(start, Utc.with_ymd_and_hms(2025, 3, 8, 22, 0, 0).unwrap());
+    assert_eq!(end, Utc.with_ymd_and_hms(2025, 3, 9, 21, 0, 0).unwrap());
+    assert_eq!(end - start, Duration::hours(23));
+
+    // Clocks go back on Sunday 2025-11-02
+    let (start, end) = rollover.bounds(date(2025, 11, 2));
+    assert_eq!(end - start, Duration::hours(25));
+
+    // Midnight skipped by a DST change starts the day at the first valid local time
+    let santiago = TradingDayRollover::new(NaiveTime::MIN, chrono_tz::America::Santiago);
+    let (start, _) = santiago.bounds(date(2025, 9, 7));
+    assert_eq!(start, Utc.with_ymd_and_hms(2025, 9, 7, 4, 0, 0).unwrap());
+}
+
+#[test]
+fn weekend_window_follows_the_rollover() {
+    let rollover = TradingDayRollover::new_york_close();
+    let lead = Duration::hours(1);
+    let ny = |day, hour| {
+        chrono_tz::America::New_York
+            .with_ymd_and_hms(2025, 6, day, hour, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    };
+
+    // Friday 2025-06-06 from 16:00 New York until Sunday 17:00
+    assert!(!rollover.is_weekend(ny(6, 15), lead));
+    assert!(rollover.is_weekend(ny(6, 16), lead));
+    assert!(rollover.is_weekend(ny(7, 12), lead));
+    assert!(rollover.is_weekend(ny(8, 16), lead));
+    assert!(!rollover.is_weekend(ny(8, 17), lead));
+
+    // Without a rollover the fixed UTC window applies: Friday 20:00 to Sunday 22:00
+    let friday = Utc.with_ymd_and_hms(2025, 6, 6, 20, 30, 0).unwrap();
+    assert!(weekend_close_window(None, friday));
+    assert!(!weekend_close_window(
+        Some(&rollover),
+        friday - Duration::hours(1)
+    ));
+    assert!(weekend_close_window(Some(&rollover), ny(6, 16)));
+}
+
+#[test]
+fn accounts_fall_back_to_the_default_rollover() {
+    let config = TradingDayConfig {
+        default: TradingDayRollover::new_york_close(),
+        accounts: HashMap::from([("acc-1".to_string(), TradingDayRollover::default())]),
+    };
+
+    assert_eq!(config.for_account("acc-1"), TradingDayRollover::default());
+    assert_eq!(
+        config.for_account("acc-2"),
+        TradingDayRollover::new_york_close()
+    );
+
+    // 2025-06-02: New York starts at 21:00 UTC the day before, UTC ends at midnight
+    let (from, to) = config.widest_bounds(date(2025, 6, 2));
+    assert_eq!(from, Utc.with_ymd_and_hms(2025, 6, 1, 21, 0, 0).unwrap());
+    assert_eq!(to, Utc.with_ymd_and_hms(2025, 6, 3, 0, 0, 0).unwrap());
+}
+
+#[tokio::test]
+async fn realized_pnl_today_resets_at_the_account_rollover() {
+    let account_id = Uuid::new_v4();
+    // A rollover that happened an hour ago, wherever the test runs
+    let now = Utc::now();
+    let rollover_time = (now - Duration::hours(1)).time();
+    let tracker = PositionTracker::new().with_trading_days(TradingDayConfig {
+        accounts: HashMap::from([(
+            account_id.to_string(),
+            TradingDayRollover::new(rollover_time, chrono_tz::UTC),
+        )]),
+        ..TradingDayConfig::default()
+    });
+
+    tracker.record_realized_pnl(account_id, dec!(40), now - Duration::minutes(90));
+    tracker.record_realized_pnl(account_id, dec!(25), now - Duration::minutes(30));
+
+    assert_eq!(
+        tracker.get_realized_pnl_today(account_id).await.unwrap(),
+        dec!(25)
+    );
+}
@@ -0,0 +1,281 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use execution_engine::execution::exit_management::{
+    ClosePositionRequest, ClosePositionResult, ExitAuditLogger, MarketData,
+    NativeTrailingStopRequest, OrderModifyRequest, OrderModifyResult, PartialCloseRequest,
+    Position, TradingPlatform, TrailingConfig, TrailingStopManager, UnifiedPositionSide,
+};
+
+/// Platform quoting a settable mid at a settable time
+#[derive(Debug)]
+struct MockPlatform {
+    position: Position,
+    quote: Mutex<(DateTime<Utc>, Decimal)>,
+    modifications: Mutex<Vec<OrderModifyRequest>>,
+    /// `Some(accept)` when the platform offers native trailing stops
+    native: Option<bool>,
+    native_trails: Mutex<Vec<NativeTrailingStopRequest>>,
+    cancelled: Mutex<Vec<String>>,
+    closed: Mutex<bool>,
+}
+
+impl MockPlatform {
+    fn new(native: Option<bool>) -> Arc<Self> {
+        Arc::new(Self {
+            position: Position {
+                id: Uuid::new_v4(),
+                order_id: "order-1".to_string(),
+                symbol: "EURUSD".to_string(),
+                position_type: UnifiedPositionSide::Long,
+                volume: dec!(10000),
+                entry_price: dec!(1.1000),
+                current_price: dec!(1.1020),
+                stop_loss: Some(dec!(1.0950)),
+                take_profit: None,
+                unrealized_pnl: Decimal::ZERO,
+                swap: Decimal::ZERO,
+                commission: Decimal::ZERO,
+                open_time: Utc::now(),
+                magic_number: None,
+                comment: None,
+            },
+            quote: Mutex::new((Utc::now(), dec!(1.1020))),
+            modifications: Mutex::new(Vec::new()),
+            native,
+            native_trails: Mutex::new(Vec::new()),
+            cancelled: Mutex::new(Vec::new()),
+            closed: Mutex::new(false),
+        })
+    }
+
+    /// Quote `mid` `millis` after the previous quote
+    fn tick(&self, millis: i64, mid: Decimal) {
+        let mut quote = self.quote.lock().unwrap();
+        *quote = (quote.0 + Duration::milliseconds(millis), mid);
+    }
+
+    fn stops_sent(&self) -> Vec<Option<Decimal>> {
+        self.modifications
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| request.new_stop_loss)
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingPlatform for MockPlatform {
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        if *self.closed.lock().unwrap() {
+            return Ok(Vec::new());
+        }
+        Ok(vec![self.position.clone()])
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        let (timestamp, mid) = *self.quote.lock().unwrap();
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: mid - dec!(0.00005),
+            ask: mid + dec!(0.00005),
+            spread: dec!(0.0001),
+            timestamp,
+        })
+    }
+
+    async fn modify_order(&self, request: OrderModifyRequest) -> Result<OrderModifyResult> {
+        self.modifications.lock().unwrap().push(request.clone());
+        Ok(OrderModifyResult {
+            order_id: request.order_id,
+            success: true,
+            message: "modified".to_string(),
+        })
+    }
+
+    async fn close_position(&self, request: ClosePositionRequest) -> Result<ClosePositionResult> {
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: self.quote.lock().unwrap().1,
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+
+    async fn close_position_partial(
+        &self,
+        request: PartialCloseRequest,
+    ) -> Result<ClosePositionResult> {
+        Ok(ClosePositionResult {
+            position_id: request.position_id,
+            close_price: self.quote.lock().unwrap().1,
+            realized_pnl: None,
+            close_time: Utc::now(),
+        })
+    }
+
+    fn supports_native_trailing(&self, _symbol: &str) -> bool {
+        self.native.is_some()
+    }
+
+    async fn place_native_trailing_stop(
+        &self,
+        request: NativeTrailingStopRequest,
+    ) -> Result<OrderModifyResult> {
+        self.native_trails.lock().unwrap().push(request.clone());
+        Ok(OrderModifyResult {
+            order_id: "trail-1".to_string(),
+            success: self.native == Some(true),
+            message: "trailing stop".to_string(),
+        })
+    }
+
+    async fn cancel_native_trailing_stop(&self, order_id: &str) -> Result<()> {
+        self.cancelled.lock().unwrap().push(order_id.to_string());
+        Ok(())
+    }
+}
+
+/// Trails 20 pips behind once 10 pips in profit
+fn manager(platform: Arc<MockPlatform>, config: TrailingConfig) -> TrailingStopManager {
+    let mut manager = TrailingStopManager::new(platform, Arc::new(ExitAuditLogger::new()));
+    manager.configure_symbol(
+        "EURUSD".to_string(),
+        TrailingConfig {
+            min_trail_distance: dec!(0.0020),
+            max_trail_distance: dec!(0.0020),
+            activation_threshold: dec!(0.0010),
+            ..config
+        },
+    );
+    manager
+}
+
+fn compensated() -> TrailingConfig {
+    TrailingConfig {
+        latency_compensation: true,
+        latency_allowance_ms: 1000,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn fast_move_is_trailed_from_projected_price() {
+    let platform = MockPlatform::new(None);
+    let manager = manager(platform.clone(), compensated());
+    manager
+        .activate_trailing_stop(&platform.position)
+        .await
+        .unwrap();
+    manager.update_trailing_stops().await.unwrap();
+    assert!(platform.stops_sent().is_empty());
+
+    // Ten pips a second, projected a second ahead
+    platform.tick(1000, dec!(1.1030));
+    manager.update_trailing_stops().await.unwrap();
+
+    assert_eq!(platform.stops_sent(), [Some(dec!(1.1020))]);
+}
+
+#[tokio::test]
+async fn projection_is_capped_at_half_the_trail_distance() {
+    let platform = MockPlatform::new(None);
+    let manager = manager(platform.clone(), compensated());
+    manager
+        .activate_trailing_stop(&platform.position)
+        .await
+        .unwrap();
+    manager.update_trailing_stops().await.unwrap();
+
+    platform.tick(500, dec!(1.1060));
+    manager.update_trailing_stops().await.unwrap();
+
+    // 1.1060 + 10 pips - 20 pips, not the 80 pips a second of velocity suggests
+    assert_eq!(platform.stops_sent(), [Some(dec!(1.1050))]);
+}
+
+#[tokio::test]
+async fn uncompensated_trail_follows_the_read_price() {
+    let platform = MockPlatform::new(None);
+    let manager = manager(platform.clone(), TrailingConfig::default());
+    manager
+        .activate_trailing_stop(&platform.position)
+        .await
+        .unwrap();
+    manager.update_trailing_stops().await.unwrap();
+
+    platform.tick(1000, dec!(1.1030));
+    manager.update_trailing_stops().await.unwrap();
+
+    assert_eq!(platform.stops_sent(), [Some(dec!(1.1010))]);
+}
+
+#[tokio::test]
+async fn native_trailing_is_left_to_the_platform() {
+    let platform = MockPlatform::new(Some(true));
+    let manager = manager(
+        platform.clone(),
+        TrailingConfig {
+            native_trailing: true,
+            ..Default::default()
+        },
+    );
+
+    manager
+        .activate_trailing_stop(&platform.position)
+        .await
+        .unwrap();
+
+    let trails = platform.native_trails.lock().unwrap().clone();
+    assert_eq!(trails.len(), 1);
+    assert_eq!(trails[0].stop_level, dec!(1.1000));
+    assert_eq!(trails[0].trail_distance, dec!(0.0020));
+    assert_eq!(
+        manager
+            .get_active_trail(platform.position.id)
+            .unwrap()
+            .native_order_id,
+        Some("trail-1".to_string())
+    );
+
+    platform.tick(1000, dec!(1.1050));
+    manager.update_trailing_stops().await.unwrap();
+    assert!(platform.stops_sent().is_empty());
+
+    // Closed by other means: the trailing order must not outlive it
+    *platform.closed.lock().unwrap() = true;
+    manager.update_trailing_stops().await.unwrap();
+    assert_eq!(*platform.cancelled.lock().unwrap(), ["trail-1"]);
+    assert!(manager.get_active_trail(platform.position.id).is_none());
+}
+
+#[tokio::test]
+async fn refused_native_trailing_is_emulated() {
+    let platform = MockPlatform::new(Some(false));
+    let manager = manager(
+        platform.clone(),
+        TrailingConfig {
+            native_trailing: true,
+            ..Default::default()
+        },
+    );
+
+    manager
+        .activate_trailing_stop(&platform.position)
+        .await
+        .unwrap();
+    assert!(manager
+        .get_active_trail(platform.position.id)
+        .unwrap()
+        .native_order_id
+        .is_none());
+
+    platform.tick(1000, dec!(1.1050));
+    manager.update_trailing_stops().await.unwrap();
+    assert_eq!(platform.stops_sent(), [Some(dec!(1.1030))]);
+}
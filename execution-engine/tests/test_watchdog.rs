@@ -0,0 +1,127 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use execution_engine::runtime::{
+    HealthLevel, HealthProbe, Heartbeat, Watchdog, WatchdogAction, WatchdogConfig,
+};
+
+fn watchdog(max_restarts: u32) -> Watchdog {
+    Watchdog::new(WatchdogConfig {
+        check_interval_ms: 100,
+        max_restarts,
+    })
+}
+
+async fn advance(millis: u64) {
+    tokio::time::advance(Duration::from_millis(millis)).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_stalled_task_is_alerted_once_until_it_recovers() {
+    let watchdog = watchdog(3);
+    let heartbeat = watchdog.register("exit-management", Duration::from_secs(1));
+
+    advance(800).await;
+    heartbeat.beat();
+    advance(800).await;
+    assert!(watchdog.check().is_empty());
+
+    advance(500).await;
+    let alerts = watchdog.check();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].task, "exit-management");
+    assert_eq!(alerts[0].action, WatchdogAction::AlertOnly);
+    assert!(alerts[0].silent_for_ms >= 1300);
+
+    let health = HealthProbe::check(&watchdog).await;
+    assert_eq!(health.len(), 1);
+    assert_eq!(health[0].level, HealthLevel::Unhealthy);
+
+    // No repeat alert while the task stays silent
+    advance(2000).await;
+    assert!(watchdog.check().is_empty());
+
+    heartbeat.beat();
+    assert!(watchdog.check().is_empty());
+    assert!(!watchdog.statuses()[0].stalled);
+    assert_eq!(
+        HealthProbe::check(&watchdog).await[0].level,
+        HealthLevel::Healthy
+    );
+    assert_eq!(watchdog.recent_alerts().len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_stalled_task_is_restarted_until_restarts_run_out() {
+    let watchdog = watchdog(2);
+    let restarts = Arc::new(AtomicU32::new(0));
+    let counter = restarts.clone();
+    watchdog.register_with_restart(
+        "fix-heartbeat:test",
+        Duration::from_secs(1),
+        Arc::new(move |_heartbeat: Heartbeat| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }),
+    );
+
+    let mut actions = Vec::new();
+    for _ in 0..4 {
+        advance(1100).await;
+        actions.extend(watchdog.check().into_iter().map(|alert| alert.action));
+    }
+
+    assert_eq!(
+        actions,
+        vec![
+            WatchdogAction::Restarted,
+            WatchdogAction::Restarted,
+            WatchdogAction::RestartsExhausted,
+        ]
+    );
+    assert_eq!(restarts.load(Ordering::SeqCst), 2);
+    assert_eq!(watchdog.statuses()[0].restarts, 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_restarted_task_beats_on_the_same_registration() {
+    let watchdog = Arc::new(watchdog(3));
+    watchdog.register_with_restart(
+        "worker",
+        Duration::from_secs(1),
+        Arc::new(|heartbeat: Heartbeat| {
+            tokio::spawn(async move {
+                loop {
+                    heartbeat.beat();
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                }
+            });
+        }),
+    );
+
+    advance(1100).await;
+    assert_eq!(watchdog.check()[0].action, WatchdogAction::Restarted);
+
+    for _ in 0..10 {
+        advance(500).await;
+        assert!(watchdog.check().is_empty());
+    }
+    assert_eq!(watchdog.statuses()[0].restarts, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_stopped_task_is_no_longer_watched() {
+    let watchdog = watchdog(3);
+    let old = watchdog.register("loop", Duration::from_secs(1));
+    let current = watchdog.register("loop", Duration::from_secs(5));
+
+    // Stopping a replaced registration leaves the current one in place
+    old.stop();
+    assert_eq!(watchdog.statuses().len(), 1);
+    assert_eq!(watchdog.statuses()[0].timeout_ms, 5000);
+
+    current.stop();
+    advance(10_000).await;
+    assert!(watchdog.statuses().is_empty());
+    assert!(watchdog.check().is_empty());
+}
@@ -262,6 +262,13 @@ impl RiskResponseSystem {
                 })
             }
 
+            // Hedges are opened by the execution engine, which holds the platforms
+            ResponseAction::HedgeExposure { account_id, .. } => {
+                Ok(ResponseExecutionResult::Failed {
+                    reason: format!("Hedging is not available for account {}", account_id),
+                })
+            }
+
             ResponseAction::Monitor => Ok(ResponseExecutionResult::MonitoringContinued),
         }
     }
@@ -101,10 +101,12 @@ impl RiskRewardTracker {
         let time_in_trade = Utc::now() - position.opened_at;
         let hours_in_trade = Decimal::from(time_in_trade.num_hours().max(1));
 
-        let price_progress = if position.take_profit.is_some() && position.stop_loss.is_some() {
+        let price_progress = if let (Some(take_profit), Some(stop_loss)) =
+            (position.take_profit, position.stop_loss)
+        {
             let total_distance = match position.position_type {
-                PositionType::Long => position.take_profit.unwrap() - position.stop_loss.unwrap(),
-                PositionType::Short => position.stop_loss.unwrap() - position.take_profit.unwrap(),
+                PositionType::Long => take_profit - stop_loss,
+                PositionType::Short => stop_loss - take_profit,
             };
 
             if total_distance != dec!(0) {
@@ -163,15 +165,15 @@ impl RiskRewardTracker {
         if distance_from_entry > dec!(0) {
             let profit_percentage = (distance_from_entry / position.entry_price) * dec!(100);
 
-            if profit_percentage > dec!(1) && position.stop_loss.is_some() {
+            if let Some(stop_loss) = position.stop_loss.filter(|_| profit_percentage > dec!(1)) {
                 let new_stop = match position.position_type {
                     PositionType::Long => position.entry_price,
                     PositionType::Short => position.entry_price,
                 };
 
                 if match position.position_type {
-                    PositionType::Long => new_stop > position.stop_loss.unwrap(),
-                    PositionType::Short => new_stop < position.stop_loss.unwrap(),
+                    PositionType::Long => new_stop > stop_loss,
+                    PositionType::Short => new_stop < stop_loss,
                 } {
                     recommendations.push(format!("Move stop to breakeven at {:.5}", new_stop));
                 }
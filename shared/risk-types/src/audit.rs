@@ -509,7 +509,7 @@ impl AuditReport {
         };
 
         let mut most_common_triggers: Vec<_> = trigger_counts.into_iter().collect();
-        most_common_triggers.sort_by(|a, b| b.1.cmp(&a.1));
+        most_common_triggers.sort_by_key(|b| std::cmp::Reverse(b.1));
         most_common_triggers.truncate(10); // Top 10 triggers
 
         Self {
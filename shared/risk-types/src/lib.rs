@@ -213,7 +213,7 @@ pub struct MarginAlert {
     pub timestamp: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum AlertLevel {
     Info,
     Warning,
@@ -266,6 +266,11 @@ pub enum ResponseAction {
         scope: EmergencyStopScope,
         reason: String,
     },
+    /// Offset open positions with opposing ones instead of closing them
+    HedgeExposure {
+        account_id: AccountId,
+        hedge_ratio: Decimal,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -303,6 +308,10 @@ pub enum ResponseExecutionResult {
         scope: EmergencyStopScope,
         timestamp: DateTime<Utc>,
     },
+    PositionsHedged {
+        hedges_opened: usize,
+        hedged_size: Decimal,
+    },
     Failed {
         reason: String,
     },